@@ -0,0 +1,69 @@
+//! Benchmarks for indexing, search and queries on a generated vault.
+//!
+//! The vault comes from `generate_test_vault` with the default spec, so
+//! runs on different machines and commits measure the same notes.
+//!
+//! Run with `cargo bench -p core_domain --features test-vault --bench vault`.
+
+use core_domain::{generate_test_vault, TestVaultSpec, Vault};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use shared_types::{FilterMatchMode, PropertyFilter, PropertyOperator, QueryRequest, QueryResultType};
+use tokio::runtime::Runtime;
+
+fn filter(key: &str, value: &str) -> PropertyFilter {
+    PropertyFilter {
+        key: key.to_string(),
+        operator: PropertyOperator::Equals,
+        value: Some(value.to_string()),
+    }
+}
+
+fn bench_vault(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let vault = rt.block_on(async {
+        generate_test_vault(dir.path(), &TestVaultSpec::default()).await.unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+        vault
+    });
+
+    let mut group = c.benchmark_group("vault");
+    group.sample_size(10);
+    group.bench_function("full_index", |b| {
+        b.to_async(&rt).iter(|| async { vault.full_index().await.unwrap() });
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("search");
+    for query in ["garden", "project review", "\"weekly focus\""] {
+        group.bench_with_input(BenchmarkId::from_parameter(query), &query, |b, query| {
+            b.to_async(&rt).iter(|| vault.repo().search(query, 50));
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("run_query");
+    let cases = [
+        ("tag", vec![filter("_tags", "tag-0")], QueryResultType::Notes),
+        ("properties", vec![filter("field-0", "value-0"), filter("field-1", "value-1")], QueryResultType::Notes),
+        ("tasks", vec![filter("_tags", "tag-1")], QueryResultType::Tasks),
+    ];
+    for (name, filters, result_type) in cases {
+        let request = QueryRequest {
+            filters,
+            match_mode: FilterMatchMode::All,
+            result_type,
+            include_completed: false,
+            limit: Some(100),
+            sort: None,
+        };
+        group.bench_with_input(BenchmarkId::from_parameter(name), &request, |b, request| {
+            b.to_async(&rt).iter(|| vault.repo().run_query(request));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_vault);
+criterion_main!(benches);
@@ -0,0 +1,234 @@
+//! Plain-text daily agenda, for receipt printers and terminals.
+//!
+//! The agenda has the same schedule blocks, tasks and habits as the day in
+//! the weekly planner, laid out in a single column of fixed width: times
+//! and checkboxes in front, long text word-wrapped under its first line.
+
+use crate::vault::{Result, Vault};
+use chrono::NaiveDate;
+use shared_types::DayOverview;
+use tracing::instrument;
+
+/// Narrowest agenda; a time range and a few letters of the label.
+const MIN_WIDTH: usize = 20;
+
+/// Format the agenda of `date`, wrapped to `width` columns (at least 20).
+#[instrument(skip(vault))]
+pub async fn format_agenda_text(vault: &Vault, date: NaiveDate, width: usize) -> Result<String> {
+    let day = vault.repo().get_day_overview(date).await?;
+    Ok(format_agenda(&day, width))
+}
+
+/// Lay out a day's agenda in `width` columns.
+pub fn format_agenda(day: &DayOverview, width: usize) -> String {
+    let width = width.max(MIN_WIDTH);
+    let mut lines = wrap(&day.date.format("%A, %B %-d, %Y").to_string(), width);
+    lines.push("=".repeat(width));
+
+    lines.push(String::new());
+    lines.push("SCHEDULE".to_string());
+    let mut blocks: Vec<_> = day.blocks.iter().collect();
+    blocks.sort_by_key(|block| (block.start_time, block.end_time));
+    if blocks.is_empty() {
+        lines.push("Nothing scheduled".to_string());
+    }
+    for block in blocks {
+        let time = format!(
+            "{}-{} ",
+            block.start_time.format("%H:%M"),
+            block.end_time.format("%H:%M")
+        );
+        let label = block.label.as_deref().unwrap_or("Untitled");
+        push_item(&mut lines, &time, label, width);
+    }
+
+    lines.push(String::new());
+    lines.push("TASKS".to_string());
+    if day.tasks.is_empty() {
+        lines.push("No tasks due".to_string());
+    }
+    for task in &day.tasks {
+        let checkbox = if task.todo.completed { "[x] " } else { "[ ] " };
+        let mut text = task.todo.description.clone();
+        if let Some(priority) = &task.todo.priority {
+            text.push_str(&format!(" ({})", priority));
+        }
+        push_item(&mut lines, checkbox, &text, width);
+    }
+
+    let habits: Vec<_> = day.habits.iter().filter(|habit| !habit.skipped).collect();
+    if !habits.is_empty() {
+        lines.push(String::new());
+        lines.push("HABITS".to_string());
+        for habit in habits {
+            let checkbox = if habit.done { "[x] " } else { "[ ] " };
+            push_item(&mut lines, checkbox, &habit.name, width);
+        }
+    }
+
+    lines.push("-".repeat(width));
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Add an item: `prefix` on the first line, and the text's continuation
+/// lines indented to line up under it.
+fn push_item(lines: &mut Vec<String>, prefix: &str, text: &str, width: usize) {
+    let indent = prefix.chars().count();
+    for (i, line) in wrap(text, width - indent).into_iter().enumerate() {
+        let lead = if i == 0 { prefix.to_string() } else { " ".repeat(indent) };
+        lines.push(format!("{}{}", lead, line));
+    }
+}
+
+/// Word-wrap text to `width` columns, splitting words longer than a line.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_len = 0;
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        if line_len > 0 && line_len + 1 + word.len() <= width {
+            line.push(' ');
+            line.extend(&word);
+            line_len += 1 + word.len();
+            continue;
+        }
+        if line_len > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        line = word.iter().collect();
+        line_len = word.len();
+    }
+    if line_len > 0 || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use shared_types::{HabitDaySummary, ScheduleBlockDto, TaskWithContext, TodoDto};
+
+    fn block(start: &str, end: &str, label: &str) -> ScheduleBlockDto {
+        ScheduleBlockDto {
+            id: 1,
+            key: "block:1".to_string(),
+            note_id: None,
+            date: NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            start_time: NaiveTime::parse_from_str(start, "%H:%M").unwrap(),
+            end_time: NaiveTime::parse_from_str(end, "%H:%M").unwrap(),
+            label: Some(label.to_string()),
+            color: None,
+            context: None,
+            rrule: None,
+            timezone: None,
+            is_occurrence: false,
+            read_only: false,
+        }
+    }
+
+    fn task(description: &str, completed: bool, priority: Option<&str>) -> TaskWithContext {
+        TaskWithContext {
+            todo: TodoDto {
+                id: 1,
+                note_id: 1,
+                line_number: None,
+                description: description.to_string(),
+                completed,
+                heading_path: None,
+                context: None,
+                priority: priority.map(String::from),
+                due_date: Some("2024-03-04".to_string()),
+                estimate_minutes: None,
+                created_at: None,
+                completed_at: None,
+            },
+            note_path: "tasks.md".to_string(),
+            note_title: None,
+            note_properties: Vec::new(),
+            embedded_in: None,
+        }
+    }
+
+    fn habit(name: &str, done: bool, skipped: bool) -> HabitDaySummary {
+        HabitDaySummary {
+            habit_id: 1,
+            name: name.to_string(),
+            color: None,
+            done,
+            skipped,
+        }
+    }
+
+    #[test]
+    fn test_format_agenda() {
+        let day = DayOverview {
+            date: NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            blocks: vec![
+                block("13:00", "14:00", "Review the quarterly planning document"),
+                block("09:00", "09:15", "Standup"),
+            ],
+            tasks: vec![
+                task("Call the plumber", false, Some("high")),
+                task("Pay rent", true, None),
+            ],
+            habits: vec![habit("Meditate", true, false), habit("Run", false, true)],
+            note_count: 2,
+        };
+
+        let expected = "\
+Monday, March 4, 2024
+========================
+
+SCHEDULE
+09:00-09:15 Standup
+13:00-14:00 Review the
+            quarterly
+            planning
+            document
+
+TASKS
+[ ] Call the plumber
+    (high)
+[x] Pay rent
+
+HABITS
+[x] Meditate
+------------------------
+";
+        assert_eq!(format_agenda(&day, 24), expected);
+
+        // Never narrower than the minimum
+        let narrow = format_agenda(&day, 5);
+        assert!(narrow.lines().all(|line| line.chars().count() <= MIN_WIDTH));
+        assert!(narrow.contains(&"=".repeat(MIN_WIDTH)));
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("", 10), vec![""]);
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefghij klm", 4), vec!["abcd", "efgh", "ij", "klm"]);
+        assert_eq!(wrap("Grüße aus Köln", 5), vec!["Grüße", "aus", "Köln"]);
+    }
+
+    #[tokio::test]
+    async fn test_format_agenda_text_empty_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+
+        let text = format_agenda_text(&vault, date, 32).await.unwrap();
+        assert!(text.starts_with("Monday, March 4, 2024\n"));
+        assert!(text.contains("Nothing scheduled"));
+        assert!(text.contains("No tasks due"));
+        assert!(!text.contains("HABITS"));
+    }
+}
@@ -0,0 +1,168 @@
+//! Per-device app settings, kept as JSON in the OS config directory (the
+//! caller picks the file), apart from the vault config.
+//!
+//! The frontend used to keep these settings in the webview's localStorage.
+//! While there is no settings file yet, [`migrate_app_settings`] takes them
+//! over once; after that the file is the only copy.
+//!
+//! The file is replaced in one rename, so a crash while saving leaves the
+//! old settings. A file that can't be parsed (e.g. after editing it by hand)
+//! reads as the defaults; at startup [`keep_invalid_app_settings`] moves it
+//! to `settings.invalid.json` next to it, so saving doesn't overwrite it.
+//!
+//! The window layout is saved by the backend when the main window closes
+//! (see [`save_window_layout`]), not with the rest of the settings.
+
+use crate::vault::{Result, VaultError};
+use shared_types::{AppSettings, WindowLayout};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// Read the app settings, or the defaults if there is no settings file yet
+/// or it can't be parsed. The file is left as it is.
+pub async fn read_app_settings(path: &Path) -> Result<AppSettings> {
+    let content = match tokio::fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AppSettings::default()),
+        Err(e) => return Err(core_fs::FsError::from(e).into()),
+    };
+
+    match serde_json::from_str(&content) {
+        Ok(settings) => Ok(settings),
+        Err(e) => {
+            warn!("Failed to parse {}: {}; using the default settings", path.display(), e);
+            Ok(AppSettings::default())
+        }
+    }
+}
+
+/// Move a settings file that can't be parsed to `settings.invalid.json`,
+/// so the user's file is kept rather than saved over with the defaults.
+/// Returns whether it was moved.
+pub async fn keep_invalid_app_settings(path: &Path) -> Result<bool> {
+    let content = match tokio::fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(core_fs::FsError::from(e).into()),
+    };
+    if serde_json::from_str::<AppSettings>(&content).is_ok() {
+        return Ok(false);
+    }
+
+    let invalid = invalid_path(path);
+    tokio::fs::rename(path, &invalid).await.map_err(core_fs::FsError::from)?;
+    info!("Kept unparsable settings as {}", invalid.display());
+    Ok(true)
+}
+
+/// Write the app settings, creating the config directory if needed.
+pub async fn write_app_settings(path: &Path, settings: &AppSettings) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(core_fs::FsError::from)?;
+    }
+
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| VaultError::Settings(format!("Failed to serialize app settings: {}", e)))?;
+    let partial = path.with_extension("json.partial");
+    tokio::fs::write(&partial, content).await.map_err(core_fs::FsError::from)?;
+    tokio::fs::rename(&partial, path).await.map_err(core_fs::FsError::from)?;
+
+    debug!("Saved app settings to {}", path.display());
+    Ok(())
+}
+
+/// Save the main window's layout, keeping the other settings.
+pub async fn save_window_layout(path: &Path, layout: WindowLayout) -> Result<()> {
+    let mut settings = read_app_settings(path).await?;
+    settings.window = Some(layout);
+    write_app_settings(path, &settings).await
+}
+
+/// Where a settings file that can't be parsed is kept.
+fn invalid_path(path: &Path) -> PathBuf {
+    path.with_extension("invalid.json")
+}
+
+/// Take over `legacy` settings (from the old localStorage store) if there
+/// is no settings file yet, and return the settings in effect. Once the
+/// file exists, `legacy` is ignored.
+pub async fn migrate_app_settings(path: &Path, legacy: AppSettings) -> Result<AppSettings> {
+    if tokio::fs::try_exists(path).await.map_err(core_fs::FsError::from)? {
+        return read_app_settings(path).await;
+    }
+
+    write_app_settings(path, &legacy).await?;
+    info!("Migrated app settings to {}", path.display());
+    Ok(legacy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_app_settings_round_trip_and_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("neuroflow").join("settings.json");
+
+        let settings = read_app_settings(&path).await.unwrap();
+        assert_eq!(settings.theme, "system");
+        assert_eq!(settings.default_calendar_view, "weekly");
+
+        // The first migration is taken over, later ones are ignored
+        let legacy = AppSettings {
+            theme: "dark".to_string(),
+            vim_mode: true,
+            ..AppSettings::default()
+        };
+        let migrated = migrate_app_settings(&path, legacy).await.unwrap();
+        assert_eq!(migrated.theme, "dark");
+
+        let mut settings = read_app_settings(&path).await.unwrap();
+        assert!(settings.vim_mode);
+        settings.editor.font_size = Some(16.0);
+        write_app_settings(&path, &settings).await.unwrap();
+
+        let again = migrate_app_settings(&path, AppSettings::default()).await.unwrap();
+        assert_eq!(again.theme, "dark");
+        assert_eq!(again.editor.font_size, Some(16.0));
+
+        // Settings files from older versions get defaults for new fields
+        std::fs::write(&path, r#"{"theme": "light"}"#).unwrap();
+        let settings = read_app_settings(&path).await.unwrap();
+        assert_eq!(settings.theme, "light");
+        assert!(settings.window.is_none());
+
+        // The window layout is saved on its own
+        let layout = WindowLayout {
+            width: 1400.0,
+            height: 900.0,
+            x: Some(10.0),
+            y: Some(20.0),
+            maximized: false,
+        };
+        save_window_layout(&path, layout.clone()).await.unwrap();
+        let settings = read_app_settings(&path).await.unwrap();
+        assert_eq!((settings.theme.as_str(), settings.window), ("light", Some(layout)));
+        assert!(!path.with_extension("json.partial").exists());
+    }
+
+    #[tokio::test]
+    async fn test_unparsable_settings_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, r#"{"theme": "dark",}"#).unwrap();
+
+        // Reading leaves the file alone
+        let settings = read_app_settings(&path).await.unwrap();
+        assert_eq!(settings.theme, "system");
+        assert!(path.exists());
+
+        assert!(keep_invalid_app_settings(&path).await.unwrap());
+        assert!(!keep_invalid_app_settings(&path).await.unwrap());
+        write_app_settings(&path, &settings).await.unwrap();
+
+        let kept = std::fs::read_to_string(dir.path().join("settings.invalid.json")).unwrap();
+        assert_eq!(kept, r#"{"theme": "dark",}"#);
+    }
+}
@@ -0,0 +1,376 @@
+//! Attachment deduplication by content hash.
+//!
+//! Attachments written by import, paste or download are hashed, and the
+//! hash and path are kept in the database. When the same content is added
+//! again, the existing file is linked instead of writing a copy. The map is
+//! only a hint: a hit is checked against the file on disk, and entries for
+//! files that were changed or deleted are dropped.
+//!
+//! [`dedupe_attachments`] merges the identical attachments already in the
+//! vault: one copy is kept (the first by path), embeds and links to the
+//! others are rewritten to it, and the others are deleted, unless an
+//! encrypted note (whose embeds can't be read) might still use them.
+
+use crate::encryption::has_encrypted_body;
+use crate::importer::ASSET_EXTENSIONS;
+use crate::links::absolute_target;
+use crate::moves::{relative_link, resolve_relative, target_path};
+use crate::vault::{parent_folder, Result, Vault};
+use core_fs::{hash_bytes, hash_file};
+use core_index::markdown::{map_markdown_links, map_wiki_links};
+use shared_types::{AttachmentDedupeResult, DuplicateAttachments};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+use tracing::{debug, info, instrument, warn};
+
+/// An attachment in the vault with the same content as `bytes`, if any.
+pub(crate) async fn find_asset(vault: &Vault, bytes: &[u8]) -> Result<Option<PathBuf>> {
+    let hash = hash_bytes(bytes);
+    for path in vault.repo().find_assets_by_hash(&hash).await? {
+        match tokio::fs::read(vault.fs().to_absolute(Path::new(&path))).await {
+            Ok(existing) if existing == bytes => return Ok(Some(PathBuf::from(path))),
+            _ => {
+                debug!("Dropping stale asset hash for {}", path);
+                vault.repo().remove_asset(&path).await?;
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Write an attachment to `target` and record its hash.
+pub(crate) async fn write_asset(vault: &Vault, target: &Path, bytes: &[u8]) -> Result<()> {
+    vault.fs().write_bytes(target, bytes).await?;
+    vault
+        .repo()
+        .record_asset(&target.to_string_lossy(), &hash_bytes(bytes), bytes.len() as u64)
+        .await?;
+    Ok(())
+}
+
+/// Store an attachment as `file_name` in `folder` (with a number suffix on
+/// a name collision), or return the existing attachment with the same
+/// content.
+pub(crate) async fn store_asset(vault: &Vault, folder: &Path, file_name: &str, bytes: &[u8]) -> Result<PathBuf> {
+    if let Some(existing) = find_asset(vault, bytes).await? {
+        debug!("Reusing identical attachment: {}", existing.display());
+        return Ok(existing);
+    }
+
+    let target = vault.fs().unique_path(folder, file_name).await;
+    write_asset(vault, &target, bytes).await?;
+    Ok(target)
+}
+
+/// Merge identical attachments in the vault.
+///
+/// Every attachment is hashed (rebuilding the hash map). Of each set of
+/// identical files the first by path is kept; wiki embeds and markdown
+/// links to the others are rewritten to it, and the others are deleted.
+/// Encrypted notes can't be rewritten, so while the vault has any, the
+/// others are kept on disk for the links in them.
+#[instrument(skip(vault))]
+pub async fn dedupe_attachments(vault: &Vault) -> Result<AttachmentDedupeResult> {
+    let mut by_hash: BTreeMap<String, Vec<(String, u64)>> = BTreeMap::new();
+    for path in list_attachments(vault).await? {
+        let (hash, size) = hash_file(&vault.fs().to_absolute(Path::new(&path))).await?;
+        by_hash.entry(hash).or_default().push((path, size));
+    }
+
+    let mut duplicates = Vec::new();
+    let mut hashes = Vec::new();
+    let mut merged = Vec::new();
+    let mut replacements: HashMap<String, String> = HashMap::new();
+    for (hash, mut files) in by_hash {
+        files.sort();
+        let (kept, size) = files[0].clone();
+        hashes.push((kept.clone(), hash.clone(), size));
+
+        let mut removed = Vec::new();
+        for (path, file_size) in &files[1..] {
+            // Compared in full, as the hash is only 64 bits
+            if size > 0 && same_content(vault, &kept, path).await? {
+                replacements.insert(path.clone(), kept.clone());
+                removed.push(path.clone());
+                merged.push((path.clone(), hash.clone(), size));
+            } else {
+                hashes.push((path.clone(), hash.clone(), *file_size));
+            }
+        }
+        if !removed.is_empty() {
+            duplicates.push(DuplicateAttachments { kept, removed, size });
+        }
+    }
+
+    let mut result = AttachmentDedupeResult {
+        duplicates,
+        ..Default::default()
+    };
+    if !replacements.is_empty() {
+        (result.notes_updated, result.encrypted_notes) = rewrite_links(vault, &replacements, &hashes).await?;
+        if result.encrypted_notes == 0 {
+            for path in replacements.keys() {
+                if let Err(e) = tokio::fs::remove_file(vault.fs().to_absolute(Path::new(path))).await {
+                    warn!("Failed to delete duplicate attachment {}: {}", path, e);
+                }
+            }
+            result.bytes_saved = result.duplicates.iter().map(|d| d.size * d.removed.len() as u64).sum();
+        } else {
+            // The copies stay, so they keep their hashes
+            hashes.extend(merged);
+        }
+    }
+    vault.repo().replace_asset_hashes(&hashes).await?;
+
+    info!(
+        "Merged {} sets of identical attachments, {} bytes saved, {} notes updated",
+        result.duplicates.len(),
+        result.bytes_saved,
+        result.notes_updated
+    );
+    Ok(result)
+}
+
+/// Whether two attachments have the same content, read in chunks.
+async fn same_content(vault: &Vault, a: &str, b: &str) -> Result<bool> {
+    let open = |path: &str| tokio::fs::File::open(vault.fs().to_absolute(Path::new(path)));
+    let mut a = open(a).await.map_err(core_fs::FsError::from)?;
+    let mut b = open(b).await.map_err(core_fs::FsError::from)?;
+    let (mut chunk_a, mut chunk_b) = (vec![0; 64 * 1024], vec![0; 64 * 1024]);
+    loop {
+        let n = read_chunk(&mut a, &mut chunk_a).await?;
+        let m = read_chunk(&mut b, &mut chunk_b).await?;
+        if chunk_a[..n] != chunk_b[..m] {
+            return Ok(false);
+        }
+        if n == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Fill `buffer` from `file` unless it ends first; returns the bytes read.
+async fn read_chunk(file: &mut tokio::fs::File, buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..]).await.map_err(core_fs::FsError::from)? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Point links to the duplicates in `replacements` (path to kept path) at
+/// the kept copies. `remaining` are the attachments left afterwards, used to
+/// tell whether a bare file name is unique. Returns the number of notes
+/// rewritten and the number of encrypted notes, which were skipped.
+async fn rewrite_links(
+    vault: &Vault,
+    replacements: &HashMap<String, String>,
+    remaining: &[(String, String, u64)],
+) -> Result<(usize, usize)> {
+    // Bare file names (`![[shot.png]]`) only resolve unambiguously when
+    // a single attachment has that name, before and after the merge
+    let mut before: HashMap<&str, usize> = HashMap::new();
+    for path in replacements.keys().chain(remaining.iter().map(|(path, _, _)| path)) {
+        *before.entry(file_name(path)).or_default() += 1;
+    }
+    let mut after: HashMap<&str, usize> = HashMap::new();
+    for (path, _, _) in remaining {
+        *after.entry(file_name(path)).or_default() += 1;
+    }
+    let by_name: HashMap<&str, &str> = replacements
+        .iter()
+        .filter(|(path, _)| before.get(file_name(path)) == Some(&1))
+        .map(|(path, kept)| (file_name(path), kept.as_str()))
+        .collect();
+
+    let (mut updated, mut encrypted) = (0, 0);
+    for note in vault.repo().list_notes().await? {
+        let content = vault.read_note(&note.path).await?;
+        if has_encrypted_body(&content) {
+            encrypted += 1;
+            continue;
+        }
+
+        let dir = parent_folder(&note.path);
+        let rewritten = map_wiki_links(&content, |target| {
+            let kept = if target.contains('/') {
+                replacements.get(absolute_target(target, Some(&note.path)).as_ref())?.as_str()
+            } else {
+                *by_name.get(target)?
+            };
+            if after.get(file_name(kept)) == Some(&1) {
+                Some(file_name(kept).to_string())
+            } else {
+                Some(kept.to_string())
+            }
+        });
+        let rewritten = map_markdown_links(&rewritten, |destination| {
+            let target = resolve_relative(dir, destination)?;
+            let kept = replacements.get(&target_path(&target))?;
+            let link = relative_link(dir, &(kept.split('/').collect(), target.1));
+            Some(link.replace(' ', "%20"))
+        });
+
+        if rewritten != content {
+            vault.write_note(&note.path, &rewritten).await?;
+            updated += 1;
+        }
+    }
+    Ok((updated, encrypted))
+}
+
+/// Vault-relative paths of the attachments in the vault: files with a media
+/// or document extension, outside hidden folders.
+pub(crate) async fn list_attachments(vault: &Vault) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut folders = vec![PathBuf::new()];
+
+    while let Some(folder) = folders.pop() {
+        let mut entries = tokio::fs::read_dir(vault.fs().to_absolute(&folder))
+            .await
+            .map_err(core_fs::FsError::from)?;
+        while let Some(entry) = entries.next_entry().await.map_err(core_fs::FsError::from)? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+
+            let path = folder.join(&name);
+            let file_type = entry.file_type().await.map_err(core_fs::FsError::from)?;
+            let is_asset = Path::new(&name)
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| ASSET_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+            if file_type.is_dir() {
+                folders.push(path);
+            } else if file_type.is_file() && is_asset {
+                files.push(path.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attachments::{import_files, save_attachment};
+    use shared_types::AttachmentSettings;
+
+    #[tokio::test]
+    async fn test_identical_attachments_are_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let settings = AttachmentSettings::default();
+
+        let first = save_attachment(&vault, &settings, None, "shot.png", b"pixels").await.unwrap();
+        let again = save_attachment(&vault, &settings, Some("inbox"), "copy.png", b"pixels").await.unwrap();
+        let other = save_attachment(&vault, &settings, None, "shot.png", b"other pixels").await.unwrap();
+        assert_eq!(first, "assets/shot.png");
+        assert_eq!(again, first);
+        assert_eq!(other, "assets/shot 1.png");
+
+        let outside = tempfile::tempdir().unwrap();
+        let source = outside.path().join("download.png");
+        std::fs::write(&source, b"other pixels").unwrap();
+        let imported = import_files(&vault, &settings, &[source], None).await.unwrap();
+        assert_eq!(imported[0].path, "assets/shot 1.png");
+
+        // New attachments go to the configured folder
+        let media = AttachmentSettings { folder: "media".to_string(), ..AttachmentSettings::default() };
+        assert_eq!(
+            save_attachment(&vault, &media, None, "new.png", b"new pixels").await.unwrap(),
+            "media/new.png"
+        );
+
+        // A changed file is no longer reused
+        std::fs::write(dir.path().join("assets/shot.png"), b"edited").unwrap();
+        assert_eq!(
+            save_attachment(&vault, &settings, None, "shot.png", b"pixels").await.unwrap(),
+            "assets/shot 2.png"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_attachments_rewrites_embeds() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::create_dir_all(dir.path().join("inbox")).unwrap();
+        std::fs::write(dir.path().join("assets/shot.png"), b"pixels").unwrap();
+        std::fs::write(dir.path().join("inbox/shot 1.png"), b"pixels").unwrap();
+        std::fs::write(dir.path().join("inbox/other.png"), b"other").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let settings = AttachmentSettings::default();
+        vault
+            .write_note(
+                "inbox/Note.md",
+                "![[shot 1.png]]\n![](shot%201.png)\n![[inbox/shot 1.png|200]]\n![[./shot 1.png]]\n![[other.png]]\n",
+            )
+            .await
+            .unwrap();
+
+        let result = dedupe_attachments(&vault).await.unwrap();
+        assert_eq!(result.duplicates.len(), 1);
+        assert_eq!(result.duplicates[0].kept, "assets/shot.png");
+        assert_eq!(result.duplicates[0].removed, ["inbox/shot 1.png"]);
+        assert_eq!(result.bytes_saved, 6);
+        assert_eq!(result.notes_updated, 1);
+
+        assert_eq!(
+            vault.read_note("inbox/Note.md").await.unwrap(),
+            "![[shot.png]]\n![](../assets/shot.png)\n![[shot.png|200]]\n![[shot.png]]\n![[other.png]]\n"
+        );
+        assert!(!dir.path().join("inbox/shot 1.png").exists());
+        assert!(dir.path().join("inbox/other.png").exists());
+
+        // The rebuilt hash map finds the kept copy
+        assert_eq!(
+            save_attachment(&vault, &settings, Some("inbox"), "again.png", b"pixels").await.unwrap(),
+            "assets/shot.png"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_keeps_copies_for_encrypted_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("assets/a.png"), b"pixels").unwrap();
+        std::fs::write(dir.path().join("assets/b.png"), b"pixels").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("Plain.md", "![[b.png]]\n").await.unwrap();
+        let secret = crate::encryption::encrypt_note("---\nencrypted: true\n---\n![[b.png]]\n", "pass").unwrap();
+        vault.write_note("Secret.md", &secret).await.unwrap();
+
+        let result = dedupe_attachments(&vault).await.unwrap();
+        assert_eq!(result.duplicates[0].removed, ["assets/b.png"]);
+        assert_eq!((result.notes_updated, result.encrypted_notes, result.bytes_saved), (1, 1, 0));
+        assert_eq!(vault.read_note("Plain.md").await.unwrap(), "![[a.png]]\n");
+        assert!(dir.path().join("assets/b.png").exists());
+    }
+
+    #[tokio::test]
+    async fn test_imported_attachments_are_reused() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::write(source.path().join("diagram.png"), b"pixels").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let settings = AttachmentSettings::default();
+
+        crate::import_obsidian_vault(&vault, source.path(), Some("imported"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            save_attachment(&vault, &settings, None, "copy.png", b"pixels").await.unwrap(),
+            "imported/diagram.png"
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Attachments: copying external files into the vault.
+//!
+//! Markdown files become notes in the target folder and are indexed right
+//! away; everything else is copied to the attachment folder from the vault's
+//! [`AttachmentSettings`]. Name collisions
+//! get a number suffix (`photo 1.png`), never overwriting existing files.
+//! An attachment with the same content as one already in the vault isn't
+//! copied; the existing file is returned instead (see [`crate::assets`]).
+//!
+//! Downloaded attachments are fetched by the caller and stored with
+//! [`save_attachment`] after their content type and size were checked.
+//! Pasted images also follow the settings' naming template.
+
+use crate::assets::{find_asset, store_asset, write_asset};
+use crate::templates::{render_template, TemplateContext};
+use crate::vault::{Result, Vault, VaultError, VaultEvent};
+use chrono::NaiveDateTime;
+use core_fs::hash_bytes;
+use shared_types::{AttachmentLocation, AttachmentSettings, ImportedFile, ImportedFileKind};
+use std::path::{Component, Path, PathBuf};
+use tracing::{debug, info, instrument};
+
+/// Largest attachment accepted from a download.
+pub const MAX_ATTACHMENT_BYTES: usize = 25 * 1024 * 1024;
+
+/// Content types accepted for downloads, with their file extension.
+const ATTACHMENT_CONTENT_TYPES: &[(&str, &str)] = &[
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/svg+xml", "svg"),
+    ("image/bmp", "bmp"),
+    ("application/pdf", "pdf"),
+];
+
+/// Copy external files into the vault, e.g. after a drag-and-drop.
+///
+/// `target_folder` is the vault folder for markdown files (vault root if
+/// None); other files go where `settings` puts attachments of notes in that
+/// folder. Returns one descriptor per file, in the order given, so the caller
+/// can link or embed them immediately.
+#[instrument(skip(vault, settings))]
+pub async fn import_files(
+    vault: &Vault,
+    settings: &AttachmentSettings,
+    paths: &[PathBuf],
+    target_folder: Option<&str>,
+) -> Result<Vec<ImportedFile>> {
+    let note_folder = vault_folder(target_folder.unwrap_or(""))?;
+    let attachment_folder = vault_folder(&folder_attachment_folder(settings, &note_folder.to_string_lossy()))?;
+
+    let mut imported = Vec::with_capacity(paths.len());
+    let mut note_ids = Vec::new();
+
+    for source in paths {
+        let file_name = source
+            .file_name()
+            .and_then(|n| n.to_str())
+            .filter(|_| source.is_file())
+            .ok_or_else(|| VaultError::InvalidImport(format!("Not a file: {}", source.display())))?;
+
+        let is_markdown = source
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("md"));
+
+        let (kind, target, note_id) = if is_markdown {
+            let content = tokio::fs::read_to_string(source)
+                .await
+                .map_err(core_fs::FsError::from)?;
+            let target = vault.fs().unique_path(&note_folder, file_name).await;
+            vault.fs().write_file(&target, &content).await?;
+
+            let note_id = vault.index_file(&target).await?;
+            note_ids.extend(note_id);
+            (ImportedFileKind::Note, target, note_id)
+        } else {
+            let bytes = tokio::fs::read(source).await.map_err(core_fs::FsError::from)?;
+            let target = store_asset(vault, &attachment_folder, file_name, &bytes).await?;
+            (ImportedFileKind::Attachment, target, None)
+        };
+
+        imported.push(ImportedFile {
+            source_path: source.to_string_lossy().to_string(),
+            path: target.to_string_lossy().to_string(),
+            kind,
+            note_id,
+        });
+    }
+
+    if !note_ids.is_empty() {
+        vault.emit(VaultEvent::NotesUpdated(note_ids));
+    }
+
+    info!("Imported {} files", imported.len());
+    Ok(imported)
+}
+
+/// Store downloaded bytes as an attachment.
+///
+/// `folder` defaults to the attachment folder from `settings` (for a note
+/// at the vault root). Returns the vault-relative path to embed, which is an
+/// existing attachment's if it has the same content.
+#[instrument(skip(vault, settings, bytes))]
+pub async fn save_attachment(
+    vault: &Vault,
+    settings: &AttachmentSettings,
+    folder: Option<&str>,
+    file_name: &str,
+    bytes: &[u8],
+) -> Result<String> {
+    let folder = match folder {
+        Some(folder) => vault_folder(folder)?,
+        None => vault_folder(&attachment_folder(settings, None))?,
+    };
+    let target = store_asset(vault, &folder, file_name, bytes).await?;
+
+    info!("Saved attachment: {}", target.display());
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Save a pasted image according to the attachment settings.
+///
+/// `note_path` is the note the image is pasted into. If an identical file
+/// already exists in the vault or the target folder, it is reused instead of
+/// saved again.
+/// Returns the path relative to the note's folder (or the vault root without
+/// a note), so links keep working when a note moves with its attachments.
+#[instrument(skip(vault, bytes))]
+pub async fn save_pasted_image(
+    vault: &Vault,
+    settings: &AttachmentSettings,
+    note_path: Option<&str>,
+    bytes: &[u8],
+    extension: &str,
+    now: NaiveDateTime,
+) -> Result<String> {
+    let folder = vault_folder(&attachment_folder(settings, note_path))?;
+
+    let existing = match find_asset(vault, bytes).await? {
+        Some(existing) => Some(existing),
+        None => find_identical_file(vault, &folder, bytes).await?,
+    };
+    let path = match existing {
+        Some(existing) => {
+            debug!("Reusing identical attachment: {}", existing.display());
+            existing
+        }
+        None => {
+            let extension: String = extension.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+            let name = render_attachment_name(settings, note_path, now);
+            let target = vault
+                .fs()
+                .unique_path(&folder, &attachment_file_name(&name, &extension))
+                .await;
+            write_asset(vault, &target, bytes).await?;
+            info!("Saved pasted image: {}", target.display());
+            target
+        }
+    };
+
+    Ok(relative_to_note(&path.to_string_lossy(), note_path))
+}
+
+/// Find a file in `folder` (not recursive) with the same content.
+async fn find_identical_file(vault: &Vault, folder: &Path, bytes: &[u8]) -> Result<Option<PathBuf>> {
+    let absolute = vault.fs().to_absolute(folder);
+    let Ok(mut entries) = tokio::fs::read_dir(&absolute).await else {
+        return Ok(None);
+    };
+
+    let hash = hash_bytes(bytes);
+    while let Some(entry) = entries.next_entry().await.map_err(core_fs::FsError::from)? {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let same_size = entry
+            .metadata()
+            .await
+            .is_ok_and(|m| m.is_file() && m.len() == bytes.len() as u64);
+        if file_name.starts_with('.') || !same_size {
+            continue;
+        }
+
+        let existing = tokio::fs::read(entry.path()).await.map_err(core_fs::FsError::from)?;
+        if hash_bytes(&existing) == hash {
+            return Ok(Some(folder.join(file_name)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Vault folder for a new attachment of the note at `note_path`.
+fn attachment_folder(settings: &AttachmentSettings, note_path: Option<&str>) -> String {
+    let note_dir = note_path
+        .and_then(|p| p.rsplit_once('/'))
+        .map(|(dir, _)| dir)
+        .unwrap_or("");
+    folder_attachment_folder(settings, note_dir)
+}
+
+/// Vault folder for new attachments of notes in `note_dir`.
+fn folder_attachment_folder(settings: &AttachmentSettings, note_dir: &str) -> String {
+    match settings.location {
+        AttachmentLocation::VaultFolder => settings.folder.clone(),
+        AttachmentLocation::SameFolder => note_dir.to_string(),
+        AttachmentLocation::NoteSubfolder if note_dir.is_empty() => settings.folder.clone(),
+        AttachmentLocation::NoteSubfolder => format!("{}/{}", note_dir, settings.folder),
+    }
+}
+
+/// File name (without extension) from the settings' name template.
+fn render_attachment_name(settings: &AttachmentSettings, note_path: Option<&str>, now: NaiveDateTime) -> String {
+    let note = note_path
+        .map(|p| p.rsplit('/').next().unwrap_or(p))
+        .map(|name| name.strip_suffix(".md").unwrap_or(name))
+        .unwrap_or("");
+
+    let ctx = TemplateContext::for_date(now.date())
+        .with_var("time", now.format("%H%M%S").to_string())
+        .with_var("timestamp", now.format("%Y%m%d%H%M%S").to_string())
+        .with_var("note", note);
+    render_template(&settings.name_template, &ctx)
+}
+
+/// Express a vault-relative path relative to the folder of `note_path`.
+fn relative_to_note(path: &str, note_path: Option<&str>) -> String {
+    let Some((note_dir, _)) = note_path.and_then(|p| p.rsplit_once('/')) else {
+        return path.to_string();
+    };
+
+    let note_dir: Vec<&str> = note_dir.split('/').collect();
+    let target: Vec<&str> = path.split('/').collect();
+    let common = note_dir
+        .iter()
+        .zip(&target[..target.len() - 1])
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts = vec![".."; note_dir.len() - common];
+    parts.extend(&target[common..]);
+    parts.join("/")
+}
+
+/// File extension for an accepted attachment content type.
+///
+/// Parameters like `; charset=utf-8` are ignored. Returns None for types
+/// that aren't accepted.
+pub fn attachment_extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    ATTACHMENT_CONTENT_TYPES
+        .iter()
+        .find(|(t, _)| *t == mime)
+        .map(|(_, ext)| *ext)
+}
+
+/// File name for a downloaded attachment.
+///
+/// `name` is the last URL path segment; it is sanitized and gets
+/// `extension` appended unless it already ends with it.
+pub fn attachment_file_name(name: &str, extension: &str) -> String {
+    let name = sanitize_file_name(name);
+    let has_extension = name.rsplit_once('.').is_some_and(|(_, ext)| {
+        ext.eq_ignore_ascii_case(extension) || (extension == "jpg" && ext.eq_ignore_ascii_case("jpeg"))
+    });
+
+    if has_extension {
+        name
+    } else {
+        format!("{}.{}", name, extension)
+    }
+}
+
+/// Replace characters that aren't allowed in file names on any platform.
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim_matches(|c: char| c == '.' || c.is_whitespace());
+
+    if sanitized.is_empty() {
+        "attachment".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// Validate a vault-relative folder path.
+pub(crate) fn vault_folder(folder: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(folder.trim_matches('/'));
+    if path.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(path)
+    } else {
+        Err(VaultError::InvalidImport(format!("Invalid target folder: {}", folder)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_folder() {
+        assert_eq!(vault_folder("").unwrap(), Path::new(""));
+        assert_eq!(vault_folder("/inbox/2024/").unwrap(), Path::new("inbox/2024"));
+        assert!(vault_folder("../outside").is_err());
+        assert!(vault_folder("inbox/../../outside").is_err());
+    }
+
+    #[test]
+    fn test_attachment_extension() {
+        assert_eq!(attachment_extension("image/png"), Some("png"));
+        assert_eq!(attachment_extension("Image/JPEG; charset=binary"), Some("jpg"));
+        assert_eq!(attachment_extension("application/pdf"), Some("pdf"));
+        assert_eq!(attachment_extension("text/html"), None);
+    }
+
+    #[test]
+    fn test_attachment_file_name() {
+        assert_eq!(attachment_file_name("diagram.png", "png"), "diagram.png");
+        assert_eq!(attachment_file_name("photo.JPEG", "jpg"), "photo.JPEG");
+        assert_eq!(attachment_file_name("image", "webp"), "image.webp");
+        assert_eq!(attachment_file_name("a:b*c?.pdf", "pdf"), "a-b-c-.pdf");
+        assert_eq!(attachment_file_name("..", "png"), "attachment.png");
+    }
+
+    #[test]
+    fn test_attachment_folder() {
+        let mut settings = AttachmentSettings::default();
+        assert_eq!(attachment_folder(&settings, Some("projects/plan.md")), "assets");
+
+        settings.location = AttachmentLocation::SameFolder;
+        assert_eq!(attachment_folder(&settings, Some("projects/plan.md")), "projects");
+        assert_eq!(attachment_folder(&settings, Some("plan.md")), "");
+
+        settings.location = AttachmentLocation::NoteSubfolder;
+        assert_eq!(attachment_folder(&settings, Some("projects/plan.md")), "projects/assets");
+        assert_eq!(attachment_folder(&settings, None), "assets");
+    }
+
+    #[test]
+    fn test_render_attachment_name() {
+        let now = chrono::NaiveDate::from_ymd_opt(2025, 12, 8)
+            .and_then(|d| d.and_hms_opt(14, 30, 0))
+            .unwrap();
+        let mut settings = AttachmentSettings::default();
+        assert_eq!(render_attachment_name(&settings, None, now), "Pasted image 20251208143000");
+
+        settings.name_template = "{{note}} {{date}} {{time}}".to_string();
+        assert_eq!(render_attachment_name(&settings, Some("projects/plan.md"), now), "plan 2025-12-08 143000");
+    }
+
+    #[test]
+    fn test_relative_to_note() {
+        assert_eq!(relative_to_note("assets/a.png", None), "assets/a.png");
+        assert_eq!(relative_to_note("assets/a.png", Some("note.md")), "assets/a.png");
+        assert_eq!(relative_to_note("projects/assets/a.png", Some("projects/plan.md")), "assets/a.png");
+        assert_eq!(relative_to_note("projects/a.png", Some("projects/plan.md")), "a.png");
+        assert_eq!(relative_to_note("assets/a.png", Some("projects/2024/plan.md")), "../../assets/a.png");
+        assert_eq!(relative_to_note("projects/x/a.png", Some("projects/y/plan.md")), "../x/a.png");
+    }
+}
@@ -0,0 +1,225 @@
+//! In-memory autocomplete index.
+//!
+//! Titles, aliases, tags, property keys and values, task contexts and
+//! headings are loaded from the database once and kept per note, so
+//! completions are answered from memory with one prefix range lookup. The
+//! vault keeps the index current from its events: updated notes are
+//! reloaded, deleted notes taken out, and a full index rebuilds it.
+
+use core_storage::AutocompleteTerm;
+use shared_types::{AutocompleteItem, AutocompleteKind};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Separates the key from the value in property value entries.
+const KEY_SEPARATOR: char = '\0';
+
+/// A term and the notes using it.
+struct Entry {
+    text: String,
+    notes: BTreeSet<i64>,
+}
+
+/// Autocomplete terms by kind, matched case-insensitively by prefix.
+#[derive(Default)]
+pub struct AutocompleteIndex {
+    /// Entries by kind and lowercase term (`key\0value` for property values).
+    entries: HashMap<AutocompleteKind, BTreeMap<String, Entry>>,
+    /// The entries of each note, to take them out when it changes.
+    notes: HashMap<i64, Vec<(AutocompleteKind, String)>>,
+}
+
+impl AutocompleteIndex {
+    /// Build the index from all terms in the vault.
+    pub fn new(terms: Vec<AutocompleteTerm>) -> Self {
+        let mut index = Self::default();
+        for term in terms {
+            index.insert(term);
+        }
+        index
+    }
+
+    /// Replace the terms of `note_ids` with `terms` (the notes' current terms).
+    pub fn update(&mut self, note_ids: &[i64], terms: Vec<AutocompleteTerm>) {
+        self.remove(note_ids);
+        for term in terms {
+            self.insert(term);
+        }
+    }
+
+    /// Take the terms of deleted notes out.
+    pub fn remove(&mut self, note_ids: &[i64]) {
+        for note_id in note_ids {
+            for (kind, lower) in self.notes.remove(note_id).unwrap_or_default() {
+                let Some(entries) = self.entries.get_mut(&kind) else {
+                    continue;
+                };
+                if let Some(entry) = entries.get_mut(&lower) {
+                    entry.notes.remove(note_id);
+                    if entry.notes.is_empty() {
+                        entries.remove(&lower);
+                    }
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, term: AutocompleteTerm) {
+        let text = term.text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let lower = match &term.key {
+            Some(key) => format!("{}{}{}", key.to_lowercase(), KEY_SEPARATOR, text.to_lowercase()),
+            None => text.to_lowercase(),
+        };
+
+        let entry = self
+            .entries
+            .entry(term.kind)
+            .or_default()
+            .entry(lower.clone())
+            .or_insert_with(|| Entry {
+                text: text.to_string(),
+                notes: BTreeSet::new(),
+            });
+        if entry.notes.insert(term.note_id) {
+            self.notes.entry(term.note_id).or_default().push((term.kind, lower));
+        }
+    }
+
+    /// Terms of `kind` starting with `prefix` (ignoring case), most used
+    /// first. Property values are those of the property `key`, or of any
+    /// property without one.
+    pub fn search(&self, kind: AutocompleteKind, prefix: &str, key: Option<&str>, limit: usize) -> Vec<AutocompleteItem> {
+        let Some(entries) = self.entries.get(&kind) else {
+            return Vec::new();
+        };
+        let prefix = prefix.trim().to_lowercase();
+
+        let mut items: Vec<AutocompleteItem> = match (kind, key) {
+            (AutocompleteKind::PropertyValue, Some(key)) => {
+                let start = format!("{}{}{}", key.to_lowercase(), KEY_SEPARATOR, prefix);
+                entries
+                    .range(start.clone()..)
+                    .take_while(|(lower, _)| lower.starts_with(&start))
+                    .map(|(_, entry)| item(&entry.text, &entry.notes))
+                    .collect()
+            }
+            (AutocompleteKind::PropertyValue, None) => {
+                // The same value under different keys is one suggestion
+                let mut values: BTreeMap<String, (&str, BTreeSet<i64>)> = BTreeMap::new();
+                for (lower, entry) in entries {
+                    let value = lower.split_once(KEY_SEPARATOR).map(|(_, v)| v).unwrap_or(lower);
+                    if value.starts_with(&prefix) {
+                        let (_, notes) = values.entry(value.to_string()).or_insert((&entry.text, BTreeSet::new()));
+                        notes.extend(&entry.notes);
+                    }
+                }
+                values.values().map(|(text, notes)| item(text, notes)).collect()
+            }
+            _ => entries
+                .range(prefix.clone()..)
+                .take_while(|(lower, _)| lower.starts_with(&prefix))
+                .map(|(_, entry)| item(&entry.text, &entry.notes))
+                .collect(),
+        };
+
+        items.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.text.cmp(&b.text)));
+        items.truncate(limit);
+        items
+    }
+}
+
+fn item(text: &str, notes: &BTreeSet<i64>) -> AutocompleteItem {
+    AutocompleteItem {
+        text: text.to_string(),
+        count: notes.len() as i64,
+        note_id: (notes.len() == 1).then(|| notes.iter().next().copied()).flatten(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(note_id: i64, kind: AutocompleteKind, key: Option<&str>, text: &str) -> AutocompleteTerm {
+        AutocompleteTerm {
+            note_id,
+            kind,
+            key: key.map(str::to_string),
+            text: text.to_string(),
+        }
+    }
+
+    fn texts(items: Vec<AutocompleteItem>) -> Vec<(String, i64)> {
+        items.into_iter().map(|i| (i.text, i.count)).collect()
+    }
+
+    #[test]
+    fn test_search_and_update() {
+        use AutocompleteKind::*;
+        let mut index = AutocompleteIndex::new(vec![
+            term(1, Title, None, "Project Plan"),
+            term(1, Tag, None, "project"),
+            term(2, Tag, None, "project"),
+            term(2, Tag, None, "personal"),
+            term(1, PropertyValue, Some("status"), "active"),
+            term(2, PropertyValue, Some("status"), "Archived"),
+            term(2, PropertyValue, Some("phase"), "active"),
+        ]);
+
+        assert_eq!(texts(index.search(Tag, "P", None, 10)), vec![("project".into(), 2), ("personal".into(), 1)]);
+        assert_eq!(index.search(Title, "proj", None, 10)[0].note_id, Some(1));
+        assert_eq!(texts(index.search(PropertyValue, "a", Some("Status"), 10)), vec![("Archived".into(), 1), ("active".into(), 1)]);
+        assert_eq!(texts(index.search(PropertyValue, "act", None, 10)), vec![("active".into(), 2)]);
+        assert_eq!(index.search(Tag, "", None, 1).len(), 1);
+
+        // Note 2 lost its tags, note 1 was deleted
+        index.update(&[2], vec![term(2, Tag, None, "work")]);
+        index.remove(&[1]);
+        assert_eq!(texts(index.search(Tag, "", None, 10)), vec![("work".into(), 1)]);
+        assert!(index.search(Title, "", None, 10).is_empty());
+        assert!(index.search(PropertyValue, "", None, 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_vault_keeps_index_current() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "# Alpha\n\n#work\n- [ ] Call @phone\n").unwrap();
+        let vault = crate::Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+        let id = vault.repo().get_note_id_by_path("a.md").await.unwrap().unwrap();
+        vault.repo().set_property(id, "status", Some("active"), Some("text")).await.unwrap();
+
+        let search = |kind, prefix: &'static str| {
+            let vault = &vault;
+            async move {
+                let items = vault.autocomplete(kind, prefix, None, 10).await.unwrap();
+                items.into_iter().map(|i| i.text).collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(search(AutocompleteKind::Tag, "w").await, vec!["work"]);
+        assert_eq!(search(AutocompleteKind::Context, "").await, vec!["phone"]);
+        let registered = shared_types::ContextMeta {
+            name: "home".to_string(),
+            icon: None,
+            description: None,
+            location: None,
+        };
+        vault.repo().set_context(&registered).await.unwrap();
+        assert_eq!(search(AutocompleteKind::Context, "").await, vec!["phone", "home"]);
+        assert_eq!(search(AutocompleteKind::Context, "H").await, vec!["home"]);
+        assert_eq!(search(AutocompleteKind::PropertyValue, "act").await, vec!["active"]);
+
+        // The write's update event reaches the index in the background
+        vault.write_note("b.md", "# Beta\n\n#writing\n").await.unwrap();
+        for _ in 0..100 {
+            if search(AutocompleteKind::Tag, "w").await.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(search(AutocompleteKind::Tag, "w").await, vec!["work", "writing"]);
+        assert_eq!(search(AutocompleteKind::Heading, "be").await, vec!["Beta"]);
+    }
+}
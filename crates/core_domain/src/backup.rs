@@ -0,0 +1,211 @@
+//! Vault backups: a zip of the vault's files and a copy of its database.
+//!
+//! Every file under the vault root is added except the database files and
+//! caches in `.neuroflow`. The database is added as a consistent copy made
+//! while it stays in use, at `.neuroflow/neuroflow.db`, wherever the vault
+//! keeps its data. Files of external folders are not part of the vault and
+//! are left out.
+//!
+//! The zip is written under a temporary name and only renamed into place
+//! once complete, so a cancelled or failed backup leaves no partial zip.
+
+use crate::jobs::Job;
+use crate::vault::{Result, Vault, VaultError};
+use core_fs::FsError;
+use shared_types::BackupResult;
+use std::path::{Path, PathBuf};
+use tracing::{info, instrument};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Entries of `.neuroflow` that are left out: the live database and caches.
+const SKIPPED_DATA_ENTRIES: &[&str] = &["neuroflow.db", "neuroflow.db-wal", "neuroflow.db-shm", "cache"];
+
+/// Name of the database copy in the zip.
+const DATABASE_ENTRY: &str = ".neuroflow/neuroflow.db";
+
+/// Zip the vault to `output_path`.
+#[instrument(skip(vault, job))]
+pub async fn backup_vault(vault: &Vault, output_path: &Path, job: Option<&Job>) -> Result<BackupResult> {
+    let partial = output_path.with_extension("zip.partial");
+    let files = list_files(vault, &[output_path, &partial]).await?;
+    // The database copy counts as the last item
+    let total = files.len() + 1;
+
+    if let Some(parent) = output_path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(FsError::from)?;
+    }
+    let result = write_zip(vault, &files, &partial, job).await;
+    let cancelled = job.is_some_and(|job| job.is_cancelled());
+    if result.is_err() || cancelled {
+        let _ = tokio::fs::remove_file(&partial).await;
+    }
+    result?;
+
+    if cancelled {
+        info!("Backup cancelled");
+        return Ok(BackupResult {
+            output_path: output_path.to_string_lossy().to_string(),
+            files: 0,
+            size_bytes: 0,
+            cancelled: true,
+        });
+    }
+
+    tokio::fs::rename(&partial, output_path).await.map_err(FsError::from)?;
+    let size_bytes = tokio::fs::metadata(output_path).await.map_err(FsError::from)?.len();
+    if let Some(job) = job {
+        job.progress(vault, total, total, None);
+    }
+
+    info!("Backed up {} files to {} ({} bytes)", total, output_path.display(), size_bytes);
+    Ok(BackupResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        files: total,
+        size_bytes,
+        cancelled: false,
+    })
+}
+
+/// Write the files and the database copy to a zip at `path`. Stops early,
+/// without finishing the zip, if the job is cancelled.
+///
+/// The files are streamed into the zip on a blocking thread, so neither a
+/// large file nor the compression holds up the async runtime. The database
+/// is copied up front and added last.
+async fn write_zip(vault: &Vault, files: &[PathBuf], path: &Path, job: Option<&Job>) -> Result<()> {
+    let database = copy_database(vault).await?;
+    let entries: Vec<(PathBuf, String)> = files
+        .iter()
+        .map(|file| (vault.fs().to_absolute(file), file.to_string_lossy().replace('\\', "/")))
+        .chain(std::iter::once((database.clone(), DATABASE_ENTRY.to_string())))
+        .collect();
+    let path = path.to_path_buf();
+    let job = job.cloned();
+    let event_tx = vault.event_sender();
+
+    let written = tokio::task::spawn_blocking(move || {
+        let total = entries.len();
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(std::fs::File::create(&path).map_err(FsError::from)?);
+
+        for (i, (file, name)) in entries.into_iter().enumerate() {
+            if let Some(job) = &job {
+                if job.is_cancelled() {
+                    return Ok(());
+                }
+                job.progress_to(
+                    |event| {
+                        let _ = event_tx.send(event);
+                    },
+                    i,
+                    total,
+                    Some(name.clone()),
+                );
+            }
+            let mut reader = std::fs::File::open(&file).map_err(FsError::from)?;
+            zip.start_file(name, options).map_err(zip_error)?;
+            std::io::copy(&mut reader, &mut zip).map_err(FsError::from)?;
+        }
+
+        zip.finish().map_err(zip_error)?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| VaultError::Backup(e.to_string()));
+    let _ = tokio::fs::remove_file(&database).await;
+    written?
+}
+
+/// Copy the database to a temporary file in the cache; returns its path.
+async fn copy_database(vault: &Vault) -> Result<PathBuf> {
+    let cache_dir = vault.fs().cache_dir();
+    tokio::fs::create_dir_all(&cache_dir).await.map_err(FsError::from)?;
+    let path = cache_dir.join("backup.db");
+    // The copy can't overwrite a file, e.g. one left by a crash
+    let _ = tokio::fs::remove_file(&path).await;
+    vault.repo().copy_database(&path).await?;
+    Ok(path)
+}
+
+/// Vault-relative paths of the files to back up, in path order, without
+/// `skip` (absolute paths).
+async fn list_files(vault: &Vault, skip: &[&Path]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut folders = vec![PathBuf::new()];
+
+    while let Some(folder) = folders.pop() {
+        let mut entries = tokio::fs::read_dir(vault.fs().to_absolute(&folder))
+            .await
+            .map_err(FsError::from)?;
+        while let Some(entry) = entries.next_entry().await.map_err(FsError::from)? {
+            let path = folder.join(entry.file_name());
+            if path.parent() == Some(Path::new(".neuroflow"))
+                && SKIPPED_DATA_ENTRIES.iter().any(|name| entry.file_name() == *name)
+            {
+                continue;
+            }
+
+            let file_type = entry.file_type().await.map_err(FsError::from)?;
+            if file_type.is_dir() {
+                folders.push(path);
+            } else if file_type.is_file() && !skip.contains(&entry.path().as_path()) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn zip_error(e: zip::result::ZipError) -> VaultError {
+    VaultError::Backup(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::JobRegistry;
+    use shared_types::JobKind;
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn test_backup_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("projects")).unwrap();
+        std::fs::write(dir.path().join("projects/plan.md"), "# Plan\n").unwrap();
+        std::fs::write(dir.path().join("photo.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        // Written into the vault itself, which it must not back up
+        let output = dir.path().join("backups/vault.zip");
+        let result = backup_vault(&vault, &output, None).await.unwrap();
+        assert!(!result.cancelled);
+        assert_eq!(result.size_bytes, std::fs::metadata(&output).unwrap().len());
+
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let mut names: Vec<_> = zip.file_names().map(str::to_string).collect();
+        names.sort();
+        assert!(names.contains(&"projects/plan.md".to_string()));
+        assert!(names.contains(&"photo.png".to_string()));
+        assert!(names.contains(&DATABASE_ENTRY.to_string()));
+        assert!(!names.iter().any(|name| name.starts_with("backups/") || name.contains("cache/")));
+        assert_eq!(result.files, names.len());
+
+        let mut plan = String::new();
+        zip.by_name("projects/plan.md").unwrap().read_to_string(&mut plan).unwrap();
+        assert_eq!(plan, "# Plan\n");
+
+        // A cancelled backup leaves nothing behind
+        let registry = JobRegistry::new();
+        let job = registry.start(dir.path(), 1, JobKind::Backup);
+        job.cancel();
+        let cancelled = dir.path().join("backups/cancelled.zip");
+        let result = backup_vault(&vault, &cancelled, Some(&job)).await.unwrap();
+        assert!(result.cancelled);
+        assert!(!cancelled.exists());
+        assert!(!cancelled.with_extension("zip.partial").exists());
+    }
+}
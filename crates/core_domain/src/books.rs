@@ -0,0 +1,271 @@
+//! Book tracking: OpenLibrary metadata and reading shelves.
+//!
+//! Books are notes with `type: book`. Metadata looked up on OpenLibrary
+//! (by ISBN through the books API, or by title through the search API) is
+//! stored as the note's `title`, `author`, `isbn`, `published`, `pages`
+//! and `cover` properties. Fetching is left to the caller; this module
+//! parses the responses.
+//!
+//! The shelves group the books by their `status` property.
+
+use crate::vault::{Result, Vault};
+use serde_json::Value;
+use shared_types::{
+    BookMetadata, BookShelf, FilterMatchMode, PropertyFilter, PropertyOperator, QueryRequest, QueryResultType,
+    QuerySort, SortDirection,
+};
+use std::collections::BTreeMap;
+use tracing::{info, instrument};
+
+/// Statuses shown first on the shelves, in this order; others follow
+/// alphabetically, then books without a status.
+const STATUS_ORDER: &[&str] = &["reading", "to-read", "read", "abandoned"];
+
+/// An ISBN-10 or ISBN-13 without separators, or None if `input` isn't one
+/// or its check digit is wrong.
+pub fn normalize_isbn(input: &str) -> Option<String> {
+    let isbn: String = input
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    // Digit values, with the ISBN-10 check digit X as 10
+    let digits: Vec<u32> = isbn
+        .char_indices()
+        .map(|(i, c)| match c {
+            'X' if i == 9 && isbn.len() == 10 => Some(10),
+            _ => c.to_digit(10),
+        })
+        .collect::<Option<_>>()?;
+
+    let valid = match digits.len() {
+        // Weights 10 down to 1, the sum a multiple of 11
+        10 => digits.iter().zip((1..=10).rev()).map(|(d, w)| d * w).sum::<u32>() % 11 == 0,
+        // Weights alternating 1 and 3, the sum a multiple of 10
+        13 => digits.iter().zip([1, 3].iter().cycle()).map(|(d, w)| d * w).sum::<u32>() % 10 == 0,
+        _ => false,
+    };
+    valid.then_some(isbn)
+}
+
+/// Metadata from an OpenLibrary books API response
+/// (`/api/books?bibkeys=ISBN:<isbn>&format=json&jscmd=data`).
+pub fn parse_isbn_response(json: &Value, isbn: &str) -> Option<BookMetadata> {
+    let book = json.get(format!("ISBN:{}", isbn))?;
+    let identifiers = book.get("identifiers");
+    let first_isbn = |kind: &str| {
+        identifiers
+            .and_then(|ids| ids.get(kind))
+            .and_then(|list| list.get(0))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    Some(BookMetadata {
+        title: string(book.get("title")),
+        authors: book
+            .get("authors")
+            .and_then(Value::as_array)
+            .map(|authors| authors.iter().filter_map(|a| string(a.get("name"))).collect())
+            .unwrap_or_default(),
+        isbn: first_isbn("isbn_13").or_else(|| first_isbn("isbn_10")).or_else(|| Some(isbn.to_string())),
+        published: string(book.get("publish_date")),
+        pages: book.get("number_of_pages").and_then(Value::as_i64),
+        cover_url: book.get("cover").and_then(|cover| string(cover.get("large"))),
+        cover_path: None,
+    })
+}
+
+/// Metadata of the first result of an OpenLibrary search API response
+/// (`/search.json?title=<title>`).
+pub fn parse_search_response(json: &Value) -> Option<BookMetadata> {
+    let doc = json.get("docs")?.get(0)?;
+    let isbns = strings(doc.get("isbn")).unwrap_or_default();
+
+    Some(BookMetadata {
+        title: string(doc.get("title")),
+        authors: strings(doc.get("author_name")).unwrap_or_default(),
+        isbn: isbns
+            .iter()
+            .find(|isbn| isbn.len() == 13)
+            .or_else(|| isbns.first())
+            .cloned(),
+        published: doc.get("first_publish_year").and_then(Value::as_i64).map(|year| year.to_string()),
+        pages: doc.get("number_of_pages_median").and_then(Value::as_i64),
+        cover_url: doc
+            .get("cover_i")
+            .and_then(Value::as_i64)
+            .map(|id| format!("https://covers.openlibrary.org/b/id/{}-L.jpg", id)),
+        cover_path: None,
+    })
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    value.and_then(Value::as_str).map(str::to_string)
+}
+
+/// An array of strings, or None if `value` isn't one.
+fn strings(value: Option<&Value>) -> Option<Vec<String>> {
+    value?.as_array()?.iter().map(|v| v.as_str().map(str::to_string)).collect()
+}
+
+/// Store book metadata as properties of a note, marking it as a book.
+/// Fields that weren't found leave the existing properties alone.
+#[instrument(skip(vault, metadata))]
+pub async fn apply_book_metadata(vault: &Vault, note_id: i64, metadata: &BookMetadata) -> Result<()> {
+    let repo = vault.repo();
+    repo.set_property(note_id, "type", Some("book"), Some("text")).await?;
+
+    let pages = metadata.pages.map(|p| p.to_string());
+    let author = (!metadata.authors.is_empty()).then(|| metadata.authors.join(", "));
+    let fields = [
+        ("title", metadata.title.as_deref(), "text"),
+        ("author", author.as_deref(), "text"),
+        ("isbn", metadata.isbn.as_deref(), "text"),
+        ("published", metadata.published.as_deref(), "text"),
+        ("pages", pages.as_deref(), "number"),
+        ("cover", metadata.cover_path.as_deref(), "text"),
+    ];
+    for (key, value, property_type) in fields {
+        if let Some(value) = value {
+            repo.set_property(note_id, key, Some(value), Some(property_type)).await?;
+        }
+    }
+    vault.refresh_autocomplete(Some(&[note_id])).await?;
+
+    info!("Stored book metadata for note {}", note_id);
+    Ok(())
+}
+
+/// The vault's books (notes with `type: book`) grouped by status, sorted
+/// by title within each shelf.
+#[instrument(skip(vault))]
+pub async fn book_shelves(vault: &Vault) -> Result<Vec<BookShelf>> {
+    let request = QueryRequest {
+        filters: vec![PropertyFilter {
+            key: "type".to_string(),
+            operator: PropertyOperator::Equals,
+            value: Some("book".to_string()),
+        }],
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Notes,
+        include_completed: false,
+        limit: Some(i32::MAX),
+        sort: Some(QuerySort {
+            property: "note_title".to_string(),
+            direction: SortDirection::Asc,
+        }),
+    };
+    let response = vault.repo().run_query(&request).await?;
+
+    // Keyed by (position, status) so the shelves come out in order
+    let mut shelves: BTreeMap<(usize, Option<String>), Vec<_>> = BTreeMap::new();
+    for item in response.results {
+        let status = item
+            .properties
+            .iter()
+            .find(|p| p.key == "status")
+            .and_then(|p| p.value.clone())
+            .filter(|s| !s.is_empty());
+        let position = match &status {
+            Some(s) => STATUS_ORDER
+                .iter()
+                .position(|known| known.eq_ignore_ascii_case(s))
+                .unwrap_or(STATUS_ORDER.len()),
+            None => STATUS_ORDER.len() + 1,
+        };
+        shelves.entry((position, status)).or_default().push(item);
+    }
+
+    Ok(shelves
+        .into_iter()
+        .map(|((_, status), books)| BookShelf { status, books })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_openlibrary_responses() {
+        assert_eq!(normalize_isbn("978-0-441-17271-9").as_deref(), Some("9780441172719"));
+        assert_eq!(normalize_isbn("0 441 17271 7").as_deref(), Some("0441172717"));
+        assert_eq!(normalize_isbn("0-8044-2957-x").as_deref(), Some("080442957X"));
+        assert_eq!(normalize_isbn("Dune"), None);
+        // Wrong check digits, and X anywhere but an ISBN-10 check digit
+        assert_eq!(normalize_isbn("978-0-441-17271-8"), None);
+        assert_eq!(normalize_isbn("0-441-17271-X"), None);
+        assert_eq!(normalize_isbn("97804411727X9"), None);
+
+        let response = json!({
+            "ISBN:9780441172719": {
+                "title": "Dune",
+                "authors": [{"name": "Frank Herbert", "url": "https://openlibrary.org/authors/OL79034A"}],
+                "identifiers": {"isbn_10": ["0441172717"], "isbn_13": ["9780441172719"]},
+                "publish_date": "1990",
+                "number_of_pages": 535,
+                "cover": {"large": "https://covers.openlibrary.org/b/id/1-L.jpg"}
+            }
+        });
+        let book = parse_isbn_response(&response, "9780441172719").unwrap();
+        assert_eq!(book.title.as_deref(), Some("Dune"));
+        assert_eq!(book.authors, vec!["Frank Herbert"]);
+        assert_eq!(book.pages, Some(535));
+        assert_eq!(book.cover_url.as_deref(), Some("https://covers.openlibrary.org/b/id/1-L.jpg"));
+        assert!(parse_isbn_response(&json!({}), "9780441172719").is_none());
+
+        let response = json!({
+            "docs": [{
+                "title": "Dune",
+                "author_name": ["Frank Herbert"],
+                "isbn": ["0441172717", "9780441172719"],
+                "first_publish_year": 1965,
+                "cover_i": 42
+            }]
+        });
+        let book = parse_search_response(&response).unwrap();
+        assert_eq!(book.isbn.as_deref(), Some("9780441172719"));
+        assert_eq!(book.published.as_deref(), Some("1965"));
+        assert_eq!(book.cover_url.as_deref(), Some("https://covers.openlibrary.org/b/id/42-L.jpg"));
+        assert!(parse_search_response(&json!({"docs": []})).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_book_shelves() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["Dune", "Emma", "Ulysses", "Notes"] {
+            std::fs::write(dir.path().join(format!("{}.md", name)), format!("# {}\n", name)).unwrap();
+        }
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        let repo = vault.repo();
+        for (path, status) in [("Dune.md", Some("read")), ("Emma.md", None), ("Ulysses.md", Some("reading"))] {
+            let id = repo.get_note_id_by_path(path).await.unwrap().unwrap();
+            let metadata = BookMetadata {
+                pages: Some(100),
+                ..Default::default()
+            };
+            apply_book_metadata(&vault, id, &metadata).await.unwrap();
+            if let Some(status) = status {
+                repo.set_property(id, "status", Some(status), Some("text")).await.unwrap();
+            }
+        }
+
+        let shelves = book_shelves(&vault).await.unwrap();
+        let summary: Vec<(Option<&str>, Vec<&str>)> = shelves
+            .iter()
+            .map(|shelf| {
+                let books = shelf.books.iter().map(|b| b.note.as_ref().unwrap().path.as_str()).collect();
+                (shelf.status.as_deref(), books)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(Some("reading"), vec!["Ulysses.md"]), (Some("read"), vec!["Dune.md"]), (None, vec!["Emma.md"])]
+        );
+    }
+}
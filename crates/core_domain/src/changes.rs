@@ -0,0 +1,285 @@
+//! Change journal - an append-only log of note changes for external tools.
+//!
+//! Every note created, modified, deleted or renamed, by the app or by the
+//! watcher picking up an external edit, is appended to
+//! `changes.jsonl` in the vault's data directory (`.neuroflow` unless the
+//! data is kept elsewhere) as one JSON record per line. Records are
+//! numbered from 1, and readers keep the last number they have seen as a
+//! cursor to fetch only what changed since.
+//!
+//! The file is read once to find where each record starts; after that only
+//! the records a reader asks for are read. Once it holds more than
+//! [`MAX_RECORDS`], the oldest are dropped, keeping the numbering, so a
+//! reader whose cursor is older than the journal is told to start over.
+//!
+//! Renames made outside the app reach the watcher as a deletion and a
+//! creation, and are journaled that way.
+
+use crate::vault::Result;
+use chrono::Utc;
+use shared_types::{ChangeKind, ChangeRecord, ChangesPage};
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Records kept before the oldest are dropped.
+const MAX_RECORDS: usize = 10_000;
+
+/// Records kept when the journal is compacted.
+const KEPT_RECORDS: usize = MAX_RECORDS / 2;
+
+/// Handle to a vault's change journal, shared by the vault and its watcher.
+#[derive(Debug, Clone)]
+pub struct ChangeJournal {
+    path: PathBuf,
+    /// Where the records are, read from the file on first use.
+    index: Arc<Mutex<Option<JournalIndex>>>,
+}
+
+/// Number and byte offset of each record in the journal file.
+#[derive(Debug, Default)]
+struct JournalIndex {
+    records: Vec<(u64, u64)>,
+    /// Length of the file when it was last read or written.
+    len: u64,
+    /// Number of the last record appended, kept if the file is deleted.
+    last_seq: u64,
+}
+
+impl ChangeJournal {
+    /// Open the journal at `path` (created on first append).
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            index: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Append a change. The journal is a side channel, so failing to write
+    /// it is logged instead of failing the change itself.
+    pub async fn record(&self, kind: ChangeKind, path: &str, old_path: Option<&str>, hash: Option<&str>) {
+        if let Err(e) = self.append(kind, path, old_path, hash).await {
+            warn!("Failed to journal {:?} of {}: {}", kind, path, e);
+        }
+    }
+
+    async fn append(
+        &self,
+        kind: ChangeKind,
+        path: &str,
+        old_path: Option<&str>,
+        hash: Option<&str>,
+    ) -> std::io::Result<()> {
+        let mut guard = self.index.lock().await;
+        let index = self.current_index(&mut guard).await?;
+        let seq = index.last_seq + 1;
+
+        let record = ChangeRecord {
+            seq,
+            timestamp: Utc::now(),
+            kind,
+            path: path.to_string(),
+            old_path: old_path.map(String::from),
+            hash: hash.map(String::from),
+        };
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+
+        index.records.push((seq, index.len));
+        index.len += line.len() as u64;
+        index.last_seq = seq;
+
+        if index.records.len() > MAX_RECORDS {
+            self.compact(index).await?;
+        }
+        Ok(())
+    }
+
+    /// Changes after `cursor` (a record number; 0 for all), at most `limit`.
+    ///
+    /// The returned cursor is the number of the last change returned, or
+    /// `cursor` if there were none. A cursor the journal can't continue
+    /// from, because the records after it were dropped or the file was
+    /// deleted, is read from the start and the page is marked `reset`.
+    pub async fn changes_since(&self, cursor: u64, limit: Option<usize>) -> Result<ChangesPage> {
+        let mut guard = self.index.lock().await;
+        let index = self.current_index(&mut guard).await.map_err(core_fs::FsError::from)?;
+
+        let first = index.records.first().map_or(index.last_seq + 1, |&(seq, _)| seq);
+        let reset = cursor > index.last_seq || cursor + 1 < first;
+        let cursor = if reset { 0 } else { cursor };
+
+        let start = index.records.partition_point(|&(seq, _)| seq <= cursor);
+        let count = (index.records.len() - start).min(limit.unwrap_or(usize::MAX));
+        let changes = match index.records.get(start) {
+            Some(&(_, offset)) if count > 0 => self.read_records(offset, count).await.map_err(core_fs::FsError::from)?,
+            _ => Vec::new(),
+        };
+        let cursor = changes.last().map_or(cursor, |record| record.seq);
+
+        Ok(ChangesPage { changes, cursor, reset })
+    }
+
+    /// The index of the journal file, read again if the file was changed or
+    /// deleted by something else.
+    async fn current_index<'a>(&self, guard: &'a mut Option<JournalIndex>) -> std::io::Result<&'a mut JournalIndex> {
+        let len = match tokio::fs::metadata(&self.path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+
+        if guard.as_ref().is_none_or(|index| index.len != len) {
+            let mut index = self.read_index().await?;
+            // Numbering goes on after the file is deleted
+            if let Some(old) = guard.as_ref() {
+                index.last_seq = index.last_seq.max(old.last_seq);
+            }
+            *guard = Some(index);
+        }
+        Ok(guard.get_or_insert_with(JournalIndex::default))
+    }
+
+    /// Read the whole file for where its records are, skipping lines that
+    /// don't parse (e.g. one cut short by a crash).
+    async fn read_index(&self) -> std::io::Result<JournalIndex> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(JournalIndex::default()),
+            Err(e) => return Err(e),
+        };
+
+        let mut index = JournalIndex {
+            len: content.len() as u64,
+            ..Default::default()
+        };
+        let mut offset = 0;
+        for line in content.split_inclusive('\n') {
+            if !line.trim().is_empty() {
+                match serde_json::from_str::<ChangeRecord>(line) {
+                    Ok(record) => {
+                        index.records.push((record.seq, offset));
+                        index.last_seq = index.last_seq.max(record.seq);
+                    }
+                    Err(e) => warn!("Skipping unreadable change record in {}: {}", self.path.display(), e),
+                }
+            }
+            offset += line.len() as u64;
+        }
+        Ok(index)
+    }
+
+    /// Read `count` records starting at byte `offset`.
+    async fn read_records(&self, offset: u64, count: usize) -> std::io::Result<Vec<ChangeRecord>> {
+        let mut file = tokio::fs::File::open(&self.path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut lines = BufReader::new(file).lines();
+
+        let mut records = Vec::with_capacity(count);
+        while records.len() < count {
+            let Some(line) = lines.next_line().await? else {
+                break;
+            };
+            if let Ok(record) = serde_json::from_str(&line) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Drop the oldest records, keeping the newest [`KEPT_RECORDS`]. The
+    /// file is rewritten under another name and renamed into place.
+    async fn compact(&self, index: &mut JournalIndex) -> std::io::Result<()> {
+        let (first_seq, start) = index.records[index.records.len() - KEPT_RECORDS];
+        let content = tokio::fs::read(&self.path).await?;
+        let kept = &content[start as usize..];
+
+        let partial = self.path.with_extension("jsonl.partial");
+        tokio::fs::write(&partial, kept).await?;
+        tokio::fs::rename(&partial, &self.path).await?;
+
+        index.records.drain(..index.records.len() - KEPT_RECORDS);
+        for (_, offset) in &mut index.records {
+            *offset -= start;
+        }
+        index.len = kept.len() as u64;
+
+        info!("Compacted change journal to records from {}", first_seq);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vault::Vault;
+    use shared_types::ChangeKind;
+
+    #[tokio::test]
+    async fn test_change_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        vault.write_note("A.md", "# A\n").await.unwrap();
+        vault.write_note("A.md", "# A\n\nMore\n").await.unwrap();
+        vault.rename_note("A.md", "B.md").await.unwrap();
+        vault.delete_note("B.md").await.unwrap();
+
+        let page = vault.changes_since(0, None).await.unwrap();
+        let kinds: Vec<ChangeKind> = page.changes.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [ChangeKind::Created, ChangeKind::Modified, ChangeKind::Renamed, ChangeKind::Deleted]
+        );
+        assert_eq!(page.cursor, 4);
+        assert_eq!(page.changes[2].old_path.as_deref(), Some("A.md"));
+        assert_eq!(page.changes[2].hash, page.changes[1].hash);
+        assert!(page.changes[3].hash.is_none());
+
+        // Only what changed after the cursor, and the journal survives reopening
+        drop(vault);
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("C.md", "# C\n").await.unwrap();
+        let page = vault.changes_since(3, Some(1)).await.unwrap();
+        assert_eq!(page.changes.len(), 1);
+        assert_eq!(page.changes[0].path, "B.md");
+        let page = vault.changes_since(page.cursor, None).await.unwrap();
+        assert_eq!(page.changes[0].seq, 5);
+        assert_eq!(page.changes[0].path, "C.md");
+        assert_eq!(vault.changes_since(page.cursor, None).await.unwrap().changes.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_change_journal_compaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = super::ChangeJournal::new(dir.path().join("changes.jsonl"));
+        for i in 0..=super::MAX_RECORDS {
+            journal.record(ChangeKind::Modified, &format!("{}.md", i), None, None).await;
+        }
+
+        // The oldest records are gone, the numbering goes on
+        let page = journal.changes_since(super::MAX_RECORDS as u64 - 1, None).await.unwrap();
+        assert!(!page.reset);
+        assert_eq!(page.changes.len(), 2);
+        assert_eq!(page.changes[1].path, format!("{}.md", super::MAX_RECORDS));
+        let page = journal.changes_since(1, Some(1)).await.unwrap();
+        assert!(page.reset);
+        assert_eq!(page.changes[0].seq, (super::MAX_RECORDS - super::KEPT_RECORDS + 2) as u64);
+
+        // A deleted journal starts over without reusing numbers
+        std::fs::remove_file(dir.path().join("changes.jsonl")).unwrap();
+        journal.record(ChangeKind::Created, "new.md", None, None).await;
+        let page = journal.changes_since(10, None).await.unwrap();
+        assert!(page.reset);
+        assert_eq!(page.changes[0].seq, super::MAX_RECORDS as u64 + 2);
+    }
+}
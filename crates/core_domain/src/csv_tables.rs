@@ -0,0 +1,261 @@
+//! CSV files to markdown tables and back.
+//!
+//! Imported CSV files become a table in a note, with the first row as the
+//! header; the delimiter (comma, semicolon, tab or pipe) is detected from
+//! the file. Pipes in cells are escaped and line breaks become `<br>`, and
+//! exporting a table undoes both.
+
+use crate::encryption::has_encrypted_body;
+use crate::habit_import::split_csv_line;
+use crate::vault::{Result, Vault, VaultError};
+use core_index::{find_tables, Table};
+use shared_types::NoteContent;
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Delimiters tried when detecting a CSV file's delimiter; the comma is
+/// last so it wins ties.
+const DELIMITERS: [char; 4] = ['|', '\t', ';', ','];
+
+/// Import a CSV file as a table into the note at `note_path`, before line
+/// `position` (1-based), or at the end. The note is created if it doesn't
+/// exist. Fails for encrypted notes while they are locked. Returns the note
+/// with its new content.
+#[instrument(skip(vault))]
+pub async fn import_csv_as_table(
+    vault: &Vault,
+    csv_path: &Path,
+    note_path: &str,
+    position: Option<usize>,
+) -> Result<NoteContent> {
+    let csv = tokio::fs::read_to_string(csv_path)
+        .await
+        .map_err(core_fs::FsError::from)?;
+    let rows = parse_csv(&csv, detect_delimiter(&csv));
+    let table = csv_to_table(rows).ok_or_else(|| VaultError::Table(format!("{} is empty", csv_path.display())))?;
+
+    let content = if vault.fs().exists(Path::new(note_path)).await {
+        vault.read_note(note_path).await?
+    } else {
+        String::new()
+    };
+    if has_encrypted_body(&content) {
+        return Err(VaultError::NoteLocked(note_path.to_string()));
+    }
+    let content = insert_block(&content, &table.to_markdown(), position);
+    let id = vault.write_note(note_path, &content).await?;
+
+    info!("Imported {} rows from {} into {}", table.rows.len(), csv_path.display(), note_path);
+    vault.note_content(id, note_path.to_string(), content).await
+}
+
+/// Export the `table_index`th table (from 0) of a note as a CSV file at
+/// `output_path`. Returns the number of rows written, header included.
+#[instrument(skip(vault))]
+pub async fn export_table_as_csv(vault: &Vault, note_id: i64, table_index: usize, output_path: &Path) -> Result<usize> {
+    let note = vault.repo().get_note(note_id).await?;
+    let content = vault.read_note(&note.path).await?;
+
+    let table = find_tables(&content)
+        .into_iter()
+        .nth(table_index)
+        .ok_or_else(|| VaultError::Table(format!("{} has no table {}", note.path, table_index)))?;
+
+    tokio::fs::write(output_path, table_to_csv(&table, ','))
+        .await
+        .map_err(core_fs::FsError::from)?;
+
+    info!("Exported table {} of {} to {}", table_index, note.path, output_path.display());
+    Ok(table.rows.len() + 1)
+}
+
+/// Detect the delimiter of CSV content: the one that splits the first lines
+/// into the same number of fields (more than one), or else the most fields.
+pub fn detect_delimiter(content: &str) -> char {
+    let sample = content.lines().take(10).collect::<Vec<_>>().join("\n");
+    DELIMITERS
+        .into_iter()
+        .max_by_key(|&delimiter| {
+            let widths: Vec<usize> = parse_csv(&sample, delimiter).iter().map(Vec::len).collect();
+            let first = widths.first().copied().unwrap_or(0);
+            (first > 1 && widths.iter().all(|&w| w == first), first)
+        })
+        .unwrap_or(',')
+}
+
+/// Parse CSV content into rows of fields.
+///
+/// Fields may be double-quoted, with `""` for a quote, and quoted fields may
+/// contain the delimiter and line breaks. Blank lines are skipped.
+pub fn parse_csv(content: &str, delimiter: char) -> Vec<Vec<String>> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    let mut rows = Vec::new();
+    let mut record = String::new();
+    let mut quotes = 0;
+    for line in content.split_inclusive('\n') {
+        record.push_str(line);
+        // A line break inside a quoted field continues the record
+        quotes += line.matches('"').count();
+        if quotes % 2 == 1 {
+            continue;
+        }
+
+        let line = record.strip_suffix('\n').unwrap_or(&record);
+        if !line.trim_end_matches('\r').is_empty() {
+            rows.push(split_csv_line(line, delimiter));
+        }
+        record.clear();
+        quotes = 0;
+    }
+    if !record.is_empty() {
+        rows.push(split_csv_line(&record, delimiter));
+    }
+
+    rows
+}
+
+/// Make a table of CSV rows, the first being the header. None without rows.
+pub fn csv_to_table(rows: Vec<Vec<String>>) -> Option<Table> {
+    let mut rows = rows.into_iter().map(|row| row.iter().map(|field| to_cell(field)).collect());
+    Some(Table {
+        header: rows.next()?,
+        rows: rows.collect(),
+        ..Default::default()
+    })
+}
+
+/// Write a table as CSV, quoting fields where needed.
+pub fn table_to_csv(table: &Table, delimiter: char) -> String {
+    let columns = table.column_count();
+    let mut csv = String::new();
+
+    for row in std::iter::once(&table.header).chain(&table.rows) {
+        let fields: Vec<String> = (0..columns)
+            .map(|i| {
+                let field = from_cell(row.get(i).map(String::as_str).unwrap_or(""));
+                if field.contains([delimiter, '"', '\n', '\r']) || field.trim() != field {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field
+                }
+            })
+            .collect();
+        csv.push_str(&fields.join(&delimiter.to_string()));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// A CSV field as a table cell: pipes escaped, line breaks as `<br>`.
+fn to_cell(field: &str) -> String {
+    field
+        .trim()
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// A table cell as a CSV field.
+fn from_cell(cell: &str) -> String {
+    cell.replace("\\|", "|").replace("<br>", "\n")
+}
+
+/// Insert a block into content before line `position` (1-based), or at the
+/// end, separated from the text around it by blank lines.
+fn insert_block(content: &str, block: &str, position: Option<usize>) -> String {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let at = position.map(|p| p.saturating_sub(1)).unwrap_or(lines.len()).min(lines.len());
+    let before = lines[..at].concat();
+    let after = lines[at..].concat();
+
+    let mut output = before;
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    if !output.trim().is_empty() && !output.ends_with("\n\n") {
+        output.push('\n');
+    }
+    output.push_str(block);
+    if !after.is_empty() && !after.starts_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(&after);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "\u{feff}name,notes\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\r\n\nDoe,\n";
+        assert_eq!(
+            parse_csv(csv, ','),
+            vec![
+                vec!["name", "notes"],
+                vec!["Smith, J", "said \"hi\"\nthen left"],
+                vec!["Doe", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a,b,c\n1,2,3\n"), ',');
+        assert_eq!(detect_delimiter("Name;Price\n\"Tea, green\";1,5\nCoffee;2,0\n"), ';');
+        assert_eq!(detect_delimiter("a\tb\n1\t2\n"), '\t');
+        assert_eq!(detect_delimiter("single\ncolumn\n"), ',');
+    }
+
+    #[test]
+    fn test_csv_table_round_trip() {
+        let csv = "Item,Note\r\nPipe,a|b\r\nLines,\"one\ntwo\"\r\nQuote,\"say \"\"hi\"\", ok\"\r\n";
+        let table = csv_to_table(parse_csv(csv, ',')).unwrap();
+
+        assert_eq!(table.rows[0][1], "a\\|b");
+        assert_eq!(table.rows[1][1], "one<br>two");
+        let markdown = table.to_markdown();
+        assert_eq!(find_tables(&markdown)[0].rows, table.rows);
+
+        assert_eq!(table_to_csv(&find_tables(&markdown)[0], ','), csv);
+    }
+
+    #[test]
+    fn test_insert_block() {
+        let table = "| a |\n| - |\n";
+        assert_eq!(insert_block("", table, None), table);
+        assert_eq!(insert_block("# Title", table, None), "# Title\n\n| a |\n| - |\n");
+        assert_eq!(
+            insert_block("# Title\n\nText\n", table, Some(3)),
+            "# Title\n\n| a |\n| - |\n\nText\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_and_export_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("Data.md", "# Data\n\nBelow\n").await.unwrap();
+
+        let csv_path = dir.path().join("prices.csv");
+        std::fs::write(&csv_path, "Item;Price\nTea;\"1,5\"\n").unwrap();
+        let note = import_csv_as_table(&vault, &csv_path, "Data.md", Some(3)).await.unwrap();
+        assert_eq!(note.content, "# Data\n\n| Item | Price |\n| ---- | ----- |\n| Tea  | 1,5   |\n\nBelow\n");
+        assert_eq!(vault.read_note("Data.md").await.unwrap(), note.content);
+
+        let note_id = note.id;
+        let output = dir.path().join("out.csv");
+        assert_eq!(export_table_as_csv(&vault, note_id, 0, &output).await.unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "Item,Price\r\nTea,\"1,5\"\r\n");
+        assert!(export_table_as_csv(&vault, note_id, 1, &output).await.is_err());
+
+        // A locked encrypted note is left alone
+        let encrypted = crate::encryption::encrypt_note("---\nencrypted: true\n---\n# Secret\n", "secret").unwrap();
+        std::fs::write(dir.path().join("Secret.md"), &encrypted).unwrap();
+        let result = import_csv_as_table(&vault, &csv_path, "Secret.md", None).await;
+        assert!(matches!(result, Err(VaultError::NoteLocked(_))));
+        assert_eq!(std::fs::read_to_string(dir.path().join("Secret.md")).unwrap(), encrypted);
+    }
+}
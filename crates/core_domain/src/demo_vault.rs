@@ -0,0 +1,343 @@
+//! Demo vault: a populated example vault for trying the app and for the docs.
+//!
+//! On top of a scaffolded vault it adds projects with tasks, contexts and
+//! embeds, dashboards with query tables and kanban boards, a few days of
+//! daily notes, habits with a week of entries and schedule blocks. The notes
+//! are fixtures bundled with the crate; dates in them are rendered relative
+//! to `today`, so due dates and daily notes are current whenever the vault
+//! is made.
+//!
+//! Properties live in the database, so the fixtures' frontmatter is copied
+//! there like an import does, and the query embeds find the notes.
+
+use crate::importer::infer_property_type;
+use crate::scaffold::scaffold_vault;
+use crate::templates::{render_template, TemplateContext};
+use crate::vault::{Result, Vault};
+use chrono::{Duration, NaiveDate};
+use core_index::frontmatter::{parse_frontmatter, PropertyValue};
+use shared_types::{ContextMeta, CreateHabitRequest, CreateVaultOptions, HabitType, LogHabitEntryRequest, TemplateSettings};
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Notes by vault-relative path.
+const NOTES: &[(&str, &str)] = &[
+    ("Start here.md", include_str!("../fixtures/demo/Start here.md")),
+    ("projects/Website relaunch.md", include_str!("../fixtures/demo/projects/Website relaunch.md")),
+    (
+        "projects/Website relaunch - Design.md",
+        include_str!("../fixtures/demo/projects/Website relaunch - Design.md"),
+    ),
+    ("projects/Vegetable garden.md", include_str!("../fixtures/demo/projects/Vegetable garden.md")),
+    ("projects/Learn Spanish.md", include_str!("../fixtures/demo/projects/Learn Spanish.md")),
+    ("dashboards/Tasks.md", include_str!("../fixtures/demo/dashboards/Tasks.md")),
+    ("dashboards/Project board.md", include_str!("../fixtures/demo/dashboards/Project board.md")),
+    ("dashboards/Habits.md", include_str!("../fixtures/demo/dashboards/Habits.md")),
+    ("areas/Reading list.md", include_str!("../fixtures/demo/areas/Reading list.md")),
+    ("inbox/Ideas.md", include_str!("../fixtures/demo/inbox/Ideas.md")),
+];
+
+/// Daily notes by how many days before today they are for.
+const DAILY_NOTES: &[(i64, &str)] = &[
+    (4, include_str!("../fixtures/demo/daily/4.md")),
+    (3, include_str!("../fixtures/demo/daily/3.md")),
+    (2, include_str!("../fixtures/demo/daily/2.md")),
+    (1, include_str!("../fixtures/demo/daily/1.md")),
+    (0, include_str!("../fixtures/demo/daily/0.md")),
+];
+
+/// Registered contexts: name, icon, description.
+const CONTEXTS: &[(&str, &str, &str)] = &[
+    ("work", "💼", "At the office"),
+    ("home", "🏠", "Around the house and garden"),
+    ("computer", "💻", "Needs a computer"),
+    ("phone", "📞", "Calls to make"),
+    ("errands", "🛒", "Out and about"),
+];
+
+/// A schedule block, linked to a note.
+struct DemoBlock {
+    /// Days from today.
+    days: i64,
+    start: &'static str,
+    end: &'static str,
+    label: &'static str,
+    color: &'static str,
+    context: &'static str,
+    note_path: &'static str,
+    rrule: Option<&'static str>,
+}
+
+const SCHEDULE_BLOCKS: &[DemoBlock] = &[
+    DemoBlock {
+        days: 0,
+        start: "09:00",
+        end: "11:00",
+        label: "Deep work",
+        color: "blue",
+        context: "work",
+        note_path: "projects/Website relaunch.md",
+        rrule: None,
+    },
+    DemoBlock {
+        days: 0,
+        start: "19:00",
+        end: "19:45",
+        label: "Spanish lesson",
+        color: "peach",
+        context: "computer",
+        note_path: "projects/Learn Spanish.md",
+        rrule: Some("FREQ=WEEKLY"),
+    },
+    DemoBlock {
+        days: 1,
+        start: "17:00",
+        end: "18:30",
+        label: "Garden",
+        color: "green",
+        context: "home",
+        note_path: "projects/Vegetable garden.md",
+        rrule: None,
+    },
+    DemoBlock {
+        days: 2,
+        start: "10:00",
+        end: "11:00",
+        label: "Design review",
+        color: "mauve",
+        context: "work",
+        note_path: "projects/Website relaunch - Design.md",
+        rrule: None,
+    },
+];
+
+/// A habit with entries for the week before today.
+struct DemoHabit {
+    name: &'static str,
+    habit_type: HabitType,
+    unit: Option<&'static str>,
+    color: &'static str,
+    target_value: Option<f64>,
+    /// Values from 7 days ago to yesterday, empty for no entry.
+    values: [&'static str; 7],
+}
+
+const HABITS: &[DemoHabit] = &[
+    DemoHabit {
+        name: "Exercise",
+        habit_type: HabitType::Boolean,
+        unit: None,
+        color: "#3b82f6",
+        target_value: None,
+        values: ["true", "", "true", "true", "", "true", "true"],
+    },
+    DemoHabit {
+        name: "Water the garden",
+        habit_type: HabitType::Boolean,
+        unit: None,
+        color: "#22c55e",
+        target_value: None,
+        values: ["true", "true", "", "true", "true", "true", "true"],
+    },
+    DemoHabit {
+        name: "Reading",
+        habit_type: HabitType::Number,
+        unit: Some("pages"),
+        color: "#f59e0b",
+        target_value: Some(20.0),
+        values: ["12", "25", "", "30", "18", "22", "40"],
+    },
+    DemoHabit {
+        name: "Mood",
+        habit_type: HabitType::Rating,
+        unit: None,
+        color: "#a855f7",
+        target_value: None,
+        values: ["3", "4", "2", "4", "5", "4", "4"],
+    },
+];
+
+/// Create a demo vault at `path` (which must be missing or empty, as for a
+/// new vault), with dates relative to `today`, and return the template
+/// settings for its config.
+#[instrument]
+pub async fn create_demo_vault(path: &Path, today: NaiveDate) -> Result<TemplateSettings> {
+    let options = CreateVaultOptions {
+        welcome_note: false,
+        ..CreateVaultOptions::default()
+    };
+    let settings = scaffold_vault(path, &options).await?;
+
+    let context = relative_dates(TemplateContext::for_date(today), today);
+    for (note_path, content) in NOTES {
+        write_note(path, note_path, &render_template(content, &context)).await?;
+    }
+    for (days_ago, content) in DAILY_NOTES {
+        let date = today - Duration::days(*days_ago);
+        let context = TemplateContext::for_date(date);
+        let note_path = render_template(&settings.daily_note_pattern, &context);
+        write_note(path, &note_path, &render_template(content, &context)).await?;
+    }
+
+    let vault = Vault::open(path).await?;
+    vault.full_index().await?;
+    import_properties(&vault).await?;
+
+    for (name, icon, description) in CONTEXTS {
+        vault
+            .repo()
+            .set_context(&ContextMeta {
+                name: name.to_string(),
+                icon: Some(icon.to_string()),
+                description: Some(description.to_string()),
+                location: None,
+            })
+            .await?;
+    }
+    create_habits(&vault, today).await?;
+
+    for block in SCHEDULE_BLOCKS {
+        let note_id = vault.repo().get_note_id_by_path(block.note_path).await?;
+        let date = (today + Duration::days(block.days)).format("%Y-%m-%d").to_string();
+        vault
+            .repo()
+            .create_schedule_block(
+                note_id,
+                &date,
+                block.start,
+                block.end,
+                Some(block.label),
+                Some(block.color),
+                Some(block.context),
+                block.rrule,
+            )
+            .await?;
+    }
+    vault.close().await;
+
+    info!("Created demo vault at {}", path.display());
+    Ok(settings)
+}
+
+/// Add the due-date variables the fixtures use.
+fn relative_dates(context: TemplateContext, today: NaiveDate) -> TemplateContext {
+    let date = |days: i64| (today + Duration::days(days)).format("%Y-%m-%d").to_string();
+    context
+        .with_var("tomorrow", date(1))
+        .with_var("in_three_days", date(3))
+        .with_var("in_week", date(7))
+        .with_var("in_two_weeks", date(14))
+}
+
+async fn write_note(root: &Path, relative: &str, content: &str) -> Result<()> {
+    let path = root.join(relative);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(core_fs::FsError::from)?;
+    }
+    tokio::fs::write(&path, content).await.map_err(core_fs::FsError::from)?;
+    Ok(())
+}
+
+/// Store the frontmatter properties of the fixture notes in the database.
+async fn import_properties(vault: &Vault) -> Result<()> {
+    for (note_path, _) in NOTES {
+        let Some(note_id) = vault.repo().get_note_id_by_path(note_path).await? else {
+            continue;
+        };
+        let content = vault.read_note(note_path).await?;
+        let (frontmatter, _) = parse_frontmatter(&content);
+        for (key, value) in &frontmatter.properties {
+            if let PropertyValue::List(items) = value {
+                vault.repo().set_list_property(note_id, key, items).await?;
+            } else if let Some(string_value) = value.to_string_value() {
+                vault
+                    .repo()
+                    .set_property(note_id, key, Some(&string_value), infer_property_type(value).as_deref())
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Create the demo habits and their entries. Today is left to fill in.
+async fn create_habits(vault: &Vault, today: NaiveDate) -> Result<()> {
+    for habit in HABITS {
+        let habit_id = vault
+            .repo()
+            .create_habit(&CreateHabitRequest {
+                name: habit.name.to_string(),
+                description: None,
+                habit_type: habit.habit_type.clone(),
+                unit: habit.unit.map(String::from),
+                color: Some(habit.color.to_string()),
+                target_value: habit.target_value,
+            })
+            .await?;
+
+        for (i, value) in habit.values.iter().enumerate().filter(|(_, value)| !value.is_empty()) {
+            let date = today - Duration::days(7 - i as i64);
+            vault
+                .repo()
+                .log_habit_entry(&LogHabitEntryRequest {
+                    habit_id,
+                    date: date.format("%Y-%m-%d").to_string(),
+                    time: None,
+                    value: value.to_string(),
+                    notes: None,
+                })
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::run_query;
+    use crate::vault::VaultError;
+    use shared_types::{FilterMatchMode, PropertyFilter, PropertyOperator, QueryRequest, QueryResultType};
+
+    #[tokio::test]
+    async fn test_create_demo_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Demo");
+        let today = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+
+        let settings = create_demo_vault(&path, today).await.unwrap();
+        assert_eq!(settings.daily_template_path.as_deref(), Some("templates/daily.md"));
+        assert!(path.join("journal/2025/03/2025-03-12.md").is_file());
+        assert!(path.join("journal/2025/03/2025-03-08.md").is_file());
+        let project = std::fs::read_to_string(path.join("projects/Website relaunch.md")).unwrap();
+        assert!(project.contains("deadline: 2025-03-26"));
+        assert!(project.contains("^2025-03-13"));
+
+        let vault = Vault::open(&path).await.unwrap();
+        let request = QueryRequest {
+            filters: vec![PropertyFilter {
+                key: "status".to_string(),
+                operator: PropertyOperator::Equals,
+                value: Some("active".to_string()),
+            }],
+            match_mode: FilterMatchMode::All,
+            result_type: QueryResultType::Tasks,
+            include_completed: false,
+            limit: Some(50),
+            sort: None,
+        };
+        let response = run_query(&vault, &request, true).await.unwrap();
+        assert_eq!(response.total_count, 9);
+
+        let habits = vault.repo().list_habits(false).await.unwrap();
+        assert_eq!(habits.len(), 4);
+        let garden = vault.repo().get_note_id_by_path("projects/Vegetable garden.md").await.unwrap().unwrap();
+        let blocks = vault.repo().get_schedule_blocks_for_note(garden).await.unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].date.to_string(), "2025-03-13");
+        assert_eq!(vault.repo().list_registered_contexts().await.unwrap().len(), 5);
+
+        // Like a new vault, only made in an empty folder
+        assert!(matches!(create_demo_vault(&path, today).await, Err(VaultError::NotEmpty(_))));
+    }
+}
@@ -0,0 +1,433 @@
+//! Diagnostics bundles for bug reports.
+//!
+//! `export_diagnostics` writes a zip with what a bug report usually lacks:
+//! vault statistics (counts and sizes, never note content or titles), the
+//! last full index's duration, the database schema, the vault config, the
+//! performance metrics and the recent log lines. Secrets in the config are
+//! redacted, and the vault and home folder paths are replaced by `<vault>`
+//! and `~` everywhere, so the bundle can be attached to a public issue.
+//!
+//! The logs and metrics name notes, so the vault's note and attachment
+//! paths, folders and note titles in them are replaced by a short hash
+//! (`<note:1a2b3c4d>`, `<folder:…>`, `<title:…>`): the same note still has
+//! the same name throughout the bundle, without giving it away.
+
+use crate::assets::list_attachments;
+use crate::vault::{Result, Vault, VaultError};
+use chrono::Utc;
+use core_fs::{hash_content, FsError};
+use serde_json::{json, Value};
+use shared_types::{DiagnosticsExport, PerformanceMetrics};
+use regex::{Regex, RegexBuilder};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::io::{Cursor, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::{info, instrument};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Log lines kept for the bundle.
+const MAX_LOG_LINES: usize = 2000;
+
+/// Config values under keys with one of these words are redacted.
+const SECRET_WORDS: &[&str] = &[
+    "key",
+    "apikey",
+    "token",
+    "secret",
+    "password",
+    "passphrase",
+    "auth",
+    "credential",
+    "credentials",
+];
+
+const REDACTED: &str = "<redacted>";
+
+/// The most recent log lines, oldest first. The app's logger writes every
+/// formatted event here as well.
+#[derive(Default)]
+pub struct RecentLogs {
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl RecentLogs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a formatted log line (or several, for multi-line events).
+    pub fn push(&self, line: &str) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == MAX_LOG_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line.trim_end().to_string());
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Write a diagnostics bundle to `output` (a zip file). Without an open
+/// vault, the bundle has the app's metrics and logs only.
+#[instrument(skip(vault, performance, logs))]
+pub async fn export_diagnostics(
+    vault: Option<&Vault>,
+    app_version: &str,
+    performance: &PerformanceMetrics,
+    logs: &RecentLogs,
+    output: &Path,
+) -> Result<DiagnosticsExport> {
+    let mut anonymizer = Anonymizer::new(vault.map(|vault| vault.root_path()));
+    if let Some(vault) = vault {
+        anonymizer.add_vault_names(vault).await?;
+    }
+
+    let mut summary = json!({
+        "app_version": app_version,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "created_at": Utc::now(),
+        "vault_open": vault.is_some(),
+    });
+    let mut files = Vec::new();
+    if let Some(vault) = vault {
+        let statements = vault.repo().schema_statements().await?;
+        summary["schema_version"] = json!(hash_content(&statements.join(";\n")));
+        files.push(("vault.json", to_json(&vault_stats(vault).await?)));
+        files.push(("schema.sql", anonymizer.apply(&format!("{};\n", statements.join(";\n\n")))));
+        files.push(("config.json", anonymizer.apply(&to_json(&read_config(vault).await?))));
+    }
+    files.insert(0, ("summary.json", to_json(&summary)));
+    files.push(("performance.json", anonymizer.apply_with_names(&to_json(&json!(performance)))));
+    files.push(("logs.txt", anonymizer.apply_with_names(&logs.lines().join("\n"))));
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in &files {
+        zip.start_file(*name, options).map_err(zip_error)?;
+        zip.write_all(content.as_bytes()).map_err(FsError::from)?;
+    }
+    let bytes = zip.finish().map_err(zip_error)?.into_inner();
+    if let Some(parent) = output.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(FsError::from)?;
+    }
+    tokio::fs::write(output, &bytes).await.map_err(FsError::from)?;
+
+    info!("Exported diagnostics to {} ({} bytes)", output.display(), bytes.len());
+    Ok(DiagnosticsExport {
+        path: output.to_string_lossy().to_string(),
+        size_bytes: bytes.len() as u64,
+        entries: files.iter().map(|(name, _)| name.to_string()).collect(),
+    })
+}
+
+/// Counts and sizes of the vault, without anything about its notes.
+async fn vault_stats(vault: &Vault) -> Result<Value> {
+    let tables: serde_json::Map<String, Value> = vault
+        .repo()
+        .table_row_counts()
+        .await?
+        .into_iter()
+        .map(|(table, count)| (table, json!(count)))
+        .collect();
+
+    let db_path = vault.fs().db_path();
+    let mut db_size = 0;
+    for suffix in ["", "-wal", "-shm"] {
+        let path = format!("{}{}", db_path.display(), suffix);
+        if let Ok(metadata) = tokio::fs::metadata(&path).await {
+            db_size += metadata.len();
+        }
+    }
+
+    let last_index = vault.last_index().await.map(|index| {
+        json!({
+            "notes_indexed": index.notes_indexed,
+            "duration_ms": index.duration_ms,
+            "warnings": index.warnings.len(),
+            "cancelled": index.cancelled,
+        })
+    });
+
+    let mut file_problems = serde_json::Map::new();
+    for problem in vault.repo().list_file_problems().await? {
+        let count = file_problems.entry(problem.kind.as_str()).or_insert(json!(0));
+        *count = json!(count.as_u64().unwrap_or(0) + 1);
+    }
+
+    Ok(json!({
+        "notes": vault.repo().count_notes().await?,
+        "tables": tables,
+        "db_size_bytes": db_size,
+        "data_dir_outside_vault": !vault.fs().data_dir().starts_with(vault.root_path()),
+        "last_full_index": last_index,
+        "file_problems": file_problems,
+    }))
+}
+
+/// The vault config with secrets redacted (empty if there is none).
+async fn read_config(vault: &Vault) -> Result<Value> {
+    let config = match tokio::fs::read_to_string(vault.fs().config_path()).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| json!({ "parse_error": e.to_string() })),
+        Err(_) => json!({}),
+    };
+    Ok(redact_secrets(config))
+}
+
+fn to_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// Replace the values under secret-looking keys, at any depth.
+fn redact_secrets(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    if is_secret_key(&key) && !value.is_null() {
+                        (key, json!(REDACTED))
+                    } else {
+                        (key, redact_secrets(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_secrets).collect()),
+        value => value,
+    }
+}
+
+/// Whether a key (snake_case, kebab-case or camelCase) has a secret word.
+fn is_secret_key(key: &str) -> bool {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in key.chars() {
+        let boundary = c == '_' || c == '-' || c == '.' || c.is_uppercase();
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        }
+    }
+    words.push(word);
+    words.iter().any(|word| SECRET_WORDS.contains(&word.as_str()))
+}
+
+/// Replaces the vault and home folder paths in text, and the names of the
+/// vault's notes, attachments and folders.
+struct Anonymizer {
+    /// (path, replacement), longest path first.
+    paths: Vec<(String, &'static str)>,
+    /// Replacements by name.
+    names: HashMap<String, String>,
+    /// Matches any of `names`, longest first.
+    names_pattern: Option<Regex>,
+}
+
+/// Titles shorter than this are left alone: they are common words more
+/// often than note names.
+const MIN_TITLE_LEN: usize = 4;
+
+impl Anonymizer {
+    fn new(vault_root: Option<&Path>) -> Self {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+        let mut paths: Vec<(String, &'static str)> = vault_root
+            .map(|root| (root.to_string_lossy().to_string(), "<vault>"))
+            .into_iter()
+            .chain(home.map(|home| (home.to_string_lossy().to_string(), "~")))
+            .filter(|(path, _)| path.len() > 1)
+            .collect();
+        paths.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+        Self {
+            paths,
+            names: HashMap::new(),
+            names_pattern: None,
+        }
+    }
+
+    /// Add the vault's note and attachment paths, their folders and the
+    /// notes' titles.
+    async fn add_vault_names(&mut self, vault: &Vault) -> Result<()> {
+        let notes = vault.repo().list_notes().await?;
+        let files: BTreeSet<String> = notes
+            .iter()
+            .map(|note| note.path.clone())
+            .chain(list_attachments(vault).await?)
+            .collect();
+
+        let mut names = HashMap::new();
+        for path in &files {
+            let replacement = format!("<note:{}>", short_hash(path));
+            // Without the extension too, as wikilinks name notes
+            if let Some(stem) = path.strip_suffix(".md") {
+                names.insert(stem.to_string(), replacement.clone());
+            }
+            for (end, _) in path.match_indices('/') {
+                let folder = &path[..end];
+                names.insert(folder.to_string(), format!("<folder:{}>", short_hash(folder)));
+            }
+            names.insert(path.clone(), replacement);
+        }
+        for title in notes.iter().filter_map(|note| note.title.as_deref()) {
+            if title.chars().count() >= MIN_TITLE_LEN {
+                names
+                    .entry(title.to_string())
+                    .or_insert_with(|| format!("<title:{}>", short_hash(title)));
+            }
+        }
+
+        let mut sorted: Vec<&String> = names.keys().collect();
+        sorted.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        let pattern = sorted.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|");
+        self.names_pattern = if names.is_empty() {
+            None
+        } else {
+            let pattern = RegexBuilder::new(&pattern)
+                .size_limit(1 << 28)
+                .build()
+                .map_err(|e| VaultError::Settings(format!("Failed to anonymize note names: {}", e)))?;
+            Some(pattern)
+        };
+        self.names = names;
+        Ok(())
+    }
+
+    /// Replace the vault and home folder paths.
+    fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (path, replacement) in &self.paths {
+            text = text.replace(path.as_str(), replacement);
+            // As escaped in JSON on Windows
+            if path.contains('\\') {
+                text = text.replace(&path.replace('\\', "\\\\"), replacement);
+            }
+        }
+        text
+    }
+
+    /// Replace the vault and home folder paths, then the vault's names
+    /// where they aren't part of a longer word.
+    fn apply_with_names(&self, text: &str) -> String {
+        let text = self.apply(text);
+        let Some(pattern) = &self.names_pattern else {
+            return text;
+        };
+
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for found in pattern.find_iter(&text) {
+            if is_word(text[..found.start()].chars().next_back()) || is_word(text[found.end()..].chars().next()) {
+                continue;
+            }
+            out.push_str(&text[last..found.start()]);
+            out.push_str(&self.names[found.as_str()]);
+            last = found.end();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+/// The first eight hex digits of the hash of `text`.
+fn short_hash(text: &str) -> String {
+    hash_content(text)[..8].to_string()
+}
+
+fn zip_error(e: zip::result::ZipError) -> VaultError {
+    VaultError::Settings(format!("Failed to write diagnostics: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_secrets() {
+        let config = json!({
+            "embedding_settings": { "endpoint_url": "http://localhost:1234/v1", "api_key": "sk-123" },
+            "sync": [{ "accessToken": "abc", "password": null }],
+            "template_settings": { "daily_note_pattern": "journal/{{year}}.md" },
+            "monkey": "not a key",
+        });
+        let redacted = redact_secrets(config);
+        assert_eq!(redacted["embedding_settings"]["api_key"], REDACTED);
+        assert_eq!(redacted["embedding_settings"]["endpoint_url"], "http://localhost:1234/v1");
+        assert_eq!(redacted["sync"][0]["accessToken"], REDACTED);
+        assert!(redacted["sync"][0]["password"].is_null());
+        assert_eq!(redacted["monkey"], "not a key");
+    }
+
+    #[tokio::test]
+    async fn test_export_diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("projects/Secret plans.md", "# Secret plans\n").await.unwrap();
+        vault.full_index().await.unwrap();
+        let logs = RecentLogs::new();
+        logs.push(&format!("INFO Opening vault at {}\n", dir.path().display()));
+        logs.push("DEBUG Indexed: projects/Secret plans.md (Secret plans) into projects/");
+        logs.push("DEBUG Resolved [[projects/Secret plans]] in projects/Secret plans.md");
+        logs.push("DEBUG Unrelated: Secret plansy and projectsX");
+
+        let output = dir.path().join("out/diagnostics.zip");
+        let performance = PerformanceMetrics {
+            commands: Vec::new(),
+            slow_queries: Vec::new(),
+            slow_query_threshold_ms: 100,
+        };
+        let export = export_diagnostics(Some(&vault), "1.2.3", &performance, &logs, &output)
+            .await
+            .unwrap();
+        assert_eq!(
+            export.entries,
+            vec!["summary.json", "vault.json", "schema.sql", "config.json", "performance.json", "logs.txt"]
+        );
+
+        let bytes = std::fs::read(&output).unwrap();
+        assert_eq!(export.size_bytes, bytes.len() as u64);
+
+        // Entries can be read back
+        let entries = read_zip(&output);
+        assert_eq!(entries.len(), 6);
+        let vault_stats: Value = serde_json::from_str(&entries["vault.json"]).unwrap();
+        assert_eq!(vault_stats["notes"], 1);
+        assert_eq!(vault_stats["last_full_index"]["notes_indexed"], 1);
+        assert!(!entries["vault.json"].contains("Secret plans"));
+        assert!(entries["schema.sql"].contains("CREATE TABLE notes"));
+
+        // Paths and titles in the logs are hashed, the same one alike
+        let logs = &entries["logs.txt"];
+        let note = format!("<note:{}>", short_hash("projects/Secret plans.md"));
+        let folder = format!("<folder:{}>", short_hash("projects"));
+        let title = format!("<title:{}>", short_hash("Secret plans"));
+        assert_eq!(
+            logs.lines().collect::<Vec<_>>(),
+            [
+                "INFO Opening vault at <vault>".to_string(),
+                format!("DEBUG Indexed: {} ({}) into {}/", note, title, folder),
+                format!("DEBUG Resolved [[{}]] in {}", note, note),
+                "DEBUG Unrelated: Secret plansy and projectsX".to_string(),
+            ]
+        );
+    }
+
+    /// Read each entry of a zip, by name.
+    fn read_zip(path: &Path) -> std::collections::HashMap<String, String> {
+        use std::io::Read;
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        let mut entries = std::collections::HashMap::new();
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i).unwrap();
+            let mut content = String::new();
+            file.read_to_string(&mut content).unwrap();
+            entries.insert(file.name().to_string(), content);
+        }
+        entries
+    }
+}
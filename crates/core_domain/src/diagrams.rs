@@ -0,0 +1,298 @@
+//! Diagram (`mermaid`) blocks rendered to SVG.
+//!
+//! Diagrams are rendered by an external mermaid-cli compatible program
+//! (the app's bundled `render-diagram.mjs` or one installed separately) into
+//! `.neuroflow/cache/diagrams/`, named after a hash of the diagram source,
+//! so a diagram is rendered once no matter how many notes contain it.
+//! Rendered HTML and exports use the cached SVG; diagrams without one stay
+//! code blocks.
+//!
+//! The renderer is a device setting, never taken from the vault config, and
+//! its SVGs are sanitized before they are used: scripts, stylesheets, event
+//! handlers and links to other documents are dropped. A renderer that takes
+//! longer than `RENDER_TIMEOUT` is killed.
+
+use crate::vault::{Result, Vault, VaultError, VaultEvent};
+use core_fs::{hash_content, VaultFs};
+use core_index::rendering::{diagram_start, DIAGRAM_END};
+use core_index::{find_diagrams, strip_frontmatter};
+use core_storage::VaultRepository;
+use shared_types::DiagramSettings;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, instrument, warn};
+
+/// How long a renderer may take for one diagram.
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Get the cache path of a diagram's SVG.
+pub fn diagram_cache_path(cache_dir: &Path, source: &str) -> PathBuf {
+    cache_dir.join("diagrams").join(format!("{}.svg", hash_content(source)))
+}
+
+/// Render the `block_index`th diagram of a note (counting from 0, in the
+/// note without its frontmatter) to SVG.
+///
+/// Uses the cached SVG if there is one, otherwise renders it with the
+/// configured renderer.
+#[instrument(skip(vault))]
+pub async fn render_diagram(vault: &Vault, note_id: i64, block_index: usize) -> Result<String> {
+    let note = vault.repo().get_note(note_id).await?;
+    let content = vault.read_note(&note.path).await?;
+
+    let diagrams = find_diagrams(strip_frontmatter(&content));
+    let source = diagrams
+        .get(block_index)
+        .ok_or_else(|| VaultError::Diagram(format!("{} has no diagram {}", note.path, block_index)))?;
+
+    diagram_svg(vault, source)
+        .await?
+        .ok_or_else(|| VaultError::Diagram("No diagram renderer configured".to_string()))
+}
+
+/// Get a diagram's sanitized SVG from the cache, or render it now if a
+/// renderer is configured. `None` if it isn't cached and there is no renderer.
+pub async fn diagram_svg(vault: &Vault, source: &str) -> Result<Option<String>> {
+    let cache_dir = vault.fs().cache_dir();
+    if let Ok(svg) = tokio::fs::read_to_string(diagram_cache_path(&cache_dir, source)).await {
+        return Ok(Some(sanitize_svg(&svg)));
+    }
+
+    match vault.diagram_settings().await.renderer_path {
+        Some(renderer) => Ok(Some(sanitize_svg(
+            &render_to_cache(Path::new(&renderer), &cache_dir, source).await?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// SVG elements a diagram may use. HTML elements are for labels in a
+/// `foreignObject`. `<style>` is not among them: its rules would apply to
+/// the whole page the diagram is shown in.
+const SVG_TAGS: &[&str] = &[
+    "svg", "g", "defs", "marker", "symbol", "use", "path", "rect", "circle", "ellipse", "line",
+    "polyline", "polygon", "text", "tspan", "textPath", "title", "desc", "clipPath", "mask", "pattern",
+    "linearGradient", "radialGradient", "stop", "filter", "feDropShadow", "feGaussianBlur", "feOffset",
+    "feBlend", "feFlood", "feComposite", "feMerge", "feMergeNode", "foreignObject", "div", "span", "p", "br",
+    "b", "strong", "i", "em", "code",
+];
+
+/// Presentation and geometry attributes a diagram may use. Event handlers
+/// (`on...`) and `href` are not among them.
+const SVG_ATTRIBUTES: &[&str] = &[
+    "id", "class", "style", "xmlns", "version", "width", "height", "viewBox", "preserveAspectRatio",
+    "role", "aria-label", "aria-roledescription", "transform", "x", "y", "x1", "y1", "x2", "y2", "dx", "dy",
+    "cx", "cy", "r", "rx", "ry", "d", "points", "pathLength", "fill", "fill-opacity", "fill-rule", "stroke",
+    "stroke-width", "stroke-dasharray", "stroke-dashoffset", "stroke-linecap", "stroke-linejoin",
+    "stroke-opacity", "opacity", "marker-start", "marker-mid", "marker-end", "markerWidth", "markerHeight",
+    "markerUnits", "refX", "refY", "orient", "clip-path", "mask", "filter", "offset", "stop-color",
+    "stop-opacity", "gradientUnits", "gradientTransform", "patternUnits", "text-anchor",
+    "dominant-baseline", "alignment-baseline", "font-family", "font-size", "font-weight", "font-style",
+    "letter-spacing", "stdDeviation", "flood-color", "flood-opacity", "in", "in2", "result", "mode",
+    "operator", "display", "visibility",
+];
+
+/// Drop everything from a rendered SVG that could run code, load other
+/// documents or restyle the page, keeping the elements and attributes
+/// diagrams are drawn with.
+pub fn sanitize_svg(svg: &str) -> String {
+    ammonia::Builder::empty()
+        .add_tags(SVG_TAGS)
+        .add_generic_attributes(SVG_ATTRIBUTES)
+        .clean(svg)
+        .to_string()
+}
+
+/// Replace the diagram elements of HTML from `render_html` with their SVGs
+/// (`svgs[i]` for diagram `i`). Diagrams without an SVG keep their code block.
+pub fn insert_diagrams(html: &str, svgs: &[Option<String>]) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    for (i, svg) in svgs.iter().enumerate() {
+        let start = diagram_start(i);
+        let Some(start_at) = rest.find(&start) else {
+            break;
+        };
+        let element = &rest[start_at + start.len()..];
+        // The code block is escaped, so the first end tag is the element's
+        let Some(end_at) = element.find(DIAGRAM_END) else {
+            break;
+        };
+
+        output.push_str(&rest[..start_at]);
+        match svg {
+            Some(svg) => output.push_str(&format!("<figure class=\"diagram\">{}</figure>\n", svg.trim())),
+            None => {
+                output.push_str("<div class=\"diagram\">");
+                output.push_str(&element[..end_at]);
+                output.push_str(DIAGRAM_END);
+            }
+        }
+        rest = &element[end_at + DIAGRAM_END.len()..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Render a diagram with a mermaid-cli compatible renderer into the cache.
+/// A `.js`/`.mjs` renderer is a script run with Node.js.
+async fn render_to_cache(renderer: &Path, cache_dir: &Path, source: &str) -> Result<String> {
+    let output = diagram_cache_path(cache_dir, source);
+    if let Some(parent) = output.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(core_fs::FsError::from)?;
+    }
+    let input = output.with_extension("mmd");
+    tokio::fs::write(&input, source).await.map_err(core_fs::FsError::from)?;
+
+    let mut command = match renderer.extension().and_then(|ext| ext.to_str()) {
+        Some("js" | "mjs") => {
+            let mut command = tokio::process::Command::new("node");
+            command.arg(renderer);
+            command
+        }
+        _ => tokio::process::Command::new(renderer),
+    };
+    command
+        .arg("-i")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .kill_on_drop(true);
+    let result = tokio::time::timeout(RENDER_TIMEOUT, command.output()).await;
+    let _ = tokio::fs::remove_file(&input).await;
+
+    let result = result
+        .map_err(|_| {
+            VaultError::Diagram(format!("{} took longer than {}s", renderer.display(), RENDER_TIMEOUT.as_secs()))
+        })?
+        .map_err(|e| VaultError::Diagram(format!("Failed to run {}: {}", renderer.display(), e)))?;
+    if !result.status.success() {
+        let _ = tokio::fs::remove_file(&output).await;
+        return Err(VaultError::Diagram(String::from_utf8_lossy(&result.stderr).trim().to_string()));
+    }
+
+    debug!("Rendered diagram to {}", output.display());
+    tokio::fs::read_to_string(&output)
+        .await
+        .map_err(|e| VaultError::Diagram(format!("Renderer wrote no SVG: {}", e)))
+}
+
+/// Render the diagrams of indexed notes into the cache, while pre-rendering
+/// is enabled, until the vault is dropped.
+pub(crate) async fn prerender_diagrams(
+    mut events: broadcast::Receiver<VaultEvent>,
+    repo: VaultRepository,
+    fs: VaultFs,
+    settings: Arc<RwLock<DiagramSettings>>,
+) {
+    loop {
+        let ids = match events.recv().await {
+            Ok(VaultEvent::NotesUpdated(ids)) => ids,
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let settings = settings.read().await.clone();
+        let Some(renderer) = settings.renderer_path.filter(|_| settings.prerender) else {
+            continue;
+        };
+
+        for id in ids {
+            let Ok(note) = repo.get_note(id).await else {
+                continue;
+            };
+            let Ok(content) = fs.read_file(Path::new(&note.path)).await else {
+                continue;
+            };
+            for source in find_diagrams(strip_frontmatter(&content)) {
+                if diagram_cache_path(&fs.cache_dir(), &source).exists() {
+                    continue;
+                }
+                if let Err(e) = render_to_cache(Path::new(&renderer), &fs.cache_dir(), &source).await {
+                    warn!("Failed to render diagram in {}: {}", note.path, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_diagrams() {
+        let html = format!(
+            "<p>Before</p>\n{}\n<pre><code class=\"language-mermaid\">pie\n</code></pre>\n{}{}\n<pre><code class=\"language-mermaid\">graph\n</code></pre>\n{}",
+            diagram_start(0),
+            DIAGRAM_END,
+            diagram_start(1),
+            DIAGRAM_END
+        );
+        let output = insert_diagrams(&html, &[None, Some("<svg>graph</svg>\n".to_string())]);
+
+        assert!(output.starts_with("<p>Before</p>\n<div class=\"diagram\">\n<pre><code class=\"language-mermaid\">pie\n"));
+        assert!(output.ends_with("<figure class=\"diagram\"><svg>graph</svg></figure>\n"));
+        assert!(!output.contains("data-diagram"));
+    }
+
+    #[test]
+    fn test_sanitize_svg() {
+        let svg = concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10" onload="alert(1)">"#,
+            r#"<style>.node { fill: red; }</style><script>alert(2)</script>"#,
+            r#"<a href="javascript:alert(3)"><g class="node" transform="translate(1,2)">"#,
+            r#"<rect width="4" height="4" onclick="alert(4)"/><use href="https://example.com/x.svg#a"/>"#,
+            r#"<foreignObject><div>Start &amp; go<img src="x" onerror="alert(5)"></div></foreignObject>"#,
+            r#"</g></a></svg>"#,
+        );
+        let clean = sanitize_svg(svg);
+
+        assert!(clean.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">"#), "{}", clean);
+        assert!(clean.contains(r#"<g class="node" transform="translate(1,2)"><rect width="4" height="4"></rect>"#));
+        assert!(clean.contains("<div>Start &amp; go</div>"));
+        for unsafe_part in ["alert", "script", "href", "<a", "<img", "<style", "fill: red"] {
+            assert!(!clean.contains(unsafe_part), "{} in {}", unsafe_part, clean);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_render_diagram_with_renderer() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault
+            .write_note("Flow.md", "---\ntitle: Flow\n---\n```mermaid\ngraph TD; A-->B\n```\n\n```mermaid\npie\n```\n")
+            .await
+            .unwrap();
+        let note_id = vault.repo().get_note_id_by_path("Flow.md").await.unwrap().unwrap();
+
+        // Without a renderer, nothing can be rendered
+        assert!(render_diagram(&vault, note_id, 1).await.is_err());
+
+        // A stand-in renderer that wraps the source in <svg>
+        let renderer = dir.path().join("mmdc");
+        std::fs::write(&renderer, "#!/bin/sh\n{ printf '<svg>'; cat \"$2\"; printf '</svg>'; } > \"$4\"\n").unwrap();
+        std::fs::set_permissions(&renderer, std::fs::Permissions::from_mode(0o755)).unwrap();
+        vault
+            .set_diagram_settings(DiagramSettings {
+                renderer_path: Some(renderer.to_string_lossy().to_string()),
+                prerender: false,
+            })
+            .await;
+
+        assert_eq!(render_diagram(&vault, note_id, 1).await.unwrap(), "<svg>pie\n</svg>");
+        assert!(diagram_cache_path(&vault.fs().cache_dir(), "pie\n").exists());
+        assert!(render_diagram(&vault, note_id, 2).await.is_err());
+
+        // Rendered notes include the diagrams
+        let rendered = crate::rendering::render_note_html(&vault, note_id, &Default::default()).await.unwrap();
+        assert!(rendered.html.contains("<figure class=\"diagram\"><svg>graph TD; A--&gt;B\n</svg></figure>"));
+        assert!(rendered.html.contains("<figure class=\"diagram\"><svg>pie\n</svg></figure>"));
+    }
+}
@@ -0,0 +1,131 @@
+//! Crash recovery of unsaved edits.
+//!
+//! While crash recovery is enabled, the editor stores its content as a
+//! draft every few seconds. A draft is only worth keeping while it differs
+//! from the file: saving the note deletes it, and so does finding the file
+//! caught up with it. After a crash, `get_unsaved_drafts` lists what is
+//! left so the user can restore or discard it.
+//!
+//! Drafts of encrypted notes and of notes with private sections
+//! (`%%private%% ... %%end%%`) are never stored: the editor holds them in
+//! the clear, and a draft with the sections blanked would lose them when
+//! restored.
+
+use crate::encryption::is_marked_encrypted;
+use crate::vault::{Result, Vault};
+use core_fs::hash_content;
+use core_index::has_private_sections;
+use shared_types::UnsavedDraft;
+use std::path::Path;
+use tracing::{debug, info, instrument};
+
+/// Store the editor content of a note as its draft. Returns whether a draft
+/// is kept: not if the content is what the file has, or the note is encrypted
+/// or has private sections.
+pub async fn save_draft(vault: &Vault, path: &str, content: &str) -> Result<bool> {
+    let file_hash = vault.fs().read_file(Path::new(path)).await.ok().map(|file| hash_content(&file));
+    if is_marked_encrypted(content)
+        || has_private_sections(content)
+        || file_hash.as_deref() == Some(hash_content(content).as_str())
+    {
+        vault.repo().delete_draft(path).await?;
+        return Ok(false);
+    }
+
+    vault.repo().save_draft(path, content, file_hash.as_deref()).await?;
+    debug!("Saved draft of {}", path);
+    Ok(true)
+}
+
+/// Drafts with content the files don't have, for recovery. Drafts the
+/// files have caught up with, and drafts of deleted files, are removed.
+#[instrument(skip(vault))]
+pub async fn get_unsaved_drafts(vault: &Vault) -> Result<Vec<UnsavedDraft>> {
+    let mut unsaved = Vec::new();
+    for (path, content, base_hash, saved_at) in vault.repo().list_drafts().await? {
+        let file_hash = vault.fs().read_file(Path::new(&path)).await.ok().map(|file| hash_content(&file));
+        let caught_up = file_hash.as_deref() == Some(hash_content(&content).as_str());
+        let deleted = file_hash.is_none() && base_hash.is_some();
+        if caught_up || deleted {
+            vault.repo().delete_draft(&path).await?;
+            continue;
+        }
+
+        unsaved.push(UnsavedDraft {
+            file_changed: file_hash != base_hash,
+            path,
+            content,
+            saved_at,
+        });
+    }
+
+    if !unsaved.is_empty() {
+        info!("Found {} unsaved drafts", unsaved.len());
+    }
+    Ok(unsaved)
+}
+
+/// Delete the draft of a note, after it was restored or dismissed.
+pub async fn discard_draft(vault: &Vault, path: &str) -> Result<()> {
+    vault.repo().delete_draft(path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unsaved_drafts() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        for name in ["saved.md", "edited.md", "external.md", "deleted.md", "caught-up.md"] {
+            vault.write_note(name, "# Note\n").await.unwrap();
+        }
+
+        // Nothing to keep while the content is the file's
+        assert!(!save_draft(&vault, "saved.md", "# Note\n").await.unwrap());
+        assert!(!save_draft(&vault, "saved.md", "---\nencrypted: true\n---\n# Secret\n").await.unwrap());
+
+        for name in ["saved.md", "edited.md", "external.md", "deleted.md", "caught-up.md", "new.md"] {
+            assert!(save_draft(&vault, name, "# Note\n\nUnsaved\n").await.unwrap());
+        }
+        // Saving the note deletes its draft
+        vault.write_note("saved.md", "# Note\n\nUnsaved\n").await.unwrap();
+        std::fs::write(dir.path().join("external.md"), "# Note\n\nElsewhere\n").unwrap();
+        std::fs::remove_file(dir.path().join("deleted.md")).unwrap();
+        std::fs::write(dir.path().join("caught-up.md"), "# Note\n\nUnsaved\n").unwrap();
+
+        let drafts = get_unsaved_drafts(&vault).await.unwrap();
+        let found: Vec<_> = drafts.iter().map(|d| (d.path.as_str(), d.file_changed)).collect();
+        assert_eq!(found, vec![("edited.md", false), ("external.md", true), ("new.md", false)]);
+        assert_eq!(drafts[0].content, "# Note\n\nUnsaved\n");
+        assert_eq!(vault.repo().list_drafts().await.unwrap().len(), 3);
+
+        discard_draft(&vault, "new.md").await.unwrap();
+        assert_eq!(get_unsaved_drafts(&vault).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_restore_draft() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("note.md", "# Note\n").await.unwrap();
+
+        let edited = "# Note\n\n- [ ] Unsaved task\n";
+        assert!(save_draft(&vault, "note.md", edited).await.unwrap());
+
+        // Restoring writes the draft back, which leaves nothing to recover
+        let drafts = get_unsaved_drafts(&vault).await.unwrap();
+        assert_eq!(drafts[0].content, edited);
+        vault.write_note("note.md", &drafts[0].content).await.unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("note.md")).unwrap(), edited);
+        assert!(get_unsaved_drafts(&vault).await.unwrap().is_empty());
+
+        // A note with private sections isn't drafted, and drops its old draft
+        assert!(save_draft(&vault, "note.md", "# Note\n\nOld\n").await.unwrap());
+        let private = "# Note\n%%private%%\npin 1234\n%%end%%\nUnsaved\n";
+        assert!(!save_draft(&vault, "note.md", private).await.unwrap());
+        assert!(vault.repo().list_drafts().await.unwrap().is_empty());
+    }
+}
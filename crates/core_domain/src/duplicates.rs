@@ -0,0 +1,175 @@
+//! Duplicate and near-duplicate note detection.
+//!
+//! Every note is read and fingerprinted (see `core_index::duplicates`);
+//! clusters of similar notes are returned with a suggested merge target.
+//! Encrypted notes that are still locked are skipped.
+
+use crate::encryption::has_encrypted_body;
+use crate::vault::{Result, Vault, VaultError};
+use core_index::{find_duplicates, fingerprint, Fingerprint};
+use shared_types::{DuplicateCluster, DuplicateNote, NoteListItem};
+use std::cmp::Ordering;
+use tracing::{debug, info, instrument};
+
+/// Lowest accepted similarity threshold; below this, near-duplicate
+/// candidates are missed and unrelated notes start to cluster.
+const MIN_THRESHOLD: f64 = 0.5;
+
+/// Find clusters of notes whose similarity is at least `threshold` (0.5 - 1.0).
+///
+/// A threshold of 1.0 finds exact duplicates only.
+#[instrument(skip(vault))]
+pub async fn find_duplicate_notes(vault: &Vault, threshold: f64) -> Result<Vec<DuplicateCluster>> {
+    let threshold = clamp_threshold(threshold)?;
+
+    let mut notes: Vec<NoteListItem> = Vec::new();
+    let mut fingerprints: Vec<Fingerprint> = Vec::new();
+    for note in vault.list_notes().await? {
+        let content = match vault.read_note(&note.path).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Skipping {}: {}", note.path, e);
+                continue;
+            }
+        };
+        if has_encrypted_body(&content) {
+            continue;
+        }
+        notes.push(note);
+        fingerprints.push(fingerprint(&content));
+    }
+
+    let groups = find_duplicates(&fingerprints, threshold);
+
+    let mut clusters = Vec::new();
+    for group in groups {
+        let mut backlinks = Vec::with_capacity(group.len());
+        for &i in &group {
+            let summary = vault.repo().get_note_summary(notes[i].id).await?;
+            backlinks.push(summary.map(|s| s.backlink_count).unwrap_or(0));
+        }
+        let members: Vec<(usize, i64)> = group.into_iter().zip(backlinks).collect();
+        clusters.push(build_cluster(&notes, &fingerprints, &members));
+    }
+    clusters.sort_by(compare_clusters);
+
+    info!("Found {} duplicate clusters in {} notes", clusters.len(), notes.len());
+    Ok(clusters)
+}
+
+/// Build a cluster from `(note index, backlink count)` pairs.
+fn build_cluster(notes: &[NoteListItem], fingerprints: &[Fingerprint], members: &[(usize, i64)]) -> DuplicateCluster {
+    // Most backlinks, then most words, then the oldest note (lowest id)
+    let &(target, _) = members
+        .iter()
+        .max_by(|(a, a_links), (b, b_links)| {
+            a_links
+                .cmp(b_links)
+                .then(fingerprints[*a].word_count.cmp(&fingerprints[*b].word_count))
+                .then(notes[*b].id.cmp(&notes[*a].id))
+        })
+        .expect("clusters have members");
+
+    let first = fingerprints[members[0].0].content_hash;
+    let exact = members.iter().all(|&(i, _)| fingerprints[i].content_hash == first);
+
+    let mut cluster_notes: Vec<DuplicateNote> = members
+        .iter()
+        .map(|&(i, backlink_count)| DuplicateNote {
+            note_id: notes[i].id,
+            path: notes[i].path.clone(),
+            title: notes[i].title.clone(),
+            word_count: fingerprints[i].word_count as u32,
+            backlink_count,
+            similarity: round2(fingerprints[i].similarity(&fingerprints[target])),
+        })
+        .collect();
+    let target_id = notes[target].id;
+    cluster_notes.sort_by(|a, b| {
+        (b.note_id == target_id)
+            .cmp(&(a.note_id == target_id))
+            .then(b.similarity.partial_cmp(&a.similarity).unwrap_or(Ordering::Equal))
+            .then(a.path.cmp(&b.path))
+    });
+
+    DuplicateCluster {
+        exact,
+        similarity: cluster_notes.iter().map(|n| n.similarity).fold(1.0, f64::min),
+        merge_target_id: target_id,
+        notes: cluster_notes,
+    }
+}
+
+/// Most similar clusters first, then larger clusters.
+fn compare_clusters(a: &DuplicateCluster, b: &DuplicateCluster) -> Ordering {
+    b.similarity
+        .partial_cmp(&a.similarity)
+        .unwrap_or(Ordering::Equal)
+        .then(b.notes.len().cmp(&a.notes.len()))
+        .then(a.notes[0].path.cmp(&b.notes[0].path))
+}
+
+/// Clamp a threshold into 0.5 - 1.0, rejecting NaN (which `clamp` passes
+/// through, and which no similarity would ever reach).
+fn clamp_threshold(threshold: f64) -> Result<f64> {
+    if threshold.is_nan() {
+        return Err(VaultError::DuplicateThreshold(threshold));
+    }
+    Ok(threshold.clamp(MIN_THRESHOLD, 1.0))
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog while the farmer watches from the old wooden porch and drinks his morning coffee slowly";
+
+    fn note(id: i64, path: &str) -> NoteListItem {
+        NoteListItem {
+            id,
+            path: path.to_string(),
+            title: None,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_clamp_threshold() {
+        assert_eq!(clamp_threshold(0.8).unwrap(), 0.8);
+        assert_eq!(clamp_threshold(0.1).unwrap(), MIN_THRESHOLD);
+        assert_eq!(clamp_threshold(f64::INFINITY).unwrap(), 1.0);
+        assert!(matches!(clamp_threshold(f64::NAN), Err(VaultError::DuplicateThreshold(_))));
+    }
+
+    #[test]
+    fn test_build_cluster_picks_merge_target() {
+        let notes = vec![note(1, "a.md"), note(2, "b.md"), note(3, "c.md")];
+        let fingerprints = vec![
+            fingerprint(TEXT),
+            fingerprint(&format!("{} and a longer ending", TEXT)),
+            fingerprint(TEXT),
+        ];
+
+        // Backlinks win over length
+        let cluster = build_cluster(&notes, &fingerprints, &[(0, 0), (1, 0), (2, 3)]);
+        assert_eq!(cluster.merge_target_id, 3);
+        assert!(!cluster.exact);
+        assert_eq!(cluster.notes[0].note_id, 3);
+        assert_eq!(cluster.notes[1].note_id, 1);
+        assert_eq!(cluster.notes[1].similarity, 1.0);
+        assert!(cluster.similarity < 1.0);
+
+        // Then the longest note
+        let cluster = build_cluster(&notes, &fingerprints, &[(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(cluster.merge_target_id, 2);
+
+        // Then the oldest
+        let cluster = build_cluster(&notes, &fingerprints, &[(0, 0), (2, 0)]);
+        assert_eq!(cluster.merge_target_id, 1);
+        assert!(cluster.exact);
+    }
+}
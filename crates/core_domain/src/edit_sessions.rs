@@ -0,0 +1,160 @@
+//! Advisory editing sessions for notes open in several windows.
+//!
+//! A window begins a session when it opens a note in the editor. If another
+//! window already holds the note, the caller is told which one, shows the
+//! note read-only and can take the session over. Nothing stops a save: the
+//! sessions only keep windows from overwriting each other's edits by
+//! accident. A window's sessions end with it, and every session ends when
+//! the vault is closed or replaced, since note ids are the vault's.
+//!
+//! Several editors of one window (the main editor and columns) can hold
+//! the same note; the window's session lasts until the last of them ends
+//! it.
+
+use chrono::Utc;
+use shared_types::EditSession;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// A window's session on a note, and the editors in it that hold it.
+struct Held {
+    session: EditSession,
+    editors: HashSet<String>,
+}
+
+/// The editing sessions of the open vault, by note id.
+#[derive(Default)]
+pub struct EditSessions {
+    sessions: Mutex<HashMap<i64, Held>>,
+}
+
+impl EditSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin editing `note_id` in `editor` of `window`. If another window
+    /// holds the note, its session is returned unchanged unless `takeover`
+    /// is set. Returns the session holding the note afterwards, and whether
+    /// it is a new one.
+    pub fn begin(&self, note_id: i64, window: &str, editor: &str, takeover: bool) -> (EditSession, bool) {
+        let mut sessions = self.sessions.lock().unwrap();
+        match sessions.get_mut(&note_id) {
+            Some(held) if held.session.window == window => {
+                held.editors.insert(editor.to_string());
+                (held.session.clone(), false)
+            }
+            Some(held) if !takeover => (held.session.clone(), false),
+            _ => {
+                let session = EditSession {
+                    note_id,
+                    window: window.to_string(),
+                    started_at: Utc::now(),
+                };
+                let editors = HashSet::from([editor.to_string()]);
+                sessions.insert(note_id, Held { session: session.clone(), editors });
+                (session, true)
+            }
+        }
+    }
+
+    /// End the session of `editor` in `window` on `note_id`. Returns
+    /// whether the window's session ended, which it does once none of its
+    /// editors hold the note.
+    pub fn end(&self, note_id: i64, window: &str, editor: &str) -> bool {
+        let mut sessions = self.sessions.lock().unwrap();
+        let Some(held) = sessions.get_mut(&note_id).filter(|held| held.session.window == window) else {
+            return false;
+        };
+        held.editors.remove(editor);
+        if held.editors.is_empty() {
+            sessions.remove(&note_id);
+            return true;
+        }
+        false
+    }
+
+    /// End every session of `window`, e.g. when it is closed. Returns the
+    /// notes it held.
+    pub fn release_window(&self, window: &str) -> Vec<i64> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let mut released: Vec<i64> = sessions
+            .values()
+            .filter(|held| held.session.window == window)
+            .map(|held| held.session.note_id)
+            .collect();
+        released.sort_unstable();
+        for note_id in &released {
+            sessions.remove(note_id);
+        }
+        released
+    }
+
+    /// End every session, when the vault is closed or replaced. Returns the
+    /// notes that were held.
+    pub fn clear(&self) -> Vec<i64> {
+        let mut released: Vec<i64> = self.sessions.lock().unwrap().drain().map(|(note_id, _)| note_id).collect();
+        released.sort_unstable();
+        released
+    }
+
+    /// The session on `note_id`, if any.
+    pub fn get(&self, note_id: i64) -> Option<EditSession> {
+        self.sessions.lock().unwrap().get(&note_id).map(|held| held.session.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_sessions() {
+        let sessions = EditSessions::new();
+        let (first, begun) = sessions.begin(1, "main", "editor", false);
+        assert_eq!(first.window, "main");
+        assert!(begun);
+
+        // Another window is told who edits the note, until it takes over
+        assert_eq!(sessions.begin(1, "window-1", "editor", false), (first.clone(), false));
+        assert_eq!(sessions.begin(1, "main", "editor", false), (first, false));
+        assert!(!sessions.end(1, "window-1", "editor"));
+        assert_eq!(sessions.begin(1, "window-1", "editor", true).0.window, "window-1");
+        assert_eq!(sessions.get(1).unwrap().window, "window-1");
+        assert!(!sessions.end(1, "main", "editor"));
+
+        sessions.begin(2, "window-1", "editor", false);
+        sessions.begin(3, "main", "editor", false);
+        assert_eq!(sessions.release_window("window-1"), vec![1, 2]);
+        assert!(sessions.get(1).is_none());
+        assert!(sessions.end(3, "main", "editor"));
+        assert!(sessions.get(3).is_none());
+
+        // Closing the vault ends every session
+        sessions.begin(4, "main", "editor", false);
+        sessions.begin(5, "window-1", "editor", false);
+        assert_eq!(sessions.clear(), vec![4, 5]);
+        assert!(sessions.get(4).is_none());
+    }
+
+    #[test]
+    fn test_edit_sessions_of_several_editors() {
+        let sessions = EditSessions::new();
+        assert!(sessions.begin(1, "main", "editor", false).1);
+        assert!(!sessions.begin(1, "main", "column-1", false).1);
+
+        // The window keeps the note until its last editor ends the session
+        assert!(!sessions.end(1, "main", "column-1"));
+        assert_eq!(sessions.get(1).unwrap().window, "main");
+        assert!(!sessions.end(1, "main", "column-1"));
+        assert!(sessions.end(1, "main", "editor"));
+        assert!(sessions.get(1).is_none());
+
+        // A takeover ends the session of every editor of the other window
+        sessions.begin(2, "main", "editor", false);
+        sessions.begin(2, "main", "column-1", false);
+        sessions.begin(2, "window-1", "editor", true);
+        assert!(!sessions.end(2, "main", "editor"));
+        assert!(sessions.end(2, "window-1", "editor"));
+    }
+}
@@ -0,0 +1,331 @@
+//! Embed resolution (`![[target]]`, `![[target#section]]`) for the editor.
+//!
+//! Media targets resolve to a file path; note targets to the note's content
+//! (or one section of it). The embeds inside an embedded note are followed
+//! here too, up to [`MAX_EMBED_DEPTH`], so a loop (a note embedding itself,
+//! or notes embedding each other) is flagged with the chain of embeds that
+//! leads back around. The content is still returned; the editor shows it
+//! with the loop instead of rendering it until the depth runs out.
+//!
+//! Chain entries are a note path and section: embedding another section of
+//! the same note is not a loop. Each note is read once per resolve, however
+//! often it is embedded along the way.
+
+use crate::moves::{resolve_relative, target_path};
+use crate::rendering::{FILE_EXTENSIONS, IMAGE_EXTENSIONS};
+use crate::resolve::NoteResolver;
+use crate::vault::{Result, Vault};
+use core_index::markdown::{extract_section_with_heading, slugify};
+use core_index::{collect_wikilinks, strip_frontmatter};
+use shared_types::{EmbedContent, EmbedCycle, ResolveEmbedRequest};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use tracing::{debug, instrument};
+
+/// How deep embeds nest.
+pub const MAX_EMBED_DEPTH: u8 = 3;
+
+/// A note (or one section of it) in the chain of embeds being resolved.
+#[derive(Debug, Clone, PartialEq)]
+struct EmbedTarget {
+    path: String,
+    /// Section slug.
+    section: Option<String>,
+}
+
+impl EmbedTarget {
+    fn label(&self) -> String {
+        match &self.section {
+            Some(section) => format!("{}#{}", self.path, section),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// Resolve an embed in the note at `request.source_path`.
+#[instrument(skip(vault))]
+pub async fn resolve_embed(vault: &Vault, request: &ResolveEmbedRequest) -> Result<EmbedContent> {
+    if request.depth > MAX_EMBED_DEPTH {
+        return Ok(unresolved(
+            request,
+            format!("Maximum embed depth ({}) exceeded", MAX_EMBED_DEPTH),
+        ));
+    }
+
+    if is_media(&request.target) {
+        // Pasted images are linked relative to the note's folder
+        let relative = request
+            .source_path
+            .as_deref()
+            .and_then(|source| resolve_relative(source.rsplit_once('/').map_or("", |(dir, _)| dir), &request.target))
+            .map(|target| target_path(&target))
+            .filter(|path| vault.fs().to_absolute(Path::new(path)).is_file());
+        let full_path = match relative {
+            Some(path) => Some(vault.fs().to_absolute(Path::new(&path))),
+            None => vault.resolve_asset_path(&request.target).await,
+        };
+        let Some(full_path) = full_path else {
+            return Ok(unresolved(request, format!("Media not found: {}", request.target)));
+        };
+        // The frontend converts the full path with convertFileSrc
+        return Ok(EmbedContent {
+            note_id: None,
+            path: request.target.clone(),
+            content: None,
+            is_image: true,
+            asset_url: Some(full_path.to_string_lossy().to_string()),
+            error: None,
+            cycle: None,
+        });
+    }
+
+    let resolver = vault.note_resolver().await?;
+    let Some(note) = resolver.resolve(&request.target, request.source_path.as_deref()) else {
+        return Ok(unresolved(request, format!("Note not found: {}", request.target)));
+    };
+
+    let target = EmbedTarget {
+        path: note.path.clone(),
+        section: request.section.as_deref().map(slugify),
+    };
+    let mut chain: Vec<EmbedTarget> = request
+        .source_path
+        .iter()
+        .map(|path| EmbedTarget {
+            path: path.clone(),
+            section: None,
+        })
+        .collect();
+
+    let mut walk = EmbedWalk::new(vault, &resolver);
+    let content = walk.read_target(&target).await?;
+    let Some(content) = content else {
+        let section = request.section.as_deref().unwrap_or_default();
+        return Ok(EmbedContent {
+            note_id: Some(note.id),
+            path: note.path.clone(),
+            content: Some(format!("Section '{}' not found", section)),
+            is_image: false,
+            asset_url: None,
+            error: None,
+            cycle: None,
+        });
+    };
+
+    let cycle = if chain.contains(&target) {
+        Some(cycle(&chain, &target))
+    } else {
+        chain.push(target);
+        walk.find_cycle(&content, &mut chain, MAX_EMBED_DEPTH - request.depth)
+            .await?
+    };
+    if let Some(cycle) = &cycle {
+        debug!("Embed loop: {}", cycle.chain.join(" -> "));
+    }
+
+    Ok(EmbedContent {
+        note_id: Some(note.id),
+        path: note.path.clone(),
+        content: Some(content),
+        is_image: false,
+        asset_url: None,
+        error: None,
+        cycle,
+    })
+}
+
+/// The notes read while following embeds, and the embeds already followed
+/// without finding a loop.
+struct EmbedWalk<'a> {
+    vault: &'a Vault,
+    resolver: &'a NoteResolver<'a>,
+    /// Note contents by path.
+    notes: HashMap<String, String>,
+    /// Labels of the targets followed, with the levels they were followed for.
+    followed: HashMap<String, u8>,
+}
+
+impl<'a> EmbedWalk<'a> {
+    fn new(vault: &'a Vault, resolver: &'a NoteResolver<'a>) -> Self {
+        Self {
+            vault,
+            resolver,
+            notes: HashMap::new(),
+            followed: HashMap::new(),
+        }
+    }
+
+    /// The content an embed of `target` shows; None for a missing section.
+    async fn read_target(&mut self, target: &EmbedTarget) -> Result<Option<String>> {
+        if !self.notes.contains_key(&target.path) {
+            let content = self.vault.read_note(&target.path).await?;
+            self.notes.insert(target.path.clone(), content);
+        }
+        let content = &self.notes[&target.path];
+        Ok(match &target.section {
+            Some(section) => extract_section_with_heading(content, section),
+            None => Some(content.clone()),
+        })
+    }
+
+    /// Follow the note embeds in `content` (the last entry of `chain`) for
+    /// up to `levels` levels, and return the first loop back into `chain`.
+    fn find_cycle<'b>(
+        &'b mut self,
+        content: &'b str,
+        chain: &'b mut Vec<EmbedTarget>,
+        levels: u8,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<EmbedCycle>>> + Send + 'b>>
+    where
+        'a: 'b,
+    {
+        Box::pin(async move {
+            if levels == 0 {
+                return Ok(None);
+            }
+
+            let from = chain.last().map(|target| target.path.clone());
+            for link in collect_wikilinks(strip_frontmatter(content)) {
+                if !link.embed || is_media(&link.target) {
+                    continue;
+                }
+                let Some(note) = self.resolver.resolve(&link.target, from.as_deref()) else {
+                    continue;
+                };
+
+                let target = EmbedTarget {
+                    path: note.path.clone(),
+                    section: link.section.as_deref().map(slugify),
+                };
+                if chain.contains(&target) {
+                    return Ok(Some(cycle(chain, &target)));
+                }
+                // Already followed as deep without finding a loop
+                let label = target.label();
+                if self.followed.get(&label).is_some_and(|&followed| followed >= levels - 1) {
+                    continue;
+                }
+
+                let Some(content) = self.read_target(&target).await? else {
+                    continue;
+                };
+                chain.push(target);
+                let found = self.find_cycle(&content, chain, levels - 1).await?;
+                chain.pop();
+                if found.is_some() {
+                    return Ok(found);
+                }
+                self.followed.insert(label, levels - 1);
+            }
+            Ok(None)
+        })
+    }
+}
+
+fn cycle(chain: &[EmbedTarget], repeated: &EmbedTarget) -> EmbedCycle {
+    let mut labels: Vec<String> = chain.iter().map(EmbedTarget::label).collect();
+    labels.push(repeated.label());
+    EmbedCycle {
+        chain: labels,
+        repeated: repeated.label(),
+    }
+}
+
+fn unresolved(request: &ResolveEmbedRequest, error: String) -> EmbedContent {
+    EmbedContent {
+        note_id: None,
+        path: request.target.clone(),
+        content: None,
+        is_image: is_media(&request.target),
+        asset_url: None,
+        error: Some(error),
+        cycle: None,
+    }
+}
+
+/// Whether an embed target is a media file (image, audio, video, PDF)
+/// rather than a note.
+pub(crate) fn is_media(target: &str) -> bool {
+    let extension = Path::new(target)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    IMAGE_EXTENSIONS.contains(&extension.as_str()) || FILE_EXTENSIONS.contains(&extension.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(target: &str, section: Option<&str>, source_path: &str) -> ResolveEmbedRequest {
+        ResolveEmbedRequest {
+            target: target.to_string(),
+            section: section.map(String::from),
+            depth: 0,
+            source_path: Some(source_path.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_embed_detects_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("A.md", "# A\n\n![[B]]\n").await.unwrap();
+        vault.write_note("B.md", "# B\n\n![[C]]\n").await.unwrap();
+        vault.write_note("C.md", "# C\n\n![[A]]\n").await.unwrap();
+        vault.write_note("Self.md", "# Self\n\n![[Self]]\n").await.unwrap();
+        vault.write_note("D.md", "# D\n\n![[E]] ![[E]]\n").await.unwrap();
+        vault.write_note("E.md", "# E\n").await.unwrap();
+        vault
+            .write_note("Sections.md", "# Sections\n\n## One\n\n![[Sections#Two]]\n\n## Two\n\nText\n")
+            .await
+            .unwrap();
+        vault.write_note("Home.md", "# Home\n").await.unwrap();
+
+        // A -> B -> C -> A, found from the note with the embed
+        let result = resolve_embed(&vault, &request("B", None, "A.md")).await.unwrap();
+        let cycle = result.cycle.unwrap();
+        assert_eq!(cycle.chain, ["A.md", "B.md", "C.md", "A.md"]);
+        assert_eq!(cycle.repeated, "A.md");
+        // The content is still shown, with the loop flagged
+        assert_eq!(result.content.as_deref(), Some("# B\n\n![[C]]\n"));
+        assert!(result.error.is_none());
+
+        let result = resolve_embed(&vault, &request("Self", None, "Home.md")).await.unwrap();
+        assert_eq!(result.cycle.unwrap().chain, ["Home.md", "Self.md", "Self.md"]);
+
+        // A loop further down the chain
+        let result = resolve_embed(&vault, &request("B", None, "Home.md")).await.unwrap();
+        assert_eq!(result.cycle.unwrap().chain, ["Home.md", "B.md", "C.md", "A.md", "B.md"]);
+
+        // The same note embedded twice is not a loop
+        let result = resolve_embed(&vault, &request("D", None, "Home.md")).await.unwrap();
+        assert!(result.cycle.is_none());
+        assert_eq!(result.content.as_deref(), Some("# D\n\n![[E]] ![[E]]\n"));
+
+        // Another section of the same note is not a loop
+        let result = resolve_embed(&vault, &request("Sections", Some("One"), "Home.md")).await.unwrap();
+        assert!(result.cycle.is_none(), "{:?}", result.cycle);
+        assert!(result.content.unwrap().starts_with("## One"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_media_relative_to_note() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("projects/assets")).unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("projects/assets/chart.png"), b"near").unwrap();
+        std::fs::write(dir.path().join("assets/chart.png"), b"root").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        let result = resolve_embed(&vault, &request("assets/chart.png", None, "projects/plan.md")).await.unwrap();
+        assert!(result.asset_url.unwrap().ends_with("projects/assets/chart.png"));
+
+        let result = resolve_embed(&vault, &request("../assets/chart.png", None, "projects/plan.md")).await.unwrap();
+        let asset = result.asset_url.unwrap();
+        assert!(asset.ends_with("assets/chart.png") && !asset.contains("projects"));
+    }
+}
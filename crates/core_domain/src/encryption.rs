@@ -0,0 +1,204 @@
+//! Per-note encryption for notes with `encrypted: true` in their frontmatter.
+//!
+//! Only the body is encrypted; the frontmatter stays readable so the flag
+//! (and other properties) can be indexed. The body is replaced by an armored
+//! block containing AES-256-GCM ciphertext, keyed with Argon2id from the
+//! vault passphrase and a random per-note salt.
+//!
+//! A key check, a known text encrypted with the passphrase, lets a
+//! passphrase be verified while no note is encrypted with it yet.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use core_index::{parse_frontmatter, PropertyValue};
+use thiserror::Error;
+
+const ARMOR_BEGIN: &str = "-----BEGIN NEUROFLOW ENCRYPTED NOTE-----";
+const ARMOR_END: &str = "-----END NEUROFLOW ENCRYPTED NOTE-----";
+
+/// Format version stored as the first byte of the payload.
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Text encrypted as the key check. Like a note it needs frontmatter, as
+/// an armored block at the start would be read as frontmatter.
+const KEY_CHECK: &str = "---\nkey_check: true\n---\nneuroflow key check\n";
+
+#[derive(Error, Debug)]
+pub enum EncryptionError {
+    #[error("Wrong passphrase or corrupted note")]
+    Decrypt,
+
+    #[error("Malformed encrypted note: {0}")]
+    Malformed(String),
+
+    #[error("Key derivation failed: {0}")]
+    KeyDerivation(String),
+}
+
+/// Whether the note's frontmatter has `encrypted: true`.
+pub fn is_marked_encrypted(content: &str) -> bool {
+    let (frontmatter, _) = parse_frontmatter(content);
+    matches!(
+        frontmatter.properties.get("encrypted"),
+        Some(PropertyValue::Bool(true))
+    )
+}
+
+/// Whether the note's body is an encrypted block.
+pub fn has_encrypted_body(content: &str) -> bool {
+    let (_, body) = parse_frontmatter(content);
+    body.trim_start().starts_with(ARMOR_BEGIN)
+}
+
+/// Encrypt the body of a note, keeping its frontmatter.
+///
+/// Notes whose body is already encrypted are returned unchanged.
+pub fn encrypt_note(content: &str, passphrase: &str) -> Result<String, EncryptionError> {
+    if has_encrypted_body(content) {
+        return Ok(content.to_string());
+    }
+
+    let (header, body) = split_frontmatter(content);
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = cipher_for(passphrase, &salt)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), body.as_bytes())
+        .map_err(|_| EncryptionError::Malformed("encryption failed".to_string()))?;
+
+    let mut payload = Vec::with_capacity(1 + SALT_LEN + NONCE_LEN + ciphertext.len());
+    payload.push(FORMAT_VERSION);
+    payload.extend_from_slice(&salt);
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+
+    let encoded = STANDARD.encode(&payload);
+    let mut armored = String::with_capacity(encoded.len() + 100);
+    armored.push_str(ARMOR_BEGIN);
+    armored.push('\n');
+    for chunk in encoded.as_bytes().chunks(64) {
+        // Base64 output is ASCII, so every chunk is valid UTF-8
+        armored.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        armored.push('\n');
+    }
+    armored.push_str(ARMOR_END);
+    armored.push('\n');
+
+    Ok(format!("{}{}", header, armored))
+}
+
+/// Decrypt the body of a note, keeping its frontmatter.
+///
+/// Notes without an encrypted body are returned unchanged.
+pub fn decrypt_note(content: &str, passphrase: &str) -> Result<String, EncryptionError> {
+    if !has_encrypted_body(content) {
+        return Ok(content.to_string());
+    }
+
+    let (header, body) = split_frontmatter(content);
+    let encoded: String = body
+        .trim()
+        .strip_prefix(ARMOR_BEGIN)
+        .and_then(|b| b.trim_end().strip_suffix(ARMOR_END))
+        .ok_or_else(|| EncryptionError::Malformed("missing armor".to_string()))?
+        .split_whitespace()
+        .collect();
+
+    let payload = STANDARD
+        .decode(encoded)
+        .map_err(|e| EncryptionError::Malformed(e.to_string()))?;
+    if payload.len() < 1 + SALT_LEN + NONCE_LEN || payload[0] != FORMAT_VERSION {
+        return Err(EncryptionError::Malformed("unsupported format".to_string()));
+    }
+
+    let (salt, rest) = payload[1..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let plaintext = cipher_for(passphrase, salt)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| EncryptionError::Decrypt)?;
+    let body = String::from_utf8(plaintext).map_err(|_| EncryptionError::Decrypt)?;
+
+    Ok(format!("{}{}", header, body))
+}
+
+/// Split a note into its frontmatter block (including delimiters) and body.
+fn split_frontmatter(content: &str) -> (&str, &str) {
+    let (frontmatter, _) = parse_frontmatter(content);
+    content.split_at(frontmatter.content_start.min(content.len()))
+}
+
+/// Derive the AES key for a note from the passphrase and its salt.
+fn cipher_for(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, EncryptionError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| EncryptionError::KeyDerivation(e.to_string()))?;
+    Aes256Gcm::new_from_slice(&key).map_err(|e| EncryptionError::KeyDerivation(e.to_string()))
+}
+
+/// Encrypt the key check with a passphrase.
+pub fn key_check(passphrase: &str) -> Result<String, EncryptionError> {
+    encrypt_note(KEY_CHECK, passphrase)
+}
+
+/// Whether `check` (from `key_check`) was made with this passphrase.
+pub fn verify_key_check(check: &str, passphrase: &str) -> bool {
+    has_encrypted_body(check) && decrypt_note(check, passphrase).is_ok_and(|text| text == KEY_CHECK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "---\nencrypted: true\ntags: [private]\n---\n# Secret\n\nThe code is 1234.\n";
+
+    #[test]
+    fn test_round_trip_keeps_frontmatter() {
+        let encrypted = encrypt_note(NOTE, "hunter2").unwrap();
+
+        assert!(encrypted.starts_with("---\nencrypted: true\ntags: [private]\n---\n"));
+        assert!(has_encrypted_body(&encrypted));
+        assert!(is_marked_encrypted(&encrypted));
+        assert!(!encrypted.contains("1234"));
+
+        assert_eq!(decrypt_note(&encrypted, "hunter2").unwrap(), NOTE);
+    }
+
+    #[test]
+    fn test_key_check() {
+        let check = key_check("hunter2").unwrap();
+        assert!(verify_key_check(&check, "hunter2"));
+        assert!(!verify_key_check(&check, "wrong"));
+        assert!(!verify_key_check(KEY_CHECK, "hunter2"));
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails() {
+        let encrypted = encrypt_note(NOTE, "hunter2").unwrap();
+        assert!(matches!(
+            decrypt_note(&encrypted, "wrong"),
+            Err(EncryptionError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_is_idempotent_and_plain_notes_pass_through() {
+        let encrypted = encrypt_note(NOTE, "hunter2").unwrap();
+        assert_eq!(encrypt_note(&encrypted, "hunter2").unwrap(), encrypted);
+
+        let plain = "# Plain\n\nNothing to hide.\n";
+        assert!(!is_marked_encrypted(plain));
+        assert_eq!(decrypt_note(plain, "hunter2").unwrap(), plain);
+    }
+}
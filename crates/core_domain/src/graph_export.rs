@@ -0,0 +1,195 @@
+//! Link graph export to Graphviz DOT, GEXF (Gephi) and JSON.
+//!
+//! Nodes are notes, labelled with their title (or file name), and edges are
+//! the resolved links between them, weighted by how often a note links to
+//! the other. The export can be restricted to a folder or a tag; links
+//! leaving the scope are dropped.
+
+use crate::vault::{Result, Vault};
+use core_index::rendering::escape_html;
+use shared_types::{GraphExportFormat, GraphExportResult, GraphNode, GraphScope, LinkGraph};
+use std::fmt::Write;
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Export the link graph of the notes in `scope` as a `format` file at
+/// `output_path`.
+#[instrument(skip(vault))]
+pub async fn export_graph(
+    vault: &Vault,
+    format: GraphExportFormat,
+    scope: &GraphScope,
+    output_path: &Path,
+) -> Result<GraphExportResult> {
+    let graph = vault.repo().get_link_graph(scope).await?;
+
+    tokio::fs::write(output_path, render_graph(&graph, format))
+        .await
+        .map_err(core_fs::FsError::from)?;
+
+    info!(
+        "Exported graph of {} notes and {} links to {}",
+        graph.nodes.len(),
+        graph.edges.len(),
+        output_path.display()
+    );
+    Ok(GraphExportResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        node_count: graph.nodes.len(),
+        edge_count: graph.edges.len(),
+    })
+}
+
+/// Render a link graph in `format`.
+pub fn render_graph(graph: &LinkGraph, format: GraphExportFormat) -> String {
+    match format {
+        GraphExportFormat::Dot => to_dot(graph),
+        GraphExportFormat::Gexf => to_gexf(graph),
+        GraphExportFormat::Json => serde_json::to_string_pretty(graph).unwrap_or_default(),
+    }
+}
+
+/// The label of a node: the note's title, or its file name.
+fn label(node: &GraphNode) -> &str {
+    node.title
+        .as_deref()
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| {
+            let name = node.path.rsplit('/').next().unwrap_or(&node.path);
+            name.strip_suffix(".md").unwrap_or(name)
+        })
+}
+
+/// Quote a string for DOT.
+fn dot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn to_dot(graph: &LinkGraph) -> String {
+    let mut out = String::from("digraph vault {\n  node [shape=box];\n");
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            "  n{} [label={}, tooltip={}];",
+            node.id,
+            dot_string(label(node)),
+            dot_string(&node.path)
+        );
+    }
+    for edge in &graph.edges {
+        if edge.weight > 1 {
+            let _ = writeln!(out, "  n{} -> n{} [weight={}];", edge.source, edge.target, edge.weight);
+        } else {
+            let _ = writeln!(out, "  n{} -> n{};", edge.source, edge.target);
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn to_gexf(graph: &LinkGraph) -> String {
+    let mut out = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" version="1.3">
+  <meta>
+    <creator>NeuroFlow Notes</creator>
+  </meta>
+  <graph defaultedgetype="directed">
+    <attributes class="node">
+      <attribute id="path" title="path" type="string"/>
+      <attribute id="tags" title="tags" type="string"/>
+    </attributes>
+    <nodes>
+"#,
+    );
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            r#"      <node id="{}" label="{}">
+        <attvalues>
+          <attvalue for="path" value="{}"/>
+          <attvalue for="tags" value="{}"/>
+        </attvalues>
+      </node>"#,
+            node.id,
+            escape_html(label(node)),
+            escape_html(&node.path),
+            escape_html(&node.tags.join("|"))
+        );
+    }
+    out.push_str("    </nodes>\n    <edges>\n");
+    for (i, edge) in graph.edges.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            r#"      <edge id="{}" source="{}" target="{}" weight="{}"/>"#,
+            i, edge.source, edge.target, edge.weight
+        );
+    }
+    out.push_str("    </edges>\n  </graph>\n</gexf>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::GraphEdge;
+
+    fn graph() -> LinkGraph {
+        LinkGraph {
+            nodes: vec![
+                GraphNode {
+                    id: 1,
+                    path: "projects/plan.md".to_string(),
+                    title: Some("The \"Plan\" & more".to_string()),
+                    tags: vec!["project".to_string(), "work".to_string()],
+                },
+                GraphNode {
+                    id: 2,
+                    path: "inbox/idea.md".to_string(),
+                    title: None,
+                    tags: Vec::new(),
+                },
+            ],
+            edges: vec![
+                GraphEdge { source: 1, target: 2, weight: 2 },
+                GraphEdge { source: 2, target: 1, weight: 1 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let dot = render_graph(&graph(), GraphExportFormat::Dot);
+        assert!(dot.starts_with("digraph vault {\n"));
+        assert!(dot.contains(r#"  n1 [label="The \"Plan\" & more", tooltip="projects/plan.md"];"#));
+        assert!(dot.contains(r#"  n2 [label="idea", tooltip="inbox/idea.md"];"#));
+        assert!(dot.contains("  n1 -> n2 [weight=2];\n  n2 -> n1;\n}"));
+    }
+
+    #[test]
+    fn test_render_gexf() {
+        let gexf = render_graph(&graph(), GraphExportFormat::Gexf);
+        assert!(gexf.contains(r#"<node id="1" label="The &quot;Plan&quot; &amp; more">"#));
+        assert!(gexf.contains(r#"<attvalue for="tags" value="project|work"/>"#));
+        assert!(gexf.contains(r#"<edge id="0" source="1" target="2" weight="2"/>"#));
+        assert!(gexf.trim_end().ends_with("</gexf>"));
+
+        let json: LinkGraph = serde_json::from_str(&render_graph(&graph(), GraphExportFormat::Json)).unwrap();
+        assert_eq!(json.edges.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_export_links_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let plan = vault.write_note("projects/Plan.md", "# Plan\n").await.unwrap();
+        let idea = vault.write_note("inbox/idea.md", "# Idea\n\nSee [[Plan]] and [[Plan|the plan]].\n").await.unwrap();
+
+        let output = dir.path().join("graph.json");
+        let result = export_graph(&vault, GraphExportFormat::Json, &GraphScope::default(), &output).await.unwrap();
+        assert_eq!((result.node_count, result.edge_count), (2, 1));
+        let graph: LinkGraph = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        let edges: Vec<_> = graph.edges.iter().map(|e| (e.source, e.target, e.weight)).collect();
+        assert_eq!(edges, [(idea, plan, 2)]);
+    }
+}
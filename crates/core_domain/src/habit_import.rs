@@ -0,0 +1,299 @@
+//! Habit data importer.
+//!
+//! Bulk-loads historical habit entries (e.g. exported step counts or sleep
+//! hours) from a CSV file using a column mapping. Rows that already have an
+//! entry for the same date and time are skipped.
+
+use crate::vault::{Vault, VaultError};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use shared_types::{HabitCsvMapping, HabitImportResult, LogHabitEntryRequest};
+use std::path::Path;
+use tracing::info;
+
+/// Date formats tried when the mapping does not specify one.
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y", "%m/%d/%Y"];
+
+/// Date-time formats tried when the mapping does not specify a date format.
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Rows parsed from a CSV file.
+#[derive(Debug, Default)]
+pub struct ParsedHabitCsv {
+    /// Entries ready to be logged.
+    pub entries: Vec<LogHabitEntryRequest>,
+    /// Rows that could not be parsed, with line numbers.
+    pub errors: Vec<String>,
+}
+
+/// Import habit entries for `habit_id` from the CSV file at `csv_path`.
+pub async fn import_habit_csv(
+    vault: &Vault,
+    habit_id: i64,
+    csv_path: &Path,
+    mapping: &HabitCsvMapping,
+) -> Result<HabitImportResult, VaultError> {
+    if vault.repo().get_habit(habit_id).await?.is_none() {
+        return Err(VaultError::InvalidImport(format!("Habit {} not found", habit_id)));
+    }
+
+    let content = tokio::fs::read_to_string(csv_path)
+        .await
+        .map_err(core_fs::FsError::from)?;
+
+    let parsed = parse_habit_csv(habit_id, &content, mapping)?;
+    let (imported, skipped_duplicates) = vault.repo().import_habit_entries(&parsed.entries).await?;
+
+    info!(
+        "Imported {} entries for habit {} from {} ({} duplicates, {} errors)",
+        imported,
+        habit_id,
+        csv_path.display(),
+        skipped_duplicates,
+        parsed.errors.len()
+    );
+
+    Ok(HabitImportResult {
+        imported,
+        skipped_duplicates,
+        errors: parsed.errors,
+    })
+}
+
+/// Parse CSV content into habit entries using the given column mapping.
+///
+/// Fails if the mapping references a column that does not exist. Individual
+/// rows that cannot be parsed are reported in `errors` and skipped.
+pub fn parse_habit_csv(
+    habit_id: i64,
+    content: &str,
+    mapping: &HabitCsvMapping,
+) -> Result<ParsedHabitCsv, VaultError> {
+    let delimiter = mapping
+        .delimiter
+        .as_deref()
+        .and_then(|d| d.chars().next())
+        .unwrap_or(',');
+    let has_header = mapping.has_header.unwrap_or(true);
+
+    let mut rows = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| (idx + 1, split_csv_line(line, delimiter)));
+
+    let header = if has_header {
+        rows.next().map(|(_, fields)| fields).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let date_idx = resolve_column(&header, &mapping.date_column)?;
+    let value_idx = resolve_column(&header, &mapping.value_column)?;
+    let time_idx = mapping
+        .time_column
+        .as_deref()
+        .map(|c| resolve_column(&header, c))
+        .transpose()?;
+    let notes_idx = mapping
+        .notes_column
+        .as_deref()
+        .map(|c| resolve_column(&header, c))
+        .transpose()?;
+
+    let mut parsed = ParsedHabitCsv::default();
+
+    for (line_no, fields) in rows {
+        let field = |idx: usize| fields.get(idx).map(|f| f.trim()).unwrap_or("");
+
+        let Some(date) = parse_date(field(date_idx), mapping.date_format.as_deref()) else {
+            parsed
+                .errors
+                .push(format!("Line {}: invalid date '{}'", line_no, field(date_idx)));
+            continue;
+        };
+
+        let value = field(value_idx);
+        if value.is_empty() {
+            parsed.errors.push(format!("Line {}: missing value", line_no));
+            continue;
+        }
+
+        let time = match time_idx.map(field).filter(|t| !t.is_empty()) {
+            Some(raw) => match parse_time(raw) {
+                Some(t) => Some(t.format("%H:%M:%S").to_string()),
+                None => {
+                    parsed
+                        .errors
+                        .push(format!("Line {}: invalid time '{}'", line_no, raw));
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let notes = notes_idx
+            .map(field)
+            .filter(|n| !n.is_empty())
+            .map(String::from);
+
+        parsed.entries.push(LogHabitEntryRequest {
+            habit_id,
+            date: date.format("%Y-%m-%d").to_string(),
+            time,
+            value: value.to_string(),
+            notes,
+        });
+    }
+
+    Ok(parsed)
+}
+
+/// Find a column by header name (case-insensitive) or 0-based index.
+fn resolve_column(header: &[String], column: &str) -> Result<usize, VaultError> {
+    let column = column.trim();
+    if let Some(idx) = header
+        .iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(column))
+    {
+        return Ok(idx);
+    }
+    column
+        .parse::<usize>()
+        .map_err(|_| VaultError::InvalidImport(format!("Column not found: {}", column)))
+}
+
+/// Parse a date using the given format, or try common formats.
+///
+/// Date-time values are accepted and truncated to their date.
+fn parse_date(raw: &str, format: Option<&str>) -> Option<NaiveDate> {
+    let formats: Vec<&str> = match format {
+        Some(f) => vec![f],
+        None => DATE_FORMATS.to_vec(),
+    };
+    let datetime_formats: Vec<&str> = match format {
+        Some(f) => vec![f],
+        None => DATETIME_FORMATS.to_vec(),
+    };
+
+    formats
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(raw, f).ok())
+        .or_else(|| {
+            datetime_formats
+                .iter()
+                .find_map(|f| NaiveDateTime::parse_from_str(raw, f).ok())
+                .map(|dt| dt.date())
+        })
+        .or_else(|| {
+            // Fall back to a leading ISO date (e.g. "2024-01-05 23:10:00 +0100")
+            raw.get(..10)
+                .filter(|_| format.is_none())
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        })
+}
+
+/// Parse a time as HH:MM:SS or HH:MM.
+fn parse_time(raw: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(raw, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(raw, "%H:%M"))
+        .ok()
+}
+
+/// Split a CSV line into fields, honouring double-quoted fields and `""` escapes.
+pub(crate) fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(date: &str, value: &str) -> HabitCsvMapping {
+        HabitCsvMapping {
+            date_column: date.to_string(),
+            value_column: value.to_string(),
+            time_column: None,
+            notes_column: None,
+            date_format: None,
+            delimiter: None,
+            has_header: None,
+        }
+    }
+
+    #[test]
+    fn test_split_csv_line() {
+        assert_eq!(split_csv_line("a,b,c", ','), vec!["a", "b", "c"]);
+        assert_eq!(
+            split_csv_line(r#"2024-01-01,"7,5","said ""hi""""#, ','),
+            vec!["2024-01-01", "7,5", r#"said "hi""#]
+        );
+        assert_eq!(split_csv_line("a;;c\r", ';'), vec!["a", "", "c"]);
+    }
+
+    #[test]
+    fn test_parse_habit_csv_by_header() {
+        let content = "Date,Steps,Comment\n2024-01-01,8000,walk\n2024-01-02 07:30:00,9500,\n";
+        let parsed = parse_habit_csv(3, content, &HabitCsvMapping {
+            notes_column: Some("comment".to_string()),
+            ..mapping("date", "Steps")
+        })
+        .unwrap();
+
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.entries.len(), 2);
+        assert_eq!(parsed.entries[0].habit_id, 3);
+        assert_eq!(parsed.entries[0].date, "2024-01-01");
+        assert_eq!(parsed.entries[0].value, "8000");
+        assert_eq!(parsed.entries[0].notes.as_deref(), Some("walk"));
+        assert_eq!(parsed.entries[1].date, "2024-01-02");
+        assert_eq!(parsed.entries[1].notes, None);
+    }
+
+    #[test]
+    fn test_parse_habit_csv_by_index_with_format() {
+        let content = "05.01.2024;7.5;22:45\nbad;8;23:00\n06.01.2024;;23:00\n07.01.2024;6;late\n";
+        let parsed = parse_habit_csv(1, content, &HabitCsvMapping {
+            time_column: Some("2".to_string()),
+            date_format: Some("%d.%m.%Y".to_string()),
+            delimiter: Some(";".to_string()),
+            has_header: Some(false),
+            ..mapping("0", "1")
+        })
+        .unwrap();
+
+        assert_eq!(parsed.entries.len(), 1);
+        assert_eq!(parsed.entries[0].date, "2024-01-05");
+        assert_eq!(parsed.entries[0].time.as_deref(), Some("22:45:00"));
+        assert_eq!(parsed.errors.len(), 3);
+        assert!(parsed.errors[0].starts_with("Line 2"));
+    }
+
+    #[test]
+    fn test_parse_habit_csv_unknown_column() {
+        let result = parse_habit_csv(1, "date,value\n", &mapping("date", "hours"));
+        assert!(result.is_err());
+    }
+}
@@ -7,10 +7,13 @@
 //! - Merging frontmatter tags with inline tags
 //! - Preserving wikilink syntax
 
-use crate::vault::Vault;
-use core_fs::hash_content;
+use crate::indexer::indexed_part;
+use crate::jobs::Job;
+use crate::vault::{resolve_backlinks, Vault};
+use core_fs::{hash_content, hash_file};
 use core_index::frontmatter::{parse_frontmatter, PropertyValue};
-use core_index::markdown::parse;
+use core_index::markdown::parse_with;
+use core_storage::CSS_CLASSES_PROPERTY;
 use shared_types::{ImportProgress, ImportResult};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -19,7 +22,7 @@ use tokio::sync::mpsc;
 use tracing::{debug, info};
 
 /// Image/asset file extensions to copy.
-const ASSET_EXTENSIONS: &[&str] = &[
+pub(crate) const ASSET_EXTENSIONS: &[&str] = &[
     "png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", // Images
     "pdf", "doc", "docx", "xls", "xlsx", // Documents
     "mp3", "wav", "ogg", "m4a", // Audio
@@ -28,12 +31,14 @@ const ASSET_EXTENSIONS: &[&str] = &[
 
 /// Import an Obsidian vault into the current vault.
 ///
-/// Returns an ImportResult with statistics.
+/// Returns an ImportResult with statistics. When run as a job, progress is
+/// reported through it and a cancelled job stops before the next file.
 pub async fn import_obsidian_vault(
     vault: &Vault,
     source_path: &Path,
     target_subfolder: Option<&str>,
     progress_tx: Option<mpsc::Sender<ImportProgress>>,
+    job: Option<&Job>,
 ) -> Result<ImportResult, crate::vault::VaultError> {
     let start = Instant::now();
     let mut result = ImportResult {
@@ -43,6 +48,7 @@ pub async fn import_obsidian_vault(
         tags_imported: 0,
         duration_ms: 0,
         warnings: vec![],
+        cancelled: false,
     };
 
     info!("Starting Obsidian vault import from {}", source_path.display());
@@ -75,16 +81,25 @@ pub async fn import_obsidian_vault(
 
     // Copy asset files first
     for (i, (rel_path, full_path)) in asset_files.iter().enumerate() {
+        if is_cancelled(vault, job, rel_path, i, total_files) {
+            result.cancelled = true;
+            break;
+        }
+
         let target_path = if target_base.is_empty() {
             rel_path.clone()
         } else {
             format!("{}/{}", target_base, rel_path)
         };
 
-        match copy_file(full_path, &vault.fs().to_absolute(Path::new(&target_path))).await {
+        let target = vault.fs().to_absolute(Path::new(&target_path));
+        match copy_file(full_path, &target).await {
             Ok(_) => {
                 result.files_copied += 1;
                 debug!("Copied asset: {} -> {}", rel_path, target_path);
+                // Recorded so later pastes and downloads reuse the copy
+                let (hash, size) = hash_file(&target).await?;
+                vault.repo().record_asset(&target_path.replace('\\', "/"), &hash, size).await?;
             }
             Err(e) => {
                 result.warnings.push(format!("Failed to copy {}: {}", rel_path, e));
@@ -106,6 +121,11 @@ pub async fn import_obsidian_vault(
     // Import markdown files
     let asset_count = asset_files.len();
     for (i, (rel_path, full_path)) in markdown_files.iter().enumerate() {
+        if result.cancelled || is_cancelled(vault, job, rel_path, asset_count + i, total_files) {
+            result.cancelled = true;
+            break;
+        }
+
         let target_path = if target_base.is_empty() {
             rel_path.clone()
         } else {
@@ -135,6 +155,15 @@ pub async fn import_obsidian_vault(
         }
     }
 
+    // Links between the imported notes resolve once they are all indexed
+    if result.notes_imported > 0 {
+        resolve_backlinks(vault.repo(), None).await?;
+    }
+
+    if let Some(job) = job.filter(|_| !result.cancelled) {
+        job.progress(vault, total_files, total_files, None);
+    }
+
     result.duration_ms = start.elapsed().as_millis() as u64;
 
     info!(
@@ -149,6 +178,20 @@ pub async fn import_obsidian_vault(
     Ok(result)
 }
 
+/// Report progress to the job before processing a file, and whether the job
+/// was cancelled.
+fn is_cancelled(vault: &Vault, job: Option<&Job>, rel_path: &str, processed: usize, total: usize) -> bool {
+    let Some(job) = job else {
+        return false;
+    };
+    if job.is_cancelled() {
+        info!("Import cancelled after {} of {} files", processed, total);
+        return true;
+    }
+    job.progress(vault, processed, total, Some(rel_path.to_string()));
+    false
+}
+
 /// Collect all files from the source directory.
 ///
 /// Returns (markdown_files, asset_files) where each is a Vec of (relative_path, absolute_path).
@@ -241,8 +284,14 @@ async fn import_markdown_file(
     // Parse frontmatter
     let (frontmatter, body) = parse_frontmatter(&content);
 
-    // Parse the body for inline tags
-    let analysis = parse(body);
+    // Parse the body for inline tags, only up to the size limit for
+    // oversized notes
+    let max_bytes = vault.index_settings().await.max_indexed_bytes;
+    let week_start = vault.locale_settings().await.week_start;
+    let indexed = indexed_part(&content, max_bytes);
+    let body_start = content.len() - body.len();
+    let mut analysis = parse_with(indexed.get(body_start..).unwrap_or_default(), week_start);
+    analysis.truncated = indexed.len() < content.len();
 
     // Merge frontmatter tags with inline tags
     let mut all_tags: HashSet<String> = HashSet::new();
@@ -259,7 +308,7 @@ async fn import_markdown_file(
 
     // Index the note
     let hash = hash_content(&content);
-    let note_id = vault.repo().index_note(target_path, &content, &hash, &analysis).await?;
+    let note_id = vault.repo().index_note(target_path, indexed, &hash, &analysis).await?;
 
     // Import frontmatter properties (excluding tags which we handle separately)
     for (key, value) in frontmatter.properties.iter() {
@@ -273,9 +322,27 @@ async fn import_markdown_file(
             // Could store aliases in a special property or skip
             // For now, store as a property
         }
+        if key_lower == "cssclasses" || key_lower == "cssclass" {
+            // Obsidian's per-note CSS classes, kept as a list under one key
+            let classes = match value {
+                PropertyValue::List(items) => items.clone(),
+                other => other
+                    .to_string_value()
+                    .map(|v| v.split([',', ' ']).filter(|c| !c.is_empty()).map(String::from).collect())
+                    .unwrap_or_default(),
+            };
+            if !classes.is_empty() {
+                vault.repo().set_list_property(note_id, CSS_CLASSES_PROPERTY, &classes).await?;
+                result.properties_imported += 1;
+            }
+            continue;
+        }
 
-        // Convert PropertyValue to string
-        if let Some(string_value) = value.to_string_value() {
+        // List items are stored as they are; other values as strings
+        if let PropertyValue::List(items) = value {
+            vault.repo().set_list_property(note_id, key, items).await?;
+            result.properties_imported += 1;
+        } else if let Some(string_value) = value.to_string_value() {
             vault.repo().set_property(
                 note_id,
                 key,
@@ -294,7 +361,7 @@ async fn import_markdown_file(
 }
 
 /// Infer the property type from the value.
-fn infer_property_type(value: &PropertyValue) -> Option<String> {
+pub(crate) fn infer_property_type(value: &PropertyValue) -> Option<String> {
     match value {
         PropertyValue::String(s) => {
             // Check if it looks like a date
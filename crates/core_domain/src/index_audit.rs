@@ -0,0 +1,201 @@
+//! Index verification: a hash audit of the whole vault.
+//!
+//! Every markdown file is hashed again and compared to the hash stored when
+//! it was indexed. Notes whose database copy is stale are listed with the
+//! likely cause, told apart by whether the file was modified after it was
+//! last indexed. Useful after restoring a vault from a backup, or when
+//! search results don't match the files; optionally only the stale notes
+//! are re-indexed, which is much faster than a full rebuild.
+
+use crate::vault::{Result, Vault, VaultEvent};
+use chrono::{DateTime, Utc};
+use core_fs::hash_content;
+use shared_types::{IndexVerificationReport, IndexWarning, StaleNote, StaleReason};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+use tracing::{info, instrument, warn};
+
+/// Indexed notes by path, as (id, hash, when last indexed).
+type IndexedNotes = HashMap<String, (i64, Option<String>, Option<DateTime<Utc>>)>;
+
+/// Check every indexed note and markdown file against the disk. With
+/// `reindex`, stale notes are re-indexed and notes of missing files removed.
+#[instrument(skip(vault))]
+pub async fn verify_index(vault: &Vault, reindex: bool) -> Result<IndexVerificationReport> {
+    let start = Instant::now();
+
+    let scan = vault.fs().scan_markdown_files().await?;
+    let mut warnings: Vec<IndexWarning> = scan
+        .warnings
+        .iter()
+        .map(|w| IndexWarning {
+            path: w.path.to_string_lossy().to_string(),
+            reason: w.kind.to_string(),
+        })
+        .collect();
+
+    let mut indexed: IndexedNotes = vault
+        .repo()
+        .list_note_hashes()
+        .await?
+        .into_iter()
+        .map(|(id, path, hash, indexed_at)| (path, (id, hash, indexed_at)))
+        .collect();
+
+    let files_checked = scan.files.len() as i64;
+    let mut up_to_date = 0;
+    let mut stale = Vec::new();
+    for file_path in &scan.files {
+        let path = file_path.to_string_lossy().to_string();
+        let content = match vault.fs().read_text(file_path).await {
+            Ok(text) => text.content,
+            Err(e) => {
+                warnings.push(IndexWarning { path, reason: e.to_string() });
+                continue;
+            }
+        };
+        let modified_at = vault.fs().modified_time(file_path).await.ok().map(DateTime::<Utc>::from);
+
+        let reason = match indexed.remove(&path) {
+            None => Some((None, None, StaleReason::NotIndexed)),
+            Some((_, Some(hash), _)) if hash == hash_content(&content) => None,
+            Some((id, _, indexed_at)) => {
+                let modified_since = match (modified_at, indexed_at) {
+                    (Some(modified_at), Some(indexed_at)) => modified_at > indexed_at,
+                    _ => true,
+                };
+                let reason = if modified_since {
+                    StaleReason::ModifiedAfterIndexing
+                } else {
+                    StaleReason::ContentMismatch
+                };
+                Some((Some(id), indexed_at, reason))
+            }
+        };
+        match reason {
+            None => up_to_date += 1,
+            Some((note_id, indexed_at, reason)) => stale.push(StaleNote {
+                path,
+                note_id,
+                reason,
+                indexed_at,
+                modified_at,
+            }),
+        }
+    }
+
+    // Indexed notes without a file
+    stale.extend(indexed.into_iter().map(|(path, (id, _, indexed_at))| StaleNote {
+        path,
+        note_id: Some(id),
+        reason: StaleReason::FileMissing,
+        indexed_at,
+        modified_at: None,
+    }));
+    stale.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let reindexed = if reindex {
+        reindex_stale(vault, &stale, &mut warnings).await
+    } else {
+        0
+    };
+
+    info!(
+        "Verified index: {} files, {} up to date, {} stale, {} re-indexed",
+        files_checked,
+        up_to_date,
+        stale.len(),
+        reindexed
+    );
+    Ok(IndexVerificationReport {
+        files_checked,
+        up_to_date,
+        stale,
+        reindexed,
+        warnings,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Re-index the stale notes and remove those of missing files. Returns how
+/// many were brought up to date; failures are added to `warnings`.
+async fn reindex_stale(vault: &Vault, stale: &[StaleNote], warnings: &mut Vec<IndexWarning>) -> i64 {
+    let mut reindexed = 0;
+    let mut updated_ids = Vec::new();
+    for note in stale {
+        let result = match note.reason {
+            StaleReason::FileMissing => vault.remove_file(&note.path).await,
+            _ => vault.index_file(Path::new(&note.path)).await.inspect(|id| updated_ids.extend(*id)),
+        };
+        match result {
+            Ok(_) => reindexed += 1,
+            Err(e) => {
+                warn!("Failed to re-index {}: {}", note.path, e);
+                warnings.push(IndexWarning {
+                    path: note.path.clone(),
+                    reason: e.to_string(),
+                });
+            }
+        }
+    }
+    if !updated_ids.is_empty() {
+        vault.emit(VaultEvent::NotesUpdated(updated_ids));
+    }
+    reindexed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[tokio::test]
+    async fn test_verify_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        for name in ["edited.md", "restored.md", "deleted.md", "current.md"] {
+            vault.write_note(name, &format!("# {}\n", name)).await.unwrap();
+        }
+
+        // Changed behind the index's back, as with the app closed
+        std::fs::write(dir.path().join("added.md"), "# Added\n").unwrap();
+        std::fs::write(dir.path().join("edited.md"), "# Edited\n\nMore\n").unwrap();
+        std::fs::remove_file(dir.path().join("deleted.md")).unwrap();
+        // A backup copy with its old timestamp
+        std::fs::write(dir.path().join("restored.md"), "# Older version\n").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(dir.path().join("restored.md"))
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+
+        let report = verify_index(&vault, false).await.unwrap();
+        assert_eq!(report.files_checked, 4);
+        assert_eq!(report.up_to_date, 1);
+        assert_eq!(report.reindexed, 0);
+        let reasons: Vec<_> = report.stale.iter().map(|n| (n.path.as_str(), n.reason)).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("added.md", StaleReason::NotIndexed),
+                ("deleted.md", StaleReason::FileMissing),
+                ("edited.md", StaleReason::ModifiedAfterIndexing),
+                ("restored.md", StaleReason::ContentMismatch),
+            ]
+        );
+        assert_eq!(report.stale[0].note_id, None);
+        assert!(report.stale[1].modified_at.is_none());
+
+        let report = verify_index(&vault, true).await.unwrap();
+        assert_eq!(report.reindexed, 4);
+        assert!(vault.repo().get_note_id_by_path("deleted.md").await.unwrap().is_none());
+        let note_id = vault.repo().get_note_id_by_path("restored.md").await.unwrap().unwrap();
+        assert_eq!(vault.repo().get_note(note_id).await.unwrap().title.as_deref(), Some("Older version"));
+
+        let report = verify_index(&vault, false).await.unwrap();
+        assert!(report.stale.is_empty());
+        assert_eq!(report.up_to_date, 4);
+    }
+}
@@ -0,0 +1,309 @@
+//! Indexing single files, shared by the vault and its file watcher so
+//! notes changed outside the app are indexed the same way as in it.
+
+use crate::changes::ChangeJournal;
+use crate::jobs::Job;
+use crate::links::absolute_target;
+use crate::vault::{rematch_moved_notes, resolve_backlinks, Result, VaultError, VaultEvent};
+use core_fs::{hash_content, FsError, VaultFs};
+use core_index::add_frontmatter_alias;
+use core_index::markdown::{parse_with, NoteAnalysis};
+use core_storage::VaultRepository;
+use shared_types::{ChangeKind, FileProblemKind, IndexCompletePayload, IndexSettings, IndexWarning, LocaleSettings, WeekStart};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info, instrument, warn};
+
+/// The parts of a vault that indexing a file needs.
+#[derive(Clone)]
+pub(crate) struct FileIndexer {
+    pub(crate) fs: VaultFs,
+    pub(crate) repo: VaultRepository,
+    pub(crate) changes: ChangeJournal,
+    pub(crate) event_tx: broadcast::Sender<VaultEvent>,
+    /// Size limit above which notes are only partly indexed.
+    pub(crate) index_settings: Arc<RwLock<IndexSettings>>,
+    /// Whether a note's previous title becomes an alias when its H1 changes.
+    pub(crate) keep_old_titles: Arc<RwLock<bool>>,
+    /// The week start resolves `^next-week` due dates.
+    pub(crate) locale_settings: Arc<RwLock<LocaleSettings>>,
+}
+
+impl FileIndexer {
+    /// Index a file, optionally leaving its full-text entry to the
+    /// background catch-up (see `VaultRepository::index_note_deferring_fts`).
+    /// With `resolve_links`, its links are resolved right away, and those of
+    /// every note if it is new or its title changed (links by name or title
+    /// may now point at it). Returns the note ID and whether the file had
+    /// changed since it was last indexed.
+    #[instrument(skip(self), fields(path = %path.display()))]
+    pub(crate) async fn index_file(&self, path: &Path, defer_fts: bool, resolve_links: bool) -> Result<(i64, bool)> {
+        let path_str = path.to_string_lossy().to_string();
+
+        // Read file content: invalid UTF-8 is replaced, binary files skipped
+        let text = match self.fs.read_text(path).await {
+            Ok(text) => text,
+            Err(FsError::Binary(_)) => {
+                // A note whose file is now binary is no longer indexed
+                self.remove_file(path).await?;
+                self.report_file_problem(&path_str, FileProblemKind::Binary).await?;
+                return Err(FsError::Binary(path.to_path_buf()).into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if text.lossy {
+            self.report_file_problem(&path_str, FileProblemKind::InvalidUtf8).await?;
+        } else {
+            self.repo.clear_file_problem(&path_str).await?;
+        }
+        let mut content = text.content;
+        let mut hash = hash_content(&content);
+
+        // Check if file changed
+        let existing_hash = self.repo.get_note_hash(&path_str).await?;
+        if existing_hash.as_ref() == Some(&hash) {
+            debug!("File unchanged: {}", path_str);
+            let existing_note = self.repo.get_note_by_path(&path_str).await?;
+            return Ok((existing_note.id, false));
+        }
+
+        // Oversized notes are only parsed and searched up to the size limit
+        let max_bytes = self.index_settings.read().await.max_indexed_bytes;
+        let week_start = self.locale_settings.read().await.week_start;
+        let mut analysis = analyze(indexed_part(&content, max_bytes), &path_str, week_start);
+
+        // The title before this change
+        let old_title = match existing_hash {
+            Some(_) => self.repo.get_note_by_path(&path_str).await?.title,
+            None => None,
+        };
+        let title_changed = existing_hash.is_none() || old_title != analysis.title;
+
+        // Keep the old title in the note's aliases, so links to it keep
+        // resolving. A note read with invalid bytes replaced is never
+        // written back.
+        if let Some(old_title) = old_title.filter(|_| !text.lossy) {
+            let renamed = analysis
+                .title
+                .as_ref()
+                .is_some_and(|title| !title.eq_ignore_ascii_case(&old_title));
+            // Read-only notes (external folders) are indexed as they are
+            if renamed && self.fs.check_writable(path).is_ok() && *self.keep_old_titles.read().await {
+                if let Some(updated) = add_frontmatter_alias(&content, &old_title) {
+                    self.fs.write_file(path, &updated).await?;
+                    debug!("Kept old title of {} as an alias: {}", path_str, old_title);
+                    content = updated;
+                    hash = hash_content(&content);
+                    analysis = analyze(indexed_part(&content, max_bytes), &path_str, week_start);
+                }
+            }
+        }
+
+        let indexed = indexed_part(&content, max_bytes);
+        analysis.truncated = indexed.len() < content.len();
+        if analysis.truncated {
+            info!("{} is over {} bytes, indexing only its start", path_str, max_bytes);
+        }
+
+        // Index to database
+        let note_id = if defer_fts {
+            self.repo.index_note_deferring_fts(&path_str, &hash, &analysis).await?
+        } else {
+            self.repo.index_note(&path_str, indexed, &hash, &analysis).await?
+        };
+
+        if resolve_links {
+            let note_ids = [note_id];
+            resolve_backlinks(&self.repo, (!title_changed).then_some(&note_ids[..])).await?;
+        }
+
+        let kind = if existing_hash.is_some() { ChangeKind::Modified } else { ChangeKind::Created };
+        self.changes.record(kind, &path_str, None, Some(&hash)).await;
+
+        debug!("Indexed file: {} (id={})", path_str, note_id);
+        Ok((note_id, true))
+    }
+
+    /// Index the notes in folders (the whole vault for an empty path) and
+    /// remove the notes that no longer exist in them. Notes moved between
+    /// the folders keep their ID. Returns the result and the IDs of the
+    /// notes that changed.
+    pub(crate) async fn index_tree(&self, folders: &[PathBuf], job: Option<&Job>) -> Result<(IndexCompletePayload, Vec<i64>)> {
+        let start = Instant::now();
+
+        // Build a set of file paths that exist on disk. Files that can't be
+        // indexed are reported instead of skipped silently.
+        let mut files = Vec::new();
+        let mut existing_paths: HashSet<String> = HashSet::new();
+        let mut warnings: Vec<IndexWarning> = Vec::new();
+        for folder in folders {
+            let scan = self.fs.scan_markdown_files_in(folder).await?;
+            for file_path in scan.files {
+                if existing_paths.insert(file_path.to_string_lossy().to_string()) {
+                    files.push(file_path);
+                }
+            }
+            warnings.extend(scan.warnings.iter().map(|w| IndexWarning {
+                path: w.path.to_string_lossy().to_string(),
+                reason: w.kind.to_string(),
+            }));
+        }
+        info!("Found {} markdown files", files.len());
+
+        // Notes in the database that no longer exist on disk, and files on
+        // disk that aren't in the database yet
+        let db_notes = self.repo.list_notes().await?;
+        let db_paths: HashSet<&str> = db_notes.iter().map(|n| n.path.as_str()).collect();
+        let new_files: Vec<String> = existing_paths.iter().filter(|p| !db_paths.contains(p.as_str())).cloned().collect();
+        let mut orphans: Vec<String> = db_notes
+            .iter()
+            .filter(|n| folders.iter().any(|folder| Path::new(&n.path).starts_with(folder)))
+            .filter(|n| !existing_paths.contains(&n.path))
+            .map(|n| n.path.clone())
+            .collect();
+
+        // Files moved outside the app keep their note (and its schedule
+        // blocks, habits and other rows referencing the note id)
+        let moved_ids = rematch_moved_notes(&self.repo, &self.fs, &self.changes, &mut orphans, &new_files).await;
+
+        // Cleanup: Remove notes from database that no longer exist on disk
+        let mut deleted_ids = Vec::new();
+        for path in orphans {
+            info!("Removing orphaned note from database: {}", path);
+            if let Ok(Some(id)) = self.repo.delete_note(&path).await {
+                self.changes.record(ChangeKind::Deleted, &path, None, None).await;
+                deleted_ids.push(id);
+            }
+        }
+
+        let notes_removed = !deleted_ids.is_empty() || !moved_ids.is_empty();
+        if !deleted_ids.is_empty() {
+            info!("Removed {} orphaned notes from database", deleted_ids.len());
+            self.emit(VaultEvent::NotesDeleted(deleted_ids));
+        }
+
+        // Index existing files
+        let mut indexed_count = 0;
+        let mut updated_ids = moved_ids;
+        let mut cancelled = false;
+
+        let total = files.len();
+        for (i, file_path) in files.into_iter().enumerate() {
+            if let Some(job) = job {
+                if job.is_cancelled() {
+                    info!("Full index cancelled after {} of {} files", i, total);
+                    cancelled = true;
+                    break;
+                }
+                job.progress_to(|event| self.emit(event), i, total, Some(file_path.to_string_lossy().to_string()));
+            }
+
+            match self.index_file(&file_path, false, false).await {
+                Ok((id, _)) => {
+                    updated_ids.push(id);
+                    indexed_count += 1;
+                }
+                Err(e @ VaultError::Fs(FsError::Binary(_))) => {
+                    // Already logged when first found
+                    warnings.push(IndexWarning {
+                        path: file_path.to_string_lossy().to_string(),
+                        reason: e.to_string(),
+                    });
+                }
+                Err(e) => {
+                    warn!("Failed to index {}: {}", file_path.display(), e);
+                    warnings.push(IndexWarning {
+                        path: file_path.to_string_lossy().to_string(),
+                        reason: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        // Links are resolved once every note is in, so links to notes
+        // indexed later in the run resolve too
+        if indexed_count > 0 || notes_removed {
+            resolve_backlinks(&self.repo, None).await?;
+        }
+
+        let duration = start.elapsed();
+        info!(
+            "Index complete: {} notes in {:?}",
+            indexed_count, duration
+        );
+
+        if let Some(job) = job.filter(|_| !cancelled) {
+            job.progress_to(|event| self.emit(event), total, total, None);
+        }
+
+        let payload = IndexCompletePayload {
+            notes_indexed: indexed_count,
+            duration_ms: duration.as_millis() as u64,
+            warnings,
+            cancelled,
+        };
+        Ok((payload, updated_ids))
+    }
+
+    /// Send a vault event, if anyone listens.
+    fn emit(&self, event: VaultEvent) {
+        let _ = self.event_tx.send(event);
+    }
+
+    /// Remove a file from the index.
+    #[instrument(skip(self), fields(path = %path.display()))]
+    pub(crate) async fn remove_file(&self, path: &Path) -> Result<Option<i64>> {
+        let path_str = path.to_string_lossy().to_string();
+        let deleted_id = self.repo.delete_note(&path_str).await?;
+        self.repo.clear_file_problem(&path_str).await?;
+
+        if let Some(id) = deleted_id {
+            resolve_backlinks(&self.repo, None).await?;
+            self.changes.record(ChangeKind::Deleted, &path_str, None, None).await;
+            let _ = self.event_tx.send(VaultEvent::NotesDeleted(vec![id]));
+        }
+
+        Ok(deleted_id)
+    }
+
+    /// Record a problem reading a file, logging it the first time only so
+    /// every index run doesn't repeat it.
+    async fn report_file_problem(&self, path: &str, kind: FileProblemKind) -> Result<()> {
+        if self.repo.record_file_problem(path, kind).await? {
+            match kind {
+                FileProblemKind::InvalidUtf8 => warn!("{} is not valid UTF-8, indexing it with the invalid bytes replaced", path),
+                FileProblemKind::Binary => warn!("{} is a binary file, skipping it", path),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a note, with relative links as vault paths.
+fn analyze(content: &str, path: &str, week_start: WeekStart) -> NoteAnalysis {
+    let mut analysis = parse_with(content, week_start);
+    for link in &mut analysis.links {
+        if let std::borrow::Cow::Owned(target) = absolute_target(link, Some(path)) {
+            *link = target;
+        }
+    }
+    analysis
+}
+
+/// The part of a note indexed for full-text search: all of it, or for notes
+/// over `max_bytes` the whole lines that fit.
+pub(crate) fn indexed_part(content: &str, max_bytes: u64) -> &str {
+    let max_bytes = usize::try_from(max_bytes).unwrap_or(usize::MAX);
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let mut end = max_bytes;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    let start = &content[..end];
+    start.rfind('\n').map_or(start, |i| &start[..i])
+}
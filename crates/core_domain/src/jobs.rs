@@ -0,0 +1,247 @@
+//! Long-running jobs that can be cancelled.
+//!
+//! Operations like the full index or a vault import take an optional `Job`.
+//! They report progress through it (as `VaultEvent::JobProgress`) and check
+//! `is_cancelled` between items, stopping early and keeping what was done
+//! so far. Cancellation is cooperative: an item in progress is finished.
+//!
+//! Jobs are queued in the vault database (see the `jobs` repository); the
+//! registry only tracks the ones currently running so they can be cancelled.
+//! Job IDs come from the vault database, so running jobs are registered
+//! under the root of their vault as well.
+
+use crate::vault::{Vault, VaultEvent};
+use shared_types::{JobKind, JobProgressPayload};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Handle to a running job, shared between the job and whoever may cancel it.
+#[derive(Debug, Clone)]
+pub struct Job {
+    id: i64,
+    kind: JobKind,
+    vault: PathBuf,
+    state: Arc<JobState>,
+}
+
+#[derive(Debug, Default)]
+struct JobState {
+    cancelled: AtomicBool,
+    processed: AtomicI64,
+    total: AtomicI64,
+}
+
+impl Job {
+    fn new(vault: &Path, id: i64, kind: JobKind) -> Self {
+        Self {
+            id,
+            kind,
+            vault: vault.to_path_buf(),
+            state: Arc::default(),
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn kind(&self) -> JobKind {
+        self.kind
+    }
+
+    /// Root of the vault the job runs in.
+    pub fn vault(&self) -> &Path {
+        &self.vault
+    }
+
+    /// Ask the job to stop at the next item.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Whether the job was cancelled before it got through its items,
+    /// including before it counted them.
+    pub fn stopped_early(&self) -> bool {
+        let (processed, total) = self.counts();
+        self.is_cancelled() && (total == 0 || processed < total)
+    }
+
+    /// Items processed so far and the total, as last reported.
+    pub fn counts(&self) -> (i64, i64) {
+        (
+            self.state.processed.load(Ordering::Relaxed),
+            self.state.total.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Record progress and emit it as a vault event.
+    pub fn progress(&self, vault: &Vault, processed: usize, total: usize, message: Option<String>) {
+        self.progress_to(|event| vault.emit(event), processed, total, message);
+    }
+
+    /// Record progress and pass the event to `emit`, for code that has the
+    /// vault's parts rather than the vault.
+    pub(crate) fn progress_to(&self, emit: impl FnOnce(VaultEvent), processed: usize, total: usize, message: Option<String>) {
+        self.state.processed.store(processed as i64, Ordering::Relaxed);
+        self.state.total.store(total as i64, Ordering::Relaxed);
+        emit(VaultEvent::JobProgress(JobProgressPayload {
+            job_id: self.id,
+            kind: self.kind,
+            processed: processed as i64,
+            total: total as i64,
+            message,
+        }));
+    }
+}
+
+/// Running jobs by vault root and ID.
+#[derive(Debug, Default)]
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<(PathBuf, i64), Job>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a job that is starting, by its vault and database ID.
+    pub fn start(&self, vault: &Path, id: i64, kind: JobKind) -> Job {
+        let job = Job::new(vault, id, kind);
+        self.jobs.lock().unwrap().insert((job.vault.clone(), id), job.clone());
+        info!("Started job {} ({:?})", job.id, kind);
+        job
+    }
+
+    /// Cancel a running job of a vault. Returns false if no job has this ID.
+    pub fn cancel(&self, vault: &Path, job_id: i64) -> bool {
+        match self.jobs.lock().unwrap().get(&(vault.to_path_buf(), job_id)) {
+            Some(job) => {
+                info!("Cancelling job {}", job_id);
+                job.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel all running jobs of a vault, e.g. because it is being closed.
+    /// Returns how many were running.
+    pub fn cancel_vault(&self, vault: &Path) -> usize {
+        let jobs = self.jobs.lock().unwrap();
+        let running: Vec<&Job> = jobs.values().filter(|job| job.vault == vault).collect();
+        for job in &running {
+            info!("Cancelling job {}", job.id);
+            job.cancel();
+        }
+        running.len()
+    }
+
+    /// Forget the jobs of a vault that was closed or replaced. Jobs still
+    /// stopping stay cancelled; they are run again when the vault is next
+    /// opened.
+    pub fn clear_vault(&self, vault: &Path) {
+        self.jobs.lock().unwrap().retain(|(root, _), job| {
+            if root == vault {
+                job.cancel();
+            }
+            root != vault
+        });
+    }
+
+    /// Remove a job that has ended.
+    pub fn finish(&self, job: &Job) {
+        self.jobs.lock().unwrap().remove(&(job.vault.clone(), job.id));
+    }
+
+    /// IDs and kinds of the running jobs of a vault.
+    pub fn running(&self, vault: &Path) -> Vec<(i64, JobKind)> {
+        let mut jobs: Vec<_> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|job| job.vault == vault)
+            .map(|job| (job.id, job.kind))
+            .collect();
+        jobs.sort_by_key(|job| job.0);
+        jobs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_job() {
+        let registry = JobRegistry::new();
+        let vault = Path::new("/vaults/work");
+        let index = registry.start(vault, 1, JobKind::FullIndex);
+        let import = registry.start(vault, 2, JobKind::VaultImport);
+        // Another vault numbers its jobs from 1 as well
+        let other = registry.start(Path::new("/vaults/home"), 1, JobKind::FullIndex);
+
+        assert!(registry.cancel(vault, index.id()));
+        assert!(index.is_cancelled());
+        assert!(!import.is_cancelled());
+        assert!(!other.is_cancelled());
+
+        // Cancelled before it counted its items
+        assert!(index.stopped_early());
+        registry.finish(&index);
+        assert!(!registry.cancel(vault, index.id()));
+        assert_eq!(registry.running(vault), vec![(2, JobKind::VaultImport)]);
+
+        assert_eq!(registry.cancel_vault(vault), 1);
+        assert!(import.is_cancelled());
+        assert!(!other.is_cancelled());
+
+        registry.clear_vault(vault);
+        assert!(registry.running(vault).is_empty());
+        assert_eq!(registry.running(Path::new("/vaults/home")), vec![(1, JobKind::FullIndex)]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_full_index_keeps_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.md", "b.md", "c.md"] {
+            std::fs::write(dir.path().join(name), "# Note\n").unwrap();
+        }
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let mut events = vault.subscribe();
+
+        let registry = JobRegistry::new();
+        let job = registry.start(dir.path(), 1, JobKind::FullIndex);
+        registry.cancel(dir.path(), job.id());
+
+        let payload = vault.full_index_job(Some(&job)).await.unwrap();
+        assert!(payload.cancelled);
+        assert_eq!(payload.notes_indexed, 0);
+        assert_eq!(job.counts(), (0, 0));
+        assert!(job.stopped_early());
+
+        let job = registry.start(dir.path(), 2, JobKind::FullIndex);
+        let payload = vault.full_index_job(Some(&job)).await.unwrap();
+        assert!(!payload.cancelled);
+        assert_eq!(payload.notes_indexed, 3);
+        assert_eq!(job.counts(), (3, 3));
+        assert!(!job.stopped_early());
+
+        let mut progress = 0;
+        while let Ok(event) = events.try_recv() {
+            if let VaultEvent::JobProgress(p) = event {
+                assert_eq!(p.job_id, job.id());
+                progress += 1;
+            }
+        }
+        assert_eq!(progress, 4);
+    }
+}
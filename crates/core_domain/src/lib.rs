@@ -2,18 +2,111 @@
 //!
 //! This crate contains:
 //! - Vault management (open, close, index)
+//! - New vault scaffolding
+//! - Demo vault from bundled fixtures
 //! - File watching and reindexing
+//! - Index verification (hash audit)
+//! - Change journal for external tools
+//! - Drafts of unsaved edits for crash recovery
+//! - Advisory editing sessions for notes open in several windows
 //! - Todo operations (toggle, sync to file)
 //! - Schedule block operations
-//! - Daily note creation
+//! - Plain-text daily agenda for printing
+//! - Daily, weekly and monthly note creation and navigation
+//! - Notes created from templates on a schedule
 //! - Obsidian vault import
+//! - Cancellable long-running jobs
+//! - Command latency and slow query metrics
+//! - Diagnostics bundles for bug reports
+//! - Habit data import from CSV
+//! - CSV files to markdown tables and back
+//! - Per-note encryption
+//! - Quick switcher index
+//! - Note resolution by score (links, embeds, quick switcher)
+//! - In-memory autocomplete index
+//! - Importing external files and attachments
+//! - Attachment deduplication by content hash
+//! - Image thumbnail cache
+//! - Photo import by EXIF date
+//! - Embed resolution with loop detection
+//! - Note rendering to HTML
+//! - Diagram rendering to SVG
+//! - Slide decks from notes
+//! - Spell checking with Hunspell dictionaries
+//! - Duplicate note detection
+//! - Note creation with safe file names
+//! - Note types with type-specific templates and properties
+//! - Note linting
+//! - Batch property edits with frontmatter sync
+//! - Queries that roll up tasks from embedded notes
+//! - Query embed result cache
+//! - Title and filename consistency
+//! - Moving notes without breaking links
+//! - Book metadata from OpenLibrary and reading shelves
+//! - Link formatting (shortest, absolute or relative)
+//! - Link suggestions for the paragraph being written
+//! - Link graph export to DOT, GEXF and JSON
+//! - Per-device app settings
+//! - Vault backups and text recognition in images as background jobs
+//! - Synthetic test vaults (`test-vault` feature)
 
+pub mod agenda;
+pub mod app_settings;
+pub mod assets;
+pub mod attachments;
+pub mod autocomplete;
+pub mod backup;
+pub mod books;
+pub mod changes;
+pub mod csv_tables;
+pub mod demo_vault;
+pub mod diagnostics;
+pub mod diagrams;
+pub mod drafts;
+pub mod duplicates;
+pub mod edit_sessions;
+pub mod embeds;
+pub mod encryption;
+pub mod graph_export;
+pub mod habit_import;
 pub mod importer;
+pub mod index_audit;
+mod indexer;
+pub mod jobs;
+pub mod link_suggestions;
+mod links;
+pub mod lint;
+pub mod moves;
+pub mod note_types;
+pub mod notes;
+pub mod ocr;
+pub mod perf;
+pub mod periodic;
+pub mod photos;
+pub mod properties;
+pub mod queries;
+pub mod query_cache;
+pub mod quick_switch;
+pub mod resolve;
+pub mod rendering;
+pub mod scaffold;
+pub mod scheduled_notes;
+pub mod slides;
+pub mod spellcheck;
 pub mod templates;
+#[cfg(feature = "test-vault")]
+pub mod test_vault;
+pub mod thumbnails;
+pub mod titles;
 pub mod todos;
 pub mod vault;
 pub mod watcher;
 
+pub use attachments::import_files;
+pub use habit_import::import_habit_csv;
 pub use importer::import_obsidian_vault;
+pub use jobs::{Job, JobRegistry};
+#[cfg(feature = "test-vault")]
+pub use test_vault::{generate_test_vault, TestVaultSpec, TestVaultSummary};
 pub use vault::Vault;
 pub use watcher::FileWatcher;
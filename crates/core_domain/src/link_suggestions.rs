@@ -0,0 +1,200 @@
+//! Link suggestions for the `[[` autocomplete.
+//!
+//! Given the paragraph being written, notes are ranked by how well their
+//! title or an alias fuzzy-matches the link being typed (the text after an
+//! unclosed `[[`), whether the paragraph mentions their title, the tags
+//! they share with the paragraph, and the embedding similarity of the note
+//! to the paragraph when the caller has one.
+
+use crate::quick_switch::fuzzy_score;
+use crate::vault::{Result, Vault};
+use core_index::markdown::parse;
+use shared_types::{LinkSuggestion, NoteListItem};
+use std::collections::{HashMap, HashSet};
+use tracing::instrument;
+
+/// Bonus for a note whose title or alias appears in the paragraph.
+const MENTION_BONUS: f64 = 30.0;
+
+/// Bonus per tag shared with the paragraph.
+const SHARED_TAG_BONUS: f64 = 10.0;
+
+/// Weight of the embedding similarity (0..1).
+const SIMILARITY_WEIGHT: f64 = 50.0;
+
+/// Titles shorter than this aren't looked for in the paragraph.
+const MIN_MENTION_LENGTH: usize = 3;
+
+/// The text of the link being typed: what follows the last `[[` that isn't
+/// closed, up to a `|` or `#`. None if there is no open link.
+pub fn open_link_query(context: &str) -> Option<&str> {
+    let start = context.rfind("[[")? + 2;
+    let rest = &context[start..];
+    if rest.contains("]]") {
+        return None;
+    }
+    Some(rest.split(['|', '#']).next().unwrap_or(rest).trim())
+}
+
+/// Rank notes as link targets for the paragraph `context`, best first.
+///
+/// `similar` holds (note_id, similarity) pairs from a vector search for the
+/// paragraph; pass an empty slice without embeddings. While a link is being
+/// typed only notes whose title, alias or path matches it are suggested.
+/// `exclude` is left out, e.g. the note being edited.
+#[instrument(skip(vault, context, similar))]
+pub async fn suggest_links(
+    vault: &Vault,
+    context: &str,
+    similar: &[(i64, f64)],
+    exclude: Option<i64>,
+    limit: usize,
+) -> Result<Vec<LinkSuggestion>> {
+    let repo = vault.repo();
+    let notes = repo.list_notes().await?;
+    let mut aliases: HashMap<i64, Vec<String>> = HashMap::new();
+    for (note_id, alias) in repo.list_all_aliases().await? {
+        aliases.entry(note_id).or_default().push(alias);
+    }
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for (note_id, tag) in repo.list_all_note_tags().await? {
+        tags.entry(note_id).or_default().push(tag);
+    }
+
+    Ok(rank(context, notes, &aliases, &tags, similar, exclude, limit))
+}
+
+fn rank(
+    context: &str,
+    notes: Vec<NoteListItem>,
+    aliases: &HashMap<i64, Vec<String>>,
+    tags: &HashMap<i64, Vec<String>>,
+    similar: &[(i64, f64)],
+    exclude: Option<i64>,
+    limit: usize,
+) -> Vec<LinkSuggestion> {
+    let query = open_link_query(context).filter(|q| !q.is_empty());
+    // The open link itself shouldn't count as a mention
+    let prose = match context.rfind("[[") {
+        Some(i) if query.is_some() => &context[..i],
+        _ => context,
+    }
+    .to_lowercase();
+    let context_tags: HashSet<String> = parse(context).tags.iter().map(|t| t.to_lowercase()).collect();
+    let similarity: HashMap<i64, f64> = similar.iter().copied().collect();
+
+    let mut suggestions: Vec<LinkSuggestion> = notes
+        .into_iter()
+        .filter(|note| Some(note.id) != exclude)
+        .filter_map(|note| {
+            let path = note.path.strip_suffix(".md").unwrap_or(&note.path);
+            let names: Vec<&str> = note
+                .title
+                .as_deref()
+                .into_iter()
+                .chain(aliases.get(&note.id).into_iter().flatten().map(String::as_str))
+                .collect();
+
+            let mut score = 0.0;
+            let mut matched_text = None;
+            if let Some(query) = query {
+                let (best, text) = names
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(path))
+                    .filter_map(|text| fuzzy_score(query, text).map(|s| (s, text)))
+                    .max_by_key(|(s, _)| *s)?;
+                score += best as f64;
+                matched_text = Some(text.to_string());
+            }
+
+            if names
+                .iter()
+                .any(|name| name.chars().count() >= MIN_MENTION_LENGTH && prose.contains(&name.to_lowercase()))
+            {
+                score += MENTION_BONUS;
+            }
+
+            let shared_tags: Vec<String> = tags
+                .get(&note.id)
+                .into_iter()
+                .flatten()
+                .filter(|tag| context_tags.contains(&tag.to_lowercase()))
+                .cloned()
+                .collect();
+            score += SHARED_TAG_BONUS * shared_tags.len() as f64;
+
+            let similarity = similarity.get(&note.id).copied();
+            score += SIMILARITY_WEIGHT * similarity.unwrap_or(0.0);
+
+            (query.is_some() || score > 0.0).then_some(LinkSuggestion {
+                note,
+                score,
+                matched_text,
+                shared_tags,
+                similarity,
+            })
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.note.path.cmp(&b.note.path))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: i64, path: &str, title: &str) -> NoteListItem {
+        NoteListItem {
+            id,
+            path: path.to_string(),
+            title: Some(title.to_string()),
+            pinned: false,
+        }
+    }
+
+    fn ranked(context: &str, similar: &[(i64, f64)]) -> Vec<String> {
+        let notes = vec![
+            note(1, "projects/roadmap.md", "Product Roadmap"),
+            note(2, "people/ada.md", "Ada Lovelace"),
+            note(3, "projects/release.md", "Release Plan"),
+            note(4, "inbox/misc.md", "Misc"),
+        ];
+        let aliases = HashMap::from([(2, vec!["Countess".to_string()])]);
+        let tags = HashMap::from([(3, vec!["work".to_string()]), (4, vec!["work".to_string()])]);
+        rank(context, notes, &aliases, &tags, similar, Some(4), 10)
+            .into_iter()
+            .map(|s| s.note.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_open_link_query() {
+        assert_eq!(open_link_query("See [[Road"), Some("Road"));
+        assert_eq!(open_link_query("See [[Road#Goals"), Some("Road"));
+        assert_eq!(open_link_query("See [[Roadmap]] and"), None);
+        assert_eq!(open_link_query("No link"), None);
+    }
+
+    #[test]
+    fn test_rank_suggestions() {
+        // Typing a link: only matching notes, aliases included
+        assert_eq!(ranked("Met with [[count", &[]), vec!["people/ada.md"]);
+        assert_eq!(ranked("Planning [[r", &[]).first().map(String::as_str), Some("projects/release.md"));
+
+        // Without an open link: mentions, shared tags and similarity; the
+        // excluded note is left out
+        assert_eq!(
+            ranked("The product roadmap is due #work", &[(2, 0.4)]),
+            vec!["projects/roadmap.md", "people/ada.md", "projects/release.md"]
+        );
+        assert!(ranked("Nothing related", &[]).is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+//! Writing wiki links in the vault's link format.
+//!
+//! The link format (see `LinkSettings`) decides how the app writes link
+//! targets, e.g. when a rename or move updates the links to a note:
+//!
+//! - Shortest: the note's name (`[[Plan]]`) if that resolves to the note
+//!   from the linking note, else its path (`[[projects/Plan]]`).
+//! - Absolute: always the vault-relative path (`[[projects/Plan]]`).
+//! - Relative: the path from the linking note's folder (`[[../projects/Plan]]`,
+//!   `[[./Plan]]`).
+//!
+//! Links starting with `./` or `../` are resolved against the linking note's
+//! folder whatever the setting, so changing it never breaks existing links.
+
+use crate::resolve::NoteResolver;
+use crate::vault::parent_folder;
+use core_index::markdown::map_wiki_links;
+use shared_types::{LinkFormat, NoteListItem};
+use std::borrow::Cow;
+
+/// The target to write in a link from the note at `from` to `note`, one of
+/// the notes of `resolver`.
+pub(crate) fn link_target(resolver: &NoteResolver, note: &NoteListItem, from: &str, format: LinkFormat) -> String {
+    let path = note.path.strip_suffix(".md").unwrap_or(&note.path);
+    match format {
+        LinkFormat::Shortest => {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            if resolver.resolve(name, Some(from)).is_some_and(|n| n.id == note.id) {
+                name.to_string()
+            } else {
+                path.to_string()
+            }
+        }
+        LinkFormat::Absolute => path.to_string(),
+        LinkFormat::Relative => {
+            let relative = relative_path(parent_folder(from), path);
+            if relative.starts_with("../") {
+                relative
+            } else {
+                format!("./{}", relative)
+            }
+        }
+    }
+}
+
+/// Rewrite the wiki links in `content` that resolve to a different note in
+/// `after` than in `before`, so they point at the same note as before.
+/// `from` is the path of the note with the links, before and after.
+pub(crate) fn relink(
+    content: &str,
+    before: &NoteResolver,
+    after: &NoteResolver,
+    from: (&str, &str),
+    format: LinkFormat,
+) -> String {
+    map_wiki_links(content, |target| {
+        let was = before.resolve(target, Some(from.0))?;
+        if after.resolve(target, Some(from.1)).is_some_and(|n| n.id == was.id) {
+            return None;
+        }
+        let note = after.notes().iter().find(|n| n.id == was.id)?;
+        Some(link_target(after, note, from.1, format))
+    })
+}
+
+/// A link target starting with `./` or `../` as a vault-relative path,
+/// resolved against the folder of the note at `from`. Other targets, and
+/// relative ones leaving the vault, are returned unchanged.
+pub(crate) fn absolute_target<'a>(target: &'a str, from: Option<&str>) -> Cow<'a, str> {
+    if !target.starts_with("./") && !target.starts_with("../") {
+        return Cow::Borrowed(target);
+    }
+
+    let mut segments: Vec<&str> = from
+        .map(parent_folder)
+        .unwrap_or("")
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if segments.pop().is_none() {
+                    return Cow::Borrowed(target);
+                }
+            }
+            _ => segments.push(segment),
+        }
+    }
+    Cow::Owned(segments.join("/"))
+}
+
+/// The path from the folder `dir` to the vault-relative `path`, using `..`
+/// to go up (without a leading `./`).
+pub(crate) fn relative_path(dir: &str, path: &str) -> String {
+    let dir: Vec<&str> = dir.split('/').filter(|s| !s.is_empty()).collect();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let common = dir
+        .iter()
+        .zip(&segments[..segments.len().saturating_sub(1)])
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts = vec![".."; dir.len() - common];
+    parts.extend(&segments[common..]);
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: i64, path: &str) -> NoteListItem {
+        NoteListItem {
+            id,
+            path: path.to_string(),
+            title: None,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_link_target_formats() {
+        let notes = vec![note(1, "archive/Plan.md"), note(2, "projects/Plan.md"), note(3, "projects/sub/Ideas.md")];
+
+        let resolver = NoteResolver::new(notes.as_slice());
+        let target = |id: usize, from: &str, format| link_target(&resolver, &notes[id - 1], from, format);
+        assert_eq!(target(1, "home.md", LinkFormat::Shortest), "Plan");
+        assert_eq!(target(2, "home.md", LinkFormat::Shortest), "projects/Plan");
+        // [[Plan]] goes to the Plan closest to the linking note: from inside
+        // projects/ that is projects/Plan, so the other one needs its folder
+        assert_eq!(target(2, "projects/sub/Ideas.md", LinkFormat::Shortest), "Plan");
+        assert_eq!(target(1, "projects/sub/Ideas.md", LinkFormat::Shortest), "archive/Plan");
+        assert_eq!(target(3, "home.md", LinkFormat::Absolute), "projects/sub/Ideas");
+        assert_eq!(target(2, "projects/sub/Ideas.md", LinkFormat::Relative), "../Plan");
+        assert_eq!(target(3, "projects/Plan.md", LinkFormat::Relative), "./sub/Ideas");
+        assert_eq!(target(1, "projects/sub/Ideas.md", LinkFormat::Relative), "../../archive/Plan");
+    }
+
+    #[test]
+    fn test_relative_targets_resolve() {
+        assert_eq!(absolute_target("../Plan", Some("projects/sub/Ideas.md")), "projects/Plan");
+        assert_eq!(absolute_target("./sub/Ideas", Some("projects/Plan.md")), "projects/sub/Ideas");
+        assert_eq!(absolute_target("../Plan", Some("home.md")), "../Plan");
+        assert_eq!(absolute_target("Plan", Some("projects/Plan.md")), "Plan");
+
+        let notes = vec![note(1, "archive/Plan.md"), note(2, "projects/Plan.md")];
+        let resolver = NoteResolver::new(notes);
+        assert_eq!(resolver.resolve("../projects/Plan", Some("archive/x.md")).map(|n| n.id), Some(2));
+    }
+
+    #[test]
+    fn test_relink_after_move() {
+        let before = NoteResolver::new(vec![note(1, "x/Plan.md"), note(2, "y/Ideas.md"), note(3, "y/Plan.md")]);
+        let after = NoteResolver::new(vec![note(1, "x/Plan.md"), note(3, "y/Plan.md"), note(2, "z/Ideas.md")]);
+
+        // Links in the moved note keep pointing at the same notes
+        let moved = relink("[[Plan]] [[./Plan|plan]]", &before, &after, ("y/Ideas.md", "z/Ideas.md"), LinkFormat::Relative);
+        assert_eq!(moved, "[[../y/Plan]] [[../y/Plan|plan]]");
+
+        let moved = relink("[[Plan]]", &before, &after, ("y/Ideas.md", "z/Ideas.md"), LinkFormat::Shortest);
+        assert_eq!(moved, "[[y/Plan]]");
+
+        // Links to it from other notes
+        let other = relink("[[Ideas]] [[y/Ideas#Top]]", &before, &after, ("x/Plan.md", "x/Plan.md"), LinkFormat::Absolute);
+        assert_eq!(other, "[[Ideas]] [[z/Ideas#Top]]");
+    }
+}
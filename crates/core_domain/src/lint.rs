@@ -0,0 +1,191 @@
+//! Note linting against the vault.
+//!
+//! The rules live in `core_index::lint`; this module resolves wikilinks to
+//! find broken ones, picks the required properties for each note's folder,
+//! checks task contexts against the context registry, and writes fixed
+//! notes back. Encrypted notes that are still locked are
+//! skipped.
+
+use crate::encryption::has_encrypted_body;
+use crate::vault::{Result, Vault};
+use core_index::{collect_wikilinks, fix_content, lint_content, required_keys, strip_frontmatter};
+use shared_types::{LintFixResult, LintSettings, NoteLintResult};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info, instrument};
+
+/// Lint a single note.
+#[instrument(skip(vault, settings))]
+pub async fn lint_note(vault: &Vault, note_id: i64, settings: &LintSettings) -> Result<NoteLintResult> {
+    let note = vault.repo().get_note(note_id).await?;
+    let content = vault.read_note(&note.path).await?;
+
+    let contexts = registered_contexts(vault).await?;
+    let mut resolved = HashMap::new();
+    Ok(lint(vault, note_id, note.path, &content, settings, &contexts, &mut resolved).await)
+}
+
+/// Lint every note; only notes with diagnostics are returned.
+#[instrument(skip(vault, settings))]
+pub async fn lint_vault(vault: &Vault, settings: &LintSettings) -> Result<Vec<NoteLintResult>> {
+    let notes = vault.list_notes().await?;
+    let note_count = notes.len();
+    let contexts = registered_contexts(vault).await?;
+
+    // Link targets are shared between notes, so each is resolved once
+    let mut resolved = HashMap::new();
+    let mut results = Vec::new();
+    for note in notes {
+        let content = match vault.read_note(&note.path).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Skipping {}: {}", note.path, e);
+                continue;
+            }
+        };
+
+        let result = lint(vault, note.id, note.path, &content, settings, &contexts, &mut resolved).await;
+        if !result.diagnostics.is_empty() {
+            results.push(result);
+        }
+    }
+
+    info!("Linted {} notes, {} with diagnostics", note_count, results.len());
+    Ok(results)
+}
+
+/// Apply the fixes of the enabled fixable rules to the given notes, or to
+/// every note if `note_ids` is None. A note that can't be found, read or
+/// written is reported in the result's errors and the rest are still fixed.
+#[instrument(skip(vault, settings))]
+pub async fn apply_lint_fixes(
+    vault: &Vault,
+    note_ids: Option<&[i64]>,
+    settings: &LintSettings,
+) -> Result<LintFixResult> {
+    let mut result = LintFixResult {
+        notes_changed: 0,
+        fixes_applied: 0,
+        errors: Vec::new(),
+    };
+
+    let paths: Vec<String> = match note_ids {
+        Some(ids) => {
+            let mut paths = Vec::with_capacity(ids.len());
+            for &id in ids {
+                match vault.repo().get_note(id).await {
+                    Ok(note) => paths.push(note.path),
+                    Err(e) => result.errors.push(format!("Note {}: {}", id, e)),
+                }
+            }
+            paths
+        }
+        None => vault.list_notes().await?.into_iter().map(|n| n.path).collect(),
+    };
+
+    for path in paths {
+        let content = match vault.read_note(&path).await {
+            Ok(content) => content,
+            Err(e) => {
+                result.errors.push(format!("{}: {}", path, e));
+                continue;
+            }
+        };
+        if has_encrypted_body(&content) {
+            continue;
+        }
+
+        let (fixed, fixes) = fix_content(&content, settings, &required_keys(settings, &path));
+        if fixed != content {
+            if let Err(e) = vault.write_note(&path, &fixed).await {
+                result.errors.push(format!("{}: {}", path, e));
+                continue;
+            }
+            result.notes_changed += 1;
+            result.fixes_applied += fixes;
+        }
+    }
+
+    info!(
+        "Applied {} lint fixes to {} notes, {} failed",
+        result.fixes_applied,
+        result.notes_changed,
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+/// Names of the registered task contexts.
+async fn registered_contexts(vault: &Vault) -> Result<Vec<String>> {
+    Ok(vault
+        .repo()
+        .list_registered_contexts()
+        .await?
+        .into_iter()
+        .map(|c| c.name)
+        .collect())
+}
+
+async fn lint(
+    vault: &Vault,
+    note_id: i64,
+    path: String,
+    content: &str,
+    settings: &LintSettings,
+    contexts: &[String],
+    resolved: &mut HashMap<String, bool>,
+) -> NoteLintResult {
+    if has_encrypted_body(content) {
+        return NoteLintResult {
+            note_id,
+            path,
+            diagnostics: Vec::new(),
+        };
+    }
+
+    let mut broken_targets = HashSet::new();
+    for link in collect_wikilinks(strip_frontmatter(content)) {
+        let exists = match resolved.get(&link.target) {
+            Some(&exists) => exists,
+            None => {
+                let exists = vault.resolve_note(&link.target).await.is_some()
+                    || vault.resolve_asset_path(&link.target).await.is_some();
+                resolved.insert(link.target.clone(), exists);
+                exists
+            }
+        };
+        if !exists {
+            broken_targets.insert(link.target);
+        }
+    }
+
+    let diagnostics = lint_content(content, settings, &required_keys(settings, &path), &broken_targets, contexts);
+    NoteLintResult {
+        note_id,
+        path,
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_apply_lint_fixes_keeps_going_after_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("A.md", "# A\n\n##\nText\n").await.unwrap();
+        vault.write_note("B.md", "# B   \n\nText\n").await.unwrap();
+
+        let mut ids: Vec<i64> = vault.list_notes().await.unwrap().into_iter().map(|n| n.id).collect();
+        ids.insert(1, i64::MAX);
+
+        let result = apply_lint_fixes(&vault, Some(&ids), &LintSettings::default()).await.unwrap();
+
+        assert_eq!(result.notes_changed, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with(&format!("Note {}", i64::MAX)));
+        assert_eq!(vault.read_note("A.md").await.unwrap(), "# A\n\nText\n");
+        assert_eq!(vault.read_note("B.md").await.unwrap(), "# B\n\nText\n");
+    }
+}
@@ -0,0 +1,154 @@
+//! Moving notes between folders without breaking links.
+//!
+//! The note is renamed with `Vault::rename_note`, which rewrites wiki links
+//! that no longer resolve to the same note: path links (`[[projects/Plan]]`),
+//! name links that become ambiguous because another note with the same name
+//! now resolves first, and relative links in the moved note.
+//!
+//! Relative markdown links in the moved note (`[x](../a.md)`,
+//! `![](img/photo.png)`) are then rewritten so they point at the same files
+//! from the new folder. Links to files that don't exist are left alone.
+
+use crate::attachments::vault_folder;
+use crate::encryption::has_encrypted_body;
+use crate::links::relative_path;
+use crate::vault::{parent_folder, Result, Vault};
+use core_index::markdown::map_markdown_links;
+use shared_types::NoteDto;
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Move the note at `path` into `target_folder` (relative to the vault root),
+/// keeping its file name. Fails if the folder already has a note with that
+/// name. Returns the moved note.
+#[instrument(skip(vault))]
+pub async fn move_note(vault: &Vault, path: &str, target_folder: &str) -> Result<NoteDto> {
+    let folder = vault_folder(target_folder)?;
+    let file_name = Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| core_fs::FsError::InvalidPath(path.to_string()))?;
+    let new_path = folder.join(file_name).to_string_lossy().replace('\\', "/");
+
+    let note = vault.repo().get_note_by_path(path).await?;
+    if new_path == path {
+        return Ok(note);
+    }
+
+    vault.rename_note(path, &new_path).await?;
+
+    // Relative links in the moved note itself
+    let content = vault.fs().read_file(Path::new(&new_path)).await?;
+    if !has_encrypted_body(&content) {
+        let updated = map_markdown_links(&content, |destination| {
+            let target = resolve_relative(parent_folder(path), destination)?;
+            if !vault.fs().to_absolute(Path::new(&target_path(&target))).exists() {
+                return None;
+            }
+            Some(relative_link(parent_folder(&new_path), &target))
+        });
+        if updated != content {
+            vault.write_note(&new_path, &updated).await?;
+        }
+    }
+
+    info!("Moved note {} -> {}", path, new_path);
+    Ok(vault.repo().get_note(note.id).await?)
+}
+
+/// A relative link destination resolved against `dir`: the vault-relative
+/// path segments and the `#fragment`, if any. `None` for URLs, absolute
+/// paths, in-page anchors and paths leaving the vault.
+pub(crate) fn resolve_relative<'a>(dir: &'a str, destination: &'a str) -> Option<(Vec<&'a str>, Option<&'a str>)> {
+    if destination.contains("://")
+        || destination.starts_with('/')
+        || destination.starts_with('#')
+        || destination.starts_with("mailto:")
+    {
+        return None;
+    }
+
+    let (link_path, fragment) = match destination.split_once('#') {
+        Some((link_path, fragment)) => (link_path, Some(fragment)),
+        None => (destination, None),
+    };
+
+    let mut segments: Vec<&str> = dir.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in link_path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => segments.push(segment),
+        }
+    }
+    Some((segments, fragment))
+}
+
+/// The vault-relative file path of a resolved link, with `%20` decoded.
+pub(crate) fn target_path((segments, _): &(Vec<&str>, Option<&str>)) -> String {
+    segments.join("/").replace("%20", " ")
+}
+
+/// A link destination from `dir` to a resolved link.
+pub(crate) fn relative_link(dir: &str, (segments, fragment): &(Vec<&str>, Option<&str>)) -> String {
+    let mut link = relative_path(dir, &segments.join("/"));
+    if let Some(fragment) = fragment {
+        link.push('#');
+        link.push_str(fragment);
+    }
+    link
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relink(from: &str, to: &str, destination: &str) -> Option<String> {
+        resolve_relative(from, destination).map(|target| relative_link(to, &target))
+    }
+
+    #[test]
+    fn test_relative_links() {
+        assert_eq!(relink("projects", "archive/2024", "../people/Ann.md").as_deref(), Some("../../people/Ann.md"));
+        assert_eq!(relink("projects", "", "img/a.png").as_deref(), Some("projects/img/a.png"));
+        assert_eq!(relink("", "projects", "projects/Plan.md#goals").as_deref(), Some("Plan.md#goals"));
+        assert_eq!(relink("", "a", "../outside.md"), None);
+        assert_eq!(relink("a", "b", "https://example.com/x.md"), None);
+        assert_eq!(relink("a", "b", "#heading"), None);
+    }
+
+    #[tokio::test]
+    async fn test_move_note_fixes_links() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("projects/img")).unwrap();
+        std::fs::create_dir_all(dir.path().join("archive")).unwrap();
+        std::fs::write(dir.path().join("projects/img/chart.png"), b"png").unwrap();
+        std::fs::write(dir.path().join("archive/Notes.md"), "# Archived notes\n").unwrap();
+        std::fs::write(dir.path().join("projects/Notes.md"), "# Notes\n\n![chart](img/chart.png) [gone](missing.md)\n").unwrap();
+        std::fs::write(
+            dir.path().join("home.md"),
+            "[[projects/Notes#Todo|todo]] and [[Other]]\n",
+        )
+        .unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        // Moving projects/Notes next to another note with the same name
+        let moved = move_note(&vault, "projects/Notes.md", "").await.unwrap();
+        assert_eq!(moved.path, "Notes.md");
+
+        let content = vault.read_note("Notes.md").await.unwrap();
+        assert!(content.contains("![chart](projects/img/chart.png)"));
+        assert!(content.contains("[gone](missing.md)"));
+
+        let home = vault.read_note("home.md").await.unwrap();
+        assert_eq!(home, "[[Notes#Todo|todo]] and [[Other]]\n");
+
+        // Moving it back, `[[Notes]]` would resolve to archive/Notes.md first
+        move_note(&vault, "Notes.md", "projects").await.unwrap();
+        let home = vault.read_note("home.md").await.unwrap();
+        assert_eq!(home, "[[projects/Notes#Todo|todo]] and [[Other]]\n");
+    }
+}
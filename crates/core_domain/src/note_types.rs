@@ -0,0 +1,175 @@
+//! Note types (meeting, book, person, ...) with type-specific defaults.
+//!
+//! A type is defined in the vault config (`note_types`) or by a template in
+//! the `types/` folder: `types/book.md` defines the type `book`, or is the
+//! template of a configured `book` type that has none. Without any
+//! configured types, meeting, book and person types are built in.
+//!
+//! `create_typed_note` creates a note in the type's folder from its template
+//! and sets the `type` property and the type's properties on it.
+
+use crate::notes::create_note;
+use crate::templates::{render_template, TemplateContext};
+use crate::vault::{Result, Vault, VaultError};
+use shared_types::{NoteDto, NoteTypeDefinition, NoteTypeProperty};
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Folder whose templates define note types.
+const TYPES_FOLDER: &str = "types";
+
+/// The built-in types, used when the vault config defines none.
+pub fn default_note_types() -> Vec<NoteTypeDefinition> {
+    let property = |key: &str, value: Option<&str>, property_type: &str| NoteTypeProperty {
+        key: key.to_string(),
+        value: value.map(|v| v.to_string()),
+        property_type: Some(property_type.to_string()),
+    };
+    let note_type = |name: &str, folder: &str, icon: &str, properties| NoteTypeDefinition {
+        name: name.to_string(),
+        template: None,
+        properties,
+        folder: Some(folder.to_string()),
+        icon: Some(icon.to_string()),
+    };
+
+    vec![
+        note_type(
+            "meeting",
+            "meetings",
+            "users",
+            vec![property("date", Some("{{date}}"), "date"), property("attendees", None, "list")],
+        ),
+        note_type(
+            "book",
+            "books",
+            "book",
+            vec![
+                property("author", None, "text"),
+                property("status", Some("to-read"), "text"),
+                property("rating", None, "number"),
+            ],
+        ),
+        note_type(
+            "person",
+            "people",
+            "user",
+            vec![property("email", None, "text"), property("company", None, "text")],
+        ),
+    ]
+}
+
+/// The vault's note types: the configured ones (or the built-in ones if
+/// there are none), then those only defined by a template in `types/`.
+pub async fn list_note_types(vault: &Vault, configured: &[NoteTypeDefinition]) -> Result<Vec<NoteTypeDefinition>> {
+    let mut types = if configured.is_empty() {
+        default_note_types()
+    } else {
+        configured.to_vec()
+    };
+
+    let mut templates = Vec::new();
+    if let Ok(mut entries) = tokio::fs::read_dir(vault.fs().to_absolute(Path::new(TYPES_FOLDER))).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                templates.push(name.to_string());
+            }
+        }
+    }
+    templates.sort();
+
+    for name in templates {
+        let template = format!("{}/{}.md", TYPES_FOLDER, name);
+        match types.iter_mut().find(|t| t.name.eq_ignore_ascii_case(&name)) {
+            Some(note_type) => {
+                note_type.template.get_or_insert(template);
+            }
+            None => types.push(NoteTypeDefinition {
+                name,
+                template: Some(template),
+                properties: Vec::new(),
+                folder: None,
+                icon: None,
+            }),
+        }
+    }
+
+    Ok(types)
+}
+
+/// Create a note titled `title` of the type named `type_name` (ignoring
+/// case): in the type's folder, from its template, with the `type`
+/// property and the type's properties set. Fails for unknown types.
+#[instrument(skip(vault, configured))]
+pub async fn create_typed_note(
+    vault: &Vault,
+    configured: &[NoteTypeDefinition],
+    type_name: &str,
+    title: &str,
+) -> Result<NoteDto> {
+    let types = list_note_types(vault, configured).await?;
+    let note_type = types
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(type_name))
+        .ok_or_else(|| VaultError::UnknownNoteType(type_name.to_string()))?;
+
+    let note = create_note(
+        vault,
+        title,
+        note_type.folder.as_deref().unwrap_or(""),
+        note_type.template.as_deref(),
+    )
+    .await?;
+
+    let repo = vault.repo();
+    repo.set_property(note.id, "type", Some(&note_type.name), Some("text")).await?;
+
+    let ctx = TemplateContext::default().with_var("title", title.trim());
+    for property in &note_type.properties {
+        let value = property.value.as_deref().map(|v| render_template(v, &ctx));
+        repo.set_property(note.id, &property.key, value.as_deref(), property.property_type.as_deref())
+            .await?;
+    }
+    vault.refresh_autocomplete(Some(&[note.id])).await?;
+
+    info!("Created {} note {}", note_type.name, note.path);
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_typed_note() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("types")).unwrap();
+        std::fs::write(dir.path().join("types/book.md"), "# {{title}}\n\n## Quotes\n").unwrap();
+        std::fs::write(dir.path().join("types/recipe.md"), "# {{title}}\n\n## Ingredients\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        let types = list_note_types(&vault, &[]).await.unwrap();
+        let names: Vec<&str> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["meeting", "book", "person", "recipe"]);
+        assert_eq!(types[1].template.as_deref(), Some("types/book.md"));
+
+        let note = create_typed_note(&vault, &[], "Book", "Dune").await.unwrap();
+        assert_eq!(note.path, "books/Dune.md");
+        assert_eq!(vault.read_note(&note.path).await.unwrap(), "# Dune\n\n## Quotes\n");
+
+        let props = vault.repo().get_properties_for_note(note.id).await.unwrap();
+        let value = |key: &str| props.iter().find(|p| p.key == key).and_then(|p| p.value.clone());
+        assert_eq!(value("type").as_deref(), Some("book"));
+        assert_eq!(value("status").as_deref(), Some("to-read"));
+        assert!(props.iter().any(|p| p.key == "author" && p.value.is_none()));
+
+        assert!(matches!(
+            create_typed_note(&vault, &[], "podcast", "Episode 1").await,
+            Err(VaultError::UnknownNoteType(_))
+        ));
+    }
+}
@@ -0,0 +1,80 @@
+//! Creating notes from a title, and appending to them.
+//!
+//! The file name is generated from the title (see `core_fs::note_file_name`)
+//! and numbered if a note with that name already exists in the folder.
+
+use crate::attachments::vault_folder;
+use crate::encryption::has_encrypted_body;
+use crate::templates::{render_template, TemplateContext};
+use crate::vault::{Result, Vault, VaultError};
+use core_fs::note_file_name;
+use shared_types::{NoteContent, NoteDto};
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Create a note titled `title` in `folder` (relative to the vault root).
+///
+/// With a template (a vault-relative path), the note's content is the
+/// rendered template, which can use `{{title}}` and the date variables;
+/// otherwise it starts with the title as its H1 heading.
+#[instrument(skip(vault))]
+pub async fn create_note(vault: &Vault, title: &str, folder: &str, template: Option<&str>) -> Result<NoteDto> {
+    let title = title.trim();
+    let folder = vault_folder(folder)?;
+
+    let content = match template {
+        Some(template_path) => {
+            let template = vault.fs().read_file(Path::new(template_path)).await?;
+            render_template(&template, &TemplateContext::default().with_var("title", title))
+        }
+        None if title.is_empty() => String::new(),
+        None => format!("# {}\n\n", title),
+    };
+
+    let path = vault.fs().unique_path(&folder, &note_file_name(title)).await;
+    let path = path.to_string_lossy().replace('\\', "/");
+    let note_id = vault.write_note(&path, &content).await?;
+
+    info!("Created note {} (id={})", path, note_id);
+    Ok(vault.repo().get_note(note_id).await?)
+}
+
+/// Append `text` to the note at `path` as a paragraph of its own, e.g. for
+/// quick capture. Fails for encrypted notes while they are locked. Returns
+/// the note with its new content.
+#[instrument(skip(vault, text))]
+pub async fn append_to_note(vault: &Vault, path: &str, text: &str) -> Result<NoteContent> {
+    let content = vault.read_note(path).await?;
+    if has_encrypted_body(&content) {
+        return Err(VaultError::NoteLocked(path.to_string()));
+    }
+
+    let content = format!("{}\n\n{}\n", content.trim_end_matches('\n'), text.trim());
+    let note_id = vault.write_note(path, &content).await?;
+
+    info!("Appended to note {}", path);
+    vault.note_content(note_id, path.to_string(), content).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_append_to_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("Daily.md", "# Today\n\n").await.unwrap();
+
+        let note = append_to_note(&vault, "Daily.md", "  Call Sam\n").await.unwrap();
+        assert_eq!(note.content, "# Today\n\nCall Sam\n");
+        assert_eq!(vault.read_note("Daily.md").await.unwrap(), note.content);
+
+        // A locked encrypted note is left alone
+        let encrypted = crate::encryption::encrypt_note("---\nencrypted: true\n---\n# Secret\n", "secret").unwrap();
+        std::fs::write(dir.path().join("Secret.md"), &encrypted).unwrap();
+        let result = append_to_note(&vault, "Secret.md", "Call Sam").await;
+        assert!(matches!(result, Err(VaultError::NoteLocked(_))));
+        assert_eq!(std::fs::read_to_string(dir.path().join("Secret.md")).unwrap(), encrypted);
+    }
+}
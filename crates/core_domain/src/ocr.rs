@@ -0,0 +1,157 @@
+//! Text recognition (OCR) for the images in the vault, so screenshots and
+//! scanned pages can be found by the text in them.
+//!
+//! Images are recognized by the `tesseract` command-line program, which has
+//! to be installed separately. The text is stored with the content hash of
+//! the image, so images that haven't changed are skipped the next time, and
+//! the text of images that were deleted is dropped.
+
+use crate::assets::list_attachments;
+use crate::jobs::Job;
+use crate::vault::{Result, Vault, VaultError};
+use core_fs::{hash_bytes, FsError};
+use shared_types::OcrResult;
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::{debug, info, instrument, warn};
+
+/// Program used to recognize text.
+const TESSERACT: &str = "tesseract";
+
+/// Image formats text is recognized in.
+const OCR_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "webp"];
+
+/// Recognize the text in the vault's new and changed images.
+#[instrument(skip(vault, job))]
+pub async fn recognize_images(vault: &Vault, job: Option<&Job>) -> Result<OcrResult> {
+    recognize_images_with(vault, Path::new(TESSERACT), job).await
+}
+
+async fn recognize_images_with(vault: &Vault, program: &Path, job: Option<&Job>) -> Result<OcrResult> {
+    let images: Vec<String> = list_attachments(vault)
+        .await?
+        .into_iter()
+        .filter(|path| {
+            Path::new(path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| OCR_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .collect();
+    let recognized = vault.repo().image_text_hashes().await?;
+    let mut result = OcrResult::default();
+
+    for (i, path) in images.iter().enumerate() {
+        if job.is_some_and(|job| job.is_cancelled()) {
+            result.cancelled = true;
+            break;
+        }
+        if let Some(job) = job {
+            job.progress(vault, i, images.len(), Some(path.clone()));
+        }
+
+        let bytes = tokio::fs::read(vault.fs().to_absolute(Path::new(path)))
+            .await
+            .map_err(FsError::from)?;
+        let hash = hash_bytes(&bytes);
+        if recognized.get(path) == Some(&hash) {
+            result.images_unchanged += 1;
+            continue;
+        }
+
+        match recognize(vault, program, path).await? {
+            Ok(text) => {
+                vault.repo().set_image_text(path, &hash, &text).await?;
+                result.images_recognized += 1;
+            }
+            Err(reason) => {
+                warn!("Could not recognize text in {}: {}", path, reason);
+                result.warnings.push(format!("{}: {}", path, reason));
+            }
+        }
+    }
+
+    if !result.cancelled {
+        let existing: HashSet<&String> = images.iter().collect();
+        for path in recognized.keys().filter(|path| !existing.contains(path)) {
+            vault.repo().remove_image_text(path).await?;
+        }
+        if let Some(job) = job {
+            job.progress(vault, images.len(), images.len(), None);
+        }
+    }
+
+    info!(
+        "Recognized text in {} images ({} unchanged, {} failed)",
+        result.images_recognized,
+        result.images_unchanged,
+        result.warnings.len()
+    );
+    Ok(result)
+}
+
+/// Run the recognizer on one image. The outer error stops the job (the
+/// program can't be run at all), the inner one only skips the image.
+async fn recognize(vault: &Vault, program: &Path, path: &str) -> Result<std::result::Result<String, String>> {
+    let output = tokio::process::Command::new(program)
+        .arg(vault.fs().to_absolute(Path::new(path)))
+        .arg("stdout")
+        .output()
+        .await
+        .map_err(|e| VaultError::Ocr(format!("Failed to run {}: {}", program.display(), e)))?;
+
+    if !output.status.success() {
+        return Ok(Err(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    debug!("Recognized text in {}", path);
+    Ok(Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[tokio::test]
+    async fn test_recognize_images() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("assets/receipt.png"), b"receipt").unwrap();
+        std::fs::write(dir.path().join("assets/broken.jpg"), b"broken").unwrap();
+        std::fs::write(dir.path().join("assets/notes.pdf"), b"pdf").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        // Stands in for tesseract: "reads" the image's bytes as its text
+        let program = tempfile::tempdir().unwrap();
+        let script = program.path().join("ocr.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\ngrep -q broken \"$1\" && { echo 'Error in pixReadStream' >&2; exit 1; }\necho \"Total $(cat \"$1\") 12.50\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let result = recognize_images_with(&vault, &script, None).await.unwrap();
+        assert_eq!(result.images_recognized, 1);
+        assert_eq!(result.warnings, ["assets/broken.jpg: Error in pixReadStream"]);
+
+        let matches = vault.repo().search_image_text("RECEIPT 12", 10).await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "assets/receipt.png");
+        assert_eq!(matches[0].snippet, "Total receipt 12.50");
+        assert!(vault.repo().search_image_text("100%", 10).await.unwrap().is_empty());
+
+        // Unchanged images are skipped, deleted ones forgotten
+        std::fs::remove_file(dir.path().join("assets/broken.jpg")).unwrap();
+        let result = recognize_images_with(&vault, &script, None).await.unwrap();
+        assert_eq!((result.images_recognized, result.images_unchanged), (0, 1));
+        std::fs::remove_file(dir.path().join("assets/receipt.png")).unwrap();
+        recognize_images_with(&vault, &script, None).await.unwrap();
+        assert!(vault.repo().search_image_text("receipt", 10).await.unwrap().is_empty());
+
+        // Without the program the job fails instead of skipping every image
+        std::fs::write(dir.path().join("assets/scan.png"), b"scan").unwrap();
+        let missing = program.path().join("missing");
+        assert!(recognize_images_with(&vault, &missing, None).await.is_err());
+    }
+}
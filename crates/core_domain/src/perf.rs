@@ -0,0 +1,328 @@
+//! Performance metrics: command latency and slow SQL statements.
+//!
+//! The app records how long each command takes and every statement slower
+//! than `SLOW_QUERY_THRESHOLD` (the vault's pools log those as warnings).
+//! Only the most recent samples are kept, in fixed-size ring buffers, so
+//! recording is cheap enough to stay on all the time.
+//!
+//! [`MetricsLayer`] feeds the recorder from tracing. Command latency is the
+//! lifetime of the command's span, so every command needs `#[instrument]`.
+//! Slow statements are the warnings sqlx logs for statements over
+//! `SLOW_QUERY_THRESHOLD` (see `Vault::open`); each is attributed to the
+//! command whose span it happened in.
+
+use chrono::Utc;
+use shared_types::{CommandLatency, PerformanceMetrics, SlowQuery};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::filter::FilterFn;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Statements taking at least this long are recorded as slow queries.
+pub const SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Latency samples kept per command.
+const MAX_SAMPLES: usize = 200;
+
+/// Slow queries kept.
+const MAX_SLOW_QUERIES: usize = 50;
+
+#[derive(Default)]
+struct CommandSamples {
+    count: i64,
+    /// Latencies of the latest calls in milliseconds, oldest first.
+    recent: VecDeque<f64>,
+}
+
+#[derive(Default)]
+struct Samples {
+    commands: HashMap<String, CommandSamples>,
+    /// Oldest first.
+    slow_queries: VecDeque<SlowQuery>,
+}
+
+/// Collects command latencies and slow queries. Shared between the tracing
+/// layer that records them and the command that reports them.
+#[derive(Default)]
+pub struct PerformanceRecorder {
+    samples: Mutex<Samples>,
+}
+
+impl PerformanceRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a call of `command` that took `elapsed`.
+    pub fn record_command(&self, command: &str, elapsed: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        let entry = samples.commands.entry(command.to_string()).or_default();
+        entry.count += 1;
+        if entry.recent.len() == MAX_SAMPLES {
+            entry.recent.pop_front();
+        }
+        entry.recent.push_back(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    /// Record a slow statement, run by `command` if known.
+    pub fn record_slow_query(&self, summary: &str, statement: &str, elapsed: Duration, command: Option<&str>) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.slow_queries.len() == MAX_SLOW_QUERIES {
+            samples.slow_queries.pop_front();
+        }
+        samples.slow_queries.push_back(SlowQuery {
+            timestamp: Utc::now(),
+            summary: summary.to_string(),
+            statement: statement.trim().to_string(),
+            elapsed_ms: elapsed.as_secs_f64() * 1000.0,
+            command: command.map(String::from),
+        });
+    }
+
+    /// Latency percentiles per command and the recent slow queries.
+    pub fn metrics(&self) -> PerformanceMetrics {
+        let samples = self.samples.lock().unwrap();
+
+        let mut commands: Vec<CommandLatency> = samples
+            .commands
+            .iter()
+            .map(|(command, calls)| {
+                let mut recent: Vec<f64> = calls.recent.iter().copied().collect();
+                recent.sort_by(f64::total_cmp);
+                CommandLatency {
+                    command: command.clone(),
+                    count: calls.count,
+                    p50_ms: percentile(&recent, 50.0),
+                    p95_ms: percentile(&recent, 95.0),
+                    max_ms: recent.last().copied().unwrap_or(0.0),
+                }
+            })
+            .collect();
+        commands.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms).then_with(|| a.command.cmp(&b.command)));
+
+        PerformanceMetrics {
+            commands,
+            slow_queries: samples.slow_queries.iter().rev().cloned().collect(),
+            slow_query_threshold_ms: SLOW_QUERY_THRESHOLD.as_millis() as u64,
+        }
+    }
+
+    /// Forget everything recorded so far.
+    pub fn reset(&self) {
+        *self.samples.lock().unwrap() = Samples::default();
+    }
+}
+
+/// Target of sqlx's statement logs.
+const QUERY_TARGET: &str = "sqlx::query";
+
+/// Tracing layer recording command latencies and slow statements.
+pub struct MetricsLayer {
+    recorder: Arc<PerformanceRecorder>,
+    /// Target prefix of the spans of command functions (their module path).
+    commands_target: &'static str,
+}
+
+impl MetricsLayer {
+    pub fn new(recorder: Arc<PerformanceRecorder>, commands_target: &'static str) -> Self {
+        Self {
+            recorder,
+            commands_target,
+        }
+    }
+
+    /// Filter for the spans and events the layer needs: command spans and
+    /// slow statement warnings. Use it as the layer's filter, so these are
+    /// recorded whatever the log level.
+    pub fn filter(commands_target: &'static str) -> FilterFn<impl Fn(&Metadata<'_>) -> bool> {
+        FilterFn::new(move |metadata| {
+            is_command(metadata, commands_target)
+                || (metadata.target() == QUERY_TARGET && *metadata.level() <= Level::WARN)
+        })
+    }
+}
+
+fn is_command(metadata: &Metadata<'_>, commands_target: &str) -> bool {
+    metadata.is_span() && metadata.target().starts_with(commands_target)
+}
+
+/// When a command span was created.
+struct CommandStart(Instant);
+
+impl<S> Layer<S> for MetricsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !is_command(attrs.metadata(), self.commands_target) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(CommandStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let start = span.extensions().get::<CommandStart>().map(|start| start.0);
+        if let Some(start) = start {
+            self.recorder.record_command(span.name(), start.elapsed());
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if event.metadata().target() != QUERY_TARGET {
+            return;
+        }
+        let mut fields = QueryFields::default();
+        event.record(&mut fields);
+        let Some(elapsed_secs) = fields.elapsed_secs else {
+            return;
+        };
+
+        let command = ctx
+            .event_scope(event)
+            .and_then(|scope| {
+                scope
+                    .from_root()
+                    .find(|span| is_command(span.metadata(), self.commands_target))
+            })
+            .map(|span| span.name());
+        self.recorder
+            .record_slow_query(&fields.summary, &fields.statement, Duration::from_secs_f64(elapsed_secs), command);
+    }
+}
+
+/// The fields of a sqlx statement log.
+#[derive(Default)]
+struct QueryFields {
+    summary: String,
+    statement: String,
+    elapsed_secs: Option<f64>,
+}
+
+impl Visit for QueryFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// Nearest-rank percentile of sorted values (0 if there are none).
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_percentiles() {
+        let recorder = PerformanceRecorder::new();
+        for ms in 1..=100 {
+            recorder.record_command("search_notes", Duration::from_millis(ms));
+        }
+        recorder.record_command("get_note", Duration::from_millis(2));
+
+        let metrics = recorder.metrics();
+        assert_eq!(metrics.commands.len(), 2);
+        let search = &metrics.commands[0];
+        assert_eq!(search.command, "search_notes");
+        assert_eq!(search.count, 100);
+        assert_eq!((search.p50_ms, search.p95_ms, search.max_ms), (50.0, 95.0, 100.0));
+        assert_eq!(metrics.commands[1].p95_ms, 2.0);
+
+        // Only the latest calls count towards the percentiles
+        for _ in 0..MAX_SAMPLES {
+            recorder.record_command("search_notes", Duration::from_millis(5));
+        }
+        let search = &recorder.metrics().commands[0];
+        assert_eq!(search.count, 100 + MAX_SAMPLES as i64);
+        assert_eq!((search.p50_ms, search.max_ms), (5.0, 5.0));
+    }
+
+    #[test]
+    fn test_metrics_layer() {
+        use tracing_subscriber::prelude::*;
+
+        let recorder = Arc::new(PerformanceRecorder::new());
+        let subscriber = tracing_subscriber::registry().with(
+            MetricsLayer::new(recorder.clone(), "app::commands")
+                .with_filter(MetricsLayer::filter("app::commands")),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(target: "app::commands::notes", "get_note");
+            span.in_scope(|| {
+                let _inner = tracing::debug_span!(target: "core_storage", "query").entered();
+                tracing::warn!(
+                    target: "sqlx::query",
+                    summary = "SELECT notes",
+                    db.statement = "SELECT * FROM notes",
+                    elapsed_secs = 0.25
+                );
+            });
+            drop(span);
+            // Not a command
+            drop(tracing::info_span!(target: "app::state", "job_worker"));
+            // Statements outside commands are still recorded
+            tracing::warn!(target: "sqlx::query", summary = "VACUUM", db.statement = "VACUUM", elapsed_secs = 1.0);
+        });
+
+        let metrics = recorder.metrics();
+        let commands: Vec<(&str, i64)> = metrics.commands.iter().map(|c| (c.command.as_str(), c.count)).collect();
+        assert_eq!(commands, [("get_note", 1)]);
+        assert_eq!(metrics.slow_queries.len(), 2);
+        assert_eq!(metrics.slow_queries[0].command, None);
+        let query = &metrics.slow_queries[1];
+        assert_eq!((query.summary.as_str(), query.statement.as_str()), ("SELECT notes", "SELECT * FROM notes"));
+        assert_eq!(query.command.as_deref(), Some("get_note"));
+        assert_eq!(query.elapsed_ms, 250.0);
+    }
+
+    #[test]
+    fn test_slow_queries() {
+        let recorder = PerformanceRecorder::new();
+        for i in 0..MAX_SLOW_QUERIES + 5 {
+            recorder.record_slow_query(
+                &format!("SELECT {}", i),
+                "",
+                Duration::from_millis(150),
+                Some("search_notes"),
+            );
+        }
+
+        let metrics = recorder.metrics();
+        assert_eq!(metrics.slow_queries.len(), MAX_SLOW_QUERIES);
+        assert_eq!(metrics.slow_queries[0].summary, format!("SELECT {}", MAX_SLOW_QUERIES + 4));
+        assert_eq!(metrics.slow_queries.last().unwrap().summary, "SELECT 5");
+        assert_eq!(metrics.slow_queries[0].command.as_deref(), Some("search_notes"));
+        assert_eq!(metrics.slow_query_threshold_ms, 100);
+
+        recorder.reset();
+        assert!(recorder.metrics().slow_queries.is_empty());
+    }
+}
@@ -0,0 +1,539 @@
+//! Periodic (journal) notes: daily, weekly and monthly.
+//!
+//! Each period has a path pattern and an optional template in the template
+//! settings. A period is identified by its first day (the date itself, the
+//! first day of the week as set in the locale settings, or the first of the
+//! month), which the pattern and the template are rendered for. The daily
+//! note template also gets the day's journal prompt as `{{prompt}}`.
+//!
+//! Week numbers are ISO 8601 weeks. A weekly note takes its `{{week}}`,
+//! `{{week_year}}` and `{{iso_week}}` from the Monday in the week, so weeks
+//! starting on Sunday or Saturday get one number each, and the week around
+//! New Year belongs to the year of its ISO week (2024-12-30 is in 2025-W01).
+//!
+//! Weekly and monthly notes get a rollup: links to the week's daily notes
+//! (or the month's weekly notes) and a query block listing the tasks
+//! completed in the period, between rollup markers. The template places it
+//! with `{{rollup}}` (it is appended otherwise), and it is refreshed when
+//! one of the linked notes is created.
+
+use crate::encryption::{has_encrypted_body, is_marked_encrypted};
+use crate::templates::{journal_prompt_for_date, render_template, TemplateContext};
+use crate::vault::{Result, Vault};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use shared_types::{NotePeriod, PeriodDirection, PeriodicNote, TemplateSettings, WeekInfo, WeekStart};
+use std::path::Path;
+use tracing::{debug, info, instrument};
+
+/// Daily note content when no template file is configured.
+const DEFAULT_DAILY_TEMPLATE: &str = r#"# {{date}}
+
+## Tasks
+- [ ]
+
+## Notes
+
+## Reflection
+
+"#;
+
+/// Weekly note content when no template file is configured.
+const DEFAULT_WEEKLY_TEMPLATE: &str = r#"# Week {{week}}, {{week_year}}
+
+## Goals
+
+{{rollup}}
+## Review
+
+"#;
+
+/// Markers around the generated rollup section of a weekly or monthly note.
+const ROLLUP_START: &str = "<!-- rollup:start -->";
+const ROLLUP_END: &str = "<!-- rollup:end -->";
+
+/// Monthly note content when no template file is configured.
+const DEFAULT_MONTHLY_TEMPLATE: &str = r#"# {{month_name}} {{year}}
+
+## Goals
+
+{{rollup}}
+## Review
+
+"#;
+
+/// The ISO week containing `date`.
+pub fn get_week_info(date: NaiveDate) -> WeekInfo {
+    let iso = date.iso_week();
+    let monday = WeekStart::Monday.start_of_week(date);
+    WeekInfo {
+        year: iso.year(),
+        week: iso.week(),
+        label: format!("{}-W{:02}", iso.year(), iso.week()),
+        start: monday.format("%Y-%m-%d").to_string(),
+        end: (monday + Duration::days(6)).format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// The Monday in the week starting on `week_start`, which numbers the week.
+fn week_monday(week_start: NaiveDate) -> NaiveDate {
+    week_start + Duration::days(((7 - week_start.weekday().num_days_from_monday()) % 7) as i64)
+}
+
+/// The first day of the period containing `date`, with weeks starting on
+/// `first_weekday`.
+pub fn period_start(date: NaiveDate, period: NotePeriod, first_weekday: WeekStart) -> NaiveDate {
+    match period {
+        NotePeriod::Daily => date,
+        NotePeriod::Weekly => first_weekday.start_of_week(date),
+        NotePeriod::Monthly => date.with_day(1).unwrap_or(date),
+    }
+}
+
+/// The first day of the period before or after the one containing `date`.
+pub fn adjacent_period(
+    date: NaiveDate,
+    period: NotePeriod,
+    direction: PeriodDirection,
+    first_weekday: WeekStart,
+) -> NaiveDate {
+    let start = period_start(date, period, first_weekday);
+    match (period, direction) {
+        (NotePeriod::Daily, PeriodDirection::Previous) => start - Duration::days(1),
+        (NotePeriod::Daily, PeriodDirection::Next) => start + Duration::days(1),
+        (NotePeriod::Weekly, PeriodDirection::Previous) => start - Duration::weeks(1),
+        (NotePeriod::Weekly, PeriodDirection::Next) => start + Duration::weeks(1),
+        (NotePeriod::Monthly, PeriodDirection::Previous) => start - Months::new(1),
+        (NotePeriod::Monthly, PeriodDirection::Next) => start + Months::new(1),
+    }
+}
+
+/// Path of the note for the period containing `date`.
+pub fn periodic_note_path(
+    settings: &TemplateSettings,
+    date: NaiveDate,
+    period: NotePeriod,
+    first_weekday: WeekStart,
+) -> String {
+    let pattern = match period {
+        NotePeriod::Daily => &settings.daily_note_pattern,
+        NotePeriod::Weekly => &settings.weekly_note_pattern,
+        NotePeriod::Monthly => &settings.monthly_note_pattern,
+    };
+    render_template(pattern, &context(settings, period_start(date, period, first_weekday), period))
+}
+
+fn context(settings: &TemplateSettings, start: NaiveDate, period: NotePeriod) -> TemplateContext {
+    let ctx = TemplateContext::for_date(start).with_settings(settings);
+    match period {
+        NotePeriod::Daily => {
+            let prompt = journal_prompt_for_date(&settings.journal_prompts, start).unwrap_or_default();
+            ctx.with_var("prompt", prompt)
+        }
+        NotePeriod::Weekly => ctx.with_week_date(week_monday(start)),
+        NotePeriod::Monthly => ctx,
+    }
+}
+
+/// The note for the period containing `date`. With `create`, a missing
+/// note is created from the period's template (or a default one if there
+/// is none or it can't be read).
+#[instrument(skip(vault, settings))]
+pub async fn periodic_note(
+    vault: &Vault,
+    settings: &TemplateSettings,
+    date: NaiveDate,
+    period: NotePeriod,
+    create: bool,
+) -> Result<PeriodicNote> {
+    let first_weekday = vault.locale_settings().await.week_start;
+    let start = period_start(date, period, first_weekday);
+    let path = periodic_note_path(settings, start, period, first_weekday);
+    let mut note = PeriodicNote {
+        period,
+        date: start.format("%Y-%m-%d").to_string(),
+        path: path.clone(),
+        note_id: None,
+        created: false,
+    };
+
+    if vault.fs().exists(Path::new(&path)).await {
+        note.note_id = match vault.repo().get_note_id_by_path(&path).await? {
+            Some(id) => Some(id),
+            // Not indexed yet (e.g. just synced in)
+            None => vault.index_file(&path).await?,
+        };
+        return Ok(note);
+    }
+    if !create {
+        return Ok(note);
+    }
+
+    let (template_path, default_template) = match period {
+        NotePeriod::Daily => (&settings.daily_template_path, DEFAULT_DAILY_TEMPLATE),
+        NotePeriod::Weekly => (&settings.weekly_template_path, DEFAULT_WEEKLY_TEMPLATE),
+        NotePeriod::Monthly => (&settings.monthly_template_path, DEFAULT_MONTHLY_TEMPLATE),
+    };
+    let template = match template_path {
+        Some(template_path) => match vault.fs().read_file(Path::new(template_path)).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Failed to read template file {}: {}, using default", template_path, e);
+                default_template.to_string()
+            }
+        },
+        None => default_template.to_string(),
+    };
+
+    let content = match period {
+        NotePeriod::Weekly | NotePeriod::Monthly => {
+            let rollup = rollup(vault, settings, start, period).await;
+            let content = render_template(&template, &context(settings, start, period).with_var("rollup", &rollup));
+            if content.contains(ROLLUP_START) {
+                content
+            } else {
+                format!("{}\n\n{}", content.trim_end(), rollup)
+            }
+        }
+        NotePeriod::Daily => render_template(&template, &context(settings, start, period)),
+    };
+    let note_id = vault.write_note(&path, &content).await?;
+    info!("Created {:?} note: {} (id={})", period, path, note_id);
+
+    // Link the new note from the rollups that list it
+    let end = period_end(start, period);
+    let parents: &[(NaiveDate, NotePeriod)] = match period {
+        NotePeriod::Daily => &[(start, NotePeriod::Weekly)],
+        // A week across two months is listed in both
+        NotePeriod::Weekly => &[(start, NotePeriod::Monthly), (end, NotePeriod::Monthly)],
+        NotePeriod::Monthly => &[],
+    };
+    let mut refreshed = Vec::new();
+    for &(date, parent) in parents {
+        let parent_start = period_start(date, parent, first_weekday);
+        let parent_path = periodic_note_path(settings, parent_start, parent, first_weekday);
+        if !refreshed.contains(&parent_path) && vault.fs().exists(Path::new(&parent_path)).await {
+            refresh_rollup(vault, settings, parent_start, parent, &parent_path).await?;
+            refreshed.push(parent_path);
+        }
+    }
+
+    note.note_id = Some(note_id);
+    note.created = true;
+    Ok(note)
+}
+
+/// The last day of the period starting on `start`.
+fn period_end(start: NaiveDate, period: NotePeriod) -> NaiveDate {
+    match period {
+        NotePeriod::Daily => start,
+        NotePeriod::Weekly => start + Duration::days(6),
+        NotePeriod::Monthly => start + Months::new(1) - Duration::days(1),
+    }
+}
+
+/// The rollup section of the weekly or monthly note starting on `start`:
+/// links to the week's existing daily notes (or the existing weekly notes
+/// of the weeks starting in the month) and a query block of the tasks
+/// completed in the period, between the rollup markers.
+pub async fn rollup(vault: &Vault, settings: &TemplateSettings, start: NaiveDate, period: NotePeriod) -> String {
+    let first_weekday = vault.locale_settings().await.week_start;
+    let end = period_end(start, period);
+
+    let (heading, parts, empty) = match period {
+        NotePeriod::Monthly => {
+            let first_week = period_start(start, NotePeriod::Weekly, first_weekday);
+            let weeks: Vec<NaiveDate> = (0..6)
+                .map(|i| first_week + Duration::weeks(i))
+                .filter(|week| *week <= end)
+                .collect();
+            ("Weeks", weeks, "No weekly notes yet.")
+        }
+        NotePeriod::Daily | NotePeriod::Weekly => {
+            let days: Vec<NaiveDate> = (0..7).map(|i| start + Duration::days(i)).collect();
+            ("Days", days, "No daily notes yet.")
+        }
+    };
+    let part_period = if period == NotePeriod::Monthly { NotePeriod::Weekly } else { NotePeriod::Daily };
+
+    let mut links = String::new();
+    for part in parts {
+        let path = periodic_note_path(settings, part, part_period, first_weekday);
+        if !vault.fs().exists(Path::new(&path)).await {
+            continue;
+        }
+        let name = Path::new(&path).file_stem().and_then(|s| s.to_str()).unwrap_or(&path);
+        let label = match part_period {
+            NotePeriod::Weekly => format!("Week {:02}", week_monday(part).iso_week().week()),
+            _ => part.format("%A").to_string(),
+        };
+        links.push_str(&format!("- [[{}|{}]]\n", name, label));
+    }
+    if links.is_empty() {
+        links.push_str(empty);
+        links.push('\n');
+    }
+
+    format!(
+        "{start_marker}\n## {heading}\n\n{links}\n## Completed\n\n```query\nfilters:\n  \
+         - key: _completed\n    operator: DateOnOrAfter\n    value: \"{from}\"\n  \
+         - key: _completed\n    operator: DateOnOrBefore\n    value: \"{to}\"\n\
+         result_type: Tasks\ninclude_completed: true\nlimit: 100\n```\n{end_marker}\n",
+        start_marker = ROLLUP_START,
+        end_marker = ROLLUP_END,
+        heading = heading,
+        links = links,
+        from = start.format("%Y-%m-%d"),
+        to = end.format("%Y-%m-%d"),
+    )
+}
+
+/// Refresh the rollup of the weekly or monthly note for the period
+/// containing `date`, creating the note if it doesn't exist. A note without
+/// rollup markers gets the rollup appended.
+#[instrument(skip(vault, settings))]
+pub async fn update_rollup(
+    vault: &Vault,
+    settings: &TemplateSettings,
+    date: NaiveDate,
+    period: NotePeriod,
+) -> Result<PeriodicNote> {
+    let mut note = periodic_note(vault, settings, date, period, true).await?;
+    if !note.created {
+        let start = period_start(date, period, vault.locale_settings().await.week_start);
+        if let Some(note_id) = refresh_rollup(vault, settings, start, period, &note.path).await? {
+            note.note_id = Some(note_id);
+        }
+    }
+    Ok(note)
+}
+
+/// Replace the rollup section of the weekly or monthly note at `path`.
+/// Returns the note's ID if it was rewritten, None if the rollup was
+/// already current or the note is encrypted, which is left alone.
+async fn refresh_rollup(
+    vault: &Vault,
+    settings: &TemplateSettings,
+    start: NaiveDate,
+    period: NotePeriod,
+    path: &str,
+) -> Result<Option<i64>> {
+    let content = vault.read_note(path).await?;
+    if is_marked_encrypted(&content) || has_encrypted_body(&content) {
+        debug!("Not refreshing the rollup of encrypted note {}", path);
+        return Ok(None);
+    }
+    let rollup = rollup(vault, settings, start, period).await;
+
+    let section = content.find(ROLLUP_START).and_then(|start| {
+        content[start..]
+            .find(ROLLUP_END)
+            .map(|end| (start, start + end + ROLLUP_END.len()))
+    });
+    let updated = match section {
+        Some((start, end)) => {
+            let rest = content[end..].strip_prefix('\n').unwrap_or(&content[end..]);
+            format!("{}{}{}", &content[..start], rollup, rest)
+        }
+        None => format!("{}\n\n{}", content.trim_end(), rollup),
+    };
+    if updated == content {
+        return Ok(None);
+    }
+
+    let note_id = vault.write_note(path, &updated).await?;
+    debug!("Refreshed {:?} rollup: {}", period, path);
+    Ok(Some(note_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_periods() {
+        // Wednesday
+        let day = date("2025-01-01");
+        let monday = WeekStart::Monday;
+        assert_eq!(period_start(day, NotePeriod::Weekly, monday), date("2024-12-30"));
+        assert_eq!(period_start(day, NotePeriod::Weekly, WeekStart::Sunday), date("2024-12-29"));
+        assert_eq!(period_start(day, NotePeriod::Weekly, WeekStart::Saturday), date("2024-12-28"));
+        assert_eq!(adjacent_period(day, NotePeriod::Daily, PeriodDirection::Previous, monday), date("2024-12-31"));
+        assert_eq!(adjacent_period(day, NotePeriod::Weekly, PeriodDirection::Next, monday), date("2025-01-06"));
+        assert_eq!(adjacent_period(day, NotePeriod::Weekly, PeriodDirection::Next, WeekStart::Sunday), date("2025-01-05"));
+        assert_eq!(
+            adjacent_period(date("2025-03-31"), NotePeriod::Monthly, PeriodDirection::Previous, monday),
+            date("2025-02-01")
+        );
+
+        let settings = TemplateSettings::default();
+        assert_eq!(periodic_note_path(&settings, day, NotePeriod::Daily, monday), "journal/2025/01/2025-01-01.md");
+        assert_eq!(periodic_note_path(&settings, day, NotePeriod::Weekly, monday), "journal/2025/2025-W01.md");
+        assert_eq!(
+            periodic_note_path(&settings, date("2024-12-30"), NotePeriod::Monthly, monday),
+            "journal/2024/2024-12.md"
+        );
+    }
+
+    #[test]
+    fn test_get_week_info() {
+        let week = get_week_info(date("2024-04-10"));
+        assert_eq!((week.year, week.week, week.label.as_str()), (2024, 15, "2024-W15"));
+        assert_eq!((week.start.as_str(), week.end.as_str()), ("2024-04-08", "2024-04-14"));
+
+        // Year boundaries: the week belongs to the year of its Thursday
+        assert_eq!(get_week_info(date("2024-12-30")).label, "2025-W01");
+        assert_eq!(get_week_info(date("2021-01-03")).label, "2020-W53");
+        assert_eq!(get_week_info(date("2021-01-03")).start, "2020-12-28");
+    }
+
+    #[test]
+    fn test_weekly_note_path_at_year_boundary() {
+        let settings = TemplateSettings {
+            weekly_note_pattern: "journal/{{iso_week}}.md".to_string(),
+            ..Default::default()
+        };
+        let path = |d: &str, first_weekday| periodic_note_path(&settings, date(d), NotePeriod::Weekly, first_weekday);
+
+        assert_eq!(path("2024-04-10", WeekStart::Monday), "journal/2024-W15.md");
+        assert_eq!(path("2025-01-01", WeekStart::Monday), "journal/2025-W01.md");
+        assert_eq!(path("2021-01-01", WeekStart::Monday), "journal/2020-W53.md");
+
+        // Weeks starting on Sunday are numbered by their Monday: the week
+        // from 2024-12-29 is 2025-W01, not a second 2024-W52
+        assert_eq!(path("2024-12-28", WeekStart::Sunday), "journal/2024-W52.md");
+        assert_eq!(path("2024-12-29", WeekStart::Sunday), "journal/2025-W01.md");
+        assert_eq!(path("2025-01-04", WeekStart::Sunday), "journal/2025-W01.md");
+        assert_eq!(path("2024-12-28", WeekStart::Saturday), "journal/2025-W01.md");
+    }
+
+    #[tokio::test]
+    async fn test_periodic_note() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("templates")).unwrap();
+        std::fs::write(dir.path().join("templates/week.md"), "# W{{week}} from {{date}}\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let settings = TemplateSettings {
+            weekly_template_path: Some("templates/week.md".to_string()),
+            ..Default::default()
+        };
+
+        let missing = periodic_note(&vault, &settings, date("2025-01-08"), NotePeriod::Weekly, false).await.unwrap();
+        assert_eq!(missing.note_id, None);
+        assert!(!vault.fs().exists(Path::new(&missing.path)).await);
+
+        let created = periodic_note(&vault, &settings, date("2025-01-08"), NotePeriod::Weekly, true).await.unwrap();
+        assert!(created.created);
+        assert_eq!(created.date, "2025-01-06");
+        let content = vault.read_note("journal/2025/2025-W02.md").await.unwrap();
+        assert!(content.starts_with("# W02 from 2025-01-06\n\n<!-- rollup:start -->"));
+
+        let existing = periodic_note(&vault, &settings, date("2025-01-12"), NotePeriod::Weekly, true).await.unwrap();
+        assert!(!existing.created);
+        assert_eq!(existing.note_id, created.note_id);
+    }
+
+    #[tokio::test]
+    async fn test_weekly_rollup() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let settings = TemplateSettings::default();
+
+        periodic_note(&vault, &settings, date("2025-01-06"), NotePeriod::Daily, true).await.unwrap();
+        let week = periodic_note(&vault, &settings, date("2025-01-08"), NotePeriod::Weekly, true).await.unwrap();
+        let content = vault.read_note(&week.path).await.unwrap();
+        assert!(content.contains("- [[2025-01-06|Monday]]\n\n## Completed"));
+        assert!(content.contains("value: \"2025-01-12\""));
+        assert!(content.contains("<!-- rollup:end -->\n\n## Review"));
+
+        // Creating a day of the week refreshes the rollup
+        periodic_note(&vault, &settings, date("2025-01-08"), NotePeriod::Daily, true).await.unwrap();
+        let content = vault.read_note(&week.path).await.unwrap();
+        assert!(content.contains("- [[2025-01-06|Monday]]\n- [[2025-01-08|Wednesday]]\n"));
+        assert_eq!(content.matches("<!-- rollup:start -->").count(), 1);
+
+        // A note without markers gets the rollup appended
+        vault.write_note(&week.path, "# My week\n").await.unwrap();
+        let updated = update_rollup(&vault, &settings, date("2025-01-06"), NotePeriod::Weekly).await.unwrap();
+        assert!(!updated.created);
+        let content = vault.read_note(&week.path).await.unwrap();
+        assert!(content.starts_with("# My week\n\n<!-- rollup:start -->\n"));
+        assert_eq!(update_rollup(&vault, &settings, date("2025-01-06"), NotePeriod::Weekly).await.unwrap().note_id, updated.note_id);
+        assert_eq!(vault.read_note(&week.path).await.unwrap(), content);
+
+        // An encrypted note is left alone
+        let encrypted = crate::encryption::encrypt_note("---\nencrypted: true\n---\n# My week\n", "secret").unwrap();
+        std::fs::write(dir.path().join(&week.path), &encrypted).unwrap();
+        let updated = update_rollup(&vault, &settings, date("2025-01-06"), NotePeriod::Weekly).await.unwrap();
+        assert!(!updated.created);
+        assert_eq!(std::fs::read_to_string(dir.path().join(&week.path)).unwrap(), encrypted);
+    }
+
+    #[tokio::test]
+    async fn test_monthly_rollup() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let settings = TemplateSettings::default();
+
+        periodic_note(&vault, &settings, date("2025-01-08"), NotePeriod::Weekly, true).await.unwrap();
+        let month = periodic_note(&vault, &settings, date("2025-01-20"), NotePeriod::Monthly, true).await.unwrap();
+        let content = vault.read_note(&month.path).await.unwrap();
+        assert!(content.starts_with("# January 2025\n\n## Goals\n\n<!-- rollup:start -->\n## Weeks\n\n- [[2025-W02|Week 02]]\n"));
+        assert!(content.contains("value: \"2025-01-01\""));
+        assert!(content.contains("value: \"2025-01-31\""));
+
+        // The week from 2025-01-27 is listed in January and February
+        let february = periodic_note(&vault, &settings, date("2025-02-03"), NotePeriod::Monthly, true).await.unwrap();
+        periodic_note(&vault, &settings, date("2025-01-29"), NotePeriod::Weekly, true).await.unwrap();
+        let content = vault.read_note(&month.path).await.unwrap();
+        assert!(content.contains("- [[2025-W02|Week 02]]\n- [[2025-W05|Week 05]]\n\n## Completed"));
+        let content = vault.read_note(&february.path).await.unwrap();
+        assert!(content.contains("## Weeks\n\n- [[2025-W05|Week 05]]\n"));
+    }
+
+    #[tokio::test]
+    async fn test_weekly_rollup_across_year_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let settings = TemplateSettings::default();
+
+        periodic_note(&vault, &settings, date("2024-12-31"), NotePeriod::Daily, true).await.unwrap();
+        periodic_note(&vault, &settings, date("2025-01-02"), NotePeriod::Daily, true).await.unwrap();
+        let week = periodic_note(&vault, &settings, date("2025-01-02"), NotePeriod::Weekly, true).await.unwrap();
+        assert_eq!(week.path, "journal/2025/2025-W01.md");
+        assert_eq!(week.date, "2024-12-30");
+
+        let content = vault.read_note(&week.path).await.unwrap();
+        assert!(content.starts_with("# Week 01, 2025\n"));
+        assert!(content.contains("- [[2024-12-31|Tuesday]]\n- [[2025-01-02|Thursday]]\n"));
+        assert!(content.contains("value: \"2024-12-30\""));
+        assert!(content.contains("value: \"2025-01-05\""));
+    }
+
+    #[tokio::test]
+    async fn test_weekly_note_honors_week_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault
+            .set_locale_settings(shared_types::LocaleSettings {
+                week_start: WeekStart::Sunday,
+                ..Default::default()
+            })
+            .await;
+        let settings = TemplateSettings::default();
+
+        let week = periodic_note(&vault, &settings, date("2025-01-08"), NotePeriod::Weekly, true).await.unwrap();
+        assert_eq!(week.date, "2025-01-05");
+        let content = vault.read_note(&week.path).await.unwrap();
+        assert!(content.contains("value: \"2025-01-11\""));
+
+        // A task due next week is due on Sunday
+        vault.write_note("Plan.md", "- [ ] Review ^next-week\n").await.unwrap();
+        let todos = vault.repo().get_todos_for_note(vault.repo().get_note_id_by_path("Plan.md").await.unwrap().unwrap()).await.unwrap();
+        let due = NaiveDate::parse_from_str(todos[0].due_date.as_deref().unwrap(), "%Y-%m-%d").unwrap();
+        assert_eq!(due.weekday(), chrono::Weekday::Sun);
+    }
+}
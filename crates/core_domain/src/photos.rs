@@ -0,0 +1,148 @@
+//! Photo import: filing camera images by the date they were taken.
+//!
+//! The capture time and GPS position are read from EXIF data. Photos without
+//! a capture time are filed by their file modification date.
+
+use crate::assets::store_asset;
+use crate::attachments::vault_folder;
+use crate::vault::{Result, Vault, VaultError};
+use chrono::{DateTime, Local, NaiveDateTime};
+use exif::{In, Reader, Tag, Value};
+use shared_types::ImportedPhoto;
+use std::path::Path;
+use tracing::{debug, instrument};
+
+/// Image formats EXIF data is read from.
+const PHOTO_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "tif", "tiff", "heic", "heif"];
+
+/// Capture time and position read from a photo.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhotoMetadata {
+    /// Capture time in the camera's local time.
+    pub taken_at: Option<NaiveDateTime>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Copy a photo into `<folder>/<year>/<month>/`. A photo already in the
+/// vault isn't copied again; the descriptor has the existing file's path.
+///
+/// The returned descriptor has no daily note yet; the caller adds the photo
+/// to one.
+#[instrument(skip(vault))]
+pub async fn import_photo(vault: &Vault, source: &Path, folder: &str) -> Result<ImportedPhoto> {
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|_| source.is_file() && is_photo(source))
+        .ok_or_else(|| VaultError::InvalidImport(format!("Not a photo: {}", source.display())))?;
+
+    let bytes = tokio::fs::read(source).await.map_err(core_fs::FsError::from)?;
+    let metadata = read_photo_metadata(&bytes);
+
+    let date = match metadata.taken_at {
+        Some(taken_at) => taken_at.date(),
+        None => {
+            let modified = tokio::fs::metadata(source)
+                .await
+                .and_then(|m| m.modified())
+                .map_err(core_fs::FsError::from)?;
+            DateTime::<Local>::from(modified).date_naive()
+        }
+    };
+
+    let target_folder = vault_folder(&format!("{}/{}", folder.trim_matches('/'), date.format("%Y/%m")))?;
+    let target = store_asset(vault, &target_folder, file_name, &bytes).await?;
+    debug!("Imported photo {} -> {}", source.display(), target.display());
+
+    Ok(ImportedPhoto {
+        source_path: source.to_string_lossy().to_string(),
+        path: target.to_string_lossy().to_string(),
+        date: date.format("%Y-%m-%d").to_string(),
+        taken_at: metadata
+            .taken_at
+            .map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        latitude: metadata.latitude,
+        longitude: metadata.longitude,
+        daily_note_path: None,
+    })
+}
+
+fn is_photo(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| PHOTO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Read the capture time and GPS position from a photo's EXIF data.
+///
+/// Missing or malformed fields are None.
+pub fn read_photo_metadata(bytes: &[u8]) -> PhotoMetadata {
+    let Ok(exif) = Reader::new().read_from_container(&mut std::io::Cursor::new(bytes)) else {
+        return PhotoMetadata::default();
+    };
+
+    let ascii = |tag: Tag| match exif.get_field(tag, In::PRIMARY).map(|f| &f.value) {
+        Some(Value::Ascii(values)) => values
+            .first()
+            .map(|v| String::from_utf8_lossy(v).trim().to_string()),
+        _ => None,
+    };
+    let coordinate = |tag: Tag, ref_tag: Tag, negative_ref: &str| {
+        let Some(Value::Rational(dms)) = exif.get_field(tag, In::PRIMARY).map(|f| &f.value) else {
+            return None;
+        };
+        let [degrees, minutes, seconds] = dms.as_slice() else {
+            return None;
+        };
+        let negative = ascii(ref_tag).is_some_and(|r| r.eq_ignore_ascii_case(negative_ref));
+        Some(dms_to_decimal(degrees.to_f64(), minutes.to_f64(), seconds.to_f64(), negative))
+    };
+
+    PhotoMetadata {
+        taken_at: ascii(Tag::DateTimeOriginal)
+            .or_else(|| ascii(Tag::DateTime))
+            .and_then(|s| parse_exif_datetime(&s)),
+        latitude: coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S").filter(|l| l.abs() <= 90.0),
+        longitude: coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, "W").filter(|l| l.abs() <= 180.0),
+    }
+}
+
+/// Parse an EXIF date-time ("2024:05:01 14:30:00").
+fn parse_exif_datetime(s: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, "%Y:%m:%d %H:%M:%S").ok()
+}
+
+/// Convert degrees, minutes, and seconds to decimal degrees.
+fn dms_to_decimal(degrees: f64, minutes: f64, seconds: f64, negative: bool) -> f64 {
+    let decimal = degrees + minutes / 60.0 + seconds / 3600.0;
+    if negative {
+        -decimal
+    } else {
+        decimal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exif_datetime() {
+        let parsed = parse_exif_datetime("2024:05:01 14:30:00").unwrap();
+        assert_eq!(parsed.format("%Y-%m-%d %H:%M").to_string(), "2024-05-01 14:30");
+        assert!(parse_exif_datetime("0000:00:00 00:00:00").is_none());
+        assert!(parse_exif_datetime("2024-05-01").is_none());
+    }
+
+    #[test]
+    fn test_dms_to_decimal() {
+        assert!((dms_to_decimal(48.0, 51.0, 29.0, false) - 48.858_055).abs() < 1e-5);
+        assert!((dms_to_decimal(74.0, 0.0, 21.6, true) + 74.006).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_read_photo_metadata_without_exif() {
+        assert_eq!(read_photo_metadata(b"not an image"), PhotoMetadata::default());
+    }
+}
@@ -0,0 +1,119 @@
+//! Batch property edits, as made in the property table.
+//!
+//! Properties live in the database, but a note can still have frontmatter
+//! (imported, or written by another tool). When an update changes a key
+//! the note's frontmatter also has, the frontmatter is rewritten too, so
+//! the file doesn't contradict the database. Keys only in the database stay
+//! there.
+
+use crate::encryption::has_encrypted_body;
+use crate::vault::{Result, Vault};
+use core_index::{delete_frontmatter_property, parse_frontmatter, set_frontmatter_list, set_frontmatter_property};
+use shared_types::{PropertyOperationResult, PropertyUpdate};
+use std::collections::BTreeMap;
+use tracing::{info, instrument};
+
+/// Apply property updates in one transaction, then sync the frontmatter of
+/// the notes that have the updated keys.
+#[instrument(skip(vault, updates))]
+pub async fn batch_set_properties(vault: &Vault, updates: &[PropertyUpdate]) -> Result<PropertyOperationResult> {
+    let result = vault.repo().batch_set_properties(updates).await?;
+
+    let mut by_note: BTreeMap<i64, Vec<&PropertyUpdate>> = BTreeMap::new();
+    for update in updates {
+        by_note.entry(update.note_id).or_default().push(update);
+    }
+
+    let mut synced = 0;
+    for (&note_id, updates) in &by_note {
+        let note = vault.repo().get_note(note_id).await?;
+        let content = vault.read_note(&note.path).await?;
+        if has_encrypted_body(&content) {
+            continue;
+        }
+
+        let updated = sync_frontmatter(&content, updates);
+        if updated != content {
+            vault.write_note(&note.path, &updated).await?;
+            synced += 1;
+        }
+    }
+
+    let note_ids: Vec<i64> = by_note.into_keys().collect();
+    vault.refresh_autocomplete(Some(&note_ids)).await?;
+
+    info!(
+        "Applied {} property updates to {} notes ({} frontmatter synced)",
+        result.affected_count, result.notes_affected, synced
+    );
+    Ok(result)
+}
+
+/// Apply the updates whose keys are in the content's frontmatter to it.
+fn sync_frontmatter(content: &str, updates: &[&PropertyUpdate]) -> String {
+    let (frontmatter, _) = parse_frontmatter(content);
+
+    let mut updated = content.to_string();
+    for update in updates.iter().filter(|u| frontmatter.properties.contains_key(&u.key)) {
+        updated = match (&update.items, update.value.as_deref()) {
+            (None, None) => delete_frontmatter_property(&updated, &update.key),
+            (Some(items), _) => set_frontmatter_list(&updated, &update.key, items),
+            (None, Some(value)) => {
+                set_frontmatter_property(&updated, &update.key, Some(value), update.property_type.as_deref())
+            }
+        };
+    }
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(note_id: i64, key: &str, value: Option<&str>) -> PropertyUpdate {
+        PropertyUpdate {
+            note_id,
+            key: key.to_string(),
+            value: value.map(String::from),
+            property_type: None,
+            items: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_set_properties_syncs_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let with_frontmatter = vault
+            .write_note("Book.md", "---\nstatus: reading\nauthor: Herbert\n---\n# Dune\n")
+            .await
+            .unwrap();
+        let without = vault.write_note("Plain.md", "# Plain\n").await.unwrap();
+
+        let result = batch_set_properties(
+            &vault,
+            &[
+                update(with_frontmatter, "status", Some("done")),
+                update(with_frontmatter, "author", None),
+                update(with_frontmatter, "rating", Some("5")),
+                update(without, "status", Some("new")),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.notes_affected, 2);
+
+        // Keys in the frontmatter are updated or removed there; new keys stay in the database
+        let content = vault.read_note("Book.md").await.unwrap();
+        let (frontmatter, body) = parse_frontmatter(&content);
+        assert_eq!(body.trim_start(), "# Dune\n");
+        assert!(matches!(frontmatter.properties.get("status"), Some(core_index::PropertyValue::String(s)) if s == "done"));
+        assert!(!frontmatter.properties.contains_key("author"));
+        assert!(!frontmatter.properties.contains_key("rating"));
+        assert_eq!(vault.read_note("Plain.md").await.unwrap(), "# Plain\n");
+
+        let rating = vault.repo().get_property(with_frontmatter, "rating").await.unwrap().unwrap();
+        assert_eq!(rating.value.as_deref(), Some("5"));
+        assert!(vault.repo().get_property(with_frontmatter, "author").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,722 @@
+//! Queries that roll up tasks from embedded notes, and the manual card
+//! order of query Kanban boards.
+//!
+//! With `include_embedded`, a query also returns the tasks of the notes the
+//! matching notes embed (`![[Sub-project]]`), one level deep, so a dashboard
+//! note lists the tasks of the notes it shows. Those tasks keep their own
+//! note path and carry the embedding note's path in `embedded_in`. A section
+//! embed (`![[Plan#Next]]`) rolls up the tasks of the whole note. The
+//! query's sort and limit apply to all tasks together.
+//!
+//! Cards moved by hand on a Kanban board get a fractional rank stored per
+//! board, so moving a card writes only its own rank. Ranked cards come
+//! first, in rank order; the others follow in query order. A board is
+//! named after the note and query block it is in, so editing the query
+//! keeps its card order.
+//!
+//! Query embed results are reused from the vault's query cache while the
+//! tables they were read from are unchanged (see `query_cache`).
+//!
+//! Filter keys and values can refer to the note the query is in, so one
+//! template works for every note made from it:
+//! - `{{this.<property>}}` - a property of the note
+//! - `{{this.file.name}}` - its file name without `.md`
+//! - `{{this.file.path}}` - its vault-relative path
+//! - `{{this.file.folder}}` - its folder (empty at the vault root)
+
+use crate::embeds::is_media;
+use crate::vault::{Result, Vault, VaultError};
+use core_fs::hash_content;
+use shared_types::{
+    QueryEmbed, QueryEmbedResponse, QueryRequest, QueryResponse, QueryResultItem, QueryResultType, QueryViewConfig,
+    NoteDto, PropertyDto, PropertyFilter, QueryViewType, TabResult,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{debug, instrument};
+
+/// Run a query, including the tasks of embedded notes if
+/// `include_embedded` is set. The request's sort and limit apply to all
+/// tasks together.
+#[instrument(skip(vault))]
+pub async fn run_query(vault: &Vault, request: &QueryRequest, include_embedded: bool) -> Result<QueryResponse> {
+    let mut response = vault.repo().run_query(request).await?;
+    if !include_embedded || matches!(request.result_type, QueryResultType::Notes) {
+        return Ok(response);
+    }
+
+    let note_ids = vault.repo().matching_note_ids(&request.filters, &request.match_mode).await?;
+    let embedded = embedded_notes(vault, &note_ids).await?;
+    if embedded.is_empty() {
+        return Ok(response);
+    }
+
+    // The query's own tasks and the embedded ones are sorted and limited
+    // together, so they replace the tasks found without embeds
+    let (tasks, total) = vault
+        .repo()
+        .query_tasks_with_embedded(request, &note_ids, &embedded)
+        .await?;
+    let own_tasks = response.results.iter().filter(|r| r.task.is_some()).count();
+    let items = tasks.into_iter().map(|task| QueryResultItem {
+        item_type: "task".to_string(),
+        properties: task.note_properties.clone(),
+        task: Some(task),
+        note: None,
+        rank: None,
+        item_ref: String::new(),
+    });
+    response.results.splice(..own_tasks, items);
+
+    debug!("Included tasks from {} embedded notes", embedded.len());
+    response.total_count = total;
+    Ok(response)
+}
+
+/// Run a query embed (its single query, or each of its tabs), reusing a
+/// recent result for the same query while the data it read is unchanged.
+/// `refresh` runs it again regardless. Failures are reported in the
+/// response's `error` and not cached.
+#[instrument(skip(vault, query))]
+pub async fn cached_query_embed(vault: &Vault, query: QueryEmbed, refresh: bool) -> Result<QueryEmbedResponse> {
+    let key = serde_json::to_string(&query).unwrap_or_default();
+    let fingerprint = vault.repo().get_data_fingerprint(&query_tables(&query)).await?;
+
+    if refresh {
+        vault.query_cache().remove(&key);
+    } else if let Some(response) = vault.query_cache().get(&key, &fingerprint) {
+        debug!("Query embed served from cache");
+        return Ok(response);
+    }
+
+    let response = execute_query_embed(vault, query).await;
+    if response.error.is_none() {
+        vault.query_cache().insert(key, fingerprint, response.clone());
+    }
+    Ok(response)
+}
+
+/// Start of a reference to the note a query is in.
+const NOTE_VARIABLE: &str = "{{this.";
+
+/// Replace the `{{this.…}}` variables in the filter keys and values of a
+/// query embed with the values of the note it is in, `note_id`. A query
+/// with variables needs that note, and each property it names.
+pub async fn resolve_note_variables(vault: &Vault, query: &mut QueryEmbed, note_id: Option<i64>) -> Result<()> {
+    let uses_variables = |filters: &[PropertyFilter]| {
+        filters
+            .iter()
+            .any(|f| f.key.contains(NOTE_VARIABLE) || f.value.as_deref().is_some_and(|v| v.contains(NOTE_VARIABLE)))
+    };
+    if !uses_variables(&query.filters) && !query.tabs.iter().any(|tab| uses_variables(&tab.filters)) {
+        return Ok(());
+    }
+    let Some(note_id) = note_id else {
+        return Err(VaultError::Query("{{this.…}} can only be used in a note".to_string()));
+    };
+
+    let note = vault.repo().get_note(note_id).await?;
+    let properties = vault.repo().get_properties_for_note(note_id).await?;
+    let filters = query.filters.iter_mut().chain(query.tabs.iter_mut().flat_map(|tab| tab.filters.iter_mut()));
+    for filter in filters {
+        filter.key = substitute_note_variables(&filter.key, &note, &properties)?;
+        if let Some(value) = &filter.value {
+            filter.value = Some(substitute_note_variables(value, &note, &properties)?);
+        }
+    }
+    Ok(())
+}
+
+/// Replace each `{{this.<name>}}` in `text` with the value it refers to.
+fn substitute_note_variables(text: &str, note: &NoteDto, properties: &[PropertyDto]) -> Result<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(NOTE_VARIABLE) {
+        let name_start = start + NOTE_VARIABLE.len();
+        let Some(len) = rest[name_start..].find("}}") else {
+            break;
+        };
+        let name = &rest[name_start..name_start + len];
+        result.push_str(&rest[..start]);
+        result.push_str(&note_variable(name, note, properties)?);
+        rest = &rest[name_start + len + 2..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// The value of `this.<name>` for a note.
+fn note_variable(name: &str, note: &NoteDto, properties: &[PropertyDto]) -> Result<String> {
+    let path = Path::new(&note.path);
+    let value = match name {
+        "file.name" => path.file_stem().map(|s| s.to_string_lossy().to_string()),
+        "file.path" => Some(note.path.clone()),
+        "file.folder" => Some(path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default()),
+        _ => properties
+            .iter()
+            .find(|p| p.key.eq_ignore_ascii_case(name))
+            .map(|p| p.value.clone().unwrap_or_default()),
+    };
+    value.ok_or_else(|| VaultError::Query(format!("{} has no property '{}'", note.path, name)))
+}
+
+/// The tables the results of a query embed are read from: filters read
+/// list items and tags (with their aliases) besides properties.
+fn query_tables(query: &QueryEmbed) -> Vec<&'static str> {
+    let mut tables = vec!["notes", "properties", "property_items", "tags", "tag_meta"];
+    let views = || std::iter::once((&query.result_type, &query.view)).chain(query.tabs.iter().map(|t| (&t.result_type, &t.view)));
+    if views().any(|(result_type, _)| !matches!(result_type, QueryResultType::Notes)) {
+        tables.push("todos");
+    }
+    if views().any(|(_, view)| matches!(view.view_type, QueryViewType::Kanban)) {
+        tables.push("board_ranks");
+    }
+    tables
+}
+
+/// Run a query embed: each of its tabs, or its single query if it has
+/// none. Kanban views come back in their manual card order.
+pub async fn execute_query_embed(vault: &Vault, mut query: QueryEmbed) -> QueryEmbedResponse {
+    let mut response = QueryEmbedResponse {
+        query: QueryEmbed::default(),
+        results: vec![],
+        total_count: 0,
+        tab_results: vec![],
+        error: None,
+    };
+
+    if !query.tabs.is_empty() {
+        for tab in &query.tabs {
+            let request = QueryRequest {
+                filters: tab.filters.clone(),
+                match_mode: tab.match_mode.clone(),
+                result_type: tab.result_type.clone(),
+                include_completed: tab.include_completed,
+                limit: Some(tab.limit),
+                sort: tab.view.sort.clone(),
+            };
+            let mut view = tab.view.clone();
+            match run_ranked_query(vault, &request, tab.include_embedded, &mut view).await {
+                Ok(result) => response.tab_results.push(TabResult {
+                    name: tab.name.clone(),
+                    results: result.results,
+                    total_count: result.total_count,
+                    view,
+                }),
+                Err(e) => {
+                    // One failing tab fails the whole embed
+                    response.tab_results.clear();
+                    response.error = Some(format!("Query execution failed for tab '{}': {}", tab.name, e));
+                    break;
+                }
+            }
+        }
+    } else {
+        let request = QueryRequest {
+            filters: query.filters.clone(),
+            match_mode: query.match_mode.clone(),
+            result_type: query.result_type.clone(),
+            include_completed: query.include_completed,
+            limit: Some(query.limit),
+            sort: query.view.sort.clone(),
+        };
+        match run_ranked_query(vault, &request, query.include_embedded, &mut query.view).await {
+            Ok(result) => {
+                debug!("Query completed: {} results", result.results.len());
+                response.results = result.results;
+                response.total_count = result.total_count;
+            }
+            Err(e) => response.error = Some(format!("Query execution failed: {}", e)),
+        }
+    }
+
+    response.query = query;
+    response
+}
+
+/// Run a query and order it for its view's board, if it's a Kanban view.
+async fn run_ranked_query(
+    vault: &Vault,
+    request: &QueryRequest,
+    include_embedded: bool,
+    view: &mut QueryViewConfig,
+) -> Result<QueryResponse> {
+    let mut response = run_query(vault, request, include_embedded).await?;
+    apply_board_ranks(vault, request, view, &mut response.results).await?;
+    Ok(response)
+}
+
+/// Name the boards of the Kanban views of a query embed that don't name
+/// one after the note the block is in and the block's position among the
+/// note's query blocks (from 0): `note:<id>:<block>`, with `:<tab>` added
+/// for each tab.
+pub fn assign_boards(query: &mut QueryEmbed, note_id: i64, block_index: usize) {
+    fn assign(view: &mut QueryViewConfig, board: String) {
+        if matches!(view.view_type, QueryViewType::Kanban) {
+            view.kanban.get_or_insert_with(Default::default).board.get_or_insert(board);
+        }
+    }
+
+    let board = format!("note:{}:{}", note_id, block_index);
+    if query.tabs.is_empty() {
+        assign(&mut query.view, board);
+    } else {
+        for (i, tab) in query.tabs.iter_mut().enumerate() {
+            assign(&mut tab.view, format!("{}:{}", board, i));
+        }
+    }
+}
+
+/// Order the results of a Kanban view by their board ranks, with their
+/// `item_ref` filled in. A view without a board (see `assign_boards`), as
+/// for a query that isn't in a note, gets one named after the query, so
+/// the board can be reordered. Other views are left alone.
+pub async fn apply_board_ranks(
+    vault: &Vault,
+    request: &QueryRequest,
+    view: &mut QueryViewConfig,
+    results: &mut [QueryResultItem],
+) -> Result<()> {
+    if !matches!(view.view_type, QueryViewType::Kanban) {
+        return Ok(());
+    }
+    let kanban = view.kanban.get_or_insert_with(Default::default);
+    let board_key = kanban
+        .board
+        .get_or_insert_with(|| {
+            let query = serde_json::json!([request.filters, request.match_mode, request.result_type, kanban.group_by]);
+            format!("query:{}", hash_content(&query.to_string()))
+        })
+        .clone();
+
+    assign_item_refs(vault, results).await?;
+    let ranks = vault.repo().get_board_ranks(&board_key).await?;
+    for item in results.iter_mut() {
+        item.rank = ranks.get(&item.item_ref).copied();
+    }
+    results.sort_by(|a, b| match (a.rank, b.rank) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    Ok(())
+}
+
+/// Fill in the `item_ref` of each result. Tasks are counted per text
+/// among all tasks of their note, completed ones included, so a task keeps
+/// its reference while others are ticked off.
+async fn assign_item_refs(vault: &Vault, results: &mut [QueryResultItem]) -> Result<()> {
+    let note_ids: HashSet<i64> = results.iter().filter_map(|r| r.task.as_ref()).map(|t| t.todo.note_id).collect();
+    let mut task_refs = HashMap::new();
+    for note_id in note_ids {
+        let mut todos = vault.repo().get_todos_for_note(note_id).await?;
+        todos.sort_by_key(|todo| (todo.line_number, todo.id));
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for todo in todos {
+            let hash = hash_content(&todo.description);
+            let n = seen.entry(hash.clone()).or_default();
+            task_refs.insert(todo.id, format!("task:{}:{}:{}", note_id, hash, n));
+            *n += 1;
+        }
+    }
+
+    for item in results.iter_mut() {
+        item.item_ref = match (&item.task, &item.note) {
+            (Some(task), _) => task_refs.get(&task.todo.id).cloned().unwrap_or_default(),
+            (None, Some(note)) => format!("note:{}", note.id),
+            (None, None) => String::new(),
+        };
+    }
+    Ok(())
+}
+
+/// Move a card on a board between the cards `before` and `after` (the ones
+/// that end up above and below it), returning its new rank. A card above
+/// it without a rank yet is ranked after all others first, since unranked
+/// cards come last. Fails if `before` is not ranked above `after`.
+#[instrument(skip(vault))]
+pub async fn reorder_query_result(
+    vault: &Vault,
+    board_key: &str,
+    item_ref: &str,
+    before: Option<&str>,
+    after: Option<&str>,
+) -> Result<f64> {
+    let repo = vault.repo();
+    let mut ranks = repo.get_board_ranks(board_key).await?;
+    ranks.remove(item_ref);
+    let last = ranks.values().copied().reduce(f64::max);
+
+    let above = match before {
+        Some(before) => match ranks.get(before) {
+            Some(rank) => Some(*rank),
+            None => {
+                let rank = last.map_or(0.0, |last| last + 1.0);
+                repo.set_board_rank(board_key, before, rank).await?;
+                Some(rank)
+            }
+        },
+        None => None,
+    };
+    let below = after.and_then(|after| ranks.get(after).copied());
+
+    let rank = match (above, below) {
+        (Some(above), Some(below)) => {
+            if above >= below {
+                let message = format!("{:?} is not above {:?} on board {}", before, after, board_key);
+                return Err(VaultError::Query(message));
+            }
+            let rank = (above + below) / 2.0;
+            if rank <= above || rank >= below {
+                // No room left between them: renumber the board and retry
+                repo.rebalance_board_ranks(board_key).await?;
+                return Box::pin(reorder_query_result(vault, board_key, item_ref, before, after)).await;
+            }
+            rank
+        }
+        (Some(above), None) => above + 1.0,
+        (None, Some(below)) => below - 1.0,
+        (None, None) => last.map_or(0.0, |last| last + 1.0),
+    };
+
+    debug!("Moved {} on board {} to rank {}", item_ref, board_key, rank);
+    repo.set_board_rank(board_key, item_ref, rank).await?;
+    Ok(rank)
+}
+
+/// The notes embedded in the notes `note_ids`, other than those notes
+/// themselves, each with the path of the first note embedding it. Embeds
+/// are taken from the index, so the notes aren't read.
+async fn embedded_notes(vault: &Vault, note_ids: &[i64]) -> Result<Vec<(i64, String)>> {
+    let targets = vault.repo().list_embed_targets(note_ids).await?;
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+    let resolver = vault.note_resolver().await?;
+    let matching: HashSet<i64> = note_ids.iter().copied().collect();
+    let paths: HashMap<i64, &str> = resolver
+        .notes()
+        .iter()
+        .filter(|n| matching.contains(&n.id))
+        .map(|n| (n.id, n.path.as_str()))
+        .collect();
+
+    let mut embedded: Vec<(i64, String)> = Vec::new();
+    for (note_id, target) in &targets {
+        let Some(path) = paths.get(note_id) else {
+            continue;
+        };
+        if is_media(target) {
+            continue;
+        }
+        let Some(note) = resolver.resolve(target, Some(path)) else {
+            continue;
+        };
+        if !matching.contains(&note.id) && !embedded.iter().any(|(id, _)| *id == note.id) {
+            embedded.push((note.id, path.to_string()));
+        }
+    }
+
+    Ok(embedded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::{FilterMatchMode, PropertyOperator, QuerySort, QueryTab, SortDirection};
+
+    #[tokio::test]
+    async fn test_run_query_includes_embedded_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let alpha = vault
+            .write_note("projects/Alpha.md", "# Alpha\n\n- [ ] Plan\n\n![[Design]]\n![[Research#Open]]\n")
+            .await
+            .unwrap();
+        vault.repo().set_property(alpha, "project", Some("alpha"), None).await.unwrap();
+        vault.write_note("projects/Design.md", "# Design\n\n- [ ] Sketch\n\n![[Deeper]]\n").await.unwrap();
+        vault.write_note("projects/Research.md", "# Research\n\n## Open\n\n- [ ] Read\n").await.unwrap();
+        vault.write_note("projects/Deeper.md", "# Deeper\n\n- [ ] Not included\n").await.unwrap();
+
+        let request = QueryRequest {
+            filters: vec![PropertyFilter {
+                key: "project".to_string(),
+                operator: PropertyOperator::Equals,
+                value: Some("alpha".to_string()),
+            }],
+            match_mode: FilterMatchMode::All,
+            result_type: QueryResultType::Tasks,
+            include_completed: false,
+            limit: Some(50),
+            sort: None,
+        };
+
+        let response = run_query(&vault, &request, false).await.unwrap();
+        assert_eq!(response.total_count, 1);
+
+        let response = run_query(&vault, &request, true).await.unwrap();
+        let tasks: Vec<(&str, &str, Option<&str>)> = response
+            .results
+            .iter()
+            .filter_map(|r| r.task.as_ref())
+            .map(|t| (t.todo.description.as_str(), t.note_path.as_str(), t.embedded_in.as_deref()))
+            .collect();
+        assert_eq!(tasks.len(), 3);
+        assert!(tasks.contains(&("Plan", "projects/Alpha.md", None)));
+        assert!(tasks.contains(&("Sketch", "projects/Design.md", Some("projects/Alpha.md"))));
+        assert!(tasks.contains(&("Read", "projects/Research.md", Some("projects/Alpha.md"))));
+        assert_eq!(response.total_count, 3);
+
+        // The limit covers the embedded tasks too, the total doesn't
+        let limited = QueryRequest { limit: Some(2), ..request.clone() };
+        let response = run_query(&vault, &limited, true).await.unwrap();
+        assert_eq!((response.results.len(), response.total_count), (2, 3));
+
+        // The sort orders the embedded tasks with the note's own
+        let sorted = QueryRequest {
+            sort: Some(QuerySort {
+                property: "_path".to_string(),
+                direction: SortDirection::Desc,
+            }),
+            ..request
+        };
+        let response = run_query(&vault, &sorted, true).await.unwrap();
+        let order: Vec<&str> = response
+            .results
+            .iter()
+            .filter_map(|r| r.task.as_ref())
+            .map(|t| t.todo.description.as_str())
+            .collect();
+        assert_eq!(order, ["Read", "Sketch", "Plan"]);
+    }
+
+    #[tokio::test]
+    async fn test_board_ranks_order_kanban_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        for name in ["A", "B", "C", "D"] {
+            let id = vault.write_note(&format!("{}.md", name), &format!("# {}\n", name)).await.unwrap();
+            vault.repo().set_property(id, "status", Some("todo"), None).await.unwrap();
+        }
+
+        let request = QueryRequest {
+            filters: vec![PropertyFilter {
+                key: "status".to_string(),
+                operator: PropertyOperator::Exists,
+                value: None,
+            }],
+            match_mode: FilterMatchMode::All,
+            result_type: QueryResultType::Notes,
+            include_completed: false,
+            limit: Some(50),
+            sort: None,
+        };
+        let mut view = QueryViewConfig {
+            view_type: QueryViewType::Kanban,
+            ..Default::default()
+        };
+        let order = |results: &[QueryResultItem]| -> Vec<String> {
+            results.iter().map(|r| r.note.as_ref().unwrap().path.clone()).collect()
+        };
+        let refs: Vec<String> = {
+            let mut results = vault.repo().run_query(&request).await.unwrap().results;
+            apply_board_ranks(&vault, &request, &mut view, &mut results).await.unwrap();
+            assert_eq!(order(&results), ["A.md", "B.md", "C.md", "D.md"]);
+            assert!(results.iter().all(|r| r.rank.is_none()));
+            results.iter().map(|r| r.item_ref.clone()).collect()
+        };
+        let board = view.kanban.as_ref().unwrap().board.clone().unwrap();
+
+        // D to the top, then C between D and A
+        reorder_query_result(&vault, &board, &refs[3], None, Some(&refs[0])).await.unwrap();
+        reorder_query_result(&vault, &board, &refs[2], Some(&refs[3]), Some(&refs[0])).await.unwrap();
+
+        // The same query finds the same board again
+        let mut again = QueryViewConfig {
+            view_type: QueryViewType::Kanban,
+            ..Default::default()
+        };
+        let mut results = vault.repo().run_query(&request).await.unwrap().results;
+        apply_board_ranks(&vault, &request, &mut again, &mut results).await.unwrap();
+        assert_eq!(again.kanban.unwrap().board.unwrap(), board);
+        assert_eq!(order(&results), ["D.md", "C.md", "A.md", "B.md"]);
+
+        // Repeated moves into the same gap renumber the board when it runs out
+        let top = refs[3].clone();
+        for _ in 0..60 {
+            let middle = refs[2].clone();
+            reorder_query_result(&vault, &board, &refs[1], Some(&top), Some(&middle)).await.unwrap();
+            reorder_query_result(&vault, &board, &middle, Some(&top), Some(&refs[1])).await.unwrap();
+        }
+        let ranks = vault.repo().get_board_ranks(&board).await.unwrap();
+        assert!(ranks[&top] < ranks[&refs[2]] && ranks[&refs[2]] < ranks[&refs[1]]);
+        assert!(!ranks.contains_key(&refs[0]));
+
+        // Cards the wrong way round, or the same card on both sides, can't
+        // have a card between them
+        for (before, after) in [(&refs[1], &top), (&top, &top)] {
+            let result = reorder_query_result(&vault, &board, &refs[2], Some(before), Some(after)).await;
+            assert!(matches!(result, Err(VaultError::Query(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_board_refs_stay_with_their_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.write_note("Chores.md", "# Chores\n\n- [ ] Water plants\n- [ ] Water plants\n").await.unwrap();
+
+        let request = QueryRequest {
+            filters: vec![],
+            match_mode: FilterMatchMode::All,
+            result_type: QueryResultType::Tasks,
+            include_completed: false,
+            limit: Some(50),
+            sort: None,
+        };
+        let refs = || async {
+            let mut view = QueryViewConfig {
+                view_type: QueryViewType::Kanban,
+                ..Default::default()
+            };
+            let mut results = run_query(&vault, &request, false).await.unwrap().results;
+            apply_board_ranks(&vault, &request, &mut view, &mut results).await.unwrap();
+            results.into_iter().map(|r| r.item_ref).collect::<Vec<_>>()
+        };
+
+        // The same tasks are told apart
+        let before = refs().await;
+        assert_eq!(before.len(), 2);
+        assert_ne!(before[0], before[1]);
+
+        // Lines added above a task, or an earlier same task ticked off,
+        // keep its reference
+        vault
+            .write_note("Chores.md", "# Chores\n\nMonday\n\n- [ ] Water plants\n- [ ] Water plants\n")
+            .await
+            .unwrap();
+        assert_eq!(refs().await, before);
+        vault
+            .write_note("Chores.md", "# Chores\n\nMonday\n\n- [x] Water plants\n- [ ] Water plants\n")
+            .await
+            .unwrap();
+        assert_eq!(refs().await, before[1..]);
+    }
+
+    #[test]
+    fn test_assign_boards() {
+        let kanban = QueryViewConfig {
+            view_type: QueryViewType::Kanban,
+            ..Default::default()
+        };
+        let mut query = QueryEmbed {
+            view: kanban.clone(),
+            ..Default::default()
+        };
+        assign_boards(&mut query, 7, 1);
+        assert_eq!(query.view.kanban.unwrap().board.as_deref(), Some("note:7:1"));
+
+        // Each tab has a board of its own, and named boards are kept
+        let tab = |view: QueryViewConfig| QueryTab {
+            name: "Tab".to_string(),
+            filters: vec![],
+            match_mode: FilterMatchMode::All,
+            result_type: QueryResultType::Tasks,
+            include_completed: false,
+            include_embedded: false,
+            limit: 50,
+            view,
+        };
+        let mut named = kanban.clone();
+        named.kanban.get_or_insert_with(Default::default).board = Some("chores".to_string());
+        let mut query = QueryEmbed {
+            tabs: vec![tab(kanban), tab(QueryViewConfig::default()), tab(named)],
+            ..Default::default()
+        };
+        assign_boards(&mut query, 7, 0);
+        let boards: Vec<_> = query.tabs.iter().map(|t| t.view.kanban.as_ref().and_then(|k| k.board.as_deref())).collect();
+        assert_eq!(boards, [Some("note:7:0:0"), None, Some("chores")]);
+    }
+
+    #[tokio::test]
+    async fn test_query_embed_results_cached_until_data_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let id = vault.write_note("Book.md", "# Book\n").await.unwrap();
+        vault.repo().set_property(id, "status", Some("reading"), None).await.unwrap();
+
+        let query = QueryEmbed {
+            filters: vec![PropertyFilter {
+                key: "status".to_string(),
+                operator: PropertyOperator::Equals,
+                value: Some("reading".to_string()),
+            }],
+            result_type: QueryResultType::Notes,
+            ..Default::default()
+        };
+        let response = cached_query_embed(&vault, query.clone(), false).await.unwrap();
+        assert_eq!(response.total_count, 1);
+        assert_eq!(vault.query_cache().len(), 1);
+
+        // A property changed in place is a miss
+        vault.repo().set_property(id, "status", Some("done"), None).await.unwrap();
+        assert_eq!(cached_query_embed(&vault, query.clone(), false).await.unwrap().total_count, 0);
+
+        // Unchanged data is a hit, even if the cached result is stale
+        vault.query_cache().clear();
+        cached_query_embed(&vault, query.clone(), false).await.unwrap();
+        let fingerprint = vault.repo().get_data_fingerprint(&query_tables(&query)).await.unwrap();
+        let key = serde_json::to_string(&query).unwrap();
+        let mut stale = vault.query_cache().get(&key, &fingerprint).unwrap();
+        stale.total_count = 7;
+        vault.query_cache().insert(key, fingerprint, stale);
+        assert_eq!(cached_query_embed(&vault, query.clone(), false).await.unwrap().total_count, 7);
+        assert_eq!(cached_query_embed(&vault, query, true).await.unwrap().total_count, 0);
+
+        // Saving a note clears the cache
+        vault.write_note("Other.md", "# Other\n").await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(vault.query_cache().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_note_variables_resolved_in_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let host = vault.write_note("projects/Garden.md", "# Garden\n").await.unwrap();
+        vault.repo().set_property(host, "project", Some("garden"), None).await.unwrap();
+
+        let filter = |key: &str, value: &str| PropertyFilter {
+            key: key.to_string(),
+            operator: PropertyOperator::Equals,
+            value: Some(value.to_string()),
+        };
+        let mut query = QueryEmbed {
+            filters: vec![filter("project", "{{this.project}}"), filter("journal_date", "{{this.file.name}}")],
+            tabs: vec![QueryTab {
+                name: "Folder".to_string(),
+                filters: vec![filter("area", "{{this.file.folder}}/{{this.file.path}}")],
+                match_mode: FilterMatchMode::All,
+                result_type: QueryResultType::Tasks,
+                include_completed: false,
+                include_embedded: false,
+                limit: 50,
+                view: QueryViewConfig::default(),
+            }],
+            ..Default::default()
+        };
+        let mut missing = query.clone();
+
+        resolve_note_variables(&vault, &mut query, Some(host)).await.unwrap();
+        assert_eq!(query.filters[0].value.as_deref(), Some("garden"));
+        assert_eq!(query.filters[1].value.as_deref(), Some("Garden"));
+        assert_eq!(query.tabs[0].filters[0].value.as_deref(), Some("projects/projects/Garden.md"));
+
+        // Without the note or one of its properties the query can't run
+        assert!(resolve_note_variables(&vault, &mut missing.clone(), None).await.is_err());
+        missing.filters[0].value = Some("{{this.client}}".to_string());
+        let error = resolve_note_variables(&vault, &mut missing, Some(host)).await.unwrap_err();
+        assert!(error.to_string().contains("client"));
+    }
+}
@@ -0,0 +1,103 @@
+//! Cache of query embed results.
+//!
+//! A note with several ```query``` blocks runs all of them whenever it is
+//! rendered. Results are kept for a short time, keyed by the parsed query
+//! (so the YAML's formatting doesn't matter) together with a fingerprint of
+//! the tables the query reads, so a change to any of them is a miss. Vault
+//! events (notes reindexed or deleted, a full index) clear the cache too.
+
+use crate::vault::VaultEvent;
+use shared_types::QueryEmbedResponse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// How long a result is reused for, at most. Relative dates in filters
+/// ("today") are the main thing a fingerprint can't see.
+const QUERY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Queries kept at most; the oldest result is dropped first.
+const QUERY_CACHE_SIZE: usize = 100;
+
+struct CachedQuery {
+    fingerprint: String,
+    stored_at: Instant,
+    response: QueryEmbedResponse,
+}
+
+/// Recent query embed results, by normalized query.
+#[derive(Default)]
+pub struct QueryCache {
+    entries: Mutex<HashMap<String, CachedQuery>>,
+}
+
+impl QueryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The result stored for `key`, if it is recent and was built from
+    /// the data `fingerprint` describes.
+    pub fn get(&self, key: &str, fingerprint: &str) -> Option<QueryEmbedResponse> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(key)
+            .filter(|cached| cached.fingerprint == fingerprint && cached.stored_at.elapsed() < QUERY_CACHE_TTL)
+            .map(|cached| cached.response.clone())
+    }
+
+    /// Store the result of a query.
+    pub fn insert(&self, key: String, fingerprint: String, response: QueryEmbedResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, cached| cached.stored_at.elapsed() < QUERY_CACHE_TTL);
+        if entries.len() >= QUERY_CACHE_SIZE && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.stored_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            CachedQuery {
+                fingerprint,
+                stored_at: Instant::now(),
+                response,
+            },
+        );
+    }
+
+    /// Drop the result of one query, so it runs again.
+    pub fn remove(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    /// Drop all results.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Number of stored results.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether no results are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Clear the cache whenever notes change, until the vault is dropped.
+pub(crate) async fn invalidate_on_changes(mut events: broadcast::Receiver<VaultEvent>, cache: Arc<QueryCache>) {
+    loop {
+        match events.recv().await {
+            Ok(VaultEvent::JobProgress(_)) => {}
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => cache.clear(),
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
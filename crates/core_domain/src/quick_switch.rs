@@ -0,0 +1,338 @@
+//! In-memory index for the quick switcher (Ctrl+P).
+//!
+//! Titles, aliases, paths, and headings are loaded once from the database and
+//! fuzzy-matched in memory on every keystroke. The note a link with the
+//! query as its target would open ranks first, and notes whose name is a
+//! near-typo of the query are offered last. The vault rebuilds the index
+//! when the repository fingerprint changes.
+
+use crate::resolve::NoteResolver;
+use shared_types::{NoteListItem, NoteMatchKind, QuickSwitchKind, QuickSwitchResult};
+use std::collections::HashMap;
+
+/// Bonus for the most recently opened note; older ones get less.
+const RECENT_BONUS: i64 = 20;
+
+/// Penalty that ranks heading and path matches below title and alias matches.
+const SECONDARY_PENALTY: i64 = 10;
+
+/// Bonus for the note the query resolves to, as a link target.
+const RESOLVED_BONUS: i64 = 100;
+
+struct SwitcherNote {
+    id: i64,
+    path: String,
+    title: Option<String>,
+    aliases: Vec<String>,
+    headings: Vec<(String, i64)>,
+}
+
+/// Snapshot of everything the quick switcher can match.
+pub struct QuickSwitchIndex {
+    fingerprint: String,
+    notes: Vec<SwitcherNote>,
+    resolver: NoteResolver<'static>,
+}
+
+impl QuickSwitchIndex {
+    /// Build the index from notes, (note_id, alias) pairs, and
+    /// (note_id, heading, line_number) triples.
+    pub fn new(
+        fingerprint: String,
+        notes: Vec<NoteListItem>,
+        aliases: Vec<(i64, String)>,
+        headings: Vec<(i64, String, i64)>,
+    ) -> Self {
+        let resolver = NoteResolver::new(notes.clone()).with_aliases(aliases.clone());
+        let mut notes: Vec<SwitcherNote> = notes
+            .into_iter()
+            .map(|n| SwitcherNote {
+                id: n.id,
+                path: n.path,
+                title: n.title,
+                aliases: Vec::new(),
+                headings: Vec::new(),
+            })
+            .collect();
+
+        let positions: HashMap<i64, usize> = notes.iter().enumerate().map(|(i, n)| (n.id, i)).collect();
+        for (note_id, alias) in aliases {
+            if let Some(&i) = positions.get(&note_id) {
+                notes[i].aliases.push(alias);
+            }
+        }
+        for (note_id, text, line_number) in headings {
+            if let Some(&i) = positions.get(&note_id) {
+                notes[i].headings.push((text, line_number));
+            }
+        }
+
+        Self {
+            fingerprint,
+            notes,
+            resolver,
+        }
+    }
+
+    /// The repository fingerprint this index was built from.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Rank matches for `query`, best first.
+    ///
+    /// Each note appears at most once for its best title, alias, or path
+    /// match, plus once per matching heading. Recently opened notes
+    /// (`recent`, most recent first) get a bonus; an empty query lists them.
+    /// Near-typos of a note's name, title or aliases come after all other
+    /// matches.
+    pub fn search(&self, query: &str, recent: &[i64], limit: usize) -> Vec<QuickSwitchResult> {
+        let recent_bonus = |id: i64| {
+            recent
+                .iter()
+                .position(|r| *r == id)
+                .map(|p| (RECENT_BONUS - 2 * p as i64).max(0))
+                .unwrap_or(0)
+        };
+
+        if query.trim().is_empty() {
+            let by_id: HashMap<i64, &SwitcherNote> = self.notes.iter().map(|n| (n.id, n)).collect();
+            return recent
+                .iter()
+                .filter_map(|id| by_id.get(id))
+                .take(limit)
+                .map(|note| result(note, QuickSwitchKind::Recent, display_name(note), None, recent_bonus(note.id)))
+                .collect();
+        }
+
+        let mut results = Vec::new();
+        for note in &self.notes {
+            let title = note.title.as_deref().map(|t| (QuickSwitchKind::Title, t, 0));
+            let aliases = note.aliases.iter().map(|a| (QuickSwitchKind::Alias, a.as_str(), 0));
+            let path = note.path.strip_suffix(".md").unwrap_or(&note.path);
+
+            let best = title
+                .into_iter()
+                .chain(aliases)
+                .chain(std::iter::once((QuickSwitchKind::Path, path, SECONDARY_PENALTY)))
+                .filter_map(|(kind, text, penalty)| {
+                    fuzzy_score(query, text).map(|score| (score - penalty, kind, text))
+                })
+                .max_by_key(|(score, _, _)| *score);
+
+            if let Some((score, kind, text)) = best {
+                results.push(result(note, kind, text, None, score + recent_bonus(note.id)));
+            }
+
+            for (heading, line_number) in &note.headings {
+                if let Some(score) = fuzzy_score(query, heading) {
+                    results.push(result(
+                        note,
+                        QuickSwitchKind::Heading,
+                        heading,
+                        Some(*line_number),
+                        score - SECONDARY_PENALTY + recent_bonus(note.id),
+                    ));
+                }
+            }
+        }
+
+        if let Some(resolved) = self.resolver.resolve(query, None) {
+            let existing = results
+                .iter_mut()
+                .find(|r| r.note_id == resolved.id && r.kind != QuickSwitchKind::Heading);
+            if let Some(existing) = existing {
+                existing.score += RESOLVED_BONUS;
+            }
+        }
+
+        let typos = self.resolver.rank(query, None, limit).candidates;
+        for candidate in typos.into_iter().filter(|c| c.kind == NoteMatchKind::Fuzzy) {
+            if results.iter().any(|r| r.note_id == candidate.note_id) {
+                continue;
+            }
+            if let Some(note) = self.notes.iter().find(|n| n.id == candidate.note_id) {
+                let kind = if note.title.is_some() {
+                    QuickSwitchKind::Title
+                } else {
+                    QuickSwitchKind::Path
+                };
+                results.push(result(note, kind, display_name(note), None, -SECONDARY_PENALTY));
+            }
+        }
+
+        results.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| a.line_number.cmp(&b.line_number))
+        });
+        results.truncate(limit);
+        results
+    }
+}
+
+/// Title of a note, falling back to its path.
+fn display_name(note: &SwitcherNote) -> &str {
+    note.title.as_deref().unwrap_or(&note.path)
+}
+
+fn result(
+    note: &SwitcherNote,
+    kind: QuickSwitchKind,
+    text: &str,
+    line_number: Option<i64>,
+    score: i64,
+) -> QuickSwitchResult {
+    QuickSwitchResult {
+        kind,
+        note_id: note.id,
+        path: note.path.clone(),
+        title: note.title.clone(),
+        text: text.to_string(),
+        line_number,
+        score,
+    }
+}
+
+/// Score `candidate` as a case-insensitive subsequence match of `query`.
+///
+/// Whitespace in the query is ignored. Matches at word starts, consecutive
+/// matches, prefixes, and exact matches score higher; longer candidates score
+/// slightly lower. Returns None if the query isn't a subsequence.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for (i, &c) in lower.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if c != query[matched] {
+            continue;
+        }
+
+        score += 1;
+        let word_start = i == 0
+            || !chars[i - 1].is_alphanumeric()
+            || (chars[i - 1].is_lowercase() && chars[i].is_uppercase());
+        if word_start {
+            score += 8;
+        }
+        if previous.is_some_and(|p| p + 1 == i) {
+            score += 5;
+        }
+        previous = Some(i);
+        matched += 1;
+    }
+    if matched < query.len() {
+        return None;
+    }
+
+    if lower.starts_with(&query) {
+        score += if lower.len() == query.len() { 50 } else { 20 };
+    }
+    score -= (chars.len().saturating_sub(query.len()) / 4) as i64;
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: i64, path: &str, title: Option<&str>) -> NoteListItem {
+        NoteListItem {
+            id,
+            path: path.to_string(),
+            title: title.map(str::to_string),
+            pinned: false,
+        }
+    }
+
+    fn index() -> QuickSwitchIndex {
+        QuickSwitchIndex::new(
+            "v1".to_string(),
+            vec![
+                note(1, "projects/neuroflow.md", Some("NeuroFlow Roadmap")),
+                note(2, "people/ada.md", Some("Ada Lovelace")),
+                note(3, "inbox/misc.md", None),
+            ],
+            vec![(2, "Countess".to_string())],
+            vec![(1, "Release Plan".to_string(), 5), (3, "Reading list".to_string(), 2)],
+        )
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("nfr", "NeuroFlow Roadmap").is_some());
+        assert!(fuzzy_score("xyz", "NeuroFlow Roadmap").is_none());
+
+        // Exact beats prefix beats scattered
+        let exact = fuzzy_score("ada", "ada").unwrap();
+        let prefix = fuzzy_score("ada", "ada lovelace").unwrap();
+        let scattered = fuzzy_score("ada", "a big dashboard").unwrap();
+        assert!(exact > prefix && prefix > scattered);
+
+        // Word starts count
+        assert!(fuzzy_score("rp", "Release Plan").unwrap() > fuzzy_score("rp", "warp drive").unwrap());
+    }
+
+    #[test]
+    fn test_search_ranks_and_tags_results() {
+        let index = index();
+
+        let results = index.search("countess", &[], 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, QuickSwitchKind::Alias);
+        assert_eq!(results[0].note_id, 2);
+
+        let results = index.search("release", &[], 10);
+        assert_eq!(results[0].kind, QuickSwitchKind::Heading);
+        assert_eq!(results[0].line_number, Some(5));
+
+        let results = index.search("neuroflow", &[], 10);
+        assert_eq!(results[0].kind, QuickSwitchKind::Title);
+        assert_eq!(results.iter().filter(|r| r.note_id == 1).count(), 1);
+
+        let results = index.search("inbox misc", &[], 10);
+        assert_eq!(results[0].kind, QuickSwitchKind::Path);
+        assert_eq!(results[0].note_id, 3);
+
+        // A link target ranks its note first; near-typos come last
+        let results = index.search("ada", &[], 10);
+        assert_eq!(results[0].note_id, 2);
+        let results = index.search("Ada Lovelcae", &[], 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, QuickSwitchKind::Title);
+        assert!(results[0].score < 0);
+    }
+
+    #[test]
+    fn test_recent_notes() {
+        let index = index();
+
+        let results = index.search("", &[3, 1, 99], 10);
+        let ids: Vec<_> = results.iter().map(|r| r.note_id).collect();
+        assert_eq!(ids, vec![3, 1]);
+        assert!(results.iter().all(|r| r.kind == QuickSwitchKind::Recent));
+        assert_eq!(results[0].text, "inbox/misc.md");
+
+        // Recently opened notes get a bonus that can change the order
+        let base = index.search("misc", &[], 10)[0].score;
+        assert_eq!(index.search("misc", &[3], 10)[0].score, base + RECENT_BONUS);
+        assert_eq!(index.search("o", &[], 10)[0].note_id, 2);
+        assert_eq!(index.search("o", &[1], 10)[0].note_id, 1);
+    }
+}
@@ -0,0 +1,314 @@
+//! Note rendering: sanitized HTML with wikilinks and embeds resolved.
+//!
+//! Wikilinks become internal links (`options.link_prefix` + vault path),
+//! note embeds are inlined up to `options.max_embed_depth`, and images are
+//! referenced by their vault path. Used for export, sharing, and print.
+//! The note's `cssclasses` are returned with the HTML so callers can apply
+//! per-note layouts (wide tables, slides). Diagrams are included as SVG
+//! when they are cached or a renderer is configured, and math as MathML
+//! (or TeX, per `options.math`).
+//!
+//! A date range of daily notes can also be rendered as one printable
+//! document with a table of contents.
+
+use crate::diagrams::{diagram_svg, insert_diagrams};
+use crate::jobs::Job;
+use crate::templates::{render_template, TemplateContext};
+use crate::vault::{Result, Vault};
+use chrono::NaiveDate;
+use core_index::markdown::{extract_section_with_heading, slugify};
+use core_index::rendering::{encode_href, escape_html};
+use core_index::{collect_wikilinks, find_diagrams, redact_private, render_html_with, strip_frontmatter, WikiLink};
+use shared_types::{RenderOptions, RenderedNote};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use tracing::{debug, instrument};
+use url::Url;
+
+/// Embeds rendered as `<img>`.
+pub(crate) const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "ico"];
+
+/// Embeds rendered as a link to the file.
+pub(crate) const FILE_EXTENSIONS: &[&str] = &["mp3", "wav", "ogg", "m4a", "flac", "mp4", "webm", "mov", "avi", "pdf"];
+
+/// Styles for the journal document; each day starts on a new page when printed.
+const JOURNAL_STYLE: &str = "\
+body { font-family: Georgia, serif; line-height: 1.5; max-width: 46em; margin: 2em auto; padding: 0 1em; }
+nav.toc ol { columns: 2; }
+section.day { break-before: page; }
+img { max-width: 100%; }
+.embed { border-left: 3px solid #ccc; padding-left: 1em; margin: 1em 0; }
+.unresolved { color: #888; }
+.query-block { color: #888; font-style: italic; }
+.diagram svg { max-width: 100%; height: auto; }
+.diagram .node rect, .diagram .node polygon, .diagram .node circle { fill: #ececff; stroke: #9370db; }
+.diagram .edgePath path, .diagram .flowchart-link { fill: none; stroke: #333; }
+@media print { body { margin: 0; max-width: none; } a { color: inherit; text-decoration: none; } }";
+
+/// Daily notes of a date range rendered as one HTML document.
+#[derive(Debug, Clone)]
+pub struct JournalDocument {
+    pub html: String,
+    /// Dates that had a daily note, in order.
+    pub dates: Vec<NaiveDate>,
+}
+
+/// Render a note to HTML.
+#[instrument(skip(vault))]
+pub async fn render_note_html(vault: &Vault, note_id: i64, options: &RenderOptions) -> Result<RenderedNote> {
+    let note = vault.repo().get_note(note_id).await?;
+    let content = vault.read_note(&note.path).await?;
+
+    let html = render_markdown(vault, &note.path, &content, options, 0).await;
+    debug!("Rendered {} ({} bytes of HTML)", note.path, html.len());
+
+    Ok(RenderedNote {
+        note_id,
+        path: note.path,
+        title: note.title,
+        html,
+        cssclasses: note.cssclasses,
+    })
+}
+
+/// Render note content (with frontmatter) to HTML.
+///
+/// `path` is the note the content comes from, whose folder is preferred
+/// when a link matches several notes. `depth` is the embed depth of this
+/// content; the note itself is 0.
+pub fn render_markdown<'a>(
+    vault: &'a Vault,
+    path: &'a str,
+    content: &'a str,
+    options: &'a RenderOptions,
+    depth: u32,
+) -> Pin<Box<dyn Future<Output = String> + Send + 'a>> {
+    Box::pin(async move {
+        let content = if options.include_private {
+            content.into()
+        } else {
+            redact_private(content)
+        };
+        let body = strip_frontmatter(&content);
+
+        let mut rendered = HashMap::new();
+        for link in collect_wikilinks(body) {
+            let html = render_link(vault, path, &link, options, depth).await;
+            rendered.insert(link, html);
+        }
+
+        let math = options.math.unwrap_or_default();
+        let html = render_html_with(body, math, |link| rendered.get(link).cloned().unwrap_or_default());
+
+        let diagrams = find_diagrams(body);
+        if diagrams.is_empty() {
+            return html;
+        }
+        let mut svgs = Vec::with_capacity(diagrams.len());
+        for source in &diagrams {
+            svgs.push(diagram_svg(vault, source).await.unwrap_or_else(|e| {
+                debug!("Not rendering diagram in {}: {}", path, e);
+                None
+            }));
+        }
+        insert_diagrams(&html, &svgs)
+    })
+}
+
+/// Render a single wikilink or embed.
+async fn render_link(vault: &Vault, from_path: &str, link: &WikiLink, options: &RenderOptions, depth: u32) -> String {
+    let label = escape_html(&link.label());
+    let extension = Path::new(&link.target)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    if link.embed && (IMAGE_EXTENSIONS.contains(&extension.as_str()) || FILE_EXTENSIONS.contains(&extension.as_str())) {
+        let Some(asset) = vault.resolve_asset_path(&link.target).await else {
+            return format!("<span class=\"embed unresolved\">{}</span>", label);
+        };
+        let src = encode_href(&asset.strip_prefix(vault.root_path()).unwrap_or(&asset).to_string_lossy());
+
+        if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+            return format!("<a class=\"embed-file\" href=\"{}\">{}</a>", src, label);
+        }
+        // ![[image.png|300]] sets the width
+        return match link.display.as_deref().and_then(|d| d.parse::<u32>().ok()) {
+            Some(width) => format!(
+                "<img class=\"embed-image\" src=\"{}\" alt=\"{}\" width=\"{}\">",
+                src,
+                escape_html(&link.target),
+                width
+            ),
+            None => format!("<img class=\"embed-image\" src=\"{}\" alt=\"{}\">", src, label),
+        };
+    }
+
+    let Some((_, path)) = vault.resolve_note_from(&link.target, Some(from_path)).await else {
+        return format!("<span class=\"internal-link unresolved\">{}</span>", label);
+    };
+
+    let mut href = format!("{}{}", escape_html(&options.link_prefix), encode_href(&path));
+    if let Some(section) = &link.section {
+        href.push('#');
+        href.push_str(&slugify(section));
+    }
+    let anchor = format!("<a class=\"internal-link\" href=\"{}\">{}</a>", href, label);
+
+    if !link.embed || depth >= options.max_embed_depth {
+        return anchor;
+    }
+
+    let content = match vault.read_note(&path).await {
+        Ok(content) => content,
+        Err(e) => {
+            debug!("Not inlining embed {}: {}", path, e);
+            return anchor;
+        }
+    };
+    let content = match &link.section {
+        Some(section) => match extract_section_with_heading(&content, &slugify(section)) {
+            Some(section_content) => section_content,
+            None => return anchor,
+        },
+        None => content,
+    };
+
+    format!(
+        "<div class=\"embed\" data-path=\"{}\">{}</div>",
+        escape_html(&path),
+        render_markdown(vault, &path, &content, options, depth + 1).await
+    )
+}
+
+/// Render the daily notes from `start` to `end` (inclusive) as one HTML document.
+///
+/// Daily notes are found with `daily_note_pattern` (the template settings'
+/// path pattern); days without a note are skipped. Frontmatter is stripped,
+/// embeds are resolved, and image paths are relative to the vault root (set
+/// as the document's base URL).
+///
+/// When run as a job, progress is reported per day; a cancelled job stops
+/// with the days rendered so far.
+#[instrument(skip(vault, job))]
+pub async fn render_journal(
+    vault: &Vault,
+    daily_note_pattern: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    options: &RenderOptions,
+    job: Option<&Job>,
+) -> Result<JournalDocument> {
+    let total = (end - start).num_days().max(0) as usize + 1;
+    let mut entries = Vec::new();
+    for (i, date) in start.iter_days().take_while(|d| *d <= end).enumerate() {
+        if let Some(job) = job {
+            if job.is_cancelled() {
+                break;
+            }
+            job.progress(vault, i, total, Some(date.format("%Y-%m-%d").to_string()));
+        }
+
+        let path = render_template(daily_note_pattern, &TemplateContext::for_date(date));
+        if !vault.fs().exists(Path::new(&path)).await {
+            continue;
+        }
+
+        let content = vault.read_note(&path).await?;
+        let mut body = render_markdown(vault, &path, &content, options, 0).await;
+        // Keep per-note layouts (`cssclasses`) in the combined document
+        if let Some(note_id) = vault.repo().get_note_id_by_path(&path).await? {
+            let classes = vault.repo().get_css_classes(note_id).await?;
+            if !classes.is_empty() {
+                body = format!("<div class=\"{}\">\n{}</div>\n", escape_html(&classes.join(" ")), body);
+            }
+        }
+        entries.push((date, body));
+    }
+    if let Some(job) = job.filter(|job| !job.is_cancelled()) {
+        job.progress(vault, total, total, None);
+    }
+
+    let title = format!("Journal {} to {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"));
+    let base_url = base_url(vault.root_path());
+    debug!("Rendered {} daily notes for {}", entries.len(), title);
+
+    Ok(JournalDocument {
+        html: journal_document(&title, &base_url, &entries),
+        dates: entries.iter().map(|(date, _)| *date).collect(),
+    })
+}
+
+/// `file://` URL of a vault root, ending in a slash, for the `<base>` of a
+/// rendered document (so vault-relative image paths resolve).
+pub(crate) fn base_url(root: &Path) -> String {
+    Url::from_directory_path(root).map(String::from).unwrap_or_default()
+}
+
+/// Assemble the journal document: title, table of contents, and one section per day.
+fn journal_document(title: &str, base_url: &str, entries: &[(NaiveDate, String)]) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<base href=\"{}\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape_html(base_url),
+        escape_html(title),
+        JOURNAL_STYLE,
+        escape_html(title)
+    );
+
+    html.push_str("<nav class=\"toc\">\n<ol>\n");
+    for (date, _) in entries {
+        html.push_str(&format!(
+            "<li><a href=\"#day-{}\">{}</a></li>\n",
+            date.format("%Y-%m-%d"),
+            date.format("%A, %B %-d, %Y")
+        ));
+    }
+    html.push_str("</ol>\n</nav>\n");
+
+    for (date, body) in entries {
+        html.push_str(&format!(
+            "<section class=\"day\" id=\"day-{}\">\n<h1 class=\"day-title\">{}</h1>\n{}</section>\n",
+            date.format("%Y-%m-%d"),
+            date.format("%A, %B %-d, %Y"),
+            body
+        ));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_base_url() {
+        assert_eq!(base_url(Path::new("/home/me/My Vault")), "file:///home/me/My%20Vault/");
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_base_url() {
+        assert_eq!(base_url(Path::new(r"C:\Users\me\My Vault")), "file:///C:/Users/me/My%20Vault/");
+    }
+
+    #[test]
+    fn test_journal_document() {
+        let entries = vec![
+            (NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(), "<p>First</p>\n".to_string()),
+            (NaiveDate::from_ymd_opt(2024, 5, 3).unwrap(), "<p>Third</p>\n".to_string()),
+        ];
+        let html = journal_document("Journal <May>", "file:///vault/", &entries);
+
+        assert!(html.contains("<title>Journal &lt;May&gt;</title>"));
+        assert!(html.contains("<base href=\"file:///vault/\">"));
+        assert!(html.contains("<a href=\"#day-2024-05-01\">Wednesday, May 1, 2024</a>"));
+        assert!(html.contains("<section class=\"day\" id=\"day-2024-05-03\">"));
+        assert!(html.find("First").unwrap() < html.find("Third").unwrap());
+    }
+}
@@ -0,0 +1,409 @@
+//! Resolving references to notes: wiki links, embeds and typed names.
+//!
+//! Every note that could be meant is scored, from most to least certain:
+//! the exact path, the file name in the linking note's folder, the file
+//! name elsewhere, the title, an alias, and finally a near-typo of the
+//! name, title or an alias. Within a kind, notes closer to the linking
+//! note's folder rank higher; remaining ties go to the shallowest path,
+//! then alphabetically, so the same link always resolves the same way.
+//!
+//! Links and embeds only resolve to exact matches; near-typos are offered
+//! as suggestions (and by the quick switcher), never followed silently.
+
+use crate::links::absolute_target;
+use crate::vault::parent_folder;
+use shared_types::{NoteListItem, NoteMatch, NoteMatchKind, NoteResolution};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Score of the best match of each kind; the kinds never overlap.
+const PATH_SCORE: i64 = 1000;
+const SAME_FOLDER_SCORE: i64 = 900;
+const NAME_SCORE: i64 = 800;
+const TITLE_SCORE: i64 = 700;
+const ALIAS_SCORE: i64 = 600;
+const FUZZY_SCORE: i64 = 500;
+
+/// Lost per typo of a near-typo match.
+const TYPO_PENALTY: i64 = 100;
+
+/// Bonus for a note in the linking note's folder, or per folder shared
+/// with it (up to just below the same-folder bonus).
+const SAME_FOLDER_BONUS: i64 = 50;
+const SHARED_FOLDER_BONUS: i64 = 5;
+
+/// A match of a note, borrowed from the resolver.
+struct Match<'n> {
+    note: &'n NoteListItem,
+    kind: NoteMatchKind,
+    score: i64,
+    confidence: f64,
+}
+
+/// Scores notes against references. Build one per batch of lookups: the
+/// notes and aliases are indexed once, by lowercase file name, title and
+/// alias, so exact lookups only score the notes they could match.
+pub struct NoteResolver<'a> {
+    notes: Cow<'a, [NoteListItem]>,
+    aliases: HashMap<i64, Vec<String>>,
+    /// Positions in `notes` by lowercase file name (without `.md`), title
+    /// and alias.
+    by_name: HashMap<String, Vec<usize>>,
+    by_title: HashMap<String, Vec<usize>>,
+    by_alias: HashMap<String, Vec<usize>>,
+}
+
+impl<'a> NoteResolver<'a> {
+    /// A resolver over `notes`, without aliases.
+    pub fn new(notes: impl Into<Cow<'a, [NoteListItem]>>) -> Self {
+        let notes = notes.into();
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut by_title: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, note) in notes.iter().enumerate() {
+            let path = note.path.to_lowercase();
+            let name = path.rsplit('/').next().unwrap_or(&path);
+            by_name.entry(name.strip_suffix(".md").unwrap_or(name).to_string()).or_default().push(i);
+            if let Some(title) = &note.title {
+                by_title.entry(title.to_lowercase()).or_default().push(i);
+            }
+        }
+        Self {
+            notes,
+            aliases: HashMap::new(),
+            by_name,
+            by_title,
+            by_alias: HashMap::new(),
+        }
+    }
+
+    /// Also match (note_id, alias) pairs.
+    pub fn with_aliases(mut self, aliases: Vec<(i64, String)>) -> Self {
+        let positions: HashMap<i64, usize> = self.notes.iter().enumerate().map(|(i, note)| (note.id, i)).collect();
+        for (note_id, alias) in aliases {
+            if let Some(&i) = positions.get(&note_id) {
+                self.by_alias.entry(alias.to_lowercase()).or_default().push(i);
+            }
+            self.aliases.entry(note_id).or_default().push(alias);
+        }
+        self
+    }
+
+    pub fn notes(&self) -> &[NoteListItem] {
+        &self.notes
+    }
+
+    /// The note `target` refers to from the note at `from`, if it matches
+    /// one exactly (not only as a near-typo).
+    pub fn resolve(&self, target: &str, from: Option<&str>) -> Option<&NoteListItem> {
+        self.matches(target, from, false).into_iter().next().map(|m| m.note)
+    }
+
+    /// The notes `target` could refer to, best first, with how sure the
+    /// best one is meant.
+    pub fn rank(&self, target: &str, from: Option<&str>, limit: usize) -> NoteResolution {
+        let matches = self.matches(target, from, true);
+        let confidence = match (matches.first(), matches.get(1)) {
+            (None, _) => 0.0,
+            (Some(best), None) => best.confidence,
+            // A tie is settled by path order only
+            (Some(best), Some(next)) if next.score == best.score => best.confidence * 0.5,
+            (Some(best), Some(next)) if tier(next.kind) == tier(best.kind) => best.confidence * 0.8,
+            (Some(best), Some(_)) => best.confidence,
+        };
+
+        NoteResolution {
+            candidates: matches
+                .into_iter()
+                .take(limit)
+                .map(|m| NoteMatch {
+                    note_id: m.note.id,
+                    path: m.note.path.clone(),
+                    title: m.note.title.clone(),
+                    kind: m.kind,
+                    score: m.score,
+                    confidence: m.confidence,
+                })
+                .collect(),
+            confidence,
+        }
+    }
+
+    /// The notes matching `target` as well as the best match does, ignoring
+    /// folders: the ones an ambiguous link could refer to. Near-typos are
+    /// never candidates.
+    pub(crate) fn candidates(&self, target: &str, from: Option<&str>) -> Vec<&NoteListItem> {
+        let matches = self.matches(target, from, false);
+        let Some(best) = matches.first().map(|m| tier(m.kind)) else {
+            return Vec::new();
+        };
+        matches.into_iter().filter(|m| tier(m.kind) == best).map(|m| m.note).collect()
+    }
+
+    /// Every note matching `target`, best first. Near-typos are only looked
+    /// for with `fuzzy`, which scores every note; otherwise only the notes
+    /// with the target's file name, title or alias are.
+    fn matches(&self, target: &str, from: Option<&str>, fuzzy: bool) -> Vec<Match<'_>> {
+        let target = absolute_target(target.trim(), from);
+        let target = target.strip_suffix(".md").unwrap_or(&target);
+        if target.is_empty() {
+            return Vec::new();
+        }
+        let target_path = format!("{}.md", target);
+        let target_path_lower = target_path.to_lowercase();
+        let suffix = format!("/{}", target_path);
+        let suffix_lower = suffix.to_lowercase();
+        let target_lower = target.to_lowercase();
+        let target_name = target_lower.rsplit('/').next().unwrap_or(&target_lower);
+        let from_folder = from.map(parent_folder);
+
+        let candidates: Box<dyn Iterator<Item = &NoteListItem>> = if fuzzy {
+            Box::new(self.notes.iter())
+        } else {
+            let mut positions: Vec<usize> = self
+                .by_name
+                .get(target_name)
+                .into_iter()
+                .chain(self.by_title.get(&target_lower))
+                .chain(self.by_alias.get(&target_lower))
+                .flatten()
+                .copied()
+                .collect();
+            positions.sort_unstable();
+            positions.dedup();
+            Box::new(positions.into_iter().map(|i| &self.notes[i]))
+        };
+
+        let mut matches: Vec<Match<'_>> = candidates
+            .filter_map(|note| {
+                let path_lower = note.path.to_lowercase();
+                let no_aliases = Vec::new();
+                let aliases = self.aliases.get(&note.id).unwrap_or(&no_aliases);
+                let same_folder = from_folder == Some(parent_folder(&note.path));
+
+                let (kind, score, typos) = if note.path == target_path {
+                    (NoteMatchKind::Path, PATH_SCORE, 0)
+                } else if path_lower == target_path_lower || path_lower.ends_with(&suffix_lower) {
+                    // Exactly as written beats a difference in case
+                    let exact_case = note.path.ends_with(&suffix);
+                    let kind = if same_folder {
+                        NoteMatchKind::SameFolder
+                    } else {
+                        NoteMatchKind::Name
+                    };
+                    let score = if same_folder { SAME_FOLDER_SCORE } else { NAME_SCORE };
+                    (kind, score - if exact_case { 0 } else { 10 }, 0)
+                } else if note.title.as_ref().is_some_and(|t| t.to_lowercase() == target_lower) {
+                    (NoteMatchKind::Title, TITLE_SCORE, 0)
+                } else if aliases.iter().any(|a| a.to_lowercase() == target_lower) {
+                    (NoteMatchKind::Alias, ALIAS_SCORE, 0)
+                } else if !fuzzy {
+                    return None;
+                } else {
+                    let name = path_lower.rsplit('/').next().unwrap_or(&path_lower);
+                    let name = name.strip_suffix(".md").unwrap_or(name);
+                    let typos = std::iter::once(name.to_string())
+                        .chain(note.title.iter().map(|t| t.to_lowercase()))
+                        .chain(aliases.iter().map(|a| a.to_lowercase()))
+                        .filter_map(|text| typos(target_name, &text))
+                        .min()?;
+                    (NoteMatchKind::Fuzzy, FUZZY_SCORE - TYPO_PENALTY * typos as i64, typos)
+                };
+
+                let proximity = match (kind, from) {
+                    (NoteMatchKind::Path | NoteMatchKind::SameFolder, _) | (_, None) => 0,
+                    _ if same_folder => SAME_FOLDER_BONUS,
+                    (_, Some(from)) => {
+                        SHARED_FOLDER_BONUS * shared_folders(parent_folder(from), parent_folder(&note.path))
+                    }
+                };
+                Some(Match {
+                    note,
+                    kind,
+                    score: score + proximity.min(SAME_FOLDER_BONUS),
+                    confidence: confidence(kind, typos, target_name.chars().count()),
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.note.path.matches('/').count().cmp(&b.note.path.matches('/').count()))
+                .then_with(|| a.note.path.cmp(&b.note.path))
+        });
+        matches
+    }
+}
+
+/// Kinds that are equally good matches when folders are ignored.
+fn tier(kind: NoteMatchKind) -> u8 {
+    match kind {
+        NoteMatchKind::Path => 0,
+        NoteMatchKind::SameFolder | NoteMatchKind::Name => 1,
+        NoteMatchKind::Title => 2,
+        NoteMatchKind::Alias => 3,
+        NoteMatchKind::Fuzzy => 4,
+    }
+}
+
+/// How sure a match of `kind` is the note meant, from 0 to 1.
+fn confidence(kind: NoteMatchKind, typos: usize, target_len: usize) -> f64 {
+    match kind {
+        NoteMatchKind::Path => 1.0,
+        NoteMatchKind::SameFolder => 0.95,
+        NoteMatchKind::Name => 0.9,
+        NoteMatchKind::Title => 0.85,
+        NoteMatchKind::Alias => 0.8,
+        NoteMatchKind::Fuzzy => 0.5 * (1.0 - typos as f64 / target_len.max(1) as f64),
+    }
+}
+
+/// Number of leading folders two folders share.
+fn shared_folders(a: &str, b: &str) -> i64 {
+    a.split('/')
+        .zip(b.split('/'))
+        .take_while(|(a, b)| !a.is_empty() && a == b)
+        .count() as i64
+}
+
+/// Typos between a name and a text, if few enough for a near-typo: one per
+/// four characters of the name, and none for names under four characters.
+fn typos(name: &str, text: &str) -> Option<usize> {
+    let allowed = (name.chars().count() / 4).min(3);
+    if allowed == 0 {
+        return None;
+    }
+    let distance = edit_distance(name, text, allowed)?;
+    (distance <= allowed).then_some(distance)
+}
+
+/// Edits (insertions, deletions, substitutions and swaps of neighbouring
+/// characters) turning `a` into `b`, or None if more than `max`.
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    // Three rows of the optimal string alignment table
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (previous[j] + 1).min(row[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut previous, row);
+    }
+    let distance = previous[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: i64, path: &str, title: &str) -> NoteListItem {
+        NoteListItem {
+            id,
+            path: path.to_string(),
+            title: Some(title.to_string()),
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_link_resolution_prefers_same_folder() {
+        let notes = vec![
+            note(1, "archive/Plan.md", "Plan"),
+            note(2, "projects/Plan.md", "Plan"),
+            note(3, "projects/Ideas.md", "Meeting"),
+            note(4, "work/Standup.md", "Meeting"),
+        ];
+
+        let resolver = NoteResolver::new(notes.as_slice());
+        let ids = |c: Vec<&NoteListItem>| c.iter().map(|n| n.id).collect::<Vec<_>>();
+        assert_eq!(ids(resolver.candidates("Plan", None)), vec![1, 2]);
+        assert_eq!(ids(resolver.candidates("projects/Plan", None)), vec![2]);
+        assert_eq!(ids(resolver.candidates("meeting", None)), vec![3, 4]);
+
+        assert_eq!(resolver.resolve("Plan", None).map(|n| n.id), Some(1));
+        assert_eq!(resolver.resolve("Plan", Some("projects/Ideas.md")).map(|n| n.id), Some(2));
+        assert_eq!(resolver.resolve("Meeting", Some("work/Other.md")).map(|n| n.id), Some(4));
+        assert_eq!(resolver.resolve("Missing", None).map(|n| n.id), None);
+    }
+
+    #[test]
+    fn test_rank_by_kind() {
+        let notes = vec![
+            note(1, "Reading list.md", "Books to read"),
+            note(2, "archive/2023/Reading list.md", "Old reading list"),
+            note(3, "projects/Garden.md", "Reading list"),
+            note(4, "projects/Roses.md", "Roses"),
+            note(5, "Readings.md", "Readings"),
+        ];
+        let resolver = NoteResolver::new(notes.as_slice())
+            .with_aliases(vec![(4, "reading list".to_string()), (4, "Flowers".to_string())]);
+
+        let resolution = resolver.rank("Reading list", Some("projects/Todo.md"), 10);
+        let kinds: Vec<_> = resolution.candidates.iter().map(|c| (c.note_id, c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (1, NoteMatchKind::Path),
+                (2, NoteMatchKind::Name),
+                (3, NoteMatchKind::Title),
+                (4, NoteMatchKind::Alias),
+            ]
+        );
+        assert_eq!(resolution.confidence, 1.0);
+
+        // A name in two folders: the linking note's folder decides, with
+        // less confidence than a unique match
+        let resolution = resolver.rank("reading list", Some("archive/2023/x.md"), 10);
+        assert_eq!(resolution.candidates[0].note_id, 2);
+        assert_eq!(resolution.candidates[0].kind, NoteMatchKind::SameFolder);
+        assert!(resolution.confidence < 0.95);
+
+        // Aliases resolve links; near-typos only suggest
+        assert_eq!(resolver.resolve("flowers", None).map(|n| n.id), Some(4));
+        assert_eq!(resolver.resolve("Rosse", None).map(|n| n.id), None);
+        let resolution = resolver.rank("Rosse", None, 10);
+        assert_eq!(resolution.candidates[0].note_id, 4);
+        assert_eq!(resolution.candidates[0].kind, NoteMatchKind::Fuzzy);
+        assert!(resolution.confidence < 0.5);
+        assert!(resolver.rank("Zzz", None, 10).candidates.is_empty());
+    }
+
+    #[test]
+    fn test_ties_resolve_the_same_way() {
+        let notes = vec![
+            note(1, "b/deep/Plan.md", "Plan"),
+            note(2, "b/Plan.md", "Plan"),
+            note(3, "a/Plan.md", "Plan"),
+        ];
+        let resolver = NoteResolver::new(notes.as_slice());
+        let order: Vec<_> = resolver.rank("Plan", None, 10).candidates.iter().map(|c| c.note_id).collect();
+        assert_eq!(order, vec![3, 2, 1]);
+        assert_eq!(resolver.rank("Plan", None, 10).confidence, 0.45);
+
+        // Closer to the linking note's folder
+        assert_eq!(resolver.resolve("Plan", Some("b/deep/other/x.md")).map(|n| n.id), Some(1));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("garden", "garden", 2), Some(0));
+        assert_eq!(edit_distance("garden", "gradens", 2), Some(2));
+        assert_eq!(edit_distance("garden", "gadren", 2), Some(1));
+        assert_eq!(edit_distance("teh", "the", 1), Some(1));
+        assert_eq!(edit_distance("garden", "kitchen", 2), None);
+        assert_eq!(typos("plan", "plans"), Some(1));
+        assert_eq!(typos("pln", "plan"), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! New vault scaffolding: the `.neuroflow` folder, default folders, starter
+//! templates and a welcome note.
+//!
+//! Only an empty folder (hidden files like `.git` aside) is scaffolded, so
+//! an existing vault or unrelated folder is never written into.
+
+use crate::vault::{Result, VaultError};
+use core_fs::VaultFs;
+use shared_types::{CreateVaultOptions, TemplateSettings};
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Folder the template picker lists templates from.
+pub const TEMPLATES_FOLDER: &str = "templates";
+
+const DAILY_TEMPLATE: &str = "# {{weekday}}, {{date}}
+
+## Plan
+
+## Notes
+
+## Done today
+";
+
+const WEEKLY_TEMPLATE: &str = "# Week {{week}}, {{week_year}}
+
+## Goals
+
+## Review
+";
+
+const MONTHLY_TEMPLATE: &str = "# {{month_name}} {{year}}
+
+## Focus
+
+## Review
+";
+
+const MEETING_TEMPLATE: &str = "---
+type: meeting
+attendees: []
+---
+# Meeting on {{date}}
+
+## Agenda
+
+## Notes
+
+## Action items
+";
+
+/// Create the folders and starter notes of a new vault at `path` (created
+/// if missing), and return the template settings for its config.
+#[instrument]
+pub async fn scaffold_vault(path: &Path, options: &CreateVaultOptions) -> Result<TemplateSettings> {
+    let inbox = folder_name(&options.inbox_folder, "inbox")?;
+    let journal = folder_name(&options.journal_folder, "journal")?;
+    check_empty(path).await?;
+    tokio::fs::create_dir_all(path).await.map_err(core_fs::FsError::from)?;
+    VaultFs::new(path).ensure_neuroflow_dir().await?;
+
+    for folder in [inbox, journal, TEMPLATES_FOLDER] {
+        tokio::fs::create_dir_all(path.join(folder)).await.map_err(core_fs::FsError::from)?;
+    }
+
+    let mut settings = TemplateSettings {
+        daily_note_pattern: format!("{}/{{{{year}}}}/{{{{month}}}}/{{{{date}}}}.md", journal),
+        weekly_note_pattern: format!("{}/{{{{week_year}}}}/{{{{week_year}}}}-W{{{{week}}}}.md", journal),
+        monthly_note_pattern: format!("{}/{{{{year}}}}/{{{{year}}}}-{{{{month}}}}.md", journal),
+        ..TemplateSettings::default()
+    };
+
+    if options.sample_templates {
+        let templates = [
+            ("daily.md", DAILY_TEMPLATE),
+            ("weekly.md", WEEKLY_TEMPLATE),
+            ("monthly.md", MONTHLY_TEMPLATE),
+            ("meeting.md", MEETING_TEMPLATE),
+        ];
+        for (name, content) in templates {
+            write_file(path, &format!("{}/{}", TEMPLATES_FOLDER, name), content).await?;
+        }
+        settings.daily_template_path = Some(format!("{}/daily.md", TEMPLATES_FOLDER));
+        settings.weekly_template_path = Some(format!("{}/weekly.md", TEMPLATES_FOLDER));
+        settings.monthly_template_path = Some(format!("{}/monthly.md", TEMPLATES_FOLDER));
+    }
+
+    if options.welcome_note {
+        write_file(path, "Welcome.md", &welcome_note(inbox, journal)).await?;
+    }
+
+    info!("Scaffolded new vault at {}", path.display());
+    Ok(settings)
+}
+
+/// Fail unless `path` is missing or holds only hidden files. A `.neuroflow`
+/// folder means there is a vault there already.
+async fn check_empty(path: &Path) -> Result<()> {
+    let mut entries = match tokio::fs::read_dir(path).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(_) if path.is_file() => return Err(VaultError::NotADirectory(path.to_path_buf())),
+        Err(e) => return Err(core_fs::FsError::from(e).into()),
+    };
+
+    while let Some(entry) = entries.next_entry().await.map_err(core_fs::FsError::from)? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with('.') || name == ".neuroflow" {
+            return Err(VaultError::NotEmpty(path.to_path_buf()));
+        }
+    }
+    Ok(())
+}
+
+/// A folder name from the options, without surrounding slashes. Nested
+/// folders are fine; `..`, `.` and empty segments, which would leave the
+/// vault or name it oddly, are not.
+fn folder_name<'a>(name: &'a str, default: &'a str) -> Result<&'a str> {
+    let name = match name.trim().trim_matches('/') {
+        "" => return Ok(default),
+        name => name,
+    };
+    let valid = !name.contains('\\')
+        && name
+            .split('/')
+            .all(|segment| !segment.trim().is_empty() && segment != "." && segment != "..");
+    if !valid {
+        return Err(VaultError::Settings(format!("Invalid folder name: {}", name)));
+    }
+    Ok(name)
+}
+
+async fn write_file(root: &Path, relative: &str, content: &str) -> Result<()> {
+    tokio::fs::write(root.join(relative), content).await.map_err(core_fs::FsError::from)?;
+    Ok(())
+}
+
+fn welcome_note(inbox: &str, journal: &str) -> String {
+    format!(
+        "# Welcome to NeuroFlow Notes
+
+This vault is a folder of markdown files. Edit them here or in any other
+editor; changes made elsewhere are picked up automatically.
+
+## Where things go
+
+- `{inbox}/` - new notes that aren't sorted yet
+- `{journal}/` - daily, weekly and monthly notes
+- `{templates}/` - templates for new notes
+
+## Try it out
+
+- [ ] Open today's daily note from the calendar
+- [ ] Link to another note with `[[double brackets]]`
+- [ ] Add a `#tag`, or a task with a context like `@home`
+
+Delete this note whenever you like.
+",
+        inbox = inbox,
+        journal = journal,
+        templates = TEMPLATES_FOLDER,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::Vault;
+
+    #[tokio::test]
+    async fn test_scaffold_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Notes");
+        let options = CreateVaultOptions {
+            journal_folder: "/Daily/".to_string(),
+            ..CreateVaultOptions::default()
+        };
+
+        let settings = scaffold_vault(&path, &options).await.unwrap();
+        assert_eq!(settings.daily_note_pattern, "Daily/{{year}}/{{month}}/{{date}}.md");
+        assert_eq!(settings.weekly_note_pattern, "Daily/{{week_year}}/{{week_year}}-W{{week}}.md");
+        assert_eq!(settings.daily_template_path.as_deref(), Some("templates/daily.md"));
+        assert!(path.join(".neuroflow").is_dir());
+        assert!(path.join("inbox").is_dir());
+        assert!(path.join("templates/meeting.md").is_file());
+        assert_eq!(settings.monthly_template_path.as_deref(), Some("templates/monthly.md"));
+
+        let vault = Vault::open(&path).await.unwrap();
+        vault.full_index().await.unwrap();
+        let welcome = vault.repo().get_note_id_by_path("Welcome.md").await.unwrap();
+        assert!(welcome.is_some());
+
+        // An existing vault is left alone
+        assert!(matches!(
+            scaffold_vault(&path, &options).await,
+            Err(VaultError::NotEmpty(_))
+        ));
+
+        // Folder names can't leave the vault
+        for name in ["../outside", "a/../../b", "a//b", "a\\b"] {
+            let options = CreateVaultOptions {
+                inbox_folder: name.to_string(),
+                ..CreateVaultOptions::default()
+            };
+            let target = dir.path().join("Escaping");
+            assert!(matches!(scaffold_vault(&target, &options).await, Err(VaultError::Settings(_))), "{}", name);
+            assert!(!target.exists());
+        }
+        assert!(!dir.path().join("outside").exists());
+
+        // Hidden files don't count
+        let hidden = dir.path().join("Hidden");
+        std::fs::create_dir_all(hidden.join(".git")).unwrap();
+        let options = CreateVaultOptions {
+            sample_templates: false,
+            welcome_note: false,
+            ..CreateVaultOptions::default()
+        };
+        let settings = scaffold_vault(&hidden, &options).await.unwrap();
+        assert!(settings.daily_template_path.is_none());
+        assert!(!hidden.join("Welcome.md").exists());
+    }
+}
@@ -0,0 +1,328 @@
+//! Scheduled note creation: "every Monday at 07:00 create
+//! `Weekly/{{date:YYYY-[W]WW}}.md` from the weekly template".
+//!
+//! A rule has an RRULE (without DTSTART) and a local time of day. While the
+//! vault is open, a background task checks the rules every minute and
+//! creates a note for each occurrence since the rule last ran, so
+//! occurrences missed while the app was closed are caught up on when the
+//! vault opens. The path and the template are rendered for the day of the
+//! occurrence with the vault's template settings. Notes that already exist
+//! are left alone. An occurrence whose note can't be created (say, its
+//! template is missing) is tried again on the next check.
+//!
+//! Times are local and floating: a rule at 07:00 runs at 07:00 wherever the
+//! computer is.
+
+use crate::templates::{render_template, TemplateContext};
+use crate::vault::{Result, Vault, VaultError};
+use chrono::{Local, NaiveDateTime, NaiveTime};
+use rrule::{RRuleSet, Tz as RRuleTz};
+use shared_types::{SaveScheduledNoteRuleRequest, ScheduledNoteCreated, ScheduledNoteRule, TemplateSettings};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
+use tracing::{debug, info, instrument, warn};
+
+/// How often the background task checks for due rules.
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Missed occurrences created per rule at most; older ones are skipped, so
+/// a daily rule doesn't create a year of notes after a long break.
+const MAX_CATCH_UP: usize = 12;
+
+/// Format of `last_run` and `created_at`.
+const LOCAL_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Check that a rule can be run: a name, a path, a time as `HH:MM` and a
+/// single-line RRULE that parses.
+pub fn validate_rule(request: &SaveScheduledNoteRuleRequest) -> Result<()> {
+    let invalid = |message: String| Err(VaultError::ScheduledNoteRule(message));
+    if request.name.trim().is_empty() {
+        return invalid("The rule has no name".to_string());
+    }
+    if request.path_pattern.trim().is_empty() {
+        return invalid("The rule has no path".to_string());
+    }
+    if NaiveTime::parse_from_str(&request.time, "%H:%M").is_err() {
+        return invalid(format!("Invalid time: {}", request.time));
+    }
+    // A second line would add to the DTSTART the rule is run with
+    if request.rrule.contains(['\n', '\r']) {
+        return invalid("The rrule must be a single line".to_string());
+    }
+    if let Err(e) = format!("DTSTART:20250101T000000Z\nRRULE:{}", request.rrule).parse::<RRuleSet>() {
+        return invalid(format!("Invalid rrule: {}", e));
+    }
+    Ok(())
+}
+
+/// Create a scheduled note rule after checking it.
+pub async fn create_rule(vault: &Vault, request: &SaveScheduledNoteRuleRequest) -> Result<i64> {
+    validate_rule(request)?;
+    Ok(vault.repo().create_scheduled_note_rule(request).await?)
+}
+
+/// Change a scheduled note rule after checking it.
+pub async fn update_rule(vault: &Vault, id: i64, request: &SaveScheduledNoteRuleRequest) -> Result<()> {
+    validate_rule(request)?;
+    Ok(vault.repo().update_scheduled_note_rule(id, request).await?)
+}
+
+/// The occurrences of a rule after `from`, up to and including `now`.
+///
+/// The recurrence starts on the day the rule was created (or `from`, if
+/// that is earlier), so rules like "every other week" keep their rhythm.
+pub fn rule_occurrences(
+    rule: &ScheduledNoteRule,
+    from: NaiveDateTime,
+    now: NaiveDateTime,
+) -> std::result::Result<Vec<NaiveDateTime>, String> {
+    let time = NaiveTime::parse_from_str(&rule.time, "%H:%M").map_err(|_| format!("Invalid time: {}", rule.time))?;
+    let created = NaiveDateTime::parse_from_str(&rule.created_at, LOCAL_TIME_FORMAT).unwrap_or(from);
+    let start = created.min(from).date().and_time(time);
+
+    let full_rrule = format!("DTSTART:{}Z\nRRULE:{}", start.format("%Y%m%dT%H%M%S"), rule.rrule);
+    let rruleset: RRuleSet = full_rrule.parse().map_err(|e| format!("Invalid rrule: {}", e))?;
+
+    // Local times are expanded as if they were UTC
+    let after = from.and_utc().with_timezone(&RRuleTz::UTC);
+    let before = now.and_utc().with_timezone(&RRuleTz::UTC);
+    Ok(rruleset
+        .after(after)
+        .before(before)
+        .all(500)
+        .dates
+        .into_iter()
+        .map(|date| date.naive_utc())
+        .filter(|date| *date > from && *date <= now)
+        .collect())
+}
+
+/// Create the notes of all enabled rules that are due as of `now` (local
+/// time).
+#[instrument(skip(vault))]
+pub async fn run_due_rules(vault: &Vault, now: NaiveDateTime) -> Result<Vec<ScheduledNoteCreated>> {
+    let mut created = Vec::new();
+    for rule in vault.repo().list_scheduled_note_rules().await? {
+        if rule.enabled {
+            created.extend(run_rule(vault, &rule, now).await?);
+        }
+    }
+    Ok(created)
+}
+
+/// Create the notes of a rule that are due as of `now` (local time). Its
+/// last run moves up to the last occurrence handled, so if a note can't be
+/// created, that occurrence and the ones after it are tried again next time.
+#[instrument(skip(vault, rule), fields(rule = %rule.name))]
+pub async fn run_rule(vault: &Vault, rule: &ScheduledNoteRule, now: NaiveDateTime) -> Result<Vec<ScheduledNoteCreated>> {
+    let from = rule
+        .last_run
+        .as_deref()
+        .and_then(|t| NaiveDateTime::parse_from_str(t, LOCAL_TIME_FORMAT).ok())
+        .unwrap_or(now);
+    let occurrences = match rule_occurrences(rule, from, now) {
+        Ok(occurrences) => occurrences,
+        Err(e) => {
+            warn!("Skipping scheduled note rule {}: {}", rule.name, e);
+            return Ok(Vec::new());
+        }
+    };
+    if occurrences.is_empty() {
+        return Ok(Vec::new());
+    }
+    let first = occurrences.len().saturating_sub(MAX_CATCH_UP);
+    if first > 0 {
+        info!(
+            "Scheduled note rule {} missed {} occurrences, creating the last {}",
+            rule.name,
+            occurrences.len(),
+            MAX_CATCH_UP
+        );
+    }
+
+    let settings = vault.template_settings().await;
+    let mut created = Vec::new();
+    let mut last_run = now;
+    for (i, occurrence) in occurrences.iter().enumerate().skip(first) {
+        match create_scheduled_note(vault, &settings, rule, *occurrence).await {
+            Ok(Some(note)) => created.push(note),
+            Ok(None) => {}
+            Err(e) => {
+                warn!("Scheduled note rule {} failed for {}, retrying later: {}", rule.name, occurrence, e);
+                last_run = if i == 0 { from } else { occurrences[i - 1] };
+                break;
+            }
+        }
+    }
+    vault.repo().set_scheduled_note_last_run(rule.id, last_run).await?;
+
+    Ok(created)
+}
+
+/// Create the note of one occurrence, unless it already exists.
+async fn create_scheduled_note(
+    vault: &Vault,
+    settings: &TemplateSettings,
+    rule: &ScheduledNoteRule,
+    occurrence: NaiveDateTime,
+) -> Result<Option<ScheduledNoteCreated>> {
+    let ctx = TemplateContext::for_date(occurrence.date()).with_settings(settings);
+    let mut path = render_template(&rule.path_pattern, &ctx);
+    if !path.ends_with(".md") {
+        path.push_str(".md");
+    }
+    if vault.fs().exists(Path::new(&path)).await {
+        debug!("Scheduled note {} already exists", path);
+        return Ok(None);
+    }
+
+    let title = Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .ok_or_else(|| core_fs::FsError::InvalidPath(path.clone()))?;
+    let content = match &rule.template_path {
+        Some(template_path) => {
+            let template = vault.fs().read_file(Path::new(template_path)).await?;
+            render_template(&template, &ctx.with_note_title(&title))
+        }
+        None => format!("# {}\n", title),
+    };
+
+    let note_id = vault.write_note(&path, &content).await?;
+    info!("Created scheduled note {} (rule {})", path, rule.name);
+    Ok(Some(ScheduledNoteCreated {
+        rule_id: rule.id,
+        scheduled_for: occurrence.format(LOCAL_TIME_FORMAT).to_string(),
+        path,
+        note_id,
+    }))
+}
+
+/// Run due rules right away and then every minute while the vault at `root`
+/// stays open. The vault is locked for one rule at a time, so closing it
+/// doesn't wait for all of them. Abort the returned task when the vault is
+/// closed, so a reopened vault doesn't get a second scheduler.
+pub fn spawn_scheduler(vault: Arc<RwLock<Option<Vault>>>, root: PathBuf) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+        'scheduler: loop {
+            interval.tick().await;
+
+            let rules = {
+                let vault_guard = vault.read().await;
+                match vault_guard.as_ref() {
+                    Some(v) if v.root_path() == root => v.repo().list_scheduled_note_rules().await,
+                    // The vault was closed or another one was opened
+                    _ => break,
+                }
+            };
+            let rules = match rules {
+                Ok(rules) => rules,
+                Err(e) => {
+                    warn!("Failed to list scheduled note rules: {}", e);
+                    continue;
+                }
+            };
+
+            let now = Local::now().naive_local();
+            for rule in rules.iter().filter(|rule| rule.enabled) {
+                let vault_guard = vault.read().await;
+                let Some(v) = vault_guard.as_ref().filter(|v| v.root_path() == root) else {
+                    break 'scheduler;
+                };
+                if let Err(e) = run_rule(v, rule, now).await {
+                    warn!("Failed to run scheduled note rule {}: {}", rule.name, e);
+                }
+            }
+        }
+        debug!("Stopped note scheduler for {}", root.display());
+    })
+    .abort_handle()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, LOCAL_TIME_FORMAT).unwrap()
+    }
+
+    fn weekly_rule() -> SaveScheduledNoteRuleRequest {
+        SaveScheduledNoteRuleRequest {
+            name: "Weekly".to_string(),
+            rrule: "FREQ=WEEKLY;BYDAY=MO".to_string(),
+            time: "07:00".to_string(),
+            path_pattern: "Weekly/{{date:YYYY-[W]WW}}.md".to_string(),
+            template_path: Some("templates/weekly.md".to_string()),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(validate_rule(&weekly_rule()).is_ok());
+
+        let invalid = [
+            SaveScheduledNoteRuleRequest { time: "7am".to_string(), ..weekly_rule() },
+            SaveScheduledNoteRuleRequest { time: "25:00".to_string(), ..weekly_rule() },
+            SaveScheduledNoteRuleRequest { rrule: "FREQ=SOMETIMES".to_string(), ..weekly_rule() },
+            SaveScheduledNoteRuleRequest { rrule: "FREQ=DAILY\nEXDATE:20250102T000000Z".to_string(), ..weekly_rule() },
+            SaveScheduledNoteRuleRequest { path_pattern: " ".to_string(), ..weekly_rule() },
+            SaveScheduledNoteRuleRequest { name: String::new(), ..weekly_rule() },
+        ];
+        for request in &invalid {
+            assert!(matches!(validate_rule(request), Err(VaultError::ScheduledNoteRule(_))), "{:?}", request);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_occurrences_are_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        let id = create_rule(&vault, &weekly_rule()).await.unwrap();
+        vault.repo().set_scheduled_note_last_run(id, at("2025-01-20T12:00:00")).await.unwrap();
+
+        // Without its template, nothing is created and nothing is skipped
+        assert!(run_due_rules(&vault, at("2025-02-03T08:00:00")).await.unwrap().is_empty());
+        let rule = &vault.repo().list_scheduled_note_rules().await.unwrap()[0];
+        assert_eq!(rule.last_run.as_deref(), Some("2025-01-20T12:00:00"));
+
+        std::fs::create_dir_all(dir.path().join("templates")).unwrap();
+        std::fs::write(dir.path().join("templates/weekly.md"), "# {{title}}\n").unwrap();
+        let created = run_due_rules(&vault, at("2025-02-03T08:05:00")).await.unwrap();
+        let paths: Vec<_> = created.iter().map(|note| note.path.as_str()).collect();
+        assert_eq!(paths, ["Weekly/2025-W05.md", "Weekly/2025-W06.md"]);
+        let rule = &vault.repo().list_scheduled_note_rules().await.unwrap()[0];
+        assert_eq!(rule.last_run.as_deref(), Some("2025-02-03T08:05:00"));
+    }
+
+    #[tokio::test]
+    async fn test_run_due_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("templates")).unwrap();
+        std::fs::write(dir.path().join("templates/weekly.md"), "# {{title}}\n\nWeek of {{date}}\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        let id = create_rule(&vault, &weekly_rule()).await.unwrap();
+        vault.repo().set_scheduled_note_last_run(id, at("2025-01-20T12:00:00")).await.unwrap();
+
+        // Catches up on the Monday missed and creates this Monday's note
+        let created = run_due_rules(&vault, at("2025-02-03T08:00:00")).await.unwrap();
+        let paths: Vec<_> = created.iter().map(|note| note.path.as_str()).collect();
+        assert_eq!(paths, ["Weekly/2025-W05.md", "Weekly/2025-W06.md"]);
+        assert_eq!(created[0].scheduled_for, "2025-01-27T07:00:00");
+        assert_eq!(vault.read_note("Weekly/2025-W06.md").await.unwrap(), "# 2025-W06\n\nWeek of 2025-02-03\n");
+
+        // Nothing is due until next Monday at 07:00
+        assert!(run_due_rules(&vault, at("2025-02-10T06:59:00")).await.unwrap().is_empty());
+
+        // An existing note is kept
+        std::fs::write(dir.path().join("Weekly/2025-W07.md"), "mine\n").unwrap();
+        assert!(run_due_rules(&vault, at("2025-02-10T07:00:00")).await.unwrap().is_empty());
+        assert_eq!(vault.read_note("Weekly/2025-W07.md").await.unwrap(), "mine\n");
+    }
+}
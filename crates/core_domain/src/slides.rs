@@ -0,0 +1,216 @@
+//! Slide decks: a note presented as HTML slides.
+//!
+//! A note is split into slides on `---` lines, or before each H2 heading
+//! when it has none. Each slide is rendered like a note (wikilinks, embeds,
+//! images) into a single self-contained HTML file with reveal.js-style
+//! markup and keyboard navigation.
+
+use crate::encryption::has_encrypted_body;
+use crate::rendering::{base_url, render_markdown};
+use crate::vault::{Result, Vault, VaultError};
+use core_index::rendering::escape_html;
+use core_index::{redact_private, strip_frontmatter};
+use shared_types::RenderOptions;
+use tracing::{debug, instrument};
+
+/// Styles for the deck; one slide is shown at a time, all of them when printed.
+const DECK_STYLE: &str = "\
+html, body { margin: 0; height: 100%; background: #191919; color: #eee; font-family: Helvetica, Arial, sans-serif; }
+.reveal .slides > section { display: none; box-sizing: border-box; height: 100vh; padding: 6vh 8vw; font-size: 3.2vh; overflow: auto; }
+.reveal .slides > section.present { display: flex; flex-direction: column; justify-content: center; }
+.reveal h1, .reveal h2 { text-align: center; }
+.reveal img { max-width: 100%; max-height: 60vh; align-self: center; }
+.reveal a { color: #42affa; }
+.reveal pre { background: #2b2b2b; padding: 1em; overflow: auto; }
+.embed { border-left: 3px solid #555; padding-left: 1em; }
+.unresolved, .query-block { color: #888; }
+.progress { position: fixed; bottom: 0; left: 0; height: 4px; background: #42affa; }
+@media print { .reveal .slides > section { display: block; height: auto; break-after: page; } .progress { display: none; } }";
+
+/// Arrow keys, space and page keys move between slides; the hash keeps the
+/// current slide across reloads.
+const DECK_SCRIPT: &str = "\
+const slides = document.querySelectorAll('.reveal .slides > section');
+const progress = document.querySelector('.progress');
+let current = 0;
+function show(i) {
+  current = Math.max(0, Math.min(slides.length - 1, i));
+  slides.forEach((s, n) => s.classList.toggle('present', n === current));
+  progress.style.width = ((current + 1) / slides.length * 100) + '%';
+  history.replaceState(null, '', '#/' + (current + 1));
+}
+document.addEventListener('keydown', (e) => {
+  if (['ArrowRight', 'ArrowDown', 'PageDown', ' '].includes(e.key)) { show(current + 1); e.preventDefault(); }
+  if (['ArrowLeft', 'ArrowUp', 'PageUp'].includes(e.key)) { show(current - 1); e.preventDefault(); }
+  if (e.key === 'Home') show(0);
+  if (e.key === 'End') show(slides.length - 1);
+});
+show((parseInt(location.hash.slice(2), 10) || 1) - 1);";
+
+/// A note rendered as an HTML slide deck.
+#[derive(Debug, Clone)]
+pub struct SlideDeck {
+    pub html: String,
+    pub slide_count: usize,
+}
+
+/// Split a note body (without frontmatter) into slides.
+///
+/// Lines that are exactly `---` separate slides; without any, a new slide
+/// starts at each H2 heading. Separators in code blocks are ignored, as are
+/// `---` lines right under paragraph text, which underline a heading
+/// (setext). Empty slides are dropped.
+pub fn split_slides(body: &str) -> Vec<String> {
+    let has_rule = slide_lines(body).any(|(_, rule, _)| rule);
+
+    let mut slides = vec![String::new()];
+    for (line, rule, in_code) in slide_lines(body) {
+        if has_rule && rule {
+            slides.push(String::new());
+            continue;
+        }
+        if !has_rule && !in_code && line.starts_with("## ") {
+            slides.push(String::new());
+        }
+        let slide = slides.last_mut().expect("slides start non-empty");
+        slide.push_str(line);
+        slide.push('\n');
+    }
+
+    slides.retain(|s| !s.trim().is_empty());
+    slides
+}
+
+/// Lines of `body` with whether each is a slide separator, and whether it
+/// is inside (or delimits) a code block.
+fn slide_lines(body: &str) -> impl Iterator<Item = (&str, bool, bool)> {
+    // Whether the previous line is paragraph text a `---` would underline
+    let mut after_text = false;
+    fence_aware_lines(body).map(move |(line, in_code)| {
+        let rule = !in_code && !after_text && line.trim_end() == "---";
+        let trimmed = line.trim_start();
+        after_text = !in_code && !rule && !trimmed.is_empty() && !trimmed.starts_with('#');
+        (line, rule, in_code)
+    })
+}
+
+/// Lines of `body` with whether each is inside (or delimits) a code block.
+fn fence_aware_lines(body: &str) -> impl Iterator<Item = (&str, bool)> {
+    let mut in_code = false;
+    body.lines().map(move |line| {
+        let is_fence = line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~");
+        if is_fence {
+            in_code = !in_code;
+            return (line, true);
+        }
+        (line, in_code)
+    })
+}
+
+/// Render a note as a slide deck. Encrypted notes are only rendered once
+/// the session is unlocked.
+///
+/// Images and attachments are referenced relative to the vault root, which
+/// is set as the document's base URL. The note's `cssclasses` are added to
+/// the deck's `<body>`.
+#[instrument(skip(vault))]
+pub async fn render_slides(vault: &Vault, note_id: i64, options: &RenderOptions) -> Result<SlideDeck> {
+    let note = vault.repo().get_note(note_id).await?;
+    let content = vault.read_note(&note.path).await?;
+    // Still ciphertext until the session is unlocked
+    if has_encrypted_body(&content) {
+        return Err(VaultError::NoteLocked(note.path));
+    }
+    let content = if options.include_private {
+        content.into()
+    } else {
+        redact_private(&content)
+    };
+
+    let mut slides = Vec::new();
+    for slide in split_slides(strip_frontmatter(&content)) {
+        slides.push(render_markdown(vault, &note.path, &slide, options, 0).await);
+    }
+    if slides.is_empty() {
+        slides.push(String::new());
+    }
+
+    let title = note.title.clone().unwrap_or_else(|| note.path.clone());
+    let base_url = base_url(vault.root_path());
+    debug!("Rendered {} as {} slides", note.path, slides.len());
+
+    Ok(SlideDeck {
+        html: deck_document(&title, &base_url, &note.cssclasses, &slides),
+        slide_count: slides.len(),
+    })
+}
+
+/// Assemble the deck: one `<section>` per slide inside reveal.js-style wrappers.
+fn deck_document(title: &str, base_url: &str, cssclasses: &[String], slides: &[String]) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<base href=\"{}\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body class=\"{}\">\n<div class=\"reveal\">\n<div class=\"slides\">\n",
+        escape_html(base_url),
+        escape_html(title),
+        DECK_STYLE,
+        escape_html(&cssclasses.join(" "))
+    );
+
+    for (i, slide) in slides.iter().enumerate() {
+        html.push_str(&format!("<section id=\"slide-{}\">\n{}</section>\n", i + 1, slide));
+    }
+
+    html.push_str("</div>\n</div>\n<div class=\"progress\"></div>\n<script>\n");
+    html.push_str(DECK_SCRIPT);
+    html.push_str("\n</script>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_slides_on_rules() {
+        let body = "# Title\n\nIntro\n\n---\n\n## Agenda\n\n```\n---\n```\n\n---\n\n---\n\n## Done\n";
+        let slides = split_slides(body);
+        assert_eq!(slides.len(), 3);
+        assert!(slides[0].starts_with("# Title"));
+        assert!(slides[1].contains("## Agenda") && slides[1].contains("```\n---\n```"));
+        assert!(slides[2].contains("## Done"));
+    }
+
+    #[test]
+    fn test_split_slides_skips_setext_underlines() {
+        let body = "Heading\n---\n\nText\n\n---\n\n# Next\n---\nMore\n";
+        let slides = split_slides(body);
+        assert_eq!(slides.len(), 3);
+        assert!(slides[0].starts_with("Heading\n---\n"));
+        assert_eq!(slides[1].trim(), "# Next");
+        assert_eq!(slides[2].trim(), "More");
+
+        // Only setext headings: split on H2 headings instead
+        assert_eq!(split_slides("Title\n---\n\n## One\n\n## Two\n").len(), 3);
+    }
+
+    #[test]
+    fn test_split_slides_on_headings() {
+        let body = "# Lesson\n\n## Part one\nText\n\n```md\n## not a slide\n```\n## Part two\nMore\n";
+        let slides = split_slides(body);
+        assert_eq!(slides.len(), 3);
+        assert_eq!(slides[0].trim(), "# Lesson");
+        assert!(slides[1].starts_with("## Part one") && slides[1].contains("## not a slide"));
+        assert!(slides[2].starts_with("## Part two"));
+    }
+
+    #[test]
+    fn test_deck_document() {
+        let slides = vec!["<h1>One</h1>\n".to_string(), "<h2>Two</h2>\n".to_string()];
+        let html = deck_document("Talk <1>", "file:///vault/", &["wide".to_string()], &slides);
+
+        assert!(html.contains("<title>Talk &lt;1&gt;</title>"));
+        assert!(html.contains("<base href=\"file:///vault/\">"));
+        assert!(html.contains("<body class=\"wide\">"));
+        assert!(html.contains("<section id=\"slide-2\">\n<h2>Two</h2>\n</section>"));
+        assert!(html.find("One").unwrap() < html.find("Two").unwrap());
+    }
+}
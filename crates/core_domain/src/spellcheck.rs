@@ -0,0 +1,134 @@
+//! Spell check dictionaries and the vault's custom words.
+//!
+//! Dictionaries (`<lang>.aff` + `<lang>.dic`) are looked up in the vault's
+//! `.neuroflow/dictionaries`, then in the directories passed by the caller
+//! (e.g. dictionaries bundled with the app), then in the system's Hunspell
+//! directories. Each language is parsed once per vault session.
+//!
+//! Words added by the user are kept in `.neuroflow/dictionary.txt`.
+
+use crate::vault::{Result, VaultError};
+use core_index::{decode_dictionary_file, Dictionary};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// System directories searched for Hunspell dictionaries.
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &[
+    "/usr/share/hunspell",
+    "/usr/share/myspell",
+    "/usr/share/myspell/dicts",
+    "/Library/Spelling",
+];
+
+/// Loaded dictionaries and custom words of a vault.
+#[derive(Default)]
+pub struct SpellChecker {
+    dictionaries: HashMap<String, Arc<Dictionary>>,
+    custom_words: Option<Arc<HashSet<String>>>,
+}
+
+impl SpellChecker {
+    /// Get the dictionary for a language (e.g. "en_US"), loading it on first use.
+    pub async fn dictionary(&mut self, lang: &str, search_dirs: &[PathBuf]) -> Result<Arc<Dictionary>> {
+        let lang = normalize_lang(lang)?;
+        if let Some(dictionary) = self.dictionaries.get(&lang) {
+            return Ok(dictionary.clone());
+        }
+
+        let dirs = search_dirs
+            .iter()
+            .cloned()
+            .chain(SYSTEM_DICTIONARY_DIRS.iter().map(PathBuf::from));
+        for dir in dirs {
+            let aff_path = dir.join(format!("{}.aff", lang));
+            let dic_path = dir.join(format!("{}.dic", lang));
+            let (Ok(aff), Ok(dic)) = (tokio::fs::read(&aff_path).await, tokio::fs::read(&dic_path).await) else {
+                continue;
+            };
+
+            let dictionary = tokio::task::spawn_blocking(move || {
+                Dictionary::new(&decode_dictionary_file(&aff), &decode_dictionary_file(&dic))
+            })
+            .await
+            .map_err(|e| VaultError::Spellcheck(e.to_string()))?
+            .map_err(|e| VaultError::Spellcheck(format!("Invalid dictionary {}: {}", dic_path.display(), e)))?;
+
+            info!("Loaded {} dictionary from {}", lang, dir.display());
+            let dictionary = Arc::new(dictionary);
+            self.dictionaries.insert(lang, dictionary.clone());
+            return Ok(dictionary);
+        }
+
+        Err(VaultError::Spellcheck(format!("No dictionary found for {}", lang)))
+    }
+
+    /// Get the custom words, reading them from `path` on first use.
+    pub async fn custom_words(&mut self, path: &Path) -> Result<Arc<HashSet<String>>> {
+        if let Some(words) = &self.custom_words {
+            return Ok(words.clone());
+        }
+
+        let words: HashSet<String> = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content
+                .lines()
+                .map(str::trim)
+                .filter(|w| !w.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(core_fs::FsError::from(e).into()),
+        };
+
+        let words = Arc::new(words);
+        self.custom_words = Some(words.clone());
+        Ok(words)
+    }
+
+    /// Add a word to the custom dictionary at `path`.
+    pub async fn add_word(&mut self, path: &Path, word: &str) -> Result<()> {
+        let word = word.trim();
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            return Err(VaultError::Spellcheck(format!("Not a single word: {:?}", word)));
+        }
+
+        let mut words = (*self.custom_words(path).await?).clone();
+        if !words.insert(word.to_string()) {
+            return Ok(());
+        }
+
+        let mut sorted: Vec<&String> = words.iter().collect();
+        sorted.sort();
+        let content: String = sorted.iter().map(|w| format!("{}\n", w)).collect();
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(core_fs::FsError::from)?;
+        }
+        tokio::fs::write(path, content).await.map_err(core_fs::FsError::from)?;
+
+        debug!("Added {:?} to the custom dictionary", word);
+        self.custom_words = Some(Arc::new(words));
+        Ok(())
+    }
+}
+
+/// Language codes are file names, so only letters, digits, `_`, and `-` are allowed.
+fn normalize_lang(lang: &str) -> Result<String> {
+    let lang = lang.trim().replace('-', "_");
+    if lang.is_empty() || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(VaultError::Spellcheck(format!("Invalid language: {:?}", lang)));
+    }
+    Ok(lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_lang() {
+        assert_eq!(normalize_lang("en-US").unwrap(), "en_US");
+        assert!(normalize_lang("../../etc/passwd").is_err());
+        assert!(normalize_lang("").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Template rendering for daily notes and other templated content.
 
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+use shared_types::TemplateSettings;
 use std::collections::HashMap;
 
 /// Context for template rendering.
@@ -8,16 +9,25 @@ use std::collections::HashMap;
 pub struct TemplateContext {
     /// The date for the template (defaults to today).
     pub date: NaiveDate,
+    /// The date whose ISO week the week variables are for (defaults to `date`).
+    pub week_date: NaiveDate,
     /// Additional custom variables.
     pub custom: HashMap<String, String>,
+    /// User-defined variables from the vault's template settings (name and
+    /// value), in the order they are defined.
+    pub variables: Vec<(String, String)>,
+    /// Snippets from the vault's template settings (name and content), in
+    /// the order they are defined.
+    pub snippets: Vec<(String, String)>,
+    /// Title of the active note, for `{{title}}`.
+    pub note_title: Option<String>,
+    /// Clipboard text, for `{{clipboard}}`.
+    pub clipboard: Option<String>,
 }
 
 impl Default for TemplateContext {
     fn default() -> Self {
-        Self {
-            date: Local::now().date_naive(),
-            custom: HashMap::new(),
-        }
+        Self::for_date(Local::now().date_naive())
     }
 }
 
@@ -26,10 +36,42 @@ impl TemplateContext {
     pub fn for_date(date: NaiveDate) -> Self {
         Self {
             date,
+            week_date: date,
             custom: HashMap::new(),
+            variables: Vec::new(),
+            snippets: Vec::new(),
+            note_title: None,
+            clipboard: None,
         }
     }
 
+    /// Add the user-defined variables and snippets of the vault.
+    pub fn with_settings(mut self, settings: &TemplateSettings) -> Self {
+        self.variables
+            .extend(settings.variables.iter().map(|v| (v.name.clone(), v.value.clone())));
+        self.snippets
+            .extend(settings.snippets.iter().map(|s| (s.name.clone(), s.content.clone())));
+        self
+    }
+
+    /// Set the title of the active note.
+    pub fn with_note_title(mut self, title: impl Into<String>) -> Self {
+        self.note_title = Some(title.into());
+        self
+    }
+
+    /// Set the clipboard text.
+    pub fn with_clipboard(mut self, text: impl Into<String>) -> Self {
+        self.clipboard = Some(text.into());
+        self
+    }
+
+    /// Take the week variables from the ISO week of another date.
+    pub fn with_week_date(mut self, week_date: NaiveDate) -> Self {
+        self.week_date = week_date;
+        self
+    }
+
     /// Add a custom variable.
     pub fn with_var(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.custom.insert(key.into(), value.into());
@@ -43,10 +85,21 @@ impl TemplateContext {
 /// - `{{date}}` - The date in YYYY-MM-DD format
 /// - `{{weekday}}` - The day of the week (Monday, Tuesday, etc.)
 /// - `{{week}}` - The ISO week number
+/// - `{{week_year}}` - The year the ISO week belongs to (differs from
+///   `{{year}}` around New Year)
+/// - `{{iso_week}}` - The ISO week as "2024-W15"
 /// - `{{year}}` - The year
 /// - `{{month}}` - The month number (01-12)
 /// - `{{day}}` - The day of month (01-31)
 /// - `{{month_name}}` - The month name (January, February, etc.)
+/// - `{{date+7d}}`, `{{date-1w:DD.MM.YYYY}}`, `{{date:dddd}}` - The date
+///   moved by days (d), weeks (w), months (m) or years (y), and formatted
+///   with YYYY, YY, MMMM, MMM, MM, DD, dddd, ddd and WW (ISO week); text
+///   in brackets is kept as is (`{{date:YYYY-[W]WW}}`)
+/// - `{{title}}` - The title of the active note, if given
+/// - `{{clipboard}}` - The clipboard text, if given
+/// - `{{snippet:name}}` - A snippet, rendered like the rest
+/// - The user-defined variables (which may use the variables above)
 /// - Any custom variables from the context
 pub fn render_template(input: &str, ctx: &TemplateContext) -> String {
     let weekday_names = [
@@ -76,18 +129,42 @@ pub fn render_template(input: &str, ctx: &TemplateContext) -> String {
 
     let weekday = weekday_names[ctx.date.weekday().num_days_from_monday() as usize];
     let month_name = month_names[ctx.date.month0() as usize];
-    let week = ctx.date.iso_week().week();
+    let week = ctx.week_date.iso_week();
 
     let mut result = input.to_string();
 
+    // Snippets and user-defined variables first, so they can use the rest.
+    // Each is replaced after those defined after it, so it can use the ones
+    // defined before it. Custom variables of the caller win over
+    // user-defined ones.
+    for (name, content) in ctx.snippets.iter().rev() {
+        result = result.replace(&format!("{{{{snippet:{}}}}}", name), content);
+    }
+    for (key, value) in ctx.variables.iter().rev() {
+        if !ctx.custom.contains_key(key) {
+            result = result.replace(&format!("{{{{{}}}}}", key), value);
+        }
+    }
+
+    // Moved and formatted dates
+    result = render_date_expressions(&result, ctx.date);
+
     // Built-in variables
     result = result.replace("{{date}}", &ctx.date.format("%Y-%m-%d").to_string());
     result = result.replace("{{weekday}}", weekday);
-    result = result.replace("{{week}}", &format!("{:02}", week));
+    result = result.replace("{{iso_week}}", &format!("{}-W{:02}", week.year(), week.week()));
+    result = result.replace("{{week}}", &format!("{:02}", week.week()));
+    result = result.replace("{{week_year}}", &week.year().to_string());
     result = result.replace("{{year}}", &ctx.date.year().to_string());
     result = result.replace("{{month}}", &format!("{:02}", ctx.date.month()));
     result = result.replace("{{day}}", &format!("{:02}", ctx.date.day()));
     result = result.replace("{{month_name}}", month_name);
+    if let Some(title) = &ctx.note_title {
+        result = result.replace("{{title}}", title);
+    }
+    if let Some(clipboard) = &ctx.clipboard {
+        result = result.replace("{{clipboard}}", clipboard);
+    }
 
     // Custom variables
     for (key, value) in &ctx.custom {
@@ -98,6 +175,131 @@ pub fn render_template(input: &str, ctx: &TemplateContext) -> String {
     result
 }
 
+/// Replace each `{{date<offset>:<format>}}` (with an offset, a format or
+/// both) with the date it describes. Invalid expressions are left as is.
+fn render_date_expressions(input: &str, date: NaiveDate) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("{{date") {
+        result.push_str(&rest[..start]);
+        let expression = &rest[start + "{{date".len()..];
+        let rendered = expression
+            .find("}}")
+            .and_then(|end| render_date_expression(&expression[..end], date).map(|text| (text, end)));
+        match rendered {
+            Some((text, end)) => {
+                result.push_str(&text);
+                rest = &expression[end + 2..];
+            }
+            None => {
+                result.push_str("{{date");
+                rest = expression;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Render what follows `{{date` in a date expression: `+7d`, `:YYYY` or
+/// `-1m:MMMM YYYY`. None for plain `{{date}}` and anything unrecognized.
+fn render_date_expression(expression: &str, date: NaiveDate) -> Option<String> {
+    if expression.is_empty() {
+        return None;
+    }
+    let (offset, format) = match expression.split_once(':') {
+        Some((offset, format)) => (offset, Some(format)),
+        None => (expression, None),
+    };
+
+    let date = if offset.is_empty() { date } else { move_date(date, offset)? };
+    let format = format.map(chrono_format).unwrap_or_else(|| "%Y-%m-%d".to_string());
+    Some(date.format(&format).to_string())
+}
+
+/// Move a date by an offset like `+7d` or `-1m`: a sign, digits and a
+/// unit. None for anything else, and for dates out of range.
+fn move_date(date: NaiveDate, offset: &str) -> Option<NaiveDate> {
+    let mut chars = offset.chars();
+    let forward = match chars.next()? {
+        '+' => true,
+        '-' => false,
+        _ => return None,
+    };
+    let unit = chars.next_back()?;
+    let digits = chars.as_str();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: u32 = digits.parse().ok()?;
+
+    let months = match unit {
+        'd' | 'w' => {
+            let days = if unit == 'd' { Duration::try_days(amount.into())? } else { Duration::try_weeks(amount.into())? };
+            return if forward { date.checked_add_signed(days) } else { date.checked_sub_signed(days) };
+        }
+        'm' => Months::new(amount),
+        'y' => Months::new(amount.checked_mul(12)?),
+        _ => return None,
+    };
+    if forward {
+        date.checked_add_months(months)
+    } else {
+        date.checked_sub_months(months)
+    }
+}
+
+/// Convert a date format like "DD.MM.YYYY" to a chrono format string.
+/// Text in brackets ("[W]WW") is literal.
+fn chrono_format(format: &str) -> String {
+    const TOKENS: [(&str, &str); 9] = [
+        ("YYYY", "%Y"),
+        ("YY", "%y"),
+        ("MMMM", "%B"),
+        ("MMM", "%b"),
+        ("MM", "%m"),
+        ("DD", "%d"),
+        ("dddd", "%A"),
+        ("ddd", "%a"),
+        ("WW", "%V"),
+    ];
+
+    let mut result = String::new();
+    let mut rest = format;
+    'outer: while let Some(c) = rest.chars().next() {
+        if c == '[' {
+            if let Some(end) = rest.find(']') {
+                result.push_str(&rest[1..end].replace('%', "%%"));
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        for (token, replacement) in TOKENS {
+            if let Some(after) = rest.strip_prefix(token) {
+                result.push_str(replacement);
+                rest = after;
+                continue 'outer;
+            }
+        }
+        if c == '%' {
+            result.push_str("%%");
+        } else {
+            result.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    result
+}
+
+/// Pick the journal prompt for a date, rotating through the list one prompt per day.
+pub fn journal_prompt_for_date(prompts: &[String], date: NaiveDate) -> Option<&str> {
+    if prompts.is_empty() {
+        return None;
+    }
+    let index = date.num_days_from_ce().rem_euclid(prompts.len() as i32) as usize;
+    Some(prompts[index].as_str())
+}
+
 /// Get the daily note path for a date.
 pub fn daily_note_path(date: NaiveDate, folder: &str) -> String {
     let filename = date.format("%Y-%m-%d.md").to_string();
@@ -111,6 +313,7 @@ pub fn daily_note_path(date: NaiveDate, folder: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared_types::{TemplateSnippet, TemplateVariable};
 
     #[test]
     fn test_render_template() {
@@ -120,6 +323,10 @@ mod tests {
 
         assert!(result.contains("# 2025-12-07 – Sunday"));
         assert!(result.contains("Week 49 of 2025"));
+
+        let ctx = TemplateContext::for_date(NaiveDate::from_ymd_opt(2024, 12, 30).unwrap());
+        assert_eq!(render_template("{{week_year}}-W{{week}} ({{year}})", &ctx), "2025-W01 (2024)");
+        assert_eq!(render_template("journal/{{iso_week}}.md", &ctx), "journal/2025-W01.md");
     }
 
     #[test]
@@ -131,6 +338,79 @@ mod tests {
         assert_eq!(result, "Created by John");
     }
 
+    #[test]
+    fn test_date_expressions() {
+        let ctx = TemplateContext::for_date(NaiveDate::from_ymd_opt(2025, 1, 31).unwrap());
+
+        assert_eq!(render_template("{{date+7d}}", &ctx), "2025-02-07");
+        assert_eq!(render_template("{{date-1w:DD.MM.YYYY}}", &ctx), "24.01.2025");
+        assert_eq!(render_template("{{date+1m:MMMM YYYY}}", &ctx), "February 2025");
+        assert_eq!(render_template("{{date-1y}} / {{date:dddd, WW}}", &ctx), "2024-01-31 / Friday, 05");
+        assert_eq!(render_template("100%: {{date:YY}}", &ctx), "100%: 25");
+        assert_eq!(render_template("Weekly/{{date:YYYY-[W]WW}}.md", &ctx), "Weekly/2025-W05.md");
+        // Unrecognized expressions are left alone
+        assert_eq!(render_template("{{date+7x}} {{date}}", &ctx), "{{date+7x}} 2025-01-31");
+        for expression in ["{{date+}}", "{{date-:YYYY}}", "{{date+7é}}", "{{date+-3d}}", "{{date+99999999999d}}", "{{date+4294967295y}}"] {
+            assert_eq!(render_template(expression, &ctx), expression);
+        }
+    }
+
+    #[test]
+    fn test_user_variables_and_snippets() {
+        let settings = TemplateSettings {
+            variables: vec![
+                TemplateVariable { name: "author".to_string(), value: "Sam".to_string() },
+                TemplateVariable { name: "sprint".to_string(), value: "Sprint {{week}}".to_string() },
+            ],
+            snippets: vec![TemplateSnippet {
+                name: "meeting".to_string(),
+                content: "## {{title}} ({{sprint}})\n\n{{clipboard}}".to_string(),
+            }],
+            ..Default::default()
+        };
+        let ctx = TemplateContext::for_date(NaiveDate::from_ymd_opt(2025, 12, 7).unwrap())
+            .with_settings(&settings)
+            .with_note_title("Planning")
+            .with_clipboard("Agenda");
+
+        assert_eq!(
+            render_template("{{snippet:meeting}}\nby {{author}}", &ctx),
+            "## Planning (Sprint 49)\n\nAgenda\nby Sam"
+        );
+        // Variables and snippets can use those defined before them
+        let chained = TemplateSettings {
+            variables: vec![
+                TemplateVariable { name: "team".to_string(), value: "Core".to_string() },
+                TemplateVariable { name: "owner".to_string(), value: "{{team}} lead".to_string() },
+            ],
+            snippets: vec![
+                TemplateSnippet { name: "sign".to_string(), content: "-- {{owner}}".to_string() },
+                TemplateSnippet { name: "footer".to_string(), content: "Done\n{{snippet:sign}}".to_string() },
+            ],
+            ..Default::default()
+        };
+        let ctx = TemplateContext::default().with_settings(&chained);
+        assert_eq!(render_template("{{snippet:footer}}", &ctx), "Done\n-- Core lead");
+
+        // Without a title, {{title}} is left for later
+        let ctx = TemplateContext::default().with_settings(&settings).with_var("author", "Kim");
+        assert_eq!(render_template("{{title}} by {{author}}", &ctx), "{{title}} by Kim");
+    }
+
+    #[test]
+    fn test_journal_prompt_rotation() {
+        let prompts = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let day = NaiveDate::from_ymd_opt(2025, 12, 7).unwrap();
+
+        let first = journal_prompt_for_date(&prompts, day).unwrap();
+        let second = journal_prompt_for_date(&prompts, day.succ_opt().unwrap()).unwrap();
+        let after_cycle = journal_prompt_for_date(&prompts, day + chrono::Duration::days(3)).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(first, after_cycle);
+        assert_eq!(journal_prompt_for_date(&[], day), None);
+    }
+
     #[test]
     fn test_daily_note_path() {
         let date = NaiveDate::from_ymd_opt(2025, 12, 7).unwrap();
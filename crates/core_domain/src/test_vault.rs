@@ -0,0 +1,303 @@
+//! Synthetic vault generator for benchmarks and tests.
+//!
+//! Builds a vault of generated notes with tags, properties, todos and
+//! wikilinks, and optionally schedule blocks, so indexing, search and query
+//! performance can be measured on the same vault every time. The output
+//! only depends on the spec: the same seed gives the same vault.
+//!
+//! Tags and property values are picked with a skew towards the first ones,
+//! so a few are common and most are rare, like in a real vault.
+//!
+//! Only built with the `test-vault` feature.
+
+use crate::vault::{Result, Vault};
+use chrono::{Duration, NaiveDate};
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use tracing::{info, instrument};
+
+const WORDS: &[&str] = &[
+    "project", "meeting", "idea", "review", "draft", "plan", "research", "notes", "design", "question",
+    "summary", "follow", "budget", "release", "feedback", "reading", "garden", "travel", "health", "habit",
+    "focus", "weekly", "client", "sketch", "outline", "archive", "insight", "problem", "decision", "context",
+];
+
+/// What to generate.
+#[derive(Debug, Clone)]
+pub struct TestVaultSpec {
+    /// Number of notes.
+    pub notes: usize,
+    /// Number of folders the notes are spread over (0 puts them in the root).
+    pub folders: usize,
+    /// Number of distinct tags.
+    pub tags: usize,
+    pub tags_per_note: RangeInclusive<usize>,
+    /// Number of distinct property keys.
+    pub property_keys: usize,
+    /// Number of distinct values per property key.
+    pub property_values: usize,
+    pub properties_per_note: RangeInclusive<usize>,
+    pub todos_per_note: RangeInclusive<usize>,
+    /// Share of todos that are checked off (0.0 to 1.0).
+    pub completed_todos: f64,
+    /// Wikilinks to other generated notes.
+    pub links_per_note: RangeInclusive<usize>,
+    pub paragraphs_per_note: RangeInclusive<usize>,
+    /// Schedule blocks linked to random notes. Creating them indexes the
+    /// vault, as blocks are only stored in the database.
+    pub schedule_blocks: usize,
+    /// Schedule blocks are spread over the 90 days from this date.
+    pub schedule_start: NaiveDate,
+    pub seed: u64,
+}
+
+impl Default for TestVaultSpec {
+    fn default() -> Self {
+        Self {
+            notes: 1000,
+            folders: 10,
+            tags: 50,
+            tags_per_note: 0..=3,
+            property_keys: 10,
+            property_values: 20,
+            properties_per_note: 1..=5,
+            todos_per_note: 0..=4,
+            completed_todos: 0.3,
+            links_per_note: 0..=5,
+            paragraphs_per_note: 1..=5,
+            schedule_blocks: 0,
+            schedule_start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            seed: 42,
+        }
+    }
+}
+
+/// What was generated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestVaultSummary {
+    pub notes: usize,
+    pub tags: usize,
+    pub properties: usize,
+    pub todos: usize,
+    pub links: usize,
+    pub schedule_blocks: usize,
+}
+
+/// Generate a vault at `path` (created if missing).
+///
+/// Existing files with the same names are overwritten. The vault is only
+/// indexed if the spec asks for schedule blocks.
+#[instrument(skip(spec))]
+pub async fn generate_test_vault(path: &Path, spec: &TestVaultSpec) -> Result<TestVaultSummary> {
+    let mut rng = Rng::new(spec.seed);
+    let mut summary = TestVaultSummary::default();
+
+    for i in 0..spec.notes {
+        let note_path = path.join(note_path(spec, i));
+        if let Some(parent) = note_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(core_fs::FsError::from)?;
+        }
+        let content = note_content(spec, i, &mut rng, &mut summary);
+        tokio::fs::write(&note_path, content).await.map_err(core_fs::FsError::from)?;
+        summary.notes += 1;
+    }
+
+    if spec.schedule_blocks > 0 && spec.notes > 0 {
+        let vault = Vault::open(path).await?;
+        vault.full_index().await?;
+
+        for _ in 0..spec.schedule_blocks {
+            let note_id = vault.repo().get_note_id_by_path(&note_path(spec, rng.below(spec.notes))).await?;
+            let date = spec.schedule_start + Duration::days(rng.below(90) as i64);
+            let start_hour = 8 + rng.below(10);
+            let end_minutes = start_hour * 60 + 30 * (1 + rng.below(4));
+            vault
+                .repo()
+                .create_schedule_block(
+                    note_id,
+                    &date.format("%Y-%m-%d").to_string(),
+                    &format!("{:02}:00", start_hour),
+                    &format!("{:02}:{:02}", end_minutes / 60, end_minutes % 60),
+                    Some(WORDS[rng.below(WORDS.len())]),
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+            summary.schedule_blocks += 1;
+        }
+        vault.close().await;
+    }
+
+    info!("Generated test vault at {}: {:?}", path.display(), summary);
+    Ok(summary)
+}
+
+/// Vault-relative path of the `i`th note.
+fn note_path(spec: &TestVaultSpec, i: usize) -> String {
+    if spec.folders == 0 {
+        format!("{}.md", note_name(i))
+    } else {
+        format!("folder-{:02}/{}.md", i % spec.folders, note_name(i))
+    }
+}
+
+fn note_name(i: usize) -> String {
+    format!("note-{:05}", i)
+}
+
+/// Content of the `i`th note, counting what it contains into `summary`.
+fn note_content(spec: &TestVaultSpec, i: usize, rng: &mut Rng, summary: &mut TestVaultSummary) -> String {
+    let mut content = String::from("---\n");
+
+    let tag_count = if spec.tags == 0 { 0 } else { rng.in_range(&spec.tags_per_note) };
+    let mut tags: Vec<usize> = (0..tag_count).map(|_| rng.skewed(spec.tags)).collect();
+    tags.sort_unstable();
+    tags.dedup();
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags.iter().map(|t| format!("tag-{}", t)).collect();
+        let _ = writeln!(content, "tags: [{}]", tags.join(", "));
+        summary.tags += tags.len();
+    }
+
+    let property_count = rng.in_range(&spec.properties_per_note).min(spec.property_keys);
+    for key in rng.sample(spec.property_keys, property_count) {
+        let value = rng.skewed(spec.property_values.max(1));
+        let _ = writeln!(content, "field-{}: value-{}", key, value);
+        summary.properties += 1;
+    }
+    content.push_str("---\n\n");
+
+    let _ = writeln!(content, "# Note {}\n", i);
+
+    for _ in 0..rng.in_range(&spec.paragraphs_per_note) {
+        let words: Vec<&str> = (0..8 + rng.below(40)).map(|_| WORDS[rng.below(WORDS.len())]).collect();
+        let _ = writeln!(content, "{}.\n", words.join(" "));
+    }
+
+    let links = rng.in_range(&spec.links_per_note);
+    if links > 0 && spec.notes > 1 {
+        let targets: Vec<String> = (0..links)
+            .map(|_| format!("[[{}]]", note_name(rng.below(spec.notes))))
+            .collect();
+        let _ = writeln!(content, "See {}.\n", targets.join(", "));
+        summary.links += links;
+    }
+
+    let todos = rng.in_range(&spec.todos_per_note);
+    for t in 0..todos {
+        let check = if rng.unit() < spec.completed_todos { "x" } else { " " };
+        let _ = writeln!(content, "- [{}] {} task {}", check, WORDS[rng.below(WORDS.len())], t + 1);
+    }
+    summary.todos += todos;
+
+    content
+}
+
+/// Small deterministic random number generator (SplitMix64), so generated
+/// vaults are the same on every platform and run.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in [0, 1).
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A number in [0, n), or 0 if n is 0.
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            (self.next_u64() % n as u64) as usize
+        }
+    }
+
+    fn in_range(&mut self, range: &RangeInclusive<usize>) -> usize {
+        if range.is_empty() {
+            return *range.start();
+        }
+        range.start() + self.below(range.end() - range.start() + 1)
+    }
+
+    /// A number in [0, n) where low numbers are more likely.
+    fn skewed(&mut self, n: usize) -> usize {
+        let u = self.unit();
+        ((u * u * n as f64) as usize).min(n.saturating_sub(1))
+    }
+
+    /// `count` distinct numbers from [0, n), in ascending order.
+    fn sample(&mut self, n: usize, count: usize) -> Vec<usize> {
+        let mut picked = Vec::with_capacity(count);
+        while picked.len() < count.min(n) {
+            let candidate = self.skewed(n);
+            if !picked.contains(&candidate) {
+                picked.push(candidate);
+            }
+        }
+        picked.sort_unstable();
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_index::{markdown::parse, parse_frontmatter};
+
+    #[test]
+    fn test_note_content_is_deterministic_and_parses() {
+        let spec = TestVaultSpec {
+            todos_per_note: 2..=2,
+            links_per_note: 3..=3,
+            properties_per_note: 2..=2,
+            ..Default::default()
+        };
+
+        let mut summary = TestVaultSummary::default();
+        let first = note_content(&spec, 7, &mut Rng::new(1), &mut summary);
+        let second = note_content(&spec, 7, &mut Rng::new(1), &mut TestVaultSummary::default());
+        assert_eq!(first, second);
+
+        let (frontmatter, _) = parse_frontmatter(&first);
+        assert_eq!(frontmatter.properties.keys().filter(|k| k.starts_with("field-")).count(), 2);
+
+        let analysis = parse(&first);
+        assert_eq!(analysis.title.as_deref(), Some("Note 7"));
+        assert_eq!(analysis.todos.len(), 2);
+        assert_eq!(summary.todos, 2);
+        assert_eq!(summary.links, 3);
+    }
+
+    #[tokio::test]
+    async fn test_generate_test_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = TestVaultSpec {
+            notes: 25,
+            folders: 3,
+            schedule_blocks: 5,
+            ..Default::default()
+        };
+
+        let summary = generate_test_vault(dir.path(), &spec).await.unwrap();
+        assert_eq!(summary.notes, 25);
+        assert_eq!(summary.schedule_blocks, 5);
+        assert!(dir.path().join("folder-01/note-00004.md").exists());
+
+        let vault = Vault::open(dir.path()).await.unwrap();
+        assert_eq!(vault.list_notes().await.unwrap().len(), 25);
+    }
+}
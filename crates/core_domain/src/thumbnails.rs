@@ -0,0 +1,158 @@
+//! Cached thumbnails for image embeds, galleries, and folder previews.
+//!
+//! Thumbnails are WebP files under `.neuroflow/cache/thumbnails/`, named
+//! after a hash of the source path and the requested size. A thumbnail older
+//! than its source is regenerated. Images that already fit, and formats that
+//! can't be resized (SVG), are returned as they are.
+
+use crate::vault::{Result, Vault, VaultError};
+use core_fs::hash_content;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument};
+
+/// Image formats thumbnails are generated for.
+const THUMBNAIL_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+/// Smallest thumbnail size, in pixels.
+const MIN_DIMENSION: u32 = 16;
+
+/// Largest thumbnail size, in pixels.
+const MAX_DIMENSION: u32 = 2048;
+
+/// Get a thumbnail of the image at `path` that fits in `max_dimension` pixels.
+///
+/// Returns the absolute path of the cached thumbnail, or of the source image
+/// if no thumbnail is needed.
+#[instrument(skip(vault))]
+pub async fn get_thumbnail(vault: &Vault, path: &str, max_dimension: u32) -> Result<PathBuf> {
+    let source = vault.fs().to_absolute(Path::new(path));
+    let source_modified = tokio::fs::metadata(&source)
+        .await
+        .and_then(|m| m.modified())
+        .map_err(|_| core_fs::FsError::NotFound(source.clone()))?;
+
+    if !is_thumbnail_format(path) {
+        return Ok(source);
+    }
+
+    let max_dimension = max_dimension.clamp(MIN_DIMENSION, MAX_DIMENSION);
+    let cached = thumbnail_path(&vault.fs().cache_dir(), path, max_dimension);
+
+    let fresh = tokio::fs::metadata(&cached)
+        .await
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified >= source_modified);
+    if fresh {
+        return Ok(cached);
+    }
+
+    let (from, to) = (source.clone(), cached.clone());
+    let generated = tokio::task::spawn_blocking(move || generate_thumbnail(&from, &to, max_dimension))
+        .await
+        .map_err(|e| VaultError::Thumbnail(e.to_string()))??;
+
+    if generated {
+        debug!("Generated thumbnail for {}", path);
+        Ok(cached)
+    } else {
+        Ok(source)
+    }
+}
+
+/// Cache file for a thumbnail of `path` at `max_dimension`.
+fn thumbnail_path(cache_dir: &Path, path: &str, max_dimension: u32) -> PathBuf {
+    cache_dir
+        .join("thumbnails")
+        .join(format!("{}-{}.webp", hash_content(path), max_dimension))
+}
+
+fn is_thumbnail_format(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| THUMBNAIL_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Resize `source` into `target` as WebP.
+///
+/// Returns false, without writing anything, if the image already fits.
+fn generate_thumbnail(source: &Path, target: &Path, max_dimension: u32) -> Result<bool> {
+    let reader = || -> Result<_> {
+        ImageReader::open(source)
+            .map_err(core_fs::FsError::from)?
+            .with_guessed_format()
+            .map_err(|e| VaultError::Thumbnail(e.to_string()))
+    };
+
+    let (width, height) = reader()?
+        .into_dimensions()
+        .map_err(|e| VaultError::Thumbnail(e.to_string()))?;
+    if width <= max_dimension && height <= max_dimension {
+        return Ok(false);
+    }
+
+    let image = reader()?
+        .decode()
+        .map_err(|e| VaultError::Thumbnail(e.to_string()))?;
+    let thumbnail = DynamicImage::ImageRgba8(image.thumbnail(max_dimension, max_dimension).to_rgba8());
+
+    let parent = target.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent).map_err(core_fs::FsError::from)?;
+
+    // Write to a uniquely named file in the same folder first, so readers
+    // never see a partial thumbnail and concurrent requests don't collide
+    let mut partial = tempfile::Builder::new()
+        .suffix(".webp.tmp")
+        .tempfile_in(parent)
+        .map_err(core_fs::FsError::from)?;
+    let mut writer = std::io::BufWriter::new(partial.as_file_mut());
+    thumbnail
+        .write_to(&mut writer, ImageFormat::WebP)
+        .map_err(|e| VaultError::Thumbnail(e.to_string()))?;
+    writer.flush().map_err(core_fs::FsError::from)?;
+    drop(writer);
+    partial
+        .persist(target)
+        .map_err(|e| core_fs::FsError::from(e.error))?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn test_thumbnail_path_and_format() {
+        let cache = Path::new("/vault/.neuroflow/cache");
+        let small = thumbnail_path(cache, "assets/photo.jpg", 128);
+        let large = thumbnail_path(cache, "assets/photo.jpg", 512);
+
+        assert!(small.starts_with("/vault/.neuroflow/cache/thumbnails"));
+        assert!(small.to_string_lossy().ends_with("-128.webp"));
+        assert_ne!(small, large);
+
+        assert!(is_thumbnail_format("assets/photo.JPG"));
+        assert!(!is_thumbnail_format("assets/diagram.svg"));
+        assert!(!is_thumbnail_format("notes/plan.md"));
+    }
+
+    #[test]
+    fn test_generate_thumbnail() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("photo.png");
+        RgbImage::new(64, 32).save(&source).unwrap();
+
+        let target = dir.path().join("cache/thumb.webp");
+        assert!(generate_thumbnail(&source, &target, 16).unwrap());
+        assert_eq!(image::image_dimensions(&target).unwrap(), (16, 8));
+
+        // Images that already fit are left alone
+        let unused = dir.path().join("cache/unused.webp");
+        assert!(!generate_thumbnail(&source, &unused, 64).unwrap());
+        assert!(!unused.exists());
+    }
+}
@@ -0,0 +1,197 @@
+//! Keeping note titles and filenames in sync.
+//!
+//! A note's title is its `title` frontmatter property, or else its first H1
+//! heading. Notes without either are never reported. A filename matches
+//! when it equals the title with characters that aren't allowed in file
+//! names replaced, or the file name `create_note` would generate for it.
+
+use crate::attachments::sanitize_file_name;
+use crate::encryption::has_encrypted_body;
+use crate::vault::{Result, Vault};
+use core_fs::note_file_name;
+use core_index::markdown::parse;
+use core_index::{parse_frontmatter, set_frontmatter_property, PropertyValue};
+use shared_types::{NoteDto, TitleMismatch, TitlePolicy, TitleSettings, TitleSource};
+use std::path::Path;
+use tracing::{debug, info, instrument};
+
+/// Find notes whose title and filename differ.
+#[instrument(skip(vault, settings))]
+pub async fn detect_title_mismatches(vault: &Vault, settings: &TitleSettings) -> Result<Vec<TitleMismatch>> {
+    let mut mismatches = Vec::new();
+    for note in vault.list_notes().await? {
+        if is_ignored(settings, &note.path) {
+            continue;
+        }
+        let content = match vault.read_note(&note.path).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Skipping {}: {}", note.path, e);
+                continue;
+            }
+        };
+        if let Some(mismatch) = title_mismatch(note.id, &note.path, &content) {
+            mismatches.push(mismatch);
+        }
+    }
+
+    info!("Found {} title/filename mismatches", mismatches.len());
+    Ok(mismatches)
+}
+
+/// Bring a note's title and filename in sync with `strategy`.
+///
+/// Renaming updates links to the note across the vault; it fails if a note
+/// with the new name already exists. Returns the note after the fix.
+#[instrument(skip(vault))]
+pub async fn fix_title_mismatch(vault: &Vault, note_id: i64, strategy: TitlePolicy) -> Result<NoteDto> {
+    let note = vault.repo().get_note(note_id).await?;
+    let content = vault.read_note(&note.path).await?;
+
+    let Some(mismatch) = title_mismatch(note_id, &note.path, &content) else {
+        return Ok(note);
+    };
+
+    match strategy {
+        TitlePolicy::FilenameFromTitle => {
+            vault.rename_note(&note.path, &mismatch.suggested_path).await?;
+        }
+        TitlePolicy::TitleFromFilename => {
+            let updated = set_title(&content, mismatch.title_source, &mismatch.suggested_title);
+            vault.write_note(&note.path, &updated).await?;
+        }
+    }
+
+    Ok(vault.repo().get_note(note_id).await?)
+}
+
+fn is_ignored(settings: &TitleSettings, path: &str) -> bool {
+    settings.ignored_folders.iter().any(|folder| {
+        let folder = folder.trim_matches('/');
+        folder.is_empty() || path.strip_prefix(folder).is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Compare a note's title with its filename.
+fn title_mismatch(note_id: i64, path: &str, content: &str) -> Option<TitleMismatch> {
+    if has_encrypted_body(content) {
+        return None;
+    }
+
+    let (title, title_source) = note_title(content)?;
+    let file_name = note_file_name(&title);
+    let stem = Path::new(path).file_stem()?.to_str()?;
+    if stem == sanitize_file_name(&title) || format!("{}.md", stem) == file_name {
+        return None;
+    }
+
+    let suggested_path = match Path::new(path).parent().and_then(|p| p.to_str()) {
+        Some(parent) if !parent.is_empty() => format!("{}/{}", parent, file_name),
+        _ => file_name,
+    };
+
+    Some(TitleMismatch {
+        note_id,
+        path: path.to_string(),
+        title,
+        title_source,
+        suggested_path,
+        suggested_title: stem.to_string(),
+    })
+}
+
+/// The note's title and where it comes from.
+fn note_title(content: &str) -> Option<(String, TitleSource)> {
+    let (frontmatter, _) = parse_frontmatter(content);
+    if let Some(PropertyValue::String(title)) = frontmatter.properties.get("title") {
+        if !title.trim().is_empty() {
+            return Some((title.trim().to_string(), TitleSource::Frontmatter));
+        }
+    }
+
+    parse(content)
+        .title
+        .filter(|title| !title.is_empty())
+        .map(|title| (title, TitleSource::Heading))
+}
+
+/// Replace the title where it comes from.
+fn set_title(content: &str, source: TitleSource, title: &str) -> String {
+    if source == TitleSource::Frontmatter {
+        return set_frontmatter_property(content, "title", Some(title), None);
+    }
+
+    let (_, body) = parse_frontmatter(content);
+    let Some(heading) = parse(content).headings.into_iter().find(|h| h.level == 1) else {
+        return content.to_string();
+    };
+
+    let mut updated = content[..content.len() - body.len()].to_string();
+    for (i, line) in body.split_inclusive('\n').enumerate() {
+        if i + 1 != heading.line_number {
+            updated.push_str(line);
+            continue;
+        }
+        let line_ending = &line[line.trim_end_matches(['\n', '\r']).len()..];
+        // Setext headings keep their underline on the next line
+        if line.trim_start().starts_with('#') {
+            updated.push_str(&format!("# {}{}", title, line_ending));
+        } else {
+            updated.push_str(&format!("{}{}", title, line_ending));
+        }
+    }
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_title() {
+        assert_eq!(
+            note_title("---\ntitle: From Frontmatter\n---\n# Heading\n"),
+            Some(("From Frontmatter".to_string(), TitleSource::Frontmatter))
+        );
+        assert_eq!(
+            note_title("Intro\n\n# The **Heading**\n"),
+            Some(("The Heading".to_string(), TitleSource::Heading))
+        );
+        assert_eq!(note_title("## Only a subheading\n"), None);
+    }
+
+    #[test]
+    fn test_title_mismatch() {
+        assert!(title_mismatch(1, "notes/Plan.md", "# Plan\n").is_none());
+        assert!(title_mismatch(1, "notes/A-B.md", "# A/B\n").is_none());
+        assert!(title_mismatch(1, "Café.md", "# Café\n").is_none());
+        assert!(title_mismatch(1, "Cafe.md", "# Café\n").is_none());
+
+        let mismatch = title_mismatch(1, "notes/draft.md", "# Project Plan\n").unwrap();
+        assert_eq!(mismatch.suggested_path, "notes/Project Plan.md");
+        assert_eq!(mismatch.suggested_title, "draft");
+
+        let mismatch = title_mismatch(1, "draft.md", "# Café: Plan?\n").unwrap();
+        assert_eq!(mismatch.suggested_path, "Cafe- Plan-.md");
+    }
+
+    #[test]
+    fn test_set_title() {
+        assert_eq!(
+            set_title("---\ntags: [a]\n---\n\n# Old\r\nBody\n", TitleSource::Heading, "New"),
+            "---\ntags: [a]\n---\n\n# New\r\nBody\n"
+        );
+        assert_eq!(set_title("Old\n===\n", TitleSource::Heading, "New"), "New\n===\n");
+        assert!(set_title("---\ntitle: Old\n---\nBody\n", TitleSource::Frontmatter, "New").contains("title: New"));
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        let settings = TitleSettings {
+            policy: TitlePolicy::FilenameFromTitle,
+            ignored_folders: vec!["daily/".to_string()],
+        };
+        assert!(is_ignored(&settings, "daily/2024-05-01.md"));
+        assert!(!is_ignored(&settings, "dailyish/note.md"));
+    }
+}
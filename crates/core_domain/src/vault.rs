@@ -1,16 +1,33 @@
 //! Vault management - opening, indexing, and coordinating vault operations.
 
+use crate::autocomplete::AutocompleteIndex;
+use crate::changes::ChangeJournal;
+use crate::diagrams::prerender_diagrams;
+use crate::encryption::{
+    decrypt_note, encrypt_note, has_encrypted_body, is_marked_encrypted, key_check, verify_key_check, EncryptionError,
+};
+use crate::indexer::{indexed_part, FileIndexer};
+use crate::jobs::Job;
+use crate::perf::SLOW_QUERY_THRESHOLD;
+use crate::links::relink;
+use crate::query_cache::QueryCache;
+use crate::quick_switch::QuickSwitchIndex;
+use crate::resolve::NoteResolver;
+use crate::spellcheck::SpellChecker;
 use crate::watcher::FileWatcher;
-use core_fs::{hash_content, VaultFs};
-use core_index::markdown::{parse, update_wiki_links};
+use core_fs::{hash_content, FileRange, FsError, VaultFs};
+use core_index::check_text;
 use core_storage::{init_database, VaultRepository};
-use shared_types::{IndexCompletePayload, NoteListItem, VaultInfo};
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use shared_types::{AutocompleteItem, AutocompleteKind, ChangeKind, ChangesPage, DeleteImpact, DiagramSettings, ExternalFolder, FileProblem, FileProblemKind, IndexCompletePayload, IndexSettings, JobProgressPayload, LinkCandidate, LinkFormat, LocaleSettings, Misspelling, NoteContent, NoteListItem, NoteResolution, QuickSwitchResult, TemplateSettings, VaultInfo};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::ConnectOptions;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::{broadcast, RwLock};
+use tokio::task::AbortHandle;
 use tracing::{debug, error, info, instrument, warn};
 
 #[derive(Error, Debug)]
@@ -21,9 +38,63 @@ pub enum VaultError {
     #[error("Vault path is not a directory: {0}")]
     NotADirectory(PathBuf),
 
+    #[error("Folder is not empty: {0}")]
+    NotEmpty(PathBuf),
+
     #[error("File already exists: {0}")]
     FileAlreadyExists(String),
 
+    #[error("Invalid import: {0}")]
+    InvalidImport(String),
+
+    #[error("Note is encrypted and locked: {0}")]
+    NoteLocked(String),
+
+    #[error("Note is not encrypted: {0}")]
+    NotEncrypted(String),
+
+    #[error("Note is not valid UTF-8 and would lose its invalid bytes: {0}")]
+    InvalidUtf8(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+
+    #[error("Thumbnail error: {0}")]
+    Thumbnail(String),
+
+    #[error("Spell check error: {0}")]
+    Spellcheck(String),
+
+    #[error("Diagram error: {0}")]
+    Diagram(String),
+
+    #[error("Table error: {0}")]
+    Table(String),
+
+    #[error("Query error: {0}")]
+    Query(String),
+
+    #[error("Unknown note type: {0}")]
+    UnknownNoteType(String),
+
+    #[error("Settings error: {0}")]
+    Settings(String),
+
+    #[error("Backup error: {0}")]
+    Backup(String),
+
+    #[error("Text recognition error: {0}")]
+    Ocr(String),
+
+    #[error("Invalid external folder: {0}")]
+    ExternalFolder(String),
+
+    #[error("Invalid scheduled note rule: {0}")]
+    ScheduledNoteRule(String),
+
+    #[error("Invalid duplicate threshold: {0}")]
+    DuplicateThreshold(f64),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -39,6 +110,20 @@ pub enum VaultError {
 
 pub type Result<T> = std::result::Result<T, VaultError>;
 
+/// How many recently opened notes the quick switcher remembers.
+const MAX_RECENT_NOTES: usize = 20;
+
+/// Folder deleted folders are moved to, like Obsidian's.
+const TRASH_FOLDER: &str = ".trash";
+
+/// How long the full-text index waits after a save before catching up, so
+/// a burst of saves is indexed once.
+const FTS_CATCH_UP_DELAY: Duration = Duration::from_millis(500);
+
+/// How long the vault metrics wait after a change before being snapshot,
+/// so a burst of edits costs one snapshot.
+const METRICS_SNAPSHOT_DELAY: Duration = Duration::from_secs(60);
+
 /// Events emitted by the vault.
 #[derive(Debug, Clone)]
 pub enum VaultEvent {
@@ -48,6 +133,8 @@ pub enum VaultEvent {
     NotesDeleted(Vec<i64>),
     /// Full index complete.
     IndexComplete(IndexCompletePayload),
+    /// Progress of a long-running job.
+    JobProgress(JobProgressPayload),
 }
 
 /// An open vault.
@@ -60,14 +147,51 @@ pub struct Vault {
     watcher: Option<FileWatcher>,
     /// Event sender for vault events.
     event_tx: broadcast::Sender<VaultEvent>,
+    /// Append-only log of note changes, for external tools.
+    changes: ChangeJournal,
     /// Track if initial index is complete.
     indexed: Arc<RwLock<bool>>,
+    /// Result of the latest full index, for diagnostics.
+    last_index: Arc<RwLock<Option<IndexCompletePayload>>>,
+    /// Passphrase for encrypted notes, set by `unlock_note` for this session.
+    note_passphrase: Arc<RwLock<Option<String>>>,
+    /// Quick switcher index, rebuilt when the repository fingerprint changes.
+    quick_switch: Arc<RwLock<Option<QuickSwitchIndex>>>,
+    /// Autocomplete index, built on first use and kept current from vault events.
+    autocomplete: Arc<RwLock<Option<AutocompleteIndex>>>,
+    /// Recently opened note IDs, most recent first.
+    recent_notes: Arc<RwLock<Vec<i64>>>,
+    /// Spell check dictionaries (loaded on first use) and custom words.
+    spell_checker: Arc<RwLock<SpellChecker>>,
+    /// How links are written when the app updates them.
+    link_format: Arc<RwLock<LinkFormat>>,
+    /// Whether a note's previous title becomes an alias when its H1 changes.
+    keep_old_titles: Arc<RwLock<bool>>,
+    /// How diagram blocks are rendered to SVG.
+    diagram_settings: Arc<RwLock<DiagramSettings>>,
+    /// Week start and date formats; the week start resolves `^next-week`.
+    locale_settings: Arc<RwLock<LocaleSettings>>,
+    /// Size limit above which notes are only partly indexed.
+    index_settings: Arc<RwLock<IndexSettings>>,
+    /// Template folder, variables and snippets used by scheduled notes.
+    template_settings: Arc<RwLock<TemplateSettings>>,
+    /// Recent query embed results, cleared when notes change.
+    query_cache: Arc<QueryCache>,
+    /// Task snapshotting the vault metrics, aborted when the vault is
+    /// closed or dropped.
+    metrics_snapshots: AbortHandle,
 }
 
 impl Vault {
     /// Open a vault at the given path.
-    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
     pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_data_dir(path, None).await
+    }
+
+    /// Open a vault, keeping its database and caches in `data_dir` instead
+    /// of the vault's `.neuroflow` folder.
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub async fn open_with_data_dir(path: impl AsRef<Path>, data_dir: Option<PathBuf>) -> Result<Self> {
         let path = path.as_ref();
 
         // Validate path
@@ -81,7 +205,10 @@ impl Vault {
         info!("Opening vault at {}", path.display());
 
         // Create filesystem handle
-        let fs = VaultFs::new(path);
+        let fs = match data_dir {
+            Some(data_dir) => VaultFs::new(path).with_data_dir(data_dir),
+            None => VaultFs::new(path),
+        };
 
         // Ensure .neuroflow directory exists
         fs.ensure_neuroflow_dir().await?;
@@ -90,9 +217,13 @@ impl Vault {
         let db_path = fs.db_path();
         info!("Database path: {}", db_path.display());
 
+        // WAL mode lets the read pool query while an import or full index
+        // is writing
         let options = SqliteConnectOptions::new()
             .filename(&db_path)
-            .create_if_missing(true);
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .log_slow_statements(log::LevelFilter::Warn, SLOW_QUERY_THRESHOLD);
 
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
@@ -102,17 +233,68 @@ impl Vault {
         // Initialize schema
         init_database(&pool).await?;
 
-        let repo = VaultRepository::new(pool);
+        let read_options = SqliteConnectOptions::new()
+            .filename(&db_path)
+            .read_only(true)
+            .log_slow_statements(log::LevelFilter::Warn, SLOW_QUERY_THRESHOLD);
+        let read_pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(read_options)
+            .await?;
+
+        let repo = VaultRepository::new(pool).with_read_pool(read_pool);
 
         // Create event channel
         let (event_tx, _) = broadcast::channel(100);
 
+        let autocomplete = Arc::new(RwLock::new(None));
+        tokio::spawn(maintain_autocomplete(event_tx.subscribe(), repo.clone(), autocomplete.clone()));
+
+        let index_settings = Arc::new(RwLock::new(IndexSettings::default()));
+        tokio::spawn(catch_up_fts(event_tx.subscribe(), repo.clone(), fs.clone(), index_settings.clone()));
+        let metrics_snapshots = tokio::spawn(snapshot_metrics(event_tx.subscribe(), repo.clone())).abort_handle();
+
+        let diagram_settings = Arc::new(RwLock::new(DiagramSettings::default()));
+        tokio::spawn(prerender_diagrams(
+            event_tx.subscribe(),
+            repo.clone(),
+            fs.clone(),
+            diagram_settings.clone(),
+        ));
+
+        let query_cache = Arc::new(QueryCache::new());
+        tokio::spawn(crate::query_cache::invalidate_on_changes(event_tx.subscribe(), query_cache.clone()));
+
+        // The journal used to be kept in .neuroflow even with the data elsewhere
+        let legacy_changes = fs.legacy_changes_path();
+        if legacy_changes != fs.changes_path() && legacy_changes.exists() && !fs.changes_path().exists() {
+            if let Err(e) = tokio::fs::rename(&legacy_changes, fs.changes_path()).await {
+                warn!("Failed to move the change journal to {}: {}", fs.changes_path().display(), e);
+            }
+        }
+        let changes = ChangeJournal::new(fs.changes_path());
+
         let vault = Self {
             fs,
             repo,
             watcher: None,
             event_tx,
+            changes,
             indexed: Arc::new(RwLock::new(false)),
+            last_index: Arc::new(RwLock::new(None)),
+            note_passphrase: Arc::new(RwLock::new(None)),
+            quick_switch: Arc::new(RwLock::new(None)),
+            autocomplete,
+            recent_notes: Arc::new(RwLock::new(Vec::new())),
+            spell_checker: Arc::new(RwLock::new(SpellChecker::default())),
+            link_format: Arc::new(RwLock::new(LinkFormat::default())),
+            keep_old_titles: Arc::new(RwLock::new(false)),
+            diagram_settings,
+            locale_settings: Arc::new(RwLock::new(LocaleSettings::default())),
+            index_settings,
+            template_settings: Arc::new(RwLock::new(TemplateSettings::default())),
+            query_cache,
+            metrics_snapshots,
         };
 
         Ok(vault)
@@ -141,6 +323,11 @@ impl Vault {
         &self.repo
     }
 
+    /// Get the cache of query embed results.
+    pub fn query_cache(&self) -> &QueryCache {
+        &self.query_cache
+    }
+
     /// Get the filesystem handle.
     pub fn fs(&self) -> &VaultFs {
         &self.fs
@@ -161,70 +348,37 @@ impl Vault {
         let _ = self.event_tx.send(event);
     }
 
-    /// Perform initial full index of the vault.
-    #[instrument(skip(self))]
-    pub async fn full_index(&self) -> Result<IndexCompletePayload> {
-        let start = Instant::now();
-        info!("Starting full vault index");
-
-        let files = self.fs.scan_markdown_files().await?;
-        info!("Found {} markdown files", files.len());
-
-        // Build a set of file paths that exist on disk
-        let mut existing_paths: std::collections::HashSet<String> = std::collections::HashSet::new();
-        for file_path in &files {
-            existing_paths.insert(file_path.to_string_lossy().to_string());
-        }
-
-        // Cleanup: Remove notes from database that no longer exist on disk
-        let db_notes = self.repo.list_notes().await?;
-        let mut deleted_ids = Vec::new();
-        for note in db_notes {
-            if !existing_paths.contains(&note.path) {
-                info!("Removing orphaned note from database: {}", note.path);
-                if let Ok(Some(id)) = self.repo.delete_note(&note.path).await {
-                    deleted_ids.push(id);
-                }
-            }
-        }
+    /// Sender for vault events, for work that runs off the vault, e.g. on a
+    /// blocking thread.
+    pub(crate) fn event_sender(&self) -> broadcast::Sender<VaultEvent> {
+        self.event_tx.clone()
+    }
 
-        if !deleted_ids.is_empty() {
-            info!("Removed {} orphaned notes from database", deleted_ids.len());
-            let _ = self.event_tx.send(VaultEvent::NotesDeleted(deleted_ids));
-        }
+    /// Note changes journaled after `cursor`, at most `limit` (see `ChangeJournal`).
+    pub async fn changes_since(&self, cursor: u64, limit: Option<usize>) -> Result<ChangesPage> {
+        self.changes.changes_since(cursor, limit).await
+    }
 
-        // Index existing files
-        let mut indexed_count = 0;
-        let mut updated_ids = Vec::new();
+    /// The result of the latest full index this session, if any.
+    pub async fn last_index(&self) -> Option<IndexCompletePayload> {
+        self.last_index.read().await.clone()
+    }
 
-        for file_path in files {
-            match self.index_file(&file_path).await {
-                Ok(Some(id)) => {
-                    updated_ids.push(id);
-                    indexed_count += 1;
-                }
-                Ok(None) => {
-                    // File unchanged, skip
-                }
-                Err(e) => {
-                    warn!("Failed to index {}: {}", file_path.display(), e);
-                }
-            }
-        }
+    /// Perform initial full index of the vault.
+    pub async fn full_index(&self) -> Result<IndexCompletePayload> {
+        self.full_index_job(None).await
+    }
 
-        let duration = start.elapsed();
-        info!(
-            "Full index complete: {} notes in {:?}",
-            indexed_count, duration
-        );
+    /// Full index as a job: reports progress per file and stops early if
+    /// the job is cancelled, keeping the notes indexed so far.
+    #[instrument(skip(self, job))]
+    pub async fn full_index_job(&self, job: Option<&Job>) -> Result<IndexCompletePayload> {
+        info!("Starting full vault index");
+        let (payload, updated_ids) = self.indexer().index_tree(&[PathBuf::new()], job).await?;
 
         // Mark as indexed
         *self.indexed.write().await = true;
-
-        let payload = IndexCompletePayload {
-            notes_indexed: indexed_count,
-            duration_ms: duration.as_millis() as u64,
-        };
+        *self.last_index.write().await = Some(payload.clone());
 
         // Emit event
         let _ = self.event_tx.send(VaultEvent::IndexComplete(payload.clone()));
@@ -236,46 +390,130 @@ impl Vault {
         Ok(payload)
     }
 
+    /// Rescan one folder (e.g. after a `git pull` touching `projects/`)
+    /// instead of the whole vault: notes in it that changed are reindexed
+    /// and notes that are gone from it are removed. The vault root is
+    /// refused; reindexing all of it is `full_index`.
+    #[instrument(skip(self))]
+    pub async fn reindex_path(&self, prefix: &str) -> Result<IndexCompletePayload> {
+        let folder = prefix.trim_matches('/');
+        if folder.is_empty() {
+            return Err(FsError::InvalidPath(prefix.to_string()).into());
+        }
+        info!("Reindexing folder {}", folder);
+        let (payload, updated_ids) = self.indexer().index_tree(&[PathBuf::from(folder)], None).await?;
+
+        if !updated_ids.is_empty() {
+            let _ = self.event_tx.send(VaultEvent::NotesUpdated(updated_ids));
+        }
+        Ok(payload)
+    }
+
     /// Index a single file. Returns the note ID if the file was indexed (changed).
-    #[instrument(skip(self), fields(path = %path.as_ref().display()))]
     pub async fn index_file(&self, path: impl AsRef<Path>) -> Result<Option<i64>> {
-        let path = path.as_ref();
-        let path_str = path.to_string_lossy().to_string();
+        self.index_file_with(path.as_ref(), false, true).await
+    }
 
-        // Read file content
-        let content = self.fs.read_file(path).await?;
-        let hash = hash_content(&content);
+    /// Index a single file, optionally leaving its full-text entry to the
+    /// background catch-up (see `FileIndexer::index_file`).
+    async fn index_file_with(&self, path: &Path, defer_fts: bool, resolve_links: bool) -> Result<Option<i64>> {
+        let (note_id, _) = self.indexer().index_file(path, defer_fts, resolve_links).await?;
+        Ok(Some(note_id))
+    }
 
-        // Check if file changed
-        let existing_hash = self.repo.get_note_hash(&path_str).await?;
-        if existing_hash.as_ref() == Some(&hash) {
-            debug!("File unchanged, returning existing note ID: {}", path_str);
-            // Return existing note ID even though content unchanged
-            let existing_note = self.repo.get_note_by_path(&path_str).await?;
-            return Ok(Some(existing_note.id));
+    /// What indexing a file needs, for the vault and its file watcher.
+    pub(crate) fn indexer(&self) -> FileIndexer {
+        FileIndexer {
+            fs: self.fs.clone(),
+            repo: self.repo.clone(),
+            changes: self.changes.clone(),
+            event_tx: self.event_tx.clone(),
+            index_settings: self.index_settings.clone(),
+            keep_old_titles: self.keep_old_titles.clone(),
+            locale_settings: self.locale_settings.clone(),
         }
+    }
 
-        // Parse markdown
-        let analysis = parse(&content);
-
-        // Index to database
-        let note_id = self.repo.index_note(&path_str, &content, &hash, &analysis).await?;
-
-        debug!("Indexed file: {} (id={})", path_str, note_id);
-        Ok(Some(note_id))
+    /// Files that could not be read cleanly when they were last indexed.
+    /// Problems of files that are gone are dropped.
+    pub async fn file_problems(&self) -> Result<Vec<FileProblem>> {
+        let mut problems = Vec::new();
+        for problem in self.repo.list_file_problems().await? {
+            if self.fs.exists(Path::new(&problem.path)).await {
+                problems.push(problem);
+            } else {
+                self.repo.clear_file_problem(&problem.path).await?;
+            }
+        }
+        Ok(problems)
     }
 
     /// Remove a file from the index.
-    #[instrument(skip(self), fields(path = %path.as_ref().display()))]
     pub async fn remove_file(&self, path: impl AsRef<Path>) -> Result<Option<i64>> {
-        let path_str = path.as_ref().to_string_lossy().to_string();
-        let deleted_id = self.repo.delete_note(&path_str).await?;
+        self.indexer().remove_file(path.as_ref()).await
+    }
 
-        if let Some(id) = deleted_id {
-            let _ = self.event_tx.send(VaultEvent::NotesDeleted(vec![id]));
+    /// Replace the read-only external folders indexed with the vault.
+    ///
+    /// Fails, leaving the folders as they were, if one is invalid (see
+    /// [`Vault::validate_external_folders`]). The watcher is restarted to
+    /// pick up the new folders. Run a full index afterwards to add their
+    /// notes and drop those of removed folders.
+    pub async fn set_external_folders(&mut self, folders: &[ExternalFolder]) -> Result<()> {
+        self.validate_external_folders(folders)?;
+        let mut fs = VaultFs::new(self.fs.root()).with_data_dir(self.fs.data_dir());
+        for folder in folders {
+            fs = fs.with_external_folder(&folder.name, &folder.path);
         }
+        self.fs = fs;
 
-        Ok(deleted_id)
+        if self.watcher.is_some() {
+            self.stop_watcher().await;
+            self.start_watcher().await?;
+        }
+        Ok(())
+    }
+
+    /// Check external folders before they are attached: names must be
+    /// usable as a top-level `@name` folder and unique, and each folder must
+    /// exist and neither contain nor be inside the vault, its data directory
+    /// or another external folder.
+    pub fn validate_external_folders(&self, folders: &[ExternalFolder]) -> Result<()> {
+        let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let overlaps = |a: &Path, b: &Path| a.starts_with(b) || b.starts_with(a);
+        let reserved = [canonical(self.fs.root()), canonical(self.fs.data_dir())];
+
+        let mut names = HashSet::new();
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for folder in folders {
+            let name = folder.name.as_str();
+            if name.is_empty() || name.trim() != name || name.starts_with('.') || name.contains(['/', '\\']) {
+                return Err(VaultError::ExternalFolder(format!("invalid name {:?}", folder.name)));
+            }
+            if !names.insert(name.to_lowercase()) {
+                return Err(VaultError::ExternalFolder(format!("duplicate name {:?}", folder.name)));
+            }
+
+            let path = Path::new(&folder.path);
+            if !path.is_dir() {
+                return Err(VaultError::ExternalFolder(format!("{} is not a folder", folder.path)));
+            }
+            let path = canonical(path);
+            if reserved.iter().any(|reserved| overlaps(&path, reserved)) {
+                return Err(VaultError::ExternalFolder(format!(
+                    "{} overlaps the vault or its data folder",
+                    folder.path
+                )));
+            }
+            if paths.iter().any(|other| overlaps(&path, other)) {
+                return Err(VaultError::ExternalFolder(format!(
+                    "{} overlaps another external folder",
+                    folder.path
+                )));
+            }
+            paths.push(path);
+        }
+        Ok(())
     }
 
     /// Start the file watcher.
@@ -284,12 +522,7 @@ impl Vault {
             return Ok(());
         }
 
-        let watcher = FileWatcher::new(
-            self.fs.root().to_path_buf(),
-            self.repo.clone(),
-            self.fs.clone(),
-            self.event_tx.clone(),
-        )?;
+        let watcher = FileWatcher::new(self.fs.root().to_path_buf(), self.indexer())?;
 
         watcher.start().await;
         self.watcher = Some(watcher);
@@ -306,24 +539,282 @@ impl Vault {
         }
     }
 
+    /// Stop the watcher and close the database, so its files can be moved.
+    pub async fn close(mut self) {
+        self.metrics_snapshots.abort();
+        self.stop_watcher().await;
+        self.repo.close().await;
+        info!("Vault closed");
+    }
+
     /// List all notes.
     pub async fn list_notes(&self) -> Result<Vec<NoteListItem>> {
         Ok(self.repo.list_notes().await?)
     }
 
     /// Read a note's content.
+    ///
+    /// Encrypted notes are decrypted once the session has been unlocked with
+    /// `unlock_note`; until then their ciphertext is returned.
     pub async fn read_note(&self, path: &str) -> Result<String> {
-        Ok(self.fs.read_file(Path::new(path)).await?)
+        let content = self.fs.read_text(Path::new(path)).await?.content;
+
+        if has_encrypted_body(&content) {
+            if let Some(passphrase) = self.note_passphrase.read().await.as_deref() {
+                match decrypt_note(&content, passphrase) {
+                    Ok(plaintext) => return Ok(plaintext),
+                    Err(e) => warn!("Failed to decrypt {}: {}", path, e),
+                }
+            }
+        }
+
+        Ok(content)
+    }
+
+    /// Read up to `length` bytes of a note from byte `offset`, for notes too
+    /// large to load at once (see `VaultFs::read_file_range`).
+    ///
+    /// Encrypted notes can only be decrypted whole, so they are returned
+    /// whole whatever the range.
+    pub async fn read_note_range(&self, path: &str, offset: u64, length: u64) -> Result<FileRange> {
+        let encrypted = match self.repo.get_note_id_by_path(path).await? {
+            Some(id) => self.repo.is_note_encrypted(id).await?,
+            None => false,
+        };
+        if encrypted {
+            let content = self.read_note(path).await?;
+            return Ok(FileRange {
+                offset: 0,
+                total_bytes: content.len() as u64,
+                content,
+            });
+        }
+
+        Ok(self.fs.read_file_range(Path::new(path), offset, length).await?)
+    }
+
+    /// A note's whole content, with whether it is over the size limit.
+    pub async fn note_content(&self, id: i64, path: String, content: String) -> Result<NoteContent> {
+        Ok(NoteContent {
+            id,
+            path,
+            offset: 0,
+            total_bytes: content.len() as u64,
+            content,
+            truncated: self.repo.is_note_truncated(id).await?,
+        })
+    }
+
+    /// Unlock encrypted notes for this session and return the decrypted note.
+    ///
+    /// The passphrase is checked against the note if it is already encrypted,
+    /// otherwise against the vault's key check. The first passphrase used
+    /// becomes the key check. Notes that are neither encrypted nor marked
+    /// `encrypted: true` can't be unlocked.
+    #[instrument(skip(self, passphrase))]
+    pub async fn unlock_note(&self, path: &str, passphrase: &str) -> Result<String> {
+        let content = self.fs.read_file(Path::new(path)).await?;
+        if !has_encrypted_body(&content) && !is_marked_encrypted(&content) {
+            return Err(VaultError::NotEncrypted(path.to_string()));
+        }
+        let plaintext = decrypt_note(&content, passphrase)?;
+
+        let check_path = self.fs.key_check_path();
+        match tokio::fs::read_to_string(&check_path).await.ok() {
+            Some(check) if !has_encrypted_body(&content) && !verify_key_check(&check, passphrase) => {
+                return Err(EncryptionError::Decrypt.into());
+            }
+            Some(_) => {}
+            None => {
+                self.fs.ensure_neuroflow_dir().await?;
+                tokio::fs::write(&check_path, key_check(passphrase)?)
+                    .await
+                    .map_err(FsError::from)?;
+            }
+        }
+
+        *self.note_passphrase.write().await = Some(passphrase.to_string());
+        info!("Encrypted notes unlocked");
+
+        Ok(plaintext)
+    }
+
+    /// Forget the passphrase for encrypted notes.
+    pub async fn lock_notes(&self) {
+        *self.note_passphrase.write().await = None;
+        info!("Encrypted notes locked");
+    }
+
+    /// Rank note titles, aliases, paths, and headings for the quick switcher.
+    ///
+    /// Matching runs against an in-memory index that is rebuilt only when
+    /// notes or aliases changed. An empty query lists recently opened notes.
+    pub async fn quick_switch(&self, query: &str, limit: usize) -> Result<Vec<QuickSwitchResult>> {
+        let fingerprint = self.repo.get_switcher_fingerprint().await?;
+        let stale = self
+            .quick_switch
+            .read()
+            .await
+            .as_ref()
+            .is_none_or(|index| index.fingerprint() != fingerprint);
+
+        if stale {
+            let index = QuickSwitchIndex::new(
+                fingerprint,
+                self.repo.list_notes().await?,
+                self.repo.list_all_aliases().await?,
+                self.repo.list_all_headings().await?,
+            );
+            debug!("Rebuilt quick switcher index");
+            *self.quick_switch.write().await = Some(index);
+        }
+
+        let recent = self.recent_notes.read().await.clone();
+        Ok(self
+            .quick_switch
+            .read()
+            .await
+            .as_ref()
+            .map(|index| index.search(query, &recent, limit))
+            .unwrap_or_default())
+    }
+
+    /// Complete titles, aliases, tags, property keys, property values (of
+    /// the property `key`, or of any property), task contexts or headings
+    /// starting with `prefix`, most used first. Registered contexts are
+    /// suggested even if no task uses them yet.
+    pub async fn autocomplete(
+        &self,
+        kind: AutocompleteKind,
+        prefix: &str,
+        key: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AutocompleteItem>> {
+        if self.autocomplete.read().await.is_none() {
+            // Built under the lock, so vault events arriving meanwhile are
+            // applied to it afterwards instead of being skipped
+            let mut index = self.autocomplete.write().await;
+            if index.is_none() {
+                *index = Some(AutocompleteIndex::new(self.repo.get_autocomplete_terms(None).await?));
+                debug!("Built autocomplete index");
+            }
+        }
+
+        let mut items = self
+            .autocomplete
+            .read()
+            .await
+            .as_ref()
+            .map(|index| index.search(kind, prefix, key, limit))
+            .unwrap_or_default();
+
+        if kind == AutocompleteKind::Context && items.len() < limit {
+            let prefix = prefix.trim().to_lowercase();
+            for context in self.repo.list_registered_contexts().await? {
+                let lower = context.name.to_lowercase();
+                if lower.starts_with(&prefix) && !items.iter().any(|i| i.text.to_lowercase() == lower) {
+                    items.push(AutocompleteItem {
+                        text: context.name,
+                        count: 0,
+                        note_id: None,
+                    });
+                }
+            }
+            items.truncate(limit);
+        }
+        Ok(items)
+    }
+
+    /// Reload the autocomplete terms of notes changed without a vault event,
+    /// such as properties edited in the database. Without `note_ids` the
+    /// index is rebuilt on next use.
+    pub async fn refresh_autocomplete(&self, note_ids: Option<&[i64]>) -> Result<()> {
+        match note_ids {
+            Some(ids) => refresh_autocomplete_notes(&self.repo, &self.autocomplete, ids).await?,
+            None => *self.autocomplete.write().await = None,
+        }
+        Ok(())
+    }
+
+    /// Find misspelled words in markdown text.
+    ///
+    /// The dictionary for `lang` is looked up in the vault's dictionaries
+    /// folder, then in `dictionary_dirs`, then in the system's Hunspell
+    /// directories. Words in the vault's custom dictionary are accepted.
+    pub async fn check_spelling(
+        &self,
+        text: &str,
+        lang: &str,
+        dictionary_dirs: &[PathBuf],
+    ) -> Result<Vec<Misspelling>> {
+        let (dictionary, custom_words) = {
+            let mut spell_checker = self.spell_checker.write().await;
+            let search_dirs: Vec<PathBuf> = std::iter::once(self.fs.dictionaries_dir())
+                .chain(dictionary_dirs.iter().cloned())
+                .collect();
+            (
+                spell_checker.dictionary(lang, &search_dirs).await?,
+                spell_checker.custom_words(&self.fs.custom_dictionary_path()).await?,
+            )
+        };
+
+        let text = text.to_string();
+        tokio::task::spawn_blocking(move || check_text(&text, &dictionary, &custom_words))
+            .await
+            .map_err(|e| VaultError::Spellcheck(e.to_string()))
+    }
+
+    /// Add a word to the vault's custom spell check dictionary.
+    pub async fn add_to_dictionary(&self, word: &str) -> Result<()> {
+        self.spell_checker
+            .write()
+            .await
+            .add_word(&self.fs.custom_dictionary_path(), word)
+            .await
+    }
+
+    /// Remember that a note was opened, for the quick switcher.
+    pub async fn record_note_opened(&self, note_id: i64) {
+        let mut recent = self.recent_notes.write().await;
+        recent.retain(|id| *id != note_id);
+        recent.insert(0, note_id);
+        recent.truncate(MAX_RECENT_NOTES);
     }
 
     /// Write a note's content.
+    ///
+    /// Returns once the note, its tasks and links are indexed; full-text
+    /// search catches up in the background shortly after.
+    ///
+    /// Notes marked `encrypted: true` are encrypted before writing, which
+    /// requires an unlocked session. Content that is already encrypted is
+    /// written as is.
     #[instrument(skip(self, content))]
     pub async fn write_note(&self, path: &str, content: &str) -> Result<i64> {
+        // A note read with invalid bytes replaced is only written once the
+        // replacement characters are gone, so saving it unchanged doesn't
+        // destroy the bytes
+        if content.contains('\u{FFFD}')
+            && self.repo.get_file_problem(path).await? == Some(FileProblemKind::InvalidUtf8)
+        {
+            return Err(VaultError::InvalidUtf8(path.to_string()));
+        }
+
+        let content = if is_marked_encrypted(content) && !has_encrypted_body(content) {
+            let passphrase = self.note_passphrase.read().await;
+            let passphrase = passphrase
+                .as_deref()
+                .ok_or_else(|| VaultError::NoteLocked(path.to_string()))?;
+            encrypt_note(content, passphrase)?
+        } else {
+            content.to_string()
+        };
+
         // Write to filesystem
-        self.fs.write_file(Path::new(path), content).await?;
+        self.fs.write_file(Path::new(path), &content).await?;
 
         // Reindex (same flow as external change)
-        let note_id = self.index_file(Path::new(path)).await?.unwrap_or_else(|| {
+        let note_id = self.index_file_with(Path::new(path), true, true).await?.unwrap_or_else(|| {
             // This shouldn't happen since we just wrote the file
             error!("Failed to get note ID after write: {}", path);
             0
@@ -337,7 +828,8 @@ impl Vault {
         Ok(note_id)
     }
 
-    /// Rename a note (file and database path), updating all references across the vault.
+    /// Rename a note (file and database path), updating all references across
+    /// the vault in the vault's link format.
     #[instrument(skip(self))]
     pub async fn rename_note(&self, old_path: &str, new_path: &str) -> Result<i64> {
         // Check if target already exists
@@ -345,58 +837,70 @@ impl Vault {
             return Err(VaultError::FileAlreadyExists(new_path.to_string()));
         }
 
-        // Get old and new note names for reference updating
-        let old_name = Path::new(old_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or(old_path);
-        let new_name = Path::new(new_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or(new_path);
-
-        // Get the note ID before renaming (for finding backlinks)
         let note = self.repo.get_note_by_path(old_path).await?;
         let note_id = note.id;
 
-        // Find all notes that link to this note
-        let linking_notes = self.repo.get_notes_linking_to(note_id).await?;
-        let mut updated_ids = vec![note_id];
+        // Where every link resolves to before and after the rename
+        let notes = self.repo.list_notes().await?;
+        let aliases = self.repo.list_all_aliases().await?;
+        let mut renamed = notes.clone();
+        if let Some(note) = renamed.iter_mut().find(|n| n.id == note_id) {
+            note.path = new_path.to_string();
+        }
+        renamed.sort_by(|a, b| a.path.cmp(&b.path));
+        let before = NoteResolver::new(notes).with_aliases(aliases.clone());
+        let after = NoteResolver::new(renamed).with_aliases(aliases);
 
-        // Update references in all linking notes
-        for linking_note in linking_notes {
-            // Read the linking note's content
-            let content = self.fs.read_file(Path::new(&linking_note.path)).await?;
+        // Rename the file on disk
+        self.fs
+            .rename_file(Path::new(old_path), Path::new(new_path))
+            .await?;
 
-            // Update wiki links
-            let updated_content = update_wiki_links(&content, old_name, new_name);
+        // Update the database path
+        self.repo.rename_note(old_path, new_path).await?;
+        let hash = self.repo.get_note_hash(new_path).await?;
+        self.changes
+            .record(ChangeKind::Renamed, new_path, Some(old_path), hash.as_deref())
+            .await;
+
+        // Update links that no longer resolve to the note they pointed at:
+        // links to the renamed note, and links in it that were relative to
+        // its old folder
+        let format = self.link_format().await;
+        let mut updated_ids = vec![note_id];
+        for other in after.notes() {
+            let from_before = if other.id == note_id { old_path } else { other.path.as_str() };
+            if let Err(e) = self.fs.check_writable(Path::new(&other.path)) {
+                debug!("Skipping {}: {}", other.path, e);
+                continue;
+            }
+            let content = match self.fs.read_file(Path::new(&other.path)).await {
+                Ok(content) => content,
+                Err(e) => {
+                    debug!("Skipping {}: {}", other.path, e);
+                    continue;
+                }
+            };
+            if !content.contains("[[") || has_encrypted_body(&content) {
+                continue;
+            }
 
-            // Only write if content changed
+            let updated_content = relink(&content, &before, &after, (from_before, &other.path), format);
             if updated_content != content {
-                debug!(
-                    "Updating references in {} ({} -> {})",
-                    linking_note.path, old_name, new_name
-                );
-
-                // Write updated content
-                self.fs
-                    .write_file(Path::new(&linking_note.path), &updated_content)
-                    .await?;
+                debug!("Updating references in {} ({} -> {})", other.path, old_path, new_path);
+                self.fs.write_file(Path::new(&other.path), &updated_content).await?;
 
                 // Reindex the updated note
-                if let Ok(Some(_)) = self.index_file(Path::new(&linking_note.path)).await {
-                    updated_ids.push(linking_note.id);
+                if let Ok(Some(_)) = self.index_file(Path::new(&other.path)).await {
+                    if other.id != note_id {
+                        updated_ids.push(other.id);
+                    }
                 }
             }
         }
 
-        // Rename the file on disk
-        self.fs
-            .rename_file(Path::new(old_path), Path::new(new_path))
-            .await?;
-
-        // Update the database path
-        self.repo.rename_note(old_path, new_path).await?;
+        // Links by name may resolve to another note now
+        resolve_backlinks(&self.repo, None).await?;
 
         // Emit event for all updated notes
         let _ = self.event_tx.send(VaultEvent::NotesUpdated(updated_ids.clone()));
@@ -422,6 +926,8 @@ impl Vault {
 
         // Emit event
         if let Some(id) = deleted_id {
+            resolve_backlinks(&self.repo, None).await?;
+            self.changes.record(ChangeKind::Deleted, path, None, None).await;
             let _ = self.event_tx.send(VaultEvent::NotesDeleted(vec![id]));
             info!("Deleted note: {} (id={})", path, id);
         }
@@ -432,6 +938,7 @@ impl Vault {
     /// Create a folder in the vault.
     #[instrument(skip(self))]
     pub async fn create_folder(&self, path: &str) -> Result<()> {
+        self.fs.check_writable(Path::new(path))?;
         let absolute = self.fs.to_absolute(Path::new(path));
         tokio::fs::create_dir_all(&absolute)
             .await
@@ -443,6 +950,8 @@ impl Vault {
     /// Rename/move a folder and update all note paths within it.
     #[instrument(skip(self))]
     pub async fn rename_folder(&self, old_path: &str, new_path: &str) -> Result<Vec<i64>> {
+        self.fs.check_writable(Path::new(old_path))?;
+        self.fs.check_writable(Path::new(new_path))?;
         let old_absolute = self.fs.to_absolute(Path::new(old_path));
         let new_absolute = self.fs.to_absolute(Path::new(new_path));
 
@@ -479,6 +988,10 @@ impl Vault {
 
                 // Update database path
                 let note_id = self.repo.rename_note(&note.path, &note_new_path).await?;
+                let hash = self.repo.get_note_hash(&note_new_path).await?;
+                self.changes
+                    .record(ChangeKind::Renamed, &note_new_path, Some(&note.path), hash.as_deref())
+                    .await;
                 updated_ids.push(note_id);
             }
         }
@@ -488,6 +1001,10 @@ impl Vault {
             .await
             .map_err(core_fs::FsError::from)?;
 
+        // Keep icon, color, and manual order with the folder
+        self.repo.rename_folder_metadata(old_path, new_path).await?;
+        resolve_backlinks(&self.repo, None).await?;
+
         // Emit event for updated notes
         if !updated_ids.is_empty() {
             let _ = self.event_tx.send(VaultEvent::NotesUpdated(updated_ids.clone()));
@@ -502,29 +1019,69 @@ impl Vault {
         Ok(updated_ids)
     }
 
+    /// Notes in a folder (at any depth), or the note itself if `path` is a note.
+    async fn notes_under(&self, path: &str) -> Result<Vec<NoteListItem>> {
+        Ok(notes_under(&self.repo.list_notes().await?, path))
+    }
+
+    /// Report what deleting a folder or note would affect, without deleting.
+    /// Links count however they are written (see `resolve_backlinks`).
+    #[instrument(skip(self))]
+    pub async fn preview_delete(&self, path: &str) -> Result<DeleteImpact> {
+        let notes = self.notes_under(path).await?;
+        let note_ids: Vec<i64> = notes.iter().map(|note| note.id).collect();
+
+        let broken_links = self.repo.count_links_into(&note_ids).await?;
+        let schedule_blocks = self.repo.get_schedule_blocks_for_notes(&note_ids).await?;
+        let pinned_notes = notes.iter().filter(|note| note.pinned).cloned().collect();
+
+        Ok(DeleteImpact {
+            notes,
+            broken_links,
+            schedule_blocks,
+            pinned_notes,
+        })
+    }
+
     /// Delete a folder and all its contents.
+    ///
+    /// With `move_to_trash`, the folder is moved into the vault's `.trash`
+    /// folder (keeping its path, numbered if the name is taken) instead of
+    /// being removed. Either way its notes leave the index, as hidden folders
+    /// aren't indexed. The index is only updated once the folder is gone, so
+    /// a failed move leaves both in place.
     #[instrument(skip(self))]
-    pub async fn delete_folder(&self, path: &str) -> Result<Vec<i64>> {
+    pub async fn delete_folder(&self, path: &str, move_to_trash: bool) -> Result<Vec<i64>> {
+        self.fs.check_writable(Path::new(path))?;
         let absolute = self.fs.to_absolute(Path::new(path));
 
-        // First, find all notes in this folder and delete them from the database
-        let notes = self.repo.list_notes().await?;
-        let folder_prefix = if path.is_empty() { String::new() } else { format!("{}/", path) };
-        let mut deleted_ids = Vec::new();
+        // First delete the folder from disk
+        if absolute.exists() {
+            if move_to_trash {
+                let trash_path = self.trash_path(path).await?;
+                tokio::fs::rename(&absolute, self.fs.to_absolute(&trash_path))
+                    .await
+                    .map_err(core_fs::FsError::from)?;
+                info!("Moved {} to {}", path, trash_path.display());
+            } else {
+                tokio::fs::remove_dir_all(&absolute)
+                    .await
+                    .map_err(core_fs::FsError::from)?;
+            }
+        }
 
-        for note in notes {
-            if note.path.starts_with(&folder_prefix) || note.path == path {
-                if let Some(id) = self.repo.delete_note(&note.path).await? {
-                    deleted_ids.push(id);
-                }
+        // Then remove its notes from the database
+        let mut deleted_ids = Vec::new();
+        for note in self.notes_under(path).await? {
+            if let Some(id) = self.repo.delete_note(&note.path).await? {
+                self.changes.record(ChangeKind::Deleted, &note.path, None, None).await;
+                deleted_ids.push(id);
             }
         }
 
-        // Then delete the folder from disk
-        if absolute.exists() {
-            tokio::fs::remove_dir_all(&absolute)
-                .await
-                .map_err(core_fs::FsError::from)?;
+        self.repo.delete_folder_metadata(path).await?;
+        if !deleted_ids.is_empty() {
+            resolve_backlinks(&self.repo, None).await?;
         }
 
         // Emit event for deleted notes
@@ -536,53 +1093,131 @@ impl Vault {
         Ok(deleted_ids)
     }
 
-    /// Resolve a note name/path to its full path and ID.
-    /// Supports fuzzy matching by title or exact path matching.
-    pub async fn resolve_note(&self, target: &str) -> Option<(i64, String)> {
-        let notes = self.repo.list_notes().await.ok()?;
+    /// Where `path` goes in the trash, with its parent folder created.
+    async fn trash_path(&self, path: &str) -> Result<PathBuf> {
+        let relative = Path::new(path);
+        let name = relative
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| core_fs::FsError::InvalidPath(path.to_string()))?;
 
-        // Try exact path match first (with or without .md)
-        let target_path = if target.ends_with(".md") {
-            target.to_string()
-        } else {
-            format!("{}.md", target)
-        };
+        let folder = Path::new(TRASH_FOLDER).join(relative.parent().unwrap_or(Path::new("")));
+        tokio::fs::create_dir_all(self.fs.to_absolute(&folder))
+            .await
+            .map_err(core_fs::FsError::from)?;
+        Ok(self.fs.unique_path(&folder, name).await)
+    }
 
-        if let Some(note) = notes.iter().find(|n| n.path == target_path) {
-            return Some((note.id, note.path.clone()));
-        }
+    /// How links are written when the app updates them.
+    pub async fn link_format(&self) -> LinkFormat {
+        *self.link_format.read().await
+    }
 
-        // Also try matching by just the filename (for notes in subdirectories)
-        if let Some(note) = notes.iter().find(|n| {
-            n.path.ends_with(&format!("/{}", target_path)) ||
-            n.path == target_path
-        }) {
-            return Some((note.id, note.path.clone()));
-        }
+    /// Set the link format (from the vault's link settings).
+    pub async fn set_link_format(&self, format: LinkFormat) {
+        *self.link_format.write().await = format;
+    }
 
-        // Try title match (case-insensitive)
-        let target_lower = target.to_lowercase();
-        if let Some(note) = notes.iter().find(|n| {
-            n.title.as_ref().map(|t| t.to_lowercase() == target_lower).unwrap_or(false)
-        }) {
-            return Some((note.id, note.path.clone()));
-        }
+    /// Whether a note's previous title becomes an alias when its H1 changes.
+    pub async fn keep_old_titles(&self) -> bool {
+        *self.keep_old_titles.read().await
+    }
 
-        // Try filename without extension match
-        let target_name = target.strip_suffix(".md").unwrap_or(target);
-        if let Some(note) = notes.iter().find(|n| {
-            let note_name = n.path
-                .rsplit('/')
-                .next()
-                .unwrap_or(&n.path)
-                .strip_suffix(".md")
-                .unwrap_or(&n.path);
-            note_name.eq_ignore_ascii_case(target_name)
-        }) {
-            return Some((note.id, note.path.clone()));
+    /// Set whether old titles are kept as aliases (from the vault's link settings).
+    pub async fn set_keep_old_titles(&self, keep: bool) {
+        *self.keep_old_titles.write().await = keep;
+    }
+
+    /// Get how diagram blocks are rendered.
+    pub async fn diagram_settings(&self) -> DiagramSettings {
+        self.diagram_settings.read().await.clone()
+    }
+
+    /// Set how diagram blocks are rendered (from the vault's diagram settings).
+    pub async fn set_diagram_settings(&self, settings: DiagramSettings) {
+        *self.diagram_settings.write().await = settings;
+    }
+
+    /// Get the week start and date formats.
+    pub async fn locale_settings(&self) -> LocaleSettings {
+        self.locale_settings.read().await.clone()
+    }
+
+    /// Set the week start and date formats (from the vault's locale settings).
+    pub async fn set_locale_settings(&self, settings: LocaleSettings) {
+        *self.locale_settings.write().await = settings;
+    }
+
+    /// Get the size limit for indexing notes.
+    pub async fn index_settings(&self) -> IndexSettings {
+        self.index_settings.read().await.clone()
+    }
+
+    /// Set the size limit for indexing notes (from the vault's index
+    /// settings). Notes already indexed keep their entries until they change.
+    pub async fn set_index_settings(&self, settings: IndexSettings) {
+        *self.index_settings.write().await = settings;
+    }
+
+    /// Get the template settings.
+    pub async fn template_settings(&self) -> TemplateSettings {
+        self.template_settings.read().await.clone()
+    }
+
+    /// Set the template settings (from the vault's template settings).
+    pub async fn set_template_settings(&self, settings: TemplateSettings) {
+        *self.template_settings.write().await = settings;
+    }
+
+    /// Resolve a note name/path to its full path and ID, by path, file
+    /// name, title or alias (see `NoteResolver`).
+    pub async fn resolve_note(&self, target: &str) -> Option<(i64, String)> {
+        self.resolve_note_from(target, None).await
+    }
+
+    /// Resolve a link in the note at `from_path`: if several notes match,
+    /// the one closest to its folder is preferred.
+    pub async fn resolve_note_from(&self, target: &str, from_path: Option<&str>) -> Option<(i64, String)> {
+        let resolver = self.note_resolver().await.ok()?;
+        resolver
+            .resolve(target, from_path)
+            .map(|note| (note.id, note.path.clone()))
+    }
+
+    /// The notes a reference from the note at `from_path` could refer to,
+    /// best first and including near-typos, with how sure the first is meant.
+    pub async fn rank_note_matches(&self, target: &str, from_path: Option<&str>, limit: usize) -> Result<NoteResolution> {
+        Ok(self.note_resolver().await?.rank(target, from_path, limit))
+    }
+
+    /// A resolver over the vault's notes and aliases.
+    pub async fn note_resolver(&self) -> Result<NoteResolver<'static>> {
+        let notes = self.repo.list_notes().await?;
+        let aliases = self.repo.list_all_aliases().await?;
+        Ok(NoteResolver::new(notes).with_aliases(aliases))
+    }
+
+    /// The notes a link in the note at `from_path` could refer to, if there
+    /// is more than one; the one it resolves to comes first. Empty if the
+    /// link is unambiguous or doesn't resolve.
+    pub async fn ambiguous_link_targets(&self, target: &str, from_path: Option<&str>) -> Result<Vec<LinkCandidate>> {
+        let resolver = self.note_resolver().await?;
+        let candidates = resolver.candidates(target, from_path);
+        if candidates.len() < 2 {
+            return Ok(Vec::new());
         }
 
-        None
+        let from_folder = from_path.map(parent_folder);
+        Ok(candidates
+            .into_iter()
+            .map(|note| LinkCandidate {
+                note_id: note.id,
+                path: note.path.clone(),
+                title: note.title.clone(),
+                folder: parent_folder(&note.path).to_string(),
+                same_folder: Some(parent_folder(&note.path)) == from_folder,
+            })
+            .collect())
     }
 
     /// Resolve an asset path (image, etc.) to its full filesystem path.
@@ -636,3 +1271,663 @@ impl Vault {
         Ok(None)
     }
 }
+
+impl Drop for Vault {
+    fn drop(&mut self) {
+        self.metrics_snapshots.abort();
+    }
+}
+
+/// Keep the autocomplete index current from vault events until the vault
+/// is dropped. Nothing is done while the index hasn't been built.
+async fn maintain_autocomplete(
+    mut events: broadcast::Receiver<VaultEvent>,
+    repo: VaultRepository,
+    index: Arc<RwLock<Option<AutocompleteIndex>>>,
+) {
+    loop {
+        let result = match events.recv().await {
+            Ok(VaultEvent::NotesUpdated(ids)) => refresh_autocomplete_notes(&repo, &index, &ids).await,
+            Ok(VaultEvent::NotesDeleted(ids)) => {
+                if let Some(index) = index.write().await.as_mut() {
+                    index.remove(&ids);
+                }
+                Ok(())
+            }
+            // Missed events or a full index: rebuild on next use
+            Ok(VaultEvent::IndexComplete(_)) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                *index.write().await = None;
+                Ok(())
+            }
+            Ok(VaultEvent::JobProgress(_)) => Ok(()),
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        if let Err(e) = result {
+            warn!("Failed to update autocomplete index: {}", e);
+            *index.write().await = None;
+        }
+    }
+}
+
+/// Catch up the full-text entries of saved notes shortly after each save,
+/// and once at startup for saves the last session didn't catch up, until
+/// the vault is dropped.
+async fn catch_up_fts(
+    mut events: broadcast::Receiver<VaultEvent>,
+    repo: VaultRepository,
+    fs: VaultFs,
+    settings: Arc<RwLock<IndexSettings>>,
+) {
+    loop {
+        let max_bytes = settings.read().await.max_indexed_bytes;
+        if let Err(e) = update_pending_fts(&repo, &fs, max_bytes).await {
+            warn!("Failed to catch up full-text index: {}", e);
+        }
+
+        // Wait for the next save
+        loop {
+            match events.recv().await {
+                Ok(VaultEvent::NotesUpdated(_)) | Err(broadcast::error::RecvError::Lagged(_)) => break,
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+        tokio::time::sleep(FTS_CATCH_UP_DELAY).await;
+    }
+}
+
+/// Match notes whose files are gone (`orphans`) to files not indexed yet
+/// (`new_files`) by content hash, and move each matched note to its new
+/// path instead of deleting it and indexing the file as a new note, so its
+/// id stays the same. A hash shared by several orphans only matches the one
+/// with the same file name. Matched orphans are removed from `orphans`;
+/// returns the ids of the moved notes.
+pub(crate) async fn rematch_moved_notes(
+    repo: &VaultRepository,
+    fs: &VaultFs,
+    changes: &ChangeJournal,
+    orphans: &mut Vec<String>,
+    new_files: &[String],
+) -> Vec<i64> {
+    if orphans.is_empty() || new_files.is_empty() {
+        return Vec::new();
+    }
+
+    let mut orphan_hashes: Vec<(String, String)> = Vec::new();
+    for path in orphans.iter() {
+        if let Ok(Some(hash)) = repo.get_note_hash(path).await {
+            orphan_hashes.push((path.clone(), hash));
+        }
+    }
+
+    let file_name = |path: &str| path.rsplit('/').next().unwrap_or(path).to_string();
+    let mut moved_ids = Vec::new();
+    for new_path in new_files {
+        let Ok(content) = fs.read_text(Path::new(new_path)).await.map(|text| text.content) else {
+            continue;
+        };
+        let hash = hash_content(&content);
+        let mut candidates: Vec<usize> = (0..orphan_hashes.len()).filter(|&i| orphan_hashes[i].1 == hash).collect();
+        if candidates.len() > 1 {
+            candidates.retain(|&i| file_name(&orphan_hashes[i].0) == file_name(new_path));
+        }
+        let [i] = candidates[..] else {
+            continue;
+        };
+
+        let (old_path, _) = orphan_hashes.remove(i);
+        match repo.rename_note(&old_path, new_path).await {
+            Ok(id) => {
+                info!("Note moved outside the app: {} -> {}", old_path, new_path);
+                changes.record(ChangeKind::Renamed, new_path, Some(&old_path), Some(&hash)).await;
+                orphans.retain(|path| *path != old_path);
+                moved_ids.push(id);
+            }
+            Err(e) => warn!("Failed to move note {} to {}: {}", old_path, new_path, e),
+        }
+    }
+    moved_ids
+}
+
+/// Resolve the stored link targets of the notes (every note if None) by
+/// path, name, title and alias, as links are followed, and store the links
+/// that changed.
+pub(crate) async fn resolve_backlinks(repo: &VaultRepository, note_ids: Option<&[i64]>) -> Result<()> {
+    let resolver = NoteResolver::new(repo.list_notes().await?).with_aliases(repo.list_all_aliases().await?);
+    let paths: HashMap<i64, &str> = resolver.notes().iter().map(|note| (note.id, note.path.as_str())).collect();
+
+    let mut resolved: BTreeMap<i64, Vec<i64>> = match note_ids {
+        Some(ids) => ids.iter().map(|id| (*id, Vec::new())).collect(),
+        None => paths.keys().map(|id| (*id, Vec::new())).collect(),
+    };
+    for (from, target) in repo.list_link_targets(note_ids).await? {
+        let Some(from_path) = paths.get(&from) else {
+            continue;
+        };
+        if let Some(note) = resolver.resolve(&target, Some(from_path)) {
+            resolved.entry(from).or_default().push(note.id);
+        }
+    }
+
+    let mut current: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (from, to) in repo.list_backlink_pairs(note_ids).await? {
+        current.entry(from).or_default().push(to);
+    }
+    let changed: Vec<(i64, Vec<i64>)> = resolved
+        .into_iter()
+        .filter_map(|(from, mut to)| {
+            to.sort_unstable();
+            let mut before = current.remove(&from).unwrap_or_default();
+            before.sort_unstable();
+            (before != to).then_some((from, to))
+        })
+        .collect();
+
+    if !changed.is_empty() {
+        debug!("Resolved the links of {} notes", changed.len());
+        repo.set_backlinks(&changed).await?;
+    }
+    Ok(())
+}
+
+/// Snapshot today's vault metrics at startup and shortly after notes
+/// change, until the vault is closed or dropped.
+async fn snapshot_metrics(mut events: broadcast::Receiver<VaultEvent>, repo: VaultRepository) {
+    loop {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        if let Err(e) = repo.snapshot_vault_metrics(&today).await {
+            warn!("Failed to snapshot vault metrics: {}", e);
+        }
+
+        // Wait for the next change
+        loop {
+            match events.recv().await {
+                Ok(VaultEvent::JobProgress(_)) => {}
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => break,
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+        tokio::time::sleep(METRICS_SNAPSHOT_DELAY).await;
+        // Changes during the delay are part of this snapshot
+        while let Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) = events.try_recv() {}
+    }
+}
+
+/// Update the full-text entries of all pending notes. Notes saved again
+/// since are left for the next round, which their save triggers. Only the
+/// start of notes over `max_bytes` is indexed.
+async fn update_pending_fts(repo: &VaultRepository, fs: &VaultFs, max_bytes: u64) -> Result<()> {
+    let mut updated = 0;
+    for (note_id, path, hash) in repo.notes_pending_fts(i32::MAX).await? {
+        let content = match fs.read_text(Path::new(&path)).await {
+            Ok(text) => text.content,
+            Err(e) => {
+                debug!("Skipping full-text catch-up of {}: {}", path, e);
+                continue;
+            }
+        };
+        if hash_content(&content) != hash || has_encrypted_body(&content) {
+            continue;
+        }
+        if repo.update_pending_fts(note_id, &hash, indexed_part(&content, max_bytes)).await? {
+            updated += 1;
+        }
+    }
+
+    if updated > 0 {
+        debug!("Caught up full-text index of {} notes", updated);
+    }
+    Ok(())
+}
+
+async fn refresh_autocomplete_notes(
+    repo: &VaultRepository,
+    index: &RwLock<Option<AutocompleteIndex>>,
+    note_ids: &[i64],
+) -> Result<()> {
+    // The terms are read under the lock, so a refresh or deletion that
+    // happens meanwhile can't be overwritten with older terms
+    let mut index = index.write().await;
+    let Some(index) = index.as_mut() else {
+        return Ok(());
+    };
+    let terms = repo.get_autocomplete_terms(Some(note_ids)).await?;
+    index.update(note_ids, terms);
+    Ok(())
+}
+
+/// The folder of a vault-relative path ("" for the root).
+pub(crate) fn parent_folder(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(folder, _)| folder)
+}
+
+/// The notes in a folder (at any depth), or the note itself if `path` is a note.
+fn notes_under(notes: &[NoteListItem], path: &str) -> Vec<NoteListItem> {
+    let folder_prefix = if path.is_empty() { String::new() } else { format!("{}/", path) };
+    notes
+        .iter()
+        .filter(|note| note.path.starts_with(&folder_prefix) || note.path == path)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_delete_folder_to_trash() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("archive/old")).unwrap();
+        std::fs::write(dir.path().join("archive/a.md"), "# A\n").unwrap();
+        std::fs::write(dir.path().join("archive/old/b.md"), "# B\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        vault.write_note("archive/a.md", "# A\n\nSee [[archive/old/b]].\n").await.unwrap();
+        vault
+            .write_note("home.md", "# Home\n\n[[archive/a]] and [[archive/old/b]], also [[b]]\n")
+            .await
+            .unwrap();
+
+        let a = vault.repo().get_note_id_by_path("archive/a.md").await.unwrap();
+        sqlx::query("UPDATE notes SET pinned = 1 WHERE id = ?")
+            .bind(a)
+            .execute(vault.repo().pool())
+            .await
+            .unwrap();
+
+        let impact = vault.preview_delete("archive").await.unwrap();
+        assert_eq!(impact.notes.len(), 2);
+        // Links by name count too; links inside the folder don't
+        assert_eq!(impact.broken_links, 3);
+        assert_eq!(impact.pinned_notes.len(), 1);
+        assert_eq!(impact.pinned_notes[0].path, "archive/a.md");
+
+        let deleted = vault.delete_folder("archive", true).await.unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert!(!dir.path().join("archive").exists());
+        assert!(dir.path().join(".trash/archive/old/b.md").exists());
+
+        // A second folder with the same name gets a numbered name in the trash
+        std::fs::create_dir_all(dir.path().join("archive")).unwrap();
+        vault.delete_folder("archive", true).await.unwrap();
+        assert!(dir.path().join(".trash/archive 1").exists());
+
+        // If the folder can't be moved, its notes stay in the index
+        std::fs::remove_dir_all(dir.path().join(".trash")).unwrap();
+        std::fs::write(dir.path().join(".trash"), "").unwrap();
+        std::fs::create_dir_all(dir.path().join("inbox")).unwrap();
+        std::fs::write(dir.path().join("inbox/c.md"), "# C\n").unwrap();
+        vault.index_file("inbox/c.md").await.unwrap();
+        assert!(vault.delete_folder("inbox", true).await.is_err());
+        assert!(vault.repo().get_note_id_by_path("inbox/c.md").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_links_by_name_are_backlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("projects")).unwrap();
+        // Links to a note indexed later in the run resolve as well
+        std::fs::write(dir.path().join("a-home.md"), "# Home\n\n[[Plan]] and [[Garden ideas]]\n").unwrap();
+        std::fs::write(dir.path().join("projects/Plan.md"), "# Plan\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        let id = |path: &'static str| {
+            let vault = &vault;
+            async move { vault.repo().get_note_id_by_path(path).await.unwrap().unwrap() }
+        };
+        let (home, plan) = (id("a-home.md").await, id("projects/Plan.md").await);
+        let backlinks = vault.repo().get_backlinks(plan).await.unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].from_note_id, home);
+        assert_eq!(vault.repo().get_hub_notes(10).await.unwrap()[0].note.id, plan);
+
+        // A note created later gets the links by its title
+        vault.write_note("ideas.md", "# Garden ideas\n").await.unwrap();
+        let ideas = id("ideas.md").await;
+        assert_eq!(vault.repo().get_backlinks(ideas).await.unwrap().len(), 1);
+        let orphans = vault.repo().get_orphan_notes().await.unwrap();
+        assert!(orphans.is_empty());
+
+        // Deleting it drops the link
+        vault.delete_note("ideas.md").await.unwrap();
+        assert_eq!(vault.repo().get_dead_end_notes().await.unwrap()[0].note.id, plan);
+    }
+
+    #[tokio::test]
+    async fn test_unlock_note() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("plain.md"), "# Plain\n").unwrap();
+        std::fs::write(dir.path().join("secret.md"), "---\nencrypted: true\n---\n# Secret\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        assert!(matches!(
+            vault.unlock_note("plain.md", "pass").await,
+            Err(VaultError::NotEncrypted(_))
+        ));
+
+        vault.unlock_note("secret.md", "pass").await.unwrap();
+        vault.write_note("secret.md", "---\nencrypted: true\n---\n# Secret\n").await.unwrap();
+        vault.lock_notes().await;
+        assert!(vault.unlock_note("secret.md", "wrong").await.is_err());
+        assert!(vault.unlock_note("secret.md", "pass").await.unwrap().contains("# Secret"));
+
+        // A note not encrypted yet is checked against the first passphrase
+        std::fs::write(dir.path().join("diary.md"), "---\nencrypted: true\n---\n# Diary\n").unwrap();
+        vault.lock_notes().await;
+        assert!(matches!(
+            vault.unlock_note("diary.md", "wrong").await,
+            Err(VaultError::Encryption(EncryptionError::Decrypt))
+        ));
+        vault.unlock_note("diary.md", "pass").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reads_during_write_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "# A\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        // An import holding the writer doesn't keep listing and search
+        // waiting, and they see only what was committed
+        let mut tx = vault.repo().pool().begin().await.unwrap();
+        sqlx::query("INSERT INTO notes (path, title, hash) VALUES ('b.md', 'B', 'x')")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        let notes = tokio::time::timeout(std::time::Duration::from_secs(1), vault.list_notes())
+            .await
+            .expect("listing waited for the writer")
+            .unwrap();
+        assert_eq!(notes.len(), 1);
+        let results = vault.repo().search("A", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+
+        tx.commit().await.unwrap();
+        assert_eq!(vault.list_notes().await.unwrap().len(), 2);
+
+        // The read pool can't write
+        assert!(sqlx::query("DELETE FROM notes")
+            .execute(vault.repo().read_pool())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_saved_note_becomes_searchable() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        let id = vault.write_note("a.md", "# A\n\n- [ ] buy saffron\n").await.unwrap();
+        assert_eq!(vault.repo().get_todos_for_note(id).await.unwrap().len(), 1);
+
+        // Full-text search catches up in the background
+        let mut results = Vec::new();
+        for _ in 0..50 {
+            results = vault.repo().search("saffron", 10).await.unwrap();
+            if results.iter().any(|r| !r.stale) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].stale);
+    }
+
+    #[tokio::test]
+    async fn test_old_title_kept_as_alias() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        // Off by default
+        vault.write_note("plan.md", "# Garden plan
+").await.unwrap();
+        vault.write_note("plan.md", "# Spring garden
+").await.unwrap();
+        assert_eq!(vault.resolve_note("Garden plan").await, None);
+
+        vault.set_keep_old_titles(true).await;
+        let id = vault.write_note("plan.md", "# Garden 2026\n").await.unwrap();
+        let kept = "---\naliases:\n- Spring garden\n---\n\n# Garden 2026\n";
+        assert_eq!(vault.read_note("plan.md").await.unwrap(), kept);
+
+        // A change of case is the same title
+        let recased = kept.replace("# Garden", "# garden");
+        vault.write_note("plan.md", &recased).await.unwrap();
+        assert_eq!(vault.read_note("plan.md").await.unwrap(), recased);
+
+        // Changed outside the app, as the file watcher indexes it
+        std::fs::write(dir.path().join("plan.md"), recased.replace("# garden 2026", "# Spring garden\n\nBeds.")).unwrap();
+        vault.index_file("plan.md").await.unwrap();
+        assert_eq!(vault.resolve_note("Spring garden").await.map(|(id, _)| id), Some(id));
+        assert_eq!(vault.resolve_note("Garden 2026").await.map(|(id, _)| id), Some(id));
+        assert_eq!(vault.repo().get_aliases_for_note(id).await.unwrap(), vec!["Spring garden", "garden 2026"]);
+        assert!(vault.read_note("plan.md").await.unwrap().starts_with("---\naliases:\n- Spring garden\n- garden 2026\n---\n"));
+    }
+
+    #[tokio::test]
+    async fn test_old_titles_not_kept_in_read_only_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let setup = outside.path().join("setup.md");
+        std::fs::write(&setup, "# Setup\n").unwrap();
+        let mut vault = Vault::open(dir.path()).await.unwrap();
+        vault
+            .set_external_folders(&[ExternalFolder {
+                name: "wiki".to_string(),
+                path: outside.path().to_string_lossy().to_string(),
+            }])
+            .await
+            .unwrap();
+        vault.full_index().await.unwrap();
+        vault.set_keep_old_titles(true).await;
+
+        // The new title is indexed, the file is left as it is
+        std::fs::write(&setup, "# Install guide\n").unwrap();
+        let id = vault.index_file("@wiki/setup.md").await.unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&setup).unwrap(), "# Install guide\n");
+        assert_eq!(vault.resolve_note("Install guide").await.map(|(id, _)| id), Some(id));
+        assert!(vault.repo().get_aliases_for_note(id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_note_indexed_partly() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.set_index_settings(IndexSettings { max_indexed_bytes: 40 }).await;
+
+        let content = "# Log\n\nfirst entry saffron\nlast entry cardamom\n- [ ] tidy up\n";
+        let id = vault.write_note("log.md", content).await.unwrap();
+        for _ in 0..50 {
+            if vault.repo().search("saffron", 10).await.unwrap().iter().any(|r| !r.stale) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        // Only its start is parsed and searched
+        assert!(vault.repo().is_note_truncated(id).await.unwrap());
+        assert!(vault.repo().get_todos_for_note(id).await.unwrap().is_empty());
+        assert_eq!(vault.repo().search("saffron", 10).await.unwrap().len(), 1);
+        assert!(vault.repo().search("cardamom", 10).await.unwrap().is_empty());
+        assert!(vault.repo().get_notes_without_embeddings(10).await.unwrap().is_empty());
+
+        let range = vault.read_note_range("log.md", 7, 19).await.unwrap();
+        assert_eq!(range.content, "first entry saffron");
+        assert_eq!(range.total_bytes, content.len() as u64);
+
+        vault.write_note("log.md", "# Log\n").await.unwrap();
+        assert!(!vault.repo().is_note_truncated(id).await.unwrap());
+        assert_eq!(indexed_part("ab\ncd€", 6), "ab");
+        assert_eq!(indexed_part("abcd€", 6), "abcd");
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_files_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("latin1.md"), b"# Caf\xe9\n").unwrap();
+        std::fs::write(dir.path().join("image.md"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        std::fs::write(dir.path().join("ok.md"), "# Ok\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+
+        let payload = vault.full_index().await.unwrap();
+        assert_eq!(payload.notes_indexed, 2);
+        assert_eq!(payload.warnings.len(), 1);
+        assert_eq!(payload.warnings[0].path, "image.md");
+        assert_eq!(vault.resolve_note("Caf\u{FFFD}").await.map(|(_, path)| path), Some("latin1.md".to_string()));
+
+        let problems: Vec<_> = vault.file_problems().await.unwrap().into_iter().map(|p| (p.path, p.kind)).collect();
+        assert_eq!(
+            problems,
+            vec![
+                ("image.md".to_string(), FileProblemKind::Binary),
+                ("latin1.md".to_string(), FileProblemKind::InvalidUtf8),
+            ]
+        );
+
+        // Saving it as read would replace its invalid bytes for good
+        assert!(matches!(
+            vault.write_note("latin1.md", "# Caf\u{FFFD}\n").await,
+            Err(VaultError::InvalidUtf8(_))
+        ));
+        assert!(vault.fs().read_file(Path::new("latin1.md")).await.is_err());
+
+        // Fixed and deleted files drop off the list
+        vault.write_note("latin1.md", "# Café\n").await.unwrap();
+        std::fs::remove_file(dir.path().join("image.md")).unwrap();
+        assert!(vault.file_problems().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reindex_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("projects/sub")).unwrap();
+        std::fs::write(dir.path().join("projects/a.md"), "# A\n").unwrap();
+        std::fs::write(dir.path().join("projects/sub/b.md"), "# B\n").unwrap();
+        std::fs::write(dir.path().join("inbox.md"), "# Inbox\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        // A pull changes, adds and deletes notes in projects/ and elsewhere
+        std::fs::write(dir.path().join("projects/a.md"), "# A2\n").unwrap();
+        std::fs::write(dir.path().join("projects/c.md"), "# C\n").unwrap();
+        std::fs::remove_file(dir.path().join("projects/sub/b.md")).unwrap();
+        std::fs::remove_file(dir.path().join("inbox.md")).unwrap();
+
+        let payload = vault.reindex_path("projects/").await.unwrap();
+        assert_eq!(payload.notes_indexed, 2);
+        let mut paths: Vec<_> = vault.repo().list_notes().await.unwrap().into_iter().map(|n| n.path).collect();
+        paths.sort();
+        // Notes outside the folder are left for the next full index
+        assert_eq!(paths, ["inbox.md", "projects/a.md", "projects/c.md"]);
+        assert_eq!(vault.resolve_note("A2").await.map(|(_, path)| path), Some("projects/a.md".to_string()));
+
+        // A folder that is gone entirely
+        std::fs::remove_dir_all(dir.path().join("projects")).unwrap();
+        vault.reindex_path("projects").await.unwrap();
+        assert_eq!(vault.repo().list_notes().await.unwrap().len(), 1);
+
+        // The vault root is for the full index
+        for prefix in ["", "/", "//"] {
+            assert!(matches!(vault.reindex_path(prefix).await, Err(VaultError::Fs(FsError::InvalidPath(_)))));
+        }
+        assert_eq!(vault.repo().list_notes().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_full_index_keeps_moved_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("archive")).unwrap();
+        std::fs::write(dir.path().join("plan.md"), "# Plan\n").unwrap();
+        std::fs::write(dir.path().join("a.md"), "").unwrap();
+        std::fs::write(dir.path().join("b.md"), "").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+
+        let id = vault.repo().get_note_id_by_path("plan.md").await.unwrap().unwrap();
+        let block = vault
+            .repo()
+            .create_schedule_block(Some(id), "2025-01-06", "09:00", "10:00", None, None, None, None)
+            .await
+            .unwrap();
+        let b = vault.repo().get_note_id_by_path("b.md").await.unwrap().unwrap();
+
+        // Moved and renamed outside the app
+        std::fs::rename(dir.path().join("plan.md"), dir.path().join("archive/old-plan.md")).unwrap();
+        // Same content as another missing note: only matched by file name
+        std::fs::remove_file(dir.path().join("a.md")).unwrap();
+        std::fs::rename(dir.path().join("b.md"), dir.path().join("archive/b.md")).unwrap();
+        vault.full_index().await.unwrap();
+
+        assert_eq!(vault.repo().get_note_id_by_path("archive/old-plan.md").await.unwrap(), Some(id));
+        assert_eq!(vault.repo().get_schedule_block(block).await.unwrap().unwrap().note_id, Some(id));
+        assert_eq!(vault.repo().get_note_id_by_path("archive/b.md").await.unwrap(), Some(b));
+        assert_eq!(vault.repo().list_notes().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_external_folders_must_not_overlap() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("vault/projects")).unwrap();
+        std::fs::create_dir_all(outside.path().join("wiki/team")).unwrap();
+        std::fs::write(outside.path().join("wiki/team/setup.md"), "# Setup\n").unwrap();
+        let mut vault = Vault::open(dir.path().join("vault")).await.unwrap();
+
+        let folder = |name: &str, path: &Path| ExternalFolder {
+            name: name.to_string(),
+            path: path.to_string_lossy().to_string(),
+        };
+        let wiki = folder("wiki", &outside.path().join("wiki"));
+        for folders in [
+            vec![folder("projects", &dir.path().join("vault/projects"))],
+            vec![folder("parent", dir.path())],
+            vec![wiki.clone(), folder("team", &outside.path().join("wiki/team"))],
+            vec![wiki.clone(), folder("Wiki", outside.path())],
+            vec![folder("../up", &outside.path().join("wiki"))],
+            vec![folder("gone", &outside.path().join("missing"))],
+        ] {
+            let result = vault.set_external_folders(&folders).await;
+            assert!(matches!(result, Err(VaultError::ExternalFolder(_))), "{:?}", folders);
+        }
+        assert_eq!(vault.fs().external_folders().count(), 0);
+
+        vault.set_external_folders(&[wiki]).await.unwrap();
+        vault.full_index().await.unwrap();
+        assert!(vault.repo().get_note_id_by_path("@wiki/team/setup.md").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_external_folders_stay_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(outside.path().join("team")).unwrap();
+        let linker = outside.path().join("team/links.md");
+        std::fs::write(&linker, "# Links\n\n[[plan]]\n").unwrap();
+        let mut vault = Vault::open(dir.path()).await.unwrap();
+        vault
+            .set_external_folders(&[ExternalFolder {
+                name: "wiki".to_string(),
+                path: outside.path().to_string_lossy().to_string(),
+            }])
+            .await
+            .unwrap();
+        vault.write_note("plan.md", "# Plan\n").await.unwrap();
+        vault.full_index().await.unwrap();
+
+        // Renaming a note leaves links in read-only notes as they are
+        vault.rename_note("plan.md", "roadmap.md").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&linker).unwrap(), "# Links\n\n[[plan]]\n");
+
+        let read_only = |result: Result<_>| matches!(result, Err(VaultError::Fs(FsError::ReadOnly(_))));
+        assert!(read_only(vault.create_folder("@wiki/new").await));
+        assert!(read_only(vault.rename_folder("@wiki/team", "team").await.map(|_| ())));
+        assert!(read_only(vault.rename_folder("projects", "@wiki/projects").await.map(|_| ())));
+        assert!(read_only(vault.delete_folder("@wiki/team", true).await.map(|_| ())));
+        assert!(linker.exists());
+    }
+}
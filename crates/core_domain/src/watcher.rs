@@ -1,28 +1,26 @@
 //! File watcher for detecting changes to markdown files.
 
-use crate::vault::VaultEvent;
-use core_fs::{hash_content, VaultFs};
-use core_index::markdown::parse;
+use crate::indexer::FileIndexer;
+use crate::vault::{rematch_moved_notes, resolve_backlinks, VaultError, VaultEvent};
+use core_fs::{FsError, VaultFs};
 use core_storage::VaultRepository;
+use shared_types::ChangeKind;
 use notify::{RecommendedWatcher, RecursiveMode};
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind, Debouncer};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex};
 use tracing::{debug, error, info, warn};
 
 /// File watcher that monitors the vault for changes.
 pub struct FileWatcher {
     /// The vault root path.
     root: PathBuf,
-    /// Repository for database operations.
-    repo: VaultRepository,
-    /// Filesystem handle.
-    fs: VaultFs,
-    /// Event sender for vault events.
-    event_tx: broadcast::Sender<VaultEvent>,
+    /// Indexes changed files like the vault does, and records and reports
+    /// the changes picked up.
+    indexer: FileIndexer,
     /// Channel to stop the watcher.
     stop_tx: Option<mpsc::Sender<()>>,
     /// The debouncer (holds the watcher).
@@ -31,17 +29,10 @@ pub struct FileWatcher {
 
 impl FileWatcher {
     /// Create a new file watcher.
-    pub fn new(
-        root: PathBuf,
-        repo: VaultRepository,
-        fs: VaultFs,
-        event_tx: broadcast::Sender<VaultEvent>,
-    ) -> Result<Self, notify::Error> {
+    pub(crate) fn new(root: PathBuf, indexer: FileIndexer) -> Result<Self, notify::Error> {
         Ok(Self {
             root,
-            repo,
-            fs,
-            event_tx,
+            indexer,
             stop_tx: None,
             debouncer: Arc::new(Mutex::new(None)),
         })
@@ -81,15 +72,20 @@ impl FileWatcher {
             return;
         }
 
+        // External folders are watched too, so their notes stay indexed
+        for (name, path) in self.indexer.fs.external_folders() {
+            if let Err(e) = debouncer.watcher().watch(path, RecursiveMode::Recursive) {
+                warn!("Failed to watch external folder {}: {}", name, e);
+            }
+        }
+
         info!("File watcher started for {}", self.root.display());
 
         // Store the debouncer
         *self.debouncer.lock().await = Some(debouncer);
 
         // Clone what we need for the async task
-        let repo = self.repo.clone();
-        let fs = self.fs.clone();
-        let vault_event_tx = self.event_tx.clone();
+        let indexer = self.indexer.clone();
         let root = self.root.clone();
 
         // Spawn the event processing task
@@ -97,7 +93,7 @@ impl FileWatcher {
             loop {
                 tokio::select! {
                     Some(events) = event_rx.recv() => {
-                        process_events(&root, &repo, &fs, &vault_event_tx, events).await;
+                        process_events(&root, &indexer, events).await;
                     }
                     _ = stop_rx.recv() => {
                         info!("File watcher stopping");
@@ -122,28 +118,36 @@ impl FileWatcher {
 /// Process a batch of file system events.
 async fn process_events(
     _root: &PathBuf,
-    repo: &VaultRepository,
-    fs: &VaultFs,
-    event_tx: &broadcast::Sender<VaultEvent>,
+    indexer: &FileIndexer,
     events: Vec<notify_debouncer_mini::DebouncedEvent>,
 ) {
+    let FileIndexer { repo, fs, changes, event_tx, .. } = indexer;
     let mut to_index: HashSet<PathBuf> = HashSet::new();
     let mut to_remove: HashSet<PathBuf> = HashSet::new();
+    let mut folders: HashSet<PathBuf> = HashSet::new();
 
     for event in events {
         let path = &event.path;
 
-        // Skip non-markdown files
-        if path.extension().and_then(|e| e.to_str()) != Some("md") {
-            continue;
-        }
-
         // Skip hidden files and .neuroflow directory
         let path_str = path.to_string_lossy();
         if path_str.contains("/.") || path_str.contains("\\.") {
             continue;
         }
 
+        // A folder moved in or out only reports the folder itself
+        if path.is_dir() || (!path.exists() && was_indexed_folder(repo, fs, path).await) {
+            if event.kind == DebouncedEventKind::Any {
+                folders.insert(path.clone());
+            }
+            continue;
+        }
+
+        // Skip non-markdown files
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+
         match event.kind {
             DebouncedEventKind::Any => {
                 if path.exists() {
@@ -161,6 +165,53 @@ async fn process_events(
         }
     }
 
+    // Changed folders are reindexed like `Vault::reindex_path`, together
+    // so notes moved from one to another keep their ID
+    let mut updated_ids = Vec::new();
+    let folders: Vec<PathBuf> = folders
+        .iter()
+        .filter_map(|folder| fs.to_relative(folder).ok())
+        .filter(|folder| !folder.as_os_str().is_empty())
+        .collect();
+    if !folders.is_empty() {
+        let in_folders = |path: &PathBuf| {
+            fs.to_relative(path)
+                .is_ok_and(|path| folders.iter().any(|folder| path.starts_with(folder)))
+        };
+        to_index.retain(|path| !in_folders(path));
+        to_remove.retain(|path| !in_folders(path));
+
+        debug!("Rescanning folders {:?}", folders);
+        match indexer.index_tree(&folders, None).await {
+            Ok((_, ids)) => updated_ids = ids,
+            Err(e) => warn!("Failed to rescan folders: {}", e),
+        }
+    }
+
+    // Files moved in one batch keep their note id
+    let relative = |paths: &HashSet<PathBuf>| -> Vec<String> {
+        paths
+            .iter()
+            .filter_map(|path| fs.to_relative(path).ok())
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    };
+    let mut moved_ids = Vec::new();
+    if !to_remove.is_empty() && !to_index.is_empty() {
+        let mut orphans = relative(&to_remove);
+        let mut new_files = Vec::new();
+        for path in relative(&to_index) {
+            if let Ok(None) = repo.get_note_hash(&path).await {
+                new_files.push(path);
+            }
+        }
+        moved_ids = rematch_moved_notes(repo, fs, changes, &mut orphans, &new_files).await;
+        to_remove.retain(|path| {
+            fs.to_relative(path)
+                .is_ok_and(|path| orphans.contains(&path.to_string_lossy().to_string()))
+        });
+    }
+
     // Process removals
     let mut deleted_ids = Vec::new();
     for path in to_remove {
@@ -169,6 +220,7 @@ async fn process_events(
             match repo.delete_note(&path_str).await {
                 Ok(Some(id)) => {
                     debug!("Removed from index: {}", path_str);
+                    changes.record(ChangeKind::Deleted, &path_str, None, None).await;
                     deleted_ids.push(id);
                 }
                 Ok(None) => {}
@@ -179,47 +231,84 @@ async fn process_events(
         }
     }
 
-    if !deleted_ids.is_empty() {
+    let notes_removed = !deleted_ids.is_empty();
+    if notes_removed {
         let _ = event_tx.send(VaultEvent::NotesDeleted(deleted_ids));
     }
 
-    // Process additions/modifications
-    let mut updated_ids = Vec::new();
+    // Process additions/modifications, like the vault indexes a file
+    updated_ids.extend(moved_ids);
     for path in to_index {
-        if let Ok(relative) = fs.to_relative(&path) {
-            let path_str = relative.to_string_lossy().to_string();
-
-            // Read and check hash
-            match fs.read_file(&relative).await {
-                Ok(content) => {
-                    let hash = hash_content(&content);
-
-                    // Check if changed
-                    let existing_hash = repo.get_note_hash(&path_str).await.ok().flatten();
-                    if existing_hash.as_ref() == Some(&hash) {
-                        continue;
-                    }
-
-                    // Parse and index
-                    let analysis = parse(&content);
-                    match repo.index_note(&path_str, &content, &hash, &analysis).await {
-                        Ok(id) => {
-                            debug!("Indexed: {}", path_str);
-                            updated_ids.push(id);
-                        }
-                        Err(e) => {
-                            warn!("Failed to index {}: {}", path_str, e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to read {}: {}", path_str, e);
-                }
+        let Ok(relative) = fs.to_relative(&path) else {
+            continue;
+        };
+        match indexer.index_file(&relative, false, false).await {
+            Ok((id, true)) => {
+                debug!("Indexed: {}", relative.display());
+                updated_ids.push(id);
+            }
+            Ok((_, false)) => {}
+            // Reported with the file's problems
+            Err(VaultError::Fs(FsError::Binary(_))) => {}
+            Err(e) => {
+                warn!("Failed to index {}: {}", relative.display(), e);
             }
         }
     }
 
+    // Links by name may resolve to added, renamed or removed notes
+    if notes_removed || !updated_ids.is_empty() {
+        if let Err(e) = resolve_backlinks(repo, None).await {
+            warn!("Failed to resolve links: {}", e);
+        }
+    }
+
     if !updated_ids.is_empty() {
         let _ = event_tx.send(VaultEvent::NotesUpdated(updated_ids));
     }
 }
+
+/// Whether a path that is gone was a folder, i.e. notes are indexed under it.
+async fn was_indexed_folder(repo: &VaultRepository, fs: &VaultFs, path: &Path) -> bool {
+    let Ok(relative) = fs.to_relative(path) else {
+        return false;
+    };
+    match repo.has_notes_in_folder(&relative.to_string_lossy()).await {
+        Ok(has_notes) => has_notes,
+        Err(e) => {
+            warn!("Failed to look up notes in {}: {}", relative.display(), e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::Vault;
+    use notify_debouncer_mini::DebouncedEvent;
+
+    #[tokio::test]
+    async fn test_moved_folder_keeps_notes() {
+        // Not hidden, since events in hidden folders are skipped
+        let dir = tempfile::Builder::new().prefix("vault").tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("v1.2")).unwrap();
+        std::fs::create_dir_all(dir.path().join("archive")).unwrap();
+        std::fs::write(dir.path().join("v1.2/plan.md"), "# Plan\n\nShip it\n").unwrap();
+        let vault = Vault::open(dir.path()).await.unwrap();
+        vault.full_index().await.unwrap();
+        let id = vault.repo().get_note_by_path("v1.2/plan.md").await.unwrap().id;
+
+        // A folder named like a file, moved in one batch
+        std::fs::rename(dir.path().join("v1.2"), dir.path().join("archive/v1.2")).unwrap();
+        let events = vec![
+            DebouncedEvent::new(dir.path().join("v1.2"), DebouncedEventKind::Any),
+            DebouncedEvent::new(dir.path().join("archive/v1.2"), DebouncedEventKind::Any),
+        ];
+        process_events(&dir.path().to_path_buf(), &vault.indexer(), events).await;
+
+        let notes = vault.repo().list_notes().await.unwrap();
+        let found: Vec<_> = notes.iter().map(|n| (n.id, n.path.as_str())).collect();
+        assert_eq!(found, [(id, "archive/v1.2/plan.md")]);
+    }
+}
@@ -0,0 +1,39 @@
+//! Embedding notes section by section.
+
+use core_index::{chunk_note, redact_private, MAX_CHUNK_CHARS};
+use core_storage::{extract_content_preview, mean_embedding, ChunkEmbedding};
+
+use crate::{EmbeddingClient, EmbeddingError};
+
+/// Embed a note's sections; returns the note embedding and the section
+/// embeddings to store with `store_chunk_embeddings`.
+///
+/// Private sections are never sent to the model. Notes without any text
+/// are embedded whole and have no sections.
+pub async fn embed_note(
+    client: &EmbeddingClient,
+    content: &str,
+) -> Result<(Vec<f32>, Vec<ChunkEmbedding>), EmbeddingError> {
+    let chunks = chunk_note(content, MAX_CHUNK_CHARS);
+    if chunks.is_empty() {
+        let embedding = client.embed(&redact_private(content)).await?;
+        return Ok((embedding, Vec::new()));
+    }
+
+    let texts: Vec<String> = chunks.iter().map(|c| c.embedding_text()).collect();
+    let embeddings = client.embed_batched(&texts).await?;
+
+    let chunk_embeddings: Vec<ChunkEmbedding> = chunks
+        .into_iter()
+        .zip(embeddings)
+        .map(|(chunk, embedding)| ChunkEmbedding {
+            snippet: extract_content_preview(&chunk.text),
+            heading_path: chunk.heading_path,
+            start_line: chunk.start_line as u32,
+            end_line: chunk.end_line as u32,
+            embedding,
+        })
+        .collect();
+
+    Ok((mean_embedding(&chunk_embeddings), chunk_embeddings))
+}
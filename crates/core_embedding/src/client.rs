@@ -167,6 +167,7 @@ mod tests {
             model: "test-model".to_string(),
             dimensions: 768,
             batch_size: 10,
+            reembed_threshold: 0.1,
         }
     }
 
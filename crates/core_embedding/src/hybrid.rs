@@ -44,6 +44,8 @@ pub async fn hybrid_search(
                 vector_score: None,
                 combined_score: r.score,
                 match_type: MatchType::Keyword,
+                section: None,
+                stale: r.stale,
             })
             .take(limit as usize)
             .collect());
@@ -68,6 +70,8 @@ pub async fn hybrid_search(
                     vector_score: None,
                     combined_score: r.score,
                     match_type: MatchType::Keyword,
+                    section: None,
+                    stale: r.stale,
                 })
                 .take(limit as usize)
                 .collect());
@@ -117,6 +121,8 @@ fn reciprocal_rank_fusion(
                 vector_score: None,
                 combined_score: rrf_score,
                 match_type: MatchType::Keyword,
+                section: None,
+                stale: result.stale,
             },
         );
     }
@@ -127,24 +133,32 @@ fn reciprocal_rank_fusion(
 
         match result_map.get_mut(&result.note_id) {
             Some(existing) => {
-                // Note exists in FTS results - add vector score
+                // Note exists in FTS results - add vector score and section
                 existing.vector_score = Some(result.score);
                 existing.combined_score += rrf_score;
                 existing.match_type = MatchType::Both;
+                existing.section = result.section;
             }
             None => {
-                // Note only in vector results - use content preview as snippet
+                // Note only in vector results - use the matching section (or
+                // the content preview) as snippet
+                let snippet = match &result.section {
+                    Some(section) => Some(section.snippet.clone()),
+                    None => result.content_preview,
+                };
                 result_map.insert(
                     result.note_id,
                     HybridSearchResult {
                         note_id: result.note_id,
                         path: result.path,
                         title: result.title,
-                        snippet: result.content_preview,
+                        snippet,
                         fts_score: None,
                         vector_score: Some(result.score),
                         combined_score: rrf_score,
                         match_type: MatchType::Semantic,
+                        section: result.section,
+                        stale: false,
                     },
                 );
             }
@@ -169,6 +183,7 @@ fn reciprocal_rank_fusion(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared_types::SearchSection;
 
     #[test]
     fn test_rrf_scoring() {
@@ -180,6 +195,7 @@ mod tests {
                 title: Some("A".to_string()),
                 snippet: None,
                 score: 10.0,
+                stale: false,
             },
             SearchResult {
                 note_id: 2,
@@ -187,6 +203,7 @@ mod tests {
                 title: Some("B".to_string()),
                 snippet: None,
                 score: 8.0,
+                stale: false,
             },
         ];
 
@@ -197,6 +214,7 @@ mod tests {
                 title: Some("B".to_string()),
                 content_preview: Some("Preview of B".to_string()),
                 score: 0.95,
+                section: None,
             },
             VectorSearchResult {
                 note_id: 3,
@@ -204,6 +222,12 @@ mod tests {
                 title: Some("C".to_string()),
                 content_preview: Some("Preview of C".to_string()),
                 score: 0.90,
+                section: Some(SearchSection {
+                    heading_path: vec!["Ideas".to_string()],
+                    snippet: "Section of C".to_string(),
+                    start_line: 3,
+                    end_line: 7,
+                }),
             },
         ];
 
@@ -217,6 +241,11 @@ mod tests {
 
         // Note 1 and 3 should follow
         assert!(combined.len() >= 3);
+
+        // Semantic-only matches show their section
+        let c = combined.iter().find(|r| r.note_id == 3).unwrap();
+        assert_eq!(c.snippet.as_deref(), Some("Section of C"));
+        assert_eq!(c.section.as_ref().map(|s| s.start_line), Some(3));
     }
 
     #[test]
@@ -228,6 +257,7 @@ mod tests {
             title: Some("A".to_string()),
             snippet: None,
             score: 10.0,
+            stale: false,
         }];
 
         let combined = reciprocal_rank_fusion(fts_results, vec![], 10);
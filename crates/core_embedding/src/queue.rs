@@ -4,8 +4,8 @@
 //! allowing note saves to complete immediately while embeddings are generated
 //! in the background.
 
-use crate::EmbeddingClient;
-use core_storage::{extract_content_preview, VaultRepository};
+use crate::{embed_note, EmbeddingClient};
+use core_storage::{content_signature, extract_content_preview, VaultRepository};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
@@ -17,6 +17,7 @@ struct EmbeddingJob {
     content: String,
     content_hash: String,
     content_preview: String,
+    signature: Vec<u64>,
 }
 
 /// Handle for queuing embedding jobs.
@@ -43,11 +44,13 @@ impl EmbeddingQueue {
     /// If the queue is full, the job is dropped with a warning.
     pub fn queue(&self, note_id: i64, content: String, content_hash: String) {
         let content_preview = extract_content_preview(&content);
+        let signature = content_signature(&content);
         let job = EmbeddingJob {
             note_id,
             content,
             content_hash,
             content_preview,
+            signature,
         };
 
         match self.tx.try_send(job) {
@@ -68,11 +71,13 @@ impl EmbeddingQueue {
     /// This should be used when you want to ensure the job is queued.
     pub async fn queue_async(&self, note_id: i64, content: String, content_hash: String) {
         let content_preview = extract_content_preview(&content);
+        let signature = content_signature(&content);
         let job = EmbeddingJob {
             note_id,
             content,
             content_hash,
             content_preview,
+            signature,
         };
 
         if let Err(e) = self.tx.send(job).await {
@@ -96,16 +101,26 @@ async fn embedding_worker(
             continue;
         }
 
-        // Check if note still needs embedding (content might have changed)
-        match repo.needs_embedding(job.note_id, &job.content_hash).await {
-            Ok(false) => {
+        // Skip notes that are unchanged or changed too little to re-embed
+        let current = repo
+            .embedding_is_current(
+                job.note_id,
+                &job.content,
+                &job.content_hash,
+                Some(&job.content_preview),
+                &job.signature,
+                client.settings().reembed_threshold,
+            )
+            .await;
+        match current {
+            Ok(true) => {
                 debug!(
                     "Note {} already has current embedding, skipping",
                     job.note_id
                 );
                 continue;
             }
-            Ok(true) => {}
+            Ok(false) => {}
             Err(e) => {
                 warn!(
                     "Failed to check if note {} needs embedding: {}",
@@ -115,15 +130,25 @@ async fn embedding_worker(
             }
         }
 
-        // Generate embedding
+        // Generate embeddings for the note's sections
         debug!("Generating embedding for note {}", job.note_id);
-        match client.embed(&job.content).await {
-            Ok(embedding) => {
-                // Store the embedding with content preview
-                match repo
-                    .store_embedding(job.note_id, &embedding, &job.content_hash, Some(&job.content_preview))
-                    .await
-                {
+        match embed_note(&client, &job.content).await {
+            Ok((embedding, chunks)) => {
+                // Sections first; the note embedding marks the note as done
+                let stored = match repo.store_chunk_embeddings(job.note_id, &chunks).await {
+                    Ok(()) => {
+                        repo.store_embedding(
+                            job.note_id,
+                            &embedding,
+                            &job.content_hash,
+                            Some(&job.content_preview),
+                            &job.signature,
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                };
+                match stored {
                     Ok(()) => {
                         debug!("Stored embedding for note {}", job.note_id);
                     }
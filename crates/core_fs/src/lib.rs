@@ -2,14 +2,18 @@
 //!
 //! This crate handles all direct filesystem interactions:
 //! - Reading and writing markdown files
-//! - Scanning directories for markdown files
+//! - Scanning directories for markdown files (reporting files that can't be indexed)
 //! - Computing file hashes for change detection
+//! - Generating safe, unique file names for new notes
+//! - Locating and moving the database and caches (the data directory)
+//! - Mapping read-only external folders into the vault under `@name/` paths
 
-use std::path::{Path, PathBuf};
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
 use thiserror::Error;
 use tokio::fs;
-use tracing::{debug, instrument};
-use xxhash_rust::xxh3::xxh3_64;
+use tracing::{debug, instrument, warn};
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
 
 /// Errors that can occur during filesystem operations.
 #[derive(Error, Debug)]
@@ -23,23 +27,149 @@ pub enum FsError {
     #[error("Invalid path: {0}")]
     InvalidPath(String),
 
+    #[error("Path is in a read-only external folder: {0}")]
+    ReadOnly(PathBuf),
+
+    #[error("Not a text file: {0}")]
+    Binary(PathBuf),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
 pub type Result<T> = std::result::Result<T, FsError>;
 
+/// Markdown files found in the vault, and the entries that were skipped.
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Vault-relative paths (all valid UTF-8).
+    pub files: Vec<PathBuf>,
+    pub warnings: Vec<ScanWarning>,
+}
+
+/// A text file's content, read with `VaultFs::read_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextFile {
+    pub content: String,
+    /// The file is not valid UTF-8; the invalid bytes were replaced with
+    /// `\u{FFFD}`, so writing the content back changes them.
+    pub lossy: bool,
+}
+
+/// Part of a file, read with `VaultFs::read_file_range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRange {
+    /// Byte offset of `content` in the file.
+    pub offset: u64,
+    pub content: String,
+    /// Size of the whole file in bytes.
+    pub total_bytes: u64,
+}
+
+/// A file or folder the scan had to skip.
+#[derive(Debug, Clone)]
+pub struct ScanWarning {
+    /// Vault-relative path.
+    pub path: PathBuf,
+    pub kind: ScanWarningKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanWarningKind {
+    /// The name is not valid UTF-8, so it can't be stored or linked to.
+    NonUtf8Name,
+    /// The folder could not be read.
+    Unreadable(String),
+}
+
+impl std::fmt::Display for ScanWarningKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanWarningKind::NonUtf8Name => write!(f, "name is not valid UTF-8"),
+            ScanWarningKind::Unreadable(e) => write!(f, "could not be read: {}", e),
+        }
+    }
+}
+
 /// A handle to a vault's filesystem.
 #[derive(Debug, Clone)]
 pub struct VaultFs {
     /// Root path of the vault.
     root: PathBuf,
+    /// Directory holding the database and caches.
+    data_dir: PathBuf,
+    /// Read-only external folders, by name. Their files appear under `@name/`.
+    external: Vec<(String, PathBuf)>,
 }
 
+/// First character of the top-level folder external folders are mapped to.
+pub const EXTERNAL_PREFIX: char = '@';
+
+/// Files and folders in the data directory that belong to the vault's
+/// database, caches and change journal (config and dictionaries stay in
+/// `.neuroflow`).
+const DATA_ENTRIES: &[&str] = &["neuroflow.db", "neuroflow.db-wal", "neuroflow.db-shm", "cache", "changes.jsonl"];
+
 impl VaultFs {
     /// Create a new VaultFs for the given root path.
+    ///
+    /// The database and caches live in the vault's `.neuroflow` folder.
     pub fn new(root: impl Into<PathBuf>) -> Self {
-        Self { root: root.into() }
+        let root = root.into();
+        let data_dir = root.join(".neuroflow");
+        Self {
+            root,
+            data_dir,
+            external: Vec::new(),
+        }
+    }
+
+    /// Keep the database and caches in `data_dir` instead of `.neuroflow`.
+    pub fn with_data_dir(mut self, data_dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = data_dir.into();
+        self
+    }
+
+    /// Attach a read-only external folder, whose markdown files are scanned
+    /// and read as `@name/...` but never written, renamed or deleted.
+    pub fn with_external_folder(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.external.push((name.into(), path.into()));
+        self
+    }
+
+    /// Get the attached external folders as `(name, path)` pairs.
+    pub fn external_folders(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.external.iter().map(|(name, path)| (name.as_str(), path.as_path()))
+    }
+
+    /// Check if a vault-relative path is inside a read-only external folder.
+    pub fn is_read_only(&self, relative: &Path) -> bool {
+        self.external_root(relative).is_some()
+    }
+
+    /// Split an `@name/...` path into the external folder's root and the
+    /// path within it.
+    fn external_root<'a>(&self, relative: &'a Path) -> Option<(&Path, &'a Path)> {
+        let mut components = relative.components();
+        let first = components.next()?.as_os_str().to_str()?;
+        let name = first.strip_prefix(EXTERNAL_PREFIX)?;
+        self.external
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, root)| (root.as_path(), components.as_path()))
+    }
+
+    /// Fail with [`FsError::ReadOnly`] for paths in external folders.
+    pub fn check_writable(&self, relative: &Path) -> Result<()> {
+        if self.is_read_only(relative) {
+            return Err(FsError::ReadOnly(relative.to_path_buf()));
+        }
+        Ok(())
+    }
+
+    /// Get the directory holding the database and caches.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
     }
 
     /// Get the root path of the vault.
@@ -47,16 +177,17 @@ impl VaultFs {
         &self.root
     }
 
-    /// Ensure the .neuroflow directory exists.
+    /// Ensure the .neuroflow directory (and the data directory) exists.
     pub async fn ensure_neuroflow_dir(&self) -> Result<PathBuf> {
         let neuroflow_dir = self.root.join(".neuroflow");
         fs::create_dir_all(&neuroflow_dir).await?;
+        fs::create_dir_all(&self.data_dir).await?;
         Ok(neuroflow_dir)
     }
 
     /// Get the path to the vault database.
     pub fn db_path(&self) -> PathBuf {
-        self.root.join(".neuroflow").join("neuroflow.db")
+        self.data_dir.join("neuroflow.db")
     }
 
     /// Get the path to the vault config.
@@ -64,24 +195,71 @@ impl VaultFs {
         self.root.join(".neuroflow").join("config.json")
     }
 
-    /// Check if a path is within the vault.
+    /// Get the path to the key check of the passphrase for encrypted notes.
+    pub fn key_check_path(&self) -> PathBuf {
+        self.root.join(".neuroflow").join("key-check")
+    }
+
+    /// Get the path to the vault's cache directory (thumbnails etc.).
+    pub fn cache_dir(&self) -> PathBuf {
+        self.data_dir.join("cache")
+    }
+
+    /// Get the path to the vault's custom spell check dictionary (one word per line).
+    pub fn custom_dictionary_path(&self) -> PathBuf {
+        self.root.join(".neuroflow").join("dictionary.txt")
+    }
+
+    /// Get the directory for vault-specific Hunspell dictionaries.
+    pub fn dictionaries_dir(&self) -> PathBuf {
+        self.root.join(".neuroflow").join("dictionaries")
+    }
+
+    /// Get the path to the vault's change journal (one JSON record per line).
+    pub fn changes_path(&self) -> PathBuf {
+        self.data_dir.join("changes.jsonl")
+    }
+
+    /// Get where the change journal was kept before it moved to the data
+    /// directory.
+    pub fn legacy_changes_path(&self) -> PathBuf {
+        self.root.join(".neuroflow").join("changes.jsonl")
+    }
+
+    /// Check if a path is within the vault or one of its external folders.
     pub fn is_within_vault(&self, path: &Path) -> bool {
-        path.starts_with(&self.root)
+        path.starts_with(&self.root) || self.external.iter().any(|(_, root)| path.starts_with(root))
     }
 
     /// Convert an absolute path to a vault-relative path.
+    ///
+    /// Paths in external folders become `@name/...`.
     pub fn to_relative(&self, path: &Path) -> Result<PathBuf> {
+        for (name, root) in &self.external {
+            if let Ok(rest) = path.strip_prefix(root) {
+                return Ok(Path::new(&format!("{}{}", EXTERNAL_PREFIX, name)).join(rest));
+            }
+        }
         path.strip_prefix(&self.root)
             .map(|p| p.to_path_buf())
             .map_err(|_| FsError::OutsideVault(path.to_path_buf()))
     }
 
     /// Convert a vault-relative path to an absolute path.
+    ///
+    /// `@name/...` paths resolve into the external folder. On Windows, paths
+    /// beyond the 260 character limit get the `\\?\` prefix so they can
+    /// still be opened.
     pub fn to_absolute(&self, relative: &Path) -> PathBuf {
-        self.root.join(relative)
+        match self.external_root(relative) {
+            Some((root, rest)) => long_path(root.join(rest)),
+            None => long_path(self.root.join(relative)),
+        }
     }
 
-    /// Read a markdown file's content.
+    /// Read a markdown file's content. Fails on invalid UTF-8, so content
+    /// that is changed and written back keeps every byte it didn't change;
+    /// indexing and display use `read_text`.
     #[instrument(skip(self), fields(vault = %self.root.display()))]
     pub async fn read_file(&self, relative_path: &Path) -> Result<String> {
         let absolute = self.to_absolute(relative_path);
@@ -95,9 +273,64 @@ impl VaultFs {
         Ok(content)
     }
 
+    /// Read a markdown file's content, replacing invalid UTF-8. Binary files
+    /// (with a NUL byte near the start) are refused with `FsError::Binary`.
+    #[instrument(skip(self), fields(vault = %self.root.display()))]
+    pub async fn read_text(&self, relative_path: &Path) -> Result<TextFile> {
+        let absolute = self.to_absolute(relative_path);
+        debug!("Reading file: {}", absolute.display());
+
+        if !absolute.exists() {
+            return Err(FsError::NotFound(absolute));
+        }
+
+        let bytes = fs::read(&absolute).await?;
+        if looks_binary(&bytes) {
+            return Err(FsError::Binary(relative_path.to_path_buf()));
+        }
+        match String::from_utf8(bytes) {
+            Ok(content) => Ok(TextFile { content, lossy: false }),
+            Err(e) => {
+                debug!("Invalid UTF-8 in {}, replacing it", absolute.display());
+                Ok(TextFile {
+                    content: String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                    lossy: true,
+                })
+            }
+        }
+    }
+
+    /// Read up to `length` bytes of a file from byte `offset`, for files too
+    /// large to load at once. The range is narrowed to whole characters, so
+    /// the next range starts at `offset + content.len()`.
+    #[instrument(skip(self), fields(vault = %self.root.display()))]
+    pub async fn read_file_range(&self, relative_path: &Path, offset: u64, length: u64) -> Result<FileRange> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let absolute = self.to_absolute(relative_path);
+        if !absolute.exists() {
+            return Err(FsError::NotFound(absolute));
+        }
+
+        let mut file = fs::File::open(&absolute).await?;
+        let total_bytes = file.metadata().await?.len();
+        let offset = offset.min(total_bytes);
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        let mut bytes = Vec::new();
+        file.take(length).read_to_end(&mut bytes).await?;
+
+        let (skipped, content) = whole_characters(&bytes);
+        Ok(FileRange {
+            offset: offset + skipped as u64,
+            content,
+            total_bytes,
+        })
+    }
+
     /// Write content to a markdown file.
     #[instrument(skip(self, content), fields(vault = %self.root.display()))]
     pub async fn write_file(&self, relative_path: &Path, content: &str) -> Result<()> {
+        self.check_writable(relative_path)?;
         let absolute = self.to_absolute(relative_path);
         debug!("Writing file: {}", absolute.display());
 
@@ -110,9 +343,50 @@ impl VaultFs {
         Ok(())
     }
 
+    /// Write raw bytes to a file (attachments, images).
+    #[instrument(skip(self, bytes), fields(vault = %self.root.display()))]
+    pub async fn write_bytes(&self, relative_path: &Path, bytes: &[u8]) -> Result<()> {
+        self.check_writable(relative_path)?;
+        let absolute = self.to_absolute(relative_path);
+        debug!("Writing {} bytes: {}", bytes.len(), absolute.display());
+
+        // Ensure parent directory exists
+        if let Some(parent) = absolute.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        fs::write(&absolute, bytes).await?;
+        Ok(())
+    }
+
+    /// Find a free path for a new file in `folder`.
+    ///
+    /// Returns `folder/file_name` if it doesn't exist yet, otherwise the first
+    /// free `folder/name 1.ext`, `folder/name 2.ext`, ...
+    ///
+    /// Names are compared case-insensitively, so the result is also free on
+    /// case-insensitive filesystems (Windows, macOS) the vault may sync to.
+    pub async fn unique_path(&self, folder: &Path, file_name: &str) -> PathBuf {
+        let mut taken = std::collections::HashSet::new();
+        if let Ok(mut entries) = fs::read_dir(self.to_absolute(folder)).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                taken.insert(entry.file_name().to_string_lossy().to_lowercase());
+            }
+        }
+
+        let mut candidate = file_name.to_string();
+        let mut n = 1;
+        while taken.contains(&candidate.to_lowercase()) || self.exists(&folder.join(&candidate)).await {
+            candidate = numbered_file_name(file_name, n);
+            n += 1;
+        }
+        folder.join(candidate)
+    }
+
     /// Delete a file.
     #[instrument(skip(self), fields(vault = %self.root.display()))]
     pub async fn delete_file(&self, relative_path: &Path) -> Result<()> {
+        self.check_writable(relative_path)?;
         let absolute = self.to_absolute(relative_path);
         debug!("Deleting file: {}", absolute.display());
 
@@ -125,6 +399,8 @@ impl VaultFs {
     /// Rename/move a file within the vault.
     #[instrument(skip(self), fields(vault = %self.root.display()))]
     pub async fn rename_file(&self, from_path: &Path, to_path: &Path) -> Result<()> {
+        self.check_writable(from_path)?;
+        self.check_writable(to_path)?;
         let from_absolute = self.to_absolute(from_path);
         let to_absolute = self.to_absolute(to_path);
         debug!("Renaming file: {} -> {}", from_absolute.display(), to_absolute.display());
@@ -156,40 +432,233 @@ impl VaultFs {
     }
 
     /// Scan the vault for all markdown files.
+    ///
+    /// External folders are scanned after the vault itself. Hidden files and
+    /// folders (including `.neuroflow`) are skipped. Files with names that
+    /// aren't valid UTF-8 and folders that can't be read are reported as
+    /// warnings instead of failing the scan.
+    #[instrument(skip(self), fields(vault = %self.root.display()))]
+    pub async fn scan_markdown_files(&self) -> Result<ScanReport> {
+        let mut report = ScanReport::default();
+        let mut entries = fs::read_dir(long_path(self.root.clone())).await?;
+        self.scan_entries(&mut entries, Path::new(""), &mut report).await;
+        for (name, root) in &self.external {
+            let relative_dir = PathBuf::from(format!("{}{}", EXTERNAL_PREFIX, name));
+            match fs::read_dir(long_path(root.clone())).await {
+                Ok(mut entries) => self.scan_entries(&mut entries, &relative_dir, &mut report).await,
+                Err(e) => report.warn(&relative_dir, ScanWarningKind::Unreadable(e.to_string())),
+            }
+        }
+        debug!(
+            "Found {} markdown files ({} skipped)",
+            report.files.len(),
+            report.warnings.len()
+        );
+        Ok(report)
+    }
+
+    /// Scan one folder of the vault (including subfolders) for markdown
+    /// files, like `scan_markdown_files`. A folder that doesn't exist has
+    /// no files.
     #[instrument(skip(self), fields(vault = %self.root.display()))]
-    pub async fn scan_markdown_files(&self) -> Result<Vec<PathBuf>> {
-        let mut files = Vec::new();
-        self.scan_dir_recursive(&self.root, &mut files).await?;
-        debug!("Found {} markdown files", files.len());
-        Ok(files)
+    pub async fn scan_markdown_files_in(&self, folder: &Path) -> Result<ScanReport> {
+        if folder.as_os_str().is_empty() {
+            return self.scan_markdown_files().await;
+        }
+        if folder.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return Err(FsError::InvalidPath(folder.to_string_lossy().to_string()));
+        }
+
+        let mut report = ScanReport::default();
+        // Hidden folders are never indexed
+        if folder.components().any(|c| c.as_os_str().as_encoded_bytes().starts_with(b".")) {
+            return Ok(report);
+        }
+        match fs::read_dir(self.to_absolute(folder)).await {
+            Ok(mut entries) => self.scan_entries(&mut entries, folder, &mut report).await,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => report.warn(folder, ScanWarningKind::Unreadable(e.to_string())),
+        }
+        debug!("Found {} markdown files in {}", report.files.len(), folder.display());
+        Ok(report)
     }
 
-    /// Recursively scan a directory for markdown files.
+    /// Recursively scan a directory's entries for markdown files.
     #[async_recursion::async_recursion]
-    async fn scan_dir_recursive(&self, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
-        let mut entries = fs::read_dir(dir).await?;
+    async fn scan_entries(&self, entries: &mut fs::ReadDir, relative_dir: &Path, report: &mut ScanReport) {
+        loop {
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(e) => {
+                    report.warn(relative_dir, ScanWarningKind::Unreadable(e.to_string()));
+                    break;
+                }
+            };
 
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let file_name = entry.file_name();
+            let relative = relative_dir.join(&file_name);
 
             // Skip hidden files/directories and .neuroflow
-            if file_name.starts_with('.') {
+            if file_name.as_encoded_bytes().starts_with(b".") {
                 continue;
             }
 
-            if path.is_dir() {
-                self.scan_dir_recursive(&path, files).await?;
-            } else if path.extension().and_then(|e| e.to_str()) == Some("md") {
-                // Store as relative path
-                if let Ok(relative) = self.to_relative(&path) {
-                    files.push(relative);
+            let is_dir = match entry.file_type().await {
+                Ok(file_type) if file_type.is_symlink() => fs::metadata(entry.path()).await.is_ok_and(|m| m.is_dir()),
+                Ok(file_type) => file_type.is_dir(),
+                Err(e) => {
+                    report.warn(&relative, ScanWarningKind::Unreadable(e.to_string()));
+                    continue;
+                }
+            };
+
+            if is_dir {
+                if file_name.to_str().is_none() {
+                    report.warn(&relative, ScanWarningKind::NonUtf8Name);
+                    continue;
+                }
+                match fs::read_dir(long_path(entry.path())).await {
+                    Ok(mut sub_entries) => self.scan_entries(&mut sub_entries, &relative, report).await,
+                    Err(e) => report.warn(&relative, ScanWarningKind::Unreadable(e.to_string())),
+                }
+            } else if Path::new(&file_name).extension() == Some(OsStr::new("md")) {
+                if file_name.to_str().is_none() {
+                    report.warn(&relative, ScanWarningKind::NonUtf8Name);
+                } else {
+                    report.files.push(relative);
                 }
             }
         }
+    }
+}
 
-        Ok(())
+impl ScanReport {
+    fn warn(&mut self, path: &Path, kind: ScanWarningKind) {
+        warn!("Skipping {}: {}", path.display(), kind);
+        self.warnings.push(ScanWarning {
+            path: path.to_path_buf(),
+            kind,
+        });
+    }
+}
+
+/// Add the `\\?\` prefix to long absolute paths on Windows.
+#[cfg(windows)]
+fn long_path(path: PathBuf) -> PathBuf {
+    const MAX_PATH: usize = 260;
+
+    let as_str = path.as_os_str();
+    if as_str.len() < MAX_PATH || !path.is_absolute() || as_str.as_encoded_bytes().starts_with(br"\\") {
+        return path;
+    }
+    // Prefixed paths are not normalized, so only backslashes are allowed
+    let mut prefixed = std::ffi::OsString::from(r"\\?\");
+    for component in path.components() {
+        match component {
+            std::path::Component::Prefix(prefix) => prefixed.push(prefix.as_os_str()),
+            std::path::Component::RootDir => {}
+            component => {
+                prefixed.push(r"\");
+                prefixed.push(component.as_os_str());
+            }
+        }
+    }
+    PathBuf::from(prefixed)
+}
+
+#[cfg(not(windows))]
+fn long_path(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// The data directory for a vault under the app's data directory, keyed by
+/// a hash of the vault's canonical path.
+///
+/// Used for vaults in cloud-synced folders, where a database inside the
+/// vault would be corrupted by the sync client.
+pub fn external_data_dir(app_data_dir: &Path, vault_root: &Path) -> PathBuf {
+    let root = std::fs::canonicalize(vault_root).unwrap_or_else(|_| vault_root.to_path_buf());
+    app_data_dir
+        .join("vaults")
+        .join(hash_bytes(root.as_os_str().as_encoded_bytes()))
+}
+
+/// Folder in the target data directory that entries are copied into before
+/// they replace the target's own.
+const DATA_STAGING_DIR: &str = ".moving";
+
+/// Copy the database and caches from one data directory to another.
+///
+/// Must only be called while the database is closed. Everything is copied
+/// into a staging folder first, so a failed copy leaves `to` as it was;
+/// the staged entries then replace those in `to`. `from` is left alone, so
+/// it can be removed with `remove_data_dir` once the new location is in
+/// use. Returns the number of entries copied.
+#[instrument]
+pub async fn copy_data_dir(from: &Path, to: &Path) -> Result<usize> {
+    let staging = to.join(DATA_STAGING_DIR);
+    remove_entry(&staging).await?;
+    fs::create_dir_all(&staging).await?;
+
+    let mut staged = Vec::new();
+    for name in DATA_ENTRIES {
+        let source = from.join(name);
+        if !fs::try_exists(&source).await? {
+            continue;
+        }
+        if let Err(e) = copy_entry(&source, &staging.join(name)).await {
+            let _ = remove_entry(&staging).await;
+            return Err(e);
+        }
+        staged.push(*name);
+    }
+
+    // Renames within one directory don't cross filesystems
+    for name in &staged {
+        let target = to.join(name);
+        remove_entry(&target).await?;
+        fs::rename(staging.join(name), &target).await?;
+    }
+    remove_entry(&staging).await?;
+
+    debug!("Copied {} data entries to {}", staged.len(), to.display());
+    Ok(staged.len())
+}
+
+/// Remove the database and caches from a data directory that is no longer
+/// used, keeping the vault config.
+#[instrument]
+pub async fn remove_data_dir(dir: &Path) -> Result<()> {
+    for name in DATA_ENTRIES {
+        remove_entry(&dir.join(name)).await?;
+    }
+    Ok(())
+}
+
+#[async_recursion::async_recursion]
+async fn copy_entry(from: &Path, to: &Path) -> Result<()> {
+    if !fs::metadata(from).await?.is_dir() {
+        fs::copy(from, to).await?;
+        return Ok(());
     }
+
+    fs::create_dir_all(to).await?;
+    let mut entries = fs::read_dir(from).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        copy_entry(&entry.path(), &to.join(entry.file_name())).await?;
+    }
+    Ok(())
+}
+
+async fn remove_entry(path: &Path) -> Result<()> {
+    match fs::metadata(path).await {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path).await?,
+        Ok(_) => fs::remove_file(path).await?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
 }
 
 /// Compute a hash of file content for change detection.
@@ -198,6 +667,122 @@ pub fn hash_content(content: &str) -> String {
     format!("{:016x}", hash)
 }
 
+/// Compute a hash of binary content (attachments) for deduplication.
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    format!("{:016x}", xxh3_64(bytes))
+}
+
+/// Hash a file like [`hash_bytes`], reading it in chunks rather than all at
+/// once. Returns the hash and the file's size.
+pub async fn hash_file(path: &Path) -> Result<(String, u64)> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = fs::File::open(path).await?;
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let n = file.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        size += n as u64;
+    }
+    Ok((format!("{:016x}", hasher.digest()), size))
+}
+
+/// Characters that aren't allowed in file names on some platform, or that
+/// would break a `[[wikilink]]` to the note.
+const RESERVED_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']'];
+
+/// Device names Windows reserves regardless of extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9",
+    "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Longest file name stem in bytes; leaves room for a number suffix and
+/// the extension within the usual 255-byte limit.
+const MAX_STEM_BYTES: usize = 200;
+
+/// Generate a markdown file name from a note title.
+///
+/// The title is transliterated to ASCII ("Café Ärger" becomes "Cafe
+/// Arger"), reserved characters become `-`, whitespace is collapsed,
+/// leading and trailing dots and spaces are removed, Windows device names
+/// get a `_` suffix, and long titles are shortened. Empty results become
+/// "Untitled".
+pub fn note_file_name(title: &str) -> String {
+    let transliterated = deunicode::deunicode(title);
+    let replaced: String = transliterated
+        .chars()
+        .map(|c| if RESERVED_CHARS.contains(&c) || c.is_control() { '-' } else { c })
+        .collect();
+    let mut stem = replaced.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if stem.len() > MAX_STEM_BYTES {
+        let mut end = MAX_STEM_BYTES;
+        while !stem.is_char_boundary(end) {
+            end -= 1;
+        }
+        stem.truncate(end);
+    }
+    let mut stem = stem.trim_matches(|c: char| c == '.' || c == ' ').to_string();
+
+    if stem.is_empty() {
+        stem = "Untitled".to_string();
+    }
+    let device_name = stem.split('.').next().unwrap_or("").to_lowercase();
+    if WINDOWS_RESERVED_NAMES.contains(&device_name.as_str()) {
+        stem.push('_');
+    }
+
+    format!("{}.md", stem)
+}
+
+/// Add a number suffix to a file name, before its extension.
+///
+/// `numbered_file_name("photo.png", 2)` is `"photo 2.png"`.
+pub fn numbered_file_name(file_name: &str, n: usize) -> String {
+    match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{} {}.{}", stem, n, ext),
+        _ => format!("{} {}", file_name, n),
+    }
+}
+
+/// Bytes checked for a NUL byte, as git does to tell binary files from text.
+const BINARY_CHECK_BYTES: usize = 8000;
+
+/// Whether a file's content is binary rather than text.
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_CHECK_BYTES).any(|&b| b == 0)
+}
+
+/// Bytes cut out of UTF-8 text as a string, without the end of a character
+/// begun before them or the start of one cut off after them. Returns how
+/// many bytes were skipped at the start.
+fn whole_characters(bytes: &[u8]) -> (usize, String) {
+    let is_continuation = |b: &u8| b & 0xC0 == 0x80;
+    let skipped = bytes.iter().take(3).take_while(|b| is_continuation(b)).count();
+    let mut bytes = &bytes[skipped..];
+
+    let last = bytes.iter().rposition(|b| !is_continuation(b)).filter(|&i| i + 4 > bytes.len());
+    if let Some(i) = last {
+        let width = match bytes[i] {
+            b if b >= 0xF0 => 4,
+            b if b >= 0xE0 => 3,
+            b if b >= 0xC0 => 2,
+            _ => 1,
+        };
+        if i + width > bytes.len() {
+            bytes = &bytes[..i];
+        }
+    }
+
+    (skipped, String::from_utf8_lossy(bytes).into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,4 +796,206 @@ mod tests {
         assert_eq!(hash1, hash2);
         assert_ne!(hash1, hash3);
     }
+
+    #[tokio::test]
+    async fn test_hash_file() {
+        let path = std::env::temp_dir().join(format!("neuroflow-hash-{}.png", std::process::id()));
+        let bytes: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &bytes).unwrap();
+
+        let hashed = hash_file(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(hashed, (hash_bytes(&bytes), bytes.len() as u64));
+    }
+
+    #[test]
+    fn test_numbered_file_name() {
+        assert_eq!(numbered_file_name("photo.png", 2), "photo 2.png");
+        assert_eq!(numbered_file_name("archive.tar.gz", 1), "archive.tar 1.gz");
+        assert_eq!(numbered_file_name("README", 1), "README 1");
+        assert_eq!(numbered_file_name(".env", 1), ".env 1");
+    }
+
+    #[test]
+    fn test_note_file_name() {
+        assert_eq!(note_file_name("Project Plan"), "Project Plan.md");
+        assert_eq!(note_file_name("Café  Ärger"), "Cafe Arger.md");
+        assert_eq!(note_file_name("Q3: Goals / Review?"), "Q3- Goals - Review-.md");
+        assert_eq!(note_file_name("[[Links]] #tag"), "--Links-- -tag.md");
+        assert_eq!(note_file_name("...hidden. "), "hidden.md");
+        assert_eq!(note_file_name("CON"), "CON_.md");
+        assert_eq!(note_file_name("aux.notes"), "aux.notes_.md");
+        assert_eq!(note_file_name("   "), "Untitled.md");
+        assert_eq!(note_file_name(&"a".repeat(300)).len(), MAX_STEM_BYTES + 3);
+    }
+
+    #[tokio::test]
+    async fn test_read_file_range_keeps_characters_whole() {
+        let dir = std::env::temp_dir().join(format!("neuroflow-range-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("log.md"), "añb€c").unwrap();
+        let vault = VaultFs::new(&dir);
+
+        // "ñ" is bytes 1-2 and "€" bytes 4-6
+        let range = vault.read_file_range(Path::new("log.md"), 0, 2).await.unwrap();
+        assert_eq!((range.offset, range.content.as_str(), range.total_bytes), (0, "a", 8));
+        let range = vault.read_file_range(Path::new("log.md"), 2, 4).await.unwrap();
+        assert_eq!((range.offset, range.content.as_str()), (3, "b"));
+        let range = vault.read_file_range(Path::new("log.md"), 4, 100).await.unwrap();
+        assert_eq!((range.offset, range.content.as_str()), (4, "€c"));
+        let range = vault.read_file_range(Path::new("log.md"), 100, 10).await.unwrap();
+        assert_eq!((range.offset, range.content.as_str()), (8, ""));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_text_replaces_invalid_utf8() {
+        let dir = std::env::temp_dir().join(format!("neuroflow-read-text-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ok.md"), "caf\u{e9}").unwrap();
+        std::fs::write(dir.join("latin1.md"), b"caf\xe9 au lait").unwrap();
+        std::fs::write(dir.join("image.md"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let vault = VaultFs::new(&dir);
+
+        let text = vault.read_text(Path::new("ok.md")).await.unwrap();
+        assert_eq!(text, TextFile { content: "caf\u{e9}".to_string(), lossy: false });
+        let text = vault.read_text(Path::new("latin1.md")).await.unwrap();
+        assert_eq!(text, TextFile { content: "caf\u{FFFD} au lait".to_string(), lossy: true });
+        assert!(matches!(vault.read_text(Path::new("image.md")).await, Err(FsError::Binary(_))));
+        assert!(vault.read_file(Path::new("latin1.md")).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unique_path_ignores_case() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("notes")).unwrap();
+        std::fs::write(dir.path().join("notes/Plan.md"), "").unwrap();
+        std::fs::write(dir.path().join("notes/plan 1.md"), "").unwrap();
+
+        let vault = VaultFs::new(dir.path());
+        assert_eq!(vault.unique_path(Path::new("notes"), "plan.md").await, Path::new("notes/plan 2.md"));
+        assert_eq!(vault.unique_path(Path::new("other"), "plan.md").await, Path::new("other/plan.md"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_markdown_files_in() {
+        let dir = std::env::temp_dir().join(format!("neuroflow-scan-in-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("projects/sub")).unwrap();
+        std::fs::write(dir.join("projects/a.md"), "").unwrap();
+        std::fs::write(dir.join("projects/sub/b.md"), "").unwrap();
+        std::fs::write(dir.join("other.md"), "").unwrap();
+
+        let vault = VaultFs::new(&dir);
+        let mut files = vault.scan_markdown_files_in(Path::new("projects")).await.unwrap().files;
+        files.sort();
+        assert_eq!(files, vec![PathBuf::from("projects/a.md"), PathBuf::from("projects/sub/b.md")]);
+        assert!(vault.scan_markdown_files_in(Path::new("gone")).await.unwrap().files.is_empty());
+        assert!(vault.scan_markdown_files_in(Path::new("../x")).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_scan_reports_non_utf8_names() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = std::env::temp_dir().join(format!("neuroflow-scan-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("notes/.hidden")).unwrap();
+        std::fs::write(dir.join("notes/ok.md"), "").unwrap();
+        std::fs::write(dir.join("notes/.hidden/skipped.md"), "").unwrap();
+        std::fs::write(dir.join("notes/image.png"), "").unwrap();
+        let bad_name = OsStr::from_bytes(b"caf\xe9.md");
+        if std::fs::write(dir.join("notes").join(bad_name), "").is_err() {
+            // Some filesystems reject non-UTF-8 names
+            std::fs::remove_dir_all(&dir).unwrap();
+            return;
+        }
+
+        let report = VaultFs::new(&dir).scan_markdown_files().await.unwrap();
+        assert_eq!(report.files, vec![PathBuf::from("notes/ok.md")]);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].path, Path::new("notes").join(bad_name));
+        assert_eq!(report.warnings[0].kind, ScanWarningKind::NonUtf8Name);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_copy_and_remove_data_dir() {
+        let dir = std::env::temp_dir().join(format!("neuroflow-move-data-{}", std::process::id()));
+        let from = dir.join("vault/.neuroflow");
+        let to = dir.join("app-data");
+        std::fs::create_dir_all(from.join("cache/thumbnails")).unwrap();
+        std::fs::write(from.join("neuroflow.db"), "db").unwrap();
+        std::fs::write(from.join("cache/thumbnails/a.webp"), "").unwrap();
+        std::fs::write(from.join("config.json"), "{}").unwrap();
+
+        let vault_fs = VaultFs::new(dir.join("vault")).with_data_dir(&to);
+        assert_eq!(vault_fs.db_path(), to.join("neuroflow.db"));
+        assert_eq!(vault_fs.config_path(), from.join("config.json"));
+
+        // A stale database at the target is replaced
+        std::fs::create_dir_all(&to).unwrap();
+        std::fs::write(to.join("neuroflow.db"), "old").unwrap();
+
+        assert_eq!(copy_data_dir(&from, &to).await.unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(to.join("neuroflow.db")).unwrap(), "db");
+        assert!(to.join("cache/thumbnails/a.webp").exists());
+        assert!(!to.join(DATA_STAGING_DIR).exists());
+        assert!(from.join("neuroflow.db").exists());
+
+        remove_data_dir(&from).await.unwrap();
+        assert!(!from.join("neuroflow.db").exists());
+        assert!(!from.join("cache").exists());
+        assert!(from.join("config.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_external_folders_are_read_only() {
+        let dir = std::env::temp_dir().join(format!("neuroflow-external-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("vault")).unwrap();
+        std::fs::create_dir_all(dir.join("wiki/guides")).unwrap();
+        std::fs::write(dir.join("vault/note.md"), "").unwrap();
+        std::fs::write(dir.join("wiki/guides/setup.md"), "Setup").unwrap();
+
+        let vault = VaultFs::new(dir.join("vault")).with_external_folder("wiki", dir.join("wiki"));
+        let report = vault.scan_markdown_files().await.unwrap();
+        assert_eq!(
+            report.files,
+            vec![PathBuf::from("note.md"), PathBuf::from("@wiki/guides/setup.md")]
+        );
+
+        let setup = Path::new("@wiki/guides/setup.md");
+        assert_eq!(vault.to_absolute(setup), dir.join("wiki/guides/setup.md"));
+        assert_eq!(vault.to_relative(&dir.join("wiki/guides/setup.md")).unwrap(), setup);
+        assert_eq!(vault.read_file(setup).await.unwrap(), "Setup");
+        assert!(vault.is_read_only(setup));
+        assert!(!vault.is_read_only(Path::new("@other/setup.md")));
+
+        assert!(matches!(vault.write_file(setup, "x").await, Err(FsError::ReadOnly(_))));
+        assert!(matches!(vault.delete_file(setup).await, Err(FsError::ReadOnly(_))));
+        assert!(matches!(
+            vault.rename_file(Path::new("note.md"), Path::new("@wiki/note.md")).await,
+            Err(FsError::ReadOnly(_))
+        ));
+        assert_eq!(std::fs::read_to_string(dir.join("wiki/guides/setup.md")).unwrap(), "Setup");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_external_data_dir() {
+        let app_data = Path::new("/app-data");
+        let a = external_data_dir(app_data, Path::new("/nonexistent/vault-a"));
+        let b = external_data_dir(app_data, Path::new("/nonexistent/vault-b"));
+        assert!(a.starts_with("/app-data/vaults"));
+        assert_ne!(a, b);
+        assert_eq!(a, external_data_dir(app_data, Path::new("/nonexistent/vault-a")));
+    }
 }
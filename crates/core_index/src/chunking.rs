@@ -0,0 +1,187 @@
+//! Splitting notes into sections for embedding.
+//!
+//! Long notes embed poorly as a whole, so each heading starts a new chunk
+//! and sections longer than the size limit are split between paragraphs.
+//! Chunks keep the headings leading to them and their line range, so a
+//! semantic search hit can point at the matching section.
+
+use crate::frontmatter::strip_frontmatter;
+use crate::markdown::parse;
+use crate::redaction::redact_private;
+
+/// Default chunk size limit in characters.
+pub const MAX_CHUNK_CHARS: usize = 1500;
+
+/// A section of a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Headings leading to the chunk, outermost first.
+    pub heading_path: Vec<String>,
+    /// The chunk's lines (private sections blanked), trimmed.
+    pub text: String,
+    /// First and last line of the chunk in the file (1-indexed).
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl Chunk {
+    /// The text to embed: the heading path followed by the chunk text, so
+    /// sections are found by the topic of their headings too.
+    pub fn embedding_text(&self) -> String {
+        if self.heading_path.is_empty() {
+            self.text.clone()
+        } else {
+            format!("{}\n\n{}", self.heading_path.join(" > "), self.text)
+        }
+    }
+}
+
+/// Split note content (with frontmatter) into chunks of at most about
+/// `max_chars` characters.
+///
+/// A paragraph longer than `max_chars` is split between lines; a single
+/// longer line becomes its own chunk. Chunks with nothing but a heading or
+/// whitespace are left out.
+pub fn chunk_note(content: &str, max_chars: usize) -> Vec<Chunk> {
+    let redacted = redact_private(content);
+    let body = strip_frontmatter(&redacted);
+    let line_offset = redacted[..redacted.len() - body.len()].matches('\n').count();
+
+    let headings = parse(content).headings;
+    let mut headings = headings.iter().peekable();
+
+    let mut chunker = Chunker {
+        chunks: Vec::new(),
+        heading_path: Vec::new(),
+        levels: Vec::new(),
+        lines: Vec::new(),
+        len: 0,
+        paragraph_start: 0,
+        max_chars,
+    };
+
+    for (i, line) in body.lines().enumerate() {
+        let body_line = i + 1;
+        if let Some(heading) = headings.next_if(|h| h.line_number == body_line) {
+            chunker.flush();
+            while chunker.levels.last().is_some_and(|&level| level >= heading.level) {
+                chunker.levels.pop();
+                chunker.heading_path.pop();
+            }
+            chunker.levels.push(heading.level);
+            chunker.heading_path.push(heading.text.clone());
+        }
+        chunker.push(line_offset + body_line, line);
+    }
+    chunker.flush();
+
+    chunker.chunks
+}
+
+struct Chunker<'a> {
+    chunks: Vec<Chunk>,
+    heading_path: Vec<String>,
+    levels: Vec<u8>,
+    /// Lines of the current chunk with their file line numbers.
+    lines: Vec<(usize, &'a str)>,
+    /// Characters in `lines`.
+    len: usize,
+    /// Index in `lines` where the current paragraph starts.
+    paragraph_start: usize,
+    max_chars: usize,
+}
+
+impl<'a> Chunker<'a> {
+    fn push(&mut self, line_number: usize, line: &'a str) {
+        let line_len = line.chars().count() + 1;
+        if !self.lines.is_empty() && self.len + line_len > self.max_chars {
+            // Split before the current paragraph if there is one, else here
+            let split = if self.paragraph_start > 0 {
+                self.paragraph_start
+            } else {
+                self.lines.len()
+            };
+            let rest = self.lines.split_off(split);
+            self.flush();
+            self.len = rest.iter().map(|(_, l)| l.chars().count() + 1).sum();
+            self.lines = rest;
+        }
+
+        if line.trim().is_empty() {
+            self.paragraph_start = self.lines.len() + 1;
+        }
+        self.lines.push((line_number, line));
+        self.len += line_len;
+    }
+
+    fn flush(&mut self) {
+        let lines = std::mem::take(&mut self.lines);
+        self.len = 0;
+        self.paragraph_start = 0;
+
+        let Some(first) = lines.iter().position(|(_, l)| !l.trim().is_empty()) else {
+            return;
+        };
+        let last = lines.iter().rposition(|(_, l)| !l.trim().is_empty()).unwrap_or(first);
+        let lines = &lines[first..=last];
+
+        // A heading followed directly by a subheading has no text of its own
+        let is_heading_only = lines.len() == 1 && lines[0].1.trim_start().starts_with('#');
+        if is_heading_only {
+            return;
+        }
+
+        self.chunks.push(Chunk {
+            heading_path: self.heading_path.clone(),
+            text: lines.iter().map(|(_, l)| *l).collect::<Vec<_>>().join("\n"),
+            start_line: lines[0].0,
+            end_line: lines[lines.len() - 1].0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_by_heading() {
+        let content = "---\ntitle: Plan\n---\nIntro line.\n\n# Project\n## Goals\nShip it.\n\n## Risks\nNone %%private%%secret%%end%%\n# Later\nMore.\n";
+        let chunks = chunk_note(content, MAX_CHUNK_CHARS);
+
+        let summary: Vec<_> = chunks
+            .iter()
+            .map(|c| (c.heading_path.join(" > "), c.start_line, c.end_line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("".to_string(), 4, 4),
+                ("Project > Goals".to_string(), 7, 8),
+                ("Project > Risks".to_string(), 10, 11),
+                ("Later".to_string(), 12, 13),
+            ]
+        );
+        assert!(!chunks[2].text.contains("secret"));
+        assert_eq!(chunks[1].embedding_text(), "Project > Goals\n\n## Goals\nShip it.");
+    }
+
+    #[test]
+    fn test_long_sections_split_between_paragraphs() {
+        let paragraph = "word ".repeat(20);
+        let content = format!("# Long\n{p}\n\n{p}\n\n{p}\n", p = paragraph.trim());
+        let chunks = chunk_note(&content, 250);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 4));
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (6, 6));
+        assert!(chunks.iter().all(|c| c.heading_path == vec!["Long".to_string()]));
+    }
+
+    #[test]
+    fn test_code_fences_are_not_headings() {
+        let chunks = chunk_note("# A\n```\n# not a heading\n```\n", MAX_CHUNK_CHARS);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].end_line, 4);
+    }
+}
@@ -0,0 +1,284 @@
+//! Duplicate and near-duplicate detection with shingling and MinHash.
+//!
+//! A note's body (frontmatter and private sections removed) is reduced to
+//! lowercase words. Exact duplicates share the hash of those words, so
+//! formatting, case, and frontmatter differences are ignored. Near
+//! duplicates are found by comparing sets of 3-word shingles: MinHash
+//! signatures with locality-sensitive hashing pick candidate pairs, and the
+//! Jaccard similarity of the shingle sets decides.
+
+use std::collections::{HashMap, HashSet};
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::frontmatter::strip_frontmatter;
+use crate::redaction::redact_private;
+
+/// Notes with fewer words are ignored (empty and stub notes match each other).
+pub const MIN_WORDS: usize = 10;
+
+/// Words per shingle.
+const SHINGLE_SIZE: usize = 3;
+
+/// Signature bands and rows per band; pairs with a Jaccard similarity
+/// above about (1/BANDS)^(1/ROWS) ≈ 0.42 almost always share a band.
+const BANDS: usize = 32;
+const ROWS: usize = 4;
+
+/// Signature pairs estimated this far below the threshold are not checked.
+const ESTIMATE_MARGIN: f64 = 0.15;
+
+/// What duplicate detection needs to know about one note.
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    /// Hash of the normalized words; equal for exact duplicates.
+    pub content_hash: u64,
+    pub word_count: usize,
+    /// Sorted, deduplicated shingle hashes.
+    shingles: Vec<u64>,
+    signature: Vec<u64>,
+}
+
+impl Fingerprint {
+    /// Jaccard similarity of the two notes' shingle sets (1.0 for exact duplicates).
+    pub fn similarity(&self, other: &Fingerprint) -> f64 {
+        if self.content_hash == other.content_hash {
+            return 1.0;
+        }
+        if self.shingles.is_empty() || other.shingles.is_empty() {
+            return 0.0;
+        }
+
+        let (mut i, mut j, mut shared) = (0, 0, 0);
+        while i < self.shingles.len() && j < other.shingles.len() {
+            match self.shingles[i].cmp(&other.shingles[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    shared += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        shared as f64 / (self.shingles.len() + other.shingles.len() - shared) as f64
+    }
+
+    /// MinHash signature, small enough to store and compare later with
+    /// `signature_similarity`.
+    pub fn signature(&self) -> &[u64] {
+        &self.signature
+    }
+
+    /// Fraction of equal signature entries (an estimate of `similarity`).
+    fn estimated_similarity(&self, other: &Fingerprint) -> f64 {
+        signature_similarity(&self.signature, &other.signature)
+    }
+}
+
+/// Estimated Jaccard similarity of the notes two signatures were computed
+/// from; 0.0 if they can't be compared (either is empty, or their lengths
+/// differ).
+///
+/// Signatures are built with xxh3 and fixed permutations, so ones stored by
+/// any build or platform compare with each other.
+pub fn signature_similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    equal as f64 / a.len() as f64
+}
+
+/// Compute the fingerprint of note content (with frontmatter).
+pub fn fingerprint(content: &str) -> Fingerprint {
+    let redacted = redact_private(content);
+    let body = strip_frontmatter(&redacted).to_lowercase();
+    let words: Vec<&str> = body
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut shingles: Vec<u64> = words
+        .windows(SHINGLE_SIZE.min(words.len()).max(1))
+        .map(hash_words)
+        .collect();
+    shingles.sort_unstable();
+    shingles.dedup();
+
+    let signature = (0..BANDS * ROWS)
+        .map(|seed| {
+            let seed = mix(seed as u64 + 1);
+            shingles.iter().map(|&s| mix(s ^ seed)).min().unwrap_or(u64::MAX)
+        })
+        .collect();
+
+    Fingerprint {
+        content_hash: hash_words(&words),
+        word_count: words.len(),
+        shingles,
+        signature,
+    }
+}
+
+/// Group fingerprints whose similarity is at least `threshold`.
+///
+/// Returns clusters of indices into `fingerprints` (each sorted, with at
+/// least two members). Notes are linked if they are similar to any other
+/// member, so not every pair in a cluster has to reach the threshold.
+pub fn find_duplicates(fingerprints: &[Fingerprint], threshold: f64) -> Vec<Vec<usize>> {
+    let mut clusters = UnionFind::new(fingerprints.len());
+
+    // Exact duplicates are linked directly; one representative each goes on
+    let mut representatives: HashMap<u64, usize> = HashMap::new();
+    for (i, fp) in fingerprints.iter().enumerate() {
+        if fp.word_count < MIN_WORDS {
+            continue;
+        }
+        match representatives.get(&fp.content_hash) {
+            Some(&first) => clusters.union(first, i),
+            None => {
+                representatives.insert(fp.content_hash, i);
+            }
+        }
+    }
+
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    for &i in representatives.values() {
+        for (band, rows) in fingerprints[i].signature.chunks(ROWS).enumerate() {
+            buckets.entry((band, hash_rows(rows))).or_default().push(i);
+        }
+    }
+
+    let mut checked = HashSet::new();
+    for members in buckets.values() {
+        for (k, &a) in members.iter().enumerate() {
+            for &b in &members[k + 1..] {
+                let pair = (a.min(b), a.max(b));
+                if !checked.insert(pair) {
+                    continue;
+                }
+                let (fa, fb) = (&fingerprints[a], &fingerprints[b]);
+                if fa.estimated_similarity(fb) >= threshold - ESTIMATE_MARGIN && fa.similarity(fb) >= threshold {
+                    clusters.union(a, b);
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, fp) in fingerprints.iter().enumerate() {
+        if fp.word_count >= MIN_WORDS {
+            groups.entry(clusters.find(i)).or_default().push(i);
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    for group in &mut groups {
+        group.sort_unstable();
+    }
+    groups.sort();
+    groups
+}
+
+/// Hash a run of words. The hash is fixed across builds and platforms, as
+/// signatures are stored with embeddings.
+fn hash_words(words: &[&str]) -> u64 {
+    xxh3_64(words.join(" ").as_bytes())
+}
+
+/// Hash a band of signature rows.
+fn hash_rows(rows: &[u64]) -> u64 {
+    let bytes: Vec<u8> = rows.iter().flat_map(|row| row.to_le_bytes()).collect();
+    xxh3_64(&bytes)
+}
+
+/// SplitMix64 finalizer, used to derive the MinHash permutations.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut i = i;
+        while self.parent[i] != root {
+            let next = self.parent[i];
+            self.parent[i] = root;
+            i = next;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[b] = a;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog while the farmer watches from the old wooden porch and drinks his morning coffee slowly";
+
+    #[test]
+    fn test_exact_duplicates_ignore_formatting() {
+        let a = fingerprint(&format!("---\ntitle: A\n---\n# {}", TEXT));
+        let b = fingerprint(&TEXT.to_uppercase());
+        assert_eq!(a.content_hash, b.content_hash);
+        assert_eq!(a.similarity(&b), 1.0);
+        assert_eq!(a.word_count, 24);
+    }
+
+    #[test]
+    fn test_hashes_are_fixed() {
+        // Stored signatures must still match after a rebuild
+        assert_eq!(hash_words(&["quick", "brown", "fox"]), xxh3_64(b"quick brown fox"));
+        assert_eq!(fingerprint(TEXT).content_hash, 7049458439709441222);
+    }
+
+    #[test]
+    fn test_similarity() {
+        let a = fingerprint(TEXT);
+        let b = fingerprint(&TEXT.replace("slowly", "quickly"));
+        let c = fingerprint("Completely different words about gardening tomatoes in raised beds during a hot and dry summer");
+
+        let near = a.similarity(&b);
+        assert!(near > 0.85 && near < 1.0, "{}", near);
+        assert!((a.estimated_similarity(&b) - near).abs() < 0.15);
+        assert_eq!(signature_similarity(a.signature(), &[]), 0.0);
+        assert_eq!(a.similarity(&c), 0.0);
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let fingerprints = vec![
+            fingerprint(TEXT),
+            fingerprint("Completely different words about gardening tomatoes in raised beds during a hot and dry summer"),
+            fingerprint(&TEXT.replace("slowly", "quickly")),
+            fingerprint(TEXT),
+            fingerprint("Too short"),
+            fingerprint("Too short"),
+        ];
+
+        assert_eq!(find_duplicates(&fingerprints, 0.8), vec![vec![0, 2, 3]]);
+        assert_eq!(find_duplicates(&fingerprints, 1.0), vec![vec![0, 3]]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Footnotes (`[^label]` references and `[^label]: text` definitions).
+//!
+//! Footnotes are found with pulldown-cmark, so markers in code are left
+//! alone. References without a definition are reported too, which the
+//! linter uses to flag them. Labels match case-insensitively.
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// A footnote reference or definition in a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footnote {
+    /// The label, as written (`1` for `[^1]`).
+    pub label: String,
+    /// Line number (1-based) of the marker.
+    pub line_number: usize,
+    /// Byte range of the `[^label]` marker.
+    pub range: Range<usize>,
+}
+
+/// The footnotes of a note, in document order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Footnotes {
+    pub definitions: Vec<Footnote>,
+    pub references: Vec<Footnote>,
+}
+
+impl Footnotes {
+    /// References to a footnote that isn't defined.
+    pub fn undefined(&self) -> impl Iterator<Item = &Footnote> {
+        let defined: HashSet<String> = self.definitions.iter().map(|d| d.label.to_lowercase()).collect();
+        self.references
+            .iter()
+            .filter(move |r| !defined.contains(&r.label.to_lowercase()))
+    }
+
+    /// Definitions that are never referenced.
+    pub fn orphaned(&self) -> impl Iterator<Item = &Footnote> {
+        let referenced: HashSet<String> = self.references.iter().map(|r| r.label.to_lowercase()).collect();
+        self.definitions
+            .iter()
+            .filter(move |d| !referenced.contains(&d.label.to_lowercase()))
+    }
+}
+
+/// Find the footnote references and definitions in markdown.
+pub fn find_footnotes(markdown: &str) -> Footnotes {
+    // The old footnote syntax reports references to undefined footnotes
+    // instead of treating them as text
+    let parser = Parser::new_ext(markdown, Options::ENABLE_OLD_FOOTNOTES);
+    let line_number = |offset: usize| markdown[..offset].matches('\n').count() + 1;
+
+    let mut footnotes = Footnotes::default();
+    for (event, range) in parser.into_offset_iter() {
+        match event {
+            Event::FootnoteReference(label) => footnotes.references.push(Footnote {
+                label: label.to_string(),
+                line_number: line_number(range.start),
+                range,
+            }),
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                // The definition starts with its `[^label]:` marker
+                let Some(end) = markdown[range.start..].find("]:") else {
+                    continue;
+                };
+                footnotes.definitions.push(Footnote {
+                    label: label.to_string(),
+                    line_number: line_number(range.start),
+                    range: range.start..range.start + end + 1,
+                });
+            }
+            _ => {}
+        }
+    }
+    footnotes
+}
+
+/// Renumber footnotes 1, 2, 3... in the order they are first referenced.
+///
+/// Definitions that are never referenced are numbered after the rest. Only
+/// the labels change; definitions stay where they are.
+pub fn renumber_footnotes(markdown: &str) -> String {
+    let footnotes = find_footnotes(markdown);
+
+    let mut numbers: HashMap<String, usize> = HashMap::new();
+    for footnote in footnotes.references.iter().chain(&footnotes.definitions) {
+        let next = numbers.len() + 1;
+        numbers.entry(footnote.label.to_lowercase()).or_insert(next);
+    }
+
+    let mut markers: Vec<&Footnote> = footnotes.references.iter().chain(&footnotes.definitions).collect();
+    markers.sort_by_key(|f| f.range.start);
+
+    let mut output = String::with_capacity(markdown.len());
+    let mut last = 0;
+    for marker in markers {
+        output.push_str(&markdown[last..marker.range.start]);
+        output.push_str(&format!("[^{}]", numbers[&marker.label.to_lowercase()]));
+        last = marker.range.end;
+    }
+    output.push_str(&markdown[last..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_footnotes() {
+        let markdown = "Intro[^note] and more[^missing].\n\n`[^code]`\n\n[^note]: Defined.\n[^extra]: Never used.\n";
+        let footnotes = find_footnotes(markdown);
+
+        let labels = |f: &[Footnote]| f.iter().map(|f| (f.label.clone(), f.line_number)).collect::<Vec<_>>();
+        assert_eq!(labels(&footnotes.references), vec![("note".to_string(), 1), ("missing".to_string(), 1)]);
+        assert_eq!(labels(&footnotes.definitions), vec![("note".to_string(), 5), ("extra".to_string(), 6)]);
+        assert_eq!(&markdown[footnotes.definitions[0].range.clone()], "[^note]");
+        assert_eq!(&markdown[footnotes.references[1].range.clone()], "[^missing]");
+
+        assert_eq!(footnotes.undefined().map(|f| f.label.as_str()).collect::<Vec<_>>(), vec!["missing"]);
+        assert_eq!(footnotes.orphaned().map(|f| f.label.as_str()).collect::<Vec<_>>(), vec!["extra"]);
+    }
+
+    #[test]
+    fn test_renumber_footnotes() {
+        let markdown = "First[^b], second[^a], again[^B].\n\n[^a]: A.\n[^unused]: U.\n[^b]: B.\n";
+        assert_eq!(
+            renumber_footnotes(markdown),
+            "First[^1], second[^2], again[^1].\n\n[^2]: A.\n[^3]: U.\n[^1]: B.\n"
+        );
+
+        let plain = "No footnotes, just [links](x) and `[^code]`.\n";
+        assert_eq!(renumber_footnotes(plain), plain);
+    }
+}
@@ -243,6 +243,42 @@ pub fn set_frontmatter_property(
     serialize_with_frontmatter(&frontmatter, body)
 }
 
+/// Update or add a list property in a markdown file's frontmatter, written as
+/// a YAML sequence so items containing commas stay whole.
+pub fn set_frontmatter_list(content: &str, key: &str, items: &[String]) -> String {
+    let (mut frontmatter, body) = parse_frontmatter(content);
+
+    frontmatter.properties.insert(key.to_string(), PropertyValue::List(items.to_vec()));
+
+    serialize_with_frontmatter(&frontmatter, body)
+}
+
+/// Add an alias to a markdown file's frontmatter, unless it has it already
+/// (ignoring case). Returns the new content, or `None` if it is unchanged.
+/// Frontmatter that can't be parsed is left alone.
+pub fn add_frontmatter_alias(content: &str, alias: &str) -> Option<String> {
+    let (mut frontmatter, body) = parse_frontmatter(content);
+    if frontmatter.content_start > 0 && frontmatter.properties.is_empty() {
+        return None;
+    }
+    if frontmatter.aliases.iter().any(|a| a.eq_ignore_ascii_case(alias)) {
+        return None;
+    }
+
+    // Keep the key the note uses (`alias`, `Aliases`, ...)
+    let key = frontmatter
+        .properties
+        .keys()
+        .find(|key| matches!(key.to_lowercase().as_str(), "aliases" | "alias"))
+        .cloned()
+        .unwrap_or_else(|| "aliases".to_string());
+    let mut aliases = frontmatter.aliases.clone();
+    aliases.push(alias.to_string());
+    frontmatter.properties.insert(key, PropertyValue::List(aliases));
+
+    Some(serialize_with_frontmatter(&frontmatter, body))
+}
+
 /// Delete a property from a markdown file's frontmatter.
 /// Returns the new content with the property removed.
 pub fn delete_frontmatter_property(content: &str, key: &str) -> String {
@@ -459,6 +495,20 @@ status: draft
         assert!(result.contains("# My Note"));
     }
 
+    #[test]
+    fn test_set_frontmatter_list_keeps_commas() {
+        let content = "---\nauthors: Old\n---\n# Note";
+        let items = vec!["Doe, Jane".to_string(), "Roe, Richard".to_string()];
+        let result = set_frontmatter_list(content, "authors", &items);
+
+        let (frontmatter, body) = parse_frontmatter(&result);
+        match frontmatter.properties.get("authors") {
+            Some(PropertyValue::List(list)) => assert_eq!(list, &items),
+            other => panic!("expected a list, got {:?}", other),
+        }
+        assert!(body.contains("# Note"));
+    }
+
     #[test]
     fn test_set_frontmatter_property_boolean() {
         let content = "# Note";
@@ -516,4 +566,21 @@ status: draft
         assert!(!result.contains("---"));
         assert!(result.contains("# Content"));
     }
+
+    #[test]
+    fn test_add_frontmatter_alias() {
+        let added = add_frontmatter_alias("# Spring garden\n", "Garden plan").unwrap();
+        assert!(added.starts_with("---\naliases:\n- Garden plan\n---\n"));
+        assert!(added.ends_with("# Spring garden\n"));
+
+        let content = "---\nalias: Plan\nstatus: draft\n---\n\n# Spring garden\n";
+        let added = add_frontmatter_alias(content, "Garden plan").unwrap();
+        let (frontmatter, _) = parse_frontmatter(&added);
+        assert_eq!(frontmatter.aliases, vec!["Plan", "Garden plan"]);
+        assert!(added.contains("status: draft"));
+
+        // Already there, or frontmatter that doesn't parse
+        assert_eq!(add_frontmatter_alias(content, "plan"), None);
+        assert_eq!(add_frontmatter_alias("---\n: [oops\n---\n# Note\n", "Old"), None);
+    }
 }
@@ -0,0 +1,388 @@
+//! HTML to markdown conversion for the web clipper.
+//!
+//! The page is parsed with `scraper` (html5ever), the main content is
+//! picked with simple readability rules (the largest `<article>`, then
+//! `<main>`, then `<body>`, without scripts, navigation, and other page
+//! chrome), and the result is rendered as markdown.
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// A web page converted to markdown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClippedPage {
+    /// Page title (`og:title`, then `<title>`, then the first `<h1>`).
+    pub title: Option<String>,
+    pub markdown: String,
+    /// Images in `markdown`, each written as `![alt](src)` with `src` as in the page.
+    pub images: Vec<ClippedImage>,
+}
+
+/// An image referenced by a clipped page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClippedImage {
+    pub alt: String,
+    pub src: String,
+}
+
+impl ClippedImage {
+    /// The markdown this image is written as.
+    pub fn markdown(&self) -> String {
+        format!("![{}]({})", self.alt, self.src)
+    }
+}
+
+/// Elements whose content is never part of the article.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "button", "iframe", "svg", "canvas", "select",
+];
+
+/// Extract the main content of an HTML page as markdown.
+pub fn extract_article(html: &str) -> ClippedPage {
+    let document = Html::parse_document(html);
+    let select = |selector: &str| {
+        let selector = Selector::parse(selector).expect("valid selector");
+        document.select(&selector).collect::<Vec<_>>()
+    };
+
+    let og_title = select(r#"meta[property="og:title"]"#)
+        .first()
+        .and_then(|m| m.attr("content"))
+        .map(str::to_string);
+    let title = og_title
+        .or_else(|| select("title").first().map(text))
+        .or_else(|| select("h1").first().map(text))
+        .map(|t| collapse_whitespace(&t).trim().to_string())
+        .filter(|t| !t.is_empty());
+
+    let content = select("article")
+        .into_iter()
+        .max_by_key(|a| text(a).len())
+        .or_else(|| select("main").into_iter().next())
+        .or_else(|| select("body").into_iter().next())
+        .unwrap_or_else(|| document.root_element());
+
+    let mut images = Vec::new();
+    let mut out = String::new();
+    render_children(content, &mut out, &mut images);
+
+    ClippedPage {
+        title,
+        markdown: normalize_blank_lines(&out),
+        images,
+    }
+}
+
+/// All text in an element, as in the source.
+fn text(element: &ElementRef) -> String {
+    element.text().collect()
+}
+
+// =============================================================================
+// Rendering
+// =============================================================================
+
+fn render_children(element: ElementRef, out: &mut String, images: &mut Vec<ClippedImage>) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => push_inline_text(out, &collapse_whitespace(text)),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    render_element(child, out, images);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn render_element(element: ElementRef, out: &mut String, images: &mut Vec<ClippedImage>) {
+    let name = element.value().name();
+    if SKIPPED_ELEMENTS.contains(&name) {
+        return;
+    }
+
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse().unwrap_or(1);
+            let text = render_inline(element, images);
+            if !text.is_empty() {
+                start_block(out);
+                out.push_str(&"#".repeat(level));
+                out.push(' ');
+                out.push_str(&text);
+                start_block(out);
+            }
+        }
+        "br" => out.push('\n'),
+        "hr" => {
+            start_block(out);
+            out.push_str("---");
+            start_block(out);
+        }
+        "pre" => {
+            let code = text(&element);
+            start_block(out);
+            out.push_str("```\n");
+            out.push_str(code.trim_matches('\n'));
+            out.push_str("\n```");
+            start_block(out);
+        }
+        "blockquote" => {
+            let mut inner = String::new();
+            render_children(element, &mut inner, images);
+            let inner = normalize_blank_lines(&inner);
+            if !inner.is_empty() {
+                start_block(out);
+                let quoted: Vec<String> = inner
+                    .trim_end()
+                    .lines()
+                    .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                    .collect();
+                out.push_str(&quoted.join("\n"));
+                start_block(out);
+            }
+        }
+        "ul" | "ol" => {
+            let mut number = element
+                .attr("start")
+                .and_then(|s| s.parse::<usize>().ok())
+                .unwrap_or(1);
+            let mut items = Vec::new();
+            for li in element.children().filter_map(ElementRef::wrap) {
+                if li.value().name() != "li" {
+                    continue;
+                }
+
+                let mut inner = String::new();
+                render_children(li, &mut inner, images);
+                let inner = normalize_blank_lines(&inner);
+                let marker = if name == "ol" { format!("{}. ", number) } else { "- ".to_string() };
+                number += 1;
+
+                // Tight list: continuation lines are indented, blank lines dropped
+                let indent = " ".repeat(marker.len());
+                let lines: Vec<String> = inner
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .enumerate()
+                    .map(|(i, line)| {
+                        if i == 0 {
+                            format!("{}{}", marker, line)
+                        } else {
+                            format!("{}{}", indent, line)
+                        }
+                    })
+                    .collect();
+                items.push(lines.join("\n"));
+            }
+            if !items.is_empty() {
+                start_block(out);
+                out.push_str(&items.join("\n"));
+                start_block(out);
+            }
+        }
+        "a" => {
+            let text = render_inline(element, images);
+            let href = element.attr("href").unwrap_or("").trim();
+            if text.is_empty() {
+                return;
+            }
+            if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+                push_inline_text(out, &text);
+            } else {
+                push_inline_text(out, &format!("[{}]({})", text, href));
+            }
+        }
+        "img" => {
+            let src = element
+                .attr("src")
+                .filter(|s| !s.is_empty())
+                .or_else(|| element.attr("data-src"))
+                .unwrap_or("")
+                .trim();
+            if src.is_empty() || src.starts_with("data:") {
+                return;
+            }
+            let alt = collapse_whitespace(element.attr("alt").unwrap_or(""))
+                .replace(['[', ']'], "")
+                .trim()
+                .to_string();
+            let image = ClippedImage { alt, src: src.to_string() };
+            push_inline_text(out, &image.markdown());
+            images.push(image);
+        }
+        "strong" | "b" => push_wrapped(out, &render_inline(element, images), "**"),
+        "em" | "i" => push_wrapped(out, &render_inline(element, images), "*"),
+        "del" | "s" | "strike" => push_wrapped(out, &render_inline(element, images), "~~"),
+        "code" => push_wrapped(out, collapse_whitespace(&text(&element)).trim(), "`"),
+        "p" | "div" | "section" | "article" | "main" | "figure" | "figcaption" | "table"
+        | "tr" | "dl" | "dt" | "dd" | "address" | "li" | "body" => {
+            start_block(out);
+            render_children(element, out, images);
+            start_block(out);
+        }
+        "td" | "th" => {
+            render_children(element, out, images);
+            push_inline_text(out, " ");
+        }
+        _ => render_children(element, out, images),
+    }
+}
+
+/// Render children as a single line of inline markdown.
+fn render_inline(element: ElementRef, images: &mut Vec<ClippedImage>) -> String {
+    let mut inner = String::new();
+    render_children(element, &mut inner, images);
+    collapse_whitespace(&inner).trim().to_string()
+}
+
+fn push_wrapped(out: &mut String, text: &str, marker: &str) {
+    if !text.is_empty() {
+        push_inline_text(out, &format!("{}{}{}", marker, text, marker));
+    }
+}
+
+/// Append inline text, without leading spaces at the start of a line.
+fn push_inline_text(out: &mut String, text: &str) {
+    if out.is_empty() || out.ends_with('\n') || out.ends_with(' ') {
+        out.push_str(text.trim_start());
+    } else {
+        out.push_str(text);
+    }
+}
+
+/// Separate the next block from the previous one by a blank line.
+fn start_block(out: &mut String) {
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push_str(if out.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                result.push(' ');
+            }
+            in_space = true;
+        } else {
+            result.push(c);
+            in_space = false;
+        }
+    }
+    result
+}
+
+/// Trim trailing spaces and collapse runs of blank lines into one.
+fn normalize_blank_lines(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut blank = false;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank = !result.is_empty();
+            continue;
+        }
+        if blank {
+            result.push('\n');
+            blank = false;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Fallback title</title>
+  <meta property="og:title" content="Tips &amp; Tricks">
+  <script>var x = "<p>not content</p>";</script>
+  <style>p { color: red; }</style>
+</head>
+<body>
+  <nav><a href="/">Home</a></nav>
+  <article>
+    <header><h1>Tips &amp; Tricks</h1></header>
+    <p>Some <strong>bold</strong> and <em>italic</em> text with a
+       <a href="https://example.com/more">link</a>.<!-- comment -->
+    <p>Second paragraph<br>with a break.</p>
+    <h2>Steps</h2>
+    <ol>
+      <li>First
+      <li>Second
+        <ul><li>Nested</li></ul>
+      </li>
+    </ol>
+    <img src="/images/diagram.png" alt="A [big] diagram">
+    <pre><code>fn main() {
+    println!("hi");
+}</code></pre>
+    <blockquote><p>Quoted</p></blockquote>
+  </article>
+  <footer>Copyright</footer>
+</body>
+</html>"#;
+
+    #[test]
+    fn test_extract_article() {
+        let page = extract_article(PAGE);
+
+        assert_eq!(page.title.as_deref(), Some("Tips & Tricks"));
+        assert_eq!(
+            page.markdown,
+            "Some **bold** and *italic* text with a [link](https://example.com/more).\n\
+             \n\
+             Second paragraph\n\
+             with a break.\n\
+             \n\
+             ## Steps\n\
+             \n\
+             1. First\n\
+             2. Second\n\
+             \u{20}  - Nested\n\
+             \n\
+             ![A big diagram](/images/diagram.png)\n\
+             \n\
+             ```\n\
+             fn main() {\n\
+             \u{20}   println!(\"hi\");\n\
+             }\n\
+             ```\n\
+             \n\
+             > Quoted\n"
+        );
+        assert_eq!(
+            page.images,
+            vec![ClippedImage {
+                alt: "A big diagram".to_string(),
+                src: "/images/diagram.png".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_body_and_title() {
+        let page = extract_article("<html><head><title> Plain\n page </title></head><body><p>Just text</p></body></html>");
+        assert_eq!(page.title.as_deref(), Some("Plain page"));
+        assert_eq!(page.markdown, "Just text\n");
+    }
+
+    #[test]
+    fn test_attributes_and_entities() {
+        let page = extract_article(
+            r#"<body><p>a &amp; b &lt;c&gt; &#39;d&#x27; &unknown; &<a href='x>y' data-id=5 hidden>t</a></p></body>"#,
+        );
+        assert_eq!(page.markdown, "a & b <c> 'd' &unknown; &[t](x>y)\n");
+    }
+}
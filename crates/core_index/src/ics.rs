@@ -0,0 +1,344 @@
+//! iCalendar (RFC 5545) parsing for calendar subscriptions.
+//!
+//! Extracts VEVENT components from an ICS feed. Only the properties needed
+//! to show events on the calendar are read; everything else is ignored.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// An event parsed from an ICS feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedCalendarEvent {
+    pub uid: String,
+    pub summary: Option<String>,
+    pub location: Option<String>,
+    pub date: NaiveDate,
+    pub start_time: NaiveTime,
+    /// End time on `date`. Events running past midnight end at 23:59:59.
+    pub end_time: NaiveTime,
+    pub all_day: bool,
+    /// RRULE value (without the "RRULE:" prefix).
+    pub rrule: Option<String>,
+    /// TZID of the start time, "UTC" for UTC times, None for floating times.
+    pub timezone: Option<String>,
+}
+
+/// A date or date-time value from DTSTART/DTEND.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IcsTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+/// A content line split into name, parameters, and value.
+struct Property<'a> {
+    name: String,
+    params: Vec<(String, &'a str)>,
+    value: &'a str,
+}
+
+impl Property<'_> {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.trim_matches('"'))
+    }
+}
+
+/// Parse all events from ICS content.
+///
+/// Cancelled events and events without a valid DTSTART are skipped.
+pub fn parse_ics(content: &str) -> Vec<ParsedCalendarEvent> {
+    let lines = unfold_lines(content);
+
+    let mut events = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    let mut depth = 0;
+
+    for line in &lines {
+        let Some(prop) = parse_property(line) else {
+            continue;
+        };
+
+        match (prop.name.as_str(), prop.value.to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") if current.is_none() => {
+                current = Some(Vec::new());
+                depth = 0;
+            }
+            ("BEGIN", _) if current.is_some() => depth += 1,
+            ("END", "VEVENT") if depth == 0 => {
+                if let Some(event) = current.take().and_then(|props| build_event(&props)) {
+                    events.push(event);
+                }
+            }
+            ("END", _) if current.is_some() => depth -= 1,
+            _ => {
+                // Properties of nested components (e.g. VALARM) are ignored
+                if let Some(props) = current.as_mut() {
+                    if depth == 0 {
+                        props.push(prop);
+                    }
+                }
+            }
+        }
+    }
+
+    events
+}
+
+/// Join folded lines (continuations start with a space or tab).
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(rest) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+/// Split a content line into name, parameters, and value.
+fn parse_property(line: &str) -> Option<Property<'_>> {
+    // The value starts at the first colon outside a quoted parameter value
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v))
+        .collect();
+
+    Some(Property { name, params, value })
+}
+
+/// Build an event from the properties of a VEVENT.
+fn build_event(props: &[Property]) -> Option<ParsedCalendarEvent> {
+    let find = |name: &str| props.iter().find(|p| p.name == name);
+
+    if find("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED")) {
+        return None;
+    }
+
+    let dtstart = find("DTSTART")?;
+    let start = parse_ics_time(dtstart.value)?;
+    let timezone = match start {
+        IcsTime::Date(_) => None,
+        IcsTime::DateTime(_) if dtstart.value.ends_with(['Z', 'z']) => Some("UTC".to_string()),
+        IcsTime::DateTime(_) => dtstart.param("TZID").map(str::to_string),
+    };
+
+    let end = find("DTEND")
+        .and_then(|p| parse_ics_time(p.value))
+        .or_else(|| {
+            let duration = parse_duration(find("DURATION")?.value)?;
+            Some(match start {
+                IcsTime::Date(d) => IcsTime::Date(d + duration),
+                IcsTime::DateTime(dt) => IcsTime::DateTime(dt + duration),
+            })
+        });
+
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59)?;
+    let (date, start_time, end_time, all_day) = match start {
+        IcsTime::Date(date) => (date, NaiveTime::MIN, end_of_day, true),
+        IcsTime::DateTime(start) => {
+            let end = match end {
+                Some(IcsTime::DateTime(end)) if end >= start => end,
+                Some(IcsTime::Date(end)) if end > start.date() => end.and_time(NaiveTime::MIN),
+                // Events without an end (or with an invalid one) take no time
+                _ => start,
+            };
+            let end_time = if end.date() > start.date() {
+                end_of_day
+            } else {
+                end.time()
+            };
+            (start.date(), start.time(), end_time, false)
+        }
+    };
+
+    let text = |name: &str| {
+        find(name)
+            .map(|p| unescape_text(p.value))
+            .filter(|s| !s.trim().is_empty())
+    };
+
+    Some(ParsedCalendarEvent {
+        uid: find("UID").map(|p| p.value.trim().to_string()).unwrap_or_default(),
+        summary: text("SUMMARY"),
+        location: text("LOCATION"),
+        date,
+        start_time,
+        end_time,
+        all_day,
+        rrule: find("RRULE").map(|p| p.value.trim().to_string()),
+        timezone,
+    })
+}
+
+/// Parse a DATE (YYYYMMDD) or DATE-TIME (YYYYMMDDTHHMMSS[Z]) value.
+fn parse_ics_time(value: &str) -> Option<IcsTime> {
+    let value = value.trim().trim_end_matches(['Z', 'z']);
+    if value.contains('T') {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(IcsTime::DateTime)
+    } else {
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(IcsTime::Date)
+    }
+}
+
+/// Parse a positive RFC 5545 duration (e.g. "PT1H30M", "P1D", "P2W").
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().trim_start_matches('+').strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+
+    number.is_empty().then_some(total)
+}
+
+/// Unescape a TEXT value.
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveTime {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_timed_event() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:abc@example.com\r\n\
+SUMMARY:Team sync\\, weekly\r\n\
+LOCATION:Room 1\r\n\
+DTSTART;TZID=Europe/Berlin:20240115T093000\r\n\
+DTEND;TZID=Europe/Berlin:20240115T100000\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO\r\n\
+BEGIN:VALARM\r\n\
+DTSTART:20240101T000000\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let events = parse_ics(ics);
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.uid, "abc@example.com");
+        assert_eq!(event.summary.as_deref(), Some("Team sync, weekly"));
+        assert_eq!(event.location.as_deref(), Some("Room 1"));
+        assert_eq!(event.date, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        assert_eq!(event.start_time, time("09:30:00"));
+        assert_eq!(event.end_time, time("10:00:00"));
+        assert!(!event.all_day);
+        assert_eq!(event.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO"));
+        assert_eq!(event.timezone.as_deref(), Some("Europe/Berlin"));
+    }
+
+    #[test]
+    fn test_parse_utc_all_day_and_duration() {
+        let ics = "BEGIN:VEVENT\n\
+UID:1\n\
+SUMMARY:Long\n\x20 folded title\n\
+DTSTART:20240301T140000Z\n\
+DURATION:PT1H30M\n\
+END:VEVENT\n\
+BEGIN:VEVENT\n\
+UID:2\n\
+DTSTART;VALUE=DATE:20240302\n\
+DTEND;VALUE=DATE:20240303\n\
+END:VEVENT\n\
+BEGIN:VEVENT\n\
+UID:3\n\
+STATUS:CANCELLED\n\
+DTSTART:20240304T090000\n\
+END:VEVENT\n";
+
+        let events = parse_ics(ics);
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(events[0].summary.as_deref(), Some("Long folded title"));
+        assert_eq!(events[0].timezone.as_deref(), Some("UTC"));
+        assert_eq!(events[0].start_time, time("14:00:00"));
+        assert_eq!(events[0].end_time, time("15:30:00"));
+
+        assert!(events[1].all_day);
+        assert_eq!(events[1].timezone, None);
+        assert_eq!(events[1].start_time, NaiveTime::MIN);
+        assert_eq!(events[1].end_time, time("23:59:59"));
+    }
+
+    #[test]
+    fn test_event_past_midnight_ends_at_end_of_day() {
+        let ics = "BEGIN:VEVENT\nUID:x\nDTSTART:20240101T220000\nDTEND:20240102T010000\nEND:VEVENT\n";
+
+        let events = parse_ics(ics);
+        assert_eq!(events[0].timezone, None);
+        assert_eq!(events[0].start_time, time("22:00:00"));
+        assert_eq!(events[0].end_time, time("23:59:59"));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT45M"), Some(Duration::minutes(45)));
+        assert_eq!(parse_duration("P1DT2H"), Some(Duration::hours(26)));
+        assert_eq!(parse_duration("P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_duration("1H"), None);
+    }
+}
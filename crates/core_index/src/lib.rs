@@ -6,12 +6,48 @@
 //! - Wikilinks ([[link]])
 //! - Tags (#tag)
 //! - YAML frontmatter
+//! - Math (`$...$`, `$$...$$`)
+//! - Footnotes (`[^label]`)
+//!
+//! Private sections (`%%private%% ... %%end%%`) are blanked before analysis.
+//!
+//! It also renders notes to sanitized HTML, scores readability, lints
+//! notes, finds duplicate notes, splits notes into sections for embedding,
+//! spell checks text against Hunspell dictionaries, formats and edits
+//! tables, parses iCalendar feeds for calendar subscriptions, and converts
+//! web pages to markdown for the web clipper.
 
+pub mod chunking;
+pub mod duplicates;
+pub mod footnotes;
 pub mod frontmatter;
+pub mod html;
+pub mod ics;
+pub mod lint;
 pub mod markdown;
+pub mod math;
+pub mod readability;
+pub mod redaction;
+pub mod rendering;
+pub mod spelling;
+pub mod tables;
 
+pub use chunking::{chunk_note, Chunk, MAX_CHUNK_CHARS};
+pub use duplicates::{find_duplicates, fingerprint, signature_similarity, Fingerprint};
+pub use footnotes::{find_footnotes, renumber_footnotes, Footnote, Footnotes};
 pub use frontmatter::{
-    delete_frontmatter_property, parse_frontmatter, set_frontmatter_property, strip_frontmatter,
-    Frontmatter, PropertyValue,
+    add_frontmatter_alias, delete_frontmatter_property, parse_frontmatter, set_frontmatter_list, set_frontmatter_property,
+    strip_frontmatter, Frontmatter, PropertyValue,
 };
+pub use html::{extract_article, ClippedImage, ClippedPage};
+pub use ics::{parse_ics, ParsedCalendarEvent};
+pub use lint::{fix_content, lint_content, required_keys};
 pub use markdown::{NoteAnalysis, ParsedHeading, ParsedProperty, ParsedTodo};
+pub use math::{blank_math, find_math, tex_to_mathml, MathSpan};
+pub use readability::analyze_readability;
+pub use redaction::{has_private_sections, redact_private};
+pub use rendering::{collect_wikilinks, find_diagrams, render_html, render_html_with, WikiLink};
+pub use spelling::{check_text, decode_dictionary_file, Dictionary};
+pub use tables::{
+    find_tables, format_table, sort_table_by_column, table_add_column, table_add_row, table_at_line, Alignment, Table,
+};
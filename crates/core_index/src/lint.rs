@@ -0,0 +1,469 @@
+//! Note linting: configurable rules that report problems in note content.
+//!
+//! The rules work line by line on the raw content, so diagnostics point at
+//! the line to fix. Headings, links, and markers inside fenced code blocks
+//! and inline code are ignored. Whether a link target exists is decided by
+//! the caller, which passes the broken targets in, as are the registered
+//! task contexts.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use shared_types::{LintDiagnostic, LintRule, LintSettings, LintSeverity};
+use std::collections::{HashMap, HashSet};
+
+use crate::footnotes::find_footnotes;
+use crate::frontmatter::{parse_frontmatter, set_frontmatter_property};
+use crate::markdown::{slugify, CONTEXT_REGEX, WIKILINK_FULL_REGEX};
+
+/// ATX heading; group 1 is the level, group 2 the text (with any closing `#`s).
+static HEADING_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}(#{1,6})(?:[ \t]+(.*))?$").unwrap());
+
+static TODO_MARKER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(TODO|FIXME|XXX)\b").unwrap());
+
+/// A task list item (`- [ ]`, `* [x]`, `1. [ ]`).
+static TASK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+\[[ xX]\]\s").unwrap());
+
+static INLINE_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"`[^`]*`").unwrap());
+
+/// The severity of a rule, or None if it is disabled.
+pub fn rule_severity(settings: &LintSettings, rule: LintRule) -> Option<LintSeverity> {
+    match settings.rules.iter().find(|s| s.rule == rule) {
+        Some(setting) if !setting.enabled => None,
+        Some(setting) => Some(setting.severity.unwrap_or(rule.default_severity())),
+        None => Some(rule.default_severity()),
+    }
+}
+
+/// Properties required for a note at `path` by the folder rules.
+pub fn required_keys(settings: &LintSettings, path: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for required in &settings.required_properties {
+        let folder = required.folder.trim_matches('/');
+        let applies = folder.is_empty() || path.strip_prefix(folder).is_some_and(|rest| rest.starts_with('/'));
+        if applies {
+            for key in &required.keys {
+                if !keys.contains(key) {
+                    keys.push(key.clone());
+                }
+            }
+        }
+    }
+    keys
+}
+
+/// Lint note content.
+///
+/// `broken_targets` holds the wikilink targets (as written) that do not
+/// resolve; `required` the properties the note must have; `contexts` the
+/// registered task contexts (none means contexts aren't checked).
+pub fn lint_content(
+    content: &str,
+    settings: &LintSettings,
+    required: &[String],
+    broken_targets: &HashSet<String>,
+    contexts: &[String],
+) -> Vec<LintDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut report = |rule: LintRule, line: usize, message: String| {
+        if let Some(severity) = rule_severity(settings, rule) {
+            diagnostics.push(LintDiagnostic {
+                rule,
+                severity,
+                message,
+                line: line as u32 + 1,
+                fixable: rule.is_fixable(),
+            });
+        }
+    };
+
+    let lines = scan_lines(content);
+
+    for (i, line) in lines.iter().enumerate() {
+        if has_trailing_whitespace(&lines, i) {
+            report(LintRule::TrailingWhitespace, i, "Trailing whitespace".to_string());
+        }
+        if line.kind != LineKind::Text {
+            continue;
+        }
+
+        let text = INLINE_CODE_REGEX.replace_all(line.text, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+        for caps in WIKILINK_FULL_REGEX.captures_iter(&text) {
+            let target = caps[2].trim();
+            if !broken_targets.contains(target) {
+                continue;
+            }
+            if &caps[1] == "!" {
+                report(LintRule::BrokenEmbed, i, format!("Embedded note or file \"{}\" does not exist", target));
+            } else {
+                report(LintRule::BrokenLink, i, format!("Linked note \"{}\" does not exist", target));
+            }
+        }
+        if let Some(caps) = TODO_MARKER_REGEX.captures(&text) {
+            report(LintRule::TodoMarker, i, format!("{} marker", &caps[1]));
+        }
+        if !contexts.is_empty() && TASK_REGEX.is_match(&text) {
+            // The first @context is the task's, as in the index
+            if let Some(caps) = CONTEXT_REGEX.captures(&text) {
+                if let Some(message) = unknown_context(&caps[1], contexts) {
+                    report(LintRule::UnknownContext, i, message);
+                }
+            }
+        }
+    }
+
+    let headings = headings(&lines);
+    let mut first_by_slug: HashMap<String, usize> = HashMap::new();
+    for (k, heading) in headings.iter().enumerate() {
+        if heading.text.is_empty() {
+            report(LintRule::EmptyHeading, heading.line, "Heading has no text".to_string());
+            continue;
+        }
+
+        match first_by_slug.get(&slugify(heading.text)) {
+            Some(&first) => report(
+                LintRule::DuplicateHeading,
+                heading.line,
+                format!("Duplicate heading \"{}\" (first on line {})", heading.text, first + 1),
+            ),
+            None => {
+                first_by_slug.insert(slugify(heading.text), heading.line);
+            }
+        }
+
+        // Empty unless the next heading is a subsection
+        let next = headings.get(k + 1);
+        let end = next.map(|h| h.line).unwrap_or(lines.len());
+        let is_blank = lines[heading.line + 1..end].iter().all(|l| l.text.trim().is_empty());
+        if is_blank && next.is_none_or(|h| h.level <= heading.level) {
+            report(LintRule::EmptySection, heading.line, format!("Section \"{}\" is empty", heading.text));
+        }
+    }
+
+    let footnotes = find_footnotes(content);
+    for reference in footnotes.undefined() {
+        report(
+            LintRule::UndefinedFootnote,
+            reference.line_number - 1,
+            format!("Footnote \"{}\" is not defined", reference.label),
+        );
+    }
+    for definition in footnotes.orphaned() {
+        report(
+            LintRule::OrphanedFootnote,
+            definition.line_number - 1,
+            format!("Footnote \"{}\" is never referenced", definition.label),
+        );
+    }
+
+    for key in missing_properties(content, required) {
+        report(LintRule::MissingProperty, 0, format!("Missing required property \"{}\"", key));
+    }
+
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+/// The message for a context that isn't registered, suggesting the closest
+/// registered one; None if it is registered (ignoring case).
+fn unknown_context(context: &str, contexts: &[String]) -> Option<String> {
+    let lower = context.to_lowercase();
+    if contexts.iter().any(|c| c.to_lowercase() == lower) {
+        return None;
+    }
+
+    let closest = contexts
+        .iter()
+        .map(|c| (edit_distance(&lower, &c.to_lowercase()), c))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance);
+    Some(match closest {
+        Some((_, suggestion)) => format!("Unknown context \"@{}\" (did you mean \"@{}\"?)", context, suggestion),
+        None => format!("Unknown context \"@{}\"", context),
+    })
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Apply the fixes of the enabled fixable rules.
+///
+/// Returns the new content and the number of fixes applied.
+pub fn fix_content(content: &str, settings: &LintSettings, required: &[String]) -> (String, u32) {
+    let fix_whitespace = rule_severity(settings, LintRule::TrailingWhitespace).is_some();
+    let fix_headings = rule_severity(settings, LintRule::EmptyHeading).is_some();
+    let fix_properties = rule_severity(settings, LintRule::MissingProperty).is_some();
+
+    let lines = scan_lines(content);
+    let empty_headings: HashSet<usize> = headings(&lines)
+        .iter()
+        .filter(|h| h.text.is_empty())
+        .map(|h| h.line)
+        .collect();
+
+    let mut fixes = 0;
+    let mut fixed = String::with_capacity(content.len());
+    for (i, line) in lines.iter().enumerate() {
+        if fix_headings && empty_headings.contains(&i) {
+            fixes += 1;
+            continue;
+        }
+        if fix_whitespace && has_trailing_whitespace(&lines, i) {
+            fixed.push_str(line.text.trim_end_matches([' ', '\t']));
+            fixes += 1;
+        } else {
+            fixed.push_str(line.text);
+        }
+        fixed.push_str(line.line_ending);
+    }
+
+    if fix_properties {
+        for key in missing_properties(&fixed, required) {
+            fixed = set_frontmatter_property(&fixed, &key, None, None);
+            fixes += 1;
+        }
+    }
+
+    (fixed, fixes)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Frontmatter,
+    Code,
+    Text,
+}
+
+struct Line<'a> {
+    /// The line without its line ending.
+    text: &'a str,
+    line_ending: &'a str,
+    kind: LineKind,
+}
+
+struct Heading<'a> {
+    /// 0-based line index.
+    line: usize,
+    level: usize,
+    text: &'a str,
+}
+
+/// Split content into lines and classify them (fence lines count as code).
+fn scan_lines(content: &str) -> Vec<Line<'_>> {
+    let (frontmatter, _) = parse_frontmatter(content);
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+    for raw in content.split_inclusive('\n') {
+        let start = offset;
+        offset += raw.len();
+
+        let text = raw.trim_end_matches(['\n', '\r']);
+        let line_ending = &raw[text.len()..];
+
+        let kind = if start < frontmatter.content_start {
+            LineKind::Frontmatter
+        } else {
+            let trimmed = text.trim_start();
+            let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+            match (fence, marker) {
+                (None, Some(marker)) => {
+                    fence = Some(marker);
+                    LineKind::Code
+                }
+                (Some(open), Some(marker)) if open == marker && trimmed.trim_start_matches(marker).trim().is_empty() => {
+                    fence = None;
+                    LineKind::Code
+                }
+                (Some(_), _) => LineKind::Code,
+                (None, None) => LineKind::Text,
+            }
+        };
+
+        lines.push(Line { text, line_ending, kind });
+    }
+    lines
+}
+
+fn headings<'a>(lines: &[Line<'a>]) -> Vec<Heading<'a>> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.kind == LineKind::Text)
+        .filter_map(|(i, line)| {
+            let caps = HEADING_REGEX.captures(line.text)?;
+            let text = caps.get(2).map(|m| m.as_str()).unwrap_or("").trim();
+            // A closing sequence of `#`s is not part of the text
+            let text = match text.trim_end_matches('#') {
+                rest if rest.is_empty() || rest.ends_with([' ', '\t']) => rest.trim_end(),
+                _ => text,
+            };
+            Some(Heading {
+                line: i,
+                level: caps[1].len(),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// Trailing spaces or tabs, except two or more spaces before another line
+/// of text (a markdown line break).
+fn has_trailing_whitespace(lines: &[Line], i: usize) -> bool {
+    let text = lines[i].text;
+    let trimmed = text.trim_end_matches([' ', '\t']);
+    if trimmed.len() == text.len() {
+        return false;
+    }
+
+    let trailing = &text[trimmed.len()..];
+    let is_line_break = lines[i].kind == LineKind::Text
+        && trailing.len() >= 2
+        && !trailing.contains('\t')
+        && !trimmed.trim().is_empty()
+        && lines.get(i + 1).is_some_and(|next| !next.text.trim().is_empty());
+    !is_line_break
+}
+
+fn missing_properties(content: &str, required: &[String]) -> Vec<String> {
+    let (frontmatter, _) = parse_frontmatter(content);
+    required
+        .iter()
+        .filter(|key| {
+            let present = frontmatter.properties.contains_key(key.as_str())
+                || (key.as_str() == "tags" && !frontmatter.tags.is_empty())
+                || (key.as_str() == "aliases" && !frontmatter.aliases.is_empty());
+            !present
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::{LintRuleSetting, RequiredProperties};
+
+    const NOTE: &str = "---\ntitle: Plan\n---\n# Plan\n\nSee [[Missing]] and ![[gone.png]].  \nNext line TODO: check `TODO` in code\n\n## Ideas\n\n##\n\n## Ideas\nSome text \n\n```\n# not a heading [[Missing]]\n```\n";
+
+    fn rules(diagnostics: &[LintDiagnostic]) -> Vec<(LintRule, u32)> {
+        diagnostics.iter().map(|d| (d.rule, d.line)).collect()
+    }
+
+    #[test]
+    fn test_lint_content() {
+        let broken: HashSet<String> = ["Missing".to_string(), "gone.png".to_string()].into();
+        let diagnostics = lint_content(NOTE, &LintSettings::default(), &["status".to_string()], &broken, &[]);
+
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                (LintRule::MissingProperty, 1),
+                (LintRule::BrokenLink, 6),
+                (LintRule::BrokenEmbed, 6),
+                (LintRule::TodoMarker, 7),
+                (LintRule::EmptySection, 9),
+                (LintRule::EmptyHeading, 11),
+                (LintRule::DuplicateHeading, 13),
+                (LintRule::TrailingWhitespace, 14),
+            ]
+        );
+        assert_eq!(diagnostics[0].severity, LintSeverity::Warning);
+        assert!(diagnostics[0].fixable);
+        assert_eq!(diagnostics[6].message, "Duplicate heading \"Ideas\" (first on line 9)");
+    }
+
+    #[test]
+    fn test_lint_footnotes() {
+        let content = "# Notes\n\nClaim[^1] and[^2].\n\n```\n[^3]\n```\n\n[^1]: Source.\n[^old]: Unused.\n";
+        let diagnostics = lint_content(content, &LintSettings::default(), &[], &HashSet::new(), &[]);
+
+        assert_eq!(
+            rules(&diagnostics),
+            vec![(LintRule::UndefinedFootnote, 3), (LintRule::OrphanedFootnote, 10)]
+        );
+        assert_eq!(diagnostics[0].message, "Footnote \"2\" is not defined");
+        assert_eq!(diagnostics[1].severity, LintSeverity::Info);
+    }
+
+    #[test]
+    fn test_lint_unknown_contexts() {
+        let content = "- [ ] Call mom @phnoe\n- [x] Shop @Errands\n* [ ] Read @garden\nEmail me@example.com\n`- [ ] x @nope`\n";
+        let contexts = ["phone".to_string(), "errands".to_string()];
+        let diagnostics = lint_content(content, &LintSettings::default(), &[], &HashSet::new(), &contexts);
+
+        assert_eq!(
+            rules(&diagnostics),
+            vec![(LintRule::UnknownContext, 1), (LintRule::UnknownContext, 3)]
+        );
+        assert_eq!(diagnostics[0].message, "Unknown context \"@phnoe\" (did you mean \"@phone\"?)");
+        assert_eq!(diagnostics[1].message, "Unknown context \"@garden\"");
+
+        // Without registered contexts nothing is checked
+        assert!(lint_content(content, &LintSettings::default(), &[], &HashSet::new(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_lint_content_respects_settings() {
+        let settings = LintSettings {
+            rules: vec![
+                LintRuleSetting { rule: LintRule::TodoMarker, enabled: false, severity: None },
+                LintRuleSetting { rule: LintRule::EmptySection, enabled: true, severity: Some(LintSeverity::Error) },
+            ],
+            required_properties: Vec::new(),
+        };
+        let diagnostics = lint_content(NOTE, &settings, &[], &HashSet::new(), &[]);
+
+        assert!(!diagnostics.iter().any(|d| d.rule == LintRule::TodoMarker));
+        let empty_section = diagnostics.iter().find(|d| d.rule == LintRule::EmptySection).unwrap();
+        assert_eq!(empty_section.severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_fix_content() {
+        let (fixed, fixes) = fix_content(NOTE, &LintSettings::default(), &["status".to_string()]);
+
+        assert_eq!(fixes, 3);
+        assert!(fixed.contains("status:"));
+        assert!(fixed.contains("![[gone.png]].  \nNext line"));
+        assert!(fixed.contains("## Ideas\nSome text\n"));
+        assert!(!fixed.contains("\n##\n"));
+
+        let diagnostics = lint_content(&fixed, &LintSettings::default(), &["status".to_string()], &HashSet::new(), &[]);
+        assert!(diagnostics.iter().all(|d| !d.fixable));
+    }
+
+    #[test]
+    fn test_required_keys() {
+        let settings = LintSettings {
+            rules: Vec::new(),
+            required_properties: vec![
+                RequiredProperties { folder: String::new(), keys: vec!["created".into()] },
+                RequiredProperties { folder: "Projects/".into(), keys: vec!["status".into(), "created".into()] },
+            ],
+        };
+        assert_eq!(required_keys(&settings, "Projects/Site/plan.md"), vec!["created", "status"]);
+        assert_eq!(required_keys(&settings, "ProjectsOld/a.md"), vec!["created"]);
+    }
+
+    #[test]
+    fn test_headings() {
+        let lines = scan_lines("# Title #\n#tag\n### C# notes\n####### too deep\n");
+        let headings = headings(&lines);
+        assert_eq!(headings.len(), 2);
+        assert_eq!(headings[0].text, "Title");
+        assert_eq!(headings[1].text, "C# notes");
+    }
+}
@@ -3,9 +3,13 @@
 use once_cell::sync::Lazy;
 use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use regex::Regex;
+use shared_types::WeekStart;
 use tracing::{debug, instrument};
 
 use crate::frontmatter::{parse_frontmatter, PropertyValue};
+use crate::footnotes::{find_footnotes, Footnotes};
+use crate::math::{blank_math, find_math, MathSpan};
+use crate::redaction::redact_private;
 
 /// Regex for matching [[wikilinks]].
 /// Matches [[link]], [[link|display text]], [[link#section]], [[link#section|display]]
@@ -15,9 +19,14 @@ static WIKILINK_REGEX: Lazy<Regex> =
 
 /// Regex for matching wikilinks with section anchors.
 /// Captures: 1=target, 2=section (optional), 3=display (optional)
-static WIKILINK_FULL_REGEX: Lazy<Regex> =
+pub(crate) static WIKILINK_FULL_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(!?)\[\[([^\]#|]+)(?:#([^\]|]+))?(?:\|([^\]]+))?\]\]").unwrap());
 
+/// Regex for matching inline markdown links and images.
+/// Captures: 1=`[text](` or `![alt](`, 2=destination, 3=optional title and `)`
+static MARKDOWN_LINK_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(!?\[[^\]]*\]\()([^)\s]+)((?:\s+"[^"]*")?\))"#).unwrap());
+
 /// Regex for matching #tags.
 /// Matches #tag but not ## headings or # in URLs
 /// Tags must start with a letter and can contain letters, numbers, underscores, hyphens, and slashes
@@ -26,7 +35,7 @@ static TAG_REGEX: Lazy<Regex> =
 
 /// Regex for matching @context annotations in tasks.
 /// Matches @word (e.g., @home, @work, @phone, @computer, @errands)
-static CONTEXT_REGEX: Lazy<Regex> =
+pub(crate) static CONTEXT_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"@([a-zA-Z][a-zA-Z0-9_\-]*)").unwrap());
 
 /// Regex for matching !priority annotations in tasks.
@@ -36,9 +45,16 @@ static PRIORITY_REGEX: Lazy<Regex> =
 
 /// Regex for matching ^due-date annotations in tasks.
 /// Matches ^YYYY-MM-DD or relative dates like ^today, ^tomorrow, ^monday, ^next-week
+/// (the first day of next week)
 static DUE_DATE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\^(\d{4}-\d{2}-\d{2}|today|tomorrow|monday|tuesday|wednesday|thursday|friday|saturday|sunday|next-week)").unwrap());
 
+/// Regex for matching est:duration annotations in tasks.
+/// Matches est:30m, est:1h, est:1h30m, or est:45 (minutes); a bare `est:`
+/// is left as text.
+static ESTIMATE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\best:(?:(\d+)h(?:(\d+)m?)?|(\d+)m?)\b").unwrap());
+
 /// A parsed property from frontmatter.
 #[derive(Debug, Clone)]
 pub struct ParsedProperty {
@@ -48,6 +64,8 @@ pub struct ParsedProperty {
     pub value: Option<String>,
     /// Inferred property type.
     pub property_type: String,
+    /// Items of a list property (empty for other types).
+    pub items: Vec<String>,
 }
 
 /// Result of analyzing a markdown note.
@@ -68,8 +86,33 @@ pub struct NoteAnalysis {
     /// All wikilinks found (target note names).
     pub links: Vec<String>,
 
+    /// Targets of the embeds (`![[...]]`) among the wikilinks.
+    pub embeds: Vec<String>,
+
     /// Properties from YAML frontmatter.
     pub properties: Vec<ParsedProperty>,
+
+    /// Aliases from YAML frontmatter (`aliases` or `alias`).
+    pub aliases: Vec<String>,
+
+    /// Number of words in the body (frontmatter and private sections excluded).
+    pub word_count: usize,
+
+    /// True if the frontmatter has `encrypted: true`. The body of such notes
+    /// is not analyzed and must not be indexed for search.
+    pub encrypted: bool,
+
+    /// Math spans (`$...$`, `$$...$$`) in the body.
+    pub math: Vec<MathSpan>,
+
+    /// Footnote references and definitions in the body, with the line
+    /// numbers and byte ranges of the whole note.
+    pub footnotes: Footnotes,
+
+    /// True if the note is over the vault's size limit, so only its start
+    /// is indexed for search and it is never embedded. Set by the indexer,
+    /// not the parser.
+    pub truncated: bool,
 }
 
 /// A heading in the document.
@@ -120,13 +163,26 @@ pub struct ParsedTodo {
 
     /// Due date as YYYY-MM-DD string.
     pub due_date: Option<String>,
+
+    /// Estimated effort in minutes (from `est:30m`, `est:1h`, ...).
+    pub estimate_minutes: Option<i32>,
 }
 
 /// Parse a markdown document and extract structured data.
-#[instrument(skip(content))]
 pub fn parse(content: &str) -> NoteAnalysis {
+    parse_with(content, WeekStart::default())
+}
+
+/// Parse a markdown document, resolving `^next-week` due dates to the
+/// next week starting on `week_start`.
+#[instrument(skip(content))]
+pub fn parse_with(content: &str, week_start: WeekStart) -> NoteAnalysis {
     let mut analysis = NoteAnalysis::default();
 
+    // Private sections never reach the index
+    let redacted = redact_private(content);
+    let content = redacted.as_ref();
+
     // Parse frontmatter first
     let (frontmatter, body) = parse_frontmatter(content);
 
@@ -164,6 +220,10 @@ pub fn parse(content: &str) -> NoteAnalysis {
             key: key.clone(),
             value: string_value,
             property_type: detected_type.to_string(),
+            items: match value {
+                PropertyValue::List(items) => items.clone(),
+                _ => Vec::new(),
+            },
         });
     }
 
@@ -173,10 +233,18 @@ pub fn parse(content: &str) -> NoteAnalysis {
             analysis.tags.push(tag.clone());
         }
     }
+    analysis.aliases = frontmatter.aliases.clone();
+
+    // The body of an encrypted note is ciphertext (or not yet encrypted), so skip it
+    if matches!(frontmatter.properties.get("encrypted"), Some(PropertyValue::Bool(true))) {
+        analysis.encrypted = true;
+        return analysis;
+    }
 
     // Use body content for further parsing (after frontmatter)
     let content_to_parse = if frontmatter.content_start > 0 { body } else { content };
     let content_len = content_to_parse.len();
+    analysis.word_count = count_words(content_to_parse);
 
     // Track line numbers
     let line_offsets = compute_line_offsets(content_to_parse);
@@ -255,29 +323,29 @@ pub fn parse(content: &str) -> NoteAnalysis {
 
             Event::Start(Tag::Item) => {}
 
-            Event::End(TagEnd::Item) => {
-                if in_task_item {
-                    let raw_text = task_text.trim().to_string();
-                    let line_number = offset_to_line(&line_offsets, current_offset);
-                    let heading_path = build_heading_path(&heading_stack);
-
-                    // Extract GTD annotations
-                    let (description, context, priority, due_date) = parse_todo_annotations(&raw_text);
-
-                    analysis.todos.push(ParsedTodo {
-                        description,
-                        raw_text,
-                        completed: task_completed,
-                        line_number,
-                        heading_path,
-                        context,
-                        priority,
-                        due_date,
-                    });
-
-                    in_task_item = false;
-                    task_text.clear();
-                }
+            Event::End(TagEnd::Item) if in_task_item => {
+                let raw_text = task_text.trim().to_string();
+                let line_number = offset_to_line(&line_offsets, current_offset);
+                let heading_path = build_heading_path(&heading_stack);
+
+                // Extract GTD annotations
+                let (description, context, priority, due_date, estimate_minutes) =
+                    parse_todo_annotations(&raw_text, week_start);
+
+                analysis.todos.push(ParsedTodo {
+                    description,
+                    raw_text,
+                    completed: task_completed,
+                    line_number,
+                    heading_path,
+                    context,
+                    priority,
+                    due_date,
+                    estimate_minutes,
+                });
+
+                in_task_item = false;
+                task_text.clear();
             }
 
             Event::TaskListMarker(completed) => {
@@ -333,10 +401,24 @@ pub fn parse(content: &str) -> NoteAnalysis {
         });
     }
 
-    // Extract wikilinks and tags using regex (from body, not frontmatter)
-    analysis.links = extract_wikilinks(content_to_parse);
+    // Counted from the start of the note, not of the body
+    let body_start = content.len() - content_to_parse.len();
+    let body_line = content[..body_start].matches('\n').count();
+    analysis.footnotes = find_footnotes(content_to_parse);
+    let footnotes = &mut analysis.footnotes;
+    for footnote in footnotes.definitions.iter_mut().chain(footnotes.references.iter_mut()) {
+        footnote.line_number += body_line;
+        footnote.range = footnote.range.start + body_start..footnote.range.end + body_start;
+    }
+
+    // Extract wikilinks and tags using regex (from body, not frontmatter),
+    // ignoring math so `\#` or `[[` in a formula isn't taken for one
+    analysis.math = find_math(content_to_parse);
+    let without_math = blank_math(content_to_parse);
+    analysis.links = extract_wikilinks(&without_math);
+    analysis.embeds = extract_embeds(&without_math);
     // Merge inline tags with frontmatter tags
-    let inline_tags = extract_tags(content_to_parse);
+    let inline_tags = extract_tags(&without_math);
     for tag in inline_tags {
         if !analysis.tags.contains(&tag) {
             analysis.tags.push(tag);
@@ -362,6 +444,15 @@ fn extract_wikilinks(content: &str) -> Vec<String> {
         .collect()
 }
 
+/// Extract the targets of embedded wikilinks from content.
+fn extract_embeds(content: &str) -> Vec<String> {
+    WIKILINK_FULL_REGEX
+        .captures_iter(content)
+        .filter(|cap| &cap[1] == "!")
+        .map(|cap| cap[2].to_string())
+        .collect()
+}
+
 /// Extract tags from content.
 fn extract_tags(content: &str) -> Vec<String> {
     let mut tags: Vec<String> = TAG_REGEX
@@ -378,9 +469,12 @@ fn extract_tags(content: &str) -> Vec<String> {
 
 /// Parse GTD annotations from a todo text.
 ///
-/// Extracts @context, !priority, and ^due-date from the text.
-/// Returns (clean_description, context, priority, due_date).
-fn parse_todo_annotations(text: &str) -> (String, Option<String>, Option<String>, Option<String>) {
+/// Extracts @context, !priority, ^due-date, and est:duration from the text.
+/// Returns (clean_description, context, priority, due_date, estimate_minutes).
+fn parse_todo_annotations(
+    text: &str,
+    week_start: WeekStart,
+) -> (String, Option<String>, Option<String>, Option<String>, Option<i32>) {
     // Extract context (@word)
     let context = CONTEXT_REGEX
         .captures(text)
@@ -405,24 +499,28 @@ fn parse_todo_annotations(text: &str) -> (String, Option<String>, Option<String>
         .map(|cap| {
             let date_str = &cap[1];
             // Convert relative dates to absolute
-            resolve_relative_date(date_str)
+            resolve_relative_date(date_str, week_start)
         });
 
+    // Extract effort estimate (est:30m, est:1h30m)
+    let estimate_minutes = parse_estimate_minutes(text);
+
     // Create clean description by removing annotations
     let clean = CONTEXT_REGEX.replace_all(text, "");
     let clean = PRIORITY_REGEX.replace_all(&clean, "");
     let clean = DUE_DATE_REGEX.replace_all(&clean, "");
+    let clean = ESTIMATE_REGEX.replace_all(&clean, "");
     // Clean up extra whitespace
     let description = clean
         .split_whitespace()
         .collect::<Vec<_>>()
         .join(" ");
 
-    (description, context, priority, due_date)
+    (description, context, priority, due_date, estimate_minutes)
 }
 
 /// Resolve relative date strings to YYYY-MM-DD format.
-fn resolve_relative_date(date_str: &str) -> String {
+fn resolve_relative_date(date_str: &str, week_start: WeekStart) -> String {
     use chrono::{Datelike, Local, Weekday};
 
     let today = Local::now().date_naive();
@@ -430,7 +528,9 @@ fn resolve_relative_date(date_str: &str) -> String {
     match date_str.to_lowercase().as_str() {
         "today" => today.format("%Y-%m-%d").to_string(),
         "tomorrow" => (today + chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
-        "next-week" => (today + chrono::Duration::days(7)).format("%Y-%m-%d").to_string(),
+        "next-week" => (week_start.start_of_week(today) + chrono::Duration::days(7))
+            .format("%Y-%m-%d")
+            .to_string(),
         // Handle day names (find next occurrence)
         day_name => {
             let target_weekday = match day_name {
@@ -500,6 +600,18 @@ fn heading_level_to_u8(level: HeadingLevel) -> u8 {
     }
 }
 
+/// Parse an effort estimate (`est:30m`, `est:1h30m`, `est:45`) from task text.
+///
+/// Returns the estimate in minutes, or None if no (non-zero) estimate is present.
+pub fn parse_estimate_minutes(text: &str) -> Option<i32> {
+    ESTIMATE_REGEX.captures_iter(text).find_map(|cap| {
+        let hours: i32 = cap.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        let minutes: i32 = cap.get(2).or(cap.get(3)).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        let total = hours * 60 + minutes;
+        (total > 0).then_some(total)
+    })
+}
+
 /// Generate a URL-safe slug from heading text.
 ///
 /// Converts "My Heading Text" to "my-heading-text".
@@ -620,6 +732,41 @@ pub fn update_wiki_links(content: &str, old_name: &str, new_name: &str) -> Strin
     }).to_string()
 }
 
+/// Rewrite wiki link targets with `retarget`, which gets the trimmed target
+/// and returns a new one for links that should change. The embed prefix,
+/// section and display text are kept.
+pub fn map_wiki_links(content: &str, retarget: impl Fn(&str) -> Option<String>) -> String {
+    WIKILINK_FULL_REGEX
+        .replace_all(content, |caps: &regex::Captures| {
+            let Some(target) = retarget(caps[2].trim()) else {
+                return caps[0].to_string();
+            };
+            let mut result = format!("{}[[{}", &caps[1], target);
+            if let Some(section) = caps.get(3) {
+                result.push('#');
+                result.push_str(section.as_str());
+            }
+            if let Some(display) = caps.get(4) {
+                result.push('|');
+                result.push_str(display.as_str());
+            }
+            result.push_str("]]");
+            result
+        })
+        .to_string()
+}
+
+/// Rewrite inline markdown link and image destinations with `retarget`,
+/// which returns a new destination for links that should change.
+pub fn map_markdown_links(content: &str, retarget: impl Fn(&str) -> Option<String>) -> String {
+    MARKDOWN_LINK_REGEX
+        .replace_all(content, |caps: &regex::Captures| match retarget(&caps[2]) {
+            Some(destination) => format!("{}{}{}", &caps[1], destination, &caps[3]),
+            None => caps[0].to_string(),
+        })
+        .to_string()
+}
+
 /// Toggle a todo's completion status and return the modified content.
 ///
 /// This function finds the todo at the given line and toggles its checkbox.
@@ -658,10 +805,29 @@ pub fn toggle_todo(content: &str, line_number: usize, completed: bool) -> String
     output
 }
 
+/// Count words, ignoring markdown markers like `#`, `-`, `>` or `[x]` on their own.
+fn count_words(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|w| !matches!(*w, "[x]" | "[X]"))
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+        .count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_ignores_links_and_tags_in_math() {
+        let content = "# Physics #notes\n\n$$\n\\left[[a, b]\\right] \\#1\n$$\n\nSee [[Waves]] and $[[x]] \\bmod #p$.\n";
+        let analysis = parse(content);
+        assert_eq!(analysis.links, vec!["Waves"]);
+        assert_eq!(analysis.tags, vec!["notes"]);
+        assert_eq!(analysis.math.len(), 2);
+        assert!(analysis.math[0].display);
+        assert_eq!(analysis.math[1].tex, "[[x]] \\bmod #p");
+    }
+
     #[test]
     fn test_parse_headings() {
         let content = "# Title\n\nSome text\n\n## Section 1\n\n### Subsection\n";
@@ -694,6 +860,27 @@ mod tests {
         assert_eq!(analysis.links.len(), 2);
         assert_eq!(analysis.links[0], "other note");
         assert_eq!(analysis.links[1], "project/sub");
+        assert!(analysis.embeds.is_empty());
+    }
+
+    #[test]
+    fn test_parse_embeds() {
+        let content = "See [[Plain]], ![[Design]] and ![[Research#Open|open questions]].\n";
+        let analysis = parse(content);
+
+        assert_eq!(analysis.links, ["Plain", "Design", "Research"]);
+        assert_eq!(analysis.embeds, ["Design", "Research"]);
+    }
+
+    #[test]
+    fn test_parse_footnotes_after_frontmatter() {
+        let content = "---\ntitle: Notes\n---\n\nA claim[^1].\n\n[^1]: Source.\n";
+        let analysis = parse(content);
+
+        let reference = &analysis.footnotes.references[0];
+        assert_eq!(reference.line_number, 5);
+        assert_eq!(&content[reference.range.clone()], "[^1]");
+        assert_eq!(analysis.footnotes.definitions[0].line_number, 7);
     }
 
     #[test]
@@ -809,6 +996,27 @@ mod tests {
         assert_eq!(updated, "See [[new note]] and [[new note#section]] and [[other]].");
     }
 
+    #[test]
+    fn test_map_wiki_links() {
+        let content = "[[a/Plan#Goals|goals]], ![[ Plan ]] and [[Other]]";
+        let updated = map_wiki_links(content, |target| {
+            target.ends_with("Plan").then(|| "b/Plan".to_string())
+        });
+        assert_eq!(updated, "[[b/Plan#Goals|goals]], ![[b/Plan]] and [[Other]]");
+    }
+
+    #[test]
+    fn test_map_markdown_links() {
+        let content = "See [the plan](../plan.md \"Plan\"), ![](img/a.png) and [site](https://example.com).";
+        let updated = map_markdown_links(content, |dest| {
+            (!dest.contains("://")).then(|| format!("x/{}", dest))
+        });
+        assert_eq!(
+            updated,
+            "See [the plan](x/../plan.md \"Plan\"), ![](x/img/a.png) and [site](https://example.com)."
+        );
+    }
+
     #[test]
     fn test_wikilinks_with_sections() {
         let content = "Link to [[note#section]] and ![[embed#heading]].\n";
@@ -822,35 +1030,54 @@ mod tests {
     #[test]
     fn test_parse_todo_annotations() {
         // Test with all annotations
-        let (desc, ctx, pri, due) = parse_todo_annotations("Call mom @phone !high ^2024-12-15");
+        let (desc, ctx, pri, due, est) = parse_todo_annotations("Call mom @phone !high ^2024-12-15 est:20m", WeekStart::Monday);
         assert_eq!(desc, "Call mom");
         assert_eq!(ctx, Some("phone".to_string()));
         assert_eq!(pri, Some("high".to_string()));
         assert_eq!(due, Some("2024-12-15".to_string()));
+        assert_eq!(est, Some(20));
 
         // Test shorthand priority
-        let (_, _, pri, _) = parse_todo_annotations("Task !h");
+        let (_, _, pri, _, _) = parse_todo_annotations("Task !h", WeekStart::Monday);
         assert_eq!(pri, Some("high".to_string()));
 
-        let (_, _, pri, _) = parse_todo_annotations("Task !m");
+        let (_, _, pri, _, _) = parse_todo_annotations("Task !m", WeekStart::Monday);
         assert_eq!(pri, Some("medium".to_string()));
 
-        let (_, _, pri, _) = parse_todo_annotations("Task !l");
+        let (_, _, pri, _, _) = parse_todo_annotations("Task !l", WeekStart::Monday);
         assert_eq!(pri, Some("low".to_string()));
 
         // Test context only
-        let (desc, ctx, pri, due) = parse_todo_annotations("Fix bug @computer");
+        let (desc, ctx, pri, due, _) = parse_todo_annotations("Fix bug @computer", WeekStart::Monday);
         assert_eq!(desc, "Fix bug");
         assert_eq!(ctx, Some("computer".to_string()));
         assert_eq!(pri, None);
         assert_eq!(due, None);
 
+        // A bare est: is part of the description
+        let (desc, _, _, _, est) = parse_todo_annotations("Ask about est:later", WeekStart::Monday);
+        assert_eq!(desc, "Ask about est:later");
+        assert_eq!(est, None);
+
         // Test no annotations
-        let (desc, ctx, pri, due) = parse_todo_annotations("Simple task");
+        let (desc, ctx, pri, due, est) = parse_todo_annotations("Simple task", WeekStart::Monday);
         assert_eq!(desc, "Simple task");
         assert_eq!(ctx, None);
         assert_eq!(pri, None);
         assert_eq!(due, None);
+        assert_eq!(est, None);
+    }
+
+    #[test]
+    fn test_parse_estimate_minutes() {
+        assert_eq!(parse_estimate_minutes("Write report est:30m"), Some(30));
+        assert_eq!(parse_estimate_minutes("Write report est:2h"), Some(120));
+        assert_eq!(parse_estimate_minutes("Write report est:1h30m @work"), Some(90));
+        assert_eq!(parse_estimate_minutes("est:45 Write report"), Some(45));
+        assert_eq!(parse_estimate_minutes("Write report"), None);
+        assert_eq!(parse_estimate_minutes("Write report est:"), None);
+        assert_eq!(parse_estimate_minutes("Write report est:soon"), None);
+        assert_eq!(parse_estimate_minutes("Write report best:30m"), None);
     }
 
     #[test]
@@ -882,20 +1109,25 @@ mod tests {
     #[test]
     fn test_relative_date_resolution() {
         // Test absolute date passes through
-        assert_eq!(resolve_relative_date("2024-12-15"), "2024-12-15");
+        assert_eq!(resolve_relative_date("2024-12-15", WeekStart::Monday), "2024-12-15");
 
         // Relative dates resolve to real dates (we can only test format)
-        let today = resolve_relative_date("today");
+        let today = resolve_relative_date("today", WeekStart::Monday);
         assert!(today.len() == 10); // YYYY-MM-DD format
         assert!(today.starts_with("20")); // Starts with year
 
-        let tomorrow = resolve_relative_date("tomorrow");
+        let tomorrow = resolve_relative_date("tomorrow", WeekStart::Monday);
         assert!(tomorrow.len() == 10);
 
-        let next_week = resolve_relative_date("next-week");
-        assert!(next_week.len() == 10);
+        // The first day of next week
+        use chrono::{Datelike, NaiveDate, Weekday};
+        let next_week = NaiveDate::parse_from_str(&resolve_relative_date("next-week", WeekStart::Monday), "%Y-%m-%d").unwrap();
+        assert_eq!(next_week.weekday(), Weekday::Mon);
+        let next_week = NaiveDate::parse_from_str(&resolve_relative_date("next-week", WeekStart::Sunday), "%Y-%m-%d").unwrap();
+        assert_eq!(next_week.weekday(), Weekday::Sun);
+        assert!(next_week > chrono::Local::now().date_naive());
 
-        let monday = resolve_relative_date("monday");
+        let monday = resolve_relative_date("monday", WeekStart::Monday);
         assert!(monday.len() == 10);
     }
 
@@ -920,4 +1152,44 @@ mod tests {
         assert!(!section_text.contains("## Section One"), "Should not contain heading: {}", section_text);
         assert!(!section_text.contains("Section two content"), "Should not contain next section: {}", section_text);
     }
+
+    #[test]
+    fn test_parse_encrypted_note_skips_body() {
+        let content = "---\nencrypted: true\ntags: [private]\n---\n# Secret\n\n- [ ] hidden task #inner [[Link]]\n";
+        let analysis = parse(content);
+
+        assert!(analysis.encrypted);
+        assert_eq!(analysis.title, None);
+        assert!(analysis.todos.is_empty());
+        assert!(analysis.links.is_empty());
+        assert_eq!(analysis.tags, vec!["private"]);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_aliases() {
+        let analysis = parse("---\naliases:\n- Garden plan\n- Beds\n---\n# Spring garden\n");
+        assert_eq!(analysis.aliases, vec!["Garden plan", "Beds"]);
+        assert!(parse("# No frontmatter\n").aliases.is_empty());
+    }
+
+    #[test]
+    fn test_parse_skips_private_sections() {
+        let content = "# Note\n\n%%private%%\n- [ ] hidden task #secret [[Hidden]]\n%%end%%\n- [ ] visible task #public\n";
+        let analysis = parse(content);
+
+        assert_eq!(analysis.todos.len(), 1);
+        assert!(analysis.todos[0].raw_text.contains("visible task"));
+        // Line numbers still refer to the original file
+        assert_eq!(analysis.todos[0].line_number, 6);
+        assert_eq!(analysis.tags, vec!["public"]);
+        assert!(analysis.links.is_empty());
+    }
+
+    #[test]
+    fn test_parse_word_count() {
+        let content = "---\ntitle: Not counted\n---\n# Heading\n\n- [ ] two words\n- [x] done\n> quoted text here\n%%private%%hidden words%%end%%\n";
+        let analysis = parse(content);
+
+        assert_eq!(analysis.word_count, 7);
+    }
 }
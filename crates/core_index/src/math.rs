@@ -0,0 +1,140 @@
+//! Math (`$...$` and `$$...$$`) detection and TeX to MathML conversion.
+//!
+//! Math spans are found with pulldown-cmark's math extension, so `$` in
+//! code and escaped dollars are left alone. Indexing blanks them before
+//! looking for tags and wikilinks, so a `\#` or `[[` in a formula isn't
+//! mistaken for one.
+//!
+//! TeX is converted to MathML with KaTeX, run in an embedded JavaScript
+//! engine. A formula KaTeX can't parse is shown as an error with its
+//! source rather than failing the whole note.
+
+use pulldown_cmark::{Event, Options, Parser};
+use std::borrow::Cow;
+use std::ops::Range;
+use tracing::warn;
+
+/// A math span in a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MathSpan {
+    /// `$$...$$` (display) rather than `$...$` (inline).
+    pub display: bool,
+    /// The TeX source, without the dollar signs.
+    pub tex: String,
+    /// Line number (1-based) where the span starts.
+    pub line_number: usize,
+    /// Byte range of the span, dollar signs included.
+    pub range: Range<usize>,
+}
+
+/// Find the math spans in markdown.
+pub fn find_math(markdown: &str) -> Vec<MathSpan> {
+    let parser = Parser::new_ext(markdown, Options::ENABLE_MATH);
+    parser
+        .into_offset_iter()
+        .filter_map(|(event, range)| {
+            let (display, tex) = match event {
+                Event::InlineMath(tex) => (false, tex),
+                Event::DisplayMath(tex) => (true, tex),
+                _ => return None,
+            };
+            Some(MathSpan {
+                display,
+                tex: tex.to_string(),
+                line_number: markdown[..range.start].matches('\n').count() + 1,
+                range,
+            })
+        })
+        .collect()
+}
+
+/// Replace math spans with spaces, keeping line breaks and byte offsets.
+pub fn blank_math(markdown: &str) -> Cow<'_, str> {
+    let spans = find_math(markdown);
+    if spans.is_empty() {
+        return Cow::Borrowed(markdown);
+    }
+
+    let mut blanked = String::with_capacity(markdown.len());
+    let mut last = 0;
+    for span in spans {
+        blanked.push_str(&markdown[last..span.range.start]);
+        blanked.extend(markdown[span.range.clone()].chars().map(|c| if c == '\n' { '\n' } else { ' ' }));
+        last = span.range.end;
+    }
+    blanked.push_str(&markdown[last..]);
+    Cow::Owned(blanked)
+}
+
+/// Convert TeX to a MathML `<math>` element (wrapped in a `katex` span),
+/// with the source kept as an annotation.
+pub fn tex_to_mathml(tex: &str, display: bool) -> String {
+    let opts = katex::Opts::builder()
+        .display_mode(display)
+        .output_type(katex::OutputType::Mathml)
+        .throw_on_error(false)
+        .build()
+        .unwrap_or_default();
+
+    match katex::render_with_opts(tex, &opts) {
+        Ok(mathml) => mathml,
+        Err(e) => {
+            warn!("Failed to render math: {}", e);
+            format!(
+                "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"{}\"><merror><mtext>{}</mtext></merror></math>",
+                if display { "block" } else { "inline" },
+                escape(tex.trim())
+            )
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_and_blank_math() {
+        let markdown = "Energy $E = mc^2$ costs \\$5.\n\n$$\n\\sum_{i=1}^n [[i]]\n$$\n\n`$not math$`\n";
+        let spans = find_math(markdown);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].tex, "E = mc^2");
+        assert!(!spans[0].display);
+        assert!(spans[1].display);
+        assert_eq!(spans[1].line_number, 3);
+        assert!(spans[1].tex.contains("\\sum_{i=1}^n"));
+
+        let blanked = blank_math(markdown);
+        assert_eq!(blanked.len(), markdown.len());
+        assert_eq!(blanked.lines().count(), markdown.lines().count());
+        assert!(!blanked.contains("[[i]]"));
+        assert!(blanked.contains("\\$5") && blanked.contains("`$not math$`"));
+    }
+
+    #[test]
+    fn test_tex_to_mathml() {
+        let mathml = tex_to_mathml("E = mc^2", false);
+        assert!(mathml.contains("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">"), "{}", mathml);
+        assert!(mathml.contains("<mi>E</mi><mo>=</mo><mi>m</mi><msup><mi>c</mi><mn>2</mn></msup>"));
+        assert!(mathml.contains("<annotation encoding=\"application/x-tex\">E = mc^2</annotation>"));
+
+        let mathml = tex_to_mathml("\\frac{1}{\\sqrt{2\\pi}} \\sum_{i=0}^{n} x_i", true);
+        assert!(mathml.contains("display=\"block\""));
+        assert!(mathml.contains("<mfrac>") && mathml.contains("<msqrt>") && mathml.contains("<munderover>"));
+        assert!(mathml.contains("<msub><mi>x</mi><mi>i</mi></msub>"));
+
+        // Environments beyond simple commands
+        let mathml = tex_to_mathml("\\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}", true);
+        assert!(mathml.contains("<mtable"));
+
+        // Errors show the source instead of failing
+        let mathml = tex_to_mathml("\\left( \\text{a < b} \\foo", false);
+        assert!(mathml.contains("katex-error"), "{}", mathml);
+        assert!(mathml.contains("a &lt; b"));
+        assert!(!mathml.contains("a < b"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Text statistics and readability scoring.
+//!
+//! Works on the prose of a note: paragraphs, list items, quotes, and table
+//! cells. Headings, code, HTML, and frontmatter are skipped, and wikilinks
+//! count as their display text. Syllables and passive voice are detected
+//! with English heuristics.
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use shared_types::{LengthBucket, ReadabilityReport};
+
+use crate::frontmatter::parse_frontmatter;
+use crate::markdown::WIKILINK_FULL_REGEX;
+use crate::redaction::redact_private;
+
+/// Sentence length buckets (in words).
+const SENTENCE_BUCKETS: &[(u32, Option<u32>)] = &[(1, Some(10)), (11, Some(20)), (21, Some(30)), (31, Some(40)), (41, None)];
+
+/// Word length buckets (in characters).
+const WORD_BUCKETS: &[(u32, Option<u32>)] = &[(1, Some(3)), (4, Some(6)), (7, Some(9)), (10, None)];
+
+/// Sentences longer than this are counted as long.
+const LONG_SENTENCE_WORDS: usize = 25;
+
+/// Passive sentences included in the report.
+const MAX_PASSIVE_EXAMPLES: usize = 10;
+
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Forms of "to be" that start a passive construction.
+const BE_FORMS: &[&str] = &["am", "is", "are", "was", "were", "be", "been", "being", "isn't", "aren't", "wasn't", "weren't"];
+
+/// Words allowed between the form of "to be" and the participle.
+const PASSIVE_FILLERS: &[&str] = &["not", "never", "also", "just", "often", "always", "being", "been", "still"];
+
+/// Common irregular past participles.
+const IRREGULAR_PARTICIPLES: &[&str] = &[
+    "awoken", "beaten", "become", "begun", "bent", "bitten", "blown", "born", "bought", "bound", "broken",
+    "brought", "built", "burnt", "caught", "chosen", "come", "cut", "dealt", "done", "drawn", "driven",
+    "eaten", "fallen", "fed", "felt", "fought", "found", "forgiven", "forgotten", "frozen", "given", "gone",
+    "grown", "heard", "held", "hidden", "hit", "hung", "hurt", "kept", "known", "laid", "led", "left",
+    "lent", "lost", "made", "meant", "met", "paid", "put", "read", "ridden", "run", "said", "seen",
+    "sent", "set", "shaken", "shown", "shut", "sold", "sought", "spent", "spoken", "stolen", "struck",
+    "sung", "taken", "taught", "thought", "thrown", "told", "torn", "understood", "woken", "won", "worn",
+    "written",
+];
+
+/// Abbreviations whose period does not end a sentence.
+const ABBREVIATIONS: &[&str] = &["e.g", "i.e", "etc", "vs", "mr", "mrs", "ms", "dr", "prof", "st", "no", "fig", "approx", "cf"];
+
+/// Compute text statistics and readability scores for note content.
+pub fn analyze_readability(content: &str) -> ReadabilityReport {
+    let redacted = redact_private(content);
+    let (_, body) = parse_frontmatter(&redacted);
+
+    let sentences: Vec<String> = prose_blocks(body)
+        .iter()
+        .flat_map(|block| split_sentences(block))
+        .collect();
+
+    let mut word_count = 0;
+    let mut letter_count = 0;
+    let mut syllable_count = 0;
+    let mut sentence_count = 0;
+    let mut long_sentence_count = 0;
+    let mut sentence_lengths: Vec<u32> = Vec::new();
+    let mut word_lengths: Vec<u32> = Vec::new();
+    let mut passive_sentences = Vec::new();
+    let mut passive_sentence_count = 0;
+
+    for sentence in &sentences {
+        let words = sentence_words(sentence);
+        if words.is_empty() {
+            continue;
+        }
+
+        sentence_count += 1;
+        word_count += words.len();
+        sentence_lengths.push(words.len() as u32);
+        if words.len() > LONG_SENTENCE_WORDS {
+            long_sentence_count += 1;
+        }
+        for word in &words {
+            let length = word.chars().filter(|c| c.is_alphanumeric()).count();
+            letter_count += length;
+            word_lengths.push(length as u32);
+            syllable_count += syllables(word);
+        }
+
+        if is_passive(&words) {
+            passive_sentence_count += 1;
+            if passive_sentences.len() < MAX_PASSIVE_EXAMPLES {
+                passive_sentences.push(sentence.clone());
+            }
+        }
+    }
+
+    let ratio = |a: usize, b: usize| if b == 0 { 0.0 } else { a as f64 / b as f64 };
+    let words_per_sentence = ratio(word_count, sentence_count);
+    let syllables_per_word = ratio(syllable_count, word_count);
+    let has_text = sentence_count > 0;
+
+    ReadabilityReport {
+        word_count: word_count as u32,
+        sentence_count: sentence_count as u32,
+        syllable_count: syllable_count as u32,
+        average_sentence_length: round1(words_per_sentence),
+        average_word_length: round1(ratio(letter_count, word_count)),
+        sentence_lengths: buckets(SENTENCE_BUCKETS, &sentence_lengths),
+        word_lengths: buckets(WORD_BUCKETS, &word_lengths),
+        long_sentence_count,
+        passive_sentence_count,
+        passive_sentences,
+        flesch_reading_ease: has_text
+            .then(|| round1(206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word)),
+        flesch_kincaid_grade: has_text
+            .then(|| round1(0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59)),
+        reading_time_minutes: word_count.div_ceil(WORDS_PER_MINUTE) as u32,
+    }
+}
+
+/// Text of the prose blocks (paragraphs, list items, table cells).
+fn prose_blocks(body: &str) -> Vec<String> {
+    let parser = Parser::new_ext(body, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS);
+
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+    // Headings, code blocks, and image alt text are not prose
+    let mut skip_depth = 0;
+
+    let mut flush = |current: &mut String| {
+        let text = WIKILINK_FULL_REGEX.replace_all(current, |caps: &regex::Captures| {
+            if &caps[1] == "!" {
+                String::new()
+            } else {
+                caps.get(4).unwrap_or_else(|| caps.get(2).unwrap()).as_str().to_string()
+            }
+        });
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            blocks.push(text);
+        }
+        current.clear();
+    };
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Heading { .. } | Tag::CodeBlock(_) | Tag::Image { .. }) => {
+                flush(&mut current);
+                skip_depth += 1;
+            }
+            Event::End(TagEnd::Heading(_) | TagEnd::CodeBlock | TagEnd::Image) => {
+                skip_depth -= 1;
+                flush(&mut current);
+            }
+            Event::Start(Tag::Paragraph | Tag::Item | Tag::TableCell | Tag::BlockQuote(_))
+            | Event::End(TagEnd::Paragraph | TagEnd::Item | TagEnd::TableCell | TagEnd::BlockQuote(_)) => {
+                flush(&mut current);
+            }
+            Event::Text(text) if skip_depth == 0 => current.push_str(&text),
+            Event::SoftBreak | Event::HardBreak if skip_depth == 0 => current.push(' '),
+            _ => {}
+        }
+    }
+    flush(&mut current);
+
+    blocks
+}
+
+/// Split a block of text into sentences at `.`, `!`, and `?`.
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+
+    for (i, &(index, c)) in chars.iter().enumerate() {
+        if !matches!(c, '.' | '!' | '?' | '…') {
+            continue;
+        }
+        let next = chars.get(i + 1).map(|&(_, n)| n);
+        if !next.is_none_or(char::is_whitespace) {
+            continue;
+        }
+        if c == '.' {
+            let last_word = text[start..index]
+                .rsplit(char::is_whitespace)
+                .next()
+                .unwrap_or("")
+                .trim_start_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            let is_initial = last_word.chars().count() == 1 && last_word.chars().all(char::is_alphabetic);
+            if is_initial || ABBREVIATIONS.contains(&last_word.as_str()) {
+                continue;
+            }
+        }
+
+        let end = index + c.len_utf8();
+        let sentence = text[start..end].trim();
+        if !sentence.is_empty() {
+            sentences.push(sentence.to_string());
+        }
+        start = end;
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+/// Words of a sentence, lowercased and without surrounding punctuation.
+fn sentence_words(sentence: &str) -> Vec<String> {
+    sentence
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| w.chars().any(char::is_alphabetic))
+        .collect()
+}
+
+/// Estimate the syllables of an English word (vowel groups, silent "e").
+fn syllables(word: &str) -> usize {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.is_empty() {
+        return 0;
+    }
+
+    let is_vowel = |c: char| "aeiouyàáâäèéêëìíîïòóôöùúûü".contains(c);
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for &c in &letters {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+
+    // Silent final "e" ("make"), but not "-le" ("table")
+    let n = letters.len();
+    if count > 1 && letters[n - 1] == 'e' && !(n > 2 && letters[n - 2] == 'l' && !is_vowel(letters[n - 3])) {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// A form of "to be" followed by a past participle.
+fn is_passive(words: &[String]) -> bool {
+    words.iter().enumerate().any(|(i, word)| {
+        if !BE_FORMS.contains(&word.as_str()) {
+            return false;
+        }
+        words[i + 1..]
+            .iter()
+            .find(|w| !(PASSIVE_FILLERS.contains(&w.as_str()) || w.ends_with("ly")))
+            .is_some_and(|w| is_participle(w))
+    })
+}
+
+fn is_participle(word: &str) -> bool {
+    (word.len() > 3 && word.ends_with("ed")) || IRREGULAR_PARTICIPLES.contains(&word)
+}
+
+fn buckets(ranges: &[(u32, Option<u32>)], lengths: &[u32]) -> Vec<LengthBucket> {
+    ranges
+        .iter()
+        .map(|&(min, max)| LengthBucket {
+            min,
+            max,
+            count: lengths
+                .iter()
+                .filter(|&&l| l >= min && max.is_none_or(|max| l <= max))
+                .count() as u32,
+        })
+        .collect()
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences() {
+        let sentences = split_sentences("Dr. Smith arrived, e.g. at noon. Was he late? Yes! J. R. R. Tolkien wrote it…");
+        assert_eq!(
+            sentences,
+            vec!["Dr. Smith arrived, e.g. at noon.", "Was he late?", "Yes!", "J. R. R. Tolkien wrote it…"]
+        );
+    }
+
+    #[test]
+    fn test_syllables() {
+        assert_eq!(syllables("cat"), 1);
+        assert_eq!(syllables("make"), 1);
+        assert_eq!(syllables("table"), 2);
+        assert_eq!(syllables("readability"), 5);
+        assert_eq!(syllables("the"), 1);
+    }
+
+    #[test]
+    fn test_is_passive() {
+        let words = |s: &str| sentence_words(s);
+        assert!(is_passive(&words("The report was written by Anna.")));
+        assert!(is_passive(&words("Mistakes were clearly made.")));
+        assert!(is_passive(&words("The bridge is being built.")));
+        assert!(!is_passive(&words("Anna wrote the report.")));
+        assert!(!is_passive(&words("The sky is blue.")));
+    }
+
+    #[test]
+    fn test_analyze_readability() {
+        let content = "---\ntitle: Draft\n---\n# Not Counted Heading\n\nThe cat sat on the mat. The report was written by [[Anna Smith|Anna]].\n\n```\ncode is not counted.\n```\n\n- A list item without a period\n";
+        let report = analyze_readability(content);
+
+        assert_eq!(report.sentence_count, 3);
+        assert_eq!(report.word_count, 18);
+        assert_eq!(report.passive_sentence_count, 1);
+        assert_eq!(report.passive_sentences, vec!["The report was written by Anna."]);
+        assert_eq!(report.sentence_lengths[0], LengthBucket { min: 1, max: Some(10), count: 3 });
+        assert_eq!(report.reading_time_minutes, 1);
+        assert!(report.flesch_reading_ease.unwrap() > 80.0);
+    }
+
+    #[test]
+    fn test_analyze_readability_empty() {
+        let report = analyze_readability("# Only a heading\n");
+        assert_eq!(report.word_count, 0);
+        assert_eq!(report.flesch_reading_ease, None);
+        assert_eq!(report.reading_time_minutes, 0);
+    }
+}
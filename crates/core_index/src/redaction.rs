@@ -0,0 +1,91 @@
+//! Private sections that are kept out of the index.
+//!
+//! Text between `%%private%%` and `%%end%%` stays in the file but is blanked
+//! before parsing, full-text indexing, and embedding. A section without an
+//! end marker runs to the end of the note.
+
+use std::borrow::Cow;
+
+/// Marker opening a private section.
+pub const PRIVATE_START: &str = "%%private%%";
+
+/// Marker closing a private section.
+pub const PRIVATE_END: &str = "%%end%%";
+
+/// Whether the content has a private section.
+pub fn has_private_sections(content: &str) -> bool {
+    content.contains(PRIVATE_START)
+}
+
+/// Blank out private sections, including their markers.
+///
+/// Every redacted character is replaced by spaces of the same byte length and
+/// newlines are kept, so line numbers and byte offsets stay valid for the
+/// original file (e.g. for toggling todos).
+pub fn redact_private(content: &str) -> Cow<'_, str> {
+    if !has_private_sections(content) {
+        return Cow::Borrowed(content);
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find(PRIVATE_START) {
+        result.push_str(&rest[..start]);
+        let section = &rest[start..];
+        let end = section[PRIVATE_START.len()..]
+            .find(PRIVATE_END)
+            .map(|i| PRIVATE_START.len() + i + PRIVATE_END.len())
+            .unwrap_or(section.len());
+
+        for c in section[..end].chars() {
+            if c == '\n' || c == '\r' {
+                result.push(c);
+            } else {
+                result.extend(std::iter::repeat_n(' ', c.len_utf8()));
+            }
+        }
+        rest = &section[end..];
+    }
+    result.push_str(rest);
+
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_private_sections_is_borrowed() {
+        let content = "# Note\n\nNothing hidden %%comment%% here.\n";
+        assert!(matches!(redact_private(content), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_redacts_sections_and_keeps_layout() {
+        let content = "# Note\n%%private%%\nsecret line\n- [ ] hidden task\n%%end%%\n- [ ] visible task\nInline %%private%%pin 1234%%end%% text.\n";
+        let redacted = redact_private(content);
+
+        assert!(!redacted.contains("secret"));
+        assert!(!redacted.contains("hidden"));
+        assert!(!redacted.contains("1234"));
+        assert!(!redacted.contains(PRIVATE_START));
+        assert!(redacted.contains("- [ ] visible task"));
+        assert!(redacted.contains("Inline"));
+        assert!(redacted.contains("text."));
+
+        assert_eq!(redacted.len(), content.len());
+        assert_eq!(redacted.lines().count(), content.lines().count());
+        assert_eq!(redacted.lines().nth(5), Some("- [ ] visible task"));
+    }
+
+    #[test]
+    fn test_unclosed_section_runs_to_end() {
+        let content = "Public\n%%private%%\nGeheimnis über alles\n";
+        let redacted = redact_private(content);
+
+        assert!(redacted.starts_with("Public\n"));
+        assert!(!redacted.contains("Geheimnis"));
+        assert_eq!(redacted.len(), content.len());
+    }
+}
@@ -0,0 +1,400 @@
+//! Markdown to HTML rendering.
+//!
+//! Raw HTML in the note is escaped and unsafe link schemes are dropped, so
+//! the output can be shown or exported as is. Wikilinks and embeds are
+//! rendered by the caller (which knows how to resolve them), `query`
+//! code blocks become placeholders, and `mermaid` code blocks are wrapped
+//! in a numbered diagram element the caller can swap for a rendered SVG.
+//! Math is rendered as MathML or kept as TeX for a renderer in the page.
+
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+use shared_types::MathRendering;
+
+use crate::markdown::{slugify, WIKILINK_FULL_REGEX};
+use crate::math::tex_to_mathml;
+
+/// A wikilink or embed found while rendering.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WikiLink {
+    /// `![[...]]` rather than `[[...]]`.
+    pub embed: bool,
+    pub target: String,
+    pub section: Option<String>,
+    pub display: Option<String>,
+}
+
+impl WikiLink {
+    /// The text shown for the link.
+    pub fn label(&self) -> String {
+        match (&self.display, &self.section) {
+            (Some(display), _) => display.clone(),
+            (None, Some(section)) => format!("{} > {}", self.target, section),
+            (None, None) => self.target.clone(),
+        }
+    }
+}
+
+/// Render markdown (without frontmatter) to sanitized HTML, with math as
+/// MathML.
+///
+/// `render_link` returns the HTML for each wikilink and embed; it is
+/// inserted as is, so it must escape any text it includes.
+pub fn render_html<F>(markdown: &str, render_link: F) -> String
+where
+    F: FnMut(&WikiLink) -> String,
+{
+    render_html_with(markdown, MathRendering::default(), render_link)
+}
+
+/// Render markdown (without frontmatter) to sanitized HTML, with math
+/// rendered as `math` says.
+pub fn render_html_with<F>(markdown: &str, math: MathRendering, mut render_link: F) -> String
+where
+    F: FnMut(&WikiLink) -> String,
+{
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_MATH,
+    );
+
+    let mut events: Vec<Event> = Vec::new();
+    let mut text = String::new();
+    let mut query: Option<String> = None;
+    let mut in_code_block = false;
+    let mut diagram_count = 0;
+    let mut in_diagram = false;
+
+    for event in parser {
+        if let Some(source) = query.as_mut() {
+            match event {
+                Event::Text(t) => source.push_str(&t),
+                Event::End(TagEnd::CodeBlock) => {
+                    events.push(Event::Html(query_placeholder(source).into()));
+                    query = None;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            // Text is split at brackets, so wikilinks are found in merged runs
+            Event::Text(t) | Event::InlineHtml(t) | Event::Html(t) if !in_code_block => {
+                text.push_str(&t);
+                continue;
+            }
+            _ => flush_text(&mut text, &mut events, &mut render_link),
+        }
+
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref lang)))
+                if lang.split_whitespace().next() == Some("query") =>
+            {
+                query = Some(String::new());
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref lang))) if is_diagram_language(lang) => {
+                events.push(Event::Html(diagram_start(diagram_count).into()));
+                diagram_count += 1;
+                in_diagram = true;
+                in_code_block = true;
+                events.push(event);
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                in_code_block = true;
+                events.push(event);
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                in_code_block = false;
+                events.push(event);
+                if in_diagram {
+                    events.push(Event::Html(DIAGRAM_END.into()));
+                    in_diagram = false;
+                }
+            }
+            Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+                events.push(Event::Start(Tag::Link {
+                    link_type,
+                    dest_url: safe_url(dest_url),
+                    title,
+                    id,
+                }));
+            }
+            Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+                events.push(Event::Start(Tag::Image {
+                    link_type,
+                    dest_url: safe_url(dest_url),
+                    title,
+                    id,
+                }));
+            }
+            Event::InlineMath(tex) => events.push(Event::InlineHtml(render_math(&tex, false, math).into())),
+            Event::DisplayMath(tex) => events.push(Event::InlineHtml(render_math(&tex, true, math).into())),
+            // Raw HTML inside code blocks is code
+            Event::Html(t) | Event::InlineHtml(t) => events.push(Event::Text(t)),
+            event => events.push(event),
+        }
+    }
+    flush_text(&mut text, &mut events, &mut render_link);
+
+    add_heading_ids(&mut events);
+
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, events.into_iter());
+    output
+}
+
+/// Find the wikilinks and embeds `render_html` would render.
+pub fn collect_wikilinks(markdown: &str) -> Vec<WikiLink> {
+    let mut links = Vec::new();
+    render_html(markdown, |link| {
+        if !links.contains(link) {
+            links.push(link.clone());
+        }
+        String::new()
+    });
+    links
+}
+
+/// Closes the element around a diagram's code block.
+pub const DIAGRAM_END: &str = "</div>\n";
+
+/// Opening tag `render_html` puts before the `index`th diagram's code block.
+pub fn diagram_start(index: usize) -> String {
+    format!("<div class=\"diagram\" data-diagram=\"{}\">", index)
+}
+
+/// Find the sources of the diagram (`mermaid`) code blocks, in the order
+/// `render_html` numbers them.
+pub fn find_diagrams(markdown: &str) -> Vec<String> {
+    let mut diagrams = Vec::new();
+    let mut current: Option<String> = None;
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_MATH,
+    );
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) if is_diagram_language(&lang) => {
+                current = Some(String::new());
+            }
+            Event::Text(t) => {
+                if let Some(source) = current.as_mut() {
+                    source.push_str(&t);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(source) = current.take() {
+                    diagrams.push(source);
+                }
+            }
+            _ => {}
+        }
+    }
+    diagrams
+}
+
+fn is_diagram_language(lang: &str) -> bool {
+    lang.split_whitespace().next() == Some("mermaid")
+}
+
+/// Escape text for use in HTML content or attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode a vault path for use in an href (slashes are kept).
+pub fn encode_href(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Emit buffered text, with wikilinks replaced by their rendered HTML.
+fn flush_text<'a, F>(text: &mut String, events: &mut Vec<Event<'a>>, render_link: &mut F)
+where
+    F: FnMut(&WikiLink) -> String,
+{
+    if text.is_empty() {
+        return;
+    }
+
+    let mut last = 0;
+    for caps in WIKILINK_FULL_REGEX.captures_iter(text) {
+        let matched = caps.get(0).unwrap();
+        if matched.start() > last {
+            events.push(Event::Text(text[last..matched.start()].to_string().into()));
+        }
+
+        let link = WikiLink {
+            embed: &caps[1] == "!",
+            target: caps[2].trim().to_string(),
+            section: caps.get(3).map(|m| m.as_str().trim().to_string()),
+            display: caps.get(4).map(|m| m.as_str().trim().to_string()),
+        };
+        events.push(Event::InlineHtml(render_link(&link).into()));
+        last = matched.end();
+    }
+    if last < text.len() {
+        events.push(Event::Text(text[last..].to_string().into()));
+    }
+    text.clear();
+}
+
+/// Give headings slug ids so `[[note#section]]` links can point at them.
+fn add_heading_ids(events: &mut [Event]) {
+    let mut i = 0;
+    while i < events.len() {
+        if let Event::Start(Tag::Heading { id: None, .. }) = &events[i] {
+            let mut heading_text = String::new();
+            for event in &events[i + 1..] {
+                match event {
+                    Event::End(TagEnd::Heading(_)) => break,
+                    Event::Text(t) | Event::Code(t) => heading_text.push_str(t),
+                    _ => {}
+                }
+            }
+
+            let slug = slugify(&heading_text);
+            if let Event::Start(Tag::Heading { id, .. }) = &mut events[i] {
+                if !slug.is_empty() {
+                    *id = Some(slug.into());
+                }
+            }
+        }
+        i += 1;
+    }
+}
+
+/// Drop URLs with schemes that can run code (e.g. `javascript:`).
+fn safe_url(url: CowStr) -> CowStr {
+    let scheme = url
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| !scheme.contains(['/', '?', '#']));
+
+    match scheme {
+        None => url,
+        Some(scheme) if ["http", "https", "mailto"].contains(&scheme.to_lowercase().as_str()) => url,
+        Some(_) => CowStr::Borrowed(""),
+    }
+}
+
+/// Render a math span as MathML, or as escaped TeX in the delimiters
+/// KaTeX's auto-render looks for.
+fn render_math(tex: &str, display: bool, math: MathRendering) -> String {
+    match (math, display) {
+        (MathRendering::Mathml, _) => tex_to_mathml(tex, display),
+        (MathRendering::Tex, false) => format!("<span class=\"math math-inline\">\\({}\\)</span>", escape_html(tex)),
+        (MathRendering::Tex, true) => format!("<span class=\"math math-display\">\\[{}\\]</span>", escape_html(tex)),
+    }
+}
+
+/// Placeholder for a query block; queries only run in the app.
+fn query_placeholder(source: &str) -> String {
+    format!(
+        "<div class=\"query-block\" data-query=\"{}\">Query results are not available here.</div>\n",
+        escape_html(source.trim_end())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(markdown: &str) -> String {
+        render_html(markdown, |link| {
+            format!("<a href=\"{}\">{}</a>", escape_html(&link.target), escape_html(&link.label()))
+        })
+    }
+
+    #[test]
+    fn test_render_wikilinks() {
+        let html = render("See [[Other Note|the other note]] and ![[Picture.png]].");
+        assert!(html.contains("<a href=\"Other Note\">the other note</a>"));
+        assert!(html.contains("<a href=\"Picture.png\">Picture.png</a>"));
+
+        let links = collect_wikilinks("[[a#Intro]] ![[b]] [[a#Intro]]\n\n```\n[[in code]]\n```\n");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].section.as_deref(), Some("Intro"));
+        assert!(links[1].embed);
+    }
+
+    #[test]
+    fn test_render_escapes_raw_html() {
+        let html = render("<script>alert(1)</script>\n\nHi <b onclick=\"x\">there</b>");
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("<b "));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_render_drops_unsafe_urls() {
+        let html = render("[click](javascript:alert(1)) [ok](https://example.com) [rel](notes/a.md)");
+        assert!(!html.contains("javascript"));
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("href=\"notes/a.md\""));
+    }
+
+    #[test]
+    fn test_render_query_placeholder_and_heading_ids() {
+        let html = render("# My Heading\n\n```query\nfilter: tag = \"x\"\n```\n");
+        assert!(html.contains("<h1 id=\"my-heading\">My Heading</h1>"));
+        assert!(html.contains("data-query=\"filter: tag = &quot;x&quot;\""));
+        assert!(!html.contains("<pre>"));
+    }
+
+    #[test]
+    fn test_render_numbers_diagrams() {
+        let markdown = "```mermaid\ngraph TD; A-->B\n```\n\n```rust\nfn main() {}\n```\n\n```mermaid\npie\n```\n";
+        let html = render(markdown);
+        assert!(html.contains("<div class=\"diagram\" data-diagram=\"0\">\n<pre><code class=\"language-mermaid\">graph TD; A--&gt;B\n</code></pre>\n</div>"));
+        assert!(html.contains("data-diagram=\"1\">\n<pre><code class=\"language-mermaid\">pie"));
+        assert_eq!(html.matches("class=\"diagram\"").count(), 2);
+
+        assert_eq!(find_diagrams(markdown), vec!["graph TD; A-->B\n", "pie\n"]);
+    }
+
+    #[test]
+    fn test_render_math() {
+        let markdown = "Mass $m < M$ and\n\n$$\nE = mc^2\n$$\n\nPrice: \\$5 or `$x$`.";
+        let html = render(markdown);
+        assert!(html.contains("<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><mi>m</mi><mo>&lt;</mo><mi>M</mi>"));
+        assert!(html.contains("display=\"block\""));
+        assert!(html.contains("$5") && html.contains("<code>$x$</code>"));
+
+        let html = render_html_with(markdown, MathRendering::Tex, |_| String::new());
+        assert!(html.contains("<span class=\"math math-inline\">\\(m &lt; M\\)</span>"));
+        assert!(html.contains("<span class=\"math math-display\">\\[\nE = mc^2\n\\]</span>"));
+        assert!(!html.contains("<math"));
+    }
+
+    #[test]
+    fn test_encode_href() {
+        assert_eq!(encode_href("Daily/2024-05-01 notes.md"), "Daily/2024-05-01%20notes.md");
+    }
+}
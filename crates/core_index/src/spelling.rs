@@ -0,0 +1,323 @@
+//! Spell checking against Hunspell dictionaries.
+//!
+//! Dictionaries are parsed and words checked and corrected by `spellbook`;
+//! this module finds the prose words of a note to check.
+
+use shared_types::Misspelling;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::frontmatter::parse_frontmatter;
+
+/// Suggestions returned per misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// A Hunspell dictionary (`.aff` + `.dic`).
+#[derive(Debug)]
+pub struct Dictionary {
+    inner: spellbook::Dictionary,
+}
+
+impl Dictionary {
+    /// Parse a dictionary from the contents of its `.aff` and `.dic` files.
+    pub fn new(aff: &str, dic: &str) -> Result<Self, String> {
+        let inner = spellbook::Dictionary::new(aff, dic).map_err(|e| e.to_string())?;
+        Ok(Self { inner })
+    }
+
+    /// Whether a word is spelled correctly.
+    ///
+    /// Capitalized and all-caps forms of dictionary words are accepted.
+    pub fn check(&self, word: &str) -> bool {
+        self.inner.check(&word.replace('’', "'"))
+    }
+
+    /// Up to five correctly spelled words close to `word`, best first.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        self.inner.suggest(&word.replace('’', "'"), &mut suggestions);
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+}
+
+/// Decode dictionary file bytes: UTF-8, or ISO 8859-1 for older dictionaries.
+pub fn decode_dictionary_file(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Find misspelled words in markdown text.
+///
+/// Code, links, URLs, tags, HTML, comments, frontmatter, and words with
+/// digits or in all caps are skipped, as are `custom_words` (exact or
+/// lowercase). Ranges are UTF-16 offsets, as used by the editor.
+pub fn check_text(text: &str, dictionary: &Dictionary, custom_words: &HashSet<String>) -> Vec<Misspelling> {
+    let mut suggestions: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut misspellings = Vec::new();
+
+    // Offsets are converted to UTF-16 incrementally (ranges are in order)
+    let mut byte_offset = 0;
+    let mut utf16_offset = 0;
+    for range in spelling_words(text) {
+        let word = &text[range.clone()];
+        if custom_words.contains(word) || custom_words.contains(&word.to_lowercase()) || dictionary.check(word) {
+            continue;
+        }
+
+        utf16_offset += text[byte_offset..range.start].encode_utf16().count();
+        let from = utf16_offset;
+        utf16_offset += word.encode_utf16().count();
+        byte_offset = range.end;
+
+        let word_suggestions = suggestions
+            .entry(word)
+            .or_insert_with(|| dictionary.suggest(word));
+
+        misspellings.push(Misspelling {
+            from: from as u32,
+            to: utf16_offset as u32,
+            word: word.to_string(),
+            suggestions: word_suggestions.clone(),
+        });
+    }
+
+    misspellings
+}
+
+/// Byte ranges of the prose words in markdown text, in order.
+fn spelling_words(text: &str) -> Vec<Range<usize>> {
+    let body_start = parse_frontmatter(text).0.content_start;
+    let mut words = Vec::new();
+    let mut in_fence = false;
+    let mut offset = body_start;
+
+    for line in text[body_start..].split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            line_words(line, line_start, &mut words);
+        }
+    }
+
+    words
+}
+
+/// Collect the prose words of a single line.
+fn line_words(line: &str, line_start: usize, words: &mut Vec<Range<usize>>) {
+    let mut i = 0;
+    let mut chunk_start = true;
+
+    while let Some(c) = line[i..].chars().next() {
+        let rest = &line[i..];
+
+        let skip = if rest.starts_with('`') {
+            skip_past(rest, 1, "`")
+        } else if rest.starts_with("[[") {
+            skip_past(rest, 2, "]]")
+        } else if rest.starts_with("](") {
+            skip_past(rest, 2, ")")
+        } else if rest.starts_with("%%") {
+            skip_past(rest, 2, "%%")
+        } else if c == '<' && rest[1..].starts_with(|n: char| n.is_ascii_alphabetic() || n == '/') {
+            skip_past(rest, 1, ">")
+        } else if chunk_start && is_non_prose(chunk(rest)) {
+            chunk(rest).len()
+        } else {
+            0
+        };
+        if skip > 0 {
+            i += skip;
+            chunk_start = false;
+            continue;
+        }
+
+        if c.is_alphanumeric() {
+            let len = token_len(rest);
+            let token = &rest[..len];
+            let is_word = token.chars().all(|c| c.is_alphabetic() || c == '\'' || c == '’');
+            let is_acronym = token.chars().count() > 1 && token.chars().all(|c| !c.is_lowercase());
+            if is_word && !is_acronym {
+                words.push(line_start + i..line_start + i + len);
+            }
+            i += len;
+            chunk_start = false;
+            continue;
+        }
+
+        chunk_start = c.is_whitespace() || "([\"'*_>".contains(c);
+        i += c.len_utf8();
+    }
+}
+
+/// Length of a word-like token: letters, digits, `_`, and inner apostrophes.
+fn token_len(s: &str) -> usize {
+    let mut end = 0;
+    let mut chars = s.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let inner_apostrophe =
+            (c == '\'' || c == '’') && chars.peek().is_some_and(|(_, next)| next.is_alphabetic());
+        if c.is_alphanumeric() || c == '_' || inner_apostrophe {
+            end = index + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+/// Skip from an opening marker past its closing marker (or to the end of the line).
+fn skip_past(s: &str, open_len: usize, close: &str) -> usize {
+    s[open_len..]
+        .find(close)
+        .map(|i| open_len + i + close.len())
+        .unwrap_or(s.len())
+}
+
+/// The text up to the next whitespace or closing bracket.
+fn chunk(s: &str) -> &str {
+    s.split(|c: char| c.is_whitespace() || c == ']').next().unwrap_or("")
+}
+
+/// URLs, email addresses, and #tags.
+fn is_non_prose(chunk: &str) -> bool {
+    chunk.contains("://")
+        || chunk.starts_with("www.")
+        || chunk.contains('@')
+        || chunk
+            .strip_prefix('#')
+            .is_some_and(|tag| tag.starts_with(|c: char| c.is_alphanumeric()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+FORBIDDENWORD !
+NEEDAFFIX *
+REP 1
+REP f ph
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX S Y 3
+SFX S 0 s [^sxy]
+SFX S y ies [^aeiou]y
+SFX S 0 es [sx]
+
+SFX D Y 2
+SFX D 0 ed [^e]
+SFX D 0 d e
+";
+
+    const DIC: &str = "8
+happy/U
+lock/USD
+box/S
+city/S
+bake/D
+phone/S
+irregardless/!
+nuisance/*
+";
+
+    fn dictionary() -> Dictionary {
+        Dictionary::new(AFF, DIC).unwrap()
+    }
+
+    #[test]
+    fn test_check_affixes() {
+        let dictionary = dictionary();
+        for word in ["happy", "unhappy", "locks", "unlocked", "unlocks", "boxes", "cities", "baked"] {
+            assert!(dictionary.check(word), "{} should be correct", word);
+        }
+        for word in ["happys", "citys", "boxs", "bakeed", "unbox", "irregardless", "nuisance"] {
+            assert!(!dictionary.check(word), "{} should be misspelled", word);
+        }
+    }
+
+    #[test]
+    fn test_check_case() {
+        let dictionary = dictionary();
+        assert!(dictionary.check("Unlocked"));
+        assert!(dictionary.check("CITIES"));
+        assert!(!dictionary.check("cIties"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggest("fone").first().map(String::as_str), Some("phone"));
+        assert!(dictionary.suggest("lcok").contains(&"lock".to_string()));
+        assert!(dictionary.suggest("happyboxes").contains(&"happy boxes".to_string()));
+    }
+
+    #[test]
+    fn test_flag_modes_and_aliases() {
+        let aff = "FLAG long\nAF 1\nAF Aa\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dictionary = Dictionary::new(aff, "1\ncat/1\n").unwrap();
+        assert!(dictionary.check("cats"));
+
+        let aff = "FLAG num\nSFX 101 Y 1\nSFX 101 0 s .\n";
+        let dictionary = Dictionary::new(aff, "1\ndog/7,101\n").unwrap();
+        assert!(dictionary.check("dogs"));
+    }
+
+    #[test]
+    fn test_bundled_en_us() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../../src-tauri/dictionaries");
+        let aff = std::fs::read(format!("{}/en_US.aff", dir)).unwrap();
+        let dic = std::fs::read(format!("{}/en_US.dic", dir)).unwrap();
+        let dictionary = Dictionary::new(&decode_dictionary_file(&aff), &decode_dictionary_file(&dic)).unwrap();
+
+        assert!(dictionary.check("Notes"));
+        assert!(dictionary.check("don’t"));
+        assert!(!dictionary.check("recieve"));
+        assert!(dictionary.suggest("recieve").contains(&"receive".to_string()));
+    }
+
+    #[test]
+    fn test_check_text() {
+        let dictionary = dictionary();
+        let custom: HashSet<String> = ["neuroflow".to_string()].into();
+        let text = "Ünlocked → cities have fones, NeuroFlow `codez` [[boxez]]";
+        let misspellings = check_text(text, &dictionary, &custom);
+
+        let words: Vec<&str> = misspellings.iter().map(|m| m.word.as_str()).collect();
+        assert_eq!(words, vec!["Ünlocked", "have", "fones"]);
+        // "→" is one UTF-16 unit but three bytes
+        assert_eq!((misspellings[1].from, misspellings[1].to), (18, 22));
+        assert_eq!(misspellings[2].suggestions, vec!["phones".to_string()]);
+    }
+
+    fn words(text: &str) -> Vec<&str> {
+        spelling_words(text).into_iter().map(|r| &text[r]).collect()
+    }
+
+    #[test]
+    fn test_spelling_words_prose() {
+        assert_eq!(words("Don't panic, it's fine."), vec!["Don't", "panic", "it's", "fine"]);
+        assert_eq!(words("Ünïcode wörds ok"), vec!["Ünïcode", "wörds", "ok"]);
+        assert_eq!(words("well-known"), vec!["well", "known"]);
+    }
+
+    #[test]
+    fn test_spelling_words_skips_markup() {
+        let text = "---\ntitle: Frontmattr\n---\n# Heading\n\nSee [[Som Note]] and [lnk](http://x.io/pth) `cde` #tagg\n```\nfenced cde\n```\nMail me@exmple.com or www.exmple.com <span>txt</span> %%comnt%% NASA abc123 end\n";
+        assert_eq!(
+            words(text),
+            vec!["Heading", "See", "and", "lnk", "Mail", "or", "txt", "end"]
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Markdown (GFM) tables: finding, formatting and editing them.
+//!
+//! Tables are found with pulldown-cmark, so table-like text in code blocks
+//! is left alone, and then split into cells on unescaped pipes. Cells keep
+//! their markdown as written (`\|` included). Every edit rewrites the table
+//! formatted: columns padded to the same width, with the delimiter row
+//! showing each column's alignment. Tables in block quotes aren't edited.
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use std::cmp::Ordering;
+
+/// Alignment of a table column, from its delimiter cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// `---`
+    #[default]
+    None,
+    /// `:--`
+    Left,
+    /// `:-:`
+    Center,
+    /// `--:`
+    Right,
+}
+
+/// A table in a note.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    /// Line number (1-based) of the header row.
+    pub start_line: usize,
+    /// Line number (1-based) of the last row.
+    pub end_line: usize,
+    /// Indentation of the header row, kept when the table is rewritten.
+    pub indent: String,
+    pub header: Vec<String>,
+    pub alignments: Vec<Alignment>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Number of columns: the widest of the header and the rows.
+    pub fn column_count(&self) -> usize {
+        self.rows.iter().map(Vec::len).chain([self.header.len()]).max().unwrap_or(0)
+    }
+
+    /// The table as formatted markdown, one line per row, each ending in `\n`.
+    pub fn to_markdown(&self) -> String {
+        let columns = self.column_count().max(1);
+        let cell = |row: &[String], i: usize| row.get(i).map(String::as_str).unwrap_or("").to_string();
+        let widths: Vec<usize> = (0..columns)
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .chain([&self.header])
+                    .map(|row| cell(row, i).chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+        let alignment = |i: usize| self.alignments.get(i).copied().unwrap_or_default();
+
+        let format_row = |row: &[String]| {
+            let mut line = format!("{}|", self.indent);
+            for (i, &width) in widths.iter().enumerate() {
+                let text = cell(row, i);
+                let pad = width - text.chars().count();
+                let (left, right) = match alignment(i) {
+                    Alignment::Right => (pad, 0),
+                    Alignment::Center => (pad / 2, pad - pad / 2),
+                    Alignment::None | Alignment::Left => (0, pad),
+                };
+                line.push_str(&format!(" {}{}{} |", " ".repeat(left), text, " ".repeat(right)));
+            }
+            line.push('\n');
+            line
+        };
+
+        let mut markdown = format_row(&self.header);
+        markdown.push_str(&self.indent);
+        markdown.push('|');
+        for (i, &width) in widths.iter().enumerate() {
+            let delimiter = match alignment(i) {
+                Alignment::None => "-".repeat(width),
+                Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+            };
+            markdown.push_str(&format!(" {} |", delimiter));
+        }
+        markdown.push('\n');
+        for row in &self.rows {
+            markdown.push_str(&format_row(row));
+        }
+        markdown
+    }
+}
+
+/// Find the tables in markdown.
+pub fn find_tables(markdown: &str) -> Vec<Table> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES);
+
+    parser
+        .into_offset_iter()
+        .filter_map(|(event, range)| {
+            if !matches!(event, Event::Start(Tag::Table(_))) {
+                return None;
+            }
+            let start = markdown[..range.start].matches('\n').count();
+            let end = start + markdown[range].trim_end().matches('\n').count();
+            parse_table(&lines[start..=end], start + 1)
+        })
+        .collect()
+}
+
+/// The table that contains line `line` (1-based).
+pub fn table_at_line(markdown: &str, line: usize) -> Option<Table> {
+    find_tables(markdown)
+        .into_iter()
+        .find(|t| (t.start_line..=t.end_line).contains(&line))
+}
+
+/// Format the table at `line`. None if there is no table there.
+pub fn format_table(markdown: &str, line: usize) -> Option<String> {
+    edit_table(markdown, line, |_| true)
+}
+
+/// Sort the rows of the table at `line` by a column (0-based).
+///
+/// Numbers sort numerically and before text, text case-insensitively, and
+/// empty cells always last. None if there is no table or column there.
+pub fn sort_table_by_column(markdown: &str, line: usize, column: usize, descending: bool) -> Option<String> {
+    edit_table(markdown, line, |table| {
+        if column >= table.column_count() {
+            return false;
+        }
+        table.rows.sort_by(|a, b| {
+            let a = a.get(column).map(|c| c.trim()).unwrap_or("");
+            let b = b.get(column).map(|c| c.trim()).unwrap_or("");
+            match (a.is_empty(), b.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) if descending => compare_cells(b, a),
+                (false, false) => compare_cells(a, b),
+            }
+        });
+        true
+    })
+}
+
+/// Insert an empty row into the table at `line`, before body row `index`
+/// (0-based), or at the end.
+pub fn table_add_row(markdown: &str, line: usize, index: Option<usize>) -> Option<String> {
+    edit_table(markdown, line, |table| {
+        let index = index.unwrap_or(table.rows.len()).min(table.rows.len());
+        table.rows.insert(index, vec![String::new(); table.column_count()]);
+        true
+    })
+}
+
+/// Insert a column headed `header` into the table at `line`, before column
+/// `index` (0-based), or at the end.
+pub fn table_add_column(markdown: &str, line: usize, index: Option<usize>, header: &str) -> Option<String> {
+    edit_table(markdown, line, |table| {
+        let columns = table.column_count();
+        let index = index.unwrap_or(columns).min(columns);
+        for row in table.rows.iter_mut().chain([&mut table.header]) {
+            row.resize(columns, String::new());
+            row.insert(index, String::new());
+        }
+        table.header[index] = header.replace('|', "\\|");
+        table.alignments.resize(columns, Alignment::None);
+        table.alignments.insert(index, Alignment::None);
+        true
+    })
+}
+
+/// Apply `edit` to the table at `line` and rewrite it, formatted. None if
+/// there is no table there or `edit` returns false.
+fn edit_table(markdown: &str, line: usize, edit: impl FnOnce(&mut Table) -> bool) -> Option<String> {
+    let mut table = table_at_line(markdown, line)?;
+    if !edit(&mut table) {
+        return None;
+    }
+
+    let lines: Vec<&str> = markdown.split_inclusive('\n').collect();
+    let mut output: String = lines[..table.start_line - 1].concat();
+    let mut formatted = table.to_markdown();
+    if !lines[table.end_line - 1].ends_with('\n') {
+        formatted.pop();
+    }
+    output.push_str(&formatted);
+    output.push_str(&lines[table.end_line..].concat());
+    Some(output)
+}
+
+/// Parse the lines of a table (header, delimiter row, body rows).
+fn parse_table(lines: &[&str], start_line: usize) -> Option<Table> {
+    let (header, rest) = lines.split_first()?;
+    let (delimiter, rows) = rest.split_first()?;
+    if lines.iter().any(|l| l.trim_start().starts_with('>')) {
+        return None;
+    }
+
+    let alignments = split_row(delimiter)
+        .iter()
+        .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+            (true, true) => Alignment::Center,
+            (true, false) => Alignment::Left,
+            (false, true) => Alignment::Right,
+            (false, false) => Alignment::None,
+        })
+        .collect();
+
+    Some(Table {
+        start_line,
+        end_line: start_line + lines.len() - 1,
+        indent: header[..header.len() - header.trim_start().len()].to_string(),
+        header: split_row(header),
+        alignments,
+        rows: rows.iter().map(|row| split_row(row)).collect(),
+    })
+}
+
+/// Split a table row into trimmed cells on pipes that aren't escaped.
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            '|' if !escaped => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    // Text after the last pipe is a cell unless the row ends with a pipe
+    if !cell.trim().is_empty() || cells.is_empty() {
+        cells.push(cell);
+    }
+    cells.into_iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// Compare two non-empty cells: numbers numerically and before text.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "# Prices\n\n| Item | Price | Note |\n|:--|--:|:-:|\n| Apple | 1.5 | a \\| b |\n|Banana|10|\n| cherry | 2 | `x` |\n\nAfter\n";
+
+    #[test]
+    fn test_find_tables() {
+        let markdown = format!("{}\n```\n| A | B |\n|---|---|\n```\n", NOTE);
+        let tables = find_tables(&markdown);
+        assert_eq!(tables.len(), 1);
+
+        let table = &tables[0];
+        assert_eq!((table.start_line, table.end_line), (3, 7));
+        assert_eq!(table.header, vec!["Item", "Price", "Note"]);
+        assert_eq!(table.alignments, vec![Alignment::Left, Alignment::Right, Alignment::Center]);
+        assert_eq!(table.rows[0], vec!["Apple", "1.5", "a \\| b"]);
+        assert_eq!(table.rows[1], vec!["Banana", "10"]);
+
+        assert_eq!(table_at_line(NOTE, 5).map(|t| t.start_line), Some(3));
+        assert!(table_at_line(NOTE, 9).is_none());
+    }
+
+    #[test]
+    fn test_format_table() {
+        assert_eq!(
+            format_table(NOTE, 4).unwrap(),
+            "# Prices\n\n\
+             | Item   | Price |  Note  |\n\
+             | :----- | ----: | :----: |\n\
+             | Apple  |   1.5 | a \\| b |\n\
+             | Banana |    10 |        |\n\
+             | cherry |     2 |  `x`   |\n\
+             \nAfter\n"
+        );
+        assert!(format_table(NOTE, 1).is_none());
+
+        // Indented tables stay indented; no newline is added at the end
+        assert_eq!(format_table("  a|b\n  -|-\n  1|2", 1).unwrap(), "  | a   | b   |\n  | --- | --- |\n  | 1   | 2   |");
+    }
+
+    #[test]
+    fn test_sort_table_by_column() {
+        let rows = |markdown: &str| table_at_line(markdown, 3).unwrap().rows;
+
+        let by_price = sort_table_by_column(NOTE, 3, 1, false).unwrap();
+        let names: Vec<String> = rows(&by_price).into_iter().map(|r| r[0].clone()).collect();
+        assert_eq!(names, vec!["Apple", "cherry", "Banana"]);
+
+        let by_name = sort_table_by_column(NOTE, 3, 0, true).unwrap();
+        let names: Vec<String> = rows(&by_name).into_iter().map(|r| r[0].clone()).collect();
+        assert_eq!(names, vec!["cherry", "Banana", "Apple"]);
+
+        // The empty note cell of Banana sorts last either way
+        let by_note = sort_table_by_column(NOTE, 3, 2, true).unwrap();
+        assert_eq!(rows(&by_note)[2][0], "Banana");
+
+        assert!(sort_table_by_column(NOTE, 3, 3, false).is_none());
+    }
+
+    #[test]
+    fn test_table_add_row_and_column() {
+        let with_row = table_add_row(NOTE, 3, Some(1)).unwrap();
+        let table = table_at_line(&with_row, 3).unwrap();
+        assert_eq!(table.rows.len(), 4);
+        assert_eq!(table.rows[1], vec!["", "", ""]);
+        assert_eq!(table.rows[2][0], "Banana");
+        assert!(with_row.ends_with("\nAfter\n"));
+
+        let with_column = table_add_column(NOTE, 3, Some(1), "Qty|Unit").unwrap();
+        let table = table_at_line(&with_column, 3).unwrap();
+        assert_eq!(table.header, vec!["Item", "Qty\\|Unit", "Price", "Note"]);
+        assert_eq!(table.alignments[1..3], [Alignment::None, Alignment::Right]);
+        assert_eq!(table.rows[1], vec!["Banana", "", "10", ""]);
+        assert_eq!(table.rows[0][3], "a \\| b");
+    }
+}
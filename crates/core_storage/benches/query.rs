@@ -0,0 +1,162 @@
+//! Benchmarks for `run_query` on a synthetic 10k-note vault.
+//!
+//! Each filter set is run through `run_query` and, for comparison, through
+//! the correlated `EXISTS` SQL the query builder used to generate.
+//!
+//! Run with `cargo bench -p core_storage --bench query`.
+
+use core_storage::{init_database, VaultRepository};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use shared_types::{FilterMatchMode, PropertyFilter, PropertyOperator, QueryRequest, QueryResultType};
+use sqlx::SqlitePool;
+use tokio::runtime::Runtime;
+
+const NOTE_COUNT: usize = 10_000;
+
+const STATUSES: [&str; 4] = ["active", "done", "waiting", "someday"];
+const PRIORITIES: [&str; 3] = ["high", "medium", "low"];
+const TOPICS: [&str; 6] = ["rust", "sqlite", "search", "design", "health", "travel"];
+
+/// Build a vault where every note has a handful of properties and tags
+/// spread over a small set of values, like a real vault's frontmatter.
+async fn synthetic_vault() -> VaultRepository {
+    let pool = SqlitePool::connect(":memory:").await.unwrap();
+    init_database(&pool).await.unwrap();
+
+    let mut tx = pool.begin().await.unwrap();
+    for i in 0..NOTE_COUNT {
+        let note_id: i64 = sqlx::query_scalar(
+            "INSERT INTO notes (path, title, hash, created_at, updated_at)
+             VALUES (?, ?, 'hash', datetime('now'), datetime('now'))
+             RETURNING id",
+        )
+        .bind(format!("folder{}/note{}.md", i % 20, i))
+        .bind(format!("Note {}", i))
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+
+        let topics = format!("{}, {}", TOPICS[i % TOPICS.len()], TOPICS[(i / 7) % TOPICS.len()]);
+        let mut properties = vec![
+            ("status", STATUSES[i % STATUSES.len()].to_string()),
+            ("priority", PRIORITIES[(i / 3) % PRIORITIES.len()].to_string()),
+            ("project", format!("project-{}", i % 100)),
+            ("due", format!("2024-{:02}-{:02}", i % 12 + 1, i % 28 + 1)),
+            ("topics", topics),
+        ];
+        // Sparse properties, as only some notes have them
+        if i % 10 == 0 {
+            properties.push(("archived", "true".to_string()));
+        }
+        for extra in 0..5 {
+            properties.push((["author", "source", "rating", "mood", "location"][extra], format!("value-{}", i % 50)));
+        }
+
+        for (sort_order, (key, value)) in properties.iter().enumerate() {
+            sqlx::query("INSERT INTO properties (note_id, key, value, type, sort_order) VALUES (?, ?, ?, 'text', ?)")
+                .bind(note_id)
+                .bind(key)
+                .bind(value)
+                .bind(sort_order as i64)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+
+        for tag in [TOPICS[i % TOPICS.len()], if i % 2 == 0 { "work" } else { "personal" }] {
+            sqlx::query("INSERT INTO tags (note_id, tag) VALUES (?, ?)")
+                .bind(note_id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+    }
+    tx.commit().await.unwrap();
+
+    sqlx::query("ANALYZE").execute(&pool).await.unwrap();
+    VaultRepository::new(pool)
+}
+
+fn filter(key: &str, operator: PropertyOperator, value: Option<&str>) -> PropertyFilter {
+    PropertyFilter {
+        key: key.to_string(),
+        operator,
+        value: value.map(|v| v.to_string()),
+    }
+}
+
+/// Filter sets with the correlated `EXISTS` SQL that used to select their
+/// notes, and its parameters.
+fn cases() -> Vec<(&'static str, Vec<PropertyFilter>, &'static str, Vec<&'static str>)> {
+    vec![
+        (
+            "equals",
+            vec![filter("project", PropertyOperator::Equals, Some("project-42"))],
+            "SELECT id FROM notes n WHERE EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?)",
+            vec!["project", "project-42"],
+        ),
+        (
+            "three_properties",
+            vec![
+                filter("status", PropertyOperator::Equals, Some("active")),
+                filter("priority", PropertyOperator::Equals, Some("high")),
+                filter("project", PropertyOperator::Equals, Some("project-8")),
+            ],
+            "SELECT id FROM notes n WHERE EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?) \
+             AND EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?) \
+             AND EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?)",
+            vec!["status", "active", "priority", "high", "project", "project-8"],
+        ),
+        (
+            "mixed",
+            vec![
+                filter("status", PropertyOperator::Equals, Some("waiting")),
+                filter("due", PropertyOperator::DateBefore, Some("2024-04-01")),
+                filter("archived", PropertyOperator::NotExists, None),
+                filter("_tags", PropertyOperator::Equals, Some("work")),
+            ],
+            "SELECT id FROM notes n WHERE EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?) \
+             AND EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND date(value) < date(?)) \
+             AND NOT EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ?) \
+             AND EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag = ?)",
+            vec!["status", "waiting", "due", "2024-04-01", "archived", "work"],
+        ),
+    ]
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let repo = rt.block_on(synthetic_vault());
+
+    let mut group = c.benchmark_group("run_query");
+    for (name, filters, legacy_sql, legacy_params) in cases() {
+        let request = QueryRequest {
+            filters,
+            match_mode: FilterMatchMode::All,
+            result_type: QueryResultType::Notes,
+            include_completed: false,
+            limit: Some(100),
+            sort: None,
+        };
+
+        group.bench_with_input(BenchmarkId::new("planned", name), &request, |b, request| {
+            b.to_async(&rt).iter(|| repo.run_query(request));
+        });
+
+        // Only selects the note IDs, which is where the time went
+        group.bench_with_input(BenchmarkId::new("correlated_exists", name), &legacy_sql, |b, sql| {
+            b.to_async(&rt).iter(|| async {
+                let mut query = sqlx::query_scalar::<_, i64>(sql);
+                for param in &legacy_params {
+                    query = query.bind(*param);
+                }
+                query.fetch_all(repo.pool()).await.unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries);
+criterion_main!(benches);
@@ -11,8 +11,8 @@ pub mod schema;
 pub mod repository;
 
 pub use repository::VaultRepository;
-pub use repository::VectorSearchResult;
-pub use repository::extract_content_preview;
+pub use repository::{AutocompleteTerm, ChunkEmbedding, VectorSearchResult, CSS_CLASSES_PROPERTY};
+pub use repository::{content_signature, extract_content_preview, mean_embedding, validate_timezone};
 pub use schema::init_database;
 
 use thiserror::Error;
@@ -27,6 +27,27 @@ pub enum StorageError {
 
     #[error("Note not found by path: {0}")]
     NoteNotFoundByPath(String),
+
+    #[error("Invalid time zone: {0}")]
+    InvalidTimezone(String),
+
+    #[error("Invalid tag: {0}")]
+    InvalidTag(String),
+
+    #[error("Invalid tag alias: {0}")]
+    InvalidTagAlias(String),
+
+    #[error("Invalid context name: {0}")]
+    InvalidContext(String),
+
+    #[error("Invalid date: {0}")]
+    InvalidDate(String),
+
+    #[error("Invalid date range: {0}")]
+    InvalidDateRange(String),
+
+    #[error("Invalid time range: {0}")]
+    InvalidTimeRange(String),
 }
 
 pub type Result<T> = std::result::Result<T, StorageError>;
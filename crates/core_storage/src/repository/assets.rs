@@ -0,0 +1,64 @@
+//! Attachment content hashes: which files in the vault have which content,
+//! so an attachment that is already stored is linked instead of copied.
+
+use crate::Result;
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Paths of the attachments recorded with this content hash, in path order.
+    pub async fn find_assets_by_hash(&self, hash: &str) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar::<_, String>("SELECT path FROM asset_hashes WHERE hash = ? ORDER BY path")
+            .bind(hash)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(paths)
+    }
+
+    /// Record the content hash of an attachment.
+    pub async fn record_asset(&self, path: &str, hash: &str, size: u64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO asset_hashes (path, hash, size) VALUES (?, ?, ?)
+            ON CONFLICT(path) DO UPDATE SET hash = excluded.hash, size = excluded.size
+            "#,
+        )
+        .bind(path)
+        .bind(hash)
+        .bind(size as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget an attachment, e.g. one deleted or changed since it was recorded.
+    pub async fn remove_asset(&self, path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM asset_hashes WHERE path = ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Replace all recorded hashes with `assets` as (path, hash, size), after
+    /// a scan of the vault.
+    #[instrument(skip(self, assets), fields(count = assets.len()))]
+    pub async fn replace_asset_hashes(&self, assets: &[(String, String, u64)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM asset_hashes").execute(&mut *tx).await?;
+        for (path, hash, size) in assets {
+            sqlx::query("INSERT OR REPLACE INTO asset_hashes (path, hash, size) VALUES (?, ?, ?)")
+                .bind(path)
+                .bind(hash)
+                .bind(*size as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        debug!("Recorded {} asset hashes", assets.len());
+        Ok(())
+    }
+}
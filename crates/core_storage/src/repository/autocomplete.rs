@@ -0,0 +1,73 @@
+//! Terms for the in-memory autocomplete index.
+
+use crate::Result;
+use shared_types::AutocompleteKind;
+
+use super::VaultRepository;
+
+/// A term of a note that autocomplete can suggest.
+#[derive(Debug, Clone)]
+pub struct AutocompleteTerm {
+    pub note_id: i64,
+    pub kind: AutocompleteKind,
+    /// The property key, for property values.
+    pub key: Option<String>,
+    pub text: String,
+}
+
+impl VaultRepository {
+    /// Get the autocomplete terms of the given notes, or of all notes for
+    /// None: titles, aliases, tags, property keys, property values (each
+    /// item of a list property), task contexts, and headings.
+    pub async fn get_autocomplete_terms(&self, note_ids: Option<&[i64]>) -> Result<Vec<AutocompleteTerm>> {
+        let filter = match note_ids {
+            Some([]) => return Ok(Vec::new()),
+            Some(ids) => format!("WHERE note_id IN ({})", vec!["?"; ids.len()].join(", ")),
+            None => String::new(),
+        };
+        let sql = format!(
+            r#"
+            SELECT note_id, kind, key, text FROM (
+                SELECT id AS note_id, 'title' AS kind, NULL AS key, title AS text FROM notes WHERE title IS NOT NULL
+                UNION ALL SELECT note_id, 'alias', NULL, alias FROM aliases
+                UNION ALL SELECT note_id, 'tag', NULL, tag FROM tags
+                UNION ALL SELECT note_id, 'property_key', NULL, key FROM properties
+                UNION ALL
+                    SELECT p.note_id, 'property_value', p.key, p.value FROM properties p
+                    WHERE p.value IS NOT NULL AND p.value != ''
+                      AND NOT EXISTS (SELECT 1 FROM property_items i WHERE i.property_id = p.id)
+                UNION ALL
+                    SELECT p.note_id, 'property_value', p.key, i.item FROM property_items i
+                    JOIN properties p ON p.id = i.property_id
+                UNION ALL SELECT note_id, 'context', NULL, context FROM todos WHERE context IS NOT NULL
+                UNION ALL SELECT note_id, 'heading', NULL, text FROM note_headings
+            )
+            {}
+            "#,
+            filter
+        );
+
+        let mut query = sqlx::query_as::<_, (i64, String, Option<String>, String)>(&sql);
+        for id in note_ids.unwrap_or_default() {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(note_id, kind, key, text)| {
+                let kind = match kind.as_str() {
+                    "title" => AutocompleteKind::Title,
+                    "alias" => AutocompleteKind::Alias,
+                    "tag" => AutocompleteKind::Tag,
+                    "property_key" => AutocompleteKind::PropertyKey,
+                    "property_value" => AutocompleteKind::PropertyValue,
+                    "context" => AutocompleteKind::Context,
+                    "heading" => AutocompleteKind::Heading,
+                    _ => return None,
+                };
+                Some(AutocompleteTerm { note_id, kind, key, text })
+            })
+            .collect())
+    }
+}
@@ -1,21 +1,38 @@
 //! Backlink tracking operations.
 
 use crate::Result;
-use shared_types::{BacklinkDto, NoteListItem};
+use shared_types::{BacklinkDto, GraphEdge, GraphNode, GraphScope, LinkGraph, NoteLinkCount, NoteListItem};
+use std::collections::HashMap;
 
-use super::VaultRepository;
+use super::{escape_like, id_array, VaultRepository, MAX_BOUND_IDS};
 
 impl VaultRepository {
     /// Replace all backlinks originating from a note.
+    ///
+    /// The targets are stored as written; here they only resolve to a note
+    /// by exact path. `set_backlinks` replaces the links once the targets
+    /// are resolved by name, title and alias as well.
     pub async fn replace_backlinks(&self, from_note_id: i64, to_paths: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
         // Delete existing backlinks from this note
         sqlx::query("DELETE FROM backlinks WHERE from_note_id = ?")
             .bind(from_note_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM link_targets WHERE from_note_id = ?")
+            .bind(from_note_id)
+            .execute(&mut *tx)
             .await?;
 
-        // Insert new backlinks (only if target note exists)
         for path in to_paths {
+            sqlx::query("INSERT INTO link_targets (from_note_id, target) VALUES (?, ?)")
+                .bind(from_note_id)
+                .bind(path)
+                .execute(&mut *tx)
+                .await?;
+
+            // Insert new backlinks (only if target note exists)
             sqlx::query(
                 r#"
                 INSERT INTO backlinks (from_note_id, to_note_id)
@@ -25,10 +42,115 @@ impl VaultRepository {
             .bind(from_note_id)
             .bind(path)
             .bind(path)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
         }
 
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Mark the link targets of a note that are embedded (`![[...]]`), after
+    /// `replace_backlinks` stored them.
+    pub async fn mark_embed_targets(&self, from_note_id: i64, embeds: &[String]) -> Result<()> {
+        if embeds.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for target in embeds {
+            sqlx::query("UPDATE link_targets SET embed = 1 WHERE from_note_id = ? AND target = ?")
+                .bind(from_note_id)
+                .bind(target)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The embedded link targets of the notes, as (from_note_id, target)
+    /// pairs in the order they were written.
+    pub async fn list_embed_targets(&self, note_ids: &[i64]) -> Result<Vec<(i64, String)>> {
+        let mut targets = Vec::new();
+        for batch in note_ids.chunks(MAX_BOUND_IDS) {
+            let sql = format!(
+                "SELECT from_note_id, target FROM link_targets WHERE embed = 1 AND from_note_id IN ({}) ORDER BY id",
+                vec!["?"; batch.len()].join(", ")
+            );
+            let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+            for id in batch {
+                query = query.bind(id);
+            }
+            targets.extend(query.fetch_all(&self.read_pool).await?);
+        }
+        Ok(targets)
+    }
+
+    /// The stored link targets of the notes (all notes if None), as
+    /// (from_note_id, target) pairs.
+    pub async fn list_link_targets(&self, note_ids: Option<&[i64]>) -> Result<Vec<(i64, String)>> {
+        let Some(note_ids) = note_ids else {
+            return Ok(sqlx::query_as("SELECT from_note_id, target FROM link_targets ORDER BY id")
+                .fetch_all(&self.pool)
+                .await?);
+        };
+        if note_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT from_note_id, target FROM link_targets WHERE from_note_id IN ({}) ORDER BY id",
+            vec!["?"; note_ids.len()].join(", ")
+        );
+        let mut query = sqlx::query_as(&sql);
+        for id in note_ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    /// The resolved links of the notes (all notes if None), as
+    /// (from_note_id, to_note_id) pairs.
+    pub async fn list_backlink_pairs(&self, note_ids: Option<&[i64]>) -> Result<Vec<(i64, i64)>> {
+        let Some(note_ids) = note_ids else {
+            return Ok(sqlx::query_as("SELECT from_note_id, to_note_id FROM backlinks ORDER BY id")
+                .fetch_all(&self.pool)
+                .await?);
+        };
+        if note_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT from_note_id, to_note_id FROM backlinks WHERE from_note_id IN ({}) ORDER BY id",
+            vec!["?"; note_ids.len()].join(", ")
+        );
+        let mut query = sqlx::query_as(&sql);
+        for id in note_ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    /// Replace the resolved links of notes, given as (from_note_id, the
+    /// note IDs its links resolve to), in one transaction.
+    pub async fn set_backlinks(&self, links: &[(i64, Vec<i64>)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (from_note_id, to_note_ids) in links {
+            sqlx::query("DELETE FROM backlinks WHERE from_note_id = ?")
+                .bind(from_note_id)
+                .execute(&mut *tx)
+                .await?;
+            for to_note_id in to_note_ids {
+                sqlx::query("INSERT INTO backlinks (from_note_id, to_note_id) VALUES (?, ?)")
+                    .bind(from_note_id)
+                    .bind(to_note_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
         Ok(())
     }
 
@@ -81,4 +203,163 @@ impl VaultRepository {
             })
             .collect())
     }
+
+    /// Notes with no links in or out, by path.
+    pub async fn get_orphan_notes(&self) -> Result<Vec<NoteLinkCount>> {
+        self.notes_with_link_counts("WHERE inbound = 0 AND outbound = 0", "n.path", None)
+            .await
+    }
+
+    /// Notes that link to no other note, most linked first.
+    pub async fn get_dead_end_notes(&self) -> Result<Vec<NoteLinkCount>> {
+        self.notes_with_link_counts("WHERE outbound = 0", "inbound DESC, n.path", None)
+            .await
+    }
+
+    /// The `limit` notes with the most links to them.
+    pub async fn get_hub_notes(&self, limit: i64) -> Result<Vec<NoteLinkCount>> {
+        self.notes_with_link_counts("WHERE inbound > 0", "inbound DESC, outbound DESC, n.path", Some(limit))
+            .await
+    }
+
+    /// Notes with their link counts, filtered and ordered by SQL over the
+    /// note alias `n` and the `inbound`/`outbound` counts. Links from a note
+    /// to itself are not counted.
+    async fn notes_with_link_counts(
+        &self,
+        filter: &str,
+        order: &str,
+        limit: Option<i64>,
+    ) -> Result<Vec<NoteLinkCount>> {
+        let sql = format!(
+            r#"
+            SELECT * FROM (
+                SELECT n.id, n.path, n.title, n.pinned,
+                    (SELECT COUNT(*) FROM backlinks b WHERE b.to_note_id = n.id AND b.from_note_id != n.id) AS inbound,
+                    (SELECT COUNT(*) FROM backlinks b WHERE b.from_note_id = n.id AND b.to_note_id != n.id) AS outbound
+                FROM notes n
+            ) n
+            {}
+            ORDER BY {}
+            LIMIT ?
+            "#,
+            filter, order
+        );
+
+        let rows = sqlx::query_as::<_, (i64, String, Option<String>, i32, i64, i64)>(&sql)
+            .bind(limit.unwrap_or(-1))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, path, title, pinned, inbound, outbound)| NoteLinkCount {
+                note: NoteListItem {
+                    id,
+                    path,
+                    title,
+                    pinned: pinned != 0,
+                },
+                inbound,
+                outbound,
+            })
+            .collect())
+    }
+
+    /// Count links from other notes into the given notes, i.e. the links
+    /// that would stop resolving if these notes were deleted. Links between
+    /// the notes themselves are not counted.
+    pub async fn count_links_into(&self, note_ids: &[i64]) -> Result<i64> {
+        if note_ids.is_empty() {
+            return Ok(0);
+        }
+
+        // The targets are counted in batches; the links between the notes
+        // are left out with all of them at once
+        let excluded = id_array(note_ids);
+        let mut count = 0;
+        for batch in note_ids.chunks(MAX_BOUND_IDS) {
+            let sql = format!(
+                "SELECT COUNT(*) FROM backlinks WHERE to_note_id IN ({}) AND from_note_id NOT IN (SELECT value FROM json_each(?))",
+                vec!["?"; batch.len()].join(", ")
+            );
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            for id in batch {
+                query = query.bind(id);
+            }
+            count += query.bind(&excluded).fetch_one(&self.pool).await?;
+        }
+        Ok(count)
+    }
+
+    /// The notes in `scope` and the resolved links between them.
+    pub async fn get_link_graph(&self, scope: &GraphScope) -> Result<LinkGraph> {
+        // The IDs of the notes in scope, shared by the queries below
+        let mut in_scope = "SELECT n.id FROM notes n WHERE 1=1".to_string();
+        let mut binds = Vec::new();
+        if let Some(folder) = scope.folder.as_deref().map(|f| f.trim_matches('/')).filter(|f| !f.is_empty()) {
+            in_scope.push_str(r" AND n.path LIKE ? ESCAPE '\'");
+            binds.push(format!("{}/%", escape_like(folder)));
+        }
+        if let Some(tag) = scope.tag.as_deref().map(|t| t.trim_start_matches('#')).filter(|t| !t.is_empty()) {
+            // Notes with an alias of the tag are in scope too
+            let tag = self.canonical_tag(tag).await?;
+            in_scope.push_str(
+                r" AND EXISTS (SELECT 1 FROM tags t LEFT JOIN tag_meta m ON m.tag = t.tag WHERE t.note_id = n.id AND (COALESCE(m.alias_of, t.tag) = ? OR COALESCE(m.alias_of, t.tag) LIKE ? ESCAPE '\'))",
+            );
+            let children = format!("{}/%", escape_like(&tag));
+            binds.push(tag);
+            binds.push(children);
+        }
+
+        let sql = format!("SELECT id, path, title FROM notes WHERE id IN ({}) ORDER BY path", in_scope);
+        let mut query = sqlx::query_as::<_, (i64, String, Option<String>)>(&sql);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let notes = query.fetch_all(&self.read_pool).await?;
+
+        let sql = format!("SELECT note_id, tag FROM tags WHERE note_id IN ({}) ORDER BY tag", in_scope);
+        let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for (note_id, tag) in query.fetch_all(&self.read_pool).await? {
+            tags.entry(note_id).or_default().push(tag);
+        }
+
+        let sql = format!(
+            r#"
+            WITH in_scope AS ({})
+            SELECT from_note_id, to_note_id, COUNT(*) FROM backlinks
+            WHERE from_note_id IN in_scope AND to_note_id IN in_scope
+            GROUP BY from_note_id, to_note_id
+            ORDER BY from_note_id, to_note_id
+            "#,
+            in_scope
+        );
+        let mut query = sqlx::query_as::<_, (i64, i64, i64)>(&sql);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let edges = query
+            .fetch_all(&self.read_pool)
+            .await?
+            .into_iter()
+            .map(|(source, target, weight)| GraphEdge { source, target, weight })
+            .collect();
+
+        let nodes = notes
+            .into_iter()
+            .map(|(id, path, title)| GraphNode {
+                id,
+                path,
+                title,
+                tags: tags.remove(&id).unwrap_or_default(),
+            })
+            .collect();
+
+        Ok(LinkGraph { nodes, edges })
+    }
 }
@@ -0,0 +1,254 @@
+//! Focus sessions on schedule blocks and planned versus actual reports.
+
+use crate::Result;
+use chrono::{NaiveDate, NaiveDateTime};
+use shared_types::{BlockSessionDto, ContextPlanVsActual, PlanVsActualReport};
+use std::collections::HashMap;
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+/// Storage format for session timestamps (local time).
+const SESSION_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+type SessionRow = (i64, i64, String, String, Option<String>);
+
+impl VaultRepository {
+    /// Start a focus session on a block at `now`.
+    ///
+    /// Only one session runs at a time: open sessions on other blocks are
+    /// ended. If the block already has a running session, it is returned.
+    /// Returns None if the block doesn't exist.
+    #[instrument(skip(self))]
+    pub async fn start_block_session(
+        &self,
+        block_id: i64,
+        now: NaiveDateTime,
+    ) -> Result<Option<BlockSessionDto>> {
+        if self.get_schedule_block(block_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        if let Some(row) = self.get_open_block_session(block_id).await? {
+            return Ok(Some(session_from_row(row, now)));
+        }
+
+        let now_str = now.format(SESSION_TIME_FORMAT).to_string();
+
+        sqlx::query("UPDATE block_sessions SET ended_at = ? WHERE ended_at IS NULL")
+            .bind(&now_str)
+            .execute(&self.pool)
+            .await?;
+
+        let date = now.date().to_string();
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO block_sessions (block_id, date, started_at) VALUES (?, ?, ?) RETURNING id",
+        )
+        .bind(block_id)
+        .bind(&date)
+        .bind(&now_str)
+        .fetch_one(&self.pool)
+        .await?;
+
+        debug!("Started session {} on block {}", id, block_id);
+        Ok(Some(session_from_row((id, block_id, date, now_str, None), now)))
+    }
+
+    /// End the running focus session on a block at `now`.
+    ///
+    /// Returns None if the block has no running session.
+    #[instrument(skip(self))]
+    pub async fn end_block_session(
+        &self,
+        block_id: i64,
+        now: NaiveDateTime,
+    ) -> Result<Option<BlockSessionDto>> {
+        let Some((id, block_id, date, started_at, _)) = self.get_open_block_session(block_id).await? else {
+            return Ok(None);
+        };
+
+        let now_str = now.format(SESSION_TIME_FORMAT).to_string();
+        sqlx::query("UPDATE block_sessions SET ended_at = ? WHERE id = ?")
+            .bind(&now_str)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Ended session {} on block {}", id, block_id);
+        Ok(Some(session_from_row((id, block_id, date, started_at, Some(now_str)), now)))
+    }
+
+    /// Compare planned block time with recorded session time per context.
+    ///
+    /// Planned time comes from the schedule blocks in the range (recurring
+    /// blocks expanded, calendar overlay events excluded). Actual time comes
+    /// from sessions started in the range; running sessions count up to `now`.
+    pub async fn get_plan_vs_actual(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        now: NaiveDateTime,
+    ) -> Result<PlanVsActualReport> {
+        let blocks = self
+            .get_schedule_blocks_for_range(&start_date.to_string(), &end_date.to_string())
+            .await?;
+        let planned: Vec<(Option<String>, i64)> = blocks
+            .into_iter()
+            .filter(|b| !b.read_only)
+            .map(|b| (b.context, (b.end_time - b.start_time).num_minutes().max(0)))
+            .collect();
+
+        let sessions = sqlx::query_as::<_, (String, Option<String>, Option<String>)>(
+            r#"
+            SELECT s.started_at, s.ended_at, b.context
+            FROM block_sessions s
+            JOIN schedule_blocks b ON b.id = s.block_id
+            WHERE s.date >= ? AND s.date <= ?
+            "#,
+        )
+        .bind(start_date.to_string())
+        .bind(end_date.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        let actual: Vec<(Option<String>, i64)> = sessions
+            .into_iter()
+            .map(|(started_at, ended_at, context)| {
+                (context, session_minutes(&started_at, ended_at.as_deref(), now))
+            })
+            .collect();
+
+        let contexts = compare_by_context(&planned, &actual);
+        Ok(PlanVsActualReport {
+            start_date,
+            end_date,
+            total_planned_minutes: contexts.iter().map(|c| c.planned_minutes).sum(),
+            total_actual_minutes: contexts.iter().map(|c| c.actual_minutes).sum(),
+            contexts,
+        })
+    }
+
+    /// Get the running session of a block, if any.
+    async fn get_open_block_session(&self, block_id: i64) -> Result<Option<SessionRow>> {
+        Ok(sqlx::query_as::<_, SessionRow>(
+            "SELECT id, block_id, date, started_at, ended_at FROM block_sessions WHERE block_id = ? AND ended_at IS NULL",
+        )
+        .bind(block_id)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+}
+
+/// Build a BlockSessionDto from a row.
+fn session_from_row(
+    (id, block_id, date, started_at, ended_at): SessionRow,
+    now: NaiveDateTime,
+) -> BlockSessionDto {
+    BlockSessionDto {
+        id,
+        block_id,
+        date: date.parse().unwrap_or_else(|_| now.date()),
+        minutes: session_minutes(&started_at, ended_at.as_deref(), now),
+        started_at,
+        ended_at,
+    }
+}
+
+/// Length of a session in minutes; running sessions last until `now`.
+fn session_minutes(started_at: &str, ended_at: Option<&str>, now: NaiveDateTime) -> i64 {
+    let Ok(start) = NaiveDateTime::parse_from_str(started_at, SESSION_TIME_FORMAT) else {
+        return 0;
+    };
+    let end = ended_at
+        .and_then(|e| NaiveDateTime::parse_from_str(e, SESSION_TIME_FORMAT).ok())
+        .unwrap_or(now);
+    (end - start).num_minutes().max(0)
+}
+
+/// Sum planned and actual minutes per context, ordered by planned minutes (most first).
+fn compare_by_context(
+    planned: &[(Option<String>, i64)],
+    actual: &[(Option<String>, i64)],
+) -> Vec<ContextPlanVsActual> {
+    let mut by_context: HashMap<Option<String>, ContextPlanVsActual> = HashMap::new();
+
+    for (context, minutes) in planned {
+        let e = context_entry(&mut by_context, context);
+        e.planned_minutes += minutes;
+        e.block_count += 1;
+    }
+    for (context, minutes) in actual {
+        let e = context_entry(&mut by_context, context);
+        e.actual_minutes += minutes;
+        e.session_count += 1;
+    }
+
+    let mut contexts: Vec<ContextPlanVsActual> = by_context
+        .into_values()
+        .map(|mut c| {
+            c.difference_minutes = c.actual_minutes - c.planned_minutes;
+            c
+        })
+        .collect();
+    contexts.sort_by(|a, b| {
+        b.planned_minutes
+            .cmp(&a.planned_minutes)
+            .then_with(|| a.context.is_none().cmp(&b.context.is_none()))
+            .then_with(|| a.context.cmp(&b.context))
+    });
+    contexts
+}
+
+/// Get or create the report row for a context.
+fn context_entry<'a>(
+    by_context: &'a mut HashMap<Option<String>, ContextPlanVsActual>,
+    context: &Option<String>,
+) -> &'a mut ContextPlanVsActual {
+    by_context
+        .entry(context.clone())
+        .or_insert_with(|| ContextPlanVsActual {
+            context: context.clone(),
+            planned_minutes: 0,
+            actual_minutes: 0,
+            difference_minutes: 0,
+            block_count: 0,
+            session_count: 0,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, SESSION_TIME_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_session_minutes() {
+        let now = at("2024-01-15T12:00:00");
+        assert_eq!(session_minutes("2024-01-15T09:00:00", Some("2024-01-15T09:45:30"), now), 45);
+        assert_eq!(session_minutes("2024-01-15T11:30:00", None, now), 30);
+        assert_eq!(session_minutes("invalid", None, now), 0);
+    }
+
+    #[test]
+    fn test_compare_by_context() {
+        let work = Some("work".to_string());
+        let home = Some("home".to_string());
+        let planned = vec![(work.clone(), 60), (work.clone(), 30), (None, 15)];
+        let actual = vec![(work.clone(), 50), (home.clone(), 20)];
+
+        let contexts = compare_by_context(&planned, &actual);
+        let names: Vec<_> = contexts.iter().map(|c| c.context.clone()).collect();
+        assert_eq!(names, vec![work, None, home]);
+
+        assert_eq!(contexts[0].planned_minutes, 90);
+        assert_eq!(contexts[0].actual_minutes, 50);
+        assert_eq!(contexts[0].difference_minutes, -40);
+        assert_eq!(contexts[0].block_count, 2);
+        assert_eq!(contexts[0].session_count, 1);
+
+        // Unplanned time shows up as a positive difference
+        assert_eq!(contexts[2].difference_minutes, 20);
+    }
+}
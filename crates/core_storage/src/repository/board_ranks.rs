@@ -0,0 +1,61 @@
+//! Manual card order on query Kanban boards.
+//!
+//! Each board (keyed by the note or block showing it) stores a fractional
+//! rank per result it has seen moved, so a card can be put between two
+//! others by writing only its own rank.
+
+use crate::Result;
+use std::collections::HashMap;
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// The ranks of the items on a board, by item reference.
+    pub async fn get_board_ranks(&self, board_key: &str) -> Result<HashMap<String, f64>> {
+        let rows = sqlx::query_as::<_, (String, f64)>("SELECT item_ref, rank FROM board_ranks WHERE board_key = ?")
+            .bind(board_key)
+            .fetch_all(&self.read_pool)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Set the rank of an item on a board.
+    pub async fn set_board_rank(&self, board_key: &str, item_ref: &str, rank: f64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO board_ranks (board_key, item_ref, rank) VALUES (?, ?, ?)
+            ON CONFLICT(board_key, item_ref) DO UPDATE SET rank = excluded.rank
+            "#,
+        )
+        .bind(board_key)
+        .bind(item_ref)
+        .bind(rank)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Renumber the ranks of a board to 1, 2, 3, ... keeping their order,
+    /// once repeated moves between the same two cards leave no room.
+    pub async fn rebalance_board_ranks(&self, board_key: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let refs = sqlx::query_scalar::<_, String>(
+            "SELECT item_ref FROM board_ranks WHERE board_key = ? ORDER BY rank, item_ref",
+        )
+        .bind(board_key)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (i, item_ref) in refs.iter().enumerate() {
+            sqlx::query("UPDATE board_ranks SET rank = ? WHERE board_key = ? AND item_ref = ?")
+                .bind((i + 1) as f64)
+                .bind(board_key)
+                .bind(item_ref)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+//! Calendar subscriptions: external ICS feeds shown as read-only schedule blocks.
+
+use crate::Result;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use core_index::ParsedCalendarEvent;
+use shared_types::{CalendarSubscriptionDto, CreateCalendarSubscriptionRequest, ScheduleBlockDto};
+use tracing::{debug, instrument, warn};
+
+use super::schedule::expand_rrule;
+use super::VaultRepository;
+
+/// Refresh interval used when a subscription doesn't specify one.
+const DEFAULT_REFRESH_MINUTES: i64 = 60;
+
+type SubscriptionRow = (i64, String, String, Option<String>, i64, Option<String>, Option<String>, i64);
+
+type CalendarEventRow = (
+    i64,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+impl VaultRepository {
+    /// Subscribe to an external calendar feed.
+    #[instrument(skip(self))]
+    pub async fn create_calendar_subscription(
+        &self,
+        request: &CreateCalendarSubscriptionRequest,
+    ) -> Result<i64> {
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO calendar_subscriptions (name, url, color, refresh_minutes, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(&request.name)
+        .bind(&request.url)
+        .bind(&request.color)
+        .bind(request.refresh_minutes.unwrap_or(DEFAULT_REFRESH_MINUTES).max(1))
+        .bind(Utc::now().to_rfc3339())
+        .fetch_one(&self.pool)
+        .await?;
+
+        debug!("Created calendar subscription {} with id {}", request.name, id);
+        Ok(id)
+    }
+
+    /// List all calendar subscriptions.
+    pub async fn list_calendar_subscriptions(&self) -> Result<Vec<CalendarSubscriptionDto>> {
+        let rows = sqlx::query_as::<_, SubscriptionRow>(
+            r#"
+            SELECT s.id, s.name, s.url, s.color, s.refresh_minutes, s.last_fetched, s.last_error,
+                   (SELECT COUNT(*) FROM calendar_events e WHERE e.subscription_id = s.id)
+            FROM calendar_subscriptions s
+            ORDER BY s.name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, name, url, color, refresh_minutes, last_fetched, last_error, event_count)| {
+                    CalendarSubscriptionDto {
+                        id,
+                        name,
+                        url,
+                        color,
+                        refresh_minutes,
+                        last_fetched,
+                        last_error,
+                        event_count,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// List subscriptions whose refresh interval has elapsed as of `now`.
+    pub async fn get_due_calendar_subscriptions(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<CalendarSubscriptionDto>> {
+        Ok(self
+            .list_calendar_subscriptions()
+            .await?
+            .into_iter()
+            .filter(|s| {
+                let last = s
+                    .last_fetched
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+                match last {
+                    Some(last) => now - last.with_timezone(&Utc) >= Duration::minutes(s.refresh_minutes),
+                    None => true,
+                }
+            })
+            .collect())
+    }
+
+    /// Delete a calendar subscription and its events.
+    #[instrument(skip(self))]
+    pub async fn delete_calendar_subscription(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM calendar_events WHERE subscription_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("DELETE FROM calendar_subscriptions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Deleted calendar subscription {}", id);
+        Ok(())
+    }
+
+    /// Replace the stored events of a subscription with freshly fetched ones
+    /// and mark the subscription as fetched, all at once.
+    ///
+    /// Time zones that aren't valid IANA names (e.g. Windows zone names) are
+    /// dropped, so those events are shown in local time.
+    #[instrument(skip(self, events), fields(count = events.len()))]
+    pub async fn replace_calendar_events(
+        &self,
+        subscription_id: i64,
+        events: &[ParsedCalendarEvent],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM calendar_events WHERE subscription_id = ?")
+            .bind(subscription_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for event in events {
+            let timezone = event
+                .timezone
+                .as_deref()
+                .filter(|tz| tz.parse::<chrono_tz::Tz>().is_ok());
+
+            sqlx::query(
+                r#"
+                INSERT INTO calendar_events
+                    (subscription_id, uid, date, start_time, end_time, all_day, summary, location, rrule, timezone)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(subscription_id)
+            .bind(&event.uid)
+            .bind(event.date.to_string())
+            .bind(event.start_time.format("%H:%M:%S").to_string())
+            .bind(event.end_time.format("%H:%M:%S").to_string())
+            .bind(event.all_day)
+            .bind(&event.summary)
+            .bind(&event.location)
+            .bind(&event.rrule)
+            .bind(timezone)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query("UPDATE calendar_subscriptions SET last_fetched = ?, last_error = NULL WHERE id = ?")
+            .bind(Utc::now().to_rfc3339())
+            .bind(subscription_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        debug!("Stored {} events for calendar subscription {}", events.len(), subscription_id);
+        Ok(())
+    }
+
+    /// Record a fetch attempt. Stored events are kept when the fetch failed.
+    pub async fn set_calendar_subscription_status(
+        &self,
+        subscription_id: i64,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE calendar_subscriptions SET last_fetched = ?, last_error = ? WHERE id = ?")
+            .bind(Utc::now().to_rfc3339())
+            .bind(error)
+            .bind(subscription_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Get subscribed calendar events in a date range as read-only schedule
+    /// blocks. They have no block id, only their own key.
+    pub(super) async fn get_calendar_overlay_blocks(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<ScheduleBlockDto>> {
+        let rows = sqlx::query_as::<_, CalendarEventRow>(
+            r#"
+            SELECT e.id, e.date, e.start_time, e.end_time, e.summary, e.rrule, e.timezone, s.color
+            FROM calendar_events e
+            JOIN calendar_subscriptions s ON s.id = e.subscription_id
+            WHERE e.date <= ? AND ((e.rrule IS NOT NULL AND e.rrule != '') OR e.date >= ?)
+            "#,
+        )
+        .bind(end_date)
+        .bind(start_date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let (Ok(start), Ok(end)) = (start_date.parse::<NaiveDate>(), end_date.parse::<NaiveDate>()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut blocks = Vec::new();
+        for (id, date, start_time, end_time, summary, rrule, timezone, color) in rows {
+            let (Ok(date), Ok(start_time), Ok(end_time)) = (
+                date.parse::<NaiveDate>(),
+                start_time.parse::<NaiveTime>(),
+                end_time.parse::<NaiveTime>(),
+            ) else {
+                continue;
+            };
+
+            let dates = match rrule.as_deref().filter(|r| !r.is_empty()) {
+                Some(rule) => match expand_rrule(rule, date, start_time, timezone.as_deref(), start, end) {
+                    Ok(dates) => dates,
+                    Err(e) => {
+                        warn!("Failed to expand rrule for calendar event {}: {}", id, e);
+                        vec![date]
+                    }
+                },
+                None => vec![date],
+            };
+
+            for occ_date in dates.into_iter().filter(|d| *d >= start && *d <= end) {
+                blocks.push(ScheduleBlockDto {
+                    id: 0,
+                    key: format!("calendar:{}", id),
+                    note_id: None,
+                    date: occ_date,
+                    start_time,
+                    end_time,
+                    label: summary.clone(),
+                    color: color.clone(),
+                    context: None,
+                    rrule: rrule.clone(),
+                    timezone: timezone.clone(),
+                    is_occurrence: occ_date != date,
+                    read_only: true,
+                });
+            }
+        }
+
+        Ok(blocks)
+    }
+}
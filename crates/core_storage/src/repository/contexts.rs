@@ -0,0 +1,109 @@
+//! GTD context registry.
+//!
+//! Task contexts (`@home`, `@phone`) are free text in notes. Registering a
+//! context gives it an icon, description and location, and lets the linter
+//! flag contexts that aren't registered (typos like `@phnoe`). Names match
+//! case-insensitively.
+
+use crate::{Result, StorageError};
+use shared_types::{ContextDto, ContextMeta};
+
+use super::VaultRepository;
+
+type ContextRow = (String, Option<String>, Option<String>, Option<String>, i64, bool);
+
+impl VaultRepository {
+    /// List registered contexts and the contexts used by tasks, with the
+    /// number of open tasks for each, most open tasks first.
+    pub async fn list_contexts(&self) -> Result<Vec<ContextDto>> {
+        let rows = sqlx::query_as::<_, ContextRow>(
+            r#"
+            SELECT name, icon, description, location, open_tasks, registered FROM (
+                SELECT c.name, c.icon, c.description, c.location,
+                    (SELECT COUNT(*) FROM todos t WHERE t.context = c.name COLLATE NOCASE AND t.completed = 0) AS open_tasks,
+                    1 AS registered
+                FROM contexts c
+                UNION ALL
+                SELECT t.context, NULL, NULL, NULL, SUM(t.completed = 0), 0
+                FROM todos t
+                WHERE t.context IS NOT NULL
+                  AND NOT EXISTS (SELECT 1 FROM contexts c WHERE c.name = t.context COLLATE NOCASE)
+                GROUP BY t.context
+            )
+            ORDER BY open_tasks DESC, name COLLATE NOCASE
+            "#,
+        )
+        .fetch_all(self.read_pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, icon, description, location, open_tasks, registered)| ContextDto {
+                name,
+                icon,
+                description,
+                location,
+                open_tasks,
+                registered,
+            })
+            .collect())
+    }
+
+    /// Get the registered contexts, by name.
+    pub async fn list_registered_contexts(&self) -> Result<Vec<ContextMeta>> {
+        let rows = sqlx::query_as::<_, (String, Option<String>, Option<String>, Option<String>)>(
+            "SELECT name, icon, description, location FROM contexts ORDER BY name",
+        )
+        .fetch_all(self.read_pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, icon, description, location)| ContextMeta {
+                name,
+                icon,
+                description,
+                location,
+            })
+            .collect())
+    }
+
+    /// Register a context or update its metadata. A leading `@` is dropped;
+    /// the name must be usable as a context in a task (a letter followed by
+    /// letters, digits, `_` or `-`).
+    pub async fn set_context(&self, context: &ContextMeta) -> Result<()> {
+        let name = context.name.trim().trim_start_matches('@');
+        let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(StorageError::InvalidContext(context.name.clone()));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO contexts (name, icon, description, location) VALUES (?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                name = excluded.name,
+                icon = excluded.icon,
+                description = excluded.description,
+                location = excluded.location
+            "#,
+        )
+        .bind(name)
+        .bind(&context.icon)
+        .bind(&context.description)
+        .bind(&context.location)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove a context from the registry. Tasks using it keep it.
+    pub async fn delete_context(&self, name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM contexts WHERE name = ?")
+            .bind(name.trim().trim_start_matches('@'))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+//! Change counters of tables, so in-memory caches can tell when the data
+//! they were built from changed - including changes made in place, which
+//! row counts and maximum ids don't show.
+//!
+//! Triggers count the changes to `notes`, `properties`, `todos` and
+//! `board_ranks` row by row. `tags`, `tag_meta` and `property_items` are
+//! written many rows at a time, so their writes call `bump_data_version`
+//! once per statement instead. Rows removed with their note or property
+//! are counted in `notes` or `properties`.
+
+use crate::Result;
+use sqlx::SqliteExecutor;
+
+use super::VaultRepository;
+
+/// Count a change to `table` (`tags`, `tag_meta` or `property_items`).
+pub(super) async fn bump_data_version<'e>(executor: impl SqliteExecutor<'e>, table: &str) -> Result<()> {
+    sqlx::query("UPDATE data_versions SET version = version + 1 WHERE name = ?")
+        .bind(table)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+impl VaultRepository {
+    /// A fingerprint of the given versioned tables that changes whenever
+    /// any of their rows does.
+    pub async fn get_data_fingerprint(&self, tables: &[&str]) -> Result<String> {
+        let mut fingerprint = Vec::with_capacity(tables.len());
+        for table in tables {
+            let version = sqlx::query_scalar::<_, i64>("SELECT version FROM data_versions WHERE name = ?")
+                .bind(table)
+                .fetch_optional(&self.pool)
+                .await?;
+            fingerprint.push(version.unwrap_or_default().to_string());
+        }
+        Ok(fingerprint.join(":"))
+    }
+}
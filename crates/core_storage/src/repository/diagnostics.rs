@@ -0,0 +1,35 @@
+//! Database statistics for diagnostics bundles.
+
+use crate::Result;
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Row counts of the tables (SQLite's own excluded), by table name.
+    pub async fn table_row_counts(&self) -> Result<Vec<(String, i64)>> {
+        let tables = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let mut counts = Vec::with_capacity(tables.len());
+        for table in tables {
+            let sql = format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\""));
+            let count = sqlx::query_scalar::<_, i64>(&sql).fetch_one(&self.read_pool).await?;
+            counts.push((table, count));
+        }
+        Ok(counts)
+    }
+
+    /// The `CREATE` statements of the schema's tables, indexes and
+    /// triggers, by name.
+    pub async fn schema_statements(&self) -> Result<Vec<String>> {
+        let statements = sqlx::query_scalar::<_, String>(
+            "SELECT sql FROM sqlite_master WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+        Ok(statements)
+    }
+}
@@ -0,0 +1,119 @@
+//! Serendipity lookups: random notes and notes from this day in past years.
+
+use crate::Result;
+use chrono::{Datelike, NaiveDate};
+use shared_types::{NoteListItem, OnThisDayNote, RandomNoteFilter};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Pick a random note, optionally restricted to a folder and/or tag.
+    /// The tag filter takes tag aliases into account.
+    ///
+    /// Returns None if no note matches.
+    pub async fn get_random_note(&self, filter: &RandomNoteFilter) -> Result<Option<NoteListItem>> {
+        let folder = filter
+            .folder
+            .as_deref()
+            .map(|f| f.trim_matches('/'))
+            .filter(|f| !f.is_empty())
+            .map(|f| format!("{}/", f));
+        let tag = match filter.tag.as_deref().map(|t| t.trim().trim_start_matches('#')) {
+            Some(tag) if !tag.is_empty() => Some(self.canonical_tag(tag).await?),
+            _ => None,
+        };
+        let tag = tag.as_deref();
+
+        let row = sqlx::query_as::<_, (i64, String, Option<String>, i32)>(
+            r#"
+            SELECT n.id, n.path, n.title, n.pinned
+            FROM notes n
+            WHERE (? IS NULL OR substr(n.path, 1, length(?)) = ?)
+              AND (? IS NULL OR EXISTS (
+                  SELECT 1 FROM tags t LEFT JOIN tag_meta m ON m.tag = t.tag
+                  WHERE t.note_id = n.id
+                    AND (COALESCE(m.alias_of, t.tag) = ?
+                         OR substr(COALESCE(m.alias_of, t.tag), 1, length(?) + 1) = ? || '/')
+              ))
+            ORDER BY RANDOM()
+            LIMIT 1
+            "#,
+        )
+        .bind(folder.as_deref())
+        .bind(folder.as_deref())
+        .bind(folder.as_deref())
+        .bind(tag)
+        .bind(tag)
+        .bind(tag)
+        .bind(tag)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(id, path, title, pinned)| NoteListItem {
+            id,
+            path,
+            title,
+            pinned: pinned != 0,
+        }))
+    }
+
+    /// Get notes journaled or created on the same month and day as `date`
+    /// in previous years, most recent year first.
+    ///
+    /// A note's date is its `journal_date` property if that is a date, else
+    /// the date its file is named after (`2022-03-14.md`), else its creation
+    /// date.
+    pub async fn get_on_this_day(&self, date: NaiveDate) -> Result<Vec<OnThisDayNote>> {
+        let month_day = date.format("%m-%d").to_string();
+        let year_start = format!("{:04}-01-01", date.year());
+
+        let rows = sqlx::query_as::<_, (i64, String, Option<String>, i32, String, String)>(
+            r#"
+            WITH named AS (
+                SELECT n.*, date(j.value) AS journal_day,
+                       CASE WHEN replace(n.path, rtrim(n.path, replace(n.path, '/', '')), '')
+                                 GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9].md'
+                            THEN date(substr(replace(n.path, rtrim(n.path, replace(n.path, '/', '')), ''), 1, 10))
+                       END AS file_day
+                FROM notes n
+                LEFT JOIN properties j ON j.note_id = n.id AND j.key = 'journal_date'
+            ),
+            dated AS (
+                SELECT id, path, title, pinned,
+                       COALESCE(journal_day, file_day, created_date) AS day,
+                       CASE
+                           WHEN journal_day IS NOT NULL THEN 'journal'
+                           WHEN file_day IS NOT NULL THEN 'file_name'
+                           ELSE 'created'
+                       END AS source
+                FROM named
+            )
+            SELECT id, path, title, pinned, day, source FROM dated
+            WHERE substr(day, 6, 5) = ? AND day < ?
+            ORDER BY day DESC, path
+            "#,
+        )
+        .bind(&month_day)
+        .bind(&year_start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, path, title, pinned, day, source)| {
+                let years_ago = date.year() - day.parse::<NaiveDate>().ok()?.year();
+                Some(OnThisDayNote {
+                    note: NoteListItem {
+                        id,
+                        path,
+                        title,
+                        pinned: pinned != 0,
+                    },
+                    date: day,
+                    source,
+                    years_ago,
+                })
+            })
+            .collect())
+    }
+}
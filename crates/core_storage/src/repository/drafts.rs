@@ -0,0 +1,58 @@
+//! Drafts: the latest unsaved editor content of a note, kept so it can be
+//! recovered if the app quits before the note is saved.
+
+use crate::Result;
+use chrono::{DateTime, Utc};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Store the draft of a note, replacing any earlier one. `base_hash` is
+    /// the hash of the file the draft was edited from (None for a new file);
+    /// the first draft's is kept until the draft is deleted.
+    pub async fn save_draft(&self, path: &str, content: &str, base_hash: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO drafts (path, content, base_hash, saved_at) VALUES (?, ?, ?, ?)
+            ON CONFLICT(path) DO UPDATE SET
+                content = excluded.content,
+                saved_at = excluded.saved_at
+            "#,
+        )
+        .bind(path)
+        .bind(content)
+        .bind(base_hash)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// All drafts as (path, content, base hash, saved at), in path order.
+    pub async fn list_drafts(&self) -> Result<Vec<(String, String, Option<String>, DateTime<Utc>)>> {
+        let rows = sqlx::query_as::<_, (String, String, Option<String>, String)>(
+            "SELECT path, content, base_hash, saved_at FROM drafts ORDER BY path",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(path, content, base_hash, saved_at)| {
+                let saved_at = DateTime::parse_from_rfc3339(&saved_at)
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or_default();
+                (path, content, base_hash, saved_at)
+            })
+            .collect())
+    }
+
+    /// Delete the draft of a note, if there is one.
+    pub async fn delete_draft(&self, path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM drafts WHERE path = ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -3,10 +3,22 @@
 //! Stores embeddings as BLOB and performs cosine similarity in Rust.
 //! This approach avoids sqlite-vec extension dependencies while
 //! still providing semantic search for typical vault sizes.
+//!
+//! Notes are embedded in sections (chunks); search returns the best
+//! matching section of each note. The note's own embedding is the mean of
+//! its chunks, and is stored after them so a note is only complete once
+//! all of its chunks are.
+//!
+//! Each embedding keeps the hash and a MinHash signature of the content it
+//! was computed from, so small edits don't trigger re-embedding. Embeddings
+//! are deleted with their note and never stored for a deleted note.
 
 use crate::{Result, VaultRepository};
+use core_index::{chunk_note, fingerprint, signature_similarity, MAX_CHUNK_CHARS};
+use shared_types::{EmbeddingConsistencyReport, SearchSection};
+use std::collections::HashMap;
 use sqlx::Row;
-use tracing::debug;
+use tracing::{debug, info};
 
 /// Result from vector similarity search.
 #[derive(Debug, Clone)]
@@ -16,6 +28,18 @@ pub struct VectorSearchResult {
     pub title: Option<String>,
     pub content_preview: Option<String>,
     pub score: f64,
+    /// Best matching section (None for notes embedded before chunking).
+    pub section: Option<SearchSection>,
+}
+
+/// The embedding of one section of a note.
+#[derive(Debug, Clone)]
+pub struct ChunkEmbedding {
+    pub heading_path: Vec<String>,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub snippet: String,
+    pub embedding: Vec<f32>,
 }
 
 /// Maximum length for content preview (characters).
@@ -23,23 +47,29 @@ const PREVIEW_MAX_CHARS: usize = 300;
 
 impl VaultRepository {
     /// Store or update an embedding for a note.
+    ///
+    /// `signature` comes from `content_signature` of the embedded content.
+    /// Nothing is stored if the note was deleted in the meantime.
     pub async fn store_embedding(
         &self,
         note_id: i64,
         embedding: &[f32],
         content_hash: &str,
         content_preview: Option<&str>,
+        signature: &[u64],
     ) -> Result<()> {
         let embedding_bytes = embedding_to_bytes(embedding);
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
-            INSERT INTO note_embeddings (note_id, embedding, content_hash, content_preview, created_at)
-            VALUES (?, ?, ?, ?, datetime('now'))
+            INSERT INTO note_embeddings (note_id, embedding, content_hash, content_preview, signature, created_at)
+            SELECT ?, ?, ?, ?, ?, datetime('now')
+            WHERE EXISTS (SELECT 1 FROM notes WHERE id = ?)
             ON CONFLICT(note_id) DO UPDATE SET
                 embedding = excluded.embedding,
                 content_hash = excluded.content_hash,
                 content_preview = excluded.content_preview,
+                signature = excluded.signature,
                 created_at = excluded.created_at
             "#,
         )
@@ -47,13 +77,137 @@ impl VaultRepository {
         .bind(&embedding_bytes)
         .bind(content_hash)
         .bind(content_preview)
+        .bind(signature_to_bytes(signature))
+        .bind(note_id)
         .execute(&self.pool)
         .await?;
 
-        debug!("Stored embedding for note {}", note_id);
+        if result.rows_affected() == 0 {
+            debug!("Note {} was deleted, embedding not stored", note_id);
+        } else {
+            debug!("Stored embedding for note {}", note_id);
+        }
+        Ok(())
+    }
+
+    /// Replace the section embeddings of a note.
+    ///
+    /// Call before `store_embedding`, which marks the note as embedded.
+    pub async fn store_chunk_embeddings(&self, note_id: i64, chunks: &[ChunkEmbedding]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM note_embedding_chunks WHERE note_id = ?")
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (index, chunk) in chunks.iter().enumerate() {
+            let heading_path = serde_json::to_string(&chunk.heading_path).unwrap_or_else(|_| "[]".to_string());
+            sqlx::query(
+                r#"
+                INSERT INTO note_embedding_chunks
+                    (note_id, chunk_index, heading_path, start_line, end_line, snippet, embedding)
+                SELECT ?, ?, ?, ?, ?, ?, ?
+                WHERE EXISTS (SELECT 1 FROM notes WHERE id = ?)
+                "#,
+            )
+            .bind(note_id)
+            .bind(index as i64)
+            .bind(heading_path)
+            .bind(chunk.start_line)
+            .bind(chunk.end_line)
+            .bind(&chunk.snippet)
+            .bind(embedding_to_bytes(&chunk.embedding))
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        debug!("Stored {} chunk embeddings for note {}", chunks.len(), note_id);
         Ok(())
     }
 
+    /// Check whether a note's stored embedding can be kept for its content.
+    ///
+    /// True if the content is unchanged, or if less than `threshold` of it
+    /// (estimated from the signatures) changed since it was embedded and the
+    /// note still has the same sections. In that case the stored hash,
+    /// preview and section line ranges are updated, so the note is no longer
+    /// reported as stale and section hits point at the right lines; the
+    /// signature is kept, so many small edits still add up to a re-embed.
+    pub async fn embedding_is_current(
+        &self,
+        note_id: i64,
+        content: &str,
+        content_hash: &str,
+        content_preview: Option<&str>,
+        signature: &[u64],
+        threshold: f64,
+    ) -> Result<bool> {
+        let row = sqlx::query("SELECT content_hash, signature FROM note_embeddings WHERE note_id = ?")
+            .bind(note_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Ok(false);
+        };
+        let stored_hash: String = row.get("content_hash");
+        if stored_hash == content_hash {
+            return Ok(true);
+        }
+
+        let stored_signature: Option<Vec<u8>> = row.get("signature");
+        let Some(stored_signature) = stored_signature else {
+            return Ok(false);
+        };
+        let changed = 1.0 - signature_similarity(&bytes_to_signature(&stored_signature), signature);
+        if changed >= threshold {
+            return Ok(false);
+        }
+
+        // Sections that were added, removed or renamed need new embeddings
+        let chunks = chunk_note(content, MAX_CHUNK_CHARS);
+        let stored_sections: Vec<String> = sqlx::query_scalar(
+            "SELECT heading_path FROM note_embedding_chunks WHERE note_id = ? ORDER BY chunk_index",
+        )
+        .bind(note_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let same_sections = stored_sections.len() == chunks.len()
+            && stored_sections.iter().zip(&chunks).all(|(stored, chunk)| {
+                serde_json::from_str::<Vec<String>>(stored).is_ok_and(|path| path == chunk.heading_path)
+            });
+        if !same_sections {
+            return Ok(false);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for (index, chunk) in chunks.iter().enumerate() {
+            sqlx::query(
+                "UPDATE note_embedding_chunks SET start_line = ?, end_line = ?, snippet = ? WHERE note_id = ? AND chunk_index = ?",
+            )
+            .bind(chunk.start_line as u32)
+            .bind(chunk.end_line as u32)
+            .bind(extract_content_preview(&chunk.text))
+            .bind(note_id)
+            .bind(index as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query("UPDATE note_embeddings SET content_hash = ?, content_preview = COALESCE(?, content_preview) WHERE note_id = ?")
+            .bind(content_hash)
+            .bind(content_preview)
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        debug!("Kept embedding for note {} ({:.0}% changed)", note_id, changed * 100.0);
+        Ok(true)
+    }
+
     /// Get embedding for a note.
     pub async fn get_embedding(&self, note_id: i64) -> Result<Option<Vec<f32>>> {
         let row = sqlx::query("SELECT embedding FROM note_embeddings WHERE note_id = ?")
@@ -83,12 +237,16 @@ impl VaultRepository {
         }
     }
 
-    /// Delete embedding when note is deleted.
+    /// Delete embedding (and section embeddings) when note is deleted.
     pub async fn delete_embedding(&self, note_id: i64) -> Result<()> {
         sqlx::query("DELETE FROM note_embeddings WHERE note_id = ?")
             .bind(note_id)
             .execute(&self.pool)
             .await?;
+        sqlx::query("DELETE FROM note_embedding_chunks WHERE note_id = ?")
+            .bind(note_id)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
@@ -110,14 +268,17 @@ impl VaultRepository {
         Ok(count.0)
     }
 
-    /// Get note IDs that don't have embeddings or are missing content preview.
+    /// Get note IDs that don't have embeddings, are missing content preview,
+    /// or changed since they were embedded. Encrypted and oversized
+    /// (truncated) notes are never embedded.
     pub async fn get_notes_without_embeddings(&self, limit: i32) -> Result<Vec<(i64, String)>> {
         let rows: Vec<(i64, String)> = sqlx::query_as(
             r#"
             SELECT n.id, n.path
             FROM notes n
             LEFT JOIN note_embeddings e ON n.id = e.note_id
-            WHERE e.note_id IS NULL OR e.content_preview IS NULL
+            WHERE (e.note_id IS NULL OR e.content_preview IS NULL OR e.content_hash != n.hash)
+                AND n.encrypted = 0 AND COALESCE(n.truncated, 0) = 0
             LIMIT ?
             "#,
         )
@@ -127,8 +288,84 @@ impl VaultRepository {
         Ok(rows)
     }
 
+    /// Check stored embeddings against the notes.
+    ///
+    /// With `repair`, embeddings of deleted or encrypted notes and those of
+    /// the wrong size (from another model) are deleted. Stale embeddings are
+    /// left to be re-embedded, as they are listed by
+    /// `get_notes_without_embeddings`.
+    pub async fn check_embeddings(&self, dimensions: i32, repair: bool) -> Result<EmbeddingConsistencyReport> {
+        let expected_bytes = dimensions as i64 * 4;
+
+        let (total, orphaned, stale, wrong_dimensions): (i64, i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*),
+                COALESCE(SUM(n.id IS NULL OR n.encrypted = 1), 0),
+                COALESCE(SUM(n.encrypted = 0 AND e.content_hash != n.hash), 0),
+                COALESCE(SUM(length(e.embedding) != ?), 0)
+            FROM note_embeddings e
+            LEFT JOIN notes n ON n.id = e.note_id
+            "#,
+        )
+        .bind(expected_bytes)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let missing: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM notes n
+            LEFT JOIN note_embeddings e ON n.id = e.note_id
+            WHERE e.note_id IS NULL AND n.encrypted = 0 AND COALESCE(n.truncated, 0) = 0
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let mut removed = 0;
+        if repair {
+            removed = sqlx::query(
+                r#"
+                DELETE FROM note_embeddings
+                WHERE note_id NOT IN (SELECT id FROM notes WHERE encrypted = 0)
+                    OR length(embedding) != ?
+                "#,
+            )
+            .bind(expected_bytes)
+            .execute(&self.pool)
+            .await?
+            .rows_affected() as i64;
+
+            // Sections belong to the note's embedding
+            sqlx::query(
+                r#"
+                DELETE FROM note_embedding_chunks
+                WHERE note_id NOT IN (SELECT note_id FROM note_embeddings)
+                    OR length(embedding) != ?
+                "#,
+            )
+            .bind(expected_bytes)
+            .execute(&self.pool)
+            .await?;
+            info!("Removed {} orphaned or wrong-sized embeddings", removed);
+        }
+
+        Ok(EmbeddingConsistencyReport {
+            total,
+            orphaned,
+            stale,
+            wrong_dimensions,
+            missing,
+            removed,
+        })
+    }
+
     /// Perform vector similarity search using cosine similarity.
     /// Returns results sorted by similarity (highest first).
+    ///
+    /// Chunked notes are scored by their best matching section, which is
+    /// returned with the result.
     pub async fn vector_search(
         &self,
         query_embedding: &[f32],
@@ -136,11 +373,54 @@ impl VaultRepository {
     ) -> Result<Vec<VectorSearchResult>> {
         // Fetch all embeddings (for small vaults, this is acceptable)
         // For larger vaults, we could add pre-filtering or use HNSW index
+        let chunk_rows = sqlx::query(
+            r#"
+            SELECT c.note_id, c.heading_path, c.start_line, c.end_line, c.snippet, c.embedding,
+                e.content_preview, n.path, n.title
+            FROM note_embedding_chunks c
+            JOIN note_embeddings e ON c.note_id = e.note_id
+            JOIN notes n ON c.note_id = n.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Keep the best section of each note
+        let mut best: HashMap<i64, VectorSearchResult> = HashMap::new();
+        for row in &chunk_rows {
+            let embedding_bytes: Vec<u8> = row.get("embedding");
+            let score = cosine_similarity(query_embedding, &bytes_to_embedding(&embedding_bytes));
+            let note_id: i64 = row.get("note_id");
+            if score <= 0.0 || best.get(&note_id).is_some_and(|b| b.score >= score) {
+                continue;
+            }
+
+            let heading_path: String = row.get("heading_path");
+            best.insert(
+                note_id,
+                VectorSearchResult {
+                    note_id,
+                    path: row.get("path"),
+                    title: row.get("title"),
+                    content_preview: row.get("content_preview"),
+                    score,
+                    section: Some(SearchSection {
+                        heading_path: serde_json::from_str(&heading_path).unwrap_or_default(),
+                        snippet: row.get("snippet"),
+                        start_line: row.get("start_line"),
+                        end_line: row.get("end_line"),
+                    }),
+                },
+            );
+        }
+
+        // Notes embedded before chunking only have a note embedding
         let rows = sqlx::query(
             r#"
             SELECT e.note_id, e.embedding, e.content_preview, n.path, n.title
             FROM note_embeddings e
             JOIN notes n ON e.note_id = n.id
+            WHERE NOT EXISTS (SELECT 1 FROM note_embedding_chunks c WHERE c.note_id = e.note_id)
             "#,
         )
         .fetch_all(&self.pool)
@@ -167,12 +447,14 @@ impl VaultRepository {
                         title,
                         content_preview,
                         score,
+                        section: None,
                     })
                 } else {
                     None
                 }
             })
             .collect();
+        results.extend(best.into_values());
 
         // Sort by similarity (descending)
         results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
@@ -222,6 +504,42 @@ pub fn extract_content_preview(content: &str) -> String {
     }
 }
 
+/// The note embedding of a chunked note: the normalized mean of its
+/// section embeddings.
+pub fn mean_embedding(chunks: &[ChunkEmbedding]) -> Vec<f32> {
+    let Some(first) = chunks.first() else {
+        return Vec::new();
+    };
+    let mut mean = vec![0.0f32; first.embedding.len()];
+    for chunk in chunks {
+        for (m, x) in mean.iter_mut().zip(&chunk.embedding) {
+            *m += x;
+        }
+    }
+
+    let norm = mean.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        mean.iter_mut().for_each(|x| *x /= norm);
+    }
+    mean
+}
+
+/// MinHash signature of note content, to store with its embedding.
+pub fn content_signature(content: &str) -> Vec<u64> {
+    fingerprint(content).signature().to_vec()
+}
+
+fn signature_to_bytes(signature: &[u64]) -> Vec<u8> {
+    signature.iter().flat_map(|h| h.to_le_bytes()).collect()
+}
+
+fn bytes_to_signature(bytes: &[u8]) -> Vec<u64> {
+    bytes
+        .chunks_exact(8)
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
 /// Convert f32 embedding to bytes for storage.
 fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
     embedding
@@ -274,6 +592,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_signature_bytes_roundtrip() {
+        let signature = content_signature("Some words that make up a short note about signatures");
+        assert_eq!(bytes_to_signature(&signature_to_bytes(&signature)), signature);
+    }
+
+    #[test]
+    fn test_mean_embedding() {
+        let chunk = |embedding: Vec<f32>| ChunkEmbedding {
+            heading_path: Vec::new(),
+            start_line: 1,
+            end_line: 1,
+            snippet: String::new(),
+            embedding,
+        };
+        let mean = mean_embedding(&[chunk(vec![1.0, 0.0]), chunk(vec![0.0, 1.0])]);
+        assert!((mean[0] - mean[1]).abs() < 1e-6);
+        assert!((cosine_similarity(&mean, &[1.0, 1.0]) - 1.0).abs() < 1e-6);
+        assert!(mean_embedding(&[]).is_empty());
+    }
+
     #[test]
     fn test_cosine_similarity_identical() {
         let a = vec![1.0, 2.0, 3.0];
@@ -0,0 +1,69 @@
+//! Files that could not be read cleanly: invalid UTF-8, or binary files
+//! with a markdown name. Each file has at most one problem, replaced or
+//! cleared whenever it is indexed again.
+
+use crate::Result;
+use chrono::{DateTime, Utc};
+use shared_types::{FileProblem, FileProblemKind};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Record a problem with a file. Returns false if it was already
+    /// recorded, so it is only reported once.
+    pub async fn record_file_problem(&self, path: &str, kind: FileProblemKind) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO file_problems (path, kind, detected_at) VALUES (?, ?, ?)
+            ON CONFLICT(path) DO UPDATE SET
+                kind = excluded.kind,
+                detected_at = excluded.detected_at
+            WHERE kind != excluded.kind
+            "#,
+        )
+        .bind(path)
+        .bind(kind.as_str())
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The recorded problem with a file, if any.
+    pub async fn get_file_problem(&self, path: &str) -> Result<Option<FileProblemKind>> {
+        let kind = sqlx::query_scalar::<_, String>("SELECT kind FROM file_problems WHERE path = ?")
+            .bind(path)
+            .fetch_optional(&self.read_pool)
+            .await?;
+        Ok(kind.as_deref().map(FileProblemKind::from_string))
+    }
+
+    /// Forget the problem with a file, once it reads cleanly or is gone.
+    pub async fn clear_file_problem(&self, path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM file_problems WHERE path = ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// All recorded file problems, in path order.
+    pub async fn list_file_problems(&self) -> Result<Vec<FileProblem>> {
+        let rows = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT path, kind, detected_at FROM file_problems ORDER BY path",
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(path, kind, detected_at)| FileProblem {
+                path,
+                kind: FileProblemKind::from_string(&kind),
+                detected_at: DateTime::parse_from_rfc3339(&detected_at)
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,170 @@
+//! Folder appearance (icon, color, collapsed state) and manual child order.
+
+use crate::Result;
+use shared_types::{FolderAppearance, FolderMetadata};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Get metadata for all folders that have any.
+    pub async fn list_folder_metadata(&self) -> Result<Vec<FolderMetadata>> {
+        let rows = sqlx::query_as::<_, (String, Option<String>, Option<String>, i32, Option<String>)>(
+            "SELECT path, icon, color, collapsed, child_order FROM folders ORDER BY path",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(path, icon, color, collapsed, child_order)| FolderMetadata {
+                path,
+                appearance: FolderAppearance {
+                    icon,
+                    color,
+                    collapsed: collapsed != 0,
+                },
+                child_order: parse_child_order(child_order),
+            })
+            .collect())
+    }
+
+    /// Set the icon, color, and collapsed state of a folder.
+    #[instrument(skip(self))]
+    pub async fn set_folder_appearance(&self, path: &str, appearance: &FolderAppearance) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO folders (path, icon, color, collapsed) VALUES (?, ?, ?, ?)
+            ON CONFLICT(path) DO UPDATE SET
+                icon = excluded.icon,
+                color = excluded.color,
+                collapsed = excluded.collapsed
+            "#,
+        )
+        .bind(normalize_folder_path(path))
+        .bind(&appearance.icon)
+        .bind(&appearance.color)
+        .bind(appearance.collapsed)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Set appearance of folder {}", path);
+        Ok(())
+    }
+
+    /// Set the manual order of a folder's children (by name).
+    ///
+    /// An empty list restores the default order.
+    #[instrument(skip(self))]
+    pub async fn set_folder_child_order(&self, path: &str, children: &[String]) -> Result<()> {
+        let child_order = serde_json::to_string(children).unwrap_or_default();
+
+        sqlx::query(
+            r#"
+            INSERT INTO folders (path, child_order) VALUES (?, ?)
+            ON CONFLICT(path) DO UPDATE SET child_order = excluded.child_order
+            "#,
+        )
+        .bind(normalize_folder_path(path))
+        .bind(&child_order)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Reordered {} children of folder {}", children.len(), path);
+        Ok(())
+    }
+
+    /// Move metadata of a folder and its subfolders to a new path.
+    ///
+    /// If the folder stays in the same parent, its name is also updated in
+    /// the parent's manual order.
+    #[instrument(skip(self))]
+    pub async fn rename_folder_metadata(&self, old_path: &str, new_path: &str) -> Result<()> {
+        let old_path = normalize_folder_path(old_path);
+        let new_path = normalize_folder_path(new_path);
+        if old_path == new_path {
+            return Ok(());
+        }
+        let old_prefix = format!("{}/", old_path);
+
+        // Stale metadata at the target would collide with the moved rows
+        self.delete_folder_metadata(new_path).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE folders SET path = ? || substr(path, length(?) + 1)
+            WHERE path = ? OR substr(path, 1, length(?)) = ?
+            "#,
+        )
+        .bind(new_path)
+        .bind(old_path)
+        .bind(old_path)
+        .bind(&old_prefix)
+        .bind(&old_prefix)
+        .execute(&self.pool)
+        .await?;
+
+        let (old_parent, old_name) = split_folder_path(old_path);
+        let (new_parent, new_name) = split_folder_path(new_path);
+        if old_parent == new_parent {
+            let order = sqlx::query_scalar::<_, Option<String>>(
+                "SELECT child_order FROM folders WHERE path = ?",
+            )
+            .bind(old_parent)
+            .fetch_optional(&self.pool)
+            .await?
+            .flatten();
+
+            let mut children = parse_child_order(order);
+            if let Some(child) = children.iter_mut().find(|c| *c == old_name) {
+                *child = new_name.to_string();
+                self.set_folder_child_order(old_parent, &children).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delete metadata of a folder and its subfolders.
+    #[instrument(skip(self))]
+    pub async fn delete_folder_metadata(&self, path: &str) -> Result<()> {
+        let path = normalize_folder_path(path);
+        let prefix = format!("{}/", path);
+
+        sqlx::query("DELETE FROM folders WHERE path = ? OR substr(path, 1, length(?)) = ?")
+            .bind(path)
+            .bind(&prefix)
+            .bind(&prefix)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Folder paths are stored without leading or trailing slashes.
+fn normalize_folder_path(path: &str) -> &str {
+    path.trim_matches('/')
+}
+
+/// Split a folder path into its parent path and name.
+fn split_folder_path(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+fn parse_child_order(child_order: Option<String>) -> Vec<String> {
+    child_order
+        .and_then(|o| serde_json::from_str(&o).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_folder_path() {
+        assert_eq!(split_folder_path("projects/2024/q1"), ("projects/2024", "q1"));
+        assert_eq!(split_folder_path("projects"), ("", "projects"));
+    }
+}
@@ -0,0 +1,301 @@
+//! Goal operations and progress tracking.
+
+use crate::Result;
+use chrono::{Local, NaiveDate, Utc};
+use shared_types::{CreateGoalRequest, GoalDto, GoalProgress, GoalTargetType, TaskQuery};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+/// Maximum number of tasks counted towards a task goal.
+const MAX_GOAL_TASKS: i32 = 10_000;
+
+type GoalRow = (
+    i64,
+    String,
+    Option<String>,
+    String,
+    Option<f64>,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<String>,
+    i32,
+);
+
+const GOAL_COLUMNS: &str =
+    "id, name, description, target_type, target_value, unit, start_date, deadline, task_query, archived";
+
+impl VaultRepository {
+    /// Create a goal and link its habits.
+    #[instrument(skip(self))]
+    pub async fn create_goal(&self, request: &CreateGoalRequest) -> Result<i64> {
+        let now = Utc::now().to_rfc3339();
+        let start_date = request
+            .start_date
+            .clone()
+            .unwrap_or_else(|| Local::now().date_naive().to_string());
+        let task_query = request
+            .task_query
+            .as_ref()
+            .and_then(|q| serde_json::to_string(q).ok());
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO goals (name, description, target_type, target_value, unit, start_date, deadline, task_query, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(request.target_type.as_str())
+        .bind(request.target_value)
+        .bind(&request.unit)
+        .bind(&start_date)
+        .bind(&request.deadline)
+        .bind(&task_query)
+        .bind(&now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        for habit_id in &request.habit_ids {
+            sqlx::query("INSERT OR IGNORE INTO goal_habits (goal_id, habit_id) VALUES (?, ?)")
+                .bind(id)
+                .bind(habit_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        debug!("Created goal {} with id {}", request.name, id);
+        Ok(id)
+    }
+
+    /// Get a goal by ID.
+    pub async fn get_goal(&self, id: i64) -> Result<Option<GoalDto>> {
+        let row = sqlx::query_as::<_, GoalRow>(&format!(
+            "SELECT {} FROM goals WHERE id = ?",
+            GOAL_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(self.goal_from_row(row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get a goal by name (case-insensitive).
+    pub async fn get_goal_by_name(&self, name: &str) -> Result<Option<GoalDto>> {
+        let row = sqlx::query_as::<_, GoalRow>(&format!(
+            "SELECT {} FROM goals WHERE name = ? COLLATE NOCASE",
+            GOAL_COLUMNS
+        ))
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(self.goal_from_row(row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List goals, ordered by deadline then name.
+    pub async fn list_goals(&self, include_archived: bool) -> Result<Vec<GoalDto>> {
+        let filter = if include_archived { "" } else { "WHERE archived = 0" };
+        let rows = sqlx::query_as::<_, GoalRow>(&format!(
+            "SELECT {} FROM goals {} ORDER BY deadline IS NULL, deadline, name",
+            GOAL_COLUMNS, filter
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut goals = Vec::with_capacity(rows.len());
+        for row in rows {
+            goals.push(self.goal_from_row(row).await?);
+        }
+        Ok(goals)
+    }
+
+    /// Archive a goal (soft delete).
+    #[instrument(skip(self))]
+    pub async fn archive_goal(&self, id: i64) -> Result<()> {
+        sqlx::query("UPDATE goals SET archived = 1 WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Archived goal {}", id);
+        Ok(())
+    }
+
+    /// Delete a goal and its habit links.
+    #[instrument(skip(self))]
+    pub async fn delete_goal(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM goal_habits WHERE goal_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("DELETE FROM goals WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Deleted goal {}", id);
+        Ok(())
+    }
+
+    /// Compute the current progress of a goal as of `today`.
+    ///
+    /// Numeric goals sum the values of linked habit entries between the start
+    /// date and the deadline (boolean entries count as 1). Task goals count
+    /// completed tasks matching the goal's task query; tasks completed
+    /// outside that window don't count towards the goal at all, unless when
+    /// they were completed is unknown.
+    pub async fn get_goal_progress(&self, id: i64, today: NaiveDate) -> Result<Option<GoalProgress>> {
+        let goal = match self.get_goal(id).await? {
+            Some(g) => g,
+            None => return Ok(None),
+        };
+
+        let (current_value, target_value, total_tasks) = match goal.target_type {
+            GoalTargetType::Numeric => {
+                let values = sqlx::query_scalar::<_, Option<String>>(
+                    r#"
+                    SELECT e.value
+                    FROM habit_entries e
+                    JOIN goal_habits g ON g.habit_id = e.habit_id
+                    WHERE g.goal_id = ? AND e.date >= ? AND (? IS NULL OR e.date <= ?)
+                    "#,
+                )
+                .bind(goal.id)
+                .bind(&goal.start_date)
+                .bind(&goal.deadline)
+                .bind(&goal.deadline)
+                .fetch_all(&self.pool)
+                .await?;
+
+                let current: f64 = values.iter().flatten().map(|v| entry_value(v)).sum();
+                (current, goal.target_value, None)
+            }
+            GoalTargetType::Tasks => {
+                let query = TaskQuery {
+                    completed: None,
+                    limit: Some(MAX_GOAL_TASKS),
+                    ..goal.task_query.clone().unwrap_or_default()
+                };
+                // Completion dates are the local date part of completed_at
+                let completed_on = |op: &str| {
+                    format!(
+                        "({{t}}.completed = 0 OR {{t}}.completed_at IS NULL OR substr({{t}}.completed_at, 1, 10) {} ?)",
+                        op
+                    )
+                };
+                let mut window = vec![(completed_on(">="), goal.start_date.clone())];
+                if let Some(deadline) = &goal.deadline {
+                    window.push((completed_on("<="), deadline.clone()));
+                }
+                let tasks = self.query_tasks_where(&query, &window).await?;
+                let total = tasks.len() as i64;
+                let completed = tasks.iter().filter(|t| t.todo.completed).count() as f64;
+                (completed, goal.target_value.or(Some(total as f64)), Some(total))
+            }
+        };
+
+        let percent = target_value
+            .filter(|t| *t > 0.0)
+            .map(|t| (current_value / t * 100.0).min(100.0));
+
+        let deadline = goal.deadline.as_deref().and_then(|d| d.parse::<NaiveDate>().ok());
+        let days_remaining = deadline.map(|d| (d - today).num_days());
+        let on_track = match (percent, deadline, goal.start_date.parse::<NaiveDate>().ok()) {
+            (Some(p), Some(deadline), Some(start)) => {
+                Some(p / 100.0 >= expected_fraction(start, deadline, today))
+            }
+            _ => None,
+        };
+
+        Ok(Some(GoalProgress {
+            goal,
+            current_value,
+            target_value,
+            percent,
+            total_tasks,
+            days_remaining,
+            on_track,
+        }))
+    }
+
+    /// Build a GoalDto from a row, loading its linked habits.
+    async fn goal_from_row(&self, row: GoalRow) -> Result<GoalDto> {
+        let habit_ids = sqlx::query_scalar::<_, i64>(
+            "SELECT habit_id FROM goal_habits WHERE goal_id = ? ORDER BY habit_id",
+        )
+        .bind(row.0)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(GoalDto {
+            id: row.0,
+            name: row.1,
+            description: row.2,
+            target_type: GoalTargetType::from_string(&row.3),
+            target_value: row.4,
+            unit: row.5,
+            start_date: row.6,
+            deadline: row.7,
+            habit_ids,
+            task_query: row.8.and_then(|q| serde_json::from_str(&q).ok()),
+            archived: row.9 != 0,
+        })
+    }
+}
+
+/// Numeric contribution of a habit entry value ("true" counts as 1).
+fn entry_value(value: &str) -> f64 {
+    match value.trim() {
+        "true" => 1.0,
+        v => v.parse().unwrap_or(0.0),
+    }
+}
+
+/// Fraction of the goal period elapsed by `today` (0.0 before start, 1.0 at or after the deadline).
+fn expected_fraction(start: NaiveDate, deadline: NaiveDate, today: NaiveDate) -> f64 {
+    let total = (deadline - start).num_days();
+    if total <= 0 {
+        return 1.0;
+    }
+    ((today - start).num_days() as f64 / total as f64).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_entry_value() {
+        assert_eq!(entry_value("true"), 1.0);
+        assert_eq!(entry_value("5.5"), 5.5);
+        assert_eq!(entry_value("false"), 0.0);
+        assert_eq!(entry_value("n/a"), 0.0);
+    }
+
+    #[test]
+    fn test_expected_fraction() {
+        let start = date("2024-01-01");
+        let deadline = date("2024-01-11");
+
+        assert_eq!(expected_fraction(start, deadline, date("2023-12-25")), 0.0);
+        assert_eq!(expected_fraction(start, deadline, date("2024-01-06")), 0.5);
+        assert_eq!(expected_fraction(start, deadline, date("2024-02-01")), 1.0);
+        assert_eq!(expected_fraction(start, start, start), 1.0);
+    }
+}
@@ -0,0 +1,149 @@
+//! Vault statistics over time: daily snapshots of the vault's metrics.
+//!
+//! A snapshot is only stored for days the metrics changed on, so a vault
+//! that isn't touched for a month adds no rows.
+
+use crate::{Result, StorageError};
+use chrono::NaiveDate;
+use shared_types::{GrowthPoint, VaultGrowth, VaultMetric, VaultMetricsSnapshot};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+type MetricsRow = (String, i64, i64, i64, i64, i64);
+
+fn snapshot_from_row((date, notes, words, tasks, completed_tasks, links): MetricsRow) -> VaultMetricsSnapshot {
+    VaultMetricsSnapshot {
+        date,
+        notes,
+        words,
+        tasks,
+        completed_tasks,
+        links,
+    }
+}
+
+impl VaultRepository {
+    /// The vault's current metrics, labelled with `date`.
+    pub async fn get_vault_metrics(&self, date: &str) -> Result<VaultMetricsSnapshot> {
+        let row = sqlx::query_as::<_, MetricsRow>(
+            r#"
+            SELECT ?,
+                   (SELECT COUNT(*) FROM notes),
+                   (SELECT COALESCE(SUM(word_count), 0) FROM notes),
+                   (SELECT COUNT(*) FROM todos),
+                   (SELECT COUNT(*) FROM todos WHERE completed = 1),
+                   (SELECT COUNT(*) FROM backlinks)
+            "#,
+        )
+        .bind(date)
+        .fetch_one(&self.read_pool)
+        .await?;
+
+        Ok(snapshot_from_row(row))
+    }
+
+    /// Store the vault's current metrics as the snapshot of `date`, unless
+    /// they are the same as on the last day before it. Returns whether a
+    /// snapshot is stored for `date`.
+    #[instrument(skip(self))]
+    pub async fn snapshot_vault_metrics(&self, date: &str) -> Result<bool> {
+        let current = self.get_vault_metrics(date).await?;
+        let previous = sqlx::query_as::<_, MetricsRow>(
+            r#"
+            SELECT date, notes, words, tasks, completed_tasks, links
+            FROM vault_metrics WHERE date < ? ORDER BY date DESC LIMIT 1
+            "#,
+        )
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(snapshot_from_row);
+
+        let unchanged = previous.is_some_and(|previous| VaultMetricsSnapshot { date: date.to_string(), ..previous } == current);
+        if unchanged {
+            // Back to the previous day's values, which carry over
+            sqlx::query("DELETE FROM vault_metrics WHERE date = ?")
+                .bind(date)
+                .execute(&self.pool)
+                .await?;
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO vault_metrics (date, notes, words, tasks, completed_tasks, links)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(date) DO UPDATE SET
+                notes = excluded.notes,
+                words = excluded.words,
+                tasks = excluded.tasks,
+                completed_tasks = excluded.completed_tasks,
+                links = excluded.links
+            "#,
+        )
+        .bind(date)
+        .bind(current.notes)
+        .bind(current.words)
+        .bind(current.tasks)
+        .bind(current.completed_tasks)
+        .bind(current.links)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Stored vault metrics for {}", date);
+        Ok(true)
+    }
+
+    /// How a metric changed from `start_date` to `end_date` (inclusive),
+    /// both YYYY-MM-DD.
+    pub async fn get_vault_growth(&self, start_date: &str, end_date: &str, metric: VaultMetric) -> Result<VaultGrowth> {
+        let parse = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+        match (parse(start_date), parse(end_date)) {
+            (Some(start), Some(end)) if start <= end => {}
+            _ => return Err(StorageError::InvalidDateRange(format!("{}..{}", start_date, end_date))),
+        }
+        let column = metric.as_str();
+
+        // The value at the start of the range is that of the last snapshot before it
+        let start = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT {} FROM vault_metrics WHERE date < ? ORDER BY date DESC LIMIT 1",
+            column
+        ))
+        .bind(start_date)
+        .fetch_optional(&self.read_pool)
+        .await?;
+
+        let rows = sqlx::query_as::<_, (String, i64)>(&format!(
+            "SELECT date, {} FROM vault_metrics WHERE date >= ? AND date <= ? ORDER BY date",
+            column
+        ))
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let mut points: Vec<GrowthPoint> = Vec::new();
+        if let Some(value) = start {
+            if rows.first().map(|(date, _)| date.as_str()) != Some(start_date) {
+                points.push(GrowthPoint {
+                    date: start_date.to_string(),
+                    value,
+                });
+            }
+        }
+        for (date, value) in rows {
+            // Days whose snapshot equals the day before (from other metrics changing)
+            if points.last().is_some_and(|last| last.value == value) {
+                continue;
+            }
+            points.push(GrowthPoint { date, value });
+        }
+
+        let change = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => last.value - first.value,
+            _ => 0,
+        };
+        Ok(VaultGrowth { metric, points, change })
+    }
+}
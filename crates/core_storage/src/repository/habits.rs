@@ -3,12 +3,17 @@
 use crate::Result;
 use chrono::{Datelike, Duration, Local, NaiveDate, Utc};
 use shared_types::{
-    CreateHabitRequest, HabitDateRange, HabitDto, HabitEntryDto, HabitTrackerQuery,
-    HabitTrackerResponse, HabitType, HabitWithEntries, LogHabitEntryRequest,
+    CreateHabitRequest, HabitDateRange, HabitDto, HabitEntryDto, HabitHeatmapCell, HabitScheduleRules,
+    HabitStreak, HabitTrackerQuery, HabitTrackerResponse, HabitType, HabitViewType, HabitWithEntries, LogHabitEntryRequest,
     UpdateHabitEntryRequest, UpdateHabitRequest,
 };
+use std::collections::HashSet;
 use tracing::{debug, instrument};
 
+/// Longest custom habit tracker range, in days. Longer ranges keep their end
+/// date and have their start moved up, as every day in the range is rendered.
+const MAX_CUSTOM_RANGE_DAYS: i64 = 5 * 366;
+
 use super::VaultRepository;
 
 impl VaultRepository {
@@ -76,8 +81,8 @@ impl VaultRepository {
 
     /// Get a habit by ID.
     pub async fn get_habit(&self, id: i64) -> Result<Option<HabitDto>> {
-        let row = sqlx::query_as::<_, (i64, String, Option<String>, String, Option<String>, Option<String>, Option<f64>, i32, i32)>(
-            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order FROM habits WHERE id = ?"
+        let row = sqlx::query_as::<_, (i64, String, Option<String>, String, Option<String>, Option<String>, Option<f64>, i32, i32, Option<String>)>(
+            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order, schedule_rules FROM habits WHERE id = ?"
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -93,13 +98,14 @@ impl VaultRepository {
             target_value: r.6,
             archived: r.7 != 0,
             sort_order: r.8,
+            schedule: parse_schedule_rules(r.9.as_deref()),
         }))
     }
 
     /// Get a habit by name.
     pub async fn get_habit_by_name(&self, name: &str) -> Result<Option<HabitDto>> {
-        let row = sqlx::query_as::<_, (i64, String, Option<String>, String, Option<String>, Option<String>, Option<f64>, i32, i32)>(
-            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order FROM habits WHERE name = ? COLLATE NOCASE"
+        let row = sqlx::query_as::<_, (i64, String, Option<String>, String, Option<String>, Option<String>, Option<f64>, i32, i32, Option<String>)>(
+            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order, schedule_rules FROM habits WHERE name = ? COLLATE NOCASE"
         )
         .bind(name)
         .fetch_optional(&self.pool)
@@ -115,18 +121,19 @@ impl VaultRepository {
             target_value: r.6,
             archived: r.7 != 0,
             sort_order: r.8,
+            schedule: parse_schedule_rules(r.9.as_deref()),
         }))
     }
 
     /// List all habits.
     pub async fn list_habits(&self, include_archived: bool) -> Result<Vec<HabitDto>> {
         let query = if include_archived {
-            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order FROM habits ORDER BY sort_order, name"
+            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order, schedule_rules FROM habits ORDER BY sort_order, name"
         } else {
-            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order FROM habits WHERE archived = 0 ORDER BY sort_order, name"
+            "SELECT id, name, description, habit_type, unit, color, target_value, archived, sort_order, schedule_rules FROM habits WHERE archived = 0 ORDER BY sort_order, name"
         };
 
-        let rows = sqlx::query_as::<_, (i64, String, Option<String>, String, Option<String>, Option<String>, Option<f64>, i32, i32)>(query)
+        let rows = sqlx::query_as::<_, (i64, String, Option<String>, String, Option<String>, Option<String>, Option<f64>, i32, i32, Option<String>)>(query)
             .fetch_all(&self.pool)
             .await?;
 
@@ -142,6 +149,7 @@ impl VaultRepository {
                 target_value: r.6,
                 archived: r.7 != 0,
                 sort_order: r.8,
+                schedule: parse_schedule_rules(r.9.as_deref()),
             })
             .collect())
     }
@@ -277,6 +285,40 @@ impl VaultRepository {
         Ok(())
     }
 
+    /// Set the schedule rules (active days, planned skips, freeze tokens) for a habit.
+    #[instrument(skip(self))]
+    pub async fn set_habit_schedule(&self, habit_id: i64, rules: &HabitScheduleRules) -> Result<()> {
+        let json = serde_json::to_string(rules).unwrap_or_default();
+
+        sqlx::query("UPDATE habits SET schedule_rules = ? WHERE id = ?")
+            .bind(&json)
+            .bind(habit_id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Updated schedule rules for habit {}", habit_id);
+        Ok(())
+    }
+
+    /// Compute the streak for a habit as of a date, honoring its schedule rules.
+    pub async fn get_habit_streak(&self, habit: &HabitDto, as_of: NaiveDate) -> Result<HabitStreak> {
+        let rows = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT date, value FROM habit_entries WHERE habit_id = ? AND date <= ?",
+        )
+        .bind(habit.id)
+        .bind(as_of.format("%Y-%m-%d").to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let done: HashSet<NaiveDate> = rows
+            .into_iter()
+            .filter(|(_, value)| value.as_deref().is_some_and(|v| entry_is_done(habit, v)))
+            .filter_map(|(date, _)| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok())
+            .collect();
+
+        Ok(compute_streak(&done, &habit.schedule, as_of))
+    }
+
     // ========================================================================
     // Habit Entry Operations
     // ========================================================================
@@ -309,6 +351,40 @@ impl VaultRepository {
         Ok(id)
     }
 
+    /// Bulk-load habit entries, skipping any that already exist.
+    ///
+    /// An entry is a duplicate when the habit already has an entry with the
+    /// same date and time (this includes earlier rows of the same batch).
+    /// Returns (imported, skipped duplicates).
+    #[instrument(skip(self, entries))]
+    pub async fn import_habit_entries(&self, entries: &[LogHabitEntryRequest]) -> Result<(i64, i64)> {
+        let mut imported = 0;
+        let mut skipped = 0;
+
+        for entry in entries {
+            let exists = sqlx::query_scalar::<_, i64>(
+                "SELECT id FROM habit_entries WHERE habit_id = ? AND date = ? AND time IS ? LIMIT 1",
+            )
+            .bind(entry.habit_id)
+            .bind(&entry.date)
+            .bind(&entry.time)
+            .fetch_optional(&self.pool)
+            .await?
+            .is_some();
+
+            if exists {
+                skipped += 1;
+                continue;
+            }
+
+            self.log_habit_entry(entry).await?;
+            imported += 1;
+        }
+
+        debug!("Imported {} habit entries ({} duplicates skipped)", imported, skipped);
+        Ok((imported, skipped))
+    }
+
     /// Get habit entries for a habit within a date range.
     pub async fn get_habit_entries(
         &self,
@@ -465,14 +541,32 @@ impl VaultRepository {
             .unwrap_or_else(|| Local::now().date_naive());
 
         let (start_date, end_date) = match query.date_range {
+            HabitDateRange::Custom => {
+                let start = query
+                    .start_date
+                    .as_ref()
+                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+                    .unwrap_or(reference_date - Duration::days(6));
+                let end = query
+                    .end_date
+                    .as_ref()
+                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+                    .unwrap_or(reference_date);
+                (start.max(end - Duration::days(MAX_CUSTOM_RANGE_DAYS - 1)), end)
+            }
+            _ if query.view == HabitViewType::Heatmap => {
+                let year = reference_date.year();
+                (
+                    NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(reference_date),
+                    NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or(reference_date),
+                )
+            }
             HabitDateRange::SingleDay => (reference_date, reference_date),
             HabitDateRange::Last7Days => (reference_date - Duration::days(6), reference_date),
             HabitDateRange::Last30Days => (reference_date - Duration::days(29), reference_date),
             HabitDateRange::ThisWeek => {
-                let weekday = reference_date.weekday().num_days_from_monday();
-                let start = reference_date - Duration::days(weekday as i64);
-                let end = start + Duration::days(6);
-                (start, end)
+                let start = query.week_start.unwrap_or_default().start_of_week(reference_date);
+                (start, start + Duration::days(6))
             }
             HabitDateRange::ThisMonth => {
                 let start = NaiveDate::from_ymd_opt(reference_date.year(), reference_date.month(), 1).unwrap_or(reference_date);
@@ -484,19 +578,6 @@ impl VaultRepository {
                 let end = next_month.unwrap_or(reference_date) - Duration::days(1);
                 (start, end)
             }
-            HabitDateRange::Custom => {
-                let start = query
-                    .start_date
-                    .as_ref()
-                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
-                    .unwrap_or(reference_date - Duration::days(6));
-                let end = query
-                    .end_date
-                    .as_ref()
-                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
-                    .unwrap_or(reference_date);
-                (start, end)
-            }
         };
 
         let start_str = start_date.format("%Y-%m-%d").to_string();
@@ -520,6 +601,9 @@ impl VaultRepository {
             ordered_habits
         };
 
+        // Streaks are reported as of the end of the range, but never past today
+        let streak_as_of = end_date.min(Local::now().date_naive());
+
         // Get entries for each habit
         let mut habits_with_entries = Vec::new();
         for habit in habits {
@@ -527,6 +611,12 @@ impl VaultRepository {
                 .get_habit_entries(habit.id, &start_str, &end_str)
                 .await?;
 
+            let heatmap = if query.view == HabitViewType::Heatmap {
+                heatmap_cells(&habit, &entries, start_date, end_date)
+            } else {
+                Vec::new()
+            };
+
             // Group entries by date
             let mut entries_by_date: std::collections::HashMap<String, Vec<HabitEntryDto>> =
                 std::collections::HashMap::new();
@@ -542,9 +632,21 @@ impl VaultRepository {
                 entries_by_date.into_iter().collect();
             sorted_entries.sort_by(|a, b| a.0.cmp(&b.0));
 
+            let skipped_dates = start_date
+                .iter_days()
+                .take_while(|d| *d <= end_date)
+                .filter(|d| is_skipped(&habit.schedule, *d))
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .collect();
+
+            let streak = self.get_habit_streak(&habit, streak_as_of).await?;
+
             habits_with_entries.push(HabitWithEntries {
                 habit,
                 entries_by_date: sorted_entries,
+                skipped_dates,
+                streak,
+                heatmap,
             });
         }
 
@@ -557,3 +659,235 @@ impl VaultRepository {
         })
     }
 }
+
+/// Parse stored schedule rules, falling back to defaults (every day, no skips).
+fn parse_schedule_rules(raw: Option<&str>) -> HabitScheduleRules {
+    raw.and_then(|r| serde_json::from_str(r).ok()).unwrap_or_default()
+}
+
+/// Whether a date is skipped by the schedule rules (inactive weekday or planned skip).
+pub(super) fn is_skipped(rules: &HabitScheduleRules, date: NaiveDate) -> bool {
+    let inactive = !rules.active_weekdays.is_empty()
+        && !rules.active_weekdays.contains(&date.weekday().number_from_monday());
+    inactive || rules.skip_dates.iter().any(|d| d == &date.format("%Y-%m-%d").to_string())
+}
+
+/// Whether an entry value counts as completing the habit for that day.
+pub(super) fn entry_is_done(habit: &HabitDto, value: &str) -> bool {
+    let value = value.trim();
+    match habit.habit_type {
+        HabitType::Boolean => value == "true",
+        HabitType::Text => !value.is_empty(),
+        HabitType::Number => value
+            .parse::<f64>()
+            .is_ok_and(|v| v >= habit.target_value.unwrap_or(f64::MIN_POSITIVE)),
+        HabitType::Rating => value.parse::<f64>().is_ok_and(|v| v > 0.0),
+    }
+}
+
+/// Build heatmap cells for every day in a range, normalized to 0-1.
+///
+/// Boolean and text habits count as 1 when done. Number habits compare the
+/// day's total against the target (or the best day in range, without a
+/// target); ratings are scaled against 5 stars.
+fn heatmap_cells(
+    habit: &HabitDto,
+    entries: &[HabitEntryDto],
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Vec<HabitHeatmapCell> {
+    let mut totals: std::collections::HashMap<&str, f64> = std::collections::HashMap::new();
+    for entry in entries {
+        let value = entry.value.trim();
+        let amount = match habit.habit_type {
+            HabitType::Number | HabitType::Rating => value.parse::<f64>().unwrap_or(0.0),
+            HabitType::Boolean | HabitType::Text => {
+                if entry_is_done(habit, value) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+        let total = totals.entry(entry.date.as_str()).or_default();
+        *total = match habit.habit_type {
+            HabitType::Number => *total + amount,
+            _ => total.max(amount),
+        };
+    }
+
+    let scale = match habit.habit_type {
+        HabitType::Number => habit
+            .target_value
+            .filter(|t| *t > 0.0)
+            .unwrap_or_else(|| totals.values().copied().fold(0.0, f64::max)),
+        HabitType::Rating => 5.0,
+        HabitType::Boolean | HabitType::Text => 1.0,
+    };
+
+    start_date
+        .iter_days()
+        .take_while(|d| *d <= end_date)
+        .map(|d| {
+            let date = d.format("%Y-%m-%d").to_string();
+            let total = totals.get(date.as_str()).copied().unwrap_or(0.0);
+            let value = if scale > 0.0 { (total / scale).clamp(0.0, 1.0) } else { 0.0 };
+            HabitHeatmapCell { date, value }
+        })
+        .collect()
+}
+
+/// Compute current and longest streaks from the set of completed dates.
+///
+/// Skipped days are neutral. Up to `freeze_tokens` missed days are forgiven
+/// within a running streak, and `as_of` itself does not break a streak until it is over.
+fn compute_streak(done: &HashSet<NaiveDate>, rules: &HabitScheduleRules, as_of: NaiveDate) -> HabitStreak {
+    let Some(first) = done.iter().min().copied() else {
+        return HabitStreak::default();
+    };
+
+    let mut current = 0;
+    let mut longest = 0;
+    let mut freezes_used = 0;
+
+    for date in first.iter_days().take_while(|d| *d <= as_of) {
+        if done.contains(&date) {
+            current += 1;
+            longest = longest.max(current);
+        } else if is_skipped(rules, date) || date == as_of {
+            continue;
+        } else if current > 0 && freezes_used < rules.freeze_tokens {
+            freezes_used += 1;
+        } else {
+            current = 0;
+            freezes_used = 0;
+        }
+    }
+
+    HabitStreak {
+        current,
+        longest,
+        freezes_used,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dates(list: &[&str]) -> HashSet<NaiveDate> {
+        list.iter().map(|s| date(s)).collect()
+    }
+
+    #[test]
+    fn test_streak_without_rules() {
+        let done = dates(&["2024-01-01", "2024-01-02", "2024-01-03", "2024-01-05", "2024-01-06"]);
+        let streak = compute_streak(&done, &HabitScheduleRules::default(), date("2024-01-06"));
+        assert_eq!(streak.current, 2);
+        assert_eq!(streak.longest, 3);
+
+        // Today not done yet doesn't break the streak
+        let streak = compute_streak(&done, &HabitScheduleRules::default(), date("2024-01-07"));
+        assert_eq!(streak.current, 2);
+
+        // But yesterday missed does
+        let streak = compute_streak(&done, &HabitScheduleRules::default(), date("2024-01-08"));
+        assert_eq!(streak.current, 0);
+    }
+
+    #[test]
+    fn test_streak_with_skip_days_and_weekdays() {
+        // 2024-01-01 is a Monday; weekdays only, with Wednesday as a planned skip
+        let rules = HabitScheduleRules {
+            active_weekdays: vec![1, 2, 3, 4, 5],
+            skip_dates: vec!["2024-01-03".to_string()],
+            freeze_tokens: 0,
+        };
+        let done = dates(&["2024-01-01", "2024-01-02", "2024-01-04", "2024-01-05", "2024-01-08"]);
+        let streak = compute_streak(&done, &rules, date("2024-01-08"));
+        assert_eq!(streak.current, 5);
+        assert_eq!(streak.longest, 5);
+    }
+
+    #[test]
+    fn test_streak_with_freeze_tokens() {
+        let rules = HabitScheduleRules {
+            freeze_tokens: 1,
+            ..Default::default()
+        };
+        let done = dates(&["2024-01-01", "2024-01-03", "2024-01-04", "2024-01-07"]);
+        let streak = compute_streak(&done, &rules, date("2024-01-07"));
+        // The miss on the 2nd is frozen; the misses on the 5th and 6th break the streak
+        assert_eq!(streak.current, 1);
+        assert_eq!(streak.longest, 3);
+
+        let streak = compute_streak(&done, &rules, date("2024-01-04"));
+        assert_eq!(streak.current, 3);
+        assert_eq!(streak.freezes_used, 1);
+    }
+
+    fn habit(habit_type: HabitType, target_value: Option<f64>) -> HabitDto {
+        HabitDto {
+            id: 1,
+            name: "Habit".to_string(),
+            description: None,
+            habit_type,
+            unit: None,
+            color: None,
+            target_value,
+            archived: false,
+            sort_order: 0,
+            schedule: HabitScheduleRules::default(),
+        }
+    }
+
+    fn entry(date: &str, value: &str) -> HabitEntryDto {
+        HabitEntryDto {
+            id: 1,
+            habit_id: 1,
+            date: date.to_string(),
+            time: None,
+            value: value.to_string(),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_heatmap_cells() {
+        let start = date("2024-01-01");
+        let end = date("2024-01-03");
+
+        // Number habit with target: daily totals against 8 glasses, capped at 1
+        let entries = vec![entry("2024-01-01", "2"), entry("2024-01-01", "2"), entry("2024-01-03", "10")];
+        let cells = heatmap_cells(&habit(HabitType::Number, Some(8.0)), &entries, start, end);
+        let values: Vec<f64> = cells.iter().map(|c| c.value).collect();
+        assert_eq!(values, vec![0.5, 0.0, 1.0]);
+        assert_eq!(cells[1].date, "2024-01-02");
+
+        // Number habit without target: relative to the best day
+        let cells = heatmap_cells(&habit(HabitType::Number, None), &entries, start, end);
+        assert_eq!(cells[0].value, 0.4);
+
+        // Boolean and rating habits
+        let cells = heatmap_cells(&habit(HabitType::Boolean, None), &[entry("2024-01-02", "true")], start, end);
+        assert_eq!(cells.iter().map(|c| c.value).collect::<Vec<_>>(), vec![0.0, 1.0, 0.0]);
+        let cells = heatmap_cells(&habit(HabitType::Rating, None), &[entry("2024-01-01", "4")], start, end);
+        assert_eq!(cells[0].value, 0.8);
+    }
+
+    #[test]
+    fn test_is_skipped() {
+        let rules = HabitScheduleRules {
+            active_weekdays: vec![6, 7],
+            skip_dates: vec!["2024-01-06".to_string()],
+            freeze_tokens: 0,
+        };
+        assert!(is_skipped(&rules, date("2024-01-05"))); // Friday
+        assert!(is_skipped(&rules, date("2024-01-06"))); // Saturday, planned skip
+        assert!(!is_skipped(&rules, date("2024-01-07"))); // Sunday
+    }
+}
@@ -0,0 +1,73 @@
+//! Note heading storage for the quick switcher.
+
+use crate::Result;
+use core_index::ParsedHeading;
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Replace all headings for a note.
+    pub async fn replace_headings(&self, note_id: i64, headings: &[ParsedHeading]) -> Result<()> {
+        sqlx::query("DELETE FROM note_headings WHERE note_id = ?")
+            .bind(note_id)
+            .execute(&self.pool)
+            .await?;
+
+        for heading in headings {
+            sqlx::query(
+                "INSERT INTO note_headings (note_id, level, text, line_number) VALUES (?, ?, ?, ?)",
+            )
+            .bind(note_id)
+            .bind(heading.level as i64)
+            .bind(&heading.text)
+            .bind(heading.line_number as i64)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Get all headings in the vault as (note_id, text, line_number), in note order.
+    pub async fn list_all_headings(&self) -> Result<Vec<(i64, String, i64)>> {
+        Ok(sqlx::query_as::<_, (i64, String, i64)>(
+            "SELECT note_id, text, line_number FROM note_headings ORDER BY note_id, line_number",
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Get all aliases in the vault as (note_id, alias).
+    pub async fn list_all_aliases(&self) -> Result<Vec<(i64, String)>> {
+        Ok(sqlx::query_as::<_, (i64, String)>("SELECT note_id, alias FROM aliases ORDER BY note_id")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// A cheap fingerprint of note paths, titles, headings, and aliases.
+    ///
+    /// It changes whenever a note is indexed, renamed, or deleted, or its
+    /// aliases are replaced, so in-memory indexes know when to rebuild.
+    pub async fn get_switcher_fingerprint(&self) -> Result<String> {
+        let (notes, last_update, aliases, last_alias) =
+            sqlx::query_as::<_, (i64, Option<String>, i64, Option<i64>)>(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM notes),
+                    (SELECT MAX(updated_at) FROM notes),
+                    (SELECT COUNT(*) FROM aliases),
+                    (SELECT MAX(id) FROM aliases)
+                "#,
+            )
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(format!(
+            "{}:{}:{}:{}",
+            notes,
+            last_update.unwrap_or_default(),
+            aliases,
+            last_alias.unwrap_or_default()
+        ))
+    }
+}
@@ -0,0 +1,98 @@
+//! Text recognized in images, with the content hash of the image it was
+//! recognized from, so unchanged images aren't recognized again.
+
+use crate::Result;
+use shared_types::ImageTextMatch;
+use std::collections::HashMap;
+
+use super::{escape_like, VaultRepository};
+
+/// Characters of context shown on each side of a match.
+const SNIPPET_CONTEXT: usize = 60;
+
+impl VaultRepository {
+    /// Content hashes of the images with recognized text, by path.
+    pub async fn image_text_hashes(&self) -> Result<HashMap<String, String>> {
+        let rows = sqlx::query_as::<_, (String, String)>("SELECT path, hash FROM image_text")
+            .fetch_all(&self.read_pool)
+            .await?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Store the text recognized in an image.
+    pub async fn set_image_text(&self, path: &str, hash: &str, text: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO image_text (path, hash, text) VALUES (?, ?, ?)
+            ON CONFLICT(path) DO UPDATE SET hash = excluded.hash, text = excluded.text
+            "#,
+        )
+        .bind(path)
+        .bind(hash)
+        .bind(text)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget the text of an image that was deleted.
+    pub async fn remove_image_text(&self, path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM image_text WHERE path = ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Images whose text contains `query` (case-insensitive for ASCII), in
+    /// path order, with the text around the first match.
+    pub async fn search_image_text(&self, query: &str, limit: i32) -> Result<Vec<ImageTextMatch>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT path, text FROM image_text
+            WHERE text LIKE '%' || ? || '%' ESCAPE '\'
+            ORDER BY path
+            LIMIT ?
+            "#,
+        )
+        .bind(escape_like(query))
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(path, text)| ImageTextMatch {
+                snippet: snippet(&text, query),
+                path,
+            })
+            .collect())
+    }
+}
+
+/// The text around the first match of `query`, on one line.
+fn snippet(text: &str, query: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let Some(start) = text.to_ascii_lowercase().find(&query.to_ascii_lowercase()) else {
+        return text.chars().take(SNIPPET_CONTEXT * 2).collect();
+    };
+
+    let from = text[..start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT - 1)
+        .map_or(0, |(i, _)| i);
+    let to = text[start..]
+        .char_indices()
+        .nth(query.chars().count() + SNIPPET_CONTEXT)
+        .map_or(text.len(), |(i, _)| start + i);
+
+    let prefix = if from > 0 { "…" } else { "" };
+    let suffix = if to < text.len() { "…" } else { "" };
+    format!("{}{}{}", prefix, &text[from..to], suffix)
+}
@@ -0,0 +1,267 @@
+//! Background job queue.
+//!
+//! Jobs are queued here and run one at a time, oldest first. A job that was
+//! running when the app quit is queued again by `requeue_interrupted_jobs`,
+//! or marked failed if it can't start over (see `JobKind::can_start_over`).
+
+use crate::Result;
+use chrono::Utc;
+use shared_types::{JobDto, JobRequest, JobStatus};
+use tracing::{debug, instrument, warn};
+
+use super::VaultRepository;
+
+type JobRow = (
+    i64,
+    String,
+    String,
+    i64,
+    i64,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<String>,
+);
+
+const JOB_COLUMNS: &str =
+    "id, status, request, processed, total, message, created_at, started_at, finished_at";
+
+/// Message of an interrupted job that can't start over.
+const INTERRUPTED_MESSAGE: &str = "Interrupted when the app quit; check what it already did before retrying it";
+
+fn job_from_row(row: JobRow) -> Option<JobDto> {
+    let (id, status, request, processed, total, message, created_at, started_at, finished_at) = row;
+    let request: JobRequest = match serde_json::from_str(&request) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Skipping job {} with unreadable request: {}", id, e);
+            return None;
+        }
+    };
+    let status = match JobStatus::from_string(&status) {
+        Ok(status) => status,
+        Err(e) => {
+            warn!("Skipping job {}: {}", id, e);
+            return None;
+        }
+    };
+
+    Some(JobDto {
+        id,
+        kind: request.kind(),
+        status,
+        request,
+        processed,
+        total,
+        message,
+        created_at,
+        started_at,
+        finished_at,
+    })
+}
+
+impl VaultRepository {
+    /// Queue a job.
+    #[instrument(skip(self))]
+    pub async fn create_job(&self, request: &JobRequest) -> Result<JobDto> {
+        let json = serde_json::to_string(request).unwrap_or_default();
+        let created_at = Utc::now().to_rfc3339();
+
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO jobs (kind, status, request, created_at) VALUES (?, ?, ?, ?) RETURNING id",
+        )
+        .bind(request.kind().as_str())
+        .bind(JobStatus::Queued.as_str())
+        .bind(&json)
+        .bind(&created_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        debug!("Queued job {} ({:?})", id, request.kind());
+        Ok(JobDto {
+            id,
+            kind: request.kind(),
+            status: JobStatus::Queued,
+            request: request.clone(),
+            processed: 0,
+            total: 0,
+            message: None,
+            created_at,
+            started_at: None,
+            finished_at: None,
+        })
+    }
+
+    /// Get a job by ID.
+    pub async fn get_job(&self, id: i64) -> Result<Option<JobDto>> {
+        let row = sqlx::query_as::<_, JobRow>(&format!("SELECT {} FROM jobs WHERE id = ?", JOB_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.and_then(job_from_row))
+    }
+
+    /// List all jobs, newest first.
+    pub async fn list_jobs(&self) -> Result<Vec<JobDto>> {
+        let rows = sqlx::query_as::<_, JobRow>(&format!("SELECT {} FROM jobs ORDER BY id DESC", JOB_COLUMNS))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().filter_map(job_from_row).collect())
+    }
+
+    /// Take the oldest queued job and mark it as running.
+    #[instrument(skip(self))]
+    pub async fn start_next_job(&self) -> Result<Option<JobDto>> {
+        let row = sqlx::query_as::<_, JobRow>(&format!(
+            r#"
+            UPDATE jobs SET status = ?, started_at = ?, finished_at = NULL
+            WHERE id = (SELECT id FROM jobs WHERE status = ? ORDER BY id LIMIT 1)
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(JobStatus::Running.as_str())
+        .bind(Utc::now().to_rfc3339())
+        .bind(JobStatus::Queued.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(job_from_row))
+    }
+
+    /// Whether any job is waiting to run.
+    pub async fn has_queued_jobs(&self) -> Result<bool> {
+        let queued = sqlx::query_scalar::<_, i64>("SELECT EXISTS(SELECT 1 FROM jobs WHERE status = ?)")
+            .bind(JobStatus::Queued.as_str())
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(queued != 0)
+    }
+
+    /// Cancel a job that hasn't started yet. Returns `None` if the job
+    /// doesn't exist or isn't queued.
+    #[instrument(skip(self))]
+    pub async fn cancel_queued_job(&self, id: i64) -> Result<Option<JobDto>> {
+        let result = sqlx::query("UPDATE jobs SET status = ?, finished_at = ? WHERE id = ? AND status = ?")
+            .bind(JobStatus::Cancelled.as_str())
+            .bind(Utc::now().to_rfc3339())
+            .bind(id)
+            .bind(JobStatus::Queued.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        debug!("Cancelled queued job {}", id);
+        self.get_job(id).await
+    }
+
+    /// Record how a job ended.
+    #[instrument(skip(self))]
+    pub async fn finish_job(
+        &self,
+        id: i64,
+        status: JobStatus,
+        processed: i64,
+        total: i64,
+        message: Option<&str>,
+    ) -> Result<Option<JobDto>> {
+        sqlx::query(
+            "UPDATE jobs SET status = ?, processed = ?, total = ?, message = ?, finished_at = ? WHERE id = ?",
+        )
+        .bind(status.as_str())
+        .bind(processed)
+        .bind(total)
+        .bind(message)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Job {} finished as {:?}", id, status);
+        self.get_job(id).await
+    }
+
+    /// Queue a failed or cancelled job again. Returns `None` if the job
+    /// doesn't exist or hasn't failed or been cancelled.
+    #[instrument(skip(self))]
+    pub async fn retry_job(&self, id: i64) -> Result<Option<JobDto>> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs SET status = ?, processed = 0, total = 0, message = NULL,
+                started_at = NULL, finished_at = NULL
+            WHERE id = ? AND status IN (?, ?)
+            "#,
+        )
+        .bind(JobStatus::Queued.as_str())
+        .bind(id)
+        .bind(JobStatus::Failed.as_str())
+        .bind(JobStatus::Cancelled.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        debug!("Requeued job {}", id);
+        self.get_job(id).await
+    }
+
+    /// Queue jobs that were running when the app last quit. Jobs that can't
+    /// start over are marked failed instead, to be retried by hand once what
+    /// they did is checked. Returns how many were queued again.
+    #[instrument(skip(self))]
+    pub async fn requeue_interrupted_jobs(&self) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query_as::<_, JobRow>(&format!("SELECT {} FROM jobs WHERE status = ?", JOB_COLUMNS))
+            .bind(JobStatus::Running.as_str())
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let mut requeued = 0;
+        for job in rows.into_iter().filter_map(job_from_row) {
+            if job.kind.can_start_over() {
+                sqlx::query("UPDATE jobs SET status = ?, started_at = NULL WHERE id = ?")
+                    .bind(JobStatus::Queued.as_str())
+                    .bind(job.id)
+                    .execute(&mut *tx)
+                    .await?;
+                requeued += 1;
+            } else {
+                sqlx::query("UPDATE jobs SET status = ?, message = ?, finished_at = ? WHERE id = ?")
+                    .bind(JobStatus::Failed.as_str())
+                    .bind(INTERRUPTED_MESSAGE)
+                    .bind(Utc::now().to_rfc3339())
+                    .bind(job.id)
+                    .execute(&mut *tx)
+                    .await?;
+                warn!("Job {} ({:?}) was interrupted and can't start over", job.id, job.kind);
+            }
+        }
+        tx.commit().await?;
+
+        if requeued > 0 {
+            debug!("Requeued {} interrupted jobs", requeued);
+        }
+        Ok(requeued)
+    }
+
+    /// Delete completed, cancelled and failed jobs. Returns how many were
+    /// deleted.
+    #[instrument(skip(self))]
+    pub async fn clear_finished_jobs(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM jobs WHERE status IN (?, ?, ?)")
+            .bind(JobStatus::Completed.as_str())
+            .bind(JobStatus::Cancelled.as_str())
+            .bind(JobStatus::Failed.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Cleared {} finished jobs", result.rows_affected());
+        Ok(result.rows_affected())
+    }
+}
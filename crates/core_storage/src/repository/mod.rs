@@ -4,21 +4,56 @@
 //! organized into submodules by domain:
 //!
 //! - `notes` - Note CRUD operations
-//! - `tags` - Tag management
+//! - `tags` - Tag management, aliases and tag metadata
 //! - `backlinks` - Backlink tracking
 //! - `todos` - Todo/task operations
+//! - `contexts` - GTD context registry and open tasks per context
+//! - `suggestions` - Context-aware task suggestions
 //! - `schedule` - Schedule block operations
+//! - `schedule_templates` - Reusable schedule routines
+//! - `calendar_subscriptions` - Read-only overlay of external ICS calendars
+//! - `block_sessions` - Focus sessions and planned versus actual time
+//! - `schedule_analytics` - Context switching report
+//! - `planning` - Automatic day planning from tasks
+//! - `overview` - Combined week overview for the weekly planner
 //! - `properties` - Property management
 //! - `queries` - Query builder and search
 //! - `dates` - Notes by date operations
 //! - `aliases` - Note alias management
 //! - `embeddings` - Vector embedding storage and search
+//! - `mood` - Daily mood tracking
+//! - `goals` - Goals and goal progress
+//! - `reviews` - Note review queue for resurfacing old notes
+//! - `discovery` - Random notes and notes from this day in past years
+//! - `workspace` - Open tabs and sidebar layout
+//! - `headings` - Note headings and switcher lookups
+//! - `autocomplete` - Terms for the autocomplete index
+//! - `search_presets` - Named searches with filters
+//! - `folders` - Folder appearance and manual sort order
+//! - `jobs` - Background job queue
+//! - `assets` - Attachment content hashes for deduplication
+//! - `diagnostics` - Table sizes and schema for diagnostics bundles
+//! - `drafts` - Unsaved editor content for crash recovery
+//! - `file_problems` - Files with invalid UTF-8 or binary content
+//! - `board_ranks` - Manual card order on query Kanban boards
+//! - `data_versions` - Change counters of tables, for cache fingerprints
+//! - `scheduled_notes` - Rules creating notes from templates on a schedule
+//! - `growth` - Daily snapshots of vault metrics
+//! - `image_text` - Text recognized in images
 
 mod notes;
 mod tags;
 mod backlinks;
 mod todos;
+mod contexts;
+mod suggestions;
 mod schedule;
+mod schedule_templates;
+mod calendar_subscriptions;
+mod block_sessions;
+mod schedule_analytics;
+mod planning;
+mod overview;
 mod properties;
 mod folder_properties;
 mod queries;
@@ -26,26 +61,102 @@ mod dates;
 mod aliases;
 mod habits;
 mod embeddings;
+mod mood;
+mod goals;
+mod reviews;
+mod discovery;
+mod workspace;
+mod headings;
+mod autocomplete;
+mod search_presets;
+mod folders;
+mod jobs;
+mod assets;
+mod diagnostics;
+mod drafts;
+mod file_problems;
+mod board_ranks;
+mod data_versions;
+mod scheduled_notes;
+mod growth;
+mod image_text;
 
-pub use embeddings::VectorSearchResult;
-pub use embeddings::extract_content_preview;
+pub use autocomplete::AutocompleteTerm;
+pub use properties::CSS_CLASSES_PROPERTY;
+pub use embeddings::{ChunkEmbedding, VectorSearchResult};
+pub use embeddings::{content_signature, extract_content_preview, mean_embedding};
+pub(crate) use properties::{split_list, store_typed_values};
+pub use schedule::validate_timezone;
 
+use crate::Result;
 use sqlx::SqlitePool;
+use std::path::Path;
 
 /// Repository for vault database operations.
+///
+/// Listing, search and queries run on a separate read pool when one is
+/// set, so they aren't queued behind the writes of an import or full index.
+/// This needs the database in WAL mode, where readers don't wait for the
+/// writer.
 #[derive(Clone)]
 pub struct VaultRepository {
     pool: SqlitePool,
+    read_pool: SqlitePool,
 }
 
 impl VaultRepository {
     /// Create a new repository with the given connection pool.
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            read_pool: pool.clone(),
+            pool,
+        }
+    }
+
+    /// Use a separate (read-only) pool for listing, search and queries.
+    pub fn with_read_pool(mut self, read_pool: SqlitePool) -> Self {
+        self.read_pool = read_pool;
+        self
     }
 
     /// Get the underlying pool (for transactions, etc.).
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
+
+    /// Get the pool for read-only queries (the main pool if none was set).
+    pub fn read_pool(&self) -> &SqlitePool {
+        &self.read_pool
+    }
+
+    /// Close both pools.
+    pub async fn close(&self) {
+        self.read_pool.close().await;
+        self.pool.close().await;
+    }
+
+    /// Write a consistent copy of the database to `path` (which must not
+    /// exist yet) while it stays in use, e.g. for a backup.
+    pub async fn copy_database(&self, path: &Path) -> Result<()> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy().to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Most IDs bound in one `IN (...)` list, to stay below SQLite's limit on
+/// variables in a statement.
+pub(crate) const MAX_BOUND_IDS: usize = 500;
+
+/// IDs as a JSON array, bound as one variable for
+/// `IN (SELECT value FROM json_each(?))` where the IDs can't be batched.
+pub(crate) fn id_array(ids: &[i64]) -> String {
+    format!("[{}]", ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(","))
+}
+
+/// Escape `%`, `_` and `\` for a `LIKE ... ESCAPE '\'` pattern.
+pub(crate) fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
 }
@@ -0,0 +1,126 @@
+//! Mood tracking operations.
+
+use crate::{Result, StorageError};
+use chrono::{NaiveDate, Utc};
+use shared_types::{MoodEntryDto, MoodTrend, MoodWeekSummary, WeekStart};
+use std::collections::BTreeMap;
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Log the mood for a date (one entry per day; logging again replaces it).
+    /// Fails with `InvalidDate` unless `date` is a `YYYY-MM-DD` calendar date.
+    #[instrument(skip(self))]
+    pub async fn log_mood(&self, date: &str, score: i32, note: Option<&str>) -> Result<i64> {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| StorageError::InvalidDate(date.to_string()))?
+            .format("%Y-%m-%d")
+            .to_string();
+        let now = Utc::now().to_rfc3339();
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO mood_entries (date, score, note, created_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(date) DO UPDATE SET
+                score = excluded.score,
+                note = excluded.note
+            RETURNING id
+            "#,
+        )
+        .bind(&date)
+        .bind(score)
+        .bind(note)
+        .bind(&now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        debug!("Logged mood {} for {} (id={})", score, date, id);
+        Ok(id)
+    }
+
+    /// Get the mood entry for a date.
+    pub async fn get_mood(&self, date: &str) -> Result<Option<MoodEntryDto>> {
+        let row = sqlx::query_as::<_, (i64, String, i32, Option<String>)>(
+            "SELECT id, date, score, note FROM mood_entries WHERE date = ?",
+        )
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(id, date, score, note)| MoodEntryDto {
+            id,
+            date,
+            score,
+            note,
+        }))
+    }
+
+    /// Delete the mood entry for a date.
+    #[instrument(skip(self))]
+    pub async fn delete_mood(&self, date: &str) -> Result<()> {
+        sqlx::query("DELETE FROM mood_entries WHERE date = ?")
+            .bind(date)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Get mood entries and aggregates (overall and weekly, with weeks
+    /// starting on `week_start`) for a date range.
+    pub async fn get_mood_trend(&self, start_date: &str, end_date: &str, week_start: WeekStart) -> Result<MoodTrend> {
+        let rows = sqlx::query_as::<_, (i64, String, i32, Option<String>)>(
+            "SELECT id, date, score, note FROM mood_entries WHERE date >= ? AND date <= ? ORDER BY date",
+        )
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let entries: Vec<MoodEntryDto> = rows
+            .into_iter()
+            .map(|(id, date, score, note)| MoodEntryDto {
+                id,
+                date,
+                score,
+                note,
+            })
+            .collect();
+
+        let average = if entries.is_empty() {
+            None
+        } else {
+            Some(entries.iter().map(|e| e.score as f64).sum::<f64>() / entries.len() as f64)
+        };
+        let min = entries.iter().map(|e| e.score).min();
+        let max = entries.iter().map(|e| e.score).max();
+
+        // Group by the first day of each week
+        let mut weeks: BTreeMap<NaiveDate, (i64, i64)> = BTreeMap::new();
+        for entry in &entries {
+            if let Ok(date) = entry.date.parse::<NaiveDate>() {
+                let week = weeks.entry(week_start.start_of_week(date)).or_default();
+                week.0 += entry.score as i64;
+                week.1 += 1;
+            }
+        }
+
+        let weekly = weeks
+            .into_iter()
+            .map(|(week_start, (sum, count))| MoodWeekSummary {
+                week_start: week_start.format("%Y-%m-%d").to_string(),
+                average: sum as f64 / count as f64,
+                count,
+            })
+            .collect();
+
+        Ok(MoodTrend {
+            entries,
+            average,
+            min,
+            max,
+            weekly,
+        })
+    }
+}
@@ -3,10 +3,10 @@
 use crate::{Result, StorageError};
 use chrono::{DateTime, Utc};
 use core_index::NoteAnalysis;
-use shared_types::{NoteDto, NoteListItem};
+use shared_types::{NoteDto, NoteListItem, NoteSummaryDto};
 use tracing::{debug, instrument};
 
-use super::VaultRepository;
+use super::{escape_like, VaultRepository};
 
 impl VaultRepository {
     /// Insert or update a note.
@@ -54,6 +54,7 @@ impl VaultRepository {
         .fetch_optional(&self.pool)
         .await?
         .ok_or(StorageError::NoteNotFound(id))?;
+        let cssclasses = self.get_css_classes(row.0).await?;
 
         Ok(NoteDto {
             id: row.0,
@@ -62,6 +63,7 @@ impl VaultRepository {
             created_at: row.3.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
             updated_at: row.4.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
             pinned: row.5 != 0,
+            cssclasses,
         })
     }
 
@@ -74,6 +76,7 @@ impl VaultRepository {
         .fetch_optional(&self.pool)
         .await?
         .ok_or_else(|| StorageError::NoteNotFoundByPath(path.to_string()))?;
+        let cssclasses = self.get_css_classes(row.0).await?;
 
         Ok(NoteDto {
             id: row.0,
@@ -82,6 +85,7 @@ impl VaultRepository {
             created_at: row.3.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
             updated_at: row.4.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
             pinned: row.5 != 0,
+            cssclasses,
         })
     }
 
@@ -103,12 +107,49 @@ impl VaultRepository {
         Ok(result)
     }
 
+    /// Every note's stored hash and when it was last indexed, as
+    /// (id, path, hash, updated_at), by path.
+    pub async fn list_note_hashes(&self) -> Result<Vec<(i64, String, Option<String>, Option<DateTime<Utc>>)>> {
+        let rows = sqlx::query_as::<_, (i64, String, Option<String>, Option<String>)>(
+            "SELECT id, path, hash, updated_at FROM notes ORDER BY path",
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, path, hash, updated_at)| {
+                let updated_at =
+                    updated_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc)));
+                (id, path, hash, updated_at)
+            })
+            .collect())
+    }
+
+    /// Check whether a note is marked `encrypted: true`.
+    pub async fn is_note_encrypted(&self, note_id: i64) -> Result<bool> {
+        let encrypted = sqlx::query_scalar::<_, i64>("SELECT encrypted FROM notes WHERE id = ?")
+            .bind(note_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(encrypted.unwrap_or(0) != 0)
+    }
+
+    /// Check whether a note was over the size limit when it was last indexed.
+    pub async fn is_note_truncated(&self, note_id: i64) -> Result<bool> {
+        let truncated = sqlx::query_scalar::<_, i64>("SELECT truncated FROM notes WHERE id = ?")
+            .bind(note_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(truncated.unwrap_or(0) != 0)
+    }
+
     /// List all notes.
     pub async fn list_notes(&self) -> Result<Vec<NoteListItem>> {
         let rows = sqlx::query_as::<_, (i64, String, Option<String>, i32)>(
             "SELECT id, path, title, pinned FROM notes ORDER BY path",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await?;
 
         Ok(rows
@@ -122,6 +163,48 @@ impl VaultRepository {
             .collect())
     }
 
+    /// Get link, task, and word counts and the latest schedule block for a note.
+    ///
+    /// Returns None if the note doesn't exist.
+    pub async fn get_note_summary(&self, note_id: i64) -> Result<Option<NoteSummaryDto>> {
+        let row = sqlx::query_as::<_, (i64, i64, i64, i64, i64, Option<i64>)>(
+            r#"
+            SELECT
+                (SELECT COUNT(DISTINCT from_note_id) FROM backlinks WHERE to_note_id = n.id),
+                (SELECT COUNT(DISTINCT to_note_id) FROM backlinks WHERE from_note_id = n.id),
+                (SELECT COUNT(*) FROM todos WHERE note_id = n.id AND completed = 0),
+                (SELECT COUNT(*) FROM todos WHERE note_id = n.id),
+                COALESCE(n.word_count, 0),
+                (SELECT id FROM schedule_blocks WHERE note_id = n.id
+                 ORDER BY date DESC, start_time DESC LIMIT 1)
+            FROM notes n
+            WHERE n.id = ?
+            "#,
+        )
+        .bind(note_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((backlink_count, outgoing_link_count, open_todo_count, todo_count, word_count, block_id)) = row else {
+            return Ok(None);
+        };
+
+        let last_schedule_block = match block_id {
+            Some(id) => self.get_schedule_block(id).await?,
+            None => None,
+        };
+
+        Ok(Some(NoteSummaryDto {
+            note_id,
+            backlink_count,
+            outgoing_link_count,
+            open_todo_count,
+            todo_count,
+            word_count,
+            last_schedule_block,
+        }))
+    }
+
     /// Delete a note by path.
     #[instrument(skip(self))]
     pub async fn delete_note(&self, path: &str) -> Result<Option<i64>> {
@@ -131,6 +214,8 @@ impl VaultRepository {
             .await?;
 
         if let Some(note_id) = id {
+            // Also covered by the foreign key, but don't rely on it being enforced
+            self.delete_embedding(note_id).await?;
             sqlx::query("DELETE FROM notes WHERE id = ?")
                 .bind(note_id)
                 .execute(&self.pool)
@@ -149,6 +234,15 @@ impl VaultRepository {
         Ok(count)
     }
 
+    /// Whether any notes are indexed in a folder or its subfolders.
+    pub async fn has_notes_in_folder(&self, folder: &str) -> Result<bool> {
+        let exists = sqlx::query_scalar::<_, bool>(r"SELECT EXISTS (SELECT 1 FROM notes WHERE path LIKE ? ESCAPE '\')")
+            .bind(format!("{}/%", escape_like(folder.trim_matches('/'))))
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(exists)
+    }
+
     /// Rename/move a note (update its path).
     #[instrument(skip(self))]
     pub async fn rename_note(&self, old_path: &str, new_path: &str) -> Result<i64> {
@@ -158,8 +252,9 @@ impl VaultRepository {
             .await?
             .ok_or_else(|| StorageError::NoteNotFoundByPath(old_path.to_string()))?;
 
-        sqlx::query("UPDATE notes SET path = ?, updated_at = datetime('now') WHERE id = ?")
+        sqlx::query("UPDATE notes SET path = ?, updated_at = ? WHERE id = ?")
             .bind(new_path)
+            .bind(Utc::now().to_rfc3339())
             .bind(note_id)
             .execute(&self.pool)
             .await?;
@@ -174,6 +269,10 @@ impl VaultRepository {
     /// Properties are stored in the database only and managed via the
     /// PropertiesPanel. If users type frontmatter in the editor, it will
     /// be converted to DB properties via the frontmatter conversion extension.
+    ///
+    /// Encrypted notes are removed from full-text search and embeddings.
+    /// `content` is what full-text search indexes: for oversized notes
+    /// (`analysis.truncated`), only their start. Their embeddings are removed.
     #[instrument(skip(self, content, analysis))]
     pub async fn index_note(
         &self,
@@ -182,15 +281,53 @@ impl VaultRepository {
         hash: &str,
         analysis: &NoteAnalysis,
     ) -> Result<i64> {
+        let note_id = self.index_note_deferring_fts(path, hash, analysis).await?;
+        if !analysis.encrypted {
+            self.update_fts(note_id, content).await?;
+        }
+
+        debug!("Indexed note {} (id={})", path, note_id);
+        Ok(note_id)
+    }
+
+    /// Index what a save must update right away (the note row, tags,
+    /// aliases, todos, links and headings), and mark the note's full-text entry as pending.
+    ///
+    /// Until `update_pending_fts` catches up, search still finds the note by
+    /// its previous content, with results flagged as stale.
+    #[instrument(skip(self, analysis))]
+    pub async fn index_note_deferring_fts(&self, path: &str, hash: &str, analysis: &NoteAnalysis) -> Result<i64> {
         let note_id = self.upsert_note(path, analysis.title.as_deref(), hash).await?;
 
         self.replace_tags(note_id, &analysis.tags).await?;
+        self.replace_aliases(note_id, &analysis.aliases).await?;
         self.replace_todos(note_id, &analysis.todos).await?;
         self.replace_backlinks(note_id, &analysis.links).await?;
+        self.mark_embed_targets(note_id, &analysis.embeds).await?;
+        self.replace_headings(note_id, &analysis.headings).await?;
         // Properties are DB-only, not synced from frontmatter
-        self.update_fts(note_id, content).await?;
 
-        debug!("Indexed note {} (id={})", path, note_id);
+        sqlx::query("UPDATE notes SET encrypted = ?, word_count = ?, truncated = ?, fts_pending = ? WHERE id = ?")
+            .bind(analysis.encrypted)
+            .bind(analysis.word_count as i64)
+            .bind(analysis.truncated)
+            .bind(!analysis.encrypted)
+            .bind(note_id)
+            .execute(&self.pool)
+            .await?;
+
+        if analysis.encrypted {
+            // Encrypted notes are never searchable, not even their ciphertext
+            sqlx::query("DELETE FROM notes_fts WHERE rowid = ?")
+                .bind(note_id)
+                .execute(&self.pool)
+                .await?;
+            self.delete_embedding(note_id).await?;
+        } else if analysis.truncated {
+            // Oversized notes are never embedded
+            self.delete_embedding(note_id).await?;
+        }
+
         Ok(note_id)
     }
 }
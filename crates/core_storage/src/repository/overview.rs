@@ -0,0 +1,128 @@
+//! Week overview: schedule, tasks, habits, and note counts in one payload.
+//! A single day (for the printable agenda) is built the same way.
+
+use crate::Result;
+use chrono::{Duration, NaiveDate};
+use shared_types::{DayOverview, HabitDaySummary, TaskQuery, WeekOverview};
+use std::collections::{HashMap, HashSet};
+use tracing::instrument;
+
+use super::habits::{entry_is_done, is_skipped};
+use super::VaultRepository;
+
+/// Maximum number of tasks loaded for a week overview.
+const MAX_WEEK_TASKS: i32 = 1000;
+
+impl VaultRepository {
+    /// Build the overview for the seven days starting at `start_date`.
+    ///
+    /// Tasks are assigned to their due date; incomplete tasks without a due
+    /// date appear on the days their note is scheduled.
+    #[instrument(skip(self))]
+    pub async fn get_week_overview(&self, start_date: NaiveDate) -> Result<WeekOverview> {
+        Ok(WeekOverview {
+            start_date,
+            end_date: start_date + Duration::days(6),
+            days: self.overview_days(start_date, 7).await?,
+        })
+    }
+
+    /// Build the overview of a single day, like a day of the week overview.
+    #[instrument(skip(self))]
+    pub async fn get_day_overview(&self, date: NaiveDate) -> Result<DayOverview> {
+        let mut days = self.overview_days(date, 1).await?;
+        Ok(days.remove(0))
+    }
+
+    /// The overviews of `count` days starting at `start_date`.
+    async fn overview_days(&self, start_date: NaiveDate, count: usize) -> Result<Vec<DayOverview>> {
+        let end_date = start_date + Duration::days(count as i64 - 1);
+        let start_str = start_date.format("%Y-%m-%d").to_string();
+        let end_str = end_date.format("%Y-%m-%d").to_string();
+
+        let blocks = self.get_schedule_blocks_for_range(&start_str, &end_str).await?;
+
+        let due_tasks = self
+            .query_tasks(&TaskQuery {
+                due_from: Some(start_str.clone()),
+                due_to: Some(end_str.clone()),
+                limit: Some(MAX_WEEK_TASKS),
+                ..Default::default()
+            })
+            .await?;
+
+        // Incomplete tasks without a due date in the scheduled notes,
+        // filtered before the cap so other notes' tasks can't crowd them out
+        let mut scheduled_notes: Vec<i64> = blocks.iter().filter_map(|b| b.note_id).collect();
+        scheduled_notes.sort_unstable();
+        scheduled_notes.dedup();
+        let undated = [("{t}.completed = ? AND {t}.due_date IS NULL".to_string(), "0".to_string())];
+        let undated_tasks = self
+            .query_tasks_by_note_ids(&scheduled_notes, false, &undated, MAX_WEEK_TASKS, None)
+            .await?;
+
+        let habits = self.list_habits(false).await?;
+        let entries = sqlx::query_as::<_, (i64, String, Option<String>)>(
+            "SELECT habit_id, date, value FROM habit_entries WHERE date >= ? AND date <= ?",
+        )
+        .bind(&start_str)
+        .bind(&end_str)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let habits_by_id: HashMap<i64, _> = habits.iter().map(|h| (h.id, h)).collect();
+        let done: HashSet<(i64, String)> = entries
+            .into_iter()
+            .filter(|(habit_id, _, value)| {
+                match (habits_by_id.get(habit_id), value.as_deref()) {
+                    (Some(habit), Some(v)) => entry_is_done(habit, v),
+                    _ => false,
+                }
+            })
+            .map(|(habit_id, date, _)| (habit_id, date))
+            .collect();
+
+        let note_counts: HashMap<String, i64> = self
+            .get_notes_for_date_range(&start_str, &end_str)
+            .await?
+            .into_iter()
+            .map(|(date, notes)| (date, notes.len() as i64))
+            .collect();
+
+        let mut days = Vec::with_capacity(count);
+        for date in start_date.iter_days().take(count) {
+            let date_str = date.format("%Y-%m-%d").to_string();
+
+            let day_blocks: Vec<_> = blocks.iter().filter(|b| b.date == date).cloned().collect();
+            let day_notes: HashSet<i64> = day_blocks.iter().filter_map(|b| b.note_id).collect();
+
+            let tasks = due_tasks
+                .iter()
+                .filter(|t| t.todo.due_date.as_deref() == Some(date_str.as_str()))
+                .chain(undated_tasks.iter().filter(|t| day_notes.contains(&t.todo.note_id)))
+                .cloned()
+                .collect();
+
+            let habit_summaries = habits
+                .iter()
+                .map(|h| HabitDaySummary {
+                    habit_id: h.id,
+                    name: h.name.clone(),
+                    color: h.color.clone(),
+                    done: done.contains(&(h.id, date_str.clone())),
+                    skipped: is_skipped(&h.schedule, date),
+                })
+                .collect();
+
+            days.push(DayOverview {
+                date,
+                blocks: day_blocks,
+                tasks,
+                habits: habit_summaries,
+                note_count: note_counts.get(&date_str).copied().unwrap_or(0),
+            });
+        }
+
+        Ok(days)
+    }
+}
@@ -0,0 +1,300 @@
+//! Day planning: fill free time with top-priority tasks and compare
+//! estimated task effort against available time.
+
+use crate::Result;
+use chrono::{Duration, NaiveDate, NaiveTime, Timelike};
+use shared_types::{DayPlan, DayWorkload, PlanDayConstraints, PlannedBlock, TaskQuery};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+/// Default duration for tasks without an estimate (minutes).
+const DEFAULT_TASK_MINUTES: i32 = 30;
+
+/// Maximum number of candidate tasks considered when no limit is given.
+const DEFAULT_MAX_CANDIDATES: i32 = 50;
+
+/// Maximum number of days covered by a single workload request.
+const MAX_WORKLOAD_DAYS: i64 = 366;
+
+impl VaultRepository {
+    /// Plan a day by placing incomplete tasks into free time within working hours.
+    ///
+    /// Tasks are taken in due-date/priority order and placed first-fit into the
+    /// gaps between existing schedule blocks. When `create_blocks` is set, a
+    /// schedule block linked to the task's note is created for each placement.
+    #[instrument(skip(self))]
+    pub async fn plan_day(
+        &self,
+        date: NaiveDate,
+        constraints: &PlanDayConstraints,
+    ) -> Result<DayPlan> {
+        let date_str = date.to_string();
+        let default_minutes = constraints
+            .default_task_minutes
+            .filter(|m| *m > 0)
+            .unwrap_or(DEFAULT_TASK_MINUTES);
+        let buffer = constraints.buffer_minutes.unwrap_or(0).max(0) as u32;
+
+        let busy: Vec<(u32, u32)> = self
+            .get_schedule_blocks_for_date(&date_str)
+            .await?
+            .iter()
+            .map(|b| (minutes_of(b.start_time), minutes_of(b.end_time)))
+            .collect();
+
+        let mut free = free_slots(
+            minutes_of(constraints.work_start),
+            minutes_of(constraints.work_end),
+            &busy,
+        );
+
+        let candidates = self
+            .query_tasks(&TaskQuery {
+                completed: Some(false),
+                context: constraints.context.clone(),
+                limit: Some(constraints.max_tasks.unwrap_or(DEFAULT_MAX_CANDIDATES)),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut planned = Vec::new();
+        let mut unplanned_todo_ids = Vec::new();
+
+        for task in candidates {
+            let estimated_minutes = task.todo.estimate_minutes;
+            let duration = estimated_minutes.unwrap_or(default_minutes) as u32;
+
+            let Some((start, end)) = take_slot(&mut free, duration, buffer) else {
+                unplanned_todo_ids.push(task.todo.id);
+                continue;
+            };
+
+            let start_time = time_of(start);
+            let end_time = time_of(end);
+
+            let block_id = if constraints.create_blocks {
+                Some(
+                    self.create_schedule_block(
+                        Some(task.todo.note_id),
+                        &date_str,
+                        &start_time.to_string(),
+                        &end_time.to_string(),
+                        Some(&task.todo.description),
+                        None,
+                        task.todo.context.as_deref(),
+                        None,
+                    )
+                    .await?,
+                )
+            } else {
+                None
+            };
+
+            planned.push(PlannedBlock {
+                todo_id: task.todo.id,
+                note_id: task.todo.note_id,
+                description: task.todo.description,
+                start_time,
+                end_time,
+                estimated_minutes,
+                block_id,
+            });
+        }
+
+        let free_minutes = free.iter().map(|(s, e)| (e - s) as i64).sum();
+
+        debug!(
+            "Planned {} tasks for {} ({} did not fit, {} free minutes left)",
+            planned.len(),
+            date,
+            unplanned_todo_ids.len(),
+            free_minutes
+        );
+
+        Ok(DayPlan {
+            date,
+            planned,
+            unplanned_todo_ids,
+            free_minutes,
+        })
+    }
+
+    /// Compute estimated task effort versus free time for each day in a range.
+    ///
+    /// An incomplete task counts towards its due date. Tasks without a due date
+    /// count towards the days their note is scheduled on. Schedule blocks linked
+    /// to a note with tasks on that day are treated as time reserved for those
+    /// tasks rather than as busy time.
+    #[instrument(skip(self))]
+    pub async fn get_workload(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        work_start: NaiveTime,
+        work_end: NaiveTime,
+    ) -> Result<Vec<DayWorkload>> {
+        let end_date = end_date.min(start_date + Duration::days(MAX_WORKLOAD_DAYS - 1));
+
+        let blocks = self
+            .get_schedule_blocks_for_range(&start_date.to_string(), &end_date.to_string())
+            .await?;
+
+        let todos = sqlx::query_as::<_, (i64, Option<String>, Option<i32>)>(
+            "SELECT note_id, due_date, estimate_minutes FROM todos WHERE completed = 0",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Days each note is scheduled on (via linked blocks)
+        let mut scheduled_days: HashMap<i64, HashSet<NaiveDate>> = HashMap::new();
+        for block in &blocks {
+            if let Some(note_id) = block.note_id {
+                scheduled_days.entry(note_id).or_default().insert(block.date);
+            }
+        }
+
+        // (estimated minutes, task count, unestimated count, notes with tasks) per day
+        let mut per_day: HashMap<NaiveDate, (i64, i64, i64, HashSet<i64>)> = HashMap::new();
+        for (note_id, due_date, estimate) in todos {
+            let days: Vec<NaiveDate> = match due_date.and_then(|d| d.parse::<NaiveDate>().ok()) {
+                Some(due) if due >= start_date && due <= end_date => vec![due],
+                Some(_) => Vec::new(),
+                None => scheduled_days
+                    .get(&note_id)
+                    .map(|days| days.iter().copied().collect())
+                    .unwrap_or_default(),
+            };
+
+            for day in days {
+                let entry = per_day.entry(day).or_default();
+                entry.0 += estimate.unwrap_or(0) as i64;
+                entry.1 += 1;
+                if estimate.is_none() {
+                    entry.2 += 1;
+                }
+                entry.3.insert(note_id);
+            }
+        }
+
+        let mut result = Vec::new();
+        let mut date = start_date;
+        while date <= end_date {
+            let (estimated_minutes, task_count, unestimated_count, task_notes) =
+                per_day.remove(&date).unwrap_or_default();
+
+            let busy: Vec<(u32, u32)> = blocks
+                .iter()
+                .filter(|b| b.date == date)
+                .filter(|b| !b.note_id.is_some_and(|id| task_notes.contains(&id)))
+                .map(|b| (minutes_of(b.start_time), minutes_of(b.end_time)))
+                .collect();
+
+            let available_minutes: i64 =
+                free_slots(minutes_of(work_start), minutes_of(work_end), &busy)
+                    .iter()
+                    .map(|(s, e)| (e - s) as i64)
+                    .sum();
+
+            result.push(DayWorkload {
+                date,
+                estimated_minutes,
+                available_minutes,
+                task_count,
+                unestimated_count,
+                overcommitted: estimated_minutes > available_minutes,
+            });
+
+            date += Duration::days(1);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Minutes since midnight for a time.
+fn minutes_of(time: NaiveTime) -> u32 {
+    time.num_seconds_from_midnight() / 60
+}
+
+/// Time for a number of minutes since midnight (clamped to the end of the day).
+fn time_of(minutes: u32) -> NaiveTime {
+    NaiveTime::from_num_seconds_from_midnight_opt(minutes.min(24 * 60 - 1) * 60, 0)
+        .unwrap_or(NaiveTime::MIN)
+}
+
+/// Compute free intervals within working hours, given busy intervals (all in minutes).
+fn free_slots(work_start: u32, work_end: u32, busy: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut busy: Vec<(u32, u32)> = busy.iter().copied().filter(|(s, e)| s < e).collect();
+    busy.sort();
+
+    let mut free = Vec::new();
+    let mut cursor = work_start;
+    for (start, end) in busy {
+        if start > cursor {
+            free.push((cursor, start.min(work_end)));
+        }
+        cursor = cursor.max(end);
+        if cursor >= work_end {
+            break;
+        }
+    }
+    if cursor < work_end {
+        free.push((cursor, work_end));
+    }
+
+    free.retain(|(s, e)| s < e);
+    free
+}
+
+/// Take the first free interval that fits `duration`, shrinking it by
+/// `duration + buffer`. Returns the placed (start, end).
+fn take_slot(free: &mut Vec<(u32, u32)>, duration: u32, buffer: u32) -> Option<(u32, u32)> {
+    let idx = free.iter().position(|(s, e)| e - s >= duration)?;
+    let (start, end) = free[idx];
+    let placed = (start, start + duration);
+
+    let next_start = start + duration + buffer;
+    if next_start < end {
+        free[idx] = (next_start, end);
+    } else {
+        free.remove(idx);
+    }
+
+    Some(placed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_slots() {
+        // 09:00-17:00 with a meeting 10:00-11:00 and lunch 12:00-13:00
+        let free = free_slots(540, 1020, &[(720, 780), (600, 660)]);
+        assert_eq!(free, vec![(540, 600), (660, 720), (780, 1020)]);
+
+        // Overlapping and out-of-hours blocks
+        let free = free_slots(540, 1020, &[(480, 570), (560, 600), (1000, 1100)]);
+        assert_eq!(free, vec![(600, 1000)]);
+
+        // Fully booked
+        assert!(free_slots(540, 600, &[(500, 700)]).is_empty());
+    }
+
+    #[test]
+    fn test_take_slot() {
+        let mut free = vec![(540, 570), (600, 720)];
+
+        // 45 minutes doesn't fit the first gap, goes into the second
+        assert_eq!(take_slot(&mut free, 45, 15), Some((600, 645)));
+        assert_eq!(free, vec![(540, 570), (660, 720)]);
+
+        // 30 minutes fits the first gap exactly
+        assert_eq!(take_slot(&mut free, 30, 0), Some((540, 570)));
+        assert_eq!(free, vec![(660, 720)]);
+
+        assert_eq!(take_slot(&mut free, 90, 0), None);
+    }
+}
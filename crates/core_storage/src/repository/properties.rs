@@ -1,12 +1,18 @@
 //! Property management operations.
 
 use crate::Result;
-use shared_types::{NoteWithPropertyValue, PropertyDto, PropertyKeyInfo};
-use std::collections::HashMap;
+use chrono::NaiveDate;
+use shared_types::{NoteWithPropertyValue, PropertyDto, PropertyKeyInfo, PropertyOperationResult, PropertyUpdate};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
 use tracing::{debug, instrument};
 
+use super::data_versions::bump_data_version;
 use super::VaultRepository;
 
+/// Property holding a note's CSS classes (as in Obsidian).
+pub const CSS_CLASSES_PROPERTY: &str = "cssclasses";
+
 impl VaultRepository {
     /// Get all properties for a note.
     pub async fn get_properties_for_note(&self, note_id: i64) -> Result<Vec<PropertyDto>> {
@@ -17,6 +23,9 @@ impl VaultRepository {
         .fetch_all(&self.pool)
         .await?;
 
+        let ids: Vec<i64> = rows.iter().map(|r| r.0).collect();
+        let mut items = self.get_property_items(&ids).await?;
+
         Ok(rows
             .into_iter()
             .map(|(id, note_id, key, value, property_type, sort_order)| PropertyDto {
@@ -26,6 +35,7 @@ impl VaultRepository {
                 value,
                 property_type,
                 sort_order,
+                items: items.remove(&id).unwrap_or_default(),
             })
             .collect())
     }
@@ -55,6 +65,9 @@ impl VaultRepository {
 
         let rows = query.fetch_all(&self.pool).await?;
 
+        let ids: Vec<i64> = rows.iter().map(|r| r.0).collect();
+        let mut items = self.get_property_items(&ids).await?;
+
         let mut result: HashMap<i64, Vec<PropertyDto>> = HashMap::new();
         for (id, note_id, key, value, property_type, sort_order) in rows {
             result.entry(note_id).or_default().push(PropertyDto {
@@ -64,6 +77,7 @@ impl VaultRepository {
                 value,
                 property_type,
                 sort_order,
+                items: items.remove(&id).unwrap_or_default(),
             });
         }
 
@@ -75,7 +89,9 @@ impl VaultRepository {
         Ok(result)
     }
 
-    /// Set a property (upsert by note_id + key).
+    /// Set a property (upsert by note_id + key). The items of a list
+    /// property are split from its comma-separated value; use
+    /// `set_list_property` for items that contain commas.
     pub async fn set_property(
         &self,
         note_id: i64,
@@ -83,27 +99,110 @@ impl VaultRepository {
         value: Option<&str>,
         property_type: Option<&str>,
     ) -> Result<i64> {
-        let id = sqlx::query_scalar::<_, i64>(
-            r#"
-            INSERT INTO properties (note_id, key, value, type)
-            VALUES (?, ?, ?, ?)
-            ON CONFLICT(note_id, key) DO UPDATE SET
-                value = excluded.value,
-                type = excluded.type
-            RETURNING id
-            "#,
-        )
-        .bind(note_id)
-        .bind(key)
-        .bind(value)
-        .bind(property_type)
-        .fetch_one(&self.pool)
-        .await?;
+        let items = match (value, property_type) {
+            (Some(value), Some("list")) => split_list(value),
+            _ => Vec::new(),
+        };
+        let id = self.store_property(note_id, key, value, property_type, &items).await?;
 
         debug!("Set property {} for note {} (id={})", key, note_id, id);
         Ok(id)
     }
 
+    /// Set a list property from its items.
+    pub async fn set_list_property(&self, note_id: i64, key: &str, items: &[String]) -> Result<i64> {
+        let value = items.join(", ");
+        let id = self.store_property(note_id, key, Some(&value), Some("list"), items).await?;
+
+        debug!("Set list property {} for note {} (id={}, {} items)", key, note_id, id, items.len());
+        Ok(id)
+    }
+
+    /// Upsert a property with its typed value and list items.
+    async fn store_property(
+        &self,
+        note_id: i64,
+        key: &str,
+        value: Option<&str>,
+        property_type: Option<&str>,
+        items: &[String],
+    ) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let id = upsert_property(&mut tx, note_id, key, value, property_type, items).await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Apply many property changes in one transaction: all of them or none.
+    ///
+    /// An update without a value or items removes the property; list items
+    /// are split from the value as in `set_property` unless given.
+    #[instrument(skip(self, updates))]
+    pub async fn batch_set_properties(&self, updates: &[PropertyUpdate]) -> Result<PropertyOperationResult> {
+        let mut tx = self.pool.begin().await?;
+        let mut affected_count = 0;
+        let mut notes = HashSet::new();
+
+        for update in updates {
+            match (&update.items, update.value.as_deref()) {
+                (None, None) => {
+                    let deleted = sqlx::query("DELETE FROM properties WHERE note_id = ? AND key = ?")
+                        .bind(update.note_id)
+                        .bind(&update.key)
+                        .execute(&mut *tx)
+                        .await?
+                        .rows_affected();
+                    if deleted == 0 {
+                        continue;
+                    }
+                }
+                (Some(items), _) => {
+                    let value = items.join(", ");
+                    upsert_property(&mut tx, update.note_id, &update.key, Some(&value), Some("list"), items).await?;
+                }
+                (None, Some(value)) => {
+                    let property_type = update.property_type.as_deref();
+                    let items = match property_type {
+                        Some("list") => split_list(value),
+                        _ => Vec::new(),
+                    };
+                    upsert_property(&mut tx, update.note_id, &update.key, Some(value), property_type, &items).await?;
+                }
+            }
+            affected_count += 1;
+            notes.insert(update.note_id);
+        }
+
+        tx.commit().await?;
+        debug!("Applied {} property updates to {} notes", affected_count, notes.len());
+        Ok(PropertyOperationResult {
+            affected_count,
+            notes_affected: notes.len() as i64,
+        })
+    }
+
+    /// Items of list properties by property ID, in order.
+    async fn get_property_items(&self, property_ids: &[i64]) -> Result<HashMap<i64, Vec<String>>> {
+        if property_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let sql = format!(
+            "SELECT property_id, item FROM property_items WHERE property_id IN ({}) ORDER BY property_id, position",
+            vec!["?"; property_ids.len()].join(", ")
+        );
+        let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+        for id in property_ids {
+            query = query.bind(id);
+        }
+
+        let mut items: HashMap<i64, Vec<String>> = HashMap::new();
+        for (property_id, item) in query.fetch_all(&self.pool).await? {
+            items.entry(property_id).or_default().push(item);
+        }
+        Ok(items)
+    }
+
     /// Delete a property by note_id and key.
     pub async fn delete_property(&self, note_id: i64, key: &str) -> Result<()> {
         sqlx::query("DELETE FROM properties WHERE note_id = ? AND key = ?")
@@ -132,20 +231,13 @@ impl VaultRepository {
     ) -> Result<()> {
         // Upsert each frontmatter property (update if exists, insert if not)
         for prop in properties {
-            sqlx::query(
-                r#"
-                INSERT INTO properties (note_id, key, value, type)
-                VALUES (?, ?, ?, ?)
-                ON CONFLICT(note_id, key) DO UPDATE SET
-                    value = excluded.value,
-                    type = excluded.type
-                "#,
+            self.store_property(
+                note_id,
+                &prop.key,
+                prop.value.as_deref(),
+                Some(&prop.property_type),
+                &prop.items,
             )
-            .bind(note_id)
-            .bind(&prop.key)
-            .bind(prop.value.as_deref())
-            .bind(&prop.property_type)
-            .execute(&self.pool)
             .await?;
         }
 
@@ -162,16 +254,52 @@ impl VaultRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|(id, note_id, key, value, property_type, sort_order)| PropertyDto {
+        let Some((id, note_id, key, value, property_type, sort_order)) = row else {
+            return Ok(None);
+        };
+        let items = self.get_property_items(&[id]).await?.remove(&id).unwrap_or_default();
+
+        Ok(Some(PropertyDto {
             id,
             note_id,
             key,
             value,
             property_type,
             sort_order,
+            items,
         }))
     }
 
+    /// Get a note's CSS classes from its `cssclasses` property.
+    ///
+    /// List items are used as they are; a text value is split on commas and
+    /// whitespace. Names that aren't valid class names are dropped.
+    pub async fn get_css_classes(&self, note_id: i64) -> Result<Vec<String>> {
+        let Some(property) = self.get_property(note_id, CSS_CLASSES_PROPERTY).await? else {
+            return Ok(Vec::new());
+        };
+
+        let names: Vec<&str> = if property.items.is_empty() {
+            property
+                .value
+                .as_deref()
+                .unwrap_or("")
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .collect()
+        } else {
+            property.items.iter().flat_map(|item| item.split_whitespace()).collect()
+        };
+
+        let mut classes: Vec<String> = Vec::new();
+        for name in names {
+            let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if valid && !classes.iter().any(|c| c == name) {
+                classes.push(name.to_string());
+            }
+        }
+        Ok(classes)
+    }
+
     // ========================================================================
     // Property Management (Bulk Operations)
     // ========================================================================
@@ -220,24 +348,72 @@ impl VaultRepository {
     /// Rename a property value across all notes with that key.
     #[instrument(skip(self))]
     pub async fn rename_property_value(&self, key: &str, old_value: &str, new_value: &str) -> Result<(i64, i64)> {
-        let notes_affected = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(DISTINCT note_id) FROM properties WHERE key = ? AND value = ?"
-        )
+        const HAS_ITEM: &str = "id IN (SELECT property_id FROM property_items WHERE item = ?)";
+
+        let mut tx = self.pool.begin().await?;
+
+        let notes_affected = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT COUNT(DISTINCT note_id) FROM properties WHERE key = ? AND (value = ? OR {})",
+            HAS_ITEM
+        ))
         .bind(key)
         .bind(old_value)
-        .fetch_one(&self.pool)
+        .bind(old_value)
+        .fetch_one(&mut *tx)
         .await?;
 
         let result = sqlx::query(
-            "UPDATE properties SET value = ? WHERE key = ? AND value = ?"
+            "UPDATE properties SET value = ? WHERE key = ? AND value = ? AND type IS NOT 'list'"
         )
         .bind(new_value)
         .bind(key)
         .bind(old_value)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        let affected_count = result.rows_affected() as i64;
+        // In list properties the value is an item, and the joined value follows
+        let items = sqlx::query(
+            "UPDATE property_items SET item = ? WHERE item = ? AND property_id IN (SELECT id FROM properties WHERE key = ?)"
+        )
+        .bind(new_value)
+        .bind(old_value)
+        .bind(key)
+        .execute(&mut *tx)
+        .await?;
+        if items.rows_affected() > 0 {
+            bump_data_version(&mut *tx, "property_items").await?;
+        }
+
+        sqlx::query(&format!(
+            r#"
+            UPDATE properties SET value = (
+                SELECT group_concat(item, ', ') FROM (
+                    SELECT item FROM property_items WHERE property_id = properties.id ORDER BY position
+                )
+            )
+            WHERE key = ? AND {}
+            "#,
+            HAS_ITEM
+        ))
+        .bind(key)
+        .bind(new_value)
+        .execute(&mut *tx)
+        .await?;
+
+        let affected_count = (result.rows_affected() + items.rows_affected()) as i64;
+
+        let rows = sqlx::query_as::<_, (i64, Option<String>, Option<String>)>(&format!(
+            "SELECT id, value, type FROM properties WHERE key = ? AND (value = ? OR {})",
+            HAS_ITEM
+        ))
+        .bind(key)
+        .bind(new_value)
+        .bind(new_value)
+        .fetch_all(&mut *tx)
+        .await?;
+        write_typed_values(&mut tx, &rows).await?;
+
+        tx.commit().await?;
 
         debug!("Renamed property value '{}' -> '{}' for key '{}': {} properties, {} notes",
                old_value, new_value, key, affected_count, notes_affected);
@@ -463,32 +639,172 @@ impl VaultRepository {
     /// Get all distinct individual values for a list-type property.
     /// Splits comma-separated values and returns unique items.
     pub async fn get_list_property_values(&self, key: &str) -> Result<Vec<String>> {
-        // First get all raw values
-        let raw_values = sqlx::query_scalar::<_, String>(
+        let values = sqlx::query_scalar::<_, String>(
             r#"
-            SELECT value
-            FROM properties
-            WHERE key = ? AND value IS NOT NULL AND value != ''
+            SELECT DISTINCT i.item
+            FROM property_items i
+            JOIN properties p ON p.id = i.property_id
+            WHERE p.key = ? AND i.item != ''
+            ORDER BY i.item
             "#,
         )
         .bind(key)
         .fetch_all(&self.pool)
         .await?;
 
-        // Split by comma and collect unique values
-        let mut unique_values: std::collections::HashSet<String> = std::collections::HashSet::new();
-        for raw in raw_values {
-            for part in raw.split(',') {
-                let trimmed = part.trim();
-                if !trimmed.is_empty() {
-                    unique_values.insert(trimmed.to_string());
-                }
-            }
-        }
-
-        // Convert to sorted vec
-        let mut values: Vec<String> = unique_values.into_iter().collect();
-        values.sort();
         Ok(values)
     }
 }
+
+/// Upsert a property with its typed value and list items on a connection
+/// (inside the caller's transaction).
+async fn upsert_property(
+    conn: &mut SqliteConnection,
+    note_id: i64,
+    key: &str,
+    value: Option<&str>,
+    property_type: Option<&str>,
+    items: &[String],
+) -> Result<i64> {
+    let typed = typed_value(value, property_type);
+
+    let id = sqlx::query_scalar::<_, i64>(
+        r#"
+        INSERT INTO properties (note_id, key, value, type, value_text, value_number, value_date, value_bool)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(note_id, key) DO UPDATE SET
+            value = excluded.value,
+            type = excluded.type,
+            value_text = excluded.value_text,
+            value_number = excluded.value_number,
+            value_date = excluded.value_date,
+            value_bool = excluded.value_bool
+        RETURNING id
+        "#,
+    )
+    .bind(note_id)
+    .bind(key)
+    .bind(value)
+    .bind(property_type)
+    .bind(typed.text)
+    .bind(typed.number)
+    .bind(typed.date)
+    .bind(typed.boolean)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let deleted = sqlx::query("DELETE FROM property_items WHERE property_id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    for (position, item) in items.iter().enumerate() {
+        sqlx::query("INSERT INTO property_items (property_id, item, position) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(item)
+            .bind(position as i64)
+            .execute(&mut *conn)
+            .await?;
+    }
+    if deleted.rows_affected() > 0 || !items.is_empty() {
+        bump_data_version(&mut *conn, "property_items").await?;
+    }
+
+    Ok(id)
+}
+
+/// A property value in the column for its type. Numbers, dates (YYYY-MM-DD)
+/// and booleans that don't parse, and values of other types, go in `text`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct TypedValue {
+    pub text: Option<String>,
+    pub number: Option<f64>,
+    pub date: Option<String>,
+    pub boolean: Option<bool>,
+}
+
+/// Convert a property value by its type ("number", "date", "boolean"; other
+/// types are text).
+pub(crate) fn typed_value(value: Option<&str>, property_type: Option<&str>) -> TypedValue {
+    let Some(value) = value else {
+        return TypedValue::default();
+    };
+    let trimmed = value.trim();
+
+    let typed = match property_type {
+        Some("number") => trimmed
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(|n| TypedValue { number: Some(n), ..Default::default() }),
+        Some("date") => parse_date(trimmed).map(|d| TypedValue {
+            date: Some(d.format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        }),
+        Some("boolean") => parse_bool(trimmed).map(|b| TypedValue { boolean: Some(b), ..Default::default() }),
+        _ => None,
+    };
+
+    typed.unwrap_or_else(|| TypedValue {
+        text: Some(value.to_string()),
+        ..Default::default()
+    })
+}
+
+/// A date, or the date of a datetime (`2024-01-15T09:00`, `2024-01-15 09:00`).
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let (date, rest) = value.split_at_checked(10)?;
+    if !(rest.is_empty() || rest.starts_with('T') || rest.starts_with(' ')) {
+        return None;
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// The items of a comma-separated list value.
+pub(crate) fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_string())
+        .collect()
+}
+
+/// Fill the typed value columns of `(id, value, type)` property rows.
+pub(crate) async fn store_typed_values(
+    pool: &SqlitePool,
+    rows: &[(i64, Option<String>, Option<String>)],
+) -> std::result::Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    write_typed_values(&mut tx, rows).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Store the typed columns of property rows within a transaction.
+async fn write_typed_values(
+    conn: &mut SqliteConnection,
+    rows: &[(i64, Option<String>, Option<String>)],
+) -> std::result::Result<(), sqlx::Error> {
+    for (id, value, property_type) in rows {
+        let typed = typed_value(value.as_deref(), property_type.as_deref());
+        sqlx::query(
+            "UPDATE properties SET value_text = ?, value_number = ?, value_date = ?, value_bool = ? WHERE id = ?",
+        )
+        .bind(typed.text)
+        .bind(typed.number)
+        .bind(typed.date)
+        .bind(typed.boolean)
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
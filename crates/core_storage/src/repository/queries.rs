@@ -1,14 +1,15 @@
 //! Query builder and search operations.
 
-use crate::Result;
-use chrono::{DateTime, Utc};
+use crate::{Result, StorageError};
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, Utc};
 use shared_types::{
-    FilterMatchMode, NoteListItem, PropertyDto, PropertyFilter, PropertyOperator,
-    QueryRequest, QueryResponse, QueryResultItem, QueryResultType, SearchResult,
-    TaskWithContext, TodoDto,
+    FilterMatchMode, NoteListItem, PropertyDto, PropertyFilter, PropertyOperator, PropertyTable,
+    PropertyTableRow, QueryRequest, QueryResponse, QueryResultItem, QueryResultType, QuerySort,
+    SearchFilters, SearchResult, SortDirection, TaskWithContext, TodoDto,
 };
+use std::collections::BTreeSet;
 
-use super::VaultRepository;
+use super::{id_array, VaultRepository};
 
 impl VaultRepository {
     /// Run a query with property filters.
@@ -27,7 +28,10 @@ impl VaultRepository {
         match request.result_type {
             QueryResultType::Tasks | QueryResultType::Both => {
                 // Query tasks from matching notes
-                let tasks = self.query_tasks_by_note_ids(&note_ids, request.include_completed, limit).await?;
+                let completed = completed_tests(&request.filters);
+                let tasks = self
+                    .query_tasks_by_note_ids(&note_ids, request.include_completed, &completed, limit, request.sort.as_ref())
+                    .await?;
                 total_count += tasks.len() as i64;
 
                 for task in tasks {
@@ -36,6 +40,8 @@ impl VaultRepository {
                         task: Some(task.clone()),
                         note: None,
                         properties: task.note_properties,
+                        rank: None,
+                        item_ref: String::new(),
                     });
                 }
             }
@@ -45,7 +51,7 @@ impl VaultRepository {
         match request.result_type {
             QueryResultType::Notes | QueryResultType::Both => {
                 // Query notes directly
-                let notes = self.query_notes_by_ids(&note_ids, limit).await?;
+                let notes = self.query_notes_by_ids(&note_ids, limit, request.sort.as_ref()).await?;
 
                 // For Both mode, don't double-count notes that have tasks
                 if matches!(request.result_type, QueryResultType::Notes) {
@@ -67,6 +73,8 @@ impl VaultRepository {
                         task: None,
                         note: Some(note),
                         properties,
+                        rank: None,
+                        item_ref: String::new(),
                     });
                 }
             }
@@ -79,10 +87,89 @@ impl VaultRepository {
         })
     }
 
+    /// IDs of the notes matching property filters, as `run_query` finds them.
+    pub async fn matching_note_ids(&self, filters: &[PropertyFilter], match_mode: &FilterMatchMode) -> Result<Vec<i64>> {
+        let (sql, params) = self.build_property_filter_sql(filters, match_mode)?;
+        self.get_matching_note_ids(&sql, &params).await
+    }
+
+    /// Tasks of the notes a query matches (`note_ids`) and of the notes
+    /// they embed, filtered, sorted and limited together like the query's
+    /// own tasks, with the number of tasks before the limit. `embedded`
+    /// pairs each embedded note's ID with the path of the note embedding it,
+    /// which becomes its tasks' `embedded_in`.
+    pub async fn query_tasks_with_embedded(
+        &self,
+        request: &QueryRequest,
+        note_ids: &[i64],
+        embedded: &[(i64, String)],
+    ) -> Result<(Vec<TaskWithContext>, i64)> {
+        let mut all_ids = note_ids.to_vec();
+        all_ids.extend(embedded.iter().map(|(id, _)| *id).filter(|id| !note_ids.contains(id)));
+        let completed = completed_tests(&request.filters);
+
+        let mut tasks = self
+            .query_tasks_by_note_ids(
+                &all_ids,
+                request.include_completed,
+                &completed,
+                request.limit.unwrap_or(100),
+                request.sort.as_ref(),
+            )
+            .await?;
+        let total = self
+            .count_tasks_by_note_ids(&all_ids, request.include_completed, &completed)
+            .await?;
+
+        for task in &mut tasks {
+            task.embedded_in = embedded
+                .iter()
+                .find(|(id, _)| *id == task.todo.note_id)
+                .map(|(_, path)| path.clone());
+        }
+        Ok((tasks, total))
+    }
+
+    /// Get the notes matching all `filters` with their properties for
+    /// `columns`, in path order. Without columns, every property key the
+    /// notes use is a column, sorted by name.
+    pub async fn get_property_table(&self, filters: &[PropertyFilter], columns: &[String]) -> Result<PropertyTable> {
+        let (sql, params) = self.build_property_filter_sql(filters, &FilterMatchMode::All)?;
+        let note_ids = self.get_matching_note_ids(&sql, &params).await?;
+        let notes = self.query_notes_by_ids(&note_ids, i32::MAX, None).await?;
+
+        let columns: Vec<String> = if columns.is_empty() {
+            let keys: BTreeSet<&String> = notes.iter().flat_map(|(_, props)| props.iter().map(|p| &p.key)).collect();
+            keys.into_iter().cloned().collect()
+        } else {
+            columns.to_vec()
+        };
+
+        let rows = notes
+            .into_iter()
+            .map(|(note, properties)| PropertyTableRow {
+                values: columns
+                    .iter()
+                    .map(|key| properties.iter().find(|p| &p.key == key).cloned())
+                    .collect(),
+                note,
+            })
+            .collect();
+
+        Ok(PropertyTable { columns, rows })
+    }
+
     /// Build SQL for property filters.
     /// Special keys:
     /// - `_path`: filters on the note's path (use StartsWith for "in folder" behavior)
     /// - `_tags`: filters on the note's tags from the note_tags table
+    /// - `_completed`: date operators on the completion date of the note's
+    ///   tasks; task results are also limited to the matching tasks
+    ///
+    /// In All mode, positive property filters are joined to the note instead
+    /// of checked with a correlated EXISTS per note, so SQLite can start from
+    /// the matching rows of the `(key, value, note_id)` index. Each note has
+    /// at most one property row per key, so the joins never duplicate notes.
     fn build_property_filter_sql(
         &self,
         filters: &[PropertyFilter],
@@ -93,218 +180,38 @@ impl VaultRepository {
             return Ok(("SELECT id FROM notes".to_string(), Vec::new()));
         }
 
-        let mut conditions = Vec::new();
-        let mut params = Vec::new();
-
-        for filter in filters {
-            // Handle special _path filter (filters on notes.path column)
-            if filter.key == "_path" {
-                let condition = match filter.operator {
-                    PropertyOperator::Equals => {
-                        params.push(filter.value.clone().unwrap_or_default());
-                        "n.path = ?".to_string()
-                    }
-                    PropertyOperator::NotEquals => {
-                        params.push(filter.value.clone().unwrap_or_default());
-                        "n.path != ?".to_string()
-                    }
-                    PropertyOperator::Contains => {
-                        params.push(format!("%{}%", filter.value.clone().unwrap_or_default()));
-                        "n.path LIKE ?".to_string()
-                    }
-                    PropertyOperator::StartsWith => {
-                        // StartsWith on _path means "in folder" - match folder/ prefix
-                        let folder = filter.value.clone().unwrap_or_default();
-                        // Ensure folder path ends with / for proper matching
-                        let folder_prefix = if folder.ends_with('/') {
-                            folder
-                        } else {
-                            format!("{}/", folder)
-                        };
-                        params.push(format!("{}%", folder_prefix));
-                        "n.path LIKE ?".to_string()
-                    }
-                    PropertyOperator::EndsWith => {
-                        params.push(format!("%{}", filter.value.clone().unwrap_or_default()));
-                        "n.path LIKE ?".to_string()
-                    }
-                    // Exists/NotExists don't make sense for path - treat as always true/false
-                    PropertyOperator::Exists => "1=1".to_string(),
-                    PropertyOperator::NotExists => "1=0".to_string(),
-                    // ContainsAll/ContainsAny/Date operators don't make sense for path
-                    PropertyOperator::ContainsAll | PropertyOperator::ContainsAny
-                    | PropertyOperator::DateOn | PropertyOperator::DateBefore
-                    | PropertyOperator::DateAfter | PropertyOperator::DateOnOrBefore
-                    | PropertyOperator::DateOnOrAfter => "1=1".to_string(),
-                };
-                conditions.push(condition);
-                continue;
-            }
-
-            // Handle special _tags filter (filters on tags table)
-            // Schema: tags(id, note_id, tag) - direct note_id -> tag mapping
-            if filter.key == "_tags" {
-                let condition = match filter.operator {
-                    PropertyOperator::Exists => {
-                        "EXISTS (SELECT 1 FROM tags WHERE note_id = n.id)".to_string()
-                    }
-                    PropertyOperator::NotExists => {
-                        "NOT EXISTS (SELECT 1 FROM tags WHERE note_id = n.id)".to_string()
-                    }
-                    PropertyOperator::Equals | PropertyOperator::Contains => {
-                        // Single tag match
-                        params.push(filter.value.clone().unwrap_or_default());
-                        "EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag = ?)".to_string()
-                    }
-                    PropertyOperator::NotEquals => {
-                        params.push(filter.value.clone().unwrap_or_default());
-                        "NOT EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag = ?)".to_string()
-                    }
-                    PropertyOperator::ContainsAll => {
-                        // Note must have ALL specified tags
-                        let value = filter.value.clone().unwrap_or_default();
-                        let tags: Vec<&str> = value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
-                        if tags.is_empty() {
-                            "1=1".to_string()
-                        } else {
-                            let mut tag_conditions = Vec::new();
-                            for tag in &tags {
-                                params.push(tag.to_string());
-                                tag_conditions.push("EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag = ?)".to_string());
-                            }
-                            format!("({})", tag_conditions.join(" AND "))
-                        }
-                    }
-                    PropertyOperator::ContainsAny => {
-                        // Note must have ANY of the specified tags
-                        let value = filter.value.clone().unwrap_or_default();
-                        let tags: Vec<&str> = value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
-                        if tags.is_empty() {
-                            "1=0".to_string()
-                        } else {
-                            let placeholders: Vec<&str> = tags.iter().map(|_| "?").collect();
-                            for tag in &tags {
-                                params.push(tag.to_string());
-                            }
-                            format!(
-                                "EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag IN ({}))",
-                                placeholders.join(", ")
-                            )
-                        }
-                    }
-                    PropertyOperator::StartsWith => {
-                        // Tags starting with prefix
-                        params.push(format!("{}%", filter.value.clone().unwrap_or_default()));
-                        "EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag LIKE ?)".to_string()
-                    }
-                    PropertyOperator::EndsWith => {
-                        params.push(format!("%{}", filter.value.clone().unwrap_or_default()));
-                        "EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag LIKE ?)".to_string()
-                    }
-                    // Date operators don't make sense for tags
-                    PropertyOperator::DateOn | PropertyOperator::DateBefore
-                    | PropertyOperator::DateAfter | PropertyOperator::DateOnOrBefore
-                    | PropertyOperator::DateOnOrAfter => "1=1".to_string(),
-                };
-                conditions.push(condition);
-                continue;
-            }
-
-            // Regular property filter
-            let condition = match filter.operator {
-                PropertyOperator::Exists => {
-                    params.push(filter.key.clone());
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ?)".to_string()
-                }
-                PropertyOperator::NotExists => {
-                    params.push(filter.key.clone());
-                    "NOT EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ?)".to_string()
-                }
-                PropertyOperator::Equals => {
-                    params.push(filter.key.clone());
-                    params.push(filter.value.clone().unwrap_or_default());
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?)".to_string()
-                }
-                PropertyOperator::NotEquals => {
-                    params.push(filter.key.clone());
-                    params.push(filter.value.clone().unwrap_or_default());
-                    "NOT EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?)".to_string()
-                }
-                PropertyOperator::Contains => {
-                    params.push(filter.key.clone());
-                    params.push(format!("%{}%", filter.value.clone().unwrap_or_default()));
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value LIKE ?)".to_string()
-                }
-                PropertyOperator::StartsWith => {
-                    params.push(filter.key.clone());
-                    params.push(format!("{}%", filter.value.clone().unwrap_or_default()));
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value LIKE ?)".to_string()
-                }
-                PropertyOperator::EndsWith => {
-                    params.push(filter.key.clone());
-                    params.push(format!("%{}", filter.value.clone().unwrap_or_default()));
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value LIKE ?)".to_string()
-                }
-                PropertyOperator::ContainsAll => {
-                    // For list properties stored as comma-separated: must contain ALL values
-                    let value = filter.value.clone().unwrap_or_default();
-                    let items: Vec<&str> = value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
-                    if items.is_empty() {
-                        "1=1".to_string()
-                    } else {
-                        let mut item_conditions = Vec::new();
-                        for item in &items {
-                            params.push(filter.key.clone());
-                            params.push(format!("%{}%", item));
-                            item_conditions.push("EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value LIKE ?)".to_string());
-                        }
-                        format!("({})", item_conditions.join(" AND "))
+        let conditions = filters.iter().map(|filter| match filter.key.as_str() {
+            "_path" => path_condition(filter),
+            "_tags" => tag_condition(filter),
+            "_completed" => completed_condition(filter),
+            _ => property_condition(filter),
+        });
+
+        let mut joins = Vec::new();
+        let mut join_params = Vec::new();
+        let mut where_conditions = Vec::new();
+        let mut where_params = Vec::new();
+        for condition in conditions {
+            match (match_mode, condition) {
+                (FilterMatchMode::All, Condition::Property { key, test, params }) => {
+                    let alias = format!("p{}", joins.len());
+                    let mut join = format!(
+                        "JOIN properties {a} ON {a}.note_id = n.id AND {a}.key = ?",
+                        a = alias
+                    );
+                    if let Some(test) = test {
+                        join.push_str(&format!(" AND {}", test.replace("{p}", &alias)));
                     }
+                    joins.push(join);
+                    join_params.push(key);
+                    join_params.extend(params);
                 }
-                PropertyOperator::ContainsAny => {
-                    // For list properties stored as comma-separated: must contain ANY value
-                    let value = filter.value.clone().unwrap_or_default();
-                    let items: Vec<&str> = value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
-                    if items.is_empty() {
-                        "1=0".to_string()
-                    } else {
-                        let mut item_conditions = Vec::new();
-                        for item in &items {
-                            params.push(filter.key.clone());
-                            params.push(format!("%{}%", item));
-                            item_conditions.push("EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value LIKE ?)".to_string());
-                        }
-                        format!("({})", item_conditions.join(" OR "))
-                    }
-                }
-                // Date operators compare property values as YYYY-MM-DD strings
-                PropertyOperator::DateOn => {
-                    params.push(filter.key.clone());
-                    params.push(filter.value.clone().unwrap_or_default());
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND date(value) = date(?))".to_string()
-                }
-                PropertyOperator::DateBefore => {
-                    params.push(filter.key.clone());
-                    params.push(filter.value.clone().unwrap_or_default());
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND date(value) < date(?))".to_string()
+                (_, condition) => {
+                    let (sql, params) = condition.into_expr();
+                    where_conditions.push(sql);
+                    where_params.extend(params);
                 }
-                PropertyOperator::DateAfter => {
-                    params.push(filter.key.clone());
-                    params.push(filter.value.clone().unwrap_or_default());
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND date(value) > date(?))".to_string()
-                }
-                PropertyOperator::DateOnOrBefore => {
-                    params.push(filter.key.clone());
-                    params.push(filter.value.clone().unwrap_or_default());
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND date(value) <= date(?))".to_string()
-                }
-                PropertyOperator::DateOnOrAfter => {
-                    params.push(filter.key.clone());
-                    params.push(filter.value.clone().unwrap_or_default());
-                    "EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND date(value) >= date(?))".to_string()
-                }
-            };
-            conditions.push(condition);
+            }
         }
 
         let joiner = match match_mode {
@@ -312,9 +219,18 @@ impl VaultRepository {
             FilterMatchMode::Any => " OR ",
         };
 
-        let where_clause = conditions.join(joiner);
-        let sql = format!("SELECT id FROM notes n WHERE {}", where_clause);
+        let mut sql = "SELECT n.id FROM notes n".to_string();
+        for join in &joins {
+            sql.push(' ');
+            sql.push_str(join);
+        }
+        if !where_conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&where_conditions.join(joiner));
+        }
 
+        let mut params = join_params;
+        params.extend(where_params);
         Ok((sql, params))
     }
 
@@ -324,69 +240,72 @@ impl VaultRepository {
         for param in params {
             query = query.bind(param);
         }
-        let ids = query.fetch_all(&self.pool).await?;
+        let ids = query.fetch_all(&self.read_pool).await?;
         Ok(ids)
     }
 
-    /// Query tasks by note IDs.
+    /// Query tasks by note IDs. `completed` holds tests on the task alias
+    /// `{t}` with their parameter, from `_completed` filters.
     pub(crate) async fn query_tasks_by_note_ids(
         &self,
         note_ids: &[i64],
         include_completed: bool,
+        completed: &[(String, String)],
         limit: i32,
+        sort: Option<&QuerySort>,
     ) -> Result<Vec<TaskWithContext>> {
         if note_ids.is_empty() {
             return Ok(Vec::new());
         }
 
-        // Build IN clause
-        let placeholders: Vec<String> = note_ids.iter().map(|_| "?".to_string()).collect();
-        let in_clause = placeholders.join(", ");
-
-        let completed_filter = if include_completed {
-            "1=1"
-        } else {
-            "t.completed = 0"
-        };
+        let completed_filter = task_completion_filter(include_completed, completed);
+        let sort = SortSql::new(sort);
 
         let sql = format!(
             r#"
             SELECT
                 t.id, t.note_id, t.line_number, t.description, t.completed, t.heading_path,
-                t.context, t.priority, t.due_date, t.created_at, t.completed_at,
+                t.context, t.priority, t.due_date, t.estimate_minutes, t.created_at, t.completed_at,
                 n.path, n.title
             FROM todos t
             JOIN notes n ON t.note_id = n.id
-            WHERE t.note_id IN ({}) AND {}
-            ORDER BY
+            {}
+            WHERE t.note_id IN (SELECT value FROM json_each(?)) AND {}
+            ORDER BY {}
                 CASE WHEN t.due_date IS NOT NULL THEN 0 ELSE 1 END,
                 t.due_date,
                 CASE t.priority WHEN 'high' THEN 0 WHEN 'medium' THEN 1 WHEN 'low' THEN 2 ELSE 3 END,
                 t.created_at DESC
             LIMIT ?
             "#,
-            in_clause, completed_filter
+            sort.join, completed_filter, sort.order
         );
 
         let mut query = sqlx::query_as::<_, (
             i64, i64, Option<i32>, String, i32, Option<String>,
-            Option<String>, Option<String>, Option<String>, Option<String>, Option<String>,
+            Option<String>, Option<String>, Option<String>, Option<i32>, Option<String>, Option<String>,
             String, Option<String>
         )>(&sql);
 
-        for id in note_ids {
-            query = query.bind(id);
+        if let Some(key) = sort.key {
+            query = query.bind(key);
+        }
+        // The IDs are bound as one JSON array: there can be more of them
+        // than SQLite allows variables in a statement
+        query = query.bind(id_array(note_ids));
+        for (_, param) in completed {
+            query = query.bind(param);
         }
         query = query.bind(limit);
 
-        let rows = query.fetch_all(&self.pool).await?;
+        let rows = query.fetch_all(&self.read_pool).await?;
 
         // Batch fetch all properties for the note_ids we found in tasks
         let task_note_ids: Vec<i64> = rows.iter().map(|r| r.1).collect();
         let properties_map = self.get_properties_for_notes(&task_note_ids).await?;
 
         let mut results = Vec::new();
-        for (id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at, note_path, note_title) in rows {
+        for (id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at, note_path, note_title) in rows {
             let note_properties = properties_map.get(&note_id).cloned().unwrap_or_default();
 
             results.push(TaskWithContext {
@@ -400,23 +319,49 @@ impl VaultRepository {
                     context,
                     priority,
                     due_date,
+                    estimate_minutes,
                     created_at: created_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                     completed_at: completed_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                 },
                 note_path,
                 note_title,
                 note_properties,
+                embedded_in: None,
             });
         }
 
         Ok(results)
     }
 
+    /// Number of tasks `query_tasks_by_note_ids` finds without a limit.
+    async fn count_tasks_by_note_ids(
+        &self,
+        note_ids: &[i64],
+        include_completed: bool,
+        completed: &[(String, String)],
+    ) -> Result<i64> {
+        if note_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let sql = format!(
+            "SELECT COUNT(*) FROM todos t WHERE t.note_id IN (SELECT value FROM json_each(?)) AND {}",
+            task_completion_filter(include_completed, completed)
+        );
+
+        let mut query = sqlx::query_scalar::<_, i64>(&sql).bind(id_array(note_ids));
+        for (_, param) in completed {
+            query = query.bind(param);
+        }
+        Ok(query.fetch_one(&self.read_pool).await?)
+    }
+
     /// Query notes by IDs.
     async fn query_notes_by_ids(
         &self,
         note_ids: &[i64],
         limit: i32,
+        sort: Option<&QuerySort>,
     ) -> Result<Vec<(NoteListItem, Vec<PropertyDto>)>> {
         if note_ids.is_empty() {
             return Ok(Vec::new());
@@ -425,18 +370,22 @@ impl VaultRepository {
         let placeholders: Vec<String> = note_ids.iter().map(|_| "?".to_string()).collect();
         let in_clause = placeholders.join(", ");
 
+        let sort = SortSql::new(sort);
         let sql = format!(
-            "SELECT id, path, title, pinned FROM notes WHERE id IN ({}) ORDER BY path LIMIT ?",
-            in_clause
+            "SELECT n.id, n.path, n.title, n.pinned FROM notes n {} WHERE n.id IN ({}) ORDER BY {}n.path LIMIT ?",
+            sort.join, in_clause, sort.order
         );
 
         let mut query = sqlx::query_as::<_, (i64, String, Option<String>, i32)>(&sql);
+        if let Some(key) = sort.key {
+            query = query.bind(key);
+        }
         for id in note_ids {
             query = query.bind(id);
         }
         query = query.bind(limit);
 
-        let rows = query.fetch_all(&self.pool).await?;
+        let rows = query.fetch_all(&self.read_pool).await?;
 
         // Batch fetch all properties for the note_ids we found
         let found_note_ids: Vec<i64> = rows.iter().map(|r| r.0).collect();
@@ -464,6 +413,8 @@ impl VaultRepository {
     // ========================================================================
 
     /// Update the FTS index for a note.
+    ///
+    /// Private sections (`%%private%% ... %%end%%`) are blanked before indexing.
     pub async fn update_fts(&self, note_id: i64, content: &str) -> Result<()> {
         // Delete existing FTS entry
         sqlx::query("DELETE FROM notes_fts WHERE rowid = ?")
@@ -472,41 +423,492 @@ impl VaultRepository {
             .await?;
 
         // Insert new FTS entry
+        let content = core_index::redact_private(content);
         sqlx::query("INSERT INTO notes_fts (rowid, content) VALUES (?, ?)")
             .bind(note_id)
-            .bind(content)
+            .bind(content.as_ref())
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("UPDATE notes SET fts_pending = 0 WHERE id = ?")
+            .bind(note_id)
             .execute(&self.pool)
             .await?;
 
         Ok(())
     }
 
+    /// Notes whose full-text entry is pending, as (id, path, hash), oldest
+    /// first.
+    pub async fn notes_pending_fts(&self, limit: i32) -> Result<Vec<(i64, String, String)>> {
+        let rows = sqlx::query_as::<_, (i64, String, String)>(
+            "SELECT id, path, hash FROM notes WHERE fts_pending = 1 AND hash IS NOT NULL ORDER BY updated_at, id LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Catch up a pending full-text entry with `content`, read when the note
+    /// had `hash`. Returns false, leaving the note pending, if it has been
+    /// saved again since.
+    pub async fn update_pending_fts(&self, note_id: i64, hash: &str, content: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let cleared = sqlx::query("UPDATE notes SET fts_pending = 0 WHERE id = ? AND hash = ? AND fts_pending = 1")
+            .bind(note_id)
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+            > 0;
+        if !cleared {
+            return Ok(false);
+        }
+
+        let content = core_index::redact_private(content);
+        sqlx::query("DELETE FROM notes_fts WHERE rowid = ?")
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO notes_fts (rowid, content) VALUES (?, ?)")
+            .bind(note_id)
+            .bind(content.as_ref())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
     /// Search notes using full-text search.
+    ///
+    /// Results for notes whose full-text entry is pending are marked stale.
     pub async fn search(&self, query: &str, limit: i32) -> Result<Vec<SearchResult>> {
-        let rows = sqlx::query_as::<_, (i64, String, Option<String>, String, f64)>(
+        let today = Local::now().date_naive();
+        self.search_filtered(query, &SearchFilters::default(), today, limit).await
+    }
+
+    /// Search notes using full-text search, scoped by folder, tags and
+    /// modification date. Relative date ranges are resolved against `today`.
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        filters: &SearchFilters,
+        today: NaiveDate,
+        limit: i32,
+    ) -> Result<Vec<SearchResult>> {
+        let mut sql = String::from(
             r#"
-            SELECT n.id, n.path, n.title, snippet(notes_fts, 0, '<mark>', '</mark>', '...', 32), bm25(notes_fts)
+            SELECT n.id, n.path, n.title, snippet(notes_fts, 0, '<mark>', '</mark>', '...', 32), bm25(notes_fts),
+                   COALESCE(n.fts_pending, 0) != 0
             FROM notes_fts
             JOIN notes n ON notes_fts.rowid = n.id
             WHERE notes_fts MATCH ?
-            ORDER BY bm25(notes_fts)
-            LIMIT ?
             "#,
-        )
-        .bind(query)
-        .bind(limit)
-        .fetch_all(&self.pool)
-        .await?;
+        );
+        let mut params = vec![query.to_string()];
+
+        let folder = filters.folder.as_deref().map(|f| f.trim_matches('/'));
+        if let Some(folder) = folder.filter(|f| !f.is_empty()) {
+            sql.push_str(r" AND n.path LIKE ? ESCAPE '\'");
+            params.push(format!("{}/%", super::escape_like(folder)));
+        }
+        for tag in &filters.tags {
+            let tag = tag.trim().trim_start_matches('#');
+            if tag.is_empty() {
+                continue;
+            }
+            sql.push_str(&format!(" AND {}", HAS_TAG));
+            params.push(tag.to_string());
+            params.push(tag.to_string());
+        }
+        if let Some(range) = filters.date_range.as_deref().filter(|r| !r.is_empty()) {
+            let (start, end) = date_range_bounds(range, today)?;
+            if let Some(start) = start {
+                sql.push_str(" AND date(n.updated_at, 'localtime') >= ?");
+                params.push(start.to_string());
+            }
+            if let Some(end) = end {
+                sql.push_str(" AND date(n.updated_at, 'localtime') <= ?");
+                params.push(end.to_string());
+            }
+        }
+        sql.push_str(" ORDER BY bm25(notes_fts) LIMIT ?");
+
+        let mut db_query = sqlx::query_as::<_, (i64, String, Option<String>, String, f64, bool)>(&sql);
+        for param in params {
+            db_query = db_query.bind(param);
+        }
+        let rows = db_query.bind(limit).fetch_all(&self.read_pool).await?;
 
         Ok(rows
             .into_iter()
-            .map(|(note_id, path, title, snippet, score)| SearchResult {
+            .map(|(note_id, path, title, snippet, score, stale)| SearchResult {
                 note_id,
                 path,
                 title,
                 snippet: Some(snippet),
                 score: -score, // bm25 returns negative scores, lower is better
+                stale,
             })
             .collect())
     }
 }
+
+/// The note `n` has the tag bound next, or a tag aliased to the same one
+/// (tag_meta). The tag is bound twice, for the alias lookup and as itself.
+const HAS_TAG: &str = "EXISTS (SELECT 1 FROM tags t LEFT JOIN tag_meta m ON m.tag = t.tag \
+    WHERE t.note_id = n.id AND COALESCE(m.alias_of, t.tag) = \
+    COALESCE((SELECT alias_of FROM tag_meta WHERE tag = ?), ?))";
+
+/// The first and last day of a search date range, either relative to
+/// `today` ("today", "this_week", "this_month", "this_year") or a custom
+/// "YYYY-MM-DD..YYYY-MM-DD" where either end may be left open.
+fn date_range_bounds(range: &str, today: NaiveDate) -> Result<(Option<NaiveDate>, Option<NaiveDate>)> {
+    let invalid = || StorageError::InvalidDateRange(range.to_string());
+    let (start, end) = match range {
+        "today" => (today, today),
+        "this_week" => {
+            let start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            (start, start + Duration::days(6))
+        }
+        "this_month" => {
+            let start = today.with_day(1).ok_or_else(invalid)?;
+            let next = start.checked_add_months(Months::new(1)).ok_or_else(invalid)?;
+            (start, next.pred_opt().ok_or_else(invalid)?)
+        }
+        "this_year" => (
+            NaiveDate::from_ymd_opt(today.year(), 1, 1).ok_or_else(invalid)?,
+            NaiveDate::from_ymd_opt(today.year(), 12, 31).ok_or_else(invalid)?,
+        ),
+        custom => {
+            let (start, end) = custom.split_once("..").ok_or_else(invalid)?;
+            let parse = |date: &str| -> Result<Option<NaiveDate>> {
+                let date = date.trim();
+                if date.is_empty() {
+                    return Ok(None);
+                }
+                NaiveDate::parse_from_str(date, "%Y-%m-%d").map(Some).map_err(|_| invalid())
+            };
+            let (start, end) = (parse(start)?, parse(end)?);
+            if matches!((start, end), (Some(start), Some(end)) if start > end) {
+                return Err(invalid());
+            }
+            return Ok((start, end));
+        }
+    };
+    Ok((Some(start), Some(end)))
+}
+
+/// A filter turned into SQL over the note alias `n`.
+enum Condition {
+    /// A boolean expression.
+    Expr { sql: String, params: Vec<String> },
+    /// A test on the note's property row for `key`, with `{p}` standing for
+    /// the row's alias; None only requires the row to exist.
+    Property {
+        key: String,
+        test: Option<String>,
+        params: Vec<String>,
+    },
+}
+
+impl Condition {
+    fn expr(sql: &str, params: Vec<String>) -> Self {
+        Condition::Expr {
+            sql: sql.to_string(),
+            params,
+        }
+    }
+
+    fn property(key: &str, test: Option<String>, params: Vec<String>) -> Self {
+        Condition::Property {
+            key: key.to_string(),
+            test,
+            params,
+        }
+    }
+
+    /// The condition as a boolean expression.
+    fn into_expr(self) -> (String, Vec<String>) {
+        match self {
+            Condition::Expr { sql, params } => (sql, params),
+            Condition::Property { key, test, params } => {
+                let mut sql = "EXISTS (SELECT 1 FROM properties p WHERE p.note_id = n.id AND p.key = ?".to_string();
+                if let Some(test) = test {
+                    sql.push_str(&format!(" AND {}", test.replace("{p}", "p")));
+                }
+                sql.push(')');
+                let mut all_params = vec![key];
+                all_params.extend(params);
+                (sql, all_params)
+            }
+        }
+    }
+}
+
+/// SQL for sorting notes (aliased `n`) by a `QuerySort`: a join to the sort
+/// property (bound to `key`) and ORDER BY terms to put before the default
+/// order. Property values sort by their typed column, so numbers and dates
+/// sort by value rather than as text; notes without the property come last.
+struct SortSql {
+    join: String,
+    key: Option<String>,
+    order: String,
+}
+
+impl SortSql {
+    fn new(sort: Option<&QuerySort>) -> Self {
+        let Some(sort) = sort else {
+            return Self {
+                join: String::new(),
+                key: None,
+                order: String::new(),
+            };
+        };
+
+        let direction = match sort.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        match sort.property.as_str() {
+            "_path" => Self {
+                join: String::new(),
+                key: None,
+                order: format!("n.path {}, ", direction),
+            },
+            "note_title" => Self {
+                join: String::new(),
+                key: None,
+                order: format!("n.title IS NULL, n.title COLLATE NOCASE {}, ", direction),
+            },
+            key => Self {
+                join: "LEFT JOIN properties ps ON ps.note_id = n.id AND ps.key = ?".to_string(),
+                key: Some(key.to_string()),
+                order: format!(
+                    "ps.id IS NULL, ps.value_number {d}, COALESCE(ps.value_date, date(ps.value)) {d}, \
+                     ps.value_bool {d}, ps.value COLLATE NOCASE {d}, ",
+                    d = direction
+                ),
+            },
+        }
+    }
+}
+
+/// The items of a ContainsAll/ContainsAny filter value: a JSON array of
+/// strings, so items may contain commas. Other values are split on commas,
+/// as query embeds written before item lists did.
+fn filter_items(value: &str) -> Vec<String> {
+    let items = match serde_json::from_str::<Vec<String>>(value) {
+        Ok(items) => items,
+        Err(_) => value.split(',').map(str::to_string).collect(),
+    };
+    items
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Condition for the special `_path` filter (filters on notes.path column).
+fn path_condition(filter: &PropertyFilter) -> Condition {
+    let value = filter.value.clone().unwrap_or_default();
+    match filter.operator {
+        PropertyOperator::Equals => Condition::expr("n.path = ?", vec![value]),
+        PropertyOperator::NotEquals => Condition::expr("n.path != ?", vec![value]),
+        PropertyOperator::Contains => Condition::expr("n.path LIKE ?", vec![format!("%{}%", value)]),
+        PropertyOperator::StartsWith => {
+            // StartsWith on _path means "in folder" - match folder/ prefix
+            // Ensure folder path ends with / for proper matching
+            let folder_prefix = if value.ends_with('/') {
+                value
+            } else {
+                format!("{}/", value)
+            };
+            Condition::expr("n.path LIKE ?", vec![format!("{}%", folder_prefix)])
+        }
+        PropertyOperator::EndsWith => Condition::expr("n.path LIKE ?", vec![format!("%{}", value)]),
+        // Exists/NotExists don't make sense for path - treat as always true/false
+        PropertyOperator::Exists => Condition::expr("1=1", Vec::new()),
+        PropertyOperator::NotExists => Condition::expr("1=0", Vec::new()),
+        // ContainsAll/ContainsAny/Date/Number operators don't make sense for path
+        PropertyOperator::ContainsAll | PropertyOperator::ContainsAny
+        | PropertyOperator::DateOn | PropertyOperator::DateBefore
+        | PropertyOperator::DateAfter | PropertyOperator::DateOnOrBefore
+        | PropertyOperator::DateOnOrAfter | PropertyOperator::GreaterThan
+        | PropertyOperator::LessThan | PropertyOperator::GreaterOrEqual
+        | PropertyOperator::LessOrEqual => Condition::expr("1=1", Vec::new()),
+    }
+}
+
+/// The SQL condition on a task's completion: the `_completed` filter
+/// tests, or open tasks unless completed ones are included.
+fn task_completion_filter(include_completed: bool, completed: &[(String, String)]) -> String {
+    if !completed.is_empty() {
+        completed
+            .iter()
+            .map(|(test, _)| test.replace("{t}", "t"))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    } else if include_completed {
+        "1=1".to_string()
+    } else {
+        "t.completed = 0".to_string()
+    }
+}
+
+/// Tests on tasks from the `_completed` filters among `filters`.
+fn completed_tests(filters: &[PropertyFilter]) -> Vec<(String, String)> {
+    filters
+        .iter()
+        .filter(|f| f.key == "_completed")
+        .filter_map(completed_test)
+        .collect()
+}
+
+/// Test on a task (aliased `{t}`) for the special `_completed` filter: the
+/// task was completed on, before or after a date (YYYY-MM-DD). Completion
+/// times are stored with the local offset, so their date part is the local
+/// day. None for operators that don't apply to dates.
+fn completed_test(filter: &PropertyFilter) -> Option<(String, String)> {
+    let op = match filter.operator {
+        PropertyOperator::DateOn => "=",
+        PropertyOperator::DateBefore => "<",
+        PropertyOperator::DateAfter => ">",
+        PropertyOperator::DateOnOrBefore => "<=",
+        PropertyOperator::DateOnOrAfter => ">=",
+        _ => return None,
+    };
+    let test = format!("{{t}}.completed = 1 AND substr({{t}}.completed_at, 1, 10) {} ?", op);
+    Some((test, filter.value.clone().unwrap_or_default()))
+}
+
+/// Condition for the special `_completed` filter: the note has a task
+/// completed in the date range.
+fn completed_condition(filter: &PropertyFilter) -> Condition {
+    match completed_test(filter) {
+        Some((test, param)) => Condition::expr(
+            &format!("EXISTS (SELECT 1 FROM todos tc WHERE tc.note_id = n.id AND {})", test.replace("{t}", "tc")),
+            vec![param],
+        ),
+        None => Condition::expr("1=1", Vec::new()),
+    }
+}
+
+/// Condition for the special `_tags` filter (filters on tags table).
+/// Schema: tags(id, note_id, tag) - direct note_id -> tag mapping
+///
+/// Exact tag matches honor aliases (tag_meta): a filter on a tag or one of
+/// its aliases matches notes with any of them.
+fn tag_condition(filter: &PropertyFilter) -> Condition {
+    const HAS_TAG_LIKE: &str = "EXISTS (SELECT 1 FROM tags WHERE note_id = n.id AND tag LIKE ?)";
+    // Each tag is bound twice, for the alias lookup and as itself
+    let has_tags = |tags: Vec<String>, joiner: &str| {
+        let sql = format!("({})", vec![HAS_TAG; tags.len()].join(joiner));
+        Condition::expr(&sql, tags.into_iter().flat_map(|tag| [tag.clone(), tag]).collect())
+    };
+
+    let value = filter.value.clone().unwrap_or_default();
+    match filter.operator {
+        PropertyOperator::Exists => Condition::expr("EXISTS (SELECT 1 FROM tags WHERE note_id = n.id)", Vec::new()),
+        PropertyOperator::NotExists => {
+            Condition::expr("NOT EXISTS (SELECT 1 FROM tags WHERE note_id = n.id)", Vec::new())
+        }
+        // Single tag match
+        PropertyOperator::Equals | PropertyOperator::Contains => has_tags(vec![value], ""),
+        PropertyOperator::NotEquals => Condition::expr(&format!("NOT {}", HAS_TAG), vec![value.clone(), value]),
+        PropertyOperator::ContainsAll => {
+            // Note must have ALL specified tags
+            let tags = filter_items(&value);
+            if tags.is_empty() {
+                return Condition::expr("1=1", Vec::new());
+            }
+            has_tags(tags, " AND ")
+        }
+        PropertyOperator::ContainsAny => {
+            // Note must have ANY of the specified tags
+            let tags = filter_items(&value);
+            if tags.is_empty() {
+                return Condition::expr("1=0", Vec::new());
+            }
+            has_tags(tags, " OR ")
+        }
+        // Tags starting with prefix
+        PropertyOperator::StartsWith => Condition::expr(HAS_TAG_LIKE, vec![format!("{}%", value)]),
+        PropertyOperator::EndsWith => Condition::expr(HAS_TAG_LIKE, vec![format!("%{}", value)]),
+        // Date and number operators don't make sense for tags
+        PropertyOperator::DateOn | PropertyOperator::DateBefore
+        | PropertyOperator::DateAfter | PropertyOperator::DateOnOrBefore
+        | PropertyOperator::DateOnOrAfter | PropertyOperator::GreaterThan
+        | PropertyOperator::LessThan | PropertyOperator::GreaterOrEqual
+        | PropertyOperator::LessOrEqual => Condition::expr("1=1", Vec::new()),
+    }
+}
+
+/// Condition for a regular property filter.
+fn property_condition(filter: &PropertyFilter) -> Condition {
+    let key = filter.key.as_str();
+    let value = filter.value.clone().unwrap_or_default();
+    let test = |sql: &str| Some(sql.to_string());
+
+    match filter.operator {
+        PropertyOperator::Exists => Condition::property(key, None, Vec::new()),
+        PropertyOperator::NotExists => Condition::expr(
+            "NOT EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ?)",
+            vec![key.to_string()],
+        ),
+        PropertyOperator::Equals => Condition::property(key, test("{p}.value = ?"), vec![value]),
+        PropertyOperator::NotEquals => Condition::expr(
+            "NOT EXISTS (SELECT 1 FROM properties WHERE note_id = n.id AND key = ? AND value = ?)",
+            vec![key.to_string(), value],
+        ),
+        PropertyOperator::Contains => {
+            Condition::property(key, test("{p}.value LIKE ?"), vec![format!("%{}%", value)])
+        }
+        PropertyOperator::StartsWith => {
+            Condition::property(key, test("{p}.value LIKE ?"), vec![format!("{}%", value)])
+        }
+        PropertyOperator::EndsWith => {
+            Condition::property(key, test("{p}.value LIKE ?"), vec![format!("%{}", value)])
+        }
+        PropertyOperator::ContainsAll | PropertyOperator::ContainsAny => {
+            // List properties match on their items; other properties keep
+            // matching anywhere in their text, as comma-separated text values
+            // written before list properties existed still rely on it
+            const HAS_ITEM: &str = "(EXISTS (SELECT 1 FROM property_items pi WHERE pi.property_id = {p}.id \
+                AND pi.item = ? COLLATE NOCASE) OR ({p}.type IS NOT 'list' AND {p}.value LIKE ?))";
+
+            let items = filter_items(&value);
+            let all = matches!(filter.operator, PropertyOperator::ContainsAll);
+            if items.is_empty() {
+                return Condition::expr(if all { "1=1" } else { "1=0" }, Vec::new());
+            }
+            let joiner = if all { " AND " } else { " OR " };
+            let sql = format!("({})", vec![HAS_ITEM; items.len()].join(joiner));
+            let params = items.iter().flat_map(|item| [item.clone(), format!("%{}%", item)]).collect();
+            Condition::property(key, Some(sql), params)
+        }
+        // Date operators compare the typed date column, falling back to
+        // the text value for date-like values of other types
+        PropertyOperator::DateOn => Condition::property(key, test(&date_test("=")), vec![value]),
+        PropertyOperator::DateBefore => Condition::property(key, test(&date_test("<")), vec![value]),
+        PropertyOperator::DateAfter => Condition::property(key, test(&date_test(">")), vec![value]),
+        PropertyOperator::DateOnOrBefore => Condition::property(key, test(&date_test("<=")), vec![value]),
+        PropertyOperator::DateOnOrAfter => Condition::property(key, test(&date_test(">=")), vec![value]),
+        // Number operators only match number properties
+        PropertyOperator::GreaterThan => Condition::property(key, test(&number_test(">")), vec![value]),
+        PropertyOperator::LessThan => Condition::property(key, test(&number_test("<")), vec![value]),
+        PropertyOperator::GreaterOrEqual => Condition::property(key, test(&number_test(">=")), vec![value]),
+        PropertyOperator::LessOrEqual => Condition::property(key, test(&number_test("<=")), vec![value]),
+    }
+}
+
+fn date_test(op: &str) -> String {
+    format!("COALESCE({{p}}.value_date, date({{p}}.value)) {} date(?)", op)
+}
+
+fn number_test(op: &str) -> String {
+    format!("{{p}}.value_number {} CAST(? AS REAL)", op)
+}
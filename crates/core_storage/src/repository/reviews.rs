@@ -0,0 +1,200 @@
+//! Note review queue: resurfacing old notes on a growing interval.
+
+use crate::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use shared_types::{NoteListItem, NoteReviewDto};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+/// Interval for notes that were never reviewed, counted from their last edit.
+const INITIAL_REVIEW_INTERVAL_DAYS: i64 = 30;
+
+/// Upper bound for the review interval.
+const MAX_REVIEW_INTERVAL_DAYS: i64 = 365;
+
+type ReviewRow = (
+    i64,
+    String,
+    Option<String>,
+    i32,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+    i64,
+    i32,
+);
+
+impl VaultRepository {
+    /// Get the notes most overdue for review, most overdue first.
+    ///
+    /// Each note has a review interval that doubles every time it is
+    /// reviewed. The interval is shortened by the note's importance: pinned
+    /// notes, notes with many backlinks, and notes with `review: true` come
+    /// back sooner. Notes that were never reviewed count from their last edit.
+    pub async fn get_notes_due_for_review(
+        &self,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<NoteReviewDto>> {
+        let rows = sqlx::query_as::<_, ReviewRow>(
+            r#"
+            SELECT n.id, n.path, n.title, n.pinned, n.created_at, n.updated_at,
+                   r.last_reviewed, r.review_interval,
+                   (SELECT COUNT(*) FROM backlinks b WHERE b.to_note_id = n.id),
+                   EXISTS(
+                       SELECT 1 FROM properties p
+                       WHERE p.note_id = n.id AND p.key = 'review' AND LOWER(p.value) = 'true'
+                   )
+            FROM notes n
+            LEFT JOIN note_reviews r ON r.note_id = n.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut due: Vec<NoteReviewDto> = rows
+            .into_iter()
+            .filter_map(|(id, path, title, pinned, created_at, updated_at, last_reviewed, interval, backlinks, flagged)| {
+                let last_reviewed = last_reviewed.as_deref().and_then(parse_timestamp);
+                let reference = last_reviewed
+                    .or_else(|| updated_at.as_deref().and_then(parse_timestamp))
+                    .or_else(|| created_at.as_deref().and_then(parse_timestamp))?;
+
+                let review_interval_days = interval.unwrap_or(INITIAL_REVIEW_INTERVAL_DAYS);
+                let importance = importance(pinned != 0, backlinks, flagged != 0);
+                let due_score = due_score(reference, now, review_interval_days, importance);
+
+                (due_score >= 1.0).then_some(NoteReviewDto {
+                    note: NoteListItem { id, path, title, pinned: pinned != 0 },
+                    last_reviewed,
+                    review_interval_days,
+                    importance,
+                    due_score,
+                })
+            })
+            .collect();
+
+        due.sort_by(|a, b| b.due_score.total_cmp(&a.due_score).then_with(|| a.note.path.cmp(&b.note.path)));
+        due.truncate(limit);
+        Ok(due)
+    }
+
+    /// Mark a note as reviewed at `now`.
+    ///
+    /// The first review keeps the initial interval; later reviews double it.
+    ///
+    /// Returns the new interval in days, or None if the note doesn't exist.
+    #[instrument(skip(self))]
+    pub async fn mark_note_reviewed(&self, note_id: i64, now: DateTime<Utc>) -> Result<Option<i64>> {
+        let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM notes WHERE id = ?")
+            .bind(note_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if exists == 0 {
+            return Ok(None);
+        }
+
+        let current = sqlx::query_scalar::<_, i64>(
+            "SELECT review_interval FROM note_reviews WHERE note_id = ?",
+        )
+        .bind(note_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let interval = next_interval(current);
+
+        sqlx::query(
+            r#"
+            INSERT INTO note_reviews (note_id, last_reviewed, review_interval)
+            VALUES (?, ?, ?)
+            ON CONFLICT(note_id) DO UPDATE SET
+                last_reviewed = excluded.last_reviewed,
+                review_interval = excluded.review_interval
+            "#,
+        )
+        .bind(note_id)
+        .bind(now.to_rfc3339())
+        .bind(interval)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Reviewed note {}, next review in {} days", note_id, interval);
+        Ok(Some(interval))
+    }
+}
+
+/// Importance weight of a note; 1.0 for a plain note.
+///
+/// Backlinks add with diminishing returns so hub notes don't crowd out
+/// everything else.
+fn importance(pinned: bool, backlinks: i64, flagged: bool) -> f64 {
+    let mut weight = 1.0 + 0.5 * (1.0 + backlinks.max(0) as f64).ln();
+    if pinned {
+        weight += 1.0;
+    }
+    if flagged {
+        weight += 1.0;
+    }
+    weight
+}
+
+/// Elapsed time since `reference` relative to the importance-weighted interval.
+fn due_score(reference: DateTime<Utc>, now: DateTime<Utc>, interval_days: i64, importance: f64) -> f64 {
+    let elapsed_days = (now - reference).num_minutes().max(0) as f64 / (24.0 * 60.0);
+    let weighted_interval = interval_days.max(1) as f64 / importance;
+    elapsed_days / weighted_interval
+}
+
+/// Interval after a review: double the current one, up to the maximum.
+fn next_interval(current: Option<i64>) -> i64 {
+    current
+        .map(|days| (days.max(1) * 2).min(MAX_REVIEW_INTERVAL_DAYS))
+        .unwrap_or(INITIAL_REVIEW_INTERVAL_DAYS)
+}
+
+/// Parse a stored timestamp (RFC 3339 or SQLite `datetime('now')` format).
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").map(|d| d.and_utc()))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_importance() {
+        assert_eq!(importance(false, 0, false), 1.0);
+        assert_eq!(importance(true, 0, true), 3.0);
+        assert!(importance(false, 10, false) > importance(false, 2, false));
+        assert!(importance(false, 10, false) < 3.0);
+    }
+
+    #[test]
+    fn test_due_score_weighted_by_importance() {
+        let now = Utc::now();
+        let reference = now - Duration::days(15);
+
+        assert_eq!(due_score(reference, now, 30, 1.0), 0.5);
+        assert_eq!(due_score(reference, now, 30, 2.0), 1.0);
+        assert_eq!(due_score(now + Duration::days(1), now, 30, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_next_interval() {
+        assert_eq!(next_interval(None), INITIAL_REVIEW_INTERVAL_DAYS);
+        assert_eq!(next_interval(Some(30)), 60);
+        assert_eq!(next_interval(Some(300)), MAX_REVIEW_INTERVAL_DAYS);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert!(parse_timestamp("2024-01-15T09:00:00+00:00").is_some());
+        assert!(parse_timestamp("2024-01-15 09:00:00").is_some());
+        assert!(parse_timestamp("yesterday").is_none());
+    }
+}
@@ -1,12 +1,12 @@
 //! Schedule block operations.
 
-use crate::Result;
-use chrono::{Datelike, NaiveDate, NaiveTime, TimeZone, Timelike};
+use crate::{Result, StorageError};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
 use rrule::{RRuleSet, Tz as RRuleTz};
 use shared_types::ScheduleBlockDto;
 use tracing::warn;
 
-use super::VaultRepository;
+use super::{VaultRepository, MAX_BOUND_IDS};
 
 impl VaultRepository {
     /// Create a schedule block.
@@ -44,14 +44,16 @@ impl VaultRepository {
     }
 
     /// Get schedule blocks for a date range, expanding recurring blocks.
+    ///
+    /// Events from calendar subscriptions are included as read-only blocks.
     pub async fn get_schedule_blocks_for_range(
         &self,
         start_date: &str,
         end_date: &str,
     ) -> Result<Vec<ScheduleBlockDto>> {
         // First get non-recurring blocks in the range
-        let non_recurring_rows = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule FROM schedule_blocks WHERE (rrule IS NULL OR rrule = '') AND date >= ? AND date <= ? ORDER BY date, start_time",
+        let non_recurring_rows = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>(
+            "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule, timezone FROM schedule_blocks WHERE (rrule IS NULL OR rrule = '') AND date >= ? AND date <= ? ORDER BY date, start_time",
         )
         .bind(start_date)
         .bind(end_date)
@@ -60,12 +62,13 @@ impl VaultRepository {
 
         let mut blocks: Vec<ScheduleBlockDto> = non_recurring_rows
             .into_iter()
-            .filter_map(|(id, note_id, date, start_time, end_time, label, color, context, rrule)| {
+            .filter_map(|(id, note_id, date, start_time, end_time, label, color, context, rrule, timezone)| {
                 let date = date.parse().ok()?;
                 let start_time = start_time.parse().ok()?;
                 let end_time = end_time.parse().ok()?;
                 Some(ScheduleBlockDto {
                     id,
+                    key: format!("block:{}", id),
                     note_id,
                     date,
                     start_time,
@@ -74,15 +77,17 @@ impl VaultRepository {
                     color,
                     context,
                     rrule,
+                    timezone,
                     is_occurrence: false,
+                    read_only: false,
                 })
             })
             .collect();
 
         // Now get recurring blocks and expand them
         // Filter by base date <= end_date since recurring events can't produce occurrences before their start
-        let recurring_rows = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule FROM schedule_blocks WHERE rrule IS NOT NULL AND rrule != '' AND date <= ?",
+        let recurring_rows = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>(
+            "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule, timezone FROM schedule_blocks WHERE rrule IS NOT NULL AND rrule != '' AND date <= ?",
         )
         .bind(end_date)
         .fetch_all(&self.pool)
@@ -92,7 +97,7 @@ impl VaultRepository {
         let end = end_date.parse::<NaiveDate>().ok();
 
         if let (Some(start), Some(end)) = (start, end) {
-            for (id, note_id, date_str, start_time_str, end_time_str, label, color, context, rrule_opt) in recurring_rows {
+            for (id, note_id, date_str, start_time_str, end_time_str, label, color, context, rrule_opt, timezone) in recurring_rows {
                 if let Some(rrule_str) = rrule_opt {
                     let base_date: NaiveDate = match date_str.parse() {
                         Ok(d) => d,
@@ -108,11 +113,12 @@ impl VaultRepository {
                     };
 
                     // Expand rrule occurrences within the date range
-                    match expand_rrule(&rrule_str, base_date, start_time, start, end) {
+                    match expand_rrule(&rrule_str, base_date, start_time, timezone.as_deref(), start, end) {
                         Ok(occurrences) => {
                             for occ_date in occurrences {
                                 blocks.push(ScheduleBlockDto {
                                     id,
+                                    key: format!("block:{}", id),
                                     note_id,
                                     date: occ_date,
                                     start_time,
@@ -121,7 +127,9 @@ impl VaultRepository {
                                     color: color.clone(),
                                     context: context.clone(),
                                     rrule: Some(rrule_str.clone()),
+                                    timezone: timezone.clone(),
                                     is_occurrence: occ_date != base_date,
+                                    read_only: false,
                                 });
                             }
                         }
@@ -131,6 +139,7 @@ impl VaultRepository {
                             if base_date >= start && base_date <= end {
                                 blocks.push(ScheduleBlockDto {
                                     id,
+                                    key: format!("block:{}", id),
                                     note_id,
                                     date: base_date,
                                     start_time,
@@ -139,7 +148,9 @@ impl VaultRepository {
                                     color,
                                     context,
                                     rrule: Some(rrule_str),
+                                    timezone,
                                     is_occurrence: false,
+                                    read_only: false,
                                 });
                             }
                         }
@@ -148,6 +159,9 @@ impl VaultRepository {
             }
         }
 
+        // Add read-only events from calendar subscriptions
+        blocks.extend(self.get_calendar_overlay_blocks(start_date, end_date).await?);
+
         // Sort by date and time
         blocks.sort_by(|a, b| {
             a.date.cmp(&b.date).then_with(|| a.start_time.cmp(&b.start_time))
@@ -213,21 +227,36 @@ impl VaultRepository {
         Ok(())
     }
 
+    /// Set the IANA time zone of a schedule block.
+    ///
+    /// `None` or an empty string reverts the block to the vault's local time.
+    pub async fn set_schedule_block_timezone(&self, id: i64, timezone: Option<&str>) -> Result<()> {
+        let timezone = validate_timezone(timezone)?;
+
+        sqlx::query("UPDATE schedule_blocks SET timezone = ? WHERE id = ?")
+            .bind(timezone)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     /// Get a schedule block by ID.
     pub async fn get_schedule_block(&self, id: i64) -> Result<Option<ScheduleBlockDto>> {
-        let row = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule FROM schedule_blocks WHERE id = ?",
+        let row = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>(
+            "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule, timezone FROM schedule_blocks WHERE id = ?",
         )
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.and_then(|(id, note_id, date, start_time, end_time, label, color, context, rrule)| {
+        Ok(row.and_then(|(id, note_id, date, start_time, end_time, label, color, context, rrule, timezone)| {
             let date = date.parse().ok()?;
             let start_time = start_time.parse().ok()?;
             let end_time = end_time.parse().ok()?;
             Some(ScheduleBlockDto {
                 id,
+                key: format!("block:{}", id),
                 note_id,
                 date,
                 start_time,
@@ -236,7 +265,9 @@ impl VaultRepository {
                 color,
                 context,
                 rrule,
+                timezone,
                 is_occurrence: false,
+                read_only: false,
             })
         }))
     }
@@ -248,21 +279,34 @@ impl VaultRepository {
 
     /// Get schedule blocks linked to a specific note.
     pub async fn get_schedule_blocks_for_note(&self, note_id: i64) -> Result<Vec<ScheduleBlockDto>> {
-        let rows = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule FROM schedule_blocks WHERE note_id = ? ORDER BY date, start_time",
-        )
-        .bind(note_id)
-        .fetch_all(&self.pool)
-        .await?;
+        self.get_schedule_blocks_for_notes(&[note_id]).await
+    }
+
+    /// Get schedule blocks linked to any of the notes.
+    pub async fn get_schedule_blocks_for_notes(&self, note_ids: &[i64]) -> Result<Vec<ScheduleBlockDto>> {
+        let mut rows = Vec::new();
+        for batch in note_ids.chunks(MAX_BOUND_IDS) {
+            let sql = format!(
+                "SELECT id, note_id, date, start_time, end_time, label, color, context, rrule, timezone FROM schedule_blocks WHERE note_id IN ({})",
+                vec!["?"; batch.len()].join(", ")
+            );
+            let mut query = sqlx::query_as::<_, (i64, Option<i64>, String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>(&sql);
+            for id in batch {
+                query = query.bind(id);
+            }
+            rows.extend(query.fetch_all(&self.pool).await?);
+        }
+        rows.sort_by(|a, b| (&a.2, &a.3).cmp(&(&b.2, &b.3)));
 
         Ok(rows
             .into_iter()
-            .filter_map(|(id, note_id, date, start_time, end_time, label, color, context, rrule)| {
+            .filter_map(|(id, note_id, date, start_time, end_time, label, color, context, rrule, timezone)| {
                 let date = date.parse().ok()?;
                 let start_time = start_time.parse().ok()?;
                 let end_time = end_time.parse().ok()?;
                 Some(ScheduleBlockDto {
                     id,
+                    key: format!("block:{}", id),
                     note_id,
                     date,
                     start_time,
@@ -271,49 +315,76 @@ impl VaultRepository {
                     color,
                     context,
                     rrule,
+                    timezone,
                     is_occurrence: false,
+                    read_only: false,
                 })
             })
             .collect())
     }
 }
 
+/// Check a schedule block's IANA time zone, so callers can reject it before
+/// writing the block. Returns it trimmed, or `None` for `None` or an empty
+/// string (the vault's local time).
+pub fn validate_timezone(timezone: Option<&str>) -> Result<Option<&str>> {
+    let timezone = timezone.map(str::trim).filter(|tz| !tz.is_empty());
+    if let Some(tz) = timezone {
+        tz.parse::<chrono_tz::Tz>()
+            .map_err(|_| StorageError::InvalidTimezone(tz.to_string()))?;
+    }
+    Ok(timezone)
+}
+
+/// A local time in `tz`. An ambiguous time (clocks going back) is the
+/// earlier of the two, and a time in a gap (clocks going forward) is moved
+/// past the gap.
+fn local_datetime(tz: &RRuleTz, local: NaiveDateTime) -> Option<DateTime<RRuleTz>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+}
+
 /// Expand an RRULE to get occurrences within a date range.
+///
+/// Blocks without a time zone are floating (vault local time) and are expanded
+/// in UTC so their wall-clock time never shifts. Blocks with a time zone are
+/// expanded in that zone, so occurrences keep their local time across DST changes.
 pub(crate) fn expand_rrule(
     rrule_str: &str,
     base_date: NaiveDate,
     base_time: NaiveTime,
+    timezone: Option<&str>,
     range_start: NaiveDate,
     range_end: NaiveDate,
 ) -> std::result::Result<Vec<NaiveDate>, String> {
-    // Build the full RRULE string with DTSTART in UTC format
-    let dtstart = format!(
-        "DTSTART:{}T{:02}{:02}{:02}Z",
+    let stamp = format!(
+        "{}T{:02}{:02}{:02}",
         base_date.format("%Y%m%d"),
         base_time.hour(),
         base_time.minute(),
         base_time.second()
     );
 
+    // Build the full RRULE string with DTSTART in the block's zone (or UTC)
+    let (tz, dtstart) = match timezone {
+        Some(name) => {
+            let tz: chrono_tz::Tz = name.parse().map_err(|_| format!("Invalid time zone: {}", name))?;
+            (RRuleTz::from(tz), format!("DTSTART;TZID={}:{}", name, stamp))
+        }
+        None => (RRuleTz::UTC, format!("DTSTART:{}Z", stamp)),
+    };
+
     let full_rrule = format!("{}\nRRULE:{}", dtstart, rrule_str);
 
     // Parse the RRULE
     let rruleset: RRuleSet = full_rrule.parse().map_err(|e| format!("Invalid rrule: {}", e))?;
 
-    // Convert range to chrono-tz datetimes for the rrule crate
-    let after = RRuleTz::UTC.with_ymd_and_hms(
-        range_start.year(),
-        range_start.month(),
-        range_start.day(),
-        0, 0, 0
-    ).single().ok_or("Invalid start date")?;
-
-    let before = RRuleTz::UTC.with_ymd_and_hms(
-        range_end.year(),
-        range_end.month(),
-        range_end.day(),
-        23, 59, 59
-    ).single().ok_or("Invalid end date")?;
+    // Convert range to datetimes in the same zone for the rrule crate. Days
+    // that start in a DST gap start when the gap ends.
+    let after = local_datetime(&tz, range_start.and_time(NaiveTime::MIN)).ok_or("Invalid start date")?;
+    let before = local_datetime(&tz, range_end.and_hms_opt(23, 59, 59).unwrap_or_default())
+        .ok_or("Invalid end date")?;
 
     // Get occurrences in range (limit to 500 to prevent runaway)
     let occurrences = rruleset
@@ -0,0 +1,274 @@
+//! Analytics over schedule blocks: context switching and focus stretches.
+
+use crate::Result;
+use chrono::NaiveDate;
+use shared_types::{
+    ContextMinutes, ContextSwitchReport, DayContextStats, FocusStretch, ScheduleBlockDto,
+    WeekContextStats, WeekStart,
+};
+use std::collections::HashMap;
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Compute context switches, time per context, and the longest focus
+    /// stretches for the schedule between `start_date` and `end_date`, with
+    /// weeks starting on `week_start`.
+    pub async fn get_context_switch_report(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        week_start: WeekStart,
+    ) -> Result<ContextSwitchReport> {
+        let blocks = self
+            .get_schedule_blocks_for_range(&start_date.to_string(), &end_date.to_string())
+            .await?;
+
+        Ok(build_context_switch_report(start_date, end_date, week_start, &blocks))
+    }
+}
+
+/// Build the report from expanded schedule blocks.
+///
+/// Blocks without a context and read-only calendar events are ignored, so a
+/// context-less break between two blocks doesn't count as a switch.
+fn build_context_switch_report(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    week_start: WeekStart,
+    blocks: &[ScheduleBlockDto],
+) -> ContextSwitchReport {
+    let mut by_date: HashMap<NaiveDate, Vec<&ScheduleBlockDto>> = HashMap::new();
+    for block in blocks {
+        let has_context = block.context.as_deref().is_some_and(|c| !c.trim().is_empty());
+        if has_context && !block.read_only {
+            by_date.entry(block.date).or_default().push(block);
+        }
+    }
+
+    let days: Vec<DayContextStats> = start_date
+        .iter_days()
+        .take_while(|d| *d <= end_date)
+        .map(|date| {
+            let mut day_blocks = by_date.remove(&date).unwrap_or_default();
+            day_blocks.sort_by_key(|b| (b.start_time, b.end_time));
+            day_stats(date, &day_blocks)
+        })
+        .collect();
+
+    let mut weeks: Vec<WeekContextStats> = Vec::new();
+    for chunk in days.chunk_by(|a, b| week_start.start_of_week(a.date) == week_start.start_of_week(b.date)) {
+        let active_days = chunk.iter().filter(|d| !d.context_minutes.is_empty()).count();
+        let switches: i64 = chunk.iter().map(|d| d.switches).sum();
+        weeks.push(WeekContextStats {
+            week_start: week_start.start_of_week(chunk[0].date),
+            switches,
+            average_daily_switches: if active_days > 0 {
+                switches as f64 / active_days as f64
+            } else {
+                0.0
+            },
+            context_minutes: merge_minutes(
+                chunk.iter().flat_map(|d| &d.context_minutes).map(|c| (&c.context, c.minutes)),
+            ),
+            longest_focus: longest(chunk.iter().filter_map(|d| d.longest_focus.as_ref())),
+        });
+    }
+
+    let trend_points: Vec<(f64, f64)> = days
+        .iter()
+        .filter(|d| !d.context_minutes.is_empty())
+        .map(|d| ((d.date - start_date).num_days() as f64, d.switches as f64))
+        .collect();
+
+    ContextSwitchReport {
+        start_date,
+        end_date,
+        total_switches: days.iter().map(|d| d.switches).sum(),
+        context_minutes: merge_minutes(
+            days.iter().flat_map(|d| &d.context_minutes).map(|c| (&c.context, c.minutes)),
+        ),
+        longest_focus: longest(days.iter().filter_map(|d| d.longest_focus.as_ref())),
+        switch_trend: slope(&trend_points),
+        weeks,
+        days,
+    }
+}
+
+/// Statistics for one day's blocks, sorted by start time.
+fn day_stats(date: NaiveDate, blocks: &[&ScheduleBlockDto]) -> DayContextStats {
+    let mut switches = 0;
+    let mut runs: Vec<FocusStretch> = Vec::new();
+
+    for block in blocks {
+        let context = block.context.clone().unwrap_or_default();
+        let minutes = (block.end_time - block.start_time).num_minutes().max(0);
+
+        match runs.last_mut() {
+            Some(run) if run.context == context => {
+                run.end_time = run.end_time.max(block.end_time);
+                run.minutes += minutes;
+            }
+            last => {
+                if last.is_some() {
+                    switches += 1;
+                }
+                runs.push(FocusStretch {
+                    date,
+                    context,
+                    start_time: block.start_time,
+                    end_time: block.end_time,
+                    minutes,
+                });
+            }
+        }
+    }
+
+    DayContextStats {
+        date,
+        switches,
+        context_minutes: merge_minutes(runs.iter().map(|r| (&r.context, r.minutes))),
+        longest_focus: longest(runs.iter()),
+    }
+}
+
+/// Sum minutes per context, most first (ties by name).
+fn merge_minutes<'a>(entries: impl Iterator<Item = (&'a String, i64)>) -> Vec<ContextMinutes> {
+    let mut totals: HashMap<&String, i64> = HashMap::new();
+    for (context, minutes) in entries {
+        *totals.entry(context).or_insert(0) += minutes;
+    }
+
+    let mut merged: Vec<ContextMinutes> = totals
+        .into_iter()
+        .map(|(context, minutes)| ContextMinutes {
+            context: context.clone(),
+            minutes,
+        })
+        .collect();
+    merged.sort_by(|a, b| b.minutes.cmp(&a.minutes).then_with(|| a.context.cmp(&b.context)));
+    merged
+}
+
+/// The longest stretch (the earliest one on ties).
+fn longest<'a>(stretches: impl Iterator<Item = &'a FocusStretch>) -> Option<FocusStretch> {
+    stretches
+        .fold(None, |best: Option<&FocusStretch>, s| match best {
+            Some(b) if b.minutes >= s.minutes => Some(b),
+            _ => Some(s),
+        })
+        .cloned()
+}
+
+/// Least-squares slope of `points`, None with fewer than two distinct x values.
+fn slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if var_x == 0.0 {
+        return None;
+    }
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    Some(cov / var_x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn block(day: &str, start: &str, end: &str, context: Option<&str>) -> ScheduleBlockDto {
+        ScheduleBlockDto {
+            id: 0,
+            key: "block:0".to_string(),
+            note_id: None,
+            date: date(day),
+            start_time: NaiveTime::parse_from_str(start, "%H:%M").unwrap(),
+            end_time: NaiveTime::parse_from_str(end, "%H:%M").unwrap(),
+            label: None,
+            color: None,
+            context: context.map(str::to_string),
+            rrule: None,
+            timezone: None,
+            is_occurrence: false,
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn test_day_switches_and_focus() {
+        let blocks = vec![
+            block("2024-01-15", "13:00", "14:00", Some("admin")),
+            block("2024-01-15", "09:00", "10:00", Some("work")),
+            block("2024-01-15", "10:00", "11:30", Some("work")),
+            block("2024-01-15", "11:30", "12:00", None),
+            block("2024-01-15", "14:00", "14:30", Some("work")),
+        ];
+
+        let report = build_context_switch_report(date("2024-01-15"), date("2024-01-15"), WeekStart::Monday, &blocks);
+        let day = &report.days[0];
+
+        // work -> admin -> work; the context-less block is ignored
+        assert_eq!(day.switches, 2);
+        assert_eq!(
+            day.context_minutes,
+            vec![
+                ContextMinutes { context: "work".to_string(), minutes: 180 },
+                ContextMinutes { context: "admin".to_string(), minutes: 60 },
+            ]
+        );
+
+        let focus = day.longest_focus.as_ref().unwrap();
+        assert_eq!(focus.context, "work");
+        assert_eq!(focus.minutes, 150);
+        assert_eq!(focus.end_time, NaiveTime::from_hms_opt(11, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_weeks_and_trend() {
+        let blocks = vec![
+            // Sunday: 3 switches
+            block("2024-01-14", "09:00", "10:00", Some("a")),
+            block("2024-01-14", "10:00", "11:00", Some("b")),
+            block("2024-01-14", "11:00", "12:00", Some("a")),
+            block("2024-01-14", "12:00", "13:00", Some("b")),
+            // Monday: 1 switch
+            block("2024-01-15", "09:00", "10:00", Some("a")),
+            block("2024-01-15", "10:00", "11:00", Some("b")),
+        ];
+
+        let report = build_context_switch_report(date("2024-01-13"), date("2024-01-16"), WeekStart::Monday, &blocks);
+
+        assert_eq!(report.days.len(), 4);
+        assert_eq!(report.total_switches, 4);
+
+        let week_starts: Vec<_> = report.weeks.iter().map(|w| w.week_start).collect();
+        assert_eq!(week_starts, vec![date("2024-01-08"), date("2024-01-15")]);
+        assert_eq!(report.weeks[0].switches, 3);
+        assert_eq!(report.weeks[0].average_daily_switches, 3.0);
+        assert_eq!(report.weeks[1].average_daily_switches, 1.0);
+
+        assert_eq!(report.switch_trend, Some(-2.0));
+
+        // Weeks starting on Sunday put Sunday and Monday together
+        let report = build_context_switch_report(date("2024-01-13"), date("2024-01-16"), WeekStart::Sunday, &blocks);
+        let week_starts: Vec<_> = report.weeks.iter().map(|w| w.week_start).collect();
+        assert_eq!(week_starts, vec![date("2024-01-07"), date("2024-01-14")]);
+        assert_eq!(report.weeks[1].switches, 4);
+    }
+
+    #[test]
+    fn test_slope() {
+        assert_eq!(slope(&[]), None);
+        assert_eq!(slope(&[(0.0, 3.0)]), None);
+        assert_eq!(slope(&[(0.0, 1.0), (1.0, 2.0), (2.0, 3.0)]), Some(1.0));
+    }
+}
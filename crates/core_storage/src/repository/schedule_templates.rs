@@ -0,0 +1,213 @@
+//! Schedule template (routine) operations.
+
+use crate::{Result, StorageError};
+use chrono::Utc;
+use shared_types::{
+    ApplyScheduleTemplateResult, CreateScheduleTemplateRequest, ScheduleTemplateDto,
+    ScheduleTemplateSlot,
+};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Create a schedule template with its slots.
+    /// Fails with `InvalidTimeRange` if a slot does not end after it starts.
+    #[instrument(skip(self))]
+    pub async fn create_schedule_template(
+        &self,
+        request: &CreateScheduleTemplateRequest,
+    ) -> Result<i64> {
+        if let Some(slot) = request.slots.iter().find(|s| s.end_time <= s.start_time) {
+            return Err(StorageError::InvalidTimeRange(format!(
+                "{}..{}",
+                slot.start_time, slot.end_time
+            )));
+        }
+
+        let now = Utc::now().to_rfc3339();
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO schedule_templates (name, description, created_at)
+            VALUES (?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(&now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        for slot in &request.slots {
+            sqlx::query(
+                r#"
+                INSERT INTO schedule_template_slots (template_id, start_time, end_time, label, color, context)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(slot.start_time.to_string())
+            .bind(slot.end_time.to_string())
+            .bind(&slot.label)
+            .bind(&slot.color)
+            .bind(&slot.context)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        debug!("Created schedule template {} with id {}", request.name, id);
+        Ok(id)
+    }
+
+    /// Get a schedule template by ID, including its slots.
+    pub async fn get_schedule_template(&self, id: i64) -> Result<Option<ScheduleTemplateDto>> {
+        let row = sqlx::query_as::<_, (i64, String, Option<String>)>(
+            "SELECT id, name, description FROM schedule_templates WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some((id, name, description)) => {
+                let slots = self.get_schedule_template_slots(id).await?;
+                Ok(Some(ScheduleTemplateDto {
+                    id,
+                    name,
+                    description,
+                    slots,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List all schedule templates, ordered by name.
+    pub async fn list_schedule_templates(&self) -> Result<Vec<ScheduleTemplateDto>> {
+        let rows = sqlx::query_as::<_, (i64, String, Option<String>)>(
+            "SELECT id, name, description FROM schedule_templates ORDER BY name COLLATE NOCASE",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut templates = Vec::with_capacity(rows.len());
+        for (id, name, description) in rows {
+            let slots = self.get_schedule_template_slots(id).await?;
+            templates.push(ScheduleTemplateDto {
+                id,
+                name,
+                description,
+                slots,
+            });
+        }
+
+        Ok(templates)
+    }
+
+    /// Delete a schedule template and its slots.
+    #[instrument(skip(self))]
+    pub async fn delete_schedule_template(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM schedule_template_slots WHERE template_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("DELETE FROM schedule_templates WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Deleted schedule template {}", id);
+        Ok(())
+    }
+
+    /// Apply a schedule template to a date, creating one block per slot.
+    ///
+    /// When `skip_conflicts` is true, slots that overlap an existing block on
+    /// that date (including recurring occurrences) are skipped and reported.
+    /// Returns `None` if the template does not exist.
+    #[instrument(skip(self))]
+    pub async fn apply_schedule_template(
+        &self,
+        template_id: i64,
+        date: &str,
+        skip_conflicts: bool,
+    ) -> Result<Option<ApplyScheduleTemplateResult>> {
+        let template = match self.get_schedule_template(template_id).await? {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+
+        let existing = if skip_conflicts {
+            self.get_schedule_blocks_for_date(date).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut created_block_ids = Vec::new();
+        let mut skipped_slots = Vec::new();
+
+        for slot in template.slots {
+            let conflicts = existing
+                .iter()
+                .any(|b| slot.start_time < b.end_time && b.start_time < slot.end_time);
+
+            if conflicts {
+                skipped_slots.push(slot);
+                continue;
+            }
+
+            let id = self
+                .create_schedule_block(
+                    None,
+                    date,
+                    &slot.start_time.to_string(),
+                    &slot.end_time.to_string(),
+                    slot.label.as_deref(),
+                    slot.color.as_deref(),
+                    slot.context.as_deref(),
+                    None,
+                )
+                .await?;
+            created_block_ids.push(id);
+        }
+
+        debug!(
+            "Applied template {} to {}: {} created, {} skipped",
+            template_id,
+            date,
+            created_block_ids.len(),
+            skipped_slots.len()
+        );
+
+        Ok(Some(ApplyScheduleTemplateResult {
+            created_block_ids,
+            skipped_slots,
+        }))
+    }
+
+    /// Get the slots for a template, ordered by start time.
+    async fn get_schedule_template_slots(&self, template_id: i64) -> Result<Vec<ScheduleTemplateSlot>> {
+        let rows = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<String>)>(
+            "SELECT start_time, end_time, label, color, context FROM schedule_template_slots WHERE template_id = ? ORDER BY start_time",
+        )
+        .bind(template_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(start_time, end_time, label, color, context)| {
+                Some(ScheduleTemplateSlot {
+                    start_time: start_time.parse().ok()?,
+                    end_time: end_time.parse().ok()?,
+                    label,
+                    color,
+                    context,
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,121 @@
+//! Scheduled note rules: notes created from a template on a recurring schedule.
+
+use crate::Result;
+use chrono::{Local, NaiveDateTime};
+use shared_types::{SaveScheduledNoteRuleRequest, ScheduledNoteRule};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+/// Format of `last_run` and `created_at`: local time without a zone.
+const LOCAL_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+type RuleRow = (i64, String, String, String, String, Option<String>, bool, Option<String>, String);
+
+impl VaultRepository {
+    /// Create a scheduled note rule. The rule counts as having run when it is
+    /// created, so occurrences before that aren't caught up on.
+    #[instrument(skip(self))]
+    pub async fn create_scheduled_note_rule(&self, request: &SaveScheduledNoteRuleRequest) -> Result<i64> {
+        let now = Local::now().naive_local().format(LOCAL_TIME_FORMAT).to_string();
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO scheduled_note_rules
+                (name, rrule, time, path_pattern, template_path, enabled, last_run, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(&request.name)
+        .bind(&request.rrule)
+        .bind(&request.time)
+        .bind(&request.path_pattern)
+        .bind(&request.template_path)
+        .bind(request.enabled)
+        .bind(&now)
+        .bind(&now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        debug!("Created scheduled note rule {} with id {}", request.name, id);
+        Ok(id)
+    }
+
+    /// Change a scheduled note rule. Enabling a disabled rule counts as a
+    /// run, so occurrences while it was disabled aren't caught up on.
+    #[instrument(skip(self))]
+    pub async fn update_scheduled_note_rule(&self, id: i64, request: &SaveScheduledNoteRuleRequest) -> Result<()> {
+        let now = Local::now().naive_local().format(LOCAL_TIME_FORMAT).to_string();
+        sqlx::query(
+            r#"
+            UPDATE scheduled_note_rules
+            SET name = ?, rrule = ?, time = ?, path_pattern = ?, template_path = ?, enabled = ?,
+                last_run = CASE WHEN enabled = 0 AND ? THEN ? ELSE last_run END
+            WHERE id = ?
+            "#,
+        )
+        .bind(&request.name)
+        .bind(&request.rrule)
+        .bind(&request.time)
+        .bind(&request.path_pattern)
+        .bind(&request.template_path)
+        .bind(request.enabled)
+        .bind(request.enabled)
+        .bind(&now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete a scheduled note rule.
+    #[instrument(skip(self))]
+    pub async fn delete_scheduled_note_rule(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM scheduled_note_rules WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        debug!("Deleted scheduled note rule {}", id);
+        Ok(())
+    }
+
+    /// List all scheduled note rules.
+    pub async fn list_scheduled_note_rules(&self) -> Result<Vec<ScheduledNoteRule>> {
+        let rows = sqlx::query_as::<_, RuleRow>(
+            r#"
+            SELECT id, name, rrule, time, path_pattern, template_path, enabled, last_run, created_at
+            FROM scheduled_note_rules
+            ORDER BY name
+            "#,
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, name, rrule, time, path_pattern, template_path, enabled, last_run, created_at)| ScheduledNoteRule {
+                    id,
+                    name,
+                    rrule,
+                    time,
+                    path_pattern,
+                    template_path,
+                    enabled,
+                    last_run,
+                    created_at,
+                },
+            )
+            .collect())
+    }
+
+    /// Record that a rule has created its notes up to `ran_at` (local time).
+    pub async fn set_scheduled_note_last_run(&self, id: i64, ran_at: NaiveDateTime) -> Result<()> {
+        sqlx::query("UPDATE scheduled_note_rules SET last_run = ? WHERE id = ?")
+            .bind(ran_at.format(LOCAL_TIME_FORMAT).to_string())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
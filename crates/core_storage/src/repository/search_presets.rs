@@ -0,0 +1,69 @@
+//! Search presets: named FTS searches with their filters.
+
+use crate::Result;
+use chrono::Utc;
+use shared_types::{SaveSearchPresetRequest, SearchPresetDto};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+impl VaultRepository {
+    /// Save a search preset, replacing any preset with the same name.
+    #[instrument(skip(self))]
+    pub async fn save_search_preset(&self, request: &SaveSearchPresetRequest) -> Result<i64> {
+        let filters = serde_json::to_string(&request.filters).unwrap_or_default();
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO search_presets (name, query, filters, created_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                query = excluded.query,
+                filters = excluded.filters
+            RETURNING id
+            "#,
+        )
+        .bind(request.name.trim())
+        .bind(&request.query)
+        .bind(&filters)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_one(&self.pool)
+        .await?;
+
+        debug!("Saved search preset {} (id={})", request.name, id);
+        Ok(id)
+    }
+
+    /// List all search presets, ordered by name.
+    pub async fn list_search_presets(&self) -> Result<Vec<SearchPresetDto>> {
+        let rows = sqlx::query_as::<_, (i64, String, String, Option<String>)>(
+            "SELECT id, name, query, filters FROM search_presets ORDER BY name COLLATE NOCASE",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, name, query, filters)| SearchPresetDto {
+                id,
+                name,
+                query,
+                filters: filters
+                    .and_then(|f| serde_json::from_str(&f).ok())
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Delete a search preset.
+    #[instrument(skip(self))]
+    pub async fn delete_search_preset(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM search_presets WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!("Deleted search preset {}", id);
+        Ok(())
+    }
+}
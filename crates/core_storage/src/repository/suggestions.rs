@@ -0,0 +1,212 @@
+//! Context-aware task suggestions ("what should I do now").
+
+use crate::Result;
+use chrono::NaiveDate;
+use shared_types::{TaskQuery, TaskSuggestion, TodoDto};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+/// Estimates at or below this many minutes count as short tasks.
+const SHORT_TASK_MINUTES: i32 = 30;
+
+/// Estimates above this many minutes count as long tasks.
+const LONG_TASK_MINUTES: i32 = 60;
+
+impl VaultRepository {
+    /// Rank incomplete tasks for the current situation.
+    ///
+    /// Scores combine context match, due date proximity, priority, whether the
+    /// estimated effort fits `available_minutes`, and the current `energy`
+    /// level ("low" favours short tasks, "high" favours important or long ones).
+    #[instrument(skip(self))]
+    pub async fn suggest_next_tasks(
+        &self,
+        today: NaiveDate,
+        context: Option<&str>,
+        available_minutes: Option<i32>,
+        energy: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<TaskSuggestion>> {
+        // Every open task is scored before the best are kept
+        let candidates = self
+            .query_tasks(&TaskQuery {
+                completed: Some(false),
+                limit: Some(i32::MAX),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut suggestions: Vec<TaskSuggestion> = candidates
+            .into_iter()
+            .map(|task| {
+                let (score, reasons) =
+                    score_task(&task.todo, today, context, available_minutes, energy);
+                TaskSuggestion {
+                    task,
+                    score,
+                    reasons,
+                }
+            })
+            .collect();
+
+        suggestions.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        suggestions.truncate(limit.max(0) as usize);
+
+        debug!("Suggesting {} tasks", suggestions.len());
+        Ok(suggestions)
+    }
+}
+
+/// Score a single task. Returns the score and the reasons behind it.
+fn score_task(
+    todo: &TodoDto,
+    today: NaiveDate,
+    context: Option<&str>,
+    available_minutes: Option<i32>,
+    energy: Option<&str>,
+) -> (f64, Vec<String>) {
+    let mut score = 0.0;
+    let mut reasons = Vec::new();
+
+    // Context match
+    if let Some(wanted) = context {
+        match todo.context.as_deref() {
+            Some(ctx) if ctx.eq_ignore_ascii_case(wanted) => {
+                score += 3.0;
+                reasons.push(format!("matches @{}", ctx));
+            }
+            Some(_) => score -= 2.0,
+            None => {}
+        }
+    }
+
+    // Due date proximity
+    if let Some(due) = todo.due_date.as_deref().and_then(|d| d.parse::<NaiveDate>().ok()) {
+        let days = (due - today).num_days();
+        let (points, reason) = match days {
+            d if d < 0 => (4.0, "overdue"),
+            0 => (3.0, "due today"),
+            1..=3 => (2.0, "due soon"),
+            4..=7 => (1.0, "due this week"),
+            _ => (0.0, ""),
+        };
+        score += points;
+        if !reason.is_empty() {
+            reasons.push(reason.to_string());
+        }
+    }
+
+    // Priority
+    match todo.priority.as_deref() {
+        Some("high") => {
+            score += 3.0;
+            reasons.push("high priority".to_string());
+        }
+        Some("medium") => score += 2.0,
+        Some("low") => score += 1.0,
+        _ => score += 0.5,
+    }
+
+    // Effort versus available time
+    if let (Some(available), Some(estimate)) = (available_minutes, todo.estimate_minutes) {
+        if estimate <= available {
+            score += 2.0;
+            reasons.push(format!("fits in {} min", available));
+        } else {
+            score -= 3.0;
+        }
+    }
+
+    // Energy level
+    match (energy, todo.estimate_minutes) {
+        (Some("low"), Some(est)) if est <= SHORT_TASK_MINUTES => {
+            score += 1.0;
+            reasons.push("quick win".to_string());
+        }
+        (Some("low"), Some(est)) if est > LONG_TASK_MINUTES => score -= 1.0,
+        (Some("high"), Some(est)) if est > LONG_TASK_MINUTES => score += 1.0,
+        (Some("high"), _) if todo.priority.as_deref() == Some("high") => score += 1.0,
+        _ => {}
+    }
+
+    (score, reasons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(
+        context: Option<&str>,
+        priority: Option<&str>,
+        due_date: Option<&str>,
+        estimate_minutes: Option<i32>,
+    ) -> TodoDto {
+        TodoDto {
+            id: 1,
+            note_id: 1,
+            line_number: Some(1),
+            description: "Task".to_string(),
+            completed: false,
+            heading_path: None,
+            context: context.map(String::from),
+            priority: priority.map(String::from),
+            due_date: due_date.map(String::from),
+            estimate_minutes,
+            created_at: None,
+            completed_at: None,
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+    }
+
+    #[test]
+    fn test_score_context_match() {
+        let (matching, reasons) = score_task(&todo(Some("work"), None, None, None), today(), Some("work"), None, None);
+        let (other, _) = score_task(&todo(Some("home"), None, None, None), today(), Some("work"), None, None);
+        let (none, _) = score_task(&todo(None, None, None, None), today(), Some("work"), None, None);
+
+        assert!(matching > none);
+        assert!(none > other);
+        assert_eq!(reasons, vec!["matches @work".to_string()]);
+    }
+
+    #[test]
+    fn test_score_due_date_proximity() {
+        let score = |due| score_task(&todo(None, None, Some(due), None), today(), None, None, None).0;
+
+        assert!(score("2024-03-01") > score("2024-03-04"));
+        assert!(score("2024-03-04") > score("2024-03-06"));
+        assert!(score("2024-03-06") > score("2024-03-10"));
+        assert!(score("2024-03-10") > score("2024-04-01"));
+    }
+
+    #[test]
+    fn test_score_effort_fits_window() {
+        let (fits, _) = score_task(&todo(None, None, None, Some(20)), today(), None, Some(30), None);
+        let (too_long, _) = score_task(&todo(None, None, None, Some(90)), today(), None, Some(30), None);
+        let (unknown, _) = score_task(&todo(None, None, None, None), today(), None, Some(30), None);
+
+        assert!(fits > unknown);
+        assert!(unknown > too_long);
+    }
+
+    #[test]
+    fn test_score_energy() {
+        let quick = todo(None, None, None, Some(15));
+        let long = todo(None, None, None, Some(120));
+
+        let low = |t: &TodoDto| score_task(t, today(), None, None, Some("low")).0;
+        let high = |t: &TodoDto| score_task(t, today(), None, None, Some("high")).0;
+
+        assert!(low(&quick) > low(&long));
+        assert!(high(&long) > high(&quick));
+    }
+}
@@ -1,8 +1,14 @@
 //! Tag management operations.
+//!
+//! Tag metadata (`tag_meta`) can declare a tag an alias of another (`#todo`
+//! for `#task`): notes with the alias are then counted under the tag and
+//! found by filters on it. Aliases point at a tag that isn't an alias
+//! itself, so resolving one takes a single lookup.
 
-use crate::Result;
-use shared_types::TagDto;
+use crate::{Result, StorageError};
+use shared_types::{TagDto, TagMeta};
 
+use super::data_versions::bump_data_version;
 use super::VaultRepository;
 
 impl VaultRepository {
@@ -22,19 +28,155 @@ impl VaultRepository {
                 .execute(&self.pool)
                 .await?;
         }
+        bump_data_version(&self.pool, "tags").await?;
 
         Ok(())
     }
 
-    /// Get all tags with their usage counts.
+    /// Get all tags with their usage counts, aliases counted under their
+    /// tag, and the tags' descriptions, colors and aliases.
     pub async fn list_tags(&self) -> Result<Vec<TagDto>> {
         let rows = sqlx::query_as::<_, (String, i64)>(
-            "SELECT tag, COUNT(*) as count FROM tags GROUP BY tag ORDER BY count DESC, tag",
+            r#"
+            SELECT COALESCE(m.alias_of, t.tag) AS canonical, COUNT(DISTINCT t.note_id) AS count
+            FROM tags t
+            LEFT JOIN tag_meta m ON m.tag = t.tag
+            GROUP BY canonical
+            ORDER BY count DESC, canonical
+            "#,
         )
         .fetch_all(&self.pool)
         .await?;
+        let meta = self.list_tag_meta().await?;
 
-        Ok(rows.into_iter().map(|(tag, count)| TagDto { tag, count }).collect())
+        Ok(rows
+            .into_iter()
+            .map(|(tag, count)| {
+                let own = meta.iter().find(|m| m.tag == tag);
+                TagDto {
+                    description: own.and_then(|m| m.description.clone()),
+                    color: own.and_then(|m| m.color.clone()),
+                    aliases: meta
+                        .iter()
+                        .filter(|m| m.alias_of.as_ref() == Some(&tag))
+                        .map(|m| m.tag.clone())
+                        .collect(),
+                    tag,
+                    count,
+                }
+            })
+            .collect())
+    }
+
+    /// Get the metadata of all tags that have any, by tag.
+    pub async fn list_tag_meta(&self) -> Result<Vec<TagMeta>> {
+        let rows = sqlx::query_as::<_, (String, Option<String>, Option<String>, Option<String>)>(
+            "SELECT tag, alias_of, description, color FROM tag_meta ORDER BY tag",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(tag, alias_of, description, color)| TagMeta {
+                tag,
+                alias_of,
+                description,
+                color,
+            })
+            .collect())
+    }
+
+    /// Set a tag's metadata, replacing what it had. A leading `#` is
+    /// dropped from tags. Fails if the tag is empty, the alias would point
+    /// at the tag itself or at another alias, or the tag has aliases of its
+    /// own.
+    pub async fn set_tag_meta(&self, meta: &TagMeta) -> Result<()> {
+        let tag = meta.tag.trim().trim_start_matches('#');
+        if tag.is_empty() {
+            return Err(StorageError::InvalidTag(format!("{:?} is empty", meta.tag)));
+        }
+        let alias_of = meta
+            .alias_of
+            .as_deref()
+            .map(|a| a.trim().trim_start_matches('#'))
+            .filter(|a| !a.is_empty());
+
+        // The checks and the write see the same aliases
+        let mut tx = self.pool.begin().await?;
+        if let Some(alias_of) = alias_of {
+            if alias_of == tag {
+                return Err(StorageError::InvalidTagAlias(format!("#{} can't be an alias of itself", tag)));
+            }
+            let target: Option<Option<String>> =
+                sqlx::query_scalar("SELECT alias_of FROM tag_meta WHERE tag = ?")
+                    .bind(alias_of)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            if let Some(Some(canonical)) = target {
+                return Err(StorageError::InvalidTagAlias(format!(
+                    "#{} is an alias of #{}",
+                    alias_of, canonical
+                )));
+            }
+            let aliases: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tag_meta WHERE alias_of = ?")
+                .bind(tag)
+                .fetch_one(&mut *tx)
+                .await?;
+            if aliases > 0 {
+                return Err(StorageError::InvalidTagAlias(format!("#{} has aliases of its own", tag)));
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO tag_meta (tag, alias_of, description, color) VALUES (?, ?, ?, ?)
+            ON CONFLICT(tag) DO UPDATE SET
+                alias_of = excluded.alias_of,
+                description = excluded.description,
+                color = excluded.color
+            "#,
+        )
+        .bind(tag)
+        .bind(alias_of)
+        .bind(&meta.description)
+        .bind(&meta.color)
+        .execute(&mut *tx)
+        .await?;
+        bump_data_version(&mut *tx, "tag_meta").await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Resolve a tag (with or without its `#`) to the tag it is an alias of,
+    /// or itself if it isn't an alias.
+    pub async fn canonical_tag(&self, tag: &str) -> Result<String> {
+        let tag = tag.trim().trim_start_matches('#');
+        let alias_of: Option<Option<String>> = sqlx::query_scalar("SELECT alias_of FROM tag_meta WHERE tag = ?")
+            .bind(tag)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(alias_of.flatten().unwrap_or_else(|| tag.to_string()))
+    }
+
+    /// Remove a tag's metadata; for an alias, that ends the alias. Aliases
+    /// of the tag are kept.
+    pub async fn delete_tag_meta(&self, tag: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM tag_meta WHERE tag = ?")
+            .bind(tag.trim().trim_start_matches('#'))
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() > 0 {
+            bump_data_version(&self.pool, "tag_meta").await?;
+        }
+        Ok(())
+    }
+
+    /// Get all tags in the vault as (note_id, tag).
+    pub async fn list_all_note_tags(&self) -> Result<Vec<(i64, String)>> {
+        Ok(sqlx::query_as::<_, (i64, String)>("SELECT note_id, tag FROM tags ORDER BY note_id")
+            .fetch_all(&self.pool)
+            .await?)
     }
 
     /// Get tags for a specific note.
@@ -1,16 +1,39 @@
 //! Todo/task operations.
 
 use crate::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
 use core_index::ParsedTodo;
 use shared_types::{TaskQuery, TaskWithContext, TodoDto};
+use std::collections::HashMap;
 
 use super::VaultRepository;
 
 impl VaultRepository {
     /// Replace all todos for a note.
+    ///
+    /// Completed todos keep the completion time of a completed todo with the
+    /// same description in the old set. A todo that was open before is
+    /// stamped now; one that is new and already completed (e.g. a note
+    /// indexed for the first time) has no known completion time.
     pub async fn replace_todos(&self, note_id: i64, todos: &[ParsedTodo]) -> Result<()> {
         let now = Utc::now().to_rfc3339();
+        let completed_now = Local::now().to_rfc3339();
+
+        let mut completed_times: HashMap<String, Option<String>> = HashMap::new();
+        let previous = sqlx::query_as::<_, (String, bool, Option<String>)>(
+            "SELECT description, completed, completed_at FROM todos WHERE note_id = ?",
+        )
+        .bind(note_id)
+        .fetch_all(&self.pool)
+        .await?;
+        for (description, completed, completed_at) in previous {
+            let time = if completed { completed_at } else { Some(completed_now.clone()) };
+            // A completed copy keeps its time over an open one
+            let entry = completed_times.entry(description).or_insert(None);
+            if entry.is_none() || completed {
+                *entry = time;
+            }
+        }
 
         // Delete existing todos
         sqlx::query("DELETE FROM todos WHERE note_id = ?")
@@ -22,8 +45,8 @@ impl VaultRepository {
         for todo in todos {
             sqlx::query(
                 r#"
-                INSERT INTO todos (note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                INSERT INTO todos (note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
             )
             .bind(note_id)
@@ -34,7 +57,9 @@ impl VaultRepository {
             .bind(&todo.context)
             .bind(&todo.priority)
             .bind(&todo.due_date)
+            .bind(todo.estimate_minutes)
             .bind(&now)
+            .bind(todo.completed.then(|| completed_times.get(&todo.description).cloned().flatten()).flatten())
             .execute(&self.pool)
             .await?;
         }
@@ -44,8 +69,8 @@ impl VaultRepository {
 
     /// Get todos for a note.
     pub async fn get_todos_for_note(&self, note_id: i64) -> Result<Vec<TodoDto>> {
-        let rows = sqlx::query_as::<_, (i64, i64, Option<i32>, String, i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at FROM todos WHERE note_id = ?",
+        let rows = sqlx::query_as::<_, (i64, i64, Option<i32>, String, i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<i32>, Option<String>, Option<String>)>(
+            "SELECT id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at FROM todos WHERE note_id = ?",
         )
         .bind(note_id)
         .fetch_all(&self.pool)
@@ -53,7 +78,7 @@ impl VaultRepository {
 
         Ok(rows
             .into_iter()
-            .map(|(id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at)| {
+            .map(|(id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at)| {
                 TodoDto {
                     id,
                     note_id,
@@ -64,6 +89,7 @@ impl VaultRepository {
                     context,
                     priority,
                     due_date,
+                    estimate_minutes,
                     created_at: created_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                     completed_at: completed_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                 }
@@ -73,15 +99,15 @@ impl VaultRepository {
 
     /// Get all incomplete todos.
     pub async fn get_incomplete_todos(&self) -> Result<Vec<TodoDto>> {
-        let rows = sqlx::query_as::<_, (i64, i64, Option<i32>, String, i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at FROM todos WHERE completed = 0",
+        let rows = sqlx::query_as::<_, (i64, i64, Option<i32>, String, i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<i32>, Option<String>, Option<String>)>(
+            "SELECT id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at FROM todos WHERE completed = 0",
         )
         .fetch_all(&self.pool)
         .await?;
 
         Ok(rows
             .into_iter()
-            .map(|(id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at)| {
+            .map(|(id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at)| {
                 TodoDto {
                     id,
                     note_id,
@@ -92,6 +118,7 @@ impl VaultRepository {
                     context,
                     priority,
                     due_date,
+                    estimate_minutes,
                     created_at: created_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                     completed_at: completed_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                 }
@@ -99,10 +126,11 @@ impl VaultRepository {
             .collect())
     }
 
-    /// Update a todo's completion status.
+    /// Update a todo's completion status. The completion time is stored in
+    /// local time, so its date is the day the user completed it.
     pub async fn update_todo_completion(&self, todo_id: i64, completed: bool) -> Result<()> {
         let completed_at = if completed {
-            Some(Utc::now().to_rfc3339())
+            Some(Local::now().to_rfc3339())
         } else {
             None
         };
@@ -119,14 +147,14 @@ impl VaultRepository {
 
     /// Get a todo by ID.
     pub async fn get_todo(&self, todo_id: i64) -> Result<Option<TodoDto>> {
-        let row = sqlx::query_as::<_, (i64, i64, Option<i32>, String, i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at FROM todos WHERE id = ?",
+        let row = sqlx::query_as::<_, (i64, i64, Option<i32>, String, i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<i32>, Option<String>, Option<String>)>(
+            "SELECT id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at FROM todos WHERE id = ?",
         )
         .bind(todo_id)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|(id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at)| {
+        Ok(row.map(|(id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at)| {
             TodoDto {
                 id,
                 note_id,
@@ -137,6 +165,7 @@ impl VaultRepository {
                 context,
                 priority,
                 due_date,
+                estimate_minutes,
                 created_at: created_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                 completed_at: completed_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
             }
@@ -145,6 +174,16 @@ impl VaultRepository {
 
     /// Query tasks with filters, returning enriched context from parent notes.
     pub async fn query_tasks(&self, query: &TaskQuery) -> Result<Vec<TaskWithContext>> {
+        self.query_tasks_where(query, &[]).await
+    }
+
+    /// Query tasks with filters and further tests on the task alias `{t}`,
+    /// each with its parameter.
+    pub(crate) async fn query_tasks_where(
+        &self,
+        query: &TaskQuery,
+        tests: &[(String, String)],
+    ) -> Result<Vec<TaskWithContext>> {
         // Build dynamic WHERE clause
         let mut conditions = Vec::new();
         let mut params: Vec<String> = Vec::new();
@@ -185,6 +224,10 @@ impl VaultRepository {
             }
         }
 
+        for (test, _) in tests {
+            conditions.push(test.replace("{t}", "t"));
+        }
+
         let where_clause = if conditions.is_empty() {
             "1=1".to_string()
         } else {
@@ -197,7 +240,7 @@ impl VaultRepository {
             r#"
             SELECT
                 t.id, t.note_id, t.line_number, t.description, t.completed, t.heading_path,
-                t.context, t.priority, t.due_date, t.created_at, t.completed_at,
+                t.context, t.priority, t.due_date, t.estimate_minutes, t.created_at, t.completed_at,
                 n.path, n.title
             FROM todos t
             JOIN notes n ON t.note_id = n.id
@@ -215,7 +258,7 @@ impl VaultRepository {
         // Build query dynamically
         let mut sqlx_query = sqlx::query_as::<_, (
             i64, i64, Option<i32>, String, i32, Option<String>,
-            Option<String>, Option<String>, Option<String>, Option<String>, Option<String>,
+            Option<String>, Option<String>, Option<String>, Option<i32>, Option<String>, Option<String>,
             String, Option<String>
         )>(&sql);
 
@@ -229,12 +272,15 @@ impl VaultRepository {
         if let Some(ref v) = prop_value {
             sqlx_query = sqlx_query.bind(v);
         }
+        for (_, param) in tests {
+            sqlx_query = sqlx_query.bind(param);
+        }
         sqlx_query = sqlx_query.bind(limit);
 
         let rows = sqlx_query.fetch_all(&self.pool).await?;
 
         let mut results = Vec::new();
-        for (id, note_id, line_number, description, completed, heading_path, context, priority, due_date, created_at, completed_at, note_path, note_title) in rows {
+        for (id, note_id, line_number, description, completed, heading_path, context, priority, due_date, estimate_minutes, created_at, completed_at, note_path, note_title) in rows {
             // Get properties for this note
             let note_properties = self.get_properties_for_note(note_id).await?;
 
@@ -249,12 +295,14 @@ impl VaultRepository {
                     context,
                     priority,
                     due_date,
+                    estimate_minutes,
                     created_at: created_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                     completed_at: completed_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|d| d.with_timezone(&Utc))),
                 },
                 note_path,
                 note_title,
                 note_properties,
+                embedded_in: None,
             });
         }
 
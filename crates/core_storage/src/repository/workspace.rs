@@ -0,0 +1,103 @@
+//! Workspace persistence: open tabs and sidebar layout.
+
+use crate::Result;
+use shared_types::{WorkspaceSidebar, WorkspaceState, WorkspaceTab};
+use tracing::{debug, instrument};
+
+use super::VaultRepository;
+
+/// vault_settings key holding the sidebar layout as JSON.
+const SIDEBAR_SETTING_KEY: &str = "workspace_sidebar";
+
+impl VaultRepository {
+    /// Replace the saved workspace.
+    ///
+    /// Tabs are stored by note ID so they follow renames. Tabs for paths
+    /// that aren't indexed are skipped.
+    #[instrument(skip(self, workspace))]
+    pub async fn save_workspace(&self, workspace: &WorkspaceState) -> Result<()> {
+        // All or nothing, so a failed save keeps the previous workspace
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM workspace_tabs")
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, tab) in workspace.tabs.iter().enumerate() {
+            let active = workspace.active_path.as_deref() == Some(tab.path.as_str());
+            sqlx::query(
+                r#"
+                INSERT INTO workspace_tabs (position, note_id, scroll_top, cursor_position, active)
+                SELECT ?, id, ?, ?, ? FROM notes WHERE path = ?
+                "#,
+            )
+            .bind(position as i64)
+            .bind(tab.scroll_top)
+            .bind(tab.cursor_position)
+            .bind(active)
+            .bind(&tab.path)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let sidebar = serde_json::to_string(&workspace.sidebar).unwrap_or_default();
+        sqlx::query(
+            r#"
+            INSERT INTO vault_settings (key, value) VALUES (?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value
+            "#,
+        )
+        .bind(SIDEBAR_SETTING_KEY)
+        .bind(&sidebar)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        debug!("Saved workspace with {} tabs", workspace.tabs.len());
+        Ok(())
+    }
+
+    /// Load the saved workspace, with tabs at their notes' current paths.
+    ///
+    /// Returns an empty workspace if none was saved.
+    pub async fn load_workspace(&self) -> Result<WorkspaceState> {
+        let rows = sqlx::query_as::<_, (String, Option<f64>, Option<i64>, bool)>(
+            r#"
+            SELECT n.path, w.scroll_top, w.cursor_position, w.active
+            FROM workspace_tabs w
+            JOIN notes n ON n.id = w.note_id
+            ORDER BY w.position
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let active_path = rows
+            .iter()
+            .find(|(_, _, _, active)| *active)
+            .map(|(path, _, _, _)| path.clone());
+        let tabs = rows
+            .into_iter()
+            .map(|(path, scroll_top, cursor_position, _)| WorkspaceTab {
+                path,
+                scroll_top: scroll_top.unwrap_or(0.0),
+                cursor_position,
+            })
+            .collect();
+
+        let sidebar = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT value FROM vault_settings WHERE key = ?",
+        )
+        .bind(SIDEBAR_SETTING_KEY)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten()
+        .and_then(|json| serde_json::from_str::<WorkspaceSidebar>(&json).ok())
+        .unwrap_or_default();
+
+        Ok(WorkspaceState {
+            tabs,
+            active_path,
+            sidebar,
+        })
+    }
+}
@@ -1,5 +1,6 @@
 //! Database schema initialization.
 
+use crate::repository::{split_list, store_typed_values};
 use sqlx::SqlitePool;
 use tracing::{info, debug};
 
@@ -145,12 +146,30 @@ pub async fn init_database(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Migration: Add created_date column for local date storage
     migrate_created_date(pool).await?;
 
+    // Migration: Add encrypted flag to notes table
+    migrate_notes_encrypted(pool).await?;
+
+    // Migration: Add word_count column to notes table
+    migrate_notes_word_count(pool).await?;
+
+    // Migration: Add fts_pending flag for deferred full-text indexing
+    migrate_notes_fts_pending(pool).await?;
+
+    // Migration: Add truncated flag for notes over the size limit
+    migrate_notes_truncated(pool).await?;
+
     // Migration: Add rrule column for recurring schedule blocks
     migrate_schedule_blocks_rrule(pool).await?;
 
+    // Migration: Add timezone column to schedule_blocks table
+    migrate_schedule_blocks_timezone(pool).await?;
+
     // Migration: Add GTD columns to todos table
     migrate_todos_gtd(pool).await?;
 
+    // Migration: Add effort estimate column to todos table
+    migrate_todos_estimate(pool).await?;
+
     // Migration: Create folder_properties table
     migrate_folder_properties(pool).await?;
 
@@ -163,9 +182,90 @@ pub async fn init_database(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Migration: Create habit tracking tables
     migrate_habit_tables(pool).await?;
 
+    // Migration: Add schedule rules column to habits table
+    migrate_habits_schedule(pool).await?;
+
     // Migration: Create embedding storage table
     migrate_embeddings(pool).await?;
 
+    // Migration: Create schedule template tables
+    migrate_schedule_templates(pool).await?;
+
+    // Migration: Create mood tracking table
+    migrate_mood_entries(pool).await?;
+
+    // Migration: Create goal tables
+    migrate_goals(pool).await?;
+
+    // Migration: Create calendar subscription tables
+    migrate_calendar_subscriptions(pool).await?;
+
+    // Migration: Create block_sessions table for focus mode
+    migrate_block_sessions(pool).await?;
+
+    // Migration: Create note_reviews table for the review queue
+    migrate_note_reviews(pool).await?;
+
+    // Migration: Create workspace_tabs table for session restore
+    migrate_workspace_tabs(pool).await?;
+
+    // Migration: Create note_headings table for the quick switcher
+    migrate_note_headings(pool).await?;
+
+    // Migration: Create search_presets table
+    migrate_search_presets(pool).await?;
+
+    // Migration: Create folders table for folder appearance and sort order
+    migrate_folders(pool).await?;
+
+    // Migration: Add composite indexes for property and tag queries
+    migrate_query_indexes(pool).await?;
+
+    // Migration: Create jobs table for the background job queue
+    migrate_jobs(pool).await?;
+
+    // Migration: Add typed property value columns for number/date queries and sorting
+    migrate_typed_property_values(pool).await?;
+
+    // Migration: Create property_items table for the items of list properties
+    migrate_property_items(pool).await?;
+
+    // Migration: Create asset_hashes table for attachment deduplication
+    migrate_asset_hashes(pool).await?;
+
+    // Migration: Create tag_meta table for tag aliases, descriptions and colors
+    migrate_tag_meta(pool).await?;
+
+    // Migration: Create contexts table for the GTD context registry
+    migrate_contexts(pool).await?;
+
+    // Migration: Create drafts table for crash recovery of unsaved edits
+    migrate_drafts(pool).await?;
+
+    // Migration: Create file_problems table for files that can't be read cleanly
+    migrate_file_problems(pool).await?;
+
+    // Migration: Create board_ranks table for manual Kanban card order
+    migrate_board_ranks(pool).await?;
+
+    // Migration: Create data_versions table and the triggers counting changes
+    migrate_data_versions(pool).await?;
+
+    // Migration: Create scheduled_note_rules table for notes created on a schedule
+    migrate_scheduled_note_rules(pool).await?;
+
+    // Migration: Create vault_metrics table for vault statistics over time
+    migrate_vault_metrics(pool).await?;
+
+    // Migration: Create image_text table for text recognized in images
+    migrate_image_text(pool).await?;
+
+    // Migration: Create link_targets table for wiki link targets as written
+    migrate_link_targets(pool).await?;
+
+    // Migration: Add embed column to link_targets for embedded notes
+    migrate_link_target_embeds(pool).await?;
+
     info!("Database schema initialized");
     Ok(())
 }
@@ -401,6 +501,25 @@ async fn migrate_schedule_blocks_rrule(pool: &SqlitePool) -> Result<(), sqlx::Er
     Ok(())
 }
 
+/// Migrate schedule_blocks table to add timezone column for time zone-aware blocks.
+async fn migrate_schedule_blocks_timezone(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('schedule_blocks')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_timezone = columns.iter().any(|(_, name, _, _, _, _)| name == "timezone");
+    if !has_timezone {
+        info!("Migrating schedule_blocks table: adding timezone column");
+        sqlx::query("ALTER TABLE schedule_blocks ADD COLUMN timezone TEXT")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Migrate todos table to add GTD columns (context, priority, due_date).
 async fn migrate_todos_gtd(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     info!("Checking todos table for GTD columns...");
@@ -463,6 +582,109 @@ async fn migrate_todos_gtd(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+/// Migrate notes table to add the encrypted flag for per-note encryption.
+async fn migrate_notes_encrypted(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('notes')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_encrypted = columns.iter().any(|(_, name, _, _, _, _)| name == "encrypted");
+    if !has_encrypted {
+        info!("Migrating notes table: adding encrypted column");
+        sqlx::query("ALTER TABLE notes ADD COLUMN encrypted INTEGER DEFAULT 0")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Migrate notes table to add the word_count column for note summaries.
+///
+/// Word counts come from parsing, so stored hashes are cleared to make the
+/// next vault scan re-index every note.
+async fn migrate_notes_word_count(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('notes')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_word_count = columns.iter().any(|(_, name, _, _, _, _)| name == "word_count");
+    if !has_word_count {
+        info!("Migrating notes table: adding word_count column");
+        sqlx::query("ALTER TABLE notes ADD COLUMN word_count INTEGER DEFAULT 0")
+            .execute(pool)
+            .await?;
+        sqlx::query("UPDATE notes SET hash = NULL")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Migrate notes table to add the fts_pending flag, set while a saved
+/// note's full-text entry hasn't caught up with it.
+async fn migrate_notes_fts_pending(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('notes')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_fts_pending = columns.iter().any(|(_, name, _, _, _, _)| name == "fts_pending");
+    if !has_fts_pending {
+        info!("Migrating notes table: adding fts_pending column");
+        sqlx::query("ALTER TABLE notes ADD COLUMN fts_pending INTEGER DEFAULT 0")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Migrate notes table to add the truncated flag, set for notes over the
+/// vault's size limit that are only partly indexed for search.
+async fn migrate_notes_truncated(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('notes')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_truncated = columns.iter().any(|(_, name, _, _, _, _)| name == "truncated");
+    if !has_truncated {
+        info!("Migrating notes table: adding truncated column");
+        sqlx::query("ALTER TABLE notes ADD COLUMN truncated INTEGER DEFAULT 0")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Migrate todos table to add the estimate_minutes column for effort estimates.
+async fn migrate_todos_estimate(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('todos')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_estimate = columns.iter().any(|(_, name, _, _, _, _)| name == "estimate_minutes");
+    if !has_estimate {
+        info!("Migrating todos table: adding estimate_minutes column");
+        sqlx::query("ALTER TABLE todos ADD COLUMN estimate_minutes INTEGER")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Create folder_properties table for inherited folder-level metadata.
 async fn migrate_folder_properties(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Create the table if it doesn't exist
@@ -607,6 +829,25 @@ async fn migrate_habit_tables(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+/// Migrate habits table to add the schedule_rules column (active days, skips, freezes).
+async fn migrate_habits_schedule(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('habits')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_schedule = columns.iter().any(|(_, name, _, _, _, _)| name == "schedule_rules");
+    if !has_schedule {
+        info!("Migrating habits table: adding schedule_rules column");
+        sqlx::query("ALTER TABLE habits ADD COLUMN schedule_rules TEXT")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Create embedding storage table for semantic search.
 async fn migrate_embeddings(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Create the embeddings table with BLOB storage for vectors
@@ -643,7 +884,790 @@ async fn migrate_embeddings(pool: &SqlitePool) -> Result<(), sqlx::Error> {
             .await?;
     }
 
+    // Add signature column (MinHash of the embedded text) to skip re-embedding small edits
+    let has_signature = columns.iter().any(|(_, name, _, _, _, _)| name == "signature");
+    if !has_signature {
+        info!("Migrating note_embeddings table: adding signature column");
+        sqlx::query("ALTER TABLE note_embeddings ADD COLUMN signature BLOB")
+            .execute(pool)
+            .await?;
+    }
+
+    // Per-section embeddings of chunked notes; the note_embeddings row is
+    // written last and marks the note as embedded
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS note_embedding_chunks (
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            chunk_index INTEGER NOT NULL,
+            heading_path TEXT NOT NULL,
+            start_line INTEGER NOT NULL,
+            end_line INTEGER NOT NULL,
+            snippet TEXT NOT NULL,
+            embedding BLOB NOT NULL,
+            PRIMARY KEY (note_id, chunk_index)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
     debug!("note_embeddings table created/verified");
 
     Ok(())
 }
+
+/// Create schedule template tables for reusable routines.
+async fn migrate_schedule_templates(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schedule_templates (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            created_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schedule_template_slots (
+            id INTEGER PRIMARY KEY,
+            template_id INTEGER NOT NULL REFERENCES schedule_templates(id) ON DELETE CASCADE,
+            start_time TEXT NOT NULL,
+            end_time TEXT NOT NULL,
+            label TEXT,
+            color TEXT,
+            context TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_schedule_template_slots_template ON schedule_template_slots(template_id)")
+        .execute(pool)
+        .await?;
+
+    debug!("schedule template tables created/verified");
+
+    Ok(())
+}
+
+/// Create mood_entries table for daily mood tracking.
+async fn migrate_mood_entries(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS mood_entries (
+            id INTEGER PRIMARY KEY,
+            date TEXT NOT NULL UNIQUE,
+            score INTEGER NOT NULL,
+            note TEXT,
+            created_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("mood_entries table created/verified");
+
+    Ok(())
+}
+
+/// Create goals and goal_habits tables for goal tracking.
+async fn migrate_goals(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS goals (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            target_type TEXT NOT NULL DEFAULT 'numeric',
+            target_value REAL,
+            unit TEXT,
+            start_date TEXT NOT NULL,
+            deadline TEXT,
+            task_query TEXT,
+            archived INTEGER DEFAULT 0,
+            created_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS goal_habits (
+            goal_id INTEGER NOT NULL REFERENCES goals(id) ON DELETE CASCADE,
+            habit_id INTEGER NOT NULL REFERENCES habits(id) ON DELETE CASCADE,
+            PRIMARY KEY (goal_id, habit_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("goal tables created/verified");
+
+    Ok(())
+}
+
+/// Create tables for external calendar subscriptions and their cached events.
+async fn migrate_calendar_subscriptions(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS calendar_subscriptions (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            url TEXT NOT NULL,
+            color TEXT,
+            refresh_minutes INTEGER NOT NULL DEFAULT 60,
+            last_fetched TEXT,
+            last_error TEXT,
+            created_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS calendar_events (
+            id INTEGER PRIMARY KEY,
+            subscription_id INTEGER NOT NULL REFERENCES calendar_subscriptions(id) ON DELETE CASCADE,
+            uid TEXT NOT NULL,
+            date TEXT NOT NULL,
+            start_time TEXT NOT NULL,
+            end_time TEXT NOT NULL,
+            all_day INTEGER DEFAULT 0,
+            summary TEXT,
+            location TEXT,
+            rrule TEXT,
+            timezone TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_calendar_events_date ON calendar_events(date)")
+        .execute(pool)
+        .await?;
+
+    debug!("calendar subscription tables created/verified");
+
+    Ok(())
+}
+
+/// Create block_sessions table for recording actual time spent on schedule blocks.
+async fn migrate_block_sessions(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS block_sessions (
+            id INTEGER PRIMARY KEY,
+            block_id INTEGER NOT NULL REFERENCES schedule_blocks(id) ON DELETE CASCADE,
+            date TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_block_sessions_date ON block_sessions(date)")
+        .execute(pool)
+        .await?;
+
+    debug!("block_sessions table created/verified");
+
+    Ok(())
+}
+
+/// Create note_reviews table for resurfacing notes.
+async fn migrate_note_reviews(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS note_reviews (
+            note_id INTEGER PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+            last_reviewed TEXT NOT NULL,
+            review_interval INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("note_reviews table created/verified");
+
+    Ok(())
+}
+
+/// Create workspace_tabs table for restoring open tabs.
+///
+/// Tabs reference notes by ID, so renamed notes keep their tab and deleted
+/// notes drop theirs.
+async fn migrate_workspace_tabs(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspace_tabs (
+            position INTEGER PRIMARY KEY,
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            scroll_top REAL,
+            cursor_position INTEGER,
+            active INTEGER NOT NULL DEFAULT 0
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("workspace_tabs table created/verified");
+
+    Ok(())
+}
+
+/// Create note_headings table so headings can be searched without reading files.
+///
+/// Stored hashes are cleared when the table is first created to make the next
+/// vault scan re-index every note.
+async fn migrate_note_headings(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'note_headings'",
+    )
+    .fetch_one(pool)
+    .await?;
+    if exists > 0 {
+        return Ok(());
+    }
+
+    info!("Creating note_headings table");
+    sqlx::query(
+        r#"
+        CREATE TABLE note_headings (
+            id INTEGER PRIMARY KEY,
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            level INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            line_number INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_note_headings_note_id ON note_headings(note_id)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("UPDATE notes SET hash = NULL")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Create search_presets table for named searches with filters.
+async fn migrate_search_presets(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS search_presets (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            query TEXT NOT NULL,
+            filters TEXT,
+            created_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("search_presets table created/verified");
+
+    Ok(())
+}
+
+/// Create folders table for per-folder appearance and manual child order.
+async fn migrate_folders(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS folders (
+            path TEXT PRIMARY KEY,
+            icon TEXT,
+            color TEXT,
+            collapsed INTEGER NOT NULL DEFAULT 0,
+            child_order TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("folders table created/verified");
+
+    Ok(())
+}
+
+/// Add composite indexes used by `run_query`.
+///
+/// Property filters look up rows by key and value and only need the note
+/// ID, so `(key, value, note_id)` answers them from the index alone; the
+/// same goes for tag filters with `(tag, note_id)`.
+async fn migrate_query_indexes(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_key_value_note ON properties(key, value, note_id)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_tags_tag_note ON tags(tag, note_id)")
+        .execute(pool)
+        .await?;
+
+    debug!("query indexes created/verified");
+
+    Ok(())
+}
+
+/// Create jobs table for the background job queue.
+///
+/// `request` holds the serialized `JobRequest`, so a job can run again
+/// after a restart or when retried.
+async fn migrate_jobs(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS jobs (
+            id INTEGER PRIMARY KEY,
+            kind TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            request TEXT NOT NULL,
+            processed INTEGER NOT NULL DEFAULT 0,
+            total INTEGER NOT NULL DEFAULT 0,
+            message TEXT,
+            created_at TEXT NOT NULL,
+            started_at TEXT,
+            finished_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status)")
+        .execute(pool)
+        .await?;
+
+    debug!("jobs table created/verified");
+
+    Ok(())
+}
+
+/// Add typed value columns to properties, so numbers and dates compare and
+/// sort by value rather than as text, and fill them for existing rows.
+async fn migrate_typed_property_values(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let columns: Vec<(i64, String, String, i64, Option<String>, i64)> = sqlx::query_as(
+        "SELECT cid, name, type, `notnull`, dflt_value, pk FROM pragma_table_info('properties')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let has_typed_values = columns.iter().any(|(_, name, _, _, _, _)| name == "value_number");
+    if !has_typed_values {
+        info!("Migrating properties table: adding typed value columns");
+        for column in ["value_text TEXT", "value_number REAL", "value_date TEXT", "value_bool INTEGER"] {
+            sqlx::query(&format!("ALTER TABLE properties ADD COLUMN {}", column))
+                .execute(pool)
+                .await?;
+        }
+
+        let rows: Vec<(i64, Option<String>, Option<String>)> =
+            sqlx::query_as("SELECT id, value, type FROM properties")
+                .fetch_all(pool)
+                .await?;
+        store_typed_values(pool, &rows).await?;
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_key_number ON properties(key, value_number)")
+        .execute(pool)
+        .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_key_date ON properties(key, value_date)")
+        .execute(pool)
+        .await?;
+
+    debug!("typed property value columns created/verified");
+
+    Ok(())
+}
+
+/// Create property_items table holding the items of list properties, one
+/// row per item, so list operators don't depend on splitting the joined
+/// value. Existing list properties are split on commas.
+async fn migrate_property_items(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let exists: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'property_items'"
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS property_items (
+            id INTEGER PRIMARY KEY,
+            property_id INTEGER NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+            item TEXT NOT NULL,
+            position INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_property_items_property ON property_items(property_id, item)")
+        .execute(pool)
+        .await?;
+
+    if exists.is_none() {
+        let lists: Vec<(i64, Option<String>)> =
+            sqlx::query_as("SELECT id, value FROM properties WHERE type = 'list'")
+                .fetch_all(pool)
+                .await?;
+        if !lists.is_empty() {
+            info!("Migrating {} list properties to property_items", lists.len());
+        }
+
+        let mut tx = pool.begin().await?;
+        for (id, value) in lists {
+            for (position, item) in split_list(value.as_deref().unwrap_or("")).iter().enumerate() {
+                sqlx::query("INSERT INTO property_items (property_id, item, position) VALUES (?, ?, ?)")
+                    .bind(id)
+                    .bind(item)
+                    .bind(position as i64)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+    }
+
+    debug!("property_items table created/verified");
+
+    Ok(())
+}
+
+/// Create asset_hashes table, mapping attachment content hashes to the
+/// files that have them, so identical attachments are stored once.
+async fn migrate_asset_hashes(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS asset_hashes (
+            path TEXT PRIMARY KEY,
+            hash TEXT NOT NULL,
+            size INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_asset_hashes_hash ON asset_hashes(hash);
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("asset_hashes table created/verified");
+
+    Ok(())
+}
+
+/// Create the tag_meta table: tag aliases, descriptions and colors.
+async fn migrate_tag_meta(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS tag_meta (
+            tag TEXT PRIMARY KEY,
+            alias_of TEXT,
+            description TEXT,
+            color TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tag_meta_alias_of ON tag_meta(alias_of);
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("tag_meta table created/verified");
+
+    Ok(())
+}
+
+/// Create the contexts table: registered task contexts and their metadata.
+async fn migrate_contexts(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS contexts (
+            name TEXT PRIMARY KEY COLLATE NOCASE,
+            icon TEXT,
+            description TEXT,
+            location TEXT
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("contexts table created/verified");
+
+    Ok(())
+}
+
+/// Create the drafts table: the latest unsaved editor content per note
+/// path, with the hash of the file it was edited from.
+async fn migrate_drafts(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS drafts (
+            path TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            base_hash TEXT,
+            saved_at TEXT NOT NULL
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("drafts table created/verified");
+
+    Ok(())
+}
+
+/// Create the file_problems table: files with invalid UTF-8 or binary
+/// content, found while indexing.
+async fn migrate_file_problems(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS file_problems (
+            path TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            detected_at TEXT NOT NULL
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("file_problems table created/verified");
+
+    Ok(())
+}
+
+/// Create the board_ranks table: the manual position of query results on
+/// each Kanban board, as fractional ranks.
+async fn migrate_board_ranks(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS board_ranks (
+            board_key TEXT NOT NULL,
+            item_ref TEXT NOT NULL,
+            rank REAL NOT NULL,
+            PRIMARY KEY (board_key, item_ref)
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("board_ranks table created/verified");
+
+    Ok(())
+}
+
+/// Tables whose changes are counted in `data_versions`.
+const VERSIONED_TABLES: &[&str] = &["notes", "properties", "todos", "board_ranks", "tags", "tag_meta", "property_items"];
+
+/// Versioned tables whose counters are bumped by triggers on every row.
+/// The others are written many rows at a time (a note's tags, a list's
+/// items), so their writes bump the counter once per statement instead.
+const ROW_VERSIONED_TABLES: &[&str] = &["notes", "properties", "todos", "board_ranks"];
+
+/// Create the data_versions table: a change counter per table, bumped on
+/// every insert, update and delete, so caches of query results can tell
+/// when the data they were built from changed.
+async fn migrate_data_versions(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS data_versions (
+            name TEXT PRIMARY KEY,
+            version INTEGER NOT NULL DEFAULT 0
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    for table in VERSIONED_TABLES {
+        sqlx::query("INSERT OR IGNORE INTO data_versions (name) VALUES (?)")
+            .bind(table)
+            .execute(pool)
+            .await?;
+        if !ROW_VERSIONED_TABLES.contains(table) {
+            continue;
+        }
+        for action in ["INSERT", "UPDATE", "DELETE"] {
+            sqlx::query(&format!(
+                r#"
+                CREATE TRIGGER IF NOT EXISTS {table}_version_{action} AFTER {action} ON {table}
+                BEGIN
+                    UPDATE data_versions SET version = version + 1 WHERE name = '{table}';
+                END;
+                "#,
+                table = table,
+                action = action.to_lowercase(),
+            ))
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    debug!("data_versions table and triggers created/verified");
+
+    Ok(())
+}
+
+/// Create the scheduled_note_rules table: notes created from a template on a
+/// recurring schedule. Times are local, without a zone.
+async fn migrate_scheduled_note_rules(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS scheduled_note_rules (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            rrule TEXT NOT NULL,
+            time TEXT NOT NULL,
+            path_pattern TEXT NOT NULL,
+            template_path TEXT,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            last_run TEXT,
+            created_at TEXT NOT NULL
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("scheduled_note_rules table created/verified");
+
+    Ok(())
+}
+
+/// Create the vault_metrics table: the vault's note, word, task and link
+/// counts per day, for days they changed on.
+async fn migrate_vault_metrics(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS vault_metrics (
+            date TEXT PRIMARY KEY,
+            notes INTEGER NOT NULL,
+            words INTEGER NOT NULL,
+            tasks INTEGER NOT NULL,
+            completed_tasks INTEGER NOT NULL,
+            links INTEGER NOT NULL
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("vault_metrics table created/verified");
+
+    Ok(())
+}
+
+/// Create the image_text table: text recognized in the vault's images, with
+/// the content hash of the image it was recognized from.
+async fn migrate_image_text(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS image_text (
+            path TEXT PRIMARY KEY,
+            hash TEXT NOT NULL,
+            text TEXT NOT NULL
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    debug!("image_text table created/verified");
+
+    Ok(())
+}
+
+/// Create link_targets table for the wiki link targets of each note as
+/// written, so links by name, title or alias can be resolved again when
+/// notes are added or renamed.
+///
+/// Stored hashes are cleared when the table is first created to make the next
+/// vault scan re-index every note.
+async fn migrate_link_targets(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'link_targets'",
+    )
+    .fetch_one(pool)
+    .await?;
+    if exists > 0 {
+        return Ok(());
+    }
+
+    info!("Creating link_targets table");
+    sqlx::query(
+        r#"
+        CREATE TABLE link_targets (
+            id INTEGER PRIMARY KEY,
+            from_note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            target TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_link_targets_from ON link_targets(from_note_id)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("UPDATE notes SET hash = NULL")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Add an `embed` column to link_targets marking the targets of embeds
+/// (`![[...]]`), so the notes a note embeds are known without reading it.
+///
+/// Stored hashes are cleared when the column is added to make the next
+/// vault scan re-index every note.
+async fn migrate_link_target_embeds(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let has_embed: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info('link_targets') WHERE name = 'embed'")
+            .fetch_one(pool)
+            .await?;
+    if has_embed > 0 {
+        return Ok(());
+    }
+
+    info!("Migrating link_targets table: adding embed column");
+    sqlx::query("ALTER TABLE link_targets ADD COLUMN embed INTEGER NOT NULL DEFAULT 0")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("UPDATE notes SET hash = NULL")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
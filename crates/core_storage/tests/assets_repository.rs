@@ -0,0 +1,26 @@
+//! Tests for attachment content hashes.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+
+#[tokio::test]
+async fn test_asset_hashes() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.record_asset("assets/b.png", "h1", 10).await.unwrap();
+    repo.record_asset("assets/a.png", "h1", 10).await.unwrap();
+    repo.record_asset("assets/c.png", "h2", 20).await.unwrap();
+    assert_eq!(repo.find_assets_by_hash("h1").await.unwrap(), vec!["assets/a.png", "assets/b.png"]);
+
+    // Recording a path again replaces its hash
+    repo.record_asset("assets/b.png", "h2", 20).await.unwrap();
+    repo.remove_asset("assets/c.png").await.unwrap();
+    assert_eq!(repo.find_assets_by_hash("h2").await.unwrap(), vec!["assets/b.png"]);
+
+    repo.replace_asset_hashes(&[("img/x.png".to_string(), "h1".to_string(), 10)])
+        .await
+        .unwrap();
+    assert_eq!(repo.find_assets_by_hash("h1").await.unwrap(), vec!["img/x.png"]);
+    assert!(repo.find_assets_by_hash("h2").await.unwrap().is_empty());
+}
@@ -0,0 +1,49 @@
+//! Tests for the autocomplete terms.
+
+mod helpers;
+
+use helpers::{insert_test_note, insert_test_tag, setup_test_repo};
+use shared_types::AutocompleteKind;
+
+#[tokio::test]
+async fn test_get_autocomplete_terms() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let plan = insert_test_note(pool, "plan.md", Some("Plan")).await;
+    let other = insert_test_note(pool, "other.md", None).await;
+    insert_test_tag(pool, plan, "work").await;
+    insert_test_tag(pool, other, "home").await;
+    repo.replace_aliases(plan, &["Roadmap".to_string()]).await.unwrap();
+    repo.set_property(plan, "status", Some("active"), Some("text")).await.unwrap();
+    repo.set_list_property(plan, "people", &["Ada".to_string(), "Grace, Jr.".to_string()])
+        .await
+        .unwrap();
+
+    let mut terms: Vec<(AutocompleteKind, Option<String>, String)> = repo
+        .get_autocomplete_terms(Some(&[plan]))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| (t.kind, t.key, t.text))
+        .collect();
+    terms.sort_by(|a, b| format!("{:?}", a).cmp(&format!("{:?}", b)));
+
+    let term = |kind, key: Option<&str>, text: &str| (kind, key.map(str::to_string), text.to_string());
+    assert_eq!(
+        terms,
+        vec![
+            term(AutocompleteKind::Alias, None, "Roadmap"),
+            term(AutocompleteKind::PropertyKey, None, "people"),
+            term(AutocompleteKind::PropertyKey, None, "status"),
+            term(AutocompleteKind::PropertyValue, Some("people"), "Ada"),
+            term(AutocompleteKind::PropertyValue, Some("people"), "Grace, Jr."),
+            term(AutocompleteKind::PropertyValue, Some("status"), "active"),
+            term(AutocompleteKind::Tag, None, "work"),
+            term(AutocompleteKind::Title, None, "Plan"),
+        ]
+    );
+
+    assert_eq!(repo.get_autocomplete_terms(None).await.unwrap().len(), terms.len() + 1);
+    assert!(repo.get_autocomplete_terms(Some(&[])).await.unwrap().is_empty());
+}
@@ -2,7 +2,8 @@
 
 mod helpers;
 
-use helpers::{count_rows, insert_test_note, setup_test_repo};
+use helpers::{count_rows, insert_test_note, insert_test_tag, setup_test_repo};
+use shared_types::{GraphScope, TagMeta};
 
 #[tokio::test]
 async fn test_replace_backlinks_insert() {
@@ -128,6 +129,88 @@ async fn test_get_notes_linking_to() {
     assert!(paths.contains(&"note2.md".to_string()));
 }
 
+#[tokio::test]
+async fn test_count_links_into() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let inside = insert_test_note(pool, "archive/a.md", Some("A")).await;
+    let sibling = insert_test_note(pool, "archive/b.md", Some("B")).await;
+    let outside = insert_test_note(pool, "home.md", Some("Home")).await;
+
+    repo.replace_backlinks(outside, &["archive/a.md".to_string(), "archive/b.md".to_string()])
+        .await
+        .unwrap();
+    repo.replace_backlinks(sibling, &["archive/a.md".to_string()]).await.unwrap();
+
+    assert_eq!(repo.count_links_into(&[inside, sibling]).await.unwrap(), 2);
+    assert_eq!(repo.count_links_into(&[inside]).await.unwrap(), 2);
+    assert_eq!(repo.count_links_into(&[]).await.unwrap(), 0);
+
+    // More notes than fit in one IN list, with the sibling in the last batch
+    let mut many = insert_notes(pool, 600).await;
+    many.insert(0, inside);
+    many.push(sibling);
+    assert_eq!(repo.count_links_into(&many).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_link_count_reports() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let home = insert_test_note(pool, "home.md", Some("Home")).await;
+    let project = insert_test_note(pool, "project.md", Some("Project")).await;
+    insert_test_note(pool, "reference.md", Some("Reference")).await;
+    let lonely = insert_test_note(pool, "lonely.md", Some("Lonely")).await;
+
+    repo.replace_backlinks(home, &["project.md".to_string(), "reference.md".to_string()])
+        .await
+        .unwrap();
+    repo.replace_backlinks(project, &["reference.md".to_string()]).await.unwrap();
+    // A link to itself doesn't count
+    repo.replace_backlinks(lonely, &["lonely.md".to_string()]).await.unwrap();
+
+    let summary = |notes: Vec<shared_types::NoteLinkCount>| -> Vec<(String, i64, i64)> {
+        notes.into_iter().map(|n| (n.note.path, n.inbound, n.outbound)).collect()
+    };
+
+    assert_eq!(
+        summary(repo.get_orphan_notes().await.unwrap()),
+        vec![("lonely.md".to_string(), 0, 0)]
+    );
+    assert_eq!(
+        summary(repo.get_dead_end_notes().await.unwrap()),
+        vec![("reference.md".to_string(), 2, 0), ("lonely.md".to_string(), 0, 0)]
+    );
+    assert_eq!(
+        summary(repo.get_hub_notes(1).await.unwrap()),
+        vec![("reference.md".to_string(), 2, 0)]
+    );
+    assert_eq!(repo.get_hub_notes(10).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_link_targets_and_set_backlinks() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let home = insert_test_note(pool, "home.md", None).await;
+    let plan = insert_test_note(pool, "projects/Plan.md", Some("Plan")).await;
+
+    // A link by name is stored as written, without an exact-path link
+    repo.replace_backlinks(home, &["Plan".to_string(), "projects/Plan".to_string()]).await.unwrap();
+    assert_eq!(
+        repo.list_link_targets(None).await.unwrap(),
+        vec![(home, "Plan".to_string()), (home, "projects/Plan".to_string())]
+    );
+    assert_eq!(repo.list_backlink_pairs(Some(&[home])).await.unwrap(), vec![(home, plan)]);
+
+    repo.set_backlinks(&[(home, vec![plan, plan])]).await.unwrap();
+    assert_eq!(repo.list_backlink_pairs(None).await.unwrap(), vec![(home, plan), (home, plan)]);
+    assert!(repo.list_link_targets(Some(&[plan])).await.unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_cascade_delete() {
     let (_pool, repo) = setup_test_repo().await;
@@ -152,3 +235,91 @@ async fn test_cascade_delete() {
     let backlink_count = count_rows(pool, "backlinks").await;
     assert_eq!(backlink_count, 0);
 }
+
+#[tokio::test]
+async fn test_list_embed_targets() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let notes: Vec<i64> = insert_notes(pool, 600).await;
+    let first = notes[0];
+    let last = notes[notes.len() - 1];
+    for note in [first, last] {
+        repo.replace_backlinks(note, &["Linked".to_string(), "Design".to_string()]).await.unwrap();
+        repo.mark_embed_targets(note, &["Design".to_string()]).await.unwrap();
+    }
+
+    // More notes than fit in one IN list
+    let targets = repo.list_embed_targets(&notes).await.unwrap();
+    assert_eq!(targets, [(first, "Design".to_string()), (last, "Design".to_string())]);
+}
+
+/// Insert `count` notes and return their IDs.
+async fn insert_notes(pool: &sqlx::SqlitePool, count: usize) -> Vec<i64> {
+    let mut ids = Vec::with_capacity(count);
+    for i in 0..count {
+        ids.push(insert_test_note(pool, &format!("note{}.md", i), None).await);
+    }
+    ids
+}
+
+#[tokio::test]
+async fn test_get_link_graph() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let a = insert_test_note(pool, "projects/a.md", Some("A")).await;
+    let b = insert_test_note(pool, "projects/sub/b.md", Some("B")).await;
+    let c = insert_test_note(pool, "inbox/c.md", Some("C")).await;
+    insert_test_tag(pool, a, "project/alpha").await;
+    insert_test_tag(pool, c, "project").await;
+
+    repo.replace_backlinks(a, &["projects/sub/b.md".to_string(), "projects/sub/b".to_string(), "inbox/c.md".to_string()])
+        .await
+        .unwrap();
+    repo.replace_backlinks(c, &["projects/a.md".to_string()]).await.unwrap();
+
+    let graph = repo.get_link_graph(&GraphScope::default()).await.unwrap();
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(graph.edges.len(), 3);
+    let a_to_b = graph.edges.iter().find(|e| e.source == a && e.target == b).unwrap();
+    assert_eq!(a_to_b.weight, 2);
+
+    // Folder scope includes subfolders and drops links leaving it
+    let scope = GraphScope { folder: Some("projects/".to_string()), tag: None };
+    let graph = repo.get_link_graph(&scope).await.unwrap();
+    let paths: Vec<_> = graph.nodes.iter().map(|n| n.path.as_str()).collect();
+    assert_eq!(paths, ["projects/a.md", "projects/sub/b.md"]);
+    assert_eq!(graph.edges.len(), 1);
+    assert_eq!(graph.nodes[0].tags, ["project/alpha"]);
+
+    // Tag scope includes nested tags
+    let scope = GraphScope { folder: None, tag: Some("#project".to_string()) };
+    let graph = repo.get_link_graph(&scope).await.unwrap();
+    let ids: Vec<_> = graph.nodes.iter().map(|n| n.id).collect();
+    assert_eq!(ids, [c, a]);
+    assert_eq!(graph.edges.len(), 2);
+
+    // `_` and `%` in a scope match only themselves
+    let d = insert_test_note(pool, "my_notes/d.md", Some("D")).await;
+    insert_test_note(pool, "myXnotes/e.md", Some("E")).await;
+    insert_test_tag(pool, d, "to_do").await;
+    let scope = GraphScope { folder: Some("my_notes".to_string()), tag: None };
+    let paths: Vec<_> = repo.get_link_graph(&scope).await.unwrap().nodes.into_iter().map(|n| n.path).collect();
+    assert_eq!(paths, ["my_notes/d.md"]);
+    let scope = GraphScope { folder: None, tag: Some("to%".to_string()) };
+    assert!(repo.get_link_graph(&scope).await.unwrap().nodes.is_empty());
+
+    // Tag scope includes notes with an alias of the tag
+    repo.set_tag_meta(&TagMeta {
+        tag: "to_do".to_string(),
+        alias_of: Some("project".to_string()),
+        description: None,
+        color: None,
+    })
+    .await
+    .unwrap();
+    let scope = GraphScope { folder: None, tag: Some("project".to_string()) };
+    let ids: Vec<_> = repo.get_link_graph(&scope).await.unwrap().nodes.into_iter().map(|n| n.id).collect();
+    assert_eq!(ids, [c, d, a]);
+}
@@ -0,0 +1,102 @@
+//! Tests for focus sessions and planned versus actual reports.
+
+mod helpers;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use helpers::setup_test_repo;
+
+fn at(s: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+}
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_start_and_end_block_session() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let block_id = repo
+        .create_schedule_block(None, "2024-01-15", "09:00:00", "10:00:00", None, None, Some("work"), None)
+        .await
+        .unwrap();
+
+    let session = repo.start_block_session(block_id, at("2024-01-15 09:05")).await.unwrap().unwrap();
+    assert_eq!(session.block_id, block_id);
+    assert!(session.ended_at.is_none());
+
+    // Starting again returns the running session
+    let again = repo.start_block_session(block_id, at("2024-01-15 09:10")).await.unwrap().unwrap();
+    assert_eq!(again.id, session.id);
+    assert_eq!(again.minutes, 5);
+
+    let ended = repo.end_block_session(block_id, at("2024-01-15 09:50")).await.unwrap().unwrap();
+    assert_eq!(ended.id, session.id);
+    assert_eq!(ended.minutes, 45);
+
+    assert!(repo.end_block_session(block_id, at("2024-01-15 10:00")).await.unwrap().is_none());
+    assert!(repo.start_block_session(9999, at("2024-01-15 10:00")).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_starting_a_block_ends_the_running_session() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let first = repo
+        .create_schedule_block(None, "2024-01-15", "09:00:00", "10:00:00", None, None, Some("work"), None)
+        .await
+        .unwrap();
+    let second = repo
+        .create_schedule_block(None, "2024-01-15", "10:00:00", "11:00:00", None, None, Some("admin"), None)
+        .await
+        .unwrap();
+
+    repo.start_block_session(first, at("2024-01-15 09:00")).await.unwrap();
+    repo.start_block_session(second, at("2024-01-15 09:40")).await.unwrap();
+
+    assert!(repo.end_block_session(first, at("2024-01-15 10:00")).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_plan_vs_actual_per_context() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let deep = repo
+        .create_schedule_block(None, "2024-01-15", "09:00:00", "11:00:00", None, None, Some("work"), None)
+        .await
+        .unwrap();
+    repo.create_schedule_block(None, "2024-01-16", "09:00:00", "10:00:00", None, None, Some("work"), None)
+        .await
+        .unwrap();
+    let admin = repo
+        .create_schedule_block(None, "2024-01-15", "14:00:00", "14:30:00", None, None, Some("admin"), None)
+        .await
+        .unwrap();
+
+    repo.start_block_session(deep, at("2024-01-15 09:15")).await.unwrap();
+    repo.end_block_session(deep, at("2024-01-15 10:45")).await.unwrap();
+    repo.start_block_session(admin, at("2024-01-15 14:00")).await.unwrap();
+
+    let report = repo
+        .get_plan_vs_actual(date("2024-01-15"), date("2024-01-21"), at("2024-01-15 14:50"))
+        .await
+        .unwrap();
+
+    assert_eq!(report.contexts.len(), 2);
+
+    let work = &report.contexts[0];
+    assert_eq!(work.context.as_deref(), Some("work"));
+    assert_eq!(work.planned_minutes, 180);
+    assert_eq!(work.actual_minutes, 90);
+    assert_eq!(work.difference_minutes, -90);
+    assert_eq!(work.block_count, 2);
+
+    // The running session counts up to now
+    let admin = &report.contexts[1];
+    assert_eq!(admin.planned_minutes, 30);
+    assert_eq!(admin.actual_minutes, 50);
+
+    assert_eq!(report.total_planned_minutes, 210);
+    assert_eq!(report.total_actual_minutes, 140);
+}
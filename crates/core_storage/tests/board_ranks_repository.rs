@@ -0,0 +1,24 @@
+//! Tests for manual card order on query Kanban boards.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+
+#[tokio::test]
+async fn test_board_ranks() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.set_board_rank("Board.md", "note:1", 2.0).await.unwrap();
+    repo.set_board_rank("Board.md", "note:2", 1.5).await.unwrap();
+    repo.set_board_rank("Board.md", "note:1", 0.5).await.unwrap();
+    repo.set_board_rank("Other.md", "note:1", 9.0).await.unwrap();
+
+    let ranks = repo.get_board_ranks("Board.md").await.unwrap();
+    assert_eq!(ranks.len(), 2);
+    assert_eq!(ranks["note:1"], 0.5);
+
+    repo.rebalance_board_ranks("Board.md").await.unwrap();
+    let ranks = repo.get_board_ranks("Board.md").await.unwrap();
+    assert_eq!((ranks["note:1"], ranks["note:2"]), (1.0, 2.0));
+    assert_eq!(repo.get_board_ranks("Other.md").await.unwrap()["note:1"], 9.0);
+}
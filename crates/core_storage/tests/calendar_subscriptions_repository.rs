@@ -0,0 +1,111 @@
+//! Tests for calendar subscriptions and the read-only schedule overlay.
+
+mod helpers;
+
+use chrono::{Duration, Utc};
+use core_index::parse_ics;
+use helpers::setup_test_repo;
+use shared_types::CreateCalendarSubscriptionRequest;
+
+const FEED: &str = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:standup
+SUMMARY:Standup
+DTSTART;TZID=Europe/Berlin:20240108T091500
+DTEND;TZID=Europe/Berlin:20240108T093000
+RRULE:FREQ=WEEKLY;BYDAY=MO
+END:VEVENT
+BEGIN:VEVENT
+UID:review
+SUMMARY:Design review
+DTSTART:20240110T130000Z
+DTEND:20240110T140000Z
+END:VEVENT
+END:VCALENDAR
+";
+
+fn subscription(name: &str) -> CreateCalendarSubscriptionRequest {
+    CreateCalendarSubscriptionRequest {
+        name: name.to_string(),
+        url: "https://example.com/work.ics".to_string(),
+        color: Some("#888888".to_string()),
+        refresh_minutes: Some(30),
+    }
+}
+
+#[tokio::test]
+async fn test_subscription_events_are_read_only_blocks() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let block_id = repo
+        .create_schedule_block(None, "2024-01-08", "10:00:00", "11:00:00", Some("Deep work"), None, None, None)
+        .await
+        .unwrap();
+
+    let sub_id = repo.create_calendar_subscription(&subscription("Work")).await.unwrap();
+    repo.replace_calendar_events(sub_id, &parse_ics(FEED)).await.unwrap();
+
+    let blocks = repo
+        .get_schedule_blocks_for_range("2024-01-08", "2024-01-21")
+        .await
+        .unwrap();
+
+    let own: Vec<_> = blocks.iter().filter(|b| !b.read_only).collect();
+    assert_eq!(own.len(), 1);
+    assert_eq!(own[0].id, block_id);
+
+    let overlay: Vec<_> = blocks.iter().filter(|b| b.read_only).collect();
+    let standups: Vec<_> = overlay
+        .iter()
+        .filter(|b| b.label.as_deref() == Some("Standup"))
+        .map(|b| b.date.to_string())
+        .collect();
+    assert_eq!(standups, vec!["2024-01-08", "2024-01-15"]);
+
+    let review = overlay
+        .iter()
+        .find(|b| b.label.as_deref() == Some("Design review"))
+        .unwrap();
+    assert_eq!(review.timezone.as_deref(), Some("UTC"));
+    assert_eq!(review.color.as_deref(), Some("#888888"));
+    assert!(review.note_id.is_none());
+
+    // Blocks are sorted by date and time across both sources
+    assert_eq!(blocks[0].label.as_deref(), Some("Standup"));
+    assert_eq!(blocks[1].label.as_deref(), Some("Deep work"));
+}
+
+#[tokio::test]
+async fn test_refresh_replaces_events_and_tracks_status() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let sub_id = repo.create_calendar_subscription(&subscription("Work")).await.unwrap();
+    assert_eq!(repo.get_due_calendar_subscriptions(Utc::now()).await.unwrap().len(), 1);
+
+    repo.replace_calendar_events(sub_id, &parse_ics(FEED)).await.unwrap();
+    repo.replace_calendar_events(sub_id, &parse_ics(FEED)).await.unwrap();
+
+    let subs = repo.list_calendar_subscriptions().await.unwrap();
+    assert_eq!(subs[0].event_count, 2);
+    assert!(subs[0].last_fetched.is_some());
+    assert!(subs[0].last_error.is_none());
+
+    // Not due again until the refresh interval has passed
+    assert!(repo.get_due_calendar_subscriptions(Utc::now()).await.unwrap().is_empty());
+    let later = Utc::now() + Duration::minutes(31);
+    assert_eq!(repo.get_due_calendar_subscriptions(later).await.unwrap().len(), 1);
+
+    // A failed fetch keeps the previous events
+    repo.set_calendar_subscription_status(sub_id, Some("HTTP 500")).await.unwrap();
+    let subs = repo.list_calendar_subscriptions().await.unwrap();
+    assert_eq!(subs[0].event_count, 2);
+    assert_eq!(subs[0].last_error.as_deref(), Some("HTTP 500"));
+
+    repo.delete_calendar_subscription(sub_id).await.unwrap();
+    assert!(repo.list_calendar_subscriptions().await.unwrap().is_empty());
+    let blocks = repo
+        .get_schedule_blocks_for_range("2024-01-01", "2024-01-31")
+        .await
+        .unwrap();
+    assert!(blocks.is_empty());
+}
@@ -0,0 +1,83 @@
+//! Tests for the contexts repository.
+
+mod helpers;
+
+use core_index::markdown::ParsedTodo;
+use core_storage::StorageError;
+use helpers::{insert_test_note, setup_test_repo};
+use shared_types::ContextMeta;
+
+fn todo(line_number: usize, context: &str, completed: bool) -> ParsedTodo {
+    ParsedTodo {
+        description: format!("Task {}", line_number),
+        raw_text: format!("- [ ] Task {} @{}", line_number, context),
+        completed,
+        line_number,
+        heading_path: None,
+        context: Some(context.to_string()),
+        priority: None,
+        due_date: None,
+        estimate_minutes: None,
+    }
+}
+
+fn context(name: &str, icon: Option<&str>) -> ContextMeta {
+    ContextMeta {
+        name: name.to_string(),
+        icon: icon.map(String::from),
+        description: None,
+        location: None,
+    }
+}
+
+#[tokio::test]
+async fn test_list_contexts_with_open_tasks() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note_id = insert_test_note(pool, "tasks.md", Some("Tasks")).await;
+    let todos = vec![
+        todo(1, "phone", false),
+        todo(2, "phone", false),
+        todo(3, "Home", false),
+        todo(4, "home", true),
+        todo(5, "errands", true),
+    ];
+    repo.replace_todos(note_id, &todos).await.unwrap();
+
+    repo.set_context(&context("@home", Some("🏠"))).await.unwrap();
+    repo.set_context(&context("office", None)).await.unwrap();
+
+    let contexts = repo.list_contexts().await.unwrap();
+    let summary: Vec<(&str, i64, bool)> = contexts
+        .iter()
+        .map(|c| (c.name.as_str(), c.open_tasks, c.registered))
+        .collect();
+    assert_eq!(
+        summary,
+        [("phone", 2, false), ("home", 1, true), ("errands", 0, false), ("office", 0, true)]
+    );
+    assert_eq!(contexts[1].icon.as_deref(), Some("🏠"));
+
+    // Updating keeps one entry per name
+    repo.set_context(&context("Office", Some("🏢"))).await.unwrap();
+    let registered = repo.list_registered_contexts().await.unwrap();
+    assert_eq!(registered.len(), 2);
+    assert_eq!(registered[1], context("Office", Some("🏢")));
+
+    repo.delete_context("@office").await.unwrap();
+    assert_eq!(repo.list_registered_contexts().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_set_context_rejects_invalid_names() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    for name in ["", "@", "2do", "at home"] {
+        assert!(
+            matches!(repo.set_context(&context(name, None)).await, Err(StorageError::InvalidContext(_))),
+            "{:?}",
+            name
+        );
+    }
+    assert!(repo.list_registered_contexts().await.unwrap().is_empty());
+}
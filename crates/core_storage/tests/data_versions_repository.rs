@@ -0,0 +1,47 @@
+//! Tests for the change counters used as cache fingerprints.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+use shared_types::TagMeta;
+
+#[tokio::test]
+async fn test_data_fingerprint_changes_with_rows() {
+    let (_pool, repo) = setup_test_repo().await;
+    let note_id = repo.upsert_note("a.md", Some("A"), "hash").await.unwrap();
+    repo.set_property(note_id, "status", Some("todo"), None).await.unwrap();
+
+    let before = repo.get_data_fingerprint(&["notes", "properties"]).await.unwrap();
+    let todos = repo.get_data_fingerprint(&["todos"]).await.unwrap();
+
+    // Updating a value in place changes it; other tables keep theirs
+    repo.set_property(note_id, "status", Some("done"), None).await.unwrap();
+    assert_ne!(repo.get_data_fingerprint(&["notes", "properties"]).await.unwrap(), before);
+    assert_eq!(repo.get_data_fingerprint(&["todos"]).await.unwrap(), todos);
+}
+
+#[tokio::test]
+async fn test_data_fingerprint_counts_tags_and_list_items() {
+    let (_pool, repo) = setup_test_repo().await;
+    let note_id = repo.upsert_note("a.md", Some("A"), "hash").await.unwrap();
+    let tables = ["tags", "tag_meta", "property_items"];
+
+    let before = repo.get_data_fingerprint(&tables).await.unwrap();
+    repo.replace_tags(note_id, &["work".to_string(), "home".to_string()]).await.unwrap();
+    let tagged = repo.get_data_fingerprint(&tables).await.unwrap();
+    assert_ne!(tagged, before);
+    // Once per write, not per row
+    assert_eq!(repo.get_data_fingerprint(&["tags"]).await.unwrap(), "1");
+
+    repo.set_list_property(note_id, "topics", &["rust".to_string(), "sqlite".to_string()]).await.unwrap();
+    let listed = repo.get_data_fingerprint(&tables).await.unwrap();
+    assert_ne!(listed, tagged);
+
+    repo.set_tag_meta(&TagMeta {
+        tag: "todo".to_string(),
+        alias_of: Some("task".to_string()),
+        description: None,
+        color: None,
+    }).await.unwrap();
+    assert_ne!(repo.get_data_fingerprint(&tables).await.unwrap(), listed);
+}
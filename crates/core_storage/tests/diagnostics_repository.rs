@@ -0,0 +1,20 @@
+//! Tests for database statistics.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+
+#[tokio::test]
+async fn test_table_row_counts_and_schema() {
+    let (_pool, repo) = setup_test_repo().await;
+    repo.record_asset("assets/a.png", "h1", 10).await.unwrap();
+    repo.record_asset("assets/b.png", "h2", 20).await.unwrap();
+
+    let counts = repo.table_row_counts().await.unwrap();
+    assert!(counts.contains(&("asset_hashes".to_string(), 2)));
+    assert!(counts.contains(&("notes".to_string(), 0)));
+    assert!(counts.iter().all(|(table, _)| !table.starts_with("sqlite_")));
+
+    let statements = repo.schema_statements().await.unwrap();
+    assert!(statements.iter().any(|sql| sql.starts_with("CREATE TABLE asset_hashes")));
+}
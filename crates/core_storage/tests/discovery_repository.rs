@@ -0,0 +1,111 @@
+//! Tests for random note and "on this day" lookups.
+
+mod helpers;
+
+use chrono::NaiveDate;
+use helpers::{insert_test_note, insert_test_property, insert_test_tag, setup_test_repo};
+use shared_types::{RandomNoteFilter, TagMeta};
+
+#[tokio::test]
+async fn test_random_note_filters() {
+    let (pool, repo) = setup_test_repo().await;
+
+    assert!(repo.get_random_note(&RandomNoteFilter::default()).await.unwrap().is_none());
+
+    let idea = insert_test_note(&pool, "ideas/app.md", Some("App")).await;
+    insert_test_tag(&pool, idea, "project/app").await;
+    let other = insert_test_note(&pool, "ideas-old/misc.md", None).await;
+    insert_test_tag(&pool, other, "projects").await;
+    insert_test_note(&pool, "journal/day.md", None).await;
+
+    let in_folder = RandomNoteFilter {
+        folder: Some("/ideas/".to_string()),
+        tag: None,
+    };
+    for _ in 0..5 {
+        let note = repo.get_random_note(&in_folder).await.unwrap().unwrap();
+        assert_eq!(note.id, idea);
+    }
+
+    let with_tag = RandomNoteFilter {
+        folder: None,
+        tag: Some("#project".to_string()),
+    };
+    for _ in 0..5 {
+        let note = repo.get_random_note(&with_tag).await.unwrap().unwrap();
+        assert_eq!(note.id, idea);
+    }
+
+    let no_match = RandomNoteFilter {
+        folder: Some("journal".to_string()),
+        tag: Some("project".to_string()),
+    };
+    assert!(repo.get_random_note(&no_match).await.unwrap().is_none());
+
+    // Notes with an alias of the tag match it, and the alias matches the tag
+    let todo = insert_test_note(&pool, "journal/todo.md", None).await;
+    insert_test_tag(&pool, todo, "todo").await;
+    repo.set_tag_meta(&TagMeta {
+        tag: "todo".to_string(),
+        alias_of: Some("task".to_string()),
+        description: None,
+        color: None,
+    })
+    .await
+    .unwrap();
+    for tag in ["task", "#todo"] {
+        let filter = RandomNoteFilter {
+            folder: None,
+            tag: Some(tag.to_string()),
+        };
+        assert_eq!(repo.get_random_note(&filter).await.unwrap().unwrap().id, todo, "{}", tag);
+    }
+}
+
+#[tokio::test]
+async fn test_on_this_day() {
+    let (pool, repo) = setup_test_repo().await;
+
+    let journal = insert_test_note(&pool, "journal/2022-03-14.md", None).await;
+    insert_test_property(&pool, journal, "journal_date", "2022-03-14", "date").await;
+
+    let created = insert_test_note(&pool, "notes/pi.md", Some("Pi")).await;
+    let this_year = insert_test_note(&pool, "notes/today.md", None).await;
+    let other_day = insert_test_note(&pool, "notes/other.md", None).await;
+    // The journal date comes before the file name and the creation date
+    let moved = insert_test_note(&pool, "journal/2021-03-14.md", None).await;
+    insert_test_property(&pool, moved, "journal_date", "2021-03-15", "date").await;
+    let named = insert_test_note(&pool, "journal/2020-03-14.md", None).await;
+    for (id, day) in [
+        (journal, "2024-01-01"),
+        (created, "2023-03-14"),
+        (this_year, "2025-03-14"),
+        (other_day, "2023-03-15"),
+        (moved, "2023-03-14"),
+        (named, "2024-03-15"),
+    ] {
+        sqlx::query("UPDATE notes SET created_date = ? WHERE id = ?")
+            .bind(day)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let notes = repo
+        .get_on_this_day(NaiveDate::from_ymd_opt(2025, 3, 14).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(notes.len(), 3);
+    assert_eq!(notes[0].note.id, created);
+    assert_eq!(notes[0].source, "created");
+    assert_eq!(notes[0].years_ago, 2);
+    assert_eq!(notes[1].note.id, journal);
+    assert_eq!(notes[1].source, "journal");
+    assert_eq!(notes[1].date, "2022-03-14");
+    assert_eq!(notes[1].years_ago, 3);
+    assert_eq!(notes[2].note.id, named);
+    assert_eq!(notes[2].source, "file_name");
+    assert_eq!(notes[2].date, "2020-03-14");
+}
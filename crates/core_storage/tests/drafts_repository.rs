@@ -0,0 +1,24 @@
+//! Tests for drafts of unsaved edits.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+
+#[tokio::test]
+async fn test_drafts() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.save_draft("b.md", "first", Some("h1")).await.unwrap();
+    repo.save_draft("a.md", "new note", None).await.unwrap();
+    // Saving again replaces the content, but not the file it was edited from
+    repo.save_draft("b.md", "second", Some("h2")).await.unwrap();
+
+    let drafts = repo.list_drafts().await.unwrap();
+    assert_eq!(drafts.len(), 2);
+    assert_eq!((drafts[0].0.as_str(), drafts[0].2.as_deref()), ("a.md", None));
+    assert_eq!((drafts[1].1.as_str(), drafts[1].2.as_deref()), ("second", Some("h1")));
+
+    repo.delete_draft("a.md").await.unwrap();
+    repo.delete_draft("missing.md").await.unwrap();
+    assert_eq!(repo.list_drafts().await.unwrap().len(), 1);
+}
@@ -0,0 +1,63 @@
+//! Tests for keeping note and section embeddings across small edits.
+
+mod helpers;
+
+use core_index::{chunk_note, MAX_CHUNK_CHARS};
+use core_storage::{content_signature, extract_content_preview, ChunkEmbedding};
+use helpers::{insert_test_note, setup_test_repo};
+
+const CONTENT: &str = "# Plan\n\nShip the first version of the garden planner before the spring planting season starts.\n\n# Notes\n\nTomatoes need full sun, regular watering and a stake or cage once they start to grow tall.\n";
+
+fn chunk_embeddings(content: &str) -> Vec<ChunkEmbedding> {
+    chunk_note(content, MAX_CHUNK_CHARS)
+        .into_iter()
+        .map(|chunk| ChunkEmbedding {
+            snippet: extract_content_preview(&chunk.text),
+            heading_path: chunk.heading_path,
+            start_line: chunk.start_line as u32,
+            end_line: chunk.end_line as u32,
+            embedding: vec![1.0, 0.0],
+        })
+        .collect()
+}
+
+async fn section_lines(pool: &sqlx::SqlitePool, note_id: i64) -> Vec<(u32, u32)> {
+    sqlx::query_as("SELECT start_line, end_line FROM note_embedding_chunks WHERE note_id = ? ORDER BY chunk_index")
+        .bind(note_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_small_edit_keeps_embedding_and_moves_sections() {
+    let (pool, repo) = setup_test_repo().await;
+    let note_id = insert_test_note(&pool, "plan.md", Some("Plan")).await;
+
+    repo.store_chunk_embeddings(note_id, &chunk_embeddings(CONTENT)).await.unwrap();
+    repo.store_embedding(note_id, &[1.0, 0.0], "h1", None, &content_signature(CONTENT))
+        .await
+        .unwrap();
+    let before = section_lines(&pool, note_id).await;
+
+    // One more line in the first section shifts the second one down
+    let edited = CONTENT.replace("starts.\n", "starts.\nSoon.\n");
+    let current = repo
+        .embedding_is_current(note_id, &edited, "h2", None, &content_signature(&edited), 0.5)
+        .await
+        .unwrap();
+    assert!(current);
+    let after = section_lines(&pool, note_id).await;
+    assert_eq!(after[0], (before[0].0, before[0].1 + 1));
+    assert_eq!(after[1], (before[1].0 + 1, before[1].1 + 1));
+    assert!(!repo.needs_embedding(note_id, "h2").await.unwrap());
+
+    // A renamed section needs a new embedding, even for a small edit
+    let renamed = edited.replace("# Notes", "# Growing");
+    let current = repo
+        .embedding_is_current(note_id, &renamed, "h3", None, &content_signature(&renamed), 0.5)
+        .await
+        .unwrap();
+    assert!(!current);
+    assert!(repo.needs_embedding(note_id, "h3").await.unwrap());
+}
@@ -0,0 +1,28 @@
+//! Tests for files that could not be read cleanly.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+use shared_types::FileProblemKind;
+
+#[tokio::test]
+async fn test_file_problems() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    assert!(repo.record_file_problem("b.md", FileProblemKind::InvalidUtf8).await.unwrap());
+    assert!(repo.record_file_problem("a.md", FileProblemKind::Binary).await.unwrap());
+    // The same problem again is not new; a different one replaces it
+    assert!(!repo.record_file_problem("b.md", FileProblemKind::InvalidUtf8).await.unwrap());
+    assert!(repo.record_file_problem("a.md", FileProblemKind::InvalidUtf8).await.unwrap());
+
+    let problems = repo.list_file_problems().await.unwrap();
+    let kinds: Vec<_> = problems.iter().map(|p| (p.path.as_str(), p.kind)).collect();
+    assert_eq!(kinds, vec![("a.md", FileProblemKind::InvalidUtf8), ("b.md", FileProblemKind::InvalidUtf8)]);
+
+    assert_eq!(repo.get_file_problem("a.md").await.unwrap(), Some(FileProblemKind::InvalidUtf8));
+
+    repo.clear_file_problem("a.md").await.unwrap();
+    repo.clear_file_problem("missing.md").await.unwrap();
+    assert_eq!(repo.list_file_problems().await.unwrap().len(), 1);
+    assert_eq!(repo.get_file_problem("a.md").await.unwrap(), None);
+}
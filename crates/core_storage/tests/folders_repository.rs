@@ -0,0 +1,87 @@
+//! Tests for folder appearance and manual child order.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+use shared_types::FolderAppearance;
+
+fn appearance(icon: &str) -> FolderAppearance {
+    FolderAppearance {
+        icon: Some(icon.to_string()),
+        color: Some("#ff8800".to_string()),
+        collapsed: true,
+    }
+}
+
+#[tokio::test]
+async fn test_set_appearance_and_child_order() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.set_folder_appearance("/projects/", &appearance("rocket")).await.unwrap();
+    repo.set_folder_child_order("projects", &["b.md".to_string(), "a".to_string()])
+        .await
+        .unwrap();
+
+    let metadata = repo.list_folder_metadata().await.unwrap();
+    assert_eq!(metadata.len(), 1);
+    assert_eq!(metadata[0].path, "projects");
+    assert_eq!(metadata[0].appearance.icon.as_deref(), Some("rocket"));
+    assert!(metadata[0].appearance.collapsed);
+    assert_eq!(metadata[0].child_order, vec!["b.md", "a"]);
+
+    // Changing the appearance keeps the order and vice versa
+    repo.set_folder_appearance("projects", &FolderAppearance::default()).await.unwrap();
+    let metadata = repo.list_folder_metadata().await.unwrap();
+    assert!(metadata[0].appearance.icon.is_none());
+    assert_eq!(metadata[0].child_order, vec!["b.md", "a"]);
+}
+
+#[tokio::test]
+async fn test_rename_and_delete_folder_metadata() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.set_folder_appearance("work", &appearance("briefcase")).await.unwrap();
+    repo.set_folder_appearance("work/meetings", &appearance("people")).await.unwrap();
+    repo.set_folder_appearance("workshop", &appearance("hammer")).await.unwrap();
+    repo.set_folder_child_order("", &["work".to_string(), "inbox".to_string()])
+        .await
+        .unwrap();
+
+    repo.rename_folder_metadata("work", "job").await.unwrap();
+
+    let metadata = repo.list_folder_metadata().await.unwrap();
+    let paths: Vec<_> = metadata.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, vec!["", "job", "job/meetings", "workshop"]);
+    assert_eq!(metadata[0].child_order, vec!["job", "inbox"]);
+
+    repo.delete_folder_metadata("job").await.unwrap();
+    let paths: Vec<_> = repo
+        .list_folder_metadata()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.path)
+        .collect();
+    assert_eq!(paths, vec!["", "workshop"]);
+}
+
+#[tokio::test]
+async fn test_rename_folder_metadata_with_non_ascii_names() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.set_folder_appearance("Bücher", &appearance("book")).await.unwrap();
+    repo.set_folder_appearance("Bücher/Romane", &appearance("pen")).await.unwrap();
+
+    repo.rename_folder_metadata("Bücher", "Lesen").await.unwrap();
+    let paths: Vec<_> = repo
+        .list_folder_metadata()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.path)
+        .collect();
+    assert_eq!(paths, vec!["Lesen", "Lesen/Romane"]);
+
+    repo.delete_folder_metadata("Lesen").await.unwrap();
+    assert!(repo.list_folder_metadata().await.unwrap().is_empty());
+}
@@ -0,0 +1,159 @@
+//! Tests for the goals repository.
+
+mod helpers;
+
+use chrono::NaiveDate;
+use core_index::ParsedTodo;
+use helpers::{insert_test_note, setup_test_repo};
+use shared_types::{CreateGoalRequest, CreateHabitRequest, GoalTargetType, HabitType, LogHabitEntryRequest, TaskQuery};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn todo(description: &str, completed: bool, context: &str, line_number: usize) -> ParsedTodo {
+    ParsedTodo {
+        description: description.to_string(),
+        raw_text: format!("- [ ] {}", description),
+        completed,
+        line_number,
+        heading_path: None,
+        context: Some(context.to_string()),
+        priority: None,
+        due_date: None,
+        estimate_minutes: None,
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_list_goals() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let habit_id = repo.create_habit(&CreateHabitRequest {
+        name: "Running".to_string(),
+        description: None,
+        habit_type: HabitType::Number,
+        unit: Some("km".to_string()),
+        color: None,
+        target_value: None,
+    }).await.unwrap();
+
+    let goal_id = repo.create_goal(&CreateGoalRequest {
+        name: "Run 100 km".to_string(),
+        description: None,
+        target_type: GoalTargetType::Numeric,
+        target_value: Some(100.0),
+        unit: Some("km".to_string()),
+        start_date: Some("2024-01-01".to_string()),
+        deadline: Some("2024-03-31".to_string()),
+        habit_ids: vec![habit_id],
+        task_query: None,
+    }).await.unwrap();
+
+    let goal = repo.get_goal_by_name("run 100 km").await.unwrap().unwrap();
+    assert_eq!(goal.id, goal_id);
+    assert_eq!(goal.habit_ids, vec![habit_id]);
+    assert_eq!(goal.target_type, GoalTargetType::Numeric);
+
+    repo.archive_goal(goal_id).await.unwrap();
+    assert!(repo.list_goals(false).await.unwrap().is_empty());
+    assert_eq!(repo.list_goals(true).await.unwrap().len(), 1);
+
+    repo.delete_goal(goal_id).await.unwrap();
+    assert!(repo.get_goal(goal_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_numeric_goal_progress() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let habit_id = repo.create_habit(&CreateHabitRequest {
+        name: "Running".to_string(),
+        description: None,
+        habit_type: HabitType::Number,
+        unit: Some("km".to_string()),
+        color: None,
+        target_value: None,
+    }).await.unwrap();
+
+    for (date, value) in [("2023-12-31", "50"), ("2024-01-02", "5"), ("2024-01-05", "10.5")] {
+        repo.log_habit_entry(&LogHabitEntryRequest {
+            habit_id,
+            date: date.to_string(),
+            time: None,
+            value: value.to_string(),
+            notes: None,
+        }).await.unwrap();
+    }
+
+    let goal_id = repo.create_goal(&CreateGoalRequest {
+        name: "Run 100 km".to_string(),
+        description: None,
+        target_type: GoalTargetType::Numeric,
+        target_value: Some(100.0),
+        unit: Some("km".to_string()),
+        start_date: Some("2024-01-01".to_string()),
+        deadline: Some("2024-01-11".to_string()),
+        habit_ids: vec![habit_id],
+        task_query: None,
+    }).await.unwrap();
+
+    let progress = repo.get_goal_progress(goal_id, date("2024-01-06")).await.unwrap().unwrap();
+
+    // The entry before the start date is not counted
+    assert_eq!(progress.current_value, 15.5);
+    assert_eq!(progress.percent, Some(15.5));
+    assert_eq!(progress.days_remaining, Some(5));
+    assert_eq!(progress.on_track, Some(false));
+}
+
+#[tokio::test]
+async fn test_task_goal_progress() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note_id = insert_test_note(pool, "thesis.md", Some("Thesis")).await;
+
+    repo.replace_todos(note_id, &[
+        todo("Write intro", true, "thesis", 1),
+        todo("Write methods", true, "thesis", 2),
+        todo("Write results", false, "thesis", 3),
+        todo("Write discussion", false, "thesis", 4),
+        todo("Buy milk", true, "home", 5),
+    ]).await.unwrap();
+
+    let goal_id = repo.create_goal(&CreateGoalRequest {
+        name: "Finish thesis".to_string(),
+        description: None,
+        target_type: GoalTargetType::Tasks,
+        target_value: None,
+        unit: None,
+        start_date: Some("2024-01-01".to_string()),
+        deadline: None,
+        habit_ids: vec![],
+        task_query: Some(TaskQuery {
+            context: Some("thesis".to_string()),
+            ..Default::default()
+        }),
+    }).await.unwrap();
+
+    let progress = repo.get_goal_progress(goal_id, date("2024-01-06")).await.unwrap().unwrap();
+
+    assert_eq!(progress.current_value, 2.0);
+    assert_eq!(progress.total_tasks, Some(4));
+    assert_eq!(progress.target_value, Some(4.0));
+    assert_eq!(progress.percent, Some(50.0));
+    assert_eq!(progress.on_track, None);
+
+    // Tasks completed before the start date aren't part of the goal
+    sqlx::query("UPDATE todos SET completed_at = '2023-12-20T10:00:00+01:00' WHERE description = 'Write intro'")
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE todos SET completed_at = '2024-01-03T10:00:00+01:00' WHERE description = 'Write methods'")
+        .execute(pool)
+        .await
+        .unwrap();
+    let progress = repo.get_goal_progress(goal_id, date("2024-01-06")).await.unwrap().unwrap();
+    assert_eq!(progress.current_value, 1.0);
+    assert_eq!(progress.total_tasks, Some(3));
+}
@@ -0,0 +1,54 @@
+//! Tests for vault statistics over time.
+
+mod helpers;
+
+use helpers::{count_rows, insert_test_note, setup_test_repo};
+use shared_types::VaultMetric;
+
+#[tokio::test]
+async fn test_vault_metrics_snapshots() {
+    let (pool, repo) = setup_test_repo().await;
+
+    let a = insert_test_note(&pool, "a.md", Some("A")).await;
+    sqlx::query("UPDATE notes SET word_count = 120 WHERE id = ?").bind(a).execute(&pool).await.unwrap();
+    assert!(repo.snapshot_vault_metrics("2025-01-01").await.unwrap());
+
+    // Unchanged days are not stored
+    assert!(!repo.snapshot_vault_metrics("2025-01-02").await.unwrap());
+
+    let b = insert_test_note(&pool, "b.md", Some("B")).await;
+    sqlx::query("INSERT INTO todos (note_id, description, completed) VALUES (?, 'Task', 1)")
+        .bind(b)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(repo.snapshot_vault_metrics("2025-01-05").await.unwrap());
+    let metrics = repo.get_vault_metrics("2025-01-05").await.unwrap();
+    assert_eq!((metrics.notes, metrics.words, metrics.tasks, metrics.completed_tasks), (2, 120, 1, 1));
+
+    // A change undone the same day drops the day's snapshot again
+    sqlx::query("DELETE FROM notes WHERE id = ?").bind(b).execute(&pool).await.unwrap();
+    sqlx::query("DELETE FROM todos").execute(&pool).await.unwrap();
+    assert!(!repo.snapshot_vault_metrics("2025-01-05").await.unwrap());
+    assert_eq!(count_rows(&pool, "vault_metrics").await, 1);
+
+    insert_test_note(&pool, "c.md", Some("C")).await;
+    repo.snapshot_vault_metrics("2025-01-10").await.unwrap();
+    insert_test_note(&pool, "d.md", Some("D")).await;
+    repo.snapshot_vault_metrics("2025-02-01").await.unwrap();
+
+    let growth = repo.get_vault_growth("2025-01-03", "2025-01-31", VaultMetric::Notes).await.unwrap();
+    let points: Vec<_> = growth.points.iter().map(|p| (p.date.as_str(), p.value)).collect();
+    assert_eq!(points, [("2025-01-03", 1), ("2025-01-10", 2)]);
+    assert_eq!(growth.change, 1);
+
+    // Word count didn't change on the 10th
+    let growth = repo.get_vault_growth("2025-01-01", "2025-02-28", VaultMetric::Words).await.unwrap();
+    assert_eq!(growth.points.len(), 1);
+    assert_eq!(growth.change, 0);
+
+    // Invalid and inverted ranges are rejected
+    assert!(repo.get_vault_growth("2025-01-31", "2025-01-01", VaultMetric::Notes).await.is_err());
+    assert!(repo.get_vault_growth("2025-13-01", "2025-12-31", VaultMetric::Notes).await.is_err());
+    assert!(repo.get_vault_growth("2025-01-01", "' OR 1=1", VaultMetric::Notes).await.is_err());
+}
@@ -3,7 +3,7 @@
 mod helpers;
 
 use helpers::setup_test_repo;
-use shared_types::{CreateHabitRequest, HabitDateRange, HabitTableOrientation, HabitTrackerQuery, HabitType, HabitViewType, LogHabitEntryRequest, UpdateHabitEntryRequest, UpdateHabitRequest};
+use shared_types::{CreateHabitRequest, HabitDateRange, HabitScheduleRules, HabitTableOrientation, HabitTrackerQuery, HabitType, HabitViewType, LogHabitEntryRequest, UpdateHabitEntryRequest, UpdateHabitRequest, WeekStart};
 
 #[tokio::test]
 async fn test_create_habit() {
@@ -292,6 +292,50 @@ async fn test_get_habit_entries_date_range() {
     assert_eq!(entries[1].value, "6.1");
 }
 
+#[tokio::test]
+async fn test_import_habit_entries_skips_duplicates() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let habit_id = repo.create_habit(&CreateHabitRequest {
+        name: "Sleep".to_string(),
+        description: None,
+        habit_type: HabitType::Number,
+        unit: Some("hours".to_string()),
+        color: None,
+        target_value: Some(8.0),
+    }).await.unwrap();
+
+    repo.log_habit_entry(&LogHabitEntryRequest {
+        habit_id,
+        date: "2024-01-01".to_string(),
+        time: None,
+        value: "7".to_string(),
+        notes: None,
+    }).await.unwrap();
+
+    let entry = |date: &str, time: Option<&str>, value: &str| LogHabitEntryRequest {
+        habit_id,
+        date: date.to_string(),
+        time: time.map(String::from),
+        value: value.to_string(),
+        notes: None,
+    };
+
+    let (imported, skipped) = repo.import_habit_entries(&[
+        entry("2024-01-01", None, "7.5"), // already logged
+        entry("2024-01-01", Some("13:00:00"), "0.5"), // nap, different time
+        entry("2024-01-02", None, "6.5"),
+        entry("2024-01-02", None, "6.5"), // repeated within the batch
+    ]).await.unwrap();
+
+    assert_eq!(imported, 2);
+    assert_eq!(skipped, 2);
+
+    let entries = repo.get_habit_entries(habit_id, "2024-01-01", "2024-01-02").await.unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].value, "7"); // existing entry untouched
+}
+
 #[tokio::test]
 async fn test_toggle_habit_for_date_on_off() {
     let (_pool, repo) = setup_test_repo().await;
@@ -401,6 +445,7 @@ async fn test_execute_habit_tracker_query_last_7_days() {
         end_date: None,
         editable: true,
         show_summary: true,
+        week_start: None,
     };
 
     let response = repo.execute_habit_tracker_query(&query).await.unwrap();
@@ -460,6 +505,7 @@ async fn test_execute_habit_tracker_query_custom_range() {
         end_date: Some("2024-01-18".to_string()),
         editable: true,
         show_summary: true,
+        week_start: None,
     };
 
     let response = repo.execute_habit_tracker_query(&query).await.unwrap();
@@ -473,6 +519,39 @@ async fn test_execute_habit_tracker_query_custom_range() {
     assert_eq!(response.habits[0].entries_by_date[0].0, "2024-01-15");
 }
 
+#[tokio::test]
+async fn test_execute_habit_tracker_query_bounds_custom_range() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.create_habit(&CreateHabitRequest {
+        name: "Exercise".to_string(),
+        description: None,
+        habit_type: HabitType::Boolean,
+        unit: None,
+        color: None,
+        target_value: None,
+    }).await.unwrap();
+
+    let query = HabitTrackerQuery {
+        habits: vec![],
+        view: HabitViewType::Heatmap,
+        orientation: HabitTableOrientation::Horizontal,
+        date_range: HabitDateRange::Custom,
+        date: None,
+        start_date: Some("0001-01-01".to_string()),
+        end_date: Some("2024-01-18".to_string()),
+        editable: false,
+        show_summary: false,
+        week_start: None,
+    };
+
+    let response = repo.execute_habit_tracker_query(&query).await.unwrap();
+
+    // The end date is kept and the start moved up to the five year limit
+    assert_eq!(response.date_range_start, "2019-01-15");
+    assert_eq!(response.date_range_end, "2024-01-18");
+}
+
 #[tokio::test]
 async fn test_delete_habit_cascade_entries() {
     let (_pool, repo) = setup_test_repo().await;
@@ -517,3 +596,115 @@ async fn test_delete_habit_cascade_entries() {
         .unwrap();
     assert_eq!(count, 0);
 }
+
+#[tokio::test]
+async fn test_habit_schedule_skips_in_tracker_response() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let habit_id = repo.create_habit(&CreateHabitRequest {
+        name: "Gym".to_string(),
+        description: None,
+        habit_type: HabitType::Boolean,
+        unit: None,
+        color: None,
+        target_value: None,
+    }).await.unwrap();
+
+    let rules = HabitScheduleRules {
+        active_weekdays: vec![1, 2, 3, 4, 5],
+        skip_dates: vec!["2024-01-10".to_string()],
+        freeze_tokens: 0,
+    };
+    repo.set_habit_schedule(habit_id, &rules).await.unwrap();
+
+    let habit = repo.get_habit(habit_id).await.unwrap().unwrap();
+    assert_eq!(habit.schedule, rules);
+
+    // Mon 8th, Tue 9th, (Wed 10th skipped), Thu 11th, Fri 12th, (weekend), Mon 15th
+    for date in ["2024-01-08", "2024-01-09", "2024-01-11", "2024-01-12", "2024-01-15"] {
+        repo.toggle_habit_for_date(habit_id, date).await.unwrap();
+    }
+
+    let query = HabitTrackerQuery {
+        date_range: HabitDateRange::Custom,
+        start_date: Some("2024-01-08".to_string()),
+        end_date: Some("2024-01-15".to_string()),
+        ..Default::default()
+    };
+
+    let response = repo.execute_habit_tracker_query(&query).await.unwrap();
+    let tracked = &response.habits[0];
+
+    assert_eq!(tracked.skipped_dates, vec!["2024-01-10", "2024-01-13", "2024-01-14"]);
+    assert_eq!(tracked.streak.current, 5);
+    assert_eq!(tracked.streak.longest, 5);
+}
+
+#[tokio::test]
+async fn test_execute_habit_tracker_query_heatmap_covers_year() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let habit_id = repo.create_habit(&CreateHabitRequest {
+        name: "Water".to_string(),
+        description: None,
+        habit_type: HabitType::Number,
+        unit: Some("glasses".to_string()),
+        color: None,
+        target_value: Some(8.0),
+    }).await.unwrap();
+
+    repo.log_habit_entry(&LogHabitEntryRequest {
+        habit_id,
+        date: "2024-03-01".to_string(),
+        time: None,
+        value: "6".to_string(),
+        notes: None,
+    }).await.unwrap();
+
+    let query = HabitTrackerQuery {
+        view: HabitViewType::Heatmap,
+        date: Some("2024-06-15".to_string()),
+        ..Default::default()
+    };
+
+    let response = repo.execute_habit_tracker_query(&query).await.unwrap();
+    assert_eq!(response.date_range_start, "2024-01-01");
+    assert_eq!(response.date_range_end, "2024-12-31");
+
+    let heatmap = &response.habits[0].heatmap;
+    assert_eq!(heatmap.len(), 366); // leap year
+    let march_first = heatmap.iter().find(|c| c.date == "2024-03-01").unwrap();
+    assert_eq!(march_first.value, 0.75);
+}
+
+#[tokio::test]
+async fn test_execute_habit_tracker_query_this_week_honors_week_start() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.create_habit(&CreateHabitRequest {
+        name: "Read".to_string(),
+        description: None,
+        habit_type: HabitType::Boolean,
+        unit: None,
+        color: None,
+        target_value: None,
+    }).await.unwrap();
+
+    // Wednesday
+    let query = HabitTrackerQuery {
+        date_range: HabitDateRange::ThisWeek,
+        date: Some("2024-01-10".to_string()),
+        ..Default::default()
+    };
+    let response = repo.execute_habit_tracker_query(&query).await.unwrap();
+    assert_eq!(response.date_range_start, "2024-01-08");
+    assert_eq!(response.date_range_end, "2024-01-14");
+
+    let query = HabitTrackerQuery {
+        week_start: Some(WeekStart::Sunday),
+        ..query
+    };
+    let response = repo.execute_habit_tracker_query(&query).await.unwrap();
+    assert_eq!(response.date_range_start, "2024-01-07");
+    assert_eq!(response.date_range_end, "2024-01-13");
+}
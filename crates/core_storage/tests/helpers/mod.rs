@@ -61,17 +61,31 @@ pub async fn insert_test_property(
     value: &str,
     property_type: &str,
 ) {
-    sqlx::query(
+    let property_id: i64 = sqlx::query_scalar(
         "INSERT INTO properties (note_id, key, value, type, sort_order) 
-         VALUES (?, ?, ?, ?, 0)"
+         VALUES (?, ?, ?, ?, 0) RETURNING id"
     )
     .bind(note_id)
     .bind(key)
     .bind(value)
     .bind(property_type)
-    .execute(pool)
+    .fetch_one(pool)
     .await
     .expect("Failed to insert test property");
+
+    // List items, split like set_property does
+    if property_type == "list" {
+        let items = value.split(',').map(|item| item.trim()).filter(|item| !item.is_empty());
+        for (position, item) in items.enumerate() {
+            sqlx::query("INSERT INTO property_items (property_id, item, position) VALUES (?, ?, ?)")
+                .bind(property_id)
+                .bind(item)
+                .bind(position as i64)
+                .execute(pool)
+                .await
+                .expect("Failed to insert test property item");
+        }
+    }
 }
 
 /// Insert a test tag for a note.
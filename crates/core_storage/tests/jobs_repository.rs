@@ -0,0 +1,131 @@
+//! Tests for the background job queue.
+
+mod helpers;
+
+use helpers::setup_test_repo;
+use shared_types::{ImportVaultRequest, JobKind, JobRequest, JobStatus};
+
+fn export_request() -> JobRequest {
+    JobRequest::JournalExport {
+        start_date: "2024-01-01".to_string(),
+        end_date: "2024-01-31".to_string(),
+        output_path: "/tmp/journal.md".to_string(),
+        options: None,
+    }
+}
+
+fn import_request() -> JobRequest {
+    JobRequest::VaultImport {
+        request: ImportVaultRequest {
+            source_path: "/tmp/obsidian".to_string(),
+            target_subfolder: None,
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_jobs_run_in_order() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let index = repo.create_job(&JobRequest::FullIndex).await.unwrap();
+    let export = repo.create_job(&export_request()).await.unwrap();
+    assert_eq!(index.status, JobStatus::Queued);
+
+    let next = repo.start_next_job().await.unwrap().unwrap();
+    assert_eq!(next.id, index.id);
+    assert_eq!(next.status, JobStatus::Running);
+    assert!(next.started_at.is_some());
+
+    let done = repo
+        .finish_job(index.id, JobStatus::Completed, 3, 3, Some("Indexed 3 notes"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((done.processed, done.total), (3, 3));
+    assert!(done.finished_at.is_some());
+
+    let next = repo.start_next_job().await.unwrap().unwrap();
+    assert_eq!(next.id, export.id);
+    assert_eq!(next.kind, JobKind::JournalExport);
+    assert!(matches!(next.request, JobRequest::JournalExport { ref end_date, .. } if end_date == "2024-01-31"));
+
+    assert!(repo.start_next_job().await.unwrap().is_none());
+
+    let jobs = repo.list_jobs().await.unwrap();
+    let ids: Vec<_> = jobs.iter().map(|j| j.id).collect();
+    assert_eq!(ids, vec![export.id, index.id]);
+}
+
+#[tokio::test]
+async fn test_requeue_interrupted_jobs() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let job = repo.create_job(&JobRequest::FullIndex).await.unwrap();
+    repo.start_next_job().await.unwrap();
+    let import = repo.create_job(&import_request()).await.unwrap();
+    repo.start_next_job().await.unwrap();
+
+    assert_eq!(repo.requeue_interrupted_jobs().await.unwrap(), 1);
+    let job = repo.get_job(job.id).await.unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Queued);
+    assert!(job.started_at.is_none());
+
+    // Importing again would duplicate what was already imported
+    let import = repo.get_job(import.id).await.unwrap().unwrap();
+    assert_eq!(import.status, JobStatus::Failed);
+    assert!(import.finished_at.is_some());
+    assert!(import.message.is_some());
+}
+
+#[tokio::test]
+async fn test_unknown_job_status_is_skipped() {
+    let (pool, repo) = setup_test_repo().await;
+
+    let job = repo.create_job(&JobRequest::FullIndex).await.unwrap();
+    sqlx::query("UPDATE jobs SET status = 'paused' WHERE id = ?")
+        .bind(job.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert!(repo.get_job(job.id).await.unwrap().is_none());
+    assert!(repo.start_next_job().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_retry_only_failed_or_cancelled_jobs() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let failed = repo.create_job(&JobRequest::FullIndex).await.unwrap();
+    let completed = repo.create_job(&JobRequest::FullIndex).await.unwrap();
+    repo.finish_job(failed.id, JobStatus::Failed, 1, 5, Some("disk full"))
+        .await
+        .unwrap();
+    repo.finish_job(completed.id, JobStatus::Completed, 5, 5, None)
+        .await
+        .unwrap();
+
+    let retried = repo.retry_job(failed.id).await.unwrap().unwrap();
+    assert_eq!(retried.status, JobStatus::Queued);
+    assert_eq!(retried.message, None);
+    assert_eq!((retried.processed, retried.total), (0, 0));
+
+    assert!(repo.retry_job(completed.id).await.unwrap().is_none());
+    assert!(repo.retry_job(9999).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_clear_finished_jobs_keeps_queued() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let queued = repo.create_job(&JobRequest::FullIndex).await.unwrap();
+    let cancelled = repo.create_job(&JobRequest::FullIndex).await.unwrap();
+    let job = repo.cancel_queued_job(cancelled.id).await.unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Cancelled);
+    assert!(repo.cancel_queued_job(cancelled.id).await.unwrap().is_none());
+    assert!(repo.has_queued_jobs().await.unwrap());
+
+    assert_eq!(repo.clear_finished_jobs().await.unwrap(), 1);
+    let ids: Vec<_> = repo.list_jobs().await.unwrap().iter().map(|j| j.id).collect();
+    assert_eq!(ids, vec![queued.id]);
+}
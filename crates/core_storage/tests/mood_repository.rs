@@ -0,0 +1,84 @@
+//! Tests for the mood repository.
+
+mod helpers;
+
+use core_storage::StorageError;
+use helpers::setup_test_repo;
+use shared_types::WeekStart;
+
+#[tokio::test]
+async fn test_log_and_get_mood() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.log_mood("2024-03-04", 3, Some("Tired")).await.unwrap();
+    let entry = repo.get_mood("2024-03-04").await.unwrap().unwrap();
+    assert_eq!(entry.score, 3);
+    assert_eq!(entry.note, Some("Tired".to_string()));
+
+    // Logging again for the same date replaces the entry
+    repo.log_mood("2024-03-04", 5, None).await.unwrap();
+    let entry = repo.get_mood("2024-03-04").await.unwrap().unwrap();
+    assert_eq!(entry.score, 5);
+    assert_eq!(entry.note, None);
+
+    repo.delete_mood("2024-03-04").await.unwrap();
+    assert!(repo.get_mood("2024-03-04").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_log_mood_rejects_invalid_dates() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    for date in ["2024-02-30", "2024-13-01", "today", ""] {
+        assert!(
+            matches!(repo.log_mood(date, 3, None).await, Err(StorageError::InvalidDate(_))),
+            "{:?} should be rejected",
+            date
+        );
+    }
+
+    // Dates are stored zero-padded, so they match the range queries
+    repo.log_mood("2024-3-4", 3, None).await.unwrap();
+    assert!(repo.get_mood("2024-03-04").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_get_mood_trend() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    // Monday and Wednesday of one week, Monday of the next
+    repo.log_mood("2024-03-04", 2, None).await.unwrap();
+    repo.log_mood("2024-03-06", 4, None).await.unwrap();
+    repo.log_mood("2024-03-11", 5, None).await.unwrap();
+    // Outside the range
+    repo.log_mood("2024-04-01", 1, None).await.unwrap();
+
+    let trend = repo.get_mood_trend("2024-03-01", "2024-03-31", WeekStart::Monday).await.unwrap();
+
+    assert_eq!(trend.entries.len(), 3);
+    assert_eq!(trend.entries[0].date, "2024-03-04");
+    assert_eq!(trend.min, Some(2));
+    assert_eq!(trend.max, Some(5));
+    assert!((trend.average.unwrap() - 11.0 / 3.0).abs() < 1e-9);
+
+    assert_eq!(trend.weekly.len(), 2);
+    assert_eq!(trend.weekly[0].week_start, "2024-03-04");
+    assert_eq!(trend.weekly[0].count, 2);
+    assert!((trend.weekly[0].average - 3.0).abs() < 1e-9);
+    assert_eq!(trend.weekly[1].week_start, "2024-03-11");
+
+    // Weeks starting on Sunday
+    let trend = repo.get_mood_trend("2024-03-01", "2024-03-31", WeekStart::Sunday).await.unwrap();
+    assert_eq!(trend.weekly[0].week_start, "2024-03-03");
+    assert_eq!(trend.weekly[1].week_start, "2024-03-10");
+}
+
+#[tokio::test]
+async fn test_get_mood_trend_empty() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let trend = repo.get_mood_trend("2024-03-01", "2024-03-31", WeekStart::Monday).await.unwrap();
+    assert!(trend.entries.is_empty());
+    assert_eq!(trend.average, None);
+    assert!(trend.weekly.is_empty());
+}
@@ -108,6 +108,20 @@ async fn test_get_note_hash() {
     assert_eq!(not_found, None);
 }
 
+#[tokio::test]
+async fn test_list_note_hashes() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let b = repo.upsert_note("b.md", Some("B"), "hash-b").await.unwrap();
+    let a = repo.upsert_note("a.md", Some("A"), "hash-a").await.unwrap();
+
+    let hashes = repo.list_note_hashes().await.unwrap();
+    assert_eq!(hashes.len(), 2);
+    assert_eq!((hashes[0].0, hashes[0].1.as_str(), hashes[0].2.as_deref()), (a, "a.md", Some("hash-a")));
+    assert_eq!((hashes[1].0, hashes[1].1.as_str(), hashes[1].2.as_deref()), (b, "b.md", Some("hash-b")));
+    assert!(hashes[0].3.is_some());
+}
+
 #[tokio::test]
 async fn test_list_notes() {
     let (_pool, repo) = setup_test_repo().await;
@@ -174,6 +188,19 @@ async fn test_count_notes() {
     assert_eq!(count, 1);
 }
 
+#[tokio::test]
+async fn test_has_notes_in_folder() {
+    let (_pool, repo) = setup_test_repo().await;
+    repo.upsert_note("projects/v1.2/plan.md", Some("Plan"), "hash1").await.unwrap();
+    repo.upsert_note("my_notes.md", Some("Notes"), "hash2").await.unwrap();
+
+    assert!(repo.has_notes_in_folder("projects").await.unwrap());
+    assert!(repo.has_notes_in_folder("projects/v1.2/").await.unwrap());
+    assert!(!repo.has_notes_in_folder("proj").await.unwrap());
+    assert!(!repo.has_notes_in_folder("my_notes.md").await.unwrap());
+    assert!(!repo.has_notes_in_folder("my%").await.unwrap());
+}
+
 #[tokio::test]
 async fn test_rename_note() {
     let (_pool, repo) = setup_test_repo().await;
@@ -209,7 +236,14 @@ async fn test_index_note() {
         tags: vec!["rust".to_string(), "testing".to_string()],
         todos: vec![],
         links: vec!["other.md".to_string()],
+        embeds: vec![],
         properties: vec![],
+        aliases: vec!["Tested".to_string()],
+        word_count: 0,
+        encrypted: false,
+        math: vec![],
+        footnotes: Default::default(),
+        truncated: false,
     };
     
     // Create the linked note first
@@ -230,6 +264,7 @@ async fn test_index_note() {
     assert_eq!(tags.len(), 2);
     assert!(tags.contains(&"rust".to_string()));
     assert!(tags.contains(&"testing".to_string()));
+    assert_eq!(repo.get_aliases_for_note(note_id).await.unwrap(), vec!["Tested"]);
     
     // Verify backlink was created
     let other_note = repo.get_note_by_path("other.md").await.unwrap();
@@ -237,3 +272,148 @@ async fn test_index_note() {
     assert_eq!(backlinks.len(), 1);
     assert_eq!(backlinks[0].from_note_id, note_id);
 }
+
+#[tokio::test]
+async fn test_index_encrypted_note_excluded_from_search() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let plain = core_index::markdown::parse("# Plain\nshared secret word");
+    let plain_id = repo.index_note("plain.md", "# Plain\nshared secret word", "hash1", &plain)
+        .await
+        .unwrap();
+
+    // Index the note before it was encrypted, then with the flag set
+    let content = "---\nencrypted: true\n---\n# Diary\nshared secret word";
+    let diary_id = repo.index_note("diary.md", "# Diary\nshared secret word", "hash2", &plain)
+        .await
+        .unwrap();
+    let analysis = core_index::markdown::parse(content);
+    repo.index_note("diary.md", content, "hash3", &analysis).await.unwrap();
+
+    assert!(repo.is_note_encrypted(diary_id).await.unwrap());
+    assert!(!repo.is_note_encrypted(plain_id).await.unwrap());
+
+    let results = repo.search("secret", 10).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].note_id, plain_id);
+
+    let needing = repo.get_notes_without_embeddings(10).await.unwrap();
+    assert_eq!(needing, vec![(plain_id, "plain.md".to_string())]);
+}
+
+#[tokio::test]
+async fn test_deferred_fts_catches_up() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let old = "# Recipe\nflour and water";
+    let note_id = repo.index_note("recipe.md", old, "hash1", &core_index::markdown::parse(old))
+        .await
+        .unwrap();
+
+    // A save indexes the note right away, but search keeps the old content
+    let new = "# Recipe\nflour and yeast";
+    repo.index_note_deferring_fts("recipe.md", "hash2", &core_index::markdown::parse(new))
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.notes_pending_fts(10).await.unwrap(),
+        vec![(note_id, "recipe.md".to_string(), "hash2".to_string())]
+    );
+    assert!(repo.search("yeast", 10).await.unwrap().is_empty());
+    let results = repo.search("flour", 10).await.unwrap();
+    assert!(results[0].stale);
+
+    // Content read before a later save doesn't clear the flag
+    assert!(!repo.update_pending_fts(note_id, "hash1", old).await.unwrap());
+    assert!(repo.update_pending_fts(note_id, "hash2", new).await.unwrap());
+
+    assert!(repo.notes_pending_fts(10).await.unwrap().is_empty());
+    assert!(repo.search("water", 10).await.unwrap().is_empty());
+    let results = repo.search("yeast", 10).await.unwrap();
+    assert_eq!(results[0].note_id, note_id);
+    assert!(!results[0].stale);
+}
+
+#[tokio::test]
+async fn test_index_note_redacts_private_sections_from_fts() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let content = "# Journal\nvisible thoughts\n%%private%%\nconfidential diagnosis\n%%end%%\nmore visible text";
+    let analysis = core_index::markdown::parse(content);
+    let note_id = repo.index_note("journal.md", content, "hash1", &analysis)
+        .await
+        .unwrap();
+
+    // The FTS table is contentless, so check what was indexed through search
+    assert!(repo.search("confidential", 10).await.unwrap().is_empty());
+    assert!(repo.search("diagnosis", 10).await.unwrap().is_empty());
+    assert!(repo.search("private", 10).await.unwrap().is_empty());
+
+    let results = repo.search("\"more visible text\"", 10).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].note_id, note_id);
+}
+
+#[tokio::test]
+async fn test_get_note_summary() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    // Linking twice still counts as one linking note
+    let other_content = "# Other\n\nSee [[summary]] and [[summary|again]].";
+    let other = core_index::markdown::parse(other_content);
+    repo.index_note("other.md", other_content, "hash1", &other).await.unwrap();
+
+    let content = "# Summary\n\nLinks to [[other]], [[other]].\n\n- [ ] open task\n- [x] done task\n";
+    let analysis = core_index::markdown::parse(content);
+    let note_id = repo.index_note("summary.md", content, "hash2", &analysis).await.unwrap();
+    // Re-index the first note so its link resolves
+    repo.index_note("other.md", other_content, "hash3", &other).await.unwrap();
+
+    repo.create_schedule_block(Some(note_id), "2024-01-10", "09:00", "10:00", None, None, None, None)
+        .await
+        .unwrap();
+    let latest = repo
+        .create_schedule_block(Some(note_id), "2024-02-01", "14:00", "15:00", None, None, None, None)
+        .await
+        .unwrap();
+
+    let summary = repo.get_note_summary(note_id).await.unwrap().unwrap();
+    assert_eq!(summary.backlink_count, 1);
+    assert_eq!(summary.outgoing_link_count, 1);
+    assert_eq!(summary.open_todo_count, 1);
+    assert_eq!(summary.todo_count, 2);
+    assert_eq!(summary.word_count, 9);
+    assert_eq!(summary.last_schedule_block.map(|b| b.id), Some(latest));
+
+    assert!(repo.get_note_summary(9999).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_index_note_stores_headings_and_switcher_fingerprint_changes() {
+    let (_pool, repo) = setup_test_repo().await;
+    let before = repo.get_switcher_fingerprint().await.unwrap();
+
+    let content = "# Title\n\n## Section\n\ntext\n";
+    let analysis = core_index::markdown::parse(content);
+    let note_id = repo.index_note("note.md", content, "hash1", &analysis).await.unwrap();
+
+    let headings = repo.list_all_headings().await.unwrap();
+    assert_eq!(
+        headings,
+        vec![
+            (note_id, "Title".to_string(), 1),
+            (note_id, "Section".to_string(), 3),
+        ]
+    );
+
+    let indexed = repo.get_switcher_fingerprint().await.unwrap();
+    assert_ne!(before, indexed);
+
+    repo.replace_aliases(note_id, &["Alias".to_string()]).await.unwrap();
+    assert_eq!(repo.list_all_aliases().await.unwrap(), vec![(note_id, "Alias".to_string())]);
+    let aliased = repo.get_switcher_fingerprint().await.unwrap();
+    assert_ne!(indexed, aliased);
+
+    repo.rename_note("note.md", "renamed.md").await.unwrap();
+    assert_ne!(aliased, repo.get_switcher_fingerprint().await.unwrap());
+}
@@ -0,0 +1,116 @@
+//! Tests for the week overview.
+
+mod helpers;
+
+use chrono::NaiveDate;
+use core_index::ParsedTodo;
+use helpers::{insert_test_note, setup_test_repo};
+use shared_types::{CreateHabitRequest, HabitScheduleRules, HabitType};
+
+fn todo(description: &str, line_number: usize, due_date: Option<&str>) -> ParsedTodo {
+    ParsedTodo {
+        description: description.to_string(),
+        raw_text: format!("- [ ] {}", description),
+        completed: false,
+        line_number,
+        heading_path: None,
+        context: None,
+        priority: None,
+        due_date: due_date.map(|d| d.to_string()),
+        estimate_minutes: None,
+    }
+}
+
+#[tokio::test]
+async fn test_week_overview_combines_schedule_tasks_and_habits() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let project = insert_test_note(pool, "project.md", Some("Project")).await;
+    let other = insert_test_note(pool, "other.md", Some("Other")).await;
+
+    repo.replace_todos(project, &[
+        todo("Undated project task", 1, None),
+        todo("Due Wednesday", 2, Some("2024-03-06")),
+    ]).await.unwrap();
+    repo.replace_todos(other, &[
+        todo("Undated unscheduled task", 1, None),
+        todo("Due next week", 2, Some("2024-03-12")),
+    ]).await.unwrap();
+
+    // Project note scheduled on Tuesday
+    repo.create_schedule_block(Some(project), "2024-03-05", "09:00:00", "10:00:00", Some("Project"), None, None, None)
+        .await
+        .unwrap();
+
+    let habit_id = repo.create_habit(&CreateHabitRequest {
+        name: "Stretch".to_string(),
+        description: None,
+        habit_type: HabitType::Boolean,
+        unit: None,
+        color: None,
+        target_value: None,
+    }).await.unwrap();
+    repo.set_habit_schedule(habit_id, &HabitScheduleRules {
+        active_weekdays: vec![1, 2, 3, 4, 5],
+        ..Default::default()
+    }).await.unwrap();
+    repo.toggle_habit_for_date(habit_id, "2024-03-04").await.unwrap();
+
+    let start = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let week = repo.get_week_overview(start).await.unwrap();
+
+    assert_eq!(week.days.len(), 7);
+    assert_eq!(week.end_date, NaiveDate::from_ymd_opt(2024, 3, 10).unwrap());
+
+    let monday = &week.days[0];
+    assert!(monday.habits[0].done);
+    assert!(monday.tasks.is_empty());
+
+    let tuesday = &week.days[1];
+    assert_eq!(tuesday.blocks.len(), 1);
+    assert_eq!(tuesday.note_count, 1);
+    assert_eq!(tuesday.tasks.len(), 1);
+    assert_eq!(tuesday.tasks[0].todo.description, "Undated project task");
+    assert!(!tuesday.habits[0].done);
+
+    let wednesday = &week.days[2];
+    assert_eq!(wednesday.tasks.len(), 1);
+    assert_eq!(wednesday.tasks[0].todo.description, "Due Wednesday");
+
+    let saturday = &week.days[5];
+    assert!(saturday.habits[0].skipped);
+
+    // A single day is the same as in the week
+    let day = repo.get_day_overview(tuesday.date).await.unwrap();
+    assert_eq!(day.date, tuesday.date);
+    assert_eq!(day.blocks.len(), 1);
+    assert_eq!(day.tasks.len(), 1);
+    assert_eq!(day.note_count, 1);
+    assert_eq!(day.habits.len(), 1);
+}
+
+#[tokio::test]
+async fn test_week_overview_keeps_scheduled_tasks_among_many() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let backlog = insert_test_note(pool, "backlog.md", Some("Backlog")).await;
+    let project = insert_test_note(pool, "project.md", Some("Project")).await;
+
+    // More open tasks elsewhere than the overview loads
+    let many: Vec<ParsedTodo> = (0..1100).map(|i| todo(&format!("Backlog task {}", i), i + 1, None)).collect();
+    repo.replace_todos(backlog, &many).await.unwrap();
+    repo.replace_todos(project, &[todo("Undated project task", 1, None)]).await.unwrap();
+    // Older than the others, so it comes last among open tasks
+    sqlx::query("UPDATE todos SET created_at = '2020-01-01T00:00:00Z' WHERE note_id = ?")
+        .bind(project)
+        .execute(pool)
+        .await
+        .unwrap();
+    repo.create_schedule_block(Some(project), "2024-03-05", "09:00:00", "10:00:00", Some("Project"), None, None, None)
+        .await
+        .unwrap();
+
+    let day = repo.get_day_overview(NaiveDate::from_ymd_opt(2024, 3, 5).unwrap()).await.unwrap();
+    assert_eq!(day.tasks.len(), 1);
+    assert_eq!(day.tasks[0].todo.description, "Undated project task");
+}
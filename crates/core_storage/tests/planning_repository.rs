@@ -0,0 +1,150 @@
+//! Tests for automatic day planning.
+
+mod helpers;
+
+use chrono::{NaiveDate, NaiveTime};
+use core_index::ParsedTodo;
+use helpers::{insert_test_note, setup_test_repo};
+use shared_types::PlanDayConstraints;
+
+fn todo(description: &str, line_number: usize, priority: Option<&str>, estimate_minutes: Option<i32>) -> ParsedTodo {
+    ParsedTodo {
+        description: description.to_string(),
+        raw_text: format!("- [ ] {}", description),
+        completed: false,
+        line_number,
+        heading_path: None,
+        context: None,
+        priority: priority.map(|p| p.to_string()),
+        due_date: None,
+        estimate_minutes,
+    }
+}
+
+fn constraints(create_blocks: bool) -> PlanDayConstraints {
+    PlanDayConstraints {
+        work_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        work_end: NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+        default_task_minutes: None,
+        buffer_minutes: None,
+        context: None,
+        max_tasks: None,
+        create_blocks,
+    }
+}
+
+#[tokio::test]
+async fn test_plan_day_fills_free_time_by_priority() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note_id = insert_test_note(pool, "tasks.md", Some("Tasks")).await;
+
+    repo.replace_todos(
+        note_id,
+        &[
+            todo("Low task", 1, Some("low"), None),
+            todo("Write report", 2, Some("high"), Some(60)),
+            todo("Huge task", 3, Some("medium"), Some(180)),
+        ],
+    )
+    .await
+    .unwrap();
+
+    // Existing meeting 10:00-10:30
+    repo.create_schedule_block(None, "2024-03-04", "10:00:00", "10:30:00", Some("Meeting"), None, None, None)
+        .await
+        .unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let plan = repo.plan_day(date, &constraints(false)).await.unwrap();
+
+    assert_eq!(plan.planned.len(), 2);
+    assert_eq!(plan.planned[0].description, "Write report");
+    assert_eq!(plan.planned[0].start_time.to_string(), "09:00:00");
+    assert_eq!(plan.planned[0].end_time.to_string(), "10:00:00");
+    assert_eq!(plan.planned[0].estimated_minutes, Some(60));
+    assert_eq!(plan.planned[1].description, "Low task");
+    assert_eq!(plan.planned[1].start_time.to_string(), "10:30:00");
+    assert_eq!(plan.planned[1].estimated_minutes, None);
+    assert_eq!(plan.unplanned_todo_ids.len(), 1);
+    assert_eq!(plan.free_minutes, 0);
+
+    // Proposal only: no blocks created beyond the meeting
+    let blocks = repo.get_schedule_blocks_for_date("2024-03-04").await.unwrap();
+    assert_eq!(blocks.len(), 1);
+}
+
+#[tokio::test]
+async fn test_plan_day_creates_blocks() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note_id = insert_test_note(pool, "tasks.md", Some("Tasks")).await;
+
+    repo.replace_todos(note_id, &[todo("Call supplier", 1, None, Some(15))])
+        .await
+        .unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let plan = repo.plan_day(date, &constraints(true)).await.unwrap();
+
+    assert_eq!(plan.planned.len(), 1);
+    let block_id = plan.planned[0].block_id.expect("block should be created");
+
+    let block = repo.get_schedule_block(block_id).await.unwrap().unwrap();
+    assert_eq!(block.note_id, Some(note_id));
+    assert_eq!(block.start_time.to_string(), "09:00:00");
+    assert_eq!(block.end_time.to_string(), "09:15:00");
+    assert_eq!(plan.free_minutes, 105);
+}
+
+#[tokio::test]
+async fn test_get_workload_flags_overcommitted_days() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let work_note = insert_test_note(pool, "work.md", Some("Work")).await;
+    let project_note = insert_test_note(pool, "project.md", Some("Project")).await;
+
+    let mut due_today = todo("Big report", 1, None, Some(300));
+    due_today.due_date = Some("2024-03-04".to_string());
+    let mut due_later = todo("Quick fix", 2, None, None);
+    due_later.due_date = Some("2024-03-05".to_string());
+    repo.replace_todos(work_note, &[due_today, due_later]).await.unwrap();
+
+    // Undated task counts towards the day its note is scheduled
+    repo.replace_todos(project_note, &[todo("Outline", 1, None, Some(60))])
+        .await
+        .unwrap();
+    repo.create_schedule_block(Some(project_note), "2024-03-05", "09:00:00", "10:00:00", None, None, None, None)
+        .await
+        .unwrap();
+
+    // Unlinked meeting takes 4 hours out of March 4th
+    repo.create_schedule_block(None, "2024-03-04", "09:00:00", "13:00:00", Some("Workshop"), None, None, None)
+        .await
+        .unwrap();
+
+    let workload = repo
+        .get_workload(
+            NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 6).unwrap(),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(workload.len(), 3);
+
+    assert_eq!(workload[0].estimated_minutes, 300);
+    assert_eq!(workload[0].available_minutes, 240);
+    assert!(workload[0].overcommitted);
+
+    assert_eq!(workload[1].estimated_minutes, 60);
+    assert_eq!(workload[1].task_count, 2);
+    assert_eq!(workload[1].unestimated_count, 1);
+    // The project block is reserved for its own tasks, not busy time
+    assert_eq!(workload[1].available_minutes, 480);
+    assert!(!workload[1].overcommitted);
+
+    assert_eq!(workload[2].task_count, 0);
+}
@@ -3,6 +3,7 @@
 mod helpers;
 
 use helpers::{insert_test_note, setup_test_repo};
+use shared_types::PropertyUpdate;
 
 #[tokio::test]
 async fn test_set_property_insert() {
@@ -152,11 +153,13 @@ async fn test_replace_properties() {
             key: "author".to_string(),
             value: Some("Alice".to_string()),
             property_type: "text".to_string(),
+            items: vec![],
         },
         ParsedProperty {
             key: "date".to_string(),
             value: Some("2024-01-01".to_string()),
             property_type: "date".to_string(),
+            items: vec![],
         },
     ];
     
@@ -216,3 +219,159 @@ async fn test_cascade_delete() {
         .unwrap();
     assert_eq!(count, 0);
 }
+
+#[tokio::test]
+async fn test_typed_property_values() {
+    let (_pool, repo) = setup_test_repo().await;
+    let note_id = insert_test_note(repo.pool(), "typed.md", Some("Typed")).await;
+
+    repo.set_property(note_id, "estimate", Some("2.5"), Some("number")).await.unwrap();
+    repo.set_property(note_id, "due", Some("2024-01-15T09:30"), Some("date")).await.unwrap();
+    repo.set_property(note_id, "done", Some("Yes"), Some("boolean")).await.unwrap();
+    repo.set_property(note_id, "count", Some("many"), Some("number")).await.unwrap();
+
+    let pool = repo.pool();
+    assert_eq!(typed_values(pool, note_id, "estimate").await, (None, Some(2.5), None, None));
+    assert_eq!(typed_values(pool, note_id, "due").await, (None, None, Some("2024-01-15".to_string()), None));
+    assert_eq!(typed_values(pool, note_id, "done").await, (None, None, None, Some(true)));
+    // Values that don't parse as their type are kept as text
+    assert_eq!(typed_values(pool, note_id, "count").await, (Some("many".to_string()), None, None, None));
+
+    // Renaming a value updates its typed column
+    repo.rename_property_value("estimate", "2.5", "4").await.unwrap();
+    assert_eq!(typed_values(pool, note_id, "estimate").await, (None, Some(4.0), None, None));
+}
+
+#[tokio::test]
+async fn test_list_property_items() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note1 = insert_test_note(pool, "a.md", None).await;
+    let note2 = insert_test_note(pool, "b.md", None).await;
+
+    let items = vec!["Smith, John".to_string(), "Doe".to_string()];
+    repo.set_list_property(note1, "authors", &items).await.unwrap();
+    repo.set_property(note2, "authors", Some("Doe, Roe"), Some("list")).await.unwrap();
+
+    let prop = repo.get_property(note1, "authors").await.unwrap().unwrap();
+    assert_eq!(prop.items, items);
+    assert_eq!(
+        repo.get_list_property_values("authors").await.unwrap(),
+        vec!["Doe", "Roe", "Smith, John"]
+    );
+
+    // Renaming an item renames it in every list and rebuilds the value
+    let (count, notes) = repo.rename_property_value("authors", "Doe", "Dough").await.unwrap();
+    assert_eq!((count, notes), (2, 2));
+    let prop = repo.get_property(note2, "authors").await.unwrap().unwrap();
+    assert_eq!(prop.items, vec!["Dough", "Roe"]);
+    assert_eq!(prop.value.as_deref(), Some("Dough, Roe"));
+
+    // Items go with their property
+    repo.delete_property(note1, "authors").await.unwrap();
+    let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM property_items")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+/// The typed value columns of a property.
+async fn typed_values(
+    pool: &sqlx::SqlitePool,
+    note_id: i64,
+    key: &str,
+) -> (Option<String>, Option<f64>, Option<String>, Option<bool>) {
+    sqlx::query_as("SELECT value_text, value_number, value_date, value_bool FROM properties WHERE note_id = ? AND key = ?")
+        .bind(note_id)
+        .bind(key)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_css_classes() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let listed = insert_test_note(pool, "listed.md", Some("Listed")).await;
+    let text = insert_test_note(pool, "text.md", Some("Text")).await;
+    let none = insert_test_note(pool, "none.md", Some("None")).await;
+
+    let items = vec!["wide".to_string(), "slides".to_string(), "wide".to_string()];
+    repo.set_list_property(listed, "cssclasses", &items).await.unwrap();
+    repo.set_property(text, "cssclasses", Some("wide, no\"quotes  cards"), Some("text")).await.unwrap();
+
+    assert_eq!(repo.get_css_classes(listed).await.unwrap(), vec!["wide", "slides"]);
+    assert_eq!(repo.get_css_classes(text).await.unwrap(), vec!["wide", "cards"]);
+    assert!(repo.get_css_classes(none).await.unwrap().is_empty());
+
+    // Exposed on the note itself
+    assert_eq!(repo.get_note(listed).await.unwrap().cssclasses, vec!["wide", "slides"]);
+    assert_eq!(repo.get_note_by_path("text.md").await.unwrap().cssclasses, vec!["wide", "cards"]);
+}
+
+#[tokio::test]
+async fn test_batch_set_properties() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let first = insert_test_note(pool, "first.md", Some("First")).await;
+    let second = insert_test_note(pool, "second.md", Some("Second")).await;
+    repo.set_property(first, "status", Some("draft"), Some("text")).await.unwrap();
+
+    let update = |note_id: i64, key: &str, value: Option<&str>, property_type: Option<&str>| PropertyUpdate {
+        note_id,
+        key: key.to_string(),
+        value: value.map(String::from),
+        property_type: property_type.map(String::from),
+        items: None,
+    };
+    let result = repo
+        .batch_set_properties(&[
+            update(first, "status", None, None),
+            update(first, "rating", Some("4"), Some("number")),
+            update(second, "tags", Some("a, b"), Some("list")),
+            PropertyUpdate {
+                items: Some(vec!["x, y".to_string()]),
+                ..update(second, "authors", None, None)
+            },
+            // Removing a property the note doesn't have changes nothing
+            update(second, "status", None, None),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(result.affected_count, 4);
+    assert_eq!(result.notes_affected, 2);
+    assert!(repo.get_property(first, "status").await.unwrap().is_none());
+    assert_eq!(repo.get_property(first, "rating").await.unwrap().unwrap().value.as_deref(), Some("4"));
+    assert_eq!(repo.get_property(second, "tags").await.unwrap().unwrap().items, vec!["a", "b"]);
+    assert_eq!(repo.get_property(second, "authors").await.unwrap().unwrap().items, vec!["x, y"]);
+}
+
+#[tokio::test]
+async fn test_batch_set_properties_is_atomic() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note_id = insert_test_note(pool, "note.md", Some("Note")).await;
+
+    let updates = [
+        PropertyUpdate {
+            note_id,
+            key: "status".to_string(),
+            value: Some("done".to_string()),
+            property_type: Some("text".to_string()),
+            items: None,
+        },
+        // The note doesn't exist, so the whole batch fails
+        PropertyUpdate {
+            note_id: note_id + 100,
+            key: "status".to_string(),
+            value: Some("done".to_string()),
+            property_type: Some("text".to_string()),
+            items: None,
+        },
+    ];
+    assert!(repo.batch_set_properties(&updates).await.is_err());
+    assert!(repo.get_property(note_id, "status").await.unwrap().is_none());
+}
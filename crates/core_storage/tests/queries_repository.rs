@@ -5,7 +5,8 @@ mod helpers;
 use core_index::markdown::ParsedTodo;
 use helpers::{insert_test_note, insert_test_property, insert_test_tag, setup_test_repo};
 use shared_types::{
-    FilterMatchMode, PropertyFilter, PropertyOperator, QueryRequest, QueryResultType,
+    FilterMatchMode, PropertyFilter, PropertyOperator, QueryRequest, QueryResponse, QueryResultType, QuerySort,
+    SortDirection, TagMeta,
 };
 
 #[tokio::test]
@@ -25,6 +26,7 @@ async fn test_run_query_no_filters() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -59,6 +61,7 @@ async fn test_run_query_property_equals() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -91,6 +94,7 @@ async fn test_run_query_property_exists() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -122,6 +126,7 @@ async fn test_run_query_property_not_exists() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -165,6 +170,7 @@ async fn test_run_query_match_mode_all() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -210,6 +216,7 @@ async fn test_run_query_match_mode_any() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -243,6 +250,7 @@ async fn test_run_query_tags_filter() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -282,6 +290,7 @@ async fn test_run_query_tags_contains_any() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -317,6 +326,7 @@ async fn test_run_query_tags_contains_all() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -326,6 +336,50 @@ async fn test_run_query_tags_contains_all() {
     assert_eq!(response.results[0].note.as_ref().unwrap().path, "both.md");
 }
 
+#[tokio::test]
+async fn test_run_query_tags_filter_honors_aliases() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let note1 = insert_test_note(pool, "task.md", Some("Task")).await;
+    insert_test_tag(pool, note1, "task").await;
+    let note2 = insert_test_note(pool, "todo.md", Some("Todo")).await;
+    insert_test_tag(pool, note2, "todo").await;
+    let _note3 = insert_test_note(pool, "other.md", Some("Other")).await;
+
+    repo.set_tag_meta(&TagMeta {
+        tag: "todo".to_string(),
+        alias_of: Some("task".to_string()),
+        description: None,
+        color: None,
+    })
+    .await
+    .unwrap();
+
+    // Filtering on the tag or its alias finds both notes
+    for (operator, value, expected) in [
+        (PropertyOperator::Equals, "task", 2),
+        (PropertyOperator::Equals, "todo", 2),
+        (PropertyOperator::ContainsAny, "todo,work", 2),
+        (PropertyOperator::NotEquals, "task", 1),
+    ] {
+        let request = QueryRequest {
+            filters: vec![PropertyFilter {
+                key: "_tags".to_string(),
+                operator: operator.clone(),
+                value: Some(value.to_string()),
+            }],
+            match_mode: FilterMatchMode::All,
+            result_type: QueryResultType::Notes,
+            include_completed: false,
+            limit: Some(100),
+            sort: None,
+        };
+        let response = repo.run_query(&request).await.unwrap();
+        assert_eq!(response.total_count, expected, "{:?} {}", operator, value);
+    }
+}
+
 #[tokio::test]
 async fn test_run_query_path_filter() {
     let (_pool, repo) = setup_test_repo().await;
@@ -347,6 +401,7 @@ async fn test_run_query_path_filter() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -380,6 +435,7 @@ async fn test_run_query_property_contains() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -409,6 +465,7 @@ async fn test_run_query_result_type_tasks() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
         ParsedTodo {
             description: "Task 2".to_string(),
@@ -419,6 +476,7 @@ async fn test_run_query_result_type_tasks() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
     ];
     repo.replace_todos(note1, &todos).await.unwrap();
@@ -434,6 +492,7 @@ async fn test_run_query_result_type_tasks() {
         result_type: QueryResultType::Tasks,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -446,6 +505,66 @@ async fn test_run_query_result_type_tasks() {
     assert!(response.results[1].task.is_some());
 }
 
+#[tokio::test]
+async fn test_query_embedded_tasks() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let dashboard = insert_test_note(pool, "dashboard.md", Some("Dashboard")).await;
+    insert_test_property(pool, dashboard, "project", "alpha", "text").await;
+    let sub = insert_test_note(pool, "sub.md", Some("Sub")).await;
+    let todo = |description: &str, completed: bool| ParsedTodo {
+        description: description.to_string(),
+        raw_text: format!("- [ ] {}", description),
+        completed,
+        line_number: 1,
+        heading_path: None,
+        context: None,
+        priority: None,
+        due_date: None,
+        estimate_minutes: None,
+    };
+    repo.replace_todos(sub, &[todo("Open", false), todo("Done", true)]).await.unwrap();
+
+    let filters = vec![PropertyFilter {
+        key: "project".to_string(),
+        operator: PropertyOperator::Equals,
+        value: Some("alpha".to_string()),
+    }];
+    assert_eq!(repo.matching_note_ids(&filters, &FilterMatchMode::All).await.unwrap(), [dashboard]);
+
+    let request = QueryRequest {
+        filters,
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Tasks,
+        include_completed: false,
+        limit: Some(100),
+        sort: None,
+    };
+    let (tasks, total) = repo
+        .query_tasks_with_embedded(&request, &[dashboard], &[(sub, "dashboard.md".to_string())])
+        .await
+        .unwrap();
+
+    // Completion filters apply, and tasks keep their own note
+    assert_eq!((tasks.len(), total), (1, 1));
+    assert_eq!(tasks[0].todo.description, "Open");
+    assert_eq!(tasks[0].note_path, "sub.md");
+    assert_eq!(tasks[0].embedded_in.as_deref(), Some("dashboard.md"));
+
+    // The total counts the tasks past the limit
+    let request = QueryRequest {
+        include_completed: true,
+        limit: Some(1),
+        ..request
+    };
+    let (tasks, total) = repo
+        .query_tasks_with_embedded(&request, &[dashboard], &[(sub, "dashboard.md".to_string())])
+        .await
+        .unwrap();
+    assert_eq!((tasks.len(), total), (1, 2));
+}
+
 #[tokio::test]
 async fn test_run_query_date_operators() {
     let (_pool, repo) = setup_test_repo().await;
@@ -472,6 +591,7 @@ async fn test_run_query_date_operators() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response = repo.run_query(&request).await.unwrap();
@@ -491,6 +611,7 @@ async fn test_run_query_date_operators() {
         result_type: QueryResultType::Notes,
         include_completed: false,
         limit: Some(100),
+        sort: None,
     };
 
     let response2 = repo.run_query(&request2).await.unwrap();
@@ -498,3 +619,363 @@ async fn test_run_query_date_operators() {
     // Should return note2 and note3
     assert_eq!(response2.total_count, 2);
 }
+
+#[tokio::test]
+async fn test_run_query_date_range_on_same_property() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    for (path, due) in [("a.md", "2024-01-10"), ("b.md", "2024-01-15"), ("c.md", "2024-01-20")] {
+        let note = insert_test_note(pool, path, None).await;
+        insert_test_property(pool, note, "due_date", due, "text").await;
+    }
+
+    // Two filters on the same key must both hold for the same value
+    let request = QueryRequest {
+        filters: vec![
+            PropertyFilter {
+                key: "due_date".to_string(),
+                operator: PropertyOperator::DateOnOrAfter,
+                value: Some("2024-01-12".to_string()),
+            },
+            PropertyFilter {
+                key: "due_date".to_string(),
+                operator: PropertyOperator::DateBefore,
+                value: Some("2024-01-20".to_string()),
+            },
+        ],
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Notes,
+        include_completed: false,
+        limit: Some(100),
+        sort: None,
+    };
+
+    let response = repo.run_query(&request).await.unwrap();
+
+    assert_eq!(response.total_count, 1);
+    assert_eq!(response.results[0].note.as_ref().unwrap().path, "b.md");
+}
+
+#[tokio::test]
+async fn test_run_query_match_mode_all_mixed_filters() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let note1 = insert_test_note(pool, "projects/match.md", Some("Match")).await;
+    insert_test_property(pool, note1, "status", "active", "text").await;
+    insert_test_property(pool, note1, "topics", "rust, sqlite, search", "list").await;
+    insert_test_tag(pool, note1, "work").await;
+
+    // Archived, so excluded by the NotExists filter
+    let note2 = insert_test_note(pool, "projects/archived.md", Some("Archived")).await;
+    insert_test_property(pool, note2, "status", "active", "text").await;
+    insert_test_property(pool, note2, "topics", "rust, sqlite", "list").await;
+    insert_test_property(pool, note2, "archived", "true", "checkbox").await;
+    insert_test_tag(pool, note2, "work").await;
+
+    // Missing one of the topics
+    let note3 = insert_test_note(pool, "projects/partial.md", Some("Partial")).await;
+    insert_test_property(pool, note3, "status", "active", "text").await;
+    insert_test_property(pool, note3, "topics", "rust", "list").await;
+    insert_test_tag(pool, note3, "work").await;
+
+    // Outside the folder
+    let note4 = insert_test_note(pool, "inbox/match.md", Some("Inbox")).await;
+    insert_test_property(pool, note4, "status", "active", "text").await;
+    insert_test_property(pool, note4, "topics", "rust, sqlite", "list").await;
+    insert_test_tag(pool, note4, "work").await;
+
+    let request = QueryRequest {
+        filters: vec![
+            PropertyFilter {
+                key: "_path".to_string(),
+                operator: PropertyOperator::StartsWith,
+                value: Some("projects".to_string()),
+            },
+            PropertyFilter {
+                key: "status".to_string(),
+                operator: PropertyOperator::Equals,
+                value: Some("active".to_string()),
+            },
+            PropertyFilter {
+                key: "topics".to_string(),
+                operator: PropertyOperator::ContainsAll,
+                value: Some("rust, sqlite".to_string()),
+            },
+            PropertyFilter {
+                key: "archived".to_string(),
+                operator: PropertyOperator::NotExists,
+                value: None,
+            },
+            PropertyFilter {
+                key: "_tags".to_string(),
+                operator: PropertyOperator::Equals,
+                value: Some("work".to_string()),
+            },
+        ],
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Notes,
+        include_completed: false,
+        limit: Some(100),
+        sort: None,
+    };
+
+    let response = repo.run_query(&request).await.unwrap();
+
+    assert_eq!(response.total_count, 1);
+    assert_eq!(response.results[0].note.as_ref().unwrap().path, "projects/match.md");
+}
+
+#[tokio::test]
+async fn test_run_query_property_contains_any() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let note1 = insert_test_note(pool, "a.md", None).await;
+    insert_test_property(pool, note1, "topics", "rust, sqlite", "list").await;
+
+    let note2 = insert_test_note(pool, "b.md", None).await;
+    insert_test_property(pool, note2, "topics", "gardening", "list").await;
+
+    let note3 = insert_test_note(pool, "c.md", None).await;
+    insert_test_property(pool, note3, "topics", "cooking, search", "list").await;
+
+    let request = QueryRequest {
+        filters: vec![PropertyFilter {
+            key: "topics".to_string(),
+            operator: PropertyOperator::ContainsAny,
+            value: Some("sqlite, search".to_string()),
+        }],
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Notes,
+        include_completed: false,
+        limit: Some(100),
+        sort: None,
+    };
+
+    let response = repo.run_query(&request).await.unwrap();
+
+    let paths: Vec<_> = response.results.iter().map(|r| r.note.as_ref().unwrap().path.as_str()).collect();
+    assert_eq!(paths, vec!["a.md", "c.md"]);
+}
+
+#[tokio::test]
+async fn test_run_query_list_items_with_commas() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let note1 = insert_test_note(pool, "a.md", None).await;
+    let authors = vec!["Smith, John".to_string(), "Doe".to_string()];
+    repo.set_list_property(note1, "authors", &authors).await.unwrap();
+
+    let note2 = insert_test_note(pool, "b.md", None).await;
+    repo.set_list_property(note2, "authors", &["Johnson".to_string()]).await.unwrap();
+
+    let query = |operator, value: &str| QueryRequest {
+        filters: vec![PropertyFilter {
+            key: "authors".to_string(),
+            operator,
+            value: Some(value.to_string()),
+        }],
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Notes,
+        include_completed: false,
+        limit: Some(100),
+        sort: None,
+    };
+    let paths = |response: QueryResponse| -> Vec<String> {
+        response.results.iter().map(|r| r.note.as_ref().unwrap().path.clone()).collect()
+    };
+
+    // Items match whole, not as substrings of the joined value
+    let response = repo.run_query(&query(PropertyOperator::ContainsAny, r#"["doe", "John"]"#)).await.unwrap();
+    assert_eq!(paths(response), vec!["a.md"]);
+
+    let response = repo.run_query(&query(PropertyOperator::ContainsAll, r#"["Doe", "Smith"]"#)).await.unwrap();
+    assert!(paths(response).is_empty());
+
+    // Items with commas are given as a JSON array
+    let response = repo.run_query(&query(PropertyOperator::ContainsAll, r#"["Smith, John", "Doe"]"#)).await.unwrap();
+    assert_eq!(paths(response), vec!["a.md"]);
+
+    // Plain values are split on commas
+    let response = repo.run_query(&query(PropertyOperator::ContainsAny, "johnson, nobody")).await.unwrap();
+    assert_eq!(paths(response), vec!["b.md"]);
+
+    // Text properties still match anywhere in their value
+    let note3 = insert_test_note(pool, "c.md", None).await;
+    repo.set_property(note3, "authors", Some("Jane Doe and others"), Some("text")).await.unwrap();
+    let response = repo.run_query(&query(PropertyOperator::ContainsAny, "doe")).await.unwrap();
+    assert_eq!(paths(response), vec!["a.md", "c.md"]);
+
+    let props = repo.get_properties_for_note(note1).await.unwrap();
+    assert_eq!(props[0].items, authors);
+    assert_eq!(props[0].value.as_deref(), Some("Smith, John, Doe"));
+}
+
+#[tokio::test]
+async fn test_run_query_number_operators_and_sort() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    // As text, "9" > "10" and "2024-1-5" doesn't compare with "2024-01-10"
+    for (path, estimate, due) in [("a.md", "9", "2024-03-01"), ("b.md", "10", "2024-01-10"), ("c.md", "2.5", "2024-02-01")] {
+        let id = insert_test_note(pool, path, None).await;
+        repo.set_property(id, "estimate", Some(estimate), Some("number")).await.unwrap();
+        repo.set_property(id, "due", Some(due), Some("date")).await.unwrap();
+    }
+    insert_test_note(pool, "d.md", None).await;
+
+    let paths = |response: shared_types::QueryResponse| -> Vec<String> {
+        response.results.iter().map(|r| r.note.as_ref().unwrap().path.clone()).collect()
+    };
+    let mut request = QueryRequest {
+        filters: vec![PropertyFilter {
+            key: "estimate".to_string(),
+            operator: PropertyOperator::GreaterThan,
+            value: Some("5".to_string()),
+        }],
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Notes,
+        include_completed: false,
+        limit: Some(100),
+        sort: Some(QuerySort {
+            property: "estimate".to_string(),
+            direction: SortDirection::Desc,
+        }),
+    };
+    assert_eq!(paths(repo.run_query(&request).await.unwrap()), vec!["b.md", "a.md"]);
+
+    request.filters[0].operator = PropertyOperator::LessOrEqual;
+    request.filters[0].value = Some("9".to_string());
+    assert_eq!(paths(repo.run_query(&request).await.unwrap()), vec!["a.md", "c.md"]);
+
+    // Notes without the sort property come last
+    request.filters.clear();
+    request.sort = Some(QuerySort {
+        property: "due".to_string(),
+        direction: SortDirection::Asc,
+    });
+    assert_eq!(paths(repo.run_query(&request).await.unwrap()), vec!["b.md", "c.md", "a.md", "d.md"]);
+}
+
+#[tokio::test]
+async fn test_run_query_completed_filter() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let todo = |description: &str, completed: bool, line_number: usize| ParsedTodo {
+        description: description.to_string(),
+        raw_text: String::new(),
+        completed,
+        line_number,
+        heading_path: None,
+        context: None,
+        priority: None,
+        due_date: None,
+        estimate_minutes: None,
+    };
+    let note1 = insert_test_note(pool, "a.md", Some("A")).await;
+    let note2 = insert_test_note(pool, "b.md", Some("B")).await;
+    repo.replace_todos(note1, &[todo("Old", true, 1), todo("Recent", true, 2), todo("Open", false, 3)])
+        .await
+        .unwrap();
+    repo.replace_todos(note2, &[todo("Older", true, 1)]).await.unwrap();
+    for (description, completed_at) in [
+        ("Old", "2025-01-02T10:00:00+00:00"),
+        ("Recent", "2025-01-08T10:00:00+00:00"),
+        ("Older", "2024-12-01T10:00:00+00:00"),
+    ] {
+        sqlx::query("UPDATE todos SET completed_at = ? WHERE description = ?")
+            .bind(completed_at)
+            .bind(description)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    // Reindexing keeps the completion times
+    repo.replace_todos(note1, &[todo("Old", true, 1), todo("Recent", true, 2), todo("Open", false, 3)])
+        .await
+        .unwrap();
+
+    let completed = |operator, value: &str| PropertyFilter {
+        key: "_completed".to_string(),
+        operator,
+        value: Some(value.to_string()),
+    };
+    let request = QueryRequest {
+        filters: vec![
+            completed(PropertyOperator::DateOnOrAfter, "2025-01-06"),
+            completed(PropertyOperator::DateOnOrBefore, "2025-01-12"),
+        ],
+        match_mode: FilterMatchMode::All,
+        result_type: QueryResultType::Tasks,
+        include_completed: true,
+        limit: Some(100),
+        sort: None,
+    };
+    let response = repo.run_query(&request).await.unwrap();
+    let descriptions: Vec<&str> = response
+        .results
+        .iter()
+        .map(|r| r.task.as_ref().unwrap().todo.description.as_str())
+        .collect();
+    assert_eq!(descriptions, vec!["Recent"]);
+
+    // Tasks already completed when first indexed have no completion time;
+    // a task completed since the last index is stamped with today's date
+    let completed_at = |description: &'static str| async move {
+        sqlx::query_scalar::<_, Option<String>>("SELECT completed_at FROM todos WHERE description = ?")
+            .bind(description)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    };
+    let note3 = insert_test_note(pool, "c.md", Some("C")).await;
+    repo.replace_todos(note3, &[todo("Imported", true, 1), todo("Later", false, 2)])
+        .await
+        .unwrap();
+    assert_eq!(completed_at("Imported").await, None);
+    repo.replace_todos(note3, &[todo("Imported", true, 1), todo("Later", true, 2)])
+        .await
+        .unwrap();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    assert!(completed_at("Later").await.unwrap().starts_with(&today));
+}
+
+#[tokio::test]
+async fn test_get_property_table() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+
+    let book = insert_test_note(pool, "books/dune.md", Some("Dune")).await;
+    insert_test_property(pool, book, "status", "reading", "text").await;
+    insert_test_property(pool, book, "rating", "5", "number").await;
+    let other = insert_test_note(pool, "books/emma.md", Some("Emma")).await;
+    insert_test_property(pool, other, "status", "done", "text").await;
+    insert_test_note(pool, "journal/today.md", None).await;
+
+    let in_books = PropertyFilter {
+        key: "_path".to_string(),
+        operator: PropertyOperator::StartsWith,
+        value: Some("books/".to_string()),
+    };
+    let table = repo
+        .get_property_table(std::slice::from_ref(&in_books), &["rating".to_string(), "status".to_string()])
+        .await
+        .unwrap();
+
+    assert_eq!(table.columns, vec!["rating", "status"]);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0].note.path, "books/dune.md");
+    assert_eq!(table.rows[0].values[0].as_ref().unwrap().value.as_deref(), Some("5"));
+    assert!(table.rows[1].values[0].is_none());
+    assert_eq!(table.rows[1].values[1].as_ref().unwrap().value.as_deref(), Some("done"));
+
+    // Without columns, every key the notes use is a column
+    let table = repo.get_property_table(&[], &[]).await.unwrap();
+    assert_eq!(table.columns, vec!["rating", "status"]);
+    assert_eq!(table.rows.len(), 3);
+}
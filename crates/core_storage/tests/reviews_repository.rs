@@ -0,0 +1,63 @@
+//! Tests for the note review queue.
+
+mod helpers;
+
+use chrono::{Duration, Utc};
+use helpers::{insert_test_note, insert_test_property, setup_test_repo};
+
+#[tokio::test]
+async fn test_important_notes_are_due_sooner() {
+    let (pool, repo) = setup_test_repo().await;
+
+    insert_test_note(&pool, "plain.md", Some("Plain")).await;
+    let flagged = insert_test_note(&pool, "flagged.md", Some("Flagged")).await;
+    insert_test_property(&pool, flagged, "review", "true", "boolean").await;
+    let pinned = insert_test_note(&pool, "pinned.md", Some("Pinned")).await;
+    insert_test_property(&pool, pinned, "review", "true", "boolean").await;
+    sqlx::query("UPDATE notes SET pinned = 1 WHERE id = ?")
+        .bind(pinned)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let now = Utc::now() + Duration::days(20);
+    let due = repo.get_notes_due_for_review(10, now).await.unwrap();
+
+    let paths: Vec<_> = due.iter().map(|r| r.note.path.as_str()).collect();
+    assert_eq!(paths, vec!["pinned.md", "flagged.md"]);
+    assert!(due[0].due_score > due[1].due_score);
+    assert!(due[0].last_reviewed.is_none());
+
+    let limited = repo.get_notes_due_for_review(1, now).await.unwrap();
+    assert_eq!(limited.len(), 1);
+}
+
+#[tokio::test]
+async fn test_mark_reviewed_grows_interval() {
+    let (pool, repo) = setup_test_repo().await;
+
+    let note_id = insert_test_note(&pool, "old.md", Some("Old")).await;
+    let now = Utc::now() + Duration::days(40);
+    assert_eq!(repo.get_notes_due_for_review(10, now).await.unwrap().len(), 1);
+
+    assert_eq!(repo.mark_note_reviewed(note_id, now).await.unwrap(), Some(30));
+    assert!(repo.get_notes_due_for_review(10, now).await.unwrap().is_empty());
+
+    let later = now + Duration::days(31);
+    let due = repo.get_notes_due_for_review(10, later).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].last_reviewed, Some(now));
+
+    assert_eq!(repo.mark_note_reviewed(note_id, later).await.unwrap(), Some(60));
+    assert!(repo
+        .get_notes_due_for_review(10, later + Duration::days(45))
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_mark_reviewed_missing_note() {
+    let (_pool, repo) = setup_test_repo().await;
+    assert_eq!(repo.mark_note_reviewed(999, Utc::now()).await.unwrap(), None);
+}
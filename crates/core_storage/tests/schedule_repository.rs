@@ -281,6 +281,74 @@ async fn test_rrule_daily_expansion() {
     }
 }
 
+#[tokio::test]
+async fn test_rrule_expansion_in_block_timezone() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    // Monday 00:30 in Tokyo is still Sunday in UTC
+    let id = repo.create_schedule_block(
+        None,
+        "2024-01-01",
+        "00:30",
+        "01:30",
+        Some("Early call"),
+        None,
+        None,
+        Some("FREQ=WEEKLY;COUNT=3"),
+    )
+    .await
+    .unwrap();
+    repo.set_schedule_block_timezone(id, Some("Asia/Tokyo")).await.unwrap();
+
+    let blocks = repo
+        .get_schedule_blocks_for_range("2024-01-01", "2024-01-31")
+        .await
+        .unwrap();
+
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0].date.to_string(), "2024-01-01");
+    assert_eq!(blocks[1].date.to_string(), "2024-01-08");
+    assert_eq!(blocks[2].date.to_string(), "2024-01-15");
+    for block in &blocks {
+        assert_eq!(block.timezone.as_deref(), Some("Asia/Tokyo"));
+        assert_eq!(block.start_time.to_string(), "00:30:00");
+    }
+
+    // Invalid zones are rejected; an empty string reverts to local time
+    assert!(repo.set_schedule_block_timezone(id, Some("Mars/Olympus")).await.is_err());
+    repo.set_schedule_block_timezone(id, Some("")).await.unwrap();
+    let block = repo.get_schedule_block(id).await.unwrap().unwrap();
+    assert_eq!(block.timezone, None);
+}
+
+#[tokio::test]
+async fn test_rrule_expansion_across_midnight_dst_gap() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    // In Santiago, 2024-09-08 has no midnight: clocks go from 00:00 to 01:00
+    let id = repo.create_schedule_block(
+        None,
+        "2024-09-01",
+        "09:00",
+        "10:00",
+        Some("Stand-up"),
+        None,
+        None,
+        Some("FREQ=DAILY;COUNT=14"),
+    )
+    .await
+    .unwrap();
+    repo.set_schedule_block_timezone(id, Some("America/Santiago")).await.unwrap();
+
+    let blocks = repo
+        .get_schedule_blocks_for_range("2024-09-08", "2024-09-08")
+        .await
+        .unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].date.to_string(), "2024-09-08");
+    assert!(blocks[0].is_occurrence);
+}
+
 #[tokio::test]
 async fn test_rrule_weekly_expansion() {
     let (_pool, repo) = setup_test_repo().await;
@@ -0,0 +1,134 @@
+//! Tests for the schedule template repository.
+
+mod helpers;
+
+use chrono::NaiveTime;
+use core_storage::StorageError;
+use helpers::setup_test_repo;
+use shared_types::{CreateScheduleTemplateRequest, ScheduleTemplateSlot};
+
+fn slot(start: &str, end: &str, label: &str) -> ScheduleTemplateSlot {
+    ScheduleTemplateSlot {
+        start_time: NaiveTime::parse_from_str(start, "%H:%M").unwrap(),
+        end_time: NaiveTime::parse_from_str(end, "%H:%M").unwrap(),
+        label: Some(label.to_string()),
+        color: None,
+        context: Some("work".to_string()),
+    }
+}
+
+fn deep_work_request() -> CreateScheduleTemplateRequest {
+    CreateScheduleTemplateRequest {
+        name: "Deep Work Morning".to_string(),
+        description: None,
+        slots: vec![slot("10:00", "10:15", "Break"), slot("08:00", "10:00", "Focus")],
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_get_schedule_template() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let id = repo.create_schedule_template(&deep_work_request()).await.unwrap();
+    let template = repo.get_schedule_template(id).await.unwrap().unwrap();
+
+    assert_eq!(template.name, "Deep Work Morning");
+    assert_eq!(template.slots.len(), 2);
+    // Slots come back ordered by start time
+    assert_eq!(template.slots[0].label, Some("Focus".to_string()));
+    assert_eq!(template.slots[1].label, Some("Break".to_string()));
+
+    let all = repo.list_schedule_templates().await.unwrap();
+    assert_eq!(all.len(), 1);
+}
+
+#[tokio::test]
+async fn test_create_schedule_template_rejects_inverted_slots() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    for (start, end) in [("10:00", "09:00"), ("10:00", "10:00")] {
+        let request = CreateScheduleTemplateRequest {
+            name: "Backwards".to_string(),
+            description: None,
+            slots: vec![slot("08:00", "09:00", "Focus"), slot(start, end, "Break")],
+        };
+        assert!(
+            matches!(
+                repo.create_schedule_template(&request).await,
+                Err(StorageError::InvalidTimeRange(_))
+            ),
+            "{}..{} should be rejected",
+            start,
+            end
+        );
+    }
+
+    // Nothing was written for the rejected templates
+    assert!(repo.list_schedule_templates().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_apply_schedule_template() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let id = repo.create_schedule_template(&deep_work_request()).await.unwrap();
+    let result = repo
+        .apply_schedule_template(id, "2024-01-15", false)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(result.created_block_ids.len(), 2);
+    assert!(result.skipped_slots.is_empty());
+
+    let blocks = repo.get_schedule_blocks_for_date("2024-01-15").await.unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].start_time.to_string(), "08:00:00");
+    assert_eq!(blocks[0].context, Some("work".to_string()));
+}
+
+#[tokio::test]
+async fn test_apply_schedule_template_skips_conflicts() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.create_schedule_block(None, "2024-01-15", "09:30:00", "10:30:00", Some("Standup"), None, None, None)
+        .await
+        .unwrap();
+
+    let id = repo.create_schedule_template(&deep_work_request()).await.unwrap();
+    let result = repo
+        .apply_schedule_template(id, "2024-01-15", true)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(result.created_block_ids.is_empty());
+    assert_eq!(result.skipped_slots.len(), 2);
+
+    // Adjacent blocks do not conflict
+    let result = repo
+        .apply_schedule_template(id, "2024-01-16", true)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.created_block_ids.len(), 2);
+}
+
+#[tokio::test]
+async fn test_apply_missing_schedule_template() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let result = repo.apply_schedule_template(999, "2024-01-15", true).await.unwrap();
+    assert!(result.is_none());
+}
+
+#[tokio::test]
+async fn test_delete_schedule_template() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let id = repo.create_schedule_template(&deep_work_request()).await.unwrap();
+    repo.delete_schedule_template(id).await.unwrap();
+
+    assert!(repo.get_schedule_template(id).await.unwrap().is_none());
+    assert!(repo.list_schedule_templates().await.unwrap().is_empty());
+}
@@ -0,0 +1,52 @@
+//! Tests for scheduled note rules.
+
+mod helpers;
+
+use chrono::NaiveDate;
+use helpers::setup_test_repo;
+use shared_types::SaveScheduledNoteRuleRequest;
+
+#[tokio::test]
+async fn test_scheduled_note_rules() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let mut request = SaveScheduledNoteRuleRequest {
+        name: "Weekly".to_string(),
+        rrule: "FREQ=WEEKLY;BYDAY=MO".to_string(),
+        time: "07:00".to_string(),
+        path_pattern: "Weekly/{{date:YYYY-[W]WW}}.md".to_string(),
+        template_path: Some("templates/weekly-template.md".to_string()),
+        enabled: true,
+    };
+    let id = repo.create_scheduled_note_rule(&request).await.unwrap();
+
+    let rules = repo.list_scheduled_note_rules().await.unwrap();
+    assert_eq!(rules.len(), 1);
+    assert!(rules[0].enabled);
+    assert_eq!(rules[0].last_run.as_ref(), Some(&rules[0].created_at));
+
+    request.enabled = false;
+    repo.update_scheduled_note_rule(id, &request).await.unwrap();
+    let ran_at = NaiveDate::from_ymd_opt(2025, 1, 27).unwrap().and_hms_opt(7, 0, 0).unwrap();
+    repo.set_scheduled_note_last_run(id, ran_at).await.unwrap();
+
+    let rule = &repo.list_scheduled_note_rules().await.unwrap()[0];
+    assert!(!rule.enabled);
+    assert_eq!(rule.last_run.as_deref(), Some("2025-01-27T07:00:00"));
+
+    // Enabling it again skips what it missed while disabled
+    request.enabled = true;
+    repo.update_scheduled_note_rule(id, &request).await.unwrap();
+    let rule = &repo.list_scheduled_note_rules().await.unwrap()[0];
+    assert!(rule.enabled);
+    assert!(rule.last_run.as_deref() > Some("2025-01-27T07:00:00"));
+
+    // Saving an enabled rule keeps its last run
+    repo.set_scheduled_note_last_run(id, ran_at).await.unwrap();
+    repo.update_scheduled_note_rule(id, &request).await.unwrap();
+    let rule = &repo.list_scheduled_note_rules().await.unwrap()[0];
+    assert_eq!(rule.last_run.as_deref(), Some("2025-01-27T07:00:00"));
+
+    repo.delete_scheduled_note_rule(id).await.unwrap();
+    assert!(repo.list_scheduled_note_rules().await.unwrap().is_empty());
+}
@@ -0,0 +1,138 @@
+//! Tests for search presets.
+
+mod helpers;
+
+use chrono::NaiveDate;
+use core_storage::StorageError;
+use helpers::setup_test_repo;
+use shared_types::{SaveSearchPresetRequest, SearchFilters, TagMeta};
+
+fn preset(name: &str, query: &str, folder: Option<&str>) -> SaveSearchPresetRequest {
+    SaveSearchPresetRequest {
+        name: name.to_string(),
+        query: query.to_string(),
+        filters: SearchFilters {
+            folder: folder.map(str::to_string),
+            tags: vec!["meeting".to_string()],
+            date_range: Some("this_month".to_string()),
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_save_and_list_search_presets() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    repo.save_search_preset(&preset("Meetings this month", "standup", Some("work")))
+        .await
+        .unwrap();
+    repo.save_search_preset(&preset("action items", "TODO", None)).await.unwrap();
+
+    let presets = repo.list_search_presets().await.unwrap();
+    let names: Vec<_> = presets.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["action items", "Meetings this month"]);
+
+    let meetings = &presets[1];
+    assert_eq!(meetings.query, "standup");
+    assert_eq!(meetings.filters.folder.as_deref(), Some("work"));
+    assert_eq!(meetings.filters.tags, vec!["meeting"]);
+    assert_eq!(meetings.filters.date_range.as_deref(), Some("this_month"));
+}
+
+#[tokio::test]
+async fn test_save_search_preset_replaces_same_name() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let id = repo.save_search_preset(&preset("Meetings", "standup", None)).await.unwrap();
+    let same = repo.save_search_preset(&preset("Meetings", "retro", Some("team"))).await.unwrap();
+    assert_eq!(id, same);
+
+    let presets = repo.list_search_presets().await.unwrap();
+    assert_eq!(presets.len(), 1);
+    assert_eq!(presets[0].query, "retro");
+
+    repo.delete_search_preset(id).await.unwrap();
+    assert!(repo.list_search_presets().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_search_filtered_by_folder_tags_and_date() {
+    let (pool, repo) = setup_test_repo().await;
+
+    for (path, content, updated_at) in [
+        ("work/standup.md", "# Standup\nstandup notes #meeting", "2026-10-14T09:00:00+00:00"),
+        ("work/old.md", "# Old\nstandup notes #meeting", "2025-03-01T09:00:00+00:00"),
+        ("work_log.md", "# Log\nstandup notes #meet", "2026-10-14T09:00:00+00:00"),
+        ("home/standup.md", "# Home\nstandup notes", "2026-10-14T09:00:00+00:00"),
+    ] {
+        repo.index_note(path, content, path, &core_index::markdown::parse(content))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE notes SET updated_at = ? WHERE path = ?")
+            .bind(updated_at)
+            .bind(path)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    repo.set_tag_meta(&TagMeta {
+        tag: "meet".to_string(),
+        alias_of: Some("meeting".to_string()),
+        description: None,
+        color: None,
+    })
+    .await
+    .unwrap();
+
+    let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+    let search = |filters: SearchFilters| {
+        let repo = &repo;
+        async move {
+            let mut paths: Vec<_> = repo
+                .search_filtered("standup", &filters, today, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|r| r.path)
+                .collect();
+            paths.sort();
+            paths
+        }
+    };
+
+    // The folder matches whole path segments, not prefixes
+    let in_work = SearchFilters { folder: Some("work".to_string()), ..Default::default() };
+    assert_eq!(search(in_work).await, ["work/old.md", "work/standup.md"]);
+
+    // Tags match through their aliases
+    let tagged = SearchFilters { tags: vec!["#meeting".to_string()], ..Default::default() };
+    assert_eq!(search(tagged).await, ["work/old.md", "work/standup.md", "work_log.md"]);
+
+    let this_week = SearchFilters {
+        folder: Some("work".to_string()),
+        date_range: Some("this_week".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(search(this_week).await, ["work/standup.md"]);
+
+    let custom = SearchFilters { date_range: Some("..2025-12-31".to_string()), ..Default::default() };
+    assert_eq!(search(custom).await, ["work/old.md"]);
+}
+
+#[tokio::test]
+async fn test_search_filtered_rejects_invalid_date_range() {
+    let (_pool, repo) = setup_test_repo().await;
+    let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+
+    for range in ["2026-10-17..2026-10-01", "yesterday", "2026-13-01.."] {
+        let filters = SearchFilters { date_range: Some(range.to_string()), ..Default::default() };
+        assert!(
+            matches!(
+                repo.search_filtered("standup", &filters, today, 10).await,
+                Err(StorageError::InvalidDateRange(_))
+            ),
+            "{} should be rejected",
+            range
+        );
+    }
+}
@@ -2,7 +2,9 @@
 
 mod helpers;
 
+use core_storage::StorageError;
 use helpers::{count_rows, get_tags_for_note, insert_test_note, setup_test_repo};
+use shared_types::TagMeta;
 
 #[tokio::test]
 async fn test_replace_tags_insert() {
@@ -157,3 +159,74 @@ async fn test_cascade_delete() {
     let tag_count = count_rows(pool, "tags").await;
     assert_eq!(tag_count, 0);
 }
+
+#[tokio::test]
+async fn test_tag_aliases_and_meta() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note1 = insert_test_note(pool, "note1.md", Some("Note 1")).await;
+    let note2 = insert_test_note(pool, "note2.md", Some("Note 2")).await;
+    repo.replace_tags(note1, &["task".to_string(), "todo".to_string()]).await.unwrap();
+    repo.replace_tags(note2, &["todo".to_string()]).await.unwrap();
+
+    repo.set_tag_meta(&TagMeta {
+        tag: "#task".to_string(),
+        alias_of: None,
+        description: Some("Things to do".to_string()),
+        color: Some("#ff0000".to_string()),
+    })
+    .await
+    .unwrap();
+    repo.set_tag_meta(&TagMeta {
+        tag: "todo".to_string(),
+        alias_of: Some("#task".to_string()),
+        description: None,
+        color: None,
+    })
+    .await
+    .unwrap();
+
+    // The alias is counted under its tag, once per note
+    let tags = repo.list_tags().await.unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].tag, "task");
+    assert_eq!(tags[0].count, 2);
+    assert_eq!(tags[0].description.as_deref(), Some("Things to do"));
+    assert_eq!(tags[0].color.as_deref(), Some("#ff0000"));
+    assert_eq!(tags[0].aliases, ["todo"]);
+
+    // Aliases can't point at themselves or another alias, or have aliases
+    let invalid = [("task", "task"), ("chore", "todo"), ("task", "chore")];
+    for (tag, alias_of) in invalid {
+        let meta = TagMeta {
+            tag: tag.to_string(),
+            alias_of: Some(alias_of.to_string()),
+            description: None,
+            color: None,
+        };
+        assert!(
+            matches!(repo.set_tag_meta(&meta).await, Err(StorageError::InvalidTagAlias(_))),
+            "{} -> {}",
+            tag,
+            alias_of
+        );
+    }
+
+    // Tags can't be empty
+    for tag in ["", " # "] {
+        let meta = TagMeta {
+            tag: tag.to_string(),
+            alias_of: None,
+            description: Some("Nothing".to_string()),
+            color: None,
+        };
+        assert!(matches!(repo.set_tag_meta(&meta).await, Err(StorageError::InvalidTag(_))), "{:?}", tag);
+    }
+
+    // Removing the alias counts the tags separately again
+    repo.delete_tag_meta("#todo").await.unwrap();
+    assert_eq!(repo.list_tag_meta().await.unwrap().len(), 1);
+    let tags = repo.list_tags().await.unwrap();
+    assert_eq!(tags.iter().find(|t| t.tag == "todo").unwrap().count, 2);
+    assert!(tags.iter().find(|t| t.tag == "task").unwrap().aliases.is_empty());
+}
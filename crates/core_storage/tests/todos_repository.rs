@@ -22,6 +22,7 @@ async fn test_replace_todos_insert() {
             context: Some("work".to_string()),
             priority: Some("high".to_string()),
             due_date: Some("2024-01-15".to_string()),
+            estimate_minutes: None,
         },
         ParsedTodo {
             description: "Review pull requests".to_string(),
@@ -32,6 +33,7 @@ async fn test_replace_todos_insert() {
             context: Some("work".to_string()),
             priority: Some("medium".to_string()),
             due_date: None,
+            estimate_minutes: None,
         },
         ParsedTodo {
             description: "Buy groceries".to_string(),
@@ -42,6 +44,7 @@ async fn test_replace_todos_insert() {
             context: Some("home".to_string()),
             priority: Some("low".to_string()),
             due_date: None,
+            estimate_minutes: None,
         },
     ];
 
@@ -79,6 +82,7 @@ async fn test_replace_todos_update() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
         ParsedTodo {
             description: "Old task 2".to_string(),
@@ -89,6 +93,7 @@ async fn test_replace_todos_update() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
     ];
 
@@ -104,6 +109,7 @@ async fn test_replace_todos_update() {
         context: Some("work".to_string()),
         priority: Some("high".to_string()),
         due_date: Some("2024-02-01".to_string()),
+        estimate_minutes: None,
     }];
 
     repo.replace_todos(note_id, &new_todos).await.unwrap();
@@ -134,6 +140,7 @@ async fn test_get_incomplete_todos() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
         ParsedTodo {
             description: "Completed task".to_string(),
@@ -144,6 +151,7 @@ async fn test_get_incomplete_todos() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
     ];
 
@@ -156,6 +164,7 @@ async fn test_get_incomplete_todos() {
         context: None,
         priority: None,
         due_date: None,
+        estimate_minutes: None,
     }];
 
     repo.replace_todos(note1, &todos_note1).await.unwrap();
@@ -192,6 +201,7 @@ async fn test_update_todo_completion() {
         context: None,
         priority: None,
         due_date: None,
+        estimate_minutes: None,
     }];
 
     repo.replace_todos(note_id, &todos).await.unwrap();
@@ -233,6 +243,7 @@ async fn test_query_tasks_with_filters() {
             context: Some("work".to_string()),
             priority: Some("high".to_string()),
             due_date: Some("2024-01-20".to_string()),
+            estimate_minutes: None,
         },
         ParsedTodo {
             description: "Low priority home task".to_string(),
@@ -243,6 +254,7 @@ async fn test_query_tasks_with_filters() {
             context: Some("home".to_string()),
             priority: Some("low".to_string()),
             due_date: None,
+            estimate_minutes: None,
         },
     ];
 
@@ -255,6 +267,7 @@ async fn test_query_tasks_with_filters() {
         context: Some("work".to_string()),
         priority: Some("medium".to_string()),
         due_date: Some("2024-01-25".to_string()),
+        estimate_minutes: None,
     }];
 
     repo.replace_todos(note1, &todos_note1).await.unwrap();
@@ -338,6 +351,7 @@ async fn test_query_tasks_with_property_filter() {
         context: None,
         priority: Some("high".to_string()),
         due_date: None,
+        estimate_minutes: None,
     }];
 
     repo.replace_todos(note_id, &todos).await.unwrap();
@@ -381,6 +395,7 @@ async fn test_cascade_delete() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
         ParsedTodo {
             description: "Task 2".to_string(),
@@ -391,6 +406,7 @@ async fn test_cascade_delete() {
             context: None,
             priority: None,
             due_date: None,
+            estimate_minutes: None,
         },
     ];
 
@@ -414,3 +430,89 @@ async fn test_cascade_delete() {
         .unwrap();
     assert_eq!(count, 0);
 }
+
+#[tokio::test]
+async fn test_suggest_next_tasks_ranking() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note_id = insert_test_note(pool, "tasks.md", Some("Tasks")).await;
+
+    let todos = vec![
+        ParsedTodo {
+            description: "Refactor module".to_string(),
+            raw_text: "- [ ] Refactor module @computer !low est:2h".to_string(),
+            completed: false,
+            line_number: 1,
+            heading_path: None,
+            context: Some("computer".to_string()),
+            priority: Some("low".to_string()),
+            due_date: None,
+            estimate_minutes: Some(120),
+        },
+        ParsedTodo {
+            description: "Call dentist".to_string(),
+            raw_text: "- [ ] Call dentist @phone !high ^2024-03-04 est:10m".to_string(),
+            completed: false,
+            line_number: 2,
+            heading_path: None,
+            context: Some("phone".to_string()),
+            priority: Some("high".to_string()),
+            due_date: Some("2024-03-04".to_string()),
+            estimate_minutes: Some(10),
+        },
+        ParsedTodo {
+            description: "Already done".to_string(),
+            raw_text: "- [x] Already done @phone".to_string(),
+            completed: true,
+            line_number: 3,
+            heading_path: None,
+            context: Some("phone".to_string()),
+            priority: Some("high".to_string()),
+            due_date: None,
+            estimate_minutes: None,
+        },
+    ];
+    repo.replace_todos(note_id, &todos).await.unwrap();
+
+    let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let suggestions = repo
+        .suggest_next_tasks(today, Some("phone"), Some(15), Some("low"), 10)
+        .await
+        .unwrap();
+
+    // Completed tasks are never suggested
+    assert_eq!(suggestions.len(), 2);
+    assert_eq!(suggestions[0].task.todo.description, "Call dentist");
+    assert!(suggestions[0].reasons.contains(&"due today".to_string()));
+    assert!(suggestions[0].score > suggestions[1].score);
+}
+
+#[tokio::test]
+async fn test_suggest_next_tasks_scores_every_open_task() {
+    let (_pool, repo) = setup_test_repo().await;
+    let pool = repo.pool();
+    let note_id = insert_test_note(pool, "tasks.md", Some("Tasks")).await;
+
+    let task = |description: String, line_number: usize, context: &str, priority: Option<&str>| ParsedTodo {
+        raw_text: format!("- [ ] {}", description),
+        description,
+        completed: false,
+        line_number,
+        heading_path: None,
+        context: Some(context.to_string()),
+        priority: priority.map(String::from),
+        due_date: None,
+        estimate_minutes: None,
+    };
+    // High priority errands are listed before the call, which fits best
+    let mut todos: Vec<ParsedTodo> = (0..600)
+        .map(|i| task(format!("Errand {}", i), i + 1, "errands", Some("high")))
+        .collect();
+    todos.push(task("Call dentist".to_string(), 601, "phone", None));
+    repo.replace_todos(note_id, &todos).await.unwrap();
+
+    let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let suggestions = repo.suggest_next_tasks(today, Some("phone"), None, None, 5).await.unwrap();
+    assert_eq!(suggestions.len(), 5);
+    assert_eq!(suggestions[0].task.todo.description, "Call dentist");
+}
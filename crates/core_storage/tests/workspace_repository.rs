@@ -0,0 +1,74 @@
+//! Tests for workspace persistence.
+
+mod helpers;
+
+use helpers::{insert_test_note, setup_test_repo};
+use shared_types::{WorkspaceSidebar, WorkspaceState, WorkspaceTab};
+
+fn tab(path: &str, scroll_top: f64) -> WorkspaceTab {
+    WorkspaceTab {
+        path: path.to_string(),
+        scroll_top,
+        cursor_position: Some(42),
+    }
+}
+
+#[tokio::test]
+async fn test_load_empty_workspace() {
+    let (_pool, repo) = setup_test_repo().await;
+
+    let workspace = repo.load_workspace().await.unwrap();
+    assert!(workspace.tabs.is_empty());
+    assert!(workspace.active_path.is_none());
+    assert!(!workspace.sidebar.open);
+}
+
+#[tokio::test]
+async fn test_save_and_load_workspace() {
+    let (pool, repo) = setup_test_repo().await;
+    insert_test_note(&pool, "a.md", None).await;
+    insert_test_note(&pool, "b.md", None).await;
+
+    let workspace = WorkspaceState {
+        tabs: vec![tab("b.md", 120.0), tab("missing.md", 0.0), tab("a.md", 0.0)],
+        active_path: Some("a.md".to_string()),
+        sidebar: WorkspaceSidebar {
+            open: true,
+            width: Some(280.0),
+            active_panel: Some("tags".to_string()),
+        },
+    };
+    repo.save_workspace(&workspace).await.unwrap();
+
+    let loaded = repo.load_workspace().await.unwrap();
+    let paths: Vec<_> = loaded.tabs.iter().map(|t| t.path.as_str()).collect();
+    assert_eq!(paths, vec!["b.md", "a.md"]);
+    assert_eq!(loaded.tabs[0].scroll_top, 120.0);
+    assert_eq!(loaded.tabs[0].cursor_position, Some(42));
+    assert_eq!(loaded.active_path.as_deref(), Some("a.md"));
+    assert!(loaded.sidebar.open);
+    assert_eq!(loaded.sidebar.width, Some(280.0));
+    assert_eq!(loaded.sidebar.active_panel.as_deref(), Some("tags"));
+}
+
+#[tokio::test]
+async fn test_workspace_follows_renamed_and_deleted_notes() {
+    let (pool, repo) = setup_test_repo().await;
+    insert_test_note(&pool, "old.md", None).await;
+    insert_test_note(&pool, "gone.md", None).await;
+
+    let workspace = WorkspaceState {
+        tabs: vec![tab("old.md", 0.0), tab("gone.md", 0.0)],
+        active_path: Some("gone.md".to_string()),
+        ..Default::default()
+    };
+    repo.save_workspace(&workspace).await.unwrap();
+
+    repo.rename_note("old.md", "new.md").await.unwrap();
+    repo.delete_note("gone.md").await.unwrap();
+
+    let loaded = repo.load_workspace().await.unwrap();
+    let paths: Vec<_> = loaded.tabs.iter().map(|t| t.path.as_str()).collect();
+    assert_eq!(paths, vec!["new.md"]);
+    assert!(loaded.active_path.is_none());
+}
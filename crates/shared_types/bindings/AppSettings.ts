@@ -0,0 +1,40 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EditorSettings } from "./EditorSettings";
+import type { EmbeddingSettings } from "./EmbeddingSettings";
+import type { WindowLayout } from "./WindowLayout";
+
+/**
+ * Settings of this device.
+ */
+export type AppSettings = { 
+/**
+ * Vault opened on startup.
+ */
+last_vault_path: string | null, 
+/**
+ * "system", "light", "dark" or a custom theme name.
+ */
+theme: string, vim_mode: boolean, 
+/**
+ * "daily", "weekly" or "monthly".
+ */
+default_calendar_view: string, editor: EditorSettings, 
+/**
+ * Keep drafts of unsaved edits, to offer them for recovery if the app
+ * quits before a note is saved.
+ */
+crash_recovery: boolean, 
+/**
+ * Window layout when the app was last closed.
+ */
+window: WindowLayout | null, 
+/**
+ * Semantic search settings (the embedding server runs on this device).
+ */
+embedding_settings: EmbeddingSettings, 
+/**
+ * Diagram renderer installed on this device (see `DiagramSettings`);
+ * the app's bundled renderer if unset. Kept here rather than in the
+ * vault config, so opening a vault never runs a program the vault chose.
+ */
+diagram_renderer_path: string | null, };
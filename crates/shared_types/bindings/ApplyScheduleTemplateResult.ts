@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScheduleTemplateSlot } from "./ScheduleTemplateSlot";
+
+/**
+ * Result of applying a schedule template to a date.
+ */
+export type ApplyScheduleTemplateResult = { 
+/**
+ * IDs of the schedule blocks that were created.
+ */
+created_block_ids: Array<bigint>, 
+/**
+ * Slots that were skipped because they overlapped an existing block.
+ */
+skipped_slots: Array<ScheduleTemplateSlot>, };
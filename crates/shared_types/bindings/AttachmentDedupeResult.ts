@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DuplicateAttachments } from "./DuplicateAttachments";
+
+/**
+ * Result of merging identical attachments.
+ */
+export type AttachmentDedupeResult = { duplicates: Array<DuplicateAttachments>, 
+/**
+ * Notes whose embeds and links were rewritten.
+ */
+notes_updated: number, 
+/**
+ * Disk space freed, in bytes.
+ */
+bytes_saved: bigint, 
+/**
+ * Encrypted notes, whose embeds can't be read or rewritten. While there
+ * are any, the merged copies are kept on disk, as those notes may
+ * embed them.
+ */
+encrypted_notes: number, };
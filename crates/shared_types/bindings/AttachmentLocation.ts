@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where new attachments are saved.
+ */
+export type AttachmentLocation = "vault_folder" | "same_folder" | "note_subfolder";
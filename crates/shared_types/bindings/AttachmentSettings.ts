@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttachmentLocation } from "./AttachmentLocation";
+
+/**
+ * Settings for saving attachments (stored in vault config).
+ */
+export type AttachmentSettings = { location: AttachmentLocation, 
+/**
+ * Folder name for `vault_folder` and `note_subfolder`.
+ */
+folder: string, 
+/**
+ * File name pattern without extension (e.g., "Pasted image {{timestamp}}").
+ * Supports the template date variables plus `{{time}}`, `{{timestamp}}`,
+ * and `{{note}}` (name of the embedding note).
+ */
+name_template: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An autocomplete suggestion.
+ */
+export type AutocompleteItem = { text: string, 
+/**
+ * Number of notes using it.
+ */
+count: bigint, 
+/**
+ * The note using it, when there is only one (e.g. for a title).
+ */
+note_id: bigint | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the autocomplete index completes.
+ */
+export type AutocompleteKind = "title" | "alias" | "tag" | "property_key" | "property_value" | "context" | "heading";
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A backup zip written by a backup job.
+ */
+export type BackupResult = { 
+/**
+ * Where the zip was written.
+ */
+output_path: string, 
+/**
+ * Number of files in the zip, including the database copy.
+ */
+files: number, size_bytes: bigint, 
+/**
+ * The backup was cancelled and no zip was written.
+ */
+cancelled: boolean, };
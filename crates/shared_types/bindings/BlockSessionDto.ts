@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Actual time spent on a schedule block, recorded in focus mode.
+ */
+export type BlockSessionDto = { id: bigint, block_id: bigint, 
+/**
+ * Day the session was started on (the occurrence date for recurring blocks).
+ */
+date: string, 
+/**
+ * Local start time (YYYY-MM-DDTHH:MM:SS).
+ */
+started_at: string, 
+/**
+ * Local end time, None while the session is running.
+ */
+ended_at: string | null, 
+/**
+ * Length in minutes (up to now for a running session).
+ */
+minutes: bigint, };
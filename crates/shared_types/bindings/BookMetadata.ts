@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Book metadata looked up on OpenLibrary.
+ */
+export type BookMetadata = { title: string | null, authors: Array<string>, 
+/**
+ * ISBN-13 if known, else ISBN-10.
+ */
+isbn: string | null, 
+/**
+ * Publication date as given by OpenLibrary (often just the year).
+ */
+published: string | null, pages: bigint | null, 
+/**
+ * URL of the large cover image.
+ */
+cover_url: string | null, 
+/**
+ * Vault path of the downloaded cover (None if there is none or it
+ * failed to download).
+ */
+cover_path: string | null, };
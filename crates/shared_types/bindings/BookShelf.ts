@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueryResultItem } from "./QueryResultItem";
+
+/**
+ * Books with the same reading status.
+ */
+export type BookShelf = { 
+/**
+ * The `status` property value (None for books without one).
+ */
+status: string | null, books: Array<QueryResultItem>, };
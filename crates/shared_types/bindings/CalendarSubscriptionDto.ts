@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An external calendar (ICS URL) whose events are shown as read-only blocks.
+ */
+export type CalendarSubscriptionDto = { id: bigint, name: string, url: string, 
+/**
+ * Color for the overlay blocks.
+ */
+color: string | null, 
+/**
+ * Minutes between automatic refreshes.
+ */
+refresh_minutes: bigint, 
+/**
+ * When the feed was last fetched (RFC 3339).
+ */
+last_fetched: string | null, 
+/**
+ * Error from the last fetch, if it failed.
+ */
+last_error: string | null, 
+/**
+ * Number of events stored from the last successful fetch.
+ */
+event_count: bigint, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeKind } from "./ChangeKind";
+
+/**
+ * An entry in the vault's change journal (`changes.jsonl` in its data directory).
+ */
+export type ChangeRecord = { 
+/**
+ * Position in the journal, from 1; pass the last one seen as the cursor.
+ */
+seq: bigint, timestamp: string, kind: ChangeKind, 
+/**
+ * Vault-relative path of the note (the new path for renames).
+ */
+path: string, 
+/**
+ * The path before a rename.
+ */
+old_path: string | null, 
+/**
+ * Content hash after the change (None for deletions).
+ */
+hash: string | null, };
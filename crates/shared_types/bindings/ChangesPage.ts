@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeRecord } from "./ChangeRecord";
+
+/**
+ * Changes after a cursor, and the cursor to continue from.
+ */
+export type ChangesPage = { changes: Array<ChangeRecord>, cursor: bigint, 
+/**
+ * The cursor was older than the journal (its records were dropped, or
+ * the journal deleted), so `changes` start from the oldest record kept
+ * and the reader should resync everything.
+ */
+reset: boolean, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Latency of a command over its recent calls.
+ */
+export type CommandLatency = { command: string, 
+/**
+ * Calls since the app started.
+ */
+count: bigint, 
+/**
+ * Median latency of the recent calls.
+ */
+p50_ms: number, 
+/**
+ * 95th percentile latency of the recent calls.
+ */
+p95_ms: number, 
+/**
+ * Slowest of the recent calls.
+ */
+max_ms: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A context used by tasks or registered, with its open-task count.
+ */
+export type ContextDto = { name: string, icon: string | null, description: string | null, location: string | null, 
+/**
+ * Number of incomplete tasks with this context.
+ */
+open_tasks: bigint, 
+/**
+ * Whether the context is in the registry (unregistered contexts are
+ * only used by tasks).
+ */
+registered: boolean, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A registered GTD context (`@home`, `@phone`) and its metadata.
+ */
+export type ContextMeta = { 
+/**
+ * Context name without the `@`.
+ */
+name: string, 
+/**
+ * Emoji or icon name.
+ */
+icon: string | null, description: string | null, 
+/**
+ * Where the context applies (e.g., "Office, 2nd floor").
+ */
+location: string | null, };
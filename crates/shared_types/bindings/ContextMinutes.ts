@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Scheduled minutes for one context.
+ */
+export type ContextMinutes = { context: string, minutes: bigint, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Planned versus actual time for one context.
+ */
+export type ContextPlanVsActual = { 
+/**
+ * Block context (None for blocks without a context).
+ */
+context: string | null, 
+/**
+ * Minutes of schedule blocks in the range.
+ */
+planned_minutes: bigint, 
+/**
+ * Minutes of focus sessions recorded on those blocks.
+ */
+actual_minutes: bigint, 
+/**
+ * Actual minus planned minutes.
+ */
+difference_minutes: bigint, 
+/**
+ * Number of planned blocks (recurring occurrences counted individually).
+ */
+block_count: bigint, 
+/**
+ * Number of recorded sessions.
+ */
+session_count: bigint, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextMinutes } from "./ContextMinutes";
+import type { DayContextStats } from "./DayContextStats";
+import type { FocusStretch } from "./FocusStretch";
+import type { WeekContextStats } from "./WeekContextStats";
+
+/**
+ * Context switching report over a date range, for charts.
+ */
+export type ContextSwitchReport = { start_date: string, end_date: string, 
+/**
+ * One entry per day in the range (days without blocks included).
+ */
+days: Array<DayContextStats>, 
+/**
+ * One entry per week touched by the range.
+ */
+weeks: Array<WeekContextStats>, total_switches: bigint, 
+/**
+ * Minutes per context over the whole range, most first.
+ */
+context_minutes: Array<ContextMinutes>, longest_focus: FocusStretch | null, 
+/**
+ * Slope of daily switches over the days with blocks (negative = fewer
+ * switches over time). None with fewer than two such days.
+ */
+switch_trend: number | null, };
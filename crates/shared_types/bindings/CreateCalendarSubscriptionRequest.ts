@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to subscribe to an external calendar.
+ */
+export type CreateCalendarSubscriptionRequest = { name: string, 
+/**
+ * ICS feed URL (http, https, or webcal).
+ */
+url: string, color: string | null, 
+/**
+ * Minutes between automatic refreshes (default: 60).
+ */
+refresh_minutes: bigint | null, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GoalTargetType } from "./GoalTargetType";
+import type { TaskQuery } from "./TaskQuery";
+
+/**
+ * Request to create a goal.
+ */
+export type CreateGoalRequest = { name: string, description: string | null, target_type: GoalTargetType, target_value: number | null, unit: string | null, 
+/**
+ * Start date as YYYY-MM-DD (defaults to today).
+ */
+start_date: string | null, 
+/**
+ * Deadline as YYYY-MM-DD.
+ */
+deadline: string | null, habit_ids: Array<bigint>, task_query: TaskQuery | null, };
@@ -7,4 +7,8 @@ export type CreateScheduleBlockRequest = { note_id: bigint | null, date: string,
 /**
  * RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR").
  */
-rrule: string | null, };
+rrule: string | null, 
+/**
+ * IANA time zone for the block's times (None = vault local time).
+ */
+timezone: string | null, };
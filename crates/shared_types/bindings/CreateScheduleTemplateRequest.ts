@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScheduleTemplateSlot } from "./ScheduleTemplateSlot";
+
+/**
+ * Request to create a new schedule template.
+ */
+export type CreateScheduleTemplateRequest = { name: string, description: string | null, slots: Array<ScheduleTemplateSlot>, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Options for scaffolding a new vault.
+ */
+export type CreateVaultOptions = { 
+/**
+ * Folder for new, unsorted notes.
+ */
+inbox_folder: string, 
+/**
+ * Folder for daily, weekly and monthly notes.
+ */
+journal_folder: string, 
+/**
+ * Add daily, weekly, monthly and meeting templates to `templates/`.
+ */
+sample_templates: boolean, 
+/**
+ * Add a welcome note explaining the basics.
+ */
+welcome_note: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a vault's database and caches are stored.
+ */
+export type DataLocation = "vault" | "app_data";
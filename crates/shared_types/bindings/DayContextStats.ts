@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextMinutes } from "./ContextMinutes";
+import type { FocusStretch } from "./FocusStretch";
+
+/**
+ * Context switching statistics for a single day.
+ */
+export type DayContextStats = { date: string, 
+/**
+ * Number of adjacent blocks with different contexts.
+ */
+switches: bigint, 
+/**
+ * Minutes per context, most first.
+ */
+context_minutes: Array<ContextMinutes>, 
+/**
+ * Longest run of same-context blocks on this day.
+ */
+longest_focus: FocusStretch | null, };
@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HabitDaySummary } from "./HabitDaySummary";
+import type { ScheduleBlockDto } from "./ScheduleBlockDto";
+import type { TaskWithContext } from "./TaskWithContext";
+
+/**
+ * Everything the weekly planner shows for a single day.
+ */
+export type DayOverview = { date: string, 
+/**
+ * Schedule blocks on this day (recurring blocks expanded).
+ */
+blocks: Array<ScheduleBlockDto>, 
+/**
+ * Tasks due on this day, plus incomplete undated tasks of notes scheduled on it.
+ */
+tasks: Array<TaskWithContext>, 
+/**
+ * Completion of each active habit.
+ */
+habits: Array<HabitDaySummary>, 
+/**
+ * Number of notes for this day (scheduled, journal, or created).
+ */
+note_count: bigint, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PlannedBlock } from "./PlannedBlock";
+
+/**
+ * A proposed (or created) plan for a day.
+ */
+export type DayPlan = { date: string, planned: Array<PlannedBlock>, 
+/**
+ * Candidate tasks that did not fit into the remaining free time.
+ */
+unplanned_todo_ids: Array<bigint>, 
+/**
+ * Free minutes left within working hours after planning.
+ */
+free_minutes: bigint, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Estimated task effort versus available time for a single day.
+ */
+export type DayWorkload = { date: string, 
+/**
+ * Sum of `est:` estimates of incomplete tasks due or scheduled on this day.
+ */
+estimated_minutes: bigint, 
+/**
+ * Free minutes within working hours after existing schedule blocks.
+ */
+available_minutes: bigint, 
+/**
+ * Number of incomplete tasks assigned to this day.
+ */
+task_count: bigint, 
+/**
+ * Number of those tasks without an estimate.
+ */
+unestimated_count: bigint, 
+/**
+ * True if estimated effort exceeds available time.
+ */
+overcommitted: boolean, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteListItem } from "./NoteListItem";
+import type { ScheduleBlockDto } from "./ScheduleBlockDto";
+
+/**
+ * What deleting a folder or note would affect.
+ */
+export type DeleteImpact = { 
+/**
+ * Notes that would be deleted.
+ */
+notes: Array<NoteListItem>, 
+/**
+ * Links from other notes that would no longer resolve.
+ */
+broken_links: bigint, 
+/**
+ * Schedule blocks linked to the notes; they are deleted with them.
+ */
+schedule_blocks: Array<ScheduleBlockDto>, 
+/**
+ * Pinned notes among the deleted ones.
+ */
+pinned_notes: Array<NoteListItem>, };
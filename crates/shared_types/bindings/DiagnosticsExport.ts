@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A diagnostics bundle written for a bug report.
+ */
+export type DiagnosticsExport = { 
+/**
+ * Where the zip was written.
+ */
+path: string, size_bytes: bigint, 
+/**
+ * The files in the zip.
+ */
+entries: Array<string>, };
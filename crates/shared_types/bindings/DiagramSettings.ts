@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How diagram (`mermaid`) blocks are rendered to SVG (stored in vault config).
+ */
+export type DiagramSettings = { 
+/**
+ * Path of a mermaid-cli compatible renderer (`mmdc -i in.mmd -o out.svg`).
+ * Without one, diagrams that aren't cached stay code blocks. Comes from
+ * the device's app settings, or is the app's bundled renderer; a path in
+ * the vault config is ignored.
+ */
+renderer_path: string | null, 
+/**
+ * Render diagrams into the cache when notes are indexed, rather than
+ * the first time they are shown or exported.
+ */
+prerender: boolean, };
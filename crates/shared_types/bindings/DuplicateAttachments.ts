@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Identical attachments merged into one by `dedupe_attachments`.
+ */
+export type DuplicateAttachments = { 
+/**
+ * The copy that was kept (vault-relative path).
+ */
+kept: string, 
+/**
+ * The copies whose embeds now point at `kept`. They are deleted unless
+ * the vault has encrypted notes (see `AttachmentDedupeResult`).
+ */
+removed: Array<string>, 
+/**
+ * Size of one copy in bytes.
+ */
+size: bigint, };
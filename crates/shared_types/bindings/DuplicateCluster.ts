@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DuplicateNote } from "./DuplicateNote";
+
+/**
+ * Notes with the same or nearly the same content.
+ */
+export type DuplicateCluster = { 
+/**
+ * All notes have the same text (ignoring frontmatter, formatting, and case).
+ */
+exact: boolean, 
+/**
+ * Lowest similarity of a note to the merge target.
+ */
+similarity: number, 
+/**
+ * The note to keep when merging: most backlinks, then most words.
+ */
+merge_target_id: bigint, 
+/**
+ * The merge target first, then by similarity.
+ */
+notes: Array<DuplicateNote>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A note in a duplicate cluster.
+ */
+export type DuplicateNote = { note_id: bigint, path: string, title: string | null, word_count: number, backlink_count: bigint, 
+/**
+ * Similarity to the cluster's merge target (0.0 - 1.0; 1.0 for the target).
+ */
+similarity: number, };
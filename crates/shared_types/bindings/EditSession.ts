@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The window editing a note. Editing sessions are advisory: saves are
+ * never refused, but other windows show the note read-only.
+ */
+export type EditSession = { note_id: bigint, 
+/**
+ * Label of the window holding the session.
+ */
+window: string, started_at: string, };
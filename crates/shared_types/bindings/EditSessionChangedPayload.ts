@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EditSession } from "./EditSession";
+
+/**
+ * Payload for edit:changed event, sent when a window begins, takes over
+ * or ends the editing session of a note.
+ */
+export type EditSessionChangedPayload = { note_id: bigint, 
+/**
+ * The session now held, if any.
+ */
+session: EditSession | null, };
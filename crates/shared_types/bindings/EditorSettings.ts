@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Editor appearance.
+ */
+export type EditorSettings = { 
+/**
+ * CSS font family (None for the theme's font).
+ */
+font_family: string | null, 
+/**
+ * Font size in pixels (None for the theme's size).
+ */
+font_size: number | null, line_height: number | null, 
+/**
+ * Dictionary language for spell checking in the editor (e.g. "en_US");
+ * None turns spell checking off.
+ */
+spell_check_language: string | null, };
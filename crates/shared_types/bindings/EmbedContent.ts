@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmbedCycle } from "./EmbedCycle";
 
 /**
  * Result of resolving an embed.
@@ -27,4 +28,9 @@ asset_url: string | null,
 /**
  * Error message if resolution failed.
  */
-error: string | null, };
+error: string | null, 
+/**
+ * The embed loop, if the target (directly or through its own embeds)
+ * embeds a note that is already being embedded.
+ */
+cycle: EmbedCycle | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An embed loop found while resolving an embed.
+ */
+export type EmbedCycle = { 
+/**
+ * The embeds resolved, as `path` or `path#section`: the note with the
+ * embed first, then each embedded note, up to and including `repeated`.
+ */
+chain: Array<string>, 
+/**
+ * The entry that appears twice, closing the loop.
+ */
+repeated: string, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of checking stored embeddings against the notes.
+ */
+export type EmbeddingConsistencyReport = { 
+/**
+ * Number of stored embeddings.
+ */
+total: bigint, 
+/**
+ * Embeddings of notes that no longer exist or are now encrypted.
+ */
+orphaned: bigint, 
+/**
+ * Embeddings of notes whose content changed since they were embedded.
+ */
+stale: bigint, 
+/**
+ * Embeddings whose size doesn't match the configured dimensions.
+ */
+wrong_dimensions: bigint, 
+/**
+ * Notes without an embedding.
+ */
+missing: bigint, 
+/**
+ * Orphaned and wrong-sized embeddings deleted by the check.
+ */
+removed: bigint, };
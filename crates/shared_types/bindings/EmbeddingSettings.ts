@@ -23,4 +23,9 @@ dimensions: number,
 /**
  * Number of texts to process in a single batch.
  */
-batch_size: number, };
+batch_size: number, 
+/**
+ * Fraction of a note's text (0.0-1.0) that has to change before it is
+ * embedded again; 0.0 re-embeds on every change.
+ */
+reembed_threshold: number, };
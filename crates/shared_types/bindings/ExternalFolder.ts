@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A read-only folder outside the vault (e.g. a shared team wiki) whose
+ * notes are indexed for search and linking under `@name/`.
+ */
+export type ExternalFolder = { 
+/**
+ * Name of the folder's root in the tree; paths start with `@name/`.
+ */
+name: string, 
+/**
+ * Absolute path of the folder.
+ */
+path: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileProblemKind } from "./FileProblemKind";
+
+/**
+ * A file that could not be read cleanly when it was last indexed.
+ */
+export type FileProblem = { 
+/**
+ * Vault-relative path.
+ */
+path: string, kind: FileProblemKind, detected_at: string, };
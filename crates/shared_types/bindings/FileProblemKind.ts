@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What is wrong with a file found in the vault.
+ */
+export type FileProblemKind = "invalid_utf8" | "binary";
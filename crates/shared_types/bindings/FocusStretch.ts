@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A run of adjacent blocks with the same context.
+ */
+export type FocusStretch = { date: string, context: string, start_time: string, end_time: string, 
+/**
+ * Scheduled minutes of the blocks in the run (gaps not counted).
+ */
+minutes: bigint, };
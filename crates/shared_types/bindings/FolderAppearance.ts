@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a folder is shown in the tree.
+ */
+export type FolderAppearance = { icon: string | null, color: string | null, collapsed: boolean, };
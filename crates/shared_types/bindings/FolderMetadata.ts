@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FolderAppearance } from "./FolderAppearance";
+
+/**
+ * Stored metadata for a folder.
+ */
+export type FolderMetadata = { 
+/**
+ * Folder path relative to the vault root ("" for the root).
+ */
+path: string, appearance: FolderAppearance, 
+/**
+ * Manual order of child names; children not listed follow in default order.
+ */
+child_order: Array<string>, };
@@ -3,4 +3,20 @@
 /**
  * A node in the folder tree.
  */
-export type FolderNode = { name: string, path: string, is_dir: boolean, children: Array<FolderNode>, };
+export type FolderNode = { name: string, path: string, is_dir: boolean, children: Array<FolderNode>, 
+/**
+ * Folder icon (folders only).
+ */
+icon: string | null, 
+/**
+ * Folder color (folders only).
+ */
+color: string | null, 
+/**
+ * Whether the folder is collapsed in the tree (folders only).
+ */
+collapsed: boolean, 
+/**
+ * Whether the entry is in a read-only external folder.
+ */
+read_only: boolean, };
@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GoalTargetType } from "./GoalTargetType";
+import type { TaskQuery } from "./TaskQuery";
+
+/**
+ * A goal definition.
+ */
+export type GoalDto = { id: bigint, name: string, description: string | null, target_type: GoalTargetType, 
+/**
+ * Target value (numeric goals) or number of tasks to complete (task goals).
+ * For task goals without a target, all matching tasks must be completed.
+ */
+target_value: number | null, 
+/**
+ * Unit for numeric goals (e.g., "km", "pages").
+ */
+unit: string | null, 
+/**
+ * Start of the tracking period as YYYY-MM-DD (defaults to the creation date).
+ */
+start_date: string, 
+/**
+ * Deadline as YYYY-MM-DD.
+ */
+deadline: string | null, 
+/**
+ * Habits whose entries count towards a numeric goal.
+ */
+habit_ids: Array<bigint>, 
+/**
+ * Task filter for task goals (the completion filter is ignored).
+ */
+task_query: TaskQuery | null, archived: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query for a goal embed (```goal``` block).
+ */
+export type GoalEmbedQuery = { 
+/**
+ * Goal name or ID.
+ */
+goal: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GoalProgress } from "./GoalProgress";
+
+/**
+ * Response for a goal embed.
+ */
+export type GoalEmbedResponse = { progress: GoalProgress | null, 
+/**
+ * Error message if the query failed.
+ */
+error: string | null, };
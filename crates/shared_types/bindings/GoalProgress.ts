@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GoalDto } from "./GoalDto";
+
+/**
+ * Current progress towards a goal.
+ */
+export type GoalProgress = { goal: GoalDto, 
+/**
+ * Current value (sum of habit values, or number of completed tasks).
+ */
+current_value: number, 
+/**
+ * Effective target (the goal target, or the number of matching tasks).
+ */
+target_value: number | null, 
+/**
+ * Percentage complete (0-100), if a target is known.
+ */
+percent: number | null, 
+/**
+ * Number of tasks matching the query (task goals only).
+ */
+total_tasks: bigint | null, 
+/**
+ * Days until the deadline (negative when past).
+ */
+days_remaining: bigint | null, 
+/**
+ * Whether progress is at or ahead of a linear pace towards the deadline.
+ */
+on_track: boolean | null, };
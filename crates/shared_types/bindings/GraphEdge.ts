@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The links from one note to another in a link graph.
+ */
+export type GraphEdge = { source: bigint, target: bigint, 
+/**
+ * Number of links from the source to the target.
+ */
+weight: bigint, };
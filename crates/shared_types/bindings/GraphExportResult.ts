@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of exporting the link graph to a file.
+ */
+export type GraphExportResult = { output_path: string, node_count: number, edge_count: number, };
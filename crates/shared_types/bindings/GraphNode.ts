@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A note in a link graph.
+ */
+export type GraphNode = { id: bigint, path: string, title: string | null, tags: Array<string>, };
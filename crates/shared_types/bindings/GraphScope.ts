@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The notes a link graph export covers. Empty means the whole vault; with
+ * both set, notes must be in the folder and have the tag.
+ */
+export type GraphScope = { 
+/**
+ * Folder (relative to vault root), including subfolders.
+ */
+folder: string | null, 
+/**
+ * Tag (without `#`), including nested tags (`project` covers `project/a`).
+ */
+tag: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The value of a metric on a day.
+ */
+export type GrowthPoint = { 
+/**
+ * Date as YYYY-MM-DD string.
+ */
+date: string, value: bigint, };
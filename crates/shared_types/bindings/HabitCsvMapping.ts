@@ -0,0 +1,36 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Column mapping for importing habit entries from a CSV file.
+ *
+ * Columns are referenced by header name (case-insensitive) or by 0-based index.
+ */
+export type HabitCsvMapping = { 
+/**
+ * Column holding the entry date.
+ */
+date_column: string, 
+/**
+ * Column holding the value to log.
+ */
+value_column: string, 
+/**
+ * Optional column holding the entry time.
+ */
+time_column: string | null, 
+/**
+ * Optional column holding notes for the entry.
+ */
+notes_column: string | null, 
+/**
+ * chrono format of the date column (e.g. "%d.%m.%Y"). Common formats are detected when omitted.
+ */
+date_format: string | null, 
+/**
+ * Field delimiter (defaults to ",").
+ */
+delimiter: string | null, 
+/**
+ * Whether the first row is a header row (defaults to true).
+ */
+has_header: boolean | null, };
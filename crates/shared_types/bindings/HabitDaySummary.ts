@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Habit completion for a single day of the week overview.
+ */
+export type HabitDaySummary = { habit_id: bigint, name: string, color: string | null, 
+/**
+ * Whether the habit was completed on this day.
+ */
+done: boolean, 
+/**
+ * Whether the day is skipped by the habit's schedule rules.
+ */
+skipped: boolean, };
@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HabitScheduleRules } from "./HabitScheduleRules";
 import type { HabitType } from "./HabitType";
 
 /**
@@ -24,4 +25,8 @@ archived: boolean,
 /**
  * Sort order for display.
  */
-sort_order: number, };
+sort_order: number, 
+/**
+ * Active days, planned skips, and streak freezes.
+ */
+schedule: HabitScheduleRules, };
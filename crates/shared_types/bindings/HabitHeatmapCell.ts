@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A single day in a habit heatmap.
+ */
+export type HabitHeatmapCell = { 
+/**
+ * Date as YYYY-MM-DD string.
+ */
+date: string, 
+/**
+ * Completion relative to the target, from 0.0 (nothing) to 1.0 (target met).
+ */
+value: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of a habit data import.
+ */
+export type HabitImportResult = { 
+/**
+ * Number of entries created.
+ */
+imported: bigint, 
+/**
+ * Rows skipped because an entry already exists for that date and time.
+ */
+skipped_duplicates: bigint, 
+/**
+ * Rows that could not be parsed, with line numbers.
+ */
+errors: Array<string>, };
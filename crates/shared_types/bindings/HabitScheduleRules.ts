@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-habit schedule rules honored by streak computations.
+ *
+ * Days that are not active or are planned skips neither extend nor break a streak.
+ */
+export type HabitScheduleRules = { 
+/**
+ * Active ISO weekdays (1 = Monday ... 7 = Sunday). Empty means every day.
+ */
+active_weekdays: Array<number>, 
+/**
+ * Planned skip dates as YYYY-MM-DD strings (e.g. travel days).
+ */
+skip_dates: Array<string>, 
+/**
+ * Number of missed days forgiven within a single streak.
+ */
+freeze_tokens: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Streak statistics for a habit.
+ */
+export type HabitStreak = { 
+/**
+ * Consecutive active days completed up to the reference date.
+ */
+current: number, 
+/**
+ * Longest streak on record.
+ */
+longest: number, 
+/**
+ * Freeze tokens used by the current streak.
+ */
+freezes_used: number, };
@@ -2,6 +2,7 @@
 import type { HabitDateRange } from "./HabitDateRange";
 import type { HabitTableOrientation } from "./HabitTableOrientation";
 import type { HabitViewType } from "./HabitViewType";
+import type { WeekStart } from "./WeekStart";
 
 /**
  * Query for habit tracker embed.
@@ -20,7 +21,8 @@ view: HabitViewType,
  */
 orientation: HabitTableOrientation, 
 /**
- * Date range preset.
+ * Date range preset. The heatmap view covers the calendar year of the
+ * reference date unless a custom range is given.
  */
 date_range: HabitDateRange, 
 /**
@@ -43,4 +45,9 @@ editable: boolean,
 /**
  * Whether to show summary row (totals, streaks).
  */
-show_summary: boolean, };
+show_summary: boolean, 
+/**
+ * First day of the week for `this_week`; the vault's locale setting
+ * if not given.
+ */
+week_start: WeekStart | null, };
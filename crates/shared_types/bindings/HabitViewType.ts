@@ -3,4 +3,4 @@
 /**
  * View type for habit tracker embeds.
  */
-export type HabitViewType = "table" | "calendar" | "streak" | "list";
+export type HabitViewType = "table" | "calendar" | "streak" | "list" | "heatmap";
@@ -1,6 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { HabitDto } from "./HabitDto";
 import type { HabitEntryDto } from "./HabitEntryDto";
+import type { HabitHeatmapCell } from "./HabitHeatmapCell";
+import type { HabitStreak } from "./HabitStreak";
 
 /**
  * Habit with its entries for a date range.
@@ -9,4 +11,16 @@ export type HabitWithEntries = { habit: HabitDto,
 /**
  * Entries organized by date (YYYY-MM-DD -> entries).
  */
-entries_by_date: Array<[string, Array<HabitEntryDto>]>, };
+entries_by_date: Array<[string, Array<HabitEntryDto>]>, 
+/**
+ * Dates in the range that are skipped (inactive weekdays or planned skips).
+ */
+skipped_dates: Array<string>, 
+/**
+ * Streak as of the end of the range (or today, if earlier).
+ */
+streak: HabitStreak, 
+/**
+ * Per-day values normalized to 0-1 against the target (heatmap view only).
+ */
+heatmap: Array<HabitHeatmapCell>, };
@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { MatchType } from "./MatchType";
+import type { SearchSection } from "./SearchSection";
 
 /**
  * A hybrid search result combining FTS5 and vector search.
@@ -20,4 +21,12 @@ combined_score: number,
 /**
  * How this result was matched.
  */
-match_type: MatchType, };
+match_type: MatchType, 
+/**
+ * Best matching section, for semantic matches of chunked notes.
+ */
+section: SearchSection | null, 
+/**
+ * The keyword match may be out of date (see `SearchResult::stale`).
+ */
+stale: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An image whose recognized text matches a search.
+ */
+export type ImageTextMatch = { 
+/**
+ * Vault-relative path of the image.
+ */
+path: string, 
+/**
+ * The recognized text around the match.
+ */
+snippet: string, };
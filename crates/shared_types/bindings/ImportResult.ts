@@ -27,4 +27,8 @@ duration_ms: bigint,
 /**
  * Any warnings or skipped files.
  */
-warnings: Array<string>, };
+warnings: Array<string>, 
+/**
+ * The import was cancelled; files imported so far are kept.
+ */
+cancelled: boolean, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportedFileKind } from "./ImportedFileKind";
+
+/**
+ * A file copied into the vault by `import_files`.
+ */
+export type ImportedFile = { 
+/**
+ * Original path outside the vault.
+ */
+source_path: string, 
+/**
+ * New path relative to the vault root (may have a number suffix).
+ */
+path: string, kind: ImportedFileKind, 
+/**
+ * Note ID for imported notes.
+ */
+note_id: bigint | null, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A photo copied into the vault by `import_photos`.
+ */
+export type ImportedPhoto = { 
+/**
+ * Original path outside the vault.
+ */
+source_path: string, 
+/**
+ * New path relative to the vault root.
+ */
+path: string, 
+/**
+ * Day the photo is filed under (YYYY-MM-DD); the file date without EXIF data.
+ */
+date: string, 
+/**
+ * EXIF capture time (YYYY-MM-DDTHH:MM:SS, camera local time).
+ */
+taken_at: string | null, latitude: number | null, longitude: number | null, 
+/**
+ * Daily note the photo was embedded in.
+ */
+daily_note_path: string | null, };
@@ -1,6 +1,15 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IndexWarning } from "./IndexWarning";
 
 /**
  * Payload for index:complete event.
  */
-export type IndexCompletePayload = { notes_indexed: bigint, duration_ms: bigint, };
+export type IndexCompletePayload = { notes_indexed: bigint, duration_ms: bigint, 
+/**
+ * Files that were found but could not be indexed.
+ */
+warnings: Array<IndexWarning>, 
+/**
+ * The index was cancelled; notes indexed so far are kept.
+ */
+cancelled: boolean, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Size limits for indexing very large notes (stored in vault config).
+ */
+export type IndexSettings = { 
+/**
+ * Notes larger than this many bytes are oversized: only their first
+ * lines up to this size are parsed (links, tags, tasks) and indexed for
+ * full-text search, and they are never embedded. Takes effect as notes are next indexed.
+ */
+max_indexed_bytes: bigint, };
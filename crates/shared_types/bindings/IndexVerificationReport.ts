@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IndexWarning } from "./IndexWarning";
+import type { StaleNote } from "./StaleNote";
+
+/**
+ * Result of checking the index against the files on disk.
+ */
+export type IndexVerificationReport = { 
+/**
+ * Markdown files found on disk.
+ */
+files_checked: bigint, 
+/**
+ * Files whose hash matches the indexed copy.
+ */
+up_to_date: bigint, stale: Array<StaleNote>, 
+/**
+ * Stale notes that were re-indexed (or removed, for missing files).
+ */
+reindexed: bigint, 
+/**
+ * Files that could not be read or re-indexed.
+ */
+warnings: Array<IndexWarning>, duration_ms: bigint, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A file skipped during indexing, and why.
+ */
+export type IndexWarning = { 
+/**
+ * Vault-relative path (invalid UTF-8 shown as `\u{FFFD}`).
+ */
+path: string, reason: string, };
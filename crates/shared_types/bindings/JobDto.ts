@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JobKind } from "./JobKind";
+import type { JobRequest } from "./JobRequest";
+import type { JobStatus } from "./JobStatus";
+
+/**
+ * A queued, running or finished job.
+ */
+export type JobDto = { id: bigint, kind: JobKind, status: JobStatus, request: JobRequest, 
+/**
+ * Items processed (all of them unless cancelled or failed); set when
+ * the job finishes.
+ */
+processed: bigint, total: bigint, 
+/**
+ * Summary of the result, or the error if the job failed.
+ */
+message: string | null, created_at: string, started_at: string | null, finished_at: string | null, };
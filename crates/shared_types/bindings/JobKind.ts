@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a job does.
+ */
+export type JobKind = "full_index" | "vault_import" | "journal_export" | "embedding_backfill" | "backup" | "ocr";
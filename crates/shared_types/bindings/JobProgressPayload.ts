@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JobKind } from "./JobKind";
+
+/**
+ * Payload for job:progress event.
+ */
+export type JobProgressPayload = { job_id: bigint, kind: JobKind, processed: bigint, total: bigint, 
+/**
+ * What is being worked on, e.g. the current file.
+ */
+message: string | null, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmbeddingSettings } from "./EmbeddingSettings";
+import type { ImportVaultRequest } from "./ImportVaultRequest";
+import type { RenderOptions } from "./RenderOptions";
+
+/**
+ * What to run, with everything needed to run it again.
+ */
+export type JobRequest = { "type": "full_index" } | { "type": "vault_import", request: ImportVaultRequest, } | { "type": "journal_export", start_date: string, end_date: string, output_path: string, options: RenderOptions | null, } | { "type": "embedding_backfill", settings: EmbeddingSettings, } | { "type": "backup", output_path: string, } | { "type": "ocr" };
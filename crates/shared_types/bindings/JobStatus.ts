@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a job is in its lifecycle.
+ */
+export type JobStatus = "queued" | "running" | "completed" | "cancelled" | "failed";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of exporting daily notes as one printable HTML document.
+ */
+export type JournalExportResult = { output_path: string, 
+/**
+ * Dates (YYYY-MM-DD) that had a daily note, in order.
+ */
+dates: Array<string>, };
@@ -15,4 +15,9 @@ card_fields: Array<string>,
 /**
  * Whether to show cards without a value in an "Uncategorized" column.
  */
-show_uncategorized: boolean, };
+show_uncategorized: boolean, 
+/**
+ * Name the manual card order is stored under. Defaults to a key made
+ * from the query and `group_by`, filled in when the query runs.
+ */
+board: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Number of sentences or words within a length range.
+ */
+export type LengthBucket = { min: number, 
+/**
+ * Inclusive upper bound; None for the open-ended last bucket.
+ */
+max: number | null, count: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A note an ambiguous wiki link could refer to.
+ */
+export type LinkCandidate = { note_id: bigint, path: string, title: string | null, 
+/**
+ * Folder of the note ("" for the vault root).
+ */
+folder: string, 
+/**
+ * Whether the note is in the same folder as the linking note, which
+ * makes it the one the link resolves to.
+ */
+same_folder: boolean, };
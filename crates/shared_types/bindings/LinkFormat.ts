@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the app writes wiki link targets, e.g. when updating links after a
+ * rename or move.
+ */
+export type LinkFormat = "shortest" | "absolute" | "relative";
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GraphEdge } from "./GraphEdge";
+import type { GraphNode } from "./GraphNode";
+
+/**
+ * The link structure of (part of) the vault. Edges only connect notes in
+ * the graph.
+ */
+export type LinkGraph = { nodes: Array<GraphNode>, edges: Array<GraphEdge>, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LinkFormat } from "./LinkFormat";
+
+/**
+ * Settings for links written by the app and for keeping old links
+ * working (stored in vault config).
+ */
+export type LinkSettings = { link_format: LinkFormat, 
+/**
+ * When a note's H1 title changes, add the old title to the `aliases`
+ * in its frontmatter so `[[Old Title]]` links keep resolving.
+ */
+keep_old_titles: boolean, };
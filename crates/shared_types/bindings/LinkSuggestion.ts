@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteListItem } from "./NoteListItem";
+
+/**
+ * A note suggested as a link target for the paragraph being written.
+ */
+export type LinkSuggestion = { note: NoteListItem, 
+/**
+ * Ranking score (higher is better).
+ */
+score: number, 
+/**
+ * Title or alias that matched the link being typed, if any.
+ */
+matched_text: string | null, 
+/**
+ * Tags the note shares with the paragraph.
+ */
+shared_tags: Array<string>, 
+/**
+ * Embedding similarity to the paragraph, when embeddings are available.
+ */
+similarity: number | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintRule } from "./LintRule";
+import type { LintSeverity } from "./LintSeverity";
+
+/**
+ * A problem found in a note.
+ */
+export type LintDiagnostic = { rule: LintRule, severity: LintSeverity, message: string, 
+/**
+ * 1-based line number.
+ */
+line: number, fixable: boolean, };
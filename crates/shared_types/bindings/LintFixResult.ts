@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of applying lint fixes.
+ */
+export type LintFixResult = { 
+/**
+ * Notes whose content changed.
+ */
+notes_changed: number, fixes_applied: number, 
+/**
+ * Notes that could not be read or written, with the error for each;
+ * the other notes are still fixed.
+ */
+errors: Array<string>, };
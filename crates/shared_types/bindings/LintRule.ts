@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A lint rule.
+ */
+export type LintRule = "broken_link" | "broken_embed" | "empty_heading" | "duplicate_heading" | "empty_section" | "trailing_whitespace" | "todo_marker" | "missing_property" | "undefined_footnote" | "orphaned_footnote" | "unknown_context";
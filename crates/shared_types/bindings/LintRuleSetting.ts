@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintRule } from "./LintRule";
+import type { LintSeverity } from "./LintSeverity";
+
+/**
+ * Settings for one rule; rules without settings are enabled with their
+ * default severity.
+ */
+export type LintRuleSetting = { rule: LintRule, enabled: boolean, severity: LintSeverity | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintRuleSetting } from "./LintRuleSetting";
+import type { RequiredProperties } from "./RequiredProperties";
+
+/**
+ * Lint settings (stored in vault config).
+ */
+export type LintSettings = { rules: Array<LintRuleSetting>, required_properties: Array<RequiredProperties>, };
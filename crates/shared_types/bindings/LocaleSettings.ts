@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WeekStart } from "./WeekStart";
+
+/**
+ * Locale settings (stored in vault config).
+ */
+export type LocaleSettings = { 
+/**
+ * First day of the week for calendars, weekly notes and week ranges.
+ */
+week_start: WeekStart, 
+/**
+ * How dates are shown, with `YYYY`, `MM`, `DD`, `M` and `D`
+ * (e.g., "DD.MM.YYYY"). Dates are still stored as YYYY-MM-DD.
+ */
+date_format: string, 
+/**
+ * How times are shown, with `HH`, `H`, `hh`, `h`, `mm` and `a` for
+ * am/pm (e.g., "h:mm a").
+ */
+time_format: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How math (`$...$`, `$$...$$`) is rendered to HTML.
+ */
+export type MathRendering = "mathml" | "tex";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathRendering } from "./MathRendering";
+
+/**
+ * Math settings (stored in vault config).
+ */
+export type MathSettings = { rendering: MathRendering, };
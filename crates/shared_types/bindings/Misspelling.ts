@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A misspelled word in checked text.
+ */
+export type Misspelling = { 
+/**
+ * Start offset in UTF-16 code units (as used by the editor).
+ */
+from: number, 
+/**
+ * End offset in UTF-16 code units.
+ */
+to: number, word: string, 
+/**
+ * Replacement suggestions, best first.
+ */
+suggestions: Array<string>, };
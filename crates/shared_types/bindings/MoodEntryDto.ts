@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A logged mood for a single day.
+ */
+export type MoodEntryDto = { id: bigint, 
+/**
+ * Date as YYYY-MM-DD string.
+ */
+date: string, 
+/**
+ * Mood score from 1 (low) to 5 (great).
+ */
+score: number, 
+/**
+ * Optional free-form note about the day.
+ */
+note: string | null, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MoodEntryDto } from "./MoodEntryDto";
+import type { MoodWeekSummary } from "./MoodWeekSummary";
+
+/**
+ * Mood entries and aggregates for a date range.
+ */
+export type MoodTrend = { 
+/**
+ * Entries ordered by date.
+ */
+entries: Array<MoodEntryDto>, 
+/**
+ * Average score over the range (None if no entries).
+ */
+average: number | null, min: number | null, max: number | null, 
+/**
+ * Weekly averages, ordered by week.
+ */
+weekly: Array<MoodWeekSummary>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Average mood for an ISO week.
+ */
+export type MoodWeekSummary = { 
+/**
+ * First day of the week as YYYY-MM-DD string.
+ */
+week_start: string, average: number, count: bigint, };
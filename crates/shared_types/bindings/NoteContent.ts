@@ -1,6 +1,20 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 /**
- * Full note content for editing.
+ * Full note content for editing, or part of it for oversized notes.
  */
-export type NoteContent = { id: bigint, path: string, content: string, };
+export type NoteContent = { id: bigint, path: string, content: string, 
+/**
+ * Byte offset of `content` in the file (0 unless a range was read).
+ */
+offset: bigint, 
+/**
+ * Size of the whole note in bytes; more than `offset` plus the length
+ * of `content` if there is more to read.
+ */
+total_bytes: bigint, 
+/**
+ * The note is over the vault's size limit: only its start is parsed
+ * and searched, and it is not embedded.
+ */
+truncated: boolean, };
@@ -3,4 +3,8 @@
 /**
  * A note as exposed to the frontend.
  */
-export type NoteDto = { id: bigint, path: string, title: string | null, created_at: string | null, updated_at: string | null, pinned: boolean, };
+export type NoteDto = { id: bigint, path: string, title: string | null, created_at: string | null, updated_at: string | null, pinned: boolean, 
+/**
+ * CSS classes from the `cssclasses` property, for per-note layouts.
+ */
+cssclasses: Array<string>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteListItem } from "./NoteListItem";
+
+/**
+ * A note with its number of links, for orphan, dead-end and hub reports.
+ */
+export type NoteLinkCount = { note: NoteListItem, 
+/**
+ * Links from other notes to this note.
+ */
+inbound: bigint, 
+/**
+ * Links from this note to other notes.
+ */
+outbound: bigint, };
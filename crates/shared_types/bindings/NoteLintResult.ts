@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LintDiagnostic } from "./LintDiagnostic";
+
+/**
+ * Diagnostics for one note.
+ */
+export type NoteLintResult = { note_id: bigint, path: string, diagnostics: Array<LintDiagnostic>, };
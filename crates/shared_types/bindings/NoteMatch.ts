@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteMatchKind } from "./NoteMatchKind";
+
+/**
+ * A note a reference could refer to.
+ */
+export type NoteMatch = { note_id: bigint, path: string, title: string | null, kind: NoteMatchKind, 
+/**
+ * Higher is better; notes closer to the linking note's folder score
+ * higher within a kind.
+ */
+score: bigint, 
+/**
+ * How sure this match is the note meant, from 0 to 1.
+ */
+confidence: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a note matches a reference to it (a link target, an embed or a
+ * typed name), from most to least certain.
+ */
+export type NoteMatchKind = "path" | "same_folder" | "name" | "title" | "alias" | "fuzzy";
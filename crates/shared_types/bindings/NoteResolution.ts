@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteMatch } from "./NoteMatch";
+
+/**
+ * The notes a reference could refer to, best first.
+ */
+export type NoteResolution = { candidates: Array<NoteMatch>, 
+/**
+ * How sure the first candidate is the note meant, from 0 to 1: lower
+ * when other notes match the same way.
+ */
+confidence: number, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteListItem } from "./NoteListItem";
+
+/**
+ * A note that is due to come back to the user's attention.
+ */
+export type NoteReviewDto = { note: NoteListItem, 
+/**
+ * When the note was last reviewed (None if never).
+ */
+last_reviewed: string | null, 
+/**
+ * Days between reviews; grows each time the note is reviewed.
+ */
+review_interval_days: bigint, 
+/**
+ * Importance weight (1.0 = plain note); shortens the interval.
+ */
+importance: number, 
+/**
+ * Elapsed time over the weighted interval; due at 1.0 and above.
+ */
+due_score: number, };
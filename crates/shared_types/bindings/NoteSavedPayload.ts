@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for note:saved event, sent to every window when one saves,
+ * renames or moves a note or toggles one of its todos, so the others can
+ * show the new content.
+ */
+export type NoteSavedPayload = { note_id: bigint, path: string, content: string, 
+/**
+ * Label of the window that saved it.
+ */
+window: string, 
+/**
+ * Where the note was before a rename or move.
+ */
+previous_path: string | null, };
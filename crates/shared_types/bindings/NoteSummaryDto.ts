@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScheduleBlockDto } from "./ScheduleBlockDto";
+
+/**
+ * Cheap aggregates for a note header.
+ */
+export type NoteSummaryDto = { note_id: bigint, 
+/**
+ * Notes linking to this note.
+ */
+backlink_count: bigint, 
+/**
+ * Notes this note links to.
+ */
+outgoing_link_count: bigint, open_todo_count: bigint, todo_count: bigint, 
+/**
+ * Words in the body (frontmatter and private sections excluded).
+ */
+word_count: bigint, 
+/**
+ * The note's schedule block with the latest date, if any.
+ */
+last_schedule_block: ScheduleBlockDto | null, };
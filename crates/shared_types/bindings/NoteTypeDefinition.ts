@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteTypeProperty } from "./NoteTypeProperty";
+
+/**
+ * A note type (e.g. meeting, book, person): where new notes of the type go
+ * and what they start with. Notes of a type have it as their `type` property.
+ */
+export type NoteTypeDefinition = { 
+/**
+ * The type's name, used as the `type` property value (e.g. "meeting").
+ */
+name: string, 
+/**
+ * Template for new notes (relative to vault root). Defaults to
+ * `types/<name>.md` if that exists.
+ */
+template: string | null, 
+/**
+ * Properties every note of the type gets.
+ */
+properties: Array<NoteTypeProperty>, 
+/**
+ * Folder new notes are created in (relative to vault root).
+ */
+folder: string | null, 
+/**
+ * Icon name shown in the new-note dialog.
+ */
+icon: string | null, };
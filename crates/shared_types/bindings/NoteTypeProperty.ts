@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A property set on new notes of a type.
+ */
+export type NoteTypeProperty = { key: string, 
+/**
+ * Default value; can use `{{title}}` and the date variables. Empty if None.
+ */
+value: string | null, 
+/**
+ * Type hint: "text", "date", "number", "boolean", "list"
+ */
+property_type: string | null, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of recognizing the text in the vault's images.
+ */
+export type OcrResult = { 
+/**
+ * Images whose text was recognized.
+ */
+images_recognized: number, 
+/**
+ * Images skipped because they haven't changed since.
+ */
+images_unchanged: number, 
+/**
+ * Images that could not be recognized, with the reason.
+ */
+warnings: Array<string>, 
+/**
+ * Recognition was cancelled; text recognized so far is kept.
+ */
+cancelled: boolean, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteListItem } from "./NoteListItem";
+
+/**
+ * A note from the same day in a previous year.
+ */
+export type OnThisDayNote = { note: NoteListItem, 
+/**
+ * The original date as YYYY-MM-DD string.
+ */
+date: string, 
+/**
+ * Where the date comes from: "journal" (the `journal_date` property),
+ * "file_name" (a `YYYY-MM-DD` file name) or "created"
+ */
+source: string, years_ago: number, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommandLatency } from "./CommandLatency";
+import type { SlowQuery } from "./SlowQuery";
+
+/**
+ * Per-command latency and recent slow queries, since the app started.
+ */
+export type PerformanceMetrics = { 
+/**
+ * Commands by p95 latency, slowest first.
+ */
+commands: Array<CommandLatency>, 
+/**
+ * Most recent first.
+ */
+slow_queries: Array<SlowQuery>, slow_query_threshold_ms: bigint, };
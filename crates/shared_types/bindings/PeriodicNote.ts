@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NotePeriod } from "./NotePeriod";
+
+/**
+ * The periodic note for a day, week or month.
+ */
+export type PeriodicNote = { period: NotePeriod, 
+/**
+ * First day of the period (YYYY-MM-DD): the date, the week's first
+ * day, or the first of the month.
+ */
+date: string, 
+/**
+ * The note path from the period's pattern.
+ */
+path: string, 
+/**
+ * The note ID, or None if the note doesn't exist.
+ */
+note_id: bigint | null, 
+/**
+ * Whether the note was created by this call.
+ */
+created: boolean, };
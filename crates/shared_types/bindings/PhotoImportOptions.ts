@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Options for importing photos into date folders and daily notes.
+ */
+export type PhotoImportOptions = { 
+/**
+ * Base folder; photos are filed under `<folder>/<year>/<month>/`.
+ */
+folder: string, 
+/**
+ * Embed each photo in the daily note of the day it was taken.
+ */
+add_to_daily_notes: boolean, };
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Constraints for automatically planning a day.
+ */
+export type PlanDayConstraints = { 
+/**
+ * Start of working hours.
+ */
+work_start: string, 
+/**
+ * End of working hours.
+ */
+work_end: string, 
+/**
+ * Duration in minutes for tasks without an `est:` token (default: 30).
+ */
+default_task_minutes: number | null, 
+/**
+ * Gap in minutes to leave between planned blocks (default: 0).
+ */
+buffer_minutes: number | null, 
+/**
+ * Only plan tasks with this context.
+ */
+context: string | null, 
+/**
+ * Maximum number of tasks to plan.
+ */
+max_tasks: number | null, 
+/**
+ * Create the schedule blocks immediately instead of only proposing them.
+ */
+create_blocks: boolean, };
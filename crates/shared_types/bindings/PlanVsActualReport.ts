@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextPlanVsActual } from "./ContextPlanVsActual";
+
+/**
+ * Planned versus actual time per context over a date range.
+ */
+export type PlanVsActualReport = { start_date: string, end_date: string, 
+/**
+ * Contexts ordered by planned minutes, most first.
+ */
+contexts: Array<ContextPlanVsActual>, total_planned_minutes: bigint, total_actual_minutes: bigint, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A task placed into a free time slot by the day planner.
+ */
+export type PlannedBlock = { todo_id: bigint, note_id: bigint, description: string, start_time: string, end_time: string, 
+/**
+ * Estimated minutes from the task's `est:` token (None if the default was used).
+ */
+estimated_minutes: number | null, 
+/**
+ * ID of the created schedule block (None when only proposed).
+ */
+block_id: bigint | null, };
@@ -7,4 +7,8 @@ export type PropertyDto = { id: bigint, note_id: bigint, key: string, value: str
 /**
  * Type hint: "text", "date", "number", "boolean", "list"
  */
-property_type: string | null, sort_order: number | null, };
+property_type: string | null, sort_order: number | null, 
+/**
+ * Items of a list property, in order (empty for other types).
+ */
+items: Array<string>, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PropertyTableRow } from "./PropertyTableRow";
+
+/**
+ * Notes with their values for a set of properties, for a spreadsheet-style
+ * editor.
+ */
+export type PropertyTable = { 
+/**
+ * The property keys shown as columns.
+ */
+columns: Array<string>, rows: Array<PropertyTableRow>, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NoteListItem } from "./NoteListItem";
+import type { PropertyDto } from "./PropertyDto";
+
+/**
+ * A note in the property table.
+ */
+export type PropertyTableRow = { note: NoteListItem, 
+/**
+ * The note's property for each column (None where it has none).
+ */
+values: Array<PropertyDto | null>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One property change of a batch update.
+ */
+export type PropertyUpdate = { note_id: bigint, key: string, 
+/**
+ * The new value; the property is removed when neither this nor
+ * `items` is set.
+ */
+value: string | null, property_type: string | null, 
+/**
+ * Items of a list property, stored as given (`value` is ignored).
+ */
+items: Array<string> | null, };
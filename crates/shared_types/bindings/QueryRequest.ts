@@ -2,6 +2,7 @@
 import type { FilterMatchMode } from "./FilterMatchMode";
 import type { PropertyFilter } from "./PropertyFilter";
 import type { QueryResultType } from "./QueryResultType";
+import type { QuerySort } from "./QuerySort";
 
 /**
  * Request to run a query.
@@ -26,4 +27,10 @@ include_completed: boolean,
 /**
  * Maximum number of results.
  */
-limit: number | null, };
+limit: number | null, 
+/**
+ * Sort by a property (or `_path`, `note_title`). Tasks are sorted by
+ * their note's value first. Defaults to path order for notes and
+ * due date and priority for tasks.
+ */
+sort: QuerySort | null, };
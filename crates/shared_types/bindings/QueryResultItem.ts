@@ -22,4 +22,16 @@ note: NoteListItem | null,
 /**
  * Properties of the note (for display in results).
  */
-properties: Array<PropertyDto>, };
+properties: Array<PropertyDto>, 
+/**
+ * Manual position on the Kanban board the query is shown on, if the
+ * card was moved by hand. Lower ranks come first.
+ */
+rank: number | null, 
+/**
+ * Stable reference to the item, for storing its position on a board:
+ * `note:<id>` for notes and `task:<note id>:<text hash>:<n>` for the
+ * nth task (from 0) with that text in its note, so a task keeps it when
+ * lines are added above it. Filled in for Kanban views.
+ */
+item_ref: string, };
@@ -28,6 +28,11 @@ result_type: QueryResultType,
  * Include completed tasks. Defaults to false.
  */
 include_completed: boolean, 
+/**
+ * Also include the tasks of notes embedded (`![[note]]`) in the
+ * matching notes, one level deep. Defaults to false.
+ */
+include_embedded: boolean, 
 /**
  * Maximum number of results. Defaults to 50.
  */
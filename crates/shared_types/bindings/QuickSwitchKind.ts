@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a quick switcher result matched.
+ */
+export type QuickSwitchKind = "title" | "alias" | "path" | "heading" | "recent";
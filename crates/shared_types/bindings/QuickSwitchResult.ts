@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuickSwitchKind } from "./QuickSwitchKind";
+
+/**
+ * A ranked quick switcher (Ctrl+P) result.
+ */
+export type QuickSwitchResult = { kind: QuickSwitchKind, note_id: bigint, path: string, title: string | null, 
+/**
+ * The matched text (title, alias, path, or heading).
+ */
+text: string, 
+/**
+ * Line of the heading (1-indexed), for heading results.
+ */
+line_number: bigint | null, 
+/**
+ * Higher is better.
+ */
+score: bigint, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Filters for picking a random note.
+ */
+export type RandomNoteFilter = { 
+/**
+ * Only notes in this folder (including subfolders).
+ */
+folder: string | null, 
+/**
+ * Only notes with this tag (including nested tags like `tag/sub`).
+ */
+tag: string | null, };
@@ -0,0 +1,50 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthBucket } from "./LengthBucket";
+
+/**
+ * Text statistics and readability scores for a note body.
+ *
+ * Headings, code, and frontmatter are not counted.
+ */
+export type ReadabilityReport = { word_count: number, sentence_count: number, syllable_count: number, 
+/**
+ * Words per sentence.
+ */
+average_sentence_length: number, 
+/**
+ * Characters per word.
+ */
+average_word_length: number, 
+/**
+ * Sentence lengths in words.
+ */
+sentence_lengths: Array<LengthBucket>, 
+/**
+ * Word lengths in characters.
+ */
+word_lengths: Array<LengthBucket>, 
+/**
+ * Sentences of more than 25 words.
+ */
+long_sentence_count: number, 
+/**
+ * Sentences that look like passive voice ("was written", "is being built").
+ */
+passive_sentence_count: number, 
+/**
+ * The first few passive sentences, for review.
+ */
+passive_sentences: Array<string>, 
+/**
+ * Flesch reading ease (higher is easier; 60-70 is plain English).
+ * None for text without sentences.
+ */
+flesch_reading_ease: number | null, 
+/**
+ * Flesch-Kincaid grade level.
+ */
+flesch_kincaid_grade: number | null, 
+/**
+ * Reading time at 200 words per minute, rounded up.
+ */
+reading_time_minutes: number, };
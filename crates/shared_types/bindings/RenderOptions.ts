@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathRendering } from "./MathRendering";
+
+/**
+ * Options for rendering a note to HTML.
+ */
+export type RenderOptions = { 
+/**
+ * How many levels of note embeds are inlined; deeper embeds become links.
+ */
+max_embed_depth: number, 
+/**
+ * Prefix for internal note links; the vault path is appended
+ * (e.g. "note:Projects/Plan.md#goals").
+ */
+link_prefix: string, 
+/**
+ * Include private sections (`%%private%% ... %%end%%`).
+ */
+include_private: boolean, 
+/**
+ * How math is rendered; the vault's math settings when not set.
+ */
+math: MathRendering | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A note rendered to HTML.
+ */
+export type RenderedNote = { note_id: bigint, path: string, title: string | null, 
+/**
+ * Sanitized HTML of the note body (without frontmatter).
+ */
+html: string, 
+/**
+ * CSS classes from the note's `cssclasses` property, for the element
+ * the HTML is placed in.
+ */
+cssclasses: Array<string>, };
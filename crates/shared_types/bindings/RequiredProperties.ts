@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Properties every note in a folder (and its subfolders) must have.
+ */
+export type RequiredProperties = { 
+/**
+ * Folder relative to the vault root; empty for the whole vault.
+ */
+folder: string, keys: Array<string>, };
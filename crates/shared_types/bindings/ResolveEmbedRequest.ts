@@ -13,6 +13,12 @@ target: string,
  */
 section: string | null, 
 /**
- * Current embedding depth (starts at 0, max 3).
+ * Current embedding depth (starts at 0, max 3). Nested embeds of the
+ * target are followed only up to the maximum.
  */
-depth: number, };
+depth: number, 
+/**
+ * Path of the note containing the embed; if several notes match the
+ * target, one in the same folder is preferred.
+ */
+source_path: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Request to create or change a scheduled note rule.
+ */
+export type SaveScheduledNoteRuleRequest = { name: string, rrule: string, time: string, path_pattern: string, template_path: string | null, enabled: boolean, };
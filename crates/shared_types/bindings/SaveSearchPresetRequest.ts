@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchFilters } from "./SearchFilters";
+
+/**
+ * Request to save a search preset. Saving under an existing name replaces it.
+ */
+export type SaveSearchPresetRequest = { name: string, query: string, filters: SearchFilters, };
@@ -3,13 +3,32 @@
 /**
  * A scheduled time block (optionally linked to a note).
  */
-export type ScheduleBlockDto = { id: bigint, note_id: bigint | null, date: string, start_time: string, end_time: string, label: string | null, color: string | null, context: string | null, 
+export type ScheduleBlockDto = { 
+/**
+ * The schedule block's id; 0 for calendar subscription events, which
+ * aren't schedule blocks.
+ */
+id: bigint, 
+/**
+ * Unique among blocks and calendar events: `block:<id>` or
+ * `calendar:<event id>`.
+ */
+key: string, note_id: bigint | null, date: string, start_time: string, end_time: string, label: string | null, color: string | null, context: string | null, 
 /**
  * RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR").
  */
 rrule: string | null, 
+/**
+ * IANA time zone of `date`/`start_time`/`end_time` (e.g., "Europe/Berlin").
+ * None means the vault's local time.
+ */
+timezone: string | null, 
 /**
  * True if this is an occurrence of a recurring block (not the master).
  * Occurrences have the same id as their master but different dates.
  */
-is_occurrence: boolean, };
+is_occurrence: boolean, 
+/**
+ * True for events from a calendar subscription, shown as a read-only overlay.
+ */
+read_only: boolean, };
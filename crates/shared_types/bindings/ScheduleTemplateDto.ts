@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScheduleTemplateSlot } from "./ScheduleTemplateSlot";
+
+/**
+ * A named routine of time slots that can be applied to any date.
+ */
+export type ScheduleTemplateDto = { id: bigint, name: string, description: string | null, 
+/**
+ * Slots ordered by start time.
+ */
+slots: Array<ScheduleTemplateSlot>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A single time slot within a schedule template.
+ */
+export type ScheduleTemplateSlot = { start_time: string, end_time: string, label: string | null, color: string | null, context: string | null, };
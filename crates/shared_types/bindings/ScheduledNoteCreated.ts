@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A note created by a scheduled note rule.
+ */
+export type ScheduledNoteCreated = { rule_id: bigint, 
+/**
+ * The occurrence the note was created for (local time).
+ */
+scheduled_for: string, path: string, note_id: bigint, };
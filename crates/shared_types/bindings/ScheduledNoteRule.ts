@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A rule that creates a note from a template on a schedule, e.g. every
+ * Monday at 07:00 create `Weekly/{{date:YYYY-[W]WW}}.md`.
+ */
+export type ScheduledNoteRule = { id: bigint, name: string, 
+/**
+ * Recurrence as an RRULE without DTSTART (e.g. "FREQ=WEEKLY;BYDAY=MO").
+ */
+rrule: string, 
+/**
+ * Local time of day the note is created ("HH:MM").
+ */
+time: string, 
+/**
+ * Path of the note, rendered for the day it is created on.
+ */
+path_pattern: string, 
+/**
+ * Template for the note's content (relative to vault root). The note
+ * only gets a title if there is none.
+ */
+template_path: string | null, enabled: boolean, 
+/**
+ * When the rule last ran (local time, "YYYY-MM-DDTHH:MM:SS"). Missed
+ * occurrences since then are caught up on.
+ */
+last_run: string | null, 
+/**
+ * When the rule was created (local time); the recurrence starts here.
+ */
+created_at: string, };
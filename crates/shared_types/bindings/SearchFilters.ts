@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Scoping filters for a search.
+ */
+export type SearchFilters = { 
+/**
+ * Only notes in this folder (including subfolders).
+ */
+folder: string | null, 
+/**
+ * Only notes with all of these tags.
+ */
+tags: Array<string>, 
+/**
+ * Range on the note's modification date: "today", "this_week",
+ * "this_month", "this_year", or "YYYY-MM-DD..YYYY-MM-DD" (either end
+ * may be left open).
+ */
+date_range: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchFilters } from "./SearchFilters";
+
+/**
+ * A named search with its filters, for one-click searches.
+ */
+export type SearchPresetDto = { id: bigint, name: string, 
+/**
+ * FTS search string.
+ */
+query: string, filters: SearchFilters, };
@@ -3,4 +3,9 @@
 /**
  * A search result.
  */
-export type SearchResult = { note_id: bigint, path: string, title: string | null, snippet: string | null, score: number, };
+export type SearchResult = { note_id: bigint, path: string, title: string | null, snippet: string | null, score: number, 
+/**
+ * The note was saved since it was indexed for search, so the snippet
+ * may be out of date (the index catches up within seconds).
+ */
+stale: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The section of a note a semantic search result matched.
+ */
+export type SearchSection = { 
+/**
+ * Headings leading to the section, outermost first.
+ */
+heading_path: Array<string>, snippet: string, 
+/**
+ * First and last line of the section in the note (1-indexed), for
+ * scrolling the editor to the match.
+ */
+start_line: number, end_line: number, };
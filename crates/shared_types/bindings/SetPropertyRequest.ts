@@ -3,4 +3,9 @@
 /**
  * Request to set a property value.
  */
-export type SetPropertyRequest = { note_id: bigint, key: string, value: string | null, property_type: string | null, };
+export type SetPropertyRequest = { note_id: bigint, key: string, value: string | null, property_type: string | null, 
+/**
+ * Items of a list property. When set, they are stored as given
+ * (items may contain commas) and `value` is ignored.
+ */
+items: Array<string> | null, };
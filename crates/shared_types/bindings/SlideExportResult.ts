@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of exporting a note as an HTML slide deck.
+ */
+export type SlideExportResult = { output_path: string, slide_count: number, };
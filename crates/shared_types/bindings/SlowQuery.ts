@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A SQL statement that took longer than the slow query threshold.
+ */
+export type SlowQuery = { timestamp: string, 
+/**
+ * The start of the statement (e.g. "SELECT id, path FROM notes …").
+ */
+summary: string, 
+/**
+ * The full statement, or empty if the summary is all of it.
+ */
+statement: string, elapsed_ms: number, 
+/**
+ * The command running when the statement was executed, if known.
+ */
+command: string | null, };
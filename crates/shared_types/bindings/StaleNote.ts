@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StaleReason } from "./StaleReason";
+
+/**
+ * A note whose database copy is stale.
+ */
+export type StaleNote = { path: string, 
+/**
+ * The note's ID, or None if it isn't indexed.
+ */
+note_id: bigint | null, reason: StaleReason, 
+/**
+ * When the note was last indexed.
+ */
+indexed_at: string | null, 
+/**
+ * When the file was last modified.
+ */
+modified_at: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a note's database copy doesn't match its file.
+ */
+export type StaleReason = "not_indexed" | "file_missing" | "modified_after_indexing" | "content_mismatch";
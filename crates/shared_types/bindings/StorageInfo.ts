@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataLocation } from "./DataLocation";
+
+/**
+ * Where the open vault's database and caches currently are.
+ */
+export type StorageInfo = { data_location: DataLocation, 
+/**
+ * Absolute path of the directory holding the database and caches.
+ */
+data_dir: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataLocation } from "./DataLocation";
+
+/**
+ * Storage settings (stored in vault config).
+ */
+export type StorageSettings = { data_location: DataLocation, };
@@ -1,6 +1,19 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 /**
- * A tag with usage count.
+ * A tag with usage count. Notes tagged with one of its aliases count
+ * towards the tag.
  */
-export type TagDto = { tag: string, count: bigint, };
+export type TagDto = { tag: string, count: bigint, 
+/**
+ * Description from the tag's metadata.
+ */
+description: string | null, 
+/**
+ * Display color (a CSS color) from the tag's metadata.
+ */
+color: string | null, 
+/**
+ * Tags declared as aliases of this one.
+ */
+aliases: Array<string>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Vault-level metadata for a tag (without `#`).
+ */
+export type TagMeta = { tag: string, 
+/**
+ * The tag this one is an alias of; it is then listed and filtered as
+ * that tag.
+ */
+alias_of: string | null, description: string | null, 
+/**
+ * Display color (a CSS color, e.g. `#e06c75`).
+ */
+color: string | null, };
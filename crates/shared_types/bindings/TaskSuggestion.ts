@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskWithContext } from "./TaskWithContext";
+
+/**
+ * A task ranked by the "what should I do now" suggestion engine.
+ */
+export type TaskSuggestion = { 
+/**
+ * The suggested task.
+ */
+task: TaskWithContext, 
+/**
+ * Ranking score (higher is better).
+ */
+score: number, 
+/**
+ * Human-readable reasons contributing to the score (e.g., "due today").
+ */
+reasons: Array<string>, };
@@ -21,4 +21,9 @@ note_title: string | null,
 /**
  * Properties inherited from the parent note (e.g., project, area).
  */
-note_properties: Array<PropertyDto>, };
+note_properties: Array<PropertyDto>, 
+/**
+ * Path of the note that embeds the task's note, for tasks a query
+ * includes from embedded notes.
+ */
+embedded_in: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Values from the app for rendering a template string.
+ */
+export type TemplateRenderContext = { 
+/**
+ * Date the date variables are for, as YYYY-MM-DD. Defaults to today.
+ */
+date: string | null, 
+/**
+ * Title of the active note, for `{{title}}`.
+ */
+note_title: string | null, 
+/**
+ * Clipboard text, for `{{clipboard}}`.
+ */
+clipboard: string | null, };
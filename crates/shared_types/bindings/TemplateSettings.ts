@@ -1,4 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TemplateSnippet } from "./TemplateSnippet";
+import type { TemplateVariable } from "./TemplateVariable";
 
 /**
  * Settings for template system (stored in vault config).
@@ -11,4 +13,37 @@ daily_template_path: string | null,
 /**
  * Pattern for daily note file paths (e.g., "journal/{{year}}/{{month}}/{{date}}.md").
  */
-daily_note_pattern: string, };
+daily_note_pattern: string, 
+/**
+ * Journal prompts rotated daily and available as `{{prompt}}` in the daily note template.
+ */
+journal_prompts: Array<string>, 
+/**
+ * Path to the weekly note template file (relative to vault root).
+ */
+weekly_template_path: string | null, 
+/**
+ * Pattern for weekly note file paths, rendered for the week's first day
+ * with the ISO week of its Monday (e.g.,
+ * "journal/{{week_year}}/{{week_year}}-W{{week}}.md" or
+ * "journal/{{iso_week}}.md").
+ */
+weekly_note_pattern: string, 
+/**
+ * Path to the monthly note template file (relative to vault root).
+ */
+monthly_template_path: string | null, 
+/**
+ * Pattern for monthly note file paths, rendered for the first of the
+ * month (e.g., "journal/{{year}}/{{year}}-{{month}}.md").
+ */
+monthly_note_pattern: string, 
+/**
+ * User-defined variables, available as `{{name}}` in all templates.
+ * Values may use the built-in variables (e.g. "Week {{week}}").
+ */
+variables: Array<TemplateVariable>, 
+/**
+ * Reusable blocks of text, inserted with `{{snippet:name}}`.
+ */
+snippets: Array<TemplateSnippet>, };
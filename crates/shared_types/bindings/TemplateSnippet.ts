@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A reusable block of template text.
+ */
+export type TemplateSnippet = { 
+/**
+ * Name used in `{{snippet:name}}`.
+ */
+name: string, 
+/**
+ * The text, rendered like the template it is inserted into.
+ */
+content: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A user-defined template variable.
+ */
+export type TemplateVariable = { 
+/**
+ * Name used in templates, without braces (e.g. "author").
+ */
+name: string, value: string, };
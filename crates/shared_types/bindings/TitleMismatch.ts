@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TitleSource } from "./TitleSource";
+
+/**
+ * A note whose title does not match its filename.
+ */
+export type TitleMismatch = { note_id: bigint, path: string, title: string, title_source: TitleSource, 
+/**
+ * Path after renaming the file after the title.
+ */
+suggested_path: string, 
+/**
+ * Title after changing it to the filename.
+ */
+suggested_title: string, };
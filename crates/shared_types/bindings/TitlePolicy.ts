@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which side wins when a note's title and filename differ.
+ */
+export type TitlePolicy = "filename_from_title" | "title_from_filename";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TitlePolicy } from "./TitlePolicy";
+
+/**
+ * Settings for keeping titles and filenames in sync (stored in vault config).
+ */
+export type TitleSettings = { policy: TitlePolicy, 
+/**
+ * Folders whose notes are not checked (e.g. daily notes named by date).
+ */
+ignored_folders: Array<string>, };
@@ -15,4 +15,8 @@ priority: string | null,
 /**
  * Due date as YYYY-MM-DD string.
  */
-due_date: string | null, created_at: string | null, completed_at: string | null, };
+due_date: string | null, 
+/**
+ * Estimated effort in minutes (from an `est:` token).
+ */
+estimate_minutes: number | null, created_at: string | null, completed_at: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Editor content that was never saved to its file, kept for crash recovery.
+ */
+export type UnsavedDraft = { path: string, content: string, saved_at: string, 
+/**
+ * The file changed on disk since the draft was started, so restoring
+ * the draft would overwrite those changes.
+ */
+file_changed: boolean, };
@@ -8,4 +8,8 @@ export type UpdateScheduleBlockRequest = { id: bigint, note_id: bigint | null, d
  * RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR").
  * Set to empty string to clear recurrence.
  */
-rrule: string | null, };
+rrule: string | null, 
+/**
+ * IANA time zone for the block's times. Set to empty string to revert to local time.
+ */
+timezone: string | null, };
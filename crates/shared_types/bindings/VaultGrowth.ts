@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GrowthPoint } from "./GrowthPoint";
+import type { VaultMetric } from "./VaultMetric";
+
+/**
+ * How a metric changed over a date range.
+ */
+export type VaultGrowth = { metric: VaultMetric, 
+/**
+ * The days the metric changed on, ordered by date. The first point is
+ * the value at the start of the range, if it is known. Days without a
+ * point have the value of the point before.
+ */
+points: Array<GrowthPoint>, 
+/**
+ * Change from the first point to the last.
+ */
+change: bigint, };
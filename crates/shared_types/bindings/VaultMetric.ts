@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A vault metric tracked by the daily snapshots.
+ */
+export type VaultMetric = "notes" | "words" | "tasks" | "completed_tasks" | "links";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The vault's metrics on one day.
+ */
+export type VaultMetricsSnapshot = { 
+/**
+ * Date as YYYY-MM-DD string.
+ */
+date: string, notes: bigint, words: bigint, tasks: bigint, completed_tasks: bigint, links: bigint, };
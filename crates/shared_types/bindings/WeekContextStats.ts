@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextMinutes } from "./ContextMinutes";
+import type { FocusStretch } from "./FocusStretch";
+
+/**
+ * Context switching statistics for a week (from the vault's week start).
+ */
+export type WeekContextStats = { week_start: string, switches: bigint, 
+/**
+ * Average switches per day with at least one block.
+ */
+average_daily_switches: number, context_minutes: Array<ContextMinutes>, longest_focus: FocusStretch | null, };
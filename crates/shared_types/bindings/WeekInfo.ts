@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An ISO 8601 week: weeks start on Monday, and week 1 is the week with the
+ * year's first Thursday, so the first and last days of a year can belong to
+ * a week of the year before or after.
+ */
+export type WeekInfo = { 
+/**
+ * The year the week belongs to.
+ */
+year: number, 
+/**
+ * The week number (1-53).
+ */
+week: number, 
+/**
+ * The week as "2024-W15".
+ */
+label: string, 
+/**
+ * The week's Monday (YYYY-MM-DD).
+ */
+start: string, 
+/**
+ * The week's Sunday (YYYY-MM-DD).
+ */
+end: string, };
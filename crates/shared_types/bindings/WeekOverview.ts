@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayOverview } from "./DayOverview";
+
+/**
+ * A week of schedule blocks, tasks, habits, and note counts in one payload.
+ */
+export type WeekOverview = { start_date: string, end_date: string, days: Array<DayOverview>, };
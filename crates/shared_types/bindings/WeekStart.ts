@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The day weeks start on.
+ */
+export type WeekStart = "monday" | "sunday" | "saturday";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Main window size and position, in logical pixels.
+ */
+export type WindowLayout = { width: number, height: number, x: number | null, y: number | null, maximized: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sidebar layout.
+ */
+export type WorkspaceSidebar = { open: boolean, 
+/**
+ * Width in pixels (None for the default width).
+ */
+width: number | null, 
+/**
+ * Selected sidebar panel (e.g. "files", "tags").
+ */
+active_panel: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorkspaceSidebar } from "./WorkspaceSidebar";
+import type { WorkspaceTab } from "./WorkspaceTab";
+
+/**
+ * UI session state of a vault.
+ *
+ * Tabs follow their notes across renames; tabs of deleted notes are dropped.
+ */
+export type WorkspaceState = { 
+/**
+ * Open tabs in display order.
+ */
+tabs: Array<WorkspaceTab>, 
+/**
+ * Path of the active tab.
+ */
+active_path: string | null, sidebar: WorkspaceSidebar, };
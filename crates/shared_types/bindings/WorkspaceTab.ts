@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An open note tab.
+ */
+export type WorkspaceTab = { 
+/**
+ * Note path relative to the vault root.
+ */
+path: string, 
+/**
+ * Editor scroll offset in pixels.
+ */
+scroll_top: number, 
+/**
+ * Cursor offset in the note, if known.
+ */
+cursor_position: bigint | null, };
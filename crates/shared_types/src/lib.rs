@@ -10,8 +10,10 @@
 //! - `todo` - Task/todo types
 //! - `schedule` - Schedule block types
 //! - `tag` - Tag types
-//! - `backlink` - Backlink types
-//! - `vault` - Vault info types
+//! - `backlink` - Backlink and link types
+//! - `book` - Book metadata and reading shelves
+//! - `duplicate` - Duplicate note detection types
+//! - `vault` - Vault info and storage location types
 //! - `search` - Search types (FTS5 and hybrid search)
 //! - `embedding` - Embedding settings for semantic search
 //! - `folder` - Folder tree types
@@ -20,7 +22,19 @@
 //! - `embed` - Embed resolution types
 //! - `query` - Query builder types
 //! - `query_embed` - Query embed/block types
+//! - `readability` - Text statistics and readability scores
+//! - `render` - Note rendering (HTML) types
 //! - `import` - Vault import types
+//! - `job` - Background job queue types
+//! - `lint` - Note linting rules and diagnostics
+//! - `attachment` - Attachment location and naming settings
+//! - `mood` - Mood tracking types
+//! - `growth` - Vault statistics over time
+//! - `goal` - Goal tracking types
+//! - `review` - Note review queue types
+//! - `spellcheck` - Spell check results
+//! - `title` - Title and filename consistency types
+//! - `workspace` - Persisted UI session state
 
 mod types;
 
@@ -0,0 +1,99 @@
+//! App settings types - per-device settings stored outside the vault.
+//!
+//! Vault settings (templates, daily note folder, lint rules) live in the
+//! vault's `.neuroflow/config.json` and travel with the vault. These
+//! settings belong to one device (theme, editor font, window layout) and
+//! are kept in the OS config directory instead.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::embedding::EmbeddingSettings;
+
+/// Editor appearance.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EditorSettings {
+    /// CSS font family (None for the theme's font).
+    #[serde(default)]
+    pub font_family: Option<String>,
+    /// Font size in pixels (None for the theme's size).
+    #[serde(default)]
+    pub font_size: Option<f64>,
+    #[serde(default)]
+    pub line_height: Option<f64>,
+    /// Dictionary language for spell checking in the editor (e.g. "en_US");
+    /// None turns spell checking off.
+    #[serde(default)]
+    pub spell_check_language: Option<String>,
+}
+
+/// Main window size and position, in logical pixels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WindowLayout {
+    pub width: f64,
+    pub height: f64,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+/// Settings of this device.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AppSettings {
+    /// Vault opened on startup.
+    #[serde(default)]
+    pub last_vault_path: Option<String>,
+    /// "system", "light", "dark" or a custom theme name.
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default)]
+    pub vim_mode: bool,
+    /// "daily", "weekly" or "monthly".
+    #[serde(default = "default_calendar_view")]
+    pub default_calendar_view: String,
+    #[serde(default)]
+    pub editor: EditorSettings,
+    /// Keep drafts of unsaved edits, to offer them for recovery if the app
+    /// quits before a note is saved.
+    #[serde(default)]
+    pub crash_recovery: bool,
+    /// Window layout when the app was last closed.
+    #[serde(default)]
+    pub window: Option<WindowLayout>,
+    /// Semantic search settings (the embedding server runs on this device).
+    #[serde(default)]
+    pub embedding_settings: EmbeddingSettings,
+    /// Diagram renderer installed on this device (see `DiagramSettings`);
+    /// the app's bundled renderer if unset. Kept here rather than in the
+    /// vault config, so opening a vault never runs a program the vault chose.
+    #[serde(default)]
+    pub diagram_renderer_path: Option<String>,
+}
+
+fn default_theme() -> String {
+    "system".to_string()
+}
+
+fn default_calendar_view() -> String {
+    "weekly".to_string()
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            last_vault_path: None,
+            theme: default_theme(),
+            vim_mode: false,
+            default_calendar_view: default_calendar_view(),
+            editor: EditorSettings::default(),
+            crash_recovery: false,
+            window: None,
+            embedding_settings: EmbeddingSettings::default(),
+            diagram_renderer_path: None,
+        }
+    }
+}
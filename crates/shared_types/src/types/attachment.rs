@@ -0,0 +1,97 @@
+//! Attachment settings (where pasted images are saved and how they're named),
+//! attachment deduplication results and text recognized in images.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Where new attachments are saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentLocation {
+    /// A fixed folder relative to the vault root.
+    #[default]
+    VaultFolder,
+    /// The folder of the note the attachment is added to.
+    SameFolder,
+    /// A subfolder of the note's folder (e.g. "assets" next to the note).
+    NoteSubfolder,
+}
+
+/// Settings for saving attachments (stored in vault config).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttachmentSettings {
+    #[serde(default)]
+    pub location: AttachmentLocation,
+
+    /// Folder name for `vault_folder` and `note_subfolder`.
+    pub folder: String,
+
+    /// File name pattern without extension (e.g., "Pasted image {{timestamp}}").
+    /// Supports the template date variables plus `{{time}}`, `{{timestamp}}`,
+    /// and `{{note}}` (name of the embedding note).
+    pub name_template: String,
+}
+
+impl Default for AttachmentSettings {
+    fn default() -> Self {
+        Self {
+            location: AttachmentLocation::VaultFolder,
+            folder: "assets".to_string(),
+            name_template: "Pasted image {{timestamp}}".to_string(),
+        }
+    }
+}
+
+/// Identical attachments merged into one by `dedupe_attachments`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DuplicateAttachments {
+    /// The copy that was kept (vault-relative path).
+    pub kept: String,
+    /// The copies whose embeds now point at `kept`. They are deleted unless
+    /// the vault has encrypted notes (see `AttachmentDedupeResult`).
+    pub removed: Vec<String>,
+    /// Size of one copy in bytes.
+    pub size: u64,
+}
+
+/// Result of merging identical attachments.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttachmentDedupeResult {
+    pub duplicates: Vec<DuplicateAttachments>,
+    /// Notes whose embeds and links were rewritten.
+    pub notes_updated: usize,
+    /// Disk space freed, in bytes.
+    pub bytes_saved: u64,
+    /// Encrypted notes, whose embeds can't be read or rewritten. While there
+    /// are any, the merged copies are kept on disk, as those notes may
+    /// embed them.
+    pub encrypted_notes: usize,
+}
+
+/// Result of recognizing the text in the vault's images.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OcrResult {
+    /// Images whose text was recognized.
+    pub images_recognized: usize,
+    /// Images skipped because they haven't changed since.
+    pub images_unchanged: usize,
+    /// Images that could not be recognized, with the reason.
+    pub warnings: Vec<String>,
+    /// Recognition was cancelled; text recognized so far is kept.
+    pub cancelled: bool,
+}
+
+/// An image whose recognized text matches a search.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ImageTextMatch {
+    /// Vault-relative path of the image.
+    pub path: String,
+    /// The recognized text around the match.
+    pub snippet: String,
+}
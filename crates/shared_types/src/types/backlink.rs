@@ -1,8 +1,10 @@
-//! Backlink types.
+//! Backlink and link types.
 
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use super::note::NoteListItem;
+
 /// A backlink (note that links to another note).
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -11,3 +13,190 @@ pub struct BacklinkDto {
     pub from_note_path: String,
     pub from_note_title: Option<String>,
 }
+
+/// A note an ambiguous wiki link could refer to.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LinkCandidate {
+    pub note_id: i64,
+    pub path: String,
+    pub title: Option<String>,
+    /// Folder of the note ("" for the vault root).
+    pub folder: String,
+    /// Whether the note is in the same folder as the linking note, which
+    /// makes it the one the link resolves to.
+    pub same_folder: bool,
+}
+
+/// How a note matches a reference to it (a link target, an embed or a
+/// typed name), from most to least certain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteMatchKind {
+    /// The vault-relative path.
+    Path,
+    /// The file name, in the linking note's folder.
+    SameFolder,
+    /// The file name (or the end of the path), in another folder.
+    Name,
+    Title,
+    Alias,
+    /// A near-typo of the file name, title or an alias. Only suggested:
+    /// links don't resolve to these.
+    Fuzzy,
+}
+
+/// A note a reference could refer to.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteMatch {
+    pub note_id: i64,
+    pub path: String,
+    pub title: Option<String>,
+    pub kind: NoteMatchKind,
+    /// Higher is better; notes closer to the linking note's folder score
+    /// higher within a kind.
+    pub score: i64,
+    /// How sure this match is the note meant, from 0 to 1.
+    pub confidence: f64,
+}
+
+/// The notes a reference could refer to, best first.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteResolution {
+    pub candidates: Vec<NoteMatch>,
+    /// How sure the first candidate is the note meant, from 0 to 1: lower
+    /// when other notes match the same way.
+    pub confidence: f64,
+}
+
+/// A note with its number of links, for orphan, dead-end and hub reports.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteLinkCount {
+    pub note: NoteListItem,
+    /// Links from other notes to this note.
+    pub inbound: i64,
+    /// Links from this note to other notes.
+    pub outbound: i64,
+}
+
+/// A note suggested as a link target for the paragraph being written.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LinkSuggestion {
+    pub note: NoteListItem,
+    /// Ranking score (higher is better).
+    pub score: f64,
+    /// Title or alias that matched the link being typed, if any.
+    pub matched_text: Option<String>,
+    /// Tags the note shares with the paragraph.
+    pub shared_tags: Vec<String>,
+    /// Embedding similarity to the paragraph, when embeddings are available.
+    pub similarity: Option<f64>,
+}
+
+/// How the app writes wiki link targets, e.g. when updating links after a
+/// rename or move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkFormat {
+    /// The note's name, or its path if the name would resolve to another note.
+    #[default]
+    Shortest,
+    /// The path from the vault root.
+    Absolute,
+    /// The path from the linking note's folder (`./` or `../`).
+    Relative,
+}
+
+/// Settings for links written by the app and for keeping old links
+/// working (stored in vault config).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LinkSettings {
+    #[serde(default)]
+    pub link_format: LinkFormat,
+    /// When a note's H1 title changes, add the old title to the `aliases`
+    /// in its frontmatter so `[[Old Title]]` links keep resolving.
+    #[serde(default)]
+    pub keep_old_titles: bool,
+}
+
+/// File format of a link graph export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphExportFormat {
+    /// Graphviz DOT.
+    Dot,
+    /// GEXF, for Gephi.
+    Gexf,
+    /// `LinkGraph` as JSON.
+    Json,
+}
+
+impl GraphExportFormat {
+    /// File extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphExportFormat::Dot => "dot",
+            GraphExportFormat::Gexf => "gexf",
+            GraphExportFormat::Json => "json",
+        }
+    }
+}
+
+/// The notes a link graph export covers. Empty means the whole vault; with
+/// both set, notes must be in the folder and have the tag.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GraphScope {
+    /// Folder (relative to vault root), including subfolders.
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Tag (without `#`), including nested tags (`project` covers `project/a`).
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// A note in a link graph.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GraphNode {
+    pub id: i64,
+    pub path: String,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// The links from one note to another in a link graph.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GraphEdge {
+    pub source: i64,
+    pub target: i64,
+    /// Number of links from the source to the target.
+    pub weight: i64,
+}
+
+/// The link structure of (part of) the vault. Edges only connect notes in
+/// the graph.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LinkGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Result of exporting the link graph to a file.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GraphExportResult {
+    pub output_path: String,
+    pub node_count: usize,
+    pub edge_count: usize,
+}
@@ -0,0 +1,17 @@
+//! Vault backups.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A backup zip written by a backup job.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BackupResult {
+    /// Where the zip was written.
+    pub output_path: String,
+    /// Number of files in the zip, including the database copy.
+    pub files: usize,
+    pub size_bytes: u64,
+    /// The backup was cancelled and no zip was written.
+    pub cancelled: bool,
+}
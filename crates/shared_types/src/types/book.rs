@@ -0,0 +1,33 @@
+//! Book tracking types (reading log metadata and shelves).
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::QueryResultItem;
+
+/// Book metadata looked up on OpenLibrary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BookMetadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    /// ISBN-13 if known, else ISBN-10.
+    pub isbn: Option<String>,
+    /// Publication date as given by OpenLibrary (often just the year).
+    pub published: Option<String>,
+    pub pages: Option<i64>,
+    /// URL of the large cover image.
+    pub cover_url: Option<String>,
+    /// Vault path of the downloaded cover (None if there is none or it
+    /// failed to download).
+    pub cover_path: Option<String>,
+}
+
+/// Books with the same reading status.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BookShelf {
+    /// The `status` property value (None for books without one).
+    pub status: Option<String>,
+    pub books: Vec<QueryResultItem>,
+}
@@ -0,0 +1,94 @@
+//! Diagnostics types: command latency, slow queries, files that could not
+//! be read cleanly and bug report bundles.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Latency of a command over its recent calls.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CommandLatency {
+    pub command: String,
+    /// Calls since the app started.
+    pub count: i64,
+    /// Median latency of the recent calls.
+    pub p50_ms: f64,
+    /// 95th percentile latency of the recent calls.
+    pub p95_ms: f64,
+    /// Slowest of the recent calls.
+    pub max_ms: f64,
+}
+
+/// A SQL statement that took longer than the slow query threshold.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SlowQuery {
+    pub timestamp: DateTime<Utc>,
+    /// The start of the statement (e.g. "SELECT id, path FROM notes …").
+    pub summary: String,
+    /// The full statement, or empty if the summary is all of it.
+    pub statement: String,
+    pub elapsed_ms: f64,
+    /// The command running when the statement was executed, if known.
+    pub command: Option<String>,
+}
+
+/// Per-command latency and recent slow queries, since the app started.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PerformanceMetrics {
+    /// Commands by p95 latency, slowest first.
+    pub commands: Vec<CommandLatency>,
+    /// Most recent first.
+    pub slow_queries: Vec<SlowQuery>,
+    pub slow_query_threshold_ms: u64,
+}
+
+/// A diagnostics bundle written for a bug report.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DiagnosticsExport {
+    /// Where the zip was written.
+    pub path: String,
+    pub size_bytes: u64,
+    /// The files in the zip.
+    pub entries: Vec<String>,
+}
+
+/// What is wrong with a file found in the vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum FileProblemKind {
+    /// Not valid UTF-8: indexed with the invalid bytes replaced by `\u{FFFD}`.
+    InvalidUtf8,
+    /// A binary file with a `.md` name: not indexed.
+    Binary,
+}
+
+impl FileProblemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileProblemKind::InvalidUtf8 => "invalid_utf8",
+            FileProblemKind::Binary => "binary",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Self {
+        match s {
+            "binary" => FileProblemKind::Binary,
+            _ => FileProblemKind::InvalidUtf8,
+        }
+    }
+}
+
+/// A file that could not be read cleanly when it was last indexed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileProblem {
+    /// Vault-relative path.
+    pub path: String,
+    pub kind: FileProblemKind,
+    pub detected_at: DateTime<Utc>,
+}
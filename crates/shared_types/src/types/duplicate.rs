@@ -0,0 +1,31 @@
+//! Duplicate note detection types.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A note in a duplicate cluster.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DuplicateNote {
+    pub note_id: i64,
+    pub path: String,
+    pub title: Option<String>,
+    pub word_count: u32,
+    pub backlink_count: i64,
+    /// Similarity to the cluster's merge target (0.0 - 1.0; 1.0 for the target).
+    pub similarity: f64,
+}
+
+/// Notes with the same or nearly the same content.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DuplicateCluster {
+    /// All notes have the same text (ignoring frontmatter, formatting, and case).
+    pub exact: bool,
+    /// Lowest similarity of a note to the merge target.
+    pub similarity: f64,
+    /// The note to keep when merging: most backlinks, then most words.
+    pub merge_target_id: i64,
+    /// The merge target first, then by similarity.
+    pub notes: Vec<DuplicateNote>,
+}
@@ -11,8 +11,13 @@ pub struct ResolveEmbedRequest {
     pub target: String,
     /// Optional section slug to extract (e.g., "my-section" from "## My Section").
     pub section: Option<String>,
-    /// Current embedding depth (starts at 0, max 3).
+    /// Current embedding depth (starts at 0, max 3). Nested embeds of the
+    /// target are followed only up to the maximum.
     pub depth: u8,
+    /// Path of the note containing the embed; if several notes match the
+    /// target, one in the same folder is preferred.
+    #[serde(default)]
+    pub source_path: Option<String>,
 }
 
 /// Result of resolving an embed.
@@ -31,6 +36,21 @@ pub struct EmbedContent {
     pub asset_url: Option<String>,
     /// Error message if resolution failed.
     pub error: Option<String>,
+    /// The embed loop, if the target (directly or through its own embeds)
+    /// embeds a note that is already being embedded.
+    #[serde(default)]
+    pub cycle: Option<EmbedCycle>,
+}
+
+/// An embed loop found while resolving an embed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EmbedCycle {
+    /// The embeds resolved, as `path` or `path#section`: the note with the
+    /// embed first, then each embedded note, up to and including `repeated`.
+    pub chain: Vec<String>,
+    /// The entry that appears twice, closing the loop.
+    pub repeated: String,
 }
 
 /// Information about a heading in a note (for section autocomplete).
@@ -17,6 +17,14 @@ pub struct EmbeddingSettings {
     pub dimensions: i32,
     /// Number of texts to process in a single batch.
     pub batch_size: i32,
+    /// Fraction of a note's text (0.0-1.0) that has to change before it is
+    /// embedded again; 0.0 re-embeds on every change.
+    #[serde(default = "default_reembed_threshold")]
+    pub reembed_threshold: f64,
+}
+
+fn default_reembed_threshold() -> f64 {
+    0.1
 }
 
 impl Default for EmbeddingSettings {
@@ -27,6 +35,7 @@ impl Default for EmbeddingSettings {
             model: "nomic-ai/nomic-embed-text-v1.5-GGUF".to_string(),
             dimensions: 768,
             batch_size: 10,
+            reembed_threshold: default_reembed_threshold(),
         }
     }
 }
@@ -58,3 +67,21 @@ pub struct EmbeddingProgress {
     /// Error message if any.
     pub error: Option<String>,
 }
+
+/// Result of checking stored embeddings against the notes.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EmbeddingConsistencyReport {
+    /// Number of stored embeddings.
+    pub total: i64,
+    /// Embeddings of notes that no longer exist or are now encrypted.
+    pub orphaned: i64,
+    /// Embeddings of notes whose content changed since they were embedded.
+    pub stale: i64,
+    /// Embeddings whose size doesn't match the configured dimensions.
+    pub wrong_dimensions: i64,
+    /// Notes without an embedding.
+    pub missing: i64,
+    /// Orphaned and wrong-sized embeddings deleted by the check.
+    pub removed: i64,
+}
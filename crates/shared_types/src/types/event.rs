@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use super::EditSession;
+
 /// Payload for notes:updated event.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -10,6 +12,31 @@ pub struct NotesUpdatedPayload {
     pub note_ids: Vec<i64>,
 }
 
+/// Payload for note:saved event, sent to every window when one saves,
+/// renames or moves a note or toggles one of its todos, so the others can
+/// show the new content.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteSavedPayload {
+    pub note_id: i64,
+    pub path: String,
+    pub content: String,
+    /// Label of the window that saved it.
+    pub window: String,
+    /// Where the note was before a rename or move.
+    pub previous_path: Option<String>,
+}
+
+/// Payload for edit:changed event, sent when a window begins, takes over
+/// or ends the editing session of a note.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EditSessionChangedPayload {
+    pub note_id: i64,
+    /// The session now held, if any.
+    pub session: Option<EditSession>,
+}
+
 /// Payload for notes:deleted event.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -23,4 +50,19 @@ pub struct NotesDeletedPayload {
 pub struct IndexCompletePayload {
     pub notes_indexed: i64,
     pub duration_ms: u64,
+    /// Files that were found but could not be indexed.
+    #[serde(default)]
+    pub warnings: Vec<IndexWarning>,
+    /// The index was cancelled; notes indexed so far are kept.
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// A file skipped during indexing, and why.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct IndexWarning {
+    /// Vault-relative path (invalid UTF-8 shown as `\u{FFFD}`).
+    pub path: String,
+    pub reason: String,
 }
@@ -11,4 +11,37 @@ pub struct FolderNode {
     pub path: String,
     pub is_dir: bool,
     pub children: Vec<FolderNode>,
+    /// Folder icon (folders only).
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Folder color (folders only).
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Whether the folder is collapsed in the tree (folders only).
+    #[serde(default)]
+    pub collapsed: bool,
+    /// Whether the entry is in a read-only external folder.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// How a folder is shown in the tree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FolderAppearance {
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+/// Stored metadata for a folder.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FolderMetadata {
+    /// Folder path relative to the vault root ("" for the root).
+    pub path: String,
+    pub appearance: FolderAppearance,
+    /// Manual order of child names; children not listed follow in default order.
+    pub child_order: Vec<String>,
 }
@@ -0,0 +1,112 @@
+//! Goal tracking types.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::todo::TaskQuery;
+
+/// How progress towards a goal is measured.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalTargetType {
+    /// Sum of values logged for the linked habits (e.g. 100 km run).
+    #[default]
+    Numeric,
+    /// Completed tasks matching the goal's task query.
+    Tasks,
+}
+
+impl GoalTargetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalTargetType::Numeric => "numeric",
+            GoalTargetType::Tasks => "tasks",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "tasks" => GoalTargetType::Tasks,
+            _ => GoalTargetType::Numeric,
+        }
+    }
+}
+
+/// A goal definition.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GoalDto {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub target_type: GoalTargetType,
+    /// Target value (numeric goals) or number of tasks to complete (task goals).
+    /// For task goals without a target, all matching tasks must be completed.
+    pub target_value: Option<f64>,
+    /// Unit for numeric goals (e.g., "km", "pages").
+    pub unit: Option<String>,
+    /// Start of the tracking period as YYYY-MM-DD (defaults to the creation date).
+    pub start_date: String,
+    /// Deadline as YYYY-MM-DD.
+    pub deadline: Option<String>,
+    /// Habits whose entries count towards a numeric goal.
+    pub habit_ids: Vec<i64>,
+    /// Task filter for task goals (the completion filter is ignored).
+    pub task_query: Option<TaskQuery>,
+    pub archived: bool,
+}
+
+/// Request to create a goal.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateGoalRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub target_type: GoalTargetType,
+    pub target_value: Option<f64>,
+    pub unit: Option<String>,
+    /// Start date as YYYY-MM-DD (defaults to today).
+    pub start_date: Option<String>,
+    /// Deadline as YYYY-MM-DD.
+    pub deadline: Option<String>,
+    #[serde(default)]
+    pub habit_ids: Vec<i64>,
+    pub task_query: Option<TaskQuery>,
+}
+
+/// Current progress towards a goal.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GoalProgress {
+    pub goal: GoalDto,
+    /// Current value (sum of habit values, or number of completed tasks).
+    pub current_value: f64,
+    /// Effective target (the goal target, or the number of matching tasks).
+    pub target_value: Option<f64>,
+    /// Percentage complete (0-100), if a target is known.
+    pub percent: Option<f64>,
+    /// Number of tasks matching the query (task goals only).
+    pub total_tasks: Option<i64>,
+    /// Days until the deadline (negative when past).
+    pub days_remaining: Option<i64>,
+    /// Whether progress is at or ahead of a linear pace towards the deadline.
+    pub on_track: Option<bool>,
+}
+
+/// Query for a goal embed (```goal``` block).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GoalEmbedQuery {
+    /// Goal name or ID.
+    pub goal: String,
+}
+
+/// Response for a goal embed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GoalEmbedResponse {
+    pub progress: Option<GoalProgress>,
+    /// Error message if the query failed.
+    pub error: Option<String>,
+}
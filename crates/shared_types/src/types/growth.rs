@@ -0,0 +1,64 @@
+//! Vault statistics over time.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A vault metric tracked by the daily snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultMetric {
+    Notes,
+    Words,
+    Tasks,
+    CompletedTasks,
+    Links,
+}
+
+impl VaultMetric {
+    /// Column of the metric in the vault_metrics table.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VaultMetric::Notes => "notes",
+            VaultMetric::Words => "words",
+            VaultMetric::Tasks => "tasks",
+            VaultMetric::CompletedTasks => "completed_tasks",
+            VaultMetric::Links => "links",
+        }
+    }
+}
+
+/// The vault's metrics on one day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VaultMetricsSnapshot {
+    /// Date as YYYY-MM-DD string.
+    pub date: String,
+    pub notes: i64,
+    pub words: i64,
+    pub tasks: i64,
+    pub completed_tasks: i64,
+    pub links: i64,
+}
+
+/// The value of a metric on a day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GrowthPoint {
+    /// Date as YYYY-MM-DD string.
+    pub date: String,
+    pub value: i64,
+}
+
+/// How a metric changed over a date range.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VaultGrowth {
+    pub metric: VaultMetric,
+    /// The days the metric changed on, ordered by date. The first point is
+    /// the value at the start of the range, if it is known. Days without a
+    /// point have the value of the point before.
+    pub points: Vec<GrowthPoint>,
+    /// Change from the first point to the last.
+    pub change: i64,
+}
@@ -1,5 +1,6 @@
 //! Habit tracker types.
 
+use super::locale::WeekStart;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
@@ -61,6 +62,38 @@ pub struct HabitDto {
     pub archived: bool,
     /// Sort order for display.
     pub sort_order: i32,
+    /// Active days, planned skips, and streak freezes.
+    #[serde(default)]
+    pub schedule: HabitScheduleRules,
+}
+
+/// Per-habit schedule rules honored by streak computations.
+///
+/// Days that are not active or are planned skips neither extend nor break a streak.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+pub struct HabitScheduleRules {
+    /// Active ISO weekdays (1 = Monday ... 7 = Sunday). Empty means every day.
+    #[serde(default)]
+    pub active_weekdays: Vec<u32>,
+    /// Planned skip dates as YYYY-MM-DD strings (e.g. travel days).
+    #[serde(default)]
+    pub skip_dates: Vec<String>,
+    /// Number of missed days forgiven within a single streak.
+    #[serde(default)]
+    pub freeze_tokens: u32,
+}
+
+/// Streak statistics for a habit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+pub struct HabitStreak {
+    /// Consecutive active days completed up to the reference date.
+    pub current: u32,
+    /// Longest streak on record.
+    pub longest: u32,
+    /// Freeze tokens used by the current streak.
+    pub freezes_used: u32,
 }
 
 /// Request to create a new habit.
@@ -135,6 +168,40 @@ pub struct UpdateHabitEntryRequest {
     pub time: Option<String>,
 }
 
+/// Column mapping for importing habit entries from a CSV file.
+///
+/// Columns are referenced by header name (case-insensitive) or by 0-based index.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HabitCsvMapping {
+    /// Column holding the entry date.
+    pub date_column: String,
+    /// Column holding the value to log.
+    pub value_column: String,
+    /// Optional column holding the entry time.
+    pub time_column: Option<String>,
+    /// Optional column holding notes for the entry.
+    pub notes_column: Option<String>,
+    /// chrono format of the date column (e.g. "%d.%m.%Y"). Common formats are detected when omitted.
+    pub date_format: Option<String>,
+    /// Field delimiter (defaults to ",").
+    pub delimiter: Option<String>,
+    /// Whether the first row is a header row (defaults to true).
+    pub has_header: Option<bool>,
+}
+
+/// Result of a habit data import.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HabitImportResult {
+    /// Number of entries created.
+    pub imported: i64,
+    /// Rows skipped because an entry already exists for that date and time.
+    pub skipped_duplicates: i64,
+    /// Rows that could not be parsed, with line numbers.
+    pub errors: Vec<String>,
+}
+
 // ============================================================================
 // Habit Tracker Embed Types
 // ============================================================================
@@ -153,6 +220,8 @@ pub enum HabitViewType {
     Streak,
     /// Simple list view.
     List,
+    /// Yearly heatmap grid with normalized per-day values.
+    Heatmap,
 }
 
 /// Orientation for table view.
@@ -195,7 +264,8 @@ pub struct HabitTrackerQuery {
     /// Table orientation (only applies to table view).
     #[serde(default)]
     pub orientation: HabitTableOrientation,
-    /// Date range preset.
+    /// Date range preset. The heatmap view covers the calendar year of the
+    /// reference date unless a custom range is given.
     #[serde(default)]
     pub date_range: HabitDateRange,
     /// Reference date for computing date ranges (YYYY-MM-DD). Defaults to today.
@@ -211,6 +281,10 @@ pub struct HabitTrackerQuery {
     /// Whether to show summary row (totals, streaks).
     #[serde(default = "default_true")]
     pub show_summary: bool,
+    /// First day of the week for `this_week`; the vault's locale setting
+    /// if not given.
+    #[serde(default)]
+    pub week_start: Option<WeekStart>,
 }
 
 fn default_true() -> bool {
@@ -229,6 +303,7 @@ impl Default for HabitTrackerQuery {
             end_date: None,
             editable: true,
             show_summary: true,
+            week_start: None,
         }
     }
 }
@@ -240,6 +315,22 @@ pub struct HabitWithEntries {
     pub habit: HabitDto,
     /// Entries organized by date (YYYY-MM-DD -> entries).
     pub entries_by_date: Vec<(String, Vec<HabitEntryDto>)>,
+    /// Dates in the range that are skipped (inactive weekdays or planned skips).
+    pub skipped_dates: Vec<String>,
+    /// Streak as of the end of the range (or today, if earlier).
+    pub streak: HabitStreak,
+    /// Per-day values normalized to 0-1 against the target (heatmap view only).
+    pub heatmap: Vec<HabitHeatmapCell>,
+}
+
+/// A single day in a habit heatmap.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+pub struct HabitHeatmapCell {
+    /// Date as YYYY-MM-DD string.
+    pub date: String,
+    /// Completion relative to the target, from 0.0 (nothing) to 1.0 (target met).
+    pub value: f64,
 }
 
 /// Response for habit tracker embed.
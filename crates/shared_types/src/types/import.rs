@@ -45,4 +45,69 @@ pub struct ImportResult {
     pub duration_ms: u64,
     /// Any warnings or skipped files.
     pub warnings: Vec<String>,
+    /// The import was cancelled; files imported so far are kept.
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// Kind of a file imported by drag-and-drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportedFileKind {
+    /// Markdown file, indexed as a note.
+    Note,
+    /// Any other file, copied to the attachment folder.
+    Attachment,
+}
+
+/// A file copied into the vault by `import_files`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ImportedFile {
+    /// Original path outside the vault.
+    pub source_path: String,
+    /// New path relative to the vault root (may have a number suffix).
+    pub path: String,
+    pub kind: ImportedFileKind,
+    /// Note ID for imported notes.
+    pub note_id: Option<i64>,
+}
+
+/// Options for importing photos into date folders and daily notes.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct PhotoImportOptions {
+    /// Base folder; photos are filed under `<folder>/<year>/<month>/`.
+    pub folder: String,
+    /// Embed each photo in the daily note of the day it was taken.
+    pub add_to_daily_notes: bool,
+}
+
+impl Default for PhotoImportOptions {
+    fn default() -> Self {
+        Self {
+            folder: "photos".to_string(),
+            add_to_daily_notes: true,
+        }
+    }
+}
+
+/// A photo copied into the vault by `import_photos`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ImportedPhoto {
+    /// Original path outside the vault.
+    pub source_path: String,
+    /// New path relative to the vault root.
+    pub path: String,
+    /// Day the photo is filed under (YYYY-MM-DD); the file date without EXIF data.
+    pub date: String,
+    /// EXIF capture time (YYYY-MM-DDTHH:MM:SS, camera local time).
+    pub taken_at: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Daily note the photo was embedded in.
+    pub daily_note_path: Option<String>,
 }
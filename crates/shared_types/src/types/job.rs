@@ -0,0 +1,150 @@
+//! Background job types.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{EmbeddingSettings, ImportVaultRequest, RenderOptions};
+
+/// What a job does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    FullIndex,
+    VaultImport,
+    JournalExport,
+    EmbeddingBackfill,
+    Backup,
+    Ocr,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::FullIndex => "full_index",
+            JobKind::VaultImport => "vault_import",
+            JobKind::JournalExport => "journal_export",
+            JobKind::EmbeddingBackfill => "embedding_backfill",
+            JobKind::Backup => "backup",
+            JobKind::Ocr => "ocr",
+        }
+    }
+
+    /// Whether the job can start over after it was interrupted. An import
+    /// can't, as it would copy or append the notes it already imported again.
+    pub fn can_start_over(&self) -> bool {
+        !matches!(self, JobKind::VaultImport)
+    }
+}
+
+/// Where a job is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    /// Stopped by `cancel_job`; what was done before is kept.
+    Cancelled,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Cancelled => "cancelled",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    /// Parse a status as stored; fails on an unknown status.
+    pub fn from_string(s: &str) -> Result<Self, String> {
+        match s {
+            "queued" => Ok(JobStatus::Queued),
+            "running" => Ok(JobStatus::Running),
+            "completed" => Ok(JobStatus::Completed),
+            "cancelled" => Ok(JobStatus::Cancelled),
+            "failed" => Ok(JobStatus::Failed),
+            _ => Err(format!("Unknown job status: {}", s)),
+        }
+    }
+
+    /// Whether the job has ended.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Cancelled | JobStatus::Failed)
+    }
+}
+
+/// What to run, with everything needed to run it again.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobRequest {
+    FullIndex,
+    VaultImport {
+        request: ImportVaultRequest,
+    },
+    JournalExport {
+        start_date: String,
+        end_date: String,
+        output_path: String,
+        options: Option<RenderOptions>,
+    },
+    EmbeddingBackfill {
+        settings: EmbeddingSettings,
+    },
+    /// Zip the vault with a copy of its database.
+    Backup {
+        output_path: String,
+    },
+    /// Recognize the text in the vault's images.
+    Ocr,
+}
+
+impl JobRequest {
+    pub fn kind(&self) -> JobKind {
+        match self {
+            JobRequest::FullIndex => JobKind::FullIndex,
+            JobRequest::VaultImport { .. } => JobKind::VaultImport,
+            JobRequest::JournalExport { .. } => JobKind::JournalExport,
+            JobRequest::EmbeddingBackfill { .. } => JobKind::EmbeddingBackfill,
+            JobRequest::Backup { .. } => JobKind::Backup,
+            JobRequest::Ocr => JobKind::Ocr,
+        }
+    }
+}
+
+/// A queued, running or finished job.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JobDto {
+    pub id: i64,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub request: JobRequest,
+    /// Items processed (all of them unless cancelled or failed); set when
+    /// the job finishes.
+    pub processed: i64,
+    pub total: i64,
+    /// Summary of the result, or the error if the job failed.
+    pub message: Option<String>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+/// Payload for job:progress event.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JobProgressPayload {
+    pub job_id: i64,
+    pub kind: JobKind,
+    pub processed: i64,
+    pub total: i64,
+    /// What is being worked on, e.g. the current file.
+    pub message: Option<String>,
+}
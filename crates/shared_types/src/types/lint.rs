@@ -0,0 +1,132 @@
+//! Note linting types (rules, settings, and diagnostics).
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    /// `[[link]]` to a note that does not exist.
+    BrokenLink,
+    /// `![[embed]]` of a note or file that does not exist.
+    BrokenEmbed,
+    /// A heading without text.
+    EmptyHeading,
+    /// Two headings with the same text, so section links are ambiguous.
+    DuplicateHeading,
+    /// A heading with nothing under it.
+    EmptySection,
+    /// Spaces or tabs at the end of a line (markdown line breaks are allowed).
+    TrailingWhitespace,
+    /// `TODO`, `FIXME`, or `XXX` left in the text.
+    TodoMarker,
+    /// A property required for the note's folder is missing.
+    MissingProperty,
+    /// `[^label]` reference to a footnote that is not defined.
+    UndefinedFootnote,
+    /// `[^label]:` footnote definition that is never referenced.
+    OrphanedFootnote,
+    /// A task `@context` that isn't in the context registry (only checked
+    /// once contexts are registered).
+    UnknownContext,
+}
+
+impl LintRule {
+    /// Severity when not overridden in the settings.
+    pub fn default_severity(self) -> LintSeverity {
+        match self {
+            LintRule::BrokenEmbed => LintSeverity::Error,
+            LintRule::BrokenLink
+            | LintRule::EmptyHeading
+            | LintRule::DuplicateHeading
+            | LintRule::MissingProperty
+            | LintRule::UndefinedFootnote
+            | LintRule::UnknownContext => LintSeverity::Warning,
+            LintRule::EmptySection
+            | LintRule::TrailingWhitespace
+            | LintRule::TodoMarker
+            | LintRule::OrphanedFootnote => LintSeverity::Info,
+        }
+    }
+
+    /// Whether `apply_lint_fixes` can fix this rule's diagnostics.
+    pub fn is_fixable(self) -> bool {
+        matches!(
+            self,
+            LintRule::EmptyHeading | LintRule::TrailingWhitespace | LintRule::MissingProperty
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Settings for one rule; rules without settings are enabled with their
+/// default severity.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LintRuleSetting {
+    pub rule: LintRule,
+    pub enabled: bool,
+    pub severity: Option<LintSeverity>,
+}
+
+/// Properties every note in a folder (and its subfolders) must have.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RequiredProperties {
+    /// Folder relative to the vault root; empty for the whole vault.
+    pub folder: String,
+    pub keys: Vec<String>,
+}
+
+/// Lint settings (stored in vault config).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LintSettings {
+    #[serde(default)]
+    pub rules: Vec<LintRuleSetting>,
+    #[serde(default)]
+    pub required_properties: Vec<RequiredProperties>,
+}
+
+/// A problem found in a note.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LintDiagnostic {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    pub message: String,
+    /// 1-based line number.
+    pub line: u32,
+    pub fixable: bool,
+}
+
+/// Diagnostics for one note.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteLintResult {
+    pub note_id: i64,
+    pub path: String,
+    pub diagnostics: Vec<LintDiagnostic>,
+}
+
+/// Result of applying lint fixes.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LintFixResult {
+    /// Notes whose content changed.
+    pub notes_changed: u32,
+    pub fixes_applied: u32,
+    /// Notes that could not be read or written, with the error for each;
+    /// the other notes are still fixed.
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,85 @@
+//! Locale settings: the first day of the week and date/time display formats.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// The day weeks start on.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+    Saturday,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+            WeekStart::Saturday => Weekday::Sat,
+        }
+    }
+
+    /// The first day of the week containing `date`.
+    pub fn start_of_week(self, date: NaiveDate) -> NaiveDate {
+        let offset = (date.weekday().num_days_from_monday() + 7 - self.weekday().num_days_from_monday()) % 7;
+        date - Duration::days(offset as i64)
+    }
+}
+
+/// Locale settings (stored in vault config).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LocaleSettings {
+    /// First day of the week for calendars, weekly notes and week ranges.
+    #[serde(default)]
+    pub week_start: WeekStart,
+    /// How dates are shown, with `YYYY`, `MM`, `DD`, `M` and `D`
+    /// (e.g., "DD.MM.YYYY"). Dates are still stored as YYYY-MM-DD.
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// How times are shown, with `HH`, `H`, `hh`, `h`, `mm` and `a` for
+    /// am/pm (e.g., "h:mm a").
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+}
+
+fn default_date_format() -> String {
+    "YYYY-MM-DD".to_string()
+}
+
+fn default_time_format() -> String {
+    "HH:mm".to_string()
+}
+
+impl Default for LocaleSettings {
+    fn default() -> Self {
+        Self {
+            week_start: WeekStart::default(),
+            date_format: default_date_format(),
+            time_format: default_time_format(),
+        }
+    }
+}
+
+/// An ISO 8601 week: weeks start on Monday, and week 1 is the week with the
+/// year's first Thursday, so the first and last days of a year can belong to
+/// a week of the year before or after.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[ts(export)]
+pub struct WeekInfo {
+    /// The year the week belongs to.
+    pub year: i32,
+    /// The week number (1-53).
+    pub week: u32,
+    /// The week as "2024-W15".
+    pub label: String,
+    /// The week's Monday (YYYY-MM-DD).
+    pub start: String,
+    /// The week's Sunday (YYYY-MM-DD).
+    pub end: String,
+}
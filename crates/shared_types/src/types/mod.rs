@@ -1,38 +1,74 @@
 //! Type modules - organized by domain.
 
+pub mod app_settings;
+pub mod attachment;
 pub mod backlink;
+pub mod backup;
+pub mod book;
+pub mod diagnostics;
+pub mod duplicate;
 pub mod embed;
 pub mod embedding;
 pub mod event;
 pub mod folder;
+pub mod goal;
+pub mod growth;
 pub mod habit;
 pub mod import;
+pub mod job;
+pub mod lint;
+pub mod locale;
+pub mod mood;
 pub mod note;
 pub mod property;
 pub mod query;
 pub mod query_embed;
+pub mod readability;
+pub mod render;
+pub mod review;
 pub mod schedule;
 pub mod search;
+pub mod spellcheck;
 pub mod tag;
 pub mod template;
+pub mod title;
 pub mod todo;
 pub mod vault;
+pub mod workspace;
 
 // Re-export all types for convenience
+pub use app_settings::*;
+pub use attachment::*;
 pub use backlink::*;
+pub use backup::*;
+pub use book::*;
+pub use diagnostics::*;
+pub use duplicate::*;
 pub use embed::*;
 pub use embedding::*;
 pub use event::*;
 pub use folder::*;
+pub use goal::*;
+pub use growth::*;
 pub use habit::*;
 pub use import::*;
+pub use job::*;
+pub use lint::*;
+pub use locale::*;
+pub use mood::*;
 pub use note::*;
 pub use property::*;
 pub use query::*;
 pub use query_embed::*;
+pub use readability::*;
+pub use render::*;
+pub use review::*;
 pub use schedule::*;
 pub use search::*;
+pub use spellcheck::*;
 pub use tag::*;
 pub use template::*;
+pub use title::*;
 pub use todo::*;
 pub use vault::*;
+pub use workspace::*;
@@ -0,0 +1,41 @@
+//! Mood tracking types.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A logged mood for a single day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MoodEntryDto {
+    pub id: i64,
+    /// Date as YYYY-MM-DD string.
+    pub date: String,
+    /// Mood score from 1 (low) to 5 (great).
+    pub score: i32,
+    /// Optional free-form note about the day.
+    pub note: Option<String>,
+}
+
+/// Average mood for an ISO week.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MoodWeekSummary {
+    /// First day of the week as YYYY-MM-DD string.
+    pub week_start: String,
+    pub average: f64,
+    pub count: i64,
+}
+
+/// Mood entries and aggregates for a date range.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MoodTrend {
+    /// Entries ordered by date.
+    pub entries: Vec<MoodEntryDto>,
+    /// Average score over the range (None if no entries).
+    pub average: Option<f64>,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    /// Weekly averages, ordered by week.
+    pub weekly: Vec<MoodWeekSummary>,
+}
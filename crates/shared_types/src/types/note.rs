@@ -4,6 +4,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use super::ScheduleBlockDto;
+
 /// A note as exposed to the frontend.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -14,6 +16,9 @@ pub struct NoteDto {
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
     pub pinned: bool,
+    /// CSS classes from the `cssclasses` property, for per-note layouts.
+    #[serde(default)]
+    pub cssclasses: Vec<String>,
 }
 
 /// Minimal note info for lists/search results.
@@ -26,11 +31,101 @@ pub struct NoteListItem {
     pub pinned: bool,
 }
 
-/// Full note content for editing.
+/// Full note content for editing, or part of it for oversized notes.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct NoteContent {
     pub id: i64,
     pub path: String,
     pub content: String,
+    /// Byte offset of `content` in the file (0 unless a range was read).
+    #[serde(default)]
+    pub offset: u64,
+    /// Size of the whole note in bytes; more than `offset` plus the length
+    /// of `content` if there is more to read.
+    #[serde(default)]
+    pub total_bytes: u64,
+    /// The note is over the vault's size limit: only its start is parsed
+    /// and searched, and it is not embedded.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Cheap aggregates for a note header.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteSummaryDto {
+    pub note_id: i64,
+    /// Notes linking to this note.
+    pub backlink_count: i64,
+    /// Notes this note links to.
+    pub outgoing_link_count: i64,
+    pub open_todo_count: i64,
+    pub todo_count: i64,
+    /// Words in the body (frontmatter and private sections excluded).
+    pub word_count: i64,
+    /// The note's schedule block with the latest date, if any.
+    pub last_schedule_block: Option<ScheduleBlockDto>,
+}
+
+/// What deleting a folder or note would affect.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DeleteImpact {
+    /// Notes that would be deleted.
+    pub notes: Vec<NoteListItem>,
+    /// Links from other notes that would no longer resolve.
+    pub broken_links: i64,
+    /// Schedule blocks linked to the notes; they are deleted with them.
+    pub schedule_blocks: Vec<ScheduleBlockDto>,
+    /// Pinned notes among the deleted ones.
+    pub pinned_notes: Vec<NoteListItem>,
+}
+
+/// Filters for picking a random note.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RandomNoteFilter {
+    /// Only notes in this folder (including subfolders).
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Only notes with this tag (including nested tags like `tag/sub`).
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// A note from the same day in a previous year.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OnThisDayNote {
+    pub note: NoteListItem,
+    /// The original date as YYYY-MM-DD string.
+    pub date: String,
+    /// Where the date comes from: "journal" (the `journal_date` property),
+    /// "file_name" (a `YYYY-MM-DD` file name) or "created"
+    pub source: String,
+    pub years_ago: i32,
+}
+
+/// The window editing a note. Editing sessions are advisory: saves are
+/// never refused, but other windows show the note read-only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EditSession {
+    pub note_id: i64,
+    /// Label of the window holding the session.
+    pub window: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Editor content that was never saved to its file, kept for crash recovery.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct UnsavedDraft {
+    pub path: String,
+    pub content: String,
+    pub saved_at: DateTime<Utc>,
+    /// The file changed on disk since the draft was started, so restoring
+    /// the draft would overwrite those changes.
+    pub file_changed: bool,
 }
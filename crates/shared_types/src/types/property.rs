@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use super::note::NoteListItem;
+
 // ============================================================================
 // Note Property Types
 // ============================================================================
@@ -18,6 +20,9 @@ pub struct PropertyDto {
     /// Type hint: "text", "date", "number", "boolean", "list"
     pub property_type: Option<String>,
     pub sort_order: Option<i32>,
+    /// Items of a list property, in order (empty for other types).
+    #[serde(default)]
+    pub items: Vec<String>,
 }
 
 /// Request to set a property value.
@@ -28,6 +33,10 @@ pub struct SetPropertyRequest {
     pub key: String,
     pub value: Option<String>,
     pub property_type: Option<String>,
+    /// Items of a list property. When set, they are stored as given
+    /// (items may contain commas) and `value` is ignored.
+    #[serde(default)]
+    pub items: Option<Vec<String>>,
 }
 
 /// Information about a property key used in the vault.
@@ -122,6 +131,44 @@ pub struct NoteWithPropertyValue {
     pub value: Option<String>,
 }
 
+// ============================================================================
+// Property Table Types
+// ============================================================================
+
+/// Notes with their values for a set of properties, for a spreadsheet-style
+/// editor.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PropertyTable {
+    /// The property keys shown as columns.
+    pub columns: Vec<String>,
+    pub rows: Vec<PropertyTableRow>,
+}
+
+/// A note in the property table.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PropertyTableRow {
+    pub note: NoteListItem,
+    /// The note's property for each column (None where it has none).
+    pub values: Vec<Option<PropertyDto>>,
+}
+
+/// One property change of a batch update.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PropertyUpdate {
+    pub note_id: i64,
+    pub key: String,
+    /// The new value; the property is removed when neither this nor
+    /// `items` is set.
+    pub value: Option<String>,
+    pub property_type: Option<String>,
+    /// Items of a list property, stored as given (`value` is ignored).
+    #[serde(default)]
+    pub items: Option<Vec<String>>,
+}
+
 // ============================================================================
 // Folder Property Types
 // ============================================================================
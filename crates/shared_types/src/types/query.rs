@@ -5,6 +5,7 @@ use ts_rs::TS;
 
 use super::note::NoteListItem;
 use super::property::PropertyDto;
+use super::query_embed::QuerySort;
 use super::todo::TaskWithContext;
 
 /// Operator for property filters.
@@ -25,9 +26,9 @@ pub enum PropertyOperator {
     StartsWith,
     /// Property ends with suffix
     EndsWith,
-    /// Property list contains ALL of the specified values (comma-separated)
+    /// Property list contains ALL of the specified values (a JSON array of strings)
     ContainsAll,
-    /// Property list contains ANY of the specified values (comma-separated)
+    /// Property list contains ANY of the specified values (a JSON array of strings)
     ContainsAny,
     /// Date property equals the specified date (YYYY-MM-DD)
     DateOn,
@@ -39,6 +40,14 @@ pub enum PropertyOperator {
     DateOnOrBefore,
     /// Date property is on or after the specified date
     DateOnOrAfter,
+    /// Number property is greater than the specified number
+    GreaterThan,
+    /// Number property is less than the specified number
+    LessThan,
+    /// Number property is greater than or equal to the specified number
+    GreaterOrEqual,
+    /// Number property is less than or equal to the specified number
+    LessOrEqual,
 }
 
 /// A single property filter condition.
@@ -89,6 +98,11 @@ pub struct QueryRequest {
     pub include_completed: bool,
     /// Maximum number of results.
     pub limit: Option<i32>,
+    /// Sort by a property (or `_path`, `note_title`). Tasks are sorted by
+    /// their note's value first. Defaults to path order for notes and
+    /// due date and priority for tasks.
+    #[serde(default)]
+    pub sort: Option<QuerySort>,
 }
 
 /// A single query result item (can be a task or a note).
@@ -103,6 +117,16 @@ pub struct QueryResultItem {
     pub note: Option<NoteListItem>,
     /// Properties of the note (for display in results).
     pub properties: Vec<PropertyDto>,
+    /// Manual position on the Kanban board the query is shown on, if the
+    /// card was moved by hand. Lower ranks come first.
+    #[serde(default)]
+    pub rank: Option<f64>,
+    /// Stable reference to the item, for storing its position on a board:
+    /// `note:<id>` for notes and `task:<note id>:<text hash>:<n>` for the
+    /// nth task (from 0) with that text in its note, so a task keeps it when
+    /// lines are added above it. Filled in for Kanban views.
+    #[serde(default)]
+    pub item_ref: String,
 }
 
 /// Response from running a query.
@@ -51,6 +51,10 @@ pub struct KanbanConfig {
     /// Whether to show cards without a value in an "Uncategorized" column.
     #[serde(default = "default_true")]
     pub show_uncategorized: bool,
+    /// Name the manual card order is stored under. Defaults to a key made
+    /// from the query and `group_by`, filled in when the query runs.
+    #[serde(default)]
+    pub board: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -63,6 +67,7 @@ impl Default for KanbanConfig {
             group_by: "priority".to_string(),
             card_fields: vec!["description".to_string(), "due_date".to_string()],
             show_uncategorized: true,
+            board: None,
         }
     }
 }
@@ -194,6 +199,10 @@ pub struct QueryTab {
     /// Include completed tasks. Defaults to false.
     #[serde(default)]
     pub include_completed: bool,
+    /// Also include the tasks of notes embedded (`![[note]]`) in the
+    /// matching notes, one level deep. Defaults to false.
+    #[serde(default)]
+    pub include_embedded: bool,
     /// Maximum number of results. Defaults to 50.
     #[serde(default = "default_limit")]
     pub limit: i32,
@@ -219,6 +228,10 @@ pub struct QueryEmbed {
     /// Include completed tasks. Defaults to false.
     #[serde(default)]
     pub include_completed: bool,
+    /// Also include the tasks of notes embedded (`![[note]]`) in the
+    /// matching notes, one level deep. Defaults to false.
+    #[serde(default)]
+    pub include_embedded: bool,
     /// Maximum number of results. Defaults to 50.
     #[serde(default = "default_limit")]
     pub limit: i32,
@@ -249,6 +262,7 @@ impl Default for QueryEmbed {
             match_mode: FilterMatchMode::All,
             result_type: QueryResultType::Tasks,
             include_completed: false,
+            include_embedded: false,
             limit: 50,
             view: QueryViewConfig::default(),
             tabs: vec![],
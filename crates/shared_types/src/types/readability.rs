@@ -0,0 +1,46 @@
+//! Readability report types (text statistics for drafting).
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Number of sentences or words within a length range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LengthBucket {
+    pub min: u32,
+    /// Inclusive upper bound; None for the open-ended last bucket.
+    pub max: Option<u32>,
+    pub count: u32,
+}
+
+/// Text statistics and readability scores for a note body.
+///
+/// Headings, code, and frontmatter are not counted.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReadabilityReport {
+    pub word_count: u32,
+    pub sentence_count: u32,
+    pub syllable_count: u32,
+    /// Words per sentence.
+    pub average_sentence_length: f64,
+    /// Characters per word.
+    pub average_word_length: f64,
+    /// Sentence lengths in words.
+    pub sentence_lengths: Vec<LengthBucket>,
+    /// Word lengths in characters.
+    pub word_lengths: Vec<LengthBucket>,
+    /// Sentences of more than 25 words.
+    pub long_sentence_count: u32,
+    /// Sentences that look like passive voice ("was written", "is being built").
+    pub passive_sentence_count: u32,
+    /// The first few passive sentences, for review.
+    pub passive_sentences: Vec<String>,
+    /// Flesch reading ease (higher is easier; 60-70 is plain English).
+    /// None for text without sentences.
+    pub flesch_reading_ease: Option<f64>,
+    /// Flesch-Kincaid grade level.
+    pub flesch_kincaid_grade: Option<f64>,
+    /// Reading time at 200 words per minute, rounded up.
+    pub reading_time_minutes: u32,
+}
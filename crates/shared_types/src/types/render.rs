@@ -0,0 +1,99 @@
+//! Note rendering types (HTML for export, sharing, and print).
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Options for rendering a note to HTML.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct RenderOptions {
+    /// How many levels of note embeds are inlined; deeper embeds become links.
+    pub max_embed_depth: u32,
+    /// Prefix for internal note links; the vault path is appended
+    /// (e.g. "note:Projects/Plan.md#goals").
+    pub link_prefix: String,
+    /// Include private sections (`%%private%% ... %%end%%`).
+    pub include_private: bool,
+    /// How math is rendered; the vault's math settings when not set.
+    pub math: Option<MathRendering>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            max_embed_depth: 3,
+            link_prefix: "note:".to_string(),
+            include_private: false,
+            math: None,
+        }
+    }
+}
+
+/// How math (`$...$`, `$$...$$`) is rendered to HTML.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum MathRendering {
+    /// MathML, which browsers show without scripts or fonts.
+    #[default]
+    Mathml,
+    /// The TeX source in `math` elements (`\(...\)`, `\[...\]`), for a
+    /// renderer such as KaTeX in the page.
+    Tex,
+}
+
+/// Math settings (stored in vault config).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MathSettings {
+    #[serde(default)]
+    pub rendering: MathRendering,
+}
+
+/// A note rendered to HTML.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RenderedNote {
+    pub note_id: i64,
+    pub path: String,
+    pub title: Option<String>,
+    /// Sanitized HTML of the note body (without frontmatter).
+    pub html: String,
+    /// CSS classes from the note's `cssclasses` property, for the element
+    /// the HTML is placed in.
+    pub cssclasses: Vec<String>,
+}
+
+/// Result of exporting daily notes as one printable HTML document.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JournalExportResult {
+    pub output_path: String,
+    /// Dates (YYYY-MM-DD) that had a daily note, in order.
+    pub dates: Vec<String>,
+}
+
+/// Result of exporting a note as an HTML slide deck.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SlideExportResult {
+    pub output_path: String,
+    pub slide_count: usize,
+}
+
+/// How diagram (`mermaid`) blocks are rendered to SVG (stored in vault config).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DiagramSettings {
+    /// Path of a mermaid-cli compatible renderer (`mmdc -i in.mmd -o out.svg`).
+    /// Without one, diagrams that aren't cached stay code blocks. Comes from
+    /// the device's app settings, or is the app's bundled renderer; a path in
+    /// the vault config is ignored.
+    #[serde(default)]
+    pub renderer_path: Option<String>,
+    /// Render diagrams into the cache when notes are indexed, rather than
+    /// the first time they are shown or exported.
+    #[serde(default)]
+    pub prerender: bool,
+}
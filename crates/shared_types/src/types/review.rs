@@ -0,0 +1,22 @@
+//! Note review (resurfacing) types.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::NoteListItem;
+
+/// A note that is due to come back to the user's attention.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteReviewDto {
+    pub note: NoteListItem,
+    /// When the note was last reviewed (None if never).
+    pub last_reviewed: Option<DateTime<Utc>>,
+    /// Days between reviews; grows each time the note is reviewed.
+    pub review_interval_days: i64,
+    /// Importance weight (1.0 = plain note); shortens the interval.
+    pub importance: f64,
+    /// Elapsed time over the weighted interval; due at 1.0 and above.
+    pub due_score: f64,
+}
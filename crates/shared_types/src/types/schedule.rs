@@ -5,12 +5,19 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use super::note::NoteListItem;
+use super::todo::TaskWithContext;
 
 /// A scheduled time block (optionally linked to a note).
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ScheduleBlockDto {
+    /// The schedule block's id; 0 for calendar subscription events, which
+    /// aren't schedule blocks.
     pub id: i64,
+    /// Unique among blocks and calendar events: `block:<id>` or
+    /// `calendar:<event id>`.
+    #[serde(default)]
+    pub key: String,
     pub note_id: Option<i64>,
     pub date: NaiveDate,
     pub start_time: NaiveTime,
@@ -20,10 +27,17 @@ pub struct ScheduleBlockDto {
     pub context: Option<String>,
     /// RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR").
     pub rrule: Option<String>,
+    /// IANA time zone of `date`/`start_time`/`end_time` (e.g., "Europe/Berlin").
+    /// None means the vault's local time.
+    #[serde(default)]
+    pub timezone: Option<String>,
     /// True if this is an occurrence of a recurring block (not the master).
     /// Occurrences have the same id as their master but different dates.
     #[serde(default)]
     pub is_occurrence: bool,
+    /// True for events from a calendar subscription, shown as a read-only overlay.
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 /// Request to create a new schedule block.
@@ -39,6 +53,9 @@ pub struct CreateScheduleBlockRequest {
     pub context: Option<String>,
     /// RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR").
     pub rrule: Option<String>,
+    /// IANA time zone for the block's times (None = vault local time).
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 /// Request to update an existing schedule block.
@@ -56,6 +73,9 @@ pub struct UpdateScheduleBlockRequest {
     /// RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR").
     /// Set to empty string to clear recurrence.
     pub rrule: Option<String>,
+    /// IANA time zone for the block's times. Set to empty string to revert to local time.
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 /// A note with its association type to a date.
@@ -68,3 +88,308 @@ pub struct NoteForDate {
     /// If source is "scheduled", the schedule block info
     pub schedule_block: Option<ScheduleBlockDto>,
 }
+
+// ============================================================================
+// Schedule Templates (Routines)
+// ============================================================================
+
+/// A single time slot within a schedule template.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScheduleTemplateSlot {
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub context: Option<String>,
+}
+
+/// A named routine of time slots that can be applied to any date.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScheduleTemplateDto {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// Slots ordered by start time.
+    pub slots: Vec<ScheduleTemplateSlot>,
+}
+
+/// Request to create a new schedule template.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateScheduleTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub slots: Vec<ScheduleTemplateSlot>,
+}
+
+/// Result of applying a schedule template to a date.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ApplyScheduleTemplateResult {
+    /// IDs of the schedule blocks that were created.
+    pub created_block_ids: Vec<i64>,
+    /// Slots that were skipped because they overlapped an existing block.
+    pub skipped_slots: Vec<ScheduleTemplateSlot>,
+}
+
+// ============================================================================
+// Calendar Subscriptions
+// ============================================================================
+
+/// An external calendar (ICS URL) whose events are shown as read-only blocks.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CalendarSubscriptionDto {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    /// Color for the overlay blocks.
+    pub color: Option<String>,
+    /// Minutes between automatic refreshes.
+    pub refresh_minutes: i64,
+    /// When the feed was last fetched (RFC 3339).
+    pub last_fetched: Option<String>,
+    /// Error from the last fetch, if it failed.
+    pub last_error: Option<String>,
+    /// Number of events stored from the last successful fetch.
+    pub event_count: i64,
+}
+
+/// Request to subscribe to an external calendar.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateCalendarSubscriptionRequest {
+    pub name: String,
+    /// ICS feed URL (http, https, or webcal).
+    pub url: String,
+    pub color: Option<String>,
+    /// Minutes between automatic refreshes (default: 60).
+    pub refresh_minutes: Option<i64>,
+}
+
+// ============================================================================
+// Focus Sessions
+// ============================================================================
+
+/// Actual time spent on a schedule block, recorded in focus mode.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BlockSessionDto {
+    pub id: i64,
+    pub block_id: i64,
+    /// Day the session was started on (the occurrence date for recurring blocks).
+    pub date: NaiveDate,
+    /// Local start time (YYYY-MM-DDTHH:MM:SS).
+    pub started_at: String,
+    /// Local end time, None while the session is running.
+    pub ended_at: Option<String>,
+    /// Length in minutes (up to now for a running session).
+    pub minutes: i64,
+}
+
+/// Planned versus actual time for one context.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ContextPlanVsActual {
+    /// Block context (None for blocks without a context).
+    pub context: Option<String>,
+    /// Minutes of schedule blocks in the range.
+    pub planned_minutes: i64,
+    /// Minutes of focus sessions recorded on those blocks.
+    pub actual_minutes: i64,
+    /// Actual minus planned minutes.
+    pub difference_minutes: i64,
+    /// Number of planned blocks (recurring occurrences counted individually).
+    pub block_count: i64,
+    /// Number of recorded sessions.
+    pub session_count: i64,
+}
+
+/// Planned versus actual time per context over a date range.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PlanVsActualReport {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Contexts ordered by planned minutes, most first.
+    pub contexts: Vec<ContextPlanVsActual>,
+    pub total_planned_minutes: i64,
+    pub total_actual_minutes: i64,
+}
+
+// ============================================================================
+// Context Switching
+// ============================================================================
+
+/// Scheduled minutes for one context.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+pub struct ContextMinutes {
+    pub context: String,
+    pub minutes: i64,
+}
+
+/// A run of adjacent blocks with the same context.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+pub struct FocusStretch {
+    pub date: NaiveDate,
+    pub context: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// Scheduled minutes of the blocks in the run (gaps not counted).
+    pub minutes: i64,
+}
+
+/// Context switching statistics for a single day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayContextStats {
+    pub date: NaiveDate,
+    /// Number of adjacent blocks with different contexts.
+    pub switches: i64,
+    /// Minutes per context, most first.
+    pub context_minutes: Vec<ContextMinutes>,
+    /// Longest run of same-context blocks on this day.
+    pub longest_focus: Option<FocusStretch>,
+}
+
+/// Context switching statistics for a week (from the vault's week start).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WeekContextStats {
+    pub week_start: NaiveDate,
+    pub switches: i64,
+    /// Average switches per day with at least one block.
+    pub average_daily_switches: f64,
+    pub context_minutes: Vec<ContextMinutes>,
+    pub longest_focus: Option<FocusStretch>,
+}
+
+/// Context switching report over a date range, for charts.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ContextSwitchReport {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// One entry per day in the range (days without blocks included).
+    pub days: Vec<DayContextStats>,
+    /// One entry per week touched by the range.
+    pub weeks: Vec<WeekContextStats>,
+    pub total_switches: i64,
+    /// Minutes per context over the whole range, most first.
+    pub context_minutes: Vec<ContextMinutes>,
+    pub longest_focus: Option<FocusStretch>,
+    /// Slope of daily switches over the days with blocks (negative = fewer
+    /// switches over time). None with fewer than two such days.
+    pub switch_trend: Option<f64>,
+}
+
+// ============================================================================
+// Day Planning
+// ============================================================================
+
+/// Constraints for automatically planning a day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PlanDayConstraints {
+    /// Start of working hours.
+    pub work_start: NaiveTime,
+    /// End of working hours.
+    pub work_end: NaiveTime,
+    /// Duration in minutes for tasks without an `est:` token (default: 30).
+    pub default_task_minutes: Option<i32>,
+    /// Gap in minutes to leave between planned blocks (default: 0).
+    pub buffer_minutes: Option<i32>,
+    /// Only plan tasks with this context.
+    pub context: Option<String>,
+    /// Maximum number of tasks to plan.
+    pub max_tasks: Option<i32>,
+    /// Create the schedule blocks immediately instead of only proposing them.
+    #[serde(default)]
+    pub create_blocks: bool,
+}
+
+/// A task placed into a free time slot by the day planner.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PlannedBlock {
+    pub todo_id: i64,
+    pub note_id: i64,
+    pub description: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// Estimated minutes from the task's `est:` token (None if the default was used).
+    pub estimated_minutes: Option<i32>,
+    /// ID of the created schedule block (None when only proposed).
+    pub block_id: Option<i64>,
+}
+
+/// A proposed (or created) plan for a day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayPlan {
+    pub date: NaiveDate,
+    pub planned: Vec<PlannedBlock>,
+    /// Candidate tasks that did not fit into the remaining free time.
+    pub unplanned_todo_ids: Vec<i64>,
+    /// Free minutes left within working hours after planning.
+    pub free_minutes: i64,
+}
+
+/// Estimated task effort versus available time for a single day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayWorkload {
+    pub date: NaiveDate,
+    /// Sum of `est:` estimates of incomplete tasks due or scheduled on this day.
+    pub estimated_minutes: i64,
+    /// Free minutes within working hours after existing schedule blocks.
+    pub available_minutes: i64,
+    /// Number of incomplete tasks assigned to this day.
+    pub task_count: i64,
+    /// Number of those tasks without an estimate.
+    pub unestimated_count: i64,
+    /// True if estimated effort exceeds available time.
+    pub overcommitted: bool,
+}
+
+/// Habit completion for a single day of the week overview.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HabitDaySummary {
+    pub habit_id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    /// Whether the habit was completed on this day.
+    pub done: bool,
+    /// Whether the day is skipped by the habit's schedule rules.
+    pub skipped: bool,
+}
+
+/// Everything the weekly planner shows for a single day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayOverview {
+    pub date: NaiveDate,
+    /// Schedule blocks on this day (recurring blocks expanded).
+    pub blocks: Vec<ScheduleBlockDto>,
+    /// Tasks due on this day, plus incomplete undated tasks of notes scheduled on it.
+    pub tasks: Vec<TaskWithContext>,
+    /// Completion of each active habit.
+    pub habits: Vec<HabitDaySummary>,
+    /// Number of notes for this day (scheduled, journal, or created).
+    pub note_count: i64,
+}
+
+/// A week of schedule blocks, tasks, habits, and note counts in one payload.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WeekOverview {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub days: Vec<DayOverview>,
+}
+
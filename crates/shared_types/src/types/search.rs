@@ -12,6 +12,10 @@ pub struct SearchResult {
     pub title: Option<String>,
     pub snippet: Option<String>,
     pub score: f64,
+    /// The note was saved since it was indexed for search, so the snippet
+    /// may be out of date (the index catches up within seconds).
+    #[serde(default)]
+    pub stale: bool,
 }
 
 /// Search query parameters.
@@ -23,6 +27,44 @@ pub struct SearchQuery {
     pub offset: Option<i32>,
 }
 
+/// Scoping filters for a search.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchFilters {
+    /// Only notes in this folder (including subfolders).
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Only notes with all of these tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Range on the note's modification date: "today", "this_week",
+    /// "this_month", "this_year", or "YYYY-MM-DD..YYYY-MM-DD" (either end
+    /// may be left open).
+    #[serde(default)]
+    pub date_range: Option<String>,
+}
+
+/// A named search with its filters, for one-click searches.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchPresetDto {
+    pub id: i64,
+    pub name: String,
+    /// FTS search string.
+    pub query: String,
+    pub filters: SearchFilters,
+}
+
+/// Request to save a search preset. Saving under an existing name replaces it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SaveSearchPresetRequest {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub filters: SearchFilters,
+}
+
 /// How a result was matched in hybrid search.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -51,6 +93,24 @@ pub struct HybridSearchResult {
     pub combined_score: f64,
     /// How this result was matched.
     pub match_type: MatchType,
+    /// Best matching section, for semantic matches of chunked notes.
+    pub section: Option<SearchSection>,
+    /// The keyword match may be out of date (see `SearchResult::stale`).
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// The section of a note a semantic search result matched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchSection {
+    /// Headings leading to the section, outermost first.
+    pub heading_path: Vec<String>,
+    pub snippet: String,
+    /// First and last line of the section in the note (1-indexed), for
+    /// scrolling the editor to the match.
+    pub start_line: u32,
+    pub end_line: u32,
 }
 
 /// Options for hybrid search.
@@ -64,3 +124,62 @@ pub struct HybridSearchOptions {
     /// Maximum results to return.
     pub limit: i32,
 }
+
+/// What a quick switcher result matched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum QuickSwitchKind {
+    /// The note title.
+    Title,
+    /// One of the note's aliases.
+    Alias,
+    /// The note path.
+    Path,
+    /// A heading inside the note.
+    Heading,
+    /// A recently opened note (shown for an empty query).
+    Recent,
+}
+
+/// A ranked quick switcher (Ctrl+P) result.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QuickSwitchResult {
+    pub kind: QuickSwitchKind,
+    pub note_id: i64,
+    pub path: String,
+    pub title: Option<String>,
+    /// The matched text (title, alias, path, or heading).
+    pub text: String,
+    /// Line of the heading (1-indexed), for heading results.
+    pub line_number: Option<i64>,
+    /// Higher is better.
+    pub score: i64,
+}
+
+/// What the autocomplete index completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum AutocompleteKind {
+    Title,
+    Alias,
+    Tag,
+    PropertyKey,
+    PropertyValue,
+    /// Task contexts (`@home`).
+    Context,
+    Heading,
+}
+
+/// An autocomplete suggestion.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AutocompleteItem {
+    pub text: String,
+    /// Number of notes using it.
+    pub count: i64,
+    /// The note using it, when there is only one (e.g. for a title).
+    pub note_id: Option<i64>,
+}
@@ -0,0 +1,17 @@
+//! Spell check types.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A misspelled word in checked text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Misspelling {
+    /// Start offset in UTF-16 code units (as used by the editor).
+    pub from: u32,
+    /// End offset in UTF-16 code units.
+    pub to: u32,
+    pub word: String,
+    /// Replacement suggestions, best first.
+    pub suggestions: Vec<String>,
+}
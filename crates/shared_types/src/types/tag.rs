@@ -3,10 +3,33 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-/// A tag with usage count.
+/// A tag with usage count. Notes tagged with one of its aliases count
+/// towards the tag.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct TagDto {
     pub tag: String,
     pub count: i64,
+    /// Description from the tag's metadata.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Display color (a CSS color) from the tag's metadata.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Tags declared as aliases of this one.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// Vault-level metadata for a tag (without `#`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TagMeta {
+    pub tag: String,
+    /// The tag this one is an alias of; it is then listed and filtered as
+    /// that tag.
+    pub alias_of: Option<String>,
+    pub description: Option<String>,
+    /// Display color (a CSS color, e.g. `#e06c75`).
+    pub color: Option<String>,
 }
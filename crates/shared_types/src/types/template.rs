@@ -1,4 +1,4 @@
-//! Template settings for daily notes and other templated content.
+//! Template settings for daily notes and other templated content, and note types.
 
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -12,6 +12,81 @@ pub struct TemplateSettings {
 
     /// Pattern for daily note file paths (e.g., "journal/{{year}}/{{month}}/{{date}}.md").
     pub daily_note_pattern: String,
+
+    /// Journal prompts rotated daily and available as `{{prompt}}` in the daily note template.
+    #[serde(default)]
+    pub journal_prompts: Vec<String>,
+
+    /// Path to the weekly note template file (relative to vault root).
+    #[serde(default)]
+    pub weekly_template_path: Option<String>,
+
+    /// Pattern for weekly note file paths, rendered for the week's first day
+    /// with the ISO week of its Monday (e.g.,
+    /// "journal/{{week_year}}/{{week_year}}-W{{week}}.md" or
+    /// "journal/{{iso_week}}.md").
+    #[serde(default = "default_weekly_note_pattern")]
+    pub weekly_note_pattern: String,
+
+    /// Path to the monthly note template file (relative to vault root).
+    #[serde(default)]
+    pub monthly_template_path: Option<String>,
+
+    /// Pattern for monthly note file paths, rendered for the first of the
+    /// month (e.g., "journal/{{year}}/{{year}}-{{month}}.md").
+    #[serde(default = "default_monthly_note_pattern")]
+    pub monthly_note_pattern: String,
+
+    /// User-defined variables, available as `{{name}}` in all templates.
+    /// Values may use the built-in variables (e.g. "Week {{week}}").
+    #[serde(default)]
+    pub variables: Vec<TemplateVariable>,
+
+    /// Reusable blocks of text, inserted with `{{snippet:name}}`.
+    #[serde(default)]
+    pub snippets: Vec<TemplateSnippet>,
+}
+
+/// A user-defined template variable.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TemplateVariable {
+    /// Name used in templates, without braces (e.g. "author").
+    pub name: String,
+    pub value: String,
+}
+
+/// A reusable block of template text.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TemplateSnippet {
+    /// Name used in `{{snippet:name}}`.
+    pub name: String,
+    /// The text, rendered like the template it is inserted into.
+    pub content: String,
+}
+
+/// Values from the app for rendering a template string.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TemplateRenderContext {
+    /// Date the date variables are for, as YYYY-MM-DD. Defaults to today.
+    #[serde(default)]
+    pub date: Option<String>,
+    /// Title of the active note, for `{{title}}`.
+    #[serde(default)]
+    pub note_title: Option<String>,
+    /// Clipboard text, for `{{clipboard}}`.
+    #[serde(default)]
+    pub clipboard: Option<String>,
+}
+
+fn default_weekly_note_pattern() -> String {
+    "journal/{{week_year}}/{{week_year}}-W{{week}}.md".to_string()
+}
+
+fn default_monthly_note_pattern() -> String {
+    "journal/{{year}}/{{year}}-{{month}}.md".to_string()
 }
 
 impl Default for TemplateSettings {
@@ -19,6 +94,13 @@ impl Default for TemplateSettings {
         Self {
             daily_template_path: None,
             daily_note_pattern: "journal/{{year}}/{{month}}/{{date}}.md".to_string(),
+            journal_prompts: Vec::new(),
+            weekly_template_path: None,
+            weekly_note_pattern: default_weekly_note_pattern(),
+            monthly_template_path: None,
+            monthly_note_pattern: default_monthly_note_pattern(),
+            variables: Vec::new(),
+            snippets: Vec::new(),
         }
     }
 }
@@ -36,3 +118,126 @@ pub struct DailyNoteResult {
     /// Whether the note was newly created (true) or already existed (false).
     pub created: bool,
 }
+
+/// The period a periodic (journal) note covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum NotePeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Direction to move from one periodic note to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum PeriodDirection {
+    Previous,
+    Next,
+}
+
+/// The periodic note for a day, week or month.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PeriodicNote {
+    pub period: NotePeriod,
+    /// First day of the period (YYYY-MM-DD): the date, the week's first
+    /// day, or the first of the month.
+    pub date: String,
+    /// The note path from the period's pattern.
+    pub path: String,
+    /// The note ID, or None if the note doesn't exist.
+    pub note_id: Option<i64>,
+    /// Whether the note was created by this call.
+    pub created: bool,
+}
+
+/// A note type (e.g. meeting, book, person): where new notes of the type go
+/// and what they start with. Notes of a type have it as their `type` property.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteTypeDefinition {
+    /// The type's name, used as the `type` property value (e.g. "meeting").
+    pub name: String,
+    /// Template for new notes (relative to vault root). Defaults to
+    /// `types/<name>.md` if that exists.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Properties every note of the type gets.
+    #[serde(default)]
+    pub properties: Vec<NoteTypeProperty>,
+    /// Folder new notes are created in (relative to vault root).
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Icon name shown in the new-note dialog.
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// A property set on new notes of a type.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NoteTypeProperty {
+    pub key: String,
+    /// Default value; can use `{{title}}` and the date variables. Empty if None.
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Type hint: "text", "date", "number", "boolean", "list"
+    #[serde(default)]
+    pub property_type: Option<String>,
+}
+
+/// A rule that creates a note from a template on a schedule, e.g. every
+/// Monday at 07:00 create `Weekly/{{date:YYYY-[W]WW}}.md`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScheduledNoteRule {
+    pub id: i64,
+    pub name: String,
+    /// Recurrence as an RRULE without DTSTART (e.g. "FREQ=WEEKLY;BYDAY=MO").
+    pub rrule: String,
+    /// Local time of day the note is created ("HH:MM").
+    pub time: String,
+    /// Path of the note, rendered for the day it is created on.
+    pub path_pattern: String,
+    /// Template for the note's content (relative to vault root). The note
+    /// only gets a title if there is none.
+    pub template_path: Option<String>,
+    pub enabled: bool,
+    /// When the rule last ran (local time, "YYYY-MM-DDTHH:MM:SS"). Missed
+    /// occurrences since then are caught up on.
+    pub last_run: Option<String>,
+    /// When the rule was created (local time); the recurrence starts here.
+    pub created_at: String,
+}
+
+/// Request to create or change a scheduled note rule.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SaveScheduledNoteRuleRequest {
+    pub name: String,
+    pub rrule: String,
+    pub time: String,
+    pub path_pattern: String,
+    #[serde(default)]
+    pub template_path: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A note created by a scheduled note rule.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScheduledNoteCreated {
+    pub rule_id: i64,
+    /// The occurrence the note was created for (local time).
+    pub scheduled_for: String,
+    pub path: String,
+    pub note_id: i64,
+}
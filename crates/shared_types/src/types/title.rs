@@ -0,0 +1,52 @@
+//! Title and filename consistency types.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Which side wins when a note's title and filename differ.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TitlePolicy {
+    /// Rename the file after the title (references are updated).
+    #[default]
+    FilenameFromTitle,
+    /// Change the title to the filename.
+    TitleFromFilename,
+}
+
+/// Settings for keeping titles and filenames in sync (stored in vault config).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TitleSettings {
+    #[serde(default)]
+    pub policy: TitlePolicy,
+    /// Folders whose notes are not checked (e.g. daily notes named by date).
+    #[serde(default)]
+    pub ignored_folders: Vec<String>,
+}
+
+/// Where a note's title comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleSource {
+    /// The `title` frontmatter property.
+    Frontmatter,
+    /// The first H1 heading.
+    Heading,
+}
+
+/// A note whose title does not match its filename.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TitleMismatch {
+    pub note_id: i64,
+    pub path: String,
+    pub title: String,
+    pub title_source: TitleSource,
+    /// Path after renaming the file after the title.
+    pub suggested_path: String,
+    /// Title after changing it to the filename.
+    pub suggested_title: String,
+}
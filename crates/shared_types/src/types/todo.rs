@@ -22,6 +22,8 @@ pub struct TodoDto {
     pub priority: Option<String>,
     /// Due date as YYYY-MM-DD string.
     pub due_date: Option<String>,
+    /// Estimated effort in minutes (from an `est:` token).
+    pub estimate_minutes: Option<i32>,
     pub created_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
 }
@@ -46,6 +48,10 @@ pub struct TaskWithContext {
     pub note_title: Option<String>,
     /// Properties inherited from the parent note (e.g., project, area).
     pub note_properties: Vec<PropertyDto>,
+    /// Path of the note that embeds the task's note, for tasks a query
+    /// includes from embedded notes.
+    #[serde(default)]
+    pub embedded_in: Option<String>,
 }
 
 /// Query parameters for filtering tasks.
@@ -67,3 +73,43 @@ pub struct TaskQuery {
     /// Maximum number of results.
     pub limit: Option<i32>,
 }
+
+/// A task ranked by the "what should I do now" suggestion engine.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskSuggestion {
+    /// The suggested task.
+    pub task: TaskWithContext,
+    /// Ranking score (higher is better).
+    pub score: f64,
+    /// Human-readable reasons contributing to the score (e.g., "due today").
+    pub reasons: Vec<String>,
+}
+
+/// A registered GTD context (`@home`, `@phone`) and its metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ContextMeta {
+    /// Context name without the `@`.
+    pub name: String,
+    /// Emoji or icon name.
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    /// Where the context applies (e.g., "Office, 2nd floor").
+    pub location: Option<String>,
+}
+
+/// A context used by tasks or registered, with its open-task count.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ContextDto {
+    pub name: String,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    /// Number of incomplete tasks with this context.
+    pub open_tasks: i64,
+    /// Whether the context is in the registry (unregistered contexts are
+    /// only used by tasks).
+    pub registered: bool,
+}
@@ -1,5 +1,6 @@
 //! Vault types.
 
+use super::event::IndexWarning;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -21,3 +22,197 @@ pub struct RecentVault {
     pub name: String,
     pub last_opened: DateTime<Utc>,
 }
+
+/// Options for scaffolding a new vault.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateVaultOptions {
+    /// Folder for new, unsorted notes.
+    #[serde(default = "default_inbox_folder")]
+    pub inbox_folder: String,
+    /// Folder for daily, weekly and monthly notes.
+    #[serde(default = "default_journal_folder")]
+    pub journal_folder: String,
+    /// Add daily, weekly, monthly and meeting templates to `templates/`.
+    #[serde(default = "default_true")]
+    pub sample_templates: bool,
+    /// Add a welcome note explaining the basics.
+    #[serde(default = "default_true")]
+    pub welcome_note: bool,
+}
+
+fn default_inbox_folder() -> String {
+    "inbox".to_string()
+}
+
+fn default_journal_folder() -> String {
+    "journal".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for CreateVaultOptions {
+    fn default() -> Self {
+        Self {
+            inbox_folder: default_inbox_folder(),
+            journal_folder: default_journal_folder(),
+            sample_templates: true,
+            welcome_note: true,
+        }
+    }
+}
+
+/// Where a vault's database and caches are stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DataLocation {
+    /// In the vault's `.neuroflow` folder.
+    #[default]
+    Vault,
+    /// In the app's data directory, outside the vault. Use this for vaults
+    /// in cloud-synced folders, where syncing corrupts the database.
+    AppData,
+}
+
+/// Storage settings (stored in vault config).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StorageSettings {
+    #[serde(default)]
+    pub data_location: DataLocation,
+}
+
+/// Size limits for indexing very large notes (stored in vault config).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct IndexSettings {
+    /// Notes larger than this many bytes are oversized: only their first
+    /// lines up to this size are parsed (links, tags, tasks) and indexed for
+    /// full-text search, and they are never embedded. Takes effect as notes are next indexed.
+    #[serde(default = "default_max_indexed_bytes")]
+    pub max_indexed_bytes: u64,
+}
+
+fn default_max_indexed_bytes() -> u64 {
+    1024 * 1024
+}
+
+impl Default for IndexSettings {
+    fn default() -> Self {
+        Self {
+            max_indexed_bytes: default_max_indexed_bytes(),
+        }
+    }
+}
+
+/// Where the open vault's database and caches currently are.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StorageInfo {
+    pub data_location: DataLocation,
+    /// Absolute path of the directory holding the database and caches.
+    pub data_dir: String,
+}
+
+/// A read-only folder outside the vault (e.g. a shared team wiki) whose
+/// notes are indexed for search and linking under `@name/`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExternalFolder {
+    /// Name of the folder's root in the tree; paths start with `@name/`.
+    pub name: String,
+    /// Absolute path of the folder.
+    pub path: String,
+}
+
+/// What happened to a note, in the vault's change journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+/// An entry in the vault's change journal (`changes.jsonl` in its data directory).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChangeRecord {
+    /// Position in the journal, from 1; pass the last one seen as the cursor.
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub kind: ChangeKind,
+    /// Vault-relative path of the note (the new path for renames).
+    pub path: String,
+    /// The path before a rename.
+    pub old_path: Option<String>,
+    /// Content hash after the change (None for deletions).
+    pub hash: Option<String>,
+}
+
+/// Changes after a cursor, and the cursor to continue from.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChangesPage {
+    pub changes: Vec<ChangeRecord>,
+    pub cursor: u64,
+    /// The cursor was older than the journal (its records were dropped, or
+    /// the journal deleted), so `changes` start from the oldest record kept
+    /// and the reader should resync everything.
+    pub reset: bool,
+}
+
+/// Why a note's database copy doesn't match its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    /// The file isn't indexed: it was added while the app was closed, or
+    /// the watcher missed it.
+    NotIndexed,
+    /// The note is indexed but its file is gone: it was deleted while the
+    /// app was closed, or the watcher missed it.
+    FileMissing,
+    /// The file was modified after it was last indexed: it was edited while
+    /// the app was closed, or the watcher missed the change.
+    ModifiedAfterIndexing,
+    /// The file differs from the indexed copy but wasn't modified since:
+    /// it was restored from a backup with its old timestamps, or indexing
+    /// was interrupted (e.g. by a crash).
+    ContentMismatch,
+}
+
+/// A note whose database copy is stale.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StaleNote {
+    pub path: String,
+    /// The note's ID, or None if it isn't indexed.
+    pub note_id: Option<i64>,
+    pub reason: StaleReason,
+    /// When the note was last indexed.
+    pub indexed_at: Option<DateTime<Utc>>,
+    /// When the file was last modified.
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+/// Result of checking the index against the files on disk.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct IndexVerificationReport {
+    /// Markdown files found on disk.
+    pub files_checked: i64,
+    /// Files whose hash matches the indexed copy.
+    pub up_to_date: i64,
+    pub stale: Vec<StaleNote>,
+    /// Stale notes that were re-indexed (or removed, for missing files).
+    pub reindexed: i64,
+    /// Files that could not be read or re-indexed.
+    pub warnings: Vec<IndexWarning>,
+    pub duration_ms: u64,
+}
@@ -0,0 +1,43 @@
+//! Workspace types - UI session state persisted per vault.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// An open note tab.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkspaceTab {
+    /// Note path relative to the vault root.
+    pub path: String,
+    /// Editor scroll offset in pixels.
+    #[serde(default)]
+    pub scroll_top: f64,
+    /// Cursor offset in the note, if known.
+    #[serde(default)]
+    pub cursor_position: Option<i64>,
+}
+
+/// Sidebar layout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkspaceSidebar {
+    pub open: bool,
+    /// Width in pixels (None for the default width).
+    pub width: Option<f64>,
+    /// Selected sidebar panel (e.g. "files", "tags").
+    pub active_panel: Option<String>,
+}
+
+/// UI session state of a vault.
+///
+/// Tabs follow their notes across renames; tabs of deleted notes are dropped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkspaceState {
+    /// Open tabs in display order.
+    pub tabs: Vec<WorkspaceTab>,
+    /// Path of the active tab.
+    pub active_path: Option<String>,
+    #[serde(default)]
+    pub sidebar: WorkspaceSidebar,
+}
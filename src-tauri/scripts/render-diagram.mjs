@@ -0,0 +1,33 @@
+#!/usr/bin/env node
+
+/**
+ * Diagram Renderer Script
+ *
+ * Renders a mermaid diagram to SVG with mermaid-cli, taking the same
+ * arguments as `mmdc`. Bundled with the app as the default diagram renderer
+ * when none is set in the device's settings.
+ *
+ * Usage:
+ *   node render-diagram.mjs -i <input.mmd> -o <output.svg>
+ */
+
+import { run } from '@mermaid-js/mermaid-cli';
+
+function argument(name) {
+    const index = process.argv.indexOf(name);
+    return index === -1 ? undefined : process.argv[index + 1];
+}
+
+const input = argument('-i');
+const output = argument('-o');
+if (!input || !output) {
+    console.error('Usage: render-diagram.mjs -i <input.mmd> -o <output.svg>');
+    process.exit(2);
+}
+
+try {
+    await run(input, output, { quiet: true });
+} catch (error) {
+    console.error(error instanceof Error ? error.message : String(error));
+    process.exit(1);
+}
@@ -0,0 +1,160 @@
+//! App settings commands - per-device settings in the OS config directory.
+
+use crate::state::AppState;
+use core_domain::app_settings;
+use shared_types::{AppSettings, WindowLayout};
+use std::path::PathBuf;
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, State, WebviewWindow, Window};
+use tracing::{instrument, warn};
+
+use super::{CommandError, Result};
+
+/// The settings file: `neuroflow/settings.json` in the OS config directory
+/// (e.g. `~/.config` on Linux).
+pub(crate) fn settings_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| CommandError::Vault("No config directory on this system".to_string()))?;
+    Ok(config_dir.join("neuroflow").join("settings.json"))
+}
+
+/// Get this device's settings (the defaults if none were saved).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_app_settings() -> Result<AppSettings> {
+    app_settings::read_app_settings(&settings_path()?)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Save this device's settings. The window layout is kept as saved when
+/// the main window last closed.
+#[tauri::command]
+#[instrument(skip(state, settings))]
+pub async fn save_app_settings(state: State<'_, AppState>, app: AppHandle, mut settings: AppSettings) -> Result<()> {
+    let path = settings_path()?;
+    settings.window = app_settings::read_app_settings(&path)
+        .await
+        .ok()
+        .and_then(|saved| saved.window);
+    app_settings::write_app_settings(&path, &settings)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    // The diagram renderer is a device setting used by the open vault
+    if let Some(vault) = state.vault.read().await.as_ref() {
+        let mut diagram_settings = vault.diagram_settings().await;
+        diagram_settings.renderer_path = settings.diagram_renderer_path.or_else(|| bundled_diagram_renderer(&app));
+        vault.set_diagram_settings(diagram_settings).await;
+    }
+    Ok(())
+}
+
+/// This device's diagram renderer: the one set in its settings, or the
+/// renderer bundled with the app.
+pub(crate) async fn diagram_renderer_path(app: &AppHandle) -> Option<String> {
+    let configured = match settings_path() {
+        Ok(path) => match app_settings::read_app_settings(&path).await {
+            Ok(settings) => settings.diagram_renderer_path,
+            Err(e) => {
+                warn!("Using the bundled diagram renderer: {}", e);
+                None
+            }
+        },
+        Err(_) => None,
+    };
+    configured.or_else(|| bundled_diagram_renderer(app))
+}
+
+/// The bundled `render-diagram.mjs` script (run with Node.js), if it is
+/// there. In development it is in src-tauri/scripts/.
+fn bundled_diagram_renderer(app: &AppHandle) -> Option<String> {
+    let script = std::path::Path::new("scripts").join("render-diagram.mjs");
+    app.path()
+        .resource_dir()
+        .ok()
+        .map(|dir| dir.join(&script))
+        .filter(|path| path.exists())
+        .or_else(|| Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(&script)).filter(|path| path.exists()))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// Take over the settings the frontend kept in localStorage, unless there
+/// is a settings file already, and return the settings in effect.
+#[tauri::command]
+#[instrument(skip(legacy))]
+pub async fn migrate_app_settings(legacy: AppSettings) -> Result<AppSettings> {
+    app_settings::migrate_app_settings(&settings_path()?, legacy)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Keep a settings file that can't be parsed as `settings.invalid.json`
+/// before anything saves the settings. Runs once at startup.
+pub fn keep_invalid_app_settings() {
+    let Ok(path) = settings_path() else {
+        return;
+    };
+    if let Err(e) = tauri::async_runtime::block_on(app_settings::keep_invalid_app_settings(&path)) {
+        warn!("Failed to keep the unparsable settings file: {}", e);
+    }
+}
+
+/// Give the main window the size and position it had when it last closed.
+pub fn restore_window_layout(window: &WebviewWindow) {
+    let Ok(path) = settings_path() else {
+        return;
+    };
+    let layout = match tauri::async_runtime::block_on(app_settings::read_app_settings(&path)) {
+        Ok(settings) => settings.window,
+        Err(e) => {
+            warn!("Not restoring the window layout: {}", e);
+            None
+        }
+    };
+    let Some(layout) = layout else {
+        return;
+    };
+
+    let _ = window.set_size(LogicalSize::new(layout.width, layout.height));
+    if let (Some(x), Some(y)) = (layout.x, layout.y) {
+        let _ = window.set_position(LogicalPosition::new(x, y));
+    }
+    if layout.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Save the main window's size and position as it closes. A maximized
+/// window keeps the size it had before it was maximized.
+pub async fn save_window_layout(window: &Window) {
+    let Ok(path) = settings_path() else {
+        return;
+    };
+    let maximized = window.is_maximized().unwrap_or(false);
+    let scale = window.scale_factor().unwrap_or(1.0);
+    // Read the window before the settings, while it is still there
+    let current = match (window.inner_size(), window.outer_position()) {
+        (Ok(size), Ok(position)) => Some((size.to_logical::<f64>(scale), position.to_logical::<f64>(scale))),
+        _ => None,
+    };
+
+    let result = async {
+        let saved = app_settings::read_app_settings(&path).await?.window;
+        let layout = match (saved, current) {
+            (Some(saved), _) if maximized => WindowLayout { maximized, ..saved },
+            (_, Some((size, position))) => WindowLayout {
+                width: size.width,
+                height: size.height,
+                x: Some(position.x),
+                y: Some(position.y),
+                maximized,
+            },
+            (_, None) => return Ok(()),
+        };
+        app_settings::save_window_layout(&path, layout).await
+    }
+    .await;
+    if let Err(e) = result {
+        warn!("Failed to save the window layout: {}", e);
+    }
+}
@@ -1,13 +1,17 @@
-//! Backlink commands.
+//! Backlink, link graph export and link settings commands.
 
 use crate::state::AppState;
-use shared_types::BacklinkDto;
+use shared_types::{BacklinkDto, GraphExportFormat, GraphExportResult, GraphScope, LinkCandidate, LinkSettings, NoteLinkCount, NoteResolution};
+use std::path::Path;
 use tauri::State;
+use tracing::{info, instrument};
 
+use super::templates::{read_vault_config, write_vault_config};
 use super::{CommandError, Result};
 
 /// Get backlinks for a note.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_backlinks(state: State<'_, AppState>, note_id: i64) -> Result<Vec<BacklinkDto>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -18,3 +22,131 @@ pub async fn get_backlinks(state: State<'_, AppState>, note_id: i64) -> Result<V
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
+
+/// Notes with no links in or out.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_orphan_notes(state: State<'_, AppState>) -> Result<Vec<NoteLinkCount>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_orphan_notes()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Notes that link to no other note.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_dead_end_notes(state: State<'_, AppState>) -> Result<Vec<NoteLinkCount>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_dead_end_notes()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// The most linked notes (20 by default).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_hub_notes(state: State<'_, AppState>, limit: Option<i64>) -> Result<Vec<NoteLinkCount>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_hub_notes(limit.unwrap_or(20))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Export the link structure of the vault, or of the notes in a folder or
+/// with a tag, as a DOT, GEXF or JSON graph file at `output_path`.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_graph(
+    state: State<'_, AppState>,
+    format: GraphExportFormat,
+    scope: Option<GraphScope>,
+    output_path: String,
+) -> Result<GraphExportResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::graph_export::export_graph(vault, format, &scope.unwrap_or_default(), Path::new(&output_path))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// List the notes a wiki link target could refer to when more than one
+/// matches, so the user can pick. `from_path` is the note containing the
+/// link; the note the link resolves to comes first.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_ambiguous_link_targets(
+    state: State<'_, AppState>,
+    target: String,
+    from_path: Option<String>,
+) -> Result<Vec<LinkCandidate>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .ambiguous_link_targets(&target, from_path.as_deref())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Rank the notes a reference could refer to, including near-typos, with
+/// how sure the best one is meant. `from_path` is the note containing it.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn resolve_note(
+    state: State<'_, AppState>,
+    target: String,
+    from_path: Option<String>,
+    limit: Option<usize>,
+) -> Result<NoteResolution> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .rank_note_matches(&target, from_path.as_deref(), limit.unwrap_or(10))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get link settings from vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_link_settings(state: State<'_, AppState>) -> Result<LinkSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.link_settings)
+}
+
+/// Save link settings to vault config. Links written from now on use the
+/// new format; existing links are left as they are.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_link_settings(state: State<'_, AppState>, settings: LinkSettings) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.link_settings = settings;
+    write_vault_config(&config_path, &config).await?;
+    vault.set_link_format(config.link_settings.link_format).await;
+    vault.set_keep_old_titles(config.link_settings.keep_old_titles).await;
+
+    info!("Saved link settings");
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Book commands - OpenLibrary metadata for the reading log and shelves.
+
+use crate::state::AppState;
+use core_domain::attachments::{attachment_file_name, save_attachment};
+use core_domain::books::{
+    apply_book_metadata, book_shelves, normalize_isbn, parse_isbn_response, parse_search_response,
+};
+use shared_types::{BookMetadata, BookShelf};
+use tauri::State;
+use tracing::{info, instrument, warn};
+
+use super::embeds::fetch_attachment;
+use super::templates::read_vault_config;
+use super::{CommandError, Result};
+
+/// Timeout for OpenLibrary API requests.
+const LOOKUP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Look up a book on OpenLibrary by ISBN (or by title if there is no ISBN)
+/// and store what was found as properties of the note, marking it as a
+/// book. The cover is downloaded to the attachment folder; a cover that
+/// fails to download is skipped. Returns the metadata.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn fetch_book_metadata(
+    state: State<'_, AppState>,
+    note_id: i64,
+    isbn: Option<String>,
+    title: Option<String>,
+) -> Result<BookMetadata> {
+    let mut metadata = match (isbn.as_deref().filter(|i| !i.trim().is_empty()), title.as_deref()) {
+        (Some(isbn), _) => {
+            let isbn = normalize_isbn(isbn).ok_or_else(|| CommandError::Vault(format!("Invalid ISBN: {}", isbn)))?;
+            let url = reqwest::Url::parse_with_params(
+                "https://openlibrary.org/api/books",
+                &[("bibkeys", format!("ISBN:{}", isbn).as_str()), ("format", "json"), ("jscmd", "data")],
+            )
+            .map_err(|e| CommandError::Vault(format!("Invalid URL: {}", e)))?;
+            parse_isbn_response(&fetch_json(url).await?, &isbn)
+        }
+        (None, Some(title)) if !title.trim().is_empty() => {
+            let url = reqwest::Url::parse_with_params(
+                "https://openlibrary.org/search.json",
+                &[("title", title.trim()), ("limit", "1")],
+            )
+            .map_err(|e| CommandError::Vault(format!("Invalid URL: {}", e)))?;
+            parse_search_response(&fetch_json(url).await?)
+        }
+        _ => return Err(CommandError::Vault("An ISBN or a title is required".to_string())),
+    }
+    .ok_or_else(|| CommandError::Vault("No book found on OpenLibrary".to_string()))?;
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    if let Some(cover_url) = metadata.cover_url.as_deref().and_then(|u| reqwest::Url::parse(u).ok()) {
+        let saved = match fetch_attachment(&cover_url).await {
+            Ok((file_name, bytes)) => {
+                let extension = file_name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("jpg");
+                let name = format!("{} cover", metadata.title.as_deref().unwrap_or("Book"));
+                let settings = read_vault_config(&vault.fs().config_path()).await?.attachment_settings;
+                save_attachment(vault, &settings, None, &attachment_file_name(&name, extension), &bytes)
+                    .await
+                    .map_err(|e| CommandError::Vault(e.to_string()))
+            }
+            Err(e) => Err(e),
+        };
+        match saved {
+            Ok(path) => metadata.cover_path = Some(path),
+            Err(e) => warn!("Failed to download cover {}: {}", cover_url, e),
+        }
+    }
+
+    apply_book_metadata(vault, note_id, &metadata)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    info!("Fetched book metadata for note {}: {:?}", note_id, metadata.title);
+    Ok(metadata)
+}
+
+/// The books in the vault (notes with `type: book`) grouped by their
+/// `status` property: reading, to-read, read, abandoned, other statuses,
+/// then books without a status.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_book_shelves(state: State<'_, AppState>) -> Result<Vec<BookShelf>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    book_shelves(vault)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+async fn fetch_json(url: reqwest::Url) -> Result<serde_json::Value> {
+    let client = reqwest::Client::builder()
+        .timeout(LOOKUP_TIMEOUT)
+        .build()
+        .map_err(|e| CommandError::Vault(format!("Failed to create HTTP client: {}", e)))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| CommandError::Vault(format!("Request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(CommandError::Vault(format!("Request failed: HTTP {}", response.status())));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| CommandError::Vault(format!("Failed to read response: {}", e)))
+}
@@ -0,0 +1,195 @@
+//! Calendar subscription commands - external ICS feeds shown as read-only blocks.
+
+use crate::state::AppState;
+use chrono::Utc;
+use core_domain::Vault;
+use shared_types::{CalendarSubscriptionDto, CreateCalendarSubscriptionRequest};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
+use tracing::{debug, instrument, warn};
+
+use super::{CommandError, Result};
+
+/// How often the background task checks for subscriptions that are due.
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Timeout for fetching a single feed.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Subscribe to an external calendar and fetch it right away.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn add_calendar_subscription(
+    state: State<'_, AppState>,
+    request: CreateCalendarSubscriptionRequest,
+) -> Result<i64> {
+    let id = {
+        let vault_guard = state.vault.read().await;
+        let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+        vault
+            .repo()
+            .create_calendar_subscription(&request)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?
+    };
+
+    // A failed first fetch is recorded on the subscription, not returned
+    refresh_subscription(&state.vault, id, &request.url).await;
+
+    Ok(id)
+}
+
+/// List all calendar subscriptions.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_calendar_subscriptions(
+    state: State<'_, AppState>,
+) -> Result<Vec<CalendarSubscriptionDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_calendar_subscriptions()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Remove a calendar subscription and its events.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn remove_calendar_subscription(state: State<'_, AppState>, id: i64) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_calendar_subscription(id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Fetch all calendar subscriptions now, regardless of their refresh interval.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn refresh_calendar_subscriptions(
+    state: State<'_, AppState>,
+) -> Result<Vec<CalendarSubscriptionDto>> {
+    let subscriptions = {
+        let vault_guard = state.vault.read().await;
+        let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+        vault
+            .repo()
+            .list_calendar_subscriptions()
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?
+    };
+
+    for subscription in &subscriptions {
+        refresh_subscription(&state.vault, subscription.id, &subscription.url).await;
+    }
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_calendar_subscriptions()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Periodically refresh due subscriptions while the vault at `root` stays
+/// open. Abort the returned task when the vault is closed.
+pub(crate) fn spawn_calendar_refresh(vault: Arc<RwLock<Option<Vault>>>, root: PathBuf) -> AbortHandle {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let due = {
+                let vault_guard = vault.read().await;
+                match vault_guard.as_ref() {
+                    Some(v) if v.root_path() == root => {
+                        v.repo().get_due_calendar_subscriptions(Utc::now()).await
+                    }
+                    // The vault was closed or another one was opened
+                    _ => break,
+                }
+            };
+
+            match due {
+                Ok(due) => {
+                    for subscription in due {
+                        refresh_subscription(&vault, subscription.id, &subscription.url).await;
+                    }
+                }
+                Err(e) => warn!("Failed to load calendar subscriptions: {}", e),
+            }
+        }
+        debug!("Stopped calendar refresh for {}", root.display());
+    })
+    .abort_handle()
+}
+
+/// Fetch one feed and store its events, recording any error on the subscription.
+///
+/// The vault lock is not held while the feed is downloaded.
+async fn refresh_subscription(vault: &RwLock<Option<Vault>>, id: i64, url: &str) {
+    let result = fetch_ics(url).await;
+
+    let vault_guard = vault.read().await;
+    let Some(vault) = vault_guard.as_ref() else {
+        return;
+    };
+    let repo = vault.repo();
+
+    let stored = match result {
+        Ok(content) => {
+            let events = core_index::parse_ics(&content);
+            repo.replace_calendar_events(id, &events).await
+        }
+        Err(e) => {
+            warn!("Failed to fetch calendar subscription {}: {}", id, e);
+            repo.set_calendar_subscription_status(id, Some(&e)).await
+        }
+    };
+
+    if let Err(e) = stored {
+        warn!("Failed to store calendar subscription {}: {}", id, e);
+    }
+}
+
+/// Download an ICS feed. `webcal://` URLs are fetched over HTTPS.
+async fn fetch_ics(url: &str) -> std::result::Result<String, String> {
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => url.to_string(),
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))
+}
@@ -0,0 +1,131 @@
+//! Web clipper commands - saving web pages as notes.
+
+use crate::state::AppState;
+use core_domain::attachments::save_attachment;
+use core_index::{extract_article, set_frontmatter_property};
+use std::collections::HashSet;
+use tauri::State;
+use tracing::{info, instrument, warn};
+
+use super::embeds::fetch_attachment;
+use super::templates::read_vault_config;
+use super::{CommandError, Result};
+
+/// Timeout for fetching the page itself.
+const PAGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Time allowed for downloading all of a page's images.
+const IMAGES_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Largest page accepted.
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Clip a web page into a new note at `target_path`.
+///
+/// The main content of the page is converted to markdown, its images are
+/// downloaded to the attachment folder and embedded locally (images that
+/// fail to download keep their remote URL), and `source` and `clipped`
+/// frontmatter is added. Pages over the size limit are refused, and images
+/// not downloaded in time count as failed.
+/// The note is indexed right away.
+/// Returns the new note's ID.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn clip_url(state: State<'_, AppState>, url: String, target_path: String) -> Result<i64> {
+    let page_url = reqwest::Url::parse(&url)
+        .map_err(|e| CommandError::Vault(format!("Invalid URL: {}", e)))?;
+    if !matches!(page_url.scheme(), "http" | "https") {
+        return Err(CommandError::Vault(format!("Unsupported URL scheme: {}", page_url.scheme())));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(PAGE_TIMEOUT)
+        .build()
+        .map_err(|e| CommandError::Vault(format!("Failed to create HTTP client: {}", e)))?;
+
+    let mut response = client
+        .get(page_url.clone())
+        .send()
+        .await
+        .map_err(|e| CommandError::Vault(format!("Request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(CommandError::Vault(format!("Request failed: HTTP {}", response.status())));
+    }
+
+    let too_large = || CommandError::Vault(format!("Page exceeds {} MB", MAX_PAGE_BYTES / (1024 * 1024)));
+    if response.content_length().is_some_and(|len| len as usize > MAX_PAGE_BYTES) {
+        return Err(too_large());
+    }
+    // Read in chunks so a missing or wrong Content-Length can't exceed the limit
+    let mut page_bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| CommandError::Vault(format!("Failed to read response: {}", e)))?
+    {
+        if page_bytes.len() + chunk.len() > MAX_PAGE_BYTES {
+            return Err(too_large());
+        }
+        page_bytes.extend_from_slice(&chunk);
+    }
+    let html = String::from_utf8_lossy(&page_bytes);
+
+    let page = extract_article(&html);
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    if vault.fs().exists(std::path::Path::new(&target_path)).await {
+        return Err(CommandError::Vault(format!("File already exists: {}", target_path)));
+    }
+
+    // Download images and embed the local copies
+    let settings = read_vault_config(&vault.fs().config_path()).await?.attachment_settings;
+    let images_deadline = tokio::time::Instant::now() + IMAGES_TIMEOUT;
+    let mut markdown = page.markdown.clone();
+    let mut seen = HashSet::new();
+    for image in &page.images {
+        if !seen.insert(image.src.as_str()) {
+            continue;
+        }
+        let Ok(image_url) = page_url.join(&image.src) else {
+            continue;
+        };
+
+        let fetched = tokio::time::timeout_at(images_deadline, fetch_attachment(&image_url))
+            .await
+            .unwrap_or_else(|_| Err(CommandError::Vault("Timed out".to_string())));
+        let saved = match fetched {
+            Ok((file_name, bytes)) => save_attachment(vault, &settings, None, &file_name, &bytes)
+                .await
+                .map_err(|e| CommandError::Vault(e.to_string())),
+            Err(e) => Err(e),
+        };
+        let replacement = match saved {
+            Ok(path) => format!("![[{}]]", path),
+            Err(e) => {
+                warn!("Failed to download image {}: {}", image_url, e);
+                format!("![{}]({})", image.alt, image_url)
+            }
+        };
+        markdown = markdown.replace(&image.markdown(), &replacement);
+    }
+
+    let mut body = String::new();
+    if let Some(title) = &page.title {
+        body.push_str(&format!("# {}\n\n", title));
+    }
+    body.push_str(&markdown);
+
+    let clipped = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let content = set_frontmatter_property(&body, "source", Some(&url), None);
+    let content = set_frontmatter_property(&content, "clipped", Some(&clipped), None);
+
+    let note_id = vault
+        .write_note(&target_path, &content)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    info!("Clipped {} to {} ({} images)", url, target_path, seen.len());
+    Ok(note_id)
+}
@@ -0,0 +1,43 @@
+//! Discovery commands - random notes and "on this day" for the serendipity widget.
+
+use crate::state::AppState;
+use chrono::NaiveDate;
+use shared_types::{NoteListItem, OnThisDayNote, RandomNoteFilter};
+use tauri::State;
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+/// Pick a random note, optionally restricted to a folder and/or tag.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_random_note(
+    state: State<'_, AppState>,
+    filters: Option<RandomNoteFilter>,
+) -> Result<Option<NoteListItem>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_random_note(&filters.unwrap_or_default())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get notes journaled or created on the same month and day in previous years.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_on_this_day(state: State<'_, AppState>, date: String) -> Result<Vec<OnThisDayNote>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    vault
+        .repo()
+        .get_on_this_day(date)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
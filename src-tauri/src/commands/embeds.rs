@@ -1,128 +1,39 @@
 //! Embed commands - resolution and image handling.
 
 use crate::state::AppState;
-use shared_types::{EmbedContent, HeadingInfo, ResolveEmbedRequest};
+use shared_types::{AttachmentSettings, EmbedContent, HeadingInfo, ResolveEmbedRequest};
 use tauri::{AppHandle, State};
 use tracing::{info, instrument};
 
+use super::templates::{read_vault_config, write_vault_config};
 use super::{CommandError, Result};
 
+/// Timeout for downloading an attachment.
+const DOWNLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
 /// Resolve an embed (![[target]] or ![[target#section]]).
 /// Returns the content to embed, handling images and notes differently.
+///
+/// Nested embeds of a note are followed up to the maximum depth; a loop
+/// is returned as an error with the chain of embeds in `cycle`.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn resolve_embed(
     state: State<'_, AppState>,
     _app: AppHandle,
     request: ResolveEmbedRequest,
 ) -> Result<EmbedContent> {
-    // Check depth limit
-    if request.depth > 3 {
-        return Ok(EmbedContent {
-            note_id: None,
-            path: request.target.clone(),
-            content: None,
-            is_image: false,
-            asset_url: None,
-            error: Some("Maximum embed depth (3) exceeded".to_string()),
-        });
-    }
-
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
-    // Check if target is a media file (image, audio, video, pdf)
-    let image_extensions = ["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "ico"];
-    let audio_extensions = ["mp3", "wav", "ogg", "m4a", "flac"];
-    let video_extensions = ["mp4", "webm", "mov", "avi"];
-    let pdf_extensions = ["pdf"];
-
-    let target_lower = request.target.to_lowercase();
-    let is_image = image_extensions
-        .iter()
-        .any(|ext| target_lower.ends_with(&format!(".{}", ext)));
-    let is_audio = audio_extensions
-        .iter()
-        .any(|ext| target_lower.ends_with(&format!(".{}", ext)));
-    let is_video = video_extensions
-        .iter()
-        .any(|ext| target_lower.ends_with(&format!(".{}", ext)));
-    let is_pdf = pdf_extensions
-        .iter()
-        .any(|ext| target_lower.ends_with(&format!(".{}", ext)));
-    let is_media = is_image || is_audio || is_video || is_pdf;
-
-    if is_media {
-        // Resolve media file path
-        info!("Resolving media path for target: {}", request.target);
-        let media_path = vault.resolve_asset_path(&request.target).await;
-        info!("Resolved media path: {:?}", media_path);
-
-        match media_path {
-            Some(full_path) => {
-                // Return the full filesystem path - frontend will convert it using convertFileSrc
-                Ok(EmbedContent {
-                    note_id: None,
-                    path: request.target,
-                    content: None,
-                    is_image: is_media, // Keep using is_image field for backwards compat (means "is media")
-                    asset_url: Some(full_path.to_string_lossy().to_string()),
-                    error: None,
-                })
-            }
-            None => Ok(EmbedContent {
-                note_id: None,
-                path: request.target.clone(),
-                content: None,
-                is_image: is_media,
-                asset_url: None,
-                error: Some(format!("Media not found: {}", request.target)),
-            }),
-        }
-    } else {
-        // Resolve note
-        let note_result = vault.resolve_note(&request.target).await;
-
-        match note_result {
-            Some((note_id, path)) => {
-                // Read note content
-                let content = vault
-                    .read_note(&path)
-                    .await
-                    .map_err(|e| CommandError::Vault(e.to_string()))?;
-
-                // Extract section if requested
-                let final_content = if let Some(ref section) = request.section {
-                    // Slugify the section name to match how headings are stored
-                    let section_slug = core_index::markdown::slugify(section);
-                    core_index::markdown::extract_section_with_heading(&content, &section_slug)
-                        .unwrap_or_else(|| format!("Section '{}' not found", section))
-                } else {
-                    content
-                };
-
-                Ok(EmbedContent {
-                    note_id: Some(note_id),
-                    path,
-                    content: Some(final_content),
-                    is_image: false,
-                    asset_url: None,
-                    error: None,
-                })
-            }
-            None => Ok(EmbedContent {
-                note_id: None,
-                path: request.target.clone(),
-                content: None,
-                is_image: false,
-                asset_url: None,
-                error: Some(format!("Note not found: {}", request.target)),
-            }),
-        }
-    }
+    core_domain::embeds::resolve_embed(vault, &request)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
 /// Get all headings from a note (for section autocomplete).
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_note_headings(
     state: State<'_, AppState>,
     path: String,
@@ -150,42 +61,194 @@ pub async fn get_note_headings(
         .collect())
 }
 
-/// Save a pasted image to the vault's assets folder.
-/// Returns the filename that was saved (e.g., "Pasted image 20251208143000.png").
+/// Save a pasted image according to the vault's attachment settings.
+///
+/// Identical images are stored only once. Returns the path relative to the
+/// folder of `note_path` (the note the image is pasted into), or to the vault
+/// root without a note.
 #[tauri::command]
 #[instrument(skip(state, image_data))]
 pub async fn save_pasted_image(
     state: State<'_, AppState>,
     image_data: String,
     extension: String,
+    note_path: Option<String>,
 ) -> Result<String> {
-    use std::io::Write;
-
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
-    // Get vault root path
-    let vault_root = vault.root_path();
-
-    // Generate filename with timestamp (matching Obsidian's format)
-    let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S");
-    let filename = format!("Pasted image {}.{}", timestamp, extension);
-    let file_path = vault_root.join(&filename);
-
     // Decode base64 image data
     use base64::Engine;
     let image_bytes = base64::engine::general_purpose::STANDARD
         .decode(&image_data)
         .map_err(|e| CommandError::Vault(format!("Failed to decode image data: {}", e)))?;
 
-    // Write the file
-    let mut file = std::fs::File::create(&file_path)
-        .map_err(|e| CommandError::Vault(format!("Failed to create image file: {}", e)))?;
-    file.write_all(&image_bytes)
-        .map_err(|e| CommandError::Vault(format!("Failed to write image data: {}", e)))?;
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    let path = core_domain::attachments::save_pasted_image(
+        vault,
+        &config.attachment_settings,
+        note_path.as_deref(),
+        &image_bytes,
+        &extension,
+        chrono::Local::now().naive_local(),
+    )
+    .await
+    .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    info!("Saved pasted image: {}", path);
+    Ok(path)
+}
+
+/// Get attachment settings from vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_attachment_settings(state: State<'_, AppState>) -> Result<AttachmentSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.attachment_settings)
+}
+
+/// Save attachment settings to vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_attachment_settings(
+    state: State<'_, AppState>,
+    settings: AttachmentSettings,
+) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.attachment_settings = settings;
+    write_vault_config(&config_path, &config).await?;
+
+    info!("Saved attachment settings");
+    Ok(())
+}
+
+/// Get a cached thumbnail of an image that fits in `max_dimension` pixels.
+///
+/// Returns the full filesystem path (for convertFileSrc), which is the
+/// image itself if it is already small enough or can't be resized.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_thumbnail(
+    state: State<'_, AppState>,
+    path: String,
+    max_dimension: u32,
+) -> Result<String> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let thumbnail = core_domain::thumbnails::get_thumbnail(vault, &path, max_dimension)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    Ok(thumbnail.to_string_lossy().to_string())
+}
+
+/// Download a remote image or PDF into the vault.
+///
+/// The file is saved to `target_folder` (the vault's attachment folder by default)
+/// under a sanitized name taken from the URL. Only image and PDF content
+/// types up to the size limit are accepted.
+/// Returns the path relative to the vault root, ready to embed.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn download_attachment(
+    state: State<'_, AppState>,
+    url: String,
+    target_folder: Option<String>,
+) -> Result<String> {
+    let parsed = reqwest::Url::parse(&url)
+        .map_err(|e| CommandError::Vault(format!("Invalid URL: {}", e)))?;
+    let (file_name, bytes) = fetch_attachment(&parsed).await?;
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    let path = core_domain::attachments::save_attachment(
+        vault,
+        &config.attachment_settings,
+        target_folder.as_deref(),
+        &file_name,
+        &bytes,
+    )
+    .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    info!("Downloaded {} to {}", url, path);
+    Ok(path)
+}
+
+/// Fetch an image or PDF, checking its content type and size.
+///
+/// Returns a sanitized file name (from the URL) and the content.
+pub(crate) async fn fetch_attachment(url: &reqwest::Url) -> Result<(String, Vec<u8>)> {
+    use core_domain::attachments::{attachment_extension, attachment_file_name, MAX_ATTACHMENT_BYTES};
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(CommandError::Vault(format!("Unsupported URL scheme: {}", url.scheme())));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| CommandError::Vault(format!("Failed to create HTTP client: {}", e)))?;
+
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(|e| CommandError::Vault(format!("Download failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(CommandError::Vault(format!("Download failed: HTTP {}", response.status())));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let extension = attachment_extension(&content_type)
+        .ok_or_else(|| CommandError::Vault(format!("Unsupported content type: {}", content_type)))?;
+
+    let too_large = || {
+        CommandError::Vault(format!(
+            "Attachment exceeds {} MB",
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        ))
+    };
+    if response.content_length().is_some_and(|len| len as usize > MAX_ATTACHMENT_BYTES) {
+        return Err(too_large());
+    }
+
+    // Read in chunks so a missing or wrong Content-Length can't exceed the limit
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| CommandError::Vault(format!("Download failed: {}", e)))?
+    {
+        if bytes.len() + chunk.len() > MAX_ATTACHMENT_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
 
-    info!("Saved pasted image: {}", file_path.display());
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or("");
+    let name = urlencoding::decode(segment)
+        .map(|s| s.into_owned())
+        .unwrap_or_else(|_| segment.to_string());
 
-    // Return the filename (relative to vault root)
-    Ok(filename)
+    Ok((attachment_file_name(&name, extension), bytes))
 }
@@ -1,13 +1,18 @@
 //! Folder tree commands and helpers.
 
 use crate::state::AppState;
-use shared_types::{FolderNode, NoteListItem};
+use core_fs::{VaultFs, EXTERNAL_PREFIX};
+use shared_types::{FolderAppearance, FolderMetadata, FolderNode, NoteListItem};
+use std::collections::HashMap;
+use std::path::Path;
 use tauri::State;
+use tracing::{instrument, warn};
 
 use super::{CommandError, Result};
 
 /// Get the folder tree for the vault.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_folder_tree(state: State<'_, AppState>) -> Result<FolderNode> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -21,16 +26,108 @@ pub async fn get_folder_tree(state: State<'_, AppState>) -> Result<FolderNode> {
     let mut root = build_folder_tree(&notes, vault.fs().root().to_string_lossy().to_string());
 
     // Also scan actual directories to include empty folders
-    scan_directories(&mut root, vault.fs().root(), vault.fs().root())
+    scan_directories(&mut root, vault.fs().root(), vault.fs())
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
+    for (name, path) in vault.fs().external_folders() {
+        let external = external_root(&mut root, name);
+        // An unreachable external folder (e.g. an unmounted share) shows up empty
+        if let Err(e) = scan_directories(external, path, vault.fs()).await {
+            warn!("Failed to scan external folder {}: {}", name, e);
+        }
+    }
 
     // Re-sort after adding directories
     sort_tree(&mut root);
 
+    // Apply folder icons, colors, collapsed state, and manual order
+    let metadata: HashMap<String, FolderMetadata> = vault
+        .repo()
+        .list_folder_metadata()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .into_iter()
+        .map(|m| (m.path.clone(), m))
+        .collect();
+    apply_folder_metadata(&mut root, &metadata);
+
+    // External folders are separate, read-only roots after the vault's own entries
+    for child in &mut root.children {
+        if vault.fs().is_read_only(Path::new(&child.path)) {
+            mark_read_only(child);
+        }
+    }
+    root.children.sort_by_key(|c| c.read_only);
+
     Ok(root)
 }
 
+/// Find or add the top-level node for an external folder.
+fn external_root<'a>(root: &'a mut FolderNode, name: &str) -> &'a mut FolderNode {
+    let path = format!("{}{}", EXTERNAL_PREFIX, name);
+    let idx = match root.children.iter().position(|c| c.path == path) {
+        Some(idx) => idx,
+        None => {
+            root.children.push(FolderNode {
+                name: path.clone(),
+                path,
+                is_dir: true,
+                children: Vec::new(),
+                icon: None,
+                color: None,
+                collapsed: false,
+                read_only: false,
+            });
+            root.children.len() - 1
+        }
+    };
+    &mut root.children[idx]
+}
+
+fn mark_read_only(node: &mut FolderNode) {
+    node.read_only = true;
+    for child in &mut node.children {
+        mark_read_only(child);
+    }
+}
+
+/// Set a folder's icon, color, and collapsed state.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn set_folder_appearance(
+    state: State<'_, AppState>,
+    path: String,
+    appearance: FolderAppearance,
+) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .set_folder_appearance(&path, &appearance)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Set the manual order of a folder's children by name ("" for the vault root).
+/// An empty list restores the default order.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn reorder_folder_children(
+    state: State<'_, AppState>,
+    path: String,
+    children: Vec<String>,
+) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .set_folder_child_order(&path, &children)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
 /// Image/media file extensions to include in the tree
 const MEDIA_EXTENSIONS: &[&str] = &[
     "png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "ico", "mp3", "wav", "ogg", "m4a", "flac",
@@ -50,7 +147,7 @@ fn is_media_file(path: &std::path::Path) -> bool {
 async fn scan_directories(
     node: &mut FolderNode,
     current_dir: &std::path::Path,
-    vault_root: &std::path::Path,
+    fs: &VaultFs,
 ) -> std::result::Result<(), String> {
     let mut entries = match tokio::fs::read_dir(current_dir).await {
         Ok(entries) => entries,
@@ -67,8 +164,8 @@ async fn scan_directories(
         }
 
         // Get relative path
-        let relative = path
-            .strip_prefix(vault_root)
+        let relative = fs
+            .to_relative(&path)
             .map_err(|e| e.to_string())?
             .to_string_lossy()
             .to_string();
@@ -84,9 +181,13 @@ async fn scan_directories(
                     path: relative.clone(),
                     is_dir: true,
                     children: Vec::new(),
+                    icon: None,
+                    color: None,
+                    collapsed: false,
+                    read_only: false,
                 };
                 // Recursively scan subdirectories
-                scan_directories(&mut new_dir, &path, vault_root).await?;
+                scan_directories(&mut new_dir, &path, fs).await?;
                 node.children.push(new_dir);
             } else {
                 // Directory exists, find it and scan its subdirectories
@@ -95,7 +196,7 @@ async fn scan_directories(
                     .iter_mut()
                     .find(|c| c.is_dir && c.path == relative)
                 {
-                    scan_directories(existing_dir, &path, vault_root).await?;
+                    scan_directories(existing_dir, &path, fs).await?;
                 }
             }
         } else if is_media_file(&path) {
@@ -108,6 +209,10 @@ async fn scan_directories(
                     path: relative,
                     is_dir: false,
                     children: Vec::new(),
+                    icon: None,
+                    color: None,
+                    collapsed: false,
+                    read_only: false,
                 });
             }
         }
@@ -123,6 +228,10 @@ fn build_folder_tree(notes: &[NoteListItem], vault_name: String) -> FolderNode {
         path: String::new(),
         is_dir: true,
         children: Vec::new(),
+        icon: None,
+        color: None,
+        collapsed: false,
+        read_only: false,
     };
 
     for note in notes {
@@ -167,6 +276,10 @@ fn insert_path(node: &mut FolderNode, parts: &[&str], full_path: &str) {
             },
             is_dir: !is_file,
             children: Vec::new(),
+            icon: None,
+            color: None,
+            collapsed: false,
+            read_only: false,
         };
 
         if !is_file {
@@ -191,3 +304,29 @@ fn sort_tree(node: &mut FolderNode) {
         sort_tree(child);
     }
 }
+
+/// Apply stored folder metadata to the (already sorted) tree.
+///
+/// Children named in a folder's manual order come first, in that order; the
+/// rest keep the default order after them.
+fn apply_folder_metadata(node: &mut FolderNode, metadata: &HashMap<String, FolderMetadata>) {
+    if let Some(meta) = metadata.get(&node.path).filter(|_| node.is_dir) {
+        node.icon = meta.appearance.icon.clone();
+        node.color = meta.appearance.color.clone();
+        node.collapsed = meta.appearance.collapsed;
+
+        if !meta.child_order.is_empty() {
+            // Stable sort keeps the default order for unlisted children
+            node.children.sort_by_key(|c| {
+                meta.child_order
+                    .iter()
+                    .position(|name| *name == c.name)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+    }
+
+    for child in &mut node.children {
+        apply_folder_metadata(child, metadata);
+    }
+}
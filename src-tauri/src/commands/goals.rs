@@ -0,0 +1,128 @@
+//! Goal commands - goal CRUD, progress, and goal embeds.
+
+use crate::state::AppState;
+use chrono::Local;
+use shared_types::{CreateGoalRequest, GoalDto, GoalEmbedQuery, GoalEmbedResponse, GoalProgress};
+use tauri::State;
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+/// Create a new goal.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_goal(state: State<'_, AppState>, request: CreateGoalRequest) -> Result<i64> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .create_goal(&request)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// List all goals.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_goals(
+    state: State<'_, AppState>,
+    include_archived: Option<bool>,
+) -> Result<Vec<GoalDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_goals(include_archived.unwrap_or(false))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Archive a goal (soft delete).
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn archive_goal(state: State<'_, AppState>, id: i64) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .archive_goal(id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Delete a goal.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_goal(state: State<'_, AppState>, id: i64) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_goal(id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get the current progress of a goal.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_goal_progress(state: State<'_, AppState>, goal_id: i64) -> Result<GoalProgress> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_goal_progress(goal_id, Local::now().date_naive())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .ok_or_else(|| CommandError::Vault(format!("Goal {} not found", goal_id)))
+}
+
+/// Execute a goal embed (```goal``` block) from YAML content.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn execute_goal_embed(
+    state: State<'_, AppState>,
+    yaml_content: String,
+) -> Result<GoalEmbedResponse> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let query: GoalEmbedQuery = match serde_yaml::from_str(&yaml_content) {
+        Ok(q) => q,
+        Err(e) => {
+            return Ok(GoalEmbedResponse {
+                progress: None,
+                error: Some(format!("Failed to parse goal config: {}", e)),
+            });
+        }
+    };
+
+    let repo = vault.repo();
+    let goal = match query.goal.trim().parse::<i64>() {
+        Ok(id) => repo.get_goal(id).await,
+        Err(_) => repo.get_goal_by_name(query.goal.trim()).await,
+    }
+    .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    let Some(goal) = goal else {
+        return Ok(GoalEmbedResponse {
+            progress: None,
+            error: Some(format!("Goal not found: {}", query.goal)),
+        });
+    };
+
+    let progress = repo
+        .get_goal_progress(goal.id, Local::now().date_naive())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    Ok(GoalEmbedResponse {
+        progress,
+        error: None,
+    })
+}
@@ -2,8 +2,9 @@
 
 use crate::state::AppState;
 use shared_types::{
-    CreateHabitRequest, HabitDto, HabitEntryDto, HabitTrackerQuery, HabitTrackerResponse,
-    LogHabitEntryRequest, UpdateHabitEntryRequest, UpdateHabitRequest,
+    CreateHabitRequest, HabitCsvMapping, HabitDto, HabitEntryDto, HabitImportResult,
+    HabitScheduleRules, HabitTrackerQuery, HabitTrackerResponse, LogHabitEntryRequest, UpdateHabitEntryRequest,
+    UpdateHabitRequest,
 };
 use tauri::State;
 use tracing::instrument;
@@ -30,6 +31,7 @@ pub async fn create_habit(state: State<'_, AppState>, request: CreateHabitReques
 
 /// List all habits.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn list_habits(
     state: State<'_, AppState>,
     include_archived: bool,
@@ -46,6 +48,7 @@ pub async fn list_habits(
 
 /// Get a habit by ID.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_habit(state: State<'_, AppState>, id: i64) -> Result<Option<HabitDto>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -99,6 +102,31 @@ pub async fn archive_habit(state: State<'_, AppState>, id: i64) -> Result<()> {
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
+/// Set a habit's schedule rules (active weekdays, planned skip dates, freeze tokens).
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn set_habit_schedule(
+    state: State<'_, AppState>,
+    habit_id: i64,
+    rules: HabitScheduleRules,
+) -> Result<()> {
+    if let Some(day) = rules.active_weekdays.iter().find(|d| !(1..=7).contains(*d)) {
+        return Err(CommandError::Vault(format!(
+            "Invalid weekday: {}. Expected 1 (Monday) to 7 (Sunday)",
+            day
+        )));
+    }
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .set_habit_schedule(habit_id, &rules)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
 // ============================================================================
 // Habit Entry Commands
 // ============================================================================
@@ -122,6 +150,7 @@ pub async fn log_habit_entry(
 
 /// Get habit entries for a habit within a date range.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_habit_entries(
     state: State<'_, AppState>,
     habit_id: i64,
@@ -184,12 +213,31 @@ pub async fn toggle_habit(state: State<'_, AppState>, habit_id: i64, date: Strin
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
+/// Import historical habit entries from a CSV file (e.g. a health app export).
+/// Rows that already have an entry for the same date and time are skipped.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn import_habit_data(
+    state: State<'_, AppState>,
+    habit_id: i64,
+    csv_path: String,
+    mapping: HabitCsvMapping,
+) -> Result<HabitImportResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::import_habit_csv(vault, habit_id, std::path::Path::new(&csv_path), &mapping)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
 // ============================================================================
 // Habit Tracker Embed Commands
 // ============================================================================
 
 /// Execute a habit tracker embed query from YAML content.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn execute_habit_tracker_embed(
     state: State<'_, AppState>,
     yaml_content: String,
@@ -198,7 +246,7 @@ pub async fn execute_habit_tracker_embed(
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
     // Parse YAML content into HabitTrackerQuery
-    let query: HabitTrackerQuery = match serde_yaml::from_str(&yaml_content) {
+    let mut query: HabitTrackerQuery = match serde_yaml::from_str(&yaml_content) {
         Ok(q) => q,
         Err(e) => {
             return Ok(HabitTrackerResponse {
@@ -211,6 +259,10 @@ pub async fn execute_habit_tracker_embed(
         }
     };
 
+    if query.week_start.is_none() {
+        query.week_start = Some(vault.locale_settings().await.week_start);
+    }
+
     vault
         .repo()
         .execute_habit_tracker_query(&query)
@@ -1,29 +1,32 @@
-//! Import commands - vault import operations.
+//! Import commands - vault import, dropped files, and photos.
 
 use crate::state::AppState;
-use shared_types::{ImportResult, ImportVaultRequest};
+use core_domain::{Job, Vault};
+use core_index::{parse_frontmatter, set_frontmatter_property};
+use shared_types::{ImportResult, ImportVaultRequest, ImportedFile, ImportedPhoto, PhotoImportOptions};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Emitter, State};
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
+use super::templates::{ensure_daily_note, read_vault_config};
 use super::{CommandError, Result};
 
-/// Import an Obsidian vault into the current vault.
+/// Import a vault, forwarding progress as import:progress events, and
+/// reindex. Runs as a job (see `start_vault_import`); the result is sent as
+/// an import:complete event.
 ///
 /// Copies all markdown files and assets, preserving folder structure.
 /// Parses YAML frontmatter and converts to properties.
 /// Merges frontmatter tags with inline tags.
-#[tauri::command]
-#[instrument(skip(state, app))]
-pub async fn import_obsidian_vault(
-    state: State<'_, AppState>,
-    app: AppHandle,
-    request: ImportVaultRequest,
+pub(super) async fn run_vault_import(
+    vault: &Vault,
+    app: &AppHandle,
+    request: &ImportVaultRequest,
+    job: Option<&Job>,
 ) -> Result<ImportResult> {
     info!("Importing Obsidian vault from: {}", request.source_path);
 
-    let vault_guard = state.vault.read().await;
-    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
-
     // Create progress channel
     let (tx, mut rx) = tokio::sync::mpsc::channel(100);
     let app_clone = app.clone();
@@ -41,13 +44,14 @@ pub async fn import_obsidian_vault(
         std::path::Path::new(&request.source_path),
         request.target_subfolder.as_deref(),
         Some(tx),
+        job,
     )
     .await
     .map_err(|e| CommandError::Vault(e.to_string()))?;
 
     // Trigger re-index to pick up all changes
     vault
-        .full_index()
+        .full_index_job(job)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
 
@@ -55,6 +59,118 @@ pub async fn import_obsidian_vault(
         "Import complete: {} notes, {} properties",
         result.notes_imported, result.properties_imported
     );
+    let _ = app.emit("import:complete", &result);
 
     Ok(result)
 }
+
+/// Copy external files into the vault (drag-and-drop).
+///
+/// Markdown files go to `target_folder` and are indexed; all other files go
+/// to the attachment folder from the vault's attachment settings. Existing
+/// files are never overwritten.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn import_files(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    target_folder: Option<String>,
+) -> Result<Vec<ImportedFile>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let settings = read_vault_config(&vault.fs().config_path()).await?.attachment_settings;
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    core_domain::import_files(vault, &settings, &paths, target_folder.as_deref())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Import photos into `<folder>/<year>/<month>/` by the date they were taken.
+///
+/// The date comes from EXIF data (or the file's modification time). With
+/// `add_to_daily_notes`, each photo is embedded in the daily note of that
+/// date, and the first GPS position of the day is stored as the note's
+/// `location` property unless it already has one.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn import_photos(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    options: PhotoImportOptions,
+) -> Result<Vec<ImportedPhoto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let mut photos = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let photo = core_domain::photos::import_photo(vault, Path::new(path), &options.folder)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?;
+        photos.push(photo);
+    }
+
+    if options.add_to_daily_notes {
+        let settings = read_vault_config(&vault.fs().config_path())
+            .await
+            .map(|c| c.template_settings)
+            .unwrap_or_default();
+
+        let mut by_date: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (i, photo) in photos.iter().enumerate() {
+            by_date.entry(photo.date.clone()).or_default().push(i);
+        }
+
+        for (date, indices) in by_date {
+            let parsed_date = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|e| CommandError::Vault(format!("Invalid photo date: {}", e)))?;
+            let daily_note = ensure_daily_note(vault, &settings, parsed_date).await?;
+
+            let original = vault
+                .read_note(&daily_note.path)
+                .await
+                .map_err(|e| CommandError::Vault(e.to_string()))?;
+            // Appending to an encrypted body would leave it unreadable
+            if core_domain::encryption::has_encrypted_body(&original) {
+                warn!("Not adding photos to encrypted daily note {}", daily_note.path);
+                continue;
+            }
+            let mut content = original.clone();
+
+            for &i in &indices {
+                let embed = format!("![[{}]]", photos[i].path);
+                if !content.contains(&embed) {
+                    if !content.is_empty() && !content.ends_with('\n') {
+                        content.push('\n');
+                    }
+                    content.push_str(&embed);
+                    content.push('\n');
+                }
+            }
+
+            let location = indices.iter().find_map(|&i| {
+                let photo = &photos[i];
+                Some(format!("{:.6}, {:.6}", photo.latitude?, photo.longitude?))
+            });
+            if let Some(location) = location {
+                if !parse_frontmatter(&content).0.properties.contains_key("location") {
+                    content = set_frontmatter_property(&content, "location", Some(&location), None);
+                }
+            }
+
+            if content != original {
+                vault
+                    .write_note(&daily_note.path, &content)
+                    .await
+                    .map_err(|e| CommandError::Vault(e.to_string()))?;
+            }
+
+            for &i in &indices {
+                photos[i].daily_note_path = Some(daily_note.path.clone());
+            }
+        }
+    }
+
+    info!("Imported {} photos", photos.len());
+    Ok(photos)
+}
@@ -0,0 +1,413 @@
+//! Job commands - long-running operations that run in the background.
+//!
+//! Each `start_*` command queues a job in the vault database and returns it
+//! right away. Queued jobs run one at a time, oldest first; jobs that were
+//! running when the app quit run again when the vault is next opened.
+//!
+//! Progress is emitted as job:progress events and every status change
+//! (queued, running, finished) as a job:status event with the whole job.
+//! `cancel_job` stops a job before its next item; whatever it finished is
+//! kept and reported.
+//!
+//! Jobs hold the vault for reading while they run (the embedding backfill
+//! only while it embeds each note). Commands that need it for writing, like
+//! closing or switching the vault, cancel its jobs first (see
+//! `AppState::cancel_vault_jobs`), so they wait for one item rather than for
+//! the whole job.
+
+use crate::state::AppState;
+use core_domain::backup::backup_vault;
+use core_domain::ocr::recognize_images;
+use core_domain::{Job, JobRegistry, Vault};
+use core_embedding::EmbeddingClient;
+use shared_types::{EmbeddingSettings, ImportVaultRequest, JobDto, JobRequest, JobStatus, RenderOptions};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::RwLock;
+use std::path::Path;
+use tracing::{info, instrument, warn};
+
+use super::import::run_vault_import;
+use super::rendering::{parse_date_range, write_journal};
+use super::search::embed_note_by_id;
+use super::{CommandError, Result};
+
+/// Reindex every note in the current vault.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn start_full_index(state: State<'_, AppState>, app: AppHandle) -> Result<JobDto> {
+    enqueue_job(&state, app, JobRequest::FullIndex).await
+}
+
+/// Import an Obsidian vault into the current vault (see `run_vault_import`).
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn start_vault_import(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    request: ImportVaultRequest,
+) -> Result<JobDto> {
+    enqueue_job(&state, app, JobRequest::VaultImport { request }).await
+}
+
+/// Export the daily notes of a date range (see `export_journal`).
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn start_journal_export(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+    output_path: String,
+    options: Option<RenderOptions>,
+) -> Result<JobDto> {
+    // Reject bad dates now rather than when the job runs
+    parse_date_range(&start_date, &end_date)?;
+
+    let request = JobRequest::JournalExport {
+        start_date,
+        end_date,
+        output_path,
+        options,
+    };
+    enqueue_job(&state, app, request).await
+}
+
+/// Embed every note that has no current embedding.
+#[tauri::command]
+#[instrument(skip(state, app, settings))]
+pub async fn start_embedding_backfill(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    settings: EmbeddingSettings,
+) -> Result<JobDto> {
+    enqueue_job(&state, app, JobRequest::EmbeddingBackfill { settings }).await
+}
+
+/// Back up the vault to a zip at `output_path` (see `backup_vault`).
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn start_backup(state: State<'_, AppState>, app: AppHandle, output_path: String) -> Result<JobDto> {
+    enqueue_job(&state, app, JobRequest::Backup { output_path }).await
+}
+
+/// Recognize the text in the vault's new and changed images (see
+/// `recognize_images`).
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn start_ocr(state: State<'_, AppState>, app: AppHandle) -> Result<JobDto> {
+    enqueue_job(&state, app, JobRequest::Ocr).await
+}
+
+/// List queued, running and finished jobs, newest first.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<JobDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_jobs()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Cancel a queued or running job. Returns false if the job has already
+/// finished or doesn't exist.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn cancel_job(state: State<'_, AppState>, app: AppHandle, job_id: i64) -> Result<bool> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    if state.jobs.cancel(vault.root_path(), job_id) {
+        return Ok(true);
+    }
+
+    let cancelled = vault
+        .repo()
+        .cancel_queued_job(job_id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    match cancelled {
+        Some(job) => {
+            let _ = app.emit("job:status", &job);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Queue a failed or cancelled job to run again from the start.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn retry_job(state: State<'_, AppState>, app: AppHandle, job_id: i64) -> Result<JobDto> {
+    let job = {
+        let vault_guard = state.vault.read().await;
+        let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+        vault
+            .repo()
+            .retry_job(job_id)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?
+            .ok_or_else(|| CommandError::Vault(format!("Job {} has not failed or been cancelled", job_id)))?
+    };
+
+    let _ = app.emit("job:status", &job);
+    spawn_job_worker(&state, app);
+    Ok(job)
+}
+
+/// Delete completed, cancelled and failed jobs. Returns how many were deleted.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn clear_finished_jobs(state: State<'_, AppState>) -> Result<u64> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .clear_finished_jobs()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Queue a job and make sure the worker is running.
+pub(crate) async fn enqueue_job(state: &AppState, app: AppHandle, request: JobRequest) -> Result<JobDto> {
+    let job = {
+        let vault_guard = state.vault.read().await;
+        let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+        vault
+            .repo()
+            .create_job(&request)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?
+    };
+
+    let _ = app.emit("job:status", &job);
+    spawn_job_worker(state, app);
+    Ok(job)
+}
+
+/// Run queued jobs in the current vault until there are none left.
+///
+/// Only one worker runs at a time; if one is already running this does
+/// nothing, as that worker picks up the new jobs.
+pub(crate) fn spawn_job_worker(state: &AppState, app: AppHandle) {
+    let vault = state.vault.clone();
+    let jobs = state.jobs.clone();
+    let worker = state.job_worker.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok(running) = worker.clone().try_lock_owned() else {
+                return;
+            };
+            while let Some(job) = next_job(&vault).await {
+                run_job(&vault, &app, &jobs, job).await;
+            }
+            drop(running);
+
+            // A job queued while the worker was stopping would otherwise
+            // wait for the next one
+            if !has_queued_jobs(&vault).await {
+                return;
+            }
+        }
+    });
+}
+
+async fn next_job(vault: &RwLock<Option<Vault>>) -> Option<JobDto> {
+    let vault_guard = vault.read().await;
+    match vault_guard.as_ref()?.repo().start_next_job().await {
+        Ok(job) => job,
+        Err(e) => {
+            warn!("Failed to load the next job: {}", e);
+            None
+        }
+    }
+}
+
+async fn has_queued_jobs(vault: &RwLock<Option<Vault>>) -> bool {
+    let vault_guard = vault.read().await;
+    match vault_guard.as_ref() {
+        Some(v) => v.repo().has_queued_jobs().await.unwrap_or(false),
+        None => false,
+    }
+}
+
+/// Run a job that was just marked as running and record how it ended.
+async fn run_job(vault: &RwLock<Option<Vault>>, app: &AppHandle, jobs: &JobRegistry, dto: JobDto) {
+    let Some(root) = vault.read().await.as_ref().map(|v| v.root_path().to_path_buf()) else {
+        return;
+    };
+    let job = jobs.start(&root, dto.id, dto.kind);
+    let _ = app.emit("job:status", &dto);
+
+    let result = run_request(vault, app, &job, dto.request).await;
+
+    let (processed, total) = job.counts();
+    let (status, message) = match result {
+        Ok(summary) if job.stopped_early() => (JobStatus::Cancelled, summary),
+        Ok(summary) => (JobStatus::Completed, summary),
+        Err(e) => {
+            warn!("Job {} failed: {}", job.id(), e);
+            (JobStatus::Failed, e.to_string())
+        }
+    };
+    info!("Job {} finished: {:?} ({} of {})", job.id(), status, processed, total);
+    jobs.finish(&job);
+
+    // If the vault was closed or replaced meanwhile, the job stays running
+    // in its database and runs again when the vault is next opened
+    let vault_guard = vault.read().await;
+    let Ok(vault) = job_vault(&vault_guard, &job) else {
+        return;
+    };
+    match vault
+        .repo()
+        .finish_job(job.id(), status, processed, total, Some(&message))
+        .await
+    {
+        Ok(Some(finished)) => {
+            let _ = app.emit("job:status", &finished);
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to record the end of job {}: {}", job.id(), e),
+    }
+}
+
+/// The open vault, if it is still the one the job runs in.
+fn job_vault<'a>(vault: &'a Option<Vault>, job: &Job) -> Result<&'a Vault> {
+    vault
+        .as_ref()
+        .filter(|vault| vault.root_path() == job.vault())
+        .ok_or(CommandError::NoVaultOpen)
+}
+
+/// Do the work of a job. Returns a summary of what it did.
+async fn run_request(
+    vault: &RwLock<Option<Vault>>,
+    app: &AppHandle,
+    job: &Job,
+    request: JobRequest,
+) -> Result<String> {
+    if let JobRequest::EmbeddingBackfill { settings } = request {
+        return embedding_backfill(vault, job, settings).await;
+    }
+
+    let vault_guard = vault.read().await;
+    let vault = job_vault(&vault_guard, job)?;
+
+    match request {
+        JobRequest::FullIndex => {
+            let payload = vault
+                .full_index_job(Some(job))
+                .await
+                .map_err(|e| CommandError::Vault(e.to_string()))?;
+            Ok(format!("Indexed {} notes", payload.notes_indexed))
+        }
+        JobRequest::VaultImport { request } => {
+            let result = run_vault_import(vault, app, &request, Some(job)).await?;
+            Ok(format!(
+                "Imported {} notes and {} files",
+                result.notes_imported,
+                result.files_copied - result.notes_imported
+            ))
+        }
+        JobRequest::JournalExport {
+            start_date,
+            end_date,
+            output_path,
+            options,
+        } => {
+            let (start, end) = parse_date_range(&start_date, &end_date)?;
+            let options = options.unwrap_or_default();
+            let result = write_journal(vault, start, end, output_path, &options, Some(job)).await?;
+            Ok(format!("Exported {} daily notes to {}", result.dates.len(), result.output_path))
+        }
+        JobRequest::Backup { output_path } => {
+            let result = backup_vault(vault, Path::new(&output_path), Some(job))
+                .await
+                .map_err(|e| CommandError::Vault(e.to_string()))?;
+            if result.cancelled {
+                return Ok("Backup cancelled".to_string());
+            }
+            Ok(format!("Backed up {} files to {}", result.files, result.output_path))
+        }
+        JobRequest::Ocr => {
+            let result = recognize_images(vault, Some(job))
+                .await
+                .map_err(|e| CommandError::Vault(e.to_string()))?;
+            let mut summary = format!("Recognized text in {} images", result.images_recognized);
+            if !result.warnings.is_empty() {
+                summary.push_str(&format!(", {} failed", result.warnings.len()));
+            }
+            Ok(summary)
+        }
+        JobRequest::EmbeddingBackfill { .. } => unreachable!("handled above"),
+    }
+}
+
+/// Failed notes in a row after which the embedding backfill stops, as the
+/// embedding server is most likely unreachable.
+const MAX_FAILURES_IN_ROW: usize = 5;
+
+/// Embed every note without a current embedding. Notes that fail to embed
+/// are skipped. The vault is only held while each note is embedded.
+async fn embedding_backfill(
+    vault: &RwLock<Option<Vault>>,
+    job: &Job,
+    settings: EmbeddingSettings,
+) -> Result<String> {
+    let notes = {
+        let vault_guard = vault.read().await;
+        job_vault(&vault_guard, job)?
+            .repo()
+            .get_notes_without_embeddings(i32::MAX)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?
+    };
+
+    let client = EmbeddingClient::new(settings);
+    let total = notes.len();
+    let (mut embedded, mut failed, mut failed_in_row) = (0, 0, 0);
+    for (i, (note_id, path)) in notes.into_iter().enumerate() {
+        if job.is_cancelled() {
+            break;
+        }
+        let vault_guard = vault.read().await;
+        let vault = job_vault(&vault_guard, job)?;
+        job.progress(vault, i, total, Some(path.clone()));
+
+        match embed_note_by_id(vault, &client, note_id).await {
+            Ok(done) => {
+                embedded += usize::from(done);
+                failed_in_row = 0;
+            }
+            Err(e) => {
+                warn!("Failed to embed {}: {}", path, e);
+                failed += 1;
+                failed_in_row += 1;
+                if failed_in_row == MAX_FAILURES_IN_ROW {
+                    return Err(CommandError::Vault(format!(
+                        "Stopped after {} notes in a row failed to embed: {}",
+                        failed_in_row, e
+                    )));
+                }
+            }
+        }
+    }
+    if !job.is_cancelled() {
+        let vault_guard = vault.read().await;
+        job.progress(job_vault(&vault_guard, job)?, total, total, None);
+    }
+
+    if failed > 0 {
+        Ok(format!("Embedded {} notes, {} failed", embedded, failed))
+    } else {
+        Ok(format!("Embedded {} notes", embedded))
+    }
+}
@@ -0,0 +1,249 @@
+//! Maintenance commands - vault health checks, attachment deduplication,
+//! note linting, title/filename sync, performance metrics, diagnostics
+//! bundles and vault growth.
+
+use crate::state::AppState;
+use shared_types::{
+    AttachmentDedupeResult, DiagnosticsExport, DuplicateCluster, FileProblem, LintFixResult, LintSettings, NoteDto, NoteLintResult, PerformanceMetrics,
+    TitleMismatch, TitlePolicy, TitleSettings, VaultGrowth, VaultMetric,
+};
+use std::path::Path;
+use tauri::State;
+use tracing::{info, instrument};
+
+use super::templates::{read_vault_config, write_vault_config};
+use super::{CommandError, Result};
+
+/// Default similarity for near duplicates.
+const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// Find exact and near-duplicate notes.
+///
+/// `threshold` is the minimum similarity (0.5 - 1.0, default 0.8) of
+/// shared 3-word phrases; 1.0 finds exact duplicates only. Each cluster
+/// suggests a merge target.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn find_duplicate_notes(
+    state: State<'_, AppState>,
+    threshold: Option<f64>,
+) -> Result<Vec<DuplicateCluster>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::duplicates::find_duplicate_notes(vault, threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Merge identical attachments: one copy of each is kept, embeds and links
+/// to the others are rewritten to it, and the others are deleted.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn dedupe_attachments(state: State<'_, AppState>) -> Result<AttachmentDedupeResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::assets::dedupe_attachments(vault)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Lint a note with the vault's lint settings.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn lint_note(state: State<'_, AppState>, note_id: i64) -> Result<NoteLintResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    core_domain::lint::lint_note(vault, note_id, &config.lint_settings)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Lint every note; only notes with diagnostics are returned.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn lint_vault(state: State<'_, AppState>) -> Result<Vec<NoteLintResult>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    core_domain::lint::lint_vault(vault, &config.lint_settings)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Fix the auto-fixable diagnostics (trailing whitespace, empty headings,
+/// missing required properties) of the given notes, or of every note.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn apply_lint_fixes(
+    state: State<'_, AppState>,
+    note_ids: Option<Vec<i64>>,
+) -> Result<LintFixResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    core_domain::lint::apply_lint_fixes(vault, note_ids.as_deref(), &config.lint_settings)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get lint settings from vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_lint_settings(state: State<'_, AppState>) -> Result<LintSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.lint_settings)
+}
+
+/// Save lint settings to vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_lint_settings(state: State<'_, AppState>, settings: LintSettings) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.lint_settings = settings;
+    write_vault_config(&config_path, &config).await?;
+
+    info!("Saved lint settings");
+    Ok(())
+}
+
+/// Find notes whose title (frontmatter `title` or first H1) and filename differ.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn detect_title_mismatches(state: State<'_, AppState>) -> Result<Vec<TitleMismatch>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    core_domain::titles::detect_title_mismatches(vault, &config.title_settings)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Sync a note's title and filename, renaming the file (with its references)
+/// or editing the title. Without a strategy, the configured policy is used.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn fix_title_mismatch(
+    state: State<'_, AppState>,
+    note_id: i64,
+    strategy: Option<TitlePolicy>,
+) -> Result<NoteDto> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let strategy = match strategy {
+        Some(strategy) => strategy,
+        None => read_vault_config(&vault.fs().config_path()).await?.title_settings.policy,
+    };
+    core_domain::titles::fix_title_mismatch(vault, note_id, strategy)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get title settings from vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_title_settings(state: State<'_, AppState>) -> Result<TitleSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.title_settings)
+}
+
+/// Save title settings to vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_title_settings(state: State<'_, AppState>, settings: TitleSettings) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.title_settings = settings;
+    write_vault_config(&config_path, &config).await?;
+
+    info!("Saved title settings");
+    Ok(())
+}
+
+/// Get the p50/p95 latency of each command and the recent slow SQL
+/// statements, since the app started (or the metrics were reset).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_performance_metrics(state: State<'_, AppState>) -> Result<PerformanceMetrics> {
+    Ok(state.perf.metrics())
+}
+
+/// Clear the recorded latencies and slow queries, e.g. before reproducing
+/// a slowdown.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn reset_performance_metrics(state: State<'_, AppState>) -> Result<()> {
+    state.perf.reset();
+    Ok(())
+}
+
+/// Files that could not be read cleanly when last indexed: notes with
+/// invalid UTF-8 (indexed with the bytes replaced) and binary files with a
+/// `.md` extension (skipped).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_file_problems(state: State<'_, AppState>) -> Result<Vec<FileProblem>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault.file_problems().await.map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get how a vault metric (notes, words, tasks, links) changed over a date
+/// range, from the daily snapshots.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_vault_growth(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    metric: VaultMetric,
+) -> Result<VaultGrowth> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_vault_growth(&start_date, &end_date, metric)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Write a zip for a bug report to `output`: anonymized vault statistics,
+/// the database schema, the vault config with secrets redacted, the
+/// performance metrics and the recent log lines. Works without an open vault.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_diagnostics(state: State<'_, AppState>, output: String) -> Result<DiagnosticsExport> {
+    let vault_guard = state.vault.read().await;
+
+    core_domain::diagnostics::export_diagnostics(
+        vault_guard.as_ref(),
+        env!("CARGO_PKG_VERSION"),
+        &state.perf.metrics(),
+        &state.logs,
+        Path::new(&output),
+    )
+    .await
+    .map_err(|e| CommandError::Vault(e.to_string()))
+}
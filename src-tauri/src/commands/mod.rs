@@ -1,38 +1,68 @@
 //! Tauri commands - the IPC boundary between frontend and backend.
 //!
 //! This module is organized by domain:
-//! - vault: Opening, closing, and vault info
-//! - notes: Note CRUD operations and folder management
-//! - todos: Task/todo operations
-//! - tags: Tag listing
-//! - backlinks: Backlink queries
+//! - vault: Creating (blank or demo), opening, closing, windows sharing the vault, vault info, index size limits, index verification and folder reindexing
+//! - notes: Note CRUD operations, drafts for crash recovery, editing sessions across windows and folder management
+//! - todos: Task/todo operations and the context registry
+//! - tags: Tag listing, aliases and tag metadata
+//! - backlinks: Backlink queries, link graph export, note resolution and link settings
+//! - books: OpenLibrary book metadata and reading shelves
 //! - search: Full-text search
 //! - folder_tree: Folder tree building
 //! - properties: Property CRUD and management
-//! - schedule: Schedule blocks and notes by date
+//! - schedule: Schedule blocks, templates, notes by date, and the plain-text agenda
+//! - calendar_subscriptions: External ICS calendars shown as read-only blocks
 //! - embeds: Embed resolution and image handling
-//! - queries: Query builder operations
-//! - import: Vault import operations
+//! - clipper: Web pages saved as notes
+//! - maintenance: Vault health checks (duplicate notes and attachments, linting, title/filename sync), performance metrics, diagnostics bundles, unreadable files and vault growth
+//! - rendering: Notes rendered to HTML and journal export for print
+//! - queries: Query builder operations and Kanban card order
+//! - import: Vault import and file drag-and-drop
+//! - jobs: Background job queue (index, import, export, embeddings)
 //! - habits: Habit tracker operations
-//! - templates: Daily note creation and template settings
+//! - goals: Goal tracking and goal embeds
+//! - mood: Daily mood logging and trends
+//! - reviews: Review queue for resurfacing old notes
+//! - discovery: Random notes and "on this day"
+//! - templates: Periodic notes, template and locale settings, and note types
+//! - scheduled_notes: Rules creating notes from templates on a schedule
+//! - spellcheck: Spell checking and the custom dictionary
+//! - tables: Formatting and editing markdown tables, CSV import and export
 //! - summarizers: External script execution for content summarization
+//! - workspace: Open tabs and sidebar layout per vault
+//! - app_settings: Per-device settings (theme, editor font, window layout)
 
+mod app_settings;
 mod backlinks;
+mod books;
+mod calendar_subscriptions;
+mod clipper;
+mod discovery;
 mod habits;
 mod embeds;
 mod folder_tree;
+mod goals;
 mod import;
+mod jobs;
+mod maintenance;
+mod mood;
 mod notes;
 mod plugins;
 mod properties;
 mod queries;
+mod rendering;
+mod reviews;
 mod schedule;
 mod search;
+mod spellcheck;
 mod summarizers;
+mod tables;
 mod tags;
 mod templates;
+mod scheduled_notes;
 mod todos;
 mod vault;
+mod workspace;
 
 use thiserror::Error;
 
@@ -62,19 +92,34 @@ impl serde::Serialize for CommandError {
 pub type Result<T> = std::result::Result<T, CommandError>;
 
 // Re-export all commands for use in main.rs
+pub use app_settings::*;
 pub use backlinks::*;
+pub use books::*;
+pub use calendar_subscriptions::*;
+pub use clipper::*;
+pub use discovery::*;
 pub use habits::*;
 pub use embeds::*;
 pub use folder_tree::*;
+pub use goals::*;
 pub use import::*;
+pub use jobs::*;
+pub use maintenance::*;
+pub use mood::*;
 pub use notes::*;
 pub use plugins::*;
 pub use properties::*;
 pub use queries::*;
+pub use rendering::*;
+pub use reviews::*;
 pub use schedule::*;
 pub use search::*;
+pub use spellcheck::*;
 pub use summarizers::*;
+pub use tables::*;
 pub use tags::*;
 pub use templates::*;
+pub use scheduled_notes::*;
 pub use todos::*;
 pub use vault::*;
+pub use workspace::*;
@@ -0,0 +1,82 @@
+//! Mood tracking commands - daily mood logging and trends.
+
+use crate::state::AppState;
+use shared_types::{MoodEntryDto, MoodTrend};
+use tauri::State;
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+/// Log the mood for a date (score 1-5). Logging again for the same date replaces it.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn log_mood(
+    state: State<'_, AppState>,
+    date: String,
+    score: i32,
+    note: Option<String>,
+) -> Result<i64> {
+    if !(1..=5).contains(&score) {
+        return Err(CommandError::Vault(format!(
+            "Mood score must be between 1 and 5, got {}",
+            score
+        )));
+    }
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .log_mood(&date, score, note.as_deref())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get the mood entry for a date.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_mood(state: State<'_, AppState>, date: String) -> Result<Option<MoodEntryDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_mood(&date)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Delete the mood entry for a date.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_mood(state: State<'_, AppState>, date: String) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_mood(&date)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get mood entries with overall and weekly averages for a date range, with
+/// weeks starting on the vault's week start.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_mood_trend(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+) -> Result<MoodTrend> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let week_start = vault.locale_settings().await.week_start;
+    vault
+        .repo()
+        .get_mood_trend(&start_date, &end_date, week_start)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
@@ -1,14 +1,19 @@
 //! Note commands - CRUD operations and folder management.
 
 use crate::state::AppState;
-use shared_types::{NoteContent, NoteDto, NoteListItem};
-use tauri::State;
+use core_domain::vault::Vault;
+use shared_types::{
+    DeleteImpact, EditSession, EditSessionChangedPayload, NoteContent, NoteDto, NoteListItem, NoteSavedPayload,
+    NoteSummaryDto, ReadabilityReport, UnsavedDraft,
+};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
 use tracing::instrument;
 
 use super::{CommandError, Result};
 
 /// List all notes in the vault.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn list_notes(state: State<'_, AppState>) -> Result<Vec<NoteListItem>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -21,6 +26,7 @@ pub async fn list_notes(state: State<'_, AppState>) -> Result<Vec<NoteListItem>>
 
 /// Get a note by ID.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_note(state: State<'_, AppState>, note_id: i64) -> Result<NoteDto> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -32,58 +38,395 @@ pub async fn get_note(state: State<'_, AppState>, note_id: i64) -> Result<NoteDt
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
-/// Get a note's content.
+/// Get link, task, and word counts and the latest schedule block for a note header.
 #[tauri::command]
-pub async fn get_note_content(state: State<'_, AppState>, path: String) -> Result<NoteContent> {
+#[instrument(skip_all)]
+pub async fn get_note_summary(state: State<'_, AppState>, note_id: i64) -> Result<NoteSummaryDto> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
+    vault
+        .repo()
+        .get_note_summary(note_id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .ok_or_else(|| CommandError::NoteNotFound(note_id.to_string()))
+}
+
+/// Get text statistics and readability scores for a note's body.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn analyze_readability(state: State<'_, AppState>, note_id: i64) -> Result<ReadabilityReport> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let note = vault
+        .repo()
+        .get_note(note_id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
     let content = vault
-        .read_note(&path)
+        .read_note(&note.path)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
 
+    Ok(core_index::analyze_readability(&content))
+}
+
+/// Renumber a note's footnotes 1, 2, 3... in the order they are first
+/// referenced, and save it. Returns the new content.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn renumber_footnotes(state: State<'_, AppState>, note_id: i64) -> Result<NoteContent> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let note = vault
+        .repo()
+        .get_note(note_id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    let content = vault
+        .read_note(&note.path)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    let renumbered = core_index::renumber_footnotes(&content);
+    if renumbered != content {
+        vault
+            .write_note(&note.path, &renumbered)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?;
+    }
+
+    vault
+        .note_content(note_id, note.path, renumbered)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get a note's content: all of it, or with `length`, up to that many bytes
+/// from byte `offset` (0 by default), so very large notes can be loaded in
+/// parts. The next part starts at the returned `offset` plus the length of
+/// its content, until `total_bytes` is reached.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_note_content(
+    state: State<'_, AppState>,
+    path: String,
+    offset: Option<u64>,
+    length: Option<u64>,
+) -> Result<NoteContent> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
     let note = vault
         .repo()
         .get_note_by_path(&path)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
 
-    Ok(NoteContent {
-        id: note.id,
-        path: note.path,
-        content,
-    })
+    let (text, offset, total_bytes) = match length {
+        Some(length) => {
+            let range = vault
+                .read_note_range(&path, offset.unwrap_or(0), length)
+                .await
+                .map_err(|e| CommandError::Vault(e.to_string()))?;
+            (range.content, range.offset, range.total_bytes)
+        }
+        None => {
+            let text = vault
+                .read_note(&path)
+                .await
+                .map_err(|e| CommandError::Vault(e.to_string()))?;
+            let total_bytes = text.len() as u64;
+            (text, 0, total_bytes)
+        }
+    };
+
+    // Only opening a note counts, not loading its later parts
+    if offset == 0 {
+        vault.record_note_opened(note.id).await;
+    }
+
+    let mut content = vault
+        .note_content(note.id, note.path, text)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    content.offset = offset;
+    content.total_bytes = total_bytes;
+    Ok(content)
 }
 
-/// Save a note's content.
+/// Unlock encrypted notes for this session with the vault passphrase.
+/// Returns the decrypted content of the given note.
 #[tauri::command]
-#[instrument(skip(state, content))]
-pub async fn save_note(state: State<'_, AppState>, path: String, content: String) -> Result<i64> {
+#[instrument(skip(state, passphrase))]
+pub async fn unlock_note(
+    state: State<'_, AppState>,
+    path: String,
+    passphrase: String,
+) -> Result<NoteContent> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
+    let content = vault
+        .unlock_note(&path, &passphrase)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    let note = vault
+        .repo()
+        .get_note_by_path(&path)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
     vault
+        .note_content(note.id, note.path, content)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Forget the passphrase for encrypted notes.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn lock_notes(state: State<'_, AppState>) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault.lock_notes().await;
+    Ok(())
+}
+
+/// Save a note's content. Its draft, if any, is no longer needed.
+///
+/// Every window is sent the new content (`note:saved`), so other windows
+/// showing the note update right away.
+#[tauri::command]
+#[instrument(skip(state, app, window, content))]
+pub async fn save_note(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
+    path: String,
+    content: String,
+) -> Result<i64> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let note_id = vault
         .write_note(&path, &content)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    core_domain::drafts::discard_draft(vault, &path)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    // Indexing can change the file (keeping an old title as an alias)
+    let content = vault.read_note(&path).await.unwrap_or(content);
+    let _ = app.emit(
+        "note:saved",
+        NoteSavedPayload {
+            note_id,
+            path,
+            content,
+            window: window.label().to_string(),
+            previous_path: None,
+        },
+    );
+    Ok(note_id)
+}
+
+/// Store the editor's unsaved content of a note for crash recovery.
+/// Returns whether a draft is kept (not if it matches the file, or the
+/// note is encrypted).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_draft(state: State<'_, AppState>, path: String, content: String) -> Result<bool> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::drafts::save_draft(vault, &path, &content)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
-/// Rename a note (file and database path).
+/// Get the drafts with content their files don't have, e.g. after a crash,
+/// so they can be restored. Drafts the files have caught up with are removed.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_unsaved_drafts(state: State<'_, AppState>) -> Result<Vec<UnsavedDraft>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::drafts::get_unsaved_drafts(vault)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Delete the draft of a note, after it was restored or dismissed.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn discard_draft(state: State<'_, AppState>, path: String) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::drafts::discard_draft(vault, &path)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Begin editing a note in `editor` of this window (an ID unique to the
+/// editor within the window). If another window edits it, its session is
+/// returned and this window should show the note read-only,
+/// unless `takeover` moves the session here. Sessions are advisory: saves
+/// are never refused.
+///
+/// Every window is sent the session when it changes (`edit:changed`), so
+/// a window whose session was taken over can stop editing.
+#[tauri::command]
+#[instrument(skip(state, app, window))]
+pub async fn begin_edit(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
+    note_id: i64,
+    editor: String,
+    takeover: Option<bool>,
+) -> Result<EditSession> {
+    let (session, begun) = state
+        .edit_sessions
+        .begin(note_id, window.label(), &editor, takeover.unwrap_or(false));
+    if begun {
+        emit_edit_changed(&app, note_id, Some(session.clone()));
+    }
+    Ok(session)
+}
+
+/// End an editor's editing session on a note. The window's session ends
+/// once none of its editors hold the note.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn end_edit(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
+    note_id: i64,
+    editor: String,
+) -> Result<()> {
+    if state.edit_sessions.end(note_id, window.label(), &editor) {
+        emit_edit_changed(&app, note_id, None);
+    }
+    Ok(())
+}
+
+/// End the editing sessions of a window that closed its vault or was closed.
+pub fn release_edit_sessions(window: &Window) {
+    let state = window.state::<AppState>();
+    for note_id in state.edit_sessions.release_window(window.label()) {
+        emit_edit_changed(window.app_handle(), note_id, None);
+    }
+}
+
+/// End every editing session, when the vault is closed or replaced.
+pub fn clear_edit_sessions(app: &AppHandle, state: &AppState) {
+    for note_id in state.edit_sessions.clear() {
+        emit_edit_changed(app, note_id, None);
+    }
+}
+
+fn emit_edit_changed(app: &AppHandle, note_id: i64, session: Option<EditSession>) {
+    let _ = app.emit("edit:changed", EditSessionChangedPayload { note_id, session });
+}
+
+/// Send every window the content of a note changed by something other than
+/// `save_note` (`note:saved`). `previous_path` is where a renamed or moved
+/// note was.
+pub(super) async fn emit_note_saved(
+    app: &AppHandle,
+    window: &Window,
+    vault: &Vault,
+    path: &str,
+    previous_path: Option<&str>,
+) {
+    let (Ok(note), Ok(content)) = (vault.repo().get_note_by_path(path).await, vault.read_note(path).await) else {
+        return;
+    };
+    let _ = app.emit(
+        "note:saved",
+        NoteSavedPayload {
+            note_id: note.id,
+            path: path.to_string(),
+            content,
+            window: window.label().to_string(),
+            previous_path: previous_path.map(str::to_string),
+        },
+    );
+}
+
+/// Create a note from a title, with a file name generated from it.
+///
+/// The file name is made safe for every platform (transliterated to ASCII,
+/// reserved characters replaced) and numbered if it is taken. `folder` is
+/// relative to the vault root (default: the root). With `template` (a vault
+/// path), the rendered template is the note's content and can use `{{title}}`.
 #[tauri::command]
 #[instrument(skip(state))]
+pub async fn create_note(
+    state: State<'_, AppState>,
+    title: String,
+    folder: Option<String>,
+    template: Option<String>,
+) -> Result<NoteDto> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::notes::create_note(vault, &title, folder.as_deref().unwrap_or(""), template.as_deref())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Rename a note (file and database path). Every window is sent it at its
+/// new path (`note:saved`).
+#[tauri::command]
+#[instrument(skip(state, app, window))]
 pub async fn rename_note(
     state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
     old_path: String,
     new_path: String,
 ) -> Result<i64> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
-    vault
+    let note_id = vault
         .rename_note(&old_path, &new_path)
         .await
-        .map_err(|e| CommandError::Vault(e.to_string()))
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    emit_note_saved(&app, &window, vault, &new_path, Some(&old_path)).await;
+    Ok(note_id)
+}
+
+/// Move a note to another folder, fixing links that would break (see `move_note`).
+/// Every window is sent it at its new path (`note:saved`).
+#[tauri::command]
+#[instrument(skip(state, app, window))]
+pub async fn move_note(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
+    path: String,
+    target_folder: String,
+) -> Result<NoteDto> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let note = core_domain::moves::move_note(vault, &path, &target_folder)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    emit_note_saved(&app, &window, vault, &note.path, Some(&path)).await;
+    Ok(note)
 }
 
 /// Delete a note (file and database record).
@@ -129,15 +472,32 @@ pub async fn rename_folder(
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
-/// Delete a folder and all its contents.
+/// Report what deleting a folder or note would affect (see `preview_delete`).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn preview_delete(state: State<'_, AppState>, path: String) -> Result<DeleteImpact> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .preview_delete(&path)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Delete a folder and all its contents, or move it to the vault's trash.
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn delete_folder(state: State<'_, AppState>, path: String) -> Result<Vec<i64>> {
+pub async fn delete_folder(
+    state: State<'_, AppState>,
+    path: String,
+    move_to_trash: Option<bool>,
+) -> Result<Vec<i64>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
     vault
-        .delete_folder(&path)
+        .delete_folder(&path, move_to_trash.unwrap_or(false))
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
@@ -6,7 +6,7 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use tauri::State;
-use tracing::{debug, info};
+use tracing::{debug, info, instrument};
 
 use super::{CommandError, Result};
 
@@ -21,6 +21,7 @@ fn get_plugins_dir(vault_root: &std::path::Path) -> PathBuf {
 
 /// Read a plugin's config file.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn read_plugin_config(
     state: State<'_, AppState>,
     plugin_id: String,
@@ -49,6 +50,7 @@ pub async fn read_plugin_config(
 
 /// Write a plugin's config file.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn write_plugin_config(
     state: State<'_, AppState>,
     plugin_id: String,
@@ -79,6 +81,7 @@ pub async fn write_plugin_config(
 
 /// List all plugin configs in the vault.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn list_plugin_configs(state: State<'_, AppState>) -> Result<Vec<String>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -132,6 +135,7 @@ pub struct HttpResponse {
 
 /// Make an HTTP request (for plugins to call external APIs).
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn plugin_http_request(options: HttpRequestOptions) -> Result<HttpResponse> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_millis(options.timeout))
@@ -8,9 +8,10 @@ use crate::state::AppState;
 use core_index::{parse_frontmatter, PropertyValue};
 use shared_types::{
     ConvertFrontmatterResponse, DeletePropertyKeyRequest, FolderPropertyDto,
-    MergePropertyKeysRequest, NoteWithPropertyValue, PropertyDto, PropertyOperationResult,
-    PropertyValueInfo, PropertyWithInheritance, RenamePropertyKeyRequest,
-    RenamePropertyValueRequest, SetFolderPropertyRequest, SetPropertyRequest,
+    MergePropertyKeysRequest, NoteWithPropertyValue, PropertyDto, PropertyFilter,
+    PropertyOperationResult, PropertyTable, PropertyUpdate, PropertyValueInfo,
+    PropertyWithInheritance, RenamePropertyKeyRequest, RenamePropertyValueRequest,
+    SetFolderPropertyRequest, SetPropertyRequest,
 };
 use tauri::State;
 use tracing::{debug, instrument};
@@ -23,6 +24,7 @@ use super::{CommandError, Result};
 
 /// Get all properties for a note.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_properties(state: State<'_, AppState>, note_id: i64) -> Result<Vec<PropertyDto>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -35,22 +37,34 @@ pub async fn get_properties(state: State<'_, AppState>, note_id: i64) -> Result<
 }
 
 /// Set a property for a note (DB-only, no file modification).
+/// A request with `items` sets a list property from them.
 #[tauri::command]
 #[instrument(skip(state))]
 pub async fn set_property(state: State<'_, AppState>, request: SetPropertyRequest) -> Result<i64> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
+    let result = match &request.items {
+        Some(items) => vault.repo().set_list_property(request.note_id, &request.key, items).await,
+        None => {
+            vault
+                .repo()
+                .set_property(
+                    request.note_id,
+                    &request.key,
+                    request.value.as_deref(),
+                    request.property_type.as_deref(),
+                )
+                .await
+        }
+    };
+    let id = result.map_err(|e| CommandError::Vault(e.to_string()))?;
+
     vault
-        .repo()
-        .set_property(
-            request.note_id,
-            &request.key,
-            request.value.as_deref(),
-            request.property_type.as_deref(),
-        )
+        .refresh_autocomplete(Some(&[request.note_id]))
         .await
-        .map_err(|e| CommandError::Vault(e.to_string()))
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    Ok(id)
 }
 
 /// Delete a property from a note (DB-only, no file modification).
@@ -64,6 +78,11 @@ pub async fn delete_property(state: State<'_, AppState>, note_id: i64, key: Stri
         .repo()
         .delete_property(note_id, &key)
         .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    vault
+        .refresh_autocomplete(Some(&[note_id]))
+        .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
@@ -86,6 +105,10 @@ pub async fn rename_property_key(
         .rename_property_key(&request.old_key, &request.new_key)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
+    vault
+        .refresh_autocomplete(None)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
 
     Ok(PropertyOperationResult {
         affected_count,
@@ -108,6 +131,10 @@ pub async fn rename_property_value(
         .rename_property_value(&request.key, &request.old_value, &request.new_value)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
+    vault
+        .refresh_autocomplete(None)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
 
     Ok(PropertyOperationResult {
         affected_count,
@@ -130,6 +157,10 @@ pub async fn merge_property_keys(
         .merge_property_keys(&request.source_key, &request.target_key)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
+    vault
+        .refresh_autocomplete(None)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
 
     Ok(PropertyOperationResult {
         affected_count,
@@ -152,6 +183,10 @@ pub async fn delete_property_key(
         .delete_property_key(&request.key)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
+    vault
+        .refresh_autocomplete(None)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
 
     Ok(PropertyOperationResult {
         affected_count,
@@ -161,6 +196,7 @@ pub async fn delete_property_key(
 
 /// Get all distinct values for a property key with usage counts.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_property_values_with_counts(
     state: State<'_, AppState>,
     key: String,
@@ -182,6 +218,7 @@ pub async fn get_property_values_with_counts(
 
 /// Get all notes that have a specific property key, along with their value.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_notes_with_property(
     state: State<'_, AppState>,
     key: String,
@@ -198,6 +235,7 @@ pub async fn get_notes_with_property(
 
 /// Get all notes that have a specific property key and value.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_notes_with_property_value(
     state: State<'_, AppState>,
     key: String,
@@ -213,12 +251,54 @@ pub async fn get_notes_with_property_value(
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
+// ============================================================================
+// Property Table Commands
+// ============================================================================
+
+/// Get the notes matching all `filters` (as in the query builder) with
+/// their properties for `columns`, for a spreadsheet-style editor. Without
+/// columns, every property the notes use is a column.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_property_table(
+    state: State<'_, AppState>,
+    filters: Vec<PropertyFilter>,
+    columns: Vec<String>,
+) -> Result<PropertyTable> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_property_table(&filters, &columns)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Apply many property changes in one transaction. An update without a
+/// value or items removes the property. Keys that are also in a note's
+/// frontmatter are updated there too.
+#[tauri::command]
+#[instrument(skip(state, updates))]
+pub async fn batch_set_properties(
+    state: State<'_, AppState>,
+    updates: Vec<PropertyUpdate>,
+) -> Result<PropertyOperationResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::properties::batch_set_properties(vault, &updates)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
 // ============================================================================
 // Folder Property Commands
 // ============================================================================
 
 /// Get all properties for a folder.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_folder_properties(
     state: State<'_, AppState>,
     folder_path: String,
@@ -276,6 +356,7 @@ pub async fn delete_folder_property(
 /// Get properties for a note with inheritance info.
 /// Returns note's own properties plus inherited folder properties.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_properties_with_inheritance(
     state: State<'_, AppState>,
     note_id: i64,
@@ -293,6 +374,7 @@ pub async fn get_properties_with_inheritance(
 
 /// Get all folders that have properties defined.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_folders_with_properties(state: State<'_, AppState>) -> Result<Vec<String>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -355,11 +437,12 @@ pub async fn convert_frontmatter_to_db(
 
         let string_value = value.to_string_value();
 
-        vault
-            .repo()
-            .set_property(note_id, key, string_value.as_deref(), prop_type)
-            .await
-            .map_err(|e| CommandError::Vault(e.to_string()))?;
+        let stored = match value {
+            // Items are kept as they are, even with commas in them
+            PropertyValue::List(items) => vault.repo().set_list_property(note_id, key, items).await,
+            _ => vault.repo().set_property(note_id, key, string_value.as_deref(), prop_type).await,
+        };
+        stored.map_err(|e| CommandError::Vault(e.to_string()))?;
 
         properties_converted += 1;
     }
@@ -373,6 +456,11 @@ pub async fn convert_frontmatter_to_db(
             .map_err(|e| CommandError::Vault(e.to_string()))?;
     }
 
+    vault
+        .refresh_autocomplete(Some(&[note_id]))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
     // Convert frontmatter tags to inline format
     let tags_converted = frontmatter.tags.clone();
 
@@ -1,14 +1,15 @@
 //! Query builder commands.
 
 use crate::state::AppState;
-use shared_types::{PropertyKeyInfo, QueryEmbed, QueryEmbedResponse, QueryRequest, QueryResponse, TabResult};
+use shared_types::{PropertyKeyInfo, QueryEmbed, QueryEmbedResponse, QueryRequest, QueryResponse};
 use tauri::State;
-use tracing::info;
+use tracing::{info, instrument};
 
 use super::{CommandError, Result};
 
 /// Get all property keys used in the vault (for query builder dropdown).
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_property_keys(state: State<'_, AppState>) -> Result<Vec<PropertyKeyInfo>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -22,6 +23,7 @@ pub async fn get_property_keys(state: State<'_, AppState>) -> Result<Vec<Propert
 
 /// Get all distinct values for a property key (for query builder value autocomplete).
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_property_values(state: State<'_, AppState>, key: String) -> Result<Vec<String>> {
     let vault_guard = state.vault.read().await;
 
@@ -37,6 +39,7 @@ pub async fn get_property_values(state: State<'_, AppState>, key: String) -> Res
 /// Splits comma-separated values and returns unique items.
 /// Use this for ContainsAny/ContainsAll operators on list properties.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_list_property_values(state: State<'_, AppState>, key: String) -> Result<Vec<String>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -50,6 +53,7 @@ pub async fn get_list_property_values(state: State<'_, AppState>, key: String) -
 
 /// Run a query with property filters.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn run_query(state: State<'_, AppState>, request: QueryRequest) -> Result<QueryResponse> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -61,132 +65,116 @@ pub async fn run_query(state: State<'_, AppState>, request: QueryRequest) -> Res
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
+/// Move a card on a query Kanban board between the cards `before` and
+/// `after` (those that end up above and below it; either may be missing at
+/// the ends of a column). `board_key` is the view's `kanban.board`, filled
+/// in by `execute_query_embed`; `item_ref` is the card's `item_ref` from
+/// the query results. Returns the card's rank.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn reorder_query_result(
+    state: State<'_, AppState>,
+    board_key: String,
+    item_ref: String,
+    before: Option<String>,
+    after: Option<String>,
+) -> Result<f64> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::queries::reorder_query_result(vault, &board_key, &item_ref, before.as_deref(), after.as_deref())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
 /// Execute a query embed from YAML content.
 /// This parses the YAML and executes the query, returning both the parsed config and results.
-/// Supports both single-query mode and multi-tab mode.
+/// Supports both single-query mode and multi-tab mode. With `include_embedded`, tasks
+/// of notes embedded in the matching notes are included. Kanban views come back in
+/// their manual card order, with their board key filled in. A recent result of the
+/// same query is reused while the notes, properties and tasks it read are unchanged.
+///
+/// `note_id` is the note containing the block; filters can use its values
+/// as `{{this.<property>}}` or `{{this.file.name}}`. `block_index` is the
+/// block's position among the note's query blocks (from 0); with the note
+/// it names the block's Kanban boards.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn execute_query_embed(
     state: State<'_, AppState>,
     yaml_content: String,
+    note_id: Option<i64>,
+    block_index: Option<usize>,
 ) -> Result<QueryEmbedResponse> {
     info!("execute_query_embed called with: {}", yaml_content);
+    run_query_embed(&state, &yaml_content, note_id, block_index, false).await
+}
+
+/// Execute a query embed again, replacing its cached result.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn refresh_query_embed(
+    state: State<'_, AppState>,
+    yaml_content: String,
+    note_id: Option<i64>,
+    block_index: Option<usize>,
+) -> Result<QueryEmbedResponse> {
+    run_query_embed(&state, &yaml_content, note_id, block_index, true).await
+}
+
+/// Parse and run a query embed. Parse and query errors are returned in
+/// the response rather than as command errors.
+async fn run_query_embed(
+    state: &AppState,
+    yaml_content: &str,
+    note_id: Option<i64>,
+    block_index: Option<usize>,
+    refresh: bool,
+) -> Result<QueryEmbedResponse> {
+    let error_response = |query: QueryEmbed, error: String| QueryEmbedResponse {
+        query,
+        results: vec![],
+        total_count: 0,
+        tab_results: vec![],
+        error: Some(error),
+    };
 
     // Parse YAML into QueryEmbed
-    let query: QueryEmbed = match serde_yaml::from_str::<QueryEmbed>(&yaml_content) {
+    let mut query: QueryEmbed = match serde_yaml::from_str::<QueryEmbed>(yaml_content) {
         Ok(q) => {
             info!(
-                "Parsed query: result_type={:?}, filters={}",
+                "Parsed query: result_type={:?}, filters={}, tabs={}",
                 q.result_type,
-                q.filters.len()
+                q.filters.len(),
+                q.tabs.len()
             );
             q
         }
         Err(e) => {
             info!("YAML parse error: {}", e);
-            return Ok(QueryEmbedResponse {
-                query: QueryEmbed::default(),
-                results: vec![],
-                total_count: 0,
-                tab_results: vec![],
-                error: Some(format!("Invalid query YAML: {}", e)),
-            });
+            return Ok(error_response(QueryEmbed::default(), format!("Invalid query YAML: {}", e)));
         }
     };
 
     let vault_guard = state.vault.read().await;
     let vault = match vault_guard.as_ref() {
         Some(v) => v,
-        None => {
-            return Ok(QueryEmbedResponse {
-                query: query.clone(),
-                results: vec![],
-                total_count: 0,
-                tab_results: vec![],
-                error: Some("No vault is currently open".to_string()),
-            });
-        }
+        None => return Ok(error_response(query, "No vault is currently open".to_string())),
     };
 
-    // Check if we're in tab mode
-    if !query.tabs.is_empty() {
-        // Multi-tab mode: execute each tab's query
-        let mut tab_results = Vec::new();
-        let tabs = query.tabs.clone();
-
-        for tab in &tabs {
-            let request = QueryRequest {
-                filters: tab.filters.clone(),
-                match_mode: tab.match_mode.clone(),
-                result_type: tab.result_type.clone(),
-                include_completed: tab.include_completed,
-                limit: Some(tab.limit),
-            };
-
-            match vault.repo().run_query(&request).await {
-                Ok(response) => {
-                    tab_results.push(TabResult {
-                        name: tab.name.clone(),
-                        results: response.results,
-                        total_count: response.total_count,
-                        view: tab.view.clone(),
-                    });
-                }
-                Err(e) => {
-                    // Return error for this tab but continue with others
-                    return Ok(QueryEmbedResponse {
-                        query,
-                        results: vec![],
-                        total_count: 0,
-                        tab_results: vec![],
-                        error: Some(format!(
-                            "Query execution failed for tab '{}': {}",
-                            tab.name, e
-                        )),
-                    });
-                }
-            }
-        }
+    // Fill in {{this.…}} from the note containing the block
+    if let Err(e) = core_domain::queries::resolve_note_variables(vault, &mut query, note_id).await {
+        return Ok(error_response(query, e.to_string()));
+    }
+    if let (Some(note_id), Some(block_index)) = (note_id, block_index) {
+        core_domain::queries::assign_boards(&mut query, note_id, block_index);
+    }
 
-        Ok(QueryEmbedResponse {
-            query,
-            results: vec![],
-            total_count: 0,
-            tab_results,
-            error: None,
-        })
-    } else {
-        // Single-query mode (original behavior)
-        info!("Single-query mode: result_type={:?}", query.result_type);
-        let request = QueryRequest {
-            filters: query.filters.clone(),
-            match_mode: query.match_mode.clone(),
-            result_type: query.result_type.clone(),
-            include_completed: query.include_completed,
-            limit: Some(query.limit),
-        };
-
-        info!("Running query...");
-        match vault.repo().run_query(&request).await {
-            Ok(response) => {
-                info!("Query completed: {} results", response.results.len());
-                Ok(QueryEmbedResponse {
-                    query,
-                    results: response.results,
-                    total_count: response.total_count,
-                    tab_results: vec![],
-                    error: None,
-                })
-            }
-            Err(e) => {
-                info!("Query failed: {}", e);
-                Ok(QueryEmbedResponse {
-                    query,
-                    results: vec![],
-                    total_count: 0,
-                    tab_results: vec![],
-                    error: Some(format!("Query execution failed: {}", e)),
-                })
-            }
+    match core_domain::queries::cached_query_embed(vault, query.clone(), refresh).await {
+        Ok(response) => Ok(response),
+        Err(e) => {
+            info!("Query failed: {}", e);
+            Ok(error_response(query, format!("Query execution failed: {}", e)))
         }
     }
 }
@@ -0,0 +1,226 @@
+//! Rendering commands - notes as sanitized HTML, diagrams, math, journal and slide export.
+
+use crate::state::AppState;
+use chrono::NaiveDate;
+use core_domain::{Job, Vault};
+use shared_types::{
+    DiagramSettings, JournalExportResult, MathSettings, RenderOptions, RenderedNote, SlideExportResult,
+};
+use tauri::State;
+use tracing::{info, instrument};
+
+use super::templates::{read_vault_config, write_vault_config};
+use super::{CommandError, Result};
+
+/// Render a note to sanitized HTML for export, sharing, or print.
+///
+/// Wikilinks become internal links, note embeds are inlined up to
+/// `options.max_embed_depth`, and query blocks become placeholders.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn render_note_html(
+    state: State<'_, AppState>,
+    note_id: i64,
+    options: Option<RenderOptions>,
+) -> Result<RenderedNote> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let options = with_math_setting(vault, options.unwrap_or_default()).await;
+    core_domain::rendering::render_note_html(vault, note_id, &options)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Render the `block_index`th diagram (`mermaid` block) of a note to SVG,
+/// from the cache or with the configured renderer.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn render_diagram(state: State<'_, AppState>, note_id: i64, block_index: usize) -> Result<String> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::diagrams::render_diagram(vault, note_id, block_index)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get the diagram settings in effect: the vault config's, with this
+/// device's renderer.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_diagram_settings(state: State<'_, AppState>) -> Result<DiagramSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    Ok(vault.diagram_settings().await)
+}
+
+/// Save diagram settings to vault config. The renderer is not saved there:
+/// it is set in this device's app settings (`diagram_renderer_path`).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_diagram_settings(state: State<'_, AppState>, settings: DiagramSettings) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.diagram_settings = DiagramSettings {
+        renderer_path: None,
+        ..settings
+    };
+    write_vault_config(&config_path, &config).await?;
+    vault
+        .set_diagram_settings(DiagramSettings {
+            renderer_path: vault.diagram_settings().await.renderer_path,
+            ..config.diagram_settings
+        })
+        .await;
+
+    info!("Saved diagram settings");
+    Ok(())
+}
+
+/// Get math settings from vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_math_settings(state: State<'_, AppState>) -> Result<MathSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.math_settings)
+}
+
+/// Save math settings to vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_math_settings(state: State<'_, AppState>, settings: MathSettings) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.math_settings = settings;
+    write_vault_config(&config_path, &config).await?;
+
+    info!("Saved math settings");
+    Ok(())
+}
+
+/// Fill in the vault's math rendering unless the caller chose one.
+async fn with_math_setting(vault: &Vault, mut options: RenderOptions) -> RenderOptions {
+    if options.math.is_none() {
+        let config = read_vault_config(&vault.fs().config_path()).await.unwrap_or_default();
+        options.math = Some(config.math_settings.rendering);
+    }
+    options
+}
+
+/// Export the daily notes of a date range (YYYY-MM-DD, inclusive) as one
+/// HTML file with a table of contents, at `output_path`.
+///
+/// Each day starts on a new page when printed, so the file can be printed
+/// or saved as PDF from the print dialog.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_journal(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    output_path: String,
+    options: Option<RenderOptions>,
+) -> Result<JournalExportResult> {
+    let (start, end) = parse_date_range(&start_date, &end_date)?;
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    write_journal(vault, start, end, output_path, &options.unwrap_or_default(), None).await
+}
+
+/// Export a note as an HTML slide deck at `output_path`.
+///
+/// The note is split into slides on `---` lines, or on H2 headings when it
+/// has none. Arrow keys move between slides; printing gives one slide per page.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_slides(
+    state: State<'_, AppState>,
+    note_id: i64,
+    output_path: String,
+    options: Option<RenderOptions>,
+) -> Result<SlideExportResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let options = with_math_setting(vault, options.unwrap_or_default()).await;
+    let deck = core_domain::slides::render_slides(vault, note_id, &options)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    tokio::fs::write(&output_path, deck.html)
+        .await
+        .map_err(|e| CommandError::Vault(format!("Failed to write {}: {}", output_path, e)))?;
+
+    info!("Exported {} slides to {}", deck.slide_count, output_path);
+    Ok(SlideExportResult {
+        output_path,
+        slide_count: deck.slide_count,
+    })
+}
+
+/// Parse an export date range, which must not end before it starts.
+pub(super) fn parse_date_range(start_date: &str, end_date: &str) -> Result<(NaiveDate, NaiveDate)> {
+    let parse_date = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))
+    };
+    let start = parse_date(start_date)?;
+    let end = parse_date(end_date)?;
+    if end < start {
+        return Err(CommandError::Vault("End date is before start date".to_string()));
+    }
+    Ok((start, end))
+}
+
+/// Render the journal and write it to `output_path`. A cancelled job writes
+/// the days rendered before it stopped.
+pub(super) async fn write_journal(
+    vault: &Vault,
+    start: NaiveDate,
+    end: NaiveDate,
+    output_path: String,
+    options: &RenderOptions,
+    job: Option<&Job>,
+) -> Result<JournalExportResult> {
+    let config = read_vault_config(&vault.fs().config_path()).await.unwrap_or_default();
+    let mut options = options.clone();
+    options.math.get_or_insert(config.math_settings.rendering);
+
+    let journal = core_domain::rendering::render_journal(
+        vault,
+        &config.template_settings.daily_note_pattern,
+        start,
+        end,
+        &options,
+        job,
+    )
+    .await
+    .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    tokio::fs::write(&output_path, journal.html)
+        .await
+        .map_err(|e| CommandError::Vault(format!("Failed to write {}: {}", output_path, e)))?;
+
+    info!("Exported {} daily notes to {}", journal.dates.len(), output_path);
+    Ok(JournalExportResult {
+        output_path,
+        dates: journal
+            .dates
+            .iter()
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .collect(),
+    })
+}
@@ -0,0 +1,44 @@
+//! Note review commands - resurfacing old notes for another look.
+
+use crate::state::AppState;
+use chrono::Utc;
+use shared_types::NoteReviewDto;
+use tauri::State;
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+/// Default number of notes returned by the review queue.
+const DEFAULT_REVIEW_LIMIT: usize = 10;
+
+/// Get the notes that are due for review, most overdue first.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_notes_due_for_review(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<NoteReviewDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .get_notes_due_for_review(limit.unwrap_or(DEFAULT_REVIEW_LIMIT), Utc::now())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Mark a note as reviewed. Returns the days until its next review.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn mark_reviewed(state: State<'_, AppState>, note_id: i64) -> Result<i64> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .mark_note_reviewed(note_id, Utc::now())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .ok_or_else(|| CommandError::NoteNotFound(note_id.to_string()))
+}
@@ -1,8 +1,12 @@
 //! Schedule block commands and notes by date.
 
 use crate::state::AppState;
+use chrono::{Local, NaiveDate, NaiveTime};
 use shared_types::{
-    CreateScheduleBlockRequest, NoteForDate, ScheduleBlockDto, UpdateScheduleBlockRequest,
+    ApplyScheduleTemplateResult, BlockSessionDto, ContextSwitchReport, CreateScheduleBlockRequest,
+    CreateScheduleTemplateRequest, DayPlan, DayWorkload, NoteForDate, PlanDayConstraints,
+    PlanVsActualReport, ScheduleBlockDto, ScheduleTemplateDto, UpdateScheduleBlockRequest,
+    WeekOverview,
 };
 use tauri::State;
 use tracing::instrument;
@@ -22,8 +26,10 @@ pub async fn create_schedule_block(
 ) -> Result<i64> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+    // Rejected before the block is written
+    core_storage::validate_timezone(request.timezone.as_deref()).map_err(|e| CommandError::Vault(e.to_string()))?;
 
-    vault
+    let id = vault
         .repo()
         .create_schedule_block(
             request.note_id,
@@ -36,11 +42,22 @@ pub async fn create_schedule_block(
             request.rrule.as_deref(),
         )
         .await
-        .map_err(|e| CommandError::Vault(e.to_string()))
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    if request.timezone.is_some() {
+        vault
+            .repo()
+            .set_schedule_block_timezone(id, request.timezone.as_deref())
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?;
+    }
+
+    Ok(id)
 }
 
 /// Get schedule blocks for a date range.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_schedule_blocks(
     state: State<'_, AppState>,
     start_date: String,
@@ -58,6 +75,7 @@ pub async fn get_schedule_blocks(
 
 /// Get schedule blocks for a single date.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_schedule_blocks_for_date(
     state: State<'_, AppState>,
     date: String,
@@ -74,6 +92,7 @@ pub async fn get_schedule_blocks_for_date(
 
 /// Get schedule blocks linked to a specific note.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_schedule_blocks_for_note(
     state: State<'_, AppState>,
     note_id: i64,
@@ -97,6 +116,7 @@ pub async fn update_schedule_block(
 ) -> Result<()> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+    core_storage::validate_timezone(request.timezone.as_deref()).map_err(|e| CommandError::Vault(e.to_string()))?;
 
     vault
         .repo()
@@ -116,7 +136,17 @@ pub async fn update_schedule_block(
             request.rrule.as_deref(),
         )
         .await
-        .map_err(|e| CommandError::Vault(e.to_string()))
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    if request.timezone.is_some() {
+        vault
+            .repo()
+            .set_schedule_block_timezone(request.id, request.timezone.as_deref())
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?;
+    }
+
+    Ok(())
 }
 
 /// Delete a schedule block.
@@ -133,12 +163,256 @@ pub async fn delete_schedule_block(state: State<'_, AppState>, id: i64) -> Resul
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
+// ============================================================================
+// Schedule Template Commands
+// ============================================================================
+
+/// Create a schedule template (named routine of time slots).
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_schedule_template(
+    state: State<'_, AppState>,
+    request: CreateScheduleTemplateRequest,
+) -> Result<i64> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .create_schedule_template(&request)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// List all schedule templates.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_schedule_templates(
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduleTemplateDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_schedule_templates()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Delete a schedule template.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_schedule_template(state: State<'_, AppState>, id: i64) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_schedule_template(id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Apply a schedule template to a date, creating its blocks.
+/// If `skip_conflicts` is true (the default), slots overlapping existing blocks are skipped.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn apply_schedule_template(
+    state: State<'_, AppState>,
+    template_id: i64,
+    date: String,
+    skip_conflicts: Option<bool>,
+) -> Result<ApplyScheduleTemplateResult> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .apply_schedule_template(template_id, &date, skip_conflicts.unwrap_or(true))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .ok_or_else(|| CommandError::Vault(format!("Schedule template not found: {}", template_id)))
+}
+
+// ============================================================================
+// Day Planning Commands
+// ============================================================================
+
+/// Plan a day by filling free time within working hours with top-priority tasks.
+/// Returns the proposed plan; blocks are only created if `constraints.create_blocks` is set.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn plan_day(
+    state: State<'_, AppState>,
+    date: String,
+    constraints: PlanDayConstraints,
+) -> Result<DayPlan> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    vault
+        .repo()
+        .plan_day(date, &constraints)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get estimated task effort versus free time per day for a date range.
+/// Working hours default to 09:00-17:00.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_workload(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    work_start: Option<NaiveTime>,
+    work_end: Option<NaiveTime>,
+) -> Result<Vec<DayWorkload>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+    let end = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    let work_start = work_start.unwrap_or(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+    let work_end = work_end.unwrap_or(NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+
+    vault
+        .repo()
+        .get_workload(start, end, work_start, work_end)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get schedule blocks, tasks, habit completion, and note counts for the
+/// seven days starting at `start_date`, in a single call for the weekly planner.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_week_overview(state: State<'_, AppState>, start_date: String) -> Result<WeekOverview> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    vault
+        .repo()
+        .get_week_overview(start)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Format the agenda of a day (schedule blocks, tasks due and habits) as
+/// plain text wrapped to `width` columns (default 32, a 58 mm receipt), for
+/// printing or piping to other tools.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn format_agenda_text(state: State<'_, AppState>, date: String, width: Option<usize>) -> Result<String> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    core_domain::agenda::format_agenda_text(vault, date, width.unwrap_or(32))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+// ============================================================================
+// Focus Session Commands
+// ============================================================================
+
+/// Start a focus session on a schedule block, ending any other running session.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn start_block(state: State<'_, AppState>, block_id: i64) -> Result<BlockSessionDto> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .start_block_session(block_id, Local::now().naive_local())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .ok_or_else(|| CommandError::Vault(format!("Schedule block {} not found", block_id)))
+}
+
+/// End the running focus session on a schedule block.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn end_block(state: State<'_, AppState>, block_id: i64) -> Result<BlockSessionDto> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .end_block_session(block_id, Local::now().naive_local())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .ok_or_else(|| CommandError::Vault(format!("No running session for block {}", block_id)))
+}
+
+/// Compare planned block time with recorded focus time per context.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_plan_vs_actual(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+) -> Result<PlanVsActualReport> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+    let end = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    vault
+        .repo()
+        .get_plan_vs_actual(start, end, Local::now().naive_local())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Get context switches, time per context, and focus stretches for a date range.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_context_switch_report(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+) -> Result<ContextSwitchReport> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+    let end = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    let week_start = vault.locale_settings().await.week_start;
+    vault
+        .repo()
+        .get_context_switch_report(start, end, week_start)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
 // ============================================================================
 // Notes by Date Commands
 // ============================================================================
 
 /// Get notes for a specific date (ordered by: scheduled > journal > created).
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_notes_for_date(
     state: State<'_, AppState>,
     date: String,
@@ -155,6 +429,7 @@ pub async fn get_notes_for_date(
 
 /// Get notes for a date range (for weekly/monthly views).
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_notes_for_date_range(
     state: State<'_, AppState>,
     start_date: String,
@@ -0,0 +1,84 @@
+//! Scheduled note commands - rules that create notes from templates on a schedule.
+
+use crate::state::AppState;
+use chrono::Local;
+use core_domain::scheduled_notes::{create_rule, run_due_rules, update_rule};
+use shared_types::{SaveScheduledNoteRuleRequest, ScheduledNoteCreated, ScheduledNoteRule};
+use tauri::State;
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+/// List all scheduled note rules.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_scheduled_note_rules(state: State<'_, AppState>) -> Result<Vec<ScheduledNoteRule>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_scheduled_note_rules()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Create a scheduled note rule. Its first note is created at the next
+/// occurrence. Fails if its time or rrule is invalid.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_scheduled_note_rule(
+    state: State<'_, AppState>,
+    request: SaveScheduledNoteRuleRequest,
+) -> Result<i64> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    create_rule(vault, &request)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Change a scheduled note rule, or enable or disable it. A rule enabled
+/// again doesn't catch up on what it missed while disabled.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn update_scheduled_note_rule(
+    state: State<'_, AppState>,
+    id: i64,
+    request: SaveScheduledNoteRuleRequest,
+) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    update_rule(vault, id, &request)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Delete a scheduled note rule. Notes it created are kept.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_scheduled_note_rule(state: State<'_, AppState>, id: i64) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_scheduled_note_rule(id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Create the notes of all rules that are due now, without waiting for the
+/// background scheduler.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn run_scheduled_notes(state: State<'_, AppState>) -> Result<Vec<ScheduledNoteCreated>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    run_due_rules(vault, Local::now().naive_local())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
@@ -1,34 +1,157 @@
 //! Search commands.
 
 use crate::state::AppState;
-use core_embedding::{hybrid_search, EmbeddingClient};
-use core_storage::extract_content_preview;
+use core_domain::link_suggestions::suggest_links as rank_link_suggestions;
+use core_domain::Vault;
+use core_embedding::{embed_note, hybrid_search, EmbeddingClient};
+use core_storage::{content_signature, extract_content_preview};
 use shared_types::{
-    EmbeddingSettings, EmbeddingStatus, HybridSearchResult, SearchResult,
+    AutocompleteItem, AutocompleteKind, EmbeddingConsistencyReport, EmbeddingSettings, EmbeddingStatus,
+    HybridSearchResult, ImageTextMatch, LinkSuggestion, QuickSwitchResult, SaveSearchPresetRequest, SearchFilters, SearchPresetDto, SearchResult,
 };
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 use tauri::State;
+use tracing::{debug, instrument};
 
 use super::{CommandError, Result};
 
-/// Search notes using FTS5.
+/// How long `suggest_links` waits for typing to pause before embedding the
+/// paragraph, so a burst of keystrokes costs one embedding request.
+const LINK_SUGGESTION_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Search notes using FTS5, optionally scoped by folder, tags and date range.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn search_notes(
     state: State<'_, AppState>,
     query: String,
     limit: Option<i32>,
+    filters: Option<SearchFilters>,
 ) -> Result<Vec<SearchResult>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
+    let today = chrono::Local::now().date_naive();
     vault
         .repo()
-        .search(&query, limit.unwrap_or(50))
+        .search_filtered(&query, &filters.unwrap_or_default(), today, limit.unwrap_or(50))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Search the text recognized in the vault's images (see `start_ocr`).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn search_image_text(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<i32>,
+) -> Result<Vec<ImageTextMatch>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .search_image_text(&query, limit.unwrap_or(20))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Fuzzy-match note titles, aliases, paths, and headings for the quick switcher.
+/// An empty query returns recently opened notes.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn quick_switch(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<QuickSwitchResult>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .quick_switch(&query, limit.unwrap_or(20))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Complete titles, aliases, tags, property keys, property values (of the
+/// property `key`, if given), task contexts or headings starting with
+/// `prefix`, from the in-memory autocomplete index.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn autocomplete(
+    state: State<'_, AppState>,
+    kind: AutocompleteKind,
+    prefix: String,
+    limit: Option<usize>,
+    key: Option<String>,
+) -> Result<Vec<AutocompleteItem>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .autocomplete(kind, &prefix, key.as_deref(), limit.unwrap_or(20))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Suggest link targets for the paragraph being written (`context_text`),
+/// ranked by fuzzy title/alias match of the link being typed, tag overlap
+/// and, when embeddings are enabled, similarity to the paragraph.
+/// `note_id` (the note being edited) is never suggested.
+///
+/// The paragraph is only embedded once typing pauses: a request followed
+/// by another within `LINK_SUGGESTION_DEBOUNCE` is ranked without
+/// similarity.
+#[tauri::command]
+#[instrument(skip(state, context_text, settings))]
+pub async fn suggest_links(
+    state: State<'_, AppState>,
+    context_text: String,
+    limit: Option<usize>,
+    note_id: Option<i64>,
+    settings: Option<EmbeddingSettings>,
+) -> Result<Vec<LinkSuggestion>> {
+    let limit = limit.unwrap_or(10);
+    let request = state.link_suggestion_request.fetch_add(1, Ordering::SeqCst) + 1;
+
+    // Embedded before taking the vault, which isn't held during the request
+    let mut embedding = None;
+    if let Some(settings) = settings.filter(|s| s.enabled) {
+        tokio::time::sleep(LINK_SUGGESTION_DEBOUNCE).await;
+        if state.link_suggestion_request.load(Ordering::SeqCst) == request {
+            match EmbeddingClient::new(settings).embed(&context_text).await {
+                Ok(e) => embedding = Some(e),
+                Err(e) => debug!("Failed to embed link context, ranking without similarity: {}", e),
+            }
+        }
+    }
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let mut similar = Vec::new();
+    if let Some(embedding) = embedding {
+        similar = vault
+            .repo()
+            .vector_search(&embedding, (limit * 5) as i32)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?
+            .into_iter()
+            .map(|r| (r.note_id, r.score))
+            .collect();
+    }
+
+    rank_link_suggestions(vault, &context_text, &similar, note_id, limit)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
 /// Search notes using hybrid FTS5 + vector search.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn hybrid_search_notes(
     state: State<'_, AppState>,
     query: String,
@@ -56,6 +179,7 @@ pub async fn hybrid_search_notes(
 
 /// Check embedding service status.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_embedding_status(
     state: State<'_, AppState>,
     settings: EmbeddingSettings,
@@ -100,6 +224,7 @@ pub async fn get_embedding_status(
 
 /// Test embedding service connection.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn test_embedding_connection(
     settings: EmbeddingSettings,
 ) -> Result<bool> {
@@ -111,7 +236,10 @@ pub async fn test_embedding_connection(
 }
 
 /// Generate embedding for a single note by ID.
+/// Notes that changed less than the re-embed threshold keep their embedding.
+/// Returns false without embedding anything for encrypted and oversized notes.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn generate_note_embedding(
     state: State<'_, AppState>,
     note_id: i64,
@@ -120,6 +248,11 @@ pub async fn generate_note_embedding(
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
+    embed_note_by_id(vault, &EmbeddingClient::new(settings), note_id).await
+}
+
+/// Embed a note unless it is encrypted, oversized or its embedding is still current.
+pub(super) async fn embed_note_by_id(vault: &Vault, client: &EmbeddingClient, note_id: i64) -> Result<bool> {
     // Get note info
     let note = vault
         .repo()
@@ -127,30 +260,60 @@ pub async fn generate_note_embedding(
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
 
+    // Encrypted and oversized notes are never embedded
+    let encrypted = vault
+        .repo()
+        .is_note_encrypted(note_id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    let truncated = vault
+        .repo()
+        .is_note_truncated(note_id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    if encrypted || truncated {
+        return Ok(false);
+    }
+
     // Read note content from filesystem
     let content = vault
         .fs()
-        .read_file(std::path::Path::new(&note.path))
+        .read_text(std::path::Path::new(&note.path))
         .await
-        .map_err(|e| CommandError::Vault(e.to_string()))?;
+        .map_err(|e| CommandError::Vault(e.to_string()))?
+        .content;
 
-    // Generate embedding
-    let client = EmbeddingClient::new(settings);
-    let embedding = client
-        .embed(&content)
-        .await
-        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    // Private sections are never sent to the embedding model
+    let redacted = core_index::redact_private(&content);
 
-    // Compute content hash
     let hash = core_fs::hash_content(&content);
+    let preview = extract_content_preview(&redacted);
+    let signature = content_signature(&content);
 
-    // Extract content preview for search results
-    let preview = extract_content_preview(&content);
+    // Small edits keep the existing embedding
+    let current = vault
+        .repo()
+        .embedding_is_current(note_id, &content, &hash, Some(&preview), &signature, client.settings().reembed_threshold)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    if current {
+        return Ok(true);
+    }
 
-    // Store embedding with preview
+    // Generate embeddings for the note's sections
+    let (embedding, chunks) = embed_note(client, &content)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    // Store sections first; the note embedding (with preview) marks the note as done
     vault
         .repo()
-        .store_embedding(note_id, &embedding, &hash, Some(&preview))
+        .store_chunk_embeddings(note_id, &chunks)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    vault
+        .repo()
+        .store_embedding(note_id, &embedding, &hash, Some(&preview), &signature)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
 
@@ -159,6 +322,7 @@ pub async fn generate_note_embedding(
 
 /// Get list of note IDs that need embeddings.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_notes_needing_embeddings(
     state: State<'_, AppState>,
     limit: i32,
@@ -172,3 +336,71 @@ pub async fn get_notes_needing_embeddings(
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
+
+/// Check stored embeddings for orphaned, stale, and wrong-sized vectors.
+/// With `repair`, orphaned and wrong-sized ones are deleted.
+#[tauri::command]
+#[instrument(skip(state, settings))]
+pub async fn check_embeddings(
+    state: State<'_, AppState>,
+    settings: EmbeddingSettings,
+    repair: bool,
+) -> Result<EmbeddingConsistencyReport> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .check_embeddings(settings.dimensions, repair)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Save a search with its filters under a name. Saving an existing name replaces it.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn save_search_preset(
+    state: State<'_, AppState>,
+    request: SaveSearchPresetRequest,
+) -> Result<i64> {
+    if request.name.trim().is_empty() {
+        return Err(CommandError::Vault("Preset name cannot be empty".to_string()));
+    }
+
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .save_search_preset(&request)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// List saved search presets.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_search_presets(state: State<'_, AppState>) -> Result<Vec<SearchPresetDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_search_presets()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Delete a search preset.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_search_preset(state: State<'_, AppState>, id: i64) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_search_preset(id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
@@ -0,0 +1,49 @@
+//! Spell check commands - checking text and the custom dictionary.
+
+use crate::state::AppState;
+use shared_types::Misspelling;
+use tauri::{AppHandle, Manager, State};
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+/// Check markdown text for misspelled words in a language (e.g. "en_US").
+///
+/// Ranges are UTF-16 offsets into `text`. Dictionaries bundled with the app
+/// are used unless the vault or the system provides one.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn check_text(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    text: String,
+    lang: String,
+) -> Result<Vec<Misspelling>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let bundled: Vec<_> = app
+        .path()
+        .resource_dir()
+        .map(|dir| dir.join("dictionaries"))
+        .into_iter()
+        .collect();
+
+    vault
+        .check_spelling(&text, &lang, &bundled)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Add a word to the vault's custom dictionary.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn add_to_dictionary(state: State<'_, AppState>, word: String) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .add_to_dictionary(&word)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
@@ -12,7 +12,7 @@ use std::process::Stdio;
 use tauri::{AppHandle, Manager, State};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
-use tracing::{error, info};
+use tracing::{error, info, instrument};
 
 use super::{CommandError, Result};
 
@@ -67,6 +67,7 @@ fn get_scripts_dir(app: &AppHandle) -> Result<PathBuf> {
 /// * `model` - Model name (optional)
 /// * `tags` - Default tags, comma-separated (optional)
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn run_link_summarizer(
     app: AppHandle,
     state: State<'_, AppState>,
@@ -208,6 +209,7 @@ pub async fn run_link_summarizer(
 /// * `model` - Model name (optional)
 /// * `asset_template` - Path template for thumbnails (optional)
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn run_transcript_summarizer(
     app: AppHandle,
     state: State<'_, AppState>,
@@ -388,6 +390,7 @@ fn resolve_path(path: &str, vault_root: &std::path::Path) -> PathBuf {
 /// Count pending transcript files in a directory.
 /// Path is relative to vault root.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn count_pending_transcripts(
     state: State<'_, AppState>,
     input_dir: String,
@@ -0,0 +1,101 @@
+//! Table commands - formatting and editing markdown tables, and CSV
+//! import and export.
+//!
+//! The editing commands work on the editor's content and return it
+//! rewritten, so the edit can be applied (and undone) like any other.
+//! `line` is the 1-based line of any row of the table.
+
+use crate::state::AppState;
+use shared_types::NoteContent;
+use std::path::Path;
+use tauri::State;
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+fn no_table(line: usize) -> CommandError {
+    CommandError::Vault(format!("No table at line {}", line))
+}
+
+/// Format the table at `line`: columns padded to the same width, with the
+/// delimiter row showing each column's alignment.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn format_table(content: String, line: usize) -> Result<String> {
+    core_index::format_table(&content, line).ok_or_else(|| no_table(line))
+}
+
+/// Sort the rows of the table at `line` by a column (0-based). Numbers sort
+/// numerically, text case-insensitively, and empty cells last.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn sort_table_by_column(
+    content: String,
+    line: usize,
+    column: usize,
+    descending: Option<bool>,
+) -> Result<String> {
+    core_index::sort_table_by_column(&content, line, column, descending.unwrap_or(false))
+        .ok_or_else(|| CommandError::Vault(format!("No table with column {} at line {}", column, line)))
+}
+
+/// Insert an empty row into the table at `line`, before body row `index`
+/// (0-based), or at the end.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn table_add_row(content: String, line: usize, index: Option<usize>) -> Result<String> {
+    core_index::table_add_row(&content, line, index).ok_or_else(|| no_table(line))
+}
+
+/// Insert a column into the table at `line`, before column `index`
+/// (0-based), or at the end.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn table_add_column(
+    content: String,
+    line: usize,
+    index: Option<usize>,
+    header: Option<String>,
+) -> Result<String> {
+    core_index::table_add_column(&content, line, index, header.as_deref().unwrap_or(""))
+        .ok_or_else(|| no_table(line))
+}
+
+/// Import a CSV file as a table into the note at `note_path`, before line
+/// `position` (1-based), or at the end; the note is created if needed.
+///
+/// The delimiter (comma, semicolon, tab or pipe) is detected and the first
+/// row becomes the header. Returns the note with its new content.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn import_csv_as_table(
+    state: State<'_, AppState>,
+    csv_path: String,
+    note_path: String,
+    position: Option<usize>,
+) -> Result<NoteContent> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::csv_tables::import_csv_as_table(vault, Path::new(&csv_path), &note_path, position)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Export the `table_index`th table (from 0) of a note as a CSV file at
+/// `output`. Returns the number of rows written, header included.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn export_table_as_csv(
+    state: State<'_, AppState>,
+    note_id: i64,
+    table_index: usize,
+    output: String,
+) -> Result<usize> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    core_domain::csv_tables::export_table_as_csv(vault, note_id, table_index, Path::new(&output))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
@@ -1,13 +1,15 @@
 //! Tag commands.
 
 use crate::state::AppState;
-use shared_types::TagDto;
+use shared_types::{TagDto, TagMeta};
 use tauri::State;
+use tracing::instrument;
 
 use super::{CommandError, Result};
 
 /// List all tags with counts.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn list_tags(state: State<'_, AppState>) -> Result<Vec<TagDto>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -18,3 +20,45 @@ pub async fn list_tags(state: State<'_, AppState>) -> Result<Vec<TagDto>> {
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
+
+/// List the metadata (aliases, descriptions, colors) of all tags that have any.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_tag_meta(state: State<'_, AppState>) -> Result<Vec<TagMeta>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_tag_meta()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Set a tag's alias, description and color.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn set_tag_meta(state: State<'_, AppState>, meta: TagMeta) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .set_tag_meta(&meta)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Remove a tag's metadata.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn delete_tag_meta(state: State<'_, AppState>, tag: String) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_tag_meta(&tag)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
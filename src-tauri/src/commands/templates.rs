@@ -1,54 +1,91 @@
-//! Template commands - daily note creation and template settings.
+//! Template commands - periodic (daily, weekly, monthly) notes, template and locale settings, and note types.
 
 use crate::state::AppState;
 use chrono::NaiveDate;
-use core_domain::templates::{render_template, TemplateContext};
+use core_domain::Vault;
+use core_domain::{note_types, notes, periodic};
+use core_domain::templates::{journal_prompt_for_date, render_template, TemplateContext};
 use serde::{Deserialize, Serialize};
-use shared_types::{DailyNoteResult, TemplateSettings};
+use shared_types::{
+    AttachmentSettings, DailyNoteResult, DiagramSettings, ExternalFolder, IndexSettings, LinkSettings, LintSettings, LocaleSettings, MathSettings, NoteContent, NoteDto, NotePeriod, NoteTypeDefinition,
+    PeriodDirection, PeriodicNote, StorageSettings, TemplateRenderContext, TemplateSettings, TitleSettings, WeekInfo,
+};
 use std::path::Path;
 use tauri::State;
-use tracing::{debug, info};
+use tracing::{debug, info, instrument};
 
 use super::{CommandError, Result};
 
 /// Vault config structure (stored in .neuroflow/config.json).
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
-struct VaultConfig {
+pub(super) struct VaultConfig {
     #[serde(default)]
-    template_settings: TemplateSettings,
+    pub template_settings: TemplateSettings,
+    #[serde(default)]
+    pub attachment_settings: AttachmentSettings,
+    #[serde(default)]
+    pub lint_settings: LintSettings,
+    #[serde(default)]
+    pub title_settings: TitleSettings,
+    #[serde(default)]
+    pub storage_settings: StorageSettings,
+    #[serde(default)]
+    pub link_settings: LinkSettings,
+    #[serde(default)]
+    pub diagram_settings: DiagramSettings,
+    #[serde(default)]
+    pub math_settings: MathSettings,
+    #[serde(default)]
+    pub locale_settings: LocaleSettings,
+    #[serde(default)]
+    pub index_settings: IndexSettings,
+    /// Note types; the built-in ones are used while this is empty.
+    #[serde(default)]
+    pub note_types: Vec<NoteTypeDefinition>,
+    /// Read-only folders indexed alongside the vault.
+    #[serde(default)]
+    pub external_folders: Vec<ExternalFolder>,
 }
 
-/// Default template content when no template file is configured.
-const DEFAULT_TEMPLATE: &str = r#"# {{date}}
+/// Read the vault config, or the defaults if there is none yet.
+pub(super) async fn read_vault_config(config_path: &Path) -> Result<VaultConfig> {
+    if !config_path.exists() {
+        return Ok(VaultConfig::default());
+    }
+
+    let content = tokio::fs::read_to_string(config_path)
+        .await
+        .map_err(|e| CommandError::Vault(format!("Failed to read vault config: {}", e)))?;
 
-## Tasks
-- [ ]
+    serde_json::from_str(&content)
+        .map_err(|e| CommandError::Vault(format!("Failed to parse vault config: {}", e)))
+}
 
-## Notes
+/// Write the vault config.
+pub(super) async fn write_vault_config(config_path: &Path, config: &VaultConfig) -> Result<()> {
+    // Ensure parent directory exists
+    if let Some(parent) = config_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| CommandError::Vault(format!("Failed to create config directory: {}", e)))?;
+    }
 
-## Reflection
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| CommandError::Vault(format!("Failed to serialize vault config: {}", e)))?;
 
-"#;
+    tokio::fs::write(config_path, content)
+        .await
+        .map_err(|e| CommandError::Vault(format!("Failed to write vault config: {}", e)))
+}
 
 /// Get template settings from vault config.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_template_settings(state: State<'_, AppState>) -> Result<TemplateSettings> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
-    let config_path = vault.fs().config_path();
-
-    if !config_path.exists() {
-        debug!("No config file, returning default template settings");
-        return Ok(TemplateSettings::default());
-    }
-
-    let content = tokio::fs::read_to_string(&config_path)
-        .await
-        .map_err(|e| CommandError::Vault(format!("Failed to read vault config: {}", e)))?;
-
-    let config: VaultConfig = serde_json::from_str(&content)
-        .map_err(|e| CommandError::Vault(format!("Failed to parse vault config: {}", e)))?;
+    let config = read_vault_config(&vault.fs().config_path()).await?;
 
     debug!("Read template settings: {:?}", config.template_settings);
     Ok(config.template_settings)
@@ -56,6 +93,7 @@ pub async fn get_template_settings(state: State<'_, AppState>) -> Result<Templat
 
 /// Save template settings to vault config.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn save_template_settings(
     state: State<'_, AppState>,
     settings: TemplateSettings,
@@ -66,40 +104,51 @@ pub async fn save_template_settings(
     let config_path = vault.fs().config_path();
 
     // Read existing config or create new one
-    let mut config: VaultConfig = if config_path.exists() {
-        let content = tokio::fs::read_to_string(&config_path)
-            .await
-            .map_err(|e| CommandError::Vault(format!("Failed to read vault config: {}", e)))?;
-
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        VaultConfig::default()
-    };
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
 
     // Update template settings
-    config.template_settings = settings;
+    config.template_settings = settings.clone();
+    write_vault_config(&config_path, &config).await?;
+    vault.set_template_settings(settings).await;
 
-    // Ensure parent directory exists
-    if let Some(parent) = config_path.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .map_err(|e| CommandError::Vault(format!("Failed to create config directory: {}", e)))?;
-    }
+    info!("Saved template settings");
+    Ok(())
+}
 
-    // Write config
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| CommandError::Vault(format!("Failed to serialize vault config: {}", e)))?;
+/// Get locale settings (week start, date and time formats) from vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_locale_settings(state: State<'_, AppState>) -> Result<LocaleSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
-    tokio::fs::write(&config_path, content)
-        .await
-        .map_err(|e| CommandError::Vault(format!("Failed to write vault config: {}", e)))?;
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.locale_settings)
+}
 
-    info!("Saved template settings");
+/// Save locale settings to vault config.
+///
+/// Due dates like `^next-week` that were already indexed keep their date
+/// until their note is indexed again.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_locale_settings(state: State<'_, AppState>, settings: LocaleSettings) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.locale_settings = settings;
+    write_vault_config(&config_path, &config).await?;
+    vault.set_locale_settings(config.locale_settings).await;
+
+    info!("Saved locale settings");
     Ok(())
 }
 
 /// List all template files in the templates/ folder.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn list_templates(state: State<'_, AppState>) -> Result<Vec<String>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -132,6 +181,7 @@ pub async fn list_templates(state: State<'_, AppState>) -> Result<Vec<String>> {
 
 /// Create or open a daily note for the given date.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn create_daily_note(
     state: State<'_, AppState>,
     date: String,
@@ -144,84 +194,164 @@ pub async fn create_daily_note(
         .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
 
     // Get template settings
-    let config_path = vault.fs().config_path();
-    let settings: TemplateSettings = if config_path.exists() {
-        let content = tokio::fs::read_to_string(&config_path)
-            .await
-            .map_err(|e| CommandError::Vault(format!("Failed to read vault config: {}", e)))?;
+    let settings = read_vault_config(&vault.fs().config_path())
+        .await
+        .map(|c| c.template_settings)
+        .unwrap_or_default();
 
-        serde_json::from_str::<VaultConfig>(&content)
-            .map(|c| c.template_settings)
-            .unwrap_or_default()
-    } else {
-        TemplateSettings::default()
-    };
+    ensure_daily_note(vault, &settings, parsed_date).await
+}
 
-    // Create template context
-    let ctx = TemplateContext::for_date(parsed_date);
+/// Get the daily note for a date, creating it from the template if needed.
+pub(super) async fn ensure_daily_note(
+    vault: &Vault,
+    settings: &TemplateSettings,
+    date: NaiveDate,
+) -> Result<DailyNoteResult> {
+    let daily = periodic::periodic_note(vault, settings, date, NotePeriod::Daily, true)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    let id = daily
+        .note_id
+        .ok_or_else(|| CommandError::Vault(format!("Daily note could not be indexed: {}", daily.path)))?;
+    let note = vault
+        .repo()
+        .get_note(id)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
 
-    // Render the file path
-    let note_path = render_template(&settings.daily_note_pattern, &ctx);
+    debug!("Daily note {}: {}", if daily.created { "created" } else { "already exists" }, note.path);
+    Ok(DailyNoteResult {
+        id: note.id,
+        path: note.path,
+        title: note.title,
+        created: daily.created,
+    })
+}
 
-    // Check if note already exists
-    if vault.fs().exists(Path::new(&note_path)).await {
-        // Note exists, return its info
-        let note = vault
-            .repo()
-            .get_note_by_path(&note_path)
-            .await
-            .map_err(|e| CommandError::Vault(e.to_string()))?;
-
-        debug!("Daily note already exists: {}", note_path);
-        return Ok(DailyNoteResult {
-            id: note.id,
-            path: note.path,
-            title: note.title,
-            created: false,
-        });
-    }
+/// Get the daily, weekly or monthly note for the period containing `date`.
+/// With `create`, a missing note is created from the period's template.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_periodic_note_for(
+    state: State<'_, AppState>,
+    date: String,
+    period: NotePeriod,
+    create: Option<bool>,
+) -> Result<PeriodicNote> {
+    let date = parse_date(&date)?;
+    periodic_note_for(&state, date, period, create.unwrap_or(false)).await
+}
 
-    // Get template content
-    let template_content = if let Some(ref template_path) = settings.daily_template_path {
-        // Try to read the template file
-        match vault.fs().read_file(Path::new(template_path)).await {
-            Ok(content) => content,
-            Err(e) => {
-                debug!("Failed to read template file {}: {}, using default", template_path, e);
-                DEFAULT_TEMPLATE.to_string()
-            }
-        }
-    } else {
-        DEFAULT_TEMPLATE.to_string()
+/// Get the periodic note before or after the one for `date` (the previous
+/// or next day, week or month), for journal navigation. With `create`, a
+/// missing note is created from the period's template.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_adjacent_periodic_note(
+    state: State<'_, AppState>,
+    date: String,
+    period: NotePeriod,
+    direction: PeriodDirection,
+    create: Option<bool>,
+) -> Result<PeriodicNote> {
+    let first_weekday = {
+        let vault_guard = state.vault.read().await;
+        let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+        vault.locale_settings().await.week_start
     };
+    let date = periodic::adjacent_period(parse_date(&date)?, period, direction, first_weekday);
+    periodic_note_for(&state, date, period, create.unwrap_or(false)).await
+}
 
-    // Render the template content
-    let rendered_content = render_template(&template_content, &ctx);
+/// Refresh the links to the daily notes and the completed-tasks query in the
+/// weekly note for the week containing `week` (YYYY-MM-DD), creating the
+/// note if it doesn't exist.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn update_weekly_rollup(state: State<'_, AppState>, week: String) -> Result<PeriodicNote> {
+    update_rollup(&state, &week, NotePeriod::Weekly).await
+}
 
-    // Create the note
-    let note_id = vault
-        .write_note(&note_path, &rendered_content)
+/// Refresh the links to the weekly notes and the completed-tasks query in
+/// the monthly note for the month containing `month` (YYYY-MM-DD), creating
+/// the note if it doesn't exist.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn update_monthly_rollup(state: State<'_, AppState>, month: String) -> Result<PeriodicNote> {
+    update_rollup(&state, &month, NotePeriod::Monthly).await
+}
+
+async fn update_rollup(state: &AppState, date: &str, period: NotePeriod) -> Result<PeriodicNote> {
+    let date = parse_date(date)?;
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let settings = read_vault_config(&vault.fs().config_path())
         .await
-        .map_err(|e| CommandError::Vault(e.to_string()))?;
+        .map(|c| c.template_settings)
+        .unwrap_or_default();
 
-    info!("Created daily note: {} (id={})", note_path, note_id);
+    periodic::update_rollup(vault, &settings, date, period)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
 
-    // Extract title from path
-    let title = Path::new(&note_path)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_string());
+/// Get the ISO 8601 week (year, number and Monday to Sunday) containing `date`.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_week_info(date: String) -> Result<WeekInfo> {
+    Ok(periodic::get_week_info(parse_date(&date)?))
+}
 
-    Ok(DailyNoteResult {
-        id: note_id,
-        path: note_path,
-        title,
-        created: true,
-    })
+async fn periodic_note_for(state: &AppState, date: NaiveDate, period: NotePeriod, create: bool) -> Result<PeriodicNote> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let settings = read_vault_config(&vault.fs().config_path())
+        .await
+        .map(|c| c.template_settings)
+        .unwrap_or_default();
+
+    periodic::periodic_note(vault, &settings, date, period, create)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))
+}
+
+/// Get the journal prompt for a date from the rotating prompt list in vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_journal_prompt(state: State<'_, AppState>, date: String) -> Result<Option<String>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let parsed_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| CommandError::Vault(format!("Invalid date format: {}. Expected YYYY-MM-DD", e)))?;
+
+    let config_path = vault.fs().config_path();
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let content = tokio::fs::read_to_string(&config_path)
+        .await
+        .map_err(|e| CommandError::Vault(format!("Failed to read vault config: {}", e)))?;
+
+    let settings = serde_json::from_str::<VaultConfig>(&content)
+        .map(|c| c.template_settings)
+        .unwrap_or_default();
+
+    Ok(journal_prompt_for_date(&settings.journal_prompts, parsed_date).map(|p| p.to_string()))
 }
 
 /// Create a new note from a template.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn create_note_from_template(
     state: State<'_, AppState>,
     target_path: String,
@@ -250,8 +380,12 @@ pub async fn create_note_from_template(
             ))
         })?;
 
-    // Create template context with current date
-    let ctx = TemplateContext::default();
+    // Create template context with current date and the vault's variables
+    let settings = read_vault_config(&vault.fs().config_path())
+        .await
+        .map(|c| c.template_settings)
+        .unwrap_or_default();
+    let ctx = TemplateContext::default().with_settings(&settings);
 
     // Render template with variable substitution
     let rendered_content = render_template(&template_content, &ctx);
@@ -270,8 +404,124 @@ pub async fn create_note_from_template(
     Ok(note_id)
 }
 
+/// Render template text with the built-in, user-defined and computed
+/// variables and the vault's snippets, e.g. for quick capture or an
+/// inserted snippet. The context gives the date (default today), the
+/// active note's title and the clipboard text.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn render_template_string(
+    state: State<'_, AppState>,
+    text: String,
+    context: Option<TemplateRenderContext>,
+) -> Result<String> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let settings = read_vault_config(&vault.fs().config_path())
+        .await
+        .map(|c| c.template_settings)
+        .unwrap_or_default();
+
+    let ctx = render_context(&settings, context.unwrap_or_default())?;
+    Ok(render_template(&text, &ctx))
+}
+
+/// Render quick capture text like `render_template_string` and append it to
+/// the daily note for the context's date (default today), creating the note
+/// if needed. Locked encrypted notes are refused. Returns the daily note
+/// with its new content.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn quick_capture(
+    state: State<'_, AppState>,
+    text: String,
+    context: Option<TemplateRenderContext>,
+) -> Result<NoteContent> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let settings = read_vault_config(&vault.fs().config_path())
+        .await
+        .map(|c| c.template_settings)
+        .unwrap_or_default();
+    let context = context.unwrap_or_default();
+    let date = match &context.date {
+        Some(date) => parse_date(date)?,
+        None => chrono::Local::now().date_naive(),
+    };
+
+    let captured = render_template(&text, &render_context(&settings, context)?);
+    let daily = ensure_daily_note(vault, &settings, date).await?;
+    notes::append_to_note(vault, &daily.path, &captured)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// The template context for rendering text with the values from the app.
+fn render_context(settings: &TemplateSettings, context: TemplateRenderContext) -> Result<TemplateContext> {
+    let mut ctx = match context.date {
+        Some(date) => TemplateContext::for_date(parse_date(&date)?),
+        None => TemplateContext::default(),
+    }
+    .with_settings(settings);
+    if let Some(title) = context.note_title {
+        ctx = ctx.with_note_title(title);
+    }
+    if let Some(clipboard) = context.clipboard {
+        ctx = ctx.with_clipboard(clipboard);
+    }
+    Ok(ctx)
+}
+
+/// List the note types for the new-note dialog (see `core_domain::note_types`).
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_note_types(state: State<'_, AppState>) -> Result<Vec<NoteTypeDefinition>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+
+    note_types::list_note_types(vault, &config.note_types)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Save the note types to vault config. An empty list restores the built-in types.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_note_types(state: State<'_, AppState>, types: Vec<NoteTypeDefinition>) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.note_types = types;
+    write_vault_config(&config_path, &config).await?;
+
+    info!("Saved {} note types", config.note_types.len());
+    Ok(())
+}
+
+/// Create a note of a type: in the type's folder, from its template, with
+/// its `type` and default properties set.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn create_typed_note(state: State<'_, AppState>, note_type: String, title: String) -> Result<NoteDto> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+
+    note_types::create_typed_note(vault, &config.note_types, &note_type, &title)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
 /// Render a preview of the daily note path for a given date (for settings UI).
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn preview_daily_note_path(
     pattern: String,
     date: String,
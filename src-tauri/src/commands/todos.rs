@@ -1,14 +1,16 @@
 //! Todo/Task commands.
 
 use crate::state::AppState;
-use shared_types::{TaskQuery, TaskWithContext, TodoDto};
-use tauri::State;
+use chrono::Local;
+use shared_types::{ContextDto, ContextMeta, TaskQuery, TaskSuggestion, TaskWithContext, TodoDto};
+use tauri::{AppHandle, State, Window};
 use tracing::instrument;
 
 use super::{CommandError, Result};
 
 /// Get todos for a specific note.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_todos_for_note(state: State<'_, AppState>, note_id: i64) -> Result<Vec<TodoDto>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -19,25 +21,41 @@ pub async fn get_todos_for_note(state: State<'_, AppState>, note_id: i64) -> Res
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
 
-/// Toggle a todo's completion status.
+/// Toggle a todo's completion status. Every window is sent the note's new
+/// content (`note:saved`).
 #[tauri::command]
-#[instrument(skip(state))]
+#[instrument(skip(state, app, window))]
 pub async fn toggle_todo(
     state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
     todo_id: i64,
     completed: bool,
 ) -> Result<()> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
 
+    // Todos are indexed anew with the note, so look up its path first
+    let todo = vault.repo().get_todo(todo_id).await.ok().flatten();
+    let path = match todo {
+        Some(todo) => vault.repo().get_note(todo.note_id).await.ok().map(|note| note.path),
+        None => None,
+    };
+
     vault
         .toggle_todo(todo_id, completed)
         .await
-        .map_err(|e| CommandError::Vault(e.to_string()))
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
+    if let Some(path) = path {
+        super::notes::emit_note_saved(&app, &window, vault, &path, None).await;
+    }
+    Ok(())
 }
 
 /// Get all incomplete todos.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_incomplete_todos(state: State<'_, AppState>) -> Result<Vec<TodoDto>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -50,6 +68,7 @@ pub async fn get_incomplete_todos(state: State<'_, AppState>) -> Result<Vec<Todo
 
 /// Query tasks with filters, returning enriched context from parent notes.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn query_tasks(
     state: State<'_, AppState>,
     query: TaskQuery,
@@ -66,6 +85,7 @@ pub async fn query_tasks(
 
 /// Get all distinct contexts used in tasks.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_task_contexts(state: State<'_, AppState>) -> Result<Vec<String>> {
     let vault_guard = state.vault.read().await;
     let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
@@ -76,3 +96,72 @@ pub async fn get_task_contexts(state: State<'_, AppState>) -> Result<Vec<String>
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))
 }
+
+/// List registered contexts and the contexts used by tasks, with open-task counts.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn list_contexts(state: State<'_, AppState>) -> Result<Vec<ContextDto>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .list_contexts()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Register a context or update its icon, description and location.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn set_context(state: State<'_, AppState>, context: ContextMeta) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .set_context(&context)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Remove a context from the registry.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn delete_context(state: State<'_, AppState>, name: String) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .delete_context(&name)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Suggest what to work on now, ranked by context match, due date proximity,
+/// priority, and whether the estimated effort fits the available window.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn suggest_next_tasks(
+    state: State<'_, AppState>,
+    context: Option<String>,
+    available_minutes: Option<i32>,
+    energy: Option<String>,
+    limit: Option<i32>,
+) -> Result<Vec<TaskSuggestion>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .suggest_next_tasks(
+            Local::now().date_naive(),
+            context.as_deref(),
+            available_minutes,
+            energy.as_deref(),
+            limit.unwrap_or(10),
+        )
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
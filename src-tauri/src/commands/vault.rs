@@ -1,27 +1,183 @@
-//! Vault commands - opening, closing, and vault info.
+//! Vault commands - creating, opening, closing, windows sharing the vault, vault info, where the database lives, the change journal, index size limits, and index verification.
 
 use crate::state::AppState;
-use core_domain::Vault;
-use shared_types::VaultInfo;
-use tauri::{AppHandle, Emitter, State};
-use tracing::{info, instrument};
+use core_domain::{demo_vault, index_audit, scaffold, Vault};
+use core_fs::{copy_data_dir, external_data_dir, remove_data_dir, VaultFs};
+use shared_types::{
+    ChangesPage, CreateVaultOptions, DataLocation, DiagramSettings, ExternalFolder, IndexCompletePayload, IndexSettings, IndexVerificationReport, JobRequest,
+    StorageInfo, VaultInfo,
+};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder, Window};
+use tracing::{info, instrument, warn};
 
+use super::app_settings::diagram_renderer_path;
+use super::jobs::enqueue_job;
+use super::templates::{read_vault_config, write_vault_config, VaultConfig};
 use super::{CommandError, Result};
 
+/// Number of the next window opened with `open_window`.
+static NEXT_WINDOW: AtomicU32 = AtomicU32::new(1);
+
 /// Open a vault at the given path.
+///
+/// If another window already has this vault open, the window shares it:
+/// one index, one file watcher, and events go to every window. A window
+/// can't open a different vault while other windows use the open one.
 #[tauri::command]
-#[instrument(skip(state, app))]
+#[instrument(skip(state, app, window))]
 pub async fn open_vault(
     state: State<'_, AppState>,
     app: AppHandle,
+    window: Window,
     path: String,
 ) -> Result<VaultInfo> {
     info!("Opening vault: {}", path);
+    let path = Path::new(&path);
+    let _lifecycle = state.vault_lifecycle.lock().await;
+
+    if let Some(info) = shared_vault_info(&state, path).await? {
+        info!("Vault already open, shared with window {}", window.label());
+        state.attach_window(window.label());
+        return Ok(info);
+    }
+
+    ensure_vault_not_shared(&state, window.label())?;
+    let info = load_vault(&state, &app, path).await?;
+    state.attach_window(window.label());
+    Ok(info)
+}
+
+/// Info on the open vault if it is the one at `path`.
+async fn shared_vault_info(state: &AppState, path: &Path) -> Result<Option<VaultInfo>> {
+    let vault_guard = state.vault.read().await;
+    let Some(vault) = vault_guard.as_ref() else {
+        return Ok(None);
+    };
+    let same = match (vault.root_path().canonicalize(), path.canonicalize()) {
+        (Ok(open), Ok(requested)) => open == requested,
+        _ => vault.root_path() == path,
+    };
+    if !same {
+        return Ok(None);
+    }
+
+    vault
+        .info()
+        .await
+        .map(Some)
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Fail if other windows use the open vault, which opening another vault
+/// in `window` would close under them.
+fn ensure_vault_not_shared(state: &AppState, window: &str) -> Result<()> {
+    if state.vault_used_elsewhere(window) {
+        return Err(CommandError::Vault(
+            "The open vault is used by another window; open other vaults there or after closing it".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Create a new vault in an empty (or missing) folder: the `.neuroflow`
+/// folder, a starter config, default folders, sample templates and a
+/// welcome note. The vault is then opened like `open_vault`.
+#[tauri::command]
+#[instrument(skip(state, app, window))]
+pub async fn create_vault(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
+    path: String,
+    options: Option<CreateVaultOptions>,
+) -> Result<VaultInfo> {
+    info!("Creating vault: {}", path);
+    let _lifecycle = state.vault_lifecycle.lock().await;
+    ensure_vault_not_shared(&state, window.label())?;
+    let path = PathBuf::from(path);
+
+    let template_settings = scaffold::scaffold_vault(&path, &options.unwrap_or_default())
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    let config = VaultConfig {
+        template_settings,
+        ..VaultConfig::default()
+    };
+    write_vault_config(&VaultFs::new(&path).config_path(), &config).await?;
+
+    let info = load_vault(&state, &app, &path).await?;
+    state.attach_window(window.label());
+    Ok(info)
+}
+
+/// Create a demo vault in an empty (or missing) folder: example projects,
+/// dashboards, daily notes, habits and schedule blocks, dated around today.
+/// The vault is then opened like `open_vault`.
+#[tauri::command]
+#[instrument(skip(state, app, window))]
+pub async fn create_demo_vault(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    window: Window,
+    path: String,
+) -> Result<VaultInfo> {
+    info!("Creating demo vault: {}", path);
+    let _lifecycle = state.vault_lifecycle.lock().await;
+    ensure_vault_not_shared(&state, window.label())?;
+    let path = PathBuf::from(path);
+
+    let today = chrono::Local::now().date_naive();
+    let template_settings = demo_vault::create_demo_vault(&path, today)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+    let config = VaultConfig {
+        template_settings,
+        ..VaultConfig::default()
+    };
+    write_vault_config(&VaultFs::new(&path).config_path(), &config).await?;
+
+    let info = load_vault(&state, &app, &path).await?;
+    state.attach_window(window.label());
+    Ok(info)
+}
+
+/// Open, index and watch a vault, and make it the current one.
+async fn load_vault(state: &AppState, app: &AppHandle, path: &Path) -> Result<VaultInfo> {
+    // A config that can't be read shouldn't keep the vault from opening
+    let config = match read_vault_config(&VaultFs::new(path).config_path()).await {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Using the default vault config: {}", e);
+            VaultConfig::default()
+        }
+    };
+    let data_dir = match config.storage_settings.data_location {
+        DataLocation::Vault => None,
+        DataLocation::AppData => Some(app_data_location(app, path)?),
+    };
 
     // Open the vault
-    let mut vault = Vault::open(&path)
+    let mut vault = Vault::open_with_data_dir(path, data_dir)
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
+    vault.set_link_format(config.link_settings.link_format).await;
+    vault.set_keep_old_titles(config.link_settings.keep_old_titles).await;
+    vault
+        .set_diagram_settings(DiagramSettings {
+            renderer_path: diagram_renderer_path(app).await,
+            ..config.diagram_settings.clone()
+        })
+        .await;
+    vault.set_locale_settings(config.locale_settings.clone()).await;
+    vault.set_index_settings(config.index_settings.clone()).await;
+    vault.set_template_settings(config.template_settings.clone()).await;
+    // A folder that is gone or now overlaps the vault shouldn't keep the
+    // vault from opening
+    if let Err(e) = vault.set_external_folders(&config.external_folders).await {
+        warn!("External folders not attached: {}", e);
+    }
 
     // Subscribe to events and forward to frontend
     let mut rx = vault.subscribe();
@@ -44,10 +200,20 @@ pub async fn open_vault(
                 core_domain::vault::VaultEvent::IndexComplete(payload) => {
                     let _ = app_clone.emit("index:complete", payload);
                 }
+                core_domain::vault::VaultEvent::JobProgress(payload) => {
+                    let _ = app_clone.emit("job:progress", payload);
+                }
             }
         }
     });
 
+    // Jobs that were running when the app quit start over
+    vault
+        .repo()
+        .requeue_interrupted_jobs()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))?;
+
     // Perform initial index
     vault
         .full_index()
@@ -66,28 +232,99 @@ pub async fn open_vault(
         .await
         .map_err(|e| CommandError::Vault(e.to_string()))?;
 
-    // Store in state
-    *state.vault.write().await = Some(vault);
+    // Store in state, stopping the watcher of the vault it replaces
+    let root = vault.root_path().to_path_buf();
+    state.cancel_vault_jobs().await;
+    if let Some(mut previous) = state.vault.write().await.replace(vault) {
+        state.clear_vault_jobs(previous.root_path());
+        super::clear_edit_sessions(&app, &state);
+        previous.stop_watcher().await;
+    }
+
+    // Keep calendar subscriptions up to date while the vault is open
+    state.abort_vault_tasks();
+    state.add_vault_task(super::calendar_subscriptions::spawn_calendar_refresh(state.vault.clone(), root.clone()));
+
+    // Create scheduled notes, catching up on those missed while the app was closed
+    state.add_vault_task(core_domain::scheduled_notes::spawn_scheduler(state.vault.clone(), root));
+
+    // Run jobs that were queued, or interrupted by quitting, last time
+    super::jobs::spawn_job_worker(state, app.clone());
 
     Ok(info)
 }
 
-/// Close the current vault.
+/// Close the current vault for this window. It stays open while other
+/// windows use it.
 #[tauri::command]
-#[instrument(skip(state))]
-pub async fn close_vault(state: State<'_, AppState>) -> Result<()> {
+#[instrument(skip(state, app, window))]
+pub async fn close_vault(state: State<'_, AppState>, app: AppHandle, window: Window) -> Result<()> {
+    super::release_edit_sessions(&window);
+    let _lifecycle = state.vault_lifecycle.lock().await;
+    if !state.detach_window(window.label()) {
+        info!("Vault still used by other windows");
+        return Ok(());
+    }
     info!("Closing vault");
 
+    state.cancel_vault_jobs().await;
+    state.abort_vault_tasks();
     let mut vault_guard = state.vault.write().await;
     if let Some(mut vault) = vault_guard.take() {
+        state.clear_vault_jobs(vault.root_path());
+        super::clear_edit_sessions(&app, &state);
         vault.stop_watcher().await;
     }
 
     Ok(())
 }
 
+/// Release the vault of a window that was closed, closing the vault if no
+/// other window uses it.
+pub fn release_window_vault(window: &Window) {
+    let state = window.state::<AppState>().inner().clone();
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    tauri::async_runtime::spawn(async move {
+        // Detached under the lock, so a vault another window is opening
+        // meanwhile isn't closed
+        let _lifecycle = state.vault_lifecycle.lock().await;
+        if !state.detach_attached_window(&label) {
+            return;
+        }
+
+        state.cancel_vault_jobs().await;
+        state.abort_vault_tasks();
+        if let Some(mut vault) = state.vault.write().await.take() {
+            state.clear_vault_jobs(vault.root_path());
+            super::clear_edit_sessions(&app, &state);
+            vault.stop_watcher().await;
+            info!("Closed vault after its last window closed");
+        }
+    });
+}
+
+/// Open another app window. It opens the last used vault like the first
+/// window does, sharing it with the other windows.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn open_window(app: AppHandle) -> Result<String> {
+    let label = format!("window-{}", NEXT_WINDOW.fetch_add(1, Ordering::Relaxed));
+    WebviewWindowBuilder::new(&app, &label, WebviewUrl::default())
+        .title("NeuroFlow Notes")
+        .inner_size(1200.0, 800.0)
+        .min_inner_size(800.0, 600.0)
+        .disable_drag_drop_handler()
+        .build()
+        .map_err(|e| CommandError::Vault(format!("Failed to open window: {}", e)))?;
+
+    info!("Opened window {}", label);
+    Ok(label)
+}
+
 /// Get information about the current vault.
 #[tauri::command]
+#[instrument(skip_all)]
 pub async fn get_vault_info(state: State<'_, AppState>) -> Result<Option<VaultInfo>> {
     let vault_guard = state.vault.read().await;
     if let Some(vault) = vault_guard.as_ref() {
@@ -100,3 +337,229 @@ pub async fn get_vault_info(state: State<'_, AppState>) -> Result<Option<VaultIn
         Ok(None)
     }
 }
+
+/// Get where the current vault's database and caches are stored.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_storage_info(state: State<'_, AppState>) -> Result<StorageInfo> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(StorageInfo {
+        data_location: config.storage_settings.data_location,
+        data_dir: vault.fs().data_dir().to_string_lossy().to_string(),
+    })
+}
+
+/// Move the current vault's database and caches to `location`.
+///
+/// The vault is closed while the files are copied and then reopened. The
+/// old files are only removed once the copy is complete and the vault
+/// config points at it, so a failure at any step reopens the vault where
+/// it was, with its data intact.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn move_vault_data(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    location: DataLocation,
+) -> Result<StorageInfo> {
+    let _lifecycle = state.vault_lifecycle.lock().await;
+    state.cancel_vault_jobs().await;
+    let mut vault_guard = state.vault.write().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let root = vault.root_path().to_path_buf();
+    let from = vault.fs().data_dir().to_path_buf();
+    let to = match location {
+        DataLocation::Vault => root.join(".neuroflow"),
+        DataLocation::AppData => app_data_location(&app, &root)?,
+    };
+    let info = StorageInfo {
+        data_location: location,
+        data_dir: to.to_string_lossy().to_string(),
+    };
+
+    if from == to {
+        save_data_location(&root, location).await?;
+        return Ok(info);
+    }
+
+    // The database has to be closed while its files are moved
+    state.abort_vault_tasks();
+    if let Some(vault) = vault_guard.take() {
+        state.clear_vault_jobs(vault.root_path());
+        vault.close().await;
+    }
+    drop(vault_guard);
+
+    let saved = match copy_data_dir(&from, &to).await {
+        Ok(count) => match save_data_location(&root, location).await {
+            Ok(()) => {
+                info!("Moved {} data entries from {} to {}", count, from.display(), to.display());
+                if let Err(e) = remove_data_dir(&from).await {
+                    warn!("Failed to remove old vault data in {}: {}", from.display(), e);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                // The config still points at the old data; drop the copy
+                let _ = remove_data_dir(&to).await;
+                Err(e)
+            }
+        },
+        Err(e) => Err(CommandError::Vault(format!("Failed to move vault data: {}", e))),
+    };
+
+    // Reopen wherever the config now points
+    load_vault(&state, &app, &root).await?;
+    saved?;
+
+    Ok(info)
+}
+
+/// Get the read-only external folders indexed with the current vault.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_external_folders(state: State<'_, AppState>) -> Result<Vec<ExternalFolder>> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.external_folders)
+}
+
+/// Replace the read-only external folders and queue a full index, so
+/// notes in added folders are indexed and those in removed folders dropped.
+#[tauri::command]
+#[instrument(skip(state, app))]
+pub async fn set_external_folders(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    folders: Vec<ExternalFolder>,
+) -> Result<Vec<ExternalFolder>> {
+    {
+        let vault_guard = state.vault.read().await;
+        let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+        vault
+            .validate_external_folders(&folders)
+            .map_err(|e| CommandError::Vault(e.to_string()))?;
+    }
+
+    state.cancel_vault_jobs().await;
+    let config = {
+        let mut vault_guard = state.vault.write().await;
+        let vault = vault_guard.as_mut().ok_or(CommandError::NoVaultOpen)?;
+
+        let config_path = vault.fs().config_path();
+        let mut config = read_vault_config(&config_path).await?;
+        config.external_folders = folders;
+        vault
+            .set_external_folders(&config.external_folders)
+            .await
+            .map_err(|e| CommandError::Vault(e.to_string()))?;
+        write_vault_config(&config_path, &config).await?;
+        config
+    };
+
+    // Indexed as a job, without holding the vault lock
+    enqueue_job(&state, app, JobRequest::FullIndex).await?;
+
+    info!("Saved {} external folders", config.external_folders.len());
+    Ok(config.external_folders)
+}
+
+/// Get the size limit for indexing notes from vault config.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_index_settings(state: State<'_, AppState>) -> Result<IndexSettings> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config = read_vault_config(&vault.fs().config_path()).await?;
+    Ok(config.index_settings)
+}
+
+/// Save the size limit for indexing notes to vault config. Notes are
+/// indexed with the new limit the next time they change.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn save_index_settings(state: State<'_, AppState>, settings: IndexSettings) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    let config_path = vault.fs().config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.index_settings = settings;
+    write_vault_config(&config_path, &config).await?;
+    vault.set_index_settings(config.index_settings).await;
+
+    info!("Saved index settings");
+    Ok(())
+}
+
+/// Get note changes journaled after `cursor` (0 for all), at most `limit`.
+/// Pass the returned cursor to the next call to get only newer changes.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn get_changes_since(
+    state: State<'_, AppState>,
+    cursor: u64,
+    limit: Option<usize>,
+) -> Result<ChangesPage> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .changes_since(cursor, limit)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Re-hash every file and compare it to the index, listing notes whose
+/// database copy is stale and why. With `reindex`, only the stale notes are
+/// re-indexed (and notes of deleted files removed).
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn verify_index(state: State<'_, AppState>, reindex: Option<bool>) -> Result<IndexVerificationReport> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    index_audit::verify_index(vault, reindex.unwrap_or(false))
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Rescan just one folder (e.g. `projects/` after a `git pull`) instead of
+/// the whole vault, reindexing changed notes and removing deleted ones.
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn reindex_path(state: State<'_, AppState>, prefix: String) -> Result<IndexCompletePayload> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .reindex_path(&prefix)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+async fn save_data_location(vault_root: &Path, location: DataLocation) -> Result<()> {
+    let config_path = VaultFs::new(vault_root).config_path();
+    let mut config = read_vault_config(&config_path).await.unwrap_or_default();
+    config.storage_settings.data_location = location;
+    write_vault_config(&config_path, &config).await?;
+
+    info!("Saved storage settings");
+    Ok(())
+}
+
+/// The vault's data directory under the app's data directory.
+fn app_data_location(app: &AppHandle, vault_root: &Path) -> Result<PathBuf> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::Vault(format!("Failed to get app data directory: {}", e)))?;
+    Ok(external_data_dir(&app_data, vault_root))
+}
@@ -0,0 +1,36 @@
+//! Workspace commands - saving and restoring the UI session per vault.
+
+use crate::state::AppState;
+use shared_types::WorkspaceState;
+use tauri::State;
+use tracing::instrument;
+
+use super::{CommandError, Result};
+
+/// Save open tabs, the active tab, and the sidebar layout.
+#[tauri::command]
+#[instrument(skip(state, workspace))]
+pub async fn save_workspace(state: State<'_, AppState>, workspace: WorkspaceState) -> Result<()> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .save_workspace(&workspace)
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
+
+/// Load the saved workspace. Tabs of deleted notes are left out.
+#[tauri::command]
+#[instrument(skip_all)]
+pub async fn load_workspace(state: State<'_, AppState>) -> Result<WorkspaceState> {
+    let vault_guard = state.vault.read().await;
+    let vault = vault_guard.as_ref().ok_or(CommandError::NoVaultOpen)?;
+
+    vault
+        .repo()
+        .load_workspace()
+        .await
+        .map_err(|e| CommandError::Vault(e.to_string()))
+}
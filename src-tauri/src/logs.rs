@@ -0,0 +1,43 @@
+//! Log capture for diagnostics bundles.
+//!
+//! A second fmt layer writes its formatted events here, into the app's
+//! `RecentLogs`, so `export_diagnostics` can include the recent log lines.
+
+use core_domain::diagnostics::RecentLogs;
+use std::io;
+use std::sync::Arc;
+use tracing_subscriber::fmt::MakeWriter;
+
+pub struct LogCapture {
+    logs: Arc<RecentLogs>,
+}
+
+impl LogCapture {
+    pub fn new(logs: Arc<RecentLogs>) -> Self {
+        Self { logs }
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogCapture {
+    type Writer = LogWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogWriter { logs: &self.logs }
+    }
+}
+
+/// Writer for one event; the fmt layer writes each event in one go.
+pub struct LogWriter<'a> {
+    logs: &'a RecentLogs,
+}
+
+impl io::Write for LogWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.logs.push(&String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
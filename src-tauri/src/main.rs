@@ -3,18 +3,37 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod logs;
 mod state;
 mod stream;
 
+use core_domain::perf::MetricsLayer;
+use logs::LogCapture;
 use state::AppState;
-use tracing::info;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tauri::Manager;
+use tracing::{info, warn};
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*, EnvFilter};
+
+/// Target of the spans of command functions, timed by the metrics layer.
+const COMMANDS_TARGET: &str = concat!(env!("CARGO_CRATE_NAME"), "::commands");
+
+/// Label of the window opened on startup.
+const MAIN_WINDOW: &str = "main";
 
 fn main() {
-    // Initialize logging
+    let state = AppState::new();
+
+    // Initialize logging, recent logs for diagnostics bundles, and metrics
+    // whatever the log level
     tracing_subscriber::registry()
-        .with(fmt::layer())
-        .with(EnvFilter::from_default_env().add_directive("neuroflow=debug".parse().unwrap()))
+        .with(fmt::layer().with_filter(EnvFilter::from_default_env().add_directive("neuroflow=debug".parse().unwrap())))
+        .with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(LogCapture::new(state.logs.clone()))
+                .with_filter(LevelFilter::INFO),
+        )
+        .with(MetricsLayer::new(state.perf.clone(), COMMANDS_TARGET).with_filter(MetricsLayer::filter(COMMANDS_TARGET)))
         .init();
 
     info!("Starting NeuroFlow Notes");
@@ -23,22 +42,71 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .register_uri_scheme_protocol("stream", stream::handle_stream_protocol)
-        .manage(AppState::new())
+        .manage(state)
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } if window.label() == MAIN_WINDOW => {
+                // Close once the layout is saved, without blocking the main thread
+                api.prevent_close();
+                let window = window.clone();
+                tauri::async_runtime::spawn(async move {
+                    commands::save_window_layout(&window).await;
+                    if let Err(e) = window.destroy() {
+                        warn!("Failed to close the main window: {}", e);
+                    }
+                });
+            }
+            tauri::WindowEvent::Destroyed => {
+                commands::release_edit_sessions(window);
+                commands::release_window_vault(window);
+            }
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
             // Vault
+            commands::create_vault,
+            commands::create_demo_vault,
             commands::open_vault,
             commands::close_vault,
+            commands::open_window,
             commands::get_vault_info,
+            commands::get_storage_info,
+            commands::move_vault_data,
+            commands::get_external_folders,
+            commands::set_external_folders,
+            commands::get_changes_since,
+            commands::verify_index,
+            commands::reindex_path,
+            commands::get_index_settings,
+            commands::save_index_settings,
             // Notes
             commands::list_notes,
             commands::get_note,
+            commands::get_note_summary,
+            commands::analyze_readability,
+            commands::renumber_footnotes,
+            commands::format_table,
+            commands::sort_table_by_column,
+            commands::table_add_row,
+            commands::table_add_column,
+            commands::import_csv_as_table,
+            commands::export_table_as_csv,
             commands::get_note_content,
             commands::save_note,
+            commands::save_draft,
+            commands::get_unsaved_drafts,
+            commands::discard_draft,
+            commands::begin_edit,
+            commands::end_edit,
+            commands::create_note,
             commands::rename_note,
+            commands::move_note,
             commands::delete_note,
+            commands::unlock_note,
+            commands::lock_notes,
             // Folders
             commands::create_folder,
             commands::rename_folder,
+            commands::preview_delete,
             commands::delete_folder,
             // Todos
             commands::get_todos_for_note,
@@ -46,18 +114,43 @@ fn main() {
             commands::get_incomplete_todos,
             commands::query_tasks,
             commands::get_task_contexts,
+            commands::list_contexts,
+            commands::set_context,
+            commands::delete_context,
+            commands::suggest_next_tasks,
             // Tags & Backlinks
             commands::list_tags,
+            commands::list_tag_meta,
+            commands::set_tag_meta,
+            commands::delete_tag_meta,
             commands::get_backlinks,
+            commands::get_orphan_notes,
+            commands::get_dead_end_notes,
+            commands::get_hub_notes,
+            commands::export_graph,
+            commands::get_ambiguous_link_targets,
+            commands::resolve_note,
+            commands::get_link_settings,
+            commands::save_link_settings,
             // Search
             commands::search_notes,
+            commands::search_image_text,
+            commands::quick_switch,
+            commands::autocomplete,
+            commands::suggest_links,
+            commands::save_search_preset,
+            commands::list_search_presets,
+            commands::delete_search_preset,
             commands::hybrid_search_notes,
             commands::get_embedding_status,
             commands::test_embedding_connection,
             commands::generate_note_embedding,
             commands::get_notes_needing_embeddings,
+            commands::check_embeddings,
             // Folder Tree
             commands::get_folder_tree,
+            commands::set_folder_appearance,
+            commands::reorder_folder_children,
             // Properties
             commands::get_properties,
             commands::set_property,
@@ -69,6 +162,26 @@ fn main() {
             commands::get_schedule_blocks_for_note,
             commands::update_schedule_block,
             commands::delete_schedule_block,
+            // Schedule Templates
+            commands::create_schedule_template,
+            commands::list_schedule_templates,
+            commands::delete_schedule_template,
+            commands::apply_schedule_template,
+            // Calendar Subscriptions
+            commands::add_calendar_subscription,
+            commands::list_calendar_subscriptions,
+            commands::remove_calendar_subscription,
+            commands::refresh_calendar_subscriptions,
+            // Day Planning
+            commands::plan_day,
+            commands::get_workload,
+            commands::get_week_overview,
+            commands::format_agenda_text,
+            // Focus Sessions
+            commands::start_block,
+            commands::end_block,
+            commands::get_plan_vs_actual,
+            commands::get_context_switch_report,
             // Notes by Date
             commands::get_notes_for_date,
             commands::get_notes_for_date_range,
@@ -77,6 +190,44 @@ fn main() {
             commands::get_note_headings,
             // Assets
             commands::save_pasted_image,
+            commands::download_attachment,
+            commands::get_attachment_settings,
+            commands::save_attachment_settings,
+            commands::get_thumbnail,
+            // Web Clipper
+            commands::clip_url,
+            // Books
+            commands::fetch_book_metadata,
+            commands::get_book_shelves,
+            // Rendering
+            commands::render_note_html,
+            commands::render_diagram,
+            commands::get_diagram_settings,
+            commands::save_diagram_settings,
+            commands::get_math_settings,
+            commands::save_math_settings,
+            commands::export_journal,
+            commands::export_slides,
+            // Maintenance
+            commands::find_duplicate_notes,
+            commands::dedupe_attachments,
+            commands::lint_note,
+            commands::lint_vault,
+            commands::apply_lint_fixes,
+            commands::get_lint_settings,
+            commands::save_lint_settings,
+            commands::detect_title_mismatches,
+            commands::fix_title_mismatch,
+            commands::get_title_settings,
+            commands::save_title_settings,
+            commands::get_performance_metrics,
+            commands::reset_performance_metrics,
+            commands::export_diagnostics,
+            commands::get_file_problems,
+            commands::get_vault_growth,
+            // Spell Check
+            commands::check_text,
+            commands::add_to_dictionary,
             // Query Builder
             commands::get_property_keys,
             commands::get_property_values,
@@ -84,6 +235,8 @@ fn main() {
             commands::run_query,
             // Query Embeds
             commands::execute_query_embed,
+            commands::refresh_query_embed,
+            commands::reorder_query_result,
             // Property Management
             commands::rename_property_key,
             commands::rename_property_value,
@@ -92,6 +245,8 @@ fn main() {
             commands::get_property_values_with_counts,
             commands::get_notes_with_property,
             commands::get_notes_with_property_value,
+            commands::get_property_table,
+            commands::batch_set_properties,
             // Folder Properties
             commands::get_folder_properties,
             commands::set_folder_property,
@@ -101,7 +256,19 @@ fn main() {
             // Frontmatter Conversion
             commands::convert_frontmatter_to_db,
             // Import
-            commands::import_obsidian_vault,
+            commands::import_files,
+            commands::import_photos,
+            // Jobs
+            commands::start_full_index,
+            commands::start_vault_import,
+            commands::start_journal_export,
+            commands::start_embedding_backfill,
+            commands::start_backup,
+            commands::start_ocr,
+            commands::list_jobs,
+            commands::cancel_job,
+            commands::retry_job,
+            commands::clear_finished_jobs,
             // Plugins
             commands::read_plugin_config,
             commands::write_plugin_config,
@@ -114,25 +281,75 @@ fn main() {
             commands::update_habit,
             commands::delete_habit,
             commands::archive_habit,
+            commands::set_habit_schedule,
             commands::log_habit_entry,
             commands::get_habit_entries,
             commands::update_habit_entry,
             commands::delete_habit_entry,
             commands::toggle_habit,
+            commands::import_habit_data,
             commands::execute_habit_tracker_embed,
+            // Goals
+            commands::create_goal,
+            commands::list_goals,
+            commands::archive_goal,
+            commands::delete_goal,
+            commands::get_goal_progress,
+            commands::execute_goal_embed,
+            // Mood Tracking
+            commands::log_mood,
+            commands::get_mood,
+            commands::delete_mood,
+            commands::get_mood_trend,
+            // Note Review
+            commands::get_notes_due_for_review,
+            commands::mark_reviewed,
+            // Discovery
+            commands::get_random_note,
+            commands::get_on_this_day,
+            // Workspace
+            commands::save_workspace,
+            commands::load_workspace,
+            // App settings
+            commands::get_app_settings,
+            commands::save_app_settings,
+            commands::migrate_app_settings,
             // Templates
             commands::get_template_settings,
             commands::save_template_settings,
             commands::list_templates,
             commands::create_daily_note,
+            commands::get_journal_prompt,
             commands::create_note_from_template,
+            commands::render_template_string,
+            commands::quick_capture,
+            // Scheduled notes
+            commands::list_scheduled_note_rules,
+            commands::create_scheduled_note_rule,
+            commands::update_scheduled_note_rule,
+            commands::delete_scheduled_note_rule,
+            commands::run_scheduled_notes,
             commands::preview_daily_note_path,
+            commands::get_periodic_note_for,
+            commands::get_adjacent_periodic_note,
+            commands::get_locale_settings,
+            commands::save_locale_settings,
+            commands::update_weekly_rollup,
+            commands::update_monthly_rollup,
+            commands::get_week_info,
+            commands::list_note_types,
+            commands::save_note_types,
+            commands::create_typed_note,
             // Summarizers
             commands::run_link_summarizer,
             commands::run_transcript_summarizer,
             commands::count_pending_transcripts,
         ])
-        .setup(|_app| {
+        .setup(|app| {
+            commands::keep_invalid_app_settings();
+            if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+                commands::restore_window_layout(&window);
+            }
             info!("Tauri app setup complete");
             Ok(())
         })
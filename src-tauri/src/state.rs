@@ -1,23 +1,118 @@
 //! Application state management.
 
-use core_domain::Vault;
+use core_domain::diagnostics::RecentLogs;
+use core_domain::edit_sessions::EditSessions;
+use core_domain::perf::PerformanceRecorder;
+use core_domain::{JobRegistry, Vault};
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::AbortHandle;
 
 /// Global application state.
+#[derive(Clone)]
 pub struct AppState {
-    /// The currently open vault (if any).
+    /// The currently open vault (if any), shared by all windows.
     pub vault: Arc<RwLock<Option<Vault>>>,
+    /// Labels of the windows using the open vault. It is closed when the
+    /// last of them closes it, or is closed itself.
+    pub vault_windows: Arc<std::sync::Mutex<HashSet<String>>>,
+    /// Which window edits each note open in several windows.
+    pub edit_sessions: Arc<EditSessions>,
+    /// Running background jobs of the open vault, for cancellation. Cleared
+    /// when the vault is closed or replaced.
+    pub jobs: Arc<JobRegistry>,
+    /// Held while a vault is opened, closed or moved, so a window opening a
+    /// vault and another closing it don't interleave.
+    pub vault_lifecycle: Arc<Mutex<()>>,
+    /// Held by the job queue worker while it runs, so only one worker runs
+    /// jobs and heavy writes run one at a time.
+    pub job_worker: Arc<Mutex<()>>,
+    /// Command latencies and slow queries, recorded by the metrics layer.
+    pub perf: Arc<PerformanceRecorder>,
+    /// Recent log lines, for diagnostics bundles.
+    pub logs: Arc<RecentLogs>,
+    /// Background tasks of the open vault (like the calendar refresh),
+    /// aborted when it is closed or replaced so reopening it doesn't run
+    /// them twice.
+    pub vault_tasks: Arc<std::sync::Mutex<Vec<AbortHandle>>>,
+    /// Number of the latest `suggest_links` request; earlier ones skip
+    /// embedding their paragraph.
+    pub link_suggestion_request: Arc<AtomicU64>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
             vault: Arc::new(RwLock::new(None)),
+            vault_windows: Arc::new(std::sync::Mutex::new(HashSet::new())),
+            edit_sessions: Arc::new(EditSessions::new()),
+            jobs: Arc::new(JobRegistry::new()),
+            vault_lifecycle: Arc::new(Mutex::new(())),
+            job_worker: Arc::new(Mutex::new(())),
+            perf: Arc::new(PerformanceRecorder::new()),
+            logs: Arc::new(RecentLogs::new()),
+            vault_tasks: Arc::new(std::sync::Mutex::new(Vec::new())),
+            link_suggestion_request: Arc::new(AtomicU64::new(0)),
         }
     }
 }
 
+impl AppState {
+    /// Whether windows other than `window` use the open vault.
+    pub fn vault_used_elsewhere(&self, window: &str) -> bool {
+        self.vault_windows.lock().unwrap().iter().any(|label| label != window)
+    }
+
+    /// Record that `window` uses the open vault.
+    pub fn attach_window(&self, window: &str) {
+        self.vault_windows.lock().unwrap().insert(window.to_string());
+    }
+
+    /// Cancel the running jobs of the open vault before taking it for
+    /// writing. Jobs hold it for reading until they stop, which they do at
+    /// their next item once cancelled.
+    pub async fn cancel_vault_jobs(&self) {
+        let root = self.vault.read().await.as_ref().map(|vault| vault.root_path().to_path_buf());
+        if let Some(root) = root {
+            self.jobs.cancel_vault(&root);
+        }
+    }
+
+    /// Keep a background task of the open vault, to abort with it.
+    pub fn add_vault_task(&self, task: AbortHandle) {
+        self.vault_tasks.lock().unwrap().push(task);
+    }
+
+    /// Stop the background tasks of a vault that is closed or replaced.
+    pub fn abort_vault_tasks(&self) {
+        for task in self.vault_tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+
+    /// Forget the jobs of a vault that was closed or replaced.
+    pub fn clear_vault_jobs(&self, root: &std::path::Path) {
+        self.jobs.clear_vault(root);
+    }
+
+    /// Record that `window` no longer uses the open vault. Returns whether
+    /// it was the last window using it.
+    pub fn detach_window(&self, window: &str) -> bool {
+        let mut windows = self.vault_windows.lock().unwrap();
+        windows.remove(window);
+        windows.is_empty()
+    }
+
+    /// Forget `window` if it uses the open vault. Returns whether it was the
+    /// last window using it, checked under the same lock.
+    pub fn detach_attached_window(&self, window: &str) -> bool {
+        let mut windows = self.vault_windows.lock().unwrap();
+        windows.remove(window) && windows.is_empty()
+    }
+}
+
 impl Default for AppState {
     fn default() -> Self {
         Self::new()
@@ -28,6 +28,8 @@ export { default as DocList } from "./DocList.svelte";
 export { default as SettingsModal } from "./SettingsModal.svelte";
 export { default as ScheduleBlockModal } from "./ScheduleBlockModal.svelte";
 export { default as SearchModal } from "./SearchModal.svelte";
+export { default as DraftRecoveryModal } from "./DraftRecoveryModal.svelte";
+export { default as QuickCaptureModal } from "./QuickCaptureModal.svelte";
 
 // Shared
 export { default as Toast } from "./shared/Toast.svelte";
@@ -31,6 +31,10 @@ export const OPERATOR_LABELS: Record<PropertyOperator, string> = {
   DateAfter: "after",
   DateOnOrBefore: "on or before",
   DateOnOrAfter: "on or after",
+  GreaterThan: "greater than",
+  LessThan: "less than",
+  GreaterOrEqual: "at least",
+  LessOrEqual: "at most",
 };
 
 /** Path filter operators (subset that make sense for paths). */
@@ -67,6 +71,19 @@ export const NUMBER_OPERATORS: Record<string, string> = {
   NotExists: "does not exist",
   Equals: "equals",
   NotEquals: "does not equal",
+  GreaterThan: "greater than",
+  LessThan: "less than",
+  GreaterOrEqual: "at least",
+  LessOrEqual: "at most",
+};
+
+/** Task completion date operators. */
+export const COMPLETED_OPERATORS: Record<string, string> = {
+  DateOn: "on date",
+  DateBefore: "before",
+  DateAfter: "after",
+  DateOnOrBefore: "on or before",
+  DateOnOrAfter: "on or after",
 };
 
 /** Boolean type operators. */
@@ -92,9 +109,31 @@ export const LIST_OPERATORS: Record<string, string> = {
 /** Operators that don't need a value. */
 export const VALUELESS_OPERATORS: PropertyOperator[] = ["Exists", "NotExists"];
 
-/** Operators that accept multiple values (comma-separated). */
+/** Operators that accept multiple values (a JSON array of strings). */
 export const MULTI_VALUE_OPERATORS: PropertyOperator[] = ["ContainsAll", "ContainsAny"];
 
+/**
+ * The values of a multi-value filter. Values that aren't a JSON array are
+ * split on commas, as query embeds written before the arrays did.
+ */
+export function parseFilterValues(value: string | null): string[] {
+  if (!value) return [];
+  try {
+    const parsed = JSON.parse(value);
+    if (Array.isArray(parsed)) {
+      return parsed.map(String);
+    }
+  } catch {
+    // Not JSON
+  }
+  return value.split(",").map((v) => v.trim()).filter((v) => v);
+}
+
+/** A multi-value filter value, or null without values. */
+export function formatFilterValues(values: string[]): string | null {
+  return values.length > 0 ? JSON.stringify(values) : null;
+}
+
 /** Date comparison operators. */
 export const DATE_OPERATORS: PropertyOperator[] = [
   "DateOn",
@@ -112,6 +151,7 @@ export const DATE_OPERATORS: PropertyOperator[] = [
 export const SPECIAL_KEYS = [
   { key: "_path", label: "Folder (path)", isSpecial: true },
   { key: "_tags", label: "Tags", isSpecial: true },
+  { key: "_completed", label: "Task completed", isSpecial: true },
 ];
 
 /** Check if a key is a special built-in key. */
@@ -137,6 +177,9 @@ export function getOperatorLabels(key: string, propertyKeys: PropertyKeyInfo[]):
   if (key === "_tags") {
     return TAGS_OPERATOR_LABELS;
   }
+  if (key === "_completed") {
+    return COMPLETED_OPERATORS;
+  }
 
   const propType = getPropertyType(key, propertyKeys);
   switch (propType) {
@@ -7,3 +7,4 @@ export { default as FuzzySelect } from "./FuzzySelect.svelte";
 export { default as MultiValueInput } from "./MultiValueInput.svelte";
 export { default as ResizeHandle } from "./ResizeHandle.svelte";
 export { default as TextInput } from "./TextInput.svelte";
+export { default as Thumbnail } from "./Thumbnail.svelte";
@@ -3,9 +3,10 @@
  * Provides common theme and extensions for all editor instances
  */
 
-import { EditorState, type Extension } from "@codemirror/state";
+import { Annotation, EditorState, type Extension } from "@codemirror/state";
 import {
   EditorView,
+  type ViewUpdate,
   keymap,
   lineNumbers,
   highlightActiveLine,
@@ -33,10 +34,12 @@ import { linkHandlerExtension } from "./linkHandler";
 import { hoverPreviewExtension } from "./hoverPreview";
 import { pasteHandlerExtension } from "./pasteHandler";
 import { vimExtension } from "./vimExtension";
+import { spellCheckExtension } from "./spellCheckExtension";
 import { calloutExtension } from "./calloutExtension";
 import { queryEmbedExtension } from "./queryEmbedExtension";
 import { habitTrackerExtension } from "./habitTrackerExtension";
 import { frontmatterConversionExtension } from "./frontmatterConversion";
+import { editorNote, type EditorNote } from "./editorNote";
 
 /**
  * Shared editor theme using CSS variables for theming support
@@ -44,11 +47,12 @@ import { frontmatterConversionExtension } from "./frontmatterConversion";
 export const editorTheme = EditorView.theme({
   "&": {
     height: "100%",
-    fontSize: "var(--font-size-md)",
+    fontSize: "var(--editor-font-size, var(--font-size-md))",
     color: "var(--text-primary)",
   },
   ".cm-content": {
-    fontFamily: "var(--font-family-mono)",
+    fontFamily: "var(--editor-font-family, var(--font-family-mono))",
+    lineHeight: "var(--editor-line-height, 1.4)",
     padding: "var(--spacing-4) 0",
     color: "var(--text-primary)",
     caretColor: "var(--editor-cursor)",
@@ -77,6 +81,34 @@ export const editorTheme = EditorView.theme({
   },
 });
 
+/**
+ * Marks transactions that replace the document with content saved in
+ * another window, so update listeners don't treat them as edits.
+ */
+const externalChange = Annotation.define<boolean>();
+
+/**
+ * Replace the document with content saved elsewhere, keeping the cursor
+ * where it was as far as the new content allows.
+ */
+export function replaceDocument(view: EditorView, content: string) {
+  if (view.state.doc.toString() === content) return;
+  const head = Math.min(view.state.selection.main.head, content.length);
+  view.dispatch({
+    changes: { from: 0, to: view.state.doc.length, insert: content },
+    selection: { anchor: head },
+    annotations: externalChange.of(true),
+  });
+}
+
+/**
+ * Whether an update changed the document through editing, rather than
+ * through `replaceDocument`.
+ */
+export function isEdit(update: ViewUpdate): boolean {
+  return update.docChanged && !update.transactions.some((tr) => tr.annotation(externalChange));
+}
+
 /**
  * Create a save keymap that calls the provided save handler on Cmd/Ctrl+S
  */
@@ -102,6 +134,8 @@ export interface EditorExtensionOptions {
   saveKeymap: Extension;
   /** Whether the editor is read-only */
   readonly?: boolean;
+  /** The note shown in the editor */
+  note?: EditorNote;
 }
 
 /**
@@ -112,6 +146,9 @@ export function createEditorExtensions(
   options: EditorExtensionOptions
 ): Extension[] {
   const extensions: Extension[] = [
+    // The note this editor shows
+    editorNote.of(options.note ?? null),
+
     // Core editing features
     lineNumbers(),
     highlightActiveLineGutter(),
@@ -160,6 +197,9 @@ export function createEditorExtensions(
     // Vim keybindings (if enabled in settings)
     ...vimExtension(),
 
+    // Spell checking (if a language is set in settings)
+    ...spellCheckExtension(),
+
     // Callout blocks (> [!note], > [!warning], etc.)
     calloutExtension(),
 
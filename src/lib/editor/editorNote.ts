@@ -0,0 +1,26 @@
+/**
+ * The note an editor instance shows, for extensions that act relative to
+ * it (pasted images, embeds, query embeds).
+ */
+
+import { Facet } from "@codemirror/state";
+import type { EditorState } from "@codemirror/state";
+
+export interface EditorNote {
+  id: number;
+  path: string;
+}
+
+/**
+ * Facet holding the note of an editor instance, or null.
+ */
+export const editorNote = Facet.define<EditorNote | null, EditorNote | null>({
+  combine: (values) => values[0] ?? null,
+});
+
+/**
+ * The note shown in the editor with this state, if any.
+ */
+export function getEditorNote(state: EditorState): EditorNote | null {
+  return state.facet(editorNote);
+}
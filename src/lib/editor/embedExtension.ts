@@ -24,6 +24,7 @@ import {
   getAudioMimeType,
 } from "../utils/fileTypes";
 import { EditorCache } from "./cache";
+import { getEditorNote } from "./editorNote";
 
 // Pattern for embeds: ![[target]] or ![[target#section]] or ![[target|size]]
 // Size can be: 200, 200px, 50%, x100 (height only), 200x100
@@ -80,13 +81,21 @@ function parseImageSize(sizeStr: string | undefined): ImageSize | undefined {
  */
 const embedCache = new EditorCache<EmbedContent>(30000); // 30 seconds TTL
 
-function getCacheKey(target: string, section?: string): string {
-  return section ? `${target}#${section}` : target;
+function getCacheKey(target: string, section?: string, sourcePath?: string): string {
+  const key = section ? `${target}#${section}` : target;
+  return sourcePath ? `${sourcePath}>${key}` : key;
 }
 
-async function resolveEmbedCached(target: string, section?: string, depth: number = 0): Promise<EmbedContent> {
-  const key = getCacheKey(target, section);
-  return embedCache.getOrFetch(key, () => resolveEmbed({ target, section, depth }));
+async function resolveEmbedCached(
+  target: string,
+  section?: string,
+  depth: number = 0,
+  sourcePath?: string
+): Promise<EmbedContent> {
+  const key = getCacheKey(target, section, sourcePath);
+  return embedCache.getOrFetch(key, () =>
+    resolveEmbed({ target, section, depth, source_path: sourcePath })
+  );
 }
 
 /**
@@ -97,7 +106,8 @@ class EmbedWidget extends WidgetType {
     private target: string,
     private section: string | undefined,
     private depth: number,
-    private size: ImageSize | undefined
+    private size: ImageSize | undefined,
+    private sourcePath: string | undefined
   ) {
     super();
   }
@@ -105,6 +115,7 @@ class EmbedWidget extends WidgetType {
   eq(other: EmbedWidget): boolean {
     return this.target === other.target &&
            this.section === other.section &&
+           this.sourcePath === other.sourcePath &&
            this.size?.width === other.size?.width &&
            this.size?.height === other.size?.height;
   }
@@ -136,7 +147,7 @@ class EmbedWidget extends WidgetType {
 
   private async resolveAndRender(container: HTMLElement): Promise<void> {
     try {
-      const content = await resolveEmbedCached(this.target, this.section, this.depth);
+      const content = await resolveEmbedCached(this.target, this.section, this.depth, this.sourcePath);
 
       // Clear loading state
       container.innerHTML = "";
@@ -325,6 +336,17 @@ class EmbedWidget extends WidgetType {
     header.appendChild(link);
     container.appendChild(header);
 
+    // The note embeds itself, directly or through other notes: its embeds
+    // aren't followed further
+    if (content.cycle) {
+      container.classList.add("cm-embed-looped");
+      const notice = document.createElement("div");
+      notice.className = "cm-embed-cycle";
+      notice.textContent = `Embed loop: ${content.cycle.chain.join(" → ")}`;
+      notice.title = `${content.cycle.repeated} is already embedded`;
+      container.appendChild(notice);
+    }
+
     // Content (rendered as plain text for now - could add markdown rendering later)
     const contentDiv = document.createElement("div");
     contentDiv.className = "cm-embed-content";
@@ -394,6 +416,7 @@ function createDecorations(view: EditorView, depth: number = 0): DecorationSet {
   const builder = new RangeSetBuilder<Decoration>();
   const activeLines = getActiveLines(view.state);
   const doc = view.state.doc;
+  const sourcePath = getEditorNote(view.state)?.path;
 
   // Process each visible line
   for (const { from, to } of view.visibleRanges) {
@@ -415,7 +438,7 @@ function createDecorations(view: EditorView, depth: number = 0): DecorationSet {
           const size = parseImageSize(sizeParam);
 
           // Create widget decoration (inline, not block)
-          const widget = new EmbedWidget(target, section, depth, size);
+          const widget = new EmbedWidget(target, section, depth, size, sourcePath);
           const deco = Decoration.replace({
             widget,
             inclusive: false,
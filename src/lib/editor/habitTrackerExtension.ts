@@ -686,33 +686,8 @@ class HabitTrackerWidget extends WidgetType {
   }
 
   private calculateStreak(habitWithEntries: HabitWithEntries): number {
-    if (habitWithEntries.habit.habit_type !== "boolean") {
-      return 0; // Streak only makes sense for boolean habits
-    }
-
-    let streak = 0;
-    const today = new Date();
-    today.setHours(0, 0, 0, 0);
-
-    // Start from today and go backwards
-    for (let i = 0; i < 365; i++) {
-      const checkDate = new Date(today);
-      checkDate.setDate(checkDate.getDate() - i);
-      const dateStr = checkDate.toISOString().split("T")[0];
-
-      const entries = this.getEntriesForDate(habitWithEntries, dateStr);
-      const hasEntry = entries.length > 0 && entries[0].value === "true";
-
-      if (hasEntry) {
-        streak++;
-      } else if (i > 0) {
-        // If we skipped today, continue checking
-        // But if we miss any other day, break
-        break;
-      }
-    }
-
-    return streak;
+    // Computed by the backend, honoring active days, skip dates, and freezes
+    return habitWithEntries.streak.current;
   }
 
   private renderCalendar() {
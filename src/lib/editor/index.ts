@@ -7,11 +7,14 @@ export {
   editorTheme,
   createSaveKeymap,
   createEditorExtensions,
+  replaceDocument,
+  isEdit,
   type EditorExtensionOptions,
 } from "./editorConfig";
 
 // Utilities
 export { EditorCache } from "./cache";
+export { editorNote, getEditorNote, type EditorNote } from "./editorNote";
 
 // Individual extensions (for advanced use cases)
 export { wikiLinkCompletion, invalidateNotesCache } from "./wikiLinkCompletion";
@@ -22,5 +25,6 @@ export { linkHandlerExtension, isPositionInWikiLink } from "./linkHandler";
 export { hoverPreviewExtension, invalidatePreviewCache } from "./hoverPreview";
 export { pasteHandlerExtension } from "./pasteHandler";
 export { vimExtension, isVimModeEnabled } from "./vimExtension";
+export { spellCheckExtension } from "./spellCheckExtension";
 export { calloutExtension } from "./calloutExtension";
 export { queryEmbedExtension, invalidateQueryCache } from "./queryEmbedExtension";
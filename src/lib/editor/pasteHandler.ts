@@ -1,11 +1,13 @@
 /**
  * Paste Handler Extension for CodeMirror
  * Handles pasting images from clipboard, saves them to the vault,
- * and inserts an embed link at the cursor position.
+ * and inserts an embed link at the cursor position. A pasted image or PDF
+ * URL is downloaded into the vault and embedded the same way.
  */
 
 import { EditorView } from "@codemirror/view";
-import { savePastedImage } from "../services/api";
+import { downloadAttachment, savePastedImage } from "../services/api";
+import { getEditorNote } from "./editorNote";
 
 /**
  * Get file extension from MIME type
@@ -22,6 +24,34 @@ function getExtensionFromMime(mimeType: string): string {
   return mimeToExt[mimeType] || "png";
 }
 
+/** A lone http(s) URL to an image or PDF, judging by its extension. */
+const ATTACHMENT_URL = /^https?:\/\/\S+\.(png|jpe?g|gif|webp|svg|bmp|pdf)(\?\S*)?$/i;
+
+/**
+ * Insert text at the cursor position, replacing the selection
+ */
+function insertAtCursor(view: EditorView, text: string) {
+  const { from, to } = view.state.selection.main;
+  view.dispatch({
+    changes: { from, to, insert: text },
+    selection: { anchor: from + text.length },
+  });
+}
+
+/**
+ * Download a pasted attachment URL and embed the local copy; the URL
+ * itself is inserted if the download fails
+ */
+async function handleUrlPaste(view: EditorView, url: string) {
+  try {
+    const savedPath = await downloadAttachment(url);
+    insertAtCursor(view, `![[${savedPath}]]`);
+  } catch (error) {
+    console.error("[PasteHandler] Failed to download attachment:", error);
+    insertAtCursor(view, url);
+  }
+}
+
 /**
  * Convert a Blob to base64 string
  */
@@ -69,17 +99,13 @@ async function handlePaste(view: EditorView, event: ClipboardEvent): Promise<boo
     const base64Data = await blobToBase64(blob);
     const extension = getExtensionFromMime(imageItem.type);
 
-    // Save the image via Tauri command
-    const savedPath = await savePastedImage(base64Data, extension);
-
-    // Insert embed link at cursor position
-    const embedText = `![[${savedPath}]]`;
-    const { from, to } = view.state.selection.main;
+    // Save the image via Tauri command; with the note's path it goes where
+    // the attachment settings say and comes back relative to the note
+    const note = getEditorNote(view.state);
+    const savedPath = await savePastedImage(base64Data, extension, note?.path);
 
-    view.dispatch({
-      changes: { from, to, insert: embedText },
-      selection: { anchor: from + embedText.length },
-    });
+    // Insert embed link at cursor position (resolved relative to the note)
+    insertAtCursor(view, `![[${savedPath}]]`);
 
     return true;
   } catch (error) {
@@ -89,11 +115,20 @@ async function handlePaste(view: EditorView, event: ClipboardEvent): Promise<boo
 }
 
 /**
- * Extension that provides image paste handling
+ * Extension that provides image and attachment URL paste handling
  */
 export function pasteHandlerExtension() {
   return EditorView.domEventHandlers({
     paste(event: ClipboardEvent, view: EditorView) {
+      // Copied images are saved as they are; a copied URL is downloaded
+      const items = Array.from(event.clipboardData?.items ?? []);
+      const text = event.clipboardData?.getData("text/plain").trim() ?? "";
+      if (!items.some((item) => item.type.startsWith("image/")) && ATTACHMENT_URL.test(text)) {
+        event.preventDefault();
+        handleUrlPaste(view, text);
+        return true;
+      }
+
       // Handle async paste - we return false to not block, but handle it ourselves
       handlePaste(view, event);
 
@@ -11,6 +11,7 @@
  * match_mode: All
  * result_type: Tasks
  * include_completed: false
+ * include_embedded: true
  * limit: 20
  * view:
  *   view_type: Table
@@ -31,17 +32,24 @@ import type { ViewUpdate, DecorationSet } from "@codemirror/view";
 import { RangeSetBuilder } from "@codemirror/state";
 import type { EditorState } from "@codemirror/state";
 import { convertFileSrc } from "@tauri-apps/api/core";
-import { executeQueryEmbed, setProperty } from "../services/api";
+import { executeQueryEmbed, getThumbnail, refreshQueryEmbed, reorderQueryResult, setProperty } from "../services/api";
 import type { QueryEmbedResponse, QueryResultItem, QueryViewConfig, KanbanConfig, InteractiveFilter, StatsConfig, CardConfig } from "../types";
 import { workspaceStore } from "../stores/workspace.svelte";
 import { vaultStore } from "../stores/vault.svelte";
 import { EditorCache } from "./cache";
+import { getEditorNote } from "./editorNote";
+import { formatDisplayDate } from "../utils/dateUtils";
 
 // Pattern to match query code block start
 const QUERY_BLOCK_START = /^```query\s*$/;
 const QUERY_BLOCK_END = /^```\s*$/;
 
+// Size of card cover thumbnails, in pixels
+const CARD_THUMBNAIL_SIZE = 400;
+
 interface QueryBlock {
+  /** Position among the note's query blocks, from 0 */
+  index: number;
   startLine: number;
   endLine: number;
   from: number;
@@ -71,6 +79,7 @@ function findQueryBlocks(state: EditorState): QueryBlock[] {
       yamlLines = [];
     } else if (inQueryBlock && QUERY_BLOCK_END.test(text)) {
       blocks.push({
+        index: blocks.length,
         startLine: blockStartLine,
         endLine: i,
         from: blockStartFrom,
@@ -91,15 +100,25 @@ function findQueryBlocks(state: EditorState): QueryBlock[] {
  */
 const queryResultCache = new EditorCache<QueryEmbedResponse>(5000); // 5 seconds TTL
 
-async function getQueryResults(yamlContent: string): Promise<QueryEmbedResponse> {
-  const cached = queryResultCache.get(yamlContent);
+/**
+ * Cache key of a query block: results differ per note and block, for
+ * `{{this.…}}` and the block's Kanban boards
+ */
+function queryCacheKey(block: QueryBlock, noteId: number | null): string {
+  return `${noteId}:${block.index}:${block.yamlContent}`;
+}
+
+async function getQueryResults(block: QueryBlock, noteId: number | null): Promise<QueryEmbedResponse> {
+  const { yamlContent } = block;
+  const key = queryCacheKey(block, noteId);
+  const cached = queryResultCache.get(key);
   if (cached) {
     return cached;
   }
 
   try {
-    const response = await executeQueryEmbed(yamlContent);
-    queryResultCache.set(yamlContent, response);
+    const response = await executeQueryEmbed(yamlContent, noteId, block.index);
+    queryResultCache.set(key, response);
     return response;
   } catch (e) {
     return {
@@ -156,23 +175,62 @@ class QueryResultWidget extends WidgetType {
   private activeTabIndex = 0;
   // Interactive filter state per tab (tab index -> filter key -> selected values)
   private tabFilterState = new Map<number, Map<string, Set<string>>>();
+  // Kanban card being dragged to a new position in its column
+  private draggedCard: HTMLElement | null = null;
 
-  constructor(private block: QueryBlock) {
+  /**
+   * `noteId` is the note containing the block (the editor's own note, not
+   * the active one), for {{this.…}} variables
+   */
+  constructor(private block: QueryBlock, private noteId: number | null) {
     super();
     this.loadResults();
   }
 
   private async loadResults() {
     this.loading = true;
-    this.response = await getQueryResults(this.block.yamlContent);
+    this.response = await getQueryResults(this.block, this.noteId);
     this.loading = false;
     if (this.element) {
       this.updateElement();
     }
   }
 
+  /**
+   * Run the query again, bypassing both the editor and backend caches
+   */
+  private async refresh() {
+    this.loading = true;
+    this.updateElement();
+    try {
+      this.response = await refreshQueryEmbed(this.block.yamlContent, this.noteId, this.block.index);
+      queryResultCache.set(queryCacheKey(this.block, this.noteId), this.response);
+    } catch (error) {
+      console.error("Failed to refresh query:", error);
+    }
+    this.loading = false;
+    this.updateElement();
+  }
+
+  private renderRefreshButton(header: HTMLElement) {
+    const refreshBtn = document.createElement("button");
+    refreshBtn.className = "cm-query-refresh-btn";
+    refreshBtn.textContent = "↻";
+    refreshBtn.title = "Refresh results";
+    refreshBtn.onclick = (e) => {
+      e.preventDefault();
+      e.stopPropagation();
+      this.refresh();
+    };
+    header.appendChild(refreshBtn);
+  }
+
   eq(other: QueryResultWidget): boolean {
-    return this.block.yamlContent === other.block.yamlContent;
+    return (
+      this.block.yamlContent === other.block.yamlContent &&
+      this.block.index === other.block.index &&
+      this.noteId === other.noteId
+    );
   }
 
   toDOM(): HTMLElement {
@@ -234,6 +292,7 @@ class QueryResultWidget extends WidgetType {
     title.className = "cm-query-embed-title";
     title.textContent = `Query Results (${this.response.total_count})`;
     header.appendChild(title);
+    this.renderRefreshButton(header);
 
     this.element.appendChild(header);
 
@@ -308,6 +367,7 @@ class QueryResultWidget extends WidgetType {
     });
 
     header.appendChild(tabsContainer);
+    this.renderRefreshButton(header);
     this.element.appendChild(header);
 
     // Tab content
@@ -552,7 +612,7 @@ class QueryResultWidget extends WidgetType {
       const coverEl = document.createElement("div");
       coverEl.className = "cm-query-card-cover";
       const img = document.createElement("img");
-      img.src = coverImage;
+      this.setCoverImageSource(img, coverImage);
       img.alt = "";
       img.loading = "lazy";
       coverEl.appendChild(img);
@@ -642,6 +702,7 @@ class QueryResultWidget extends WidgetType {
     return card;
   }
 
+  /** Cover image of a card: a remote URL or a path relative to the vault root. */
   private getCoverImageForCard(item: QueryResultItem, coverProperty: string | null): string | null {
     if (!coverProperty) return null;
     
@@ -653,16 +714,29 @@ class QueryResultWidget extends WidgetType {
       return value;
     }
 
-    // Resolve relative path against vault root
-    const vaultPath = vaultStore.info?.path;
-    if (!vaultPath) return null;
-
     // Handle paths that might start with ./ or just be relative
-    const cleanPath = value.startsWith("./") ? value.slice(2) : value;
-    const fullPath = `${vaultPath}/${cleanPath}`;
+    return value.startsWith("./") ? value.slice(2) : value;
+  }
+
+  /** Show a cover image, using a cached thumbnail for vault images. */
+  private setCoverImageSource(img: HTMLImageElement, coverImage: string): void {
+    if (coverImage.startsWith("http://") || coverImage.startsWith("https://")) {
+      img.src = coverImage;
+      return;
+    }
 
-    // Convert to Tauri asset URL
-    return convertFileSrc(fullPath);
+    // Resolve relative path against vault root
+    const vaultPath = vaultStore.info?.path;
+    if (!vaultPath) return;
+
+    getThumbnail(coverImage, CARD_THUMBNAIL_SIZE)
+      .then((thumbnailPath) => {
+        img.src = convertFileSrc(thumbnailPath);
+      })
+      .catch(() => {
+        // Fall back to the full image
+        img.src = convertFileSrc(`${vaultPath}/${coverImage}`);
+      });
   }
 
   private getItemTitle(item: QueryResultItem): string {
@@ -743,42 +817,55 @@ class QueryResultWidget extends WidgetType {
     const groups = new Map<string, QueryResultItem[]>();
 
     for (const item of results) {
-      const value = this.getGroupValue(item, groupBy);
+      // An item with a list property goes in the column of each of its items
+      const values = this.getGroupValues(item, groupBy);
 
-      if (!value && !showUncategorized) {
+      if (values.length === 0 && !showUncategorized) {
         continue;
       }
 
-      const groupName = value || "Uncategorized";
-      const existing = groups.get(groupName) || [];
-      existing.push(item);
-      groups.set(groupName, existing);
+      for (const groupName of values.length > 0 ? values : ["Uncategorized"]) {
+        const existing = groups.get(groupName) || [];
+        existing.push(item);
+        groups.set(groupName, existing);
+      }
     }
 
     return groups;
   }
 
-  private getGroupValue(item: QueryResultItem, groupBy: string): string | null {
+  private getGroupValues(item: QueryResultItem, groupBy: string): string[] {
     if (item.item_type === "task" && item.task) {
+      let value: string | null;
       switch (groupBy) {
         case "priority":
-          return item.task.todo.priority;
+          value = item.task.todo.priority;
+          break;
         case "context":
-          return item.task.todo.context;
+          value = item.task.todo.context;
+          break;
         case "completed":
-          return item.task.todo.completed ? "Completed" : "Not Completed";
+          value = item.task.todo.completed ? "Completed" : "Not Completed";
+          break;
         case "due_date":
-          return this.categorizeDate(item.task.todo.due_date);
+          value = this.categorizeDate(item.task.todo.due_date);
+          break;
         default:
           // Check note properties
-          const prop = item.properties.find((p) => p.key === groupBy);
-          return prop?.value || null;
+          return this.getPropertyValues(item, groupBy);
       }
+      return value ? [value] : [];
     } else if (item.note) {
-      const prop = item.properties.find((p) => p.key === groupBy);
-      return prop?.value || null;
+      return this.getPropertyValues(item, groupBy);
     }
-    return null;
+    return [];
+  }
+
+  private getPropertyValues(item: QueryResultItem, key: string): string[] {
+    const prop = item.properties.find((p) => p.key === key);
+    if (!prop) return [];
+    if (prop.property_type === "list") return prop.items ?? [];
+    return prop.value ? [prop.value] : [];
   }
 
   private categorizeDate(dateStr: string | null): string | null {
@@ -867,14 +954,66 @@ class QueryResultWidget extends WidgetType {
 
     for (const item of items) {
       const card = this.renderKanbanCard(item, config);
+      if (config.board) {
+        card.draggable = true;
+        card.dataset.itemRef = item.item_ref;
+        card.ondragstart = () => {
+          this.draggedCard = card;
+          card.classList.add("dragging");
+        };
+        card.ondragend = () => {
+          this.draggedCard = null;
+          card.classList.remove("dragging");
+        };
+      }
       cardsContainer.appendChild(card);
     }
 
+    if (config.board) {
+      const board = config.board;
+      // Cards are reordered within their column; the order is kept per board
+      cardsContainer.ondragover = (e) => {
+        if (this.draggedCard?.parentElement !== cardsContainer) return;
+        e.preventDefault();
+        const next = this.cardBelow(cardsContainer, e.clientY);
+        if (next !== this.draggedCard) {
+          cardsContainer.insertBefore(this.draggedCard, next);
+        }
+      };
+      cardsContainer.ondrop = async (e) => {
+        const card = this.draggedCard;
+        if (card?.parentElement !== cardsContainer) return;
+        e.preventDefault();
+        const before = (card.previousElementSibling as HTMLElement | null)?.dataset.itemRef ?? null;
+        const after = (card.nextElementSibling as HTMLElement | null)?.dataset.itemRef ?? null;
+        try {
+          await reorderQueryResult(board, card.dataset.itemRef!, before, after);
+          invalidateQueryCache();
+        } catch (error) {
+          console.error("Failed to reorder card:", error);
+        }
+      };
+    }
+
     column.appendChild(cardsContainer);
 
     return column;
   }
 
+  /**
+   * The first card whose middle is below `y`, other than the dragged one
+   */
+  private cardBelow(container: HTMLElement, y: number): HTMLElement | null {
+    for (const child of Array.from(container.children) as HTMLElement[]) {
+      if (child === this.draggedCard) continue;
+      const box = child.getBoundingClientRect();
+      if (y < box.top + box.height / 2) {
+        return child;
+      }
+    }
+    return null;
+  }
+
   private getColumnColorClass(columnName: string, groupBy: string): string {
     if (groupBy === "priority") {
       switch (columnName.toLowerCase()) {
@@ -987,7 +1126,8 @@ class QueryResultWidget extends WidgetType {
           const c = item.task.todo.context;
           return c ? `@${c}` : null;
         case "due_date":
-          return item.task.todo.due_date;
+          const due = item.task.todo.due_date;
+          return due ? formatDisplayDate(due) : null;
         case "note_title":
           return null; // Shown separately
         default:
@@ -1039,7 +1179,7 @@ class QueryResultWidget extends WidgetType {
           return c ? `<span class="cm-query-badge context">@${c}</span>` : "";
         case "due_date":
           const d = item.task.todo.due_date;
-          return d ? `<span class="cm-query-badge due-date">${d}</span>` : "";
+          return d ? `<span class="cm-query-badge due-date">${formatDisplayDate(d)}</span>` : "";
         case "note_title":
           return item.task.note_title || item.task.note_path.replace(".md", "");
         case "title":
@@ -1438,7 +1578,7 @@ function createDecorations(view: EditorView): DecorationSet {
 
     // Replace the closing ``` line with the results widget
     const endLine = doc.line(block.endLine);
-    const widget = new QueryResultWidget(block);
+    const widget = new QueryResultWidget(block, getEditorNote(view.state)?.id ?? null);
     allDecorations.push({
       from: endLine.from,
       to: endLine.to,
@@ -1835,6 +1975,23 @@ const injectStyles = () => {
       box-shadow: var(--shadow-sm);
     }
 
+    .cm-query-refresh-btn {
+      margin-left: auto;
+      padding: 0 6px;
+      background: none;
+      border: none;
+      color: var(--text-muted);
+      cursor: pointer;
+    }
+
+    .cm-query-refresh-btn:hover {
+      color: var(--text-primary);
+    }
+
+    .cm-query-kanban-card.dragging {
+      opacity: 0.5;
+    }
+
     .cm-query-kanban-card.completed {
       opacity: 0.6;
     }
@@ -0,0 +1,208 @@
+/**
+ * Spell Check Extension for CodeMirror
+ * Underlines misspelled words found by the backend (Hunspell dictionaries
+ * plus the vault's custom word list) and offers suggestions on hover.
+ */
+
+import {
+  EditorView,
+  Decoration,
+  ViewPlugin,
+  hoverTooltip,
+} from "@codemirror/view";
+import type { DecorationSet, ViewUpdate, Tooltip } from "@codemirror/view";
+import { StateEffect, StateField } from "@codemirror/state";
+import type { Extension } from "@codemirror/state";
+import { checkText, addToDictionary } from "../services/api";
+import { getSetting } from "../services/settings";
+import type { Misspelling } from "../types";
+
+/** Delay after the last edit before the document is checked again. */
+const CHECK_DELAY = 600;
+
+const misspellingMark = Decoration.mark({ class: "cm-misspelling" });
+
+/** Replaces the misspellings shown in the editor. */
+const setMisspellings = StateEffect.define<Misspelling[]>();
+
+function markMisspellings(misspellings: Misspelling[]): DecorationSet {
+  return Decoration.set(misspellings.map((m) => misspellingMark.range(m.from, m.to)), true);
+}
+
+interface SpellState {
+  misspellings: Misspelling[];
+  decorations: DecorationSet;
+}
+
+const spellField = StateField.define<SpellState>({
+  create: () => ({ misspellings: [], decorations: Decoration.none }),
+  update(value, tr) {
+    for (const effect of tr.effects) {
+      if (effect.is(setMisspellings)) {
+        const length = tr.state.doc.length;
+        const valid = effect.value.filter((m) => m.from < m.to && m.to <= length);
+        return { misspellings: valid, decorations: markMisspellings(valid) };
+      }
+    }
+    if (!tr.docChanged) return value;
+    // Keep the marks in place until the next check; words that were edited
+    // are dropped rather than shown with stale suggestions
+    const misspellings = value.misspellings
+      .filter((m) => !tr.changes.touchesRange(m.from, m.to))
+      .map((m) => ({ ...m, from: tr.changes.mapPos(m.from), to: tr.changes.mapPos(m.to) }));
+    return { misspellings, decorations: markMisspellings(misspellings) };
+  },
+  provide: (field) => EditorView.decorations.from(field, (value) => value.decorations),
+});
+
+/**
+ * Check the document after edits, dropping results of outdated checks.
+ */
+function checkerPlugin(lang: string) {
+  return ViewPlugin.fromClass(
+    class {
+      private timer: ReturnType<typeof setTimeout> | null = null;
+      private generation = 0;
+
+      constructor(private view: EditorView) {
+        this.schedule();
+      }
+
+      update(update: ViewUpdate) {
+        if (update.docChanged) {
+          this.schedule();
+        }
+      }
+
+      schedule() {
+        if (this.timer) clearTimeout(this.timer);
+        this.timer = setTimeout(() => this.check(), CHECK_DELAY);
+      }
+
+      async check() {
+        const generation = ++this.generation;
+        const text = this.view.state.doc.toString();
+        try {
+          const misspellings = await checkText(text, lang);
+          // Skip results for a document that has changed since
+          if (generation !== this.generation || this.view.state.doc.toString() !== text) return;
+          this.view.dispatch({ effects: setMisspellings.of(misspellings) });
+        } catch (e) {
+          console.error("[SpellCheck] Failed to check text:", e);
+        }
+      }
+
+      destroy() {
+        if (this.timer) clearTimeout(this.timer);
+        this.generation++;
+      }
+    }
+  );
+}
+
+/**
+ * Tooltip with suggestions for a misspelled word, and a button to add it
+ * to the vault's dictionary.
+ */
+const suggestionTooltip = hoverTooltip((view, pos): Tooltip | null => {
+  const misspelling = view.state
+    .field(spellField)
+    .misspellings.find((m) => pos >= m.from && pos <= m.to);
+  if (!misspelling) return null;
+
+  return {
+    pos: misspelling.from,
+    end: misspelling.to,
+    above: true,
+    create() {
+      const dom = document.createElement("div");
+      dom.className = "cm-spell-tooltip";
+
+      const replace = (text: string) => {
+        const { from, to } = misspelling;
+        if (view.state.sliceDoc(from, to) !== misspelling.word) return;
+        view.dispatch({ changes: { from, to, insert: text } });
+      };
+
+      if (misspelling.suggestions.length === 0) {
+        const empty = document.createElement("div");
+        empty.className = "cm-spell-empty";
+        empty.textContent = "No suggestions";
+        dom.appendChild(empty);
+      }
+      for (const suggestion of misspelling.suggestions.slice(0, 5)) {
+        const button = document.createElement("button");
+        button.className = "cm-spell-suggestion";
+        button.textContent = suggestion;
+        button.onclick = () => replace(suggestion);
+        dom.appendChild(button);
+      }
+
+      const add = document.createElement("button");
+      add.className = "cm-spell-add";
+      add.textContent = `Add "${misspelling.word}" to dictionary`;
+      add.onclick = async () => {
+        try {
+          await addToDictionary(misspelling.word);
+          const misspellings = view.state
+            .field(spellField)
+            .misspellings.filter((m) => m.word !== misspelling.word);
+          view.dispatch({ effects: setMisspellings.of(misspellings) });
+        } catch (e) {
+          console.error("[SpellCheck] Failed to add word to dictionary:", e);
+        }
+      };
+      dom.appendChild(add);
+
+      return { dom };
+    },
+  };
+});
+
+const spellCheckTheme = EditorView.baseTheme({
+  ".cm-misspelling": {
+    textDecoration: "underline wavy var(--color-error)",
+    textDecorationSkipInk: "none",
+    textUnderlineOffset: "3px",
+  },
+  ".cm-spell-tooltip": {
+    display: "flex",
+    flexDirection: "column",
+    padding: "var(--spacing-1)",
+    minWidth: "160px",
+  },
+  ".cm-spell-tooltip button": {
+    background: "none",
+    border: "none",
+    textAlign: "left",
+    padding: "var(--spacing-1) var(--spacing-2)",
+    color: "var(--text-primary)",
+    cursor: "pointer",
+    borderRadius: "var(--radius-sm)",
+  },
+  ".cm-spell-tooltip button:hover": {
+    background: "var(--bg-hover)",
+  },
+  ".cm-spell-suggestion": {
+    fontWeight: "600",
+  },
+  ".cm-spell-add": {
+    color: "var(--text-muted)",
+    borderTop: "1px solid var(--border-light)",
+  },
+  ".cm-spell-empty": {
+    padding: "var(--spacing-1) var(--spacing-2)",
+    color: "var(--text-muted)",
+  },
+});
+
+/**
+ * Returns the spell check extension if a spell check language is set
+ */
+export function spellCheckExtension(): Extension[] {
+  const lang = getSetting("editor").spell_check_language;
+  if (!lang) {
+    return [];
+  }
+  return [spellField, checkerPlugin(lang), suggestionTooltip, spellCheckTheme];
+}
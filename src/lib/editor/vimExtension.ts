@@ -11,7 +11,7 @@ import { getSetting } from "../services/settings";
  * Returns vim extension if vim mode is enabled in settings
  */
 export function vimExtension(): Extension[] {
-  if (getSetting("vimMode")) {
+  if (getSetting("vim_mode")) {
     return [vim()];
   }
   return [];
@@ -21,5 +21,5 @@ export function vimExtension(): Extension[] {
  * Check if vim mode is currently enabled
  */
 export function isVimModeEnabled(): boolean {
-  return getSetting("vimMode");
+  return getSetting("vim_mode");
 }
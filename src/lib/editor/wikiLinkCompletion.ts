@@ -2,6 +2,8 @@
  * Wiki-link autocomplete extension for CodeMirror
  * Triggers on [[ and shows available notes from the vault
  * Also provides section completion after # (e.g., [[note#section]])
+ * and tag (#tag) and context (@context) completion from the vault's
+ * autocomplete index
  */
 
 import {
@@ -17,7 +19,7 @@ import type {
 import { keymap, ViewPlugin, tooltips } from "@codemirror/view";
 import type { ViewUpdate } from "@codemirror/view";
 import { Prec, EditorState } from "@codemirror/state";
-import { listNotes, getNoteHeadings, getFolderTree } from "../services/api";
+import { listNotes, getNoteHeadings, getFolderTree, autocomplete } from "../services/api";
 import type { NoteListItem, HeadingInfo, FolderNode } from "../types";
 import { logger } from "../utils/logger";
 
@@ -288,6 +290,36 @@ function wikiLinkCompletionSource(
   };
 }
 
+/**
+ * Completion source for #tags and @contexts, answered by the backend's
+ * autocomplete index
+ */
+async function tagCompletionSource(context: CompletionContext): Promise<CompletionResult | null> {
+  // Same start rule as the indexer: not preceded by a word character or #
+  const before = context.matchBefore(/(?:^|[^\w#@])[#@][a-zA-Z][a-zA-Z0-9_\-/]*$/);
+  if (!before) return null;
+
+  const markerIndex = before.text.search(/[#@][a-zA-Z]/);
+  const marker = before.text[markerIndex];
+  const prefix = before.text.slice(markerIndex + 1);
+  try {
+    const items = await autocomplete(marker === "#" ? "tag" : "context", prefix, 30);
+    if (items.length === 0) return null;
+    return {
+      from: before.from + markerIndex + 1,
+      options: items.map((item) => ({
+        label: item.text,
+        detail: `${item.count}`,
+        type: marker === "#" ? "keyword" : "variable",
+      })),
+      validFor: /^[a-zA-Z0-9_\-/]*$/,
+    };
+  } catch (error) {
+    logger.error("WikiLink", "Failed to fetch tag completions:", error);
+    return null;
+  }
+}
+
 /**
  * Check if cursor is inside an unclosed wiki link on the current line
  */
@@ -346,7 +378,7 @@ export function wikiLinkCompletion() {
       activateOnTyping: true, // Activate on any typing - completion source filters to [[
       maxRenderedOptions: 30,
       icons: false,
-      override: [wikiLinkCompletionSource],
+      override: [wikiLinkCompletionSource, tagCompletionSource],
       defaultKeymap: true,
     }),
     // Use highest precedence so Enter accepts completion instead of inserting newline
@@ -0,0 +1,31 @@
+/**
+ * Books API - OpenLibrary metadata for the reading log and shelves
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { BookMetadata, BookShelf } from "../../types";
+
+/**
+ * Look up a book on OpenLibrary by ISBN (or by title without an ISBN) and
+ * store the result as properties of the note, marking it as a book.
+ * The cover is downloaded to the attachment folder.
+ *
+ * @param noteId - The note to fill in
+ * @param isbn - ISBN-10 or ISBN-13 (dashes allowed)
+ * @param title - Title to search for when there is no ISBN
+ */
+export async function fetchBookMetadata(
+  noteId: number,
+  isbn: string | null,
+  title: string | null = null
+): Promise<BookMetadata> {
+  return invoke<BookMetadata>("fetch_book_metadata", { noteId, isbn, title });
+}
+
+/**
+ * Get the books in the vault (notes with `type: book`) grouped by status:
+ * reading, to-read, read, abandoned, other statuses, then books without one.
+ */
+export async function getBookShelves(): Promise<BookShelf[]> {
+  return invoke<BookShelf[]>("get_book_shelves");
+}
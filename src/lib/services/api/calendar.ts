@@ -48,3 +48,13 @@ export async function getNotesForDateRange(
 ): Promise<[string, NoteForDate[]][]> {
   return invoke<[string, NoteForDate[]][]>("get_notes_for_date_range", { startDate, endDate });
 }
+
+// Agenda
+
+/**
+ * The agenda of a day (schedule blocks, tasks due and habits) as plain text
+ * wrapped to `width` columns (default 32, a 58 mm receipt printer).
+ */
+export async function formatAgendaText(date: string, width?: number): Promise<string> {
+  return invoke<string>("format_agenda_text", { date, width });
+}
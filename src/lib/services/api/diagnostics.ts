@@ -0,0 +1,46 @@
+/**
+ * Diagnostics API - performance metrics, bug report bundles and vault growth
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { DiagnosticsExport, FileProblem, PerformanceMetrics, VaultGrowth, VaultMetric } from "../../types";
+
+/**
+ * Get the p50/p95 latency of each command and the recent slow SQL
+ * statements, since the app started (or the metrics were reset).
+ */
+export async function getPerformanceMetrics(): Promise<PerformanceMetrics> {
+  return invoke<PerformanceMetrics>("get_performance_metrics");
+}
+
+/**
+ * Clear the recorded latencies and slow queries.
+ */
+export async function resetPerformanceMetrics(): Promise<void> {
+  return invoke("reset_performance_metrics");
+}
+
+/**
+ * Write a zip for a bug report to `output`: anonymized vault statistics,
+ * the database schema, the vault config with secrets redacted, the
+ * performance metrics and the recent log lines.
+ */
+export async function exportDiagnostics(output: string): Promise<DiagnosticsExport> {
+  return invoke<DiagnosticsExport>("export_diagnostics", { output });
+}
+
+/**
+ * Get the files that could not be read cleanly when last indexed: notes
+ * with invalid UTF-8 and binary files with a `.md` extension.
+ */
+export async function getFileProblems(): Promise<FileProblem[]> {
+  return invoke<FileProblem[]>("get_file_problems");
+}
+
+/**
+ * Get how a vault metric changed between two dates (YYYY-MM-DD, inclusive),
+ * for growth charts.
+ */
+export async function getVaultGrowth(startDate: string, endDate: string, metric: VaultMetric): Promise<VaultGrowth> {
+  return invoke<VaultGrowth>("get_vault_growth", { startDate, endDate, metric });
+}
@@ -3,7 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { EmbedContent, HeadingInfo, ResolveEmbedRequest } from "../../types";
+import type { EmbedContent, EmbedCycle, HeadingInfo, ResolveEmbedRequest } from "../../types";
 
 /**
  * Resolve an embed (![[target]] or ![[target#section]]).
@@ -17,10 +17,12 @@ export async function resolveEmbed(request: ResolveEmbedRequest): Promise<EmbedC
     is_image: boolean;
     asset_url: string | null;
     error: string | null;
+    cycle: EmbedCycle | null;
   }>("resolve_embed", { request: {
     target: request.target,
     section: request.section ?? null,
     depth: request.depth,
+    source_path: request.source_path ?? null,
   }});
 
   return {
@@ -30,6 +32,7 @@ export async function resolveEmbed(request: ResolveEmbedRequest): Promise<EmbedC
     isImage: result.is_image,
     assetUrl: result.asset_url,
     error: result.error,
+    cycle: result.cycle,
   };
 }
 
@@ -51,11 +54,36 @@ export async function getNoteHeadings(path: string): Promise<HeadingInfo[]> {
 }
 
 /**
- * Save a pasted image to the vault's assets folder.
+ * Save a pasted image according to the vault's attachment settings.
  * @param imageData Base64-encoded image data
  * @param extension File extension (e.g., "png", "jpg")
- * @returns The relative path to the saved image (e.g., "assets/Pasted image 20251208143000.png")
+ * @param notePath Path of the note the image is pasted into
+ * @returns The path to the saved image, relative to the note's folder (e.g., "assets/Pasted image 20251208143000.png")
  */
-export async function savePastedImage(imageData: string, extension: string): Promise<string> {
-  return await invoke<string>("save_pasted_image", { imageData, extension });
+export async function savePastedImage(
+  imageData: string,
+  extension: string,
+  notePath?: string
+): Promise<string> {
+  return await invoke<string>("save_pasted_image", { imageData, extension, notePath });
+}
+
+/**
+ * Download a remote image or PDF into the vault.
+ * @param url The http(s) URL to download
+ * @param targetFolder Vault folder to save to (the attachment folder by default)
+ * @returns The path relative to the vault root, ready to embed
+ */
+export async function downloadAttachment(url: string, targetFolder?: string): Promise<string> {
+  return await invoke<string>("download_attachment", { url, targetFolder });
+}
+
+/**
+ * Get a cached thumbnail for an image in the vault.
+ * @param path Image path relative to the vault root
+ * @param maxDimension Maximum width and height in pixels
+ * @returns Full filesystem path of the thumbnail (or of the image if it is already small enough)
+ */
+export async function getThumbnail(path: string, maxDimension: number): Promise<string> {
+  return await invoke<string>("get_thumbnail", { path, maxDimension });
 }
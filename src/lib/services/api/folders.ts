@@ -3,7 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { FolderNode } from "../../types";
+import type { DeleteImpact, FolderNode } from "../../types";
 
 export async function createFolder(path: string): Promise<void> {
   return invoke("create_folder", { path });
@@ -13,8 +13,13 @@ export async function renameFolder(oldPath: string, newPath: string): Promise<nu
   return invoke<number[]>("rename_folder", { oldPath, newPath });
 }
 
-export async function deleteFolder(path: string): Promise<number[]> {
-  return invoke<number[]>("delete_folder", { path });
+export async function deleteFolder(path: string, moveToTrash = false): Promise<number[]> {
+  return invoke<number[]>("delete_folder", { path, moveToTrash });
+}
+
+/** Report what deleting a folder or note would affect, without deleting. */
+export async function previewDelete(path: string): Promise<DeleteImpact> {
+  return invoke<DeleteImpact>("preview_delete", { path });
 }
 
 export async function getFolderTree(): Promise<FolderNode> {
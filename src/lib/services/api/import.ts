@@ -1,19 +1,25 @@
 /**
- * Import API - vault import operations
+ * Import API - copying external files and web pages into the vault
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { ImportVaultRequest, ImportResult } from "../../types";
+import type { ImportedFile } from "../../types";
 
 /**
- * Import an Obsidian vault into the current vault.
- *
- * Copies all markdown files and assets, preserving folder structure.
- * Parses YAML frontmatter and converts to properties.
- * Merges frontmatter tags with inline tags.
- *
- * Listen for "import:progress" events to get real-time progress updates.
+ * Copy external files into the vault.
+ * Markdown files go to `targetFolder` (the vault root by default) and are
+ * indexed; other files go to the attachment folder. Returns one descriptor
+ * per file, in the order given.
  */
-export async function importObsidianVault(request: ImportVaultRequest): Promise<ImportResult> {
-  return invoke<ImportResult>("import_obsidian_vault", { request });
+export async function importFiles(paths: string[], targetFolder?: string): Promise<ImportedFile[]> {
+  return invoke<ImportedFile[]>("import_files", { paths, targetFolder });
+}
+
+/**
+ * Clip a web page into a new note at `targetPath`: its main content as
+ * markdown, with images downloaded into the vault and `source`/`clipped`
+ * frontmatter. Returns the new note's ID.
+ */
+export async function clipUrl(url: string, targetPath: string): Promise<number> {
+  return invoke<number>("clip_url", { url, targetPath });
 }
@@ -4,6 +4,7 @@
  */
 
 export * from "./vault";
+export * from "./settings";
 export * from "./notes";
 export * from "./folders";
 export * from "./todos";
@@ -13,6 +14,11 @@ export * from "./properties";
 export * from "./calendar";
 export * from "./embeds";
 export * from "./query";
-export * from "./import";
+export * from "./jobs";
 export * from "./habits";
 export * from "./templates";
+export * from "./books";
+export * from "./tables";
+export * from "./diagnostics";
+export * from "./spellcheck";
+export * from "./import";
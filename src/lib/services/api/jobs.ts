@@ -0,0 +1,54 @@
+/**
+ * Jobs API - long-running operations that run in the background
+ *
+ * Each `start*` function queues a job and returns it right away. Listen for
+ * "job:progress" and "job:status" events to follow it.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { EmbeddingSettings, ImportVaultRequest, JobDto } from "../../types";
+
+/** Reindex every note in the vault. */
+export async function startFullIndex(): Promise<JobDto> {
+  return invoke<JobDto>("start_full_index");
+}
+
+/** Import an Obsidian vault into the current vault. */
+export async function startVaultImport(request: ImportVaultRequest): Promise<JobDto> {
+  return invoke<JobDto>("start_vault_import", { request });
+}
+
+/** Embed every note that has no current embedding. */
+export async function startEmbeddingBackfill(settings: EmbeddingSettings): Promise<JobDto> {
+  return invoke<JobDto>("start_embedding_backfill", { settings });
+}
+
+/** Back up the vault, with a copy of its database, to a zip file. */
+export async function startBackup(outputPath: string): Promise<JobDto> {
+  return invoke<JobDto>("start_backup", { outputPath });
+}
+
+/** Recognize the text in the vault's new and changed images. */
+export async function startOcr(): Promise<JobDto> {
+  return invoke<JobDto>("start_ocr");
+}
+
+/** List queued, running and finished jobs, newest first. */
+export async function listJobs(): Promise<JobDto[]> {
+  return invoke<JobDto[]>("list_jobs");
+}
+
+/** Cancel a queued or running job. Returns false if it has already finished. */
+export async function cancelJob(jobId: number): Promise<boolean> {
+  return invoke<boolean>("cancel_job", { jobId });
+}
+
+/** Queue a failed or cancelled job to run again. */
+export async function retryJob(jobId: number): Promise<JobDto> {
+  return invoke<JobDto>("retry_job", { jobId });
+}
+
+/** Delete finished jobs. Returns how many were deleted. */
+export async function clearFinishedJobs(): Promise<number> {
+  return invoke<number>("clear_finished_jobs");
+}
@@ -3,7 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { NoteListItem, NoteDto, NoteContent } from "../../types";
+import type { NoteListItem, NoteDto, NoteContent, DiagramSettings, MathSettings, UnsavedDraft, EditSession } from "../../types";
 
 export async function listNotes(): Promise<NoteListItem[]> {
   return invoke<NoteListItem[]>("list_notes");
@@ -13,18 +13,90 @@ export async function getNote(noteId: number): Promise<NoteDto> {
   return invoke<NoteDto>("get_note", { noteId });
 }
 
-export async function getNoteContent(path: string): Promise<NoteContent> {
-  return invoke<NoteContent>("get_note_content", { path });
+/**
+ * Get a note's content: all of it, or with `length`, up to that many bytes
+ * from byte `offset`, to load very large notes in parts.
+ */
+export async function getNoteContent(path: string, offset?: number, length?: number): Promise<NoteContent> {
+  return invoke<NoteContent>("get_note_content", { path, offset, length });
 }
 
 export async function saveNote(path: string, content: string): Promise<number> {
   return invoke<number>("save_note", { path, content });
 }
 
+/**
+ * Store the editor's unsaved content of a note for crash recovery.
+ * Returns whether a draft is kept (not if it matches the file, or the note
+ * is encrypted).
+ */
+export async function saveDraft(path: string, content: string): Promise<boolean> {
+  return invoke<boolean>("save_draft", { path, content });
+}
+
+/**
+ * Get the drafts with content their files don't have, e.g. after a crash.
+ */
+export async function getUnsavedDrafts(): Promise<UnsavedDraft[]> {
+  return invoke<UnsavedDraft[]>("get_unsaved_drafts");
+}
+
+/**
+ * Delete the draft of a note, after it was restored or dismissed.
+ */
+export async function discardDraft(path: string): Promise<void> {
+  return invoke("discard_draft", { path });
+}
+
+/**
+ * Begin editing a note in `editor`, an ID unique to the editor within this
+ * window. Returns the session holding the note, which is another window's
+ * if it edits the note already, unless `takeover` moves the session here.
+ */
+export async function beginEdit(noteId: number, editor: string, takeover = false): Promise<EditSession> {
+  return invoke<EditSession>("begin_edit", { noteId, editor, takeover });
+}
+
+/**
+ * End an editor's editing session on a note. This window's session ends
+ * once none of its editors hold the note.
+ */
+export async function endEdit(noteId: number, editor: string): Promise<void> {
+  return invoke("end_edit", { noteId, editor });
+}
+
+export async function renumberFootnotes(noteId: number): Promise<NoteContent> {
+  return invoke<NoteContent>("renumber_footnotes", { noteId });
+}
+
 export async function renameNote(oldPath: string, newPath: string): Promise<number> {
   return invoke<number>("rename_note", { oldPath, newPath });
 }
 
+export async function moveNote(path: string, targetFolder: string): Promise<NoteDto> {
+  return invoke<NoteDto>("move_note", { path, targetFolder });
+}
+
 export async function deleteNote(path: string): Promise<number | null> {
   return invoke<number | null>("delete_note", { path });
 }
+
+export async function renderDiagram(noteId: number, blockIndex: number): Promise<string> {
+  return invoke<string>("render_diagram", { noteId, blockIndex });
+}
+
+export async function getDiagramSettings(): Promise<DiagramSettings> {
+  return invoke<DiagramSettings>("get_diagram_settings");
+}
+
+export async function saveDiagramSettings(settings: DiagramSettings): Promise<void> {
+  return invoke("save_diagram_settings", { settings });
+}
+
+export async function getMathSettings(): Promise<MathSettings> {
+  return invoke<MathSettings>("get_math_settings");
+}
+
+export async function saveMathSettings(settings: MathSettings): Promise<void> {
+  return invoke("save_math_settings", { settings });
+}
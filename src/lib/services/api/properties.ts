@@ -17,6 +17,9 @@ import type {
   SetFolderPropertyRequest,
   PropertyWithInheritance,
   ConvertFrontmatterResponse,
+  PropertyFilter,
+  PropertyTable,
+  PropertyUpdate,
 } from "../../types";
 
 export async function getProperties(noteId: number): Promise<PropertyDto[]> {
@@ -85,6 +88,18 @@ export async function getNotesWithPropertyValue(
   return invoke<NoteWithPropertyValue[]>("get_notes_with_property_value", { key, value });
 }
 
+// ============================================================================
+// Property Table Operations
+// ============================================================================
+
+export async function getPropertyTable(filters: PropertyFilter[], columns: string[]): Promise<PropertyTable> {
+  return invoke<PropertyTable>("get_property_table", { filters, columns });
+}
+
+export async function batchSetProperties(updates: PropertyUpdate[]): Promise<PropertyOperationResult> {
+  return invoke<PropertyOperationResult>("batch_set_properties", { updates });
+}
+
 // ============================================================================
 // Folder Property Operations
 // ============================================================================
@@ -43,7 +43,41 @@ export async function runQuery(request: QueryRequest): Promise<QueryResponse> {
 /**
  * Execute a query embed from YAML content.
  * This parses the YAML and executes the query, returning both the parsed config and results.
+ * `noteId` is the note containing the block, for `{{this.<property>}}` and
+ * `{{this.file.name}}` in filters. `blockIndex` is the block's position among
+ * the note's query blocks, which names its Kanban boards.
  */
-export async function executeQueryEmbed(yamlContent: string): Promise<QueryEmbedResponse> {
-  return invoke<QueryEmbedResponse>("execute_query_embed", { yamlContent });
+export async function executeQueryEmbed(
+  yamlContent: string,
+  noteId: number | null = null,
+  blockIndex: number | null = null
+): Promise<QueryEmbedResponse> {
+  return invoke<QueryEmbedResponse>("execute_query_embed", { yamlContent, noteId, blockIndex });
+}
+
+/**
+ * Execute a query embed again, bypassing the backend's cache of recent
+ * results.
+ */
+export async function refreshQueryEmbed(
+  yamlContent: string,
+  noteId: number | null = null,
+  blockIndex: number | null = null
+): Promise<QueryEmbedResponse> {
+  return invoke<QueryEmbedResponse>("refresh_query_embed", { yamlContent, noteId, blockIndex });
+}
+
+/**
+ * Move a card on a query Kanban board between the cards `before` and `after`
+ * (those that end up above and below it). `boardKey` is the view's
+ * `kanban.board`; `itemRef` is the card's `item_ref`.
+ * Returns the card's new rank.
+ */
+export async function reorderQueryResult(
+  boardKey: string,
+  itemRef: string,
+  before: string | null,
+  after: string | null
+): Promise<number> {
+  return invoke<number>("reorder_query_result", { boardKey, itemRef, before, after });
 }
@@ -5,15 +5,53 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   SearchResult,
+  SearchFilters,
+  SearchPresetDto,
+  SaveSearchPresetRequest,
+  AutocompleteItem,
+  AutocompleteKind,
   BacklinkDto,
+  GraphExportFormat,
+  GraphExportResult,
+  GraphScope,
+  LinkCandidate,
+  LinkSettings,
+  LinkSuggestion,
+  NoteLinkCount,
+  NoteResolution,
   HybridSearchResult,
+  ImageTextMatch,
   EmbeddingSettings,
   EmbeddingStatus,
 } from "../../types";
 
-/** Search notes using FTS5. */
-export async function searchNotes(query: string, limit?: number): Promise<SearchResult[]> {
-  return invoke<SearchResult[]>("search_notes", { query, limit });
+/** Search notes using FTS5, optionally scoped by folder, tags and date range. */
+export async function searchNotes(
+  query: string,
+  limit?: number,
+  filters?: SearchFilters
+): Promise<SearchResult[]> {
+  return invoke<SearchResult[]>("search_notes", { query, limit, filters });
+}
+
+/** Save a search with its filters under a name. Returns the preset ID. */
+export async function saveSearchPreset(request: SaveSearchPresetRequest): Promise<number> {
+  return invoke<number>("save_search_preset", { request });
+}
+
+/** List saved search presets. */
+export async function listSearchPresets(): Promise<SearchPresetDto[]> {
+  return invoke<SearchPresetDto[]>("list_search_presets");
+}
+
+/** Delete a search preset. */
+export async function deleteSearchPreset(id: number): Promise<void> {
+  return invoke<void>("delete_search_preset", { id });
+}
+
+/** Search the text recognized in the vault's images (see `startOcr`). */
+export async function searchImageText(query: string, limit?: number): Promise<ImageTextMatch[]> {
+  return invoke<ImageTextMatch[]>("search_image_text", { query, limit });
 }
 
 /** Search notes using hybrid FTS5 + vector search. */
@@ -51,6 +89,81 @@ export async function getBacklinks(noteId: number): Promise<BacklinkDto[]> {
   return invoke<BacklinkDto[]>("get_backlinks", { noteId });
 }
 
+/**
+ * Complete titles, aliases, tags, property keys, property values (of the
+ * property `key`, if given), task contexts or headings by prefix, from the
+ * in-memory autocomplete index.
+ */
+export async function autocomplete(
+  kind: AutocompleteKind,
+  prefix: string,
+  limit?: number,
+  key?: string
+): Promise<AutocompleteItem[]> {
+  return invoke<AutocompleteItem[]>("autocomplete", { kind, prefix, limit, key });
+}
+
+/**
+ * Suggest link targets for the paragraph being written, ranked by fuzzy
+ * match of the link being typed (after `[[`), shared tags and, with
+ * embedding settings enabled, similarity to the paragraph.
+ */
+export async function suggestLinks(
+  contextText: string,
+  limit?: number,
+  noteId?: number,
+  settings?: EmbeddingSettings
+): Promise<LinkSuggestion[]> {
+  return invoke<LinkSuggestion[]>("suggest_links", { contextText, limit, noteId, settings });
+}
+
+/** Notes with no links in or out. */
+export async function getOrphanNotes(): Promise<NoteLinkCount[]> {
+  return invoke<NoteLinkCount[]>("get_orphan_notes");
+}
+
+/** Notes that link to no other note, most linked first. */
+export async function getDeadEndNotes(): Promise<NoteLinkCount[]> {
+  return invoke<NoteLinkCount[]>("get_dead_end_notes");
+}
+
+/** The most linked notes. */
+export async function getHubNotes(limit?: number): Promise<NoteLinkCount[]> {
+  return invoke<NoteLinkCount[]>("get_hub_notes", { limit });
+}
+
+/**
+ * Export the link structure of the vault (or of a folder or tag) as a DOT,
+ * GEXF or JSON graph file, e.g. for Gephi or Graphviz.
+ */
+export async function exportGraph(
+  format: GraphExportFormat,
+  outputPath: string,
+  scope: GraphScope = {},
+): Promise<GraphExportResult> {
+  return invoke<GraphExportResult>("export_graph", { format, scope, outputPath });
+}
+
+/** Notes a wiki link could refer to, if more than one; empty if unambiguous. */
+export async function getAmbiguousLinkTargets(target: string, fromPath?: string): Promise<LinkCandidate[]> {
+  return invoke<LinkCandidate[]>("get_ambiguous_link_targets", { target, fromPath });
+}
+
+/** Rank the notes a reference could refer to, including near-typos. */
+export async function resolveNote(target: string, fromPath?: string, limit?: number): Promise<NoteResolution> {
+  return invoke<NoteResolution>("resolve_note", { target, fromPath, limit });
+}
+
+/** Get the vault's link settings. */
+export async function getLinkSettings(): Promise<LinkSettings> {
+  return invoke<LinkSettings>("get_link_settings");
+}
+
+/** Save the vault's link settings; existing links are not rewritten. */
+export async function saveLinkSettings(settings: LinkSettings): Promise<void> {
+  return invoke("save_link_settings", { settings });
+}
+
 /** Generate embedding for a single note. */
 export async function generateNoteEmbedding(
   noteId: number,
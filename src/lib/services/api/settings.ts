@@ -0,0 +1,22 @@
+/**
+ * App settings API - per-device settings in the OS config directory
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { AppSettings } from "../../types";
+
+export async function getAppSettings(): Promise<AppSettings> {
+  return invoke<AppSettings>("get_app_settings");
+}
+
+export async function saveAppSettings(settings: AppSettings): Promise<void> {
+  return invoke("save_app_settings", { settings });
+}
+
+/**
+ * Hand settings kept by an older version over to the settings file, unless
+ * there is one already. Returns the settings in effect.
+ */
+export async function migrateAppSettings(legacy: AppSettings): Promise<AppSettings> {
+  return invoke<AppSettings>("migrate_app_settings", { legacy });
+}
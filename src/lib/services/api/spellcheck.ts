@@ -0,0 +1,21 @@
+/**
+ * Spell check API - checking text and the vault's custom dictionary
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { Misspelling } from "../../types";
+
+/**
+ * Check markdown text for misspelled words in a language (e.g. "en_US").
+ * Ranges are UTF-16 offsets into `text`, as used by the editor.
+ */
+export async function checkText(text: string, lang: string): Promise<Misspelling[]> {
+  return invoke<Misspelling[]>("check_text", { text, lang });
+}
+
+/**
+ * Add a word to the vault's custom dictionary.
+ */
+export async function addToDictionary(word: string): Promise<void> {
+  return invoke("add_to_dictionary", { word });
+}
@@ -0,0 +1,47 @@
+/**
+ * Tables API - formatting and editing markdown tables, CSV import/export
+ *
+ * The editing functions take the note content and the 1-based line of any
+ * row of the table, and return the content with the table rewritten.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { NoteContent } from "../../types";
+
+export async function formatTable(content: string, line: number): Promise<string> {
+  return invoke<string>("format_table", { content, line });
+}
+
+export async function sortTableByColumn(
+  content: string,
+  line: number,
+  column: number,
+  descending?: boolean
+): Promise<string> {
+  return invoke<string>("sort_table_by_column", { content, line, column, descending });
+}
+
+export async function tableAddRow(content: string, line: number, index?: number): Promise<string> {
+  return invoke<string>("table_add_row", { content, line, index });
+}
+
+export async function tableAddColumn(
+  content: string,
+  line: number,
+  index?: number,
+  header?: string
+): Promise<string> {
+  return invoke<string>("table_add_column", { content, line, index, header });
+}
+
+export async function importCsvAsTable(
+  csvPath: string,
+  notePath: string,
+  position?: number
+): Promise<NoteContent> {
+  return invoke<NoteContent>("import_csv_as_table", { csvPath, notePath, position });
+}
+
+export async function exportTableAsCsv(noteId: number, tableIndex: number, output: string): Promise<number> {
+  return invoke<number>("export_table_as_csv", { noteId, tableIndex, output });
+}
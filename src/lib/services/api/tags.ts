@@ -3,7 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { TagDto } from "../../types";
+import type { TagDto, TagMeta } from "../../types";
 
 export async function listTags(): Promise<TagDto[]> {
   return invoke<TagDto[]>("list_tags");
@@ -16,3 +16,18 @@ export async function getAllTags(): Promise<string[]> {
   const tags = await listTags();
   return tags.map((t) => t.tag).sort((a, b) => a.localeCompare(b));
 }
+
+export async function listTagMeta(): Promise<TagMeta[]> {
+  return invoke<TagMeta[]>("list_tag_meta");
+}
+
+/**
+ * Set a tag's alias, description and color.
+ */
+export async function setTagMeta(meta: TagMeta): Promise<void> {
+  return invoke("set_tag_meta", { meta });
+}
+
+export async function deleteTagMeta(tag: string): Promise<void> {
+  return invoke("delete_tag_meta", { tag });
+}
@@ -1,9 +1,24 @@
 /**
- * Templates API - daily note creation and template settings
+ * Templates API - daily note creation, template settings and note types
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { TemplateSettings, DailyNoteResult } from "../../types";
+import type {
+  TemplateSettings,
+  DailyNoteResult,
+  NoteContent,
+  NoteDto,
+  NoteTypeDefinition,
+  NotePeriod,
+  PeriodDirection,
+  PeriodicNote,
+  LocaleSettings,
+  TemplateRenderContext,
+  ScheduledNoteRule,
+  SaveScheduledNoteRuleRequest,
+  ScheduledNoteCreated,
+  WeekInfo,
+} from "../../types";
 
 // ============================================================================
 // Template Settings
@@ -23,6 +38,24 @@ export async function saveTemplateSettings(settings: TemplateSettings): Promise<
   return invoke("save_template_settings", { settings });
 }
 
+// ============================================================================
+// Locale Settings
+// ============================================================================
+
+/**
+ * Get locale settings (week start, date and time formats) from vault config.
+ */
+export async function getLocaleSettings(): Promise<LocaleSettings> {
+  return invoke<LocaleSettings>("get_locale_settings");
+}
+
+/**
+ * Save locale settings to vault config.
+ */
+export async function saveLocaleSettings(settings: LocaleSettings): Promise<void> {
+  return invoke("save_locale_settings", { settings });
+}
+
 // ============================================================================
 // Template Files
 // ============================================================================
@@ -50,6 +83,65 @@ export async function createNoteFromTemplate(
   return invoke<number>("create_note_from_template", { targetPath, templatePath });
 }
 
+/**
+ * Render template text with the built-in variables, date arithmetic such as
+ * `{{date+7d:DD.MM.YYYY}}`, the vault's variables and snippets, and the
+ * active note title and clipboard text from `context`.
+ */
+export async function renderTemplateString(text: string, context: TemplateRenderContext = {}): Promise<string> {
+  return invoke<string>("render_template_string", { text, context });
+}
+
+/**
+ * Render quick capture text like `renderTemplateString` and append it to
+ * the daily note (today's, unless `context.date` is set). Fails for a locked
+ * encrypted note. Returns the daily note with its new content.
+ */
+export async function quickCapture(text: string, context: TemplateRenderContext = {}): Promise<NoteContent> {
+  return invoke<NoteContent>("quick_capture", { text, context });
+}
+
+// ============================================================================
+// Scheduled Notes
+// ============================================================================
+
+/**
+ * List the rules that create notes on a schedule.
+ */
+export async function listScheduledNoteRules(): Promise<ScheduledNoteRule[]> {
+  return invoke<ScheduledNoteRule[]>("list_scheduled_note_rules");
+}
+
+/**
+ * Create a scheduled note rule. Its first note is created at the next occurrence.
+ * Fails if its time or rrule is invalid.
+ */
+export async function createScheduledNoteRule(request: SaveScheduledNoteRuleRequest): Promise<number> {
+  return invoke<number>("create_scheduled_note_rule", { request });
+}
+
+/**
+ * Change a scheduled note rule, or enable or disable it. A rule enabled again
+ * doesn't catch up on what it missed while disabled.
+ */
+export async function updateScheduledNoteRule(id: number, request: SaveScheduledNoteRuleRequest): Promise<void> {
+  return invoke("update_scheduled_note_rule", { id, request });
+}
+
+/**
+ * Delete a scheduled note rule. Notes it created are kept.
+ */
+export async function deleteScheduledNoteRule(id: number): Promise<void> {
+  return invoke("delete_scheduled_note_rule", { id });
+}
+
+/**
+ * Create the notes of all rules that are due now.
+ */
+export async function runScheduledNotes(): Promise<ScheduledNoteCreated[]> {
+  return invoke<ScheduledNoteCreated[]>("run_scheduled_notes");
+}
+
 // ============================================================================
 // Daily Notes
 // ============================================================================
@@ -65,6 +157,70 @@ export async function createDailyNote(date: string): Promise<DailyNoteResult> {
   return invoke<DailyNoteResult>("create_daily_note", { date });
 }
 
+/**
+ * Get the daily, weekly or monthly note for the period containing a date.
+ *
+ * @param date - Date in YYYY-MM-DD format
+ * @param period - "daily", "weekly" or "monthly"
+ * @param create - Create the note from the period's template if it doesn't exist
+ */
+export async function getPeriodicNoteFor(
+  date: string,
+  period: NotePeriod,
+  create = false
+): Promise<PeriodicNote> {
+  return invoke<PeriodicNote>("get_periodic_note_for", { date, period, create });
+}
+
+/**
+ * Get the periodic note before or after the one for a date (previous/next
+ * day, week or month), for journal navigation.
+ *
+ * @param date - Date in YYYY-MM-DD format, anywhere in the current period
+ * @param period - "daily", "weekly" or "monthly"
+ * @param direction - "previous" or "next"
+ * @param create - Create the note from the period's template if it doesn't exist
+ */
+export async function getAdjacentPeriodicNote(
+  date: string,
+  period: NotePeriod,
+  direction: PeriodDirection,
+  create = false
+): Promise<PeriodicNote> {
+  return invoke<PeriodicNote>("get_adjacent_periodic_note", { date, period, direction, create });
+}
+
+/**
+ * Refresh the rollup of a weekly note: links to the week's daily notes and
+ * a query block of the tasks completed that week. Creates the weekly note
+ * if it doesn't exist.
+ *
+ * @param week - Any date in the week, YYYY-MM-DD
+ */
+export async function updateWeeklyRollup(week: string): Promise<PeriodicNote> {
+  return invoke<PeriodicNote>("update_weekly_rollup", { week });
+}
+
+/**
+ * Refresh the rollup of a monthly note: links to the month's weekly notes
+ * and a query block of the tasks completed that month. Creates the monthly
+ * note if it doesn't exist.
+ *
+ * @param month - Any date in the month, YYYY-MM-DD
+ */
+export async function updateMonthlyRollup(month: string): Promise<PeriodicNote> {
+  return invoke<PeriodicNote>("update_monthly_rollup", { month });
+}
+
+/**
+ * Get the ISO 8601 week containing a date.
+ *
+ * @param date - Date in YYYY-MM-DD format
+ */
+export async function getWeekInfo(date: string): Promise<WeekInfo> {
+  return invoke<WeekInfo>("get_week_info", { date });
+}
+
 /**
  * Preview the daily note path for a given date pattern.
  * Useful for settings UI to show what path will be generated.
@@ -76,3 +232,35 @@ export async function createDailyNote(date: string): Promise<DailyNoteResult> {
 export async function previewDailyNotePath(pattern: string, date: string): Promise<string> {
   return invoke<string>("preview_daily_note_path", { pattern, date });
 }
+
+// ============================================================================
+// Note Types
+// ============================================================================
+
+/**
+ * List the vault's note types for the new-note dialog: the configured ones
+ * (or the built-in meeting, book and person types), then those defined by a
+ * template in the types/ folder.
+ */
+export async function listNoteTypes(): Promise<NoteTypeDefinition[]> {
+  return invoke<NoteTypeDefinition[]>("list_note_types");
+}
+
+/**
+ * Save the note types to vault config. An empty list restores the built-in types.
+ */
+export async function saveNoteTypes(types: NoteTypeDefinition[]): Promise<void> {
+  return invoke("save_note_types", { types });
+}
+
+/**
+ * Create a note of a type, in the type's folder and from its template,
+ * with its `type` and default properties set.
+ *
+ * @param noteType - The type's name (e.g., "meeting")
+ * @param title - The note title
+ * @returns The created note
+ */
+export async function createTypedNote(noteType: string, title: string): Promise<NoteDto> {
+  return invoke<NoteDto>("create_typed_note", { noteType, title });
+}
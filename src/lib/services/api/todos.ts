@@ -3,7 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { TodoDto, TaskQuery, TaskWithContext } from "../../types";
+import type { ContextDto, ContextMeta, TodoDto, TaskQuery, TaskWithContext } from "../../types";
 
 export async function getTodosForNote(noteId: number): Promise<TodoDto[]> {
   return invoke<TodoDto[]>("get_todos_for_note", { noteId });
@@ -30,3 +30,18 @@ export async function queryTasks(query: TaskQuery = {}): Promise<TaskWithContext
 export async function getTaskContexts(): Promise<string[]> {
   return invoke<string[]>("get_task_contexts");
 }
+
+/**
+ * List registered contexts and the contexts used by tasks, with open-task counts.
+ */
+export async function listContexts(): Promise<ContextDto[]> {
+  return invoke<ContextDto[]>("list_contexts");
+}
+
+export async function setContext(context: ContextMeta): Promise<void> {
+  return invoke("set_context", { context });
+}
+
+export async function deleteContext(name: string): Promise<void> {
+  return invoke("delete_context", { name });
+}
@@ -3,7 +3,31 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { VaultInfo } from "../../types";
+import type {
+  ChangesPage,
+  CreateVaultOptions,
+  ExternalFolder,
+  IndexCompletePayload,
+  IndexSettings,
+  IndexVerificationReport,
+  VaultInfo,
+} from "../../types";
+
+/**
+ * Create a new vault in an empty folder (folders, starter config, templates
+ * and a welcome note), then open it.
+ */
+export async function createVault(path: string, options?: CreateVaultOptions): Promise<VaultInfo> {
+  return invoke<VaultInfo>("create_vault", { path, options: options ?? null });
+}
+
+/**
+ * Create a demo vault in an empty folder (example projects, dashboards,
+ * daily notes, habits and schedule blocks), then open it.
+ */
+export async function createDemoVault(path: string): Promise<VaultInfo> {
+  return invoke<VaultInfo>("create_demo_vault", { path });
+}
 
 export async function openVault(path: string): Promise<VaultInfo> {
   return invoke<VaultInfo>("open_vault", { path });
@@ -13,6 +37,56 @@ export async function closeVault(): Promise<void> {
   return invoke("close_vault");
 }
 
+/**
+ * Open another app window on the current vault. Returns the new window's
+ * label. Saves in one window are sent to the others.
+ */
+export async function openWindow(): Promise<string> {
+  return invoke<string>("open_window");
+}
+
 export async function getVaultInfo(): Promise<VaultInfo | null> {
   return invoke<VaultInfo | null>("get_vault_info");
 }
+
+export async function getExternalFolders(): Promise<ExternalFolder[]> {
+  return invoke<ExternalFolder[]>("get_external_folders");
+}
+
+/**
+ * Replace the read-only external folders. The vault is reindexed by a
+ * full index job, reported through the job events.
+ */
+export async function setExternalFolders(folders: ExternalFolder[]): Promise<ExternalFolder[]> {
+  return invoke<ExternalFolder[]>("set_external_folders", { folders });
+}
+
+export async function getChangesSince(cursor: number, limit?: number): Promise<ChangesPage> {
+  return invoke<ChangesPage>("get_changes_since", { cursor, limit });
+}
+
+/**
+ * Re-hash every file and compare it to the index, listing notes whose
+ * database copy is stale and why. With `reindex`, only the stale notes are
+ * re-indexed (e.g. after restoring the vault from a backup).
+ */
+export async function verifyIndex(reindex = false): Promise<IndexVerificationReport> {
+  return invoke<IndexVerificationReport>("verify_index", { reindex });
+}
+
+/**
+ * Rescan one folder (including subfolders) instead of the whole vault:
+ * changed notes are reindexed and deleted ones removed. Fails for the vault root.
+ */
+export async function reindexPath(prefix: string): Promise<IndexCompletePayload> {
+  return invoke<IndexCompletePayload>("reindex_path", { prefix });
+}
+
+export async function getIndexSettings(): Promise<IndexSettings> {
+  return invoke<IndexSettings>("get_index_settings");
+}
+
+/** Save the size limit for indexing notes; notes use it the next time they change. */
+export async function saveIndexSettings(settings: IndexSettings): Promise<void> {
+  return invoke("save_index_settings", { settings });
+}
@@ -3,11 +3,17 @@
  */
 
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import type {
   NotesUpdatedPayload,
   NotesDeletedPayload,
+  NoteSavedPayload,
+  EditSessionChangedPayload,
   IndexCompletePayload,
   ImportProgress,
+  ImportResult,
+  JobProgressPayload,
+  JobDto,
 } from "../types";
 
 export type EventCallback<T> = (payload: T) => void;
@@ -24,6 +30,22 @@ export function onNotesDeleted(callback: EventCallback<NotesDeletedPayload>): Pr
   });
 }
 
+/** Notes saved in other windows; saves of this window are skipped. */
+export function onNoteSavedElsewhere(callback: EventCallback<NoteSavedPayload>): Promise<UnlistenFn> {
+  const label = getCurrentWindow().label;
+  return listen<NoteSavedPayload>("note:saved", (event) => {
+    if (event.payload.window !== label) {
+      callback(event.payload);
+    }
+  });
+}
+
+export function onEditSessionChanged(callback: EventCallback<EditSessionChangedPayload>): Promise<UnlistenFn> {
+  return listen<EditSessionChangedPayload>("edit:changed", (event) => {
+    callback(event.payload);
+  });
+}
+
 export function onIndexComplete(callback: EventCallback<IndexCompletePayload>): Promise<UnlistenFn> {
   return listen<IndexCompletePayload>("index:complete", (event) => {
     callback(event.payload);
@@ -35,3 +57,22 @@ export function onImportProgress(callback: EventCallback<ImportProgress>): Promi
     callback(event.payload);
   });
 }
+
+/** The result of a vault import job, sent just before the job finishes. */
+export function onImportComplete(callback: EventCallback<ImportResult>): Promise<UnlistenFn> {
+  return listen<ImportResult>("import:complete", (event) => {
+    callback(event.payload);
+  });
+}
+
+export function onJobProgress(callback: EventCallback<JobProgressPayload>): Promise<UnlistenFn> {
+  return listen<JobProgressPayload>("job:progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+export function onJobStatus(callback: EventCallback<JobDto>): Promise<UnlistenFn> {
+  return listen<JobDto>("job:status", (event) => {
+    callback(event.payload);
+  });
+}
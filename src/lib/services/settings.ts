@@ -1,65 +1,116 @@
 /**
- * Settings service - persists user preferences to localStorage.
+ * Settings service - this device's settings, stored by the backend in the
+ * OS config directory (vault settings live in the vault config instead).
+ *
+ * Settings are loaded once with initSettings() and read from memory after
+ * that; changes are saved in the background. Older versions kept settings
+ * in localStorage; initSettings() hands those over on first run.
  */
 
-import type { CalendarView } from "../stores/workspace.svelte";
+import * as api from "./api";
+import type { AppSettings } from "../types";
+import { DEFAULT_EMBEDDING_SETTINGS } from "../types";
 import { logger } from "../utils/logger";
 
-const STORAGE_KEY = "neuroflow-settings";
+/** localStorage keys used by older versions. */
+const LEGACY_SETTINGS_KEY = "neuroflow-settings";
+const LEGACY_EMBEDDING_KEY = "neuroflow:embeddingSettings";
 
 // Theme is a string to allow dynamic themes from the themes directory
 // Built-in values: "system" (follows OS), "light", "dark"
 // Custom themes: any filename from src/lib/styles/themes/ without .css extension
 export type Theme = string;
 
-export interface AppSettings {
-  lastVaultPath: string | null;
-  defaultCalendarView: CalendarView;
-  theme: Theme;
-  vimMode: boolean;
-}
-
 const defaultSettings: AppSettings = {
-  lastVaultPath: null,
-  defaultCalendarView: "weekly",
+  last_vault_path: null,
   theme: "system",
-  vimMode: false,
+  vim_mode: false,
+  default_calendar_view: "weekly",
+  editor: { font_family: null, font_size: null, line_height: null, spell_check_language: null },
+  crash_recovery: false,
+  window: null,
+  embedding_settings: { ...DEFAULT_EMBEDDING_SETTINGS },
+  diagram_renderer_path: null,
 };
 
+let settings: AppSettings = { ...defaultSettings };
+
+/** Whether the saved settings were loaded; until then, saving would replace them. */
+let loaded = false;
+
 /**
- * Load settings from localStorage.
+ * Load the settings from the backend, taking over the settings in
+ * localStorage if an older version left any.
  */
-export function loadSettings(): AppSettings {
+export async function initSettings(): Promise<AppSettings> {
   try {
-    const stored = localStorage.getItem(STORAGE_KEY);
-    if (stored) {
-      const parsed = JSON.parse(stored);
-      return { ...defaultSettings, ...parsed };
+    const legacy = loadLegacySettings();
+    if (legacy) {
+      settings = await api.migrateAppSettings(legacy);
+      localStorage.removeItem(LEGACY_SETTINGS_KEY);
+      localStorage.removeItem(LEGACY_EMBEDDING_KEY);
+    } else {
+      settings = await api.getAppSettings();
     }
+    loaded = true;
   } catch (e) {
     logger.error("Settings", "Failed to load settings:", e);
   }
-  return { ...defaultSettings };
+  return settings;
 }
 
 /**
- * Save settings to localStorage.
+ * Settings left in localStorage by an older version, if any.
  */
-export function saveSettings(settings: Partial<AppSettings>): void {
+function loadLegacySettings(): AppSettings | null {
   try {
-    const current = loadSettings();
-    const updated = { ...current, ...settings };
-    localStorage.setItem(STORAGE_KEY, JSON.stringify(updated));
+    const stored = localStorage.getItem(LEGACY_SETTINGS_KEY);
+    const embedding = localStorage.getItem(LEGACY_EMBEDDING_KEY);
+    if (!stored && !embedding) {
+      return null;
+    }
+
+    const parsed = stored ? JSON.parse(stored) : {};
+    return {
+      ...defaultSettings,
+      last_vault_path: parsed.lastVaultPath ?? defaultSettings.last_vault_path,
+      theme: parsed.theme ?? defaultSettings.theme,
+      vim_mode: parsed.vimMode ?? defaultSettings.vim_mode,
+      default_calendar_view: parsed.defaultCalendarView ?? defaultSettings.default_calendar_view,
+      embedding_settings: { ...DEFAULT_EMBEDDING_SETTINGS, ...(embedding ? JSON.parse(embedding) : {}) },
+    };
   } catch (e) {
-    logger.error("Settings", "Failed to save settings:", e);
+    logger.error("Settings", "Failed to read legacy settings:", e);
+    return null;
   }
 }
 
+/**
+ * Get all settings.
+ */
+export function loadSettings(): AppSettings {
+  return { ...settings };
+}
+
+/**
+ * Update settings and save them in the background.
+ */
+export function saveSettings(update: Partial<AppSettings>): void {
+  settings = { ...settings, ...update };
+  if (!loaded) {
+    logger.warn("Settings", "Not saving settings: the saved ones could not be loaded");
+    return;
+  }
+  api.saveAppSettings(settings).catch((e) => {
+    logger.error("Settings", "Failed to save settings:", e);
+  });
+}
+
 /**
  * Get a single setting value.
  */
 export function getSetting<K extends keyof AppSettings>(key: K): AppSettings[K] {
-  return loadSettings()[key];
+  return settings[key];
 }
 
 /**
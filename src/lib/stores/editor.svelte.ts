@@ -2,16 +2,31 @@
  * Editor store - manages the currently open note and its content.
  */
 
-import type { NoteContent, TodoDto } from "../types";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import type { EditSession, EditSessionChangedPayload, NoteContent, TodoDto } from "../types";
 import * as api from "../services/api";
 import { extractH1Title, generatePathFromTitle, titleToFilename } from "../utils/docListUtils";
 import { workspaceStore } from "./workspace.svelte";
 import { vaultStore } from "./vault.svelte";
+import { getSetting } from "../services/settings";
 import { logger } from "../utils/logger";
 
 // Autosave delay in milliseconds
 const AUTOSAVE_DELAY = 1500;
 
+// How often a draft is stored while typing, with crash recovery enabled.
+// Autosave waits for a pause in typing; drafts don't.
+const DRAFT_INTERVAL = 1000;
+
+// Notes are loaded in parts of this many bytes, so the start of a very
+// large note shows before the rest is read
+const CONTENT_PART_BYTES = 512 * 1024;
+
+const utf8 = new TextEncoder();
+
+// Editing sessions are held per editor; columns use their own IDs
+const MAIN_EDITOR = "main";
+
 class EditorStore {
   currentNote = $state<NoteContent | null>(null);
   todos = $state<TodoDto[]>([]);
@@ -19,12 +34,30 @@ class EditorStore {
   isDirty = $state(false);
   error = $state<string | null>(null);
 
+  // Session of another window editing the current note; the editor is
+  // read-only while it is set
+  otherEditor = $state<EditSession | null>(null);
+
+  // The file's content when saving changed it (e.g. a new title kept the
+  // old one as an alias), for the editor to show
+  pendingContent = $state<string | null>(null);
+
+  // The rest of a large note is still loading; the editor is read-only
+  // until it has all of it
+  isLoadingRest = $state(false);
+
+  // H1 title of the content last saved or loaded
+  private savedTitle: string | null = null;
+
   // Flag to prevent reload during save/rename operations
   private isSaving = false;
 
   // Autosave timer
   private autosaveTimer: ReturnType<typeof setTimeout> | null = null;
 
+  // Crash recovery draft timer
+  private draftTimer: ReturnType<typeof setTimeout> | null = null;
+
   // Callback to notify App.svelte to refresh calendar data
   onScheduleBlocksUpdated: (() => void) | null = null;
 
@@ -54,11 +87,22 @@ class EditorStore {
 
     this.isLoading = true;
     this.error = null;
+    const previousId = this.currentId;
 
     try {
-      this.currentNote = await api.getNoteContent(path);
+      const note = await api.getNoteContent(path, 0, CONTENT_PART_BYTES);
+      this.currentNote = note;
+      this.savedTitle = extractH1Title(note.content);
       this.isDirty = false;
+      this.isLoadingRest = note.content.length > 0 && nextOffset(note) < note.total_bytes;
+      if (previousId !== null && previousId !== this.currentId) {
+        this.endEditSession(previousId);
+      }
+      await this.beginEditSession();
       await this.refreshTodos();
+      if (this.isLoadingRest) {
+        this.loadRest(note);
+      }
     } catch (e) {
       this.error = e instanceof Error ? e.message : String(e);
       throw e;
@@ -67,6 +111,79 @@ class EditorStore {
     }
   }
 
+  /**
+   * Read the rest of a large note part by part after its start is shown,
+   * then show all of it.
+   */
+  private async loadRest(start: NoteContent) {
+    const parts = [start.content];
+    let offset = nextOffset(start);
+    try {
+      while (offset < start.total_bytes) {
+        const part = await api.getNoteContent(start.path, offset, CONTENT_PART_BYTES);
+        if (this.currentNote?.id !== start.id) return;
+        if (part.content.length === 0) break;
+        parts.push(part.content);
+        offset = nextOffset(part);
+      }
+    } catch (e) {
+      logger.error("EditorStore", "Failed to load the rest of the note:", e);
+      if (this.currentNote?.id === start.id) {
+        this.error = e instanceof Error ? e.message : String(e);
+      }
+      return;
+    }
+
+    if (this.currentNote?.id !== start.id) return;
+    this.currentNote.content = parts.join("");
+    this.isLoadingRest = false;
+  }
+
+  /**
+   * Begin editing the current note in this window, or take over the
+   * session of the window editing it.
+   */
+  async beginEditSession(takeover = false) {
+    const noteId = this.currentId;
+    if (noteId === null) return;
+    try {
+      const session = await api.beginEdit(noteId, MAIN_EDITOR, takeover);
+      if (this.currentId === noteId) {
+        this.otherEditor = session.window === getCurrentWindow().label ? null : session;
+      }
+    } catch (e) {
+      logger.error("EditorStore", "Failed to begin editing session:", e);
+    }
+  }
+
+  private endEditSession(noteId: number) {
+    this.otherEditor = null;
+    api.endEdit(noteId, MAIN_EDITOR).catch((e) => logger.error("EditorStore", "Failed to end editing session:", e));
+  }
+
+  /**
+   * Follow editing sessions of the current note changed by other windows:
+   * stop editing when another window takes over (saving what was typed),
+   * and resume when the other window is done.
+   */
+  async handleEditSessionChanged(payload: EditSessionChangedPayload) {
+    if (payload.note_id !== this.currentId) return;
+
+    const session = payload.session;
+    if (session && session.window !== getCurrentWindow().label) {
+      if (!this.otherEditor && this.isDirty) {
+        try {
+          await this.save();
+        } catch (e) {
+          logger.error("EditorStore", "Failed to save before handing over editing:", e);
+        }
+      }
+      this.otherEditor = session;
+    } else if (!session && this.otherEditor) {
+      await this.beginEditSession();
+    }
+  }
+
   async refreshTodos() {
     if (!this.currentId) return;
 
@@ -82,6 +199,36 @@ class EditorStore {
       this.currentNote.content = content;
       this.isDirty = true;
       this.scheduleAutosave();
+      this.scheduleDraft();
+    }
+  }
+
+  /**
+   * Store a draft of the content for crash recovery, if enabled.
+   * Unlike autosave, this is not postponed while typing continues.
+   */
+  private scheduleDraft() {
+    if (this.draftTimer || !getSetting("crash_recovery")) return;
+
+    this.draftTimer = setTimeout(async () => {
+      this.draftTimer = null;
+      if (this.isDirty && this.currentNote) {
+        try {
+          await api.saveDraft(this.currentNote.path, this.currentNote.content);
+        } catch (e) {
+          logger.error("EditorStore", "Failed to save draft:", e);
+        }
+      }
+    }, DRAFT_INTERVAL);
+  }
+
+  /**
+   * Cancel any pending draft.
+   */
+  private cancelDraft() {
+    if (this.draftTimer) {
+      clearTimeout(this.draftTimer);
+      this.draftTimer = null;
     }
   }
 
@@ -119,18 +266,26 @@ class EditorStore {
   }
 
   async save() {
-    if (!this.currentNote || !this.isDirty) return;
+    // Saving part of a note would cut off the rest
+    if (!this.currentNote || !this.isDirty || this.isLoadingRest) return;
 
-    // Cancel any pending autosave since we're saving now
+    // Cancel any pending autosave and draft since we're saving now
     this.cancelAutosave();
+    this.cancelDraft();
 
     // Note: Don't set isLoading = true here - that would hide the editor and destroy the container
     this.isSaving = true;
     this.error = null;
 
     try {
-      await api.saveNote(this.currentNote.path, this.currentNote.content);
+      const { path, content } = this.currentNote;
+      await api.saveNote(path, content);
       this.isDirty = false;
+      const title = extractH1Title(content);
+      if (title !== this.savedTitle) {
+        this.savedTitle = title;
+        await this.takeSavedContent(path, content);
+      }
       await this.refreshTodos();
 
       // Sync H1 title to linked schedule blocks and rename file if needed
@@ -143,6 +298,22 @@ class EditorStore {
     }
   }
 
+  /**
+   * Show the file's content if saving changed it, unless typing went on
+   * meanwhile.
+   */
+  private async takeSavedContent(path: string, content: string) {
+    try {
+      const saved = await api.getNoteContent(path);
+      if (saved.content !== content && this.currentNote?.path === path && this.currentNote.content === content) {
+        this.currentNote.content = saved.content;
+        this.pendingContent = saved.content;
+      }
+    } catch (e) {
+      logger.error("EditorStore", "Failed to read saved note:", e);
+    }
+  }
+
   /**
    * Sync the current note's H1 title to schedule blocks and rename file if needed.
    */
@@ -228,11 +399,21 @@ class EditorStore {
 
   close() {
     this.cancelAutosave();
+    this.cancelDraft();
+    if (this.currentId !== null) {
+      this.endEditSession(this.currentId);
+    }
     this.currentNote = null;
     this.todos = [];
     this.isDirty = false;
+    this.isLoadingRest = false;
     this.error = null;
   }
 }
 
+/** Byte offset of the part of a note after the given one. */
+function nextOffset(part: NoteContent): number {
+  return part.offset + utf8.encode(part.content).length;
+}
+
 export const editorStore = new EditorStore();
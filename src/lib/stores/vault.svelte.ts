@@ -2,9 +2,11 @@
  * Vault store - manages the currently open vault state.
  */
 
-import type { VaultInfo, FolderNode } from "../types";
+import type { VaultInfo, FolderNode, LocaleSettings, UnsavedDraft } from "../types";
+import { DEFAULT_LOCALE_SETTINGS } from "../types";
 import * as api from "../services/api";
 import { getSetting, setSetting } from "../services/settings";
+import { setLocaleSettings } from "../utils/dateUtils";
 import { logger } from "../utils/logger";
 
 class VaultStore {
@@ -12,6 +14,10 @@ class VaultStore {
   folderTree = $state<FolderNode | null>(null);
   isLoading = $state(false);
   error = $state<string | null>(null);
+  /** Week start and date/time formats of the open vault. */
+  locale = $state<LocaleSettings>({ ...DEFAULT_LOCALE_SETTINGS });
+  /** Drafts left by a crash, offered for recovery when the vault opens. */
+  unsavedDrafts = $state<UnsavedDraft[]>([]);
 
   get isOpen() {
     return this.info !== null;
@@ -23,9 +29,11 @@ class VaultStore {
 
     try {
       this.info = await api.openVault(path);
+      await this.loadLocale();
       await this.refreshFolderTree();
+      await this.loadUnsavedDrafts();
       // Save as last opened vault
-      setSetting("lastVaultPath", path);
+      setSetting("last_vault_path", path);
     } catch (e) {
       this.error = e instanceof Error ? e.message : String(e);
       throw e;
@@ -36,7 +44,7 @@ class VaultStore {
 
   /** Try to open the last used vault */
   async openLastVault(): Promise<boolean> {
-    const lastPath = getSetting("lastVaultPath");
+    const lastPath = getSetting("last_vault_path");
     if (lastPath) {
       try {
         await this.open(lastPath);
@@ -44,7 +52,7 @@ class VaultStore {
       } catch (e) {
         logger.warn("VaultStore", "Failed to open last vault:", e);
         // Clear the invalid path
-        setSetting("lastVaultPath", null);
+        setSetting("last_vault_path", null);
       }
     }
     return false;
@@ -56,9 +64,49 @@ class VaultStore {
     } finally {
       this.info = null;
       this.folderTree = null;
+      this.unsavedDrafts = [];
+      this.applyLocale({ ...DEFAULT_LOCALE_SETTINGS });
     }
   }
 
+  async loadLocale() {
+    try {
+      this.applyLocale(await api.getLocaleSettings());
+    } catch (e) {
+      logger.error("VaultStore", "Failed to load locale settings:", e);
+    }
+  }
+
+  async saveLocale(settings: LocaleSettings) {
+    await api.saveLocaleSettings(settings);
+    this.applyLocale(settings);
+  }
+
+  private applyLocale(settings: LocaleSettings) {
+    setLocaleSettings(settings);
+    this.locale = settings;
+  }
+
+  async loadUnsavedDrafts() {
+    try {
+      this.unsavedDrafts = await api.getUnsavedDrafts();
+    } catch (e) {
+      logger.error("VaultStore", "Failed to load unsaved drafts:", e);
+    }
+  }
+
+  /** Save a draft to its note, replacing the file's content. */
+  async restoreDraft(draft: UnsavedDraft) {
+    await api.saveNote(draft.path, draft.content);
+    this.unsavedDrafts = this.unsavedDrafts.filter((d) => d.path !== draft.path);
+    await this.refreshFolderTree();
+  }
+
+  async discardDraft(path: string) {
+    await api.discardDraft(path);
+    this.unsavedDrafts = this.unsavedDrafts.filter((d) => d.path !== path);
+  }
+
   async refreshFolderTree() {
     if (!this.isOpen) return;
 
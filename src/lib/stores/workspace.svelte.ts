@@ -7,6 +7,7 @@
  */
 
 import { getSetting, setSetting, type Theme } from "../services/settings";
+import type { EditorSettings } from "../types";
 
 export type WorkspaceState = "calendar-only" | "calendar-with-doc" | "doc-finder";
 export type CalendarView = "monthly" | "weekly" | "daily";
@@ -32,7 +33,7 @@ class WorkspaceStore {
   state = $state<WorkspaceState>("calendar-only");
 
   // Calendar settings - initialized from saved settings
-  calendarView = $state<CalendarView>(getSetting("defaultCalendarView"));
+  calendarView = $state<CalendarView>(getSetting("default_calendar_view"));
   selectedDate = $state<Date>(new Date());
 
   // Currently open media file (if any)
@@ -149,12 +150,12 @@ class WorkspaceStore {
 
   /** Set the default calendar view (persisted to settings) */
   setDefaultCalendarView(view: CalendarView) {
-    setSetting("defaultCalendarView", view);
+    setSetting("default_calendar_view", view);
   }
 
   /** Get the saved default calendar view */
   getDefaultCalendarView(): CalendarView {
-    return getSetting("defaultCalendarView");
+    return getSetting("default_calendar_view");
   }
 
   /** Go to today */
@@ -334,6 +335,36 @@ class WorkspaceStore {
   /** Initialize theme on app start */
   initTheme() {
     this.applyTheme(this.theme);
+    this.applyEditorSettings(getSetting("editor"));
+  }
+
+  /** Set the editor font settings */
+  setEditorSettings(editor: EditorSettings) {
+    setSetting("editor", editor);
+    this.applyEditorSettings(editor);
+  }
+
+  /** Apply editor font settings to document (unset ones use the theme's) */
+  applyEditorSettings(editor: EditorSettings) {
+    const root = document.documentElement;
+    const set = (name: string, value: string | null) => {
+      if (value) {
+        root.style.setProperty(name, value);
+      } else {
+        root.style.removeProperty(name);
+      }
+    };
+
+    set("--editor-font-family", editor.font_family);
+    set("--editor-font-size", editor.font_size ? `${editor.font_size}px` : null);
+    set("--editor-line-height", editor.line_height ? String(editor.line_height) : null);
+  }
+
+  /** Take over the saved settings once they are loaded (see initSettings) */
+  loadSettings() {
+    this.calendarView = getSetting("default_calendar_view");
+    this.theme = getSetting("theme");
+    this.vimMode = getSetting("vim_mode");
   }
 
   // ========================================================================
@@ -341,12 +372,12 @@ class WorkspaceStore {
   // ========================================================================
 
   // Vim mode state (read from settings)
-  vimMode = $state(getSetting("vimMode"));
+  vimMode = $state(getSetting("vim_mode"));
 
   /** Toggle vim mode */
   toggleVimMode() {
     this.vimMode = !this.vimMode;
-    setSetting("vimMode", this.vimMode);
+    setSetting("vim_mode", this.vimMode);
     // Note: Editor needs to be recreated to apply vim mode change
     // This will happen on next note load or reload
   }
@@ -354,7 +385,7 @@ class WorkspaceStore {
   /** Set vim mode preference */
   setVimMode(enabled: boolean) {
     this.vimMode = enabled;
-    setSetting("vimMode", enabled);
+    setSetting("vim_mode", enabled);
   }
 }
 
@@ -0,0 +1,31 @@
+/**
+ * Book types - reading log metadata and shelves
+ */
+
+import type { QueryResultItem } from "./query";
+
+/**
+ * Book metadata looked up on OpenLibrary.
+ */
+export interface BookMetadata {
+  title: string | null;
+  authors: string[];
+  /** ISBN-13 if known, else ISBN-10. */
+  isbn: string | null;
+  /** Publication date as given by OpenLibrary (often just the year). */
+  published: string | null;
+  pages: number | null;
+  /** URL of the large cover image. */
+  cover_url: string | null;
+  /** Vault path of the downloaded cover (null if there is none or it failed to download). */
+  cover_path: string | null;
+}
+
+/**
+ * Books with the same reading status.
+ */
+export interface BookShelf {
+  /** The `status` property value (null for books without one). */
+  status: string | null;
+  books: QueryResultItem[];
+}
@@ -5,7 +5,10 @@
 import type { NoteListItem } from "./note";
 
 export interface ScheduleBlockDto {
+  /** The schedule block's id; 0 for calendar subscription events */
   id: number;
+  /** Unique among blocks and calendar events: "block:<id>" or "calendar:<event id>" */
+  key: string;
   note_id: number | null;
   date: string;
   start_time: string;
@@ -15,8 +18,12 @@ export interface ScheduleBlockDto {
   context: string | null;
   /** RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR") */
   rrule: string | null;
+  /** IANA time zone of date/start_time/end_time (null = vault local time) */
+  timezone: string | null;
   /** True if this is an occurrence of a recurring block (not the master) */
   is_occurrence: boolean;
+  /** True for events from a calendar subscription (read-only overlay) */
+  read_only: boolean;
 }
 
 export interface CreateScheduleBlockRequest {
@@ -29,6 +36,8 @@ export interface CreateScheduleBlockRequest {
   context: string | null;
   /** RFC 5545 recurrence rule (e.g., "FREQ=WEEKLY;BYDAY=MO,WE,FR") */
   rrule: string | null;
+  /** IANA time zone for the block's times (null = vault local time) */
+  timezone?: string | null;
 }
 
 export interface UpdateScheduleBlockRequest {
@@ -42,6 +51,32 @@ export interface UpdateScheduleBlockRequest {
   context: string | null;
   /** RFC 5545 recurrence rule. Set to empty string to clear recurrence. */
   rrule: string | null;
+  /** IANA time zone. Set to empty string to revert to local time. */
+  timezone?: string | null;
+}
+
+export interface CalendarSubscriptionDto {
+  id: number;
+  name: string;
+  /** ICS feed URL */
+  url: string;
+  color: string | null;
+  /** Minutes between automatic refreshes */
+  refresh_minutes: number;
+  /** When the feed was last fetched (RFC 3339) */
+  last_fetched: string | null;
+  /** Error from the last fetch, if it failed */
+  last_error: string | null;
+  event_count: number;
+}
+
+export interface CreateCalendarSubscriptionRequest {
+  name: string;
+  /** ICS feed URL (http, https, or webcal) */
+  url: string;
+  color: string | null;
+  /** Minutes between automatic refreshes (default: 60) */
+  refresh_minutes: number | null;
 }
 
 export interface NoteForDate {
@@ -0,0 +1,85 @@
+/**
+ * Diagnostics types
+ */
+
+/** Latency of a command over its recent calls. */
+export interface CommandLatency {
+  command: string;
+  /** Calls since the app started. */
+  count: number;
+  /** Median latency of the recent calls. */
+  p50_ms: number;
+  /** 95th percentile latency of the recent calls. */
+  p95_ms: number;
+  /** Slowest of the recent calls. */
+  max_ms: number;
+}
+
+/** A SQL statement that took longer than the slow query threshold. */
+export interface SlowQuery {
+  timestamp: string;
+  /** The start of the statement (e.g. "SELECT id, path FROM notes …"). */
+  summary: string;
+  /** The full statement, or empty if the summary is all of it. */
+  statement: string;
+  elapsed_ms: number;
+  /** The command running when the statement was executed, if known. */
+  command: string | null;
+}
+
+/** Per-command latency and recent slow queries, since the app started. */
+export interface PerformanceMetrics {
+  /** Commands by p95 latency, slowest first. */
+  commands: CommandLatency[];
+  /** Most recent first. */
+  slow_queries: SlowQuery[];
+  slow_query_threshold_ms: number;
+}
+
+/** A diagnostics bundle written for a bug report. */
+export interface DiagnosticsExport {
+  /** Where the zip was written. */
+  path: string;
+  size_bytes: number;
+  /** The files in the zip. */
+  entries: string[];
+}
+
+/** Why a file could not be read cleanly. */
+export type FileProblemKind = "invalid_utf8" | "binary";
+
+/**
+ * A file that could not be read cleanly when last indexed. Invalid UTF-8 is
+ * indexed with the bytes replaced; binary files are skipped.
+ */
+export interface FileProblem {
+  path: string;
+  kind: FileProblemKind;
+  /** When the problem was first found. */
+  detected_at: string;
+}
+
+/** A vault metric tracked by the daily snapshots. */
+export type VaultMetric = "notes" | "words" | "tasks" | "completed_tasks" | "links";
+
+/** The value of a metric on a day. */
+export interface GrowthPoint {
+  /** Date as YYYY-MM-DD. */
+  date: string;
+  value: number;
+}
+
+/**
+ * How a metric changed over a date range.
+ */
+export interface VaultGrowth {
+  metric: VaultMetric;
+  /**
+   * The days the metric changed on, ordered by date. The first point is the
+   * value at the start of the range, if known. Days without a point have the
+   * value of the point before.
+   */
+  points: GrowthPoint[];
+  /** Change from the first point to the last. */
+  change: number;
+}
@@ -10,8 +10,10 @@ export interface ResolveEmbedRequest {
   target: string;
   /** Optional section slug to extract (e.g., "my-section" from "## My Section"). */
   section?: string;
-  /** Current embedding depth (starts at 0, max 3). */
+  /** Current embedding depth (starts at 0, max 3); nested embeds are followed up to the max. */
   depth: number;
+  /** Path of the note containing the embed; same-folder matches are preferred. */
+  source_path?: string;
 }
 
 /**
@@ -30,6 +32,18 @@ export interface EmbedContent {
   assetUrl: string | null;
   /** Error message if resolution failed. */
   error: string | null;
+  /** The embed loop, if the target (directly or through its embeds) embeds a note already being embedded. */
+  cycle: EmbedCycle | null;
+}
+
+/**
+ * An embed loop found while resolving an embed.
+ */
+export interface EmbedCycle {
+  /** The embeds resolved (`path` or `path#section`), from the note with the embed up to and including `repeated`. */
+  chain: string[];
+  /** The entry that appears twice, closing the loop. */
+  repeated: string;
 }
 
 /**
@@ -16,6 +16,8 @@ export interface EmbeddingSettings {
   dimensions: number;
   /** Number of texts to process in a single batch. */
   batch_size: number;
+  /** Fraction of a note's text (0.0-1.0) that has to change before it is embedded again. */
+  reembed_threshold: number;
 }
 
 /**
@@ -46,6 +48,24 @@ export interface EmbeddingProgress {
   error: string | null;
 }
 
+/**
+ * Result of checking stored embeddings against the notes.
+ */
+export interface EmbeddingConsistencyReport {
+  /** Number of stored embeddings. */
+  total: number;
+  /** Embeddings of notes that no longer exist or are now encrypted. */
+  orphaned: number;
+  /** Embeddings of notes whose content changed since they were embedded. */
+  stale: number;
+  /** Embeddings whose size doesn't match the configured dimensions. */
+  wrong_dimensions: number;
+  /** Notes without an embedding. */
+  missing: number;
+  /** Orphaned and wrong-sized embeddings deleted by the check. */
+  removed: number;
+}
+
 /**
  * Default embedding settings.
  */
@@ -55,4 +75,5 @@ export const DEFAULT_EMBEDDING_SETTINGS: EmbeddingSettings = {
   model: "nomic-ai/nomic-embed-text-v1.5-GGUF",
   dimensions: 768,
   batch_size: 10,
+  reembed_threshold: 0.1,
 };
@@ -2,6 +2,10 @@
  * Event payload types for Tauri events
  */
 
+import type { EmbeddingSettings } from "./embedding";
+import type { ImportVaultRequest } from "./import";
+import type { EditSession } from "./note";
+
 export interface NotesUpdatedPayload {
   note_ids: number[];
 }
@@ -13,4 +17,84 @@ export interface NotesDeletedPayload {
 export interface IndexCompletePayload {
   notes_indexed: number;
   duration_ms: number;
+  /** Files that were found but could not be indexed. */
+  warnings: IndexWarning[];
+  /** The index was cancelled; notes indexed so far are kept. */
+  cancelled: boolean;
+}
+
+export interface IndexWarning {
+  path: string;
+  reason: string;
+}
+
+export type JobKind =
+  | "full_index"
+  | "vault_import"
+  | "journal_export"
+  | "embedding_backfill"
+  | "backup"
+  | "ocr";
+
+export type JobStatus = "queued" | "running" | "completed" | "cancelled" | "failed";
+
+/** What a job runs, with everything needed to run it again. */
+export type JobRequest =
+  | { type: "full_index" }
+  | { type: "vault_import"; request: ImportVaultRequest }
+  | {
+      type: "journal_export";
+      start_date: string;
+      end_date: string;
+      output_path: string;
+      options: { max_embed_depth: number; link_prefix: string; include_private: boolean } | null;
+    }
+  | { type: "embedding_backfill"; settings: EmbeddingSettings }
+  | { type: "backup"; output_path: string }
+  | { type: "ocr" };
+
+/** A queued, running or finished job; sent as the job:status event. */
+export interface JobDto {
+  id: number;
+  kind: JobKind;
+  status: JobStatus;
+  request: JobRequest;
+  /** Items processed (all of them unless cancelled or failed); set when the job finishes. */
+  processed: number;
+  total: number;
+  /** Summary of the result, or the error if the job failed. */
+  message: string | null;
+  created_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+}
+
+export interface JobProgressPayload {
+  job_id: number;
+  kind: JobKind;
+  processed: number;
+  total: number;
+  /** What is being worked on, e.g. the current file. */
+  message: string | null;
+}
+
+/**
+ * Sent when a window begins, takes over or ends the editing session of a
+ * note.
+ */
+export interface EditSessionChangedPayload {
+  note_id: number;
+  /** The session now held, if any. */
+  session: EditSession | null;
+}
+
+/** Sent to every window when a note is saved, renamed or moved, or one of its todos is toggled. */
+export interface NoteSavedPayload {
+  note_id: number;
+  path: string;
+  content: string;
+  /** Label of the window that saved it. */
+  window: string;
+  /** Where the note was before a rename or move. */
+  previous_path: string | null;
 }
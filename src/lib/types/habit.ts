@@ -2,6 +2,8 @@
  * Habit tracker types
  */
 
+import type { WeekStart } from "./locale";
+
 // ============================================================================
 // Habit Types
 // ============================================================================
@@ -25,6 +27,28 @@ export interface HabitDto {
   archived: boolean;
   /** Sort order for display. */
   sort_order: number;
+  /** Active days, planned skips, and streak freezes. */
+  schedule: HabitScheduleRules;
+}
+
+/** Per-habit schedule rules honored by streak computations. */
+export interface HabitScheduleRules {
+  /** Active ISO weekdays (1 = Monday ... 7 = Sunday). Empty means every day. */
+  active_weekdays: number[];
+  /** Planned skip dates as YYYY-MM-DD strings (e.g. travel days). */
+  skip_dates: string[];
+  /** Number of missed days forgiven within a single streak. */
+  freeze_tokens: number;
+}
+
+/** Streak statistics for a habit. */
+export interface HabitStreak {
+  /** Consecutive active days completed up to the reference date. */
+  current: number;
+  /** Longest streak on record. */
+  longest: number;
+  /** Freeze tokens used by the current streak. */
+  freezes_used: number;
 }
 
 /** Request to create a new habit. */
@@ -94,7 +118,7 @@ export interface UpdateHabitEntryRequest {
 // ============================================================================
 
 /** View type for habit tracker embeds. */
-export type HabitViewType = "table" | "calendar" | "streak" | "list";
+export type HabitViewType = "table" | "calendar" | "streak" | "list" | "heatmap";
 
 /** Orientation for table view. */
 export type HabitTableOrientation = "horizontal" | "vertical";
@@ -116,7 +140,9 @@ export interface HabitTrackerQuery {
   view: HabitViewType;
   /** Table orientation (only applies to table view). */
   orientation: HabitTableOrientation;
-  /** Date range preset. */
+  /** Date range preset. The heatmap view covers the calendar year of the
+   * reference date unless a custom range is given.
+   */
   date_range: HabitDateRange;
   /** Reference date for computing date ranges (YYYY-MM-DD). Defaults to today.
    * Useful for templates where you want to center the view on a specific date.
@@ -131,6 +157,8 @@ export interface HabitTrackerQuery {
   editable: boolean;
   /** Whether to show summary row (totals, streaks). */
   show_summary: boolean;
+  /** First day of the week for this_week; the vault's locale setting if not given. */
+  week_start?: WeekStart | null;
 }
 
 /** Habit with its entries for a date range. */
@@ -138,6 +166,20 @@ export interface HabitWithEntries {
   habit: HabitDto;
   /** Entries organized by date (YYYY-MM-DD -> entries). */
   entries_by_date: [string, HabitEntryDto[]][];
+  /** Dates in the range that are skipped (inactive weekdays or planned skips). */
+  skipped_dates: string[];
+  /** Streak as of the end of the range (or today, if earlier). */
+  streak: HabitStreak;
+  /** Per-day values normalized to 0-1 against the target (heatmap view only). */
+  heatmap: HabitHeatmapCell[];
+}
+
+/** A single day in a habit heatmap. */
+export interface HabitHeatmapCell {
+  /** Date as YYYY-MM-DD string. */
+  date: string;
+  /** Completion relative to the target, from 0.0 (nothing) to 1.0 (target met). */
+  value: number;
 }
 
 /** Response for habit tracker embed. */
@@ -38,4 +38,20 @@ export interface ImportResult {
   duration_ms: number;
   /** Any warnings or skipped files. */
   warnings: string[];
+  /** The import was cancelled; files imported so far are kept. */
+  cancelled: boolean;
+}
+
+/** Kind of a file copied into the vault by `importFiles`. */
+export type ImportedFileKind = "note" | "attachment";
+
+/** A file copied into the vault by `importFiles`. */
+export interface ImportedFile {
+  /** Original path outside the vault. */
+  source_path: string;
+  /** New path relative to the vault root (may have a number suffix). */
+  path: string;
+  kind: ImportedFileKind;
+  /** Note ID for imported notes. */
+  note_id: number | null;
 }
@@ -6,6 +6,7 @@ export * from "./common";
 export * from "./note";
 export * from "./calendar";
 export * from "./vault";
+export * from "./settings";
 export * from "./metadata";
 export * from "./search";
 export * from "./embedding";
@@ -15,3 +16,7 @@ export * from "./query";
 export * from "./import";
 export * from "./habit";
 export * from "./template";
+export * from "./book";
+export * from "./locale";
+export * from "./diagnostics";
+export * from "./spellcheck";
@@ -0,0 +1,47 @@
+/**
+ * Locale types
+ */
+
+/** The day weeks start on. */
+export type WeekStart = "monday" | "sunday" | "saturday";
+
+/**
+ * Locale settings (stored in vault config).
+ */
+export interface LocaleSettings {
+  /** First day of the week for calendars, weekly notes and week ranges. */
+  week_start: WeekStart;
+  /**
+   * How dates are shown, with `YYYY`, `MM`, `DD`, `M` and `D`
+   * (e.g., "DD.MM.YYYY"). Dates are still stored as YYYY-MM-DD.
+   */
+  date_format: string;
+  /** How times are shown, with `HH`, `H`, `hh`, `h`, `mm` and `a` for am/pm (e.g., "h:mm a"). */
+  time_format: string;
+}
+
+/**
+ * An ISO 8601 week: weeks start on Monday, and week 1 is the week with the
+ * year's first Thursday.
+ */
+export interface WeekInfo {
+  /** The year the week belongs to. */
+  year: number;
+  /** The week number (1-53). */
+  week: number;
+  /** The week as "2024-W15". */
+  label: string;
+  /** The week's Monday (YYYY-MM-DD). */
+  start: string;
+  /** The week's Sunday (YYYY-MM-DD). */
+  end: string;
+}
+
+/**
+ * Default locale settings.
+ */
+export const DEFAULT_LOCALE_SETTINGS: LocaleSettings = {
+  week_start: "monday",
+  date_format: "YYYY-MM-DD",
+  time_format: "HH:mm",
+};
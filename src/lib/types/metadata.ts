@@ -2,6 +2,8 @@
  * Metadata types - todos, tags, backlinks, properties, tasks
  */
 
+import type { NoteListItem } from "./note";
+
 export interface TodoDto {
   id: number;
   note_id: number;
@@ -15,6 +17,8 @@ export interface TodoDto {
   priority: string | null;
   /** Due date as YYYY-MM-DD string. */
   due_date: string | null;
+  /** Estimated effort in minutes (from an `est:` token). */
+  estimate_minutes: number | null;
   created_at: string | null;
   completed_at: string | null;
 }
@@ -25,6 +29,24 @@ export interface TaskWithContext {
   note_path: string;
   note_title: string | null;
   note_properties: PropertyDto[];
+  /** Path of the note embedding this task's note, for tasks rolled up from embedded notes. */
+  embedded_in?: string | null;
+}
+
+/** A registered GTD context (`@home`, `@phone`) and its metadata. */
+export interface ContextMeta {
+  /** Context name without the `@`. */
+  name: string;
+  icon: string | null;
+  description: string | null;
+  location: string | null;
+}
+
+/** A context used by tasks or registered, with its open-task count. */
+export interface ContextDto extends ContextMeta {
+  open_tasks: number;
+  /** Whether the context is in the registry. */
+  registered: boolean;
 }
 
 /** Query parameters for filtering tasks. */
@@ -48,6 +70,18 @@ export interface TaskQuery {
 export interface TagDto {
   tag: string;
   count: number;
+  description?: string | null;
+  color?: string | null;
+  /** Tags counted as this one. */
+  aliases?: string[];
+}
+
+export interface TagMeta {
+  tag: string;
+  /** The tag this one is an alias of. */
+  alias_of: string | null;
+  description: string | null;
+  color: string | null;
 }
 
 export interface BacklinkDto {
@@ -56,6 +90,113 @@ export interface BacklinkDto {
   from_note_title: string | null;
 }
 
+/** A note with its number of links, for orphan, dead-end and hub reports. */
+export interface NoteLinkCount {
+  note: NoteListItem;
+  /** Links from other notes to this note. */
+  inbound: number;
+  /** Links from this note to other notes. */
+  outbound: number;
+}
+
+/** File format of a link graph export: Graphviz DOT, GEXF (Gephi) or JSON. */
+export type GraphExportFormat = "dot" | "gexf" | "json";
+
+/**
+ * The notes a link graph export covers. Empty means the whole vault; with
+ * both set, notes must be in the folder and have the tag.
+ */
+export interface GraphScope {
+  /** Folder (relative to vault root), including subfolders. */
+  folder?: string | null;
+  /** Tag (without `#`), including nested tags. */
+  tag?: string | null;
+}
+
+/** A note in a link graph. */
+export interface GraphNode {
+  id: number;
+  path: string;
+  title: string | null;
+  tags: string[];
+}
+
+/** The links from one note to another in a link graph. */
+export interface GraphEdge {
+  source: number;
+  target: number;
+  /** Number of links from the source to the target. */
+  weight: number;
+}
+
+/** The link structure of (part of) the vault, as written by the JSON export. */
+export interface LinkGraph {
+  nodes: GraphNode[];
+  edges: GraphEdge[];
+}
+
+/** Result of exporting the link graph to a file. */
+export interface GraphExportResult {
+  output_path: string;
+  node_count: number;
+  edge_count: number;
+}
+
+/** A note suggested as a link target for the paragraph being written. */
+export interface LinkSuggestion {
+  note: NoteListItem;
+  /** Ranking score (higher is better). */
+  score: number;
+  /** Title or alias that matched the link being typed, if any. */
+  matched_text: string | null;
+  /** Tags the note shares with the paragraph. */
+  shared_tags: string[];
+  /** Embedding similarity to the paragraph, when embeddings are available. */
+  similarity: number | null;
+}
+
+/** A note an ambiguous wiki link could refer to. */
+export interface LinkCandidate {
+  note_id: number;
+  path: string;
+  title: string | null;
+  /** Folder of the note ("" for the vault root). */
+  folder: string;
+  /** Whether the note is in the linking note's folder, which makes it the one the link resolves to. */
+  same_folder: boolean;
+}
+
+/** How a note matched a reference to it, from most to least certain. */
+export type NoteMatchKind = "path" | "same_folder" | "name" | "title" | "alias" | "fuzzy";
+
+/** A note a reference could refer to. */
+export interface NoteMatch {
+  note_id: number;
+  path: string;
+  title: string | null;
+  kind: NoteMatchKind;
+  /** Ranking score (higher is better). */
+  score: number;
+  /** How sure this match is the note meant, from 0 to 1. */
+  confidence: number;
+}
+
+/** The notes a reference could refer to, best first. */
+export interface NoteResolution {
+  candidates: NoteMatch[];
+  /** How sure the first candidate is the note meant, from 0 to 1. */
+  confidence: number;
+}
+
+/** How the app writes wiki link targets (e.g. when updating links after a rename or move). */
+export type LinkFormat = "shortest" | "absolute" | "relative";
+
+export interface LinkSettings {
+  link_format: LinkFormat;
+  /** Add a note's previous title to its frontmatter aliases when its H1 changes. */
+  keep_old_titles: boolean;
+}
+
 export interface PropertyDto {
   id: number;
   note_id: number;
@@ -63,6 +204,8 @@ export interface PropertyDto {
   value: string | null;
   property_type: string | null;
   sort_order: number | null;
+  /** Items of a list property, in order (empty for other types). */
+  items: string[];
 }
 
 export interface SetPropertyRequest {
@@ -70,6 +213,8 @@ export interface SetPropertyRequest {
   key: string;
   value: string | null;
   property_type: string | null;
+  /** Items of a list property; when set, `value` is ignored. */
+  items?: string[] | null;
 }
 
 // ============================================================================
@@ -120,6 +265,31 @@ export interface NoteWithPropertyValue {
   value: string | null;
 }
 
+// ============================================================================
+// Property Table Types
+// ============================================================================
+
+/** Notes with their values for a set of properties (spreadsheet view). */
+export interface PropertyTable {
+  columns: string[];
+  rows: PropertyTableRow[];
+}
+
+export interface PropertyTableRow {
+  note: NoteListItem;
+  /** The note's property for each column (null where it has none). */
+  values: (PropertyDto | null)[];
+}
+
+/** One change of a batch update; without `value` and `items` the property is removed. */
+export interface PropertyUpdate {
+  note_id: number;
+  key: string;
+  value: string | null;
+  property_type: string | null;
+  items?: string[] | null;
+}
+
 // ============================================================================
 // Folder Property Types
 // ============================================================================
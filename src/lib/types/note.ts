@@ -2,6 +2,8 @@
  * Note-related types
  */
 
+import type { ScheduleBlockDto } from "./calendar";
+
 export interface NoteDto {
   id: number;
   path: string;
@@ -9,6 +11,8 @@ export interface NoteDto {
   created_at: string | null;
   updated_at: string | null;
   pinned: boolean;
+  /** CSS classes from the `cssclasses` property */
+  cssclasses: string[];
 }
 
 export interface NoteListItem {
@@ -22,4 +26,60 @@ export interface NoteContent {
   id: number;
   path: string;
   content: string;
+  /** Byte offset of `content` in the file (0 unless a range was read) */
+  offset: number;
+  /** Size of the whole note in bytes */
+  total_bytes: number;
+  /** Over the vault's size limit: only its start is parsed and searched, and it is not embedded */
+  truncated: boolean;
+}
+
+/**
+ * The window editing a note. Editing sessions are advisory: saves are
+ * never refused, but other windows show the note read-only.
+ */
+export interface EditSession {
+  note_id: number;
+  /** Label of the window holding the session. */
+  window: string;
+  started_at: string;
+}
+
+/** Editor content that was never saved to its file, kept for crash recovery. */
+export interface UnsavedDraft {
+  path: string;
+  content: string;
+  saved_at: string;
+  /**
+   * The file changed on disk since the draft was started, so restoring
+   * the draft would overwrite those changes.
+   */
+  file_changed: boolean;
+}
+
+/** What deleting a folder or note would affect. */
+export interface DeleteImpact {
+  /** Notes that would be deleted. */
+  notes: NoteListItem[];
+  /** Links from other notes that would no longer resolve. */
+  broken_links: number;
+  /** Schedule blocks linked to the notes; they are deleted with them. */
+  schedule_blocks: ScheduleBlockDto[];
+  /** Pinned notes among the deleted ones. */
+  pinned_notes: NoteListItem[];
+}
+
+/** How diagram (mermaid) blocks are rendered to SVG */
+export interface DiagramSettings {
+  /** Path of a mermaid-cli compatible renderer, from the app settings */
+  renderer_path: string | null;
+  /** Render diagrams into the cache when notes are indexed */
+  prerender: boolean;
+}
+
+/** How math is rendered in exports: MathML, or TeX for a script like KaTeX */
+export type MathRendering = "mathml" | "tex";
+
+export interface MathSettings {
+  rendering: MathRendering;
 }
@@ -20,7 +20,11 @@ export type PropertyOperator =
   | "DateBefore"
   | "DateAfter"
   | "DateOnOrBefore"
-  | "DateOnOrAfter";
+  | "DateOnOrAfter"
+  | "GreaterThan"
+  | "LessThan"
+  | "GreaterOrEqual"
+  | "LessOrEqual";
 
 /** A single property filter condition. */
 export interface PropertyFilter {
@@ -28,7 +32,10 @@ export interface PropertyFilter {
   key: string;
   /** The comparison operator. */
   operator: PropertyOperator;
-  /** The value to compare against (not used for Exists/NotExists). */
+  /**
+   * The value to compare against (not used for Exists/NotExists).
+   * For ContainsAll/ContainsAny, a JSON array of strings.
+   */
   value: string | null;
 }
 
@@ -50,6 +57,8 @@ export interface QueryRequest {
   include_completed: boolean;
   /** Maximum number of results. */
   limit: number | null;
+  /** Sort by a property (or `_path`, `note_title`); numbers and dates sort by value. */
+  sort?: QuerySort | null;
 }
 
 /** A single query result item (can be a task or a note). */
@@ -62,6 +71,16 @@ export interface QueryResultItem {
   note: NoteListItem | null;
   /** Properties of the note (for display in results). */
   properties: PropertyDto[];
+  /**
+   * Manual position on the Kanban board the query is shown on, if the card
+   * was moved by hand. Lower ranks come first.
+   */
+  rank: number | null;
+  /**
+   * Stable reference to the item for its position on a board: `note:<id>` or
+   * `task:<note id>:<text hash>:<n>`. Filled in for Kanban views.
+   */
+  item_ref: string;
 }
 
 /** Response from running a query. */
@@ -100,6 +119,10 @@ export const OPERATOR_LABELS: Record<PropertyOperator, string> = {
   DateAfter: "after",
   DateOnOrBefore: "on or before",
   DateOnOrAfter: "on or after",
+  GreaterThan: "greater than",
+  LessThan: "less than",
+  GreaterOrEqual: "at least",
+  LessOrEqual: "at most",
 };
 
 /** Operators that don't require a value. */
@@ -131,6 +154,11 @@ export interface KanbanConfig {
   card_fields: string[];
   /** Whether to show cards without a value in an "Uncategorized" column. */
   show_uncategorized: boolean;
+  /**
+   * Name the manual card order is stored under. Defaults to a key made from
+   * the query and `group_by`, filled in when the query runs.
+   */
+  board?: string | null;
 }
 
 /** Card-specific configuration. */
@@ -203,6 +231,8 @@ export interface QueryTab {
   result_type: QueryResultType;
   /** Include completed tasks. Defaults to false. */
   include_completed: boolean;
+  /** Also include tasks of notes embedded in the matching notes, one level deep. Defaults to false. */
+  include_embedded?: boolean;
   /** Maximum number of results. Defaults to 50. */
   limit: number;
   /** View configuration for this tab. */
@@ -220,6 +250,8 @@ export interface QueryEmbed {
   result_type: QueryResultType;
   /** Include completed tasks. Defaults to false. */
   include_completed: boolean;
+  /** Also include tasks of notes embedded in the matching notes, one level deep. Defaults to false. */
+  include_embedded?: boolean;
   /** Maximum number of results. Defaults to 50. */
   limit: number;
   /** View configuration. */
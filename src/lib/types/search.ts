@@ -8,6 +8,45 @@ export interface SearchResult {
   title: string | null;
   snippet: string | null;
   score: number;
+  /** Saved since it was indexed for search; the snippet may be out of date. */
+  stale: boolean;
+}
+
+/** An image whose recognized text matches a search. */
+export interface ImageTextMatch {
+  path: string;
+  /** The recognized text around the match. */
+  snippet: string;
+}
+
+/** Scoping filters for a search. */
+export interface SearchFilters {
+  /** Only notes in this folder (including subfolders). */
+  folder: string | null;
+  /** Only notes with all of these tags. */
+  tags: string[];
+  /**
+   * Range on the note's modification date: "today", "this_week",
+   * "this_month", "this_year", or "YYYY-MM-DD..YYYY-MM-DD" (either end
+   * may be left open).
+   */
+  date_range: string | null;
+}
+
+/** A named search with its filters, for one-click searches. */
+export interface SearchPresetDto {
+  id: number;
+  name: string;
+  /** FTS search string. */
+  query: string;
+  filters: SearchFilters;
+}
+
+/** Request to save a search preset. Saving under an existing name replaces it. */
+export interface SaveSearchPresetRequest {
+  name: string;
+  query: string;
+  filters: SearchFilters;
 }
 
 /**
@@ -31,6 +70,23 @@ export interface HybridSearchResult {
   combined_score: number;
   /** How this result was matched. */
   match_type: MatchType;
+  /** Best matching section, for semantic matches of chunked notes. */
+  section: SearchSection | null;
+  /** The keyword match may be out of date. */
+  stale: boolean;
+}
+
+/**
+ * The section of a note a semantic search result matched.
+ */
+export interface SearchSection {
+  /** Headings leading to the section, outermost first. */
+  heading_path: string[];
+  snippet: string;
+  /** First line of the section in the note (1-indexed). */
+  start_line: number;
+  /** Last line of the section in the note (1-indexed). */
+  end_line: number;
 }
 
 /**
@@ -44,3 +100,26 @@ export interface HybridSearchOptions {
   /** Maximum results to return. */
   limit: number;
 }
+
+/**
+ * What the autocomplete index completes.
+ */
+export type AutocompleteKind =
+  | "title"
+  | "alias"
+  | "tag"
+  | "property_key"
+  | "property_value"
+  | "context"
+  | "heading";
+
+/**
+ * An autocomplete suggestion.
+ */
+export interface AutocompleteItem {
+  text: string;
+  /** Number of notes using it. */
+  count: number;
+  /** The note using it, when there is only one (e.g. for a title). */
+  note_id: number | null;
+}
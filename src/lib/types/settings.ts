@@ -0,0 +1,51 @@
+/**
+ * App settings types - per-device settings stored outside the vault
+ */
+
+import type { EmbeddingSettings } from "./embedding";
+
+/** Editor appearance. */
+export interface EditorSettings {
+  /** CSS font family (null for the theme's font). */
+  font_family: string | null;
+  /** Font size in pixels (null for the theme's size). */
+  font_size: number | null;
+  line_height: number | null;
+  /** Dictionary language for spell checking (e.g. "en_US"); null turns it off. */
+  spell_check_language: string | null;
+}
+
+/** Main window size and position, in logical pixels. */
+export interface WindowLayout {
+  width: number;
+  height: number;
+  x: number | null;
+  y: number | null;
+  maximized: boolean;
+}
+
+/** Settings of this device (vault settings live in the vault config). */
+export interface AppSettings {
+  /** Vault opened on startup. */
+  last_vault_path: string | null;
+  /** "system", "light", "dark" or a custom theme name. */
+  theme: string;
+  vim_mode: boolean;
+  default_calendar_view: "monthly" | "weekly" | "daily";
+  editor: EditorSettings;
+  /**
+   * Keep drafts of unsaved edits, to offer them for recovery if the app
+   * quits before a note is saved.
+   */
+  crash_recovery: boolean;
+  /** Window layout when the app was last closed. */
+  window: WindowLayout | null;
+  /** Semantic search settings (the embedding server runs on this device). */
+  embedding_settings: EmbeddingSettings;
+  /**
+   * Diagram renderer installed on this device; the app's bundled renderer
+   * if unset. Kept here rather than in the vault config, so opening a vault
+   * never runs a program it chose.
+   */
+  diagram_renderer_path: string | null;
+}
@@ -0,0 +1,14 @@
+/**
+ * Spell check types
+ */
+
+/** A misspelled word in checked text. */
+export interface Misspelling {
+  /** Start offset in UTF-16 code units (as used by the editor). */
+  from: number;
+  /** End offset in UTF-16 code units. */
+  to: number;
+  word: string;
+  /** Replacement suggestions, best first. */
+  suggestions: string[];
+}
@@ -1,5 +1,5 @@
 /**
- * Template types - daily note creation and template settings
+ * Template types - daily note creation, template settings and note types
  */
 
 /**
@@ -10,6 +10,120 @@ export interface TemplateSettings {
   daily_template_path: string | null;
   /** Pattern for daily note file paths (e.g., "journal/{{year}}/{{month}}/{{date}}.md"). */
   daily_note_pattern: string;
+  /** Journal prompts rotated daily and available as `{{prompt}}` in the daily note template. */
+  journal_prompts: string[];
+  /** Path to the weekly note template file (relative to vault root). */
+  weekly_template_path: string | null;
+  /** Pattern for weekly note file paths, rendered for the week's first day with the ISO week of its Monday (e.g., "journal/{{week_year}}/{{week_year}}-W{{week}}.md" or "journal/{{iso_week}}.md"). */
+  weekly_note_pattern: string;
+  /** Path to the monthly note template file (relative to vault root). */
+  monthly_template_path: string | null;
+  /** Pattern for monthly note file paths, rendered for the first of the month (e.g., "journal/{{year}}/{{year}}-{{month}}.md"). */
+  monthly_note_pattern: string;
+  /** User-defined variables, available as `{{name}}` in all templates. Values may use the built-in variables (e.g. "Week {{week}}"). */
+  variables: TemplateVariable[];
+  /** Reusable blocks of text, inserted with `{{snippet:name}}`. */
+  snippets: TemplateSnippet[];
+}
+
+/**
+ * A user-defined template variable.
+ */
+export interface TemplateVariable {
+  /** Name used in templates, without braces (e.g. "author"). */
+  name: string;
+  value: string;
+}
+
+/**
+ * A reusable block of template text.
+ */
+export interface TemplateSnippet {
+  /** Name used in `{{snippet:name}}`. */
+  name: string;
+  /** The text, rendered like the template it is inserted into. */
+  content: string;
+}
+
+/**
+ * Values from the app for rendering a template string.
+ */
+export interface TemplateRenderContext {
+  /** Date the date variables are for, as YYYY-MM-DD. Defaults to today. */
+  date?: string | null;
+  /** Title of the active note, for `{{title}}`. */
+  note_title?: string | null;
+  /** Clipboard text, for `{{clipboard}}`. */
+  clipboard?: string | null;
+}
+
+/**
+ * A rule that creates a note from a template on a schedule.
+ */
+export interface ScheduledNoteRule {
+  id: number;
+  name: string;
+  /** Recurrence as an RRULE without DTSTART, e.g. "FREQ=WEEKLY;BYDAY=MO". */
+  rrule: string;
+  /** Local time of day the note is created ("HH:MM"). */
+  time: string;
+  /** Note path, rendered for the day, e.g. "Weekly/{{date:YYYY-[W]WW}}.md". */
+  path_pattern: string;
+  /** Template for the note's content (relative to vault root). */
+  template_path: string | null;
+  enabled: boolean;
+  /** When the rule last ran (local "YYYY-MM-DDTHH:MM:SS"). */
+  last_run: string | null;
+  /** When the rule was created (local); the recurrence starts here. */
+  created_at: string;
+}
+
+/**
+ * Request to create or change a scheduled note rule.
+ */
+export interface SaveScheduledNoteRuleRequest {
+  name: string;
+  rrule: string;
+  time: string;
+  path_pattern: string;
+  template_path?: string | null;
+  enabled?: boolean;
+}
+
+/**
+ * A note created by a scheduled note rule.
+ */
+export interface ScheduledNoteCreated {
+  rule_id: number;
+  /** The occurrence the note was created for (local time). */
+  scheduled_for: string;
+  path: string;
+  note_id: number;
+}
+
+/**
+ * The period a periodic (journal) note covers.
+ */
+export type NotePeriod = "daily" | "weekly" | "monthly";
+
+/**
+ * Direction to move from one periodic note to the next.
+ */
+export type PeriodDirection = "previous" | "next";
+
+/**
+ * The periodic note for a day, week or month.
+ */
+export interface PeriodicNote {
+  period: NotePeriod;
+  /** First day of the period (YYYY-MM-DD): the date, the week's first day, or the first of the month. */
+  date: string;
+  /** The note path from the period's pattern. */
+  path: string;
+  /** The note ID, or null if the note doesn't exist. */
+  note_id: number | null;
+  /** Whether the note was created by this call. */
+  created: boolean;
 }
 
 /**
@@ -26,10 +140,45 @@ export interface DailyNoteResult {
   created: boolean;
 }
 
+/**
+ * A note type (e.g. meeting, book, person): where new notes of the type go
+ * and what they start with. Notes of a type have it as their `type` property.
+ */
+export interface NoteTypeDefinition {
+  /** The type's name, used as the `type` property value (e.g. "meeting"). */
+  name: string;
+  /** Template for new notes (relative to vault root). Defaults to `types/<name>.md` if that exists. */
+  template: string | null;
+  /** Properties every note of the type gets. */
+  properties: NoteTypeProperty[];
+  /** Folder new notes are created in (relative to vault root). */
+  folder: string | null;
+  /** Icon name shown in the new-note dialog. */
+  icon: string | null;
+}
+
+/**
+ * A property set on new notes of a type.
+ */
+export interface NoteTypeProperty {
+  key: string;
+  /** Default value; can use `{{title}}` and the date variables. Empty if null. */
+  value: string | null;
+  /** Type hint: "text", "date", "number", "boolean", "list" */
+  property_type: string | null;
+}
+
 /**
  * Default template settings.
  */
 export const DEFAULT_TEMPLATE_SETTINGS: TemplateSettings = {
   daily_template_path: null,
   daily_note_pattern: "journal/{{year}}/{{month}}/{{date}}.md",
+  journal_prompts: [],
+  weekly_template_path: null,
+  weekly_note_pattern: "journal/{{week_year}}/{{week_year}}-W{{week}}.md",
+  monthly_template_path: null,
+  monthly_note_pattern: "journal/{{year}}/{{year}}-{{month}}.md",
+  variables: [],
+  snippets: [],
 };
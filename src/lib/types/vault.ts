@@ -2,15 +2,110 @@
  * Vault and folder types
  */
 
+import type { IndexWarning } from "./events";
+
 export interface VaultInfo {
   path: string;
   name: string;
   note_count: number;
 }
 
+/** Options for scaffolding a new vault. */
+export interface CreateVaultOptions {
+  /** Folder for new, unsorted notes (default "inbox"). */
+  inbox_folder?: string;
+  /** Folder for daily, weekly and monthly notes (default "journal"). */
+  journal_folder?: string;
+  /** Add daily, weekly, monthly and meeting templates to `templates/` (default true). */
+  sample_templates?: boolean;
+  /** Add a welcome note explaining the basics (default true). */
+  welcome_note?: boolean;
+}
+
 export interface FolderNode {
   name: string;
   path: string;
   is_dir: boolean;
   children: FolderNode[];
+  icon: string | null;
+  color: string | null;
+  collapsed: boolean;
+  /** In a read-only external folder */
+  read_only: boolean;
+}
+
+export interface FolderAppearance {
+  icon: string | null;
+  color: string | null;
+  collapsed: boolean;
+}
+
+/** A read-only folder outside the vault, indexed under `@name/` */
+export interface ExternalFolder {
+  name: string;
+  path: string;
+}
+
+export type ChangeKind = "created" | "modified" | "deleted" | "renamed";
+
+/** An entry in the vault's change journal (`changes.jsonl` in its data directory) */
+export interface ChangeRecord {
+  /** Position in the journal, from 1 */
+  seq: number;
+  timestamp: string;
+  kind: ChangeKind;
+  path: string;
+  /** The path before a rename */
+  old_path: string | null;
+  /** Content hash after the change (null for deletions) */
+  hash: string | null;
+}
+
+export interface ChangesPage {
+  changes: ChangeRecord[];
+  cursor: number;
+  /**
+   * The cursor was older than the journal (records dropped or the journal
+   * deleted): changes start from the oldest kept, so resync everything
+   */
+  reset: boolean;
+}
+
+/** Why a note's database copy doesn't match its file */
+export type StaleReason =
+  /** Added while the app was closed, or missed by the watcher */
+  | "not_indexed"
+  /** Deleted while the app was closed, or missed by the watcher */
+  | "file_missing"
+  /** Edited while the app was closed, or missed by the watcher */
+  | "modified_after_indexing"
+  /** Restored from a backup with old timestamps, or indexing was interrupted */
+  | "content_mismatch";
+
+/** A note whose database copy is stale */
+export interface StaleNote {
+  path: string;
+  /** null if the file isn't indexed */
+  note_id: number | null;
+  reason: StaleReason;
+  indexed_at: string | null;
+  modified_at: string | null;
+}
+
+/** Size limits for indexing very large notes */
+export interface IndexSettings {
+  /** Notes over this many bytes are only parsed and searched up to this size, and never embedded */
+  max_indexed_bytes: number;
+}
+
+/** Result of checking the index against the files on disk */
+export interface IndexVerificationReport {
+  files_checked: number;
+  up_to_date: number;
+  stale: StaleNote[];
+  /** Stale notes re-indexed (or removed, for missing files) */
+  reindexed: number;
+  /** Files that could not be read or re-indexed */
+  warnings: IndexWarning[];
+  duration_ms: number;
 }
@@ -6,6 +6,7 @@
 
 import type { ScheduleBlockDto } from "../types";
 import { getBlockColorVar, DEFAULT_BLOCK_COLOR } from "../constants/colors";
+import { formatDisplayTime } from "./dateUtils";
 
 /**
  * Parse a time string "HH:MM" or "HH:MM:SS" to decimal hours.
@@ -27,11 +28,11 @@ export function formatTime(decimalHours: number): string {
 }
 
 /**
- * Format a time string to short format "HH:MM".
- * e.g., "14:30:00" -> "14:30"
+ * Format a time string without seconds, in the locale's time format.
+ * e.g., "14:30:00" -> "14:30" (or "2:30 pm")
  */
 export function formatTimeShort(timeStr: string): string {
-  return timeStr.slice(0, 5);
+  return formatDisplayTime(timeStr.slice(0, 5));
 }
 
 /**
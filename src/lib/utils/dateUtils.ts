@@ -1,14 +1,49 @@
 /**
  * Date utility functions for calendar and note management.
  * Centralizes all date formatting, parsing, and range calculations.
+ *
+ * Week ranges and display formats follow the vault's locale settings,
+ * set with `setLocaleSettings` when a vault is opened.
  */
 
+import type { LocaleSettings, WeekStart } from "../types";
+import { DEFAULT_LOCALE_SETTINGS } from "../types";
+
 // Day and month name constants
 export const DAY_NAMES_SHORT = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
 export const DAY_NAMES_FULL = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
 export const MONTH_NAMES_SHORT = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
 export const MONTH_NAMES_FULL = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
 
+const WEEK_START_DAY: Record<WeekStart, number> = { sunday: 0, monday: 1, saturday: 6 };
+
+let locale: LocaleSettings = { ...DEFAULT_LOCALE_SETTINGS };
+
+/**
+ * Set the locale settings used for week ranges and display formats.
+ */
+export function setLocaleSettings(settings: LocaleSettings): void {
+  locale = { ...settings };
+}
+
+/**
+ * Short day names in week order, starting at the first day of the week.
+ */
+export function getWeekdayNames(weekStart: WeekStart = locale.week_start): string[] {
+  const first = WEEK_START_DAY[weekStart];
+  return Array.from({ length: 7 }, (_, i) => DAY_NAMES_FULL[(first + i) % 7].slice(0, 3));
+}
+
+/**
+ * Get the first day of the week containing a date.
+ */
+export function getStartOfWeek(date: Date, weekStart: WeekStart = locale.week_start): Date {
+  const start = new Date(date);
+  const diff = (date.getDay() - WEEK_START_DAY[weekStart] + 7) % 7;
+  start.setDate(date.getDate() - diff);
+  return start;
+}
+
 /**
  * Format a Date as YYYY-MM-DD string (local date without time).
  * This is the standard format used for date keys throughout the app.
@@ -45,6 +80,62 @@ export function isSameMonth(date: Date, reference: Date): boolean {
   );
 }
 
+/**
+ * Format a date with the locale's date format (e.g., "DD.MM.YYYY").
+ * Accepts a Date or a YYYY-MM-DD string; other strings are returned as is.
+ */
+export function formatDisplayDate(date: Date | string, format: string = locale.date_format): string {
+  let year: number, month: number, day: number;
+  if (typeof date === "string") {
+    const match = /^(\d{4})-(\d{2})-(\d{2})$/.exec(date);
+    if (!match) return date;
+    [year, month, day] = [Number(match[1]), Number(match[2]), Number(match[3])];
+  } else {
+    [year, month, day] = [date.getFullYear(), date.getMonth() + 1, date.getDate()];
+  }
+  return format.replace(/YYYY|MM|DD|M|D/g, (token) => {
+    switch (token) {
+      case "YYYY":
+        return String(year);
+      case "MM":
+        return String(month).padStart(2, "0");
+      case "DD":
+        return String(day).padStart(2, "0");
+      case "M":
+        return String(month);
+      default:
+        return String(day);
+    }
+  });
+}
+
+/**
+ * Format an "HH:MM" time with the locale's time format (e.g., "h:mm a").
+ * Other strings are returned as is.
+ */
+export function formatDisplayTime(time: string, format: string = locale.time_format): string {
+  const match = /^(\d{1,2}):(\d{2})/.exec(time);
+  if (!match) return time;
+  const hours = Number(match[1]);
+  const hours12 = hours % 12 === 0 ? 12 : hours % 12;
+  return format.replace(/HH|H|hh|h|mm|a/g, (token) => {
+    switch (token) {
+      case "HH":
+        return String(hours).padStart(2, "0");
+      case "H":
+        return String(hours);
+      case "hh":
+        return String(hours12).padStart(2, "0");
+      case "h":
+        return String(hours12);
+      case "mm":
+        return match[2];
+      default:
+        return hours < 12 ? "am" : "pm";
+    }
+  });
+}
+
 /**
  * Format a date as "Monday, Dec 7" style.
  */
@@ -60,36 +151,53 @@ export function formatMonthYear(date: Date): string {
 }
 
 /**
- * Get the week range (Monday to Sunday) containing a date.
+ * Get the ISO 8601 week of a date: weeks start on Monday and week 1 is the
+ * week with the year's first Thursday, so early January can be in the last
+ * week of the year before and late December in week 1 of the next year.
+ */
+export function getIsoWeek(date: Date): { year: number; week: number; label: string } {
+  // The Thursday of the date's week decides the year
+  const thursday = new Date(date.getFullYear(), date.getMonth(), date.getDate());
+  thursday.setDate(thursday.getDate() + 3 - ((thursday.getDay() + 6) % 7));
+  const year = thursday.getFullYear();
+  const firstThursday = new Date(year, 0, 4);
+  firstThursday.setDate(firstThursday.getDate() + 3 - ((firstThursday.getDay() + 6) % 7));
+  const week = 1 + Math.round((thursday.getTime() - firstThursday.getTime()) / (7 * 24 * 60 * 60 * 1000));
+  return { year, week, label: `${year}-W${String(week).padStart(2, "0")}` };
+}
+
+/**
+ * Get the ISO week number of the week containing a date, taken from the
+ * Monday in that week, so weeks starting on another day get one number each.
+ */
+export function getWeekNumber(date: Date, weekStart: WeekStart = locale.week_start): number {
+  const monday = getWeekDates(date, weekStart).find((d) => d.getDay() === 1) ?? date;
+  return getIsoWeek(monday).week;
+}
+
+/**
+ * Get the week range containing a date, from the first day of the week.
  * Returns ISO date strings for start and end.
  */
-export function getWeekRange(date: Date): { start: string; end: string } {
-  const d = new Date(date);
-  const day = d.getDay();
-  const diff = day === 0 ? -6 : 1 - day; // Adjust for Monday start
-  const monday = new Date(d);
-  monday.setDate(d.getDate() + diff);
-  const sunday = new Date(monday);
-  sunday.setDate(monday.getDate() + 6);
+export function getWeekRange(date: Date, weekStart: WeekStart = locale.week_start): { start: string; end: string } {
+  const start = getStartOfWeek(date, weekStart);
+  const end = new Date(start);
+  end.setDate(start.getDate() + 6);
   return {
-    start: formatDateKey(monday),
-    end: formatDateKey(sunday),
+    start: formatDateKey(start),
+    end: formatDateKey(end),
   };
 }
 
 /**
- * Get all dates in the week containing a date (Monday to Sunday).
+ * Get all dates in the week containing a date, from the first day of the week.
  */
-export function getWeekDates(date: Date): Date[] {
-  const d = new Date(date);
-  const day = d.getDay();
-  const diff = day === 0 ? -6 : 1 - day;
-  const monday = new Date(d);
-  monday.setDate(d.getDate() + diff);
+export function getWeekDates(date: Date, weekStart: WeekStart = locale.week_start): Date[] {
+  const start = getStartOfWeek(date, weekStart);
 
   return Array.from({ length: 7 }, (_, i) => {
-    const weekDay = new Date(monday);
-    weekDay.setDate(monday.getDate() + i);
+    const weekDay = new Date(start);
+    weekDay.setDate(start.getDate() + i);
     return weekDay;
   });
 }
@@ -97,8 +205,8 @@ export function getWeekDates(date: Date): Date[] {
 /**
  * Format a week range as "Dec 2 - 8" or "Nov 28 - Dec 4" style.
  */
-export function formatWeekRange(date: Date): string {
-  const dates = getWeekDates(date);
+export function formatWeekRange(date: Date, weekStart: WeekStart = locale.week_start): string {
+  const dates = getWeekDates(date, weekStart);
   const start = dates[0];
   const end = dates[6];
 
@@ -111,8 +219,8 @@ export function formatWeekRange(date: Date): string {
 /**
  * Format a week range with year as "Dec 2 - 8, 2025" style.
  */
-export function formatWeekRangeWithYear(date: Date): string {
-  const dates = getWeekDates(date);
+export function formatWeekRangeWithYear(date: Date, weekStart: WeekStart = locale.week_start): string {
+  const dates = getWeekDates(date, weekStart);
   const start = dates[0];
   const end = dates[6];
 
@@ -140,18 +248,17 @@ export function getMonthRange(date: Date): { start: string; end: string } {
 /**
  * Generate a calendar grid for a month (6 weeks x 7 days).
  * Includes days from previous/next months to fill the grid.
- * Week starts on Monday.
+ * Weeks start on the locale's first day of the week.
  */
-export function getMonthCalendarGrid(date: Date): Date[][] {
+export function getMonthCalendarGrid(date: Date, weekStart: WeekStart = locale.week_start): Date[][] {
   const year = date.getFullYear();
   const month = date.getMonth();
 
   const firstDay = new Date(year, month, 1);
   const lastDay = new Date(year, month + 1, 0);
 
-  // Adjust for Monday start (0 = Monday, 6 = Sunday)
-  let startOffset = firstDay.getDay() - 1;
-  if (startOffset < 0) startOffset = 6;
+  // Days before the 1st in its week
+  const startOffset = (firstDay.getDay() - WEEK_START_DAY[weekStart] + 7) % 7;
 
   const days: Date[] = [];
 